@@ -0,0 +1,91 @@
+/**
+ * IntentPool — Durable Object holding the intent mempool (src/lib/intents.ts).
+ *
+ * One instance (named "global") stores every pending intent under
+ * `int:<agent>:<id>`, so one agent's intents list and count by prefix. An
+ * alarm at the earliest `expires_at` drops expired intents; intents that were
+ * filled or superseded (the agent's on-chain nonce moved past them) are
+ * dropped by GET /intents/pending when it sees them.
+ *
+ * Internal API, called only by src/routes/intents.ts:
+ *   POST /intents                 body: StoredIntent → 201 (409 duplicate, 429 agent at its limit)
+ *   GET  /intents?pool=&agent=    → StoredIntent[] not yet expired, oldest first
+ *   POST /intents/drop            body: StoredIntent[] → 204
+ */
+
+import type { AppEnv } from '../env.js';
+import { type StoredIntent, MAX_INTENTS_PER_AGENT } from '../lib/intents.js';
+import { errorBody } from '../lib/errors.js';
+
+const INTENT_PREFIX = 'int:';
+
+function key(intent: Pick<StoredIntent, 'agent' | 'id'>): string {
+  return `${INTENT_PREFIX}${intent.agent}:${intent.id}`;
+}
+
+export class IntentPool {
+  constructor(private readonly ctx: DurableObjectState, _env: AppEnv['Bindings']) {}
+
+  /** Wake at `expiresAt` (unix seconds) unless an alarm is already due sooner. */
+  private async alarmBy(expiresAt: number): Promise<void> {
+    const at = expiresAt * 1000 + 1000;
+    const current = await this.ctx.storage.getAlarm();
+    if (current === null || current > at) await this.ctx.storage.setAlarm(at);
+  }
+
+  /** storage.delete takes at most 128 keys per call. */
+  private async deleteAll(keys: string[]): Promise<void> {
+    for (let i = 0; i < keys.length; i += 128) await this.ctx.storage.delete(keys.slice(i, i + 128));
+  }
+
+  async fetch(request: Request): Promise<Response> {
+    const url = new URL(request.url);
+    const now = Math.floor(Date.now() / 1000);
+
+    if (request.method === 'POST' && url.pathname === '/intents') {
+      const intent = await request.json() as StoredIntent;
+      if (await this.ctx.storage.get(key(intent)) !== undefined) {
+        return Response.json(errorBody('INVALID_ARGUMENT', 'Intent already pending'), { status: 409 });
+      }
+      const mine = await this.ctx.storage.list<StoredIntent>({ prefix: `${INTENT_PREFIX}${intent.agent}:` });
+      const live = [...mine.values()].filter(i => i.expires_at >= now).length;
+      if (live >= MAX_INTENTS_PER_AGENT) {
+        return Response.json(
+          errorBody('INVALID_ARGUMENT', `Agent already has ${MAX_INTENTS_PER_AGENT} pending intents`, { max: MAX_INTENTS_PER_AGENT }),
+          { status: 429 },
+        );
+      }
+      await this.ctx.storage.put(key(intent), intent);
+      await this.alarmBy(intent.expires_at);
+      return Response.json(intent, { status: 201 });
+    }
+
+    if (request.method === 'GET' && url.pathname === '/intents') {
+      const agent = url.searchParams.get('agent');
+      const pool  = url.searchParams.get('pool');
+      const all   = await this.ctx.storage.list<StoredIntent>({ prefix: agent ? `${INTENT_PREFIX}${agent}:` : INTENT_PREFIX });
+      const pending = [...all.values()]
+        .filter(i => i.expires_at >= now && (!pool || i.pool === pool))
+        .sort((a, b) => a.receivedAt - b.receivedAt);
+      return Response.json(pending);
+    }
+
+    if (request.method === 'POST' && url.pathname === '/intents/drop') {
+      const stale = await request.json() as StoredIntent[];
+      await this.deleteAll(stale.map(key));
+      return new Response(null, { status: 204 });
+    }
+
+    return Response.json({ error: 'Not found' }, { status: 404 });
+  }
+
+  async alarm(): Promise<void> {
+    const now = Math.floor(Date.now() / 1000);
+    const all = await this.ctx.storage.list<StoredIntent>({ prefix: INTENT_PREFIX });
+    const expired = [...all.entries()].filter(([, i]) => i.expires_at < now).map(([k]) => k);
+    await this.deleteAll(expired);
+
+    const next = [...all.values()].filter(i => i.expires_at >= now).map(i => i.expires_at);
+    if (next.length > 0) await this.alarmBy(Math.min(...next));
+  }
+}
@@ -0,0 +1,64 @@
+/**
+ * PositionCache — Durable Object holding one wallet's snapshot for
+ * /my-positions and /my-fees (src/lib/walletSnapshot.ts).
+ *
+ * One instance per wallet (idFromName(wallet)). A snapshot younger than
+ * POSITION_CACHE_TTL_SECS (default 30) is served as is; otherwise, or with
+ * `refresh=1`, it is re-read from the RPC. Concurrent misses share one read.
+ * The stored copy is dropped by an alarm once the wallet goes quiet.
+ *
+ * Internal API, called only by loadWallet():
+ *   GET /snapshot?wallet=<base58>[&refresh=1]  → WalletSnapshot
+ */
+
+import type { AppEnv } from '../env.js';
+import { rpcUrl } from '../lib/rpc.js';
+import {
+  type WalletSnapshot, DEFAULT_POSITION_CACHE_TTL_SECS, fetchWalletSnapshot,
+} from '../lib/walletSnapshot.js';
+
+const SNAPSHOT_KEY = 'snapshot';
+/** Keep the stored snapshot this long past its TTL before the alarm drops it. */
+const IDLE_MS = 10 * 60 * 1000;
+
+export class PositionCache {
+  private snapshot: WalletSnapshot | undefined;
+  private inflight: Promise<WalletSnapshot> | undefined;
+
+  constructor(private readonly ctx: DurableObjectState, private readonly env: AppEnv['Bindings']) {}
+
+  private ttlMs(): number {
+    const secs = Number(this.env.POSITION_CACHE_TTL_SECS ?? DEFAULT_POSITION_CACHE_TTL_SECS);
+    return Math.max(0, Number.isFinite(secs) ? secs : DEFAULT_POSITION_CACHE_TTL_SECS) * 1000;
+  }
+
+  async fetch(request: Request): Promise<Response> {
+    const url    = new URL(request.url);
+    const wallet = url.searchParams.get('wallet');
+    if (url.pathname !== '/snapshot' || !wallet) return Response.json({ error: 'Not found' }, { status: 404 });
+
+    this.snapshot ??= await this.ctx.storage.get<WalletSnapshot>(SNAPSHOT_KEY);
+    const fresh = this.snapshot !== undefined && Date.now() - this.snapshot.fetchedAt < this.ttlMs();
+    if (fresh && url.searchParams.get('refresh') !== '1') return Response.json(this.snapshot);
+
+    this.inflight ??= this.reload(wallet).finally(() => { this.inflight = undefined; });
+    try {
+      return Response.json(await this.inflight);
+    } catch (e) {
+      return Response.json({ error: String(e) }, { status: 502 });
+    }
+  }
+
+  private async reload(wallet: string): Promise<WalletSnapshot> {
+    const snapshot = await fetchWalletSnapshot(rpcUrl(this.env), wallet);
+    this.snapshot = snapshot;
+    await this.ctx.storage.put(SNAPSHOT_KEY, snapshot);
+    await this.ctx.storage.setAlarm(snapshot.fetchedAt + this.ttlMs() + IDLE_MS);
+    return snapshot;
+  }
+
+  async alarm(): Promise<void> {
+    this.snapshot = undefined;
+    await this.ctx.storage.deleteAll();
+  }
+}
@@ -0,0 +1,128 @@
+/**
+ * WebhookHub — Durable Object that stores webhook subscriptions, polls for
+ * their events and delivers them with retries.
+ *
+ * One instance (named "global") holds every subscription. An alarm runs every
+ * WEBHOOK_POLL_SECS (default 30): it re-checks each active subscription
+ * (src/lib/webhooks.ts), queues a delivery per event, and sends whatever is
+ * due. Failed deliveries retry after RETRY_DELAYS_SECS; a subscription whose
+ * deliveries keep failing is disabled.
+ *
+ * Internal API, called only by src/routes/webhooks.ts:
+ *   POST   /subscriptions        body: Subscription → 201
+ *   GET    /subscriptions/:id    → Subscription (with secret, for auth checks)
+ *   DELETE /subscriptions/:id    → 204
+ */
+
+import type { AppEnv } from '../env.js';
+import {
+  type Delivery, type Subscription,
+  MAX_CONSECUTIVE_FAILURES, RETRY_DELAYS_SECS, deliver, newDeliveryId, poll,
+} from '../lib/webhooks.js';
+
+const DEFAULT_POLL_SECS = 30;
+const SUB_PREFIX      = 'sub:';
+const DELIVERY_PREFIX = 'dlv:';
+
+export class WebhookHub {
+  constructor(private readonly ctx: DurableObjectState, private readonly env: AppEnv['Bindings']) {}
+
+  private pollMs(): number {
+    const secs = Number(this.env.WEBHOOK_POLL_SECS ?? DEFAULT_POLL_SECS);
+    return Math.max(10, Number.isFinite(secs) ? secs : DEFAULT_POLL_SECS) * 1000;
+  }
+
+  private async ensureAlarm(): Promise<void> {
+    if (await this.ctx.storage.getAlarm() === null) {
+      await this.ctx.storage.setAlarm(Date.now() + this.pollMs());
+    }
+  }
+
+  async fetch(request: Request): Promise<Response> {
+    const url = new URL(request.url);
+    const [, collection, id] = url.pathname.split('/');
+    if (collection !== 'subscriptions') return Response.json({ error: 'Not found' }, { status: 404 });
+
+    if (request.method === 'POST' && !id) {
+      const sub = await request.json() as Subscription;
+      await this.ctx.storage.put(SUB_PREFIX + sub.id, sub);
+      await this.ensureAlarm();
+      return Response.json(sub, { status: 201 });
+    }
+
+    const sub = id ? await this.ctx.storage.get<Subscription>(SUB_PREFIX + id) : undefined;
+    if (!sub) return Response.json({ error: 'Webhook not found' }, { status: 404 });
+
+    if (request.method === 'GET') return Response.json(sub);
+    if (request.method === 'DELETE') {
+      await this.ctx.storage.delete(SUB_PREFIX + sub.id);
+      const pending = await this.ctx.storage.list<Delivery>({ prefix: DELIVERY_PREFIX });
+      const mine = [...pending.entries()].filter(([, d]) => d.webhook === sub.id).map(([k]) => k);
+      if (mine.length > 0) await this.ctx.storage.delete(mine);
+      return new Response(null, { status: 204 });
+    }
+    return Response.json({ error: 'Method not allowed' }, { status: 405 });
+  }
+
+  async alarm(): Promise<void> {
+    const subs = await this.ctx.storage.list<Subscription>({ prefix: SUB_PREFIX });
+    const now = Date.now();
+
+    // 1. Check every active subscription for new events.
+    for (const sub of subs.values()) {
+      if (sub.status !== 'active') continue;
+      let events: Array<Record<string, unknown>>;
+      try {
+        events = await poll(sub, this.env);
+      } catch (e) {
+        console.error(`[webhooks] poll ${sub.id} failed:`, e);
+        continue;
+      }
+      await this.ctx.storage.put(SUB_PREFIX + sub.id, sub);
+      for (const data of events) {
+        const d: Delivery = {
+          id: newDeliveryId(), webhook: sub.id, event: sub.event, createdAt: now, data, attempts: 0, dueAt: now,
+        };
+        await this.ctx.storage.put(DELIVERY_PREFIX + d.id, d);
+      }
+    }
+
+    // 2. Send every delivery that is due.
+    const deliveries = await this.ctx.storage.list<Delivery>({ prefix: DELIVERY_PREFIX });
+    for (const [key, d] of deliveries) {
+      if (d.dueAt > now) continue;
+      const sub = await this.ctx.storage.get<Subscription>(SUB_PREFIX + d.webhook);
+      if (!sub || sub.status !== 'active') {
+        await this.ctx.storage.delete(key);
+        continue;
+      }
+
+      if (await deliver(sub, d)) {
+        await this.ctx.storage.delete(key);
+        if (sub.failures > 0) {
+          sub.failures = 0;
+          await this.ctx.storage.put(SUB_PREFIX + sub.id, sub);
+        }
+        continue;
+      }
+
+      d.attempts += 1;
+      if (d.attempts <= RETRY_DELAYS_SECS.length) {
+        d.dueAt = now + RETRY_DELAYS_SECS[d.attempts - 1] * 1000;
+        await this.ctx.storage.put(key, d);
+        continue;
+      }
+
+      // Out of retries: drop it, and disable endpoints that keep failing.
+      await this.ctx.storage.delete(key);
+      sub.failures += 1;
+      if (sub.failures >= MAX_CONSECUTIVE_FAILURES) sub.status = 'disabled';
+      await this.ctx.storage.put(SUB_PREFIX + sub.id, sub);
+    }
+
+    const active = [...subs.values()].some(s => s.status === 'active');
+    if (active || deliveries.size > 0) {
+      await this.ctx.storage.setAlarm(Date.now() + this.pollMs());
+    }
+  }
+}
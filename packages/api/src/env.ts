@@ -15,5 +15,35 @@ export interface AppEnv {
     X402_CONVERT_AMOUNT:  string;
     /** Solana address of the facilitator fee-payer (optional; fetched from /supported if absent). */
     X402_FEE_PAYER?:      string;
+    /** WebhookHub Durable Object; /webhooks answers 503 without it. */
+    WEBHOOKS?:            DurableObjectNamespace;
+    /** Seconds between webhook trigger checks (default 30, minimum 10). */
+    WEBHOOK_POLL_SECS?:   string;
+    /** IntentPool Durable Object; /intents answers 503 without it. */
+    INTENTS?:             DurableObjectNamespace;
+    /** PositionCache Durable Object; /my-positions and /my-fees read the RPC directly without it. */
+    POSITIONS?:           DurableObjectNamespace;
+    /** Seconds a cached wallet snapshot is served before it is re-read (default 30). */
+    POSITION_CACHE_TTL_SECS?: string;
+    /** KV namespace the cron writes pool snapshots to; /pool-stats, /pool-history and /pool-stream answer 503 without it. */
+    POOL_SNAPSHOTS?:      KVNamespace;
+    /** Pool whose reserves /health checks; the check is skipped when unset. */
+    HEALTH_CANARY_POOL?:  string;
+    /** /health marks a check degraded above this many milliseconds (default 2000). */
+    HEALTH_SLOW_MS?:      string;
+    /** KV namespace storing /convert responses for Idempotency-Key replay; keys are echoed but not replayed without it. */
+    IDEMPOTENCY?:         KVNamespace;
+    /** Seconds a stored /convert response is replayed for (default 600, minimum 60). */
+    IDEMPOTENCY_TTL_SECS?: string;
+    /** Cluster this deployment serves (default "mainnet-beta"); "devnet" enables /devnet/bootstrap. */
+    SOLANA_NETWORK?:      string;
+    /** Analytics Engine dataset receiving one data point per request (see lib/metrics.ts). */
+    METRICS?:             AnalyticsEngineDataset;
+    /** OTLP/HTTP collector base URL; one server span per request goes to {OTLP_ENDPOINT}/v1/traces. */
+    OTLP_ENDPOINT?:       string;
+    /** Authorization header for the OTLP collector (secret). */
+    OTLP_AUTH?:           string;
+    /** Seeded devnet pool addresses (comma-separated) that /devnet/bootstrap reports. */
+    DEVNET_TEST_POOLS?:   string;
   };
 }
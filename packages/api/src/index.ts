@@ -3,39 +3,85 @@
  *
  * Endpoints:
  *   GET  /capability-card  free  — self-describing JSON for agent discovery
+ *   GET  /idl              free  — the program's Anchor IDL this API was built with
  *   POST /simulate         free  — swap simulation with fee breakdown
+ *   POST /simulate-liquidity free — LP shares / tokens returned for a deposit or withdrawal
  *   GET  /compare-quotes   free  — A2A vs Jupiter quote side-by-side (agent chooses)
  *   POST /swap             paid  — x402 (0.001 USDC): returns unsigned swap transaction
  *   POST /convert          paid  — alias for /swap (backwards compat)
+ *   GET|POST /pay/convert  free  — Solana Pay transaction request for a swap (wallet signs)
+ *   GET|POST /actions/swap free  — Solana Actions (Blink) for a swap; GET /actions.json maps it
  *   GET  /pool-info        free  — pool state + vault reserves
  *   GET  /active-pools     free  — all pools with reserves and fee rates
+ *   GET  /pool-stats       free  — latest pool snapshot: reserves, price, 24h change
+ *   GET  /pool-history     free  — a pool's snapshots over the last 24h
+ *   GET  /pool-stream      free  — server-sent events, one per new snapshot
+ *   GET  /candles          free  — OHLC + volume candles from the snapshots (1m–1h)
+ *   GET  /tokens           free  — token registry: symbols, mints, decimals, pools per token
  *   GET  /my-positions     free  — LP positions for a wallet (with USD values)
  *   GET  /my-fees          free  — pending + owed fees for a wallet (with USD values)
- *   GET  /health           free  — liveness check
+ *   POST /claim-all        free  — unsigned transactions claiming fees from every position
+ *   GET  /my-tier          free  — protocol fee discount tier from the wallet's tracked volume
+ *   GET  /receipt/:position free — metadata JSON for an LP receipt NFT
+ *   POST /rpc              mixed — JSON-RPC 2.0: a2a.simulate, a2a.convert (x402), a2a.poolInfo
+ *   POST /webhooks         free  — register a webhook (fees.threshold, price.move, swap.fill)
+ *   GET|DELETE /webhooks/:id free — webhook status / unsubscribe (Bearer <secret>)
+ *   POST /intents          free  — publish a signed swap intent for relayers to fill
+ *   GET  /intents/pending  free  — signed intents still executable (?pool=, ?agent=)
+ *   POST /devnet/bootstrap free  — devnet only: airdrop SOL, seeded test pools, missing ATA instructions
+ *   GET  /metrics          free  — per-route requests, error rate and latency for the serving isolate
+ *
+ * A cron trigger (scheduled handler below) snapshots every pool into the
+ * POOL_SNAPSHOTS KV namespace once a minute for the /pool-* endpoints.
+ *
+ *   GET  /health           free  — RPC, program and canary-pool checks (503 when down)
  */
 
 import { Hono }  from 'hono';
 import { cors }  from 'hono/cors';
 import type { AppEnv } from './env.js';
 import { x402 }           from './middleware/x402.js';
+import { metrics }        from './middleware/metrics.js';
 import simulateRouter     from './routes/simulate.js';
+import simulateLiquidityRouter from './routes/simulateLiquidity.js';
 import convertRouter      from './routes/convert.js';
+import payRouter          from './routes/pay.js';
+import actionsRouter      from './routes/actions.js';
 import poolInfoRouter     from './routes/poolInfo.js';
 import positionsRouter    from './routes/positions.js';
+import claimAllRouter     from './routes/claimAll.js';
 import activePoolsRouter  from './routes/activePools.js';
+import tokensRouter       from './routes/tokens.js';
 import capabilityRouter   from './routes/capabilityCard.js';
 import compareRouter      from './routes/compareQuotes.js';
 import verifyMoltRouter  from './routes/verifyMolt.js';
+import receiptRouter     from './routes/receipt.js';
+import { rpcRouter }      from './routes/rpc.js';
+import webhooksRouter     from './routes/webhooks.js';
+import healthRouter       from './routes/health.js';
+import poolStatsRouter    from './routes/poolStats.js';
+import candlesRouter      from './routes/candles.js';
+import tierRouter         from './routes/tier.js';
+import intentsRouter      from './routes/intents.js';
+import devnetRouter       from './routes/devnet.js';
+import metricsRouter      from './routes/metrics.js';
 import { VERSION }        from './lib/constants.js';
+import { apiError }       from './lib/errors.js';
+import IDL                from '../../core/idl/a2a_swap.json';
+import { rpcUrl }         from './lib/rpc.js';
+import { takePoolSnapshot, storePoolSnapshot } from './lib/poolSnapshot.js';
 
 const app = new Hono<AppEnv>();
 
+// ── Request metrics (isolate summary, Analytics Engine, OTLP) ────────────────
+app.use('*', metrics);
+
 // ── CORS (public API — all origins) ──────────────────────────────────────────
 app.use('*', cors({
   origin:         '*',
-  allowMethods:   ['GET', 'POST', 'OPTIONS'],
-  allowHeaders:   ['Content-Type', 'X-Payment'],
-  exposeHeaders:  ['X-Payment-Response'],
+  allowMethods:   ['GET', 'POST', 'DELETE', 'OPTIONS'],
+  allowHeaders:   ['Content-Type', 'X-Payment', 'Authorization', 'Content-Encoding', 'Accept-Encoding', 'Idempotency-Key'],
+  exposeHeaders:  ['X-Payment-Response', 'X-Action-Version', 'X-Blockchain-Ids', 'Idempotency-Key', 'Idempotent-Replayed'],
 }));
 
 // ── Index + Health ────────────────────────────────────────────────────────────
@@ -46,27 +92,65 @@ app.get('/', (c) => c.json({
   docs:    'https://github.com/liqdlad-rgb/a2a-swap',
   endpoints: [
     { method: 'GET',  path: '/capability-card', auth: 'free',                  description: 'Self-describing agent capability card' },
-    { method: 'GET',  path: '/health',          auth: 'free',                  description: 'Liveness check' },
+    { method: 'GET',  path: '/health',          auth: 'free',                  description: 'Dependency checks — RPC, program account, canary pool' },
+    { method: 'GET',  path: '/idl',             auth: 'free',                  description: "The program's Anchor IDL (also on-chain; see capability card)" },
     { method: 'POST', path: '/simulate',        auth: 'free',                  description: 'Swap quote — amount-out, fees, price impact' },
+    { method: 'POST', path: '/simulate-liquidity', auth: 'free',               description: 'Deposit / withdrawal preview — LP shares, pool share, price after' },
     { method: 'GET',  path: '/compare-quotes',  auth: 'free',                  description: 'A2A vs Jupiter quote side-by-side (agent chooses)' },
     { method: 'POST', path: '/swap',            auth: 'x402 (0.001 USDC)',     description: 'Build unsigned swap transaction (SOL wrap/unwrap included)' },
     { method: 'POST', path: '/convert',         auth: 'x402 (0.001 USDC)',     description: 'Alias for /swap — backwards compatible' },
+    { method: 'GET',  path: '/pay/convert',     auth: 'free',                  description: 'Solana Pay transaction request for a swap — a wallet POSTs { account } and signs' },
+    { method: 'GET',  path: '/actions/swap',    auth: 'free',                  description: 'Solana Actions (Blink) swap — metadata on GET, transaction for { account } on POST' },
     { method: 'GET',  path: '/pool-info',       auth: 'free',                  description: 'Pool reserves, LP supply, fee rate' },
     { method: 'GET',  path: '/active-pools',    auth: 'free',                  description: 'All pools with reserves and fee rates' },
+    { method: 'GET',  path: '/pool-stats',      auth: 'free',                  description: 'Latest pool snapshot — reserves, price, 24h change' },
+    { method: 'GET',  path: '/pool-history',    auth: 'free',                  description: "A pool's snapshots over the last 24h" },
+    { method: 'GET',  path: '/pool-stream',     auth: 'free',                  description: 'Server-sent events, one per new pool snapshot' },
+    { method: 'GET',  path: '/candles',         auth: 'free',                  description: 'OHLC + volume candles of pool spot price (?pair=SOL-USDC&interval=5m)' },
+    { method: 'GET',  path: '/tokens',          auth: 'free',                  description: 'Token registry — symbols, mints, decimals and the pools each token trades in' },
     { method: 'GET',  path: '/my-positions',    auth: 'free',                  description: 'LP positions for a wallet (with USD values)' },
     { method: 'GET',  path: '/my-fees',         auth: 'free',                  description: 'Pending + owed fees for a wallet (with USD values)' },
+    { method: 'GET',  path: '/my-tier',         auth: 'free',                  description: 'Protocol fee discount tier from the wallet\'s tracked swap volume' },
+    { method: 'POST', path: '/claim-all',       auth: 'free',                  description: 'Unsigned transactions claiming fees from every position of a wallet' },
+    { method: 'GET',  path: '/receipt/:position', auth: 'free',                description: 'Metadata JSON for an LP receipt NFT' },
+    { method: 'POST', path: '/webhooks',        auth: 'free',                  description: 'Register a signed webhook: fees.threshold, price.move, swap.fill' },
+    { method: 'GET',  path: '/webhooks/:id',    auth: 'webhook secret',        description: 'Webhook status (DELETE to unsubscribe)' },
+    { method: 'POST', path: '/intents',         auth: 'free',                  description: 'Publish a signed swap intent (SDK sign_intent JSON) for relayers to fill' },
+    { method: 'GET',  path: '/intents/pending', auth: 'free',                  description: 'Signed swap intents still executable, oldest first (?pool=&agent=&limit=)' },
+    { method: 'POST', path: '/rpc',             auth: 'a2a.convert: x402',     description: 'JSON-RPC 2.0 — a2a.simulate, a2a.convert, a2a.poolInfo; batches allowed' },
+    { method: 'POST', path: '/devnet/bootstrap', auth: 'free',                 description: 'Devnet only — airdrop SOL, list seeded test pools, ATA instructions for their mints' },
+    { method: 'GET',  path: '/metrics',         auth: 'free',                  description: 'Per-route requests, error rate and latency for the serving isolate' },
   ],
 }));
 
-app.get('/health', (c) => c.json({ status: 'ok', version: VERSION }));
+app.route('/health', healthRouter);
 
 // ── Free routes ───────────────────────────────────────────────────────────────
 app.route('/capability-card', capabilityRouter);
+app.get('/idl', (c) => c.json(IDL));
 app.route('/simulate',        simulateRouter);
+app.route('/simulate-liquidity', simulateLiquidityRouter);
+app.route('/pay',             payRouter);
+app.route('/actions',         actionsRouter);
+
+// Solana Actions discovery: Blink clients read this to find the endpoints.
+app.get('/actions.json', (c) => c.json({
+  rules: [{ pathPattern: '/actions/**', apiPath: '/actions/**' }],
+}));
 app.route('/compare-quotes',  compareRouter);
 app.route('/pool-info',       poolInfoRouter);
 app.route('/active-pools',    activePoolsRouter);
+app.route('/tokens',          tokensRouter);
 app.route('/',                positionsRouter);   // handles /my-positions and /my-fees
+app.route('/claim-all',       claimAllRouter);
+app.route('/my-tier',         tierRouter);
+app.route('/',                poolStatsRouter);   // handles /pool-stats, /pool-history and /pool-stream
+app.route('/candles',         candlesRouter);
+app.route('/receipt',         receiptRouter);
+app.route('/webhooks',        webhooksRouter);
+app.route('/intents',         intentsRouter);
+app.route('/devnet',          devnetRouter);      // 403 unless SOLANA_NETWORK = "devnet"
+app.route('/metrics',         metricsRouter);
 
 // ── x402-protected routes ─────────────────────────────────────────────────────
 app.use('/swap',     x402);
@@ -75,13 +159,32 @@ app.use('/convert',  x402);
 app.route('/convert', convertRouter);  // backwards-compatible alias
 app.route('/verify-molt', verifyMoltRouter);  // Molt NFT verification for zero-fee
 
+// ── JSON-RPC 2.0 (dispatches into the routes above; x402 applies per call) ────
+app.route('/rpc', rpcRouter(app));
+
 // ── Error handler ─────────────────────────────────────────────────────────────
 app.onError((err, c) => {
   console.error('[a2a-swap-api error]', err);
-  return c.json({ error: err.message ?? String(err) }, 500);
+  return apiError(c, 500, 'INTERNAL_ERROR', err.message ?? String(err));
 });
 
 // ── 404 ───────────────────────────────────────────────────────────────────────
-app.notFound((c) => c.json({ error: 'Not found' }, 404));
+app.notFound((c) => apiError(c, 404, 'ROUTE_NOT_FOUND', `route not found: ${c.req.path}`, { path: c.req.path }));
+
+// ── Cron: pool snapshots ──────────────────────────────────────────────────────
+async function scheduled(_controller: ScheduledController, env: AppEnv['Bindings']): Promise<void> {
+  const kv = env.POOL_SNAPSHOTS;
+  if (!kv) return;
+  try {
+    await storePoolSnapshot(kv, await takePoolSnapshot(rpcUrl(env)));
+  } catch (err) {
+    console.error('[a2a-swap-api pool snapshot]', err);
+  }
+}
+
+export default { fetch: app.fetch, scheduled };
 
-export default app;
+// Durable Object classes must be exported from the Worker's main module.
+export { WebhookHub } from './durable/webhookHub.js';
+export { PositionCache } from './durable/positionCache.js';
+export { IntentPool } from './durable/intentPool.js';
@@ -41,49 +41,96 @@
 //        -H 'Content-Type: application/json' \
 //        -d '{"in":"SOL","out":"USDC","amount":1000000000}'
 //
+//   # Estimate the LP shares a deposit would mint (no transaction built)
+//   curl -X POST "$BASE/simulate-liquidity" \
+//        -H 'Content-Type: application/json' \
+//        -d '{"pair":"SOL-USDC","amount_a":1000000000}'
+//
 //   # Build a swap instruction ready to sign and submit
 //   curl -X POST "$BASE/convert" \
 //        -H 'Content-Type: application/json' \
 //        -d '{"in":"SOL","out":"USDC","amount":1000000000,"agent":"<AGENT_PUBKEY>"}'
 //
+//   # Safe retries: a repeat with the same Idempotency-Key replays the first
+//   # response (needs the IDEMPOTENCY KV binding — see wrangler.toml)
+//   curl -X POST "$BASE/convert" -H 'Idempotency-Key: <UNIQUE_KEY>' \
+//        -H 'Content-Type: application/json' \
+//        -d '{"in":"SOL","out":"USDC","amount":1000000000,"agent":"<AGENT_PUBKEY>"}'
+//
+//   # Per-route request / error / latency summary for this isolate
+//   curl "$BASE/metrics"
+//
+//   # Devnet deployment only (SOLANA_NETWORK = "devnet"): airdrop + test pools
+//   curl -X POST "$BASE/devnet/bootstrap" \
+//        -H 'Content-Type: application/json' \
+//        -d '{"agent":"<AGENT_PUBKEY>"}'
+//
 // ── Agent usage note ──────────────────────────────────────────────────────────
 // This API is fully agent-native: all responses are structured JSON, no HTML,
 // no sessions, no auth.  POST /convert returns a ready-to-sign instruction
 // (programId + accounts + base64 data) — the agent signs and submits itself.
 
+use a2a_swap_core::{
+    ix, math, pda, tokens, Account as _, Instruction as _, Pool, Position, PROGRAM_ADDRESS,
+};
 use worker::*;
 
+// Error codes are shared with the Rust SDK (a dependency-free file), so agents
+// branch on the same strings whether they call HTTP or a2a_swap_sdk::Error::code.
+#[allow(dead_code)]
+#[path = "../../sdk-rust/src/error_code.rs"]
+mod error_code;
+use error_code::ErrorCode;
+
 const VERSION: &str = "0.1.0";
-const PROGRAM_ID:     &str = "8XJfG4mHqRZjByAd7HxHdEALfB8jVtJVQsdhGEmysTFq";
+const PROGRAM_ID:     &str = a2a_swap_core::PROGRAM_ID;
 const TOKEN_PROGRAM_ID: &str = "TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA";
 const ATA_PROGRAM_ID:   &str = "ATokenGPvbdGVxr1b2hvZbsiqW5xWH25efTNsLJA8knL";
+const SYSTEM_PROGRAM_ID: &str = "11111111111111111111111111111111";
+/// Current `Pool` layout version (programs/a2a-swap/src/constants.rs)
+const POOL_VERSION: u8 = 8;
 
 // ── Entry point ───────────────────────────────────────────────────────────────
 
 #[event(fetch)]
 pub async fn main(req: Request, env: Env, ctx: Context) -> Result<Response> {
+    let ray = req.headers()
+        .get("cf-ray")
+        .unwrap_or_default()
+        .unwrap_or_default();
     // Log every incoming request
-    console_log!(
-        "{} {} (cf-ray: {})",
-        req.method().to_string(),
-        req.path(),
-        req.headers()
-            .get("cf-ray")
-            .unwrap_or_default()
-            .unwrap_or_default(),
-    );
+    console_log!("{} {} (cf-ray: {})", req.method().to_string(), req.path(), ray);
 
-    Router::new()
+    let started = Date::now().as_millis();
+    let method  = req.method().to_string();
+    let route   = route_label(&req.path());
+    RPC_CALLS.with(|c| c.set(0));
+
+    let result = Router::new()
         .get_async("/", handle_root)
         .get("/health", handle_health)
         .post_async("/simulate",           handle_simulate)
+        .post_async("/simulate-liquidity", handle_simulate_liquidity)
         .post_async("/convert",            handle_convert)
         .get_async("/pool-info",           handle_pool_info)
         .get_async("/my-positions",        handle_my_positions)
         .get_async("/my-fees",             handle_my_fees)
+        .post_async("/devnet/bootstrap",   handle_devnet_bootstrap)
+        .get("/metrics",                   handle_metrics)
         .or_else_any_method("/*path",      handle_not_found)
-        .run(req, env)
-        .await
+        .run(req, env.clone())
+        .await;
+
+    record_request(&env, &ctx, RequestMetric {
+        route,
+        method,
+        status:     result.as_ref().map_or(500, |r| r.status_code()),
+        started,
+        latency_ms: Date::now().as_millis().saturating_sub(started),
+        rpc_calls:  RPC_CALLS.with(|c| c.get()),
+        ray,
+    });
+    result
 }
 
 // ── Handlers ──────────────────────────────────────────────────────────────────
@@ -100,44 +147,45 @@ async fn handle_root(_req: Request, ctx: RouteContext<()>) -> Result<Response> {
         "version":  VERSION,
         "url":      url,
         "program":  PROGRAM_ID,
-        "network":  "mainnet-beta",
+        "network":  network(&ctx.env),
         "docs":     "https://github.com/a2a-swap/a2a-swap",
         "endpoints": {
             "GET  /":             "this response",
             "GET  /health":       "liveness check",
             "POST /simulate":     "estimate swap output and fees  {in, out, amount}",
-            "POST /convert":      "build swap instruction  {in, out, amount, agent, max_slippage_bps?}",
+            "POST /simulate-liquidity": "estimate LP shares or tokens returned  {pair, amount_a, amount_b?} | {pair, lp_shares}",
+            "POST /convert":      "build swap instruction  {in, out, amount, agent, max_slippage_bps?, max_price_impact_bps?, deadline_unix?}",
             "GET  /pool-info":    "pool reserves and spot price  ?pair=SOL-USDC",
             "GET  /my-positions": "LP positions for a wallet  ?pubkey=BASE58",
             "GET  /my-fees":      "claimable fees for a wallet  ?pubkey=BASE58",
+            "POST /devnet/bootstrap": "devnet only: airdrop SOL + test pools + ATA instructions  {agent, lamports?}",
+            "GET  /metrics":      "per-route request count, error rate and latency for this isolate",
         },
     }))
 }
 
 /// GET /health  →  extended liveness payload
-fn handle_health(_req: Request, _ctx: RouteContext<()>) -> Result<Response> {
+fn handle_health(_req: Request, ctx: RouteContext<()>) -> Result<Response> {
     json_ok(&serde_json::json!({
         "status":  "ok",
         "service": "a2a-swap-api",
         "version": VERSION,
         "program": PROGRAM_ID,
-        "network": "mainnet-beta",
+        "network": network(&ctx.env),
     }))
 }
 
 // ── /simulate helpers ─────────────────────────────────────────────────────────
 
 /// Resolve a token symbol to its mainnet-beta base58 mint address.
-/// SOL / USDC / USDT are recognised case-insensitively.
-/// Any other string that is 32–44 characters is treated as a raw base58 mint
-/// address and passed through unchanged.
+/// The built-in symbols (`a2a_swap_core::tokens::KNOWN_TOKENS`) are
+/// recognised case-insensitively. Any other string that is 32–44 characters
+/// is treated as a raw base58 mint address and passed through unchanged.
 fn resolve_mint(token: &str) -> Option<String> {
-    match token.to_uppercase().as_str() {
-        "SOL"  => Some("So11111111111111111111111111111111111111112".into()),
-        "USDC" => Some("EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v".into()),
-        "USDT" => Some("Es9vMFrzaCERmJfrF4H2FYD4KCoNkY11McCe8BenwNYB".into()),
-        _ if token.len() >= 32 && token.len() <= 44 => Some(token.to_string()),
-        _ => None,
+    match tokens::known_token(token) {
+        Some(known) => Some(known.mint.into()),
+        None if token.len() >= 32 && token.len() <= 44 => Some(token.to_string()),
+        None => None,
     }
 }
 
@@ -164,6 +212,7 @@ async fn rpc_get_account_info(
         .with_body(Some(body.into())); // String → JsValue via wasm-bindgen From impl
 
     let req = Request::new_with_init(rpc_url, &init).map_err(|e| e.to_string())?;
+    RPC_CALLS.with(|c| c.set(c.get() + 1));
     let mut res = Fetch::Request(req).send().await.map_err(|e| e.to_string())?;
     let json: serde_json::Value = res.json().await.map_err(|e| e.to_string())?;
 
@@ -188,33 +237,44 @@ async fn rpc_get_account_info(
 
 /// Find the pool for a mint pair by trying both PDA orderings (AB, then BA).
 /// Mirrors `sdk/src/client.rs::find_pool_inner`.
-/// Returns `(pool_pda_b58, PoolState, a_to_b)`.
+/// Returns `(pool_pda_b58, Pool, a_to_b)`, or the error code + message.
 async fn find_pool_rpc(
     rpc_url:  &str,
     mint_in:  &str,
     mint_out: &str,
-) -> std::result::Result<(String, PoolState, bool), String> {
+) -> std::result::Result<(String, Pool, bool), (ErrorCode, String)> {
+    let rpc_err   = |e: String| (ErrorCode::RpcError, e);
+    let parse_err = |e: String| (ErrorCode::AccountParseError, e);
+
     // Ordering A→B: mint_in is token_a
-    let (pda_ab, _) = derive_pool_pda(mint_in, mint_out)?;
-    if let Some(data) = rpc_get_account_info(rpc_url, &pda_ab).await? {
-        let pool = parse_pool(&data).map_err(|e| e.to_string())?;
+    let (pda_ab, _) = derive_pool_pda(mint_in, mint_out)
+        .map_err(|e| (ErrorCode::InvalidArgument, e))?;
+    if let Some(data) = rpc_get_account_info(rpc_url, &pda_ab).await.map_err(rpc_err)? {
+        let pool = parse_pool(&data).map_err(parse_err)?;
         return Ok((pda_ab, pool, true));
     }
     // Ordering B→A: mint_in is token_b
-    let (pda_ba, _) = derive_pool_pda(mint_out, mint_in)?;
-    if let Some(data) = rpc_get_account_info(rpc_url, &pda_ba).await? {
-        let pool = parse_pool(&data).map_err(|e| e.to_string())?;
+    let (pda_ba, _) = derive_pool_pda(mint_out, mint_in)
+        .map_err(|e| (ErrorCode::InvalidArgument, e))?;
+    if let Some(data) = rpc_get_account_info(rpc_url, &pda_ba).await.map_err(rpc_err)? {
+        let pool = parse_pool(&data).map_err(parse_err)?;
         return Ok((pda_ba, pool, false));
     }
-    Err(format!("pool not found for {mint_in} / {mint_out}"))
+    Err((ErrorCode::PoolNotFound, format!("pool not found for {mint_in} / {mint_out}")))
 }
 
-/// Fetch both vault token balances and return `(reserve_in, reserve_out)`.
+/// The reserves the program prices against, as `(reserve_in, reserve_out)`.
+/// Mirrors `sdk/src/client.rs::fetch_reserves`: the pool's tracked reserves,
+/// or both vault balances while they're untracked.
 async fn fetch_reserves(
     rpc_url: &str,
-    pool:    &PoolState,
+    pool:    &Pool,
     a_to_b:  bool,
 ) -> std::result::Result<(u64, u64), String> {
+    if pool.reserves_tracked {
+        let (ra, rb) = (pool.reserve_a, pool.reserve_b);
+        return Ok(if a_to_b { (ra, rb) } else { (rb, ra) });
+    }
     let vault_a = bs58::encode(&pool.token_a_vault).into_string();
     let vault_b = bs58::encode(&pool.token_b_vault).into_string();
 
@@ -229,16 +289,42 @@ async fn fetch_reserves(
     Ok(if a_to_b { (ra, rb) } else { (rb, ra) })
 }
 
+/// Fetch the live protocol fee from the `ProtocolConfig` at the treasury PDA.
+/// Mirrors `sdk/src/client.rs::fetch_protocol_fee` — the default applies
+/// until the config account exists.
+async fn fetch_protocol_fee(rpc_url: &str) -> std::result::Result<u64, String> {
+    let (treasury, _) = derive_treasury_pda()?;
+    match rpc_get_account_info(rpc_url, &treasury).await? {
+        Some(data) if !data.is_empty() => parse_protocol_fee_bps(&data),
+        _ => Ok(math::PROTOCOL_FEE_BPS as u64),
+    }
+}
+
+/// Byte offset of `decimals` in an SPL mint account.
+const MINT_DECIMALS_OFFSET: usize = 44;
+
+/// Mint decimals: the built-in table first, then the mint account itself.
+/// `None` when the mint cannot be read — the UI fields are then null.
+async fn fetch_mint_decimals(rpc_url: &str, mint: &str) -> Option<u8> {
+    if let Some(known) = tokens::KNOWN_TOKENS.iter().find(|t| t.mint == mint) {
+        return Some(known.decimals);
+    }
+    let data = rpc_get_account_info(rpc_url, mint).await.ok()??;
+    data.get(MINT_DECIMALS_OFFSET).copied()
+}
+
 /// Call Solana JSON-RPC `getProgramAccounts` via worker::Fetch.
-/// Filters by account data size and a memcmp at a given byte offset.
+/// Filters by Anchor account type (discriminator) and a memcmp at a given
+/// byte offset. No size filter, so accounts of every layout version match.
 /// Returns Vec<(pubkey_b58, account_data_bytes)>.
 async fn rpc_get_program_accounts(
     rpc_url:       &str,
     program_id:    &str,
-    data_size:     u64,
+    disc:          [u8; 8],
     memcmp_offset: u64,
     memcmp_bytes:  &str,   // base58-encoded bytes to compare at the offset
 ) -> std::result::Result<Vec<(String, Vec<u8>)>, String> {
+    let disc = bs58::encode(disc).into_string();
     let payload = serde_json::json!({
         "jsonrpc": "2.0",
         "id":      1,
@@ -248,7 +334,7 @@ async fn rpc_get_program_accounts(
             {
                 "encoding": "base64",
                 "filters": [
-                    { "dataSize": data_size },
+                    { "memcmp": { "offset": 0, "bytes": disc } },
                     { "memcmp": { "offset": memcmp_offset, "bytes": memcmp_bytes } }
                 ]
             }
@@ -265,6 +351,7 @@ async fn rpc_get_program_accounts(
         .with_body(Some(body.into()));
 
     let req = Request::new_with_init(rpc_url, &init).map_err(|e| e.to_string())?;
+    RPC_CALLS.with(|c| c.set(c.get() + 1));
     let mut res = Fetch::Request(req).send().await.map_err(|e| e.to_string())?;
     let json: serde_json::Value = res.json().await.map_err(|e| e.to_string())?;
 
@@ -290,11 +377,12 @@ async fn rpc_get_program_accounts(
 
 /// POST /simulate
 /// Body: { "in": "SOL", "out": "USDC", "amount": 1000000000 }
-/// Returns: full SimulateResult — estimated_out, protocol_fee, lp_fee, price_impact, etc.
+/// Returns: full SimulateResult — estimated_out, protocol_fee, lp_fee, price_impact, etc.,
+/// plus decimals_in / decimals_out and human-unit amount_in_ui / estimated_out_ui.
 async fn handle_simulate(mut req: Request, ctx: RouteContext<()>) -> Result<Response> {
     let body: serde_json::Value = match req.json().await {
         Ok(v) => v,
-        Err(_) => return json_error(400, "invalid JSON body"),
+        Err(_) => return json_error(400, ErrorCode::InvalidRequest, "invalid JSON body"),
     };
 
     let token_in  = body["in"].as_str().unwrap_or("").to_string();
@@ -302,7 +390,10 @@ async fn handle_simulate(mut req: Request, ctx: RouteContext<()>) -> Result<Resp
     let amount_in = body["amount"].as_u64().unwrap_or(0);
 
     if token_in.is_empty() || token_out.is_empty() || amount_in == 0 {
-        return json_error(400, r#"required fields: "in", "out", "amount""#);
+        return json_error_details(
+            400, ErrorCode::InvalidRequest, r#"required fields: "in", "out", "amount""#,
+            serde_json::json!({ "required": ["in", "out", "amount"] }),
+        );
     }
 
     console_log!("simulate {} {} → {}", amount_in, token_in, token_out);
@@ -310,11 +401,11 @@ async fn handle_simulate(mut req: Request, ctx: RouteContext<()>) -> Result<Resp
     // Resolve symbols → mint addresses
     let mint_in = match resolve_mint(&token_in) {
         Some(m) => m,
-        None    => return json_error(400, &format!("unknown token: {token_in}")),
+        None    => return unknown_token(&token_in),
     };
     let mint_out = match resolve_mint(&token_out) {
         Some(m) => m,
-        None    => return json_error(400, &format!("unknown token: {token_out}")),
+        None    => return unknown_token(&token_out),
     };
 
     // RPC endpoint from Cloudflare env binding; fallback to public mainnet
@@ -326,7 +417,7 @@ async fn handle_simulate(mut req: Request, ctx: RouteContext<()>) -> Result<Resp
     let (pool_pda, pool_state, a_to_b) =
         match find_pool_rpc(&rpc_url, &mint_in, &mint_out).await {
             Ok(r)  => r,
-            Err(e) => return json_error(404, &e),
+            Err((code, e)) => return json_error(pool_lookup_status(code), code, &e),
         };
     console_log!("pool {} a_to_b={}", pool_pda, a_to_b);
 
@@ -334,25 +425,166 @@ async fn handle_simulate(mut req: Request, ctx: RouteContext<()>) -> Result<Resp
     let (reserve_in, reserve_out) =
         match fetch_reserves(&rpc_url, &pool_state, a_to_b).await {
             Ok(r)  => r,
-            Err(e) => return json_error(500, &e),
+            Err(e) => return json_error(500, ErrorCode::RpcError, &e),
         };
 
-    // Run simulation (identical arithmetic to sdk/src/math.rs::simulate_detailed)
-    match simulate_detailed(pool_pda, &pool_state, reserve_in, reserve_out, amount_in, a_to_b) {
+    let protocol_fee_bps = match fetch_protocol_fee(&rpc_url).await {
+        Ok(f)  => f,
+        Err(e) => return json_error(500, ErrorCode::RpcError, &e),
+    };
+
+    let decimals_in  = fetch_mint_decimals(&rpc_url, &mint_in).await;
+    let decimals_out = fetch_mint_decimals(&rpc_url, &mint_out).await;
+
+    // Run simulation (a2a_swap_core::math, the same arithmetic the SDK uses)
+    match simulate_detailed(
+        pool_pda, &pool_state, reserve_in, reserve_out, amount_in, protocol_fee_bps, a_to_b,
+    ) {
         Ok(result) => {
+            let result = result.with_decimals(decimals_in, decimals_out);
             let json = match serde_json::to_value(&result) {
                 Ok(v)  => v,
-                Err(e) => return json_error(500, &e.to_string()),
+                Err(e) => return json_error(500, ErrorCode::InternalError, &e.to_string()),
             };
             json_ok(&json)
         }
-        Err(e) => json_error(500, e),
+        Err(code) => json_error(400, code, simulate_error_message(code)),
     }
 }
 
+/// POST /simulate-liquidity
+/// Body: { "pair": "SOL-USDC", "amount_a": 1000000000, "amount_b": 150000000 }
+///    or { "pair": "SOL-USDC", "lp_shares": 1000000 }
+///
+/// The first form previews a deposit of `amount_a` of the pair's first token;
+/// `amount_b` is computed from the reserves when left out, and required while
+/// the pool is empty. The second previews burning `lp_shares`. Amounts come
+/// back in pool ordering (`token_a_mint` / `token_b_mint`), with the pool
+/// share and the spot price before and after — the same math as the SDK's
+/// `simulate_provide` / `simulate_remove`.
+async fn handle_simulate_liquidity(mut req: Request, ctx: RouteContext<()>) -> Result<Response> {
+    let body: serde_json::Value = match req.json().await {
+        Ok(v) => v,
+        Err(_) => return json_error(400, ErrorCode::InvalidRequest, "invalid JSON body"),
+    };
+
+    let pair      = body["pair"].as_str().unwrap_or("").to_string();
+    let amount_a  = body["amount_a"].as_u64().unwrap_or(0);
+    let amount_b  = body["amount_b"].as_u64();
+    let lp_shares = body["lp_shares"].as_u64().unwrap_or(0);
+
+    if pair.is_empty() || (amount_a == 0) == (lp_shares == 0) {
+        return json_error_details(
+            400, ErrorCode::InvalidRequest, r#"required fields: "pair" and one of "amount_a" or "lp_shares""#,
+            serde_json::json!({ "required": ["pair"], "one_of": ["amount_a", "lp_shares"] }),
+        );
+    }
+    let parts: Vec<&str> = pair.splitn(2, '-').collect();
+    if parts.len() != 2 || parts[0].is_empty() || parts[1].is_empty() {
+        return json_error(400, ErrorCode::InvalidArgument, "pair must be two tokens separated by \"-\" (e.g. SOL-USDC)");
+    }
+    let mint_a = match resolve_mint(parts[0]) {
+        Some(m) => m,
+        None    => return unknown_token(parts[0]),
+    };
+    let mint_b = match resolve_mint(parts[1]) {
+        Some(m) => m,
+        None    => return unknown_token(parts[1]),
+    };
+
+    console_log!("simulate-liquidity {} amount_a={} lp_shares={}", pair, amount_a, lp_shares);
+
+    let rpc_url = ctx.env.var("SOLANA_RPC_URL")
+        .map(|v| v.to_string())
+        .unwrap_or_else(|_| "https://api.mainnet-beta.solana.com".to_string());
+
+    let (pool_pda, pool, a_to_b) =
+        match find_pool_rpc(&rpc_url, &mint_a, &mint_b).await {
+            Ok(r)  => r,
+            Err((code, e)) => return json_error(pool_lookup_status(code), code, &e),
+        };
+    let (reserve_a, reserve_b) =
+        match fetch_reserves(&rpc_url, &pool, true).await {
+            Ok(r)  => r,
+            Err(e) => return json_error(500, ErrorCode::RpcError, &e),
+        };
+    let share_pct = |part: u64, whole: u64| if whole == 0 { 0.0 } else { part as f64 / whole as f64 * 100.0 };
+    let price_before = math::spot_price(pool.curve, reserve_a, reserve_b);
+    let mut result = serde_json::json!({
+        "pool":         pool_pda,
+        "token_a_mint": bs58::encode(&pool.token_a_mint).into_string(),
+        "token_b_mint": bs58::encode(&pool.token_b_mint).into_string(),
+        "price_before": price_before,
+    });
+
+    let fields = if lp_shares > 0 {
+        if lp_shares > pool.lp_supply {
+            return json_error(400, ErrorCode::InvalidArgument, "lp_shares exceeds the pool's LP supply");
+        }
+        let (out_a, out_b) = math::lp_underlying(lp_shares, pool.lp_supply, reserve_a, reserve_b);
+        serde_json::json!({
+            "action":          "remove",
+            "lp_shares":       lp_shares,
+            "amount_a":        out_a,
+            "amount_b":        out_b,
+            "lp_supply_after": pool.lp_supply - lp_shares,
+            "pool_share_pct":  share_pct(lp_shares, pool.lp_supply),
+            "price_after":     math::spot_price(pool.curve, reserve_a - out_a, reserve_b - out_b),
+        })
+    } else {
+        // `amount_a` is the pair's first token; map it onto the pool's sides.
+        let (reserve_fixed, reserve_other) = if a_to_b { (reserve_a, reserve_b) } else { (reserve_b, reserve_a) };
+        let other = match amount_b {
+            Some(b) => b,
+            None if pool.lp_supply == 0 => return json_error(
+                400, ErrorCode::AmountBRequired, "amount_b is required while the pool is empty (it sets the price)",
+            ),
+            None => match math::proportional_amount(amount_a, reserve_fixed, reserve_other) {
+                Ok(b) if b > 0 => b,
+                Ok(_)  => return json_error(400, ErrorCode::AmountBZero, "amount_a is too small for this pool"),
+                Err(_) => return json_error(400, ErrorCode::NoLiquidity, "pool has no liquidity"),
+            },
+        };
+        let (dep_a, dep_b) = if a_to_b { (amount_a, other) } else { (other, amount_a) };
+        let lp_minted = match math::lp_shares_for_deposit(dep_a, dep_b, pool.lp_supply, reserve_a, reserve_b) {
+            Ok(v)  => v,
+            Err(math::MathError::NoLiquidity) => return json_error(400, ErrorCode::NoLiquidity, "pool has no liquidity"),
+            Err(_) => return json_error(400, ErrorCode::MathOverflow, "integer overflow in LP share math"),
+        };
+        let lp_supply_after = pool.lp_supply.saturating_add(lp_minted);
+        serde_json::json!({
+            "action":          "provide",
+            "amount_a":        dep_a,
+            "amount_b":        dep_b,
+            "lp_minted":       lp_minted,
+            "lp_supply_after": lp_supply_after,
+            "pool_share_pct":  share_pct(lp_minted, lp_supply_after),
+            "price_after":     math::spot_price(
+                pool.curve, reserve_a.saturating_add(dep_a), reserve_b.saturating_add(dep_b),
+            ),
+        })
+    };
+    if let (Some(out), serde_json::Value::Object(fields)) = (result.as_object_mut(), fields) {
+        out.extend(fields);
+    }
+    json_ok(&result)
+}
+
 /// POST /convert
 /// Body: { "in": "SOL", "out": "USDC", "amount": 1000000000,
-///         "agent": "<agentPubkey>", "max_slippage_bps": 50 }
+///         "agent": "<agentPubkey>", "max_slippage_bps": 50,
+///         "max_price_impact_bps": 300, "deadline_unix": 1760000000,
+///         "referrer": "<referrerPubkey>" }
+///
+/// `max_price_impact_bps` (default 0 = no cap) is checked here and passed to
+/// the on-chain swap, which enforces it again against live reserves.
+///
+/// `deadline_unix` (default 0 = none) is the last unix time the swap may
+/// land; a deadline already in the past is rejected here.
+///
+/// `referrer` (optional) is a wallet whose `in`-mint ATA is appended as the
+/// trailing `referrer_token` account; it receives the referral share of the
+/// protocol fee. The ATA must already exist.
 ///
 /// Returns the swap instruction in a format the agent can use to build,
 /// sign, and submit its own transaction — no private keys are held here.
@@ -362,24 +594,110 @@ async fn handle_simulate(mut req: Request, ctx: RouteContext<()>) -> Result<Resp
 ///   "instruction": {
 ///     "programId": "8XJfG4m...",
 ///     "accounts":  [ { "pubkey": "...", "isSigner": bool, "isWritable": bool }, ... ],
-///     "data":      "<base64 encoded: disc(swap) || amount_in || min_amount_out || a_to_b>"
+///     "data":      "<base64 encoded: disc(swap) || amount_in || min_amount_out || a_to_b || max_price_impact_bps || deadline_unix>"
 ///   },
+///   "preInstructions": [ ...same shape, to run before "instruction"... ],
 ///   "simulation": { ...full SimulateResult... }
 /// }
+///
+/// `preInstructions` holds a `migrate_pool` instruction (agent pays the extra
+/// rent) when the pool is still on an older account layout, and is empty
+/// otherwise.
+///
+/// Optional `Idempotency-Key` header (1–255 printable ASCII): the first 200
+/// response is stored in the IDEMPOTENCY KV namespace for IDEMPOTENCY_TTL_SECS
+/// (default 600) and replayed for repeats with the same agent + key, so a
+/// retry after a timeout never builds a second swap. The key is echoed in the
+/// `Idempotency-Key` header and `idempotency_key` field; `Idempotent-Replayed`
+/// says whether the response came from KV. Reusing a key with a different
+/// body is 422 IDEMPOTENCY_KEY_REUSED. Errors are not stored.
 async fn handle_convert(mut req: Request, ctx: RouteContext<()>) -> Result<Response> {
     let body: serde_json::Value = match req.json().await {
         Ok(v) => v,
-        Err(_) => return json_error(400, "invalid JSON body"),
+        Err(_) => return json_error(400, ErrorCode::InvalidRequest, "invalid JSON body"),
     };
 
+    let key = match req.headers().get("Idempotency-Key")? {
+        None => return build_convert(body, &ctx).await,
+        Some(k) if is_valid_idempotency_key(&k) => k,
+        Some(_) => return json_error(
+            400,
+            ErrorCode::InvalidArgument,
+            "Idempotency-Key must be 1–255 printable ASCII characters",
+        ),
+    };
+
+    let kv = match ctx.env.kv(IDEMPOTENCY_KV) {
+        Ok(kv) => kv,
+        Err(_) => {
+            // Binding not configured on this deployment — serve uncached.
+            console_log!("convert: Idempotency-Key sent but no {} KV binding", IDEMPOTENCY_KV);
+            return build_convert(body, &ctx).await;
+        }
+    };
+
+    // Scope keys per agent so two agents cannot collide on the same key.
+    let agent       = body["agent"].as_str().unwrap_or("");
+    let kv_key      = format!("convert:{agent}:{key}");
+    let fingerprint = bs58::encode(sha256(&[body.to_string().as_bytes()])).into_string();
+
+    if let Ok(Some(stored)) = kv.get(&kv_key).json::<serde_json::Value>().await {
+        if stored["fingerprint"] != fingerprint.as_str() {
+            return json_error_details(
+                422,
+                ErrorCode::IdempotencyKeyReused,
+                "Idempotency-Key was already used with a different request body",
+                serde_json::json!({ "idempotency_key": key }),
+            );
+        }
+        return idempotent_response(&stored["response"], &key, true);
+    }
+
+    let mut res = build_convert(body, &ctx).await?;
+    if res.status_code() != 200 {
+        return Ok(res);
+    }
+    let mut response: serde_json::Value = res.json().await?;
+    response["idempotency_key"] = serde_json::Value::String(key.clone());
+
+    let ttl = ctx.env.var("IDEMPOTENCY_TTL_SECS")
+        .ok()
+        .and_then(|v| v.to_string().parse::<u64>().ok())
+        .unwrap_or(IDEMPOTENCY_TTL_DEFAULT)
+        .max(60); // KV's minimum expiration_ttl
+    let record = serde_json::json!({ "fingerprint": fingerprint, "response": response });
+    let stored = match kv.put(&kv_key, record.to_string()) {
+        Ok(put) => put.expiration_ttl(ttl).execute().await.is_ok(),
+        Err(_)  => false,
+    };
+    if !stored {
+        // Still return the built instruction — a failed write only loses replay.
+        console_log!("convert: failed to store Idempotency-Key {}", key);
+    }
+
+    idempotent_response(&response, &key, false)
+}
+
+/// Build the /convert response for a parsed body (no idempotency handling).
+async fn build_convert(body: serde_json::Value, ctx: &RouteContext<()>) -> Result<Response> {
     let token_in         = body["in"].as_str().unwrap_or("").to_string();
     let token_out        = body["out"].as_str().unwrap_or("").to_string();
     let amount_in        = body["amount"].as_u64().unwrap_or(0);
     let agent            = body["agent"].as_str().unwrap_or("").to_string();
     let max_slippage_bps = body["max_slippage_bps"].as_u64().unwrap_or(50) as u16;
+    let max_price_impact_bps = body["max_price_impact_bps"].as_u64().unwrap_or(0) as u16;
+    let deadline_unix    = body["deadline_unix"].as_i64().unwrap_or(0);
+    let referrer         = body["referrer"].as_str().filter(|s| !s.is_empty()).map(str::to_string);
 
     if token_in.is_empty() || token_out.is_empty() || amount_in == 0 || agent.is_empty() {
-        return json_error(400, r#"required fields: "in", "out", "amount", "agent""#);
+        return json_error_details(
+            400, ErrorCode::InvalidRequest, r#"required fields: "in", "out", "amount", "agent""#,
+            serde_json::json!({ "required": ["in", "out", "amount", "agent"] }),
+        );
+    }
+
+    if deadline_unix != 0 && deadline_unix < (Date::now().as_millis() / 1000) as i64 {
+        return json_error(400, ErrorCode::InvalidArgument, "deadline_unix has already passed");
     }
 
     console_log!("convert {} {} → {} agent={}", amount_in, token_in, token_out, &agent[..8]);
@@ -387,11 +705,11 @@ async fn handle_convert(mut req: Request, ctx: RouteContext<()>) -> Result<Respo
     // Resolve symbols → mints
     let mint_in = match resolve_mint(&token_in) {
         Some(m) => m,
-        None    => return json_error(400, &format!("unknown token: {token_in}")),
+        None    => return unknown_token(&token_in),
     };
     let mint_out = match resolve_mint(&token_out) {
         Some(m) => m,
-        None    => return json_error(400, &format!("unknown token: {token_out}")),
+        None    => return unknown_token(&token_out),
     };
 
     // RPC endpoint from Cloudflare env binding; fallback to public mainnet
@@ -403,7 +721,7 @@ async fn handle_convert(mut req: Request, ctx: RouteContext<()>) -> Result<Respo
     let (pool_pda, pool_state, a_to_b) =
         match find_pool_rpc(&rpc_url, &mint_in, &mint_out).await {
             Ok(r)  => r,
-            Err(e) => return json_error(404, &e),
+            Err((code, e)) => return json_error(pool_lookup_status(code), code, &e),
         };
     console_log!("convert pool {} a_to_b={}", pool_pda, a_to_b);
 
@@ -411,16 +729,25 @@ async fn handle_convert(mut req: Request, ctx: RouteContext<()>) -> Result<Respo
     let (reserve_in, reserve_out) =
         match fetch_reserves(&rpc_url, &pool_state, a_to_b).await {
             Ok(r)  => r,
-            Err(e) => return json_error(500, &e),
+            Err(e) => return json_error(500, ErrorCode::RpcError, &e),
         };
 
+    let protocol_fee_bps = match fetch_protocol_fee(&rpc_url).await {
+        Ok(f)  => f,
+        Err(e) => return json_error(500, ErrorCode::RpcError, &e),
+    };
+
     // Simulate to get estimated_out + full fee breakdown
     let sim = match simulate_detailed(
-        pool_pda.clone(), &pool_state, reserve_in, reserve_out, amount_in, a_to_b,
+        pool_pda.clone(), &pool_state, reserve_in, reserve_out, amount_in, protocol_fee_bps,
+        a_to_b,
     ) {
         Ok(s)  => s,
-        Err(e) => return json_error(400, e),
+        Err(code) => return json_error(400, code, simulate_error_message(code)),
     };
+    let decimals_in  = fetch_mint_decimals(&rpc_url, &mint_in).await;
+    let decimals_out = fetch_mint_decimals(&rpc_url, &mint_out).await;
+    let sim = sim.with_decimals(decimals_in, decimals_out);
 
     // Apply slippage guard (0 = disabled)
     let min_amount_out = if max_slippage_bps == 0 {
@@ -430,12 +757,23 @@ async fn handle_convert(mut req: Request, ctx: RouteContext<()>) -> Result<Respo
             .saturating_sub(sim.estimated_out * max_slippage_bps as u64 / 10_000)
     };
 
+    // Price-impact cap (0 = disabled) — same integer bps the program checks
+    let impact_bps = math::price_impact_bps(sim.after_fees, sim.reserve_in);
+    if max_price_impact_bps > 0 && impact_bps > max_price_impact_bps as u64 {
+        return json_error_details(
+            400,
+            ErrorCode::PriceImpactExceeded,
+            &format!("price impact {impact_bps} bps exceeds max_price_impact_bps {max_price_impact_bps}"),
+            serde_json::json!({ "impact_bps": impact_bps, "max_bps": max_price_impact_bps }),
+        );
+    }
+
     // ── Derive all 10 accounts for the swap instruction ───────────────────────
     // Account order mirrors sdk/src/instructions.rs::swap_ix exactly.
 
     let pool_authority = match derive_pool_authority_pda(&pool_pda) {
         Ok((a, _)) => a,
-        Err(e)     => return json_error(500, &format!("pool_authority PDA: {e}")),
+        Err(e)     => return json_error(500, ErrorCode::InternalError, &format!("pool_authority PDA: {e}")),
     };
     // Vaults are read from pool state (not derived — they are keypair accounts)
     let vault_a = bs58::encode(&pool_state.token_a_vault).into_string();
@@ -443,28 +781,29 @@ async fn handle_convert(mut req: Request, ctx: RouteContext<()>) -> Result<Respo
 
     let agent_token_in = match derive_ata_address(&agent, &mint_in) {
         Ok(a)  => a,
-        Err(e) => return json_error(500, &format!("agent_token_in ATA: {e}")),
+        Err(e) => return json_error(500, ErrorCode::InternalError, &format!("agent_token_in ATA: {e}")),
     };
     let agent_token_out = match derive_ata_address(&agent, &mint_out) {
         Ok(a)  => a,
-        Err(e) => return json_error(500, &format!("agent_token_out ATA: {e}")),
+        Err(e) => return json_error(500, ErrorCode::InternalError, &format!("agent_token_out ATA: {e}")),
     };
     let treasury = match derive_treasury_pda() {
         Ok((a, _)) => a,
-        Err(e)     => return json_error(500, &format!("treasury PDA: {e}")),
+        Err(e)     => return json_error(500, ErrorCode::InternalError, &format!("treasury PDA: {e}")),
     };
     let treasury_token_in = match derive_ata_address(&treasury, &mint_in) {
         Ok(a)  => a,
-        Err(e) => return json_error(500, &format!("treasury_token_in ATA: {e}")),
+        Err(e) => return json_error(500, ErrorCode::InternalError, &format!("treasury_token_in ATA: {e}")),
+    };
+    let referrer_token = match referrer.as_deref().map(|r| derive_ata_address(r, &mint_in)) {
+        None         => None,
+        Some(Ok(a))  => Some(a),
+        Some(Err(e)) => return json_error(400, ErrorCode::InvalidArgument, &format!("referrer: {e}")),
     };
 
     // ── Build instruction data ────────────────────────────────────────────────
-    // Mirrors sdk/src/instructions.rs::swap_ix:
-    //   disc("swap") [8] || amount_in [8 LE] || min_amount_out [8 LE] || a_to_b [1]
-    let mut ix_data = instruction_disc("swap").to_vec();
-    ix_data.extend_from_slice(&amount_in.to_le_bytes());
-    ix_data.extend_from_slice(&min_amount_out.to_le_bytes());
-    ix_data.push(a_to_b as u8);
+    // Encoded by the IDL-generated `ix::Swap`, like sdk/src/instructions.rs::swap_ix.
+    let ix_data = ix::Swap { amount_in, min_amount_out, a_to_b, max_price_impact_bps, deadline_unix }.data();
 
     use base64::{Engine as _, engine::general_purpose::STANDARD};
     let data_b64 = STANDARD.encode(&ix_data);
@@ -472,29 +811,51 @@ async fn handle_convert(mut req: Request, ctx: RouteContext<()>) -> Result<Respo
     // ── Assemble response ─────────────────────────────────────────────────────
     let sim_json = match serde_json::to_value(&sim) {
         Ok(v)  => v,
-        Err(e) => return json_error(500, &e.to_string()),
+        Err(e) => return json_error(500, ErrorCode::InternalError, &e.to_string()),
     };
 
-    json_ok(&serde_json::json!({
-        "instruction": {
+    // Account order must be preserved exactly — the on-chain program
+    // reads accounts by position, not by name.
+    let mut accounts = serde_json::json!([
+        { "pubkey": agent,              "isSigner": true,  "isWritable": true  },
+        { "pubkey": pool_pda,           "isSigner": false, "isWritable": true  },
+        { "pubkey": pool_authority,     "isSigner": false, "isWritable": false },
+        { "pubkey": vault_a,            "isSigner": false, "isWritable": true  },
+        { "pubkey": vault_b,            "isSigner": false, "isWritable": true  },
+        { "pubkey": agent_token_in,     "isSigner": false, "isWritable": true  },
+        { "pubkey": agent_token_out,    "isSigner": false, "isWritable": true  },
+        { "pubkey": treasury,           "isSigner": false, "isWritable": false },
+        { "pubkey": treasury_token_in,  "isSigner": false, "isWritable": true  },
+        { "pubkey": TOKEN_PROGRAM_ID,   "isSigner": false, "isWritable": false },
+    ]);
+    if let (Some(referrer_token), Some(list)) = (referrer_token, accounts.as_array_mut()) {
+        list.push(serde_json::json!({ "pubkey": referrer_token, "isSigner": false, "isWritable": true }));
+    }
+
+    // Older pool layouts must be migrated before the program can load them.
+    let pre_instructions = if pool_state.version < POOL_VERSION {
+        vec![serde_json::json!({
             "programId": PROGRAM_ID,
-            // Account order must be preserved exactly — the on-chain program
-            // reads accounts by position, not by name.
             "accounts": [
-                { "pubkey": agent,              "isSigner": true,  "isWritable": true  },
-                { "pubkey": pool_pda,           "isSigner": false, "isWritable": true  },
-                { "pubkey": pool_authority,     "isSigner": false, "isWritable": false },
-                { "pubkey": vault_a,            "isSigner": false, "isWritable": true  },
-                { "pubkey": vault_b,            "isSigner": false, "isWritable": true  },
-                { "pubkey": agent_token_in,     "isSigner": false, "isWritable": true  },
-                { "pubkey": agent_token_out,    "isSigner": false, "isWritable": true  },
-                { "pubkey": treasury,           "isSigner": false, "isWritable": false },
-                { "pubkey": treasury_token_in,  "isSigner": false, "isWritable": true  },
-                { "pubkey": TOKEN_PROGRAM_ID,   "isSigner": false, "isWritable": false },
+                { "pubkey": agent,             "isSigner": true,  "isWritable": true  },
+                { "pubkey": pool_pda,          "isSigner": false, "isWritable": true  },
+                { "pubkey": SYSTEM_PROGRAM_ID, "isSigner": false, "isWritable": false },
             ],
+            "data": STANDARD.encode(ix::MigratePool.data()),
+        })]
+    } else {
+        Vec::new()
+    };
+
+    json_ok(&serde_json::json!({
+        "instruction": {
+            "programId": PROGRAM_ID,
+            "accounts":  accounts,
             "data": data_b64,
         },
+        "preInstructions": pre_instructions,
         "simulation": sim_json,
+        "min_out_ui": decimals_out.map(|d| format_units(min_amount_out, d)),
     }))
 }
 
@@ -508,21 +869,21 @@ async fn handle_pool_info(req: Request, ctx: RouteContext<()>) -> Result<Respons
         .map(|(_, v)| v.into_owned())
         .unwrap_or_default();
     if pair.is_empty() {
-        return json_error(400, "missing query param: pair (e.g. ?pair=SOL-USDC)");
+        return json_error(400, ErrorCode::InvalidRequest, "missing query param: pair (e.g. ?pair=SOL-USDC)");
     }
 
     let parts: Vec<&str> = pair.splitn(2, '-').collect();
     if parts.len() != 2 || parts[0].is_empty() || parts[1].is_empty() {
-        return json_error(400, "pair must be two tokens separated by \"-\" (e.g. SOL-USDC)");
+        return json_error(400, ErrorCode::InvalidArgument, "pair must be two tokens separated by \"-\" (e.g. SOL-USDC)");
     }
 
     let mint_a = match resolve_mint(parts[0]) {
         Some(m) => m,
-        None    => return json_error(400, &format!("unknown token: {}", parts[0])),
+        None    => return unknown_token(parts[0]),
     };
     let mint_b = match resolve_mint(parts[1]) {
         Some(m) => m,
-        None    => return json_error(400, &format!("unknown token: {}", parts[1])),
+        None    => return unknown_token(parts[1]),
     };
 
     let rpc_url = ctx.env.var("SOLANA_RPC_URL")
@@ -532,16 +893,21 @@ async fn handle_pool_info(req: Request, ctx: RouteContext<()>) -> Result<Respons
     let (pool_pda, pool_state, _) =
         match find_pool_rpc(&rpc_url, &mint_a, &mint_b).await {
             Ok(r)  => r,
-            Err(e) => return json_error(404, &e),
+            Err((code, e)) => return json_error(pool_lookup_status(code), code, &e),
         };
 
     // Always return reserves in canonical pool order (a_to_b=true → ra, rb)
     let (reserve_a, reserve_b) =
         match fetch_reserves(&rpc_url, &pool_state, true).await {
             Ok(r)  => r,
-            Err(e) => return json_error(500, &e),
+            Err(e) => return json_error(500, ErrorCode::RpcError, &e),
         };
 
+    let protocol_fee_bps = match fetch_protocol_fee(&rpc_url).await {
+        Ok(f)  => f,
+        Err(e) => return json_error(500, ErrorCode::RpcError, &e),
+    };
+
     let spot_a_to_b = if reserve_a == 0 { 0.0 } else { reserve_b as f64 / reserve_a as f64 };
     let spot_b_to_a = if reserve_b == 0 { 0.0 } else { reserve_a as f64 / reserve_b as f64 };
 
@@ -553,6 +919,15 @@ async fn handle_pool_info(req: Request, ctx: RouteContext<()>) -> Result<Respons
         "reserve_b":         reserve_b,
         "lp_supply":         pool_state.lp_supply,
         "fee_rate_bps":      pool_state.fee_rate_bps,
+        "protocol_fee_bps":  protocol_fee_bps,
+        "volume_a":          pool_state.volume_a,
+        "volume_b":          pool_state.volume_b,
+        "fees_collected_a":  pool_state.fees_collected_a,
+        "fees_collected_b":  pool_state.fees_collected_b,
+        "version":           pool_state.version,
+        "permissioned":      pool_state.allowlist_root != [0; 32],
+        "gate_mint":         (pool_state.gate_mint != [0; 32])
+                                 .then(|| bs58::encode(&pool_state.gate_mint).into_string()),
         "spot_price_a_to_b": spot_a_to_b,
         "spot_price_b_to_a": spot_b_to_a,
     }))
@@ -567,22 +942,22 @@ async fn handle_my_positions(req: Request, ctx: RouteContext<()>) -> Result<Resp
         .map(|(_, v)| v.into_owned())
         .unwrap_or_default();
     if owner.is_empty() {
-        return json_error(400, "missing query param: pubkey");
+        return json_error(400, ErrorCode::InvalidRequest, "missing query param: pubkey");
     }
     if owner.len() < 32 || owner.len() > 44 {
-        return json_error(400, "pubkey must be a base58 Solana address");
+        return json_error(400, ErrorCode::InvalidArgument, "pubkey must be a base58 Solana address");
     }
 
     let rpc_url = ctx.env.var("SOLANA_RPC_URL")
         .map(|v| v.to_string())
         .unwrap_or_else(|_| "https://api.mainnet-beta.solana.com".to_string());
 
-    // Filter: dataSize=138, memcmp at offset 8 = owner pubkey (base58)
+    // Filter: Position discriminator, memcmp at offset 8 = owner pubkey (base58)
     let accounts = match rpc_get_program_accounts(
-        &rpc_url, PROGRAM_ID, 138, 8, &owner,
+        &rpc_url, PROGRAM_ID, a2a_swap_core::Position::DISCRIMINATOR, 8, &owner,
     ).await {
         Ok(v)  => v,
-        Err(e) => return json_error(500, &e),
+        Err(e) => return json_error(500, ErrorCode::RpcError, &e),
     };
 
     let mut positions = Vec::with_capacity(accounts.len());
@@ -599,6 +974,7 @@ async fn handle_my_positions(req: Request, ctx: RouteContext<()>) -> Result<Resp
             "fees_owed_b":       pos.fees_owed_b,
             "auto_compound":     pos.auto_compound,
             "compound_threshold": pos.compound_threshold,
+            "version":           pos.version,
         }));
     }
 
@@ -615,10 +991,10 @@ async fn handle_my_fees(req: Request, ctx: RouteContext<()>) -> Result<Response>
         .map(|(_, v)| v.into_owned())
         .unwrap_or_default();
     if owner.is_empty() {
-        return json_error(400, "missing query param: pubkey");
+        return json_error(400, ErrorCode::InvalidRequest, "missing query param: pubkey");
     }
     if owner.len() < 32 || owner.len() > 44 {
-        return json_error(400, "pubkey must be a base58 Solana address");
+        return json_error(400, ErrorCode::InvalidArgument, "pubkey must be a base58 Solana address");
     }
 
     let rpc_url = ctx.env.var("SOLANA_RPC_URL")
@@ -627,10 +1003,10 @@ async fn handle_my_fees(req: Request, ctx: RouteContext<()>) -> Result<Response>
 
     // Fetch all Position accounts owned by this wallet
     let accounts = match rpc_get_program_accounts(
-        &rpc_url, PROGRAM_ID, 138, 8, &owner,
+        &rpc_url, PROGRAM_ID, a2a_swap_core::Position::DISCRIMINATOR, 8, &owner,
     ).await {
         Ok(v)  => v,
-        Err(e) => return json_error(500, &e),
+        Err(e) => return json_error(500, ErrorCode::RpcError, &e),
     };
 
     let mut results = Vec::with_capacity(accounts.len());
@@ -650,7 +1026,7 @@ async fn handle_my_fees(req: Request, ctx: RouteContext<()>) -> Result<Response>
             _ => continue,
         };
 
-        let (pending_a, pending_b) = pending_fees_for_position(&pos, &pool_state);
+        let (pending_a, pending_b) = math::pending_fees_for_position(&pos, &pool_state);
 
         results.push(serde_json::json!({
             "position":       pos_pubkey,
@@ -668,14 +1044,220 @@ async fn handle_my_fees(req: Request, ctx: RouteContext<()>) -> Result<Response>
     json_ok(&serde_json::json!({ "fees": results }))
 }
 
+/// Default and maximum airdrop for /devnet/bootstrap (the devnet faucet caps
+/// single requests at a few SOL and rate-limits aggressively).
+const DEVNET_AIRDROP_DEFAULT: u64 = 1_000_000_000;
+const DEVNET_AIRDROP_MAX:     u64 = 2_000_000_000;
+
+/// POST /devnet/bootstrap
+/// Body: { "agent": "<BASE58>", "lamports"?: 1000000000 }
+/// Only served when SOLANA_NETWORK = "devnet". Airdrops SOL to the agent,
+/// lists the seeded test pools from DEVNET_TEST_POOLS (comma-separated pool
+/// PDAs), and returns unsigned createAssociatedTokenAccountIdempotent
+/// instructions for any test-token ATA the agent does not have yet.
+/// The worker holds no keys — the agent signs and pays for the ATAs itself
+/// with the airdropped SOL.
+async fn handle_devnet_bootstrap(mut req: Request, ctx: RouteContext<()>) -> Result<Response> {
+    if network(&ctx.env) != "devnet" {
+        return json_error(403, ErrorCode::NotAvailable, "devnet bootstrap is only available on the devnet deployment");
+    }
+
+    let body: serde_json::Value = match req.json().await {
+        Ok(v) => v,
+        Err(_) => return json_error(400, ErrorCode::InvalidRequest, "invalid JSON body"),
+    };
+    let agent    = body["agent"].as_str().unwrap_or("").to_string();
+    let lamports = body["lamports"].as_u64().unwrap_or(DEVNET_AIRDROP_DEFAULT);
+
+    match bs58::decode(&agent).into_vec() {
+        Ok(b) if b.len() == 32 => {}
+        _ => return json_error(400, ErrorCode::InvalidRequest, r#"required field: "agent" (base58 pubkey)"#),
+    }
+    if lamports > DEVNET_AIRDROP_MAX {
+        return json_error(400, ErrorCode::InvalidArgument, &format!("lamports must be ≤ {DEVNET_AIRDROP_MAX}"));
+    }
+
+    console_log!("devnet bootstrap agent={} lamports={}", &agent[..8], lamports);
+
+    let rpc_url = ctx.env.var("SOLANA_RPC_URL")
+        .map(|v| v.to_string())
+        .unwrap_or_else(|_| "https://api.devnet.solana.com".to_string());
+
+    // ── 1. Airdrop (faucet failures are reported, not fatal) ──────────────────
+    let airdrop = if lamports == 0 {
+        serde_json::json!({ "skipped": true })
+    } else {
+        match rpc_call(&rpc_url, "requestAirdrop", serde_json::json!([agent, lamports])).await {
+            Ok(sig) => serde_json::json!({ "signature": sig, "lamports": lamports }),
+            Err(e)  => serde_json::json!({ "error": e, "lamports": lamports }),
+        }
+    };
+
+    // ── 2. Seeded test pools ──────────────────────────────────────────────────
+    let pool_list = ctx.env.var("DEVNET_TEST_POOLS")
+        .map(|v| v.to_string())
+        .unwrap_or_default();
+
+    let mut pools = Vec::new();
+    let mut mints: Vec<String> = Vec::new();
+    for pool_b58 in pool_list.split(',').map(str::trim).filter(|p| !p.is_empty()) {
+        let data = match rpc_get_account_info(&rpc_url, pool_b58).await {
+            Ok(Some(d)) => d,
+            Ok(None)    => return json_error(500, ErrorCode::InternalError, &format!("test pool not found: {pool_b58}")),
+            Err(e)      => return json_error(500, ErrorCode::RpcError, &e),
+        };
+        let pool = match parse_pool(&data) {
+            Ok(p)  => p,
+            Err(e) => return json_error(500, ErrorCode::AccountParseError, &format!("test pool {pool_b58}: {e}")),
+        };
+        let (reserve_a, reserve_b) = match fetch_reserves(&rpc_url, &pool, true).await {
+            Ok(r)  => r,
+            Err(e) => return json_error(500, ErrorCode::RpcError, &e),
+        };
+        let mint_a = bs58::encode(&pool.token_a_mint).into_string();
+        let mint_b = bs58::encode(&pool.token_b_mint).into_string();
+        for m in [&mint_a, &mint_b] {
+            if !mints.contains(m) {
+                mints.push(m.clone());
+            }
+        }
+        pools.push(serde_json::json!({
+            "pool":         pool_b58,
+            "token_a_mint": mint_a,
+            "token_b_mint": mint_b,
+            "reserve_a":    reserve_a,
+            "reserve_b":    reserve_b,
+            "lp_supply":    pool.lp_supply,
+            "fee_rate_bps": pool.fee_rate_bps,
+        }));
+    }
+
+    // ── 3. Test-token ATAs the agent still needs ──────────────────────────────
+    let mut atas = Vec::with_capacity(mints.len());
+    let mut instructions = Vec::new();
+    for mint in &mints {
+        let ata = match derive_ata_address(&agent, mint) {
+            Ok(a)  => a,
+            Err(e) => return json_error(500, ErrorCode::InternalError, &format!("agent ATA: {e}")),
+        };
+        let exists = match rpc_get_account_info(&rpc_url, &ata).await {
+            Ok(acct) => acct.is_some(),
+            Err(e)   => return json_error(500, ErrorCode::RpcError, &e),
+        };
+        if !exists {
+            instructions.push(serde_json::json!({
+                "programId": ATA_PROGRAM_ID,
+                "accounts": [
+                    { "pubkey": agent,              "isSigner": true,  "isWritable": true  },
+                    { "pubkey": ata,                "isSigner": false, "isWritable": true  },
+                    { "pubkey": agent,              "isSigner": false, "isWritable": false },
+                    { "pubkey": mint,               "isSigner": false, "isWritable": false },
+                    { "pubkey": SYSTEM_PROGRAM_ID,  "isSigner": false, "isWritable": false },
+                    { "pubkey": TOKEN_PROGRAM_ID,   "isSigner": false, "isWritable": false },
+                ],
+                "data": "AQ==",  // [1] = CreateIdempotent
+            }));
+        }
+        atas.push(serde_json::json!({ "mint": mint, "ata": ata, "exists": exists }));
+    }
+
+    json_ok(&serde_json::json!({
+        "network":      "devnet",
+        "program":      PROGRAM_ID,
+        "agent":        agent,
+        "airdrop":      airdrop,
+        "pools":        pools,
+        "atas":         atas,
+        "instructions": instructions,
+    }))
+}
+
 /// Catch-all for unknown routes
+/// GET /metrics  →  per-route summary accumulated by this isolate
+///
+/// Workers run many isolates, each with its own counters, so this is a spot
+/// check of one instance — use the Analytics Engine dataset or OTLP backend
+/// (see `record_request`) for fleet-wide error rates.
+fn handle_metrics(_req: Request, ctx: RouteContext<()>) -> Result<Response> {
+    let routes = ROUTE_STATS.with(|stats| {
+        stats.borrow().iter().map(|(route, s)| {
+            let errors = s.errors_4xx + s.errors_5xx;
+            (route.to_string(), serde_json::json!({
+                "requests":       s.requests,
+                "errors_4xx":     s.errors_4xx,
+                "errors_5xx":     s.errors_5xx,
+                "error_rate":     errors as f64 / s.requests.max(1) as f64,
+                "latency_ms_avg": s.latency_ms_total / s.requests.max(1),
+                "latency_ms_max": s.latency_ms_max,
+                "rpc_calls":      s.rpc_calls,
+            }))
+        }).collect::<serde_json::Map<_, _>>()
+    });
+
+    json_ok(&serde_json::json!({
+        "scope":         "isolate",
+        "isolate_since": ISOLATE_STARTED.with(|t| t.get()),
+        "network":       network(&ctx.env),
+        "exporters": {
+            "analytics_engine": ctx.env.analytics_engine(METRICS_DATASET).is_ok(),
+            "otlp":             ctx.env.var("OTLP_ENDPOINT").is_ok(),
+        },
+        "routes": routes,
+    }))
+}
+
 fn handle_not_found(req: Request, _ctx: RouteContext<()>) -> Result<Response> {
     console_log!("404 {}", req.path());
-    json_error(404, &format!("route not found: {}", req.path()))
+    json_error_details(
+        404,
+        ErrorCode::RouteNotFound,
+        &format!("route not found: {}", req.path()),
+        serde_json::json!({ "path": req.path() }),
+    )
 }
 
 // ── Helpers ───────────────────────────────────────────────────────────────────
 
+/// Cluster this deployment serves (SOLANA_NETWORK var), default "mainnet-beta".
+fn network(env: &Env) -> String {
+    env.var("SOLANA_NETWORK")
+        .map(|v| v.to_string())
+        .unwrap_or_else(|_| "mainnet-beta".to_string())
+}
+
+/// Generic JSON-RPC call via worker::Fetch; returns the `result` field.
+async fn rpc_call(
+    rpc_url: &str,
+    method:  &str,
+    params:  serde_json::Value,
+) -> std::result::Result<serde_json::Value, String> {
+    let payload = serde_json::json!({
+        "jsonrpc": "2.0",
+        "id":      1,
+        "method":  method,
+        "params":  params,
+    });
+    let body = serde_json::to_string(&payload).map_err(|e| e.to_string())?;
+
+    let headers = Headers::new();
+    headers.set("Content-Type", "application/json").map_err(|e| e.to_string())?;
+
+    let mut init = RequestInit::new();
+    init.with_method(Method::Post)
+        .with_headers(headers)
+        .with_body(Some(body.into()));
+
+    let req = Request::new_with_init(rpc_url, &init).map_err(|e| e.to_string())?;
+    RPC_CALLS.with(|c| c.set(c.get() + 1));
+    let mut res = Fetch::Request(req).send().await.map_err(|e| e.to_string())?;
+    let json: serde_json::Value = res.json().await.map_err(|e| e.to_string())?;
+
+    if let Some(err) = json.get("error") {
+        return Err(format!("RPC error: {err}"));
+    }
+    Ok(json["result"].clone())
+}
+
 /// Return a 200 JSON response.
 fn json_ok(body: &serde_json::Value) -> Result<Response> {
     let mut res = Response::from_json(body)?;
@@ -684,50 +1266,262 @@ fn json_ok(body: &serde_json::Value) -> Result<Response> {
     Ok(res)
 }
 
-/// Return an error JSON response with the given HTTP status.
-fn json_error(status: u16, message: &str) -> Result<Response> {
-    let body = serde_json::json!({ "error": message });
+/// KV binding holding stored /convert responses.
+const IDEMPOTENCY_KV: &str = "IDEMPOTENCY";
+/// Default replay window for an Idempotency-Key, in seconds.
+const IDEMPOTENCY_TTL_DEFAULT: u64 = 600;
+
+/// 1–255 printable, non-space ASCII characters.
+fn is_valid_idempotency_key(key: &str) -> bool {
+    (1..=255).contains(&key.len()) && key.bytes().all(|b| (0x21..=0x7e).contains(&b))
+}
+
+/// 200 JSON response echoing the key and whether it was replayed from KV.
+fn idempotent_response(body: &serde_json::Value, key: &str, replayed: bool) -> Result<Response> {
+    let mut res = json_ok(body)?;
+    res.headers_mut().set("Idempotency-Key", key)?;
+    res.headers_mut().set("Idempotent-Replayed", if replayed { "true" } else { "false" })?;
+    Ok(res)
+}
+
+/// Return an error envelope with the given HTTP status:
+/// `{ "error": { "code": "POOL_NOT_FOUND", "message": "...", "details": {} } }`
+fn json_error(status: u16, code: ErrorCode, message: &str) -> Result<Response> {
+    json_error_details(status, code, message, serde_json::json!({}))
+}
+
+/// [`json_error`] with a structured `details` object agents can read fields from.
+fn json_error_details(
+    status:  u16,
+    code:    ErrorCode,
+    message: &str,
+    details: serde_json::Value,
+) -> Result<Response> {
+    let body = serde_json::json!({
+        "error": {
+            "code":    code.as_str(),
+            "message": message,
+            "details": details,
+        },
+    });
     let res = Response::from_json(&body)?
         .with_status(status);
     Ok(res)
 }
 
-// ── PDA derivation ────────────────────────────────────────────────────────────
+/// 400 UNKNOWN_TOKEN for a symbol `resolve_mint` could not map.
+fn unknown_token(token: &str) -> Result<Response> {
+    json_error_details(
+        400,
+        ErrorCode::UnknownToken,
+        &format!("unknown token: {token}"),
+        serde_json::json!({
+            "token": token,
+            "known": tokens::KNOWN_TOKENS.iter().map(|t| t.symbol).collect::<Vec<_>>(),
+        }),
+    )
+}
+
+/// HTTP status for a `find_pool_rpc` failure.
+fn pool_lookup_status(code: ErrorCode) -> u16 {
+    match code {
+        ErrorCode::PoolNotFound      => 404,
+        ErrorCode::AccountParseError => 500,
+        _                            => 502,
+    }
+}
+
+/// Human message for a `simulate_detailed` failure.
+fn simulate_error_message(code: ErrorCode) -> &'static str {
+    match code {
+        ErrorCode::NoLiquidity => "pool has no liquidity",
+        _                      => "integer overflow in fee / swap math",
+    }
+}
+
+// ── Metrics ───────────────────────────────────────────────────────────────────
 //
-// Mirrors sdk/src/instructions.rs PDA helpers but uses sha2 + curve25519-dalek
-// instead of solana_sdk (which cannot compile to wasm32-unknown-unknown).
+// Every request produces one RequestMetric. It is folded into this isolate's
+// in-memory summary (GET /metrics) and exported to whichever sinks are
+// configured:
+//   METRICS        Analytics Engine binding — one data point per request
+//                  (index: route; blobs: route, method, status, network;
+//                   doubles: latency_ms, rpc_calls, status)
+//   OTLP_ENDPOINT  OTLP/HTTP collector base URL — one server span per request,
+//                  POSTed to {OTLP_ENDPOINT}/v1/traces after the response;
+//                  OTLP_AUTH (secret) is sent as the Authorization header
+
+/// Analytics Engine binding name.
+const METRICS_DATASET: &str = "METRICS";
+
+thread_local! {
+    /// RPC calls made by the current request. Requests that overlap in one
+    /// isolate share the counter, so treat it as approximate under load.
+    static RPC_CALLS: std::cell::Cell<u32> = const { std::cell::Cell::new(0) };
+    static ROUTE_STATS: std::cell::RefCell<std::collections::BTreeMap<&'static str, RouteStats>> =
+        const { std::cell::RefCell::new(std::collections::BTreeMap::new()) };
+    static ISOLATE_STARTED: std::cell::Cell<u64> = std::cell::Cell::new(Date::now().as_millis());
+}
+
+struct RequestMetric {
+    route:      &'static str,
+    method:     String,
+    status:     u16,
+    started:    u64,
+    latency_ms: u64,
+    rpc_calls:  u32,
+    ray:        String,
+}
+
+#[derive(Default)]
+struct RouteStats {
+    requests:         u64,
+    errors_4xx:       u64,
+    errors_5xx:       u64,
+    latency_ms_total: u64,
+    latency_ms_max:   u64,
+    rpc_calls:        u64,
+}
+
+/// Fixed route label, so unknown paths cannot blow up metric cardinality.
+fn route_label(path: &str) -> &'static str {
+    match path {
+        "/"                 => "/",
+        "/health"           => "/health",
+        "/simulate"         => "/simulate",
+        "/simulate-liquidity" => "/simulate-liquidity",
+        "/convert"          => "/convert",
+        "/pool-info"        => "/pool-info",
+        "/my-positions"     => "/my-positions",
+        "/my-fees"          => "/my-fees",
+        "/devnet/bootstrap" => "/devnet/bootstrap",
+        "/metrics"          => "/metrics",
+        _                   => "not_found",
+    }
+}
+
+/// Fold `m` into the isolate summary and push it to the configured sinks.
+/// Export failures are logged, never surfaced to the caller.
+fn record_request(env: &Env, ctx: &Context, m: RequestMetric) {
+    // Touch the start time so /metrics reports when counting began.
+    ISOLATE_STARTED.with(|_| ());
+    ROUTE_STATS.with(|stats| {
+        let mut stats = stats.borrow_mut();
+        let s = stats.entry(m.route).or_default();
+        s.requests         += 1;
+        s.errors_4xx       += u64::from((400..500).contains(&m.status));
+        s.errors_5xx       += u64::from(m.status >= 500);
+        s.latency_ms_total += m.latency_ms;
+        s.latency_ms_max    = s.latency_ms_max.max(m.latency_ms);
+        s.rpc_calls        += u64::from(m.rpc_calls);
+    });
+
+    console_log!(
+        "{} {} → {} in {}ms ({} rpc)",
+        m.method, m.route, m.status, m.latency_ms, m.rpc_calls
+    );
+
+    if let Ok(dataset) = env.analytics_engine(METRICS_DATASET) {
+        let point = AnalyticsEngineDataPointBuilder::new()
+            .indexes([m.route])
+            .add_blob(m.route)
+            .add_blob(m.method.as_str())
+            .add_blob(m.status.to_string().as_str())
+            .add_blob(network(env).as_str())
+            .add_double(m.latency_ms as f64)
+            .add_double(m.rpc_calls)
+            .add_double(m.status)
+            .write_to(&dataset);
+        if let Err(e) = point {
+            console_log!("metrics: analytics engine write failed: {}", e);
+        }
+    }
+
+    if let Ok(endpoint) = env.var("OTLP_ENDPOINT") {
+        let url  = format!("{}/v1/traces", endpoint.to_string().trim_end_matches('/'));
+        let auth = env.secret("OTLP_AUTH").ok().map(|v| v.to_string());
+        let body = otlp_span(&m, &network(env)).to_string();
+        ctx.wait_until(async move {
+            if let Err(e) = post_otlp(&url, auth.as_deref(), body).await {
+                console_log!("metrics: OTLP export failed: {}", e);
+            }
+        });
+    }
+}
+
+/// One OTLP/JSON server span describing the request.
+fn otlp_span(m: &RequestMetric, network: &str) -> serde_json::Value {
+    // No RNG in the worker — derive stable ids from the cf-ray + start time.
+    let id = sha256(&[m.ray.as_bytes(), &m.started.to_le_bytes()]);
+    let hex = |bytes: &[u8]| bytes.iter().map(|b| format!("{b:02x}")).collect::<String>();
+    let attr = |key: &str, value: serde_json::Value| serde_json::json!({ "key": key, "value": value });
+
+    serde_json::json!({
+        "resourceSpans": [{
+            "resource": { "attributes": [
+                attr("service.name",    serde_json::json!({ "stringValue": "a2a-swap-api" })),
+                attr("service.version", serde_json::json!({ "stringValue": VERSION })),
+                attr("solana.network",  serde_json::json!({ "stringValue": network })),
+            ]},
+            "scopeSpans": [{
+                "scope": { "name": "a2a-swap-api" },
+                "spans": [{
+                    "traceId":           hex(&id[..16]),
+                    "spanId":            hex(&id[16..24]),
+                    "name":              format!("{} {}", m.method, m.route),
+                    "kind":              2, // SPAN_KIND_SERVER
+                    "startTimeUnixNano": (m.started * 1_000_000).to_string(),
+                    "endTimeUnixNano":   ((m.started + m.latency_ms) * 1_000_000).to_string(),
+                    "attributes": [
+                        attr("http.route",                serde_json::json!({ "stringValue": m.route })),
+                        attr("http.request.method",       serde_json::json!({ "stringValue": m.method })),
+                        attr("http.response.status_code", serde_json::json!({ "intValue": m.status.to_string() })),
+                        attr("a2a.rpc_calls",             serde_json::json!({ "intValue": m.rpc_calls.to_string() })),
+                        attr("cf.ray",                    serde_json::json!({ "stringValue": m.ray })),
+                    ],
+                    // STATUS_CODE_ERROR for 5xx only, per HTTP server semconv
+                    "status": { "code": if m.status >= 500 { 2 } else { 0 } },
+                }],
+            }],
+        }],
+    })
+}
+
+async fn post_otlp(url: &str, auth: Option<&str>, body: String) -> Result<()> {
+    let headers = Headers::new();
+    headers.set("Content-Type", "application/json")?;
+    if let Some(auth) = auth {
+        headers.set("Authorization", auth)?;
+    }
+    let mut init = RequestInit::new();
+    init.with_method(Method::Post)
+        .with_headers(headers)
+        .with_body(Some(body.into()));
+    let res = Fetch::Request(Request::new_with_init(url, &init)?).send().await?;
+    if res.status_code() >= 300 {
+        return Err(Error::RustError(format!("collector returned {}", res.status_code())));
+    }
+    Ok(())
+}
+
+// ── PDA derivation ────────────────────────────────────────────────────────────
 //
-// On-chain seeds confirmed in programs/a2a-swap/src/instructions/:
-//   Pool:           ["pool",           mint_a, mint_b]
-//   PoolAuthority:  ["pool_authority", pool]
-//   Treasury:       ["treasury"]
-//   ATA:            [wallet, token_program, mint]  (program = ATA_PROGRAM_ID)
+// a2a_swap_core::pda derives on raw key bytes with sha2 + curve25519-dalek
+// (solana_sdk cannot compile to wasm32-unknown-unknown); these wrappers take
+// and return base58.
 // NOTE: pool mints are NOT sorted — caller must try both AB and BA orderings.
 
-/// Generic find_program_address: SHA-256(seeds... ‖ [nonce] ‖ program_id ‖ "ProgramDerivedAddress")
-/// Tries nonces 255 → 0, returns the first candidate NOT on the Ed25519 curve.
-fn find_pda(
-    seeds:          &[&[u8]],
-    program_id_b58: &str,
-) -> std::result::Result<(String, u8), String> {
-    let program_id = bs58::decode(program_id_b58)
+/// Decode a base58 public key, naming `what` in the error.
+fn decode_key(b58: &str, what: &str) -> std::result::Result<[u8; 32], String> {
+    bs58::decode(b58)
         .into_vec()
-        .map_err(|_| format!("invalid program_id: {program_id_b58}"))?;
-
-    for nonce in (0u8..=255).rev() {
-        let nonce_buf = [nonce];
-        let mut inputs: Vec<&[u8]> = Vec::with_capacity(seeds.len() + 3);
-        inputs.extend_from_slice(seeds);
-        inputs.push(&nonce_buf);
-        inputs.push(&program_id);
-        inputs.push(b"ProgramDerivedAddress");
-
-        let candidate = pda_hash(&inputs);
-        if !is_on_ed25519_curve(&candidate) {
-            return Ok((bs58::encode(candidate).into_string(), nonce));
-        }
-    }
-    Err("could not find a valid PDA nonce (exhausted 0–255)".into())
+        .ok()
+        .and_then(|v| <[u8; 32]>::try_from(v).ok())
+        .ok_or_else(|| format!("invalid {what}: {b58}"))
+}
+
+fn encode_pda((key, bump): ([u8; 32], u8)) -> (String, u8) {
+    (bs58::encode(key).into_string(), bump)
 }
 
 /// Derive pool PDA for a specific (mint_a, mint_b) ordering.
@@ -736,47 +1530,31 @@ fn derive_pool_pda(
     mint_a_b58: &str,
     mint_b_b58: &str,
 ) -> std::result::Result<(String, u8), String> {
-    let mint_a = bs58::decode(mint_a_b58).into_vec()
-        .map_err(|_| format!("invalid mint_a: {mint_a_b58}"))?;
-    let mint_b = bs58::decode(mint_b_b58).into_vec()
-        .map_err(|_| format!("invalid mint_b: {mint_b_b58}"))?;
-    if mint_a.len() != 32 || mint_b.len() != 32 {
-        return Err("mints must be 32 bytes".into());
-    }
-    find_pda(&[b"pool", &mint_a, &mint_b], PROGRAM_ID)
+    let mint_a = decode_key(mint_a_b58, "mint_a")?;
+    let mint_b = decode_key(mint_b_b58, "mint_b")?;
+    Ok(encode_pda(pda::derive_pool(&mint_a, &mint_b, &PROGRAM_ADDRESS)))
 }
 
 /// Derive the pool-authority PDA (signs vault transfers on behalf of the pool).
 fn derive_pool_authority_pda(pool_b58: &str) -> std::result::Result<(String, u8), String> {
-    let pool = bs58::decode(pool_b58).into_vec()
-        .map_err(|_| format!("invalid pool: {pool_b58}"))?;
-    find_pda(&[b"pool_authority", &pool], PROGRAM_ID)
+    let pool = decode_key(pool_b58, "pool")?;
+    Ok(encode_pda(pda::derive_pool_authority(&pool, &PROGRAM_ADDRESS)))
 }
 
 /// Derive the global treasury PDA (receives protocol fees).
 fn derive_treasury_pda() -> std::result::Result<(String, u8), String> {
-    find_pda(&[b"treasury"], PROGRAM_ID)
+    Ok(encode_pda(pda::derive_treasury(&PROGRAM_ADDRESS)))
 }
 
 /// Derive the Associated Token Account (ATA) for a wallet + mint.
-/// Uses ATA_PROGRAM_ID as the derive program (not the main swap program).
 fn derive_ata_address(wallet_b58: &str, mint_b58: &str) -> std::result::Result<String, String> {
-    let wallet        = bs58::decode(wallet_b58).into_vec()
-        .map_err(|_| format!("invalid wallet: {wallet_b58}"))?;
-    let mint          = bs58::decode(mint_b58).into_vec()
-        .map_err(|_| format!("invalid mint: {mint_b58}"))?;
-    let token_program = bs58::decode(TOKEN_PROGRAM_ID).into_vec()
-        .map_err(|_| "invalid TOKEN_PROGRAM_ID".to_string())?;
-    if wallet.len() != 32 || mint.len() != 32 {
-        return Err("wallet and mint must be 32 bytes".into());
-    }
-    let (ata, _) = find_pda(&[&wallet, &token_program, &mint], ATA_PROGRAM_ID)?;
-    Ok(ata)
+    let wallet = decode_key(wallet_b58, "wallet")?;
+    let mint   = decode_key(mint_b58, "mint")?;
+    Ok(bs58::encode(pda::derive_ata(&wallet, &mint)).into_string())
 }
 
 /// SHA-256 over the concatenation of all input slices.
-/// Identical to solana_sdk::hash::Hasher — no length prefixes, no separators.
-fn pda_hash(inputs: &[&[u8]]) -> [u8; 32] {
+fn sha256(inputs: &[&[u8]]) -> [u8; 32] {
     use sha2::{Digest, Sha256};
     let mut h = Sha256::new();
     for input in inputs {
@@ -785,70 +1563,26 @@ fn pda_hash(inputs: &[&[u8]]) -> [u8; 32] {
     h.finalize().into()
 }
 
-/// Returns true if `bytes` is a valid compressed Ed25519 point.
-/// Valid PDAs must NOT be on the curve — mirrors solana_sdk::pubkey::bytes_are_curve_point.
-fn is_on_ed25519_curve(bytes: &[u8; 32]) -> bool {
-    use curve25519_dalek::edwards::CompressedEdwardsY;
-    CompressedEdwardsY(*bytes).decompress().is_some()
-}
-
-/// Anchor instruction discriminator: sha256("global:{name}")[..8].
-/// Mirrors sdk/src/instructions.rs::disc.
-fn instruction_disc(name: &str) -> [u8; 8] {
-    let h = pda_hash(&[format!("global:{name}").as_bytes()]);
-    h[..8].try_into().expect("8 bytes from 32-byte hash")
-}
-
-// ── SDK math ported from sdk/src/math.rs and sdk/src/state.rs for WASM compatibility ──
+// ── Account state ─────────────────────────────────────────────────────────────
 //
 // a2a-swap-sdk depends on solana-client (native TCP / tokio) which cannot compile
-// to wasm32-unknown-unknown (the Cloudflare Workers target).  The functions below
-// are ported verbatim from the SDK source files listed above.
-// Pubkeys are represented as [u8; 32] to avoid the solana-sdk dependency.
-// The arithmetic in simulate_detailed is identical to the on-chain program.
-
-// ─── Fee constants (sdk/src/math.rs) ─────────────────────────────────────────
-
-/// Protocol fee: 0.020% = 20 / 100_000
-const PROTOCOL_FEE_BPS: u128 = 20;
-const PROTOCOL_FEE_DENOMINATOR: u128 = 100_000;
-/// LP fee denominator (basis points: 1 bps = 0.01%)
-const BPS_DENOMINATOR: u128 = 10_000;
-
-// ─── Account state (sdk/src/state.rs) ────────────────────────────────────────
-
-/// Deserialized Pool account.  Layout (after 8-byte Anchor discriminator):
-/// authority(32) authority_bump(1) token_a_mint(32) token_b_mint(32)
-/// token_a_vault(32) token_b_vault(32) lp_supply(8) fee_rate_bps(2)
-/// fee_growth_global_a(16) fee_growth_global_b(16) bump(1)  = 212 bytes
-struct PoolState {
-    token_a_mint:        [u8; 32],
-    token_b_mint:        [u8; 32],
-    token_a_vault:       [u8; 32],
-    token_b_vault:       [u8; 32],
-    lp_supply:           u64,
-    fee_rate_bps:        u16,
-    #[allow(dead_code)]
-    fee_growth_global_a: u128,
-    #[allow(dead_code)]
-    fee_growth_global_b: u128,
-}
-
-/// Deserialize a Pool account from raw bytes.
-fn parse_pool(data: &[u8]) -> std::result::Result<PoolState, &'static str> {
+// to wasm32-unknown-unknown (the Cloudflare Workers target). Account layouts,
+// fee math and PDAs come from a2a-swap-core instead — the crate the SDK itself
+// is built on, so the numbers here are the SDK's.
+
+/// Deserialize a Pool account through the IDL-generated layout. Older layouts
+/// lack the tail (zero counters / version 0); newer ones only append, so
+/// anything at least 212 bytes parses. Layouts from before
+/// `reserves_tracked` (404 bytes or less) get it as `migrate_pool` sets it.
+fn parse_pool(data: &[u8]) -> std::result::Result<Pool, String> {
     if data.len() < 212 {
-        return Err("pool account too short");
+        return Err("pool account too short".into());
     }
-    Ok(PoolState {
-        token_a_mint:        read_pubkey(data, 41),
-        token_b_mint:        read_pubkey(data, 73),
-        token_a_vault:       read_pubkey(data, 105),
-        token_b_vault:       read_pubkey(data, 137),
-        lp_supply:           read_u64(data,  169),
-        fee_rate_bps:        read_u16(data,  177),
-        fee_growth_global_a: read_u128(data, 179),
-        fee_growth_global_b: read_u128(data, 195),
-    })
+    let mut pool = Pool::from_account_data(data).map_err(|e| e.to_string())?;
+    if data.len() <= 404 {
+        pool.reserves_tracked = pool.reserve_a != 0 || pool.reserve_b != 0 || pool.lp_supply == 0;
+    }
+    Ok(pool)
 }
 
 /// Read the `amount` field from a packed SPL token account.
@@ -860,79 +1594,29 @@ fn parse_token_amount(data: &[u8]) -> std::result::Result<u64, &'static str> {
     Ok(read_u64(data, 64))
 }
 
-/// Deserialized Position account (sdk/src/state.rs).
-/// Layout (after 8-byte discriminator):
-/// owner(32) pool(32) lp_shares(8)
-/// fee_growth_checkpoint_a(16) fee_growth_checkpoint_b(16)
-/// fees_owed_a(8) fees_owed_b(8) auto_compound(1) compound_threshold(8) bump(1)
-/// = 138 bytes total
-struct PositionState {
-    owner:                   [u8; 32],
-    pool:                    [u8; 32],
-    lp_shares:               u64,
-    fee_growth_checkpoint_a: u128,
-    fee_growth_checkpoint_b: u128,
-    fees_owed_a:             u64,
-    fees_owed_b:             u64,
-    auto_compound:           bool,
-    compound_threshold:      u64,
+/// Read `protocol_fee_bps` from a ProtocolConfig account (sdk/src/state.rs):
+/// 77 bytes or more — older configs lack the later fields.
+fn parse_protocol_fee_bps(data: &[u8]) -> std::result::Result<u64, String> {
+    if data.len() < 77 {
+        return Err(format!("ProtocolConfig account too short: {} bytes", data.len()));
+    }
+    let config = a2a_swap_core::ProtocolConfig::from_account_data(data).map_err(|e| e.to_string())?;
+    Ok(config.protocol_fee_bps as u64)
 }
 
-/// Deserialize a Position account from raw bytes.
-fn parse_position(data: &[u8]) -> std::result::Result<PositionState, &'static str> {
+/// Deserialize a Position account through the IDL-generated layout: 171
+/// bytes, or 138 / 139 (versions 0 / 1) until `migrate_position`.
+fn parse_position(data: &[u8]) -> std::result::Result<Position, String> {
     if data.len() < 138 {
-        return Err("position account too short");
+        return Err("position account too short".into());
     }
-    Ok(PositionState {
-        owner:                   read_pubkey(data, 8),
-        pool:                    read_pubkey(data, 40),
-        lp_shares:               read_u64(data,  72),
-        fee_growth_checkpoint_a: read_u128(data, 80),
-        fee_growth_checkpoint_b: read_u128(data, 96),
-        fees_owed_a:             read_u64(data,  112),
-        fees_owed_b:             read_u64(data,  120),
-        auto_compound:           data[128] != 0,
-        compound_threshold:      read_u64(data,  129),
-    })
-}
-
-/// Anchor account discriminator: sha256("account:{TypeName}")[..8].
-/// Used to filter getProgramAccounts results to the correct account type.
-fn account_disc(type_name: &str) -> [u8; 8] {
-    let preimage = format!("account:{type_name}");
-    let h = pda_hash(&[preimage.as_bytes()]);
-    h[..8].try_into().expect("8 bytes from 32-byte hash")
-}
-
-/// Compute pending (unclaimed) fees for a position since its last on-chain sync.
-/// Mirrors sdk/src/math.rs::pending_fees_for_position exactly.
-/// Returns (pending_fees_a, pending_fees_b) in atomic units.
-fn pending_fees_for_position(pos: &PositionState, pool: &PoolState) -> (u64, u64) {
-    let delta_a = pool.fee_growth_global_a.saturating_sub(pos.fee_growth_checkpoint_a);
-    let delta_b = pool.fee_growth_global_b.saturating_sub(pos.fee_growth_checkpoint_b);
-    let pending_a = ((pos.lp_shares as u128).saturating_mul(delta_a) >> 64) as u64;
-    let pending_b = ((pos.lp_shares as u128).saturating_mul(delta_b) >> 64) as u64;
-    (pending_a, pending_b)
-}
-
-// ─── Byte-slice helpers (sdk/src/state.rs) ───────────────────────────────────
-
-fn read_pubkey(data: &[u8], offset: usize) -> [u8; 32] {
-    data[offset..offset + 32].try_into().expect("read_pubkey: slice too short")
-}
-
-fn read_u16(data: &[u8], offset: usize) -> u16 {
-    u16::from_le_bytes(data[offset..offset + 2].try_into().expect("read_u16"))
+    Position::from_account_data(data).map_err(|e| e.to_string())
 }
 
 fn read_u64(data: &[u8], offset: usize) -> u64 {
     u64::from_le_bytes(data[offset..offset + 8].try_into().expect("read_u64"))
 }
 
-fn read_u128(data: &[u8], offset: usize) -> u128 {
-    u128::from_le_bytes(data[offset..offset + 16].try_into().expect("read_u128"))
-}
-
 // ─── Simulation result (sdk/src/types.rs) ────────────────────────────────────
 
 #[derive(serde::Serialize)]
@@ -960,78 +1644,85 @@ struct SimulateResult {
     fee_rate_bps:     u16,
     reserve_in:       u64,
     reserve_out:      u64,
+    /// Mint decimals of the input token (null if the mint can't be read).
+    decimals_in:      Option<u8>,
+    /// Mint decimals of the output token.
+    decimals_out:     Option<u8>,
+    /// amount_in in whole tokens, as a decimal string.
+    amount_in_ui:     Option<String>,
+    /// estimated_out in whole tokens, as a decimal string.
+    estimated_out_ui: Option<String>,
 }
 
-// ─── Core simulation math (sdk/src/math.rs) ──────────────────────────────────
+impl SimulateResult {
+    /// Fill in the decimals and human-unit amounts.
+    fn with_decimals(self, decimals_in: Option<u8>, decimals_out: Option<u8>) -> Self {
+        Self {
+            amount_in_ui:     decimals_in.map(|d| format_units(self.amount_in, d)),
+            estimated_out_ui: decimals_out.map(|d| format_units(self.estimated_out, d)),
+            decimals_in,
+            decimals_out,
+            ..self
+        }
+    }
+}
 
-/// Full fee and slippage breakdown for a hypothetical swap.
-/// Mirrors sdk/src/math.rs::simulate_detailed exactly.
+/// Atomic units → decimal string, e.g. (1_500_000_000, 9) → "1.5".
+fn format_units(amount: u64, decimals: u8) -> String {
+    let base = 10u128.pow(decimals as u32);
+    let (whole, frac) = (amount as u128 / base, amount as u128 % base);
+    if frac == 0 {
+        return whole.to_string();
+    }
+    let frac = format!("{frac:0width$}", width = decimals as usize);
+    format!("{whole}.{}", frac.trim_end_matches('0'))
+}
+
+// ─── Simulation ──────────────────────────────────────────────────────────────
+
+/// Full fee and slippage breakdown for a hypothetical swap at the pool's base
+/// fee, through `a2a_swap_core::math::quote_swap`.
 /// `pool_addr` is the base58-encoded pool PDA address (included in the result).
 fn simulate_detailed(
     pool_addr:   String,
-    pool:        &PoolState,
+    pool:        &Pool,
     reserve_in:  u64,
     reserve_out: u64,
     amount_in:   u64,
+    protocol_fee_bps: u64,
     a_to_b:      bool,
-) -> std::result::Result<SimulateResult, &'static str> {
-    if reserve_in == 0 || reserve_out == 0 {
-        return Err("no liquidity");
-    }
-
-    let in_u128 = amount_in as u128;
-
-    let protocol_fee = in_u128
-        .checked_mul(PROTOCOL_FEE_BPS)
-        .ok_or("math overflow")?
-        / PROTOCOL_FEE_DENOMINATOR;
-
-    let net_pool_input = in_u128
-        .checked_sub(protocol_fee)
-        .ok_or("math overflow")?;
-
-    let lp_fee = net_pool_input
-        .checked_mul(pool.fee_rate_bps as u128)
-        .ok_or("math overflow")?
-        / BPS_DENOMINATOR;
-
-    let after_fees = net_pool_input
-        .checked_sub(lp_fee)
-        .ok_or("math overflow")?;
-
-    let r_in  = reserve_in  as u128;
-    let r_out = reserve_out as u128;
-
-    let estimated_out = r_out
-        .checked_mul(after_fees)
-        .ok_or("math overflow")?
-        .checked_div(
-            r_in.checked_add(after_fees).ok_or("math overflow")?
-        )
-        .ok_or("math overflow")? as u64;
+) -> std::result::Result<SimulateResult, ErrorCode> {
+    let quote = math::quote_swap(
+        pool.curve, pool.fee_rate_bps, reserve_in, reserve_out, amount_in, protocol_fee_bps,
+    )
+    .map_err(|e| match e {
+        math::MathError::NoLiquidity => ErrorCode::NoLiquidity,
+        _                            => ErrorCode::MathOverflow,
+    })?;
 
     let effective_rate = if amount_in == 0 {
         0.0
     } else {
-        estimated_out as f64 / amount_in as f64
+        quote.amount_out as f64 / amount_in as f64
     };
 
-    let price_impact_pct =
-        after_fees as f64 / (r_in as f64 + after_fees as f64) * 100.0;
-
     Ok(SimulateResult {
         pool:             pool_addr,
         a_to_b,
         amount_in,
-        protocol_fee:     protocol_fee as u64,
-        net_pool_input:   net_pool_input as u64,
-        lp_fee:           lp_fee as u64,
-        after_fees:       after_fees as u64,
-        estimated_out,
+        protocol_fee:     quote.protocol_fee,
+        net_pool_input:   quote.net_pool_input,
+        lp_fee:           quote.lp_fee,
+        after_fees:       quote.after_fees,
+        estimated_out:    quote.amount_out,
         effective_rate,
-        price_impact_pct,
+        price_impact_pct: quote.price_impact_pct,
         fee_rate_bps:     pool.fee_rate_bps,
         reserve_in,
         reserve_out,
+        decimals_in:      None,
+        decimals_out:     None,
+        amount_in_ui:     None,
+        estimated_out_ui: None,
     })
 }
@@ -3,46 +3,56 @@ export const TOKEN_PROGRAM  = 'TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA';
 export const ATA_PROGRAM    = 'ATokenGPvbdGVxr1b2hvZbsiqW5xWH25efTNsLJA8knL';
 export const USDC_MINT      = 'EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v';
 
-export const KNOWN_TOKENS: Record<string, string> = {
-  SOL:  'So11111111111111111111111111111111111111112',
-  USDC: 'EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v',
-  USDT: 'Es9vMFrzaCERmJfrF4H2FYD4KCoNkY11McCe8BenwNYB',
-};
-
-// Offsets inside a Pool account (after 8-byte Anchor discriminator)
-export const POOL = {
-  token_a_mint:        41,
-  token_b_mint:        73,
-  token_a_vault:       105,
-  token_b_vault:       137,
-  lp_supply:           169,  // u64 LE
-  fee_rate_bps:        177,  // u16 LE
-  fee_growth_global_a: 179,  // u128 LE
-  fee_growth_global_b: 195,  // u128 LE
-  TOTAL:               212,
-};
-
-// Offsets inside a Position account (after 8-byte Anchor discriminator)
-export const POSITION = {
-  owner:                   8,
-  pool:                    40,
-  lp_shares:               72,   // u64 LE
-  fee_growth_checkpoint_a: 80,   // u128 LE
-  fee_growth_checkpoint_b: 96,   // u128 LE
-  fees_owed_a:             112,  // u64 LE
-  fees_owed_b:             120,  // u64 LE
-  auto_compound:           128,  // bool
-  compound_threshold:      129,  // u64 LE
-  TOTAL:                   138,
-};
+export interface TokenEntry {
+  symbol:   string;
+  mint:     string;
+  decimals: number;
+  name:     string;
+}
+
+// Mirrors a2a_swap_core::tokens::KNOWN_TOKENS (packages/core/src/tokens.rs).
+export const TOKEN_REGISTRY: readonly TokenEntry[] = [
+  { symbol: 'SOL',  mint: 'So11111111111111111111111111111111111111112',  decimals: 9, name: 'Wrapped SOL' },
+  { symbol: 'USDC', mint: 'EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v', decimals: 6, name: 'USD Coin' },
+  { symbol: 'USDT', mint: 'Es9vMFrzaCERmJfrF4H2FYD4KCoNkY11McCe8BenwNYB', decimals: 6, name: 'USDT' },
+];
+
+export const KNOWN_TOKENS: Record<string, string> = Object.fromEntries(
+  TOKEN_REGISTRY.map((t) => [t.symbol, t.mint]),
+);
+
+// Account layouts come from the program IDL via lib/idl.ts. Smallest
+// (pre-versioning) sizes the parsers accept; current accounts are larger.
+export const POOL_MIN_LEN        = 212;
+// Pool size before `reserves_tracked`; smaller pools get it as migrate_pool sets it.
+export const POOL_OPEN_POSITIONS_LEN = 404;
+export const POSITION_MIN_LEN    = 138;
+export const PROTOCOL_CONFIG_LEN = 77;
+export const AGENT_VOLUME_LEN    = 57;
+export const INTENT_NONCE_LEN    = 49;
+
+// Current Pool layout version (0 = account predates versioning).
+// Fields are only ever appended, so parsers read what they know and ignore the rest.
+export const POOL_VERSION = 8;
+
+// Current Position layout version; older positions need migrate_position first.
+export const POSITION_VERSION = 2;
 
 // x402 Solana network identifier (CAIP-2) and facilitator fee payer (from /supported).
 export const X402_SOLANA_NETWORK = 'solana:5eykt4UsFv8P8NJdTREpY1vzqKqZKvdp';
 export const X402_FEE_PAYER_ADDR = '2wKupLR9q6wXYppw8Gr2NvWxKBUqm4PPJKkQfoxHDBg4';
 
-// Fee constants — must match programs/a2a-swap/src/constants.rs
+// Fee constants — must match programs/a2a-swap/src/constants.rs.
+// PROTOCOL_FEE_BPS is the default until the on-chain ProtocolConfig exists.
 export const PROTOCOL_FEE_BPS  = 20n;
 export const PROTOCOL_FEE_DENOM = 100_000n;
 export const BPS_DENOM          = 10_000n;
+export const REFERRAL_SHARE_BPS = 2_000;
+export const MIN_POOL_FEE_BPS   = 1;
+export const MAX_POOL_FEE_BPS   = 100;
+
+// ProtocolConfig.paused bits — must match PAUSE_* in constants.rs.
+export const PAUSE_SWAPS         = 1;
+export const PAUSE_POOL_CREATION = 2;
 
 export const VERSION = '0.3.0';
@@ -0,0 +1,87 @@
+/**
+ * The error envelope every non-2xx response uses:
+ *
+ *   { "error": { "code": "POOL_NOT_FOUND", "message": "...", "details": {} } }
+ *
+ * `code` is stable — clients branch on it, not on `message`. The strings
+ * mirror packages/sdk-rust/src/error_code.rs: add new ones there too, never
+ * rename.
+ */
+
+import type { Context } from 'hono';
+import type { ContentfulStatusCode } from 'hono/utils/http-status';
+import { KNOWN_TOKENS } from './constants.js';
+
+export type ErrorCode =
+  // SDK / protocol
+  | 'RPC_ERROR'
+  | 'POOL_NOT_FOUND'
+  | 'NO_LIQUIDITY'
+  | 'AMOUNT_B_REQUIRED'
+  | 'AMOUNT_B_ZERO'
+  | 'SLIPPAGE_EXCEEDED'
+  | 'PRICE_IMPACT_EXCEEDED'
+  | 'MATH_OVERFLOW'
+  | 'ACCOUNT_PARSE_ERROR'
+  | 'INVALID_ARGUMENT'
+  | 'PROGRAM_ERROR'
+  | 'INSUFFICIENT_BALANCE'
+  // HTTP API only
+  | 'INVALID_REQUEST'
+  | 'UNKNOWN_TOKEN'
+  | 'ROUTE_NOT_FOUND'
+  | 'NOT_AVAILABLE'
+  | 'IDEMPOTENCY_KEY_REUSED'
+  | 'INTERNAL_ERROR'
+  | 'NOT_FOUND';
+
+export interface ErrorBody {
+  error: {
+    code:    ErrorCode;
+    message: string;
+    details: Record<string, unknown>;
+  };
+}
+
+export function errorBody(
+  code:    ErrorCode,
+  message: string,
+  details: Record<string, unknown> = {},
+): ErrorBody {
+  return { error: { code, message, details } };
+}
+
+/** Respond with the error envelope. */
+export function apiError(
+  c:       Context,
+  status:  ContentfulStatusCode,
+  code:    ErrorCode,
+  message: string,
+  details: Record<string, unknown> = {},
+) {
+  return c.json(errorBody(code, message, details), status);
+}
+
+/** 400 INVALID_REQUEST: a body that is not JSON, or missing fields. */
+export function invalidRequest(c: Context, message: string, required: string[] = []) {
+  return apiError(c, 400, 'INVALID_REQUEST', message, required.length ? { required } : {});
+}
+
+/** 400 UNKNOWN_TOKEN, listing the symbols the registry does know. */
+export function unknownToken(c: Context, token: string) {
+  return apiError(c, 400, 'UNKNOWN_TOKEN', `Unknown token: ${token}`, {
+    token,
+    known: Object.keys(KNOWN_TOKENS),
+  });
+}
+
+/**
+ * Map a simulateDetailed / compute failure: an empty pool is NO_LIQUIDITY,
+ * anything else the fee / curve math rejected is MATH_OVERFLOW.
+ */
+export function simulateError(c: Context, e: unknown) {
+  const message = e instanceof Error ? e.message : String(e);
+  return /liquidity/i.test(message)
+    ? apiError(c, 400, 'NO_LIQUIDITY', message)
+    : apiError(c, 400, 'MATH_OVERFLOW', message);
+}
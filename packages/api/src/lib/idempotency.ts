@@ -0,0 +1,41 @@
+/**
+ * Idempotency-Key replay for POST /convert.
+ *
+ * The first 200 response for an agent + key is stored in the IDEMPOTENCY KV
+ * namespace, with a fingerprint of the request body, for IDEMPOTENCY_TTL_SECS.
+ * A retry with the same key and body replays it; the same key with a
+ * different body is 422 IDEMPOTENCY_KEY_REUSED.
+ */
+
+/** Default replay window in seconds. */
+const TTL_DEFAULT_SECS = 600;
+/** KV's minimum expirationTtl. */
+const TTL_MIN_SECS     = 60;
+
+/** A stored /convert response and the body it answered. */
+export interface IdempotentRecord {
+  fingerprint: string;
+  response:    Record<string, unknown>;
+}
+
+/** 1–255 printable, non-space ASCII characters. */
+export function isValidIdempotencyKey(key: string): boolean {
+  return /^[\x21-\x7e]{1,255}$/.test(key);
+}
+
+/** KV key — scoped per agent so two agents cannot collide on the same key. */
+export function idempotencyKvKey(agent: string, key: string): string {
+  return `convert:${agent}:${key}`;
+}
+
+/** Hex sha256 of the re-serialised body, so whitespace differences still match. */
+export async function bodyFingerprint(body: unknown): Promise<string> {
+  const digest = await crypto.subtle.digest('SHA-256', new TextEncoder().encode(JSON.stringify(body)));
+  return [...new Uint8Array(digest)].map(b => b.toString(16).padStart(2, '0')).join('');
+}
+
+/** Replay window from IDEMPOTENCY_TTL_SECS (default 600, at least 60). */
+export function idempotencyTtlSecs(env: { IDEMPOTENCY_TTL_SECS?: string }): number {
+  const ttl = Number(env.IDEMPOTENCY_TTL_SECS ?? TTL_DEFAULT_SECS);
+  return Number.isFinite(ttl) ? Math.max(Math.floor(ttl), TTL_MIN_SECS) : TTL_DEFAULT_SECS;
+}
@@ -0,0 +1,237 @@
+/**
+ * Account decoding and instruction encoding driven by the program's Anchor IDL
+ * (packages/core/idl/a2a_swap.json, regenerated by scripts/build-idl.sh) — the
+ * same artifact the a2a-swap-core crate generates the SDK and CLI layouts from,
+ * so the Worker cannot drift from them.
+ *
+ * Decoding follows a2a-swap-core's forward-compatibility rule: a field the
+ * account is too short to hold decodes as its default (0, false, the first
+ * enum variant), as does everything after it. Callers check minimum sizes.
+ */
+
+import IDL from '../../../core/idl/a2a_swap.json';
+
+type IdlType =
+  | string
+  | { defined: { name: string } }
+  | { vec: IdlType }
+  | { array: [IdlType, number] };
+
+interface IdlField { name: string; type: IdlType }
+
+interface IdlTypeDef {
+  name: string;
+  type:
+    | { kind: 'struct'; fields: IdlField[] }
+    | { kind: 'enum'; variants: Array<{ name: string; fields?: IdlField[] }> };
+}
+
+/** A decoded or encodable value: u64 and wider are bigint, pubkeys raw bytes. */
+export type IdlValue =
+  | string | number | bigint | boolean | Uint8Array | IdlValue[]
+  | { [field: string]: IdlValue };
+
+/** Decoded enums carry their variant name in `kind`, next to its fields. */
+export type IdlStruct = { [field: string]: IdlValue };
+
+const TYPES = new Map((IDL.types as unknown as IdlTypeDef[]).map(t => [t.name, t]));
+
+const INT_SIZES: Record<string, number> = {
+  u8: 1, i8: 1, u16: 2, i16: 2, u32: 4, i32: 4, u64: 8, i64: 8, u128: 16, i128: 16,
+};
+
+function typeDef(name: string): IdlTypeDef {
+  const def = TYPES.get(name);
+  if (!def) throw new Error(`IDL has no type ${name}`);
+  return def;
+}
+
+// ── Discriminators ────────────────────────────────────────────────────────────
+
+/** Discriminator for an account type, as the IDL records it. */
+export function accountDisc(name: string): Uint8Array {
+  const acc = IDL.accounts.find(a => a.name === name);
+  if (!acc) throw new Error(`IDL has no account ${name}`);
+  return Uint8Array.from(acc.discriminator);
+}
+
+/**
+ * Byte offset of `field` in an account, discriminator included — for
+ * getProgramAccounts memcmp filters. Every field before it must be fixed-size.
+ */
+export function fieldOffset(account: string, field: string): number {
+  const def = typeDef(account).type;
+  if (def.kind !== 'struct') throw new Error(`${account} is not a struct`);
+  let offset = 8;
+  for (const f of def.fields) {
+    if (f.name === field) return offset;
+    offset += fixedSize(f.type);
+  }
+  throw new Error(`${account} has no field ${field}`);
+}
+
+function fixedSize(ty: IdlType): number {
+  if (typeof ty === 'string') {
+    if (ty === 'pubkey') return 32;
+    if (ty === 'bool') return 1;
+    if (ty in INT_SIZES) return INT_SIZES[ty];
+  } else if ('array' in ty) {
+    return fixedSize(ty.array[0]) * ty.array[1];
+  } else if ('defined' in ty) {
+    const def = typeDef(ty.defined.name).type;
+    if (def.kind === 'struct') return def.fields.reduce((n, f) => n + fixedSize(f.type), 0);
+  }
+  throw new Error(`IDL type ${JSON.stringify(ty)} has no fixed size`);
+}
+
+// ── Decoding ──────────────────────────────────────────────────────────────────
+
+class Reader {
+  offset    = 0;
+  exhausted = false;
+  constructor(private data: Uint8Array) {}
+
+  /** The next `n` bytes, or zeros once the data runs out. */
+  read(n: number): Uint8Array {
+    if (this.exhausted || this.offset + n > this.data.length) {
+      this.exhausted = true;
+      this.offset    = this.data.length;
+      return new Uint8Array(n);
+    }
+    const b = this.data.subarray(this.offset, this.offset + n);
+    this.offset += n;
+    return b;
+  }
+}
+
+function readInt(b: Uint8Array, signed: boolean): bigint {
+  let v = 0n;
+  for (let i = b.length - 1; i >= 0; i--) v = (v << 8n) | BigInt(b[i]);
+  return signed ? BigInt.asIntN(b.length * 8, v) : v;
+}
+
+function decodeValue(r: Reader, ty: IdlType): IdlValue {
+  if (typeof ty === 'string') {
+    if (ty === 'pubkey') return r.read(32).slice();
+    if (ty === 'bool') return r.read(1)[0] !== 0;
+    const size = INT_SIZES[ty];
+    if (!size) throw new Error(`Unsupported IDL type ${ty}`);
+    const v = readInt(r.read(size), ty[0] === 'i');
+    return size <= 4 ? Number(v) : v;
+  }
+  if ('vec' in ty) {
+    const start = r.offset;
+    const len = Number(readInt(r.read(4), false));
+    if (r.exhausted) return [];
+    const out: IdlValue[] = [];
+    for (let i = 0; i < len; i++) {
+      out.push(decodeValue(r, ty.vec));
+      if (r.exhausted) throw new Error(`Vector at offset ${start} is truncated`);
+    }
+    return out;
+  }
+  if ('array' in ty) {
+    const [inner, len] = ty.array;
+    if (inner === 'u8') return r.read(len).slice();
+    return Array.from({ length: len }, () => decodeValue(r, inner));
+  }
+  const def = typeDef(ty.defined.name);
+  if (def.type.kind === 'struct') return decodeFields(r, def.type.fields);
+  const at  = r.offset;
+  const tag = r.read(1)[0];
+  const variant = def.type.variants[tag];
+  if (!variant) throw new Error(`Unknown ${def.name} tag ${tag} at offset ${at}`);
+  return { kind: variant.name, ...decodeFields(r, variant.fields ?? []) } as IdlStruct;
+}
+
+function decodeFields(r: Reader, fields: IdlField[]): IdlStruct {
+  const out: IdlStruct = {};
+  for (const f of fields) out[f.name] = decodeValue(r, f.type);
+  return out;
+}
+
+/** Decode raw account data, discriminator included; fields keep their IDL names. */
+export function decodeAccount(name: string, data: Uint8Array): IdlStruct {
+  const disc = accountDisc(name);
+  if (!disc.every((b, i) => data[i] === b)) throw new Error(`Not a ${name} account`);
+  const r = new Reader(data);
+  r.read(8);
+  return decodeValue(r, { defined: { name } }) as IdlStruct;
+}
+
+// ── Encoding ──────────────────────────────────────────────────────────────────
+
+function encodeValue(out: number[], ty: IdlType, v: IdlValue): void {
+  if (typeof ty === 'string') {
+    if (ty === 'pubkey') { out.push(...(v as Uint8Array)); return; }
+    if (ty === 'bool')   { out.push(v ? 1 : 0); return; }
+    let n = BigInt.asUintN(INT_SIZES[ty] * 8, BigInt(v as number | bigint));
+    for (let i = 0; i < INT_SIZES[ty]; i++, n >>= 8n) out.push(Number(n & 0xffn));
+    return;
+  }
+  if ('vec' in ty) {
+    const items = v as IdlValue[];
+    encodeValue(out, 'u32', items.length);
+    for (const x of items) encodeValue(out, ty.vec, x);
+    return;
+  }
+  if ('array' in ty) {
+    const [inner, len] = ty.array;
+    if (inner === 'u8') { out.push(...(v as Uint8Array).subarray(0, len)); return; }
+    for (const x of (v as IdlValue[]).slice(0, len)) encodeValue(out, inner, x);
+    return;
+  }
+  const def = typeDef(ty.defined.name);
+  const obj = v as IdlStruct;
+  if (def.type.kind === 'struct') { encodeFields(out, def.type.fields, obj); return; }
+  const tag = def.type.variants.findIndex(x => x.name === obj.kind);
+  if (tag < 0) throw new Error(`Unknown ${def.name} variant ${String(obj.kind)}`);
+  out.push(tag);
+  encodeFields(out, def.type.variants[tag].fields ?? [], obj);
+}
+
+function encodeFields(out: number[], fields: IdlField[], obj: IdlStruct): void {
+  for (const f of fields) {
+    if (!(f.name in obj)) throw new Error(`Missing argument ${f.name}`);
+    encodeValue(out, f.type, obj[f.name]);
+  }
+}
+
+/** Instruction data: the IDL discriminator followed by `args` in IDL order. */
+export function instructionData(name: string, args: IdlStruct = {}): Uint8Array {
+  const ix = IDL.instructions.find(i => i.name === name);
+  if (!ix) throw new Error(`IDL has no instruction ${name}`);
+  const out = [...ix.discriminator];
+  encodeFields(out, ix.args as unknown as IdlField[], args);
+  return Uint8Array.from(out);
+}
+
+// ── IDL account ───────────────────────────────────────────────────────────────
+
+/** `sha256("account:IdlAccount")[..8]`. */
+const IDL_ACCOUNT_DISC = [140, 36, 166, 2, 103, 197, 33, 164];
+
+/** Names of the instructions in an Anchor IDL, in IDL order. */
+export function idlInstructions(idl: unknown): string[] {
+  const ixs = (idl as { instructions?: Array<{ name?: unknown }> }).instructions ?? [];
+  return ixs.flatMap(ix => typeof ix.name === 'string' ? [ix.name] : []);
+}
+
+/** The IDL this Worker was built with. */
+export const BUNDLED_IDL: unknown = IDL;
+
+/**
+ * The JSON IDL in the program's on-chain IDL account, as `anchor idl init`
+ * writes it: discriminator, authority(32), data_len(u32), zlib data.
+ */
+export async function parseIdlAccount(data: Uint8Array): Promise<unknown> {
+  if (data.length < 44 || !IDL_ACCOUNT_DISC.every((b, i) => data[i] === b)) {
+    throw new Error('Not an Anchor IDL account');
+  }
+  const len = new DataView(data.buffer, data.byteOffset).getUint32(40, true);
+  if (44 + len > data.length) throw new Error(`IDL data length ${len} runs past the account`);
+  const json = new Blob([data.subarray(44, 44 + len)])
+    .stream()
+    .pipeThrough(new DecompressionStream('deflate'));
+  return JSON.parse(await new Response(json).text());
+}
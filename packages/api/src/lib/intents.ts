@@ -0,0 +1,189 @@
+/**
+ * Signed swap intents for the intent mempool (POST /intents, GET /intents/pending).
+ *
+ * An intent is the SDK's `SignedIntent` JSON: the agent's ed25519 signature
+ * over a `swap_with_intent` call (programs/a2a-swap/src/instructions/
+ * swap_with_intent.rs), which any relayer can submit while the agent's
+ * IntentNonce still equals `nonce` and the clock is before `expires_at`.
+ * The Worker only stores and serves them; it never submits anything.
+ */
+
+import { PublicKey } from '@solana/web3.js';
+import { base58Decode, base58Encode } from './math.js';
+import { decodeAccount } from './idl.js';
+import { INTENT_NONCE_LEN, PROGRAM_ID } from './constants.js';
+
+/** Prefix of every signed intent message — the program's INTENT_DOMAIN. */
+const INTENT_DOMAIN = new TextEncoder().encode('a2a-swap:swap_with_intent:v1');
+
+/** Furthest in the future an intent may expire when posted. */
+export const MAX_INTENT_TTL_SECS = 3600;
+/** Pending intents one agent may hold in the mempool at once. */
+export const MAX_INTENTS_PER_AGENT = 16;
+
+const U64_MAX = (1n << 64n) - 1n;
+
+/** An intent as stored and served; u64 fields as decimal strings. */
+export interface StoredIntent {
+  /** The signature, base58 — also the intent's id. */
+  id:                   string;
+  agent:                string;
+  pool:                 string;
+  amount_in:            string;
+  min_amount_out:       string;
+  a_to_b:               boolean;
+  max_price_impact_bps: number;
+  nonce:                string;
+  expires_at:           number;
+  signature:            string;
+  /** When the mempool accepted it, ms since epoch. */
+  receivedAt:           number;
+}
+
+function pubkey(v: unknown, name: string): string {
+  if (typeof v !== 'string') throw new Error(`${name} must be a base58 public key`);
+  try {
+    return new PublicKey(v).toBase58();
+  } catch {
+    throw new Error(`Invalid ${name}: ${v}`);
+  }
+}
+
+/** An unsigned integer no larger than `max`, from a JSON number or decimal string. */
+function uint(v: unknown, name: string, max: bigint): bigint {
+  let n: bigint;
+  if (typeof v === 'string' && /^\d+$/.test(v)) {
+    n = BigInt(v);
+  } else if (typeof v === 'number' && Number.isSafeInteger(v) && v >= 0) {
+    n = BigInt(v);
+  } else if (typeof v === 'number' && Number.isInteger(v) && v > 0) {
+    throw new Error(`${name} above 2^53 must be sent as a decimal string`);
+  } else {
+    throw new Error(`${name} must be a non-negative integer`);
+  }
+  if (n > max) throw new Error(`${name} is out of range`);
+  return n;
+}
+
+/**
+ * `JSON.parse` that keeps integers above 2^53 exact, as decimal strings —
+ * the SDK writes u64 fields as plain JSON numbers.
+ */
+export function parseJsonExact(text: string): unknown {
+  return (JSON.parse as (t: string, r: (k: string, v: unknown, ctx?: { source?: string }) => unknown) => unknown)(
+    text,
+    (_key, value, ctx) =>
+      typeof value === 'number' && !Number.isSafeInteger(value) && ctx?.source && /^\d+$/.test(ctx.source)
+        ? ctx.source
+        : value,
+  );
+}
+
+/** A posted `SignedIntent`, checked for shape only; a string is the reason it was rejected. */
+export function parseIntent(body: unknown, now: number): StoredIntent | string {
+  if (typeof body !== 'object' || body === null) return 'Body must be a SignedIntent JSON object';
+  const b = body as Record<string, unknown>;
+  try {
+    if (typeof b.a_to_b !== 'boolean') throw new Error('a_to_b must be a boolean');
+    if (typeof b.signature !== 'string') throw new Error('signature must be base58');
+    let signature: Uint8Array;
+    try {
+      signature = base58Decode(b.signature);
+    } catch {
+      throw new Error('signature must be base58');
+    }
+    if (signature.length !== 64) throw new Error('signature must be 64 bytes');
+    const expiresAt = Number(uint(b.expires_at, 'expires_at', BigInt(Number.MAX_SAFE_INTEGER)));
+    const amountIn  = uint(b.amount_in, 'amount_in', U64_MAX);
+    if (amountIn === 0n) throw new Error('amount_in must be greater than zero');
+
+    return {
+      id:                   base58Encode(signature),
+      agent:                pubkey(b.agent, 'agent'),
+      pool:                 pubkey(b.pool, 'pool'),
+      amount_in:            amountIn.toString(),
+      min_amount_out:       uint(b.min_amount_out, 'min_amount_out', U64_MAX).toString(),
+      a_to_b:               b.a_to_b,
+      max_price_impact_bps: Number(uint(b.max_price_impact_bps, 'max_price_impact_bps', 0xffffn)),
+      nonce:                uint(b.nonce, 'nonce', U64_MAX).toString(),
+      expires_at:           expiresAt,
+      signature:            base58Encode(signature),
+      receivedAt:           now,
+    };
+  } catch (e) {
+    return (e as Error).message;
+  }
+}
+
+function le(value: bigint, bytes: number): Uint8Array {
+  const out = new Uint8Array(bytes);
+  let v = BigInt.asUintN(bytes * 8, value);
+  for (let i = 0; i < bytes; i++) {
+    out[i] = Number(v & 0xffn);
+    v >>= 8n;
+  }
+  return out;
+}
+
+/** The bytes the agent signed — mirrors the program's `intent_message`. */
+export function intentMessage(intent: StoredIntent): Uint8Array {
+  const parts = [
+    INTENT_DOMAIN,
+    new PublicKey(PROGRAM_ID).toBytes(),
+    new PublicKey(intent.agent).toBytes(),
+    new PublicKey(intent.pool).toBytes(),
+    le(BigInt(intent.amount_in), 8),
+    le(BigInt(intent.min_amount_out), 8),
+    Uint8Array.of(intent.a_to_b ? 1 : 0),
+    le(BigInt(intent.max_price_impact_bps), 2),
+    le(BigInt(intent.nonce), 8),
+    le(BigInt(intent.expires_at), 8),
+  ];
+  const message = new Uint8Array(parts.reduce((n, p) => n + p.length, 0));
+  let at = 0;
+  for (const p of parts) {
+    message.set(p, at);
+    at += p.length;
+  }
+  return message;
+}
+
+/** Whether `intent.signature` is the agent's over the intent. */
+export async function verifyIntent(intent: StoredIntent): Promise<boolean> {
+  try {
+    const key = await crypto.subtle.importKey(
+      'raw', new PublicKey(intent.agent).toBytes(), { name: 'Ed25519' }, false, ['verify'],
+    );
+    return await crypto.subtle.verify('Ed25519', key, base58Decode(intent.signature), intentMessage(intent));
+  } catch {
+    return false;
+  }
+}
+
+/** The nonce stored in an IntentNonce account; 0 when the account does not exist. */
+export function parseIntentNonce(data: Uint8Array | null): bigint {
+  if (!data || data.length === 0) return 0n;
+  if (data.length < INTENT_NONCE_LEN) throw new Error(`IntentNonce account too short: ${data.length}`);
+  return decodeAccount('IntentNonce', data).nonce as bigint;
+}
+
+/**
+ * `intent` as the SDK's `SignedIntent` JSON plus `id` and `received_at`,
+ * ready to POST to a relayer. u64 fields are written as exact JSON numbers,
+ * which `JSON.stringify` cannot do above 2^53.
+ */
+export function intentJson(i: StoredIntent): string {
+  return [
+    `{"id":${JSON.stringify(i.id)}`,
+    `"agent":${JSON.stringify(i.agent)}`,
+    `"pool":${JSON.stringify(i.pool)}`,
+    `"amount_in":${i.amount_in}`,
+    `"min_amount_out":${i.min_amount_out}`,
+    `"a_to_b":${i.a_to_b}`,
+    `"max_price_impact_bps":${i.max_price_impact_bps}`,
+    `"nonce":${i.nonce}`,
+    `"expires_at":${i.expires_at}`,
+    `"signature":${JSON.stringify(i.signature)}`,
+    `"received_at":${i.receivedAt}}`,
+  ].join(',');
+}
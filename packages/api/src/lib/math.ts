@@ -3,32 +3,22 @@
  * All arithmetic uses BigInt to match the on-chain u128 precision exactly.
  */
 
-import { POOL, POSITION, PROTOCOL_FEE_BPS, PROTOCOL_FEE_DENOM, BPS_DENOM } from './constants.js';
+import {
+  POOL_MIN_LEN, POOL_OPEN_POSITIONS_LEN, POSITION_MIN_LEN, PROTOCOL_CONFIG_LEN, AGENT_VOLUME_LEN,
+  PROTOCOL_FEE_BPS, PROTOCOL_FEE_DENOM, BPS_DENOM, REFERRAL_SHARE_BPS,
+  MIN_POOL_FEE_BPS, MAX_POOL_FEE_BPS,
+} from './constants.js';
+import { decodeAccount } from './idl.js';
+import type { IdlStruct } from './idl.js';
 
 // ── Byte readers ──────────────────────────────────────────────────────────────
 
-function readPubkey(data: Uint8Array, offset: number): string {
-  const bytes = data.slice(offset, offset + 32);
-  // Base58 encode using the btoa / browser approach via Uint8Array
-  return base58Encode(bytes);
-}
-
-function readU16(data: Uint8Array, offset: number): number {
-  return data[offset] | (data[offset + 1] << 8);
-}
-
 function readU64(data: Uint8Array, offset: number): bigint {
   let v = 0n;
   for (let i = 7; i >= 0; i--) v = (v << 8n) | BigInt(data[offset + i]);
   return v;
 }
 
-function readU128(data: Uint8Array, offset: number): bigint {
-  let v = 0n;
-  for (let i = 15; i >= 0; i--) v = (v << 8n) | BigInt(data[offset + i]);
-  return v;
-}
-
 // ── Base58 (no deps — used for pubkey encoding only) ──────────────────────────
 
 const B58_CHARS = '123456789ABCDEFGHJKLMNPQRSTUVWXYZabcdefghijkmnopqrstuvwxyz';
@@ -73,6 +63,20 @@ export interface PoolState {
   feeRateBps:        number;
   feeGrowthGlobalA:  bigint;
   feeGrowthGlobalB:  bigint;
+  volumeA:           bigint;
+  volumeB:           bigint;
+  feesCollectedA:    bigint;
+  feesCollectedB:    bigint;
+  version:           number;
+  /** Tracked reserves swaps price against, once `reservesTracked`. */
+  reserveA:          bigint;
+  reserveB:          bigint;
+  /** Whether `reserveA` / `reserveB` hold the reserves; false until a migrated pool is first touched. */
+  reservesTracked:   boolean;
+  /** Whether only allowlisted traders may swap. */
+  permissioned:      boolean;
+  /** Mint a trader must hold to swap; null when ungated. */
+  gateMint:          string | null;
 }
 
 export interface PositionState {
@@ -85,6 +89,7 @@ export interface PositionState {
   feesOwedB:              bigint;
   autoCompound:           boolean;
   compoundThreshold:      bigint;
+  version:                number;
 }
 
 export interface SimulateResult {
@@ -106,34 +111,172 @@ export interface SimulateResult {
 // ── State parsers ─────────────────────────────────────────────────────────────
 
 export function parsePool(data: Uint8Array): PoolState {
-  if (data.length < POOL.TOTAL) throw new Error(`Pool account too short: ${data.length}`);
+  if (data.length < POOL_MIN_LEN) throw new Error(`Pool account too short: ${data.length}`);
+  const p = decodeAccount('Pool', data);
   return {
-    tokenAMint:       readPubkey(data, POOL.token_a_mint),
-    tokenBMint:       readPubkey(data, POOL.token_b_mint),
-    tokenAVault:      readPubkey(data, POOL.token_a_vault),
-    tokenBVault:      readPubkey(data, POOL.token_b_vault),
-    lpSupply:         readU64(data,  POOL.lp_supply),
-    feeRateBps:       readU16(data,  POOL.fee_rate_bps),
-    feeGrowthGlobalA: readU128(data, POOL.fee_growth_global_a),
-    feeGrowthGlobalB: readU128(data, POOL.fee_growth_global_b),
+    tokenAMint:       base58Encode(p.token_a_mint as Uint8Array),
+    tokenBMint:       base58Encode(p.token_b_mint as Uint8Array),
+    tokenAVault:      base58Encode(p.token_a_vault as Uint8Array),
+    tokenBVault:      base58Encode(p.token_b_vault as Uint8Array),
+    lpSupply:         p.lp_supply as bigint,
+    feeRateBps:       p.fee_rate_bps as number,
+    feeGrowthGlobalA: p.fee_growth_global_a as bigint,
+    feeGrowthGlobalB: p.fee_growth_global_b as bigint,
+    volumeA:          p.volume_a as bigint,
+    volumeB:          p.volume_b as bigint,
+    feesCollectedA:   p.fees_collected_a as bigint,
+    feesCollectedB:   p.fees_collected_b as bigint,
+    version:          p.version as number,
+    reserveA:         p.reserve_a as bigint,
+    reserveB:         p.reserve_b as bigint,
+    // Older layouts read as migrate_pool will set it.
+    reservesTracked:  data.length > POOL_OPEN_POSITIONS_LEN
+      ? p.reserves_tracked as boolean
+      : p.reserve_a !== 0n || p.reserve_b !== 0n || p.lp_supply === 0n,
+    permissioned:     (p.allowlist_root as Uint8Array).some((b) => b !== 0),
+    gateMint:         gateMintOf(p.gate_mint as Uint8Array),
   };
 }
 
+/** Base58 gate mint, or null for the all-zero (ungated) key. */
+function gateMintOf(key: Uint8Array): string | null {
+  return key.some((b) => b !== 0) ? base58Encode(key) : null;
+}
+
+/**
+ * `[reserveA, reserveB]` the program prices against — mirrors `Pool::reserves`:
+ * the tracked reserves, so tokens sent straight to a vault don't count until
+ * `sync`, or the vault balances while they're untracked.
+ */
+export function poolReserves(pool: PoolState, vaultA: bigint, vaultB: bigint): [bigint, bigint] {
+  if (!pool.reservesTracked) return [vaultA, vaultB];
+  return [pool.reserveA, pool.reserveB];
+}
+
+/** {@link poolReserves} in swap direction: `[reserveIn, reserveOut]`. */
+export function swapReserves(
+  pool: PoolState, aToB: boolean, vaultIn: bigint, vaultOut: bigint,
+): [bigint, bigint] {
+  if (aToB) return poolReserves(pool, vaultIn, vaultOut);
+  const [ra, rb] = poolReserves(pool, vaultOut, vaultIn);
+  return [rb, ra];
+}
+
 export function parsePosition(data: Uint8Array): PositionState {
-  if (data.length < POSITION.TOTAL) throw new Error(`Position account too short: ${data.length}`);
+  if (data.length < POSITION_MIN_LEN) throw new Error(`Position account too short: ${data.length}`);
+  const p = decodeAccount('Position', data);
+  return {
+    owner:                base58Encode(p.owner as Uint8Array),
+    pool:                 base58Encode(p.pool as Uint8Array),
+    lpShares:             p.lp_shares as bigint,
+    feeGrowthCheckpointA: p.fee_growth_checkpoint_a as bigint,
+    feeGrowthCheckpointB: p.fee_growth_checkpoint_b as bigint,
+    feesOwedA:            p.fees_owed_a as bigint,
+    feesOwedB:            p.fees_owed_b as bigint,
+    autoCompound:         p.auto_compound as boolean,
+    compoundThreshold:    p.compound_threshold as bigint,
+    version:              p.version as number,
+  };
+}
+
+/**
+ * Live protocol fee from the treasury PDA's ProtocolConfig account, or the
+ * compiled-in default while that account does not exist.
+ */
+export function parseProtocolFeeBps(data: Uint8Array | null): bigint {
+  if (!data || data.length === 0) return PROTOCOL_FEE_BPS;
+  if (data.length < PROTOCOL_CONFIG_LEN) throw new Error(`ProtocolConfig account too short: ${data.length}`);
+  return BigInt(decodeAccount('ProtocolConfig', data).protocol_fee_bps as number);
+}
+
+export interface FeeTier {
+  minVolume:   bigint;
+  discountBps: number;
+}
+
+export interface AgentVolumeState {
+  agent:  string;
+  volume: bigint;
+  swaps:  bigint;
+}
+
+/**
+ * Protocol fee and discount tiers from the ProtocolConfig account. Configs
+ * written before fee tiers existed read as all-zero (disabled) tiers.
+ */
+export function parseFeeSchedule(data: Uint8Array | null): { protocolFeeBps: bigint; tiers: FeeTier[] } {
+  if (!data || data.length === 0) return { protocolFeeBps: PROTOCOL_FEE_BPS, tiers: [] };
+  if (data.length < PROTOCOL_CONFIG_LEN) throw new Error(`ProtocolConfig account too short: ${data.length}`);
+  const cfg = decodeAccount('ProtocolConfig', data);
+  const tiers = (cfg.fee_tiers as IdlStruct[]).map((t) => ({
+    minVolume:   t.min_volume as bigint,
+    discountBps: t.discount_bps as number,
+  }));
+  return { protocolFeeBps: BigInt(cfg.protocol_fee_bps as number), tiers };
+}
+
+export interface ProtocolConfigState {
+  /** False before the account exists; the rest are then the defaults. */
+  initialized:      boolean;
+  protocolFeeBps:   bigint;
+  referralShareBps: number;
+  /** PAUSE_* bits of the operations governance has switched off. */
+  paused:           number;
+  minPoolFeeBps:    number;
+  maxPoolFeeBps:    number;
+  tiers:            FeeTier[];
+}
+
+/**
+ * The whole ProtocolConfig, with the program's defaults for a config that
+ * does not exist yet. Configs from before the pause switches read unpaused,
+ * and those without pool fee bounds read the default 1–100 bps.
+ */
+export function parseProtocolConfig(data: Uint8Array | null): ProtocolConfigState {
+  if (!data || data.length === 0) {
+    return {
+      initialized: false, protocolFeeBps: PROTOCOL_FEE_BPS, referralShareBps: REFERRAL_SHARE_BPS,
+      paused: 0, minPoolFeeBps: MIN_POOL_FEE_BPS, maxPoolFeeBps: MAX_POOL_FEE_BPS, tiers: [],
+    };
+  }
+  const { protocolFeeBps, tiers } = parseFeeSchedule(data);
+  const cfg = decodeAccount('ProtocolConfig', data);
+  const bounded = (cfg.max_pool_fee_bps as number) !== 0;
   return {
-    owner:                readPubkey(data, POSITION.owner),
-    pool:                 readPubkey(data, POSITION.pool),
-    lpShares:             readU64(data,  POSITION.lp_shares),
-    feeGrowthCheckpointA: readU128(data, POSITION.fee_growth_checkpoint_a),
-    feeGrowthCheckpointB: readU128(data, POSITION.fee_growth_checkpoint_b),
-    feesOwedA:            readU64(data,  POSITION.fees_owed_a),
-    feesOwedB:            readU64(data,  POSITION.fees_owed_b),
-    autoCompound:         data[POSITION.auto_compound] !== 0,
-    compoundThreshold:    readU64(data,  POSITION.compound_threshold),
+    initialized:      true,
+    protocolFeeBps,
+    referralShareBps: cfg.referral_share_bps as number,
+    paused:           cfg.paused as number,
+    minPoolFeeBps:    bounded ? cfg.min_pool_fee_bps as number : MIN_POOL_FEE_BPS,
+    maxPoolFeeBps:    bounded ? cfg.max_pool_fee_bps as number : MAX_POOL_FEE_BPS,
+    tiers,
   };
 }
 
+export function parseAgentVolume(data: Uint8Array): AgentVolumeState {
+  if (data.length < AGENT_VOLUME_LEN) throw new Error(`AgentVolume account too short: ${data.length}`);
+  const v = decodeAccount('AgentVolume', data);
+  return {
+    agent:  base58Encode(v.agent as Uint8Array),
+    volume: v.volume as bigint,
+    swaps:  v.swaps as bigint,
+  };
+}
+
+/**
+ * Index of the tier `volume` qualifies for — the one with the largest
+ * discount among enabled tiers whose threshold it meets — mirrors
+ * `ProtocolConfig::fee_discount_bps`. `null` when none applies.
+ */
+export function tierFor(tiers: FeeTier[], volume: bigint): number | null {
+  let best: number | null = null;
+  tiers.forEach((t, i) => {
+    if (t.discountBps === 0 || volume < t.minVolume) return;
+    if (best === null || t.discountBps > tiers[best].discountBps) best = i;
+  });
+  return best;
+}
+
 /** Read the `amount` field (offset 64, 8 bytes) from a packed SPL token account. */
 export function parseTokenAmount(data: Uint8Array): bigint {
   if (data.length < 72) throw new Error('Token account too short');
@@ -152,11 +295,12 @@ export function simulateDetailed(
   reserveIn:  bigint,
   reserveOut: bigint,
   amountIn:   bigint,
+  protocolFeeBps: bigint,
   aToB:       boolean,
 ): SimulateResult {
   if (reserveIn === 0n || reserveOut === 0n) throw new Error('no liquidity in pool');
 
-  const protocolFee  = (amountIn * PROTOCOL_FEE_BPS) / PROTOCOL_FEE_DENOM;
+  const protocolFee  = (amountIn * protocolFeeBps) / PROTOCOL_FEE_DENOM;
   const netPoolInput = amountIn - protocolFee;
   const lpFee        = (netPoolInput * BigInt(pool.feeRateBps)) / BPS_DENOM;
   const afterFees    = netPoolInput - lpFee;
@@ -175,6 +319,48 @@ export function simulateDetailed(
   };
 }
 
+// ── Liquidity math ────────────────────────────────────────────────────────────
+
+/** Integer square root, rounded down. Mirrors the on-chain `isqrt`. */
+function isqrt(n: bigint): bigint {
+  if (n === 0n) return 0n;
+  let x = n;
+  let y = (x >> 1n) + (x & 1n);
+  while (y < x) {
+    x = y;
+    y = (y + n / y) >> 1n;
+  }
+  return x;
+}
+
+/**
+ * LP shares minted for a deposit — sqrt(a × b) for the first one, otherwise
+ * the smaller ratio against the reserves. Mirrors `lp_shares_for_deposit`.
+ */
+export function lpSharesForDeposit(
+  amountA: bigint, amountB: bigint, lpSupply: bigint, reserveA: bigint, reserveB: bigint,
+): bigint {
+  if (lpSupply === 0n) return isqrt(amountA * amountB);
+  if (reserveA === 0n || reserveB === 0n) throw new Error('no liquidity in pool');
+  const lpA = (amountA * lpSupply) / reserveA;
+  const lpB = (amountB * lpSupply) / reserveB;
+  return lpA < lpB ? lpA : lpB;
+}
+
+/** Other side of a deposit of `amount`, rounded up as `provide_liquidity_proportional` does. */
+export function proportionalAmount(amount: bigint, reserveFrom: bigint, reserveTo: bigint): bigint {
+  if (reserveFrom === 0n || reserveTo === 0n) throw new Error('no liquidity in pool');
+  return (amount * reserveTo + reserveFrom - 1n) / reserveFrom;
+}
+
+/** Tokens returned for burning `lpShares`, floored like `remove_liquidity`. */
+export function lpUnderlying(
+  lpShares: bigint, lpSupply: bigint, reserveA: bigint, reserveB: bigint,
+): [bigint, bigint] {
+  if (lpSupply === 0n) return [0n, 0n];
+  return [(lpShares * reserveA) / lpSupply, (lpShares * reserveB) / lpSupply];
+}
+
 /** Pending (unclaimed) fees since the last on-chain sync. Mirrors sdk/src/math.rs. */
 export function pendingFees(pos: PositionState, pool: PoolState): [bigint, bigint] {
   const deltaA = pool.feeGrowthGlobalA > pos.feeGrowthCheckpointA
@@ -195,9 +381,43 @@ export function resolveMint(token: string, knownTokens: Record<string, string>):
   return null;
 }
 
-/** Serialize a SimulateResult to a plain JSON-safe object. */
-export function serializeSimulate(r: SimulateResult): Record<string, unknown> {
+/** Atomic units → decimal string, e.g. (1500000000n, 9) → "1.5". */
+export function formatUnits(amount: bigint, decimals: number): string {
+  const base  = 10n ** BigInt(decimals);
+  const whole = amount / base;
+  const frac  = (amount % base).toString().padStart(decimals, '0').replace(/0+$/, '');
+  return frac ? `${whole}.${frac}` : whole.toString();
+}
+
+/** Decimal string → atomic units, e.g. ("1.5", 9) → 1500000000n. Throws on malformed input or excess precision. */
+export function parseUnits(amount: string, decimals: number): bigint {
+  const m = /^(\d*)(?:\.(\d*))?$/.exec(amount.trim());
+  if (!m || (!m[1] && !m[2])) throw new Error(`invalid amount: ${amount}`);
+  const frac = (m[2] ?? '').replace(/0+$/, '');
+  if (frac.length > decimals) throw new Error(`amount has more than ${decimals} decimals: ${amount}`);
+  return BigInt(m[1] || '0') * 10n ** BigInt(decimals) + BigInt(frac.padEnd(decimals, '0') || '0');
+}
+
+/** Mint decimals of the swap's input and output tokens (null if unknown). */
+export interface SwapDecimals {
+  in:  number | null;
+  out: number | null;
+}
+
+/**
+ * Serialize a SimulateResult to a plain JSON-safe object. With `decimals`,
+ * adds `decimals_in` / `decimals_out` and human-unit `amount_in_ui` /
+ * `estimated_out_ui` strings (null where the mint's decimals are unknown).
+ */
+export function serializeSimulate(r: SimulateResult, decimals?: SwapDecimals): Record<string, unknown> {
+  const ui = decimals && {
+    decimals_in:      decimals.in,
+    decimals_out:     decimals.out,
+    amount_in_ui:     decimals.in  === null ? null : formatUnits(r.amountIn, decimals.in),
+    estimated_out_ui: decimals.out === null ? null : formatUnits(r.estimatedOut, decimals.out),
+  };
   return {
+    ...ui,
     pool:            r.pool,
     a_to_b:          r.aToB,
     amount_in:       r.amountIn.toString(),
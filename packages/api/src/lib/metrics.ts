@@ -0,0 +1,176 @@
+/**
+ * Per-request metrics: route, method, status, latency and Solana RPC calls.
+ *
+ * Every request produces one RequestMetric (middleware/metrics.ts). It is
+ * folded into this isolate's in-memory summary (GET /metrics) and exported
+ * to whichever sinks are configured:
+ *   METRICS        Analytics Engine binding — one data point per request
+ *                  (index: route; blobs: route, method, status, network;
+ *                   doubles: latency_ms, rpc_calls, status)
+ *   OTLP_ENDPOINT  OTLP/HTTP collector base URL — one server span per request,
+ *                  POSTed to {OTLP_ENDPOINT}/v1/traces after the response;
+ *                  OTLP_AUTH (secret) is sent as the Authorization header
+ */
+
+import { AsyncLocalStorage } from 'node:async_hooks';
+import type { AppEnv } from '../env.js';
+import { VERSION } from './constants.js';
+
+export interface RequestMetric {
+  route:      string;
+  method:     string;
+  status:     number;
+  started:    number;
+  latency_ms: number;
+  rpc_calls:  number;
+  ray:        string;
+}
+
+interface RouteStats {
+  requests:         number;
+  errors_4xx:       number;
+  errors_5xx:       number;
+  latency_ms_total: number;
+  latency_ms_max:   number;
+  rpc_calls:        number;
+}
+
+/** Fixed route labels, so unknown paths cannot blow up metric cardinality. */
+const ROUTES = new Set([
+  '/', '/health', '/capability-card', '/idl', '/simulate', '/simulate-liquidity',
+  '/compare-quotes', '/swap', '/convert', '/pay/convert', '/pay/icon.svg',
+  '/actions/swap', '/actions.json', '/pool-info', '/active-pools', '/pool-stats',
+  '/pool-history', '/pool-stream', '/candles', '/tokens', '/my-positions',
+  '/my-fees', '/claim-all', '/my-tier', '/webhooks', '/intents',
+  '/intents/pending', '/rpc', '/devnet/bootstrap', '/metrics',
+]);
+
+/** Routes with a path parameter: prefix → label. */
+const PARAM_ROUTES: Array<[string, string]> = [
+  ['/receipt/',     '/receipt/:position'],
+  ['/webhooks/',    '/webhooks/:id'],
+  ['/verify-molt/', '/verify-molt/:wallet'],
+];
+
+export function routeLabel(path: string): string {
+  const p = path.length > 1 ? path.replace(/\/+$/, '') : path;
+  if (ROUTES.has(p)) return p;
+  return PARAM_ROUTES.find(([prefix]) => p.startsWith(prefix))?.[1] ?? 'not_found';
+}
+
+// ── RPC call counting ─────────────────────────────────────────────────────────
+
+/** RPC calls made by the request running in the current async context. */
+const rpcScope = new AsyncLocalStorage<{ calls: number }>();
+
+/** Run `fn` with a fresh RPC call counter; returns the count once it settles. */
+export async function countingRpcCalls(fn: () => Promise<void>): Promise<number> {
+  const counter = { calls: 0 };
+  await rpcScope.run(counter, fn);
+  return counter.calls;
+}
+
+/** Called by lib/rpc.ts for every JSON-RPC request it sends. */
+export function recordRpcCall(): void {
+  const counter = rpcScope.getStore();
+  if (counter) counter.calls++;
+}
+
+// ── Isolate summary ───────────────────────────────────────────────────────────
+
+// Set on the first request: Date.now() does not advance at isolate startup.
+let isolateSince: number | null = null;
+const routeStats = new Map<string, RouteStats>();
+
+/** Fold `m` into the isolate summary. */
+export function recordRequest(m: RequestMetric): void {
+  isolateSince ??= m.started;
+  let s = routeStats.get(m.route);
+  if (!s) {
+    s = { requests: 0, errors_4xx: 0, errors_5xx: 0, latency_ms_total: 0, latency_ms_max: 0, rpc_calls: 0 };
+    routeStats.set(m.route, s);
+  }
+  s.requests         += 1;
+  s.errors_4xx       += m.status >= 400 && m.status < 500 ? 1 : 0;
+  s.errors_5xx       += m.status >= 500 ? 1 : 0;
+  s.latency_ms_total += m.latency_ms;
+  s.latency_ms_max    = Math.max(s.latency_ms_max, m.latency_ms);
+  s.rpc_calls        += m.rpc_calls;
+}
+
+/** Per-route summary accumulated by this isolate, for GET /metrics. */
+export function isolateSummary(): { isolate_since: number | null; routes: Record<string, unknown> } {
+  const routes: Record<string, unknown> = {};
+  for (const [route, s] of [...routeStats].sort(([a], [b]) => a.localeCompare(b))) {
+    const requests = Math.max(s.requests, 1);
+    routes[route] = {
+      requests:       s.requests,
+      errors_4xx:     s.errors_4xx,
+      errors_5xx:     s.errors_5xx,
+      error_rate:     (s.errors_4xx + s.errors_5xx) / requests,
+      latency_ms_avg: Math.floor(s.latency_ms_total / requests),
+      latency_ms_max: s.latency_ms_max,
+      rpc_calls:      s.rpc_calls,
+    };
+  }
+  return { isolate_since: isolateSince, routes };
+}
+
+// ── Exporters ─────────────────────────────────────────────────────────────────
+
+/** One Analytics Engine data point for `m`. */
+export function writeDataPoint(dataset: AnalyticsEngineDataset, m: RequestMetric, network: string): void {
+  dataset.writeDataPoint({
+    indexes: [m.route],
+    blobs:   [m.route, m.method, String(m.status), network],
+    doubles: [m.latency_ms, m.rpc_calls, m.status],
+  });
+}
+
+function hex(bytes: Uint8Array): string {
+  return [...bytes].map(b => b.toString(16).padStart(2, '0')).join('');
+}
+
+/** One OTLP/JSON server span describing the request. */
+export function otlpSpan(m: RequestMetric, network: string): unknown {
+  const attr = (key: string, value: Record<string, string>) => ({ key, value });
+  const ns   = (ms: number) => (BigInt(ms) * 1_000_000n).toString();
+  return {
+    resourceSpans: [{
+      resource: { attributes: [
+        attr('service.name',    { stringValue: 'a2a-swap-api' }),
+        attr('service.version', { stringValue: VERSION }),
+        attr('solana.network',  { stringValue: network }),
+      ]},
+      scopeSpans: [{
+        scope: { name: 'a2a-swap-api' },
+        spans: [{
+          traceId:           hex(crypto.getRandomValues(new Uint8Array(16))),
+          spanId:            hex(crypto.getRandomValues(new Uint8Array(8))),
+          name:              `${m.method} ${m.route}`,
+          kind:              2, // SPAN_KIND_SERVER
+          startTimeUnixNano: ns(m.started),
+          endTimeUnixNano:   ns(m.started + m.latency_ms),
+          attributes: [
+            attr('http.route',                { stringValue: m.route }),
+            attr('http.request.method',       { stringValue: m.method }),
+            attr('http.response.status_code', { intValue: String(m.status) }),
+            attr('a2a.rpc_calls',             { intValue: String(m.rpc_calls) }),
+            attr('cf.ray',                    { stringValue: m.ray }),
+          ],
+          // STATUS_CODE_ERROR for 5xx only, per HTTP server semconv
+          status: { code: m.status >= 500 ? 2 : 0 },
+        }],
+      }],
+    }],
+  };
+}
+
+/** POST `span` to the collector; throws on a non-2xx answer. */
+export async function postOtlp(env: AppEnv['Bindings'], span: unknown): Promise<void> {
+  const endpoint = (env.OTLP_ENDPOINT ?? '').replace(/\/+$/, '');
+  const headers: Record<string, string> = { 'Content-Type': 'application/json' };
+  if (env.OTLP_AUTH) headers.Authorization = env.OTLP_AUTH;
+  const res = await fetch(`${endpoint}/v1/traces`, { method: 'POST', headers, body: JSON.stringify(span) });
+  if (!res.ok) throw new Error(`collector returned ${res.status}`);
+}
@@ -28,6 +28,22 @@ export function resolveTreasury(): PublicKey {
   return PublicKey.findProgramAddressSync([Buffer.from('treasury')], PROG)[0];
 }
 
+/** The program's Anchor IDL account, as `anchor idl init` creates it. */
+export async function resolveIdlAddress(): Promise<PublicKey> {
+  const base = PublicKey.findProgramAddressSync([], PROG)[0];
+  return PublicKey.createWithSeed(base, 'anchor:idl', PROG);
+}
+
+export function resolveAgentVolume(agent: PublicKey | string): PublicKey {
+  const a = typeof agent === 'string' ? new PublicKey(agent) : agent;
+  return PublicKey.findProgramAddressSync([Buffer.from('agent_volume'), a.toBytes()], PROG)[0];
+}
+
+export function resolveIntentNonce(agent: PublicKey | string): PublicKey {
+  const a = typeof agent === 'string' ? new PublicKey(agent) : agent;
+  return PublicKey.findProgramAddressSync([Buffer.from('intent_nonce'), a.toBytes()], PROG)[0];
+}
+
 export function resolveAta(wallet: PublicKey | string, mint: PublicKey | string): PublicKey {
   const w = typeof wallet === 'string' ? new PublicKey(wallet) : wallet;
   const m = typeof mint   === 'string' ? new PublicKey(mint)   : mint;
@@ -36,10 +52,3 @@ export function resolveAta(wallet: PublicKey | string, mint: PublicKey | string)
     ATA,
   )[0];
 }
-
-/** Discriminator for an Anchor instruction: sha256("global:{name}")[0..8] */
-export async function instructionDisc(name: string): Promise<Uint8Array> {
-  const data   = new TextEncoder().encode(`global:${name}`);
-  const digest = await crypto.subtle.digest('SHA-256', data);
-  return new Uint8Array(digest).slice(0, 8);
-}
@@ -0,0 +1,162 @@
+/**
+ * Pool snapshots — every pool's reserves and price, taken by the scheduled
+ * handler (src/index.ts) once a minute and kept in the POOL_SNAPSHOTS KV
+ * namespace, so /pool-stats, /pool-history and /pool-stream never touch the
+ * RPC.
+ *
+ * KV keys:
+ *   pools:latest          PoolSnapshot — the newest snapshot, with 24h changes
+ *   pools:history:<pool>  HistoryPoint[] — one point per snapshot, oldest
+ *                         first, trimmed to HISTORY_WINDOW_MS
+ *
+ * A snapshot costs three RPC calls however many pools exist: getSlot,
+ * getProgramAccounts for the pools and getMultipleAccounts for the vaults of
+ * pools whose reserves are not tracked in the pool account.
+ */
+
+import { getMultipleAccountsData, getProgramAccountsByType, getSlot } from './rpc.js';
+import { parsePool, parseTokenAmount, poolReserves } from './math.js';
+import { accountDisc } from './idl.js';
+import { KNOWN_TOKENS, PROGRAM_ID } from './constants.js';
+
+export const LATEST_KEY     = 'pools:latest';
+export const HISTORY_PREFIX = 'pools:history:';
+/** How much history each pool keeps, and the window `change_24h` covers. */
+export const HISTORY_WINDOW_MS = 24 * 60 * 60 * 1000;
+
+const MINT_TO_SYMBOL: Record<string, string> = Object.fromEntries(
+  Object.entries(KNOWN_TOKENS).map(([sym, mint]) => [mint, sym]),
+);
+
+/** One pool at one snapshot. Amounts are atomic-unit strings. */
+export interface HistoryPoint {
+  /** Unix ms the snapshot was taken. */
+  t:         number;
+  reserve_a: string;
+  reserve_b: string;
+  /** reserve_b / reserve_a in atomic units; null while a reserve is empty. */
+  price:     number | null;
+  /** Lifetime swap volume, so a window's volume is the difference of two points. */
+  volume_a:  string;
+  volume_b:  string;
+}
+
+export interface PoolStat {
+  pool:             string;
+  token_a_mint:     string;
+  token_a_symbol:   string | null;
+  token_b_mint:     string;
+  token_b_symbol:   string | null;
+  reserve_a:        string;
+  reserve_b:        string;
+  price:            number | null;
+  lp_supply:        string;
+  fee_rate_bps:     number;
+  volume_a:         string;
+  volume_b:         string;
+  fees_collected_a: string;
+  fees_collected_b: string;
+  /** Against the oldest point within the last 24h; null before the pool has one. */
+  change_24h: {
+    price_pct: number | null;
+    volume_a:  string;
+    volume_b:  string;
+    since:     number;
+  } | null;
+}
+
+export interface PoolSnapshot {
+  /** Unix ms the accounts were read. */
+  taken_at: number;
+  slot:     number;
+  pools:    PoolStat[];
+}
+
+/** Read every pool and the vaults it is priced against. */
+export async function takePoolSnapshot(url: string): Promise<PoolSnapshot> {
+  const taken_at = Date.now();
+  const [slot, accounts] = await Promise.all([
+    getSlot(url),
+    getProgramAccountsByType(url, PROGRAM_ID, accountDisc('Pool')),
+  ]);
+
+  const pools = accounts.flatMap(({ pubkey, data }) => {
+    try { return [{ pubkey, pool: parsePool(data) }]; } catch { return []; }
+  });
+  const untracked = pools.filter(({ pool }) => !pool.reservesTracked);
+  const vaultData = await getMultipleAccountsData(
+    url, untracked.flatMap(({ pool }) => [pool.tokenAVault, pool.tokenBVault]),
+  );
+  const vaults = new Map<string, bigint>();
+  untracked.forEach(({ pool }, i) => {
+    const [a, b] = [vaultData[2 * i], vaultData[2 * i + 1]];
+    try { if (a) vaults.set(pool.tokenAVault, parseTokenAmount(a)); } catch { /* skip */ }
+    try { if (b) vaults.set(pool.tokenBVault, parseTokenAmount(b)); } catch { /* skip */ }
+  });
+
+  const stats = pools.map(({ pubkey, pool }): PoolStat => {
+    const [reserveA, reserveB] = poolReserves(
+      pool, vaults.get(pool.tokenAVault) ?? 0n, vaults.get(pool.tokenBVault) ?? 0n,
+    );
+    return {
+      pool:             pubkey,
+      token_a_mint:     pool.tokenAMint,
+      token_a_symbol:   MINT_TO_SYMBOL[pool.tokenAMint] ?? null,
+      token_b_mint:     pool.tokenBMint,
+      token_b_symbol:   MINT_TO_SYMBOL[pool.tokenBMint] ?? null,
+      reserve_a:        reserveA.toString(),
+      reserve_b:        reserveB.toString(),
+      price:            reserveA > 0n && reserveB > 0n ? Number(reserveB) / Number(reserveA) : null,
+      lp_supply:        pool.lpSupply.toString(),
+      fee_rate_bps:     pool.feeRateBps,
+      volume_a:         pool.volumeA.toString(),
+      volume_b:         pool.volumeB.toString(),
+      fees_collected_a: pool.feesCollectedA.toString(),
+      fees_collected_b: pool.feesCollectedB.toString(),
+      change_24h:       null,
+    };
+  });
+  stats.sort((x, y) => x.pool.localeCompare(y.pool));
+  return { taken_at, slot, pools: stats };
+}
+
+/**
+ * Append `snapshot` to each pool's history, fill in its `change_24h` from
+ * that history, and store it as the latest snapshot.
+ */
+export async function storePoolSnapshot(kv: KVNamespace, snapshot: PoolSnapshot): Promise<void> {
+  const cutoff = snapshot.taken_at - HISTORY_WINDOW_MS;
+  await Promise.all(snapshot.pools.map(async (stat) => {
+    const key     = HISTORY_PREFIX + stat.pool;
+    const history = (await kv.get<HistoryPoint[]>(key, 'json') ?? []).filter((p) => p.t >= cutoff);
+    const oldest  = history[0];
+    if (oldest) {
+      stat.change_24h = {
+        price_pct: stat.price != null && oldest.price ? (stat.price / oldest.price - 1) * 100 : null,
+        volume_a:  (BigInt(stat.volume_a) - BigInt(oldest.volume_a)).toString(),
+        volume_b:  (BigInt(stat.volume_b) - BigInt(oldest.volume_b)).toString(),
+        since:     oldest.t,
+      };
+    }
+    history.push({
+      t:         snapshot.taken_at,
+      reserve_a: stat.reserve_a,
+      reserve_b: stat.reserve_b,
+      price:     stat.price,
+      volume_a:  stat.volume_a,
+      volume_b:  stat.volume_b,
+    });
+    await kv.put(key, JSON.stringify(history));
+  }));
+  await kv.put(LATEST_KEY, JSON.stringify(snapshot));
+}
+
+/** The newest stored snapshot, or null before the first scheduled run. */
+export async function latestPoolSnapshot(kv: KVNamespace): Promise<PoolSnapshot | null> {
+  return kv.get<PoolSnapshot>(LATEST_KEY, 'json');
+}
+
+/** `pool`'s stored history, oldest first. */
+export async function poolHistory(kv: KVNamespace, pool: string): Promise<HistoryPoint[]> {
+  return await kv.get<HistoryPoint[]>(HISTORY_PREFIX + pool, 'json') ?? [];
+}
@@ -2,13 +2,22 @@
  * Solana JSON-RPC helpers — all calls use Worker fetch (no Node.js TCP).
  */
 
+import { TOKEN_REGISTRY } from './constants.js';
+import { recordRpcCall } from './metrics.js';
+
 const DEFAULT_RPC = 'https://api.mainnet-beta.solana.com';
 
 export function rpcUrl(env: { SOLANA_RPC_URL?: string }): string {
   return env.SOLANA_RPC_URL ?? DEFAULT_RPC;
 }
 
+/** Cluster this deployment serves (SOLANA_NETWORK var), default "mainnet-beta". */
+export function solanaNetwork(env: { SOLANA_NETWORK?: string }): string {
+  return env.SOLANA_NETWORK || 'mainnet-beta';
+}
+
 async function rpcPost(url: string, body: unknown): Promise<unknown> {
+  recordRpcCall();
   const res = await fetch(url, {
     method:  'POST',
     headers: { 'Content-Type': 'application/json' },
@@ -32,6 +41,62 @@ export async function getAccountData(url: string, pubkey: string): Promise<Uint8
   return Uint8Array.from(atob(result.value.data[0]), c => c.charCodeAt(0));
 }
 
+/** Most keys one getMultipleAccounts call accepts. */
+const MAX_MULTIPLE_ACCOUNTS = 100;
+
+/**
+ * Raw data of each of `pubkeys`, in order (null where an account does not
+ * exist) — one getMultipleAccounts per 100 keys.
+ */
+export async function getMultipleAccountsData(url: string, pubkeys: string[]): Promise<Array<Uint8Array | null>> {
+  const pages: string[][] = [];
+  for (let i = 0; i < pubkeys.length; i += MAX_MULTIPLE_ACCOUNTS) {
+    pages.push(pubkeys.slice(i, i + MAX_MULTIPLE_ACCOUNTS));
+  }
+  const results = await Promise.all(pages.map(page => rpcPost(url, {
+    jsonrpc: '2.0', id: 1,
+    method: 'getMultipleAccounts',
+    params: [page, { encoding: 'base64', commitment: 'confirmed' }],
+  }) as Promise<{ value: Array<null | { data: [string, string] }> }>));
+  return results.flatMap(r => r.value.map(acc =>
+    acc ? Uint8Array.from(atob(acc.data[0]), c => c.charCodeAt(0)) : null,
+  ));
+}
+
+// SPL Token mint layout: decimals at byte 44.
+const MINT_DECIMALS_OFFSET = 44;
+
+// Mint decimals never change, so they are cached for the isolate's lifetime.
+const MINT_DECIMALS = new Map<string, number>(TOKEN_REGISTRY.map((t) => [t.mint, t.decimals]));
+
+/** Decimals of an SPL mint (cached), or null if the account is missing or not a mint. */
+export async function getMintDecimals(url: string, mint: string): Promise<number | null> {
+  const cached = MINT_DECIMALS.get(mint);
+  if (cached !== undefined) return cached;
+  const data = await getAccountData(url, mint);
+  if (!data || data.length <= MINT_DECIMALS_OFFSET) return null;
+  MINT_DECIMALS.set(mint, data[MINT_DECIMALS_OFFSET]);
+  return data[MINT_DECIMALS_OFFSET];
+}
+
+/** Current confirmed slot. */
+export async function getSlot(url: string): Promise<number> {
+  return await rpcPost(url, {
+    jsonrpc: '2.0', id: 1,
+    method: 'getSlot',
+    params: [{ commitment: 'confirmed' }],
+  }) as number;
+}
+
+/** Ask the cluster's faucet (devnet / testnet only) for lamports; returns the signature. */
+export async function requestAirdrop(url: string, pubkey: string, lamports: number): Promise<string> {
+  return await rpcPost(url, {
+    jsonrpc: '2.0', id: 1,
+    method: 'requestAirdrop',
+    params: [pubkey, lamports, { commitment: 'confirmed' }],
+  }) as string;
+}
+
 /** Returns the latest confirmed blockhash string. */
 export async function getLatestBlockhash(url: string): Promise<string> {
   const result = await rpcPost(url, {
@@ -42,11 +107,19 @@ export async function getLatestBlockhash(url: string): Promise<string> {
   return result.value.blockhash;
 }
 
-/** getProgramAccounts filtered by data size only (no memcmp) — returns all accounts of that size. */
-export async function getProgramAccountsBySize(
+/** memcmp filter matching an Anchor account discriminator at offset 0. */
+function discFilter(disc: Uint8Array) {
+  return { memcmp: { offset: 0, bytes: btoa(String.fromCharCode(...disc)), encoding: 'base64' } };
+}
+
+/**
+ * getProgramAccounts filtered by Anchor account type only — returns every
+ * account of that type. No size filter, so all layout versions match.
+ */
+export async function getProgramAccountsByType(
   url:       string,
   programId: string,
-  dataSize:  number,
+  disc:      Uint8Array,   // accountDisc(<type name>)
 ): Promise<Array<{ pubkey: string; data: Uint8Array }>> {
   const result = await rpcPost(url, {
     jsonrpc: '2.0', id: 1,
@@ -55,7 +128,7 @@ export async function getProgramAccountsBySize(
       programId,
       {
         encoding: 'base64',
-        filters: [{ dataSize }],
+        filters: [discFilter(disc)],
       },
     ],
   }) as Array<{ pubkey: string; account: { data: [string, string] } }>;
@@ -67,13 +140,13 @@ export async function getProgramAccountsBySize(
 }
 
 /**
- * getProgramAccounts filtered by data size + one memcmp.
+ * getProgramAccounts filtered by Anchor account type + one memcmp.
  * Returns [ { pubkey, data } ].
  */
 export async function getProgramAccounts(
   url:          string,
   programId:    string,
-  dataSize:     number,
+  disc:         Uint8Array,   // accountDisc(<type name>)
   memcmpOffset: number,
   memcmpBytes:  string,   // base58 of the bytes to match
 ): Promise<Array<{ pubkey: string; data: Uint8Array }>> {
@@ -85,7 +158,7 @@ export async function getProgramAccounts(
       {
         encoding: 'base64',
         filters: [
-          { dataSize },
+          discFilter(disc),
           { memcmp: { offset: memcmpOffset, bytes: memcmpBytes } },
         ],
       },
@@ -97,3 +170,39 @@ export async function getProgramAccounts(
     data:   Uint8Array.from(atob(item.account.data[0]), c => c.charCodeAt(0)),
   }));
 }
+
+/**
+ * Signatures involving `address`, newest first. With `until`, stops before
+ * that signature — i.e. only what is newer.
+ */
+export async function getSignaturesForAddress(
+  url:     string,
+  address: string,
+  until?:  string,
+  limit =  25,
+): Promise<Array<{ signature: string; slot: number; err: unknown; blockTime: number | null }>> {
+  return await rpcPost(url, {
+    jsonrpc: '2.0', id: 1,
+    method: 'getSignaturesForAddress',
+    params: [address, { limit, until, commitment: 'confirmed' }],
+  }) as Array<{ signature: string; slot: number; err: unknown; blockTime: number | null }>;
+}
+
+/** Log messages of a confirmed transaction, or null if it is not found. */
+export async function getTransactionLogs(
+  url:       string,
+  signature: string,
+): Promise<{ slot: number; blockTime: number | null; err: unknown; logs: string[] } | null> {
+  const result = await rpcPost(url, {
+    jsonrpc: '2.0', id: 1,
+    method: 'getTransaction',
+    params: [signature, { encoding: 'json', commitment: 'confirmed', maxSupportedTransactionVersion: 0 }],
+  }) as null | { slot: number; blockTime: number | null; meta: { err: unknown; logMessages?: string[] } | null };
+  if (!result) return null;
+  return {
+    slot:      result.slot,
+    blockTime: result.blockTime,
+    err:       result.meta?.err ?? null,
+    logs:      result.meta?.logMessages ?? [],
+  };
+}
@@ -0,0 +1,101 @@
+/**
+ * Wallet snapshots — every account /my-positions and /my-fees read for one
+ * wallet, cached per wallet by the PositionCache Durable Object
+ * (src/durable/positionCache.ts).
+ *
+ * A snapshot costs at most three RPC calls however many positions the wallet
+ * has: getProgramAccounts for its Position accounts, one getMultipleAccounts
+ * for their pools (each pool once), and one for the vaults of pools whose
+ * reserves are not tracked in the pool account. Account data is kept base64
+ * so a snapshot survives Durable Object storage and JSON responses.
+ */
+
+import type { AppEnv } from '../env.js';
+import { PROGRAM_ID } from './constants.js';
+import { accountDisc, fieldOffset } from './idl.js';
+import { parsePool, parsePosition } from './math.js';
+import { getMultipleAccountsData, getProgramAccounts, rpcUrl } from './rpc.js';
+
+/** How long a cached snapshot is served before it is re-read (POSITION_CACHE_TTL_SECS). */
+export const DEFAULT_POSITION_CACHE_TTL_SECS = 30;
+
+export interface WalletSnapshot {
+  /** Unix ms the accounts were read. */
+  fetchedAt: number;
+  positions: Array<{ pubkey: string; data: string }>;
+  /** Pools and vaults by address; null where the account does not exist. */
+  accounts:  Record<string, string | null>;
+}
+
+/** A snapshot with its data decoded, and its age when it was loaded. */
+export interface WalletAccounts {
+  positions: Array<{ pubkey: string; data: Uint8Array }>;
+  /** Pool or vault data; null if missing or not part of the snapshot. */
+  account(pubkey: string): Uint8Array | null;
+  staleMs: number;
+}
+
+const toBase64   = (data: Uint8Array): string => btoa(String.fromCharCode(...data));
+const fromBase64 = (b64: string): Uint8Array => Uint8Array.from(atob(b64), c => c.charCodeAt(0));
+
+/** Read `wallet`'s positions, their pools and the vaults they price against. */
+export async function fetchWalletSnapshot(url: string, wallet: string): Promise<WalletSnapshot> {
+  const fetchedAt = Date.now();
+  const positions = await getProgramAccounts(
+    url, PROGRAM_ID, accountDisc('Position'), fieldOffset('Position', 'owner'), wallet,
+  );
+  const pools    = [...new Set(positions.map(({ data }) => parsePosition(data).pool))];
+  const poolData = await getMultipleAccountsData(url, pools);
+
+  const vaults: string[] = [];
+  for (const data of poolData) {
+    if (!data) continue;
+    const pool = parsePool(data);
+    if (!pool.reservesTracked) vaults.push(pool.tokenAVault, pool.tokenBVault);
+  }
+  const vaultData = await getMultipleAccountsData(url, vaults);
+
+  const accounts: Record<string, string | null> = {};
+  const keys = [...pools, ...vaults];
+  [...poolData, ...vaultData].forEach((data, i) => { accounts[keys[i]] = data ? toBase64(data) : null; });
+  return {
+    fetchedAt,
+    positions: positions.map(({ pubkey, data }) => ({ pubkey, data: toBase64(data) })),
+    accounts,
+  };
+}
+
+/**
+ * `wallet`'s snapshot from its PositionCache instance — re-read when older
+ * than the TTL or when `refresh` is set — or straight from the RPC when the
+ * POSITIONS binding is absent.
+ */
+export async function loadWallet(
+  env:     AppEnv['Bindings'],
+  wallet:  string,
+  refresh: boolean,
+): Promise<WalletAccounts> {
+  let snapshot: WalletSnapshot;
+  const ns = env.POSITIONS;
+  if (ns) {
+    const query = `wallet=${encodeURIComponent(wallet)}${refresh ? '&refresh=1' : ''}`;
+    const res = await ns.get(ns.idFromName(wallet)).fetch(`https://positions/snapshot?${query}`);
+    if (!res.ok) throw new Error(`Position cache error: HTTP ${res.status}`);
+    snapshot = await res.json() as WalletSnapshot;
+  } else {
+    snapshot = await fetchWalletSnapshot(rpcUrl(env), wallet);
+  }
+
+  const decoded = new Map<string, Uint8Array | null>();
+  return {
+    positions: snapshot.positions.map(({ pubkey, data }) => ({ pubkey, data: fromBase64(data) })),
+    account(pubkey: string): Uint8Array | null {
+      if (!decoded.has(pubkey)) {
+        const b64 = snapshot.accounts[pubkey];
+        decoded.set(pubkey, b64 ? fromBase64(b64) : null);
+      }
+      return decoded.get(pubkey) ?? null;
+    },
+    staleMs: Math.max(0, Date.now() - snapshot.fetchedAt),
+  };
+}
@@ -0,0 +1,341 @@
+/**
+ * Webhook subscriptions — event definitions, trigger checks and signing.
+ *
+ * Stored and driven by the WebhookHub Durable Object (src/durable/webhookHub.ts);
+ * this module holds the parts that do not depend on Durable Object storage.
+ *
+ * Events:
+ *   fees.threshold  A position's claimable fees (owed + pending) reached
+ *                   `thresholdA` or `thresholdB`. Fires once, then re-arms when
+ *                   claimable fees drop back below both (e.g. after a claim).
+ *   price.move      A pool's price (reserve_b / reserve_a) moved `movePct`
+ *                   percent from the last notified price (initially the price at
+ *                   registration).
+ *   swap.fill       A swap signed by `wallet` confirmed on A2A-Swap.
+ *
+ * Deliveries are POSTed as JSON with:
+ *   X-A2A-Event      the event name
+ *   X-A2A-Delivery   unique delivery id (stable across retries)
+ *   X-A2A-Signature  t=<unix seconds>,v1=<hex HMAC-SHA256(secret, "<t>.<body>")>
+ */
+
+import { rpcUrl, getAccountData, getProgramAccounts, getSignaturesForAddress, getTransactionLogs } from './rpc.js';
+import {
+  parsePool, parsePosition, parseTokenAmount, poolReserves, pendingFees, resolveMint,
+} from './math.js';
+import { accountDisc, fieldOffset } from './idl.js';
+import { resolvePool } from './pda.js';
+import { KNOWN_TOKENS, PROGRAM_ID } from './constants.js';
+
+export const EVENTS = ['fees.threshold', 'price.move', 'swap.fill'] as const;
+export type EventName = typeof EVENTS[number];
+
+/** Retry delays after a failed delivery, in seconds; one attempt per entry plus the first. */
+export const RETRY_DELAYS_SECS = [30, 120, 600, 3_600, 21_600];
+/** Consecutive failed deliveries (after all retries) that disable a subscription. */
+export const MAX_CONSECUTIVE_FAILURES = 10;
+/** Most new signatures inspected per swap.fill subscription per poll. */
+const MAX_FILLS_PER_POLL = 25;
+/** Delivery request timeout. */
+const DELIVERY_TIMEOUT_MS = 10_000;
+
+export interface Subscription {
+  id:        string;
+  secret:    string;
+  url:       string;
+  event:     EventName;
+  createdAt: number;
+  /** `active`, or `disabled` after MAX_CONSECUTIVE_FAILURES failed deliveries. */
+  status:    'active' | 'disabled';
+  failures:  number;
+
+  /** fees.threshold / swap.fill: the wallet to watch. */
+  wallet?:     string;
+  /** fees.threshold: only this pool's position (optional). price.move: the pool. */
+  pool?:       string;
+  thresholdA?: string;
+  thresholdB?: string;
+  movePct?:    number;
+
+  /** Trigger state, updated by each poll. */
+  state: {
+    /** fees.threshold: positions that already fired and have not re-armed. */
+    fired?:         string[];
+    /** price.move: price the next move is measured from. */
+    referencePrice?: number;
+    /** swap.fill: newest signature already seen. */
+    lastSignature?: string;
+  };
+}
+
+export interface Delivery {
+  id:        string;
+  webhook:   string;
+  event:     EventName;
+  createdAt: number;
+  data:      Record<string, unknown>;
+  attempts:  number;
+  /** Unix ms of the next attempt. */
+  dueAt:     number;
+}
+
+/** Public view of a subscription — everything but the secret and trigger state. */
+export function describe(sub: Subscription): Record<string, unknown> {
+  const { secret: _secret, state: _state, ...rest } = sub;
+  return rest;
+}
+
+// ── Registration ─────────────────────────────────────────────────────────────
+
+export interface RegisterBody {
+  url?:        string;
+  event?:      string;
+  wallet?:     string;
+  pool?:       string;
+  tokenA?:     string;
+  tokenB?:     string;
+  thresholdA?: string;
+  thresholdB?: string;
+  movePct?:    number;
+}
+
+const BASE58 = /^[1-9A-HJ-NP-Za-km-z]{32,44}$/;
+
+function isAtomicAmount(s: unknown): s is string {
+  return typeof s === 'string' && /^\d+$/.test(s) && BigInt(s) > 0n;
+}
+
+/** Validate a registration body into a new subscription, or return an error message. */
+export function buildSubscription(body: RegisterBody, now: number): Subscription | string {
+  const { url, event } = body;
+  if (!url || !event) return 'url and event are required';
+  let parsed: URL;
+  try { parsed = new URL(url); } catch { return 'url is not a valid URL'; }
+  if (parsed.protocol !== 'https:') return 'url must be https';
+  if (!(EVENTS as readonly string[]).includes(event)) {
+    return `event must be one of: ${EVENTS.join(', ')}`;
+  }
+
+  const sub: Subscription = {
+    id:        `wh_${randomHex(12)}`,
+    secret:    `whsec_${randomHex(24)}`,
+    url,
+    event:     event as EventName,
+    createdAt: now,
+    status:    'active',
+    failures:  0,
+    state:     {},
+  };
+
+  switch (sub.event) {
+    case 'fees.threshold': {
+      if (!body.wallet || !BASE58.test(body.wallet)) return 'wallet (base58) is required';
+      if (body.pool !== undefined && !BASE58.test(body.pool)) return 'pool must be a base58 address';
+      if (body.thresholdA === undefined && body.thresholdB === undefined) {
+        return 'thresholdA and/or thresholdB (atomic units) is required';
+      }
+      for (const t of [body.thresholdA, body.thresholdB]) {
+        if (t !== undefined && !isAtomicAmount(t)) return 'thresholds must be positive integer strings';
+      }
+      Object.assign(sub, { wallet: body.wallet, pool: body.pool, thresholdA: body.thresholdA, thresholdB: body.thresholdB });
+      break;
+    }
+    case 'price.move': {
+      let pool = body.pool;
+      if (!pool) {
+        const mintA = body.tokenA && resolveMint(body.tokenA, KNOWN_TOKENS);
+        const mintB = body.tokenB && resolveMint(body.tokenB, KNOWN_TOKENS);
+        if (!mintA || !mintB) return 'pool, or tokenA and tokenB, is required';
+        pool = resolvePool(mintA, mintB).toBase58();
+      } else if (!BASE58.test(pool)) {
+        return 'pool must be a base58 address';
+      }
+      const pct = body.movePct;
+      if (typeof pct !== 'number' || !(pct > 0 && pct <= 100)) return 'movePct must be a number in (0, 100]';
+      Object.assign(sub, { pool, movePct: pct });
+      break;
+    }
+    case 'swap.fill': {
+      if (!body.wallet || !BASE58.test(body.wallet)) return 'wallet (base58) is required';
+      sub.wallet = body.wallet;
+      break;
+    }
+  }
+  return sub;
+}
+
+// ── Trigger checks ───────────────────────────────────────────────────────────
+
+/** Payloads for every event `sub` has produced since the last poll; updates `sub.state`. */
+export async function poll(sub: Subscription, env: { SOLANA_RPC_URL?: string }): Promise<Array<Record<string, unknown>>> {
+  const url = rpcUrl(env);
+  switch (sub.event) {
+    case 'fees.threshold': return pollFees(sub, url);
+    case 'price.move':     return pollPrice(sub, url);
+    case 'swap.fill':      return pollFills(sub, url);
+  }
+}
+
+async function pollFees(sub: Subscription, url: string): Promise<Array<Record<string, unknown>>> {
+  const accounts = await getProgramAccounts(
+    url, PROGRAM_ID, accountDisc('Position'), fieldOffset('Position', 'owner'), sub.wallet!,
+  );
+  const thresholdA = sub.thresholdA !== undefined ? BigInt(sub.thresholdA) : null;
+  const thresholdB = sub.thresholdB !== undefined ? BigInt(sub.thresholdB) : null;
+  const fired = new Set(sub.state.fired ?? []);
+  const out: Array<Record<string, unknown>> = [];
+
+  for (const { pubkey, data } of accounts) {
+    const pos = parsePosition(data);
+    if (sub.pool && pos.pool !== sub.pool) continue;
+    const poolData = await getAccountData(url, pos.pool);
+    if (!poolData) continue;
+    const [pendingA, pendingB] = pendingFees(pos, parsePool(poolData));
+    const claimableA = pos.feesOwedA + pendingA;
+    const claimableB = pos.feesOwedB + pendingB;
+    const over = (thresholdA !== null && claimableA >= thresholdA)
+              || (thresholdB !== null && claimableB >= thresholdB);
+
+    if (over && !fired.has(pubkey)) {
+      fired.add(pubkey);
+      out.push({
+        position:    pubkey,
+        pool:        pos.pool,
+        owner:       pos.owner,
+        claimable_a: claimableA.toString(),
+        claimable_b: claimableB.toString(),
+        threshold_a: sub.thresholdA ?? null,
+        threshold_b: sub.thresholdB ?? null,
+      });
+    } else if (!over) {
+      fired.delete(pubkey);
+    }
+  }
+  sub.state.fired = [...fired];
+  return out;
+}
+
+async function pollPrice(sub: Subscription, url: string): Promise<Array<Record<string, unknown>>> {
+  const poolData = await getAccountData(url, sub.pool!);
+  if (!poolData) throw new Error(`Pool not found: ${sub.pool}`);
+  const pool = parsePool(poolData);
+  const [vaultA, vaultB] = await Promise.all([
+    getAccountData(url, pool.tokenAVault),
+    getAccountData(url, pool.tokenBVault),
+  ]);
+  if (!vaultA || !vaultB) throw new Error('Vault account(s) not found');
+  const [reserveA, reserveB] = poolReserves(pool, parseTokenAmount(vaultA), parseTokenAmount(vaultB));
+  if (reserveA === 0n) return [];
+  const price = Number(reserveB) / Number(reserveA);
+
+  const reference = sub.state.referencePrice;
+  if (reference === undefined || reference === 0) {
+    sub.state.referencePrice = price;
+    return [];
+  }
+  const movePct = (price / reference - 1) * 100;
+  if (Math.abs(movePct) < sub.movePct!) return [];
+
+  sub.state.referencePrice = price;
+  return [{
+    pool:            sub.pool,
+    token_a_mint:    pool.tokenAMint,
+    token_b_mint:    pool.tokenBMint,
+    reserve_a:       reserveA.toString(),
+    reserve_b:       reserveB.toString(),
+    price,
+    reference_price: reference,
+    move_pct:        movePct,
+  }];
+}
+
+/** `key=value` pairs of the program's `Swap:` / `Approved swap:` log line. */
+function parseSwapLog(logs: string[]): Record<string, string> | null {
+  const line = logs.find(l => /^Program log: (Approved swap|Swap): /.test(l));
+  if (!line) return null;
+  const fields: Record<string, string> = {
+    kind: line.startsWith('Program log: Approved') ? 'approved_swap' : 'swap',
+  };
+  for (const [, k, v] of line.matchAll(/(\w+)=(\S+)/g)) fields[k] = v;
+  return fields;
+}
+
+async function pollFills(sub: Subscription, url: string): Promise<Array<Record<string, unknown>>> {
+  const sigs = await getSignaturesForAddress(url, sub.wallet!, sub.state.lastSignature, MAX_FILLS_PER_POLL);
+  if (sigs.length === 0) return [];
+  const first = sub.state.lastSignature === undefined;
+  sub.state.lastSignature = sigs[0].signature;
+  // The first poll only records where history starts.
+  if (first) return [];
+
+  const out: Array<Record<string, unknown>> = [];
+  for (const s of sigs.reverse()) {
+    if (s.err) continue;
+    const tx = await getTransactionLogs(url, s.signature);
+    if (!tx || tx.err || !tx.logs.some(l => l.startsWith(`Program ${PROGRAM_ID} invoke`))) continue;
+    const swap = parseSwapLog(tx.logs);
+    if (!swap) continue;
+    out.push({
+      signature:    s.signature,
+      slot:         tx.slot,
+      block_time:   tx.blockTime,
+      wallet:       sub.wallet,
+      kind:         swap.kind,
+      amount_in:    swap.in,
+      amount_out:   swap.out,
+      protocol_fee: swap.protocol_fee,
+      referral_fee: swap.referral_fee ?? '0',
+      lp_fee:       swap.lp_fee,
+      a_to_b:       swap.a_to_b === 'true',
+    });
+  }
+  return out;
+}
+
+// ── Delivery ─────────────────────────────────────────────────────────────────
+
+function randomHex(bytes: number): string {
+  return [...crypto.getRandomValues(new Uint8Array(bytes))].map(b => b.toString(16).padStart(2, '0')).join('');
+}
+
+export function newDeliveryId(): string {
+  return `dlv_${randomHex(12)}`;
+}
+
+/** `X-A2A-Signature` value for `body` sent at unix second `t`. */
+export async function sign(secret: string, t: number, body: string): Promise<string> {
+  const key = await crypto.subtle.importKey(
+    'raw', new TextEncoder().encode(secret), { name: 'HMAC', hash: 'SHA-256' }, false, ['sign'],
+  );
+  const mac = await crypto.subtle.sign('HMAC', key, new TextEncoder().encode(`${t}.${body}`));
+  const hex = [...new Uint8Array(mac)].map(b => b.toString(16).padStart(2, '0')).join('');
+  return `t=${t},v1=${hex}`;
+}
+
+/** POST one delivery; true on a 2xx response. */
+export async function deliver(sub: Subscription, d: Delivery): Promise<boolean> {
+  const body = JSON.stringify({
+    id:         d.id,
+    webhook:    d.webhook,
+    event:      d.event,
+    created_at: d.createdAt,
+    data:       d.data,
+  });
+  try {
+    const res = await fetch(sub.url, {
+      method:  'POST',
+      headers: {
+        'Content-Type':    'application/json',
+        'User-Agent':      'a2a-swap-webhooks',
+        'X-A2A-Event':     d.event,
+        'X-A2A-Delivery':  d.id,
+        'X-A2A-Signature': await sign(sub.secret, Math.floor(Date.now() / 1000), body),
+      },
+      body,
+      signal: AbortSignal.timeout(DELIVERY_TIMEOUT_MS),
+    });
+    return res.ok;
+  } catch {
+    return false;
+  }
+}
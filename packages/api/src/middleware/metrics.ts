@@ -0,0 +1,54 @@
+/**
+ * Request metrics middleware: times every request, counts its Solana RPC
+ * calls and records the result (lib/metrics.ts) — into the isolate summary
+ * GET /metrics serves, the METRICS Analytics Engine dataset and the OTLP
+ * collector, whichever are configured. Export failures are logged, never
+ * surfaced to the caller.
+ *
+ * Requests /rpc dispatches internally pass through here too and are recorded
+ * under their own route.
+ */
+
+import type { MiddlewareHandler } from 'hono';
+import type { AppEnv } from '../env.js';
+import { solanaNetwork } from '../lib/rpc.js';
+import {
+  type RequestMetric, routeLabel, countingRpcCalls, recordRequest,
+  writeDataPoint, otlpSpan, postOtlp,
+} from '../lib/metrics.js';
+
+export const metrics: MiddlewareHandler<AppEnv> = async (c, next) => {
+  const started   = Date.now();
+  const rpc_calls = await countingRpcCalls(next);
+
+  const m: RequestMetric = {
+    route:      routeLabel(c.req.path),
+    method:     c.req.method,
+    status:     c.res.status,
+    started,
+    latency_ms: Date.now() - started,
+    rpc_calls,
+    ray:        c.req.header('cf-ray') ?? '',
+  };
+  recordRequest(m);
+  console.log(`${m.method} ${m.route} → ${m.status} in ${m.latency_ms}ms (${m.rpc_calls} rpc)`);
+
+  const network = solanaNetwork(c.env);
+  if (c.env.METRICS) {
+    try {
+      writeDataPoint(c.env.METRICS, m, network);
+    } catch (e) {
+      console.error('metrics: analytics engine write failed:', e);
+    }
+  }
+
+  if (c.env.OTLP_ENDPOINT) {
+    const exporting = postOtlp(c.env, otlpSpan(m, network))
+      .catch(e => console.error('metrics: OTLP export failed:', e));
+    try {
+      c.executionCtx.waitUntil(exporting);
+    } catch {
+      // No ExecutionContext (an in-process test request): the export still runs.
+    }
+  }
+};
@@ -24,6 +24,7 @@
 import type { MiddlewareHandler } from 'hono';
 import type { AppEnv } from '../env.js';
 import { USDC_MINT, X402_SOLANA_NETWORK, X402_FEE_PAYER_ADDR } from '../lib/constants.js';
+import { apiError } from '../lib/errors.js';
 
 const PAYMENT_HEADER = 'X-Payment';
 
@@ -151,19 +152,17 @@ export const x402: MiddlewareHandler<AppEnv> = async (c, next) => {
   } catch (err) {
     // Settlement failed — don't execute the swap, return error for agent to retry
     console.error('Payment settlement failed:', err);
-    return c.json(
-      { error: 'payment_settlement_failed', retryAfter: 5 },
-      500,
-    );
+    return apiError(c, 500, 'INTERNAL_ERROR', 'Payment settlement failed', {
+      reason: 'payment_settlement_failed', retry_after_secs: 5,
+    });
   }
 
   if (!settleRes.ok) {
     const errText = await settleRes.text().catch(() => '');
     console.error('Payment settlement HTTP error:', settleRes.status, errText);
-    return c.json(
-      { error: 'payment_settlement_failed', retryAfter: 5 },
-      500,
-    );
+    return apiError(c, 500, 'INTERNAL_ERROR', 'Payment settlement failed', {
+      reason: 'payment_settlement_failed', retry_after_secs: 5,
+    });
   }
 
   // Settlement confirmed — now serve the request.
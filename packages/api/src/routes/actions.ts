@@ -0,0 +1,113 @@
+/**
+ * GET|POST /actions/swap — Solana Actions endpoint, so a swap can be shared
+ * as a Blink in social and agent interfaces.
+ *
+ * Query:
+ *   tokenIn      string?  — token symbol or base58 mint (default USDC)
+ *   tokenOut     string?  — token symbol or base58 mint (default SOL)
+ *   amount       string?  — input amount in whole tokens (e.g. "12.5"); left
+ *                           out, the Blink asks the user for it
+ *   slippageBps  number?  — allowed slippage in bps (default 50 = 0.5%)
+ *
+ * GET  → ActionGetResponse: icon, title, description and the swap button
+ *   (or an amount field and button when `amount` is not set).
+ * POST { account } → ActionPostResponse `{ type: 'transaction', transaction,
+ *   message }`: the /convert transaction for `account` to sign, quoted now.
+ *
+ * Errors answer `{ message }` (ActionError). Every response carries the
+ * X-Action-Version and X-Blockchain-Ids headers; GET /actions.json maps the
+ * site's /actions/** paths here for Blink clients.
+ */
+
+import { Hono } from 'hono';
+import type { Context } from 'hono';
+import type { AppEnv } from '../env.js';
+import { rpcUrl, getMintDecimals } from '../lib/rpc.js';
+import { parseUnits, resolveMint } from '../lib/math.js';
+import { KNOWN_TOKENS, X402_SOLANA_NETWORK } from '../lib/constants.js';
+import { buildWalletSwap } from './pay.js';
+
+/** Actions spec version these responses follow. */
+const ACTION_VERSION = '2.4';
+
+const router = new Hono<AppEnv>();
+
+router.use('*', async (c, next) => {
+  await next();
+  c.header('X-Action-Version', ACTION_VERSION);
+  c.header('X-Blockchain-Ids', X402_SOLANA_NETWORK);  // mainnet, CAIP-2
+});
+
+/** The swap's tokens from the query, with the defaults applied. */
+function pair(c: Context<AppEnv>): { tokenIn: string; tokenOut: string } {
+  return {
+    tokenIn:  c.req.query('tokenIn')  || 'USDC',
+    tokenOut: c.req.query('tokenOut') || 'SOL',
+  };
+}
+
+router.get('/swap', (c) => {
+  const { tokenIn, tokenOut } = pair(c);
+  const amount      = c.req.query('amount');
+  const slippageBps = c.req.query('slippageBps');
+
+  const params = new URLSearchParams({ tokenIn, tokenOut });
+  if (slippageBps) params.set('slippageBps', slippageBps);
+  const href = (amount: string) => `/actions/swap?${params}&amount=${amount}`;
+
+  const action = amount
+    ? { type: 'transaction', label: `Swap ${amount} ${tokenIn}`, href: href(encodeURIComponent(amount)) }
+    : {
+      type:       'transaction',
+      label:      'Swap',
+      href:       href('{amount}'),
+      parameters: [{ name: 'amount', label: `Amount of ${tokenIn}`, type: 'number', required: true }],
+    };
+
+  return c.json({
+    type:        'action',
+    icon:        `${c.env.API_URL}/pay/icon.svg`,
+    title:       `Swap ${tokenIn} for ${tokenOut} on A2A-Swap`,
+    description: `Sell ${tokenIn} for ${tokenOut} in the A2A-Swap pool at the current price, ` +
+                 `with ${Number(slippageBps ?? 50) / 100}% maximum slippage.`,
+    label:       action.label,
+    links:       { actions: [action] },
+  });
+});
+
+router.post('/swap', async (c) => {
+  let account: unknown;
+  try {
+    ({ account } = await c.req.json() as { account?: unknown });
+  } catch {
+    return c.json({ message: 'Invalid JSON body' }, 400);
+  }
+  if (typeof account !== 'string' || !account) {
+    return c.json({ message: 'account is required' }, 400);
+  }
+
+  const { tokenIn, tokenOut } = pair(c);
+  const amount = c.req.query('amount');
+  if (!amount) return c.json({ message: 'amount is required' }, 400);
+
+  const mintIn = resolveMint(tokenIn, KNOWN_TOKENS);
+  if (!mintIn) return c.json({ message: `Unknown token: ${tokenIn}` }, 400);
+  const decimals = await getMintDecimals(rpcUrl(c.env), mintIn).catch(() => null);
+  if (decimals === null) return c.json({ message: `Could not read the decimals of ${tokenIn}` }, 502);
+
+  let raw: bigint;
+  try { raw = parseUnits(amount, decimals); } catch (e) {
+    return c.json({ message: e instanceof Error ? e.message : String(e) }, 400);
+  }
+
+  const swap = await buildWalletSwap(c.env, {
+    tokenIn,
+    tokenOut,
+    amount:      raw.toString(),
+    slippageBps: c.req.query('slippageBps'),
+  }, account);
+  if (!swap.ok) return c.json({ message: swap.message }, swap.status);
+  return c.json({ type: 'transaction', transaction: swap.transaction, message: swap.message });
+});
+
+export default router;
@@ -10,9 +10,11 @@
 
 import { Hono } from 'hono';
 import type { AppEnv } from '../env.js';
-import { rpcUrl, getProgramAccountsBySize, getAccountData } from '../lib/rpc.js';
-import { parsePool, parseTokenAmount } from '../lib/math.js';
-import { PROGRAM_ID, POOL, KNOWN_TOKENS } from '../lib/constants.js';
+import { rpcUrl, getProgramAccountsByType, getAccountData } from '../lib/rpc.js';
+import { parsePool, parseTokenAmount, poolReserves } from '../lib/math.js';
+import { accountDisc } from '../lib/idl.js';
+import { PROGRAM_ID, KNOWN_TOKENS } from '../lib/constants.js';
+import { apiError } from '../lib/errors.js';
 
 // Reverse lookup: mint address → human symbol (e.g. SOL, USDC)
 const MINT_TO_SYMBOL: Record<string, string> = {};
@@ -27,9 +29,9 @@ router.get('/', async (c) => {
 
   let accounts: Array<{ pubkey: string; data: Uint8Array }>;
   try {
-    accounts = await getProgramAccountsBySize(url, PROGRAM_ID, POOL.TOTAL);
+    accounts = await getProgramAccountsByType(url, PROGRAM_ID, accountDisc('Pool'));
   } catch (e) {
-    return c.json({ error: `getProgramAccounts failed: ${e}` }, 502);
+    return apiError(c, 502, 'RPC_ERROR', `getProgramAccounts failed: ${e}`);
   }
 
   // Fetch all vault balances in parallel across all pools.
@@ -42,9 +44,10 @@ router.get('/', async (c) => {
       getAccountData(url, pool.tokenBVault),
     ]);
 
-    let reserveA = 0n, reserveB = 0n;
-    try { if (vaultAData) reserveA = parseTokenAmount(vaultAData); } catch { /* skip */ }
-    try { if (vaultBData) reserveB = parseTokenAmount(vaultBData); } catch { /* skip */ }
+    let vaultA = 0n, vaultB = 0n;
+    try { if (vaultAData) vaultA = parseTokenAmount(vaultAData); } catch { /* skip */ }
+    try { if (vaultBData) vaultB = parseTokenAmount(vaultBData); } catch { /* skip */ }
+    const [reserveA, reserveB] = poolReserves(pool, vaultA, vaultB);
 
     return {
       pool:           pubkey,
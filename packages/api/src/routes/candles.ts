@@ -0,0 +1,154 @@
+/**
+ * GET /candles — OHLC candles of a pool's spot price from the scheduled
+ * snapshots (src/lib/poolSnapshot.ts); no RPC reads beyond mint decimals.
+ *
+ * Query params:
+ *   pair=SOL-USDC        base-quote, symbols or mints, in either pool order
+ *   pool=<address>       instead of pair: the pool, priced as token B per token A
+ *   interval=5m          1m | 5m | 15m | 1h (default 5m)
+ *   limit=288            newest candles returned (default and max: the 24h of history)
+ *
+ * Each candle: `t` (bucket start, unix ms), `open` / `high` / `low` / `close`
+ * (quote per base, in whole tokens), `volume_base` / `volume_quote` (atomic
+ * units swapped during the bucket) and `samples`. Buckets without a
+ * snapshot are omitted. `price_units` is `atomic` when a mint's decimals
+ * could not be read.
+ */
+
+import { Hono } from 'hono';
+import type { AppEnv } from '../env.js';
+import { rpcUrl, getMintDecimals } from '../lib/rpc.js';
+import { resolveMint } from '../lib/math.js';
+import { KNOWN_TOKENS } from '../lib/constants.js';
+import {
+  HISTORY_WINDOW_MS, latestPoolSnapshot, poolHistory, type HistoryPoint, type PoolStat,
+} from '../lib/poolSnapshot.js';
+import { apiError, invalidRequest, unknownToken } from '../lib/errors.js';
+
+const INTERVALS: Record<string, number> = {
+  '1m':  60_000,
+  '5m':  5 * 60_000,
+  '15m': 15 * 60_000,
+  '1h':  60 * 60_000,
+};
+const DEFAULT_INTERVAL = '5m';
+
+interface Candle {
+  t:            number;
+  open:         number;
+  high:         number;
+  low:          number;
+  close:        number;
+  volume_base:  string;
+  volume_quote: string;
+  samples:      number;
+}
+
+/**
+ * Bucket `points` into candles of `intervalMs`. `invert` flips the stored
+ * B-per-A price and volumes to the requested orientation; `scale` converts
+ * the atomic-unit price to whole tokens.
+ */
+function toCandles(points: HistoryPoint[], intervalMs: number, invert: boolean, scale: number): Candle[] {
+  const candles: Candle[] = [];
+  // Lifetime volume at the previous point; each point adds the difference.
+  let prevVolume: [bigint, bigint] | null = null;
+  let current: Candle | null = null;
+
+  for (const p of points) {
+    const volume: [bigint, bigint] = invert
+      ? [BigInt(p.volume_b), BigInt(p.volume_a)]
+      : [BigInt(p.volume_a), BigInt(p.volume_b)];
+    const t = Math.floor(p.t / intervalMs) * intervalMs;
+    if (current && current.t !== t) {
+      candles.push(current);
+      current = null;
+    }
+
+    const raw   = p.price == null ? null : invert ? 1 / p.price : p.price;
+    const price = raw == null ? null : raw * scale;
+    const base  = prevVolume ?? volume;
+    if (price != null) {
+      if (!current) {
+        current = { t, open: price, high: price, low: price, close: price, volume_base: '0', volume_quote: '0', samples: 0 };
+      }
+      current.high  = Math.max(current.high, price);
+      current.low   = Math.min(current.low, price);
+      current.close = price;
+      current.samples += 1;
+    }
+    if (current) {
+      current.volume_base  = (volume[0] - base[0] + BigInt(current.volume_base)).toString();
+      current.volume_quote = (volume[1] - base[1] + BigInt(current.volume_quote)).toString();
+    }
+    prevVolume = volume;
+  }
+  if (current) candles.push(current);
+  return candles;
+}
+
+const router = new Hono<AppEnv>();
+
+router.get('/', async (c) => {
+  const kv = c.env.POOL_SNAPSHOTS;
+  if (!kv) return apiError(c, 503, 'NOT_AVAILABLE', 'Pool snapshots are not enabled on this deployment');
+
+  const interval   = c.req.query('interval') ?? DEFAULT_INTERVAL;
+  const intervalMs = INTERVALS[interval];
+  if (!intervalMs) {
+    return apiError(c, 400, 'INVALID_ARGUMENT', `interval must be one of ${Object.keys(INTERVALS).join(', ')}`);
+  }
+  const maxLimit = HISTORY_WINDOW_MS / intervalMs;
+  const limit    = Number(c.req.query('limit') ?? maxLimit);
+  if (!Number.isInteger(limit) || limit < 1) return apiError(c, 400, 'INVALID_ARGUMENT', 'limit must be a positive integer');
+
+  const snapshot = await latestPoolSnapshot(kv);
+  if (!snapshot) return apiError(c, 503, 'NOT_AVAILABLE', 'No pool snapshot taken yet');
+
+  const pair      = c.req.query('pair');
+  const poolParam = c.req.query('pool');
+  let baseMint: string, quoteMint: string;
+  let stat: PoolStat | undefined;
+  if (poolParam) {
+    stat = snapshot.pools.find((p) => p.pool === poolParam);
+    if (!stat) return apiError(c, 404, 'POOL_NOT_FOUND', `Pool not found: ${poolParam}`);
+    [baseMint, quoteMint] = [stat.token_a_mint, stat.token_b_mint];
+  } else if (pair) {
+    const [base, quote, ...rest] = pair.split('-');
+    if (!base || !quote || rest.length > 0) return apiError(c, 400, 'INVALID_ARGUMENT', 'pair must look like SOL-USDC');
+    const mintBase  = resolveMint(base, KNOWN_TOKENS);
+    const mintQuote = resolveMint(quote, KNOWN_TOKENS);
+    if (!mintBase)  return unknownToken(c, base);
+    if (!mintQuote) return unknownToken(c, quote);
+    stat = snapshot.pools.find((p) =>
+      (p.token_a_mint === mintBase && p.token_b_mint === mintQuote)
+      || (p.token_a_mint === mintQuote && p.token_b_mint === mintBase));
+    if (!stat) return apiError(c, 404, 'POOL_NOT_FOUND', `No pool found for ${pair}`);
+    [baseMint, quoteMint] = [mintBase, mintQuote];
+  } else {
+    return invalidRequest(c, 'Provide ?pair=SOL-USDC or ?pool=<address>', ['pair', 'pool']);
+  }
+
+  const url = rpcUrl(c.env);
+  const [baseDecimals, quoteDecimals] = await Promise.all([
+    getMintDecimals(url, baseMint), getMintDecimals(url, quoteMint),
+  ]);
+  // Without decimals the price stays in atomic units.
+  const scale = baseDecimals != null && quoteDecimals != null ? 10 ** (baseDecimals - quoteDecimals) : 1;
+
+  const invert  = stat.token_a_mint !== baseMint;
+  const candles = toCandles(await poolHistory(kv, stat.pool), intervalMs, invert, scale)
+    .slice(-Math.min(limit, maxLimit));
+
+  return c.json({
+    pool:          stat.pool,
+    base_mint:     baseMint,
+    quote_mint:    quoteMint,
+    interval,
+    price_units:   baseDecimals == null || quoteDecimals == null ? 'atomic' : 'whole_tokens',
+    count:         candles.length,
+    candles,
+  });
+});
+
+export default router;
@@ -5,25 +5,83 @@
  * supported actions, fee structure, live pool count, and where to call.
  * Agents and MCP planners can call this once on startup to understand
  * what A2A-Swap can do without reading any documentation.
+ *
+ * The program half is read live: the instruction set from the on-chain IDL
+ * (the bundled one when none is published) and fees, fee bounds and pause
+ * switches from ProtocolConfig, so features the deployed program lacks or
+ * governance has paused show as unavailable.
  */
 
 import { Hono } from 'hono';
 import type { AppEnv } from '../env.js';
-import { rpcUrl, getProgramAccountsBySize } from '../lib/rpc.js';
-import { PROGRAM_ID, POOL, VERSION } from '../lib/constants.js';
+import { rpcUrl, solanaNetwork, getMultipleAccountsData, getProgramAccountsByType } from '../lib/rpc.js';
+import { parseProtocolConfig } from '../lib/math.js';
+import type { ProtocolConfigState } from '../lib/math.js';
+import { accountDisc, BUNDLED_IDL, idlInstructions, parseIdlAccount } from '../lib/idl.js';
+import { resolveIdlAddress, resolveTreasury } from '../lib/pda.js';
+import {
+  PROGRAM_ID, PROTOCOL_FEE_DENOM, PAUSE_SWAPS, PAUSE_POOL_CREATION, VERSION,
+} from '../lib/constants.js';
+
+/**
+ * Optional program features, the instruction that provides each and the
+ * pause switch that turns it off (0 = none) — same table as the Rust SDK's
+ * CapabilityCard.
+ */
+const FEATURES: Array<[string, string, number]> = [
+  ['swap',                  'swap',                           PAUSE_SWAPS],
+  ['exact_out_swaps',       'swap_exact_out',                 PAUSE_SWAPS],
+  ['commit_reveal',         'commit_swap',                    PAUSE_SWAPS],
+  ['approval_mode',         'approve_and_execute',            PAUSE_SWAPS],
+  ['delegated_swaps',       'swap_as_delegate',               PAUSE_SWAPS],
+  ['signed_intents',        'swap_with_intent',               PAUSE_SWAPS],
+  ['pool_creation',         'initialize_pool',                PAUSE_POOL_CREATION],
+  ['range_pools',           'initialize_range_pool',          0],
+  ['liquidity',             'provide_liquidity',              0],
+  ['proportional_deposits', 'provide_liquidity_proportional', 0],
+  ['position_locks',        'lock_position',                  0],
+  ['fee_tiers',             'open_agent_volume',              0],
+  ['permissioned_pools',    'set_pool_allowlist',             0],
+  ['token_gated_pools',     'set_pool_gate',                  0],
+  ['dynamic_fees',          'configure_dynamic_fee',          0],
+  ['governance',            'create_proposal',                0],
+];
 
 const router = new Hono<AppEnv>();
 
 router.get('/', async (c) => {
   const url = rpcUrl(c.env);
+  const idlAddress = (await resolveIdlAddress()).toBase58();
 
   // Fetch live pool count — best-effort, fall back to null on error.
   let poolCount: number | null = null;
   try {
-    const pools = await getProgramAccountsBySize(url, PROGRAM_ID, POOL.TOTAL);
+    const pools = await getProgramAccountsByType(url, PROGRAM_ID, accountDisc('Pool'));
     poolCount = pools.length;
   } catch { /* non-fatal */ }
 
+  // Deployed instruction set and live ProtocolConfig — the bundled IDL and
+  // the program defaults on error.
+  let idl = BUNDLED_IDL;
+  let idlOnChain = false;
+  let config: ProtocolConfigState = parseProtocolConfig(null);
+  try {
+    const [idlData, configData] = await getMultipleAccountsData(url, [idlAddress, resolveTreasury().toBase58()]);
+    config = parseProtocolConfig(configData);
+    if (idlData && idlData.length > 0) {
+      idl = await parseIdlAccount(idlData);
+      idlOnChain = true;
+    }
+  } catch { /* non-fatal */ }
+
+  const instructions  = idlInstructions(idl);
+  const swapsPaused   = (config.paused & PAUSE_SWAPS) !== 0;
+  const poolsPaused   = (config.paused & PAUSE_POOL_CREATION) !== 0;
+  const capabilities  = Object.fromEntries(FEATURES.map(([feature, ix, pause]) =>
+    [feature, instructions.includes(ix) && (config.paused & pause) === 0],
+  ));
+  const protocolFeeBps = Number(config.protocolFeeBps);
+
   return c.json({
     name:        'a2a-swap',
     version:     VERSION,
@@ -34,11 +92,32 @@ router.get('/', async (c) => {
     docs:        'https://github.com/liqdlad-rgb/a2a-swap',
     live_pools:  poolCount,
 
+    // Anchor IDL: on-chain in the program's IDL account (published with
+    // scripts/publish-idl.sh), or the copy this API was built with.
+    idl: {
+      account:  idlAddress,
+      url:      'https://a2a-swap-api.a2a-swap.workers.dev/idl',
+      on_chain: idlOnChain,
+    },
+
+    // What the deployed program offers right now.
+    instructions,
+    capabilities,
+    paused: { swaps: swapsPaused, pool_creation: poolsPaused },
+    config_initialized: config.initialized,
+
     fee_structure: {
-      protocol_fee_bps: 20,
-      protocol_fee_pct: '0.020%',
+      protocol_fee_bps: protocolFeeBps,
+      protocol_fee_denominator: Number(PROTOCOL_FEE_DENOM),
+      protocol_fee_pct: `${(protocolFeeBps / 1_000).toFixed(3)}%`,
       protocol_fee_zero_for_molt: true,
       molt_collection: 'EvXNCtaoVuC1NQLQswAnqsbQKPgVTdjrrLKa8MpMJiLf',
+      referral_share_bps: config.referralShareBps,
+      pool_fee_bounds_bps: { min: config.minPoolFeeBps, max: config.maxPoolFeeBps },
+      fee_tiers: config.tiers
+        .filter(t => t.discountBps > 0)
+        .sort((a, b) => (a.minVolume < b.minVolume ? -1 : a.minVolume > b.minVolume ? 1 : 0))
+        .map(t => ({ min_volume: t.minVolume.toString(), discount_bps: t.discountBps })),
       typical_lp_fee_bps: 25,
       x402_per_swap_usdc: '0.001',
     },
@@ -58,6 +137,14 @@ router.get('/', async (c) => {
         description: 'Quote a swap: estimated output, fees, and price impact. No transaction built.',
         params:      { tokenIn: 'string', tokenOut: 'string', amount: 'string (atomic units)' },
       },
+      {
+        name:        'simulate_liquidity',
+        method:      'POST',
+        path:        '/simulate-liquidity',
+        auth:        'free',
+        description: 'Preview a deposit (LP shares minted) or withdrawal (tokens returned), with pool share and price after. No transaction built.',
+        params:      { tokenA: 'string', tokenB: 'string', amountA: 'string (optional)', amountB: 'string (optional)', lpShares: 'string (optional)' },
+      },
       {
         name:        'compare_quotes',
         method:      'GET',
@@ -73,6 +160,25 @@ router.get('/', async (c) => {
         auth:        'x402 (0.001 USDC)',
         description: 'Build an unsigned Solana swap transaction. Agent signs and submits. SOL wrap/unwrap included automatically.',
         params:      { tokenIn: 'string', tokenOut: 'string', amount: 'string', wallet: 'string (base58)', slippageBps: 'number (optional, default 50)' },
+        available:   !swapsPaused,
+      },
+      {
+        name:        'pay_swap',
+        method:      'GET',
+        path:        '/pay/convert',
+        auth:        'free',
+        description: 'Solana Pay transaction request for a swap: hand `solana:` + this URL to a human, whose wallet POSTs { account } and signs the returned transaction.',
+        params:      { tokenIn: 'string', tokenOut: 'string', amount: 'string', slippageBps: 'number (optional, default 50)', deadlineUnix: 'number (optional)', referrer: 'string (optional)' },
+        available:   !swapsPaused,
+      },
+      {
+        name:        'actions_swap',
+        method:      'GET',
+        path:        '/actions/swap',
+        auth:        'free',
+        description: 'Solana Actions endpoint for a swap, to share as a Blink. GET returns the action metadata; POST { account } returns the transaction to sign. Amount in whole tokens.',
+        params:      { tokenIn: 'string (optional, default USDC)', tokenOut: 'string (optional, default SOL)', amount: 'string (optional, whole tokens)', slippageBps: 'number (optional, default 50)' },
+        available:   !swapsPaused,
       },
       {
         name:        'active_pools',
@@ -82,12 +188,20 @@ router.get('/', async (c) => {
         description: 'List all deployed pools with live reserves, LP supply, and fee rate.',
         params:      {},
       },
+      {
+        name:        'tokens',
+        method:      'GET',
+        path:        '/tokens',
+        auth:        'free',
+        description: 'List known tokens (symbol, mint, decimals) and the pools each one trades in.',
+        params:      {},
+      },
       {
         name:        'pool_info',
         method:      'GET',
         path:        '/pool-info',
         auth:        'free',
-        description: 'Fetch state for a single pool by token pair or pool address.',
+        description: 'Fetch state for a single pool by token pair or pool address, including who may swap (permissioned, gate_mint).',
         params:      { tokenA: 'string (optional)', tokenB: 'string (optional)', pool: 'string (optional)' },
       },
       {
@@ -95,19 +209,67 @@ router.get('/', async (c) => {
         method:      'GET',
         path:        '/my-positions',
         auth:        'free',
-        description: 'List all LP positions owned by a wallet, with USD values.',
-        params:      { wallet: 'string (base58)' },
+        description: 'List all LP positions owned by a wallet, with USD values. stale_ms is the age of the cached account data.',
+        params:      { wallet: 'string (base58)', refresh: 'boolean (optional, re-read accounts)' },
       },
       {
         name:        'my_fees',
         method:      'GET',
         path:        '/my-fees',
         auth:        'free',
-        description: 'Claimable and pending fees for all positions owned by a wallet, with USD values.',
-        params:      { wallet: 'string (base58)' },
+        description: 'Claimable and pending fees for all positions owned by a wallet, with USD values. stale_ms is the age of the cached account data.',
+        params:      { wallet: 'string (base58)', refresh: 'boolean (optional, re-read accounts)' },
+      },
+      {
+        name:        'register_webhook',
+        method:      'POST',
+        path:        '/webhooks',
+        auth:        'free',
+        description: 'Push notifications: fees.threshold (claimable fees crossed a threshold), price.move (pool price moved movePct %), swap.fill (a swap by wallet confirmed). Deliveries are HMAC-signed (X-A2A-Signature) and retried.',
+        params:      { url: 'string (https)', event: 'string', wallet: 'string (optional)', pool: 'string (optional)', tokenA: 'string (optional)', tokenB: 'string (optional)', thresholdA: 'string (optional)', thresholdB: 'string (optional)', movePct: 'number (optional)' },
+      },
+      {
+        name:        'publish_intent',
+        method:      'POST',
+        path:        '/intents',
+        auth:        'free',
+        description: 'Publish a signed swap intent (Rust SDK sign_intent JSON) for relayers to execute gaslessly via swap_with_intent. Checked for signature, expiry (at most 1h out) and the agent\'s current nonce.',
+        params:      { agent: 'string (base58)', pool: 'string (base58)', amount_in: 'integer', min_amount_out: 'integer', a_to_b: 'boolean', max_price_impact_bps: 'integer', nonce: 'integer', expires_at: 'integer (unix seconds)', signature: 'string (base58)' },
+        available:   !swapsPaused,
+      },
+      {
+        name:        'pending_intents',
+        method:      'GET',
+        path:        '/intents/pending',
+        auth:        'free',
+        description: 'Signed swap intents still executable, oldest first — for solver / relayer agents to fill.',
+        params:      { pool: 'string (optional)', agent: 'string (optional)', limit: 'integer (optional, default 100, max 500)' },
+      },
+      {
+        name:        'json_rpc',
+        method:      'POST',
+        path:        '/rpc',
+        auth:        'free (a2a.convert: x402 via X-Payment header)',
+        description: 'JSON-RPC 2.0 endpoint with a2a.simulate, a2a.convert and a2a.poolInfo. Params are the REST params by name; batches allowed.',
+        params:      { jsonrpc: "'2.0'", method: 'string', params: 'object', id: 'string | number' },
+      },
+      {
+        name:        'devnet_bootstrap',
+        method:      'POST',
+        path:        '/devnet/bootstrap',
+        auth:        'free',
+        description: 'Devnet deployment only: airdrops SOL to the agent, lists the seeded test pools and returns unsigned createAssociatedTokenAccountIdempotent instructions for any test-token ATA the agent is missing.',
+        params:      { agent: 'string (base58)', lamports: 'integer (optional, default 1000000000, max 2000000000)' },
+        available:   solanaNetwork(c.env) === 'devnet',
       },
     ],
 
+    // Pools may restrict who swaps; pool_info reports both per pool.
+    access_control: {
+      permissioned_pools: 'Only traders on the creator\'s merkle allowlist may swap; each registers a TraderPass first (Rust SDK / CLI `allowlist register`). pool_info.permissioned.',
+      token_gated_pools:  'Only holders of the pool\'s gate mint (e.g. a soulbound compliance credential, SPL Token or Token-2022) may swap; the swap passes the trader\'s token account of it. pool_info.gate_mint, null when ungated.',
+    },
+
     integrations: [
       { name: 'MCP server',            package: '@liqdlad/mcp-a2a-swap',           install: 'npx @liqdlad/mcp-a2a-swap' },
       { name: 'ElizaOS plugin',        package: '@liqdlad/eliza-plugin-a2a-swap',  install: 'npm i @liqdlad/eliza-plugin-a2a-swap' },
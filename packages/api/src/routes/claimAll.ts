@@ -0,0 +1,240 @@
+/**
+ * POST /claim-all — builds unsigned transactions that claim fees from every
+ * position a wallet holds with anything to claim.
+ *
+ * Request body (JSON):
+ *   wallet  string  — the positions' owner (fee payer + signer)
+ *
+ * Response JSON:
+ *   wallet        string
+ *   transactions  string[]  — base64-encoded unsigned Transactions, each
+ *                             under the 1232-byte packet limit; sign and send all
+ *   claims        object[]  — one per position: position, pool, mint_a, mint_b,
+ *                             fees_a, fees_b, compounds, transaction (index into
+ *                             `transactions`)
+ *   totals        object[]  — { mint, symbol, amount } paid out per token
+ *
+ * A position set to auto-compound whose fees reach its threshold is still
+ * claimed, but the program turns the fees into LP shares (`compounds: true`)
+ * and they are left out of `totals`.
+ *
+ * Each claim is preceded by idempotent createATA instructions for the
+ * wallet's token A / B accounts (once per transaction), and by
+ * migrate_pool / migrate_position for accounts on an older layout. The
+ * wallet snapshot is always re-read (refreshing the /my-positions cache with
+ * it) so the estimates match the chain.
+ */
+
+import { Hono } from 'hono';
+import { Transaction, TransactionInstruction, PublicKey } from '@solana/web3.js';
+import type { AppEnv } from '../env.js';
+import { rpcUrl, getLatestBlockhash } from '../lib/rpc.js';
+import {
+  parsePosition, parsePool, parseTokenAmount, poolReserves, pendingFees,
+} from '../lib/math.js';
+import { resolvePoolAuthority, resolveAta } from '../lib/pda.js';
+import { instructionData } from '../lib/idl.js';
+import { loadWallet, type WalletAccounts } from '../lib/walletSnapshot.js';
+import {
+  PROGRAM_ID, TOKEN_PROGRAM, ATA_PROGRAM, POOL_VERSION, POSITION_VERSION, TOKEN_REGISTRY,
+} from '../lib/constants.js';
+import { apiError, invalidRequest } from '../lib/errors.js';
+
+const SYSTEM_PROG = '11111111111111111111111111111111';
+
+// Largest serialized transaction the cluster accepts.
+const PACKET_DATA_SIZE = 1232;
+
+const router = new Hono<AppEnv>();
+
+/** createAssociatedTokenAccountIdempotent — no-op if ATA already exists. */
+function createAtaIdempotentIx(
+  payer: PublicKey, ata: PublicKey, owner: PublicKey, mint: PublicKey,
+): TransactionInstruction {
+  return new TransactionInstruction({
+    programId: new PublicKey(ATA_PROGRAM),
+    keys: [
+      { pubkey: payer,                          isSigner: true,  isWritable: true  },
+      { pubkey: ata,                            isSigner: false, isWritable: true  },
+      { pubkey: owner,                          isSigner: false, isWritable: false },
+      { pubkey: mint,                           isSigner: false, isWritable: false },
+      { pubkey: new PublicKey(SYSTEM_PROG),     isSigner: false, isWritable: false },
+      { pubkey: new PublicKey(TOKEN_PROGRAM),   isSigner: false, isWritable: false },
+    ],
+    data: Buffer.from([1]),  // 1 = CreateIdempotent
+  });
+}
+
+/** migrate_pool / migrate_position — permissionless, payer covers the extra rent. */
+function migrateIx(name: 'migrate_pool' | 'migrate_position', payer: PublicKey, account: PublicKey): TransactionInstruction {
+  return new TransactionInstruction({
+    programId: new PublicKey(PROGRAM_ID),
+    keys: [
+      { pubkey: payer,                      isSigner: true,  isWritable: true  },
+      { pubkey: account,                    isSigner: false, isWritable: true  },
+      { pubkey: new PublicKey(SYSTEM_PROG), isSigner: false, isWritable: false },
+    ],
+    data: Buffer.from(instructionData(name)),
+  });
+}
+
+/** Signature count byte + one signature + the message. */
+function serializedSize(tx: Transaction): number {
+  return 1 + 64 + tx.serializeMessage().length;
+}
+
+interface Claim {
+  position:  string;
+  pool:      string;
+  mintA:     string;
+  mintB:     string;
+  feesA:     bigint;
+  feesB:     bigint;
+  compounds: boolean;
+  /** createATA instructions by ATA address, emitted once per transaction. */
+  atas:      Map<string, TransactionInstruction>;
+  ixs:       TransactionInstruction[];
+}
+
+router.post('/', async (c) => {
+  let body: { wallet?: string };
+  try {
+    body = await c.req.json() as { wallet?: string };
+  } catch {
+    return invalidRequest(c, 'Invalid JSON body');
+  }
+  if (!body.wallet) return invalidRequest(c, 'wallet is required', ['wallet']);
+
+  let agentPk: PublicKey;
+  try { agentPk = new PublicKey(body.wallet); } catch {
+    return apiError(c, 400, 'INVALID_ARGUMENT', 'Invalid wallet public key');
+  }
+  const wallet = agentPk.toBase58();
+
+  let snapshot: WalletAccounts, blockhash: string;
+  try {
+    [snapshot, blockhash] = await Promise.all([
+      loadWallet(c.env, wallet, true),
+      getLatestBlockhash(rpcUrl(c.env)),
+    ]);
+  } catch (e) {
+    return apiError(c, 502, 'RPC_ERROR', String(e));
+  }
+
+  const claims: Claim[] = [];
+  for (const { pubkey, data } of snapshot.positions) {
+    const pos      = parsePosition(data);
+    const poolData = snapshot.account(pos.pool);
+    if (!poolData) continue;
+    const pool = parsePool(poolData);
+
+    const [pendingA, pendingB] = pendingFees(pos, pool);
+    const feesA = pos.feesOwedA + pendingA;
+    const feesB = pos.feesOwedB + pendingB;
+    if (feesA === 0n && feesB === 0n) continue;
+
+    // Mirrors claim_fees: compounding needs the threshold met and at least
+    // one LP share minted, otherwise the fees are transferred out.
+    const vaultA = snapshot.account(pool.tokenAVault);
+    const vaultB = snapshot.account(pool.tokenBVault);
+    const [reserveA, reserveB] = poolReserves(
+      pool, vaultA ? parseTokenAmount(vaultA) : 0n, vaultB ? parseTokenAmount(vaultB) : 0n,
+    );
+    const fromA = reserveA > 0n ? feesA * pool.lpSupply / reserveA : 0n;
+    const fromB = reserveB > 0n ? feesB * pool.lpSupply / reserveB : 0n;
+    const compounds = pos.autoCompound && feesA + feesB >= pos.compoundThreshold
+      && pool.lpSupply > 0n && (fromA < fromB ? fromA : fromB) > 0n;
+
+    const poolPk     = new PublicKey(pos.pool);
+    const positionPk = new PublicKey(pubkey);
+    const mintA      = new PublicKey(pool.tokenAMint);
+    const mintB      = new PublicKey(pool.tokenBMint);
+    const ataA       = resolveAta(agentPk, mintA);
+    const ataB       = resolveAta(agentPk, mintB);
+
+    const ixs: TransactionInstruction[] = [];
+    if (pool.version < POOL_VERSION) ixs.push(migrateIx('migrate_pool', agentPk, poolPk));
+    if (pos.version < POSITION_VERSION) ixs.push(migrateIx('migrate_position', agentPk, positionPk));
+    const atas = new Map([
+      [ataA.toBase58(), createAtaIdempotentIx(agentPk, ataA, agentPk, mintA)],
+      [ataB.toBase58(), createAtaIdempotentIx(agentPk, ataB, agentPk, mintB)],
+    ]);
+    // Account order must match the on-chain ClaimFees struct exactly:
+    //   agent, pool, pool_authority, position, token_a_vault, token_b_vault,
+    //   agent_token_a, agent_token_b, token_program
+    ixs.push(new TransactionInstruction({
+      programId: new PublicKey(PROGRAM_ID),
+      keys: [
+        { pubkey: agentPk,                         isSigner: true,  isWritable: true  },
+        { pubkey: poolPk,                          isSigner: false, isWritable: true  },
+        { pubkey: resolvePoolAuthority(poolPk),    isSigner: false, isWritable: false },
+        { pubkey: positionPk,                      isSigner: false, isWritable: true  },
+        { pubkey: new PublicKey(pool.tokenAVault), isSigner: false, isWritable: true  },
+        { pubkey: new PublicKey(pool.tokenBVault), isSigner: false, isWritable: true  },
+        { pubkey: ataA,                            isSigner: false, isWritable: true  },
+        { pubkey: ataB,                            isSigner: false, isWritable: true  },
+        { pubkey: new PublicKey(TOKEN_PROGRAM),    isSigner: false, isWritable: false },
+      ],
+      data: Buffer.from(instructionData('claim_fees')),
+    }));
+
+    claims.push({
+      position: pubkey, pool: pos.pool, mintA: pool.tokenAMint, mintB: pool.tokenBMint,
+      feesA, feesB, compounds, atas, ixs,
+    });
+  }
+
+  // Pack claims into as few transactions as fit under the packet limit.
+  const txs: Transaction[] = [];
+  const txIndex: number[] = [];
+  const newTx = () => new Transaction({ recentBlockhash: blockhash, feePayer: agentPk });
+  const claimIxs = (claim: Claim, created: Set<string>) => [
+    ...[...claim.atas].filter(([ata]) => !created.has(ata)).map(([, ix]) => ix),
+    ...claim.ixs,
+  ];
+  let current = newTx();
+  let created = new Set<string>();
+  for (const claim of claims) {
+    const candidate = newTx().add(...current.instructions, ...claimIxs(claim, created));
+    if (current.instructions.length > 0 && serializedSize(candidate) > PACKET_DATA_SIZE) {
+      txs.push(current);
+      created = new Set();
+      current = newTx().add(...claimIxs(claim, created));
+    } else {
+      current = candidate;
+    }
+    for (const ata of claim.atas.keys()) created.add(ata);
+    txIndex.push(txs.length);
+  }
+  if (current.instructions.length > 0) txs.push(current);
+
+  const totals = new Map<string, bigint>();
+  for (const claim of claims.filter((cl) => !cl.compounds)) {
+    totals.set(claim.mintA, (totals.get(claim.mintA) ?? 0n) + claim.feesA);
+    totals.set(claim.mintB, (totals.get(claim.mintB) ?? 0n) + claim.feesB);
+  }
+
+  return c.json({
+    wallet,
+    transactions: txs.map((tx) => Buffer.from(
+      tx.serialize({ requireAllSignatures: false, verifySignatures: false }),
+    ).toString('base64')),
+    claims: claims.map((claim, i) => ({
+      position:    claim.position,
+      pool:        claim.pool,
+      mint_a:      claim.mintA,
+      mint_b:      claim.mintB,
+      fees_a:      claim.feesA.toString(),
+      fees_b:      claim.feesB.toString(),
+      compounds:   claim.compounds,
+      transaction: txIndex[i],
+    })),
+    totals: [...totals].filter(([, amount]) => amount > 0n).map(([mint, amount]) => ({
+      mint,
+      symbol: TOKEN_REGISTRY.find((t) => t.mint === mint)?.symbol ?? null,
+      amount: amount.toString(),
+    })),
+  });
+});
+
+export default router;
@@ -11,10 +11,11 @@ import type { AppEnv } from '../env.js';
 import { rpcUrl, getAccountData } from '../lib/rpc.js';
 import {
   parsePool, parseTokenAmount,
-  simulateDetailed, serializeSimulate, resolveMint,
+  simulateDetailed, serializeSimulate, swapReserves, resolveMint,
 } from '../lib/math.js';
-import { resolvePool } from '../lib/pda.js';
+import { resolvePool, resolveTreasury } from '../lib/pda.js';
 import { KNOWN_TOKENS } from '../lib/constants.js';
+import { apiError, invalidRequest, unknownToken } from '../lib/errors.js';
 
 const router = new Hono<AppEnv>();
 
@@ -27,19 +28,19 @@ router.get('/', async (c) => {
   const amount   = c.req.query('amount');
 
   if (!tokenIn || !tokenOut || !amount) {
-    return c.json({ error: 'tokenIn, tokenOut, and amount query params are required' }, 400);
+    return invalidRequest(c, 'tokenIn, tokenOut, and amount query params are required', ['tokenIn', 'tokenOut', 'amount']);
   }
 
   const mintIn  = resolveMint(tokenIn,  KNOWN_TOKENS);
   const mintOut = resolveMint(tokenOut, KNOWN_TOKENS);
-  if (!mintIn)  return c.json({ error: `Unknown token: ${tokenIn}`  }, 400);
-  if (!mintOut) return c.json({ error: `Unknown token: ${tokenOut}` }, 400);
+  if (!mintIn)  return unknownToken(c, tokenIn);
+  if (!mintOut) return unknownToken(c, tokenOut);
 
   let amountIn: bigint;
   try { amountIn = BigInt(amount); } catch {
-    return c.json({ error: 'amount must be an integer string' }, 400);
+    return apiError(c, 400, 'INVALID_ARGUMENT', 'amount must be an integer string');
   }
-  if (amountIn <= 0n) return c.json({ error: 'amount must be positive' }, 400);
+  if (amountIn <= 0n) return apiError(c, 400, 'INVALID_ARGUMENT', 'amount must be positive');
 
   const url = rpcUrl(c.env);
 
@@ -68,15 +69,18 @@ router.get('/', async (c) => {
     const pool = parsePool(poolData);
     const vaultInAddr  = aToB ? pool.tokenAVault : pool.tokenBVault;
     const vaultOutAddr = aToB ? pool.tokenBVault : pool.tokenAVault;
-    const [vaultInData, vaultOutData] = await Promise.all([
+    const [vaultInData, vaultOutData, configData] = await Promise.all([
       getAccountData(url, vaultInAddr),
       getAccountData(url, vaultOutAddr),
+      getAccountData(url, resolveTreasury().toBase58()),
     ]);
     if (!vaultInData || !vaultOutData) throw new Error('Vault accounts not found');
 
-    const reserveIn  = parseTokenAmount(vaultInData);
-    const reserveOut = parseTokenAmount(vaultOutData);
-    const result = simulateDetailed(poolAddr, pool, reserveIn, reserveOut, amountIn, aToB);
+    const [reserveIn, reserveOut] = swapReserves(
+      pool, aToB, parseTokenAmount(vaultInData), parseTokenAmount(vaultOutData),
+    );
+    const protocolFeeBps = parseProtocolFeeBps(configData);
+    const result = simulateDetailed(poolAddr, pool, reserveIn, reserveOut, amountIn, protocolFeeBps, aToB);
     a2aQuote = serializeSimulate(result);
   } catch (e) {
     a2aError = String(e);
@@ -7,35 +7,60 @@
  *   amount      string   — input amount in raw atomic units
  *   wallet      string   — agent's base58 public key (fee payer + signer)
  *   slippageBps number?  — allowed slippage in bps (default 50 = 0.5%)
+ *   referrer    string?  — referrer's base58 wallet; its tokenIn ATA receives
+ *                          the referral share of the protocol fee (must exist)
+ *   deadlineUnix number? — last unix time the swap may execute, enforced
+ *                          on-chain (default 0 = no deadline)
  *
  * Response JSON:
  *   transaction  string  — base64-encoded unsigned Solana Transaction
- *   simulation   object  — SimulateResult (amounts, fees, price impact)
+ *   simulation   object  — SimulateResult (amounts, fees, price impact, decimals,
+ *                          amount_in_ui / estimated_out_ui in whole tokens)
  *   pool         string  — pool address
  *   min_out      string  — minimum output enforced by the instruction
+ *   min_out_ui   string? — min_out in whole tokens (null if the mint's decimals are unknown)
  *   wrapped_sol  boolean — true if SOL wrap/unwrap instructions were embedded
  *
  * SOL is handled automatically:
  *   tokenIn=SOL  → wrap instructions prepended (createATA + transfer + syncNative)
  *   tokenOut=SOL → unwrap instruction appended (closeAccount → native SOL)
  *   The agent does NOT need a pre-funded wSOL ATA.
+ *
+ * Pools still on an older account layout get a permissionless migrate_pool
+ * instruction (agent pays the extra rent) ahead of the swap.
+ *
+ * Optional `Idempotency-Key` header (1–255 printable ASCII): the first 200
+ * response is stored per wallet + key in the IDEMPOTENCY KV namespace for
+ * IDEMPOTENCY_TTL_SECS (default 600) and replayed verbatim for repeats, so a
+ * retry after a timeout never builds a second swap. The key is echoed in the
+ * `Idempotency-Key` header and `idempotency_key` field; `Idempotent-Replayed`
+ * says whether the response came from KV. Reusing a key with a different body
+ * is 422 IDEMPOTENCY_KEY_REUSED. Errors are not stored.
  */
 
 import { Hono } from 'hono';
+import type { Context } from 'hono';
 import {
   Transaction, TransactionInstruction, PublicKey,
   type AccountMeta,
 } from '@solana/web3.js';
 import type { AppEnv } from '../env.js';
-import { rpcUrl, getAccountData, getLatestBlockhash } from '../lib/rpc.js';
+import { rpcUrl, getAccountData, getLatestBlockhash, getMintDecimals } from '../lib/rpc.js';
 import {
-  parsePool, parseTokenAmount,
-  simulateDetailed, serializeSimulate, resolveMint,
+  parsePool, parseTokenAmount, parseProtocolFeeBps,
+  simulateDetailed, serializeSimulate, swapReserves, formatUnits, resolveMint,
 } from '../lib/math.js';
 import {
-  resolvePool, resolvePoolAuthority, resolveTreasury, resolveAta, instructionDisc,
+  resolvePool, resolvePoolAuthority, resolveTreasury, resolveAta,
 } from '../lib/pda.js';
-import { KNOWN_TOKENS, PROGRAM_ID, TOKEN_PROGRAM, ATA_PROGRAM } from '../lib/constants.js';
+import { instructionData } from '../lib/idl.js';
+import {
+  KNOWN_TOKENS, PROGRAM_ID, TOKEN_PROGRAM, ATA_PROGRAM, POOL_VERSION,
+} from '../lib/constants.js';
+import { apiError, invalidRequest, unknownToken, simulateError } from '../lib/errors.js';
+import {
+  type IdempotentRecord, isValidIdempotencyKey, idempotencyKvKey, bodyFingerprint, idempotencyTtlSecs,
+} from '../lib/idempotency.js';
 
 const WSOL_MINT    = 'So11111111111111111111111111111111111111112';
 const SYSTEM_PROG  = '11111111111111111111111111111111';
@@ -114,6 +139,8 @@ interface ConvertBody {
   amount:      string;
   wallet:      string;
   slippageBps?: number;
+  referrer?:    string;
+  deadlineUnix?: number;
 }
 
 router.post('/', async (c) => {
@@ -121,30 +148,84 @@ router.post('/', async (c) => {
   try {
     body = await c.req.json() as ConvertBody;
   } catch {
-    return c.json({ error: 'Invalid JSON body' }, 400);
+    return invalidRequest(c, 'Invalid JSON body');
+  }
+
+  const key = c.req.header('Idempotency-Key');
+  if (key === undefined) return buildConvert(c, body);
+  if (!isValidIdempotencyKey(key)) {
+    return apiError(c, 400, 'INVALID_ARGUMENT', 'Idempotency-Key must be 1–255 printable ASCII characters');
   }
 
+  // Without the KV binding the key is still echoed, but nothing is replayed.
+  const kv = c.env.IDEMPOTENCY;
+  if (!kv) console.log('convert: Idempotency-Key sent but no IDEMPOTENCY KV binding');
+
+  const kvKey       = idempotencyKvKey(typeof body.wallet === 'string' ? body.wallet : '', key);
+  const fingerprint = await bodyFingerprint(body);
+  const stored      = kv ? await kv.get<IdempotentRecord>(kvKey, 'json').catch(() => null) : null;
+  if (stored) {
+    if (stored.fingerprint !== fingerprint) {
+      return apiError(c, 422, 'IDEMPOTENCY_KEY_REUSED',
+        'Idempotency-Key was already used with a different request body', { idempotency_key: key });
+    }
+    return c.json(stored.response, 200, { 'Idempotency-Key': key, 'Idempotent-Replayed': 'true' });
+  }
+
+  // Errors are not stored, so a failed attempt can be retried with the same key.
+  const res = await buildConvert(c, body);
+  if (res.status !== 200) return res;
+  const response = { ...await res.json() as Record<string, unknown>, idempotency_key: key };
+
+  if (kv) {
+    const record: IdempotentRecord = { fingerprint, response };
+    try {
+      await kv.put(kvKey, JSON.stringify(record), { expirationTtl: idempotencyTtlSecs(c.env) });
+    } catch (e) {
+      // Still return the built transaction — a failed write only loses replay.
+      console.error(`convert: failed to store Idempotency-Key ${key}:`, e);
+    }
+  }
+  return c.json(response, 200, { 'Idempotency-Key': key, 'Idempotent-Replayed': 'false' });
+});
+
+/** Validate the body and build the swap transaction (no idempotency handling). */
+async function buildConvert(c: Context<AppEnv>, body: ConvertBody): Promise<Response> {
   const { tokenIn, tokenOut, amount, wallet } = body;
   const slippageBps = body.slippageBps ?? 50;
+  const deadlineUnix = body.deadlineUnix ?? 0;
 
   if (!tokenIn || !tokenOut || !amount || !wallet) {
-    return c.json({ error: 'tokenIn, tokenOut, amount, and wallet are required' }, 400);
+    return invalidRequest(c, 'tokenIn, tokenOut, amount, and wallet are required', ['tokenIn', 'tokenOut', 'amount', 'wallet']);
   }
 
   const mintIn  = resolveMint(tokenIn,  KNOWN_TOKENS);
   const mintOut = resolveMint(tokenOut, KNOWN_TOKENS);
-  if (!mintIn)  return c.json({ error: `Unknown token: ${tokenIn}`  }, 400);
-  if (!mintOut) return c.json({ error: `Unknown token: ${tokenOut}` }, 400);
+  if (!mintIn)  return unknownToken(c, tokenIn);
+  if (!mintOut) return unknownToken(c, tokenOut);
 
   let amountIn: bigint;
   try { amountIn = BigInt(amount); } catch {
-    return c.json({ error: 'amount must be an integer string' }, 400);
+    return apiError(c, 400, 'INVALID_ARGUMENT', 'amount must be an integer string');
+  }
+  if (amountIn <= 0n) return apiError(c, 400, 'INVALID_ARGUMENT', 'amount must be positive');
+  if (!Number.isInteger(deadlineUnix) || deadlineUnix < 0) {
+    return apiError(c, 400, 'INVALID_ARGUMENT', 'deadlineUnix must be a non-negative integer');
+  }
+  if (deadlineUnix !== 0 && deadlineUnix < Math.floor(Date.now() / 1000)) {
+    return apiError(c, 400, 'INVALID_ARGUMENT', 'deadlineUnix has already passed');
   }
-  if (amountIn <= 0n) return c.json({ error: 'amount must be positive' }, 400);
 
   let agentPk: PublicKey;
   try { agentPk = new PublicKey(wallet); } catch {
-    return c.json({ error: 'Invalid wallet public key' }, 400);
+    return apiError(c, 400, 'INVALID_ARGUMENT', 'Invalid wallet public key');
+  }
+
+  let referrerPk: PublicKey | null = null;
+  if (body.referrer) {
+    try { referrerPk = new PublicKey(body.referrer); } catch {
+      return apiError(c, 400, 'INVALID_ARGUMENT', 'Invalid referrer public key');
+    }
   }
 
   const url = rpcUrl(c.env);
@@ -163,56 +244,58 @@ router.post('/', async (c) => {
     poolAddr = pAB.addr; poolData = pAB.data; aToB = true;
   } else {
     const pBA = await tryPool(mintOut, mintIn);
-    if (!pBA) return c.json({ error: `No pool found for ${tokenIn}/${tokenOut}` }, 404);
+    if (!pBA) return apiError(c, 404, 'POOL_NOT_FOUND', `No pool found for ${tokenIn}/${tokenOut}`);
     poolAddr = pBA.addr; poolData = pBA.data; aToB = false;
   }
 
   let pool;
   try { pool = parsePool(poolData); } catch (e) {
-    return c.json({ error: `Pool parse error: ${e}` }, 502);
+    return apiError(c, 502, 'ACCOUNT_PARSE_ERROR', `Pool parse error: ${e}`);
   }
 
   const vaultInAddr  = aToB ? pool.tokenAVault : pool.tokenBVault;
   const vaultOutAddr = aToB ? pool.tokenBVault : pool.tokenAVault;
 
-  const [vaultInData, vaultOutData, blockhash] = await Promise.all([
+  const [vaultInData, vaultOutData, configData, blockhash, decimalsIn, decimalsOut] = await Promise.all([
     getAccountData(url, vaultInAddr),
     getAccountData(url, vaultOutAddr),
+    getAccountData(url, resolveTreasury().toBase58()),
     getLatestBlockhash(url),
+    getMintDecimals(url, mintIn).catch(() => null),
+    getMintDecimals(url, mintOut).catch(() => null),
   ]);
 
   if (!vaultInData || !vaultOutData) {
-    return c.json({ error: 'Vault account(s) not found' }, 502);
+    return apiError(c, 502, 'RPC_ERROR', 'Vault account(s) not found');
   }
 
-  let reserveIn: bigint, reserveOut: bigint;
+  let reserveIn: bigint, reserveOut: bigint, protocolFeeBps: bigint;
   try {
-    reserveIn  = parseTokenAmount(vaultInData);
-    reserveOut = parseTokenAmount(vaultOutData);
+    [reserveIn, reserveOut] = swapReserves(
+      pool, aToB, parseTokenAmount(vaultInData), parseTokenAmount(vaultOutData),
+    );
+    protocolFeeBps = parseProtocolFeeBps(configData);
   } catch (e) {
-    return c.json({ error: `Vault parse error: ${e}` }, 502);
+    return apiError(c, 502, 'ACCOUNT_PARSE_ERROR', `Account parse error: ${e}`);
   }
 
   let simulation;
   try {
-    simulation = simulateDetailed(poolAddr, pool, reserveIn, reserveOut, amountIn, aToB);
+    simulation = simulateDetailed(poolAddr, pool, reserveIn, reserveOut, amountIn, protocolFeeBps, aToB);
   } catch (e) {
-    return c.json({ error: String(e) }, 400);
+    return simulateError(c, e);
   }
 
   // Apply slippage: minOut = estimatedOut * (10000 - slippageBps) / 10000
   const minAmountOut = (simulation.estimatedOut * BigInt(10_000 - slippageBps)) / 10_000n;
 
   // ── Build swap instruction ──────────────────────────────────────────────────
-  // Instruction data: disc(8) + amount_in(8 LE) + min_amount_out(8 LE) + a_to_b(1) = 25 bytes
-  // Parameter order must match the Anchor handler signature:
-  //   handler(ctx, amount_in: u64, min_amount_out: u64, a_to_b: bool)
-  const disc = await instructionDisc('swap');
-  const data = new Uint8Array(25);
-  data.set(disc, 0);
-  writeU64LE(data, 8,  amountIn);
-  writeU64LE(data, 16, minAmountOut);
-  data[24] = aToB ? 1 : 0;
+  // Arguments are encoded in the order the IDL lists them for `swap`;
+  // max_price_impact_bps 0 = no cap, deadline_unix 0 = no deadline.
+  const data = instructionData('swap', {
+    amount_in: amountIn, min_amount_out: minAmountOut, a_to_b: aToB, max_price_impact_bps: 0,
+    deadline_unix: BigInt(deadlineUnix),
+  });
 
   const poolPk      = new PublicKey(poolAddr);
   const poolAuth    = resolvePoolAuthority(poolPk);
@@ -225,7 +308,8 @@ router.post('/', async (c) => {
   const agentOutAta   = resolveAta(agentPk, mintOutPk);
   const treasuryInAta = resolveAta(treasury, mintInPk);
 
-  // Account order must match the on-chain Swap struct exactly (10 accounts):
+  // Account order must match the on-chain Swap struct exactly (10 accounts,
+  // plus an optional trailing referrer_token):
   //   agent, pool, pool_authority, token_a_vault, token_b_vault,
   //   agent_token_in, agent_token_out, treasury, treasury_token_in, token_program
   const keys: AccountMeta[] = [
@@ -240,6 +324,9 @@ router.post('/', async (c) => {
     { pubkey: treasuryInAta,                    isSigner: false, isWritable: true  },
     { pubkey: new PublicKey(TOKEN_PROGRAM),     isSigner: false, isWritable: false },
   ];
+  if (referrerPk) {
+    keys.push({ pubkey: resolveAta(referrerPk, mintInPk), isSigner: false, isWritable: true });
+  }
 
   const swapIx = new TransactionInstruction({
     programId: new PublicKey(PROGRAM_ID),
@@ -255,6 +342,19 @@ router.post('/', async (c) => {
   const wsolMintPk = new PublicKey(WSOL_MINT);
   let wrappedSol = false;
 
+  // Older pool layouts must be migrated before the program can load them.
+  if (pool.version < POOL_VERSION) {
+    tx.add(new TransactionInstruction({
+      programId: new PublicKey(PROGRAM_ID),
+      keys: [
+        { pubkey: agentPk,                    isSigner: true,  isWritable: true  },
+        { pubkey: poolPk,                     isSigner: false, isWritable: true  },
+        { pubkey: new PublicKey(SYSTEM_PROG), isSigner: false, isWritable: false },
+      ],
+      data: Buffer.from(instructionData('migrate_pool')),
+    }));
+  }
+
   // If tokenIn is SOL: create wSOL ATA (idempotent), wrap input lamports, sync.
   if (mintIn === WSOL_MINT) {
     tx.add(createAtaIdempotentIx(agentPk, agentInAta, agentPk, wsolMintPk));
@@ -280,11 +380,12 @@ router.post('/', async (c) => {
 
   return c.json({
     transaction: txBase64,
-    simulation:  serializeSimulate(simulation),
+    simulation:  serializeSimulate(simulation, { in: decimalsIn, out: decimalsOut }),
     pool:        poolAddr,
     min_out:     minAmountOut.toString(),
+    min_out_ui:  decimalsOut === null ? null : formatUnits(minAmountOut, decimalsOut),
     wrapped_sol: wrappedSol,
   });
-});
+}
 
 export default router;
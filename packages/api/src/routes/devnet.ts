@@ -0,0 +1,156 @@
+/**
+ * POST /devnet/bootstrap — one call to get an agent trading on devnet.
+ *
+ * Only served when SOLANA_NETWORK is "devnet" (`wrangler deploy --env devnet`);
+ * every other deployment answers 403 NOT_AVAILABLE.
+ *
+ * Request body (JSON):
+ *   agent     string  — the agent's wallet (base58)
+ *   lamports  number? — airdrop size (default 1 SOL, at most 2 SOL; 0 skips it)
+ *
+ * Response JSON:
+ *   network       "devnet"
+ *   program       string    — the a2a-swap program id
+ *   agent         string
+ *   airdrop       object    — { signature, lamports }, { error, lamports } when
+ *                             the faucet refuses, or { skipped: true }
+ *   pools         object[]  — each DEVNET_TEST_POOLS pool: pool, token_a_mint,
+ *                             token_b_mint, reserve_a, reserve_b, lp_supply,
+ *                             fee_rate_bps
+ *   atas          object[]  — { mint, ata, exists } for every test-pool mint
+ *   instructions  object[]  — unsigned createAssociatedTokenAccountIdempotent
+ *                             instructions ({ programId, accounts, data }) for
+ *                             the ATAs the agent is missing
+ *
+ * The worker holds no keys: the agent signs and pays for the ATA
+ * instructions itself, from the airdrop.
+ */
+
+import { Hono } from 'hono';
+import { PublicKey } from '@solana/web3.js';
+import type { AppEnv } from '../env.js';
+import {
+  rpcUrl, solanaNetwork, requestAirdrop, getAccountData, getMultipleAccountsData,
+} from '../lib/rpc.js';
+import { parsePool, parseTokenAmount, poolReserves } from '../lib/math.js';
+import { resolveAta } from '../lib/pda.js';
+import { PROGRAM_ID, TOKEN_PROGRAM, ATA_PROGRAM } from '../lib/constants.js';
+import { apiError, invalidRequest } from '../lib/errors.js';
+
+const SYSTEM_PROG = '11111111111111111111111111111111';
+
+const AIRDROP_DEFAULT_LAMPORTS = 1_000_000_000;
+const AIRDROP_MAX_LAMPORTS     = 2_000_000_000;
+
+const router = new Hono<AppEnv>();
+
+router.post('/bootstrap', async (c) => {
+  if (solanaNetwork(c.env) !== 'devnet') {
+    return apiError(c, 403, 'NOT_AVAILABLE', 'devnet bootstrap is only available on the devnet deployment');
+  }
+
+  let body: { agent?: unknown; lamports?: unknown };
+  try {
+    body = await c.req.json() as typeof body;
+  } catch {
+    return invalidRequest(c, 'Invalid JSON body');
+  }
+
+  const agent = typeof body.agent === 'string' ? body.agent : '';
+  try {
+    new PublicKey(agent);
+  } catch {
+    return invalidRequest(c, 'agent (base58 public key) is required', ['agent']);
+  }
+
+  const lamports = body.lamports === undefined ? AIRDROP_DEFAULT_LAMPORTS : Number(body.lamports);
+  if (!Number.isSafeInteger(lamports) || lamports < 0 || lamports > AIRDROP_MAX_LAMPORTS) {
+    return apiError(c, 400, 'INVALID_ARGUMENT', `lamports must be an integer from 0 to ${AIRDROP_MAX_LAMPORTS}`);
+  }
+
+  const url = rpcUrl(c.env);
+
+  // ── 1. Airdrop (faucet failures are reported, not fatal) ────────────────────
+  let airdrop: Record<string, unknown>;
+  if (lamports === 0) {
+    airdrop = { skipped: true };
+  } else {
+    try {
+      airdrop = { signature: await requestAirdrop(url, agent, lamports), lamports };
+    } catch (e) {
+      airdrop = { error: e instanceof Error ? e.message : String(e), lamports };
+    }
+  }
+
+  // ── 2. Seeded test pools ────────────────────────────────────────────────────
+  const poolAddrs = (c.env.DEVNET_TEST_POOLS ?? '').split(',').map(p => p.trim()).filter(Boolean);
+  const pools: Array<Record<string, unknown>> = [];
+  const mints: string[] = [];
+  for (const poolAddr of poolAddrs) {
+    let data: Uint8Array | null;
+    try {
+      data = await getAccountData(url, poolAddr);
+    } catch (e) {
+      return apiError(c, 502, 'RPC_ERROR', String(e));
+    }
+    if (!data) return apiError(c, 500, 'INTERNAL_ERROR', `Test pool not found: ${poolAddr}`);
+
+    let reserveA: bigint, reserveB: bigint, pool;
+    try {
+      pool = parsePool(data);
+      const [vaultA, vaultB] = await getMultipleAccountsData(url, [pool.tokenAVault, pool.tokenBVault]);
+      if (!vaultA || !vaultB) return apiError(c, 502, 'RPC_ERROR', `Vault account(s) not found for ${poolAddr}`);
+      [reserveA, reserveB] = poolReserves(pool, parseTokenAmount(vaultA), parseTokenAmount(vaultB));
+    } catch (e) {
+      return apiError(c, 502, 'ACCOUNT_PARSE_ERROR', `Test pool ${poolAddr}: ${e}`);
+    }
+
+    for (const m of [pool.tokenAMint, pool.tokenBMint]) {
+      if (!mints.includes(m)) mints.push(m);
+    }
+    pools.push({
+      pool:         poolAddr,
+      token_a_mint: pool.tokenAMint,
+      token_b_mint: pool.tokenBMint,
+      reserve_a:    reserveA.toString(),
+      reserve_b:    reserveB.toString(),
+      lp_supply:    pool.lpSupply.toString(),
+      fee_rate_bps: pool.feeRateBps,
+    });
+  }
+
+  // ── 3. Test-token ATAs the agent still needs ────────────────────────────────
+  const ataAddrs = mints.map(m => resolveAta(agent, m).toBase58());
+  let ataData: Array<Uint8Array | null>;
+  try {
+    ataData = await getMultipleAccountsData(url, ataAddrs);
+  } catch (e) {
+    return apiError(c, 502, 'RPC_ERROR', String(e));
+  }
+
+  const atas = mints.map((mint, i) => ({ mint, ata: ataAddrs[i], exists: ataData[i] !== null }));
+  const instructions = atas.filter(a => !a.exists).map(a => ({
+    programId: ATA_PROGRAM,
+    accounts: [
+      { pubkey: agent,         isSigner: true,  isWritable: true  },
+      { pubkey: a.ata,         isSigner: false, isWritable: true  },
+      { pubkey: agent,         isSigner: false, isWritable: false },
+      { pubkey: a.mint,        isSigner: false, isWritable: false },
+      { pubkey: SYSTEM_PROG,   isSigner: false, isWritable: false },
+      { pubkey: TOKEN_PROGRAM, isSigner: false, isWritable: false },
+    ],
+    data: 'AQ==',  // [1] = CreateIdempotent
+  }));
+
+  return c.json({
+    network: 'devnet',
+    program: PROGRAM_ID,
+    agent,
+    airdrop,
+    pools,
+    atas,
+    instructions,
+  });
+});
+
+export default router;
@@ -0,0 +1,106 @@
+/**
+ * GET /health — dependency checks, not just liveness.
+ *
+ * Checks, run concurrently with a 5 s timeout each:
+ *   rpc          — getSlot on the configured RPC
+ *   program      — the program account exists
+ *   canary_pool  — only when HEALTH_CANARY_POOL is set: the pool parses and
+ *                  has non-zero reserves
+ *
+ * Each check reports `status` (ok | degraded | down), `latency_ms` and, when
+ * not ok, `error`. A check slower than HEALTH_SLOW_MS (default 2000) is
+ * degraded. The overall status is the worst of rpc and program, and at most
+ * degraded from the canary. HTTP 200 for ok and degraded, 503 for down.
+ */
+
+import { Hono } from 'hono';
+import type { AppEnv } from '../env.js';
+import { rpcUrl, getSlot, getAccountData, getMultipleAccountsData } from '../lib/rpc.js';
+import { parsePool, parseTokenAmount, poolReserves } from '../lib/math.js';
+import { PROGRAM_ID, VERSION } from '../lib/constants.js';
+
+type Status = 'ok' | 'degraded' | 'down';
+
+interface Check {
+  status:     Status;
+  latency_ms: number;
+  error?:     string;
+  [detail: string]: unknown;
+}
+
+const CHECK_TIMEOUT_MS = 5_000;
+const DEFAULT_SLOW_MS  = 2_000;
+const RANK: Record<Status, number> = { ok: 0, degraded: 1, down: 2 };
+
+/**
+ * Run `probe` against the timeout. A thrown error is `down`; a returned
+ * `error` string is `degraded`; anything slower than `slowMs` is degraded.
+ */
+async function check(
+  slowMs: number,
+  probe:  () => Promise<{ error?: string; [detail: string]: unknown }>,
+): Promise<Check> {
+  const start = Date.now();
+  let timer: ReturnType<typeof setTimeout> | undefined;
+  try {
+    const detail = await Promise.race([
+      probe(),
+      new Promise<never>((_, reject) => {
+        timer = setTimeout(() => reject(new Error(`timed out after ${CHECK_TIMEOUT_MS} ms`)), CHECK_TIMEOUT_MS);
+      }),
+    ]);
+    const latency_ms = Date.now() - start;
+    if (detail.error) return { ...detail, status: 'degraded', latency_ms };
+    if (latency_ms > slowMs) return { ...detail, status: 'degraded', latency_ms, error: `slower than ${slowMs} ms` };
+    return { ...detail, status: 'ok', latency_ms };
+  } catch (e) {
+    return { status: 'down', latency_ms: Date.now() - start, error: e instanceof Error ? e.message : String(e) };
+  } finally {
+    clearTimeout(timer);
+  }
+}
+
+const router = new Hono<AppEnv>();
+
+router.get('/', async (c) => {
+  const url    = rpcUrl(c.env);
+  const slow   = Number(c.env.HEALTH_SLOW_MS ?? DEFAULT_SLOW_MS);
+  const slowMs = Number.isFinite(slow) && slow > 0 ? slow : DEFAULT_SLOW_MS;
+  const canary = c.env.HEALTH_CANARY_POOL;
+
+  const [rpc, program, canaryPool] = await Promise.all([
+    check(slowMs, async () => ({ slot: await getSlot(url) })),
+    check(slowMs, async () => {
+      if (!await getAccountData(url, PROGRAM_ID)) throw new Error(`program account ${PROGRAM_ID} not found`);
+      return { address: PROGRAM_ID };
+    }),
+    canary ? check(slowMs, async () => {
+      const data = await getAccountData(url, canary);
+      if (!data) throw new Error(`pool ${canary} not found`);
+      const pool = parsePool(data);
+      const [vaultA, vaultB] = await getMultipleAccountsData(url, [pool.tokenAVault, pool.tokenBVault]);
+      const [reserveA, reserveB] = poolReserves(
+        pool,
+        vaultA ? parseTokenAmount(vaultA) : 0n,
+        vaultB ? parseTokenAmount(vaultB) : 0n,
+      );
+      return {
+        pool:      canary,
+        reserve_a: reserveA.toString(),
+        reserve_b: reserveB.toString(),
+        ...(reserveA === 0n || reserveB === 0n ? { error: 'pool has an empty reserve' } : {}),
+      };
+    }) : undefined,
+  ]);
+
+  const checks: Record<string, Check> = { rpc, program };
+  let worst: Status = RANK[rpc.status] >= RANK[program.status] ? rpc.status : program.status;
+  if (canaryPool) {
+    checks.canary_pool = canaryPool;
+    if (canaryPool.status !== 'ok' && worst === 'ok') worst = 'degraded';
+  }
+
+  return c.json({ status: worst, version: VERSION, checks }, worst === 'down' ? 503 : 200);
+});
+
+export default router;
@@ -0,0 +1,113 @@
+/**
+ * Intent mempool — agents post signed swap intents, relayers pick them up.
+ *
+ * POST /intents          publish a SignedIntent (SDK `sign_intent` JSON); returns it with its `id`
+ * GET  /intents/pending  intents still executable: ?pool=, ?agent=, ?limit= (default 100, max 500)
+ *
+ * The Worker checks the agent's signature, that `expires_at` is in the future
+ * (at most an hour out) and that `nonce` is the agent's current IntentNonce,
+ * then stores the intent in the IntentPool Durable Object. It never submits
+ * anything: relayers POST what they fetch to their own relayer
+ * (`a2a-swap-relayer`, or `A2ASwapClient::relay_intent`). Each intent in
+ * /intents/pending is the SDK's SignedIntent JSON, plus `id` and `received_at`.
+ *
+ * An intent drops out once it expires or its agent's nonce moves on —
+ * because it, or another intent with the same nonce, was executed.
+ */
+
+import { Hono } from 'hono';
+import type { Context } from 'hono';
+import type { AppEnv } from '../env.js';
+import { rpcUrl, getAccountData, getMultipleAccountsData } from '../lib/rpc.js';
+import { resolveIntentNonce } from '../lib/pda.js';
+import {
+  type StoredIntent, MAX_INTENT_TTL_SECS, intentJson, parseIntent, parseIntentNonce, parseJsonExact, verifyIntent,
+} from '../lib/intents.js';
+import { apiError, invalidRequest } from '../lib/errors.js';
+
+const DEFAULT_LIMIT = 100;
+const MAX_LIMIT     = 500;
+
+const router = new Hono<AppEnv>();
+
+/** All intents live in one IntentPool instance. */
+function mempool(c: Context<AppEnv>): DurableObjectStub | null {
+  const ns = c.env.INTENTS;
+  return ns ? ns.get(ns.idFromName('global')) : null;
+}
+
+router.post('/', async (c) => {
+  const stub = mempool(c);
+  if (!stub) return apiError(c, 503, 'NOT_AVAILABLE', 'The intent mempool is not enabled on this deployment');
+
+  let body: unknown;
+  try {
+    body = parseJsonExact(await c.req.text());
+  } catch {
+    return invalidRequest(c, 'Invalid JSON body');
+  }
+
+  const now    = Math.floor(Date.now() / 1000);
+  const intent = parseIntent(body, Date.now());
+  if (typeof intent === 'string') return apiError(c, 400, 'INVALID_ARGUMENT', intent);
+  if (intent.expires_at <= now) return apiError(c, 400, 'INVALID_ARGUMENT', 'Intent has expired');
+  if (intent.expires_at > now + MAX_INTENT_TTL_SECS) {
+    return apiError(c, 400, 'INVALID_ARGUMENT', `expires_at must be within ${MAX_INTENT_TTL_SECS} seconds`);
+  }
+  if (!await verifyIntent(intent)) return apiError(c, 400, 'INVALID_ARGUMENT', 'Signature does not match the intent');
+
+  let nonce: bigint;
+  try {
+    nonce = parseIntentNonce(await getAccountData(rpcUrl(c.env), resolveIntentNonce(intent.agent).toBase58()));
+  } catch (e) {
+    return apiError(c, 502, 'RPC_ERROR', `IntentNonce read failed: ${e}`);
+  }
+  if (nonce.toString() !== intent.nonce) {
+    return apiError(c, 409, 'INVALID_ARGUMENT', `Stale nonce: the agent's next intent must carry nonce ${nonce}`, { expected_nonce: String(nonce) });
+  }
+
+  const res = await stub.fetch('https://intents/intents', { method: 'POST', body: JSON.stringify(intent) });
+  if (res.status === 409 || res.status === 429) return c.json(await res.json() as object, res.status as 409 | 429);
+  if (!res.ok) return apiError(c, 502, 'INTERNAL_ERROR', `Intent store error: HTTP ${res.status}`);
+  return c.body(intentJson(intent), 201, { 'Content-Type': 'application/json' });
+});
+
+router.get('/pending', async (c) => {
+  const stub = mempool(c);
+  if (!stub) return apiError(c, 503, 'NOT_AVAILABLE', 'The intent mempool is not enabled on this deployment');
+
+  const limit = Number(c.req.query('limit') ?? DEFAULT_LIMIT);
+  if (!Number.isInteger(limit) || limit < 1 || limit > MAX_LIMIT) {
+    return apiError(c, 400, 'INVALID_ARGUMENT', `limit must be an integer from 1 to ${MAX_LIMIT}`);
+  }
+  const query = new URLSearchParams();
+  for (const name of ['pool', 'agent']) {
+    const value = c.req.query(name);
+    if (value) query.set(name, value);
+  }
+
+  const res = await stub.fetch(`https://intents/intents?${query}`);
+  if (!res.ok) return apiError(c, 502, 'INTERNAL_ERROR', `Intent store error: HTTP ${res.status}`);
+  const candidates = (await res.json() as StoredIntent[]).slice(0, limit);
+
+  // Drop intents whose agent has moved past their nonce: executed or superseded.
+  const agents = [...new Set(candidates.map(i => i.agent))];
+  let nonces: Map<string, bigint>;
+  try {
+    const data = await getMultipleAccountsData(rpcUrl(c.env), agents.map(a => resolveIntentNonce(a).toBase58()));
+    nonces = new Map(agents.map((a, i) => [a, parseIntentNonce(data[i])]));
+  } catch (e) {
+    return apiError(c, 502, 'RPC_ERROR', `IntentNonce read failed: ${e}`);
+  }
+  const pending = candidates.filter(i => nonces.get(i.agent)?.toString() === i.nonce);
+  const stale   = candidates.filter(i => nonces.get(i.agent)?.toString() !== i.nonce);
+  if (stale.length > 0) {
+    c.executionCtx.waitUntil(stub.fetch('https://intents/intents/drop', { method: 'POST', body: JSON.stringify(stale) }));
+  }
+
+  return c.body(`{"count":${pending.length},"intents":[${pending.map(intentJson).join(',')}]}`, 200, {
+    'Content-Type': 'application/json',
+  });
+});
+
+export default router;
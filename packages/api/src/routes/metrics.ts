@@ -0,0 +1,38 @@
+/**
+ * GET /metrics — per-route summary accumulated by the serving isolate.
+ *
+ * Workers run many isolates, each with its own counters, so this is a spot
+ * check of one instance — query the METRICS Analytics Engine dataset or the
+ * OTLP backend (middleware/metrics.ts) for fleet-wide error rates.
+ *
+ * Response JSON:
+ *   scope          "isolate"
+ *   isolate_since  number?  — unix ms of the first request this isolate counted
+ *   network        string   — SOLANA_NETWORK
+ *   exporters      object   — { analytics_engine, otlp }: which sinks are configured
+ *   routes         object   — per route: requests, errors_4xx, errors_5xx,
+ *                             error_rate, latency_ms_avg, latency_ms_max, rpc_calls
+ */
+
+import { Hono } from 'hono';
+import type { AppEnv } from '../env.js';
+import { solanaNetwork } from '../lib/rpc.js';
+import { isolateSummary } from '../lib/metrics.js';
+
+const router = new Hono<AppEnv>();
+
+router.get('/', (c) => {
+  const { isolate_since, routes } = isolateSummary();
+  return c.json({
+    scope:   'isolate',
+    isolate_since,
+    network: solanaNetwork(c.env),
+    exporters: {
+      analytics_engine: c.env.METRICS !== undefined,
+      otlp:             Boolean(c.env.OTLP_ENDPOINT),
+    },
+    routes,
+  });
+});
+
+export default router;
@@ -0,0 +1,126 @@
+/**
+ * GET|POST /pay/convert — Solana Pay transaction request for a swap.
+ *
+ * The link an agent hands to a human (`solana:` + this URL, percent-encoded;
+ * see the SDKs' `solana_pay_link`) carries the swap in its query string:
+ *   tokenIn      string   — token symbol or base58 mint
+ *   tokenOut     string   — token symbol or base58 mint
+ *   amount       string   — input amount in raw atomic units
+ *   slippageBps  number?  — allowed slippage in bps (default 50 = 0.5%)
+ *   deadlineUnix number?  — last unix time the swap may execute (default 0 = none)
+ *   referrer     string?  — referrer's base58 wallet
+ *
+ * GET  → { label, icon } for the wallet to show before it asks to sign.
+ * POST { account } → { transaction, message }: the /convert transaction for
+ *   the wallet's account, unsigned and base64-encoded, quoted now. Errors
+ *   answer with the /convert status and `{ message }` for the wallet to show.
+ *
+ * Free: the wallet signs and pays the network fee itself and cannot attach
+ * an x402 payment. GET /pay/icon.svg serves the icon.
+ */
+
+import { Hono } from 'hono';
+import type { AppEnv } from '../env.js';
+import convertRouter from './convert.js';
+
+const LABEL = 'A2A-Swap';
+
+const ICON_SVG =
+  '<svg xmlns="http://www.w3.org/2000/svg" viewBox="0 0 64 64">' +
+  '<rect width="64" height="64" rx="14" fill="#14151a"/>' +
+  '<path d="M18 26h24l-6-6M46 38H22l6 6" fill="none" stroke="#14f195" ' +
+  'stroke-width="5" stroke-linecap="round" stroke-linejoin="round"/></svg>';
+
+/** A wallet-signed swap as the links carry it; `amount` in atomic units. */
+export interface WalletSwapQuery {
+  tokenIn?:      string;
+  tokenOut?:     string;
+  amount?:       string;
+  slippageBps?:  string;
+  deadlineUnix?: string;
+  referrer?:     string;
+}
+
+export type WalletSwap =
+  | { ok: true;  transaction: string; message: string }
+  | { ok: false; status: 400 | 404 | 500 | 502; message: string };
+
+/**
+ * Build the /convert transaction for `account` to sign, with a one-line
+ * description for the wallet. Shared with /actions/swap.
+ */
+export async function buildWalletSwap(
+  env:     AppEnv['Bindings'],
+  q:       WalletSwapQuery,
+  account: string,
+): Promise<WalletSwap> {
+  const body = {
+    tokenIn:      q.tokenIn,
+    tokenOut:     q.tokenOut,
+    amount:       q.amount,
+    wallet:       account,
+    slippageBps:  q.slippageBps  === undefined ? undefined : Number(q.slippageBps),
+    deadlineUnix: q.deadlineUnix === undefined ? undefined : Number(q.deadlineUnix),
+    referrer:     q.referrer,
+  };
+
+  // Build through the /convert route itself, so validation and the
+  // transaction match it exactly.
+  const res = await convertRouter.request('/', {
+    method:  'POST',
+    headers: { 'Content-Type': 'application/json' },
+    body:    JSON.stringify(body),
+  }, env);
+  const built = await res.json() as {
+    error?:       { message?: string };
+    transaction?: string;
+    simulation?:  { amount_in_ui?: string | null };
+    min_out?:     string;
+    min_out_ui?:  string | null;
+  };
+  if (!res.ok || !built.transaction) {
+    return {
+      ok:      false,
+      status:  res.ok ? 502 : res.status as 400 | 404 | 500 | 502,
+      message: built.error?.message ?? 'Could not build the swap',
+    };
+  }
+
+  const amountIn = built.simulation?.amount_in_ui ?? q.amount;
+  const minOut   = built.min_out_ui ?? built.min_out;
+  return {
+    ok:          true,
+    transaction: built.transaction,
+    message:     `Swap ${amountIn} ${q.tokenIn} for at least ${minOut} ${q.tokenOut}`,
+  };
+}
+
+const router = new Hono<AppEnv>();
+
+router.get('/icon.svg', (c) => c.body(ICON_SVG, 200, {
+  'Content-Type':  'image/svg+xml',
+  'Cache-Control': 'public, max-age=86400',
+}));
+
+router.get('/convert', (c) => c.json({
+  label: LABEL,
+  icon:  `${c.env.API_URL}/pay/icon.svg`,
+}));
+
+router.post('/convert', async (c) => {
+  let account: unknown;
+  try {
+    ({ account } = await c.req.json() as { account?: unknown });
+  } catch {
+    return c.json({ message: 'Invalid JSON body' }, 400);
+  }
+  if (typeof account !== 'string' || !account) {
+    return c.json({ message: 'account is required' }, 400);
+  }
+
+  const swap = await buildWalletSwap(c.env, c.req.query(), account);
+  if (!swap.ok) return c.json({ message: swap.message }, swap.status);
+  return c.json({ transaction: swap.transaction, message: swap.message });
+});
+
+export default router;
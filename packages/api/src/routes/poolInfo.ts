@@ -7,15 +7,20 @@
  *
  * Response JSON:
  *   pool, token_a_mint, token_b_mint, token_a_vault, token_b_vault,
- *   reserve_a, reserve_b, lp_supply, fee_rate_bps
+ *   reserve_a, reserve_b, lp_supply, fee_rate_bps, protocol_fee_bps,
+ *   volume_a, volume_b, fees_collected_a, fees_collected_b (lifetime, atomic units),
+ *   version (account layout; below the current one means migrate_pool is due)
  */
 
 import { Hono } from 'hono';
 import type { AppEnv } from '../env.js';
 import { rpcUrl, getAccountData } from '../lib/rpc.js';
-import { parsePool, parseTokenAmount, resolveMint } from '../lib/math.js';
-import { resolvePool } from '../lib/pda.js';
+import {
+  parsePool, parseTokenAmount, parseProtocolFeeBps, poolReserves, resolveMint,
+} from '../lib/math.js';
+import { resolvePool, resolveTreasury } from '../lib/pda.js';
 import { KNOWN_TOKENS } from '../lib/constants.js';
+import { apiError, invalidRequest, unknownToken } from '../lib/errors.js';
 
 const router = new Hono<AppEnv>();
 
@@ -31,13 +36,13 @@ router.get('/', async (c) => {
     const tokenA = c.req.query('tokenA');
     const tokenB = c.req.query('tokenB');
     if (!tokenA || !tokenB) {
-      return c.json({ error: 'Provide ?pool=<address> or ?tokenA=...&tokenB=...' }, 400);
+      return invalidRequest(c, 'Provide ?pool=<address> or ?tokenA=...&tokenB=...', ['pool', 'tokenA', 'tokenB']);
     }
 
     const mintA = resolveMint(tokenA, KNOWN_TOKENS);
     const mintB = resolveMint(tokenB, KNOWN_TOKENS);
-    if (!mintA) return c.json({ error: `Unknown token: ${tokenA}` }, 400);
-    if (!mintB) return c.json({ error: `Unknown token: ${tokenB}` }, 400);
+    if (!mintA) return unknownToken(c, tokenA);
+    if (!mintB) return unknownToken(c, tokenB);
 
     // Try both orderings.
     const addrAB = resolvePool(mintA, mintB).toBase58();
@@ -47,34 +52,37 @@ router.get('/', async (c) => {
     } else {
       const addrBA = resolvePool(mintB, mintA).toBase58();
       const dataBA = await getAccountData(url, addrBA);
-      if (!dataBA) return c.json({ error: `No pool found for ${tokenA}/${tokenB}` }, 404);
+      if (!dataBA) return apiError(c, 404, 'POOL_NOT_FOUND', `No pool found for ${tokenA}/${tokenB}`);
       poolAddr = addrBA;
     }
   }
 
   const poolData = await getAccountData(url, poolAddr);
-  if (!poolData) return c.json({ error: `Pool not found: ${poolAddr}` }, 404);
+  if (!poolData) return apiError(c, 404, 'POOL_NOT_FOUND', `Pool not found: ${poolAddr}`);
 
   let pool;
   try { pool = parsePool(poolData); } catch (e) {
-    return c.json({ error: `Pool parse error: ${e}` }, 502);
+    return apiError(c, 502, 'ACCOUNT_PARSE_ERROR', `Pool parse error: ${e}`);
   }
 
-  const [vaultAData, vaultBData] = await Promise.all([
+  const [vaultAData, vaultBData, configData] = await Promise.all([
     getAccountData(url, pool.tokenAVault),
     getAccountData(url, pool.tokenBVault),
+    getAccountData(url, resolveTreasury().toBase58()),
   ]);
 
   if (!vaultAData || !vaultBData) {
-    return c.json({ error: 'Vault account(s) not found' }, 502);
+    return apiError(c, 502, 'RPC_ERROR', 'Vault account(s) not found');
   }
 
-  let reserveA: bigint, reserveB: bigint;
+  let reserveA: bigint, reserveB: bigint, protocolFeeBps: bigint;
   try {
-    reserveA = parseTokenAmount(vaultAData);
-    reserveB = parseTokenAmount(vaultBData);
+    [reserveA, reserveB] = poolReserves(
+      pool, parseTokenAmount(vaultAData), parseTokenAmount(vaultBData),
+    );
+    protocolFeeBps = parseProtocolFeeBps(configData);
   } catch (e) {
-    return c.json({ error: `Vault parse error: ${e}` }, 502);
+    return apiError(c, 502, 'ACCOUNT_PARSE_ERROR', `Account parse error: ${e}`);
   }
 
   return c.json({
@@ -87,6 +95,14 @@ router.get('/', async (c) => {
     reserve_b:      reserveB.toString(),
     lp_supply:      pool.lpSupply.toString(),
     fee_rate_bps:   pool.feeRateBps,
+    protocol_fee_bps: Number(protocolFeeBps),
+    volume_a:         pool.volumeA.toString(),
+    volume_b:         pool.volumeB.toString(),
+    fees_collected_a: pool.feesCollectedA.toString(),
+    fees_collected_b: pool.feesCollectedB.toString(),
+    version:          pool.version,
+    permissioned:     pool.permissioned,
+    gate_mint:        pool.gateMint,
   });
 });
 
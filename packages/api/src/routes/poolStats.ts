@@ -0,0 +1,112 @@
+/**
+ * Pool state from the scheduled snapshots (src/lib/poolSnapshot.ts) — none of
+ * these read the RPC, and all answer 503 without the POOL_SNAPSHOTS binding.
+ *
+ * GET /pool-stats[?pool=<address>]
+ *   The latest snapshot: reserves, price (reserve_b / reserve_a), lifetime
+ *   volume and fees, and `change_24h` per pool, plus `age_ms`.
+ *
+ * GET /pool-history?pool=<address>[&since=<unix ms>]
+ *   One point per snapshot over the last 24 hours, oldest first.
+ *
+ * GET /pool-stream[?pool=<address>]
+ *   Server-sent events: a `snapshot` event with the /pool-stats body whenever
+ *   a new snapshot lands. The stream ends after STREAM_MAX_MS; EventSource
+ *   clients reconnect on their own.
+ */
+
+import { Hono } from 'hono';
+import type { AppEnv } from '../env.js';
+import { latestPoolSnapshot, poolHistory, type PoolSnapshot } from '../lib/poolSnapshot.js';
+import { apiError, invalidRequest } from '../lib/errors.js';
+
+/** How often /pool-stream checks KV for a new snapshot. */
+const STREAM_POLL_MS = 5_000;
+/** How long one /pool-stream connection stays open. */
+const STREAM_MAX_MS  = 10 * 60 * 1000;
+
+const NOT_ENABLED = 'Pool snapshots are not enabled on this deployment';
+
+const router = new Hono<AppEnv>();
+
+/** `snapshot` as returned to clients, narrowed to `pool` when given. */
+function view(snapshot: PoolSnapshot, pool: string | undefined) {
+  const pools = pool ? snapshot.pools.filter((p) => p.pool === pool) : snapshot.pools;
+  return {
+    taken_at: snapshot.taken_at,
+    slot:     snapshot.slot,
+    age_ms:   Math.max(0, Date.now() - snapshot.taken_at),
+    count:    pools.length,
+    pools,
+  };
+}
+
+router.get('/pool-stats', async (c) => {
+  const kv = c.env.POOL_SNAPSHOTS;
+  if (!kv) return apiError(c, 503, 'NOT_AVAILABLE', NOT_ENABLED);
+
+  const snapshot = await latestPoolSnapshot(kv);
+  if (!snapshot) return apiError(c, 503, 'NOT_AVAILABLE', 'No pool snapshot taken yet');
+
+  const pool = c.req.query('pool');
+  const body = view(snapshot, pool);
+  if (pool && body.count === 0) return apiError(c, 404, 'POOL_NOT_FOUND', `Pool not found: ${pool}`);
+  return c.json(body);
+});
+
+router.get('/pool-history', async (c) => {
+  const kv = c.env.POOL_SNAPSHOTS;
+  if (!kv) return apiError(c, 503, 'NOT_AVAILABLE', NOT_ENABLED);
+
+  const pool = c.req.query('pool');
+  if (!pool) return invalidRequest(c, 'pool query param required', ['pool']);
+  const since = Number(c.req.query('since') ?? 0);
+  if (!Number.isFinite(since)) return apiError(c, 400, 'INVALID_ARGUMENT', 'since must be a unix timestamp in ms');
+
+  const points = (await poolHistory(kv, pool)).filter((p) => p.t >= since);
+  return c.json({ pool, count: points.length, points });
+});
+
+router.get('/pool-stream', (c) => {
+  const kv = c.env.POOL_SNAPSHOTS;
+  if (!kv) return apiError(c, 503, 'NOT_AVAILABLE', NOT_ENABLED);
+
+  const pool    = c.req.query('pool');
+  const signal  = c.req.raw.signal;
+  const encoder = new TextEncoder();
+
+  const body = new ReadableStream<Uint8Array>({
+    async start(controller) {
+      const send = (chunk: string) => controller.enqueue(encoder.encode(chunk));
+      const deadline = Date.now() + STREAM_MAX_MS;
+      let lastTakenAt = 0;
+
+      send(`retry: ${STREAM_POLL_MS}\n\n`);
+      try {
+        while (!signal.aborted && Date.now() < deadline) {
+          const snapshot = await latestPoolSnapshot(kv);
+          if (snapshot && snapshot.taken_at !== lastTakenAt) {
+            lastTakenAt = snapshot.taken_at;
+            send(`event: snapshot\nid: ${snapshot.taken_at}\ndata: ${JSON.stringify(view(snapshot, pool))}\n\n`);
+          } else {
+            send(': keep-alive\n\n');
+          }
+          await new Promise((resolve) => setTimeout(resolve, STREAM_POLL_MS));
+        }
+      } catch {
+        // Client went away mid-write; nothing left to do.
+      }
+      try { controller.close(); } catch { /* already closed */ }
+    },
+  });
+
+  return new Response(body, {
+    headers: {
+      'Content-Type':  'text/event-stream',
+      'Cache-Control': 'no-cache',
+      'Connection':    'keep-alive',
+    },
+  });
+});
+
+export default router;
@@ -1,15 +1,24 @@
 /**
  * GET /my-positions?wallet=<pubkey>  — list all LP positions for a wallet.
+ *     Optional: pool, min_lp_shares, auto_compound=true|false to filter;
+ *     sort=address|pending_fees|value (fees and value in USD, largest first);
+ *     limit and cursor (the previous page's next_cursor) to page.
  * GET /my-fees?wallet=<pubkey>       — pending + owed fees for each position.
  *
  * Both endpoints include USD values fetched from Jupiter Price API (free, no auth).
+ *
+ * Account reads come from the wallet's cached snapshot (src/lib/walletSnapshot.ts);
+ * `stale_ms` in the response is its age. Pass `refresh=true` to re-read it first.
  */
 
 import { Hono } from 'hono';
+import type { Context } from 'hono';
 import type { AppEnv } from '../env.js';
-import { rpcUrl, getProgramAccounts, getAccountData } from '../lib/rpc.js';
-import { parsePosition, parsePool, parseTokenAmount, pendingFees } from '../lib/math.js';
-import { PROGRAM_ID, POSITION } from '../lib/constants.js';
+import {
+  parsePosition, parsePool, parseTokenAmount, poolReserves, pendingFees,
+} from '../lib/math.js';
+import { loadWallet } from '../lib/walletSnapshot.js';
+import { apiError, invalidRequest } from '../lib/errors.js';
 
 const router = new Hono<AppEnv>();
 
@@ -64,38 +73,76 @@ function decimalsFor(mint: string): number {
   return DECIMALS[mint] ?? DEFAULT_DECIMALS;
 }
 
-router.get('/my-positions', async (c) => {
-  const wallet = c.req.query('wallet');
-  if (!wallet) return c.json({ error: 'wallet query param required' }, 400);
+function wantsRefresh(c: Context<AppEnv>): boolean {
+  const refresh = c.req.query('refresh');
+  return refresh === 'true' || refresh === '1';
+}
 
-  const url = rpcUrl(c.env);
+const POSITION_SORTS = ['address', 'pending_fees', 'value'] as const;
+type PositionSort = typeof POSITION_SORTS[number];
 
-  try {
-    const accounts = await getProgramAccounts(
-      url, PROGRAM_ID, POSITION.TOTAL, POSITION.owner, wallet,
-    );
+interface PositionQuery {
+  pool:         string | undefined;
+  minLpShares:  bigint;
+  autoCompound: boolean | undefined;
+  sort:         PositionSort;
+  limit:        number | undefined;
+  offset:       number;
+}
 
-    if (accounts.length === 0) {
-      return c.json({ wallet, count: 0, positions: [], total_usd_value: null });
-    }
+// Filters, order and page for /my-positions; a string is a 400 error message.
+function parsePositionQuery(c: Context<AppEnv>): PositionQuery | string {
+  const minLp = c.req.query('min_lp_shares') ?? '0';
+  if (!/^\d+$/.test(minLp)) return 'min_lp_shares must be a non-negative integer';
+  const auto = c.req.query('auto_compound');
+  if (auto != null && auto !== 'true' && auto !== 'false') return 'auto_compound must be true or false';
+  const sort = c.req.query('sort') ?? 'address';
+  if (!(POSITION_SORTS as readonly string[]).includes(sort)) {
+    return `sort must be one of ${POSITION_SORTS.join(', ')}`;
+  }
+  const limitParam = c.req.query('limit');
+  const limit = limitParam == null ? undefined : Number(limitParam);
+  if (limit != null && (!Number.isInteger(limit) || limit < 1)) return 'limit must be a positive integer';
+  const cursor = c.req.query('cursor') ?? '0';
+  if (!/^\d+$/.test(cursor)) return 'cursor must come from next_cursor';
+  return {
+    pool:         c.req.query('pool') || undefined,
+    minLpShares:  BigInt(minLp),
+    autoCompound: auto == null ? undefined : auto === 'true',
+    sort:         sort as PositionSort,
+    limit,
+    offset:       Number(cursor),
+  };
+}
 
-    // Parse positions and collect pool addresses.
-    const parsed = accounts.map(({ pubkey, data }) => ({
-      pubkey,
-      pos: parsePosition(data),
-    }));
+router.get('/my-positions', async (c) => {
+  const wallet = c.req.query('wallet');
+  if (!wallet) return invalidRequest(c, 'wallet query param required', ['wallet']);
+  const query = parsePositionQuery(c);
+  if (typeof query === 'string') return apiError(c, 400, 'INVALID_ARGUMENT', query);
 
-    // Batch-fetch pool accounts to get mint addresses.
-    const poolAddrs = [...new Set(parsed.map(({ pos }) => pos.pool))];
-    const poolDataMap: Record<string, Awaited<ReturnType<typeof getAccountData>>> = {};
-    await Promise.all(poolAddrs.map(async (addr) => {
-      poolDataMap[addr] = await getAccountData(url, addr);
-    }));
+  try {
+    const snapshot = await loadWallet(c.env, wallet, wantsRefresh(c));
+    const stale_ms = snapshot.staleMs;
+
+    const parsed = snapshot.positions
+      .map(({ pubkey, data }) => ({ pubkey, pos: parsePosition(data) }))
+      .filter(({ pos }) =>
+        (query.pool == null || pos.pool === query.pool)
+        && pos.lpShares >= query.minLpShares
+        && (query.autoCompound == null || pos.autoCompound === query.autoCompound));
+
+    if (parsed.length === 0) {
+      return c.json({
+        wallet, count: 0, total: 0, positions: [], next_cursor: null, total_usd_value: null, stale_ms,
+      });
+    }
 
     // Collect all unique mints for USD price lookup.
+    const poolAddrs = [...new Set(parsed.map(({ pos }) => pos.pool))];
     const mints: string[] = [];
     for (const addr of poolAddrs) {
-      const data = poolDataMap[addr];
+      const data = snapshot.account(addr);
       if (data) {
         const pool = parsePool(data);
         mints.push(pool.tokenAMint, pool.tokenBMint);
@@ -103,25 +150,11 @@ router.get('/my-positions', async (c) => {
     }
     const prices = await fetchUsdPrices(mints);
 
-    // Batch-fetch vault balances to compute LP share value.
-    const vaultAddrs: string[] = [];
-    for (const addr of poolAddrs) {
-      const data = poolDataMap[addr];
-      if (data) {
-        const pool = parsePool(data);
-        vaultAddrs.push(pool.tokenAVault, pool.tokenBVault);
-      }
-    }
-    const vaultDataMap: Record<string, Awaited<ReturnType<typeof getAccountData>>> = {};
-    await Promise.all(vaultAddrs.map(async (addr) => {
-      vaultDataMap[addr] = await getAccountData(url, addr);
-    }));
-
     let totalUsdValue = 0;
     let totalUsdKnown = true;
 
     const positions = parsed.map(({ pubkey, pos }) => {
-      const poolData = poolDataMap[pos.pool];
+      const poolData = snapshot.account(pos.pool);
       if (!poolData) {
         return {
           address:            pubkey,
@@ -129,21 +162,28 @@ router.get('/my-positions', async (c) => {
           lp_shares:          pos.lpShares.toString(),
           fees_owed_a:        pos.feesOwedA.toString(),
           fees_owed_b:        pos.feesOwedB.toString(),
+          pending_fees_a:     null,
+          pending_fees_b:     null,
           auto_compound:      pos.autoCompound,
           compound_threshold: pos.compoundThreshold.toString(),
           usd_value:          null,
+          usd_fees:           null,
         };
       }
 
       const pool = parsePool(poolData);
-      const vaultAData = vaultDataMap[pool.tokenAVault];
-      const vaultBData = vaultDataMap[pool.tokenBVault];
+      // Vaults are only in the snapshot for pools that don't track reserves.
+      const vaultAData = snapshot.account(pool.tokenAVault);
+      const vaultBData = snapshot.account(pool.tokenBVault);
       let usdValue: string | null = null;
 
-      if (vaultAData && vaultBData && pool.lpSupply > 0n) {
+      if (pool.lpSupply > 0n) {
         try {
-          const reserveA = parseTokenAmount(vaultAData);
-          const reserveB = parseTokenAmount(vaultBData);
+          const [reserveA, reserveB] = poolReserves(
+            pool,
+            vaultAData ? parseTokenAmount(vaultAData) : 0n,
+            vaultBData ? parseTokenAmount(vaultBData) : 0n,
+          );
           // LP share value = proportion of both reserves.
           const shareA = pos.lpShares * reserveA / pool.lpSupply;
           const shareB = pos.lpShares * reserveB / pool.lpSupply;
@@ -159,54 +199,72 @@ router.get('/my-positions', async (c) => {
         } catch { totalUsdKnown = false; }
       }
 
+      const [pendingA, pendingB] = pendingFees(pos, pool);
+      const feesA = toUsd(pos.feesOwedA + pendingA, prices[pool.tokenAMint], decimalsFor(pool.tokenAMint));
+      const feesB = toUsd(pos.feesOwedB + pendingB, prices[pool.tokenBMint], decimalsFor(pool.tokenBMint));
+
       return {
         address:            pubkey,
         pool:               pos.pool,
         lp_shares:          pos.lpShares.toString(),
         fees_owed_a:        pos.feesOwedA.toString(),
         fees_owed_b:        pos.feesOwedB.toString(),
+        pending_fees_a:     pendingA.toString(),
+        pending_fees_b:     pendingB.toString(),
         auto_compound:      pos.autoCompound,
         compound_threshold: pos.compoundThreshold.toString(),
         usd_value:          usdValue,
+        usd_fees:           feesA != null && feesB != null ? (parseFloat(feesA) + parseFloat(feesB)).toFixed(4) : null,
       };
     });
 
+    // Largest first for fees and value; positions without a USD price last.
+    const usd = (v: string | null) => (v == null ? -1 : parseFloat(v));
+    if (query.sort === 'address') {
+      positions.sort((x, y) => (x.address < y.address ? -1 : x.address > y.address ? 1 : 0));
+    } else if (query.sort === 'pending_fees') {
+      positions.sort((x, y) => usd(y.usd_fees) - usd(x.usd_fees));
+    } else {
+      positions.sort((x, y) => usd(y.usd_value) - usd(x.usd_value));
+    }
+
+    // The cursor is an offset into the sorted matches.
+    const total = positions.length;
+    const end   = query.limit == null ? total : Math.min(query.offset + query.limit, total);
+    const page  = positions.slice(query.offset, end);
+
     return c.json({
       wallet,
-      count:           positions.length,
-      positions,
+      count:           page.length,
+      total,
+      positions:       page,
+      next_cursor:     end < total ? String(end) : null,
       total_usd_value: totalUsdKnown ? totalUsdValue.toFixed(4) : null,
+      stale_ms,
     });
   } catch (e) {
-    return c.json({ error: String(e) }, 502);
+    return apiError(c, 502, 'RPC_ERROR', String(e));
   }
 });
 
 router.get('/my-fees', async (c) => {
   const wallet = c.req.query('wallet');
-  if (!wallet) return c.json({ error: 'wallet query param required' }, 400);
-
-  const url = rpcUrl(c.env);
+  if (!wallet) return invalidRequest(c, 'wallet query param required', ['wallet']);
 
   try {
-    const accounts = await getProgramAccounts(
-      url, PROGRAM_ID, POSITION.TOTAL, POSITION.owner, wallet,
-    );
+    const snapshot = await loadWallet(c.env, wallet, wantsRefresh(c));
+    const stale_ms = snapshot.staleMs;
 
-    if (accounts.length === 0) {
-      return c.json({ wallet, fees: [], total_usd_fees: null });
+    if (snapshot.positions.length === 0) {
+      return c.json({ wallet, fees: [], total_usd_fees: null, stale_ms });
     }
 
     // Collect mints for price lookup.
-    const poolDataCache: Record<string, Awaited<ReturnType<typeof getAccountData>>> = {};
-    const poolAddrs = [...new Set(accounts.map(({ data }) => parsePosition(data).pool))];
-    await Promise.all(poolAddrs.map(async (addr) => {
-      poolDataCache[addr] = await getAccountData(url, addr);
-    }));
-
+    const parsed    = snapshot.positions.map(({ pubkey, data }) => ({ pubkey, pos: parsePosition(data) }));
+    const poolAddrs = [...new Set(parsed.map(({ pos }) => pos.pool))];
     const mints: string[] = [];
     for (const addr of poolAddrs) {
-      const data = poolDataCache[addr];
+      const data = snapshot.account(addr);
       if (data) {
         const pool = parsePool(data);
         mints.push(pool.tokenAMint, pool.tokenBMint);
@@ -217,9 +275,8 @@ router.get('/my-fees', async (c) => {
     let totalUsdFees = 0;
     let totalUsdKnown = true;
 
-    const fees = await Promise.all(accounts.map(async ({ pubkey, data }) => {
-      const pos      = parsePosition(data);
-      const poolData = poolDataCache[pos.pool] ?? await getAccountData(url, pos.pool);
+    const fees = parsed.map(({ pubkey, pos }) => {
+      const poolData = snapshot.account(pos.pool);
       if (!poolData) return null;
 
       const pool                = parsePool(poolData);
@@ -249,15 +306,16 @@ router.get('/my-fees', async (c) => {
         lp_shares:         pos.lpShares.toString(),
         usd_fees_earned:   usdFeesEarned,
       };
-    }));
+    });
 
     return c.json({
       wallet,
       fees:            fees.filter(Boolean),
       total_usd_fees:  totalUsdKnown ? totalUsdFees.toFixed(4) : null,
+      stale_ms,
     });
   } catch (e) {
-    return c.json({ error: String(e) }, 502);
+    return apiError(c, 502, 'RPC_ERROR', String(e));
   }
 });
 
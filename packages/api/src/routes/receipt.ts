@@ -0,0 +1,53 @@
+/**
+ * GET /receipt/:position — Metaplex metadata JSON for an LP receipt NFT.
+ *
+ * `provide_liquidity` can mint a Metaplex Core asset per position whose URI
+ * points here (see programs/a2a-swap/src/instructions/receipt.rs). The
+ * response is the standard off-chain metadata shape wallets render, with the
+ * position's live share balance as attributes.
+ */
+
+import { Hono } from 'hono';
+import type { AppEnv } from '../env.js';
+import { rpcUrl, getAccountData } from '../lib/rpc.js';
+import { parsePool, parsePosition } from '../lib/math.js';
+import { apiError } from '../lib/errors.js';
+
+const router = new Hono<AppEnv>();
+
+router.get('/:position', async (c) => {
+  const position = c.req.param('position');
+  const url = rpcUrl(c.env);
+
+  const posData = await getAccountData(url, position);
+  if (!posData) return apiError(c, 404, 'NOT_FOUND', `Position not found: ${position}`);
+
+  let pos, pool;
+  try {
+    pos = parsePosition(posData);
+    const poolData = await getAccountData(url, pos.pool);
+    if (!poolData) return apiError(c, 404, 'POOL_NOT_FOUND', `Pool not found: ${pos.pool}`);
+    pool = parsePool(poolData);
+  } catch (e) {
+    return apiError(c, 502, 'ACCOUNT_PARSE_ERROR', `Account parse error: ${e}`);
+  }
+
+  return c.json({
+    name:         'A2A-Swap LP Position',
+    symbol:       'A2ALP',
+    description:  `Receipt for an A2A-Swap liquidity position in pool ${pos.pool}. ` +
+                  'Burned automatically when the position is fully withdrawn.',
+    external_url: 'https://github.com/liqdlad-rgb/a2a-swap',
+    attributes: [
+      { trait_type: 'position',      value: position },
+      { trait_type: 'pool',          value: pos.pool },
+      { trait_type: 'token_a_mint',  value: pool.tokenAMint },
+      { trait_type: 'token_b_mint',  value: pool.tokenBMint },
+      { trait_type: 'lp_shares',     value: pos.lpShares.toString() },
+      { trait_type: 'lp_supply',     value: pool.lpSupply.toString() },
+      { trait_type: 'auto_compound', value: pos.autoCompound ? 'true' : 'false' },
+    ],
+  });
+});
+
+export default router;
@@ -0,0 +1,187 @@
+/**
+ * POST /rpc — JSON-RPC 2.0 front end for agent frameworks that do not speak REST.
+ *
+ * Methods (params are always by name, exactly as the REST endpoint takes them):
+ *   a2a.simulate  { tokenIn, tokenOut, amount }                            → POST /simulate
+ *   a2a.convert   { tokenIn, tokenOut, amount, wallet, slippageBps?,
+ *                   referrer? }                                            → POST /convert (x402)
+ *   a2a.poolInfo  { pool } | { tokenA, tokenB }                            → GET  /pool-info
+ *
+ * `result` is the REST response body. Each call is dispatched through the app
+ * itself, so validation, x402 payment and errors match the REST routes:
+ * a2a.convert reads the HTTP request's X-Payment header, and a 402 comes back
+ * as error -32002 with the payment requirements in `data`.
+ *
+ * Batches (arrays) run concurrently and answer in request order. One X-Payment
+ * pays for one swap, so a batch may hold at most one a2a.convert. Requests
+ * without an `id` are notifications: they run but get no response, and a
+ * batch of only notifications answers 204.
+ *
+ * Error codes:
+ *   -32700 parse error        -32600 invalid request    -32601 method not found
+ *   -32602 invalid params     -32603 internal error     (JSON-RPC 2.0)
+ *   -32001 not found (404)    -32002 payment required (402)
+ *   -32003 upstream error (502: RPC or account decoding)
+ * For REST failures `data` carries the HTTP `status` and the envelope's
+ * `code` and `details`.
+ */
+
+import { Hono } from 'hono';
+import type { Context } from 'hono';
+import type { AppEnv } from '../env.js';
+
+type Id = string | number | null;
+
+interface RpcRequest {
+  jsonrpc: '2.0';
+  method:  string;
+  params?: unknown;
+  id?:     Id;
+}
+
+interface RpcError {
+  code:    number;
+  message: string;
+  data?:   unknown;
+}
+
+type RpcResponse =
+  | { jsonrpc: '2.0'; result: unknown;  id: Id }
+  | { jsonrpc: '2.0'; error:  RpcError; id: Id };
+
+export const PARSE_ERROR      = -32700;
+export const INVALID_REQUEST  = -32600;
+export const METHOD_NOT_FOUND = -32601;
+export const INVALID_PARAMS   = -32602;
+export const INTERNAL_ERROR   = -32603;
+export const NOT_FOUND        = -32001;
+export const PAYMENT_REQUIRED = -32002;
+export const UPSTREAM_ERROR   = -32003;
+
+/** How each method maps onto a REST route. */
+const METHODS: Record<string, { method: 'GET' | 'POST'; path: string }> = {
+  'a2a.simulate': { method: 'POST', path: '/simulate'  },
+  'a2a.convert':  { method: 'POST', path: '/convert'   },
+  'a2a.poolInfo': { method: 'GET',  path: '/pool-info' },
+};
+
+/** Header carrying the x402 payment, passed through to the REST request. */
+const PAYMENT_HEADER = 'X-Payment';
+
+// ── Helpers ──────────────────────────────────────────────────────────────────
+
+function failure(id: Id, code: number, message: string, data?: unknown): RpcResponse {
+  return { jsonrpc: '2.0', error: data === undefined ? { code, message } : { code, message, data }, id };
+}
+
+function isRequest(v: unknown): v is RpcRequest {
+  if (typeof v !== 'object' || v === null || Array.isArray(v)) return false;
+  const r = v as Record<string, unknown>;
+  return r.jsonrpc === '2.0'
+    && typeof r.method === 'string'
+    && (r.id === undefined || r.id === null || typeof r.id === 'string' || typeof r.id === 'number');
+}
+
+/** The id to echo back: the request's, or null when it could not be read. */
+function idOf(v: unknown): Id {
+  if (typeof v !== 'object' || v === null) return null;
+  const id = (v as { id?: unknown }).id;
+  return typeof id === 'string' || typeof id === 'number' ? id : null;
+}
+
+function errorCodeFor(status: number): number {
+  switch (status) {
+    case 400: return INVALID_PARAMS;
+    case 402: return PAYMENT_REQUIRED;
+    case 404: return NOT_FOUND;
+    case 502: return UPSTREAM_ERROR;
+    default:  return INTERNAL_ERROR;
+  }
+}
+
+/** Run one call through the REST route it maps to. */
+async function dispatch(
+  app: Hono<AppEnv>,
+  c:   Context<AppEnv>,
+  req: RpcRequest,
+): Promise<RpcResponse> {
+  const id = req.id ?? null;
+  const route = METHODS[req.method];
+  if (!route) return failure(id, METHOD_NOT_FOUND, `Method not found: ${req.method}`);
+
+  const params = req.params ?? {};
+  if (typeof params !== 'object' || params === null || Array.isArray(params)) {
+    return failure(id, INVALID_PARAMS, 'params must be an object of named parameters');
+  }
+
+  const url = new URL(route.path, c.req.url);
+  const headers = new Headers();
+  const payment = c.req.header(PAYMENT_HEADER);
+  if (payment) headers.set(PAYMENT_HEADER, payment);
+  let body: string | undefined;
+  if (route.method === 'GET') {
+    for (const [k, v] of Object.entries(params)) {
+      if (v !== undefined && v !== null) url.searchParams.set(k, String(v));
+    }
+  } else {
+    headers.set('Content-Type', 'application/json');
+    body = JSON.stringify(params);
+  }
+
+  let res: Response;
+  try {
+    res = await app.fetch(new Request(url, { method: route.method, headers, body }), c.env, c.executionCtx);
+  } catch (e) {
+    return failure(id, INTERNAL_ERROR, e instanceof Error ? e.message : String(e));
+  }
+
+  const payload = await res.json().catch(() => null) as Record<string, unknown> | null;
+  if (res.ok) return { jsonrpc: '2.0', result: payload, id };
+
+  // The 402 body is the x402 payment requirements: pass it on whole. Any
+  // other failure carries the REST error envelope.
+  const err = payload?.error as { code?: string; message?: string; details?: unknown } | string | undefined;
+  const message = typeof err === 'string' ? err : err?.message ?? `HTTP ${res.status}`;
+  const data = res.status === 402 ? payload
+    : err && typeof err === 'object' ? { status: res.status, code: err.code, details: err.details }
+    : { status: res.status };
+  return failure(id, errorCodeFor(res.status), message, data);
+}
+
+// ── Route ─────────────────────────────────────────────────────────────────────
+
+/** The /rpc router; dispatches into `app`, which must also serve the REST routes. */
+export function rpcRouter(app: Hono<AppEnv>): Hono<AppEnv> {
+  const router = new Hono<AppEnv>();
+
+  router.post('/', async (c) => {
+    let body: unknown;
+    try {
+      body = JSON.parse(await c.req.text());
+    } catch {
+      return c.json(failure(null, PARSE_ERROR, 'Parse error'));
+    }
+
+    const batch = Array.isArray(body);
+    const calls = batch ? body as unknown[] : [body];
+    if (calls.length === 0) return c.json(failure(null, INVALID_REQUEST, 'Empty batch'));
+
+    let converts = 0;
+    const responses = await Promise.all(calls.map(async (call): Promise<RpcResponse | null> => {
+      if (!isRequest(call)) return failure(idOf(call), INVALID_REQUEST, 'Invalid Request');
+      if (call.method === 'a2a.convert' && ++converts > 1) {
+        return call.id === undefined
+          ? null
+          : failure(call.id, INVALID_REQUEST, 'Only one a2a.convert per batch — each needs its own X-Payment');
+      }
+      const res = await dispatch(app, c, call);
+      return call.id === undefined ? null : res;
+    }));
+
+    const answered = responses.filter((r): r is RpcResponse => r !== null);
+    if (answered.length === 0) return c.body(null, 204);
+    return c.json(batch ? answered : answered[0]);
+  });
+
+  return router;
+}
@@ -6,18 +6,20 @@
  *   tokenOut  string  — token symbol or base58 mint address
  *   amount    string  — input amount in raw atomic units (e.g. "1000000000" for 1 SOL)
  *
- * Response: SimulateResult serialised as JSON (all bigints as decimal strings).
+ * Response: SimulateResult serialised as JSON (all bigints as decimal strings),
+ * plus the mints' decimals and `amount_in_ui` / `estimated_out_ui` in whole tokens.
  */
 
 import { Hono } from 'hono';
 import type { AppEnv } from '../env.js';
-import { rpcUrl, getAccountData } from '../lib/rpc.js';
+import { rpcUrl, getAccountData, getMintDecimals } from '../lib/rpc.js';
 import {
-  parsePool, parseTokenAmount,
-  simulateDetailed, serializeSimulate, resolveMint,
+  parsePool, parseTokenAmount, parseProtocolFeeBps,
+  simulateDetailed, serializeSimulate, swapReserves, resolveMint,
 } from '../lib/math.js';
-import { resolvePool } from '../lib/pda.js';
+import { resolvePool, resolveTreasury } from '../lib/pda.js';
 import { KNOWN_TOKENS } from '../lib/constants.js';
+import { apiError, invalidRequest, unknownToken, simulateError } from '../lib/errors.js';
 
 const router = new Hono<AppEnv>();
 
@@ -32,24 +34,24 @@ router.post('/', async (c) => {
   try {
     body = await c.req.json() as SimBody;
   } catch {
-    return c.json({ error: 'Invalid JSON body' }, 400);
+    return invalidRequest(c, 'Invalid JSON body');
   }
 
   const { tokenIn, tokenOut, amount } = body;
   if (!tokenIn || !tokenOut || !amount) {
-    return c.json({ error: 'tokenIn, tokenOut, and amount are required' }, 400);
+    return invalidRequest(c, 'tokenIn, tokenOut, and amount are required', ['tokenIn', 'tokenOut', 'amount']);
   }
 
   const mintIn  = resolveMint(tokenIn,  KNOWN_TOKENS);
   const mintOut = resolveMint(tokenOut, KNOWN_TOKENS);
-  if (!mintIn)  return c.json({ error: `Unknown token: ${tokenIn}`  }, 400);
-  if (!mintOut) return c.json({ error: `Unknown token: ${tokenOut}` }, 400);
+  if (!mintIn)  return unknownToken(c, tokenIn);
+  if (!mintOut) return unknownToken(c, tokenOut);
 
   let amountIn: bigint;
   try { amountIn = BigInt(amount); } catch {
-    return c.json({ error: 'amount must be an integer string' }, 400);
+    return apiError(c, 400, 'INVALID_ARGUMENT', 'amount must be an integer string');
   }
-  if (amountIn <= 0n) return c.json({ error: 'amount must be positive' }, 400);
+  if (amountIn <= 0n) return apiError(c, 400, 'INVALID_ARGUMENT', 'amount must be positive');
 
   const url = rpcUrl(c.env);
 
@@ -69,42 +71,48 @@ router.post('/', async (c) => {
     poolAddr = pAB.addr; poolData = pAB.data; aToB = true;
   } else {
     const pBA = await tryPool(mintOut, mintIn);
-    if (!pBA) return c.json({ error: `No pool found for ${tokenIn}/${tokenOut}` }, 404);
+    if (!pBA) return apiError(c, 404, 'POOL_NOT_FOUND', `No pool found for ${tokenIn}/${tokenOut}`);
     poolAddr = pBA.addr; poolData = pBA.data; aToB = false;
   }
 
   let pool;
   try { pool = parsePool(poolData); } catch (e) {
-    return c.json({ error: `Pool parse error: ${e}` }, 502);
+    return apiError(c, 502, 'ACCOUNT_PARSE_ERROR', `Pool parse error: ${e}`);
   }
 
-  // Fetch vault balances.
+  // Fetch vault balances (used until the pool tracks its reserves).
   const vaultInAddr  = aToB ? pool.tokenAVault : pool.tokenBVault;
   const vaultOutAddr = aToB ? pool.tokenBVault : pool.tokenAVault;
 
-  const [vaultInData, vaultOutData] = await Promise.all([
+  const [vaultInData, vaultOutData, configData, decimalsIn, decimalsOut] = await Promise.all([
     getAccountData(url, vaultInAddr),
     getAccountData(url, vaultOutAddr),
+    getAccountData(url, resolveTreasury().toBase58()),
+    getMintDecimals(url, mintIn).catch(() => null),
+    getMintDecimals(url, mintOut).catch(() => null),
   ]);
 
   if (!vaultInData || !vaultOutData) {
-    return c.json({ error: 'Vault account(s) not found' }, 502);
+    return apiError(c, 502, 'RPC_ERROR', 'Vault account(s) not found');
   }
 
   let reserveIn: bigint;
   let reserveOut: bigint;
+  let protocolFeeBps: bigint;
   try {
-    reserveIn  = parseTokenAmount(vaultInData);
-    reserveOut = parseTokenAmount(vaultOutData);
+    [reserveIn, reserveOut] = swapReserves(
+      pool, aToB, parseTokenAmount(vaultInData), parseTokenAmount(vaultOutData),
+    );
+    protocolFeeBps = parseProtocolFeeBps(configData);
   } catch (e) {
-    return c.json({ error: `Vault parse error: ${e}` }, 502);
+    return apiError(c, 502, 'ACCOUNT_PARSE_ERROR', `Account parse error: ${e}`);
   }
 
   try {
-    const result = simulateDetailed(poolAddr, pool, reserveIn, reserveOut, amountIn, aToB);
-    return c.json(serializeSimulate(result));
+    const result = simulateDetailed(poolAddr, pool, reserveIn, reserveOut, amountIn, protocolFeeBps, aToB);
+    return c.json(serializeSimulate(result, { in: decimalsIn, out: decimalsOut }));
   } catch (e) {
-    return c.json({ error: String(e) }, 400);
+    return simulateError(c, e);
   }
 });
 
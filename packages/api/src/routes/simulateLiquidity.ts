@@ -0,0 +1,161 @@
+/**
+ * POST /simulate-liquidity — free deposit / withdrawal preview.
+ *
+ * Request body (JSON):
+ *   tokenA    string  — token symbol ("SOL", "USDC") or base58 mint address
+ *   tokenB    string  — the pool's other token
+ *   amountA   string  — tokenA to deposit, raw atomic units
+ *   amountB   string? — tokenB to deposit; computed from the reserves when
+ *                       omitted, required while the pool is empty
+ *   lpShares  string  — LP shares to burn (instead of amountA / amountB)
+ *
+ * Response: amounts in pool ordering (token_a_mint / token_b_mint), LP
+ * shares minted or burned, the pool share they are and the spot price
+ * before and after. All bigints as decimal strings.
+ */
+
+import { Hono } from 'hono';
+import type { AppEnv } from '../env.js';
+import { rpcUrl, getAccountData } from '../lib/rpc.js';
+import {
+  parsePool, parseTokenAmount, poolReserves, resolveMint,
+  lpSharesForDeposit, lpUnderlying, proportionalAmount,
+} from '../lib/math.js';
+import { resolvePool } from '../lib/pda.js';
+import { KNOWN_TOKENS } from '../lib/constants.js';
+import { apiError, invalidRequest, unknownToken, simulateError } from '../lib/errors.js';
+
+const router = new Hono<AppEnv>();
+
+interface SimLiquidityBody {
+  tokenA:    string;
+  tokenB:    string;
+  amountA?:  string;
+  amountB?:  string;
+  lpShares?: string;
+}
+
+/** Token B per token A in raw units; 0 for an empty side. */
+function spot(reserveA: bigint, reserveB: bigint): number {
+  return reserveA === 0n || reserveB === 0n ? 0 : Number(reserveB) / Number(reserveA);
+}
+
+function sharePct(part: bigint, whole: bigint): number {
+  return whole === 0n ? 0 : Number(part) / Number(whole) * 100;
+}
+
+router.post('/', async (c) => {
+  let body: SimLiquidityBody;
+  try {
+    body = await c.req.json() as SimLiquidityBody;
+  } catch {
+    return invalidRequest(c, 'Invalid JSON body');
+  }
+
+  const { tokenA, tokenB, amountA, amountB, lpShares } = body;
+  if (!tokenA || !tokenB || !amountA === !lpShares) {
+    return invalidRequest(c, 'tokenA, tokenB and one of amountA or lpShares are required', ['tokenA', 'tokenB']);
+  }
+
+  const mintA = resolveMint(tokenA, KNOWN_TOKENS);
+  const mintB = resolveMint(tokenB, KNOWN_TOKENS);
+  if (!mintA) return unknownToken(c, tokenA);
+  if (!mintB) return unknownToken(c, tokenB);
+
+  let fixed: bigint, other: bigint | null, shares: bigint;
+  try {
+    fixed  = BigInt(amountA ?? 0);
+    other  = amountB === undefined ? null : BigInt(amountB);
+    shares = BigInt(lpShares ?? 0);
+  } catch {
+    return apiError(c, 400, 'INVALID_ARGUMENT', 'amountA, amountB and lpShares must be integer strings');
+  }
+  if (fixed < 0n || shares < 0n || (other !== null && other < 0n)) {
+    return apiError(c, 400, 'INVALID_ARGUMENT', 'amounts must not be negative');
+  }
+
+  const url = rpcUrl(c.env);
+
+  // Try both PDA orderings; aToB says whether tokenA is the pool's token A.
+  let poolAddr = resolvePool(mintA, mintB).toBase58();
+  let poolData = await getAccountData(url, poolAddr);
+  let aToB = true;
+  if (!poolData) {
+    poolAddr = resolvePool(mintB, mintA).toBase58();
+    poolData = await getAccountData(url, poolAddr);
+    aToB = false;
+  }
+  if (!poolData) return apiError(c, 404, 'POOL_NOT_FOUND', `No pool found for ${tokenA}/${tokenB}`);
+
+  let pool;
+  try { pool = parsePool(poolData); } catch (e) {
+    return apiError(c, 502, 'ACCOUNT_PARSE_ERROR', `Pool parse error: ${e}`);
+  }
+
+  const [vaultAData, vaultBData] = await Promise.all([
+    getAccountData(url, pool.tokenAVault),
+    getAccountData(url, pool.tokenBVault),
+  ]);
+  if (!vaultAData || !vaultBData) {
+    return apiError(c, 502, 'RPC_ERROR', 'Vault account(s) not found');
+  }
+
+  let reserveA: bigint, reserveB: bigint;
+  try {
+    [reserveA, reserveB] = poolReserves(pool, parseTokenAmount(vaultAData), parseTokenAmount(vaultBData));
+  } catch (e) {
+    return apiError(c, 502, 'ACCOUNT_PARSE_ERROR', `Account parse error: ${e}`);
+  }
+
+  const base = {
+    pool:         poolAddr,
+    token_a_mint: pool.tokenAMint,
+    token_b_mint: pool.tokenBMint,
+    price_before: spot(reserveA, reserveB),
+  };
+
+  if (shares > 0n) {
+    if (shares > pool.lpSupply) {
+      return apiError(c, 400, 'INVALID_ARGUMENT', `lpShares exceeds the pool's LP supply (${pool.lpSupply})`);
+    }
+    const [outA, outB] = lpUnderlying(shares, pool.lpSupply, reserveA, reserveB);
+    return c.json({
+      ...base,
+      action:          'remove',
+      lp_shares:       shares.toString(),
+      amount_a:        outA.toString(),
+      amount_b:        outB.toString(),
+      lp_supply_after: (pool.lpSupply - shares).toString(),
+      pool_share_pct:  sharePct(shares, pool.lpSupply),
+      price_after:     spot(reserveA - outA, reserveB - outB),
+    });
+  }
+
+  try {
+    if (other === null) {
+      if (pool.lpSupply === 0n) {
+        return apiError(c, 400, 'AMOUNT_B_REQUIRED', 'amountB is required while the pool is empty (it sets the price)');
+      }
+      other = aToB
+        ? proportionalAmount(fixed, reserveA, reserveB)
+        : proportionalAmount(fixed, reserveB, reserveA);
+    }
+    const [depA, depB] = aToB ? [fixed, other] : [other, fixed];
+    const lpMinted = lpSharesForDeposit(depA, depB, pool.lpSupply, reserveA, reserveB);
+    const supplyAfter = pool.lpSupply + lpMinted;
+    return c.json({
+      ...base,
+      action:          'provide',
+      amount_a:        depA.toString(),
+      amount_b:        depB.toString(),
+      lp_minted:       lpMinted.toString(),
+      lp_supply_after: supplyAfter.toString(),
+      pool_share_pct:  sharePct(lpMinted, supplyAfter),
+      price_after:     spot(reserveA + depA, reserveB + depB),
+    });
+  } catch (e) {
+    return simulateError(c, e);
+  }
+});
+
+export default router;
@@ -0,0 +1,70 @@
+/**
+ * GET /my-tier?wallet=<pubkey> — the wallet's protocol fee discount tier.
+ *
+ * Mirrors the SDK's `my_tier()`: the wallet's AgentVolume account (opened with
+ * `open_agent_volume`) measured against the fee tiers in ProtocolConfig. A
+ * wallet without the account reports zero volume and no tier — its swaps are
+ * not tracked and pay the full protocol fee. Volume is the raw sum of
+ * `amount_in` across tracked swaps, in each swap's input-token atomic units.
+ */
+
+import { Hono } from 'hono';
+import { PublicKey } from '@solana/web3.js';
+import type { AppEnv } from '../env.js';
+import { rpcUrl, getMultipleAccountsData } from '../lib/rpc.js';
+import { parseAgentVolume, parseFeeSchedule, tierFor } from '../lib/math.js';
+import { resolveAgentVolume, resolveTreasury } from '../lib/pda.js';
+import { PROTOCOL_FEE_DENOM } from '../lib/constants.js';
+import { apiError, invalidRequest } from '../lib/errors.js';
+
+const router = new Hono<AppEnv>();
+
+router.get('/', async (c) => {
+  const wallet = c.req.query('wallet');
+  if (!wallet) return invalidRequest(c, 'Missing required query param: wallet', ['wallet']);
+  let agent: PublicKey;
+  try {
+    agent = new PublicKey(wallet);
+  } catch {
+    return apiError(c, 400, 'INVALID_ARGUMENT', `Invalid wallet: ${wallet}`);
+  }
+
+  const address = resolveAgentVolume(agent);
+  const [volumeData, configData] = await getMultipleAccountsData(
+    rpcUrl(c.env), [address.toBase58(), resolveTreasury().toBase58()],
+  );
+
+  let volume, schedule;
+  try {
+    volume   = volumeData && volumeData.length > 0 ? parseAgentVolume(volumeData) : null;
+    schedule = parseFeeSchedule(configData);
+  } catch (e) {
+    return apiError(c, 502, 'ACCOUNT_PARSE_ERROR', `Account parse error: ${e}`);
+  }
+
+  const traded = volume?.volume ?? 0n;
+  // Untracked swaps pay the full fee whatever the lowest tier requires.
+  const tier     = volume ? tierFor(schedule.tiers, traded) : null;
+  const discount = tier === null ? 0 : schedule.tiers[tier].discountBps;
+  const fee      = schedule.protocolFeeBps * BigInt(10_000 - discount) / 10_000n;
+  const next = schedule.tiers
+    .filter((t) => t.discountBps > discount && (tier === null || t.minVolume > traded))
+    .sort((a, b) => (a.minVolume < b.minVolume ? -1 : a.minVolume > b.minVolume ? 1 : 0))[0];
+
+  return c.json({
+    wallet,
+    agent_volume:      address.toBase58(),
+    tracking:          volume !== null,
+    volume:            traded.toString(),
+    swaps:             (volume?.swaps ?? 0n).toString(),
+    tier,
+    discount_bps:      discount,
+    protocol_fee_bps:  Number(fee),
+    protocol_fee_pct:  Number(fee) * 100 / Number(PROTOCOL_FEE_DENOM),
+    next_tier: next
+      ? { min_volume: next.minVolume.toString(), discount_bps: next.discountBps }
+      : null,
+  });
+});
+
+export default router;
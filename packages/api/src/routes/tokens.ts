@@ -0,0 +1,69 @@
+/**
+ * GET /tokens — the token registry, and the pools each token trades in.
+ *
+ * Lists every built-in symbol plus every mint that appears in a deployed
+ * pool, with decimals (read from the mint account for tokens outside the
+ * registry) and the pools it is paired in, so agents can discover tradable
+ * assets before calling /simulate with raw mints.
+ *
+ * Pool discovery needs getProgramAccounts (Helius / private RPC). When the
+ * RPC refuses it, the registry is still returned with `pools_available: false`.
+ */
+
+import { Hono } from 'hono';
+import type { AppEnv } from '../env.js';
+import { rpcUrl, getProgramAccountsByType, getMintDecimals } from '../lib/rpc.js';
+import { parsePool } from '../lib/math.js';
+import { accountDisc } from '../lib/idl.js';
+import { PROGRAM_ID, TOKEN_REGISTRY } from '../lib/constants.js';
+
+interface TokenPool {
+  pool:        string;
+  pair_mint:   string;
+  pair_symbol: string | null;
+}
+
+const router = new Hono<AppEnv>();
+
+router.get('/', async (c) => {
+  const url = rpcUrl(c.env);
+  const bySymbol = new Map(TOKEN_REGISTRY.map((t) => [t.mint, t.symbol]));
+
+  const tokens = new Map<string, {
+    symbol: string | null; mint: string; decimals: number | null; name: string | null; pools: TokenPool[];
+  }>();
+  for (const t of TOKEN_REGISTRY) {
+    tokens.set(t.mint, { symbol: t.symbol, mint: t.mint, decimals: t.decimals, name: t.name, pools: [] });
+  }
+
+  let poolsAvailable = true;
+  try {
+    const accounts = await getProgramAccountsByType(url, PROGRAM_ID, accountDisc('Pool'));
+    for (const { pubkey, data } of accounts) {
+      let pool;
+      try { pool = parsePool(data); } catch { continue; }
+      const sides: Array<[string, string]> = [
+        [pool.tokenAMint, pool.tokenBMint],
+        [pool.tokenBMint, pool.tokenAMint],
+      ];
+      for (const [mint, pair] of sides) {
+        if (!tokens.has(mint)) {
+          tokens.set(mint, { symbol: null, mint, decimals: null, name: null, pools: [] });
+        }
+        tokens.get(mint)!.pools.push({ pool: pubkey, pair_mint: pair, pair_symbol: bySymbol.get(pair) ?? null });
+      }
+    }
+  } catch {
+    poolsAvailable = false;
+  }
+
+  // Decimals for pool mints outside the registry, from their mint accounts.
+  await Promise.all([...tokens.values()].filter((t) => t.decimals === null).map(async (t) => {
+    try { t.decimals = await getMintDecimals(url, t.mint); } catch { /* leave null */ }
+  }));
+
+  const list = [...tokens.values()];
+  return c.json({ count: list.length, pools_available: poolsAvailable, tokens: list });
+});
+
+export default router;
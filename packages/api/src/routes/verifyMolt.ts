@@ -8,6 +8,7 @@
 import { Hono } from 'hono';
 import type { AppEnv } from '../env.js';
 import { PublicKey } from '@solana/web3.js';
+import { apiError } from '../lib/errors.js';
 
 const router = new Hono<AppEnv>();
 
@@ -23,7 +24,7 @@ router.get('/:wallet', async (c) => {
   try {
     new PublicKey(walletStr);
   } catch {
-    return c.json({ error: 'Invalid wallet address' }, 400);
+    return apiError(c, 400, 'INVALID_ARGUMENT', 'Invalid wallet address');
   }
 
   try {
@@ -0,0 +1,79 @@
+/**
+ * Webhook push notifications (see src/lib/webhooks.ts for events and signing).
+ *
+ * POST   /webhooks      register; returns the subscription with its `secret` (shown once)
+ * GET    /webhooks/:id  subscription status       — Authorization: Bearer <secret>
+ * DELETE /webhooks/:id  unsubscribe               — Authorization: Bearer <secret>
+ *
+ * Request body (JSON) for POST:
+ *   url         string  — https endpoint that receives deliveries
+ *   event       string  — "fees.threshold" | "price.move" | "swap.fill"
+ *   wallet      string  — fees.threshold, swap.fill: wallet to watch
+ *   pool        string  — fees.threshold: only this pool (optional); price.move: the pool
+ *   tokenA/B    string  — price.move: instead of pool, the pair (symbol or mint)
+ *   thresholdA  string  — fees.threshold: claimable token A, atomic units (this and/or thresholdB)
+ *   thresholdB  string  — fees.threshold: claimable token B, atomic units
+ *   movePct     number  — price.move: percent move that triggers (e.g. 2)
+ */
+
+import { Hono } from 'hono';
+import type { Context } from 'hono';
+import type { AppEnv } from '../env.js';
+import { buildSubscription, describe, type RegisterBody, type Subscription } from '../lib/webhooks.js';
+import { apiError, invalidRequest } from '../lib/errors.js';
+
+const router = new Hono<AppEnv>();
+
+/** All subscriptions live in one WebhookHub instance. */
+function hub(c: Context<AppEnv>): DurableObjectStub | null {
+  const ns = c.env.WEBHOOKS;
+  return ns ? ns.get(ns.idFromName('global')) : null;
+}
+
+/** The subscription `:id`, if the request carries its secret. */
+async function authorized(c: Context<AppEnv>, stub: DurableObjectStub): Promise<Subscription | Response> {
+  const res = await stub.fetch(`https://webhooks/subscriptions/${encodeURIComponent(c.req.param('id') ?? '')}`);
+  if (res.status === 404) return apiError(c, 404, 'NOT_FOUND', 'Webhook not found');
+  const sub = await res.json() as Subscription;
+  const auth = c.req.header('Authorization') ?? '';
+  if (auth !== `Bearer ${sub.secret}`) return apiError(c, 404, 'NOT_FOUND', 'Webhook not found');
+  return sub;
+}
+
+router.post('/', async (c) => {
+  const stub = hub(c);
+  if (!stub) return apiError(c, 503, 'NOT_AVAILABLE', 'Webhooks are not enabled on this deployment');
+
+  let body: RegisterBody;
+  try {
+    body = await c.req.json() as RegisterBody;
+  } catch {
+    return invalidRequest(c, 'Invalid JSON body');
+  }
+
+  const sub = buildSubscription(body, Date.now());
+  if (typeof sub === 'string') return apiError(c, 400, 'INVALID_ARGUMENT', sub);
+
+  const res = await stub.fetch('https://webhooks/subscriptions', { method: 'POST', body: JSON.stringify(sub) });
+  if (!res.ok) return apiError(c, 502, 'INTERNAL_ERROR', `Webhook store error: HTTP ${res.status}`);
+  return c.json({ ...describe(sub), secret: sub.secret }, 201);
+});
+
+router.get('/:id', async (c) => {
+  const stub = hub(c);
+  if (!stub) return apiError(c, 503, 'NOT_AVAILABLE', 'Webhooks are not enabled on this deployment');
+  const sub = await authorized(c, stub);
+  if (sub instanceof Response) return sub;
+  return c.json(describe(sub));
+});
+
+router.delete('/:id', async (c) => {
+  const stub = hub(c);
+  if (!stub) return apiError(c, 503, 'NOT_AVAILABLE', 'Webhooks are not enabled on this deployment');
+  const sub = await authorized(c, stub);
+  if (sub instanceof Response) return sub;
+  await stub.fetch(`https://webhooks/subscriptions/${sub.id}`, { method: 'DELETE' });
+  return c.body(null, 204);
+});
+
+export default router;
@@ -0,0 +1,261 @@
+//! `~/.config/a2a-swap/config.toml` — named profiles for global options.
+//!
+//! ```toml
+//! default_profile = "prod"
+//!
+//! [profiles.prod]
+//! rpc_url      = "https://my-private-rpc.example.com"
+//! keypair      = "~/agent-keys/prod.json"
+//! max_slippage = 0.3        # percent, default for `convert --max-slippage`
+//! priority_fee = 10000      # micro-lamports per compute unit
+//! network      = "mainnet-beta"
+//! notify       = "slack://hooks.slack.com/services/T000/B000/XXXX"
+//!
+//! [profiles.dev]
+//! network = "localnet"
+//!
+//! default_wallet = "treasury"
+//!
+//! [wallets.treasury]
+//! keypair      = "~/agent-keys/treasury.json"
+//! max_slippage = 0.1
+//!
+//! [wallets.market-maker]
+//! keypair      = "~/agent-keys/mm.json"
+//! priority_fee = 50000
+//! ```
+//!
+//! Precedence for every setting: command-line flag → environment variable →
+//! selected wallet → selected profile → built-in default.
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+
+use crate::error::input_error;
+
+/// Profile used when neither `--profile` nor `default_profile` is set.
+pub const DEFAULT_PROFILE: &str = "default";
+
+/// Keys accepted by `a2a-swap config set/get`.
+pub const KEYS: &[&str] = &["rpc_url", "keypair", "max_slippage", "priority_fee", "network", "notify"];
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Config {
+    /// Profile selected when `--profile` is not given.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub default_profile: Option<String>,
+
+    #[serde(default)]
+    pub profiles: BTreeMap<String, Profile>,
+
+    /// Wallet selected when `--wallet` is not given (`a2a-swap wallets use`).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub default_wallet: Option<String>,
+
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub wallets: BTreeMap<String, Wallet>,
+}
+
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Profile {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rpc_url: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub keypair: Option<String>,
+    /// Default `convert --max-slippage`, in percent.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_slippage: Option<f64>,
+    /// Compute-unit price in micro-lamports added to every transaction.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub priority_fee: Option<u64>,
+    /// `mainnet-beta`, `devnet` or `localnet` — picks the RPC when `rpc_url` is unset.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub network: Option<String>,
+    /// Default `--notify` target (slack://…, discord://… or a webhook URL).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub notify: Option<String>,
+}
+
+/// A named agent keypair with its own defaults, layered over the profile.
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Wallet {
+    pub keypair: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_slippage: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub priority_fee: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub notify: Option<String>,
+}
+
+/// Config file location: `$A2A_CONFIG`, else `~/.config/a2a-swap/config.toml`.
+pub fn path() -> PathBuf {
+    if let Ok(p) = std::env::var("A2A_CONFIG") {
+        return PathBuf::from(p);
+    }
+    let home = std::env::var("HOME").unwrap_or_else(|_| ".".into());
+    PathBuf::from(home).join(".config").join("a2a-swap").join("config.toml")
+}
+
+/// User token list written by `a2a-swap tokens add/import`: `$A2A_TOKENS`,
+/// else `tokens.json` next to the config file.
+pub fn tokens_path() -> PathBuf {
+    if let Ok(p) = std::env::var("A2A_TOKENS") {
+        return PathBuf::from(p);
+    }
+    path().with_file_name("tokens.json")
+}
+
+impl Config {
+    /// Load the config file; a missing file is an empty config.
+    pub fn load() -> Result<Config> {
+        let path = path();
+        let text = match std::fs::read_to_string(&path) {
+            Ok(t) => t,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Config::default()),
+            Err(e) => return Err(e).with_context(|| format!("Cannot read {}", path.display())),
+        };
+        let cfg: Config = toml::from_str(&text)
+            .with_context(|| format!("Invalid config file {}", path.display()))?;
+        for (name, profile) in &cfg.profiles {
+            profile.validate().with_context(|| format!("profile `{name}` in {}", path.display()))?;
+        }
+        for (name, wallet) in &cfg.wallets {
+            wallet.validate().with_context(|| format!("wallet `{name}` in {}", path.display()))?;
+        }
+        Ok(cfg)
+    }
+
+    /// Write the config file, creating `~/.config/a2a-swap/` if needed.
+    pub fn save(&self) -> Result<PathBuf> {
+        let path = path();
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)
+                .with_context(|| format!("Cannot create {}", dir.display()))?;
+        }
+        std::fs::write(&path, toml::to_string_pretty(self)?)
+            .with_context(|| format!("Cannot write {}", path.display()))?;
+        Ok(path)
+    }
+
+    /// Name of the profile to use: `--profile`, else `default_profile`, else `"default"`.
+    pub fn profile_name<'a>(&'a self, requested: Option<&'a str>) -> &'a str {
+        requested
+            .or(self.default_profile.as_deref())
+            .unwrap_or(DEFAULT_PROFILE)
+    }
+
+    /// Resolve the active profile. An explicitly requested profile must
+    /// exist; the implicit default may be absent (empty profile).
+    pub fn profile(&self, requested: Option<&str>) -> Result<Profile> {
+        let name = self.profile_name(requested);
+        match self.profiles.get(name) {
+            Some(p) => Ok(p.clone()),
+            None if requested.is_none() && self.default_profile.is_none() => Ok(Profile::default()),
+            None => Err(input_error!(
+                "Profile `{name}` not found in {}.\n  \
+                 Create it with: a2a-swap --profile {name} config set rpc_url <URL>",
+                path().display()
+            )),
+        }
+    }
+
+    /// Resolve the active wallet: `--wallet`, else `default_wallet`, else
+    /// none. A selected wallet must exist.
+    pub fn wallet(&self, requested: Option<&str>) -> Result<Option<(&str, &Wallet)>> {
+        let Some(name) = requested.or(self.default_wallet.as_deref()) else { return Ok(None) };
+        match self.wallets.get_key_value(name) {
+            Some((name, wallet)) => Ok(Some((name.as_str(), wallet))),
+            None => Err(input_error!(
+                "Wallet `{name}` not found in {}.\n  \
+                 Add it with: a2a-swap wallets add {name} <KEYPAIR_PATH>",
+                path().display()
+            )),
+        }
+    }
+}
+
+impl Profile {
+    /// This profile with `wallet`'s keypair and whichever defaults it sets.
+    pub fn with_wallet(mut self, wallet: &Wallet) -> Profile {
+        self.keypair = Some(wallet.keypair.clone());
+        self.max_slippage = wallet.max_slippage.or(self.max_slippage);
+        self.priority_fee = wallet.priority_fee.or(self.priority_fee);
+        self.notify = wallet.notify.clone().or(self.notify);
+        self
+    }
+
+    /// Set `key` from its string form, validating the value.
+    pub fn set(&mut self, key: &str, value: &str) -> Result<()> {
+        match key {
+            "rpc_url"      => self.rpc_url = Some(value.to_string()),
+            "keypair"      => self.keypair = Some(value.to_string()),
+            "max_slippage" => self.max_slippage = Some(value.parse()
+                .map_err(|_| input_error!("max_slippage must be a number (percent), got `{value}`"))?),
+            "priority_fee" => self.priority_fee = Some(value.parse()
+                .map_err(|_| input_error!("priority_fee must be an integer (micro-lamports), got `{value}`"))?),
+            "network"      => self.network = Some(value.to_string()),
+            "notify"       => self.notify = Some(value.to_string()),
+            _ => return Err(unknown_key(key)),
+        }
+        self.validate()
+    }
+
+    /// Current value of `key`, if set.
+    pub fn get(&self, key: &str) -> Result<Option<String>> {
+        Ok(match key {
+            "rpc_url"      => self.rpc_url.clone(),
+            "keypair"      => self.keypair.clone(),
+            "max_slippage" => self.max_slippage.map(|v| v.to_string()),
+            "priority_fee" => self.priority_fee.map(|v| v.to_string()),
+            "network"      => self.network.clone(),
+            "notify"       => self.notify.clone(),
+            _ => return Err(unknown_key(key)),
+        })
+    }
+
+    fn validate(&self) -> Result<()> {
+        if let Some(s) = self.max_slippage {
+            if !(0.0..=100.0).contains(&s) {
+                return Err(input_error!("max_slippage {s} is out of range. Use 0–100 (percent)."));
+            }
+        }
+        if let Some(n) = &self.network {
+            network_rpc(n)?;
+        }
+        if let Some(t) = &self.notify {
+            crate::notify::Target::parse(t)?;
+        }
+        Ok(())
+    }
+}
+
+impl Wallet {
+    pub fn validate(&self) -> Result<()> {
+        if self.keypair.is_empty() {
+            return Err(input_error!("keypair must be a path to a keypair JSON file"));
+        }
+        Profile::default().with_wallet(self).validate()
+    }
+}
+
+/// Default RPC endpoint for a network name.
+pub fn network_rpc(network: &str) -> Result<&'static str> {
+    match network {
+        "mainnet-beta" | "mainnet" => Ok("https://api.mainnet-beta.solana.com"),
+        "devnet"                   => Ok("https://api.devnet.solana.com"),
+        "localnet" | "localhost"   => Ok("http://127.0.0.1:8899"),
+        other => Err(input_error!(
+            "Unknown network `{other}`. Use mainnet-beta, devnet or localnet."
+        )),
+    }
+}
+
+fn unknown_key(key: &str) -> anyhow::Error {
+    input_error!("Unknown config key `{key}`. Valid keys: {}", KEYS.join(", "))
+}
@@ -0,0 +1,475 @@
+//! `a2a-swap dashboard` — one live screen for pools, positions, fees and swaps.
+//!
+//! Loads a snapshot over JSON-RPC, then follows changes over the RPC
+//! websocket: `accountSubscribe` for every watched pool, vault and position,
+//! and `logsSubscribe` on the program for swaps. If the websocket cannot be
+//! reached the dashboard falls back to polling every `--refresh` seconds.
+
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::time::{Duration, Instant};
+
+use anyhow::Result;
+use ratatui::{
+    crossterm::event::{self, Event, KeyCode, KeyEventKind},
+    layout::{Constraint, Layout},
+    style::{Modifier, Style, Stylize},
+    text::Line,
+    widgets::{Block, Cell, Paragraph, Row, Table},
+    Frame,
+};
+use solana_client::{
+    pubsub_client::PubsubClient,
+    rpc_client::{GetConfirmedSignaturesForAddress2Config, RpcClient},
+    rpc_config::{RpcAccountInfoConfig, RpcTransactionLogsConfig, RpcTransactionLogsFilter},
+    rpc_request::RpcRequest,
+};
+use solana_account_decoder_client_types::UiAccountEncoding;
+use solana_sdk::{commitment_config::CommitmentConfig, pubkey::Pubkey};
+
+use crate::error::CliError;
+use crate::events::{parse_logs, ProgramEvent};
+use crate::{
+    get_agent_positions, parse_pool, parse_position, parse_token_amount, pending_fees,
+    resolve_symbol, spot_price, PoolState, PositionState,
+};
+
+/// Swaps kept in the "recent swaps" panel.
+const MAX_SWAPS: usize = 50;
+
+/// Something that changed since the last frame.
+enum Update {
+    /// New data for a watched pool, vault or position account.
+    Account(Pubkey, Vec<u8>),
+    /// A swap seen in the program's logs.
+    Swap(SwapEvent),
+}
+
+/// A swap seen on the program, for the "recent swaps" panel.
+struct SwapEvent {
+    slot:       u64,
+    signature:  String,
+    amount_in:  u64,
+    amount_out: u64,
+    impact_bps: Option<u64>,
+    a_to_b:     bool,
+}
+
+impl SwapEvent {
+    /// First swap decoded from a transaction's logs, if any.
+    fn from_logs(slot: u64, signature: &str, logs: &[String]) -> Option<SwapEvent> {
+        parse_logs(logs).into_iter().find_map(|e| match e {
+            ProgramEvent::Swap(fill) => Some(SwapEvent {
+                slot,
+                signature:  signature.to_string(),
+                amount_in:  fill.amount_in,
+                amount_out: fill.amount_out,
+                impact_bps: fill.impact_bps,
+                a_to_b:     fill.a_to_b,
+            }),
+            _ => None,
+        })
+    }
+}
+
+struct Dashboard {
+    owner:      Pubkey,
+    program_id: Pubkey,
+    rpc_url:    String,
+    /// Pools in display order: the owner's position pools plus `--pair`s.
+    pools:      Vec<Pubkey>,
+    pool_state: HashMap<Pubkey, PoolState>,
+    /// Vault → token balance.
+    reserves:   HashMap<Pubkey, u64>,
+    positions:  Vec<(Pubkey, PositionState)>,
+    swaps:      VecDeque<SwapEvent>,
+    seen_sigs:  HashSet<String>,
+    /// "websocket" or "polling every Ns".
+    source:     String,
+    status:     String,
+    updated:    Instant,
+}
+
+impl Dashboard {
+    /// Initial snapshot over JSON-RPC.
+    fn load(
+        client:     &RpcClient,
+        rpc_url:    &str,
+        owner:      Pubkey,
+        program_id: Pubkey,
+        watch:      &[Pubkey],
+    ) -> Result<Dashboard> {
+        let mut dash = Dashboard {
+            owner,
+            program_id,
+            rpc_url:    rpc_url.to_string(),
+            pools:      watch.to_vec(),
+            pool_state: HashMap::new(),
+            reserves:   HashMap::new(),
+            positions:  Vec::new(),
+            swaps:      VecDeque::new(),
+            seen_sigs:  HashSet::new(),
+            source:     String::new(),
+            status:     String::new(),
+            updated:    Instant::now(),
+        };
+        dash.refresh(client)?;
+        Ok(dash)
+    }
+
+    /// Re-fetch positions, pools and vault balances.
+    fn refresh(&mut self, client: &RpcClient) -> Result<()> {
+        self.positions = get_agent_positions(client, &self.owner, &self.program_id)?;
+        for (_, pos) in &self.positions {
+            if !self.pools.contains(&pos.pool) {
+                self.pools.push(pos.pool);
+            }
+        }
+
+        let pool_accounts = client.get_multiple_accounts(&self.pools)?;
+        for (key, acct) in self.pools.iter().zip(pool_accounts) {
+            if let Some(pool) = acct.and_then(|a| parse_pool(&a.data).ok()) {
+                self.pool_state.insert(*key, pool);
+            }
+        }
+
+        let vaults = self.vaults();
+        let vault_accounts = client.get_multiple_accounts(&vaults)?;
+        for (key, acct) in vaults.iter().zip(vault_accounts) {
+            if let Some(amount) = acct.and_then(|a| parse_token_amount(&a.data).ok()) {
+                self.reserves.insert(*key, amount);
+            }
+        }
+
+        self.updated = Instant::now();
+        Ok(())
+    }
+
+    /// Poll the program's recent signatures for swaps (websocket fallback).
+    fn poll_swaps(&mut self, client: &RpcClient) -> Result<()> {
+        let config = GetConfirmedSignaturesForAddress2Config {
+            limit: Some(20),
+            commitment: Some(CommitmentConfig::confirmed()),
+            ..GetConfirmedSignaturesForAddress2Config::default()
+        };
+        let sigs = client.get_signatures_for_address_with_config(&self.program_id, config)?;
+        // Oldest first so the panel stays newest-on-top.
+        for sig in sigs.into_iter().rev() {
+            if sig.err.is_some() || self.seen_sigs.contains(&sig.signature) {
+                continue;
+            }
+            let tx: serde_json::Value = client.send(
+                RpcRequest::GetTransaction,
+                serde_json::json!([
+                    sig.signature,
+                    { "encoding": "json", "commitment": "confirmed", "maxSupportedTransactionVersion": 0 },
+                ]),
+            )?;
+            let logs: Vec<String> = tx["meta"]["logMessages"]
+                .as_array()
+                .map(|l| l.iter().filter_map(|s| s.as_str().map(String::from)).collect())
+                .unwrap_or_default();
+            self.seen_sigs.insert(sig.signature.clone());
+            if let Some(swap) = SwapEvent::from_logs(sig.slot, &sig.signature, &logs) {
+                self.push_swap(swap);
+            }
+        }
+        Ok(())
+    }
+
+    fn vaults(&self) -> Vec<Pubkey> {
+        self.pools
+            .iter()
+            .filter_map(|p| self.pool_state.get(p))
+            .flat_map(|s| [s.token_a_vault, s.token_b_vault])
+            .collect()
+    }
+
+    fn push_swap(&mut self, swap: SwapEvent) {
+        self.seen_sigs.insert(swap.signature.clone());
+        self.swaps.push_front(swap);
+        self.swaps.truncate(MAX_SWAPS);
+    }
+
+    fn apply(&mut self, update: Update) {
+        match update {
+            Update::Account(key, data) => {
+                if let Some(state) = self.pool_state.get_mut(&key) {
+                    if let Ok(pool) = parse_pool(&data) {
+                        *state = pool;
+                    }
+                } else if let Some(reserve) = self.reserves.get_mut(&key) {
+                    if let Ok(amount) = parse_token_amount(&data) {
+                        *reserve = amount;
+                    }
+                } else if let Some(slot) = self.positions.iter_mut().find(|(k, _)| *k == key) {
+                    if let Ok(pos) = parse_position(&data) {
+                        slot.1 = pos;
+                    }
+                }
+            }
+            Update::Swap(swap) => {
+                if !self.seen_sigs.contains(&swap.signature) {
+                    self.push_swap(swap);
+                }
+            }
+        }
+        self.updated = Instant::now();
+    }
+
+    fn pair_label(&self, pool: &Pubkey) -> String {
+        match self.pool_state.get(pool) {
+            Some(s) => format!("{}-{}", resolve_symbol(&s.token_a_mint), resolve_symbol(&s.token_b_mint)),
+            None    => short(&pool.to_string()),
+        }
+    }
+
+    // ── Rendering ─────────────────────────────────────────────────────────────
+
+    fn draw(&self, f: &mut Frame) {
+        let [header, pools, positions, swaps, footer] = Layout::vertical([
+            Constraint::Length(3),
+            Constraint::Length(self.pools.len().clamp(1, 8) as u16 + 3),
+            Constraint::Length(self.positions.len().clamp(1, 8) as u16 + 3),
+            Constraint::Min(5),
+            Constraint::Length(1),
+        ])
+        .areas(f.area());
+
+        f.render_widget(
+            Paragraph::new(vec![
+                Line::from(format!(
+                    "owner {}   rpc {}   source {}",
+                    self.owner, self.rpc_url, self.source,
+                )),
+            ])
+            .block(Block::bordered().title(" A2A-Swap dashboard ".bold())),
+            header,
+        );
+
+        let bold = Style::new().add_modifier(Modifier::BOLD);
+
+        let pool_rows = self.pools.iter().map(|p| {
+            let Some(s) = self.pool_state.get(p) else {
+                return Row::new(vec![Cell::from(self.pair_label(p)), Cell::from("not found")]);
+            };
+            let ra = self.reserves.get(&s.token_a_vault).copied().unwrap_or(0);
+            let rb = self.reserves.get(&s.token_b_vault).copied().unwrap_or(0);
+            let spot = spot_price(s.curve, ra, rb);
+            Row::new(vec![
+                self.pair_label(p),
+                short(&p.to_string()),
+                ra.to_string(),
+                rb.to_string(),
+                format!("{spot:.6}"),
+                s.lp_supply.to_string(),
+                format!("{:.2}%", s.fee_rate_bps as f64 / 100.0),
+            ])
+        });
+        f.render_widget(
+            Table::new(pool_rows, [
+                Constraint::Length(14), Constraint::Length(14), Constraint::Length(20),
+                Constraint::Length(20), Constraint::Length(14), Constraint::Length(20),
+                Constraint::Length(6),
+            ])
+            .header(Row::new(["Pair", "Pool", "Reserve A", "Reserve B", "Spot B/A", "LP supply", "Fee"]).style(bold))
+            .block(Block::bordered().title(" Pools ")),
+            pools,
+        );
+
+        let position_rows = self.positions.iter().map(|(key, pos)| {
+            let (share, fees_a, fees_b) = match self.pool_state.get(&pos.pool) {
+                Some(pool) => {
+                    let (a, b) = pending_fees(pos, pool);
+                    let share = if pool.lp_supply == 0 { 0.0 }
+                                else { pos.lp_shares as f64 / pool.lp_supply as f64 * 100.0 };
+                    (format!("{share:.4}%"), a.to_string(), b.to_string())
+                }
+                None => ("—".into(), pos.fees_owed_a.to_string(), pos.fees_owed_b.to_string()),
+            };
+            Row::new(vec![
+                self.pair_label(&pos.pool),
+                short(&key.to_string()),
+                pos.lp_shares.to_string(),
+                share,
+                fees_a,
+                fees_b,
+                if pos.auto_compound { "on".into() } else { "off".into() },
+            ])
+        });
+        f.render_widget(
+            Table::new(position_rows, [
+                Constraint::Length(14), Constraint::Length(14), Constraint::Length(20),
+                Constraint::Length(10), Constraint::Length(18), Constraint::Length(18),
+                Constraint::Length(8),
+            ])
+            .header(Row::new(["Pair", "Position", "LP shares", "Share", "Fees A", "Fees B", "Compound"]).style(bold))
+            .block(Block::bordered().title(" Positions · pending fees ")),
+            positions,
+        );
+
+        let swap_rows = self.swaps.iter().map(|s| {
+            Row::new(vec![
+                s.slot.to_string(),
+                short(&s.signature),
+                if s.a_to_b { "A → B".into() } else { "B → A".into() },
+                s.amount_in.to_string(),
+                s.amount_out.to_string(),
+                s.impact_bps.map_or("—".into(), |bps| format!("{:.2}%", bps as f64 / 100.0)),
+            ])
+        });
+        f.render_widget(
+            Table::new(swap_rows, [
+                Constraint::Length(12), Constraint::Length(14), Constraint::Length(7),
+                Constraint::Length(20), Constraint::Length(20), Constraint::Length(8),
+            ])
+            .header(Row::new(["Slot", "Signature", "Dir", "In", "Out", "Impact"]).style(bold))
+            .block(Block::bordered().title(" Recent swaps (all pools) ")),
+            swaps,
+        );
+
+        f.render_widget(
+            Paragraph::new(format!(
+                " q quit · r refresh · updated {}s ago  {}",
+                self.updated.elapsed().as_secs(),
+                self.status,
+            ))
+            .dim(),
+            footer,
+        );
+    }
+}
+
+/// `abcd…wxyz` for long base-58 strings.
+fn short(s: &str) -> String {
+    if s.len() <= 12 { s.to_string() } else { format!("{}…{}", &s[..5], &s[s.len() - 5..]) }
+}
+
+/// Websocket endpoint for an HTTP RPC URL (`solana-test-validator` serves it on port + 1).
+fn ws_url_for(rpc_url: &str) -> String {
+    let ws = if let Some(rest) = rpc_url.strip_prefix("https://") {
+        format!("wss://{rest}")
+    } else if let Some(rest) = rpc_url.strip_prefix("http://") {
+        format!("ws://{rest}")
+    } else {
+        rpc_url.to_string()
+    };
+    ws.replace(":8899", ":8900")
+}
+
+/// Subscribe to every watched account and the program's logs, forwarding
+/// updates into `tx`. Each subscription lives on its own thread.
+fn subscribe(ws_url: &str, dash: &Dashboard, tx: &Sender<Update>) -> Result<()> {
+    let config = RpcAccountInfoConfig {
+        encoding:   Some(UiAccountEncoding::Base64),
+        commitment: Some(CommitmentConfig::confirmed()),
+        ..RpcAccountInfoConfig::default()
+    };
+    let accounts = dash.pools.iter()
+        .copied()
+        .chain(dash.vaults())
+        .chain(dash.positions.iter().map(|(k, _)| *k));
+    for key in accounts {
+        let (sub, rx) = PubsubClient::account_subscribe(ws_url, &key, Some(config.clone()))
+            .map_err(|e| CliError::Rpc(format!("accountSubscribe {key}: {e}")))?;
+        let tx = tx.clone();
+        std::thread::spawn(move || {
+            let _sub = sub; // dropping it unsubscribes
+            for resp in rx.iter() {
+                let Some(data) = resp.value.data.decode() else { continue };
+                if tx.send(Update::Account(key, data)).is_err() {
+                    break;
+                }
+            }
+        });
+    }
+
+    let (sub, rx) = PubsubClient::logs_subscribe(
+        ws_url,
+        RpcTransactionLogsFilter::Mentions(vec![dash.program_id.to_string()]),
+        RpcTransactionLogsConfig { commitment: Some(CommitmentConfig::confirmed()) },
+    )
+    .map_err(|e| CliError::Rpc(format!("logsSubscribe: {e}")))?;
+    let tx = tx.clone();
+    std::thread::spawn(move || {
+        let _sub = sub;
+        for resp in rx.iter() {
+            if resp.value.err.is_some() {
+                continue;
+            }
+            let swap = SwapEvent::from_logs(resp.context.slot, &resp.value.signature, &resp.value.logs);
+            if let Some(swap) = swap {
+                if tx.send(Update::Swap(swap)).is_err() {
+                    break;
+                }
+            }
+        }
+    });
+    Ok(())
+}
+
+/// Run the dashboard until the user presses `q`.
+pub fn run(
+    client:     &RpcClient,
+    rpc_url:    &str,
+    ws_url:     Option<&str>,
+    owner:      Pubkey,
+    program_id: Pubkey,
+    watch:      &[Pubkey],
+    refresh:    Duration,
+) -> Result<()> {
+    let mut dash = Dashboard::load(client, rpc_url, owner, program_id, watch)?;
+
+    let (tx, rx): (Sender<Update>, Receiver<Update>) = mpsc::channel();
+    let ws = ws_url.map(String::from).unwrap_or_else(|| ws_url_for(rpc_url));
+    let live = match subscribe(&ws, &dash, &tx) {
+        Ok(()) => {
+            dash.source = format!("websocket {ws}");
+            true
+        }
+        Err(e) => {
+            dash.source = format!("polling every {}s", refresh.as_secs());
+            dash.status = format!("websocket unavailable ({e})");
+            false
+        }
+    };
+
+    let mut terminal = ratatui::init();
+    let mut last_poll = Instant::now() - refresh;
+    let result = (|| -> Result<()> {
+        loop {
+            while let Ok(update) = rx.try_recv() {
+                dash.apply(update);
+            }
+            if !live && last_poll.elapsed() >= refresh {
+                last_poll = Instant::now();
+                let polled = dash.refresh(client).and_then(|_| dash.poll_swaps(client));
+                if let Err(e) = polled {
+                    dash.status = format!("refresh failed: {e}");
+                }
+            }
+
+            terminal.draw(|f| dash.draw(f))?;
+
+            if event::poll(Duration::from_millis(250))? {
+                if let Event::Key(key) = event::read()? {
+                    if key.kind != KeyEventKind::Press {
+                        continue;
+                    }
+                    match key.code {
+                        KeyCode::Char('q') | KeyCode::Esc => return Ok(()),
+                        KeyCode::Char('r') => {
+                            dash.status = match dash.refresh(client) {
+                                Ok(())  => String::new(),
+                                Err(e)  => format!("refresh failed: {e}"),
+                            };
+                        }
+                        _ => {}
+                    }
+                }
+            }
+        }
+    })();
+    ratatui::restore();
+    result
+}
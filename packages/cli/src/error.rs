@@ -0,0 +1,232 @@
+//! How a failed command reports itself: its exit code and, under `--json`,
+//! one error object on stdout instead of prose on stderr.
+//!
+//! ```json
+//! {"status":"error","command":"convert","code":"SLIPPAGE_EXCEEDED",
+//!  "message":"Transaction failed (…): Program error …","retryable":true}
+//! ```
+//!
+//! Exit codes, so a supervising agent can branch without reading either:
+//!
+//! | Code | Meaning |
+//! |------|---------|
+//! | 0 | Success |
+//! | 1 | Any other failure (see `code` / the message) |
+//! | 2 | User input error: a flag, argument or value the command cannot use |
+//! | 3 | Pool not found for the pair |
+//! | 4 | Slippage: the trade or deposit would exceed its slippage, price impact or max-amount limit |
+//! | 5 | RPC failure: the node could not be reached or failed the request |
+//! | 6 | Insufficient balance: a wallet or position holds less than the command needs |
+//!
+//! The CLI's own checks fail with a [`CliError`]; failures from the SDK, the
+//! RPC client and the cluster are classified by walking the error's causes.
+//! `code` is the SDK's and the HTTP API's [`ErrorCode`] string wherever one
+//! fits; `command` is `null` when the arguments did not parse. `retryable`
+//! says whether running the same command again may succeed without changing
+//! it — RPC failures, expired blockhashes and a pool that moved past the
+//! slippage limit.
+//!
+//! A swap's pre-flight balance check fails with `INSUFFICIENT_BALANCE` and
+//! `details`: the `account` that fell short — the input token account, or
+//! the wallet for lamports — and the `needed` and `available` atomic units.
+
+use a2a_swap_sdk::{Error as SdkError, ErrorCode};
+use serde_json::{json, Value};
+use solana_client::{
+    client_error::{ClientError, ClientErrorKind},
+    rpc_request::RpcError,
+};
+use solana_sdk::{instruction::InstructionError, transaction::TransactionError};
+
+/// A failure of one of the kinds supervising agents branch on, each with its
+/// own exit code.
+#[derive(Debug)]
+pub enum CliError {
+    /// A flag, argument or value the command cannot use.
+    InvalidInput(String),
+    /// No pool exists for the pair.
+    PoolNotFound(String),
+    /// The trade or deposit would exceed its slippage, price impact or
+    /// max-amount limit at the live reserves.
+    Slippage(String),
+    /// The RPC node could not be reached or failed the request.
+    Rpc(String),
+    /// A wallet or position holds less than the command needs.
+    InsufficientBalance(String),
+}
+
+impl std::fmt::Display for CliError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CliError::InvalidInput(msg)
+            | CliError::PoolNotFound(msg)
+            | CliError::Slippage(msg)
+            | CliError::Rpc(msg)
+            | CliError::InsufficientBalance(msg) => f.write_str(msg),
+        }
+    }
+}
+
+impl std::error::Error for CliError {}
+
+impl CliError {
+    /// Process exit code.
+    pub fn exit_code(&self) -> u8 {
+        match self {
+            CliError::InvalidInput(_)        => 2,
+            CliError::PoolNotFound(_)        => 3,
+            CliError::Slippage(_)            => 4,
+            CliError::Rpc(_)                 => 5,
+            CliError::InsufficientBalance(_) => 6,
+        }
+    }
+
+    /// `--json` error code.
+    pub fn code(&self) -> &'static str {
+        match self {
+            CliError::InvalidInput(_)        => ErrorCode::InvalidArgument.as_str(),
+            CliError::PoolNotFound(_)        => ErrorCode::PoolNotFound.as_str(),
+            CliError::Slippage(_)            => ErrorCode::SlippageExceeded.as_str(),
+            CliError::Rpc(_)                 => ErrorCode::RpcError.as_str(),
+            CliError::InsufficientBalance(_) => ErrorCode::InsufficientBalance.as_str(),
+        }
+    }
+}
+
+/// [`CliError::InvalidInput`] as an `anyhow::Error`, with `format!` arguments
+/// — a drop-in for `anyhow!` where the user gave something unusable.
+macro_rules! input_error {
+    ($($arg:tt)*) => {
+        anyhow::Error::from($crate::error::CliError::InvalidInput(format!($($arg)*)))
+    };
+}
+pub(crate) use input_error;
+
+/// Exit code for any other failure.
+const EXIT_FAILURE: u8 = 1;
+
+/// An account the command needs does not exist.
+const ACCOUNT_NOT_FOUND: &str = "ACCOUNT_NOT_FOUND";
+/// A file or terminal read or write failed.
+const IO_ERROR: &str = "IO_ERROR";
+/// Any other failure: a check in the CLI itself, with `message` saying which.
+const COMMAND_FAILED: &str = "COMMAND_FAILED";
+
+/// How a failure is reported.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Failure {
+    /// `--json` error code.
+    pub code:      &'static str,
+    /// Whether running the same command again may succeed.
+    pub retryable: bool,
+    /// Process exit code.
+    pub exit_code: u8,
+}
+
+impl Failure {
+    const fn new(code: &'static str, retryable: bool, exit_code: u8) -> Self {
+        Self { code, retryable, exit_code }
+    }
+}
+
+/// The `--json` error object for `err`.
+pub fn to_json(command: Option<&str>, err: &anyhow::Error) -> Value {
+    let failure = classify(err);
+    let mut object = json!({
+        "status":    "error",
+        "command":   command,
+        "code":      failure.code,
+        "message":   message(err),
+        "retryable": failure.retryable,
+    });
+    // The pre-flight balance check says which account fell short, and by how much.
+    let short = err.chain().find_map(|cause| match cause.downcast_ref::<SdkError>() {
+        Some(SdkError::InsufficientBalance { account, needed, available }) => Some((account, needed, available)),
+        _ => None,
+    });
+    if let Some((account, needed, available)) = short {
+        object["details"] = json!({
+            "account":   account.to_string(),
+            "needed":    needed,
+            "available": available,
+        });
+    }
+    object
+}
+
+/// `err` and its causes on one line. Solana client errors repeat their
+/// source in their own text, so causes already quoted are left out.
+fn message(err: &anyhow::Error) -> String {
+    let mut message = err.to_string();
+    for cause in err.chain().skip(1) {
+        let text = cause.to_string();
+        if !message.contains(&text) {
+            message = format!("{message}: {text}");
+        }
+    }
+    message.trim_end().to_string()
+}
+
+/// How to report `err`: the first cause that says what kind of failure it is.
+pub fn classify(err: &anyhow::Error) -> Failure {
+    for cause in err.chain() {
+        if let Some(e) = cause.downcast_ref::<CliError>() {
+            let retryable = matches!(e, CliError::Slippage(_) | CliError::Rpc(_));
+            return Failure::new(e.code(), retryable, e.exit_code());
+        }
+        if let Some(e) = cause.downcast_ref::<SdkError>() {
+            return match e {
+                SdkError::Rpc(e) => client_error(e),
+                _ => sdk_error(e.code()),
+            };
+        }
+        if let Some(e) = cause.downcast_ref::<ClientError>() {
+            return client_error(e);
+        }
+        if cause.is::<clap::Error>() {
+            return sdk_error(ErrorCode::InvalidArgument);
+        }
+        if cause.is::<std::io::Error>() {
+            return Failure::new(IO_ERROR, false, EXIT_FAILURE);
+        }
+    }
+    Failure::new(COMMAND_FAILED, false, EXIT_FAILURE)
+}
+
+/// An SDK or program failure, by its shared code.
+fn sdk_error(code: ErrorCode) -> Failure {
+    let exit_code = match code {
+        ErrorCode::InvalidArgument | ErrorCode::AmountBRequired | ErrorCode::AmountBZero => 2,
+        ErrorCode::PoolNotFound                                                         => 3,
+        ErrorCode::SlippageExceeded | ErrorCode::PriceImpactExceeded                    => 4,
+        ErrorCode::RpcError                                                             => 5,
+        ErrorCode::InsufficientBalance                                                  => 6,
+        _                                                                               => EXIT_FAILURE,
+    };
+    let retryable = matches!(code, ErrorCode::RpcError | ErrorCode::SlippageExceeded);
+    Failure::new(code.as_str(), retryable, exit_code)
+}
+
+/// A transaction the cluster rejected is a program error; anything else is
+/// the RPC node's.
+fn client_error(e: &ClientError) -> Failure {
+    // `get_account` reports a missing account as `AccountNotFound: pubkey=…`,
+    // and a failed request as the same followed by `: <cause>`.
+    if let ClientErrorKind::RpcError(RpcError::ForUser(msg)) = e.kind() {
+        if msg.strip_prefix("AccountNotFound: pubkey=").is_some_and(|rest| !rest.contains(':')) {
+            return Failure::new(ACCOUNT_NOT_FOUND, false, EXIT_FAILURE);
+        }
+    }
+    match e.get_transaction_error() {
+        // Expired before it landed: sending again fetches a fresh blockhash.
+        None | Some(TransactionError::BlockhashNotFound) => sdk_error(ErrorCode::RpcError),
+        Some(
+            TransactionError::InsufficientFundsForFee
+            | TransactionError::InsufficientFundsForRent { .. }
+            // Custom error 1 is insufficient funds in both SPL Token and the
+            // System Program; A2A-Swap's own codes start at 6000.
+            | TransactionError::InstructionError(_, InstructionError::Custom(1)),
+        ) => sdk_error(ErrorCode::InsufficientBalance),
+        Some(_) => Failure::new(ErrorCode::ProgramError.as_str(), false, EXIT_FAILURE),
+    }
+}
@@ -0,0 +1,193 @@
+//! Typed decoding of the program's log lines.
+//!
+//! Every instruction ends with one `msg!` summarising what it did (see
+//! `programs/a2a-swap/src/instructions/`). [`parse_logs`] turns a
+//! transaction's log messages back into [`ProgramEvent`]s, and [`swap_fills`]
+//! pairs swap events with the pool each one traded against.
+
+use std::str::FromStr;
+
+use solana_sdk::{bs58, pubkey::Pubkey};
+
+use a2a_swap_core::{
+    ix::{ApproveAndExecute, Swap, SwapAsDelegate},
+    AccountSpec, Instruction as _,
+};
+
+/// One state change reported by the program.
+#[allow(dead_code)] // decoded in full; each command reads the fields it needs
+pub enum ProgramEvent {
+    /// `Pool created: {mint_a}/{mint_b} fee={}bps[ curve=stable amp={}]`
+    PoolCreated { mint_a: Pubkey, mint_b: Pubkey, fee_rate_bps: u16 },
+    /// `Liquidity provided: lp={} a={} b={} auto_compound={}`
+    LiquidityProvided { lp_shares: u64, amount_a: u64, amount_b: u64, auto_compound: bool },
+    /// `Liquidity removed: lp={} a={} b={}`
+    LiquidityRemoved { lp_shares: u64, amount_a: u64, amount_b: u64 },
+    /// `Fees claimed: a={} b={}`, or the zero-LP compound fallback transfer.
+    FeesClaimed { amount_a: u64, amount_b: u64 },
+    /// `Fees auto-compounded: new_lp={} from a={} b={}`
+    FeesCompounded { lp_shares: u64, amount_a: u64, amount_b: u64 },
+    /// `Swap: …`, `Approved swap: …` or `Delegated swap: …`
+    Swap(SwapFill),
+}
+
+/// A filled swap, from `swap`, `approve_and_execute` or `swap_as_delegate`.
+pub struct SwapFill {
+    /// Co-signer for `approve_and_execute`; `None` for a plain swap.
+    #[allow(dead_code)]
+    pub approver:     Option<Pubkey>,
+    pub amount_in:    u64,
+    pub protocol_fee: u64,
+    pub lp_fee:       u64,
+    pub amount_out:   u64,
+    /// Curve price impact; only the plain `swap` instruction logs it.
+    pub impact_bps:   Option<u64>,
+    pub a_to_b:       bool,
+}
+
+/// Value of `key=` in a space-separated `key=value` line.
+fn field<'a>(line: &'a str, key: &str) -> Option<&'a str> {
+    line.split_whitespace()
+        .find_map(|kv| kv.strip_prefix(key)?.strip_prefix('='))
+}
+
+fn num(line: &str, key: &str) -> Option<u64> {
+    field(line, key)?.parse().ok()
+}
+
+fn flag(line: &str, key: &str) -> Option<bool> {
+    field(line, key)?.parse().ok()
+}
+
+/// Decode one log message; `None` for anything the program didn't emit.
+pub fn parse_line(log: &str) -> Option<ProgramEvent> {
+    let line = log.strip_prefix("Program log: ")?;
+
+    if let Some(rest) = line.strip_prefix("Swap: ") {
+        return Some(ProgramEvent::Swap(SwapFill {
+            approver:     None,
+            amount_in:    num(rest, "in")?,
+            protocol_fee: num(rest, "protocol_fee")?,
+            lp_fee:       num(rest, "lp_fee")?,
+            amount_out:   num(rest, "out")?,
+            impact_bps:   num(rest, "impact_bps"),
+            a_to_b:       flag(rest, "a_to_b")?,
+        }));
+    }
+    if let Some(rest) = line.strip_prefix("Approved swap: ") {
+        return Some(ProgramEvent::Swap(SwapFill {
+            approver:     Pubkey::from_str(field(rest, "approver")?).ok(),
+            amount_in:    num(rest, "in")?,
+            protocol_fee: num(rest, "protocol_fee")?,
+            lp_fee:       num(rest, "lp_fee")?,
+            amount_out:   num(rest, "out")?,
+            impact_bps:   None,
+            a_to_b:       flag(rest, "a_to_b")?,
+        }));
+    }
+    if let Some(rest) = line.strip_prefix("Delegated swap: ") {
+        return Some(ProgramEvent::Swap(SwapFill {
+            approver:     None,
+            amount_in:    num(rest, "in")?,
+            protocol_fee: num(rest, "protocol_fee")?,
+            lp_fee:       num(rest, "lp_fee")?,
+            amount_out:   num(rest, "out")?,
+            impact_bps:   None,
+            a_to_b:       flag(rest, "a_to_b")?,
+        }));
+    }
+    if let Some(rest) = line.strip_prefix("Liquidity provided: ") {
+        return Some(ProgramEvent::LiquidityProvided {
+            lp_shares:     num(rest, "lp")?,
+            amount_a:      num(rest, "a")?,
+            amount_b:      num(rest, "b")?,
+            auto_compound: flag(rest, "auto_compound")?,
+        });
+    }
+    if let Some(rest) = line.strip_prefix("Liquidity removed: ") {
+        return Some(ProgramEvent::LiquidityRemoved {
+            lp_shares: num(rest, "lp")?,
+            amount_a:  num(rest, "a")?,
+            amount_b:  num(rest, "b")?,
+        });
+    }
+    if let Some(rest) = line.strip_prefix("Fees auto-compounded: ") {
+        return Some(ProgramEvent::FeesCompounded {
+            lp_shares: num(rest, "new_lp")?,
+            amount_a:  num(rest, "a")?,
+            amount_b:  num(rest, "b")?,
+        });
+    }
+    if let Some(rest) = line.strip_prefix("Fees claimed: ")
+        .or_else(|| line.strip_prefix("Compound yielded 0 LP shares"))
+    {
+        return Some(ProgramEvent::FeesClaimed {
+            amount_a: num(rest, "a")?,
+            amount_b: num(rest, "b")?,
+        });
+    }
+    if let Some(rest) = line.strip_prefix("Pool created: ") {
+        let mut parts = rest.split_whitespace();
+        let (a, b) = parts.next()?.split_once('/')?;
+        let fee = parts.next()?;
+        return Some(ProgramEvent::PoolCreated {
+            mint_a:       Pubkey::from_str(a).ok()?,
+            mint_b:       Pubkey::from_str(b).ok()?,
+            fee_rate_bps: fee.strip_prefix("fee=")?.strip_suffix("bps")?.parse().ok()?,
+        });
+    }
+    None
+}
+
+/// Decode every program event in a transaction's log messages, in order.
+pub fn parse_logs<S: AsRef<str>>(logs: &[S]) -> Vec<ProgramEvent> {
+    logs.iter().filter_map(|l| parse_line(l.as_ref())).collect()
+}
+
+/// Swaps in a `getTransaction` (`"encoding": "json"`) result, each paired
+/// with the pool it traded against.
+///
+/// The pool comes from the top-level `swap` / `approve_and_execute` /
+/// `swap_as_delegate` instructions, matched in order with the swap log lines. Swaps reached
+/// through CPI from another program are not attributed and are skipped.
+pub fn swap_fills(tx: &serde_json::Value, program_id: &Pubkey) -> Vec<(Pubkey, SwapFill)> {
+    let msg = &tx["transaction"]["message"];
+    let loaded = &tx["meta"]["loadedAddresses"];
+    let keys: Vec<Pubkey> = [&msg["accountKeys"], &loaded["writable"], &loaded["readonly"]]
+        .into_iter()
+        .filter_map(|v| v.as_array())
+        .flatten()
+        .filter_map(|k| Pubkey::from_str(k.as_str()?).ok())
+        .collect();
+
+    let pool_index = |accounts: &[AccountSpec]| accounts.iter().position(|a| a.name == "pool");
+
+    // Index of the pool account in each swap-type instruction, from the IDL.
+    let pools = msg["instructions"].as_array().into_iter().flatten().filter_map(|ix| {
+        let program = keys.get(ix["programIdIndex"].as_u64()? as usize)?;
+        if program != program_id {
+            return None;
+        }
+        let data = bs58::decode(ix["data"].as_str()?).into_vec().ok()?;
+        let pool_at = match data.get(..8)? {
+            d if d == Swap::DISCRIMINATOR              => pool_index(Swap::ACCOUNTS)?,
+            d if d == ApproveAndExecute::DISCRIMINATOR => pool_index(ApproveAndExecute::ACCOUNTS)?,
+            d if d == SwapAsDelegate::DISCRIMINATOR    => pool_index(SwapAsDelegate::ACCOUNTS)?,
+            _ => return None,
+        };
+        keys.get(ix["accounts"].get(pool_at)?.as_u64()? as usize).copied()
+    });
+
+    let logs: Vec<&str> = tx["meta"]["logMessages"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(|l| l.as_str())
+        .collect();
+    let fills = parse_logs(&logs).into_iter().filter_map(|e| match e {
+        ProgramEvent::Swap(fill) => Some(fill),
+        _ => None,
+    });
+
+    pools.zip(fills).collect()
+}
@@ -0,0 +1,119 @@
+//! `a2a-swap grid run --config grid.toml` — the grid file.
+//!
+//! ```toml
+//! pair                = "SOL-USDC"   # base-quote: the grid buys and sells SOL for USDC
+//! levels              = 5            # levels on each side of the center
+//! spacing             = 1.0          # percent between neighbouring levels
+//! level_size          = 100000000    # base atomic units per level (0.1 SOL)
+//! max_slippage        = 0.3          # percent below a level's price a fill may land
+//! max_price_impact    = 1.0          # percent per level swap; 0 = no cap
+//! max_levels_per_step = 2            # levels one step may fill; 0 = no limit
+//! interval            = 30           # seconds between steps
+//! # center_price = 0.185             # quote per base atomic unit; default: pool spot
+//! # state        = "grid.state.json" # default: next to this file
+//! # referrer     = "<pubkey>"
+//! ```
+//!
+//! The state file is created on the first run and rewritten after every
+//! step; delete it to re-center the grid.
+
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+use anyhow::{Context, Result};
+use a2a_swap_sdk::strategies::grid::GridConfig;
+use serde::Deserialize;
+use solana_sdk::pubkey::Pubkey;
+
+use crate::error::input_error;
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct GridFile {
+    /// `BASE-QUOTE`, as in `--pair`.
+    pub pair: String,
+    pub levels: u32,
+    /// Percent between neighbouring levels.
+    pub spacing: f64,
+    /// Base atomic units bought or sold per level.
+    pub level_size: u64,
+    /// Percent below a level's price a fill may come out.
+    #[serde(default = "default_max_slippage")]
+    pub max_slippage: f64,
+    /// Percent price-impact cap per level swap; 0 = no cap.
+    #[serde(default)]
+    pub max_price_impact: f64,
+    /// Levels one step may fill; 0 = no limit.
+    #[serde(default)]
+    pub max_levels_per_step: u32,
+    /// Seconds between steps.
+    #[serde(default = "default_interval")]
+    pub interval: u64,
+    /// Center of a fresh grid, quote per base atomic unit; default: pool spot.
+    #[serde(default)]
+    pub center_price: Option<f64>,
+    /// State file; default: `<config stem>.state.json` next to the config.
+    #[serde(default)]
+    pub state: Option<PathBuf>,
+    #[serde(default)]
+    pub referrer: Option<String>,
+}
+
+fn default_max_slippage() -> f64 {
+    0.5
+}
+
+fn default_interval() -> u64 {
+    30
+}
+
+impl GridFile {
+    pub fn load(path: &Path) -> Result<Self> {
+        let text = std::fs::read_to_string(path)
+            .with_context(|| format!("reading grid config {}", path.display()))?;
+        let file: Self = toml::from_str(&text)
+            .with_context(|| format!("parsing grid config {}", path.display()))?;
+        if file.interval == 0 {
+            return Err(input_error!("grid config: interval must be at least 1 second."));
+        }
+        Ok(file)
+    }
+
+    /// Where the grid state lives, relative paths resolved against `config_path`.
+    pub fn state_path(&self, config_path: &Path) -> PathBuf {
+        let dir = config_path.parent().unwrap_or(Path::new(""));
+        match &self.state {
+            Some(p) if p.is_absolute() => p.clone(),
+            Some(p) => dir.join(p),
+            None => {
+                let stem = config_path.file_stem().and_then(|s| s.to_str()).unwrap_or("grid");
+                dir.join(format!("{stem}.state.json"))
+            }
+        }
+    }
+
+    /// The SDK grid parameters for resolved `base` / `quote` mints.
+    pub fn grid_config(&self, base_mint: Pubkey, quote_mint: Pubkey) -> Result<GridConfig> {
+        let referrer = self.referrer.as_deref()
+            .map(|r| Pubkey::from_str(r).map_err(|_| input_error!("grid config: invalid referrer pubkey {r}")))
+            .transpose()?;
+        Ok(GridConfig {
+            base_mint,
+            quote_mint,
+            levels:               self.levels,
+            spacing_bps:          pct_to_bps("spacing", self.spacing)?,
+            level_size:           self.level_size,
+            max_slippage_bps:     pct_to_bps("max_slippage", self.max_slippage)?,
+            max_price_impact_bps: pct_to_bps("max_price_impact", self.max_price_impact)?,
+            max_levels_per_step:  self.max_levels_per_step,
+            referrer,
+        })
+    }
+}
+
+fn pct_to_bps(key: &str, pct: f64) -> Result<u16> {
+    if !(0.0..=100.0).contains(&pct) {
+        return Err(input_error!("grid config: {key} {pct} is out of range. Use 0–100 (percent)."));
+    }
+    Ok((pct * 100.0).round() as u16)
+}
@@ -1,20 +1,48 @@
-use clap::{CommandFactory, Parser, Subcommand};
+use a2a_swap_core::{ix, Account as _, Instruction as _};
+use a2a_swap_sdk::allowlist::{verify_proof as verify_allowlist_proof, AllowlistTree};
+use a2a_swap_sdk::analytics::lots::LotMethod;
+use a2a_swap_sdk::export::ExportFormat;
+use a2a_swap_sdk::A2AErrorCode;
+use a2a_swap_sdk::keystore::{self, Keystore, ScryptParams};
+use a2a_swap_sdk::state::POOL_OPEN_POSITIONS_VERSION;
+use a2a_swap_sdk::signer::RemoteSigner;
+use a2a_swap_sdk::instructions as sdk_ix;
+use a2a_swap_sdk::math::{check_swap_funds as check_funds, LAMPORTS_PER_SIGNATURE};
+use a2a_swap_sdk::rebalancer::{plan_rebalance, TargetRatio};
+use a2a_swap_sdk::state::{parse_idl_account, parse_trader_pass, GovernanceAction, GovernanceState, PAUSE_POOL_CREATION, PAUSE_SWAPS, TOKEN_ACCOUNT_LEN};
+use a2a_swap_sdk::strategies::grid::{self as sdk_grid, GridState};
+use a2a_swap_sdk::tokens::{TokenInfo, TokenRegistry};
+use clap::{CommandFactory, FromArgMatches, Parser, Subcommand};
 use anyhow::{anyhow, Context, Result};
 use serde_json::json;
-use solana_client::rpc_client::RpcClient;
+use solana_client::rpc_client::{GetConfirmedSignaturesForAddress2Config, RpcClient};
 use solana_client::rpc_config::{RpcAccountInfoConfig, RpcProgramAccountsConfig};
 use solana_account_decoder_client_types::UiAccountEncoding;
 use solana_client::rpc_filter::{Memcmp, MemcmpEncodedBytes, RpcFilterType};
+use solana_client::rpc_request::RpcRequest;
 use solana_sdk::{
     commitment_config::CommitmentConfig,
-    hash::hash,
     instruction::{AccountMeta, Instruction},
     pubkey::Pubkey,
-    signature::{read_keypair_file, Keypair, Signer},
+    signature::{Keypair, Signer},
     transaction::Transaction,
 };
 use std::collections::HashMap;
+use std::io::IsTerminal;
+use std::process::ExitCode;
 use std::str::FromStr;
+use std::sync::OnceLock;
+
+mod config;
+mod dashboard;
+mod error;
+mod events;
+mod grid;
+mod monitor;
+mod notify;
+mod telegram;
+
+use error::{input_error, CliError};
 
 /// System program — hardcoded to avoid deprecated solana_sdk::system_program
 const SYSTEM_PROGRAM_ID: &str = "11111111111111111111111111111111";
@@ -75,13 +103,71 @@ fn system_transfer_ix(from: &Pubkey, to: &Pubkey, lamports: u64) -> Result<Instr
     })
 }
 
+// ─── SPL mint helpers (dev bootstrap) ────────────────────────────────────────
+
+/// SPL Token `Mint` account size.
+const MINT_LEN: u64 = 82;
+
+/// SystemProgram.createAccount — allocate `space` bytes owned by `owner`.
+fn create_account_ix(from: &Pubkey, to: &Pubkey, lamports: u64, space: u64, owner: &Pubkey) -> Result<Instruction> {
+    let mut data = vec![0u8, 0, 0, 0];  // CreateAccount instruction index (u32 LE)
+    data.extend_from_slice(&lamports.to_le_bytes());
+    data.extend_from_slice(&space.to_le_bytes());
+    data.extend_from_slice(owner.as_ref());
+    Ok(Instruction {
+        program_id: Pubkey::from_str(SYSTEM_PROGRAM_ID)?,
+        accounts: vec![
+            AccountMeta::new(*from, true),
+            AccountMeta::new(*to, true),
+        ],
+        data,
+    })
+}
+
+/// initializeMint2 (SPL Token ix 20) — no freeze authority.
+fn initialize_mint2_ix(mint: &Pubkey, authority: &Pubkey, decimals: u8) -> Result<Instruction> {
+    let mut data = vec![20u8, decimals];
+    data.extend_from_slice(authority.as_ref());
+    data.push(0);  // COption::None for freeze authority
+    Ok(Instruction {
+        program_id: Pubkey::from_str(TOKEN_PROGRAM_ID)?,
+        accounts: vec![AccountMeta::new(*mint, false)],
+        data,
+    })
+}
+
+/// mintTo (SPL Token ix 7).
+fn mint_to_ix(mint: &Pubkey, dest: &Pubkey, authority: &Pubkey, amount: u64) -> Result<Instruction> {
+    let mut data = vec![7u8];
+    data.extend_from_slice(&amount.to_le_bytes());
+    Ok(Instruction {
+        program_id: Pubkey::from_str(TOKEN_PROGRAM_ID)?,
+        accounts: vec![
+            AccountMeta::new(*mint, false),
+            AccountMeta::new(*dest, false),
+            AccountMeta::new_readonly(*authority, true),
+        ],
+        data,
+    })
+}
+
 // ─── Program constants ────────────────────────────────────────────────────────
 
-const PROGRAM_ID: &str           = "8XJfG4mHqRZjByAd7HxHdEALfB8jVtJVQsdhGEmysTFq";
+const DEFAULT_RPC_URL: &str = "https://api.mainnet-beta.solana.com";
+const DEFAULT_KEYPAIR: &str = "~/.config/solana/id.json";
+/// ComputeBudget program — setComputeUnitPrice for --priority-fee
+const COMPUTE_BUDGET_PROGRAM_ID: &str = "ComputeBudget111111111111111111111111111111";
+
+const PROGRAM_ID: &str           = a2a_swap_core::PROGRAM_ID;
 const POOL_SEED: &[u8]           = b"pool";
 const POOL_AUTHORITY_SEED: &[u8] = b"pool_authority";
 const POSITION_SEED: &[u8]       = b"position";
 const TREASURY_SEED: &[u8]       = b"treasury";
+const RECEIPT_SEED: &[u8]        = b"receipt";
+
+/// Account layout versions — must mirror programs/a2a-swap/src/constants.rs
+const POOL_VERSION: u8     = 8;
+const POSITION_VERSION: u8 = 2;
 
 /// SPL Token program (well-known, never changes)
 const TOKEN_PROGRAM_ID: &str = "TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA";
@@ -89,45 +175,64 @@ const TOKEN_PROGRAM_ID: &str = "TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA";
 const ATA_PROGRAM_ID: &str   = "ATokenGPvbdGVxr1b2hvZbsiqW5xWH25efTNsLJA8knL";
 /// Rent sysvar (well-known, never changes)
 const RENT_SYSVAR_ID: &str   = "SysvarRent111111111111111111111111111111111";
+/// Metaplex Core — owns the optional LP receipt NFTs
+const MPL_CORE_PROGRAM_ID: &str = "CoREENxT6tW1HoK8ypY1SxRMZTcVPm7R94rH4PZNhX7d";
 
 // ─── Fee constants — must mirror programs/a2a-swap/src/constants.rs ──────────
 
-const PROTOCOL_FEE_BPS: u128         = 20;       // 0.020 %
+const PROTOCOL_FEE_BPS: u128         = 20;       // 0.020 % until ProtocolConfig exists
 const PROTOCOL_FEE_DENOMINATOR: u128 = 100_000;
 const BPS_DENOMINATOR: u128          = 10_000;
 
-// ─── Token symbol registry (mainnet-beta) ────────────────────────────────────
+/// Dynamic fees: accumulated price impact halves every this many seconds
+const VOLATILITY_HALF_LIFE_SECS: i64 = 300;
+/// Dynamic fees: bps of accumulated price impact per extra bps of LP fee
+const VOLATILITY_FEE_DIVISOR: u32    = 10;
 
-const KNOWN_TOKENS: &[(&str, &str)] = &[
-    ("SOL",  "So11111111111111111111111111111111111111112"),
-    ("USDC", "EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v"),
-    ("USDT", "Es9vMFrzaCERmJfrF4H2FYD4KCoNkY11McCe8BenwNYB"),
-];
+/// Price impact above which `simulate` / `convert` print a warning (percent).
+const PRICE_IMPACT_WARN_PCT: f64 = 1.0;
 
-/// Resolve a symbol (SOL, USDC, USDT) or raw base-58 mint address to a Pubkey.
-fn resolve_mint(symbol_or_address: &str) -> Result<Pubkey> {
-    let upper = symbol_or_address.to_uppercase();
-    for (sym, addr) in KNOWN_TOKENS {
-        if upper == *sym {
-            return Ok(Pubkey::from_str(addr)?);
+// ─── Token symbol registry ────────────────────────────────────────────────────
+
+static TOKENS: OnceLock<TokenRegistry> = OnceLock::new();
+
+/// Built-in symbols (SOL, USDC, USDT) plus the user token list from
+/// `a2a-swap tokens add/import`, which overrides them.
+fn tokens() -> &'static TokenRegistry {
+    TOKENS.get_or_init(|| {
+        let mut registry = TokenRegistry::builtin();
+        match load_user_tokens() {
+            Ok(user) => registry.extend(user),
+            Err(e)   => eprintln!("warning: ignoring {}: {e:#}", config::tokens_path().display()),
         }
+        registry
+    })
+}
+
+/// The user token list; a missing file is an empty list.
+fn load_user_tokens() -> Result<TokenRegistry> {
+    match std::fs::read_to_string(config::tokens_path()) {
+        Ok(text) => Ok(TokenRegistry::from_json(&text)?),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(TokenRegistry::new()),
+        Err(e) => Err(e.into()),
     }
-    Pubkey::from_str(symbol_or_address)
-        .map_err(|_| anyhow!(
-            "Unknown token '{}'. Use a built-in symbol ({}) or a base-58 mint address.",
-            symbol_or_address,
-            KNOWN_TOKENS.iter().map(|(s, _)| *s).collect::<Vec<_>>().join(", ")
-        ))
+}
+
+/// Resolve a token symbol or raw base-58 mint address to a Pubkey.
+fn resolve_mint(symbol_or_address: &str) -> Result<Pubkey> {
+    tokens().resolve_mint(symbol_or_address).map_err(|_| input_error!(
+        "Unknown token '{symbol_or_address}'. Use a known symbol (see `a2a-swap tokens list`) \
+         or a base-58 mint address.\n  \
+         Add a symbol with: a2a-swap tokens add <SYMBOL> <MINT> --decimals <N>"
+    ))
 }
 
 /// Reverse-lookup: mint address → symbol, or shortened address for unknowns.
 fn resolve_symbol(mint: &Pubkey) -> String {
-    let addr = mint.to_string();
-    for (sym, known) in KNOWN_TOKENS {
-        if addr == *known {
-            return sym.to_string();
-        }
+    if let Some(token) = tokens().by_mint(mint) {
+        return token.symbol.clone();
     }
+    let addr = mint.to_string();
     format!("{}…{}", &addr[..4], &addr[addr.len() - 4..])
 }
 
@@ -140,42 +245,81 @@ fn expand_home(path: &str) -> String {
     }
 }
 
+/// Load a plain id.json keypair, or a keystore from `a2a-swap key import`
+/// (asking for its passphrase unless A2A_KEYSTORE_PASSPHRASE is set).
 fn load_keypair(path: &str) -> Result<solana_sdk::signature::Keypair> {
     let expanded = expand_home(path);
-    read_keypair_file(&expanded)
-        .map_err(|e| anyhow!(
-            "Cannot load keypair from '{}': {}\n  \
-             Set A2A_KEYPAIR or pass --keypair to specify a different path.",
-            expanded, e
-        ))
+    let contents = std::fs::read_to_string(&expanded)
+        .map_err(|e| keypair_error(&expanded, e))?;
+    if !keystore::is_keystore(&contents) {
+        return keystore::keypair_from_json(&contents, None).map_err(|e| keypair_error(&expanded, e));
+    }
+    let sealed     = Keystore::from_json(&contents).map_err(|e| keypair_error(&expanded, e))?;
+    let passphrase = read_passphrase(&format!("Passphrase for {expanded} ({}): ", sealed.pubkey), false)?;
+    sealed.decrypt(&passphrase)
+        .map_err(|e| anyhow!("Cannot unlock keystore '{expanded}': {e}"))
 }
 
-/// Anchor discriminator: first 8 bytes of SHA-256(`"{namespace}:{name}"`).
-/// Use `"global"` for instructions, `"account"` for account types.
-fn anchor_disc(namespace: &str, name: &str) -> [u8; 8] {
-    let h = hash(format!("{namespace}:{name}").as_bytes());
-    let mut d = [0u8; 8];
-    d.copy_from_slice(&h.to_bytes()[..8]);
-    d
+/// Public key of a keypair or keystore file, without asking for a passphrase.
+fn load_pubkey(path: &str) -> Result<Pubkey> {
+    let expanded = expand_home(path);
+    keystore::read_pubkey(&expanded).map_err(|e| keypair_error(&expanded, e))
 }
 
-// ─── Byte-slice helpers ───────────────────────────────────────────────────────
+fn keypair_error(path: &str, e: impl std::fmt::Display) -> anyhow::Error {
+    anyhow!(
+        "Cannot load keypair from '{}': {}\n  \
+         Set A2A_KEYPAIR or pass --keypair to specify a different path.",
+        path, e
+    )
+}
 
-fn read_pubkey(data: &[u8], offset: usize) -> Result<Pubkey> {
-    let b: [u8; 32] = data[offset..offset + 32]
-        .try_into()
-        .map_err(|_| anyhow!("slice error at offset {offset} (pubkey)"))?;
-    Ok(Pubkey::from(b))
+/// Keystore passphrase from A2A_KEYSTORE_PASSPHRASE, else typed at the
+/// terminal (twice with `confirm`, for a new keystore).
+fn read_passphrase(prompt: &str, confirm: bool) -> Result<String> {
+    if let Some(passphrase) = keystore::passphrase_from_env() {
+        return Ok(passphrase);
+    }
+    if !std::io::stdin().is_terminal() {
+        return Err(input_error!(
+            "A keystore passphrase is needed but stdin is not a terminal.\n  \
+             Set {}.", keystore::PASSPHRASE_ENV
+        ));
+    }
+    let passphrase = prompt_hidden(prompt)?;
+    if confirm {
+        if passphrase.is_empty() {
+            return Err(input_error!("Empty passphrase"));
+        }
+        if prompt_hidden("Repeat passphrase: ")? != passphrase {
+            return Err(input_error!("Passphrases do not match"));
+        }
+    }
+    Ok(passphrase)
 }
 
-fn read_u16(data: &[u8], offset: usize) -> Result<u16> {
-    Ok(u16::from_le_bytes(
-        data[offset..offset + 2]
-            .try_into()
-            .map_err(|_| anyhow!("slice error at offset {offset} (u16)"))?,
-    ))
+/// Read a line from the terminal with echo off. Without `stty` (Windows) the
+/// input stays visible.
+fn prompt_hidden(prompt: &str) -> Result<String> {
+    use std::io::Write;
+    use std::process::{Command, Stdio};
+    eprint!("{prompt}");
+    std::io::stderr().flush().ok();
+    let stty = |arg: &str| Command::new("stty").arg(arg).stdin(Stdio::inherit()).status()
+        .is_ok_and(|s| s.success());
+    let hidden = stty("-echo");
+    let mut line = String::new();
+    let read = std::io::stdin().read_line(&mut line);
+    if hidden {
+        stty("echo");
+        eprintln!();
+    }
+    read?;
+    Ok(line.trim_end_matches(['\r', '\n']).to_string())
 }
 
+// ─── Byte-slice helpers ───────────────────────────────────────────────────────
+
 fn read_u64(data: &[u8], offset: usize) -> Result<u64> {
     Ok(u64::from_le_bytes(
         data[offset..offset + 8]
@@ -184,15 +328,59 @@ fn read_u64(data: &[u8], offset: usize) -> Result<u64> {
     ))
 }
 
-fn read_u128(data: &[u8], offset: usize) -> Result<u128> {
-    Ok(u128::from_le_bytes(
-        data[offset..offset + 16]
-            .try_into()
-            .map_err(|_| anyhow!("slice error at offset {offset} (u128)"))?,
-    ))
+// ─── Pool state ───────────────────────────────────────────────────────────────
+
+/// Swap invariant of a pool — mirrors the program's `CurveKind`.
+#[derive(Clone, Copy)]
+enum CurveKind {
+    ConstantProduct,
+    StableSwap { amp: u64 },
 }
 
-// ─── Pool state ───────────────────────────────────────────────────────────────
+impl From<a2a_swap_core::CurveKind> for CurveKind {
+    fn from(c: a2a_swap_core::CurveKind) -> Self {
+        match c {
+            a2a_swap_core::CurveKind::ConstantProduct     => CurveKind::ConstantProduct,
+            a2a_swap_core::CurveKind::StableSwap { amp } => CurveKind::StableSwap { amp },
+        }
+    }
+}
+
+impl From<CurveKind> for a2a_swap_core::CurveKind {
+    fn from(c: CurveKind) -> Self {
+        match c {
+            CurveKind::ConstantProduct     => a2a_swap_core::CurveKind::ConstantProduct,
+            CurveKind::StableSwap { amp } => a2a_swap_core::CurveKind::StableSwap { amp },
+        }
+    }
+}
+
+impl CurveKind {
+    fn label(self) -> String {
+        match self {
+            CurveKind::ConstantProduct     => "constant-product".into(),
+            CurveKind::StableSwap { amp } => format!("stable (A={amp})"),
+        }
+    }
+}
+
+/// `{"kind": …, "amp": …}` — same shape as the SDK's serde encoding.
+fn curve_json(curve: CurveKind) -> serde_json::Value {
+    match curve {
+        CurveKind::ConstantProduct     => json!({ "kind": "constant_product" }),
+        CurveKind::StableSwap { amp } => json!({ "kind": "stable_swap", "amp": amp }),
+    }
+}
+
+/// `{"min_fee_bps": …, …}` — same shape as the SDK's serde encoding.
+fn dynamic_fee_json(df: &DynamicFee) -> serde_json::Value {
+    json!({
+        "min_fee_bps":    df.min_fee_bps,
+        "max_fee_bps":    df.max_fee_bps,
+        "volatility_bps": df.volatility_bps,
+        "last_update_ts": df.last_update_ts,
+    })
+}
 
 struct PoolState {
     token_a_mint:        Pubkey,
@@ -203,30 +391,128 @@ struct PoolState {
     fee_rate_bps:        u16,
     fee_growth_global_a: u128,
     fee_growth_global_b: u128,
+    curve:               CurveKind,
+    dynamic_fee:         DynamicFee,
+    /// Lifetime swap input / LP fees per side; zero until `migrate_pool`
+    volume_a:            u64,
+    volume_b:            u64,
+    fees_collected_a:    u64,
+    fees_collected_b:    u64,
+    /// Layout version; 0 for pools from before versioning
+    version:             u8,
+    /// Tracked reserves swaps price against, once `reserves_tracked`
+    reserve_a:           u64,
+    reserve_b:           u64,
+    /// Most a slot's swaps may move the price, net (bps); 0 = no circuit breaker
+    max_move_bps:        u16,
+    /// Wallet that may close the pool once empty; default when not recorded
+    creator:             Pubkey,
+    /// Merkle root of the traders allowed to swap; all zeros = open pool
+    allowlist_root:      [u8; 32],
+    /// Mint a trader must hold to swap; default = ungated
+    gate_mint:           Pubkey,
+    /// Positions not yet closed; u64::MAX for migrated pools that predate the count
+    open_positions:      u64,
+    /// Whether reserve_a / reserve_b hold the reserves; false until a
+    /// migrated pool is first touched
+    reserves_tracked:    bool,
 }
 
-/// Deserialize a Pool account (212 bytes).
-///
-/// Layout after 8-byte Anchor discriminator:
-///   authority(32) authority_bump(1) token_a_mint(32) token_b_mint(32)
-///   token_a_vault(32) token_b_vault(32) lp_supply(8) fee_rate_bps(2)
-///   fee_growth_global_a(16) fee_growth_global_b(16) bump(1)
+/// Volatility-driven LP fee bounds; `max_fee_bps == 0` means disabled.
+#[derive(Default)]
+struct DynamicFee {
+    min_fee_bps:    u16,
+    max_fee_bps:    u16,
+    volatility_bps: u32,
+    last_update_ts: i64,
+}
+
+impl PoolState {
+    /// Tracked `(reserve_a, reserve_b)`, or the given vault balances while
+    /// the pool's reserves are untracked. Mirrors `Pool::reserves`.
+    fn reserves(&self, vault_a: u64, vault_b: u64) -> (u64, u64) {
+        if self.reserves_tracked {
+            (self.reserve_a, self.reserve_b)
+        } else {
+            (vault_a, vault_b)
+        }
+    }
+
+    /// Whether only allowlisted traders may swap.
+    fn is_permissioned(&self) -> bool {
+        self.allowlist_root != [0; 32]
+    }
+
+    /// Whether traders must hold `gate_mint` to swap.
+    fn is_gated(&self) -> bool {
+        self.gate_mint != Pubkey::default()
+    }
+
+    /// LP fee a swap pays right now. Mirrors the program's `effective_fee_bps`:
+    /// the base fee plus accumulated price impact / 10 (halved every 5 min),
+    /// clamped to the dynamic fee bounds.
+    fn effective_fee_bps(&self) -> u16 {
+        let df = &self.dynamic_fee;
+        if df.max_fee_bps == 0 {
+            return self.fee_rate_bps;
+        }
+        let now = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map_or(0, |d| d.as_secs() as i64);
+        let half_lives = now.saturating_sub(df.last_update_ts).max(0) / VOLATILITY_HALF_LIFE_SECS;
+        let volatility = df.volatility_bps.checked_shr(half_lives.min(32) as u32).unwrap_or(0);
+        let fee = (self.fee_rate_bps as u32).saturating_add(volatility / VOLATILITY_FEE_DIVISOR);
+        fee.clamp(df.min_fee_bps as u32, df.max_fee_bps as u32) as u16
+    }
+}
+
+/// Deserialize a Pool account (405 bytes; 212, 221, 237, 269, 270, 286, 300, 332, 364,
+/// 396 or 404 for pools not yet migrated) through the IDL-generated layout. Fields are only ever appended,
+/// so accounts written by a newer program version parse too, with the fields
+/// this CLI knows about.
 fn parse_pool(data: &[u8]) -> Result<PoolState> {
     if data.len() < 212 {
         return Err(anyhow!(
-            "Pool account is {} bytes; expected 212 — may not be an A2A-Swap pool.",
-            data.len()
+            "Pool account is {} bytes; expected {} — may not be an A2A-Swap pool.",
+            data.len(),
+            a2a_swap_core::Pool::LEN
         ));
     }
+    let p = a2a_swap_core::Pool::from_account_data(data)?;
     Ok(PoolState {
-        token_a_mint:        read_pubkey(data, 41)?,
-        token_b_mint:        read_pubkey(data, 73)?,
-        token_a_vault:       read_pubkey(data, 105)?,
-        token_b_vault:       read_pubkey(data, 137)?,
-        lp_supply:           read_u64(data, 169)?,
-        fee_rate_bps:        read_u16(data, 177)?,
-        fee_growth_global_a: read_u128(data, 179)?,
-        fee_growth_global_b: read_u128(data, 195)?,
+        token_a_mint:        p.token_a_mint.into(),
+        token_b_mint:        p.token_b_mint.into(),
+        token_a_vault:       p.token_a_vault.into(),
+        token_b_vault:       p.token_b_vault.into(),
+        lp_supply:           p.lp_supply,
+        fee_rate_bps:        p.fee_rate_bps,
+        fee_growth_global_a: p.fee_growth_global_a,
+        fee_growth_global_b: p.fee_growth_global_b,
+        curve:               p.curve.into(),
+        dynamic_fee:         DynamicFee {
+            min_fee_bps:    p.dynamic_fee.min_fee_bps,
+            max_fee_bps:    p.dynamic_fee.max_fee_bps,
+            volatility_bps: p.dynamic_fee.volatility_bps,
+            last_update_ts: p.dynamic_fee.last_update_ts,
+        },
+        volume_a:            p.volume_a,
+        volume_b:            p.volume_b,
+        fees_collected_a:    p.fees_collected_a,
+        fees_collected_b:    p.fees_collected_b,
+        version:             p.version,
+        reserve_a:           p.reserve_a,
+        reserve_b:           p.reserve_b,
+        max_move_bps:        p.circuit_breaker.max_move_bps,
+        creator:             p.creator.into(),
+        allowlist_root:      p.allowlist_root,
+        gate_mint:           p.gate_mint.into(),
+        open_positions:      p.open_positions,
+        // Older layouts read as `migrate_pool` will set it
+        reserves_tracked:    if data.len() > a2a_swap_sdk::state::POOL_OPEN_POSITIONS_LEN {
+            p.reserves_tracked
+        } else {
+            p.reserve_a != 0 || p.reserve_b != 0 || p.lp_supply == 0
+        },
     })
 }
 
@@ -238,6 +524,57 @@ fn parse_token_amount(data: &[u8]) -> Result<u64> {
     read_u64(data, 64)
 }
 
+/// `(reserve_a, reserve_b)` the program prices against ([`PoolState::reserves`]);
+/// the vaults are only read while the pool's reserves are untracked.
+fn pool_reserves(client: &RpcClient, pool: &PoolState) -> Result<(u64, u64)> {
+    if pool.reserves_tracked {
+        return Ok((pool.reserve_a, pool.reserve_b));
+    }
+    let ra = parse_token_amount(&client.get_account(&pool.token_a_vault).context("fetch vault_a")?.data)?;
+    let rb = parse_token_amount(&client.get_account(&pool.token_b_vault).context("fetch vault_b")?.data)?;
+    Ok(pool.reserves(ra, rb))
+}
+
+/// Live protocol fee from the `ProtocolConfig` at the treasury PDA, or the
+/// default `PROTOCOL_FEE_BPS` while that account has not been created.
+fn fetch_protocol_fee(client: &RpcClient, program_id: &Pubkey) -> Result<u16> {
+    let (treasury, _) = Pubkey::find_program_address(&[TREASURY_SEED], program_id);
+    let account = client
+        .get_account_with_commitment(&treasury, client.commitment())
+        .context("fetch protocol config")?
+        .value;
+    match account {
+        Some(acc) if acc.data.len() >= a2a_swap_sdk::state::PROTOCOL_CONFIG_LEGACY_LEN => {
+            Ok(a2a_swap_core::ProtocolConfig::from_account_data(&acc.data)?.protocol_fee_bps)
+        }
+        Some(acc) if !acc.data.is_empty() => {
+            Err(anyhow!("ProtocolConfig account too short: {} bytes", acc.data.len()))
+        }
+        _ => Ok(PROTOCOL_FEE_BPS as u16),
+    }
+}
+
+/// Inclusive `fee_rate_bps` range governance allows for new pools; 1–100
+/// while the `ProtocolConfig` is missing or sets no bounds.
+fn fetch_pool_fee_bounds(client: &RpcClient, program_id: &Pubkey) -> Result<(u16, u16)> {
+    let (treasury, _) = Pubkey::find_program_address(&[TREASURY_SEED], program_id);
+    let account = client
+        .get_account_with_commitment(&treasury, client.commitment())
+        .context("fetch protocol config")?
+        .value;
+    match account {
+        Some(acc) if !acc.data.is_empty() => {
+            Ok(a2a_swap_sdk::state::parse_protocol_config(&acc.data)?.pool_fee_bounds())
+        }
+        _ => Ok((a2a_swap_sdk::state::MIN_POOL_FEE_BPS, a2a_swap_sdk::state::MAX_POOL_FEE_BPS)),
+    }
+}
+
+/// Format a protocol fee (out of 100_000) as a percentage, e.g. `0.020%`.
+fn protocol_fee_pct(protocol_fee_bps: u16) -> String {
+    format!("{:.3}%", protocol_fee_bps as f64 * 100.0 / PROTOCOL_FEE_DENOMINATOR as f64)
+}
+
 /// Derive the ATA address for `wallet` holding `mint`.
 fn derive_ata(wallet: &Pubkey, mint: &Pubkey) -> Pubkey {
     let ata_prog   = Pubkey::from_str(ATA_PROGRAM_ID).expect("valid");
@@ -261,26 +598,71 @@ struct PositionState {
     fees_owed_b:             u64,
     auto_compound:           bool,
     compound_threshold:      u64,
+    /// Layout version; 0 for positions from before versioning
+    version:                 u8,
+    /// Program holding the position as collateral, if locked
+    locked_by:               Option<Pubkey>,
 }
 
-/// Deserialize a Position account (138 bytes).
+/// Deserialize a Position account (171 bytes; 138 or 139 until `migrate_position`).
+/// Like `parse_pool`, tolerates fields appended by newer program versions.
 fn parse_position(data: &[u8]) -> Result<PositionState> {
     if data.len() < 138 {
-        return Err(anyhow!("Position account is {} bytes; expected 138.", data.len()));
+        return Err(anyhow!(
+            "Position account is {} bytes; expected {}.",
+            data.len(),
+            a2a_swap_core::Position::LEN
+        ));
     }
+    let p = a2a_swap_core::Position::from_account_data(data)?;
     Ok(PositionState {
-        owner:                   read_pubkey(data, 8)?,
-        pool:                    read_pubkey(data, 40)?,
-        lp_shares:               read_u64(data, 72)?,
-        fee_growth_checkpoint_a: read_u128(data, 80)?,
-        fee_growth_checkpoint_b: read_u128(data, 96)?,
-        fees_owed_a:             read_u64(data, 112)?,
-        fees_owed_b:             read_u64(data, 120)?,
-        auto_compound:           data[128] != 0,
-        compound_threshold:      read_u64(data, 129)?,
+        owner:                   p.owner.into(),
+        pool:                    p.pool.into(),
+        lp_shares:               p.lp_shares,
+        fee_growth_checkpoint_a: p.fee_growth_checkpoint_a,
+        fee_growth_checkpoint_b: p.fee_growth_checkpoint_b,
+        fees_owed_a:             p.fees_owed_a,
+        fees_owed_b:             p.fees_owed_b,
+        auto_compound:           p.auto_compound,
+        compound_threshold:      p.compound_threshold,
+        version:                 p.version,
+        locked_by:               Some(Pubkey::from(p.locked_by)).filter(|k| *k != Pubkey::default()),
     })
 }
 
+/// `migrate_pool` / `migrate_position` instructions for whichever of the pool
+/// and position predate the current layout. The program can't load them
+/// until they are migrated, so these go ahead of the instruction that uses them.
+fn migration_ixs(
+    payer: &Pubkey,
+    pool_pda: &Pubkey,
+    pool: &PoolState,
+    position: Option<(&Pubkey, &PositionState)>,
+) -> Result<Vec<Instruction>> {
+    let program_id = Pubkey::from_str(PROGRAM_ID)?;
+    let migrate = |data: Vec<u8>, account: &Pubkey| -> Result<Instruction> {
+        Ok(Instruction {
+            program_id,
+            data,
+            accounts: vec![
+                AccountMeta::new(*payer, true),
+                AccountMeta::new(*account, false),
+                AccountMeta::new_readonly(Pubkey::from_str(SYSTEM_PROGRAM_ID)?, false),
+            ],
+        })
+    };
+    let mut ixs = Vec::new();
+    if pool.version < POOL_VERSION {
+        ixs.push(migrate(ix::MigratePool.data(), pool_pda)?);
+    }
+    if let Some((position_pda, pos)) = position {
+        if pos.version < POSITION_VERSION {
+            ixs.push(migrate(ix::MigratePosition.data(), position_pda)?);
+        }
+    }
+    Ok(ixs)
+}
+
 /// Compute total unclaimed fees (stored + accrued-since-last-sync).
 ///
 /// Mirrors `accrue_fees` in the on-chain program:
@@ -294,16 +676,66 @@ fn pending_fees(pos: &PositionState, pool: &PoolState) -> (u64, u64) {
     (pos.fees_owed_a.saturating_add(pa), pos.fees_owed_b.saturating_add(pb))
 }
 
+/// Impermanent loss vs. holding after the price moves from `entry` to
+/// `current`: `2·√r / (1 + r) − 1`, `r = current / entry`. Always ≤ 0.
+fn impermanent_loss(entry: f64, current: f64) -> f64 {
+    if entry <= 0.0 || current <= 0.0 {
+        return 0.0;
+    }
+    let r = current / entry;
+    2.0 * r.sqrt() / (1.0 + r) - 1.0
+}
+
+/// Deposit snapshot `(deposited_a, deposited_b)` rebuilt from the program
+/// logs of every transaction that touched `position`, oldest first.
+/// Deposits before the position was last emptied are dropped.
+fn position_entry(client: &RpcClient, position: &Pubkey) -> Result<Option<(u64, u64)>> {
+    let sigs = client.get_signatures_for_address(position)?;
+    let (mut lp, mut dep_a, mut dep_b) = (0u64, 0u64, 0u64);
+    for sig in sigs.iter().rev().filter(|s| s.err.is_none()) {
+        let tx: serde_json::Value = client.send(
+            RpcRequest::GetTransaction,
+            json!([
+                sig.signature,
+                { "encoding": "json", "commitment": "confirmed", "maxSupportedTransactionVersion": 0 },
+            ]),
+        )?;
+        let logs: Vec<&str> = tx["meta"]["logMessages"].as_array().into_iter().flatten()
+            .filter_map(|l| l.as_str())
+            .collect();
+        for event in events::parse_logs(&logs) {
+            match event {
+                events::ProgramEvent::LiquidityProvided { lp_shares, amount_a, amount_b, .. } => {
+                    lp    = lp.saturating_add(lp_shares);
+                    dep_a = dep_a.saturating_add(amount_a);
+                    dep_b = dep_b.saturating_add(amount_b);
+                }
+                events::ProgramEvent::LiquidityRemoved { lp_shares, .. } => {
+                    lp = lp.saturating_sub(lp_shares);
+                    if lp == 0 {
+                        (dep_a, dep_b) = (0, 0);
+                    }
+                }
+                events::ProgramEvent::FeesCompounded { lp_shares, .. } => {
+                    lp = lp.saturating_add(lp_shares);
+                }
+                _ => {}
+            }
+        }
+    }
+    Ok((dep_a > 0 && dep_b > 0).then_some((dep_a, dep_b)))
+}
+
 /// Fetch all Position accounts owned by `agent` via `get_program_accounts_with_config`.
 fn get_agent_positions(
     client: &RpcClient,
     agent: &Pubkey,
     program_id: &Pubkey,
 ) -> Result<Vec<(Pubkey, PositionState)>> {
-    let disc = anchor_disc("account", "Position");
+    let disc = a2a_swap_core::Position::DISCRIMINATOR;
     let config = RpcProgramAccountsConfig {
         filters: Some(vec![
-            RpcFilterType::DataSize(138),
+            // No size filter: positions are 139 bytes, or 138 until `migrate_position` runs.
             RpcFilterType::Memcmp(Memcmp::new(0, MemcmpEncodedBytes::Bytes(disc.to_vec()))),
             RpcFilterType::Memcmp(Memcmp::new(8, MemcmpEncodedBytes::Bytes(agent.to_bytes().to_vec()))),
         ]),
@@ -352,6 +784,106 @@ fn pool_label(key: &Pubkey, pool_map: &HashMap<Pubkey, PoolState>) -> String {
     }
 }
 
+// ─── Instruction builders ─────────────────────────────────────────────────────
+
+/// `initialize_pool` — `vault_a` / `vault_b` must be fresh keypairs that co-sign.
+fn initialize_pool_ix(
+    payer: &Pubkey,
+    mint_a: &Pubkey,
+    mint_b: &Pubkey,
+    vault_a: &Pubkey,
+    vault_b: &Pubkey,
+    fee_rate_bps: u16,
+    curve: CurveKind,
+) -> Result<Instruction> {
+    let program_id = Pubkey::from_str(PROGRAM_ID)?;
+    let (pool_pda, _) = Pubkey::find_program_address(
+        &[POOL_SEED, mint_a.as_ref(), mint_b.as_ref()], &program_id);
+    let (pool_auth, _) = Pubkey::find_program_address(
+        &[POOL_AUTHORITY_SEED, pool_pda.as_ref()], &program_id);
+    let (treasury, _) = Pubkey::find_program_address(&[TREASURY_SEED], &program_id);
+
+    let ix_data = ix::InitializePool { fee_rate_bps, curve: curve.into() }.data();
+
+    Ok(Instruction {
+        program_id,
+        data: ix_data,
+        accounts: vec![
+            AccountMeta::new(*payer,                  true),
+            AccountMeta::new_readonly(*mint_a,        false),
+            AccountMeta::new_readonly(*mint_b,        false),
+            AccountMeta::new(pool_pda,                false),
+            AccountMeta::new_readonly(pool_auth,      false),
+            AccountMeta::new(*vault_a,                true),
+            AccountMeta::new(*vault_b,                true),
+            AccountMeta::new_readonly(Pubkey::from_str(TOKEN_PROGRAM_ID)?,  false),
+            AccountMeta::new_readonly(Pubkey::from_str(SYSTEM_PROGRAM_ID)?, false),
+            AccountMeta::new_readonly(Pubkey::from_str(RENT_SYSVAR_ID)?,    false),
+            AccountMeta::new_readonly(treasury,       false), // fee bounds, pause
+        ],
+    })
+}
+
+/// `provide_liquidity` with `min_lp = 0`; `ata_a` / `ata_b` follow pool ordering.
+#[allow(clippy::too_many_arguments)]
+fn provide_liquidity_ix(
+    payer: &Pubkey,
+    pool_pda: &Pubkey,
+    vault_a: &Pubkey,
+    vault_b: &Pubkey,
+    ata_a: &Pubkey,
+    ata_b: &Pubkey,
+    amount_a: u64,
+    amount_b: u64,
+    auto_compound: bool,
+    compound_threshold: u64,
+) -> Result<Instruction> {
+    let program_id = Pubkey::from_str(PROGRAM_ID)?;
+    let (pool_auth, _) = Pubkey::find_program_address(
+        &[POOL_AUTHORITY_SEED, pool_pda.as_ref()], &program_id);
+    let (position_pda, _) = Pubkey::find_program_address(
+        &[POSITION_SEED, pool_pda.as_ref(), payer.as_ref()], &program_id);
+
+    let ix_data = ix::ProvideLiquidity {
+        amount_a,
+        amount_b,
+        min_lp: 0,
+        auto_compound,
+        compound_threshold,
+    }
+    .data();
+
+    Ok(Instruction {
+        program_id,
+        data: ix_data,
+        accounts: vec![
+            AccountMeta::new(*payer,                  true),
+            AccountMeta::new(*pool_pda,               false),
+            AccountMeta::new_readonly(pool_auth,      false),
+            AccountMeta::new(position_pda,            false),
+            AccountMeta::new(*vault_a,                false),
+            AccountMeta::new(*vault_b,                false),
+            AccountMeta::new(*ata_a,                  false),
+            AccountMeta::new(*ata_b,                  false),
+            AccountMeta::new_readonly(Pubkey::from_str(TOKEN_PROGRAM_ID)?,  false),
+            AccountMeta::new_readonly(Pubkey::from_str(SYSTEM_PROGRAM_ID)?, false),
+            AccountMeta::new_readonly(Pubkey::from_str(RENT_SYSVAR_ID)?,    false),
+        ],
+    })
+}
+
+/// LP receipt asset for a position, plus the trailing `[receipt, mpl_core]`
+/// accounts that opt `provide_liquidity` / `remove_liquidity` into minting
+/// and burning it.
+fn receipt_accounts(position: &Pubkey, program_id: &Pubkey) -> Result<(Pubkey, [AccountMeta; 2])> {
+    let (receipt, _) = Pubkey::find_program_address(
+        &[RECEIPT_SEED, position.as_ref()], program_id);
+    Ok((receipt, [
+        AccountMeta::new(receipt, false),
+        AccountMeta::new_readonly(Pubkey::from_str(MPL_CORE_PROGRAM_ID)?, false),
+    ]))
+}
+
 // ─── Swap math ────────────────────────────────────────────────────────────────
 
 /// Try both PDA orderings to locate a pool for a token pair.
@@ -372,7 +904,7 @@ fn find_pool(
             &[POOL_SEED, first.as_ref(), second.as_ref()],
             program_id,
         );
-        if let Ok(acct) = client.get_account(&pda) {
+        if let Some(acct) = fetch_account(client, &pda)? {
             let pool = parse_pool(&acct.data)?;
             let (auth, _) = Pubkey::find_program_address(
                 &[POOL_AUTHORITY_SEED, pda.as_ref()],
@@ -381,16 +913,199 @@ fn find_pool(
             return Ok((pda, auth, pool, a_to_b));
         }
     }
-    Err(anyhow!(
+    Err(no_pool_error())
+}
+
+/// `address`'s account, `None` when it does not exist — unlike
+/// `RpcClient::get_account`, which reports a failed request the same way.
+fn fetch_account(client: &RpcClient, address: &Pubkey) -> Result<Option<solana_sdk::account::Account>> {
+    Ok(client.get_account_with_commitment(address, client.commitment())?.value)
+}
+
+fn no_pool_error() -> anyhow::Error {
+    CliError::PoolNotFound(
         "No pool found for this token pair.\n  \
          Run `a2a-swap create-pool --pair <A>-<B> --initial-price <P>` to create one,\n  \
-         or check that --in / --out use the correct symbols or mint addresses."
-    ))
+         or check that --in / --out use the correct symbols or mint addresses.".to_string()
+    ).into()
+}
+
+// ─── Routing ──────────────────────────────────────────────────────────────────
+
+/// Tokens `simulate` tries as the middle of a two-hop route.
+const ROUTE_INTERMEDIATES: [&str; 3] = ["SOL", "USDC", "USDT"];
+
+/// One pool of a route, oriented in the direction it is traded.
+#[derive(Clone, Copy)]
+struct RouteHop {
+    pool:         Pubkey,
+    a_to_b:       bool,
+    mint_in:      Pubkey,
+    mint_out:     Pubkey,
+    reserve_in:   u64,
+    reserve_out:  u64,
+    fee_rate_bps: u16,
+    curve:        CurveKind,
+}
+
+/// A direct pool, or two pools through an intermediate token.
+struct Route {
+    hops: Vec<RouteHop>,
+}
+
+/// A route simulated end to end.
+struct RouteQuote {
+    /// One simulation per hop; each hop's `estimated_out` is the next hop's input
+    hops:             Vec<SwapSimulation>,
+    estimated_out:    u64,
+    effective_rate:   f64,
+    /// Hop impacts compounded: 1 − Π(1 − impactᵢ)
+    price_impact_pct: f64,
+    price_impact_bps: u64,
+}
+
+impl Route {
+    /// `direct`, or `via <SYMBOL>` for two hops.
+    fn label(&self) -> String {
+        match &self.hops[..] {
+            [_]         => "direct".to_string(),
+            [first, ..] => format!("via {}", resolve_symbol(&first.mint_out)),
+            []          => unreachable!("routes have at least one hop"),
+        }
+    }
+
+    /// Hop pools, shortened and joined with `→`.
+    fn pools_label(&self) -> String {
+        self.hops.iter()
+            .map(|hop| {
+                let addr = hop.pool.to_string();
+                format!("{}…{}", &addr[..4], &addr[addr.len() - 4..])
+            })
+            .collect::<Vec<_>>()
+            .join(" → ")
+    }
+
+    fn quote(&self, amount_in: u64, protocol_fee_bps: u16) -> RouteQuote {
+        let mut amount = amount_in;
+        let hops: Vec<SwapSimulation> = self.hops.iter()
+            .map(|hop| {
+                let sim = simulate_detailed(
+                    amount, protocol_fee_bps, hop.reserve_in, hop.reserve_out, hop.fee_rate_bps, hop.curve,
+                );
+                amount = sim.estimated_out;
+                sim
+            })
+            .collect();
+        let kept = hops.iter().map(|sim| 1.0 - sim.price_impact_pct / 100.0).product::<f64>();
+        let kept_bps = hops.iter().fold(BPS_DENOMINATOR, |kept, sim| {
+            kept * BPS_DENOMINATOR.saturating_sub(sim.price_impact_bps as u128) / BPS_DENOMINATOR
+        });
+        RouteQuote {
+            estimated_out:    amount,
+            effective_rate:   if amount_in > 0 { amount as f64 / amount_in as f64 } else { 0.0 },
+            price_impact_pct: (1.0 - kept) * 100.0,
+            price_impact_bps: (BPS_DENOMINATOR - kept_bps) as u64,
+            hops,
+        }
+    }
+}
+
+/// Every route from `mint_in` to `mint_out` through pools with liquidity:
+/// both direct PDA orderings, plus each pair of pools that connects through
+/// one of [`ROUTE_INTERMEDIATES`]. Direct routes come first.
+fn find_routes(
+    client: &RpcClient,
+    mint_in: &Pubkey,
+    mint_out: &Pubkey,
+    program_id: &Pubkey,
+) -> Result<Vec<Route>> {
+    let mut mids: Vec<Pubkey> = ROUTE_INTERMEDIATES.iter()
+        .filter_map(|symbol| resolve_mint(symbol).ok())
+        .filter(|mint| mint != mint_in && mint != mint_out)
+        .collect();
+    mids.dedup();
+
+    // Every (from, to, pool, a_to_b) a route could trade through.
+    let legs: Vec<(Pubkey, Pubkey)> = std::iter::once((*mint_in, *mint_out))
+        .chain(mids.iter().flat_map(|mid| [(*mint_in, *mid), (*mid, *mint_out)]))
+        .collect();
+    let candidates: Vec<(Pubkey, Pubkey, Pubkey, bool)> = legs.iter()
+        .flat_map(|&(from, to)| [(from, to, true), (to, from, false)].map(|(first, second, a_to_b)| {
+            let (pda, _) = Pubkey::find_program_address(
+                &[POOL_SEED, first.as_ref(), second.as_ref()], program_id);
+            (from, to, pda, a_to_b)
+        }))
+        .collect();
+    let keys: Vec<Pubkey> = candidates.iter().map(|c| c.2).collect();
+    let pool_map = fetch_pool_map(client, &keys);
+
+    let mut hops: Vec<RouteHop> = Vec::new();
+    let mut dry_direct = false;
+    for (from, to, pool, a_to_b) in candidates {
+        let Some(state) = pool_map.get(&pool) else { continue };
+        let (ra, rb) = pool_reserves(client, state)?;
+        if ra == 0 || rb == 0 {
+            dry_direct |= from == *mint_in && to == *mint_out;
+            continue;
+        }
+        let (reserve_in, reserve_out) = if a_to_b { (ra, rb) } else { (rb, ra) };
+        hops.push(RouteHop {
+            pool, a_to_b,
+            mint_in: from, mint_out: to,
+            reserve_in, reserve_out,
+            fee_rate_bps: state.effective_fee_bps(),
+            curve:        state.curve,
+        });
+    }
+
+    let hops = &hops;
+    let legs_of = move |from: Pubkey, to: Pubkey| {
+        hops.iter().filter(move |hop| hop.mint_in == from && hop.mint_out == to).copied()
+    };
+    let mut routes: Vec<Route> = legs_of(*mint_in, *mint_out)
+        .map(|hop| Route { hops: vec![hop] })
+        .collect();
+    for mid in &mids {
+        for first in legs_of(*mint_in, *mid) {
+            routes.extend(legs_of(*mid, *mint_out).map(|second| Route { hops: vec![first, second] }));
+        }
+    }
+
+    if routes.is_empty() {
+        return Err(if dry_direct {
+            anyhow!(
+                "Pool has no liquidity yet.\n  \
+                 Run `a2a-swap provide --pair {}-{}` to seed it first.",
+                resolve_symbol(mint_in), resolve_symbol(mint_out)
+            )
+        } else {
+            no_pool_error()
+        });
+    }
+    Ok(routes)
+}
+
+/// `--route`: `best` (the first of `ranked`), `direct` (the best direct
+/// pool) or a pool address (the best route through that pool).
+fn select_route<'a>(ranked: &'a [Route], choice: &str) -> Result<&'a Route> {
+    match choice {
+        "best"   => ranked.first().ok_or_else(no_pool_error),
+        "direct" => ranked.iter().find(|r| r.hops.len() == 1).ok_or_else(|| CliError::PoolNotFound(
+            "No direct pool with liquidity for this pair. Use --route best to take a two-hop route.".to_string()
+        ).into()),
+        pool => {
+            let pool = Pubkey::from_str(pool)
+                .map_err(|_| input_error!("--route must be best, direct or a pool address, got '{pool}'."))?;
+            ranked.iter()
+                .find(|r| r.hops.iter().any(|hop| hop.pool == pool))
+                .ok_or_else(|| input_error!("Pool {pool} is not on any route between --in and --out."))
+        }
+    }
 }
 
 /// Detailed swap simulation result.
 struct SwapSimulation {
-    /// Tokens sent to the protocol treasury (0.020% of amount_in)
+    /// Tokens sent to the protocol treasury (protocol fee share of amount_in)
     protocol_fee:     u64,
     /// LP fee that stays in the vault, grows k
     lp_fee:           u64,
@@ -398,12 +1113,16 @@ struct SwapSimulation {
     net_pool_input:   u64,
     /// net_pool_input − lp_fee — the amount that actually moves the AMM curve
     after_fees:       u64,
-    /// Tokens out from the constant-product formula
+    /// Tokens out from the pool's curve
     estimated_out:    u64,
     /// estimated_out / amount_in (out-per-unit-in, raw units)
     effective_rate:   f64,
-    /// Pure AMM slippage: after_fees / (reserve_in + after_fees) × 100
+    /// Pure AMM slippage against the pre-trade spot price, × 100
+    /// (after_fees / (reserve_in + after_fees) for x·y=k)
     price_impact_pct: f64,
+    /// Same as `price_impact_pct` in integer bps, rounded down — the value
+    /// the on-chain program checks against `max_price_impact_bps`
+    price_impact_bps: u64,
 }
 
 /// Run the full swap fee math and return a detailed breakdown.
@@ -411,28 +1130,31 @@ struct SwapSimulation {
 /// Mirrors `programs/a2a-swap/src/instructions/swap.rs` exactly.
 fn simulate_detailed(
     amount_in: u64,
+    protocol_fee_bps: u16,
     reserve_in: u64,
     reserve_out: u64,
     fee_rate_bps: u16,
+    curve: CurveKind,
 ) -> SwapSimulation {
     let in_u128        = amount_in as u128;
-    let protocol_fee   = in_u128 * PROTOCOL_FEE_BPS / PROTOCOL_FEE_DENOMINATOR;
+    let protocol_fee   = in_u128 * protocol_fee_bps as u128 / PROTOCOL_FEE_DENOMINATOR;
     let net_pool_input = in_u128 - protocol_fee;
     let lp_fee         = net_pool_input * fee_rate_bps as u128 / BPS_DENOMINATOR;
     let after_fees     = net_pool_input - lp_fee;
     let r_in           = reserve_in as u128;
     let r_out          = reserve_out as u128;
 
-    let estimated_out = if r_in + after_fees > 0 {
-        (r_out * after_fees / (r_in + after_fees)) as u64
-    } else {
-        0
-    };
-
-    let price_impact_pct = if r_in + after_fees > 0 {
-        after_fees as f64 / (r_in + after_fees) as f64 * 100.0
-    } else {
-        0.0
+    let (estimated_out, price_impact_pct, price_impact_bps) = match curve {
+        _ if r_in + after_fees == 0 => (0, 0.0, 0),
+        CurveKind::ConstantProduct => (
+            (r_out * after_fees / (r_in + after_fees)) as u64,
+            after_fees as f64 / (r_in + after_fees) as f64 * 100.0,
+            (after_fees * BPS_DENOMINATOR / (r_in + after_fees)) as u64,
+        ),
+        CurveKind::StableSwap { amp } => match stable_swap_out(after_fees, r_in, r_out, amp) {
+            Some((out, bps)) => (out, bps as f64 / 100.0, bps),
+            None             => (0, 100.0, BPS_DENOMINATOR as u64),
+        },
     };
 
     let effective_rate = if amount_in > 0 {
@@ -449,46 +1171,169 @@ fn simulate_detailed(
         estimated_out,
         effective_rate,
         price_impact_pct,
+        price_impact_bps,
     }
 }
 
-// ─── Approval gate ────────────────────────────────────────────────────────────
+// Two-coin StableSwap (Curve) invariant with Ann = 4A:
+//   Ann·(x + y) + D = Ann·D + D³ / (4·x·y)
+// Mirrors programs/a2a-swap/src/instructions/fee_math.rs; `None` where the
+// program would fail with MathOverflow / CurveNotConverged.
+const STABLE_MAX_ITERATIONS: usize = 64;
 
-/// Stub approval gate. For `none`, returns immediately. For `webhook`/`slack`,
-/// logs a message and proceeds (HTTP call stubbed for MVP).
-fn approval_gate(
-    mode: &str,
-    webhook_url: Option<&str>,
-    details: &serde_json::Value,
-) -> Result<()> {
-    match mode {
-        "none" => Ok(()),
-        "webhook" => {
-            let url = webhook_url.ok_or_else(|| {
-                anyhow!(
-                    "--webhook-url is required when --approval-mode webhook.\n  \
-                     Example: --webhook-url https://my-agent.example.com/approve"
-                )
-            })?;
-            eprintln!("[approval] mode=webhook  url={url}");
-            eprintln!("[approval] payload={details}");
-            eprintln!("[approval] HTTP call stubbed — proceeding automatically for now");
-            Ok(())
+fn stable_invariant(x: u128, y: u128, amp: u64) -> Option<u128> {
+    let sum = x.checked_add(y)?;
+    if sum == 0 {
+        return Some(0);
+    }
+    let ann = amp as u128 * 4;
+    let mut d = sum;
+    for _ in 0..STABLE_MAX_ITERATIONS {
+        let mut d_p = d;
+        for r in [x, y] {
+            d_p = d_p.checked_mul(d)? / r.checked_mul(2)?.max(1);
         }
-        "slack" => {
-            eprintln!("[approval] mode=slack");
-            eprintln!("[approval] payload={details}");
-            eprintln!("[approval] Slack DM stubbed — proceeding automatically for now");
-            Ok(())
+        let prev = d;
+        let num = ann.checked_mul(sum)?.checked_add(d_p.checked_mul(2)?)?.checked_mul(d)?;
+        let den = (ann - 1).checked_mul(d)?.checked_add(d_p.checked_mul(3)?)?;
+        d = num / den;
+        if d.abs_diff(prev) <= 1 {
+            return Some(d);
         }
-        other => Err(anyhow!(
-            "Unknown --approval-mode '{}'. Valid values: none, webhook, slack",
-            other
-        )),
     }
+    None
 }
 
-// ─── Version banner ───────────────────────────────────────────────────────────
+fn stable_y(x_new: u128, d: u128, amp: u64) -> Option<u128> {
+    if x_new == 0 {
+        return None;
+    }
+    let ann = amp as u128 * 4;
+    let c = d.checked_mul(d)? / (x_new * 2);
+    let c = c.checked_mul(d)? / (ann * 2);
+    let b = x_new + d / ann;
+    let mut y = d;
+    for _ in 0..STABLE_MAX_ITERATIONS {
+        let prev = y;
+        let den = (y * 2 + b).checked_sub(d).filter(|&v| v > 0)?;
+        y = y.checked_mul(y)?.checked_add(c)? / den;
+        if y.abs_diff(prev) <= 1 {
+            return Some(y);
+        }
+    }
+    None
+}
+
+/// `t = 16A·(xy/D)² / D`; the marginal price of x in y is `(t + y) / (t + x)`.
+fn stable_price_term(x: u128, y: u128, d: u128, amp: u64) -> Option<u128> {
+    if d == 0 {
+        return Some(0);
+    }
+    let k = x.checked_mul(y)? / d;
+    Some((amp as u128 * 16).checked_mul(k)?.checked_mul(k)? / d)
+}
+
+/// StableSwap `(amount_out, price_impact_bps)`; one unit held back as on-chain.
+fn stable_swap_out(after_fees: u128, reserve_in: u128, reserve_out: u128, amp: u64) -> Option<(u64, u64)> {
+    let d = stable_invariant(reserve_in, reserve_out, amp)?;
+    let y_new = stable_y(reserve_in.checked_add(after_fees)?, d, amp)?;
+    let amount_out = reserve_out.saturating_sub(y_new).saturating_sub(1);
+    let t = stable_price_term(reserve_in, reserve_out, d, amp)?;
+    let ideal_out = after_fees.checked_mul(t + reserve_out)? / (t + reserve_in);
+    let impact_bps = if ideal_out == 0 {
+        0
+    } else {
+        ideal_out.saturating_sub(amount_out) * BPS_DENOMINATOR / ideal_out
+    };
+    Some((amount_out as u64, impact_bps as u64))
+}
+
+/// Marginal price of token A in token B (raw atomic units); 0 for an empty pool.
+fn spot_price(curve: CurveKind, ra: u64, rb: u64) -> f64 {
+    if ra == 0 || rb == 0 {
+        return 0.0;
+    }
+    let (x, y) = (ra as u128, rb as u128);
+    match curve {
+        CurveKind::StableSwap { amp } => stable_invariant(x, y, amp)
+            .and_then(|d| stable_price_term(x, y, d, amp))
+            .map_or(rb as f64 / ra as f64, |t| (t + y) as f64 / (t + x) as f64),
+        CurveKind::ConstantProduct => rb as f64 / ra as f64,
+    }
+}
+
+/// Print a stderr warning when a trade moves the pool price noticeably.
+fn warn_price_impact(price_impact_pct: f64) {
+    if price_impact_pct > PRICE_IMPACT_WARN_PCT {
+        eprintln!(
+            "Warning: price impact {price_impact_pct:.2}% is above {PRICE_IMPACT_WARN_PCT:.1}% — \
+             the pool is thin for this size. Consider a smaller --amount or --max-price-impact."
+        );
+    }
+}
+
+// ─── Approval gate ────────────────────────────────────────────────────────────
+
+/// `convert` approval options.
+struct Approval<'a> {
+    mode:          &'a str,
+    webhook_url:   Option<&'a str>,
+    approver:      Option<&'a str>,
+    telegram_chat: Option<&'a str>,
+    telegram_bot:  Option<&'a str>,
+    /// Slots an approve_and_execute approval stays valid for (0 = no expiry).
+    expiry_slots:  u64,
+}
+
+/// Approval gate. For `none`, returns immediately. For `webhook`, logs a
+/// message and proceeds (HTTP call stubbed for MVP). For `telegram`, checks
+/// the options; the request itself is posted when the swap is sent.
+fn approval_gate(approval: &Approval<'_>, details: &serde_json::Value) -> Result<()> {
+    match approval.mode {
+        "none" => Ok(()),
+        "webhook" => {
+            let url = approval.webhook_url.ok_or_else(|| {
+                input_error!(
+                    "--webhook-url is required when --approval-mode webhook.\n  \
+                     Example: --webhook-url https://my-agent.example.com/approve"
+                )
+            })?;
+            eprintln!("[approval] mode=webhook  url={url}");
+            eprintln!("[approval] payload={details}");
+            eprintln!("[approval] HTTP call stubbed — proceeding automatically for now");
+            Ok(())
+        }
+        "telegram" => {
+            let missing: Vec<&str> = [
+                ("--approver", approval.approver),
+                ("--telegram-chat", approval.telegram_chat),
+                ("--telegram-bot-token", approval.telegram_bot),
+            ]
+            .into_iter()
+            .filter_map(|(flag, v)| v.is_none().then_some(flag))
+            .collect();
+            if !missing.is_empty() {
+                return Err(input_error!(
+                    "{} required when --approval-mode telegram.\n  \
+                     Example: --approver <PUBKEY> --telegram-chat <CHAT_ID> \
+                     (bot token via A2A_TELEGRAM_BOT_TOKEN)",
+                    missing.join(", ")
+                ));
+            }
+            Ok(())
+        }
+        "slack" => Err(input_error!(
+            "--approval-mode slack has been replaced by --notify.\n  \
+             Example: --notify slack://hooks.slack.com/services/T000/B000/XXXX"
+        )),
+        other => Err(input_error!(
+            "Unknown --approval-mode '{}'. Valid values: none, webhook, telegram",
+            other
+        )),
+    }
+}
+
+// ─── Version banner ───────────────────────────────────────────────────────────
 
 /// Print the A2A-Swap banner to stdout.
 fn print_banner() {
@@ -511,6 +1356,7 @@ fn print_banner() {
 /// Global options can also be set via environment variables:
 ///   A2A_RPC_URL  — Solana JSON-RPC endpoint
 ///   A2A_KEYPAIR  — path to agent Ed25519 keypair JSON
+/// or per profile in ~/.config/a2a-swap/config.toml (see `config`).
 #[derive(Parser)]
 #[command(
     name        = "a2a-swap",
@@ -520,15 +1366,32 @@ fn print_banner() {
         "Program:      8XJfG4mHqRZjByAd7HxHdEALfB8jVtJVQsdhGEmysTFq\n",
         "Network:      Solana mainnet-beta\n",
         "Protocol fee: 0.020%  (20 / 100_000 of amount_in)\n",
-        "LP fee range: 1–100 bps  (0.01%–1.00%, set per pool)\n",
+        "LP fee range: 1–100 bps  (0.01%–1.00%, set per pool; governance may change it)\n",
         "License:      MIT",
     ),
     author  = "A2A Protocol",
     about   = "Agent-native constant-product AMM — zero-human-in-the-loop token swaps on Solana.",
     after_help = "\
 ENVIRONMENT:
-  A2A_RPC_URL    Solana JSON-RPC endpoint  [default: https://api.mainnet-beta.solana.com]
-  A2A_KEYPAIR    Path to Ed25519 keypair JSON  [default: ~/.config/solana/id.json]
+  A2A_RPC_URL       Solana JSON-RPC endpoint  [default: https://api.mainnet-beta.solana.com]
+  A2A_KEYPAIR       Path to Ed25519 keypair JSON  [default: ~/.config/solana/id.json]
+  A2A_PROFILE       Config profile to use  [default: default_profile, else \"default\"]
+  A2A_WALLET        Named wallet to sign with  [default: default_wallet]
+  A2A_KEYSTORE_PASSPHRASE  Passphrase for keystore keypairs  [default: prompt]
+  A2A_PRIORITY_FEE  Compute-unit price in micro-lamports  [default: 0]
+  A2A_CONFIG        Config file  [default: ~/.config/a2a-swap/config.toml]
+  A2A_NOTIFY        Slack / Discord / webhook target for transaction results
+
+CONFIG PROFILES:
+  a2a-swap --profile prod config set rpc_url https://my-rpc.example.com
+  a2a-swap --profile prod config set keypair ~/agent-keys/prod.json
+  a2a-swap --profile prod convert --in SOL --out USDC --amount 1000000000
+  Flags override env vars, which override the profile.
+
+WALLETS:
+  a2a-swap wallets add treasury ~/agent-keys/treasury.json --max-slippage 0.1
+  a2a-swap wallets use treasury
+  a2a-swap --wallet market-maker convert --in SOL --out USDC --amount 1000000000
 
 QUICK START:
   a2a-swap simulate         --in SOL --out USDC --amount 1000000000
@@ -539,29 +1402,42 @@ QUICK START:
   a2a-swap my-fees
   a2a-swap remove-liquidity --pair SOL-USDC --shares 1000000  (legacy)
 
+EXIT CODES:
+  0 success · 1 other failure · 2 invalid input · 3 pool not found
+  4 slippage exceeded · 5 RPC failure · 6 insufficient balance
+
 PROGRAM:
   8XJfG4mHqRZjByAd7HxHdEALfB8jVtJVQsdhGEmysTFq  (Solana mainnet-beta)"
 )]
 struct Cli {
     /// Solana JSON-RPC endpoint
-    #[arg(
-        long,
-        global     = true,
-        value_name = "URL",
-        default_value = "https://api.mainnet-beta.solana.com",
-        env = "A2A_RPC_URL"
-    )]
-    rpc_url: String,
+    /// [default: profile rpc_url / network, else https://api.mainnet-beta.solana.com]
+    #[arg(long, global = true, value_name = "URL", env = "A2A_RPC_URL")]
+    rpc_url: Option<String>,
 
     /// Path to the agent's Ed25519 keypair JSON file
-    #[arg(
-        long,
-        global     = true,
-        value_name = "PATH",
-        default_value = "~/.config/solana/id.json",
-        env = "A2A_KEYPAIR"
-    )]
-    keypair: String,
+    /// [default: profile keypair, else ~/.config/solana/id.json]
+    #[arg(long, global = true, value_name = "PATH", env = "A2A_KEYPAIR")]
+    keypair: Option<String>,
+
+    /// Named profile from ~/.config/a2a-swap/config.toml
+    #[arg(long, global = true, value_name = "NAME", env = "A2A_PROFILE")]
+    profile: Option<String>,
+
+    /// Named wallet from `a2a-swap wallets` — its keypair and defaults
+    /// override the profile's [default: default_wallet]
+    #[arg(long, global = true, value_name = "NAME", env = "A2A_WALLET")]
+    wallet: Option<String>,
+
+    /// Priority fee: compute-unit price in micro-lamports added to every
+    /// transaction [default: profile priority_fee, else 0]
+    #[arg(long, global = true, value_name = "MICRO_LAMPORTS", env = "A2A_PRIORITY_FEE")]
+    priority_fee: Option<u64>,
+
+    /// Post each transaction's result (or failure) to Slack, Discord or a JSON
+    /// webhook: slack://…, discord://… or https://… [default: profile notify]
+    #[arg(long, global = true, value_name = "TARGET", env = "A2A_NOTIFY")]
+    notify: Option<String>,
 
     /// Output machine-readable JSON instead of human-readable text
     #[arg(long, global = true, default_value_t = false)]
@@ -573,7 +1449,7 @@ struct Cli {
 
 #[derive(Subcommand)]
 enum Commands {
-    /// Create a new x·y=k liquidity pool for a token pair
+    /// Create a new x·y=k (or StableSwap) liquidity pool for a token pair
     ///
     /// The pool authority is a PDA — no human key required.
     /// On-chain program initializes two token vaults and
@@ -587,32 +1463,79 @@ EXAMPLES:
   # Create and suggest a seed command with 1 SOL worth of liquidity
   a2a-swap create-pool --pair SOL-USDC --initial-price 185 --seed-amount 1000000000
 
+  # Create and seed atomically: the pool is never visible empty
+  a2a-swap create-pool --pair SOL-USDC --initial-price 0.185 --seed-amount 1000000000 --seed
+
   # Use custom mint addresses
   a2a-swap create-pool --pair <mintA>-<mintB> --initial-price 1.0 --fee-bps 10
 
+  # Stable pair: StableSwap curve with amplification 100, 0.04% fee
+  a2a-swap create-pool --pair USDC-USDT --initial-price 1.0 --fee-bps 4 --stable-amp 100
+
+  # Guided: look up both mints, price in whole tokens, create + seed in one tx
+  a2a-swap create-pool --wizard --pair SOL-USDC
+
 NOTES:
-  After creation the pool is empty. Run `provide` to seed initial liquidity.
-  Fee range: 1–100 bps (0.01%–1.00%). Default 30 bps (0.30%) suits most pools."
+  After creation the pool is empty. Run `provide` to seed initial liquidity,
+  or pass --seed to deposit in the creation transaction itself.
+  Fee range: 1–100 bps (0.01%–1.00%) unless governance has set other bounds.
+  Default 30 bps (0.30%) suits most pools."
     )]
     CreatePool {
         /// Token pair, e.g. SOL-USDC or <mintA>-<mintB>
-        #[arg(long, value_name = "A-B")]
-        pair: String,
+        #[arg(long, value_name = "A-B", required_unless_present = "wizard")]
+        pair: Option<String>,
 
         /// Reference spot price at creation: how many token B equal one token A.
         /// Used only to compute the `provide` hint; not stored on-chain.
-        #[arg(long, value_name = "FLOAT")]
-        initial_price: f64,
+        #[arg(long, value_name = "FLOAT", required_unless_present = "wizard")]
+        initial_price: Option<f64>,
 
         /// Amount of token A (atomic units) for the seed-command hint.
         /// Prints a ready-to-run `provide` command. Set to 0 to skip.
         #[arg(long, value_name = "AMOUNT", default_value_t = 0)]
         seed_amount: u64,
 
+        /// Deposit --seed-amount of token A and --seed-amount × --initial-price
+        /// of token B in the same transaction as the pool creation, so the
+        /// pool is never on-chain empty for someone else to price.
+        #[arg(long, default_value_t = false, requires = "seed_amount")]
+        seed: bool,
+
         /// LP fee charged on every swap (basis points, 1 bp = 0.01%).
-        /// Range 1–100. Default 30 = 0.30%.
+        /// Range 1–100 unless governance sets other bounds. Default 30 = 0.30%.
         #[arg(long, value_name = "BPS", default_value_t = 30)]
         fee_bps: u16,
+
+        /// Create a StableSwap (Curve-style) pool with this amplification
+        /// coefficient instead of x·y=k. For correlated pairs such as
+        /// USDC-USDT; range 1–10000, typical 100.
+        #[arg(long, value_name = "A")]
+        stable_amp: Option<u64>,
+
+        /// Interactive: read both mints, check their decimals, ask for a
+        /// human price and seed size, then create and seed the pool in one
+        /// transaction. --pair / --initial-price / --fee-bps become defaults.
+        #[arg(long, default_value_t = false)]
+        wizard: bool,
+    },
+
+    /// Close an empty pool and reclaim the rent of it and its vaults
+    ///
+    /// Only the wallet that created the pool, or the protocol admin, may
+    /// close it. The pool must have no LP shares, no tracked reserves and no
+    /// open positions; vault dust goes to the closer. Pools created before
+    /// positions were counted can't be closed.
+    #[command(
+        after_help = "\
+EXAMPLES:
+  a2a-swap close-pool --pair SOL-USDC
+  a2a-swap close-pool --pair <mintA>-<mintB> --json"
+    )]
+    ClosePool {
+        /// Token pair of the pool, e.g. SOL-USDC or <mintA>-<mintB>
+        #[arg(long, value_name = "A-B")]
+        pair: String,
     },
 
     /// Add liquidity to a pool and receive LP shares
@@ -633,9 +1556,19 @@ EXAMPLES:
   # Enable auto-compounding of accrued fees
   a2a-swap provide --pair SOL-USDC --amount 500000000 --auto-compound
 
+  # Also mint a receipt NFT for the position
+  a2a-swap provide --pair SOL-USDC --amount 500000000 --receipt
+
+  # Fail instead of depositing more than 95 USDC if the price has moved
+  a2a-swap provide --pair SOL-USDC --amount 500000000 --max-b 95000000
+
+  # Preview the LP shares and pool share a deposit would get
+  a2a-swap provide --pair SOL-USDC --amount 500000000 --simulate
+
 NOTES:
   First deposit requires --amount-b to establish the initial price.
-  Subsequent deposits omit --amount-b; the SDK computes it proportionally.
+  Subsequent deposits omit --amount-b; the program computes it from its
+  reserves when the transaction lands, capped by --max-b.
   Amounts are in atomic units: lamports for SOL, μUSDC for USDC, etc."
     )]
     Provide {
@@ -649,7 +1582,7 @@ NOTES:
 
         /// Amount of token B (atomic units).
         /// Required for the first deposit (sets the initial price ratio).
-        /// Omit for subsequent deposits — computed from live reserves.
+        /// Omit for subsequent deposits — computed on-chain from live reserves.
         #[arg(long, value_name = "AMOUNT")]
         amount_b: Option<u64>,
 
@@ -661,12 +1594,31 @@ NOTES:
         /// auto-compound fires. 0 = compound every time fees exist.
         #[arg(long, value_name = "AMOUNT", default_value_t = 0)]
         compound_threshold: u64,
+
+        /// Mint a Metaplex Core receipt NFT for the position (shows up in
+        /// wallets; burned automatically when the position is fully removed)
+        #[arg(long, default_value_t = false)]
+        receipt: bool,
+
+        /// Most token A the deposit may take (atomic units)
+        #[arg(long, value_name = "AMOUNT")]
+        max_a: Option<u64>,
+
+        /// Most token B the deposit may take (atomic units). Checked on-chain
+        /// against the --amount-b the program computes, in case reserves moved.
+        #[arg(long, value_name = "AMOUNT")]
+        max_b: Option<u64>,
+
+        /// Print the LP shares, pool share and price the deposit would give,
+        /// without sending it (no keypair needed)
+        #[arg(long, default_value_t = false)]
+        simulate: bool,
     },
 
     /// Execute an atomic token swap through a constant-product pool
     ///
     /// Pre-flight simulation runs automatically before sending the transaction.
-    /// Pass --approval-mode webhook or slack to require human co-signature.
+    /// Pass --approval-mode webhook or telegram to require human co-signature.
     /// Protocol fee (0.020%) and LP fee are deducted from amount_in.
     #[command(
         after_help = "\
@@ -677,24 +1629,38 @@ EXAMPLES:
   # Swap with tighter slippage tolerance (0.1%)
   a2a-swap convert --in SOL --out USDC --amount 1000000000 --max-slippage 0.1
 
+  # Refuse to move the pool price by more than 2% (checked again on-chain)
+  a2a-swap convert --in SOL --out USDC --amount 1000000000 --max-price-impact 2
+
+  # Fail on-chain instead of filling if the swap lands more than 60s from now
+  a2a-swap convert --in SOL --out USDC --amount 1000000000 --deadline 60
+
   # Swap requiring webhook approval before sending
   a2a-swap convert --in SOL --out USDC --amount 1000000000 \\
     --approval-mode webhook --webhook-url https://mybot.example.com/approve
 
+  # Approve / Reject in Telegram; `a2a-swap approver telegram` co-signs
+  a2a-swap convert --in SOL --out USDC --amount 1000000000 \\
+    --approval-mode telegram --approver <APPROVER_PUBKEY> --telegram-chat <CHAT_ID>
+
   # Machine-readable output (for agent pipelines)
   a2a-swap convert --in SOL --out USDC --amount 1000000000 --json
 
+  # Route part of the protocol fee to an integrator wallet
+  a2a-swap convert --in SOL --out USDC --amount 1000000000 --referrer <PUBKEY>
+
 FEE MODEL:
-  protocol_fee = amount_in × 0.020%   → treasury PDA
+  protocol_fee = amount_in × protocol fee (0.020% default)  → treasury PDA
+                 (20% of it by default → --referrer's ATA, when given)
   lp_fee       = net × fee_bps / 100  → stays in vault (accrues to LPs)
   estimated_out = reserve_out × (net − lp_fee) / (reserve_in + net − lp_fee)"
     )]
     Convert {
-        /// Token to sell — symbol (SOL, USDC, USDT) or base-58 mint address
+        /// Token to sell — symbol (SOL, USDC, USDT, or any in `tokens list`) or base-58 mint address
         #[arg(long = "in", value_name = "TOKEN")]
         token_in: String,
 
-        /// Token to receive — symbol (SOL, USDC, USDT) or base-58 mint address
+        /// Token to receive — symbol (SOL, USDC, USDT, or any in `tokens list`) or base-58 mint address
         #[arg(long = "out", value_name = "TOKEN")]
         token_out: String,
 
@@ -705,7 +1671,8 @@ FEE MODEL:
         /// Approval gate mode before the transaction is sent.
         /// none: proceed immediately (default, fully autonomous)
         /// webhook: stub POST to --webhook-url then proceed
-        /// slack: stub Slack DM then proceed
+        /// telegram: post to --telegram-chat with Approve / Reject buttons;
+        ///   sends approve_and_execute, co-signed by `approver telegram`
         #[arg(long, value_name = "MODE", default_value = "none")]
         approval_mode: String,
 
@@ -713,10 +1680,46 @@ FEE MODEL:
         #[arg(long, value_name = "URL")]
         webhook_url: Option<String>,
 
+        /// Approver that must co-sign approve_and_execute
+        /// (required when --approval-mode telegram)
+        #[arg(long, value_name = "PUBKEY")]
+        approver: Option<String>,
+
+        /// Telegram chat the approval request is posted to
+        /// (required when --approval-mode telegram)
+        #[arg(long, value_name = "CHAT_ID", env = "A2A_TELEGRAM_CHAT")]
+        telegram_chat: Option<String>,
+
+        /// Telegram bot token (required when --approval-mode telegram)
+        #[arg(long, value_name = "TOKEN", env = "A2A_TELEGRAM_BOT_TOKEN", hide_env_values = true)]
+        telegram_bot_token: Option<String>,
+
+        /// Slots an approval stays valid for: approve_and_execute fails on-chain
+        /// after the current slot + SLOTS (~150 slots/min). 0 = no expiry.
+        #[arg(long, value_name = "SLOTS", default_value_t = 150)]
+        approval_expiry: u64,
+
         /// Reject the swap if real output falls more than this many percent below
         /// the pre-flight estimate. 0 = accept any output (no slippage guard).
-        #[arg(long, value_name = "PCT", default_value_t = 0.5)]
-        max_slippage: f64,
+        /// [default: profile max_slippage, else 0.5]
+        #[arg(long, value_name = "PCT")]
+        max_slippage: Option<f64>,
+
+        /// Reject the swap if its price impact exceeds this many percent.
+        /// Enforced client-side and by the on-chain program. 0 = no cap.
+        #[arg(long, value_name = "PCT", default_value_t = 0.0)]
+        max_price_impact: f64,
+
+        /// Seconds from now the swap may still execute in: the program rejects
+        /// it after that, so a transaction stuck in a retry queue cannot fill
+        /// at a stale price. 0 = no deadline.
+        #[arg(long, value_name = "SECS", default_value_t = 0)]
+        deadline: u64,
+
+        /// Integrator wallet (base-58) that receives the referral share of the
+        /// protocol fee, paid into its existing ATA for the input token
+        #[arg(long, value_name = "PUBKEY")]
+        referrer: Option<String>,
     },
 
     /// Preview a swap's fee breakdown without sending any transaction
@@ -733,8 +1736,15 @@ EXAMPLES:
   # Machine-readable JSON output for agent decision logic
   a2a-swap simulate --in SOL --out USDC --amount 1000000000 --json
 
+  # Compare several sizes against the same pool read
+  a2a-swap simulate --in SOL --out USDC --amounts 1e8,5e8,1e9,5e9
+
+  # Price only the direct pool, or one specific pool
+  a2a-swap simulate --in SOL --out USDC --amount 1000000000 --route direct
+  a2a-swap simulate --in SOL --out USDC --amount 1000000000 --route <POOL>
+
 OUTPUT FIELDS:
-  protocol_fee   — 0.020% of amount_in, sent to treasury PDA
+  protocol_fee   — on-chain protocol fee (0.020% default) of amount_in, sent to treasury PDA
   lp_fee         — pool fee_rate_bps% of (amount_in - protocol_fee)
   after_fees     — amount that moves the AMM curve
   estimated_out  — constant-product formula output
@@ -751,18 +1761,32 @@ OUTPUT FIELDS:
         token_out: String,
 
         /// Amount of the input token to simulate selling (atomic units)
-        #[arg(long, value_name = "AMOUNT")]
-        amount: u64,
+        #[arg(long, value_name = "AMOUNT", required_unless_present = "amounts")]
+        amount: Option<u64>,
+
+        /// Comma-separated amounts to compare in one table, e.g. 1e8,5e8,1e9
+        /// (atomic units; exponents allowed). All are priced against one read
+        /// of the pool.
+        #[arg(long, value_name = "LIST", conflicts_with = "amount")]
+        amounts: Option<String>,
 
         /// Routing mode. Only "direct" is supported in this release.
         #[arg(long, value_name = "MODE", default_value = "direct")]
         mode: String,
+
+        /// Which candidate route to price: best (highest output), direct (the
+        /// best single pool) or a pool address. Candidates are both mint
+        /// orderings of the pair and two-hop routes via SOL, USDC or USDT.
+        #[arg(long, value_name = "ROUTE", default_value = "best")]
+        route: String,
     },
 
     /// List all open LP positions owned by the agent keypair
     ///
     /// Fetches on-chain Position accounts filtered by the agent's public key.
-    /// Shows LP shares, pool pair, and auto-compound settings.
+    /// Shows LP shares, pool pair, auto-compound settings, and impermanent
+    /// loss (il_pct) against the deposit-weighted entry price rebuilt from
+    /// each position's transaction history.
     /// Run `my-fees` to see claimable fee balances for each position.
     #[command(
         after_help = "\
@@ -782,6 +1806,9 @@ EXAMPLES:
   a2a-swap pool-info --pair SOL-USDC
   a2a-swap pool-info --pair <mintA>-<mintB> --json
 
+  # Value the reserves (TVL) in SOL instead of USDC
+  a2a-swap pool-info --pair BONK-USDC --quote SOL
+
   # Spot price is reserveB / reserveA in raw atomic units.
   # Divide by decimals to get a human price (e.g. 185.0 USDC/SOL)."
     )]
@@ -789,6 +1816,11 @@ EXAMPLES:
         /// Token pair to query, e.g. SOL-USDC or <mintA>-<mintB>
         #[arg(long, value_name = "A-B")]
         pair: String,
+
+        /// Token to value the reserves (TVL) in, through each side's direct
+        /// pool against it — symbol or mint address
+        #[arg(long, value_name = "TOKEN", default_value = "USDC")]
+        quote: String,
     },
 
     /// List every pool deployed under the program with live reserves and spot prices
@@ -807,6 +1839,25 @@ EXAMPLES:
     )]
     ActivePools,
 
+    /// Download and print the program's Anchor IDL
+    ///
+    /// Reads the IDL account `anchor idl init` created for the program
+    /// (scripts/publish-idl.sh) and pretty-prints the decompressed JSON: the
+    /// instructions, accounts, types and errors an agent needs to build its
+    /// own instructions or decode accounts. No transaction is sent.
+    #[command(
+        after_help = "\
+EXAMPLES:
+  a2a-swap idl
+  a2a-swap idl -o a2a_swap.json
+  a2a-swap idl --json | jq '.idl.instructions[].name'"
+    )]
+    Idl {
+        /// Write the IDL to this file instead of printing it
+        #[arg(long, short, value_name = "PATH")]
+        output: Option<std::path::PathBuf>,
+    },
+
     /// Show total unclaimed LP fees across all positions
     ///
     /// Computes fees_owed (stored on-chain) PLUS fees accrued since the
@@ -823,6 +1874,199 @@ EXAMPLES:
     )]
     MyFees,
 
+    /// Value the wallet, LP positions and pending fees in one quote token
+    ///
+    /// Adds up wallet token balances (native SOL counted as wSOL), each LP
+    /// position's share of the pool reserves, and its claimable fees. Every
+    /// asset is priced at spot through an A2A-Swap pool that pairs it
+    /// directly with --quote; assets without such a pool are listed as
+    /// unpriced and left out of the total. Values are quote atomic units.
+    #[command(
+        after_help = "\
+EXAMPLES:
+  a2a-swap portfolio
+  a2a-swap portfolio --quote SOL
+  a2a-swap portfolio --json | jq .total_value"
+    )]
+    Portfolio {
+        /// Token to value everything in (symbol or mint)
+        #[arg(long, value_name = "TOKEN", default_value = "USDC")]
+        quote: String,
+    },
+
+    /// Show the wallet's protocol fee tier from its tracked swap volume
+    ///
+    /// Agents that opt in with --open get an on-chain volume account; every
+    /// later `convert` adds to it and pays the protocol fee less the
+    /// discount of the tier reached.
+    #[command(
+        after_help = "\
+EXAMPLES:
+  a2a-swap tier
+  a2a-swap tier --open
+  a2a-swap tier --json
+
+NOTES:
+  Volume is the sum of amount_in over tracked swaps, in atomic units of each
+  swap's input token. Swaps made before --open are not counted."
+    )]
+    Tier {
+        /// Start tracking volume (creates the account; the wallet pays its rent)
+        #[arg(long, default_value_t = false)]
+        open: bool,
+    },
+
+    /// Swap back to a target value ratio between two holdings
+    ///
+    /// Values the wallet's balances of both tokens (native SOL counted as
+    /// wSOL) in token B at the pool's spot price. When token A's share of the
+    /// total drifts more than --band from the target, sells the overweight
+    /// token for the amount that restores the ratio, like `convert`. With
+    /// --every it repeats on that interval until interrupted; a failed round
+    /// is reported and the next one still runs.
+    #[command(
+        after_help = "\
+EXAMPLES:
+  # Keep SOL and USDC at equal value, acting past 5 points of drift
+  a2a-swap rebalance --pair SOL-USDC --target 50:50 --band 5%
+
+  # 70% SOL / 30% USDC, checked every 10 minutes
+  a2a-swap rebalance --pair SOL-USDC --target 70:30 --band 2% --every 600
+
+NOTES:
+  Network fees come out of the same SOL balance; keep some SOL outside the
+  target when rebalancing a SOL pair. --json prints one `rebalance` object
+  per round, followed by the `convert` object when it swaps."
+    )]
+    Rebalance {
+        /// Token pair, e.g. SOL-USDC; --target weights follow this order
+        #[arg(long, value_name = "A-B")]
+        pair: String,
+
+        /// Target value ratio A:B, e.g. 50:50 or 70:30
+        #[arg(long, value_name = "A:B")]
+        target: String,
+
+        /// Tolerated drift of token A's value share, in percentage points
+        /// (e.g. 5% or 5)
+        #[arg(long, value_name = "PCT", default_value = "5%")]
+        band: String,
+
+        /// Repeat every SECS seconds instead of running once
+        #[arg(long, value_name = "SECS")]
+        every: Option<u64>,
+
+        /// Slippage guard for the rebalancing swap, as in `convert`.
+        /// [default: profile max_slippage, else 0.5]
+        #[arg(long, value_name = "PCT")]
+        max_slippage: Option<f64>,
+
+        /// Reject the swap if its price impact exceeds this many percent. 0 = no cap.
+        #[arg(long, value_name = "PCT", default_value_t = 0.0)]
+        max_price_impact: f64,
+    },
+
+    /// Grid trading: buy and sell a ladder of price levels around the pool price
+    #[command(subcommand)]
+    Grid(GridCommands),
+
+    /// Show the agent's recent swaps with realized price, fees and P&L
+    ///
+    /// Walks the agent's transaction signatures (newest first), decodes the
+    /// program's swap logs, and prints one row per fill: realized price
+    /// (token B per token A, atomic units), fees paid (protocol + LP, in the
+    /// input token) and mark-to-market P&L against the pool's current spot
+    /// price, in token B atomic units. Costs one getTransaction per signature.
+    #[command(
+        after_help = "\
+EXAMPLES:
+  a2a-swap history
+  a2a-swap history --pair SOL-USDC --limit 20
+  a2a-swap history --json | jq '.trades[] | {signature, pnl_b}'"
+    )]
+    History {
+        /// Only show swaps against this pool, e.g. SOL-USDC
+        #[arg(long, value_name = "PAIR")]
+        pair: Option<String>,
+
+        /// Maximum number of swaps to show
+        #[arg(long, value_name = "N", default_value_t = 50)]
+        limit: usize,
+    },
+
+    /// Write trades, LP positions, LP deposits / withdrawals or fee income to
+    /// a CSV or Parquet file
+    ///
+    /// Trades, liquidity and fees are rebuilt from the agent's transaction
+    /// history, one getTransaction per signature back to --since. Each trade
+    /// carries a running average cost basis per pool and the realized P&L of
+    /// sales, in token B atomic units; token A bought before --since has no
+    /// basis. Fees lists every claim and auto-compound. Positions is a
+    /// snapshot of the open LP positions with their deposit cost basis;
+    /// --since does not apply to it.
+    #[command(
+        after_help = "\
+EXAMPLES:
+  a2a-swap export
+  a2a-swap export --what fees --since 2025-01-01
+  a2a-swap export --format parquet --what trades --since 2025-01-01 -o trades-2025.parquet
+  a2a-swap export --what positions --json
+  a2a-swap export --what liquidity"
+    )]
+    Export {
+        /// File format
+        #[arg(long, value_name = "FORMAT", value_parser = ["csv", "parquet"], default_value = "csv")]
+        format: String,
+
+        /// Records to export
+        #[arg(long, value_name = "WHAT", value_parser = ["trades", "positions", "liquidity", "fees"], default_value = "trades")]
+        what: String,
+
+        /// Only activity at or after this date: YYYY-MM-DD (UTC) or unix seconds
+        #[arg(long, value_name = "DATE")]
+        since: Option<String>,
+
+        /// Output file [default: a2a-swap-<WHAT>.<FORMAT> in the current directory]
+        #[arg(long, short, value_name = "PATH")]
+        output: Option<std::path::PathBuf>,
+    },
+
+    /// Realized gains per token from FIFO / LIFO tax lots
+    ///
+    /// Replays the agent's swaps, LP deposits / withdrawals and fee income
+    /// back to --since, valued in --quote: each acquisition opens a lot at
+    /// its value, each disposal draws on the oldest (fifo) or newest (lifo)
+    /// open lot and realizes the difference. LP shares are lots of their
+    /// pool. Tokens acquired before --since have no lots, so their disposals
+    /// are reported as unmatched. Swaps with neither side in --quote are
+    /// valued at the last price seen for either token, or skipped. Values
+    /// are quote atomic units.
+    #[command(
+        after_help = "\
+EXAMPLES:
+  a2a-swap gains
+  a2a-swap gains --method lifo --since 2025-01-01
+  a2a-swap gains --since 2025-01-01 -o disposals-2025.csv
+  a2a-swap gains --quote SOL --json"
+    )]
+    Gains {
+        /// Token to value lots and gains in (symbol or mint)
+        #[arg(long, value_name = "TOKEN", default_value = "USDC")]
+        quote: String,
+
+        /// Lot matching order
+        #[arg(long, value_name = "METHOD", value_parser = ["fifo", "lifo"], default_value = "fifo")]
+        method: String,
+
+        /// Only activity at or after this date: YYYY-MM-DD (UTC) or unix seconds
+        #[arg(long, value_name = "DATE")]
+        since: Option<String>,
+
+        /// Also write every disposal, lot by lot, to this file (.csv or .parquet)
+        #[arg(long, short, value_name = "PATH")]
+        output: Option<std::path::PathBuf>,
+    },
+
     /// Burn LP shares and withdraw proportional tokens from a pool
     ///
     /// Fees are synced before withdrawal but NOT transferred — run
@@ -842,9 +2086,13 @@ EXAMPLES:
   # Machine-readable output
   a2a-swap remove-liquidity --pair SOL-USDC --shares 1000000 --json
 
+  # Exit completely: also claim remaining fees and close the position
+  a2a-swap remove-liquidity --pair SOL-USDC --shares 1000000 --close-if-empty
+
 NOTES:
   Run `a2a-swap my-positions` to see your current LP share balance.
   Run `a2a-swap claim-fees --pair <PAIR>` after to collect accrued fees.
+  --close-if-empty only acts when --shares is the whole position.
   Amounts are in atomic units (lamports for SOL, μUSDC for USDC, etc.)."
     )]
     RemoveLiquidity {
@@ -863,6 +2111,11 @@ NOTES:
         /// Minimum token B to accept — reject if below (slippage guard, atomic units)
         #[arg(long, value_name = "AMOUNT", default_value_t = 0)]
         min_b: u64,
+
+        /// If this empties the position, pay out its fees and close it to
+        /// reclaim the rent, in the same transaction
+        #[arg(long, default_value_t = false)]
+        close_if_empty: bool,
     },
 
     /// Claim accrued LP trading fees for one pool position
@@ -950,349 +2203,4107 @@ NOTES:
         #[arg(long, value_name = "AMOUNT", default_value_t = 0)]
         min_b: u64,
     },
-}
 
-// ─── Entry point ──────────────────────────────────────────────────────────────
+    /// Change an LP position's settings, or close an empty one
+    #[command(subcommand)]
+    Position(PositionCommands),
 
-fn main() -> Result<()> {
-    // When invoked with no arguments, show banner + full help and exit cleanly.
-    if std::env::args().len() == 1 {
-        print_banner();
-        Cli::command().print_long_help().ok();
-        println!();
-        return Ok(());
-    }
+    /// Restrict a pool's swaps to an allowlist of traders, and join one
+    #[command(subcommand)]
+    Allowlist(AllowlistCommands),
 
-    let cli = Cli::parse();
+    /// Restrict a pool's swaps to holders of a credential token
+    #[command(subcommand)]
+    Gate(GateCommands),
 
-    match &cli.command {
-        Commands::CreatePool { pair, initial_price, seed_amount, fee_bps } => {
-            cmd_create_pool(
-                &cli.rpc_url, &cli.keypair,
-                pair, *initial_price, *seed_amount, *fee_bps,
-                cli.json,
-            )?;
-        }
-        Commands::Provide { pair, amount, amount_b, auto_compound, compound_threshold } => {
-            cmd_provide(
-                &cli.rpc_url, &cli.keypair,
-                pair, *amount, *amount_b, *auto_compound, *compound_threshold,
-                cli.json,
-            )?;
-        }
-        Commands::Convert { token_in, token_out, amount, approval_mode, webhook_url, max_slippage } => {
-            cmd_convert(
-                &cli.rpc_url, &cli.keypair,
-                token_in, token_out, *amount,
-                approval_mode, webhook_url.as_deref(), *max_slippage,
-                cli.json,
-            )?;
-        }
-        Commands::Simulate { token_in, token_out, amount, mode } => {
-            cmd_simulate(&cli.rpc_url, token_in, token_out, *amount, mode, cli.json)?;
-        }
-        Commands::MyPositions => {
-            cmd_my_positions(&cli.rpc_url, &cli.keypair, cli.json)?;
-        }
-        Commands::PoolInfo { pair } => {
-            cmd_pool_info(&cli.rpc_url, pair, cli.json)?;
-        }
-        Commands::ActivePools => {
-            cmd_active_pools(&cli.rpc_url, cli.json)?;
-        }
-        Commands::MyFees => {
-            cmd_my_fees(&cli.rpc_url, &cli.keypair, cli.json)?;
-        }
-        Commands::RemoveLiquidity { pair, shares, min_a, min_b } => {
-            cmd_remove_liquidity(
-                &cli.rpc_url, &cli.keypair,
-                pair, *shares, *min_a, *min_b,
-                cli.json,
-            )?;
-        }
-        Commands::ClaimFees { pair, all } => {
-            if *all {
-                cmd_claim_fees_all(&cli.rpc_url, &cli.keypair, cli.json)?;
-            } else {
-                let p = pair.as_deref().ok_or_else(|| anyhow!(
-                    "Provide --pair <A-B> or --all.\n  \
-                     Example: a2a-swap claim-fees --pair SOL-USDC\n  \
-                     Example: a2a-swap claim-fees --all"
-                ))?;
-                cmd_claim_fees(&cli.rpc_url, &cli.keypair, p, cli.json)?;
-            }
-        }
-        Commands::Remove { pair, percentage, amount, min_a, min_b } => {
-            cmd_remove(
-                &cli.rpc_url, &cli.keypair,
-                pair, *percentage, *amount, *min_a, *min_b,
-                cli.json,
-            )?;
-        }
-    }
+    /// Token-holder governance of the protocol fee, pool fee bounds and pauses
+    #[command(subcommand)]
+    Governance(GovernanceCommands),
 
-    Ok(())
-}
+    /// Local-validator developer tooling (refuses non-loopback RPC endpoints)
+    #[command(subcommand)]
+    Dev(DevCommands),
 
-// ─── create-pool ─────────────────────────────────────────────────────────────
+    /// Live terminal dashboard: pools, positions, pending fees, recent swaps
+    ///
+    /// Watches every pool the agent has a position in, plus any --pair given.
+    /// Updates stream over the RPC websocket (accountSubscribe / logsSubscribe);
+    /// if it cannot be reached the dashboard polls every --refresh seconds.
+    /// Keys: q / Esc quit, r reload positions.
+    #[command(
+        after_help = "\
+EXAMPLES:
+  a2a-swap dashboard
+  a2a-swap dashboard --pair SOL-USDC --pair BONK-USDC
+  a2a-swap dashboard --owner <AGENT_PUBKEY>
+  a2a-swap --rpc-url https://my-rpc.example.com dashboard --ws-url wss://my-rpc.example.com"
+    )]
+    Dashboard {
+        /// Extra pool to watch, e.g. SOL-USDC (repeatable)
+        #[arg(long, value_name = "PAIR")]
+        pair: Vec<String>,
 
-fn cmd_create_pool(
-    rpc_url: &str,
-    keypair_path: &str,
-    pair: &str,
-    initial_price: f64,
-    seed_amount: u64,
-    fee_rate_bps: u16,
-    json_output: bool,
-) -> Result<()> {
-    let (sym_a, sym_b, mint_a, mint_b) = parse_pair(pair)?;
-    if !(1..=100).contains(&fee_rate_bps) {
-        return Err(anyhow!(
-            "--fee-bps {} is out of range. Allowed: 1–100 (0.01%–1.00%).",
-            fee_rate_bps
-        ));
-    }
-    if initial_price <= 0.0 {
-        return Err(anyhow!(
-            "--initial-price must be > 0 (number of {} per {}).",
-            sym_b, sym_a
-        ));
+        /// Wallet whose positions to show (default: the --keypair public key)
+        #[arg(long, value_name = "PUBKEY")]
+        owner: Option<String>,
+
+        /// Websocket endpoint (default: derived from --rpc-url)
+        #[arg(long, value_name = "URL")]
+        ws_url: Option<String>,
+
+        /// Polling interval in seconds when the websocket is unavailable
+        #[arg(long, value_name = "SECS", default_value_t = 5)]
+        refresh: u64,
+    },
+
+    /// Watch the agent's LP positions and alert or exit when a rule trips
+    ///
+    /// Runs until interrupted. Every `interval` seconds it reads the agent's
+    /// positions and their pools and checks each [[rule]] in the config:
+    /// impermanent loss beyond il_pct, pool fee APR below min_fee_apr, or a
+    /// reserve more than reserve_drop_pct below its high within `window`.
+    /// A condition fires once when it starts holding, is sent to --notify
+    /// (or the file's `notify`), and runs the rule's action: notify,
+    /// claim-fees or remove (withdraw the whole position).
+    #[command(
+        after_help = "\
+EXAMPLES:
+  a2a-swap monitor --config monitor.toml
+  a2a-swap monitor --config monitor.toml --notify slack://T000/B000/XXXX --json
+
+monitor.toml:
+  interval = 60                 # seconds between checks, default 60
+  window   = 3600               # seconds of pool history, default 3600
+  [[rule]]
+  pair             = \"SOL-USDC\"  # omit to match every position
+  il_pct           = 5.0
+  min_fee_apr      = 10.0
+  reserve_drop_pct = 30.0
+  action           = \"remove\"    # notify (default) | claim-fees | remove"
+    )]
+    Monitor {
+        /// Rules file (TOML)
+        #[arg(long, value_name = "FILE")]
+        config: std::path::PathBuf,
+
+        /// Run one check and exit
+        #[arg(long, default_value_t = false)]
+        once: bool,
+    },
+
+    /// Run an approver: co-sign swaps that a human approves
+    #[command(subcommand)]
+    Approver(ApproverCommands),
+
+    /// Read and write profile settings in ~/.config/a2a-swap/config.toml
+    ///
+    /// Keys: rpc_url, keypair, max_slippage (percent), priority_fee
+    /// (micro-lamports per compute unit), network (mainnet-beta, devnet,
+    /// localnet), notify (--notify target). The profile is chosen with --profile; without it the
+    /// file's default_profile, else "default".
+    #[command(
+        subcommand,
+        after_help = "\
+EXAMPLES:
+  a2a-swap --profile prod config set rpc_url https://my-rpc.example.com
+  a2a-swap --profile prod config set priority_fee 10000
+  a2a-swap --profile prod config get
+  a2a-swap config get rpc_url --json
+  a2a-swap config set default_profile prod"
+    )]
+    Config(ConfigCommands),
+
+    /// Name agent keypairs and switch between them
+    ///
+    /// Wallets live in ~/.config/a2a-swap/config.toml next to the profiles:
+    /// each has a keypair path and optional max_slippage, priority_fee and
+    /// notify defaults that override the profile's. Commands sign with
+    /// --wallet, else the wallet chosen with `wallets use`; --keypair and
+    /// A2A_KEYPAIR still take precedence over both.
+    #[command(
+        subcommand,
+        after_help = "\
+EXAMPLES:
+  a2a-swap wallets add treasury ~/agent-keys/treasury.json --max-slippage 0.1
+  a2a-swap wallets add mm ~/agent-keys/mm.json --priority-fee 50000 --use
+  a2a-swap wallets list
+  a2a-swap wallets use mm
+  a2a-swap --wallet treasury my-positions"
+    )]
+    Wallets(WalletsCommands),
+
+    /// Encrypt agent keypairs under a passphrase, and decrypt them again
+    ///
+    /// A keystore is a JSON file holding the keypair sealed with scrypt and
+    /// AES-256-GCM-SIV; its public key stays readable. Keystores work
+    /// wherever a keypair path does (--keypair, profiles, wallets): commands
+    /// that sign ask for the passphrase, or read A2A_KEYSTORE_PASSPHRASE.
+    /// Plain id.json files keep working unchanged.
+    #[command(
+        subcommand,
+        after_help = "\
+EXAMPLES:
+  a2a-swap key import ~/.config/solana/id.json -o ~/agent-keys/agent.keystore.json
+  a2a-swap --keypair ~/agent-keys/agent.keystore.json key unlock
+  A2A_KEYSTORE_PASSPHRASE=... a2a-swap --keypair ~/agent-keys/agent.keystore.json convert --in SOL --out USDC --amount 1000000000
+  a2a-swap key export ~/agent-keys/agent.keystore.json -o /tmp/id.json"
+    )]
+    Key(KeyCommands),
+
+    /// List and extend the token symbols --in / --out / --pair accept
+    ///
+    /// Built in: SOL, USDC, USDT. `add` and `import` write to
+    /// ~/.config/a2a-swap/tokens.json (or $A2A_TOKENS), which every command
+    /// reads; its entries override the built-ins.
+    #[command(
+        subcommand,
+        after_help = "\
+EXAMPLES:
+  a2a-swap tokens list
+  a2a-swap tokens add BONK DezXAZ8z7PnrnRJjz3wXBoRgixCa6xjnB7YaB1pPB263 --decimals 5
+  a2a-swap tokens import https://raw.githubusercontent.com/solana-labs/token-list/main/src/tokens/solana.tokenlist.json
+  a2a-swap tokens import ./my-tokens.json"
+    )]
+    Tokens(TokensCommands),
+
+    /// Print a shell completion script to stdout
+    #[command(
+        after_help = "\
+EXAMPLES:
+  a2a-swap completions bash > ~/.local/share/bash-completion/completions/a2a-swap
+  a2a-swap completions zsh  > ~/.zfunc/_a2a-swap
+  a2a-swap completions fish > ~/.config/fish/completions/a2a-swap.fish"
+    )]
+    Completions {
+        /// Target shell
+        #[arg(value_name = "SHELL")]
+        shell: clap_complete::Shell,
+    },
+
+    /// Print the full command / flag tree as JSON for programmatic introspection
+    ///
+    /// Every command with its arguments: long/short names, value type,
+    /// default, env var, allowed values, and whether it is required or global.
+    /// Global arguments are listed once, on the root command.
+    #[command(
+        after_help = "\
+EXAMPLES:
+  a2a-swap schema --json
+  a2a-swap schema --json | jq '.subcommands[] | select(.name == \"convert\") | .args'"
+    )]
+    Schema,
+}
+
+#[derive(Subcommand)]
+enum ConfigCommands {
+    /// Set a key in the selected profile (or `default_profile` itself)
+    Set {
+        /// rpc_url | keypair | max_slippage | priority_fee | network | notify | default_profile
+        key: String,
+        /// New value
+        value: String,
+    },
+
+    /// Print one key, or the whole profile when KEY is omitted
+    Get {
+        /// rpc_url | keypair | max_slippage | priority_fee | network | notify | default_profile
+        key: Option<String>,
+    },
+}
+
+#[derive(Subcommand)]
+enum WalletsCommands {
+    /// Add (or replace) a named wallet
+    Add {
+        /// Wallet name, used with --wallet
+        name: String,
+        /// Path to the wallet's Ed25519 keypair JSON file
+        keypair: String,
+        /// Default `convert --max-slippage` for this wallet, in percent
+        #[arg(long, value_name = "PCT")]
+        max_slippage: Option<f64>,
+        /// Compute-unit price in micro-lamports for this wallet's transactions
+        #[arg(long, value_name = "MICRO_LAMPORTS")]
+        priority_fee: Option<u64>,
+        /// --notify target for this wallet's transactions
+        #[arg(long, value_name = "TARGET")]
+        notify: Option<String>,
+        /// Also make it the default wallet
+        #[arg(long = "use")]
+        use_it: bool,
+    },
+
+    /// Print every wallet with its public key and defaults
+    List,
+
+    /// Sign with this wallet from now on (sets default_wallet)
+    Use {
+        /// Wallet name
+        name: String,
+    },
+}
+
+#[derive(Subcommand)]
+enum KeyCommands {
+    /// Encrypt a plain keypair JSON file into a keystore
+    Import {
+        /// Plain Ed25519 keypair JSON (solana-keygen format)
+        keypair: String,
+        /// Keystore to write [default: KEYPAIR with a .keystore.json extension]
+        #[arg(short, long, value_name = "PATH")]
+        output: Option<String>,
+        /// scrypt cost as log2(N); each step doubles unlock time and memory
+        #[arg(long, value_name = "N", default_value_t = ScryptParams::DEFAULT.log_n)]
+        scrypt_log_n: u8,
+        /// Overwrite the output file if it exists
+        #[arg(long)]
+        force: bool,
+    },
+
+    /// Decrypt a keystore into a plain keypair JSON file
+    Export {
+        /// Keystore written by `key import`
+        keystore: String,
+        /// Plain keypair file to write
+        #[arg(short, long, value_name = "PATH")]
+        output: String,
+        /// Overwrite the output file if it exists
+        #[arg(long)]
+        force: bool,
+    },
+
+    /// Check a keystore's passphrase and print its public key
+    Unlock {
+        /// Keystore to open [default: the resolved --keypair]
+        keystore: Option<String>,
+    },
+}
+
+#[derive(Subcommand)]
+enum TokensCommands {
+    /// Print every known symbol with its mint and decimals
+    List,
+
+    /// Add (or replace) one symbol in the user token list
+    Add {
+        /// Ticker, e.g. BONK (matched case-insensitively)
+        symbol: String,
+        /// Base-58 mint address
+        mint: String,
+        /// Mint decimals
+        #[arg(long, value_name = "N")]
+        decimals: u8,
+        /// Display name
+        #[arg(long)]
+        name: Option<String>,
+    },
+
+    /// Merge a token list (file path or http(s) URL) into the user token list
+    Import {
+        /// Path or URL of a token list JSON ({"tokens": [...]} or a bare array)
+        source: String,
+    },
+}
+
+#[derive(Subcommand)]
+enum PositionCommands {
+    /// Turn auto-compound on or off, or change its threshold
+    ///
+    /// Sends update_position_settings for the keypair's position in the
+    /// pool; no tokens move. Omitted flags keep their current value.
+    #[command(
+        after_help = "\
+EXAMPLES:
+  a2a-swap position set --pair SOL-USDC --auto-compound on --threshold 1000000
+  a2a-swap position set --pair SOL-USDC --auto-compound off
+  a2a-swap position set --pair SOL-USDC --threshold 0 --json
+
+NOTES:
+  The threshold is the combined fee balance (token A + B, atomic units) at
+  which claim-fees compounds instead of paying out. 0 = every claim."
+    )]
+    Set {
+        /// Token pair of the pool, e.g. SOL-USDC or <mintA>-<mintB>
+        #[arg(long, value_name = "A-B")]
+        pair: String,
+
+        /// Reinvest claimed fees into LP shares (on) or pay them out (off)
+        #[arg(long, value_name = "ON|OFF", value_parser = ["on", "off"])]
+        auto_compound: Option<String>,
+
+        /// Minimum combined fee balance (token A + B, atomic units) before
+        /// auto-compound fires
+        #[arg(long, value_name = "AMOUNT")]
+        threshold: Option<u64>,
+    },
+
+    /// Close an empty position and reclaim its rent
+    ///
+    /// The position must hold no LP shares and no unclaimed fees: run
+    /// remove-liquidity and claim-fees first, or remove-liquidity
+    /// --close-if-empty to do all three at once.
+    #[command(
+        after_help = "\
+EXAMPLES:
+  a2a-swap position close --pair SOL-USDC
+  a2a-swap position close --pair SOL-USDC --json"
+    )]
+    Close {
+        /// Token pair of the pool, e.g. SOL-USDC or <mintA>-<mintB>
+        #[arg(long, value_name = "A-B")]
+        pair: String,
+    },
+}
+
+#[derive(Subcommand)]
+enum AllowlistCommands {
+    /// Make a pool permissioned: only the listed traders may swap (creator only)
+    ///
+    /// Sets the pool's allowlist to the merkle root of the traders in
+    /// --traders. Each trader then registers once with a proof from
+    /// `allowlist proof`. Replacing the list makes every trader register
+    /// again; liquidity provision is not restricted.
+    #[command(
+        after_help = "\
+EXAMPLES:
+  a2a-swap allowlist set --pair SOL-USDC --traders traders.txt
+  a2a-swap allowlist set --pair <mintA>-<mintB> --traders traders.json --json
+
+NOTES:
+  The traders file lists one wallet per line (blank lines and # comments
+  are skipped) or is a JSON array of wallet strings. Order and duplicates
+  don't change the root."
+    )]
+    Set {
+        /// Token pair of the pool, e.g. SOL-USDC or <mintA>-<mintB>
+        #[arg(long, value_name = "A-B")]
+        pair: String,
+
+        /// File listing the allowed traders' wallets
+        #[arg(long, value_name = "FILE")]
+        traders: String,
+    },
+
+    /// Open a permissioned pool to every trader again (creator only)
+    #[command(
+        after_help = "\
+EXAMPLES:
+  a2a-swap allowlist clear --pair SOL-USDC"
+    )]
+    Clear {
+        /// Token pair of the pool, e.g. SOL-USDC or <mintA>-<mintB>
+        #[arg(long, value_name = "A-B")]
+        pair: String,
+    },
+
+    /// Print the allowlist root and a trader's proof (offline)
+    ///
+    /// Share the proof with the trader, who passes it to
+    /// `allowlist register --proof`.
+    #[command(
+        after_help = "\
+EXAMPLES:
+  a2a-swap allowlist proof --traders traders.txt --trader <WALLET>
+  a2a-swap allowlist proof --traders traders.txt --trader <WALLET> --json"
+    )]
+    Proof {
+        /// File listing the allowed traders' wallets (see `allowlist set`)
+        #[arg(long, value_name = "FILE")]
+        traders: String,
+
+        /// Wallet to prove membership for
+        #[arg(long, value_name = "PUBKEY")]
+        trader: String,
+    },
+
+    /// Prove the keypair is on a pool's allowlist so it may swap there
+    ///
+    /// Creates (or refreshes after the list changed) the keypair's trader
+    /// pass; the keypair pays its rent. convert adds the pass to swaps on
+    /// permissioned pools automatically.
+    #[command(
+        after_help = "\
+EXAMPLES:
+  a2a-swap allowlist register --pair SOL-USDC --proof <HEX>,<HEX>,<HEX>
+  a2a-swap allowlist register --pair SOL-USDC --traders traders.txt"
+    )]
+    Register {
+        /// Token pair of the pool, e.g. SOL-USDC or <mintA>-<mintB>
+        #[arg(long, value_name = "A-B")]
+        pair: String,
+
+        /// Comma-separated proof hashes from `allowlist proof`
+        #[arg(long, value_name = "HEX,...", required_unless_present = "traders")]
+        proof: Option<String>,
+
+        /// Build the proof from the full traders file instead
+        #[arg(long, value_name = "FILE", conflicts_with = "proof")]
+        traders: Option<String>,
+    },
+}
+
+#[derive(Subcommand)]
+enum GateCommands {
+    /// Token-gate a pool: only holders of --mint may swap (creator only)
+    ///
+    /// --mint is typically a soulbound credential or attestation (SPL Token
+    /// or Token-2022). A swap must then pass the trader's account of it
+    /// holding a balance; convert adds it automatically. Liquidity
+    /// provision is not restricted.
+    #[command(
+        after_help = "\
+EXAMPLES:
+  a2a-swap gate set --pair SOL-USDC --mint <CREDENTIAL_MINT>
+  a2a-swap gate set --pair <mintA>-<mintB> --mint <CREDENTIAL_MINT> --json"
+    )]
+    Set {
+        /// Token pair of the pool, e.g. SOL-USDC or <mintA>-<mintB>
+        #[arg(long, value_name = "A-B")]
+        pair: String,
+
+        /// Mint traders must hold
+        #[arg(long, value_name = "MINT")]
+        mint: String,
+    },
+
+    /// Lift a pool's token gate (creator only)
+    #[command(
+        after_help = "\
+EXAMPLES:
+  a2a-swap gate clear --pair SOL-USDC"
+    )]
+    Clear {
+        /// Token pair of the pool, e.g. SOL-USDC or <mintA>-<mintB>
+        #[arg(long, value_name = "A-B")]
+        pair: String,
+    },
+}
+
+#[derive(Subcommand)]
+enum GovernanceCommands {
+    /// Hand the protocol config to holders of a vote token (admin, one-time)
+    ///
+    /// The keypair must be the protocol config admin. Afterwards only
+    /// proposals passed by --vote-mint holders change the protocol fee, pool
+    /// fee bounds, pause switches and pools' dynamic fees and circuit
+    /// breakers.
+    #[command(
+        after_help = "\
+EXAMPLES:
+  a2a-swap governance init --vote-mint <MINT> --quorum 1000000000 --voting-period 259200"
+    )]
+    Init {
+        /// Token voters escrow; one atomic unit is one vote
+        #[arg(long, value_name = "MINT")]
+        vote_mint: String,
+
+        /// Least votes (for + against, atomic units) a proposal needs to pass
+        #[arg(long, value_name = "AMOUNT")]
+        quorum: u64,
+
+        /// How long each proposal is open for votes (seconds)
+        #[arg(long, value_name = "SECS")]
+        voting_period: i64,
+    },
+
+    /// Propose a protocol config or pool change; voting opens immediately
+    #[command(
+        after_help = "\
+EXAMPLES:
+  a2a-swap governance propose --protocol-fee 25 --referral-share 1000
+  a2a-swap governance propose --pool-fee-bounds 5-300
+  a2a-swap governance propose --pause swaps
+  a2a-swap governance propose --pause none
+  a2a-swap governance propose --fee-tier 0:1000000000000:2500
+  a2a-swap governance propose --locker 0:<LENDER_PROGRAM_ID>
+  a2a-swap governance propose --pair SOL-USDC --dynamic-fee 5-50
+  a2a-swap governance propose --pair SOL-USDC --circuit-breaker 500
+
+NOTES:
+  Give exactly one of --protocol-fee, --pool-fee-bounds, --pause, --fee-tier,
+  --locker, or --pair with --dynamic-fee or --circuit-breaker.
+  --protocol-fee is out of 100_000 (max 1_000 = 1%); --referral-share is the
+  referrer's cut of it, out of 10_000. Pool fee bounds are at most 1000 bps.
+  --fee-tier INDEX:MIN_VOLUME:DISCOUNT sets tier 0-3: agents whose tracked
+  volume reaches MIN_VOLUME get DISCOUNT (out of 10_000) off the protocol
+  fee; a discount of 0 clears the tier.
+  --locker INDEX:PROGRAM approves PROGRAM (slot 0-3) to lock LP positions as
+  collateral; PROGRAM 11111111111111111111111111111111 clears the slot.
+  --dynamic-fee MIN-MAX bounds the pool's volatility-driven LP fee (at most
+  100 bps); 0-0 turns it off. --circuit-breaker caps the pool's net price
+  move per slot, out of 10_000; 0 turns it off."
+    )]
+    Propose {
+        /// New protocol fee, out of 100_000 (requires --referral-share)
+        #[arg(long, value_name = "BPS", requires = "referral_share")]
+        protocol_fee: Option<u16>,
+
+        /// New referrer share of the protocol fee, out of 10_000
+        #[arg(long, value_name = "BPS", requires = "protocol_fee")]
+        referral_share: Option<u16>,
+
+        /// Fee range new pools must use, in bps, e.g. 5-300
+        #[arg(long, value_name = "MIN-MAX", conflicts_with_all = ["protocol_fee", "pause"])]
+        pool_fee_bounds: Option<String>,
+
+        /// Operations to switch off; none resumes everything
+        #[arg(long, value_name = "WHAT", value_parser = ["none", "swaps", "pool-creation", "all"],
+              conflicts_with = "protocol_fee")]
+        pause: Option<String>,
+
+        /// Protocol fee discount tier, INDEX:MIN_VOLUME:DISCOUNT_BPS
+        #[arg(long, value_name = "TIER", conflicts_with_all = ["protocol_fee", "pool_fee_bounds", "pause"])]
+        fee_tier: Option<String>,
+
+        /// Program allowed to lock positions as collateral, INDEX:PROGRAM
+        #[arg(long, value_name = "LOCKER",
+              conflicts_with_all = ["protocol_fee", "pool_fee_bounds", "pause", "fee_tier"])]
+        locker: Option<String>,
+
+        /// Pool a --dynamic-fee or --circuit-breaker proposal changes, e.g. SOL-USDC
+        #[arg(long, value_name = "PAIR",
+              conflicts_with_all = ["protocol_fee", "pool_fee_bounds", "pause", "fee_tier", "locker"])]
+        pair: Option<String>,
+
+        /// Bounds for the pool's volatility-driven LP fee, in bps, e.g. 5-50
+        #[arg(long, value_name = "MIN-MAX", requires = "pair")]
+        dynamic_fee: Option<String>,
+
+        /// Cap on the pool's net price move per slot, out of 10_000
+        #[arg(long, value_name = "BPS", requires = "pair", conflicts_with = "dynamic_fee")]
+        circuit_breaker: Option<u16>,
+    },
+
+    /// List proposals with their tallies and outcome
+    #[command(
+        after_help = "\
+EXAMPLES:
+  a2a-swap governance proposals
+  a2a-swap governance proposals --json"
+    )]
+    Proposals,
+
+    /// Vote on an open proposal, escrowing vote tokens until withdraw
+    #[command(
+        after_help = "\
+EXAMPLES:
+  a2a-swap governance vote --proposal 3 --for --amount 500000000
+  a2a-swap governance vote --proposal 3 --against --amount 500000000
+
+NOTES:
+  Tokens come from the keypair's associated account for the vote mint and
+  stay in the vote vault until `governance withdraw` after voting closes.
+  One vote per wallet per proposal."
+    )]
+    Vote {
+        /// Proposal id (see `governance proposals`)
+        #[arg(long, value_name = "ID")]
+        proposal: u64,
+
+        /// Vote for the proposal
+        #[arg(long = "for", required_unless_present = "against")]
+        support: bool,
+
+        /// Vote against the proposal
+        #[arg(long, conflicts_with = "support")]
+        against: bool,
+
+        /// Vote tokens to escrow (atomic units); the vote's weight
+        #[arg(long, value_name = "AMOUNT")]
+        amount: u64,
+    },
+
+    /// Apply a passed proposal once voting has closed (anyone may)
+    #[command(
+        after_help = "\
+EXAMPLES:
+  a2a-swap governance execute --proposal 3"
+    )]
+    Execute {
+        /// Proposal id (see `governance proposals`)
+        #[arg(long, value_name = "ID")]
+        proposal: u64,
+    },
+
+    /// Return escrowed vote tokens once voting on a proposal has closed
+    #[command(
+        after_help = "\
+EXAMPLES:
+  a2a-swap governance withdraw --proposal 3"
+    )]
+    Withdraw {
+        /// Proposal id (see `governance proposals`)
+        #[arg(long, value_name = "ID")]
+        proposal: u64,
+    },
+}
+
+#[derive(Subcommand)]
+enum ApproverCommands {
+    /// Co-sign approve_and_execute swaps approved in a Telegram chat
+    ///
+    /// Holds --keypair (or the --signer hardware wallet) as the approver key
+    /// and runs until interrupted. Agents
+    /// post requests with `convert --approval-mode telegram --approver <this
+    /// key>`. An Approve press from an --allow user checks the transaction
+    /// (agent-signed, one approve_and_execute, approver key used nowhere
+    /// else), co-signs and submits it; Reject closes the request. Requests
+    /// expire with their blockhash, after roughly a minute.
+    #[command(
+        after_help = "\
+EXAMPLES:
+  export A2A_TELEGRAM_BOT_TOKEN=123456:ABC...
+  a2a-swap --keypair ~/keys/approver.json approver telegram \\
+    --telegram-chat -1001234567890 --allow 11111111 --allow 22222222
+  # Approver key on a Ledger (Solana app open, blind signing enabled):
+  a2a-swap approver telegram --signer ledger://?key=0 \\
+    --telegram-chat -1001234567890 --allow 11111111"
+    )]
+    Telegram {
+        /// Chat the agents post requests to; presses elsewhere are ignored
+        #[arg(long, value_name = "CHAT_ID", env = "A2A_TELEGRAM_CHAT", allow_hyphen_values = true)]
+        telegram_chat: i64,
+
+        /// Telegram user id allowed to approve or reject (repeatable)
+        #[arg(long, value_name = "USER_ID", required = true)]
+        allow: Vec<i64>,
+
+        /// Telegram bot token
+        #[arg(long, value_name = "TOKEN", env = "A2A_TELEGRAM_BOT_TOKEN", hide_env_values = true)]
+        telegram_bot_token: String,
+
+        /// Sign with a remote signer instead of --keypair:
+        /// ledger://[?key=ACCOUNT[/CHANGE]] (each approval confirmed on the
+        /// device, within the blockhash lifetime), turnkey://ORG_ID/ADDRESS
+        /// (TURNKEY_API_PRIVATE_KEY) or privy://WALLET_ID (PRIVY_APP_ID,
+        /// PRIVY_APP_SECRET)
+        #[arg(long, value_name = "URI", env = "A2A_SIGNER")]
+        signer: Option<String>,
+    },
+}
+
+#[derive(Subcommand)]
+enum GridCommands {
+    /// Run the grid in a config file until interrupted
+    ///
+    /// Levels sit `spacing` percent apart around the center (the pool's spot
+    /// price when the grid is first created). Each step reads the spot price
+    /// and, for every level it has crossed since the last fill, swaps one
+    /// `level_size` of base: selling above, buying below. A level never fills
+    /// more than `max_slippage` below its own price — if the pool can't
+    /// honour that, it stays armed. State is saved after every step and
+    /// picked up on the next run; a failed step is reported and the next one
+    /// still runs.
+    #[command(
+        after_help = "\
+EXAMPLES:
+  a2a-swap grid run --config grid.toml
+  a2a-swap grid run --config grid.toml --once --json
+
+grid.toml:
+  pair                = \"SOL-USDC\"   # base-quote
+  levels              = 5            # per side
+  spacing             = 1.0          # percent
+  level_size          = 100000000    # base atomic units per level
+  max_slippage        = 0.3          # percent, default 0.5
+  max_price_impact    = 1.0          # percent, default 0 = no cap
+  max_levels_per_step = 2            # default 0 = no limit
+  interval            = 30           # seconds, default 30
+  # center_price = 0.185  state = \"grid.state.json\"  referrer = \"<pubkey>\"
+
+NOTES:
+  Level swaps are sent through the Rust SDK client: --priority-fee does not
+  apply. Keep both tokens (and SOL for fees) in the wallet. Delete the state
+  file to re-center the grid."
+    )]
+    Run {
+        /// Grid config file (TOML)
+        #[arg(long, value_name = "FILE")]
+        config: std::path::PathBuf,
+
+        /// Run a single step and exit
+        #[arg(long)]
+        once: bool,
+    },
+}
+
+#[derive(Subcommand)]
+enum DevCommands {
+    /// Create two test mints, fund the keypair, and seed a pool — in one command
+    ///
+    /// Requires `solana-test-validator` with the program deployed. Airdrops
+    /// SOL if needed, creates two fresh mints (keypair is mint authority),
+    /// mints balances into the keypair's ATAs, creates a pool for the pair,
+    /// and deposits the initial liquidity.
+    #[command(
+        after_help = "\
+EXAMPLES:
+  solana-test-validator --bpf-program 8XJfG4mHqRZjByAd7HxHdEALfB8jVtJVQsdhGEmysTFq \\
+    target/deploy/a2a_swap.so --reset &
+
+  a2a-swap dev bootstrap --rpc-url http://127.0.0.1:8899
+  a2a-swap dev bootstrap --rpc-url http://127.0.0.1:8899 --amount-b 185000000000 --json
+
+NOTES:
+  Prints the new mints as a <mintA>-<mintB> pair usable with every other command."
+    )]
+    Bootstrap {
+        /// Decimals for both test mints
+        #[arg(long, value_name = "N", default_value_t = 6)]
+        decimals: u8,
+
+        /// Amount of each test token minted to the keypair (atomic units)
+        #[arg(long, value_name = "AMOUNT", default_value_t = 1_000_000_000_000)]
+        mint_amount: u64,
+
+        /// Token A deposited as initial liquidity (atomic units)
+        #[arg(long, value_name = "AMOUNT", default_value_t = 100_000_000_000)]
+        amount_a: u64,
+
+        /// Token B deposited as initial liquidity (atomic units) — sets the price
+        #[arg(long, value_name = "AMOUNT", default_value_t = 100_000_000_000)]
+        amount_b: u64,
+
+        /// LP fee for the new pool (basis points, 1–100)
+        #[arg(long, value_name = "BPS", default_value_t = 30)]
+        fee_bps: u16,
+
+        /// Airdrop the keypair up to this many SOL. 0 = skip the airdrop.
+        #[arg(long, value_name = "SOL", default_value_t = 10.0)]
+        airdrop: f64,
+    },
+}
+
+// ─── Entry point ──────────────────────────────────────────────────────────────
+
+fn main() -> ExitCode {
+    // When invoked with no arguments, show banner + full help and exit cleanly.
+    if std::env::args().len() == 1 {
+        print_banner();
+        Cli::command().print_long_help().ok();
+        println!();
+        return ExitCode::SUCCESS;
+    }
+
+    // --json is global, so it is known even when the arguments do not parse.
+    let json = std::env::args().any(|arg| arg == "--json");
+    let parsed = Cli::command()
+        .try_get_matches()
+        .and_then(|matches| Ok((matches.subcommand_name().map(str::to_owned), Cli::from_arg_matches(&matches)?)));
+    let (command, result) = match parsed {
+        Ok((command, cli)) => (command, execute(cli)),
+        // --help and --version, or a usage error outside --json.
+        Err(e) if !json || !e.use_stderr() => e.exit(),
+        Err(e) => (None, Err(e.into())),
+    };
+
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            if json {
+                println!("{}", error::to_json(command.as_deref(), &e));
+            } else {
+                eprintln!("Error: {e:?}");
+            }
+            ExitCode::from(error::classify(&e).exit_code)
+        }
+    }
+}
+
+/// Run the parsed command line.
+fn execute(cli: Cli) -> Result<()> {
+    // Introspection needs no config, keypair or RPC.
+    match &cli.command {
+        Commands::Completions { shell } => return cmd_completions(*shell),
+        Commands::Schema                => return cmd_schema(cli.json),
+        Commands::Tokens(cmd)           => return cmd_tokens(cmd, cli.json),
+        _ => {}
+    }
+
+    let cfg = config::Config::load()?;
+
+    match &cli.command {
+        Commands::Config(cmd)  => return cmd_config(cfg, cli.profile.as_deref(), cmd, cli.json),
+        Commands::Wallets(cmd) => return cmd_wallets(cfg, cmd, cli.json),
+        _ => {}
+    }
+
+    // Flag / env var → wallet → profile → built-in default.
+    let mut profile = cfg.profile(cli.profile.as_deref())?;
+    if let Some((_, wallet)) = cfg.wallet(cli.wallet.as_deref())? {
+        profile = profile.with_wallet(wallet);
+    }
+    let rpc_url = match (&cli.rpc_url, &profile.rpc_url, &profile.network) {
+        (Some(url), _, _) | (None, Some(url), _) => url.clone(),
+        (None, None, Some(network))              => config::network_rpc(network)?.to_string(),
+        (None, None, None)                       => DEFAULT_RPC_URL.to_string(),
+    };
+    let keypair = cli.keypair.clone()
+        .or(profile.keypair.clone())
+        .unwrap_or_else(|| DEFAULT_KEYPAIR.to_string());
+    PRIORITY_FEE.get_or_init(|| cli.priority_fee.or(profile.priority_fee).unwrap_or(0));
+
+    let notify = cli.notify.as_deref()
+        .or(profile.notify.as_deref())
+        .map(notify::Target::parse)
+        .transpose()?;
+
+    let result = run(&cli, &profile, &rpc_url, &keypair);
+    if let (Some(target), Some(command)) = (&notify, notify_command(&cli.command)) {
+        notify::send(target, command, &result);
+    }
+    result
+}
+
+/// Run every command that needs the resolved RPC URL and keypair.
+fn run(cli: &Cli, profile: &config::Profile, rpc_url: &str, keypair: &str) -> Result<()> {
+    match &cli.command {
+        Commands::CreatePool { pair, initial_price, seed_amount, seed, fee_bps, stable_amp, wizard } => {
+            if *wizard {
+                cmd_create_pool_wizard(
+                    rpc_url, keypair,
+                    pair.as_deref(), *initial_price, *fee_bps, *stable_amp,
+                    cli.json,
+                )?;
+            } else {
+                cmd_create_pool(
+                    rpc_url, keypair,
+                    pair.as_deref().context("--pair is required")?,
+                    initial_price.context("--initial-price is required")?,
+                    *seed_amount, *seed, *fee_bps, *stable_amp,
+                    cli.json,
+                )?;
+            }
+        }
+        Commands::ClosePool { pair } => {
+            cmd_close_pool(rpc_url, keypair, pair, cli.json)?;
+        }
+        Commands::Provide {
+            pair, amount, amount_b, auto_compound, compound_threshold, receipt, max_a, max_b, simulate,
+        } => {
+            cmd_provide(
+                rpc_url, keypair,
+                pair, *amount, *amount_b, *auto_compound, *compound_threshold, *receipt,
+                *max_a, *max_b, *simulate, cli.json,
+            )?;
+        }
+        Commands::Convert {
+            token_in, token_out, amount, approval_mode, webhook_url, approver, telegram_chat,
+            telegram_bot_token, approval_expiry, max_slippage, max_price_impact, deadline, referrer,
+        } => {
+            let approval = Approval {
+                mode:          approval_mode,
+                webhook_url:   webhook_url.as_deref(),
+                approver:      approver.as_deref(),
+                telegram_chat: telegram_chat.as_deref(),
+                telegram_bot:  telegram_bot_token.as_deref(),
+                expiry_slots:  *approval_expiry,
+            };
+            cmd_convert(
+                rpc_url, keypair,
+                token_in, token_out, *amount,
+                &approval,
+                max_slippage.or(profile.max_slippage).unwrap_or(0.5), *max_price_impact,
+                *deadline,
+                referrer.as_deref(),
+                cli.json,
+            )?;
+        }
+        Commands::Simulate { token_in, token_out, amount, amounts, mode, route } => {
+            let amounts = match amounts {
+                Some(list) => parse_amounts(list).context("--amounts")?,
+                None       => amount.iter().copied().collect(),
+            };
+            cmd_simulate(rpc_url, token_in, token_out, &amounts, mode, route, cli.json)?;
+        }
+        Commands::MyPositions => {
+            cmd_my_positions(rpc_url, keypair, cli.json)?;
+        }
+        Commands::PoolInfo { pair, quote } => {
+            cmd_pool_info(rpc_url, pair, quote, cli.json)?;
+        }
+        Commands::ActivePools => {
+            cmd_active_pools(rpc_url, cli.json)?;
+        }
+        Commands::Idl { output } => {
+            cmd_idl(rpc_url, output.as_deref(), cli.json)?;
+        }
+        Commands::MyFees => {
+            cmd_my_fees(rpc_url, keypair, cli.json)?;
+        }
+        Commands::Portfolio { quote } => {
+            cmd_portfolio(rpc_url, keypair, quote, cli.json)?;
+        }
+        Commands::Tier { open } => {
+            cmd_tier(rpc_url, keypair, *open, cli.json)?;
+        }
+        Commands::Rebalance { pair, target, band, every, max_slippage, max_price_impact } => {
+            cmd_rebalance(
+                rpc_url, keypair,
+                pair, target, band, *every,
+                max_slippage.or(profile.max_slippage).unwrap_or(0.5), *max_price_impact,
+                cli.json,
+            )?;
+        }
+        Commands::Grid(GridCommands::Run { config, once }) => {
+            cmd_grid_run(rpc_url, keypair, config, *once, cli.json)?;
+        }
+        Commands::History { pair, limit } => {
+            cmd_history(rpc_url, keypair, pair.as_deref(), *limit, cli.json)?;
+        }
+        Commands::Export { format, what, since, output } => {
+            cmd_export(rpc_url, keypair, format, what, since.as_deref(), output.as_deref(), cli.json)?;
+        }
+        Commands::Gains { quote, method, since, output } => {
+            cmd_gains(rpc_url, keypair, quote, method, since.as_deref(), output.as_deref(), cli.json)?;
+        }
+        Commands::RemoveLiquidity { pair, shares, min_a, min_b, close_if_empty } => {
+            cmd_remove_liquidity(
+                rpc_url, keypair,
+                pair, *shares, *min_a, *min_b, *close_if_empty,
+                cli.json,
+            )?;
+        }
+        Commands::ClaimFees { pair, all } => {
+            if *all {
+                cmd_claim_fees_all(rpc_url, keypair, cli.json)?;
+            } else {
+                let p = pair.as_deref().ok_or_else(|| input_error!(
+                    "Provide --pair <A-B> or --all.\n  \
+                     Example: a2a-swap claim-fees --pair SOL-USDC\n  \
+                     Example: a2a-swap claim-fees --all"
+                ))?;
+                cmd_claim_fees(rpc_url, keypair, p, cli.json)?;
+            }
+        }
+        Commands::Remove { pair, percentage, amount, min_a, min_b } => {
+            cmd_remove(
+                rpc_url, keypair,
+                pair, *percentage, *amount, *min_a, *min_b,
+                cli.json,
+            )?;
+        }
+        Commands::Position(PositionCommands::Set { pair, auto_compound, threshold }) => {
+            let auto_compound = auto_compound.as_deref().map(|v| v == "on");
+            cmd_position_set(rpc_url, keypair, pair, auto_compound, *threshold, cli.json)?;
+        }
+        Commands::Position(PositionCommands::Close { pair }) => {
+            cmd_position_close(rpc_url, keypair, pair, cli.json)?;
+        }
+        Commands::Allowlist(AllowlistCommands::Set { pair, traders }) => {
+            cmd_allowlist_set(rpc_url, keypair, pair, Some(traders), cli.json)?;
+        }
+        Commands::Allowlist(AllowlistCommands::Clear { pair }) => {
+            cmd_allowlist_set(rpc_url, keypair, pair, None, cli.json)?;
+        }
+        Commands::Allowlist(AllowlistCommands::Proof { traders, trader }) => {
+            cmd_allowlist_proof(traders, trader, cli.json)?;
+        }
+        Commands::Allowlist(AllowlistCommands::Register { pair, proof, traders }) => {
+            cmd_allowlist_register(rpc_url, keypair, pair, proof.as_deref(), traders.as_deref(), cli.json)?;
+        }
+        Commands::Gate(GateCommands::Set { pair, mint }) => {
+            cmd_gate_set(rpc_url, keypair, pair, Some(mint), cli.json)?;
+        }
+        Commands::Gate(GateCommands::Clear { pair }) => {
+            cmd_gate_set(rpc_url, keypair, pair, None, cli.json)?;
+        }
+        Commands::Governance(GovernanceCommands::Init { vote_mint, quorum, voting_period }) => {
+            cmd_governance_init(rpc_url, keypair, vote_mint, *quorum, *voting_period, cli.json)?;
+        }
+        Commands::Governance(GovernanceCommands::Propose {
+            protocol_fee, referral_share, pool_fee_bounds, pause, fee_tier, locker,
+            pair, dynamic_fee, circuit_breaker,
+        }) => {
+            let action = match pair {
+                Some(pair) => {
+                    let program_id = Pubkey::from_str(PROGRAM_ID)?;
+                    let (pool, ..) = find_pool_by_pair(&rpc(rpc_url), pair, &program_id)?;
+                    pool_governance_action(pool, dynamic_fee.as_deref(), *circuit_breaker)?
+                }
+                None => governance_action(
+                    *protocol_fee, *referral_share, pool_fee_bounds.as_deref(), pause.as_deref(),
+                    fee_tier.as_deref(), locker.as_deref(),
+                )?,
+            };
+            cmd_governance_propose(rpc_url, keypair, action, cli.json)?;
+        }
+        Commands::Governance(GovernanceCommands::Proposals) => {
+            cmd_governance_proposals(rpc_url, cli.json)?;
+        }
+        Commands::Governance(GovernanceCommands::Vote { proposal, support, against: _, amount }) => {
+            cmd_governance_vote(rpc_url, keypair, *proposal, *support, *amount, cli.json)?;
+        }
+        Commands::Governance(GovernanceCommands::Execute { proposal }) => {
+            cmd_governance_execute(rpc_url, keypair, *proposal, cli.json)?;
+        }
+        Commands::Governance(GovernanceCommands::Withdraw { proposal }) => {
+            cmd_governance_withdraw(rpc_url, keypair, *proposal, cli.json)?;
+        }
+        Commands::Dev(DevCommands::Bootstrap {
+            decimals, mint_amount, amount_a, amount_b, fee_bps, airdrop,
+        }) => {
+            cmd_dev_bootstrap(
+                rpc_url, keypair,
+                *decimals, *mint_amount, *amount_a, *amount_b, *fee_bps, *airdrop,
+                cli.json,
+            )?;
+        }
+        Commands::Monitor { config, once } => {
+            let notify = cli.notify.as_deref().or(profile.notify.as_deref());
+            cmd_monitor(rpc_url, keypair, config, *once, notify, cli.json)?;
+        }
+        Commands::Dashboard { pair, owner, ws_url, refresh } => {
+            cmd_dashboard(
+                rpc_url, keypair,
+                pair, owner.as_deref(), ws_url.as_deref(), *refresh,
+                cli.json,
+            )?;
+        }
+        Commands::Key(cmd) => {
+            cmd_key(cmd, keypair, cli.json)?;
+        }
+        Commands::Approver(ApproverCommands::Telegram { telegram_chat, allow, telegram_bot_token, signer }) => {
+            cmd_approver_telegram(rpc_url, keypair, signer.as_deref(), *telegram_chat, allow, telegram_bot_token)?;
+        }
+        Commands::Config(_) | Commands::Wallets(_) | Commands::Tokens(_) | Commands::Completions { .. }
+        | Commands::Schema => {
+            unreachable!("handled above")
+        }
+    }
+
+    Ok(())
+}
+
+/// Name reported to `--notify` for commands that send a transaction.
+fn notify_command(command: &Commands) -> Option<&'static str> {
+    match command {
+        Commands::CreatePool { .. }      => Some("create-pool"),
+        Commands::ClosePool { .. }       => Some("close-pool"),
+        Commands::Provide { .. }         => Some("provide"),
+        Commands::Convert { .. }         => Some("convert"),
+        Commands::Rebalance { .. }       => Some("rebalance"),
+        Commands::RemoveLiquidity { .. } => Some("remove-liquidity"),
+        Commands::ClaimFees { .. }       => Some("claim-fees"),
+        Commands::Remove { .. }          => Some("remove"),
+        Commands::Tier { open: true }    => Some("tier-open"),
+        Commands::Position(PositionCommands::Set { .. })   => Some("position-set"),
+        Commands::Position(PositionCommands::Close { .. }) => Some("position-close"),
+        Commands::Allowlist(AllowlistCommands::Set { .. })      => Some("allowlist-set"),
+        Commands::Allowlist(AllowlistCommands::Clear { .. })    => Some("allowlist-clear"),
+        Commands::Allowlist(AllowlistCommands::Register { .. }) => Some("allowlist-register"),
+        Commands::Gate(GateCommands::Set { .. })   => Some("gate-set"),
+        Commands::Gate(GateCommands::Clear { .. }) => Some("gate-clear"),
+        Commands::Governance(GovernanceCommands::Init { .. })     => Some("governance-init"),
+        Commands::Governance(GovernanceCommands::Propose { .. })  => Some("governance-propose"),
+        Commands::Governance(GovernanceCommands::Vote { .. })     => Some("governance-vote"),
+        Commands::Governance(GovernanceCommands::Execute { .. })  => Some("governance-execute"),
+        Commands::Governance(GovernanceCommands::Withdraw { .. }) => Some("governance-withdraw"),
+        _ => None,
+    }
+}
+
+// ─── completions / schema ────────────────────────────────────────────────────
+
+fn cmd_completions(shell: clap_complete::Shell) -> Result<()> {
+    let mut cmd = Cli::command();
+    let name = cmd.get_name().to_string();
+    clap_complete::generate(shell, &mut cmd, name, &mut std::io::stdout());
+    Ok(())
+}
+
+fn cmd_schema(json_output: bool) -> Result<()> {
+    let schema = json!({
+        "status":  "ok",
+        "command": "schema",
+        "version": env!("CARGO_PKG_VERSION"),
+        "program": PROGRAM_ID,
+        "root":    command_schema(&Cli::command()),
+    });
+    if json_output {
+        println!("{schema}");
+    } else {
+        println!("{}", serde_json::to_string_pretty(&schema)?);
+    }
+    Ok(())
+}
+
+/// One command and, recursively, its subcommands.
+fn command_schema(cmd: &clap::Command) -> serde_json::Value {
+    json!({
+        "name":  cmd.get_name(),
+        "about": cmd.get_about().map(|s| s.to_string()),
+        "args":  cmd.get_arguments()
+            .filter(|a| !matches!(a.get_id().as_str(), "help" | "version"))
+            .map(arg_schema)
+            .collect::<Vec<_>>(),
+        "subcommands": cmd.get_subcommands()
+            .filter(|c| c.get_name() != "help")
+            .map(command_schema)
+            .collect::<Vec<_>>(),
+    })
+}
+
+fn arg_schema(arg: &clap::Arg) -> serde_json::Value {
+    let possible: Vec<String> = arg.get_possible_values()
+        .iter()
+        .map(|v| v.get_name().to_string())
+        .collect();
+    let default: Option<String> = arg.get_default_values()
+        .first()
+        .map(|v| v.to_string_lossy().into_owned());
+    json!({
+        "name":            arg.get_id().as_str(),
+        "long":            arg.get_long().map(|l| format!("--{l}")),
+        "short":           arg.get_short().map(|c| format!("-{c}")),
+        "positional":      arg.is_positional(),
+        "type":            arg_type(arg, !possible.is_empty()),
+        "value_name":      arg.get_value_names().and_then(|v| v.first()).map(|v| v.to_string()),
+        "required":        arg.is_required_set(),
+        "global":          arg.is_global_set(),
+        "default":         default,
+        "env":             arg.get_env().map(|e| e.to_string_lossy().into_owned()),
+        "possible_values": possible,
+        "help":            arg.get_help().map(|h| h.to_string()),
+    })
+}
+
+/// JSON-schema-style type name for an argument's value.
+fn arg_type(arg: &clap::Arg, has_possible_values: bool) -> &'static str {
+    use std::any::TypeId;
+    if !arg.get_action().takes_values() {
+        return "boolean";
+    }
+    if has_possible_values {
+        return "enum";
+    }
+    let id = arg.get_value_parser().type_id();
+    if id == TypeId::of::<u8>() || id == TypeId::of::<u16>() || id == TypeId::of::<u64>() {
+        "integer"
+    } else if id == TypeId::of::<f64>() {
+        "number"
+    } else {
+        "string"
+    }
+}
+
+// ─── config ──────────────────────────────────────────────────────────────────
+
+fn cmd_config(
+    mut cfg: config::Config,
+    requested: Option<&str>,
+    cmd: &ConfigCommands,
+    json_output: bool,
+) -> Result<()> {
+    let name = cfg.profile_name(requested).to_string();
+    match cmd {
+        ConfigCommands::Set { key, value } => {
+            if key == "default_profile" {
+                cfg.default_profile = Some(value.clone());
+            } else {
+                cfg.profiles.entry(name.clone()).or_default().set(key, value)?;
+            }
+            let path = cfg.save()?;
+            if json_output {
+                println!("{}", json!({
+                    "status":  "ok",
+                    "command": "config-set",
+                    "profile": name,
+                    "key":     key,
+                    "value":   value,
+                    "path":    path.display().to_string(),
+                }));
+            } else {
+                println!("  {key} = {value}  (profile {name}, {})", path.display());
+            }
+        }
+        ConfigCommands::Get { key: Some(key) } => {
+            let value = if key == "default_profile" {
+                cfg.default_profile.clone()
+            } else {
+                cfg.profile(requested)?.get(key)?
+            };
+            if json_output {
+                println!("{}", json!({
+                    "status":  "ok",
+                    "command": "config-get",
+                    "profile": name,
+                    "key":     key,
+                    "value":   value,
+                }));
+            } else {
+                match value {
+                    Some(v) => println!("{v}"),
+                    None    => return Err(input_error!("`{key}` is not set in profile {name}.")),
+                }
+            }
+        }
+        ConfigCommands::Get { key: None } => {
+            let profile = cfg.profile(requested)?;
+            if json_output {
+                println!("{}", json!({
+                    "status":  "ok",
+                    "command": "config-get",
+                    "profile": name,
+                    "path":    config::path().display().to_string(),
+                    "values":  profile,
+                }));
+            } else {
+                println!("─── Profile {name} ─────────────────────────────────────────");
+                println!("  File             {}", config::path().display());
+                for key in config::KEYS {
+                    let v = profile.get(key)?.unwrap_or_else(|| "(unset)".into());
+                    println!("  {key:<16} {v}");
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+// ─── wallets ─────────────────────────────────────────────────────────────────
+
+fn cmd_wallets(mut cfg: config::Config, cmd: &WalletsCommands, json_output: bool) -> Result<()> {
+    match cmd {
+        WalletsCommands::Add { name, keypair, max_slippage, priority_fee, notify, use_it } => {
+            let pubkey = load_pubkey(keypair)?;
+            let wallet = config::Wallet {
+                keypair:      keypair.clone(),
+                max_slippage: *max_slippage,
+                priority_fee: *priority_fee,
+                notify:       notify.clone(),
+            };
+            wallet.validate()?;
+            cfg.wallets.insert(name.clone(), wallet);
+            if *use_it {
+                cfg.default_wallet = Some(name.clone());
+            }
+            let path = cfg.save()?;
+            if json_output {
+                println!("{}", json!({
+                    "status":  "ok",
+                    "command": "wallets-add",
+                    "name":    name,
+                    "pubkey":  pubkey.to_string(),
+                    "default": cfg.default_wallet.as_deref() == Some(name),
+                    "path":    path.display().to_string(),
+                }));
+            } else {
+                println!("  Added wallet {name} ({pubkey}) to {}", path.display());
+                if *use_it {
+                    println!("  {name} is now the default wallet");
+                }
+            }
+        }
+        WalletsCommands::List => {
+            let rows: Vec<serde_json::Value> = cfg.wallets.iter().map(|(name, w)| json!({
+                "name":         name,
+                "keypair":      w.keypair,
+                "pubkey":       load_pubkey(&w.keypair).ok().map(|k| k.to_string()),
+                "default":      cfg.default_wallet.as_deref() == Some(name.as_str()),
+                "max_slippage": w.max_slippage,
+                "priority_fee": w.priority_fee,
+                "notify":       w.notify,
+            })).collect();
+            if json_output {
+                println!("{}", json!({
+                    "status":  "ok",
+                    "command": "wallets-list",
+                    "path":    config::path().display().to_string(),
+                    "wallets": rows,
+                }));
+                return Ok(());
+            }
+            if rows.is_empty() {
+                println!("  No wallets yet. Add one with: a2a-swap wallets add <NAME> <KEYPAIR_PATH>");
+                return Ok(());
+            }
+            println!("─── Wallets ───────────────────────────────────────────────────────");
+            for row in &rows {
+                let marker = if row["default"] == true { "*" } else { " " };
+                let pubkey = row["pubkey"].as_str().unwrap_or("(keypair unreadable)");
+                println!("{marker} {:<16} {pubkey}", row["name"].as_str().unwrap_or_default());
+                println!("    keypair      {}", row["keypair"].as_str().unwrap_or_default());
+                for key in ["max_slippage", "priority_fee", "notify"] {
+                    if !row[key].is_null() {
+                        println!("    {key:<12} {}", row[key].to_string().trim_matches('"'));
+                    }
+                }
+            }
+        }
+        WalletsCommands::Use { name } => {
+            if !cfg.wallets.contains_key(name) {
+                return Err(input_error!(
+                    "Wallet `{name}` not found in {}.\n  \
+                     Add it with: a2a-swap wallets add {name} <KEYPAIR_PATH>",
+                    config::path().display()
+                ));
+            }
+            cfg.default_wallet = Some(name.clone());
+            let path = cfg.save()?;
+            if json_output {
+                println!("{}", json!({
+                    "status":  "ok",
+                    "command": "wallets-use",
+                    "name":    name,
+                    "path":    path.display().to_string(),
+                }));
+            } else {
+                println!("  Default wallet: {name}  ({})", path.display());
+            }
+        }
+    }
+    Ok(())
+}
+
+// ─── key ─────────────────────────────────────────────────────────────────────
+
+fn cmd_key(cmd: &KeyCommands, keypair_path: &str, json_output: bool) -> Result<()> {
+    let (command, path, pubkey) = match cmd {
+        KeyCommands::Import { keypair, output, scrypt_log_n, force } => {
+            let source = expand_home(keypair);
+            let plain  = std::fs::read_to_string(&source).map_err(|e| keypair_error(&source, e))?;
+            if keystore::is_keystore(&plain) {
+                return Err(input_error!("'{source}' is already a keystore"));
+            }
+            let key    = keystore::keypair_from_json(&plain, None).map_err(|e| keypair_error(&source, e))?;
+            let params = ScryptParams { log_n: *scrypt_log_n, ..ScryptParams::DEFAULT };
+            let output = match output {
+                Some(o) => expand_home(o),
+                None    => format!("{}.keystore.json", source.strip_suffix(".json").unwrap_or(&source)),
+            };
+            let passphrase = read_passphrase(&format!("New passphrase for {output}: "), true)?;
+            write_private(&output, &Keystore::encrypt_with(&key, &passphrase, params)?.to_json(), *force)?;
+            ("key-import", output, key.pubkey())
+        }
+        KeyCommands::Export { keystore, output, force } => {
+            let key    = load_keypair(keystore)?;
+            let output = expand_home(output);
+            write_private(&output, &serde_json::to_string(&key.to_bytes().to_vec())?, *force)?;
+            ("key-export", output, key.pubkey())
+        }
+        KeyCommands::Unlock { keystore } => {
+            let path = keystore.as_deref().unwrap_or(keypair_path);
+            ("key-unlock", expand_home(path), load_keypair(path)?.pubkey())
+        }
+    };
+    if json_output {
+        println!("{}", json!({
+            "status":  "ok",
+            "command": command,
+            "pubkey":  pubkey.to_string(),
+            "path":    path,
+        }));
+        return Ok(());
+    }
+    match cmd {
+        KeyCommands::Import { .. } => {
+            println!("  Encrypted {pubkey} into {path}");
+            println!("  The plain keypair file is untouched; delete it once the keystore is backed up.");
+        }
+        KeyCommands::Export { .. } => println!("  Wrote the plain keypair for {pubkey} to {path}"),
+        KeyCommands::Unlock { .. } => println!("  Unlocked {pubkey}  ({path})"),
+    }
+    Ok(())
+}
+
+/// Create `path` readable by the owner only; refuse to replace it unless `force`.
+fn write_private(path: &str, contents: &str, force: bool) -> Result<()> {
+    use std::io::Write;
+    let mut options = std::fs::OpenOptions::new();
+    options.write(true);
+    if force {
+        options.create(true).truncate(true);
+    } else {
+        options.create_new(true);
+    }
+    #[cfg(unix)]
+    std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+    let mut file = options.open(path).map_err(|e| match e.kind() {
+        std::io::ErrorKind::AlreadyExists => input_error!("'{path}' already exists; pass --force to overwrite it"),
+        _ => anyhow!("Cannot write '{path}': {e}"),
+    })?;
+    file.write_all(contents.as_bytes())?;
+    Ok(())
+}
+
+// ─── tokens ──────────────────────────────────────────────────────────────────
+
+fn cmd_tokens(cmd: &TokensCommands, json_output: bool) -> Result<()> {
+    let path = config::tokens_path();
+    let (command, added) = match cmd {
+        TokensCommands::List => {
+            let registry = tokens();
+            if json_output {
+                println!("{}", json!({
+                    "status":  "ok",
+                    "command": "tokens-list",
+                    "path":    path.display().to_string(),
+                    "tokens":  registry.iter().map(|t| json!({
+                        "symbol":   t.symbol,
+                        "mint":     t.mint.to_string(),
+                        "decimals": t.decimals,
+                        "name":     t.name,
+                    })).collect::<Vec<_>>(),
+                }));
+            } else {
+                println!("─── Tokens ({}) ──────────────────────────────────────────", registry.len());
+                for t in registry.iter() {
+                    println!("  {:<10} {:<44}  {:>2} dp  {}", t.symbol, t.mint, t.decimals,
+                        t.name.as_deref().unwrap_or(""));
+                }
+                println!("  User list        {}", path.display());
+            }
+            return Ok(());
+        }
+        TokensCommands::Add { symbol, mint, decimals, name } => {
+            let token = TokenInfo {
+                symbol:   symbol.clone(),
+                mint:     Pubkey::from_str(mint).context("mint")?,
+                decimals: *decimals,
+                name:     name.clone(),
+            };
+            let mut added = TokenRegistry::new();
+            added.add(token);
+            ("tokens-add", added)
+        }
+        TokensCommands::Import { source } => {
+            let text = if source.starts_with("http://") || source.starts_with("https://") {
+                reqwest::blocking::get(source)
+                    .and_then(|r| r.error_for_status())
+                    .and_then(|r| r.text())
+                    .with_context(|| format!("Cannot download {source}"))?
+            } else {
+                std::fs::read_to_string(source).with_context(|| format!("Cannot read {source}"))?
+            };
+            ("tokens-import", TokenRegistry::from_json(&text).with_context(|| source.clone())?)
+        }
+    };
+
+    let count = added.len();
+    let mut user = load_user_tokens().with_context(|| format!("Invalid token list {}", path.display()))?;
+    user.extend(added);
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir).with_context(|| format!("Cannot create {}", dir.display()))?;
+    }
+    std::fs::write(&path, user.to_json()).with_context(|| format!("Cannot write {}", path.display()))?;
+
+    if json_output {
+        println!("{}", json!({
+            "status":  "ok",
+            "command": command,
+            "added":   count,
+            "total":   user.len(),
+            "path":    path.display().to_string(),
+        }));
+    } else {
+        println!("  Added {count} token(s) — {} in {}", user.len(), path.display());
+    }
+    Ok(())
+}
+
+// ─── create-pool ─────────────────────────────────────────────────────────────
+
+/// Check `--fee-bps` against the protocol's pool fee `bounds` and turn
+/// `--stable-amp` into the pool's curve.
+fn pool_curve(fee_rate_bps: u16, stable_amp: Option<u64>, bounds: (u16, u16)) -> Result<CurveKind> {
+    let (min, max) = bounds;
+    if !(min..=max).contains(&fee_rate_bps) {
+        return Err(input_error!(
+            "--fee-bps {} is out of range. Allowed: {min}–{max} ({:.2}%–{:.2}%).",
+            fee_rate_bps,
+            min as f64 / 100.0,
+            max as f64 / 100.0,
+        ));
+    }
+    match stable_amp {
+        None => Ok(CurveKind::ConstantProduct),
+        Some(amp) if (1..=10_000).contains(&amp) => Ok(CurveKind::StableSwap { amp }),
+        Some(amp) => Err(input_error!(
+            "--stable-amp {amp} is out of range. Allowed: 1–10000 (typical: 100)."
+        )),
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn cmd_create_pool(
+    rpc_url: &str,
+    keypair_path: &str,
+    pair: &str,
+    initial_price: f64,
+    seed_amount: u64,
+    seed: bool,
+    fee_rate_bps: u16,
+    stable_amp: Option<u64>,
+    json_output: bool,
+) -> Result<()> {
+    let (sym_a, sym_b, mint_a, mint_b) = parse_pair(pair)?;
+    let client = rpc(rpc_url);
+    let curve = pool_curve(fee_rate_bps, stable_amp, fetch_pool_fee_bounds(&client, &Pubkey::from_str(PROGRAM_ID)?)?)?;
+    if initial_price <= 0.0 {
+        return Err(input_error!(
+            "--initial-price must be > 0 (number of {} per {}).",
+            sym_b, sym_a
+        ));
+    }
+    let amount_b = (seed_amount as f64 * initial_price).round() as u64;
+    if seed && (seed_amount == 0 || amount_b == 0) {
+        return Err(input_error!(
+            "--seed needs --seed-amount > 0 with a matching token B amount (--seed-amount × --initial-price = {amount_b})."
+        ));
+    }
+
+    let payer      = load_keypair(keypair_path)?;
+    let program_id = Pubkey::from_str(PROGRAM_ID)?;
+
+    let (pool_pda, _) = Pubkey::find_program_address(
+        &[POOL_SEED, mint_a.as_ref(), mint_b.as_ref()], &program_id);
+    let (pool_auth, _) = Pubkey::find_program_address(
+        &[POOL_AUTHORITY_SEED, pool_pda.as_ref()], &program_id);
+
+    let vault_a = Keypair::new();
+    let vault_b = Keypair::new();
+
+    let mut ixs = vec![initialize_pool_ix(
+        &payer.pubkey(), &mint_a, &mint_b, &vault_a.pubkey(), &vault_b.pubkey(), fee_rate_bps, curve,
+    )?];
+    let position = seed.then(|| Pubkey::find_program_address(
+        &[POSITION_SEED, pool_pda.as_ref(), payer.pubkey().as_ref()], &program_id).0);
+    if seed {
+        ixs.push(provide_liquidity_ix(
+            &payer.pubkey(), &pool_pda, &vault_a.pubkey(), &vault_b.pubkey(),
+            &derive_ata(&payer.pubkey(), &mint_a), &derive_ata(&payer.pubkey(), &mint_b),
+            seed_amount, amount_b, false, 0,
+        )?);
+    }
+
+    let sig = sign_and_send(&client, &ixs, &payer, &[&payer, &vault_a, &vault_b])
+        .context(if seed {
+            "initialize_pool + provide_liquidity transaction failed"
+        } else {
+            "initialize_pool transaction failed"
+        })?;
+
+    let summary = json!({
+        "status":         "ok",
+        "command":        "create-pool",
+        "pair":           pair,
+        "pool":           pool_pda.to_string(),
+        "pool_authority": pool_auth.to_string(),
+        "token_a_mint":   mint_a.to_string(),
+        "token_b_mint":   mint_b.to_string(),
+        "vault_a":        vault_a.pubkey().to_string(),
+        "vault_b":        vault_b.pubkey().to_string(),
+        "fee_rate_bps":   fee_rate_bps,
+        "curve":          curve_json(curve),
+        "initial_price":  initial_price,
+        "seed_amount":    seed_amount,
+        "seeded":         seed,
+        "position":       position.map(|p| p.to_string()),
+        "amount_b":       seed.then_some(amount_b),
+        "tx":             sig.to_string(),
+    });
+    notify::record(&summary);
+    if json_output {
+        println!("{summary}");
+    } else {
+        println!("─── Pool Created ─────────────────────────────────────────────────");
+        println!("  Pair             {pair}");
+        println!("  Token A          {sym_a}  ({mint_a})");
+        println!("  Token B          {sym_b}  ({mint_b})");
+        println!("  Pool PDA         {pool_pda}");
+        println!("  Pool authority   {pool_auth}");
+        println!("  Vault A          {}", vault_a.pubkey());
+        println!("  Vault B          {}", vault_b.pubkey());
+        println!("  Fee rate         {fee_rate_bps} bps  ({:.2}% per swap)", fee_rate_bps as f64 / 100.0);
+        println!("  Curve            {}", curve.label());
+        println!("  Transaction      {sig}");
+        if let Some(position) = position {
+            println!("  Position         {position}");
+            println!("  Deposited A      {:>20}", seed_amount);
+            println!("  Deposited B      {:>20}", amount_b);
+        } else if seed_amount > 0 {
+            println!();
+            println!("  Pool is empty — seed it next:");
+            println!("    a2a-swap provide --pair {pair} \\");
+            println!("      --amount {seed_amount} --amount-b {amount_b}");
+        } else {
+            println!();
+            println!("  Run `a2a-swap provide --pair {pair} --amount <AMT_A> --amount-b <AMT_B>`");
+            println!("  to seed the pool with initial liquidity.");
+        }
+    }
+    Ok(())
+}
+
+// ─── create-pool --wizard ────────────────────────────────────────────────────
+
+/// What the wizard reads from an SPL mint account.
+struct MintInfo {
+    decimals: u8,
+    supply:   u64,
+}
+
+/// Decode an SPL Token mint: supply at 36, decimals at 44, initialized flag at 45.
+fn parse_mint(mint: &Pubkey, account: Option<&solana_sdk::account::Account>) -> Result<MintInfo> {
+    let account = account.ok_or_else(|| anyhow!("Mint {mint} does not exist on this cluster."))?;
+    if account.owner != Pubkey::from_str(TOKEN_PROGRAM_ID)? {
+        return Err(anyhow!(
+            "{mint} is not an SPL Token mint (owner {}). Token-2022 mints are not supported.",
+            account.owner
+        ));
+    }
+    if account.data.len() < 82 || account.data[45] != 1 {
+        return Err(anyhow!("{mint} is not an initialized mint account."));
+    }
+    Ok(MintInfo { decimals: account.data[44], supply: read_u64(&account.data, 36)? })
+}
+
+/// Ask `question` on stderr; an empty answer takes `default`.
+fn prompt(question: &str, default: Option<&str>) -> Result<String> {
+    use std::io::Write;
+    match default {
+        Some(d) => eprint!("  {question} [{d}]: "),
+        None    => eprint!("  {question}: "),
+    }
+    std::io::stderr().flush().ok();
+    let mut line = String::new();
+    if std::io::stdin().read_line(&mut line)? == 0 {
+        return Err(anyhow!("Wizard aborted (end of input)"));
+    }
+    let answer = line.trim();
+    match (answer.is_empty(), default) {
+        (true, Some(d)) => Ok(d.to_string()),
+        (true, None)    => prompt(question, default),
+        (false, _)      => Ok(answer.to_string()),
+    }
+}
+
+/// Whole tokens → atomic units; errors when it rounds to 0 or overflows.
+fn to_atomic(amount: f64, decimals: u8, what: &str) -> Result<u64> {
+    let atomic = (amount * 10f64.powi(decimals as i32)).round();
+    if !atomic.is_finite() || atomic < 1.0 || atomic > u64::MAX as f64 {
+        return Err(input_error!("{what} {amount} is out of range for a {decimals}-decimal token."));
+    }
+    Ok(atomic as u64)
+}
+
+/// Interactive `create-pool`: validates both mints on-chain, turns a human
+/// price and seed size into atomic amounts, and sends `initialize_pool` +
+/// `provide_liquidity` as one transaction.
+fn cmd_create_pool_wizard(
+    rpc_url: &str,
+    keypair_path: &str,
+    pair_arg: Option<&str>,
+    price_arg: Option<f64>,
+    fee_bps_arg: u16,
+    stable_amp_arg: Option<u64>,
+    json_output: bool,
+) -> Result<()> {
+    if !std::io::stdin().is_terminal() {
+        return Err(input_error!(
+            "--wizard needs an interactive terminal. Use --pair / --initial-price / --seed-amount instead."
+        ));
+    }
+    let payer      = load_keypair(keypair_path)?;
+    let program_id = Pubkey::from_str(PROGRAM_ID)?;
+    let client     = rpc(rpc_url);
+
+    eprintln!("─── Create Pool ──────────────────────────────────────────────────");
+    let pair = prompt("Token pair (A-B)", pair_arg)?;
+    let (sym_a, sym_b, mint_a, mint_b) = parse_pair(&pair)?;
+
+    // Both mints plus both pool orderings in one round trip.
+    let (pool_pda, _) = Pubkey::find_program_address(
+        &[POOL_SEED, mint_a.as_ref(), mint_b.as_ref()], &program_id);
+    let (pool_rev, _) = Pubkey::find_program_address(
+        &[POOL_SEED, mint_b.as_ref(), mint_a.as_ref()], &program_id);
+    let accounts = client.get_multiple_accounts(&[mint_a, mint_b, pool_pda, pool_rev])
+        .context("Failed to read the mints — check your RPC endpoint")?;
+    if let Some(existing) = [pool_pda, pool_rev].iter().zip(&accounts[2..]).find(|(_, a)| a.is_some()) {
+        return Err(anyhow!(
+            "A pool for {pair} already exists: {}. Add liquidity with `a2a-swap provide`.",
+            existing.0
+        ));
+    }
+    let info_a = parse_mint(&mint_a, accounts[0].as_ref())?;
+    let info_b = parse_mint(&mint_b, accounts[1].as_ref())?;
+    for (sym, mint, info) in [(sym_a, &mint_a, &info_a), (sym_b, &mint_b, &info_b)] {
+        if let Some(known) = tokens().by_mint(mint) {
+            if known.decimals != info.decimals {
+                return Err(anyhow!(
+                    "Token list says {} has {} decimals but mint {mint} has {}. Fix it with \
+                     `a2a-swap tokens add {} {mint} --decimals {}`.",
+                    known.symbol, known.decimals, info.decimals, known.symbol, info.decimals
+                ));
+            }
+        }
+        let supply = info.supply as f64 / 10f64.powi(info.decimals as i32);
+        eprintln!("  {sym:<8} {mint}  {} dp  supply {supply}", info.decimals);
+    }
+
+    let default_price = price_arg.map(|p| p.to_string());
+    let price: f64 = prompt(&format!("Price — how many {sym_b} is 1 {sym_a}"), default_price.as_deref())?
+        .parse().context("price must be a number")?;
+    if !price.is_finite() || price <= 0.0 {
+        return Err(input_error!("Price must be > 0."));
+    }
+    let seed: f64 = prompt(&format!("Seed liquidity — how many {sym_a} to deposit"), None)?
+        .parse().context("seed amount must be a number")?;
+    let amount_a = to_atomic(seed, info_a.decimals, "Seed amount")?;
+    let amount_b = to_atomic(seed * price, info_b.decimals, "Matching amount")?;
+
+    let (fee_min, fee_max) = fetch_pool_fee_bounds(&client, &program_id)?;
+    let fee_default = fee_bps_arg.to_string();
+    let fee_rate_bps: u16 = prompt(&format!("LP fee in bps ({fee_min}–{fee_max})"), Some(&fee_default))?
+        .parse().context("fee must be a whole number of bps")?;
+    let amp_default = stable_amp_arg.map_or("none".to_string(), |a| a.to_string());
+    let amp = prompt("StableSwap amplification (none for x·y=k)", Some(&amp_default))?;
+    let stable_amp = match amp.as_str() {
+        "none" | "0" => None,
+        a => Some(a.parse().context("amplification must be a whole number")?),
+    };
+    let curve = pool_curve(fee_rate_bps, stable_amp, (fee_min, fee_max))?;
+
+    // Warn early rather than fail in the transaction.
+    let ata_a = derive_ata(&payer.pubkey(), &mint_a);
+    let ata_b = derive_ata(&payer.pubkey(), &mint_b);
+    for (sym, ata, need) in [(sym_a, &ata_a, amount_a), (sym_b, &ata_b, amount_b)] {
+        let have = client.get_token_account_balance(ata).ok()
+            .and_then(|b| b.amount.parse::<u64>().ok())
+            .unwrap_or(0);
+        if have < need {
+            eprintln!("  warning: {sym} balance {have} is below the {need} needed ({ata})");
+        }
+    }
+
+    eprintln!();
+    eprintln!("  Pool             {pool_pda}");
+    eprintln!("  Deposit A        {amount_a:>20}  {sym_a}");
+    eprintln!("  Deposit B        {amount_b:>20}  {sym_b}");
+    eprintln!("  Initial price    {price} {sym_b}/{sym_a}");
+    eprintln!("  Fee rate         {fee_rate_bps} bps  ({:.2}% per swap)", fee_rate_bps as f64 / 100.0);
+    eprintln!("  Curve            {}", curve.label());
+    if !matches!(prompt("Create and seed the pool? (y/N)", Some("N"))?.as_str(), "y" | "Y" | "yes") {
+        return Err(anyhow!("Aborted — nothing was sent."));
+    }
+
+    let vault_a = Keypair::new();
+    let vault_b = Keypair::new();
+    let ixs = [
+        initialize_pool_ix(
+            &payer.pubkey(), &mint_a, &mint_b, &vault_a.pubkey(), &vault_b.pubkey(), fee_rate_bps, curve,
+        )?,
+        provide_liquidity_ix(
+            &payer.pubkey(), &pool_pda, &vault_a.pubkey(), &vault_b.pubkey(), &ata_a, &ata_b,
+            amount_a, amount_b, false, 0,
+        )?,
+    ];
+    let sig = sign_and_send(&client, &ixs, &payer, &[&payer, &vault_a, &vault_b])
+        .context("initialize_pool + provide_liquidity transaction failed")?;
+    let (position_pda, _) = Pubkey::find_program_address(
+        &[POSITION_SEED, pool_pda.as_ref(), payer.pubkey().as_ref()], &program_id);
+
+    let summary = json!({
+        "status":        "ok",
+        "command":       "create-pool",
+        "pair":          pair,
+        "pool":          pool_pda.to_string(),
+        "position":      position_pda.to_string(),
+        "token_a_mint":  mint_a.to_string(),
+        "token_b_mint":  mint_b.to_string(),
+        "decimals_a":    info_a.decimals,
+        "decimals_b":    info_b.decimals,
+        "vault_a":       vault_a.pubkey().to_string(),
+        "vault_b":       vault_b.pubkey().to_string(),
+        "fee_rate_bps":  fee_rate_bps,
+        "curve":         curve_json(curve),
+        "initial_price": price,
+        "amount_a":      amount_a,
+        "amount_b":      amount_b,
+        "tx":            sig.to_string(),
+    });
+    notify::record(&summary);
+    if json_output {
+        println!("{summary}");
+    } else {
+        println!("─── Pool Created and Seeded ──────────────────────────────────────");
+        println!("  Pair             {pair}");
+        println!("  Pool PDA         {pool_pda}");
+        println!("  Position         {position_pda}");
+        println!("  Deposited A      {amount_a:>20}");
+        println!("  Deposited B      {amount_b:>20}");
+        println!("  Transaction      {sig}");
+    }
+    Ok(())
+}
+
+// ─── provide ─────────────────────────────────────────────────────────────────
+
+#[allow(clippy::too_many_arguments)]
+fn cmd_provide(
+    rpc_url: &str,
+    keypair_path: &str,
+    pair: &str,
+    amount_a: u64,
+    amount_b_arg: Option<u64>,
+    auto_compound: bool,
+    compound_threshold: u64,
+    mint_receipt: bool,
+    max_a: Option<u64>,
+    max_b: Option<u64>,
+    simulate: bool,
+    json_output: bool,
+) -> Result<()> {
+    let (_, _, mint_a, mint_b) = parse_pair(pair)?;
+    if amount_a == 0 {
+        return Err(input_error!(
+            "--amount must be > 0 (atomic units: lamports for SOL, μUSDC for USDC, etc.)"
+        ));
+    }
+
+    let program_id = Pubkey::from_str(PROGRAM_ID)?;
+    let client     = rpc(rpc_url);
+
+    let (pool_pda, _) = Pubkey::find_program_address(
+        &[POOL_SEED, mint_a.as_ref(), mint_b.as_ref()], &program_id);
+
+    let pool_acct = fetch_account(&client, &pool_pda)?.ok_or_else(|| CliError::PoolNotFound(format!(
+        "Pool not found for '{pair}'. Run `a2a-swap create-pool --pair {pair}` first."
+    )))?;
+    let pool = parse_pool(&pool_acct.data)?;
+
+    let amount_b: u64 = if let Some(b) = amount_b_arg {
+        b
+    } else if pool.lp_supply == 0 {
+        return Err(input_error!(
+            "Pool '{}' is empty — pass --amount-b to set the initial price.\n  \
+             Example: --amount-b {} (for a 1:1 ratio).",
+            pair, amount_a
+        ));
+    } else {
+        let (ra, rb) = pool_reserves(&client, &pool)?;
+        if ra == 0 {
+            return Err(anyhow!("Vault A empty with non-zero lp_supply — inconsistent state"));
+        }
+        // Rounded up, as provide_liquidity_proportional does on-chain.
+        let b = ((amount_a as u128) * (rb as u128)).div_ceil(ra as u128);
+        if b == 0 {
+            return Err(input_error!(
+                "Computed amount_b = 0 — --amount {} is too small for this pool.\n  \
+                 Try a larger amount or pass --amount-b explicitly.",
+                amount_a
+            ));
+        }
+        b as u64
+    };
+    if let Some(max) = max_a.filter(|&max| amount_a > max) {
+        return Err(input_error!("--amount {amount_a} exceeds --max-a {max}."));
+    }
+    if let Some(max) = max_b.filter(|&max| amount_b > max) {
+        if amount_b_arg.is_some() {
+            return Err(input_error!("--amount-b {amount_b} exceeds --max-b {max}."));
+        }
+        return Err(CliError::Slippage(format!(
+            "Deposit needs {amount_b} of token B at live reserves, above --max-b {max} — the \
+             pool price has moved.\n  Lower --amount or raise --max-b."
+        )).into());
+    }
+    if simulate {
+        return print_provide_simulation(&client, pair, &pool_pda, &pool, amount_a, amount_b, json_output);
+    }
+
+    let payer = load_keypair(keypair_path)?;
+    let (position_pda, _) = Pubkey::find_program_address(
+        &[POSITION_SEED, pool_pda.as_ref(), payer.pubkey().as_ref()], &program_id);
+    let ata_a = derive_ata(&payer.pubkey(), &pool.token_a_mint);
+    let ata_b = derive_ata(&payer.pubkey(), &pool.token_b_mint);
+
+    let mut ix = provide_liquidity_ix(
+        &payer.pubkey(), &pool_pda, &pool.token_a_vault, &pool.token_b_vault, &ata_a, &ata_b,
+        amount_a, amount_b, auto_compound, compound_threshold,
+    )?;
+    let proportional = amount_b_arg.is_none();
+    if proportional {
+        // Same accounts; the program sizes token B from its reserves at execution.
+        ix.data = ix::ProvideLiquidityProportional {
+            amount: amount_a,
+            fixed_a: true,
+            max_other: max_b.unwrap_or(0),
+            min_lp: 0,
+            auto_compound,
+            compound_threshold,
+        }
+        .data();
+    }
+    let receipt = if mint_receipt {
+        let (receipt, metas) = receipt_accounts(&position_pda, &program_id)?;
+        ix.accounts.extend(metas);
+        Some(receipt)
+    } else {
+        None
+    };
+
+    let existing = client.get_account(&position_pda).ok()
+        .map(|acct| parse_position(&acct.data))
+        .transpose()?;
+    let mut ixs = migration_ixs(
+        &payer.pubkey(), &pool_pda, &pool, existing.as_ref().map(|pos| (&position_pda, pos)),
+    )?;
+    ixs.push(ix);
+    let sig = sign_and_send(&client, &ixs, &payer, &[&payer])
+        .context("provide_liquidity transaction failed")?;
+
+    // Read the position back for the shares minted. Best effort: the
+    // deposit has landed either way.
+    let lp_before = existing.as_ref().map_or(0, |pos| pos.lp_shares);
+    let share = client.get_multiple_accounts(&[position_pda, pool_pda]).ok().and_then(|accounts| {
+        let position = parse_position(&accounts.first()?.as_ref()?.data).ok()?;
+        let pool = parse_pool(&accounts.get(1)?.as_ref()?.data).ok()?;
+        let pct = if pool.lp_supply == 0 {
+            0.0
+        } else {
+            position.lp_shares as f64 / pool.lp_supply as f64 * 100.0
+        };
+        Some((position.lp_shares, pct))
+    });
+    let lp_minted = share.and_then(|(lp, _)| lp.checked_sub(lp_before));
+
+    let summary = json!({
+        "status":             "ok",
+        "command":            "provide",
+        "pair":               pair,
+        "pool":               pool_pda.to_string(),
+        "position":           position_pda.to_string(),
+        "amount_a":           amount_a,
+        "amount_b":           amount_b,
+        "amount_b_estimated": proportional,
+        "auto_compound":      auto_compound,
+        "compound_threshold": compound_threshold,
+        "receipt":            receipt.map(|r| r.to_string()),
+        "lp_minted":          lp_minted,
+        "lp_shares":          share.map(|(lp, _)| lp),
+        "pool_share_pct":     share.map(|(_, pct)| pct),
+        "tx":                 sig.to_string(),
+    });
+    notify::record(&summary);
+    if json_output {
+        println!("{summary}");
+    } else {
+        println!("─── Liquidity Provided ───────────────────────────────────────────");
+        println!("  Pair             {pair}");
+        println!("  Pool             {pool_pda}");
+        println!("  Position         {position_pda}");
+        println!("  Deposited A      {:>20}", amount_a);
+        if proportional {
+            println!("  Deposited B      {:>20}  (est.; sized on-chain)", amount_b);
+        } else {
+            println!("  Deposited B      {:>20}", amount_b);
+        }
+        println!("  Auto-compound    {}", if auto_compound { "enabled" } else { "disabled" });
+        if auto_compound && compound_threshold > 0 {
+            println!("  Cmpnd threshold  {:>20}", compound_threshold);
+        }
+        if let Some(lp_minted) = lp_minted {
+            println!("  LP minted        {:>20}", lp_minted);
+        }
+        if let Some((lp_shares, pct)) = share {
+            println!("  LP shares        {:>20}", lp_shares);
+            println!("  Pool share       {:>19.4}%", pct);
+        }
+        if let Some(receipt) = receipt {
+            println!("  Receipt NFT      {receipt}");
+        }
+        println!("  Transaction      {sig}");
+        println!();
+        println!("  Run `a2a-swap my-fees --json` to check claimable LP fee balances.");
+    }
+    Ok(())
+}
+
+/// `provide --simulate`: the LP shares `amount_a` / `amount_b` would mint at
+/// the pool's current reserves, their share of the pool and the price
+/// either side. Nothing is sent.
+fn print_provide_simulation(
+    client: &RpcClient,
+    pair: &str,
+    pool_pda: &Pubkey,
+    pool: &PoolState,
+    amount_a: u64,
+    amount_b: u64,
+    json_output: bool,
+) -> Result<()> {
+    let (ra, rb) = pool_reserves(client, pool)?;
+    let lp_minted = a2a_swap_core::math::lp_shares_for_deposit(amount_a, amount_b, pool.lp_supply, ra, rb)
+        .context("estimate LP shares")?;
+    let lp_supply_after = pool.lp_supply.saturating_add(lp_minted);
+    let pool_share_pct = if lp_supply_after == 0 {
+        0.0
+    } else {
+        lp_minted as f64 / lp_supply_after as f64 * 100.0
+    };
+    let price_before = spot_price(pool.curve, ra, rb);
+    let price_after  = spot_price(pool.curve, ra.saturating_add(amount_a), rb.saturating_add(amount_b));
+
+    if json_output {
+        println!("{}", json!({
+            "status":          "ok",
+            "command":         "provide",
+            "simulated":       true,
+            "pair":            pair,
+            "pool":            pool_pda.to_string(),
+            "amount_a":        amount_a,
+            "amount_b":        amount_b,
+            "lp_minted":       lp_minted,
+            "lp_supply_after": lp_supply_after,
+            "pool_share_pct":  pool_share_pct,
+            "price_before":    price_before,
+            "price_after":     price_after,
+        }));
+    } else {
+        println!("─── Liquidity Simulation ─────────────────────────────────────────");
+        println!("  Pair             {pair}");
+        println!("  Pool             {pool_pda}");
+        println!("  Deposit A        {:>20}", amount_a);
+        println!("  Deposit B        {:>20}", amount_b);
+        println!("  LP minted        {:>20}", lp_minted);
+        println!("  LP supply after  {:>20}", lp_supply_after);
+        println!("  Pool share       {:>19.4}%", pool_share_pct);
+        println!("  Price            {price_before:.8} → {price_after:.8}  (B per A, raw atomic units)");
+        println!();
+        println!("  No transaction sent.");
+    }
+    Ok(())
+}
+
+// ─── convert ─────────────────────────────────────────────────────────────────
+
+#[allow(clippy::too_many_arguments)]
+fn cmd_convert(
+    rpc_url: &str,
+    keypair_path: &str,
+    token_in: &str,
+    token_out: &str,
+    amount_in: u64,
+    approval: &Approval<'_>,
+    max_slippage: f64,
+    max_price_impact: f64,
+    deadline_secs: u64,
+    referrer: Option<&str>,
+    json_output: bool,
+) -> Result<()> {
+    let mint_in  = resolve_mint(token_in).context("--in")?;
+    let mint_out = resolve_mint(token_out).context("--out")?;
+    if mint_in == mint_out {
+        return Err(input_error!("--in and --out must be different tokens."));
+    }
+    if amount_in == 0 {
+        return Err(input_error!(
+            "--amount must be > 0 (atomic units: lamports for SOL, μUSDC for USDC, etc.)"
+        ));
+    }
+    if !(0.0..=100.0).contains(&max_slippage) {
+        return Err(input_error!(
+            "--max-slippage {} is out of range. Use 0–100 (percent). Default 0.5 = 0.5%.",
+            max_slippage
+        ));
+    }
+    if !(0.0..=100.0).contains(&max_price_impact) {
+        return Err(input_error!(
+            "--max-price-impact {} is out of range. Use 0–100 (percent). 0 = no cap.",
+            max_price_impact
+        ));
+    }
+    let max_price_impact_bps = (max_price_impact * 100.0).round() as u16;
+    let referrer = referrer
+        .map(|r| Pubkey::from_str(r).map_err(|_| input_error!("--referrer '{}' is not a valid public key.", r)))
+        .transpose()?;
+    // --approval-mode telegram sends approve_and_execute, co-signed by the approver.
+    let approver = match (approval.mode, approval.approver) {
+        ("telegram", Some(a)) => Some(
+            Pubkey::from_str(a).map_err(|_| input_error!("--approver '{}' is not a valid public key.", a))?,
+        ),
+        _ => None,
+    };
+    if approver.is_some() && referrer.is_some() {
+        return Err(input_error!(
+            "--referrer is not supported with --approval-mode telegram \
+             (approve_and_execute has no referral account)."
+        ));
+    }
+
+    let payer      = load_keypair(keypair_path)?;
+    let program_id = Pubkey::from_str(PROGRAM_ID)?;
+    let client     = rpc(rpc_url);
+
+    let (pool_pda, pool_auth, pool, a_to_b) =
+        find_pool(&client, &mint_in, &mint_out, &program_id)?;
+
+    let (ra, rb) = pool_reserves(&client, &pool)?;
+    if ra == 0 || rb == 0 {
+        return Err(anyhow!(
+            "Pool has no liquidity yet.\n  \
+             Run `a2a-swap provide --pair {}-{}` to seed it first.",
+            token_in, token_out
+        ));
+    }
+    let (reserve_in, reserve_out) = if a_to_b { (ra, rb) } else { (rb, ra) };
+
+    let protocol_fee_bps = fetch_protocol_fee(&client, &program_id)?;
+    let sim = simulate_detailed(
+        amount_in, protocol_fee_bps, reserve_in, reserve_out, pool.effective_fee_bps(), pool.curve,
+    );
+    let min_amount_out = (sim.estimated_out as f64 * (1.0 - max_slippage / 100.0)) as u64;
+
+    if max_price_impact_bps > 0 && sim.price_impact_bps > max_price_impact_bps as u64 {
+        return Err(CliError::Slippage(format!(
+            "Price impact {:.2}% exceeds --max-price-impact {:.2}%.\n  \
+             Reduce --amount or run `a2a-swap simulate` to size the trade.",
+            sim.price_impact_pct, max_price_impact
+        )).into());
+    }
+    if !json_output {
+        warn_price_impact(sim.price_impact_pct);
+    }
+    // The approver co-signs approve_and_execute; the agent pays for both signatures.
+    check_swap_funds(&client, &payer.pubkey(), &mint_in, &mint_out, amount_in, 1 + approver.is_some() as u64)?;
+
+    approval_gate(approval, &json!({
+        "token_in":      token_in,
+        "token_out":     token_out,
+        "amount_in":     amount_in,
+        "estimated_out": sim.estimated_out,
+        "price_impact":  format!("{:.4}%", sim.price_impact_pct),
+        "pool":          pool_pda.to_string(),
+        "agent":         payer.pubkey().to_string(),
+    }))?;
+
+    let ata_in  = derive_ata(&payer.pubkey(), &mint_in);
+    let ata_out = derive_ata(&payer.pubkey(), &mint_out);
+    let (treasury, _) = Pubkey::find_program_address(&[TREASURY_SEED], &program_id);
+    let treasury_ata  = derive_ata(&treasury, &mint_in);
+
+    let expires_at_slot = match (approver, approval.expiry_slots) {
+        (Some(_), slots) if slots > 0 => client.get_slot()? + slots,
+        _ => 0,
+    };
+    let deadline_unix = match deadline_secs {
+        0    => 0,
+        secs => {
+            let now = std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .map_or(0, |d| d.as_secs() as i64);
+            now + secs as i64
+        }
+    };
+    let swap_ix = match approver {
+        Some(approver) => Instruction {
+            program_id,
+            data: ix::ApproveAndExecute {
+                amount_in, min_amount_out, a_to_b, expires_at_slot, deadline_unix, max_price_impact_bps,
+            }.data(),
+            accounts: vec![
+                AccountMeta::new(payer.pubkey(),      true),
+                AccountMeta::new_readonly(approver,   true),
+                AccountMeta::new(pool_pda,            false),
+                AccountMeta::new_readonly(pool_auth,  false),
+                AccountMeta::new(pool.token_a_vault,  false),
+                AccountMeta::new(pool.token_b_vault,  false),
+                AccountMeta::new(ata_in,              false),
+                AccountMeta::new(ata_out,             false),
+                AccountMeta::new_readonly(treasury,   false),
+                AccountMeta::new(treasury_ata,        false),
+                AccountMeta::new_readonly(Pubkey::from_str(TOKEN_PROGRAM_ID)?, false),
+            ],
+        },
+        None => {
+            let ix_data = ix::Swap { amount_in, min_amount_out, a_to_b, max_price_impact_bps, deadline_unix }.data();
+
+            let mut accounts = vec![
+                AccountMeta::new(payer.pubkey(),      true),
+                AccountMeta::new(pool_pda,            false),
+                AccountMeta::new_readonly(pool_auth,  false),
+                AccountMeta::new(pool.token_a_vault,  false),
+                AccountMeta::new(pool.token_b_vault,  false),
+                AccountMeta::new(ata_in,              false),
+                AccountMeta::new(ata_out,             false),
+                AccountMeta::new_readonly(treasury,   false),
+                AccountMeta::new(treasury_ata,        false),
+                AccountMeta::new_readonly(Pubkey::from_str(TOKEN_PROGRAM_ID)?, false),
+            ];
+            // Optional trailing referrer_token: the referrer's ATA for the input token.
+            if let Some(referrer) = referrer {
+                accounts.push(AccountMeta::new(derive_ata(&referrer, &mint_in), false));
+            }
+            let swap = Instruction { program_id, data: ix_data, accounts };
+            // Count the swap towards the agent's fee tier once it has opted in.
+            let (agent_volume, _) = sdk_ix::derive_agent_volume(&payer.pubkey(), &program_id);
+            if client.get_account_with_commitment(&agent_volume, client.commitment())?.value.is_some() {
+                sdk_ix::with_agent_volume(swap, &payer.pubkey())
+            } else {
+                swap
+            }
+        }
+    };
+    // Permissioned pools only take swaps carrying the agent's TraderPass.
+    let swap_ix = if pool.is_permissioned() {
+        require_trader_pass(&client, &pool_pda, &pool, &payer.pubkey(), &program_id, &format!("{token_in}-{token_out}"))?;
+        sdk_ix::with_trader_pass(swap_ix, &pool_pda, &payer.pubkey())
+    } else {
+        swap_ix
+    };
+    // Token-gated pools only take swaps showing the agent holds the gate mint.
+    let swap_ix = if pool.is_gated() {
+        let gate_token = require_gate_token(&client, &pool, &payer.pubkey(), &format!("{token_in}-{token_out}"))?;
+        sdk_ix::with_gate_token(swap_ix, &gate_token)
+    } else {
+        swap_ix
+    };
+
+    let wsol_mint = Pubkey::from_str(WSOL_MINT)?;
+    let mut instructions = migration_ixs(&payer.pubkey(), &pool_pda, &pool, None)?;
+
+    // If tokenIn is SOL: wrap native SOL → wSOL ATA before the swap.
+    if mint_in == wsol_mint {
+        instructions.push(create_ata_idempotent_ix(&payer.pubkey(), &ata_in, &payer.pubkey(), &wsol_mint)?);
+        instructions.push(system_transfer_ix(&payer.pubkey(), &ata_in, amount_in)?);
+        instructions.push(sync_native_ix(&ata_in)?);
+    }
+
+    // If tokenOut is SOL: ensure the wSOL output ATA exists before the swap.
+    if mint_out == wsol_mint {
+        instructions.push(create_ata_idempotent_ix(&payer.pubkey(), &ata_out, &payer.pubkey(), &wsol_mint)?);
+    }
+
+    instructions.push(swap_ix);
+
+    // If tokenOut is SOL: close the wSOL ATA and return lamports as native SOL.
+    if mint_out == wsol_mint {
+        instructions.push(close_account_ix(&ata_out, &payer.pubkey(), &payer.pubkey())?);
+    }
+
+    let sig = match (approver, approval.telegram_chat, approval.telegram_bot) {
+        (Some(_), Some(chat), Some(token)) => {
+            let tx = sign_for_approval(&client, &instructions, &payer)?;
+            let request = format!(
+                "Swap approval requested\n\
+                 Agent    {}\n\
+                 Sell     {amount_in} {token_in}\n\
+                 Receive  ~{} {token_out} (min {min_amount_out})\n\
+                 Impact   {:.4}%\n\
+                 Pool     {pool_pda}\n\
+                 Expires  {}",
+                payer.pubkey(), sim.estimated_out, sim.price_impact_pct,
+                if expires_at_slot > 0 { format!("slot {expires_at_slot}") } else { "never".into() },
+            );
+            telegram::request_approval(&client, &telegram::Bot::new(token)?, chat, &tx, &request)?
+        }
+        _ => sign_and_send(&client, &instructions, &payer, &[&payer])
+            .context("swap transaction failed")?,
+    };
+
+    let summary = json!({
+        "status":         "ok",
+        "command":        "convert",
+        "token_in":       token_in,
+        "token_out":      token_out,
+        "amount_in":      amount_in,
+        "protocol_fee":   sim.protocol_fee,
+        "lp_fee":         sim.lp_fee,
+        "estimated_out":  sim.estimated_out,
+        "min_amount_out": min_amount_out,
+        "price_impact_pct": sim.price_impact_pct,
+        "max_price_impact_bps": max_price_impact_bps,
+        "a_to_b":         a_to_b,
+        "pool":           pool_pda.to_string(),
+        "approval_mode":  approval.mode,
+        "expires_at_slot": (expires_at_slot > 0).then_some(expires_at_slot),
+        "deadline_unix":  (deadline_unix > 0).then_some(deadline_unix),
+        "referrer":       referrer.map(|r| r.to_string()),
+        "tx":             sig.to_string(),
+    });
+    notify::record(&summary);
+    if json_output {
+        println!("{summary}");
+    } else {
+        let dir = if a_to_b { "A → B" } else { "B → A" };
+        println!("─── Swap Executed ────────────────────────────────────────────────");
+        println!("  Direction        {dir}  ({token_in} → {token_out})");
+        println!("  Pool             {pool_pda}");
+        println!();
+        println!("  ─── Fee Breakdown ────────────────────────────────");
+        println!("  Sold             {:>20}  {token_in}", amount_in);
+        println!("  Protocol fee     {:>20}  ({})", sim.protocol_fee, protocol_fee_pct(protocol_fee_bps));
+        println!("  LP fee           {:>20}  ({:.2}% of net)", sim.lp_fee, pool.fee_rate_bps as f64 / 100.0);
+        println!("  After all fees   {:>20}", sim.after_fees);
+        println!();
+        println!("  ─── Output ───────────────────────────────────────");
+        println!("  Received (est.)  {:>20}  {token_out}", sim.estimated_out);
+        println!("  Min accepted     {:>20}  {token_out}  ({:.1}% slippage guard)", min_amount_out, max_slippage);
+        println!("  Price impact     {:>19.4}%", sim.price_impact_pct);
+        if max_price_impact_bps > 0 {
+            println!("  Impact cap       {:>19.2}%", max_price_impact);
+        }
+        if deadline_unix > 0 {
+            println!("  Deadline         {:>20}  (unix, {deadline_secs}s after sending)", deadline_unix);
+        }
+        println!();
+        if approval.mode != "none" {
+            println!("  Approval mode    {}", approval.mode);
+        }
+        println!("  Transaction      {sig}");
+    }
+    Ok(())
+}
+
+// ─── simulate ────────────────────────────────────────────────────────────────
+
+fn cmd_simulate(
+    rpc_url: &str,
+    token_in: &str,
+    token_out: &str,
+    amounts: &[u64],
+    mode: &str,
+    route: &str,
+    json_output: bool,
+) -> Result<()> {
+    if mode != "direct" {
+        return Err(input_error!(
+            "Unsupported --mode '{}'. Only 'direct' is available in this release.",
+            mode
+        ));
+    }
+    let mint_in  = resolve_mint(token_in).context("--in")?;
+    let mint_out = resolve_mint(token_out).context("--out")?;
+    if mint_in == mint_out {
+        return Err(input_error!("--in and --out must be different tokens."));
+    }
+    if amounts.is_empty() || amounts.contains(&0) {
+        return Err(input_error!(
+            "--amount must be > 0 (atomic units: lamports for SOL, μUSDC for USDC, etc.)"
+        ));
+    }
+
+    let program_id = Pubkey::from_str(PROGRAM_ID)?;
+    let client     = rpc(rpc_url);
+
+    let mut routes = find_routes(&client, &mint_in, &mint_out, &program_id)?;
+    let protocol_fee_bps = fetch_protocol_fee(&client, &program_id)?;
+
+    // Rank by output at the largest size asked for.
+    let rank_amount = amounts.iter().copied().max().unwrap_or_default();
+    routes.sort_by_cached_key(|r| std::cmp::Reverse(r.quote(rank_amount, protocol_fee_bps).estimated_out));
+    let selected = select_route(&routes, route)?;
+    let label    = selected.label();
+
+    let ranked: Vec<serde_json::Value> = routes.iter().map(|r| {
+        let quote = r.quote(rank_amount, protocol_fee_bps);
+        json!({
+            "route":            r.label(),
+            "pools":            r.hops.iter().map(|hop| hop.pool.to_string()).collect::<Vec<_>>(),
+            "amount_in":        rank_amount,
+            "estimated_out":    quote.estimated_out,
+            "price_impact_pct": quote.price_impact_pct,
+            "selected":         std::ptr::eq(r, selected),
+        })
+    }).collect();
+    let hops_json = |quote: &RouteQuote| -> Vec<serde_json::Value> {
+        selected.hops.iter().zip(&quote.hops).map(|(hop, sim)| json!({
+            "pool":             hop.pool.to_string(),
+            "token_in":         resolve_symbol(&hop.mint_in),
+            "token_out":        resolve_symbol(&hop.mint_out),
+            "a_to_b":           hop.a_to_b,
+            "amount_in":        sim.net_pool_input + sim.protocol_fee,
+            "protocol_fee":     sim.protocol_fee,
+            "lp_fee":           sim.lp_fee,
+            "estimated_out":    sim.estimated_out,
+            "effective_rate":   sim.effective_rate,
+            "price_impact_pct": sim.price_impact_pct,
+            "price_impact_bps": sim.price_impact_bps,
+            "fee_rate_bps":     hop.fee_rate_bps,
+            "reserve_in":       hop.reserve_in,
+            "reserve_out":      hop.reserve_out,
+        })).collect()
+    };
+    let print_routes = || {
+        if routes.len() < 2 {
+            return;
+        }
+        println!("  ─── Routes (ranked at {rank_amount}) ─────────────────────────");
+        for (i, r) in routes.iter().enumerate() {
+            let quote = r.quote(rank_amount, protocol_fee_bps);
+            let mark  = if std::ptr::eq(r, selected) { "*" } else { " " };
+            println!("  {mark}{:>2}  {:<10}  {:<21}  {:>20}  {:>9.4}%",
+                     i + 1, r.label(), r.pools_label(), quote.estimated_out, quote.price_impact_pct);
+        }
+        println!();
+    };
+
+    if let [amount_in] = *amounts {
+        let quote = selected.quote(amount_in, protocol_fee_bps);
+        if json_output {
+            let mut out = json!({
+                "status":           "ok",
+                "command":          "simulate",
+                "token_in":         token_in,
+                "token_out":        token_out,
+                "mode":             mode,
+                "route":            label,
+                "amount_in":        amount_in,
+                "estimated_out":    quote.estimated_out,
+                "effective_rate":   quote.effective_rate,
+                "price_impact_pct": quote.price_impact_pct,
+                "price_impact_bps": quote.price_impact_bps,
+                "high_price_impact": quote.price_impact_pct > PRICE_IMPACT_WARN_PCT,
+                "protocol_fee_bps": protocol_fee_bps,
+                "hops":             hops_json(&quote),
+                "routes":           ranked,
+            });
+            if let ([hop], [sim], Some(fields)) = (&selected.hops[..], &quote.hops[..], out.as_object_mut()) {
+                fields.extend([
+                    ("pool",           json!(hop.pool.to_string())),
+                    ("a_to_b",         json!(hop.a_to_b)),
+                    ("protocol_fee",   json!(sim.protocol_fee)),
+                    ("net_pool_input", json!(sim.net_pool_input)),
+                    ("lp_fee",         json!(sim.lp_fee)),
+                    ("after_fees",     json!(sim.after_fees)),
+                    ("fee_rate_bps",   json!(hop.fee_rate_bps)),
+                    ("reserve_in",     json!(hop.reserve_in)),
+                    ("reserve_out",    json!(hop.reserve_out)),
+                ].map(|(k, v)| (k.to_string(), v)));
+            }
+            println!("{out}");
+        } else {
+            println!("─── Swap Simulation ──────────────────────────────────────────────");
+            match &selected.hops[..] {
+                [hop] => {
+                    let dir = if hop.a_to_b { "A → B" } else { "B → A" };
+                    println!("  {token_in} → {token_out}  [{mode} / {dir}]");
+                }
+                _ => println!("  {token_in} → {token_out}  [{label}]"),
+            }
+            let multi_hop = selected.hops.len() > 1;
+            let mut hop_in = amount_in;
+            for (i, (hop, sim)) in selected.hops.iter().zip(&quote.hops).enumerate() {
+                if multi_hop {
+                    println!();
+                    println!("  ─── Hop {}: {} → {} ─────────────────────────────",
+                             i + 1, resolve_symbol(&hop.mint_in), resolve_symbol(&hop.mint_out));
+                }
+                println!("  Pool             {}", hop.pool);
+                println!("  Reserve in       {:>20}", hop.reserve_in);
+                println!("  Reserve out      {:>20}", hop.reserve_out);
+                println!();
+                println!("  ─── Fee Breakdown ────────────────────────────────");
+                println!("  Amount in        {:>20}", hop_in);
+                println!("  Protocol fee     {:>20}  ({}  →  treasury)",
+                         sim.protocol_fee, protocol_fee_pct(protocol_fee_bps));
+                println!("  Net to pool      {:>20}", sim.net_pool_input);
+                println!("  LP fee           {:>20}  ({:.2}%  →  vault/LPs)",
+                         sim.lp_fee, hop.fee_rate_bps as f64 / 100.0);
+                println!("  After all fees   {:>20}", sim.after_fees);
+                if multi_hop {
+                    println!("  Estimated out    {:>20}", sim.estimated_out);
+                }
+                hop_in = sim.estimated_out;
+            }
+            println!();
+            println!("  ─── Output Estimate ──────────────────────────────");
+            println!("  Estimated out    {:>20}", quote.estimated_out);
+            println!("  Effective rate   {:>20.8}  {token_out}/{token_in} (raw units)",
+                     quote.effective_rate);
+            println!("  Price impact     {:>19.4}%", quote.price_impact_pct);
+            println!();
+            print_routes();
+            warn_price_impact(quote.price_impact_pct);
+            println!("  No transaction sent.  To execute:");
+            match &selected.hops[..] {
+                [first, _] => {
+                    let mid = resolve_symbol(&first.mint_out);
+                    println!("    a2a-swap convert --in {token_in} --out {mid} --amount {amount_in}");
+                    println!("    a2a-swap convert --in {mid} --out {token_out} --amount <{mid} received>");
+                }
+                _ => println!("    a2a-swap convert --in {token_in} --out {token_out} --amount {amount_in}"),
+            }
+        }
+        return Ok(());
+    }
+
+    let ladder: Vec<_> = amounts.iter()
+        .map(|&amount_in| (amount_in, selected.quote(amount_in, protocol_fee_bps)))
+        .collect();
+    if json_output {
+        let rows: Vec<serde_json::Value> = ladder.iter().map(|(amount_in, quote)| {
+            let mut row = json!({
+                "amount_in":        amount_in,
+                "estimated_out":    quote.estimated_out,
+                "effective_rate":   quote.effective_rate,
+                "price_impact_pct": quote.price_impact_pct,
+                "price_impact_bps": quote.price_impact_bps,
+                "high_price_impact": quote.price_impact_pct > PRICE_IMPACT_WARN_PCT,
+                "hops":             hops_json(quote),
+            });
+            if let ([sim], Some(fields)) = (&quote.hops[..], row.as_object_mut()) {
+                fields.insert("protocol_fee".to_string(), json!(sim.protocol_fee));
+                fields.insert("lp_fee".to_string(), json!(sim.lp_fee));
+            }
+            row
+        }).collect();
+        let mut out = json!({
+            "status":           "ok",
+            "command":          "simulate",
+            "token_in":         token_in,
+            "token_out":        token_out,
+            "mode":             mode,
+            "route":            label,
+            "protocol_fee_bps": protocol_fee_bps,
+            "ladder":           rows,
+            "routes":           ranked,
+        });
+        if let ([hop], Some(fields)) = (&selected.hops[..], out.as_object_mut()) {
+            fields.extend([
+                ("pool",         json!(hop.pool.to_string())),
+                ("a_to_b",       json!(hop.a_to_b)),
+                ("fee_rate_bps", json!(hop.fee_rate_bps)),
+                ("reserve_in",   json!(hop.reserve_in)),
+                ("reserve_out",  json!(hop.reserve_out)),
+            ].map(|(k, v)| (k.to_string(), v)));
+        }
+        println!("{out}");
+    } else {
+        println!("─── Swap Simulation ──────────────────────────────────────────────");
+        match &selected.hops[..] {
+            [hop] => {
+                let dir = if hop.a_to_b { "A → B" } else { "B → A" };
+                println!("  {token_in} → {token_out}  [{mode} / {dir}]");
+            }
+            _ => println!("  {token_in} → {token_out}  [{label}]"),
+        }
+        for hop in &selected.hops {
+            if selected.hops.len() > 1 {
+                println!("  ─── {} → {}", resolve_symbol(&hop.mint_in), resolve_symbol(&hop.mint_out));
+            }
+            println!("  Pool             {}", hop.pool);
+            println!("  Reserve in       {:>20}", hop.reserve_in);
+            println!("  Reserve out      {:>20}", hop.reserve_out);
+            println!("  Fees             {} protocol, {:.2}% LP",
+                     protocol_fee_pct(protocol_fee_bps), hop.fee_rate_bps as f64 / 100.0);
+        }
+        println!();
+        println!("  {:>20}  {:>20}  {:>16}  {:>10}", "Amount in", "Estimated out", "Effective rate", "Impact");
+        for (amount_in, quote) in &ladder {
+            println!("  {:>20}  {:>20}  {:>16.8}  {:>9.4}%",
+                     amount_in, quote.estimated_out, quote.effective_rate, quote.price_impact_pct);
+        }
+        println!();
+        print_routes();
+        let worst = ladder.iter().map(|(_, quote)| quote.price_impact_pct).fold(0.0, f64::max);
+        warn_price_impact(worst);
+        println!("  No transaction sent.");
+    }
+    Ok(())
+}
+
+/// `--amounts`: comma-separated whole atomic amounts; `1e9` and `2.5e8` are
+/// accepted as long as they come out whole.
+fn parse_amounts(list: &str) -> Result<Vec<u64>> {
+    list.split(',')
+        .map(|item| {
+            let item = item.trim();
+            if let Ok(n) = item.parse::<u64>() {
+                return Ok(n);
+            }
+            match item.parse::<f64>() {
+                Ok(x) if x >= 0.0 && x.fract() == 0.0 && x <= u64::MAX as f64 => Ok(x as u64),
+                _ => Err(input_error!("`{item}` is not a whole number of atomic units")),
+            }
+        })
+        .collect()
+}
+
+// ─── my-positions ─────────────────────────────────────────────────────────────
+
+fn cmd_my_positions(rpc_url: &str, keypair_path: &str, json_output: bool) -> Result<()> {
+    let payer      = load_keypair(keypair_path)?;
+    let program_id = Pubkey::from_str(PROGRAM_ID)?;
+    let client     = rpc(rpc_url);
+
+    let positions = get_agent_positions(&client, &payer.pubkey(), &program_id)?;
+
+    if positions.is_empty() {
+        if json_output {
+            println!("{}", json!({
+                "status": "ok", "command": "my-positions",
+                "agent": payer.pubkey().to_string(), "positions": [],
+            }));
+        } else {
+            println!("─── My Positions ─────────────────────────────────────────────────");
+            println!("  Agent   {}", payer.pubkey());
+            println!();
+            println!("  No LP positions found.");
+            println!("  Run `a2a-swap provide --pair <PAIR> --amount <AMT>` to become an LP.");
+        }
+        return Ok(());
+    }
+
+    let pool_keys: Vec<Pubkey> = dedup_pool_keys(&positions);
+    let pool_map = fetch_pool_map(&client, &pool_keys);
+
+    // Current spot per pool and entry price per position, for IL.
+    let spot: HashMap<Pubkey, f64> = pool_map.iter().filter_map(|(key, pool)| {
+        let (ra, rb) = pool_reserves(&client, pool).ok()?;
+        Some((*key, spot_price(pool.curve, ra, rb)))
+    }).collect();
+    let mut il: Vec<Option<(u64, u64, f64, f64)>> = Vec::with_capacity(positions.len());
+    for (pda, pos) in &positions {
+        let entry = position_entry(&client, pda)?;
+        il.push(entry.zip(spot.get(&pos.pool)).map(|((a, b), &current)| {
+            let entry_price = b as f64 / a as f64;
+            (a, b, entry_price, impermanent_loss(entry_price, current) * 100.0)
+        }));
+    }
+
+    if json_output {
+        let items: Vec<_> = positions.iter().zip(&il).map(|((pda, pos), il)| json!({
+            "position":           pda.to_string(),
+            "pool":               pos.pool.to_string(),
+            "pair":               pool_label(&pos.pool, &pool_map),
+            "lp_shares":          pos.lp_shares,
+            "auto_compound":      pos.auto_compound,
+            "compound_threshold": pos.compound_threshold,
+            "locked_by":          pos.locked_by.map(|k| k.to_string()),
+            "deposited_a":        il.map(|(a, ..)| a),
+            "deposited_b":        il.map(|(_, b, ..)| b),
+            "entry_price":        il.map(|(_, _, p, _)| p),
+            "current_price":      spot.get(&pos.pool),
+            "il_pct":             il.map(|(.., pct)| pct),
+        })).collect();
+        println!("{}", json!({
+            "status": "ok", "command": "my-positions",
+            "agent": payer.pubkey().to_string(), "positions": items,
+        }));
+    } else {
+        println!("─── My Positions ─────────────────────────────────────────────────");
+        println!("  Agent   {}", payer.pubkey());
+        println!();
+        for (i, ((pda, pos), il)) in positions.iter().zip(&il).enumerate() {
+            let label = pool_label(&pos.pool, &pool_map);
+            println!("  [{i:>2}]  Pair       {label}");
+            println!("        Position   {pda}");
+            println!("        Pool       {}", pos.pool);
+            println!("        LP shares  {:>20}", pos.lp_shares);
+            match il {
+                Some((_, _, entry, pct)) => println!(
+                    "        IL         {pct:>+19.4}%  (entry {entry:.8} → now {:.8} B/A)",
+                    spot.get(&pos.pool).copied().unwrap_or(0.0),
+                ),
+                None => println!("        IL         — (no deposit found in history)"),
+            }
+            println!("        Auto-cmpnd {}{}",
+                if pos.auto_compound { "enabled" } else { "disabled" },
+                if pos.auto_compound && pos.compound_threshold > 0 {
+                    format!("  (threshold: {})", pos.compound_threshold)
+                } else { String::new() });
+            if let Some(locker) = pos.locked_by {
+                println!("        Locked     as collateral by {locker}");
+            }
+            println!();
+        }
+        println!("  Total: {} position(s)  ·  run `my-fees` to see claimable balances", positions.len());
+    }
+    Ok(())
+}
+
+// ─── dashboard ────────────────────────────────────────────────────────────────
+
+fn cmd_dashboard(
+    rpc_url:      &str,
+    keypair_path: &str,
+    pairs:        &[String],
+    owner:        Option<&str>,
+    ws_url:       Option<&str>,
+    refresh:      u64,
+    json_output:  bool,
+) -> Result<()> {
+    if json_output {
+        return Err(input_error!(
+            "dashboard is interactive and has no --json output.\n  \
+             For machine-readable state use: a2a-swap my-positions --json"
+        ));
+    }
+    let program_id = Pubkey::from_str(PROGRAM_ID)?;
+    let client     = rpc(rpc_url);
+    let owner = match owner {
+        Some(o) => Pubkey::from_str(o).map_err(|_| input_error!("Invalid --owner pubkey: {o}"))?,
+        None    => load_pubkey(keypair_path)?,
+    };
+    let watch = pairs
+        .iter()
+        .map(|pair| find_pool_by_pair(&client, pair, &program_id).map(|(pda, ..)| pda))
+        .collect::<Result<Vec<_>>>()?;
+
+    dashboard::run(
+        &client, rpc_url, ws_url, owner, program_id, &watch,
+        std::time::Duration::from_secs(refresh.max(1)),
+    )
+}
+
+// ─── pool-info ────────────────────────────────────────────────────────────────
+
+fn cmd_pool_info(rpc_url: &str, pair: &str, quote: &str, json_output: bool) -> Result<()> {
+    let (sym_a, sym_b, mint_a, mint_b) = parse_pair(pair)?;
+    let quote_mint = resolve_mint(quote).context("--quote")?;
+    let program_id = Pubkey::from_str(PROGRAM_ID)?;
+    let client     = rpc(rpc_url);
+
+    let (pool_pda, _) = Pubkey::find_program_address(
+        &[POOL_SEED, mint_a.as_ref(), mint_b.as_ref()], &program_id);
+
+    let pool_acct = fetch_account(&client, &pool_pda)?.ok_or_else(|| CliError::PoolNotFound(format!(
+        "Pool not found for '{pair}'. Run `a2a-swap create-pool --pair {pair}` first."
+    )))?;
+    let pool = parse_pool(&pool_acct.data)?;
+
+    let (ra, rb) = pool_reserves(&client, &pool)?;
+
+    let spot_price: f64 = spot_price(pool.curve, ra, rb);
+    let protocol_fee_bps = fetch_protocol_fee(&client, &program_id)?;
+    let share_value = |reserve: u64| {
+        if pool.lp_supply == 0 { 0.0 } else { reserve as f64 / pool.lp_supply as f64 }
+    };
+    let (share_a, share_b) = (share_value(ra), share_value(rb));
+
+    // Each side valued through its direct pool against the quote token (or
+    // this pool, when it pairs with the quote).
+    let candidates: Vec<Pubkey> = [mint_a, mint_b].iter()
+        .filter(|m| **m != quote_mint)
+        .flat_map(|m| [
+            Pubkey::find_program_address(&[POOL_SEED, m.as_ref(), quote_mint.as_ref()], &program_id).0,
+            Pubkey::find_program_address(&[POOL_SEED, quote_mint.as_ref(), m.as_ref()], &program_id).0,
+        ])
+        .filter(|k| *k != pool_pda)
+        .collect();
+    let mut pairs = vec![(mint_a, mint_b, ra, rb)];
+    for quote_pool in fetch_pool_map(&client, &candidates).values() {
+        let (qa, qb) = pool_reserves(&client, quote_pool)?;
+        pairs.push((quote_pool.token_a_mint, quote_pool.token_b_mint, qa, qb));
+    }
+    let value_of = |mint: &Pubkey, amount: u64| -> Option<u64> {
+        if *mint == quote_mint {
+            return Some(amount);
+        }
+        let (reserve_asset, reserve_quote) = pairs.iter().find_map(|&(a, b, qa, qb)| {
+            if a == *mint && b == quote_mint && qa > 0 {
+                Some((qa, qb))
+            } else if b == *mint && a == quote_mint && qb > 0 {
+                Some((qb, qa))
+            } else {
+                None
+            }
+        })?;
+        let v = amount as u128 * reserve_quote as u128 / reserve_asset as u128;
+        Some(u64::try_from(v).unwrap_or(u64::MAX))
+    };
+    let (value_a, value_b) = (value_of(&mint_a, ra), value_of(&mint_b, rb));
+    let tvl = value_a.zip(value_b).map(|(a, b)| a.saturating_add(b));
+    let created = fetch_creation(&client, &pool_pda)?;
+
+    if json_output {
+        println!("{}", json!({
+            "status":  "ok",
+            "command": "pool-info",
+            "pair":    pair,
+            "pool":    pool_pda.to_string(),
+            "token_a": {
+                "symbol": sym_a, "mint": mint_a.to_string(),
+                "vault":  pool.token_a_vault.to_string(), "reserve": ra,
+                "volume": pool.volume_a, "fees_collected": pool.fees_collected_a,
+            },
+            "token_b": {
+                "symbol": sym_b, "mint": mint_b.to_string(),
+                "vault":  pool.token_b_vault.to_string(), "reserve": rb,
+                "volume": pool.volume_b, "fees_collected": pool.fees_collected_b,
+            },
+            "lp_supply":          pool.lp_supply,
+            "fee_rate_bps":       pool.fee_rate_bps,
+            "fee_rate_pct":       pool.fee_rate_bps as f64 / 100.0,
+            "effective_fee_bps":  pool.effective_fee_bps(),
+            "dynamic_fee":        dynamic_fee_json(&pool.dynamic_fee),
+            "max_move_bps":       pool.max_move_bps,
+            "permissioned":       pool.is_permissioned(),
+            "gate_mint":          pool.is_gated().then(|| pool.gate_mint.to_string()),
+            "protocol_fee_bps":   protocol_fee_bps,
+            "curve":              curve_json(pool.curve),
+            "spot_price_b_per_a": spot_price,
+            "lp_share_value_a":   share_a,
+            "lp_share_value_b":   share_b,
+            "tvl": {
+                "quote":   resolve_symbol(&quote_mint),
+                "mint":    quote_mint.to_string(),
+                "value_a": value_a,
+                "value_b": value_b,
+                "total":   tvl,
+            },
+            "created_slot":       created.map(|(slot, _)| slot),
+            "created_at":         created.and_then(|(_, time)| time),
+        }));
+    } else {
+        println!("─── Pool Info: {pair} ──────────────────────────────────────────────");
+        println!("  Pool             {pool_pda}");
+        println!();
+        println!("  Token A          {sym_a}  ({mint_a})");
+        println!("  Vault A          {}", pool.token_a_vault);
+        println!("  Reserve A        {:>20}", ra);
+        println!("  Volume A         {:>20}  (lifetime, LP fees {})", pool.volume_a, pool.fees_collected_a);
+        println!();
+        println!("  Token B          {sym_b}  ({mint_b})");
+        println!("  Vault B          {}", pool.token_b_vault);
+        println!("  Reserve B        {:>20}", rb);
+        println!("  Volume B         {:>20}  (lifetime, LP fees {})", pool.volume_b, pool.fees_collected_b);
+        println!();
+        println!("  LP supply        {:>20}", pool.lp_supply);
+        println!("  Per LP share     {share_a:.8} {sym_a} + {share_b:.8} {sym_b}  (raw atomic units)");
+        match tvl {
+            Some(total) => println!("  TVL              {:>20}  {}  (raw atomic units)", total, resolve_symbol(&quote_mint)),
+            None        => println!("  TVL              — (no pool prices both sides in {})", resolve_symbol(&quote_mint)),
+        }
+        match created {
+            Some((slot, Some(time))) => {
+                let now = std::time::SystemTime::now()
+                    .duration_since(std::time::UNIX_EPOCH)
+                    .map_or(0, |d| d.as_secs() as i64);
+                println!("  Created          slot {slot}  ({})", format_age(now - time));
+            }
+            Some((slot, None)) => println!("  Created          slot {slot}"),
+            None               => println!("  Created          — (history too long to scan)"),
+        }
+        println!("  Fee rate         {} bps  ({:.2}% per swap)",
+                 pool.fee_rate_bps, pool.fee_rate_bps as f64 / 100.0);
+        if pool.dynamic_fee.max_fee_bps > 0 {
+            println!("  Dynamic fee      {} bps now  (bounds {}–{} bps, volatility {} bps)",
+                     pool.effective_fee_bps(), pool.dynamic_fee.min_fee_bps,
+                     pool.dynamic_fee.max_fee_bps, pool.dynamic_fee.volatility_bps);
+        }
+        if pool.max_move_bps > 0 {
+            println!("  Circuit breaker  {} bps  (max net price move per slot)", pool.max_move_bps);
+        }
+        if pool.is_permissioned() {
+            println!("  Allowlist        {}  (only registered traders may swap)", hex32(&pool.allowlist_root));
+        }
+        if pool.is_gated() {
+            println!("  Gate mint        {}  (only holders may swap)", pool.gate_mint);
+        }
+        println!("  Protocol fee     {}  (per swap, to treasury)", protocol_fee_pct(protocol_fee_bps));
+        println!("  Curve            {}", pool.curve.label());
+        if ra > 0 {
+            println!("  Spot price       {spot_price:.8}  {sym_b}/{sym_a}  (raw atomic units)");
+        } else {
+            println!("  Spot price       — (pool is empty, no liquidity)");
+        }
+    }
+    Ok(())
+}
+
+/// Slot and block time of the oldest transaction that touched `pool` — its
+/// `create_pool`; `None` when it is more than ten pages of signatures back.
+fn fetch_creation(client: &RpcClient, pool: &Pubkey) -> Result<Option<(u64, Option<i64>)>> {
+    const PAGE: usize = 1_000;
+    const MAX_PAGES: usize = 10;
+    let mut before = None;
+    let mut oldest = None;
+    for _ in 0..MAX_PAGES {
+        let page = client.get_signatures_for_address_with_config(
+            pool,
+            GetConfirmedSignaturesForAddress2Config {
+                before,
+                limit:      Some(PAGE),
+                commitment: Some(CommitmentConfig::confirmed()),
+                ..GetConfirmedSignaturesForAddress2Config::default()
+            },
+        )?;
+        let Some(last) = page.last() else { return Ok(oldest) };
+        oldest = Some((last.slot, last.block_time));
+        if page.len() < PAGE {
+            return Ok(oldest);
+        }
+        before = Some(last.signature.parse()?);
+    }
+    Ok(None)
+}
+
+// ─── active-pools ─────────────────────────────────────────────────────────────
+
+fn cmd_idl(rpc_url: &str, output: Option<&std::path::Path>, json_output: bool) -> Result<()> {
+    let program_id = Pubkey::from_str(PROGRAM_ID)?;
+    let address    = sdk_ix::derive_idl_address(&program_id);
+    let account    = rpc(rpc_url)
+        .get_account_with_commitment(&address, CommitmentConfig::confirmed())?
+        .value
+        .ok_or_else(|| anyhow!("No IDL published for {program_id} (expected at {address})"))?;
+    let idl = parse_idl_account(&account.data)?;
+
+    if let Some(path) = output {
+        std::fs::write(path, serde_json::to_string_pretty(&idl)? + "\n")
+            .with_context(|| format!("writing {}", path.display()))?;
+    }
+    if json_output {
+        println!("{}", json!({
+            "status":  "ok",
+            "command": "idl",
+            "program": program_id.to_string(),
+            "address": address.to_string(),
+            "output":  output.map(|p| p.display().to_string()),
+            "idl":     if output.is_some() { serde_json::Value::Null } else { idl },
+        }));
+    } else if let Some(path) = output {
+        println!("IDL for {program_id} (account {address}) written to {}", path.display());
+    } else {
+        println!("{}", serde_json::to_string_pretty(&idl)?);
+    }
+    Ok(())
+}
+
+fn cmd_active_pools(rpc_url: &str, json_output: bool) -> Result<()> {
+    let program_id = Pubkey::from_str(PROGRAM_ID)?;
+    let client     = rpc(rpc_url);
+    let disc       = a2a_swap_core::Pool::DISCRIMINATOR;
+
+    let config = RpcProgramAccountsConfig {
+        filters: Some(vec![
+            // No size filter: pools are 269 bytes, or smaller until `migrate_pool` runs.
+            RpcFilterType::Memcmp(Memcmp::new(0, MemcmpEncodedBytes::Bytes(disc.to_vec()))),
+        ]),
+        account_config: RpcAccountInfoConfig {
+            encoding: Some(UiAccountEncoding::Base64),
+            ..RpcAccountInfoConfig::default()
+        },
+        ..RpcProgramAccountsConfig::default()
+    };
+
+    let raw = client
+        .get_program_accounts_with_config(&program_id, config)
+        .context("getProgramAccounts failed — set --rpc to a Helius or private RPC endpoint")?;
+
+    if raw.is_empty() {
+        if json_output {
+            println!("{}", json!({ "status": "ok", "command": "active-pools", "count": 0, "pools": [] }));
+        } else {
+            println!("No pools found.");
+        }
+        return Ok(());
+    }
+
+    struct PoolEntry {
+        pubkey: Pubkey,
+        pool:   PoolState,
+        ra:     u64,
+        rb:     u64,
+    }
+
+    let mut entries: Vec<PoolEntry> = Vec::with_capacity(raw.len());
+    for (pk, acct) in &raw {
+        match parse_pool(&acct.data) {
+            Ok(pool) => {
+                let (ra, rb) = pool_reserves(&client, &pool).unwrap_or((0, 0));
+                entries.push(PoolEntry { pubkey: *pk, pool, ra, rb });
+            }
+            Err(e) => eprintln!("Warning: skipping malformed pool {pk}: {e}"),
+        }
+    }
+
+    if json_output {
+        let arr: Vec<_> = entries.iter().map(|e| {
+            let spot: f64 = spot_price(e.pool.curve, e.ra, e.rb);
+            json!({
+                "pool":                e.pubkey.to_string(),
+                "token_a": {
+                    "symbol": resolve_symbol(&e.pool.token_a_mint),
+                    "mint":   e.pool.token_a_mint.to_string(),
+                    "vault":  e.pool.token_a_vault.to_string(),
+                    "reserve": e.ra,
+                },
+                "token_b": {
+                    "symbol": resolve_symbol(&e.pool.token_b_mint),
+                    "mint":   e.pool.token_b_mint.to_string(),
+                    "vault":  e.pool.token_b_vault.to_string(),
+                    "reserve": e.rb,
+                },
+                "lp_supply":          e.pool.lp_supply,
+                "fee_rate_bps":        e.pool.fee_rate_bps,
+                "fee_rate_pct":        e.pool.fee_rate_bps as f64 / 100.0,
+                "curve":               curve_json(e.pool.curve),
+                "spot_price_b_per_a":  spot,
+            })
+        }).collect();
+        println!("{}", json!({ "status": "ok", "command": "active-pools", "count": arr.len(), "pools": arr }));
+    } else {
+        println!("─── Active Pools ({}) ──────────────────────────────────────────────", entries.len());
+        for (i, e) in entries.iter().enumerate() {
+            let sym_a  = resolve_symbol(&e.pool.token_a_mint);
+            let sym_b  = resolve_symbol(&e.pool.token_b_mint);
+            let spot: f64 = spot_price(e.pool.curve, e.ra, e.rb);
+            println!();
+            println!("  [{}] {}  {}  {}", i + 1, e.pubkey, sym_a, sym_b);
+            println!("      Token A       {} ({})", sym_a, e.pool.token_a_mint);
+            println!("      Token B       {} ({})", sym_b, e.pool.token_b_mint);
+            println!("      Reserve A     {:>20}", e.ra);
+            println!("      Reserve B     {:>20}", e.rb);
+            println!("      LP supply     {:>20}", e.pool.lp_supply);
+            println!("      Fee rate      {} bps  ({:.2}%)", e.pool.fee_rate_bps, e.pool.fee_rate_bps as f64 / 100.0);
+            println!("      Curve         {}", e.pool.curve.label());
+            if e.ra > 0 {
+                println!("      Spot price    {spot:.8}  {sym_b}/{sym_a}");
+            } else {
+                println!("      Spot price    — (pool empty)");
+            }
+        }
+    }
+    Ok(())
+}
+
+// ─── my-fees ──────────────────────────────────────────────────────────────────
+
+fn cmd_my_fees(rpc_url: &str, keypair_path: &str, json_output: bool) -> Result<()> {
+    let payer      = load_keypair(keypair_path)?;
+    let program_id = Pubkey::from_str(PROGRAM_ID)?;
+    let client     = rpc(rpc_url);
+
+    let positions = get_agent_positions(&client, &payer.pubkey(), &program_id)?;
+
+    if positions.is_empty() {
+        if json_output {
+            println!("{}", json!({
+                "status": "ok", "command": "my-fees",
+                "agent": payer.pubkey().to_string(),
+                "fees": [], "total_fees_a": 0, "total_fees_b": 0,
+            }));
+        } else {
+            println!("─── My Fees ──────────────────────────────────────────────────────");
+            println!("  Agent   {}", payer.pubkey());
+            println!();
+            println!("  No LP positions found — no fees to show.");
+            println!("  Run `a2a-swap provide --pair <PAIR> --amount <AMT>` to earn LP fees.");
+        }
+        return Ok(());
+    }
+
+    let pool_keys = dedup_pool_keys(&positions);
+    let pool_map  = fetch_pool_map(&client, &pool_keys);
+
+    struct Row { position: Pubkey, pool: Pubkey, label: String, fa: u64, fb: u64 }
+
+    let mut rows: Vec<Row> = Vec::new();
+    let mut total_a: u64 = 0;
+    let mut total_b: u64 = 0;
+
+    for (pda, pos) in &positions {
+        let (fa, fb) = pool_map.get(&pos.pool)
+            .map(|ps| pending_fees(pos, ps))
+            .unwrap_or((pos.fees_owed_a, pos.fees_owed_b));
+        total_a = total_a.saturating_add(fa);
+        total_b = total_b.saturating_add(fb);
+        rows.push(Row {
+            position: *pda,
+            pool:     pos.pool,
+            label:    pool_label(&pos.pool, &pool_map),
+            fa, fb,
+        });
+    }
+
+    if json_output {
+        let items: Vec<_> = rows.iter().map(|r| json!({
+            "position": r.position.to_string(),
+            "pool":     r.pool.to_string(),
+            "pair":     r.label,
+            "fees_a":   r.fa,
+            "fees_b":   r.fb,
+        })).collect();
+        println!("{}", json!({
+            "status": "ok", "command": "my-fees",
+            "agent": payer.pubkey().to_string(),
+            "fees": items, "total_fees_a": total_a, "total_fees_b": total_b,
+        }));
+    } else {
+        println!("─── My Fees ──────────────────────────────────────────────────────");
+        println!("  Agent   {}", payer.pubkey());
+        println!();
+        for (i, r) in rows.iter().enumerate() {
+            println!("  [{:>2}]  Pair       {}", i + 1, r.label);
+            println!("        Position   {}", r.position);
+            println!("        Pool       {}", r.pool);
+            println!("        Fees A     {:>20}  (token A, atomic units)", r.fa);
+            println!("        Fees B     {:>20}  (token B, atomic units)", r.fb);
+            println!();
+        }
+        println!("  ─── Totals ───────────────────────────────────────");
+        println!("  Total fees A     {:>20}  (across {} position(s))", total_a, rows.len());
+        println!("  Total fees B     {:>20}  (across {} position(s))", total_b, rows.len());
+        println!();
+        println!("  Includes pending fees accrued since last on-chain sync.");
+        println!("  Amounts are in atomic units (lamports, μUSDC, etc.).");
+    }
+    Ok(())
+}
+
+// ─── portfolio ────────────────────────────────────────────────────────────────
+
+/// Wallet token balances by mint, native SOL folded into wSOL.
+fn wallet_balances(client: &RpcClient, owner: &Pubkey) -> Result<std::collections::BTreeMap<Pubkey, u64>> {
+    let resp: serde_json::Value = client.send(
+        RpcRequest::GetTokenAccountsByOwner,
+        json!([
+            owner.to_string(),
+            { "programId": TOKEN_PROGRAM_ID },
+            { "encoding": "jsonParsed" },
+        ]),
+    ).context("getTokenAccountsByOwner failed")?;
+    let mut balances: std::collections::BTreeMap<Pubkey, u64> = Default::default();
+    for acct in resp["value"].as_array().into_iter().flatten() {
+        let info = &acct["account"]["data"]["parsed"]["info"];
+        let mint = info["mint"].as_str().and_then(|m| Pubkey::from_str(m).ok());
+        let amount = info["tokenAmount"]["amount"].as_str().and_then(|a| a.parse::<u64>().ok());
+        if let (Some(mint), Some(amount)) = (mint, amount) {
+            let total = balances.entry(mint).or_default();
+            *total = total.saturating_add(amount);
+        }
+    }
+    let lamports = client.get_balance(owner)?;
+    *balances.entry(Pubkey::from_str(WSOL_MINT)?).or_default() += lamports;
+    Ok(balances)
+}
+
+fn cmd_tier(rpc_url: &str, keypair_path: &str, open: bool, json_output: bool) -> Result<()> {
+    let payer      = load_keypair(keypair_path)?;
+    let program_id = Pubkey::from_str(PROGRAM_ID)?;
+    let sdk = a2a_swap_sdk::A2ASwapClient::new(rpc_url).with_program_id(program_id);
+    let runtime = tokio::runtime::Builder::new_current_thread().enable_all().build()?;
+
+    let mut opened = None;
+    if open && !runtime.block_on(sdk.my_tier(&payer.pubkey()))?.tracking {
+        let client = rpc(rpc_url);
+        let ix = sdk_ix::open_agent_volume_ix(&program_id, &payer.pubkey());
+        let sig = sign_and_send(&client, &[ix], &payer, &[&payer])
+            .context("open_agent_volume transaction failed")?;
+        opened = Some(sig.to_string());
+    }
+    let tier = runtime.block_on(sdk.my_tier(&payer.pubkey()))?;
+
+    let summary = json!({
+        "status":  "ok",
+        "command": "tier",
+        "tier":    tier,
+        "tx":      opened,
+    });
+    if opened.is_some() {
+        notify::record(&summary);
+    }
+    if json_output {
+        println!("{summary}");
+        return Ok(());
+    }
+    if let Some(sig) = &opened {
+        println!("Volume tracking opened: {sig}");
+        println!();
+    }
+    println!("─── Fee tier ──────────────────────────────────────────────────────");
+    println!("  Agent         {}", tier.agent);
+    if !tier.tracking {
+        println!("  Tracking      off — run `a2a-swap tier --open` to start counting volume");
+    }
+    println!("  Volume        {:>20}", tier.volume);
+    println!("  Swaps         {:>20}", tier.swaps);
+    match tier.tier {
+        Some(index) => println!(
+            "  Tier          {index} ({:.2}% off)",
+            tier.discount_bps as f64 / 100.0
+        ),
+        None => println!("  Tier          none"),
+    }
+    println!("  Protocol fee  {}", protocol_fee_pct(tier.protocol_fee_bps));
+    if let Some(next) = tier.next_tier {
+        println!(
+            "  Next tier     {:.2}% off from volume {}",
+            next.discount_bps as f64 / 100.0,
+            next.min_volume
+        );
+    }
+    Ok(())
+}
+
+fn cmd_portfolio(rpc_url: &str, keypair_path: &str, quote: &str, json_output: bool) -> Result<()> {
+    let payer      = load_keypair(keypair_path)?;
+    let owner      = payer.pubkey();
+    let program_id = Pubkey::from_str(PROGRAM_ID)?;
+    let quote_mint = resolve_mint(quote)?;
+    let client     = rpc(rpc_url);
+
+    let mut balances = wallet_balances(&client, &owner)?;
+    balances.retain(|_, amount| *amount > 0);
+
+    let positions = get_agent_positions(&client, &owner, &program_id)?;
+    let mut pool_map = fetch_pool_map(&client, &dedup_pool_keys(&positions));
+
+    // Direct pools between every held mint and the quote token.
+    let mut mints: Vec<Pubkey> = balances.keys().copied()
+        .chain(pool_map.values().flat_map(|p| [p.token_a_mint, p.token_b_mint]))
+        .filter(|m| *m != quote_mint)
+        .collect();
+    mints.sort();
+    mints.dedup();
+    let candidates: Vec<Pubkey> = mints.iter()
+        .flat_map(|m| [
+            Pubkey::find_program_address(&[POOL_SEED, m.as_ref(), quote_mint.as_ref()], &program_id).0,
+            Pubkey::find_program_address(&[POOL_SEED, quote_mint.as_ref(), m.as_ref()], &program_id).0,
+        ])
+        .filter(|k| !pool_map.contains_key(k))
+        .collect();
+    pool_map.extend(fetch_pool_map(&client, &candidates));
+
+    // Reserves for every pool in one batch.
+    let pool_keys: Vec<Pubkey> = pool_map.keys().copied().collect();
+    let vaults: Vec<Pubkey> = pool_keys.iter()
+        .flat_map(|k| [pool_map[k].token_a_vault, pool_map[k].token_b_vault])
+        .collect();
+    let vault_accounts = if vaults.is_empty() { Vec::new() } else { client.get_multiple_accounts(&vaults)? };
+    let reserve = |acct: &Option<solana_sdk::account::Account>| {
+        acct.as_ref().and_then(|a| parse_token_amount(&a.data).ok()).unwrap_or(0)
+    };
+    let reserves: HashMap<Pubkey, (u64, u64)> = pool_keys.iter()
+        .zip(vault_accounts.chunks(2))
+        .map(|(k, pair)| (*k, pool_map[k].reserves(reserve(&pair[0]), reserve(&pair[1]))))
+        .collect();
+
+    // mint → (reserve_asset, reserve_quote)
+    let mut prices: HashMap<Pubkey, (u64, u64)> = HashMap::new();
+    for (key, pool) in &pool_map {
+        let (ra, rb) = reserves.get(key).copied().unwrap_or((0, 0));
+        if pool.token_b_mint == quote_mint && ra > 0 {
+            prices.insert(pool.token_a_mint, (ra, rb));
+        } else if pool.token_a_mint == quote_mint && rb > 0 {
+            prices.insert(pool.token_b_mint, (rb, ra));
+        }
+    }
+    let value_of = |mint: &Pubkey, amount: u64| -> Option<u64> {
+        if *mint == quote_mint {
+            return Some(amount);
+        }
+        let (reserve_asset, reserve_quote) = prices.get(mint)?;
+        let v = amount as u128 * *reserve_quote as u128 / *reserve_asset as u128;
+        Some(u64::try_from(v).unwrap_or(u64::MAX))
+    };
+
+    let mut unpriced: Vec<Pubkey> = Vec::new();
+    let mut wallet_value: u64 = 0;
+    let holdings: Vec<serde_json::Value> = balances.iter().map(|(mint, amount)| {
+        let value = value_of(mint, *amount);
+        match value {
+            Some(v) => wallet_value = wallet_value.saturating_add(v),
+            None if !unpriced.contains(mint) => unpriced.push(*mint),
+            None => {}
+        }
+        json!({
+            "mint":   mint.to_string(),
+            "symbol": resolve_symbol(mint),
+            "amount": amount,
+            "value":  value,
+        })
+    }).collect();
+
+    let mut positions_value: u64 = 0;
+    let position_rows: Vec<serde_json::Value> = positions.iter().filter_map(|(key, pos)| {
+        let pool = pool_map.get(&pos.pool)?;
+        let (ra, rb) = reserves.get(&pos.pool).copied().unwrap_or((0, 0));
+        let (amount_a, amount_b) = if pool.lp_supply == 0 { (0, 0) } else {
+            ((pos.lp_shares as u128 * ra as u128 / pool.lp_supply as u128) as u64,
+             (pos.lp_shares as u128 * rb as u128 / pool.lp_supply as u128) as u64)
+        };
+        let (fees_a, fees_b) = pending_fees(pos, pool);
+        let value_a = value_of(&pool.token_a_mint, amount_a.saturating_add(fees_a));
+        let value_b = value_of(&pool.token_b_mint, amount_b.saturating_add(fees_b));
+        for (mint, v) in [(pool.token_a_mint, value_a), (pool.token_b_mint, value_b)] {
+            if v.is_none() && !unpriced.contains(&mint) {
+                unpriced.push(mint);
+            }
+        }
+        let value = value_a.zip(value_b).map(|(a, b)| a.saturating_add(b));
+        if let Some(v) = value {
+            positions_value = positions_value.saturating_add(v);
+        }
+        Some(json!({
+            "position":  key.to_string(),
+            "pool":      pos.pool.to_string(),
+            "pair":      pool_label(&pos.pool, &pool_map),
+            "lp_shares": pos.lp_shares,
+            "amount_a":  amount_a,
+            "amount_b":  amount_b,
+            "fees_a":    fees_a,
+            "fees_b":    fees_b,
+            "value":     value,
+        }))
+    }).collect();
+    let total_value = wallet_value.saturating_add(positions_value);
+
+    if json_output {
+        println!("{}", json!({
+            "status":          "ok",
+            "command":         "portfolio",
+            "owner":           owner.to_string(),
+            "quote":           resolve_symbol(&quote_mint),
+            "quote_mint":      quote_mint.to_string(),
+            "holdings":        holdings,
+            "positions":       position_rows,
+            "wallet_value":    wallet_value,
+            "positions_value": positions_value,
+            "total_value":     total_value,
+            "unpriced":        unpriced.iter().map(|m| m.to_string()).collect::<Vec<_>>(),
+        }));
+        return Ok(());
     }
 
-    let payer      = load_keypair(keypair_path)?;
-    let program_id = Pubkey::from_str(PROGRAM_ID)?;
+    let sym_q = resolve_symbol(&quote_mint);
+    let show  = |v: &serde_json::Value| v.as_u64().map_or("— (unpriced)".to_string(), |v| format!("{v:>20}"));
+    println!("─── Portfolio ─────────────────────────────────────────────────────");
+    println!("  Owner   {owner}");
+    println!("  Quote   {sym_q} ({quote_mint})  — all values in atomic units");
+    println!();
+    println!("  Wallet");
+    for h in &holdings {
+        println!("    {:<10} {:>20}  →  {}",
+            h["symbol"].as_str().unwrap_or("?"), h["amount"], show(&h["value"]));
+    }
+    if !position_rows.is_empty() {
+        println!();
+        println!("  LP positions (underlying + claimable fees)");
+        for p in &position_rows {
+            println!("    {:<10} A {:>16}  B {:>16}  →  {}",
+                p["pair"].as_str().unwrap_or("?"),
+                p["amount_a"].as_u64().unwrap_or(0) + p["fees_a"].as_u64().unwrap_or(0),
+                p["amount_b"].as_u64().unwrap_or(0) + p["fees_b"].as_u64().unwrap_or(0),
+                show(&p["value"]));
+        }
+    }
+    println!();
+    println!("  Wallet value      {wallet_value:>20}  {sym_q}");
+    println!("  Positions value   {positions_value:>20}  {sym_q}");
+    println!("  Total             {total_value:>20}  {sym_q}");
+    if !unpriced.is_empty() {
+        println!();
+        println!("  Unpriced (no direct pool against {sym_q}, excluded from total):");
+        for m in &unpriced {
+            println!("    {} ({m})", resolve_symbol(m));
+        }
+    }
+    Ok(())
+}
 
-    let (pool_pda, _) = Pubkey::find_program_address(
-        &[POOL_SEED, mint_a.as_ref(), mint_b.as_ref()], &program_id);
-    let (pool_auth, _) = Pubkey::find_program_address(
-        &[POOL_AUTHORITY_SEED, pool_pda.as_ref()], &program_id);
+// ─── rebalance ────────────────────────────────────────────────────────────────
 
-    let vault_a = Keypair::new();
-    let vault_b = Keypair::new();
+#[allow(clippy::too_many_arguments)]
+fn cmd_rebalance(
+    rpc_url: &str,
+    keypair_path: &str,
+    pair: &str,
+    target: &str,
+    band: &str,
+    every: Option<u64>,
+    max_slippage: f64,
+    max_price_impact: f64,
+    json_output: bool,
+) -> Result<()> {
+    let target: TargetRatio = target.parse().context("--target")?;
+    let band_pct: f64 = band.trim().trim_end_matches('%').trim().parse()
+        .map_err(|_| input_error!("--band '{}' is not a percentage, e.g. 5% or 2.5.", band))?;
+    if !(0.0..=100.0).contains(&band_pct) {
+        return Err(input_error!("--band {} is out of range. Use 0–100 (percentage points).", band_pct));
+    }
+    let band_bps = (band_pct * 100.0).round() as u16;
 
-    let mut ix_data = anchor_disc("global", "initialize_pool").to_vec();
-    ix_data.extend_from_slice(&fee_rate_bps.to_le_bytes());
+    let Some(secs) = every else {
+        return rebalance_round(
+            rpc_url, keypair_path, pair, target, band_bps, max_slippage, max_price_impact, json_output,
+        );
+    };
+    if secs == 0 {
+        return Err(input_error!("--every must be at least 1 second."));
+    }
+    loop {
+        if let Err(e) = rebalance_round(
+            rpc_url, keypair_path, pair, target, band_bps, max_slippage, max_price_impact, json_output,
+        ) {
+            eprintln!("rebalance: {e:#}");
+        }
+        std::thread::sleep(std::time::Duration::from_secs(secs));
+    }
+}
 
-    let token_prog  = Pubkey::from_str(TOKEN_PROGRAM_ID)?;
-    let rent_sysvar = Pubkey::from_str(RENT_SYSVAR_ID)?;
+/// Check the drift once and swap if it is outside the band.
+#[allow(clippy::too_many_arguments)]
+fn rebalance_round(
+    rpc_url: &str,
+    keypair_path: &str,
+    pair: &str,
+    target: TargetRatio,
+    band_bps: u16,
+    max_slippage: f64,
+    max_price_impact: f64,
+    json_output: bool,
+) -> Result<()> {
+    let (sym_a, sym_b, mint_a, mint_b) = parse_pair(pair)?;
+    let payer      = load_keypair(keypair_path)?;
+    let program_id = Pubkey::from_str(PROGRAM_ID)?;
+    let client     = rpc(rpc_url);
 
-    let ix = Instruction {
-        program_id,
-        data: ix_data,
-        accounts: vec![
-            AccountMeta::new(payer.pubkey(),          true),
-            AccountMeta::new_readonly(mint_a,         false),
-            AccountMeta::new_readonly(mint_b,         false),
-            AccountMeta::new(pool_pda,                false),
-            AccountMeta::new_readonly(pool_auth,      false),
-            AccountMeta::new(vault_a.pubkey(),        true),
-            AccountMeta::new(vault_b.pubkey(),        true),
-            AccountMeta::new_readonly(token_prog,     false),
-            AccountMeta::new_readonly(Pubkey::from_str(SYSTEM_PROGRAM_ID)?, false),
-            AccountMeta::new_readonly(rent_sysvar,    false),
-        ],
-    };
+    let (pool_pda, _, pool, _, _) = find_pool_by_pair(&client, pair, &program_id)?;
+    let (ra, rb) = pool_reserves(&client, &pool)?;
+    let (reserve_a, reserve_b) = if pool.token_a_mint == mint_a { (ra, rb) } else { (rb, ra) };
+    if reserve_a == 0 || reserve_b == 0 {
+        return Err(anyhow!(
+            "Pool has no liquidity yet.\n  \
+             Run `a2a-swap provide --pair {pair}` to seed it first."
+        ));
+    }
 
-    let client = rpc(rpc_url);
-    let sig = sign_and_send(&client, &[ix], &payer, &[&payer, &vault_a, &vault_b])
-        .context("initialize_pool transaction failed")?;
+    let balances = wallet_balances(&client, &payer.pubkey())?;
+    let balance = |mint: &Pubkey| balances.get(mint).copied().unwrap_or(0);
+    let (balance_a, balance_b) = (balance(&mint_a), balance(&mint_b));
+    let plan = plan_rebalance(
+        balance_a, balance_b, spot_price(pool.curve, reserve_a, reserve_b), target, band_bps,
+    )?;
 
     if json_output {
         println!("{}", json!({
-            "status":         "ok",
-            "command":        "create-pool",
-            "pair":           pair,
-            "pool":           pool_pda.to_string(),
-            "pool_authority": pool_auth.to_string(),
-            "token_a_mint":   mint_a.to_string(),
-            "token_b_mint":   mint_b.to_string(),
-            "vault_a":        vault_a.pubkey().to_string(),
-            "vault_b":        vault_b.pubkey().to_string(),
-            "fee_rate_bps":   fee_rate_bps,
-            "initial_price":  initial_price,
-            "seed_amount":    seed_amount,
-            "tx":             sig.to_string(),
+            "status":             "ok",
+            "command":            "rebalance",
+            "pair":               pair,
+            "pool":               pool_pda.to_string(),
+            "target":             target.to_string(),
+            "band_bps":           band_bps,
+            "balance_a":          balance_a,
+            "balance_b":          balance_b,
+            "value_a":            plan.value_a,
+            "value_b":            plan.value_b,
+            "share_a_bps":        plan.share_a_bps,
+            "target_share_a_bps": plan.target_share_a_bps,
+            "drift_bps":          plan.drift_bps,
+            "trade":              plan.trade,
         }));
     } else {
-        println!("─── Pool Created ─────────────────────────────────────────────────");
-        println!("  Pair             {pair}");
-        println!("  Token A          {sym_a}  ({mint_a})");
-        println!("  Token B          {sym_b}  ({mint_b})");
-        println!("  Pool PDA         {pool_pda}");
-        println!("  Pool authority   {pool_auth}");
-        println!("  Vault A          {}", vault_a.pubkey());
-        println!("  Vault B          {}", vault_b.pubkey());
-        println!("  Fee rate         {fee_rate_bps} bps  ({:.2}% per swap)", fee_rate_bps as f64 / 100.0);
-        println!("  Transaction      {sig}");
-        if seed_amount > 0 {
-            let amount_b = (seed_amount as f64 * initial_price).round() as u64;
-            println!();
-            println!("  Pool is empty — seed it next:");
-            println!("    a2a-swap provide --pair {pair} \\");
-            println!("      --amount {seed_amount} --amount-b {amount_b}");
-        } else {
-            println!();
-            println!("  Run `a2a-swap provide --pair {pair} --amount <AMT_A> --amount-b <AMT_B>`");
-            println!("  to seed the pool with initial liquidity.");
+        println!("─── Rebalance {pair} ─────────────────────────────────────────");
+        println!("  Target           {target}  (band ±{:.2}%)", band_bps as f64 / 100.0);
+        println!("  {sym_a:<16} {:>20}  (value {:.0} {sym_b})", balance_a, plan.value_a);
+        println!("  {sym_b:<16} {:>20}", balance_b);
+        println!(
+            "  {:<16} {:>19.2}%  (target {:.2}%)",
+            format!("{sym_a} share"), plan.share_a_bps / 100.0, plan.target_share_a_bps / 100.0,
+        );
+        println!("  Drift            {:>+19.2}%", plan.drift_bps / 100.0);
+        match plan.trade {
+            Some(t) => {
+                let (sym_in, sym_out) = if t.a_to_b { (sym_a, sym_b) } else { (sym_b, sym_a) };
+                println!("  Action           sell {} {sym_in} for {sym_out}", t.amount_in);
+            }
+            None => println!("  Action           none — within band"),
         }
+        println!();
     }
-    Ok(())
+
+    let Some(trade) = plan.trade else {
+        return Ok(());
+    };
+    let (sym_in, sym_out) = if trade.a_to_b { (sym_a, sym_b) } else { (sym_b, sym_a) };
+    let approval = Approval {
+        mode:          "none",
+        webhook_url:   None,
+        approver:      None,
+        telegram_chat: None,
+        telegram_bot:  None,
+        expiry_slots:  0,
+    };
+    cmd_convert(
+        rpc_url, keypair_path,
+        sym_in, sym_out, trade.amount_in,
+        &approval,
+        max_slippage, max_price_impact,
+        0,
+        None,
+        json_output,
+    )
 }
 
-// ─── provide ─────────────────────────────────────────────────────────────────
+// ─── grid ─────────────────────────────────────────────────────────────────────
 
-fn cmd_provide(
-    rpc_url: &str,
+fn cmd_grid_run(
+    rpc_url:      &str,
     keypair_path: &str,
-    pair: &str,
-    amount_a: u64,
-    amount_b_arg: Option<u64>,
-    auto_compound: bool,
-    compound_threshold: u64,
-    json_output: bool,
+    config_path:  &std::path::Path,
+    once:         bool,
+    json_output:  bool,
 ) -> Result<()> {
-    let (_, _, mint_a, mint_b) = parse_pair(pair)?;
-    if amount_a == 0 {
-        return Err(anyhow!(
-            "--amount must be > 0 (atomic units: lamports for SOL, μUSDC for USDC, etc.)"
-        ));
+    let file = grid::GridFile::load(config_path)?;
+    let (sym_base, sym_quote, base_mint, quote_mint) = parse_pair(&file.pair)?;
+    let config     = file.grid_config(base_mint, quote_mint)?;
+    let state_path = file.state_path(config_path);
+    let payer      = load_keypair(keypair_path)?;
+    let client     = a2a_swap_sdk::A2ASwapClient::new(rpc_url)
+        .with_program_id(Pubkey::from_str(PROGRAM_ID)?);
+    let runtime    = tokio::runtime::Builder::new_current_thread().enable_all().build()?;
+
+    let mut state = if state_path.exists() {
+        let text = std::fs::read_to_string(&state_path)
+            .with_context(|| format!("reading grid state {}", state_path.display()))?;
+        GridState::from_json(&text)?
+    } else {
+        let center = match file.center_price {
+            Some(p) => p,
+            None    => runtime.block_on(sdk_grid::pool_price(&client, &config))?,
+        };
+        let state = GridState::new(&config, center)?;
+        save_grid_state(&state_path, &state)?;
+        state
+    };
+    if !json_output {
+        println!("─── Grid {} ─────────────────────────────────────────────", file.pair);
+        println!("  Levels           {} per side, {:.2}% apart", config.levels, file.spacing);
+        println!(
+            "  Range            {:.6} – {:.6} {sym_quote}/{sym_base}",
+            state.level_prices[0], state.level_prices[state.level_prices.len() - 1],
+        );
+        println!("  Anchor           level {} at {:.6}", state.anchor, state.level_prices[state.anchor]);
+        println!("  State            {}", state_path.display());
+        println!();
+    }
+
+    loop {
+        let result = runtime.block_on(sdk_grid::step(&client, &payer, &config, &mut state));
+        // Fills before a failed swap are already in `state`.
+        save_grid_state(&state_path, &state)?;
+        match result {
+            Ok(fills) if json_output => {
+                let (net_base, net_quote) = state.net_flows();
+                println!("{}", json!({
+                    "status":    "ok",
+                    "command":   "grid",
+                    "pair":      file.pair,
+                    "anchor":    state.anchor,
+                    "fills":     fills,
+                    "net_base":  net_base.to_string(),
+                    "net_quote": net_quote.to_string(),
+                }));
+            }
+            Ok(fills) => {
+                for f in &fills {
+                    let (sym_in, sym_out) = match f.side {
+                        sdk_grid::GridSide::Sell => (sym_base, sym_quote),
+                        sdk_grid::GridSide::Buy  => (sym_quote, sym_base),
+                    };
+                    println!(
+                        "  {:?} level {} @ {:.6}: {} {sym_in} → {} {sym_out}  {}",
+                        f.side, f.level, f.level_price, f.amount_in, f.amount_out,
+                        f.signature.as_deref().unwrap_or("-"),
+                    );
+                }
+            }
+            Err(e) if once => return Err(e.into()),
+            Err(e) => eprintln!("grid: {e:#}"),
+        }
+        if once {
+            return Ok(());
+        }
+        std::thread::sleep(std::time::Duration::from_secs(file.interval));
     }
+}
 
+fn save_grid_state(path: &std::path::Path, state: &GridState) -> Result<()> {
+    std::fs::write(path, state.to_json())
+        .with_context(|| format!("writing grid state {}", path.display()))
+}
+
+// ─── monitor ──────────────────────────────────────────────────────────────────
+
+fn cmd_monitor(
+    rpc_url:      &str,
+    keypair_path: &str,
+    config_path:  &std::path::Path,
+    once:         bool,
+    notify:       Option<&str>,
+    json_output:  bool,
+) -> Result<()> {
+    let file = monitor::MonitorFile::load(config_path)?;
+    let target = file.notify.as_deref().or(notify).map(notify::Target::parse).transpose()?;
+    let rule_mints = file.rules.iter()
+        .map(|r| r.pair.as_deref().map(parse_pair).transpose().map(|p| p.map(|(.., a, b)| (a, b))))
+        .collect::<Result<Vec<_>>>()?;
     let payer      = load_keypair(keypair_path)?;
     let program_id = Pubkey::from_str(PROGRAM_ID)?;
     let client     = rpc(rpc_url);
 
-    let (pool_pda, _) = Pubkey::find_program_address(
-        &[POOL_SEED, mint_a.as_ref(), mint_b.as_ref()], &program_id);
-    let (pool_auth, _) = Pubkey::find_program_address(
-        &[POOL_AUTHORITY_SEED, pool_pda.as_ref()], &program_id);
-    let (position_pda, _) = Pubkey::find_program_address(
-        &[POSITION_SEED, pool_pda.as_ref(), payer.pubkey().as_ref()], &program_id);
+    if !json_output {
+        println!("─── Monitor ──────────────────────────────────────────────────────");
+        println!("  Agent            {}", payer.pubkey());
+        println!("  Rules            {}  ·  every {}s, {}s window", file.rules.len(), file.interval, file.window);
+        println!("  Notify           {}", if target.is_some() { "on" } else { "off" });
+        println!();
+    }
 
-    let pool_acct = client.get_account(&pool_pda)
-        .with_context(|| format!(
-            "Pool not found for '{}'. Run `a2a-swap create-pool --pair {}` first.",
-            pair, pair
-        ))?;
-    let pool = parse_pool(&pool_acct.data)?;
+    let mut pools: HashMap<Pubkey, monitor::PoolHistory> = HashMap::new();
+    // Entry prices are rebuilt from history, so only when lp_shares changes.
+    let mut entries: HashMap<Pubkey, (u64, Option<f64>)> = HashMap::new();
+    let mut fired = monitor::Fired::default();
+
+    loop {
+        let mut check = || -> Result<()> {
+            let now = std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .map_or(0, |d| d.as_secs());
+            let positions = get_agent_positions(&client, &payer.pubkey(), &program_id)?;
+            let pool_map  = fetch_pool_map(&client, &dedup_pool_keys(&positions));
+            let mut spot  = HashMap::new();
+            for (key, pool) in &pool_map {
+                let (ra, rb) = pool_reserves(&client, pool)?;
+                pools.entry(*key).or_default().push(monitor::Sample {
+                    at:        now,
+                    reserve_a: ra,
+                    reserve_b: rb,
+                    fees_a:    pool.fees_collected_a,
+                    fees_b:    pool.fees_collected_b,
+                }, file.window);
+                spot.insert(*key, spot_price(pool.curve, ra, rb));
+            }
 
-    let amount_b: u64 = if let Some(b) = amount_b_arg {
-        b
-    } else if pool.lp_supply == 0 {
-        return Err(anyhow!(
-            "Pool '{}' is empty — pass --amount-b to set the initial price.\n  \
-             Example: --amount-b {} (for a 1:1 ratio).",
-            pair, amount_a
-        ));
-    } else {
-        let ra = parse_token_amount(&client.get_account(&pool.token_a_vault)?.data)?;
-        let rb = parse_token_amount(&client.get_account(&pool.token_b_vault)?.data)?;
-        if ra == 0 {
-            return Err(anyhow!("Vault A empty with non-zero lp_supply — inconsistent state"));
+            for (pda, pos) in &positions {
+                let Some(pool) = pool_map.get(&pos.pool) else { continue };
+                if pos.lp_shares == 0 {
+                    continue;
+                }
+                let entry = match entries.get(pda) {
+                    Some(&(shares, price)) if shares == pos.lp_shares => price,
+                    _ => {
+                        let price = position_entry(&client, pda)?.map(|(a, b)| b as f64 / a as f64);
+                        entries.insert(*pda, (pos.lp_shares, price));
+                        price
+                    }
+                };
+                let il_pct = entry.zip(spot.get(&pos.pool))
+                    .map(|(entry, &current)| -impermanent_loss(entry, current) * 100.0);
+                let history = &pools[&pos.pool];
+                let pair = format!("{}-{}", pool.token_a_mint, pool.token_b_mint);
+
+                for (i, rule) in file.rules.iter().enumerate() {
+                    let matches = rule_mints[i].map_or(true, |(a, b)| {
+                        (pool.token_a_mint, pool.token_b_mint) == (a, b)
+                            || (pool.token_a_mint, pool.token_b_mint) == (b, a)
+                    });
+                    if !matches {
+                        continue;
+                    }
+                    let started = fired.update(*pda, i, &monitor::breaches(rule, il_pct, history));
+                    for (condition, value, threshold) in &started {
+                        let alert = json!({
+                            "status":    "alert",
+                            "command":   "monitor",
+                            "at":        now,
+                            "position":  pda.to_string(),
+                            "pool":      pos.pool.to_string(),
+                            "pair":      pool_label(&pos.pool, &pool_map),
+                            "condition": condition.name(),
+                            "value":     value,
+                            "threshold": threshold,
+                            "action":    rule.action.label(),
+                        });
+                        if json_output {
+                            println!("{alert}");
+                        } else {
+                            println!(
+                                "  [{now}] {} {pda}: {} {value:.4} (threshold {threshold}) → {}",
+                                pool_label(&pos.pool, &pool_map), condition.name(), rule.action.label(),
+                            );
+                        }
+                        if let Some(target) = &target {
+                            notify::alert(target, &alert);
+                        }
+                    }
+                    if started.is_empty() {
+                        continue;
+                    }
+                    // One exit per check, however many conditions started.
+                    match rule.action {
+                        monitor::Action::Notify    => {}
+                        monitor::Action::ClaimFees => cmd_claim_fees(rpc_url, keypair_path, &pair, json_output)?,
+                        monitor::Action::Remove    => {
+                            cmd_remove(rpc_url, keypair_path, &pair, Some(100.0), None, 0, 0, json_output)?;
+                            break;
+                        }
+                    }
+                }
+            }
+            Ok(())
+        };
+        match check() {
+            Ok(()) => {}
+            Err(e) if once => return Err(e),
+            Err(e) => eprintln!("monitor: {e:#}"),
         }
-        let b = (amount_a as u128) * (rb as u128) / (ra as u128);
-        if b == 0 {
-            return Err(anyhow!(
-                "Computed amount_b = 0 — --amount {} is too small for this pool.\n  \
-                 Try a larger amount or pass --amount-b explicitly.",
-                amount_a
-            ));
+        if once {
+            return Ok(());
         }
-        b as u64
-    };
+        std::thread::sleep(std::time::Duration::from_secs(file.interval));
+    }
+}
 
-    let ata_a = derive_ata(&payer.pubkey(), &pool.token_a_mint);
-    let ata_b = derive_ata(&payer.pubkey(), &pool.token_b_mint);
+// ─── history ──────────────────────────────────────────────────────────────────
 
-    let mut ix_data = anchor_disc("global", "provide_liquidity").to_vec();
-    ix_data.extend_from_slice(&amount_a.to_le_bytes());
-    ix_data.extend_from_slice(&amount_b.to_le_bytes());
-    ix_data.extend_from_slice(&0u64.to_le_bytes()); // min_lp = 0
-    ix_data.push(auto_compound as u8);
-    ix_data.extend_from_slice(&compound_threshold.to_le_bytes());
+/// Signatures inspected by `history` before giving up on reaching `--limit`.
+const HISTORY_SCAN_LIMIT: usize = 1_000;
 
-    let token_prog  = Pubkey::from_str(TOKEN_PROGRAM_ID)?;
-    let rent_sysvar = Pubkey::from_str(RENT_SYSVAR_ID)?;
+fn cmd_history(
+    rpc_url:      &str,
+    keypair_path: &str,
+    pair:         Option<&str>,
+    limit:        usize,
+    json_output:  bool,
+) -> Result<()> {
+    let payer      = load_keypair(keypair_path)?;
+    let agent      = payer.pubkey();
+    let program_id = Pubkey::from_str(PROGRAM_ID)?;
+    let client     = rpc(rpc_url);
 
-    let ix = Instruction {
-        program_id,
-        data: ix_data,
-        accounts: vec![
-            AccountMeta::new(payer.pubkey(),          true),
-            AccountMeta::new(pool_pda,                false),
-            AccountMeta::new_readonly(pool_auth,      false),
-            AccountMeta::new(position_pda,            false),
-            AccountMeta::new(pool.token_a_vault,      false),
-            AccountMeta::new(pool.token_b_vault,      false),
-            AccountMeta::new(ata_a,                   false),
-            AccountMeta::new(ata_b,                   false),
-            AccountMeta::new_readonly(token_prog,     false),
-            AccountMeta::new_readonly(Pubkey::from_str(SYSTEM_PROGRAM_ID)?, false),
-            AccountMeta::new_readonly(rent_sysvar,    false),
-        ],
+    let only_pool = match pair {
+        Some(p) => Some(find_pool_by_pair(&client, p, &program_id)?.0),
+        None    => None,
     };
 
-    let sig = sign_and_send(&client, &[ix], &payer, &[&payer])
-        .context("provide_liquidity transaction failed")?;
+    struct Trade {
+        signature:   String,
+        slot:        u64,
+        block_time:  Option<i64>,
+        pool:        Pubkey,
+        fill:        events::SwapFill,
+        network_fee: u64,
+    }
+
+    let mut trades: Vec<Trade> = Vec::new();
+    let mut before  = None;
+    let mut scanned = 0;
+    'scan: while trades.len() < limit && scanned < HISTORY_SCAN_LIMIT {
+        let page = client.get_signatures_for_address_with_config(
+            &agent,
+            GetConfirmedSignaturesForAddress2Config {
+                before,
+                limit:      Some(100),
+                commitment: Some(CommitmentConfig::confirmed()),
+                ..GetConfirmedSignaturesForAddress2Config::default()
+            },
+        )?;
+        let Some(last) = page.last() else { break };
+        before = Some(last.signature.parse()?);
+
+        for sig in page {
+            scanned += 1;
+            if sig.err.is_some() {
+                continue;
+            }
+            let tx: serde_json::Value = client.send(
+                RpcRequest::GetTransaction,
+                json!([
+                    sig.signature,
+                    { "encoding": "json", "commitment": "confirmed", "maxSupportedTransactionVersion": 0 },
+                ]),
+            )?;
+            let network_fee = tx["meta"]["fee"].as_u64().unwrap_or(0);
+            for (pool, fill) in events::swap_fills(&tx, &program_id) {
+                if only_pool.is_some_and(|p| p != pool) {
+                    continue;
+                }
+                trades.push(Trade {
+                    signature: sig.signature.clone(),
+                    slot:      sig.slot,
+                    block_time: sig.block_time,
+                    pool,
+                    fill,
+                    network_fee,
+                });
+                if trades.len() >= limit {
+                    break 'scan;
+                }
+            }
+        }
+    }
+
+    // Current state of every pool traded, for symbols and the P&L mark.
+    let mut pool_keys: Vec<Pubkey> = trades.iter().map(|t| t.pool).collect();
+    pool_keys.sort();
+    pool_keys.dedup();
+    let pool_map = fetch_pool_map(&client, &pool_keys);
+    let spot: HashMap<Pubkey, f64> = pool_map.iter().filter_map(|(key, pool)| {
+        let (ra, rb) = pool_reserves(&client, pool).ok()?;
+        (ra > 0).then(|| (*key, spot_price(pool.curve, ra, rb)))
+    }).collect();
+
+    let rows: Vec<serde_json::Value> = trades.iter().map(|t| {
+        let f = &t.fill;
+        let (sym_in, sym_out) = match pool_map.get(&t.pool) {
+            Some(p) if f.a_to_b => (resolve_symbol(&p.token_a_mint), resolve_symbol(&p.token_b_mint)),
+            Some(p)             => (resolve_symbol(&p.token_b_mint), resolve_symbol(&p.token_a_mint)),
+            None                => ("?".into(), "?".into()),
+        };
+        // Realized B-per-A price, whichever way the trade went.
+        let (a, b) = if f.a_to_b { (f.amount_in, f.amount_out) } else { (f.amount_out, f.amount_in) };
+        let price = if a > 0 { b as f64 / a as f64 } else { 0.0 };
+        // Mark-to-market against the current spot, in token B atomic units.
+        let pnl_b = spot.get(&t.pool).map(|&s| {
+            if f.a_to_b { f.amount_out as f64 - f.amount_in as f64 * s }
+            else        { f.amount_out as f64 * s - f.amount_in as f64 }
+        });
+        json!({
+            "signature":           t.signature,
+            "slot":                t.slot,
+            "block_time":          t.block_time,
+            "pool":                t.pool.to_string(),
+            "pair":                pool_label(&t.pool, &pool_map),
+            "token_in":            sym_in,
+            "token_out":           sym_out,
+            "a_to_b":              f.a_to_b,
+            "amount_in":           f.amount_in,
+            "amount_out":          f.amount_out,
+            "price_b_per_a":       price,
+            "protocol_fee":        f.protocol_fee,
+            "lp_fee":              f.lp_fee,
+            "fees_paid":           f.protocol_fee + f.lp_fee,
+            "price_impact_bps":    f.impact_bps,
+            "network_fee_lamports": t.network_fee,
+            "pnl_b":               pnl_b.map(|p| p.round() as i64),
+        })
+    }).collect();
 
     if json_output {
         println!("{}", json!({
-            "status":             "ok",
-            "command":            "provide",
-            "pair":               pair,
-            "pool":               pool_pda.to_string(),
-            "position":           position_pda.to_string(),
-            "amount_a":           amount_a,
-            "amount_b":           amount_b,
-            "auto_compound":      auto_compound,
-            "compound_threshold": compound_threshold,
-            "tx":                 sig.to_string(),
+            "status":  "ok",
+            "command": "history",
+            "agent":   agent.to_string(),
+            "count":   rows.len(),
+            "scanned": scanned,
+            "trades":  rows,
+        }));
+        return Ok(());
+    }
+
+    println!("─── Swap History ({}) ──────────────────────────────────────────────", rows.len());
+    println!("  Agent   {agent}");
+    if rows.is_empty() {
+        println!();
+        println!("  No swaps found in the last {scanned} transaction(s).");
+        return Ok(());
+    }
+    let now = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs() as i64);
+    for (t, row) in trades.iter().zip(&rows) {
+        let age = t.block_time.map_or("—".into(), |bt| format_age(now - bt));
+        println!();
+        println!("  {}  {}  {}", row["pair"].as_str().unwrap_or("?"), age, t.signature);
+        println!("      Sold          {:>20}  {}", t.fill.amount_in, row["token_in"].as_str().unwrap_or("?"));
+        println!("      Received      {:>20}  {}", t.fill.amount_out, row["token_out"].as_str().unwrap_or("?"));
+        println!("      Price         {:.8}  B/A", row["price_b_per_a"].as_f64().unwrap_or(0.0));
+        println!("      Fees paid     {:>20}  (protocol {} + LP {})",
+            t.fill.protocol_fee + t.fill.lp_fee, t.fill.protocol_fee, t.fill.lp_fee);
+        match row["pnl_b"].as_i64() {
+            Some(p) => println!("      P&L vs spot   {p:>+20}  (token B)"),
+            None    => println!("      P&L vs spot   — (pool empty)"),
+        }
+    }
+    Ok(())
+}
+
+/// `42s ago`, `5m ago`, `3h ago`, `2d ago` — coarse age for human output.
+fn format_age(secs: i64) -> String {
+    match secs.max(0) {
+        s if s < 60     => format!("{s}s ago"),
+        s if s < 3_600  => format!("{}m ago", s / 60),
+        s if s < 86_400 => format!("{}h ago", s / 3_600),
+        s               => format!("{}d ago", s / 86_400),
+    }
+}
+
+// ─── export ───────────────────────────────────────────────────────────────────
+
+fn cmd_export(
+    rpc_url:      &str,
+    keypair_path: &str,
+    format:       &str,
+    what:         &str,
+    since:        Option<&str>,
+    output:       Option<&std::path::Path>,
+    json_output:  bool,
+) -> Result<()> {
+    let format: ExportFormat = format.parse()?;
+    let since  = since.map(parse_since).transpose()?;
+    let agent  = load_pubkey(keypair_path)?;
+    let client = a2a_swap_sdk::A2ASwapClient::new(rpc_url)
+        .with_program_id(Pubkey::from_str(PROGRAM_ID)?);
+    let runtime = tokio::runtime::Builder::new_current_thread().enable_all().build()?;
+
+    let (rows, bytes) = match what {
+        "trades" => {
+            let records = runtime.block_on(client.export_trades(&agent, since))?;
+            (records.len(), format.encode(&records))
+        }
+        "positions" => {
+            let records = runtime.block_on(client.export_positions(&agent))?;
+            (records.len(), format.encode(&records))
+        }
+        "liquidity" => {
+            let records = runtime.block_on(client.export_liquidity(&agent, since))?;
+            (records.len(), format.encode(&records))
+        }
+        "fees" => {
+            let records = runtime.block_on(client.export_fees(&agent, since))?;
+            (records.len(), format.encode(&records))
+        }
+        other => return Err(input_error!("unknown --what `{other}` (expected trades, positions, liquidity or fees)")),
+    };
+    let path = output.map_or_else(
+        || std::path::PathBuf::from(format!("a2a-swap-{what}.{}", format.extension())),
+        std::path::Path::to_path_buf,
+    );
+    std::fs::write(&path, &bytes).with_context(|| format!("writing {}", path.display()))?;
+
+    if json_output {
+        println!("{}", json!({
+            "status":  "ok",
+            "command": "export",
+            "what":    what,
+            "format":  format.extension(),
+            "since":   since,
+            "rows":    rows,
+            "path":    path.display().to_string(),
         }));
     } else {
-        println!("─── Liquidity Provided ───────────────────────────────────────────");
-        println!("  Pair             {pair}");
-        println!("  Pool             {pool_pda}");
-        println!("  Position         {position_pda}");
-        println!("  Deposited A      {:>20}", amount_a);
-        println!("  Deposited B      {:>20}", amount_b);
-        println!("  Auto-compound    {}", if auto_compound { "enabled" } else { "disabled" });
-        if auto_compound && compound_threshold > 0 {
-            println!("  Cmpnd threshold  {:>20}", compound_threshold);
+        println!("Exported {rows} {what} record(s) to {} ({format}, {} bytes)", path.display(), bytes.len());
+    }
+    Ok(())
+}
+
+fn cmd_gains(
+    rpc_url:      &str,
+    keypair_path: &str,
+    quote:        &str,
+    method:       &str,
+    since:        Option<&str>,
+    output:       Option<&std::path::Path>,
+    json_output:  bool,
+) -> Result<()> {
+    let method: LotMethod = method.parse()?;
+    let quote_mint = resolve_mint(quote)?;
+    let since  = since.map(parse_since).transpose()?;
+    let agent  = load_pubkey(keypair_path)?;
+    let client = a2a_swap_sdk::A2ASwapClient::new(rpc_url)
+        .with_program_id(Pubkey::from_str(PROGRAM_ID)?);
+    let runtime = tokio::runtime::Builder::new_current_thread().enable_all().build()?;
+    let lots    = runtime.block_on(client.realized_gains(&agent, since, method, quote_mint))?;
+    let summary = lots.summary();
+
+    if let Some(path) = output {
+        let format = match path.extension().and_then(|e| e.to_str()) {
+            Some(ext) => ext.parse::<ExportFormat>()?,
+            None      => ExportFormat::Csv,
+        };
+        std::fs::write(path, format.encode(&lots.disposals))
+            .with_context(|| format!("writing {}", path.display()))?;
+    }
+
+    // LP shares are lots of their pool: name them after its pair.
+    let lp_pools: Vec<Pubkey> = summary.iter().filter(|s| s.lp_shares).map(|s| s.asset).collect();
+    let pool_map = fetch_pool_map(&rpc(rpc_url), &lp_pools);
+    let total: i64 = summary.iter().map(|s| s.realized_gain).sum();
+
+    if json_output {
+        println!("{}", json!({
+            "status":        "ok",
+            "command":       "gains",
+            "method":        method.to_string(),
+            "quote":         resolve_symbol(&quote_mint),
+            "quote_mint":    quote_mint.to_string(),
+            "since":         since,
+            "realized_gain": total,
+            "tokens":        summary,
+            "skipped":       lots.skipped,
+            "path":          output.map(|p| p.display().to_string()),
+        }));
+        return Ok(());
+    }
+
+    let sym_q = resolve_symbol(&quote_mint);
+    println!("─── Realized gains ({method}) ───────────────────────────────────────────");
+    println!("  Quote   {sym_q} ({quote_mint})  — all values in atomic units");
+    println!();
+    if summary.is_empty() {
+        println!("  No swaps, LP activity or fee income in range.");
+    }
+    for s in &summary {
+        let name = if s.lp_shares { format!("LP {}", pool_label(&s.asset, &pool_map)) } else { resolve_symbol(&s.asset) };
+        println!("  {name}");
+        println!("    Disposed   {:>20}  proceeds {:>20}  cost {:>20}", s.disposed, s.proceeds, s.cost_basis);
+        println!("    Gain       {:>20}", s.realized_gain);
+        if s.unmatched_quantity > 0 {
+            println!("    Unmatched  {:>20}  proceeds {:>20}  (no lot in range)", s.unmatched_quantity, s.unmatched_proceeds);
         }
-        println!("  Transaction      {sig}");
-        println!();
-        println!("  Run `a2a-swap my-fees --json` to check claimable LP fee balances.");
+        if s.income > 0 {
+            println!("    Fee income {:>20}", s.income);
+        }
+        println!("    Open       {:>20}  cost     {:>20}", s.open_quantity, s.open_cost);
+    }
+    println!();
+    println!("  Total realized gain  {total} {sym_q}");
+    if !lots.skipped.is_empty() {
+        println!("  {} event(s) could not be valued in {sym_q} and were skipped", lots.skipped.len());
+    }
+    if let Some(path) = output {
+        println!("  Disposals written to {}", path.display());
     }
     Ok(())
 }
 
-// ─── convert ─────────────────────────────────────────────────────────────────
+/// `--since`: unix seconds, or a `YYYY-MM-DD` date taken as UTC midnight.
+fn parse_since(s: &str) -> Result<i64> {
+    if let Ok(secs) = s.parse::<i64>() {
+        return Ok(secs);
+    }
+    let bad = || input_error!("--since `{s}`: expected YYYY-MM-DD or unix seconds");
+    let mut parts = s.splitn(3, '-').map(|p| p.parse::<i64>().map_err(|_| bad()));
+    let (y, m, d) = (
+        parts.next().ok_or_else(bad)??,
+        parts.next().ok_or_else(bad)??,
+        parts.next().ok_or_else(bad)??,
+    );
+    if !(1..=12).contains(&m) || !(1..=31).contains(&d) {
+        return Err(bad());
+    }
+    // Days since 1970-01-01 in the proleptic Gregorian calendar
+    // (Howard Hinnant's `days_from_civil`).
+    let y   = if m <= 2 { y - 1 } else { y };
+    let era = y.div_euclid(400);
+    let yoe = y - era * 400;
+    let doy = (153 * ((m + 9) % 12) + 2) / 5 + d - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    Ok((era * 146_097 + doe - 719_468) * 86_400)
+}
 
-fn cmd_convert(
+// ─── remove-liquidity ────────────────────────────────────────────────────────
+
+#[allow(clippy::too_many_arguments)]
+fn cmd_remove_liquidity(
     rpc_url: &str,
     keypair_path: &str,
-    token_in: &str,
-    token_out: &str,
-    amount_in: u64,
-    approval_mode: &str,
-    webhook_url: Option<&str>,
-    max_slippage: f64,
+    pair: &str,
+    lp_shares: u64,
+    min_a: u64,
+    min_b: u64,
+    close_if_empty: bool,
     json_output: bool,
 ) -> Result<()> {
-    let mint_in  = resolve_mint(token_in).context("--in")?;
-    let mint_out = resolve_mint(token_out).context("--out")?;
-    if mint_in == mint_out {
-        return Err(anyhow!("--in and --out must be different tokens."));
-    }
-    if amount_in == 0 {
-        return Err(anyhow!(
-            "--amount must be > 0 (atomic units: lamports for SOL, μUSDC for USDC, etc.)"
-        ));
-    }
-    if !(0.0..=100.0).contains(&max_slippage) {
-        return Err(anyhow!(
-            "--max-slippage {} is out of range. Use 0–100 (percent). Default 0.5 = 0.5%.",
-            max_slippage
+    if lp_shares == 0 {
+        return Err(input_error!(
+            "--shares must be > 0 (run `a2a-swap my-positions` to see your LP share balance)."
         ));
     }
 
@@ -1300,756 +6311,1247 @@ fn cmd_convert(
     let program_id = Pubkey::from_str(PROGRAM_ID)?;
     let client     = rpc(rpc_url);
 
-    let (pool_pda, pool_auth, pool, a_to_b) =
-        find_pool(&client, &mint_in, &mint_out, &program_id)?;
+    let (pool_pda, pool_auth, pool, mint_a, mint_b) =
+        find_pool_by_pair(&client, pair, &program_id)?;
 
-    let ra = parse_token_amount(&client.get_account(&pool.token_a_vault)?.data)?;
-    let rb = parse_token_amount(&client.get_account(&pool.token_b_vault)?.data)?;
-    if ra == 0 || rb == 0 {
-        return Err(anyhow!(
-            "Pool has no liquidity yet.\n  \
-             Run `a2a-swap provide --pair {}-{}` to seed it first.",
-            token_in, token_out
-        ));
-    }
-    let (reserve_in, reserve_out) = if a_to_b { (ra, rb) } else { (rb, ra) };
+    let (position_pda, _) = Pubkey::find_program_address(
+        &[POSITION_SEED, pool_pda.as_ref(), payer.pubkey().as_ref()],
+        &program_id,
+    );
 
-    let sim            = simulate_detailed(amount_in, reserve_in, reserve_out, pool.fee_rate_bps);
-    let min_amount_out = (sim.estimated_out as f64 * (1.0 - max_slippage / 100.0)) as u64;
+    // Verify position exists and has enough shares
+    let pos_acct = client.get_account(&position_pda)
+        .with_context(|| format!(
+            "No position found for this keypair in pool '{pair}'.\n  \
+             Run `a2a-swap my-positions` to see your LP positions."
+        ))?;
+    let pos = parse_position(&pos_acct.data)?;
+    if pos.lp_shares < lp_shares {
+        return Err(CliError::InsufficientBalance(format!(
+            "Requested {} LP shares but position only holds {}.\n  \
+             Run `a2a-swap my-positions` to see your current balance.",
+            lp_shares, pos.lp_shares
+        )).into());
+    }
 
-    approval_gate(approval_mode, webhook_url, &json!({
-        "token_in":      token_in,
-        "token_out":     token_out,
-        "amount_in":     amount_in,
-        "estimated_out": sim.estimated_out,
-        "price_impact":  format!("{:.4}%", sim.price_impact_pct),
-        "pool":          pool_pda.to_string(),
-        "agent":         payer.pubkey().to_string(),
-    }))?;
+    // Pre-compute expected amounts for display (mirrors on-chain math)
+    let (reserve_a, reserve_b) = pool_reserves(&client, &pool)?;
+    let expected_a = if pool.lp_supply > 0 {
+        (lp_shares as u128 * reserve_a as u128 / pool.lp_supply as u128) as u64
+    } else { 0 };
+    let expected_b = if pool.lp_supply > 0 {
+        (lp_shares as u128 * reserve_b as u128 / pool.lp_supply as u128) as u64
+    } else { 0 };
 
-    let ata_in  = derive_ata(&payer.pubkey(), &mint_in);
-    let ata_out = derive_ata(&payer.pubkey(), &mint_out);
-    let (treasury, _) = Pubkey::find_program_address(&[TREASURY_SEED], &program_id);
-    let treasury_ata  = derive_ata(&treasury, &mint_in);
+    let ata_a = derive_ata(&payer.pubkey(), &mint_a);
+    let ata_b = derive_ata(&payer.pubkey(), &mint_b);
 
-    let mut ix_data = anchor_disc("global", "swap").to_vec();
-    ix_data.extend_from_slice(&amount_in.to_le_bytes());
-    ix_data.extend_from_slice(&min_amount_out.to_le_bytes());
-    ix_data.push(a_to_b as u8);
+    let ix_data = ix::RemoveLiquidity { lp_shares, min_a, min_b }.data();
 
-    let swap_ix = Instruction {
+    let mut ix = Instruction {
         program_id,
         data: ix_data,
         accounts: vec![
-            AccountMeta::new(payer.pubkey(),      true),
-            AccountMeta::new(pool_pda,            false),
-            AccountMeta::new_readonly(pool_auth,  false),
-            AccountMeta::new(pool.token_a_vault,  false),
-            AccountMeta::new(pool.token_b_vault,  false),
-            AccountMeta::new(ata_in,              false),
-            AccountMeta::new(ata_out,             false),
-            AccountMeta::new_readonly(treasury,   false),
-            AccountMeta::new(treasury_ata,        false),
+            AccountMeta::new(payer.pubkey(),          true),
+            AccountMeta::new(pool_pda,                false),
+            AccountMeta::new_readonly(pool_auth,      false),
+            AccountMeta::new(position_pda,            false),
+            AccountMeta::new(pool.token_a_vault,      false),
+            AccountMeta::new(pool.token_b_vault,      false),
+            AccountMeta::new(ata_a,                   false),
+            AccountMeta::new(ata_b,                   false),
             AccountMeta::new_readonly(Pubkey::from_str(TOKEN_PROGRAM_ID)?, false),
         ],
     };
-
-    let wsol_mint = Pubkey::from_str(WSOL_MINT)?;
-    let mut instructions: Vec<Instruction> = Vec::new();
-
-    // If tokenIn is SOL: wrap native SOL → wSOL ATA before the swap.
-    if mint_in == wsol_mint {
-        instructions.push(create_ata_idempotent_ix(&payer.pubkey(), &ata_in, &payer.pubkey(), &wsol_mint)?);
-        instructions.push(system_transfer_ix(&payer.pubkey(), &ata_in, amount_in)?);
-        instructions.push(sync_native_ix(&ata_in)?);
-    }
-
-    // If tokenOut is SOL: ensure the wSOL output ATA exists before the swap.
-    if mint_out == wsol_mint {
-        instructions.push(create_ata_idempotent_ix(&payer.pubkey(), &ata_out, &payer.pubkey(), &wsol_mint)?);
+    // A full exit burns the position's receipt NFT, if it has one.
+    let (receipt, metas) = receipt_accounts(&position_pda, &program_id)?;
+    let burn_receipt = lp_shares == pos.lp_shares && client.get_account(&receipt).is_ok();
+    if burn_receipt {
+        ix.accounts.extend(metas);
     }
 
-    instructions.push(swap_ix);
-
-    // If tokenOut is SOL: close the wSOL ATA and return lamports as native SOL.
-    if mint_out == wsol_mint {
-        instructions.push(close_account_ix(&ata_out, &payer.pubkey(), &payer.pubkey())?);
+    let mut ixs = migration_ixs(&payer.pubkey(), &pool_pda, &pool, Some((&position_pda, &pos)))?;
+    ixs.push(ix);
+    // Everything the removal accrues is paid out, never compounded: the
+    // position is about to go, so its auto-compound setting is switched off.
+    let close = close_if_empty && lp_shares == pos.lp_shares;
+    let (fees_a, fees_b) = if close { pending_fees(&pos, &pool) } else { (0, 0) };
+    if close {
+        if fees_a > 0 || fees_b > 0 {
+            if pos.auto_compound {
+                ixs.push(Instruction {
+                    program_id,
+                    data: ix::UpdatePositionSettings {
+                        auto_compound:      false,
+                        compound_threshold: pos.compound_threshold,
+                    }.data(),
+                    accounts: vec![
+                        AccountMeta::new_readonly(payer.pubkey(), true),
+                        AccountMeta::new(position_pda,            false),
+                    ],
+                });
+            }
+            ixs.push(Instruction {
+                program_id,
+                data: ix::ClaimFees.data(),
+                accounts: vec![
+                    AccountMeta::new(payer.pubkey(),          true),
+                    AccountMeta::new(pool_pda,                false),
+                    AccountMeta::new_readonly(pool_auth,      false),
+                    AccountMeta::new(position_pda,            false),
+                    AccountMeta::new(pool.token_a_vault,      false),
+                    AccountMeta::new(pool.token_b_vault,      false),
+                    AccountMeta::new(ata_a,                   false),
+                    AccountMeta::new(ata_b,                   false),
+                    AccountMeta::new_readonly(Pubkey::from_str(TOKEN_PROGRAM_ID)?, false),
+                ],
+            });
+        }
+        ixs.push(close_position_ix(&payer.pubkey(), &position_pda, &pool_pda)?);
     }
+    let sig = sign_and_send(&client, &ixs, &payer, &[&payer])
+        .context("remove_liquidity transaction failed")?;
 
-    let sig = sign_and_send(&client, &instructions, &payer, &[&payer])
-        .context("swap transaction failed")?;
-
+    let summary = json!({
+        "status":     "ok",
+        "command":    "remove-liquidity",
+        "pair":       pair,
+        "pool":       pool_pda.to_string(),
+        "position":   position_pda.to_string(),
+        "lp_shares":  lp_shares,
+        "expected_a": expected_a,
+        "expected_b": expected_b,
+        "min_a":      min_a,
+        "min_b":      min_b,
+        "receipt_burned": burn_receipt.then(|| receipt.to_string()),
+        "closed":     close,
+        "fees_a":     close.then_some(fees_a),
+        "fees_b":     close.then_some(fees_b),
+        "tx":         sig.to_string(),
+    });
+    notify::record(&summary);
     if json_output {
-        println!("{}", json!({
-            "status":         "ok",
-            "command":        "convert",
-            "token_in":       token_in,
-            "token_out":      token_out,
-            "amount_in":      amount_in,
-            "protocol_fee":   sim.protocol_fee,
-            "lp_fee":         sim.lp_fee,
-            "estimated_out":  sim.estimated_out,
-            "min_amount_out": min_amount_out,
-            "price_impact_pct": sim.price_impact_pct,
-            "a_to_b":         a_to_b,
-            "pool":           pool_pda.to_string(),
-            "approval_mode":  approval_mode,
-            "tx":             sig.to_string(),
-        }));
+        println!("{summary}");
     } else {
-        let dir = if a_to_b { "A → B" } else { "B → A" };
-        println!("─── Swap Executed ────────────────────────────────────────────────");
-        println!("  Direction        {dir}  ({token_in} → {token_out})");
+        println!("─── Liquidity Removed ────────────────────────────────────────────");
+        println!("  Pair             {pair}");
         println!("  Pool             {pool_pda}");
-        println!();
-        println!("  ─── Fee Breakdown ────────────────────────────────");
-        println!("  Sold             {:>20}  {token_in}", amount_in);
-        println!("  Protocol fee     {:>20}  (0.020%)", sim.protocol_fee);
-        println!("  LP fee           {:>20}  ({:.2}% of net)", sim.lp_fee, pool.fee_rate_bps as f64 / 100.0);
-        println!("  After all fees   {:>20}", sim.after_fees);
-        println!();
-        println!("  ─── Output ───────────────────────────────────────");
-        println!("  Received (est.)  {:>20}  {token_out}", sim.estimated_out);
-        println!("  Min accepted     {:>20}  {token_out}  ({:.1}% slippage guard)", min_amount_out, max_slippage);
-        println!("  Price impact     {:>19.4}%", sim.price_impact_pct);
-        println!();
-        if approval_mode != "none" {
-            println!("  Approval mode    {approval_mode}");
+        println!("  Position         {position_pda}");
+        println!("  LP shares burnt  {:>20}", lp_shares);
+        println!("  Expected A       {:>20}  (token A, atomic units)", expected_a);
+        println!("  Expected B       {:>20}  (token B, atomic units)", expected_b);
+        if min_a > 0 || min_b > 0 {
+            println!("  Min A guard      {:>20}", min_a);
+            println!("  Min B guard      {:>20}", min_b);
+        }
+        if burn_receipt {
+            println!("  Receipt burned   {receipt}");
+        }
+        if close {
+            println!("  Fees A claimed   {:>20}  (token A, atomic units)", fees_a);
+            println!("  Fees B claimed   {:>20}  (token B, atomic units)", fees_b);
+            println!("  Position         closed, rent returned");
         }
         println!("  Transaction      {sig}");
+        if !close {
+            println!();
+            if close_if_empty {
+                println!("  Position not closed: {} LP shares remain.", pos.lp_shares - lp_shares);
+            }
+            println!("  Run `a2a-swap claim-fees --pair {pair}` to collect any accrued fees.");
+        }
     }
     Ok(())
 }
 
-// ─── simulate ────────────────────────────────────────────────────────────────
+// ─── claim-fees ───────────────────────────────────────────────────────────────
 
-fn cmd_simulate(
+fn cmd_claim_fees(
     rpc_url: &str,
-    token_in: &str,
-    token_out: &str,
-    amount_in: u64,
-    mode: &str,
+    keypair_path: &str,
+    pair: &str,
     json_output: bool,
 ) -> Result<()> {
-    if mode != "direct" {
-        return Err(anyhow!(
-            "Unsupported --mode '{}'. Only 'direct' is available in this release.",
-            mode
-        ));
-    }
-    let mint_in  = resolve_mint(token_in).context("--in")?;
-    let mint_out = resolve_mint(token_out).context("--out")?;
-    if mint_in == mint_out {
-        return Err(anyhow!("--in and --out must be different tokens."));
-    }
-    if amount_in == 0 {
-        return Err(anyhow!(
-            "--amount must be > 0 (atomic units: lamports for SOL, μUSDC for USDC, etc.)"
-        ));
-    }
-
+    let payer      = load_keypair(keypair_path)?;
     let program_id = Pubkey::from_str(PROGRAM_ID)?;
     let client     = rpc(rpc_url);
 
-    let (pool_pda, _, pool, a_to_b) =
-        find_pool(&client, &mint_in, &mint_out, &program_id)?;
+    let (pool_pda, pool_auth, pool, mint_a, mint_b) =
+        find_pool_by_pair(&client, pair, &program_id)?;
 
-    let ra = parse_token_amount(&client.get_account(&pool.token_a_vault)
-        .context("fetch vault_a")?.data)?;
-    let rb = parse_token_amount(&client.get_account(&pool.token_b_vault)
-        .context("fetch vault_b")?.data)?;
-    if ra == 0 || rb == 0 {
-        return Err(anyhow!(
-            "Pool has no liquidity yet.\n  \
-             Run `a2a-swap provide --pair {}-{}` to seed it first.",
-            token_in, token_out
-        ));
+    let (position_pda, _) = Pubkey::find_program_address(
+        &[POSITION_SEED, pool_pda.as_ref(), payer.pubkey().as_ref()],
+        &program_id,
+    );
+
+    let pos_acct = client.get_account(&position_pda)
+        .with_context(|| format!(
+            "No position found for this keypair in pool '{pair}'.\n  \
+             Run `a2a-swap my-positions` to see your LP positions."
+        ))?;
+    let pos = parse_position(&pos_acct.data)?;
+
+    // Pre-flight: compute fees so we can show them even if zero
+    let (fees_a, fees_b) = pending_fees(&pos, &pool);
+
+    if fees_a == 0 && fees_b == 0 {
+        if json_output {
+            println!("{}", json!({
+                "status":   "ok",
+                "command":  "claim-fees",
+                "pair":     pair,
+                "pool":     pool_pda.to_string(),
+                "position": position_pda.to_string(),
+                "fees_a":   0,
+                "fees_b":   0,
+                "note":     "No fees to claim",
+            }));
+        } else {
+            println!("─── Claim Fees ───────────────────────────────────────────────────");
+            println!("  Pair       {pair}");
+            println!("  Position   {position_pda}");
+            println!();
+            println!("  No fees to claim for this position.");
+        }
+        return Ok(());
     }
 
-    let (reserve_in, reserve_out) = if a_to_b { (ra, rb) } else { (rb, ra) };
-    let sim = simulate_detailed(amount_in, reserve_in, reserve_out, pool.fee_rate_bps);
+    let ata_a = derive_ata(&payer.pubkey(), &mint_a);
+    let ata_b = derive_ata(&payer.pubkey(), &mint_b);
 
-    if json_output {
-        println!("{}", json!({
-            "status":           "ok",
-            "command":          "simulate",
-            "token_in":         token_in,
-            "token_out":        token_out,
-            "pool":             pool_pda.to_string(),
-            "a_to_b":           a_to_b,
-            "mode":             mode,
-            "amount_in":        amount_in,
-            "protocol_fee":     sim.protocol_fee,
-            "net_pool_input":   sim.net_pool_input,
-            "lp_fee":           sim.lp_fee,
-            "after_fees":       sim.after_fees,
-            "estimated_out":    sim.estimated_out,
-            "effective_rate":   sim.effective_rate,
-            "price_impact_pct": sim.price_impact_pct,
-            "fee_rate_bps":     pool.fee_rate_bps,
-            "reserve_in":       reserve_in,
-            "reserve_out":      reserve_out,
-        }));
+    let ix_data = ix::ClaimFees.data();
+
+    let ix = Instruction {
+        program_id,
+        data: ix_data,
+        accounts: vec![
+            AccountMeta::new(payer.pubkey(),          true),
+            AccountMeta::new(pool_pda,                false),
+            AccountMeta::new_readonly(pool_auth,      false),
+            AccountMeta::new(position_pda,            false),
+            AccountMeta::new(pool.token_a_vault,      false),
+            AccountMeta::new(pool.token_b_vault,      false),
+            AccountMeta::new(ata_a,                   false),
+            AccountMeta::new(ata_b,                   false),
+            AccountMeta::new_readonly(Pubkey::from_str(TOKEN_PROGRAM_ID)?, false),
+        ],
+    };
+
+    let mut ixs = migration_ixs(&payer.pubkey(), &pool_pda, &pool, Some((&position_pda, &pos)))?;
+    ixs.push(ix);
+    let sig = sign_and_send(&client, &ixs, &payer, &[&payer])
+        .context("claim_fees transaction failed")?;
+
+    let summary = json!({
+        "status":        "ok",
+        "command":       "claim-fees",
+        "pair":          pair,
+        "pool":          pool_pda.to_string(),
+        "position":      position_pda.to_string(),
+        "fees_a":        fees_a,
+        "fees_b":        fees_b,
+        "auto_compound": pos.auto_compound,
+        "tx":            sig.to_string(),
+    });
+    notify::record(&summary);
+    if json_output {
+        println!("{summary}");
     } else {
-        let dir = if a_to_b { "A → B" } else { "B → A" };
-        println!("─── Swap Simulation ──────────────────────────────────────────────");
-        println!("  {token_in} → {token_out}  [{mode} / {dir}]");
+        let mode = if pos.auto_compound {
+            "auto-compounded → LP shares"
+        } else {
+            "transferred to agent wallet"
+        };
+        println!("─── Fees Claimed ─────────────────────────────────────────────────");
+        println!("  Pair             {pair}");
         println!("  Pool             {pool_pda}");
-        println!("  Reserve in       {:>20}", reserve_in);
-        println!("  Reserve out      {:>20}", reserve_out);
-        println!();
-        println!("  ─── Fee Breakdown ────────────────────────────────");
-        println!("  Amount in        {:>20}", amount_in);
-        println!("  Protocol fee     {:>20}  (0.020%  →  treasury)", sim.protocol_fee);
-        println!("  Net to pool      {:>20}", sim.net_pool_input);
-        println!("  LP fee           {:>20}  ({:.2}%  →  vault/LPs)",
-                 sim.lp_fee, pool.fee_rate_bps as f64 / 100.0);
-        println!("  After all fees   {:>20}", sim.after_fees);
-        println!();
-        println!("  ─── Output Estimate ──────────────────────────────");
-        println!("  Estimated out    {:>20}", sim.estimated_out);
-        println!("  Effective rate   {:>20.8}  {token_out}/{token_in} (raw units)",
-                 sim.effective_rate);
-        println!("  Price impact     {:>19.4}%", sim.price_impact_pct);
-        println!();
-        println!("  No transaction sent.  To execute:");
-        println!("    a2a-swap convert --in {token_in} --out {token_out} --amount {amount_in}");
+        println!("  Position         {position_pda}");
+        println!("  Fees A           {:>20}  (token A, atomic units)", fees_a);
+        println!("  Fees B           {:>20}  (token B, atomic units)", fees_b);
+        println!("  Mode             {mode}");
+        println!("  Transaction      {sig}");
     }
     Ok(())
 }
 
-// ─── my-positions ─────────────────────────────────────────────────────────────
+// ─── position set / close ────────────────────────────────────────────────────
+
+/// `close_position` for `payer`'s position in `pool`; the program checks it
+/// is empty and counts it out of the pool's open positions.
+fn close_position_ix(payer: &Pubkey, position: &Pubkey, pool: &Pubkey) -> Result<Instruction> {
+    Ok(Instruction {
+        program_id: Pubkey::from_str(PROGRAM_ID)?,
+        data: ix::ClosePosition.data(),
+        accounts: vec![
+            AccountMeta::new(*payer,    true),
+            AccountMeta::new(*position, false),
+            AccountMeta::new(*pool,     false),
+        ],
+    })
+}
+
+fn cmd_position_set(
+    rpc_url: &str,
+    keypair_path: &str,
+    pair: &str,
+    auto_compound: Option<bool>,
+    threshold: Option<u64>,
+    json_output: bool,
+) -> Result<()> {
+    if auto_compound.is_none() && threshold.is_none() {
+        return Err(input_error!(
+            "Nothing to change: pass --auto-compound on|off and/or --threshold <AMOUNT>.\n  \
+             Example: a2a-swap position set --pair {pair} --auto-compound on --threshold 1000000"
+        ));
+    }
 
-fn cmd_my_positions(rpc_url: &str, keypair_path: &str, json_output: bool) -> Result<()> {
     let payer      = load_keypair(keypair_path)?;
     let program_id = Pubkey::from_str(PROGRAM_ID)?;
     let client     = rpc(rpc_url);
 
-    let positions = get_agent_positions(&client, &payer.pubkey(), &program_id)?;
+    let (pool_pda, _, pool, _, _) = find_pool_by_pair(&client, pair, &program_id)?;
 
-    if positions.is_empty() {
-        if json_output {
-            println!("{}", json!({
-                "status": "ok", "command": "my-positions",
-                "agent": payer.pubkey().to_string(), "positions": [],
-            }));
-        } else {
-            println!("─── My Positions ─────────────────────────────────────────────────");
-            println!("  Agent   {}", payer.pubkey());
-            println!();
-            println!("  No LP positions found.");
-            println!("  Run `a2a-swap provide --pair <PAIR> --amount <AMT>` to become an LP.");
-        }
-        return Ok(());
-    }
+    let (position_pda, _) = Pubkey::find_program_address(
+        &[POSITION_SEED, pool_pda.as_ref(), payer.pubkey().as_ref()],
+        &program_id,
+    );
 
-    let pool_keys: Vec<Pubkey> = dedup_pool_keys(&positions);
-    let pool_map = fetch_pool_map(&client, &pool_keys);
+    let pos_acct = client.get_account(&position_pda)
+        .with_context(|| format!(
+            "No position found for this keypair in pool '{pair}'.\n  \
+             Run `a2a-swap my-positions` to see your LP positions."
+        ))?;
+    let pos = parse_position(&pos_acct.data)?;
 
-    if json_output {
-        let items: Vec<_> = positions.iter().map(|(pda, pos)| json!({
-            "position":           pda.to_string(),
-            "pool":               pos.pool.to_string(),
-            "pair":               pool_label(&pos.pool, &pool_map),
-            "lp_shares":          pos.lp_shares,
+    let auto_compound = auto_compound.unwrap_or(pos.auto_compound);
+    let threshold     = threshold.unwrap_or(pos.compound_threshold);
+
+    let ix = Instruction {
+        program_id,
+        data: ix::UpdatePositionSettings { auto_compound, compound_threshold: threshold }.data(),
+        accounts: vec![
+            AccountMeta::new_readonly(payer.pubkey(), true),
+            AccountMeta::new(position_pda,            false),
+        ],
+    };
+
+    let mut ixs = migration_ixs(&payer.pubkey(), &pool_pda, &pool, Some((&position_pda, &pos)))?;
+    ixs.push(ix);
+    let sig = sign_and_send(&client, &ixs, &payer, &[&payer])
+        .context("update_position_settings transaction failed")?;
+
+    let summary = json!({
+        "status":             "ok",
+        "command":            "position-set",
+        "pair":               pair,
+        "pool":               pool_pda.to_string(),
+        "position":           position_pda.to_string(),
+        "auto_compound":      auto_compound,
+        "compound_threshold": threshold,
+        "previous": {
             "auto_compound":      pos.auto_compound,
             "compound_threshold": pos.compound_threshold,
-        })).collect();
-        println!("{}", json!({
-            "status": "ok", "command": "my-positions",
-            "agent": payer.pubkey().to_string(), "positions": items,
-        }));
+        },
+        "tx":                 sig.to_string(),
+    });
+    notify::record(&summary);
+    if json_output {
+        println!("{summary}");
     } else {
-        println!("─── My Positions ─────────────────────────────────────────────────");
-        println!("  Agent   {}", payer.pubkey());
-        println!();
-        for (i, (pda, pos)) in positions.iter().enumerate() {
-            let label = pool_label(&pos.pool, &pool_map);
-            println!("  [{i:>2}]  Pair       {label}");
-            println!("        Position   {pda}");
-            println!("        Pool       {}", pos.pool);
-            println!("        LP shares  {:>20}", pos.lp_shares);
-            println!("        Auto-cmpnd {}{}",
-                if pos.auto_compound { "enabled" } else { "disabled" },
-                if pos.auto_compound && pos.compound_threshold > 0 {
-                    format!("  (threshold: {})", pos.compound_threshold)
-                } else { String::new() });
-            println!();
-        }
-        println!("  Total: {} position(s)  ·  run `my-fees` to see claimable balances", positions.len());
+        let onoff = |on: bool| if on { "enabled" } else { "disabled" };
+        println!("─── Position Updated ─────────────────────────────────────────────");
+        println!("  Pair             {pair}");
+        println!("  Position         {position_pda}");
+        println!("  Auto-compound    {}  (was {})", onoff(auto_compound), onoff(pos.auto_compound));
+        println!("  Threshold        {threshold}  (was {})", pos.compound_threshold);
+        println!("  Transaction      {sig}");
     }
     Ok(())
 }
 
-// ─── pool-info ────────────────────────────────────────────────────────────────
-
-fn cmd_pool_info(rpc_url: &str, pair: &str, json_output: bool) -> Result<()> {
-    let (sym_a, sym_b, mint_a, mint_b) = parse_pair(pair)?;
+fn cmd_position_close(
+    rpc_url: &str,
+    keypair_path: &str,
+    pair: &str,
+    json_output: bool,
+) -> Result<()> {
+    let payer      = load_keypair(keypair_path)?;
     let program_id = Pubkey::from_str(PROGRAM_ID)?;
     let client     = rpc(rpc_url);
 
-    let (pool_pda, _) = Pubkey::find_program_address(
-        &[POOL_SEED, mint_a.as_ref(), mint_b.as_ref()], &program_id);
+    let (pool_pda, _, pool, _, _) = find_pool_by_pair(&client, pair, &program_id)?;
 
-    let pool_acct = client.get_account(&pool_pda)
+    let (position_pda, _) = Pubkey::find_program_address(
+        &[POSITION_SEED, pool_pda.as_ref(), payer.pubkey().as_ref()],
+        &program_id,
+    );
+
+    let pos_acct = client.get_account(&position_pda)
         .with_context(|| format!(
-            "Pool not found for '{}'. Run `a2a-swap create-pool --pair {}` first.",
-            pair, pair
+            "No position found for this keypair in pool '{pair}'.\n  \
+             Run `a2a-swap my-positions` to see your LP positions."
         ))?;
-    let pool = parse_pool(&pool_acct.data)?;
-
-    let ra = parse_token_amount(&client.get_account(&pool.token_a_vault)?.data)?;
-    let rb = parse_token_amount(&client.get_account(&pool.token_b_vault)?.data)?;
-
-    let spot_price: f64 = if ra > 0 { rb as f64 / ra as f64 } else { 0.0 };
+    let pos = parse_position(&pos_acct.data)?;
+    if pos.lp_shares > 0 {
+        return Err(anyhow!(
+            "Position still holds {} LP shares.\n  \
+             Run `a2a-swap remove-liquidity --pair {pair} --shares {} --close-if-empty` to exit and close it.",
+            pos.lp_shares, pos.lp_shares
+        ));
+    }
+    if pos.fees_owed_a > 0 || pos.fees_owed_b > 0 {
+        return Err(anyhow!(
+            "Position has unclaimed fees (a={} b={}).\n  \
+             Run `a2a-swap claim-fees --pair {pair}` first.",
+            pos.fees_owed_a, pos.fees_owed_b
+        ));
+    }
 
+    let mut ixs = migration_ixs(&payer.pubkey(), &pool_pda, &pool, Some((&position_pda, &pos)))?;
+    ixs.push(close_position_ix(&payer.pubkey(), &position_pda, &pool_pda)?);
+    let sig = sign_and_send(&client, &ixs, &payer, &[&payer])
+        .context("close_position transaction failed")?;
+
+    let summary = json!({
+        "status":   "ok",
+        "command":  "position-close",
+        "pair":     pair,
+        "pool":     pool_pda.to_string(),
+        "position": position_pda.to_string(),
+        "rent":     pos_acct.lamports,
+        "tx":       sig.to_string(),
+    });
+    notify::record(&summary);
     if json_output {
-        println!("{}", json!({
-            "status":  "ok",
-            "command": "pool-info",
-            "pair":    pair,
-            "pool":    pool_pda.to_string(),
-            "token_a": {
-                "symbol": sym_a, "mint": mint_a.to_string(),
-                "vault":  pool.token_a_vault.to_string(), "reserve": ra,
-            },
-            "token_b": {
-                "symbol": sym_b, "mint": mint_b.to_string(),
-                "vault":  pool.token_b_vault.to_string(), "reserve": rb,
-            },
-            "lp_supply":          pool.lp_supply,
-            "fee_rate_bps":       pool.fee_rate_bps,
-            "fee_rate_pct":       pool.fee_rate_bps as f64 / 100.0,
-            "spot_price_b_per_a": spot_price,
-        }));
+        println!("{summary}");
     } else {
-        println!("─── Pool Info: {pair} ──────────────────────────────────────────────");
-        println!("  Pool             {pool_pda}");
-        println!();
-        println!("  Token A          {sym_a}  ({mint_a})");
-        println!("  Vault A          {}", pool.token_a_vault);
-        println!("  Reserve A        {:>20}", ra);
-        println!();
-        println!("  Token B          {sym_b}  ({mint_b})");
-        println!("  Vault B          {}", pool.token_b_vault);
-        println!("  Reserve B        {:>20}", rb);
-        println!();
-        println!("  LP supply        {:>20}", pool.lp_supply);
-        println!("  Fee rate         {} bps  ({:.2}% per swap)",
-                 pool.fee_rate_bps, pool.fee_rate_bps as f64 / 100.0);
-        if ra > 0 {
-            println!("  Spot price       {spot_price:.8}  {sym_b}/{sym_a}  (raw atomic units)");
-        } else {
-            println!("  Spot price       — (pool is empty, no liquidity)");
-        }
+        println!("─── Position Closed ──────────────────────────────────────────────");
+        println!("  Pair             {pair}");
+        println!("  Position         {position_pda}");
+        println!("  Rent returned    {} lamports", pos_acct.lamports);
+        println!("  Transaction      {sig}");
     }
     Ok(())
 }
 
-// ─── active-pools ─────────────────────────────────────────────────────────────
+// ─── close-pool ──────────────────────────────────────────────────────────────
 
-fn cmd_active_pools(rpc_url: &str, json_output: bool) -> Result<()> {
+fn cmd_close_pool(
+    rpc_url: &str,
+    keypair_path: &str,
+    pair: &str,
+    json_output: bool,
+) -> Result<()> {
+    let payer      = load_keypair(keypair_path)?;
     let program_id = Pubkey::from_str(PROGRAM_ID)?;
     let client     = rpc(rpc_url);
-    let disc       = anchor_disc("account", "Pool");
-
-    let config = RpcProgramAccountsConfig {
-        filters: Some(vec![
-            RpcFilterType::DataSize(212),
-            RpcFilterType::Memcmp(Memcmp::new(0, MemcmpEncodedBytes::Bytes(disc.to_vec()))),
-        ]),
-        account_config: RpcAccountInfoConfig {
-            encoding: Some(UiAccountEncoding::Base64),
-            ..RpcAccountInfoConfig::default()
-        },
-        ..RpcProgramAccountsConfig::default()
-    };
-
-    let raw = client
-        .get_program_accounts_with_config(&program_id, config)
-        .context("getProgramAccounts failed — set --rpc to a Helius or private RPC endpoint")?;
 
-    if raw.is_empty() {
-        if json_output {
-            println!("{}", json!({ "status": "ok", "command": "active-pools", "count": 0, "pools": [] }));
-        } else {
-            println!("No pools found.");
-        }
-        return Ok(());
+    let (pool_pda, pool_auth, pool, _, _) = find_pool_by_pair(&client, pair, &program_id)?;
+    if pool.lp_supply > 0 || pool.reserve_a > 0 || pool.reserve_b > 0 {
+        return Err(anyhow!(
+            "Pool is not empty: {} LP shares, reserves a={} b={}.\n  \
+             Every LP must remove-liquidity first.",
+            pool.lp_supply, pool.reserve_a, pool.reserve_b
+        ));
     }
-
-    struct PoolEntry {
-        pubkey: Pubkey,
-        pool:   PoolState,
-        ra:     u64,
-        rb:     u64,
+    if pool.version < POOL_OPEN_POSITIONS_VERSION || pool.open_positions == u64::MAX {
+        return Err(anyhow!(
+            "Pool predates position counting, so it can't be closed: positions \
+             opened before then may still be owed fees."
+        ));
     }
-
-    let mut entries: Vec<PoolEntry> = Vec::with_capacity(raw.len());
-    for (pk, acct) in &raw {
-        match parse_pool(&acct.data) {
-            Ok(pool) => {
-                let ra = client.get_account(&pool.token_a_vault)
-                    .ok()
-                    .and_then(|a| parse_token_amount(&a.data).ok())
-                    .unwrap_or(0);
-                let rb = client.get_account(&pool.token_b_vault)
-                    .ok()
-                    .and_then(|a| parse_token_amount(&a.data).ok())
-                    .unwrap_or(0);
-                entries.push(PoolEntry { pubkey: *pk, pool, ra, rb });
-            }
-            Err(e) => eprintln!("Warning: skipping malformed pool {pk}: {e}"),
-        }
+    if pool.open_positions > 0 {
+        return Err(anyhow!(
+            "Pool still has {} open position(s).\n  \
+             Every LP must claim-fees and `position close` first.",
+            pool.open_positions
+        ));
     }
-
-    if json_output {
-        let arr: Vec<_> = entries.iter().map(|e| {
-            let spot: f64 = if e.ra > 0 { e.rb as f64 / e.ra as f64 } else { 0.0 };
-            json!({
-                "pool":                e.pubkey.to_string(),
-                "token_a": {
-                    "symbol": resolve_symbol(&e.pool.token_a_mint),
-                    "mint":   e.pool.token_a_mint.to_string(),
-                    "vault":  e.pool.token_a_vault.to_string(),
-                    "reserve": e.ra,
-                },
-                "token_b": {
-                    "symbol": resolve_symbol(&e.pool.token_b_mint),
-                    "mint":   e.pool.token_b_mint.to_string(),
-                    "vault":  e.pool.token_b_vault.to_string(),
-                    "reserve": e.rb,
-                },
-                "lp_supply":          e.pool.lp_supply,
-                "fee_rate_bps":        e.pool.fee_rate_bps,
-                "fee_rate_pct":        e.pool.fee_rate_bps as f64 / 100.0,
-                "spot_price_b_per_a":  spot,
-            })
-        }).collect();
-        println!("{}", json!({ "status": "ok", "command": "active-pools", "count": arr.len(), "pools": arr }));
+    // Anything left in the vaults is untracked dust, swept to our ATAs.
+    let dust_a = parse_token_amount(&client.get_account(&pool.token_a_vault)?.data)?;
+    let dust_b = parse_token_amount(&client.get_account(&pool.token_b_vault)?.data)?;
+    let closer_token_a = derive_ata(&payer.pubkey(), &pool.token_a_mint);
+    let closer_token_b = derive_ata(&payer.pubkey(), &pool.token_b_mint);
+
+    // The creator closes on their own authority; anyone else needs to be the
+    // protocol admin, which the program checks against the config.
+    let as_admin = pool.creator != payer.pubkey();
+    let protocol_config = if as_admin {
+        Pubkey::find_program_address(&[TREASURY_SEED], &program_id).0
+    } else {
+        program_id
+    };
+    let rent: u64 = [pool_pda, pool.token_a_vault, pool.token_b_vault]
+        .iter()
+        .map(|k| client.get_balance(k).unwrap_or(0))
+        .sum();
+
+    let mut ixs = migration_ixs(&payer.pubkey(), &pool_pda, &pool, None)?;
+    ixs.push(create_ata_idempotent_ix(&payer.pubkey(), &closer_token_a, &payer.pubkey(), &pool.token_a_mint)?);
+    ixs.push(create_ata_idempotent_ix(&payer.pubkey(), &closer_token_b, &payer.pubkey(), &pool.token_b_mint)?);
+    ixs.push(Instruction {
+        program_id,
+        data: ix::ClosePool.data(),
+        accounts: vec![
+            AccountMeta::new(payer.pubkey(),                true),
+            AccountMeta::new(pool_pda,                      false),
+            AccountMeta::new_readonly(pool_auth,            false),
+            AccountMeta::new(pool.token_a_vault,            false),
+            AccountMeta::new(pool.token_b_vault,            false),
+            AccountMeta::new(closer_token_a,                false),
+            AccountMeta::new(closer_token_b,                false),
+            AccountMeta::new_readonly(protocol_config,      false),
+            AccountMeta::new_readonly(Pubkey::from_str(TOKEN_PROGRAM_ID)?, false),
+        ],
+    });
+    let sig = sign_and_send(&client, &ixs, &payer, &[&payer])
+        .context("close_pool transaction failed")?;
+
+    let summary = json!({
+        "status":   "ok",
+        "command":  "close-pool",
+        "pair":     pair,
+        "pool":     pool_pda.to_string(),
+        "as_admin": as_admin,
+        "rent":     rent,
+        "dust_a":   dust_a,
+        "dust_b":   dust_b,
+        "tx":       sig.to_string(),
+    });
+    notify::record(&summary);
+    if json_output {
+        println!("{summary}");
     } else {
-        println!("─── Active Pools ({}) ──────────────────────────────────────────────", entries.len());
-        for (i, e) in entries.iter().enumerate() {
-            let sym_a  = resolve_symbol(&e.pool.token_a_mint);
-            let sym_b  = resolve_symbol(&e.pool.token_b_mint);
-            let spot: f64 = if e.ra > 0 { e.rb as f64 / e.ra as f64 } else { 0.0 };
-            println!();
-            println!("  [{}] {}  {}  {}", i + 1, e.pubkey, sym_a, sym_b);
-            println!("      Token A       {} ({})", sym_a, e.pool.token_a_mint);
-            println!("      Token B       {} ({})", sym_b, e.pool.token_b_mint);
-            println!("      Reserve A     {:>20}", e.ra);
-            println!("      Reserve B     {:>20}", e.rb);
-            println!("      LP supply     {:>20}", e.pool.lp_supply);
-            println!("      Fee rate      {} bps  ({:.2}%)", e.pool.fee_rate_bps, e.pool.fee_rate_bps as f64 / 100.0);
-            if e.ra > 0 {
-                println!("      Spot price    {spot:.8}  {sym_b}/{sym_a}");
-            } else {
-                println!("      Spot price    — (pool empty)");
-            }
+        println!("─── Pool Closed ──────────────────────────────────────────────────");
+        println!("  Pair             {pair}");
+        println!("  Pool             {pool_pda}");
+        println!("  Closed as        {}", if as_admin { "protocol admin" } else { "creator" });
+        println!("  Rent returned    {rent} lamports");
+        if dust_a > 0 || dust_b > 0 {
+            println!("  Dust swept       a={dust_a} b={dust_b}");
         }
+        println!("  Transaction      {sig}");
     }
     Ok(())
 }
 
-// ─── my-fees ──────────────────────────────────────────────────────────────────
+// ─── allowlist ───────────────────────────────────────────────────────────────
 
-fn cmd_my_fees(rpc_url: &str, keypair_path: &str, json_output: bool) -> Result<()> {
-    let payer      = load_keypair(keypair_path)?;
-    let program_id = Pubkey::from_str(PROGRAM_ID)?;
-    let client     = rpc(rpc_url);
+/// Read a traders file: one wallet per line (blank lines and `#` comments
+/// skipped), or a JSON array of wallet strings.
+fn read_traders(path: &str) -> Result<Vec<Pubkey>> {
+    let text = std::fs::read_to_string(path).with_context(|| format!("Cannot read {path}"))?;
+    let entries: Vec<String> = if text.trim_start().starts_with('[') {
+        serde_json::from_str(&text).with_context(|| format!("{path} is not a JSON array of wallets"))?
+    } else {
+        text.lines()
+            .map(|l| l.split('#').next().unwrap_or("").trim().to_string())
+            .filter(|l| !l.is_empty())
+            .collect()
+    };
+    entries
+        .iter()
+        .map(|t| Pubkey::from_str(t).map_err(|_| input_error!("'{t}' in {path} is not a valid public key.")))
+        .collect()
+}
 
-    let positions = get_agent_positions(&client, &payer.pubkey(), &program_id)?;
+fn hex32(bytes: &[u8; 32]) -> String {
+    bytes.iter().map(|b| format!("{b:02x}")).collect()
+}
 
-    if positions.is_empty() {
-        if json_output {
-            println!("{}", json!({
-                "status": "ok", "command": "my-fees",
-                "agent": payer.pubkey().to_string(),
-                "fees": [], "total_fees_a": 0, "total_fees_b": 0,
-            }));
-        } else {
-            println!("─── My Fees ──────────────────────────────────────────────────────");
-            println!("  Agent   {}", payer.pubkey());
-            println!();
-            println!("  No LP positions found — no fees to show.");
-            println!("  Run `a2a-swap provide --pair <PAIR> --amount <AMT>` to earn LP fees.");
-        }
-        return Ok(());
+fn parse_hex32(s: &str) -> Result<[u8; 32]> {
+    let s = s.trim();
+    let bad = || input_error!("'{s}' is not a 32-byte hex hash.");
+    if s.len() != 64 || !s.is_ascii() {
+        return Err(bad());
     }
+    let mut out = [0u8; 32];
+    for (i, byte) in out.iter_mut().enumerate() {
+        *byte = u8::from_str_radix(&s[2 * i..2 * i + 2], 16).map_err(|_| bad())?;
+    }
+    Ok(out)
+}
 
-    let pool_keys = dedup_pool_keys(&positions);
-    let pool_map  = fetch_pool_map(&client, &pool_keys);
+/// Fail with the fix if `trader` has no pass for `pool`'s current allowlist.
+fn require_trader_pass(
+    client: &RpcClient,
+    pool_pda: &Pubkey,
+    pool: &PoolState,
+    trader: &Pubkey,
+    program_id: &Pubkey,
+    pair: &str,
+) -> Result<()> {
+    let (pass, _) = sdk_ix::derive_trader_pass(pool_pda, trader, program_id);
+    let registered = client
+        .get_account_with_commitment(&pass, client.commitment())?
+        .value
+        .map(|a| parse_trader_pass(&a.data))
+        .transpose()?;
+    match registered {
+        Some(p) if p.root == pool.allowlist_root => Ok(()),
+        Some(_) => Err(anyhow!(
+            "Pool '{pair}' changed its allowlist since this keypair registered.\n  \
+             Get a new proof from the pool creator and run `a2a-swap allowlist register`."
+        )),
+        None => Err(anyhow!(
+            "Pool '{pair}' is permissioned and this keypair has not registered.\n  \
+             Get a proof from the pool creator and run `a2a-swap allowlist register`."
+        )),
+    }
+}
 
-    struct Row { position: Pubkey, pool: Pubkey, label: String, fa: u64, fb: u64 }
+fn cmd_allowlist_set(
+    rpc_url: &str,
+    keypair_path: &str,
+    pair: &str,
+    traders: Option<&str>,
+    json_output: bool,
+) -> Result<()> {
+    let tree = traders
+        .map(|path| -> Result<AllowlistTree> { Ok(AllowlistTree::new(&read_traders(path)?)?) })
+        .transpose()?;
+    let root = tree.as_ref().map_or([0; 32], AllowlistTree::root);
 
-    let mut rows: Vec<Row> = Vec::new();
-    let mut total_a: u64 = 0;
-    let mut total_b: u64 = 0;
+    let payer      = load_keypair(keypair_path)?;
+    let program_id = Pubkey::from_str(PROGRAM_ID)?;
+    let client     = rpc(rpc_url);
 
-    for (pda, pos) in &positions {
-        let (fa, fb) = pool_map.get(&pos.pool)
-            .map(|ps| pending_fees(pos, ps))
-            .unwrap_or((pos.fees_owed_a, pos.fees_owed_b));
-        total_a = total_a.saturating_add(fa);
-        total_b = total_b.saturating_add(fb);
-        rows.push(Row {
-            position: *pda,
-            pool:     pos.pool,
-            label:    pool_label(&pos.pool, &pool_map),
-            fa, fb,
-        });
+    let (pool_pda, _, pool, _, _) = find_pool_by_pair(&client, pair, &program_id)?;
+    if pool.creator != payer.pubkey() {
+        return Err(anyhow!(
+            "Only the pool's creator may change its allowlist (creator: {}).",
+            pool.creator
+        ));
     }
 
+    let mut ixs = migration_ixs(&payer.pubkey(), &pool_pda, &pool, None)?;
+    ixs.push(sdk_ix::set_pool_allowlist_ix(&program_id, &payer.pubkey(), &pool_pda, root));
+    let sig = sign_and_send(&client, &ixs, &payer, &[&payer])
+        .context("set_pool_allowlist transaction failed")?;
+
+    let command = if tree.is_some() { "allowlist-set" } else { "allowlist-clear" };
+    let summary = json!({
+        "status":  "ok",
+        "command": command,
+        "pair":    pair,
+        "pool":    pool_pda.to_string(),
+        "root":    hex32(&root),
+        "traders": tree.as_ref().map_or(0, AllowlistTree::len),
+        "tx":      sig.to_string(),
+    });
+    notify::record(&summary);
+    if json_output {
+        println!("{summary}");
+    } else if let Some(tree) = &tree {
+        println!("─── Allowlist Set ────────────────────────────────────────────────");
+        println!("  Pair             {pair}");
+        println!("  Pool             {pool_pda}");
+        println!("  Traders          {}", tree.len());
+        println!("  Root             {}", hex32(&root));
+        println!("  Transaction      {sig}");
+        println!();
+        println!("  Traders join with `a2a-swap allowlist register --pair {pair} --proof ...`;");
+        println!("  `a2a-swap allowlist proof` prints each one's proof.");
+    } else {
+        println!("─── Allowlist Cleared ────────────────────────────────────────────");
+        println!("  Pair             {pair}");
+        println!("  Pool             {pool_pda}");
+        println!("  Transaction      {sig}");
+    }
+    Ok(())
+}
+
+fn cmd_allowlist_proof(traders: &str, trader: &str, json_output: bool) -> Result<()> {
+    let trader = Pubkey::from_str(trader)
+        .map_err(|_| input_error!("--trader '{trader}' is not a valid public key."))?;
+    let tree  = AllowlistTree::new(&read_traders(traders)?)?;
+    let proof = tree
+        .proof(&trader)
+        .ok_or_else(|| input_error!("{trader} is not in {traders}."))?;
+    let hashes: Vec<String> = proof.iter().map(hex32).collect();
+
     if json_output {
-        let items: Vec<_> = rows.iter().map(|r| json!({
-            "position": r.position.to_string(),
-            "pool":     r.pool.to_string(),
-            "pair":     r.label,
-            "fees_a":   r.fa,
-            "fees_b":   r.fb,
-        })).collect();
         println!("{}", json!({
-            "status": "ok", "command": "my-fees",
-            "agent": payer.pubkey().to_string(),
-            "fees": items, "total_fees_a": total_a, "total_fees_b": total_b,
+            "status": "ok",
+            "trader": trader.to_string(),
+            "root":   hex32(&tree.root()),
+            "proof":  hashes,
         }));
     } else {
-        println!("─── My Fees ──────────────────────────────────────────────────────");
-        println!("  Agent   {}", payer.pubkey());
-        println!();
-        for (i, r) in rows.iter().enumerate() {
-            println!("  [{:>2}]  Pair       {}", i + 1, r.label);
-            println!("        Position   {}", r.position);
-            println!("        Pool       {}", r.pool);
-            println!("        Fees A     {:>20}  (token A, atomic units)", r.fa);
-            println!("        Fees B     {:>20}  (token B, atomic units)", r.fb);
-            println!();
-        }
-        println!("  ─── Totals ───────────────────────────────────────");
-        println!("  Total fees A     {:>20}  (across {} position(s))", total_a, rows.len());
-        println!("  Total fees B     {:>20}  (across {} position(s))", total_b, rows.len());
-        println!();
-        println!("  Includes pending fees accrued since last on-chain sync.");
-        println!("  Amounts are in atomic units (lamports, μUSDC, etc.).");
+        println!("─── Allowlist Proof ──────────────────────────────────────────────");
+        println!("  Trader           {trader}");
+        println!("  Root             {}", hex32(&tree.root()));
+        println!("  Proof            {}", if hashes.is_empty() { "(none: only trader)".into() } else { hashes.join(",") });
     }
     Ok(())
 }
 
-// ─── remove-liquidity ────────────────────────────────────────────────────────
-
-fn cmd_remove_liquidity(
+fn cmd_allowlist_register(
     rpc_url: &str,
     keypair_path: &str,
     pair: &str,
-    lp_shares: u64,
-    min_a: u64,
-    min_b: u64,
+    proof: Option<&str>,
+    traders: Option<&str>,
     json_output: bool,
 ) -> Result<()> {
-    if lp_shares == 0 {
+    let payer      = load_keypair(keypair_path)?;
+    let program_id = Pubkey::from_str(PROGRAM_ID)?;
+    let client     = rpc(rpc_url);
+
+    let proof = match (proof, traders) {
+        (_, Some(path)) => AllowlistTree::new(&read_traders(path)?)?
+            .proof(&payer.pubkey())
+            .ok_or_else(|| input_error!("{} is not in {path}.", payer.pubkey()))?,
+        (Some(hashes), None) => hashes
+            .split(',')
+            .filter(|h| !h.trim().is_empty())
+            .map(parse_hex32)
+            .collect::<Result<_>>()?,
+        (None, None) => return Err(input_error!("Give --proof or --traders.")),
+    };
+
+    let (pool_pda, _, pool, _, _) = find_pool_by_pair(&client, pair, &program_id)?;
+    if !pool.is_permissioned() {
+        return Err(input_error!("Pool '{pair}' has no allowlist; anyone may swap there."));
+    }
+    if !verify_allowlist_proof(&pool.allowlist_root, &payer.pubkey(), &proof) {
         return Err(anyhow!(
-            "--shares must be > 0 (run `a2a-swap my-positions` to see your LP share balance)."
+            "Proof does not match pool '{pair}''s allowlist for {}.\n  \
+             Ask the pool creator for a proof from their current list.",
+            payer.pubkey()
+        ));
+    }
+
+    let (pass, _) = sdk_ix::derive_trader_pass(&pool_pda, &payer.pubkey(), &program_id);
+    let ix = sdk_ix::register_trader_ix(&program_id, &payer.pubkey(), &pool_pda, proof);
+    let sig = sign_and_send(&client, &[ix], &payer, &[&payer])
+        .context("register_trader transaction failed")?;
+
+    let summary = json!({
+        "status":      "ok",
+        "command":     "allowlist-register",
+        "pair":        pair,
+        "pool":        pool_pda.to_string(),
+        "trader_pass": pass.to_string(),
+        "tx":          sig.to_string(),
+    });
+    notify::record(&summary);
+    if json_output {
+        println!("{summary}");
+    } else {
+        println!("─── Trader Registered ────────────────────────────────────────────");
+        println!("  Pair             {pair}");
+        println!("  Pool             {pool_pda}");
+        println!("  Trader pass      {pass}");
+        println!("  Transaction      {sig}");
+    }
+    Ok(())
+}
+
+// ─── gate ────────────────────────────────────────────────────────────────────
+
+/// The trader's token account of `pool`'s gate mint, or fail with the fix if
+/// it holds none.
+fn require_gate_token(client: &RpcClient, pool: &PoolState, trader: &Pubkey, pair: &str) -> Result<Pubkey> {
+    let mint = client
+        .get_account_with_commitment(&pool.gate_mint, client.commitment())?
+        .value
+        .ok_or_else(|| anyhow!("Pool '{pair}' is gated by mint {}, which does not exist.", pool.gate_mint))?;
+    let gate_token = sdk_ix::derive_ata_for_program(trader, &pool.gate_mint, &mint.owner);
+    let held = client
+        .get_account_with_commitment(&gate_token, client.commitment())?
+        .value
+        .map(|a| parse_token_amount(&a.data))
+        .transpose()?
+        .unwrap_or(0);
+    if held == 0 {
+        return Err(anyhow!(
+            "Pool '{pair}' is token-gated and this keypair holds no {}.\n  \
+             Obtain the credential into its associated token account ({gate_token}) to swap here.",
+            pool.gate_mint
         ));
     }
+    Ok(gate_token)
+}
+
+fn cmd_gate_set(
+    rpc_url: &str,
+    keypair_path: &str,
+    pair: &str,
+    mint: Option<&str>,
+    json_output: bool,
+) -> Result<()> {
+    let gate_mint = mint
+        .map(|m| Pubkey::from_str(m).map_err(|_| input_error!("--mint '{m}' is not a valid public key.")))
+        .transpose()?;
 
     let payer      = load_keypair(keypair_path)?;
     let program_id = Pubkey::from_str(PROGRAM_ID)?;
     let client     = rpc(rpc_url);
 
-    let (pool_pda, pool_auth, pool, mint_a, mint_b) =
-        find_pool_by_pair(&client, pair, &program_id)?;
+    let (pool_pda, _, pool, _, _) = find_pool_by_pair(&client, pair, &program_id)?;
+    if pool.creator != payer.pubkey() {
+        return Err(anyhow!(
+            "Only the pool's creator may change its gate (creator: {}).",
+            pool.creator
+        ));
+    }
 
-    let (position_pda, _) = Pubkey::find_program_address(
-        &[POSITION_SEED, pool_pda.as_ref(), payer.pubkey().as_ref()],
-        &program_id,
-    );
+    let mut ixs = migration_ixs(&payer.pubkey(), &pool_pda, &pool, None)?;
+    ixs.push(sdk_ix::set_pool_gate_ix(&program_id, &payer.pubkey(), &pool_pda, &gate_mint.unwrap_or_default()));
+    let sig = sign_and_send(&client, &ixs, &payer, &[&payer])
+        .context("set_pool_gate transaction failed")?;
+
+    let command = if gate_mint.is_some() { "gate-set" } else { "gate-clear" };
+    let summary = json!({
+        "status":    "ok",
+        "command":   command,
+        "pair":      pair,
+        "pool":      pool_pda.to_string(),
+        "gate_mint": gate_mint.map(|m| m.to_string()),
+        "tx":        sig.to_string(),
+    });
+    notify::record(&summary);
+    if json_output {
+        println!("{summary}");
+    } else if let Some(gate_mint) = gate_mint {
+        println!("─── Gate Set ─────────────────────────────────────────────────────");
+        println!("  Pair             {pair}");
+        println!("  Pool             {pool_pda}");
+        println!("  Gate mint        {gate_mint}");
+        println!("  Transaction      {sig}");
+        println!();
+        println!("  Only wallets holding {gate_mint} may swap on this pool now.");
+    } else {
+        println!("─── Gate Cleared ─────────────────────────────────────────────────");
+        println!("  Pair             {pair}");
+        println!("  Pool             {pool_pda}");
+        println!("  Transaction      {sig}");
+    }
+    Ok(())
+}
 
-    // Verify position exists and has enough shares
-    let pos_acct = client.get_account(&position_pda)
-        .with_context(|| format!(
-            "No position found for this keypair in pool '{pair}'.\n  \
-             Run `a2a-swap my-positions` to see your LP positions."
+// ─── governance ───────────────────────────────────────────────────────────────
+
+/// The program's `Governance` account.
+fn fetch_governance(client: &RpcClient, program_id: &Pubkey) -> Result<GovernanceState> {
+    let (governance, _) = sdk_ix::derive_governance(program_id);
+    let account = client
+        .get_account_with_commitment(&governance, client.commitment())
+        .context("fetch governance")?
+        .value
+        .ok_or_else(|| anyhow!(
+            "Governance is not initialized.\n  \
+             The protocol admin runs `a2a-swap governance init` to hand the config to token holders."
         ))?;
-    let pos = parse_position(&pos_acct.data)?;
-    if pos.lp_shares < lp_shares {
-        return Err(anyhow!(
-            "Requested {} LP shares but position only holds {}.\n  \
-             Run `a2a-swap my-positions` to see your current balance.",
-            lp_shares, pos.lp_shares
-        ));
+    Ok(a2a_swap_sdk::state::parse_governance(&account.data)?)
+}
+
+/// `migrate_protocol_config` if the treasury's config predates the pause
+/// switches, pool fee bounds or fee tiers.
+fn protocol_config_migration_ixs(client: &RpcClient, payer: &Pubkey, program_id: &Pubkey) -> Result<Vec<Instruction>> {
+    let (treasury, _) = Pubkey::find_program_address(&[TREASURY_SEED], program_id);
+    let account = client
+        .get_account_with_commitment(&treasury, client.commitment())
+        .context("fetch protocol config")?
+        .value
+        .ok_or_else(|| anyhow!("The protocol config has not been initialized."))?;
+    Ok(if account.data.len() < a2a_swap_sdk::state::PROTOCOL_CONFIG_LEN {
+        vec![sdk_ix::migrate_protocol_config_ix(program_id, payer)]
+    } else {
+        Vec::new()
+    })
+}
+
+/// The action `governance propose` flags describe; exactly one kind.
+fn governance_action(
+    protocol_fee:    Option<u16>,
+    referral_share:  Option<u16>,
+    pool_fee_bounds: Option<&str>,
+    pause:           Option<&str>,
+    fee_tier:        Option<&str>,
+    locker:          Option<&str>,
+) -> Result<GovernanceAction> {
+    match (protocol_fee, referral_share, pool_fee_bounds, pause, fee_tier, locker) {
+        (Some(protocol_fee_bps), Some(referral_share_bps), None, None, None, None) => {
+            Ok(GovernanceAction::SetProtocolFee { protocol_fee_bps, referral_share_bps })
+        }
+        (None, None, Some(bounds), None, None, None) => {
+            let (min, max) = bounds.split_once('-')
+                .ok_or_else(|| input_error!("--pool-fee-bounds `{bounds}`: expected MIN-MAX, e.g. 5-300"))?;
+            Ok(GovernanceAction::SetPoolFeeBounds {
+                min_fee_bps: min.trim().parse().context("--pool-fee-bounds minimum")?,
+                max_fee_bps: max.trim().parse().context("--pool-fee-bounds maximum")?,
+            })
+        }
+        (None, None, None, Some(pause), None, None) => Ok(GovernanceAction::SetPaused {
+            paused: match pause {
+                "swaps"         => PAUSE_SWAPS,
+                "pool-creation" => PAUSE_POOL_CREATION,
+                "all"           => PAUSE_SWAPS | PAUSE_POOL_CREATION,
+                _               => 0,
+            },
+        }),
+        (None, None, None, None, Some(tier), None) => {
+            let parts: Vec<&str> = tier.split(':').map(str::trim).collect();
+            let [index, min_volume, discount] = parts[..] else {
+                return Err(input_error!(
+                    "--fee-tier `{tier}`: expected INDEX:MIN_VOLUME:DISCOUNT_BPS, e.g. 0:1000000000000:2500"
+                ));
+            };
+            Ok(GovernanceAction::SetFeeTier {
+                index:        index.parse().context("--fee-tier index")?,
+                min_volume:   min_volume.parse().context("--fee-tier minimum volume")?,
+                discount_bps: discount.parse().context("--fee-tier discount")?,
+            })
+        }
+        (None, None, None, None, None, Some(locker)) => {
+            let (index, program) = locker.split_once(':')
+                .ok_or_else(|| input_error!("--locker `{locker}`: expected INDEX:PROGRAM"))?;
+            Ok(GovernanceAction::SetLocker {
+                index:   index.trim().parse().context("--locker index")?,
+                program: Pubkey::from_str(program.trim()).context("--locker program")?,
+            })
+        }
+        _ => Err(input_error!(
+            "Give exactly one of --protocol-fee (with --referral-share), --pool-fee-bounds, --pause, --fee-tier, \
+             --locker, or --pair with --dynamic-fee or --circuit-breaker."
+        )),
     }
+}
 
-    // Pre-compute expected amounts for display (mirrors on-chain math)
-    let reserve_a = parse_token_amount(&client.get_account(&pool.token_a_vault)?.data)?;
-    let reserve_b = parse_token_amount(&client.get_account(&pool.token_b_vault)?.data)?;
-    let expected_a = if pool.lp_supply > 0 {
-        (lp_shares as u128 * reserve_a as u128 / pool.lp_supply as u128) as u64
-    } else { 0 };
-    let expected_b = if pool.lp_supply > 0 {
-        (lp_shares as u128 * reserve_b as u128 / pool.lp_supply as u128) as u64
-    } else { 0 };
+/// The pool proposal `governance propose --pair` describes.
+fn pool_governance_action(
+    pool:            Pubkey,
+    dynamic_fee:     Option<&str>,
+    circuit_breaker: Option<u16>,
+) -> Result<GovernanceAction> {
+    match (dynamic_fee, circuit_breaker) {
+        (Some(bounds), None) => {
+            let (min, max) = bounds.split_once('-')
+                .ok_or_else(|| input_error!("--dynamic-fee `{bounds}`: expected MIN-MAX, e.g. 5-50"))?;
+            Ok(GovernanceAction::ConfigureDynamicFee {
+                pool,
+                min_fee_bps: min.trim().parse().context("--dynamic-fee minimum")?,
+                max_fee_bps: max.trim().parse().context("--dynamic-fee maximum")?,
+            })
+        }
+        (None, Some(max_move_bps)) => Ok(GovernanceAction::ConfigureCircuitBreaker { pool, max_move_bps }),
+        _ => Err(input_error!("--pair needs exactly one of --dynamic-fee or --circuit-breaker.")),
+    }
+}
 
-    let ata_a = derive_ata(&payer.pubkey(), &mint_a);
-    let ata_b = derive_ata(&payer.pubkey(), &mint_b);
+/// `closes in 5h` / `closed 2d ago` for a proposal's `voting_ends_at`.
+fn voting_window(voting_ends_at: i64) -> String {
+    let now = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs() as i64);
+    if now < voting_ends_at {
+        format!("closes in {}", format_age(voting_ends_at - now).trim_end_matches(" ago"))
+    } else {
+        format!("closed {}", format_age(now - voting_ends_at))
+    }
+}
+
+/// One-line description of a governance action.
+fn governance_action_label(action: &GovernanceAction) -> String {
+    match *action {
+        GovernanceAction::SetProtocolFee { protocol_fee_bps, referral_share_bps } => format!(
+            "protocol fee {}, referral share {:.2}%",
+            protocol_fee_pct(protocol_fee_bps),
+            referral_share_bps as f64 / 100.0
+        ),
+        GovernanceAction::SetPoolFeeBounds { min_fee_bps, max_fee_bps } => {
+            format!("pool fee bounds {min_fee_bps}–{max_fee_bps} bps")
+        }
+        GovernanceAction::SetPaused { paused } => {
+            let mut what = Vec::new();
+            if paused & PAUSE_SWAPS != 0 {
+                what.push("swaps");
+            }
+            if paused & PAUSE_POOL_CREATION != 0 {
+                what.push("pool creation");
+            }
+            if what.is_empty() { "unpause everything".to_string() } else { format!("pause {}", what.join(" + ")) }
+        }
+        GovernanceAction::SetFeeTier { index, min_volume, discount_bps: 0 } => {
+            format!("clear fee tier {index} (was from volume {min_volume})")
+        }
+        GovernanceAction::SetFeeTier { index, min_volume, discount_bps } => format!(
+            "fee tier {index}: {:.2}% off the protocol fee from volume {min_volume}",
+            discount_bps as f64 / 100.0
+        ),
+        GovernanceAction::SetLocker { index, program } if program == Pubkey::default() => {
+            format!("clear locker {index}")
+        }
+        GovernanceAction::SetLocker { index, program } => {
+            format!("locker {index}: {program} may lock positions as collateral")
+        }
+        GovernanceAction::ConfigureDynamicFee { pool, max_fee_bps: 0, .. } => {
+            format!("turn off pool {pool}'s dynamic fee")
+        }
+        GovernanceAction::ConfigureDynamicFee { pool, min_fee_bps, max_fee_bps } => {
+            format!("pool {pool}: dynamic fee {min_fee_bps}–{max_fee_bps} bps")
+        }
+        GovernanceAction::ConfigureCircuitBreaker { pool, max_move_bps: 0 } => {
+            format!("turn off pool {pool}'s circuit breaker")
+        }
+        GovernanceAction::ConfigureCircuitBreaker { pool, max_move_bps } => format!(
+            "pool {pool}: circuit breaker at {:.2}% price move per slot",
+            max_move_bps as f64 / 100.0
+        ),
+    }
+}
+
+fn cmd_governance_init(
+    rpc_url: &str,
+    keypair_path: &str,
+    vote_mint: &str,
+    quorum: u64,
+    voting_period: i64,
+    json_output: bool,
+) -> Result<()> {
+    if quorum == 0 || voting_period <= 0 {
+        return Err(input_error!("--quorum and --voting-period must be > 0"));
+    }
+    let vote_mint  = resolve_mint(vote_mint)?;
+    let payer      = load_keypair(keypair_path)?;
+    let program_id = Pubkey::from_str(PROGRAM_ID)?;
+    let client     = rpc(rpc_url);
 
-    let mut ix_data = anchor_disc("global", "remove_liquidity").to_vec();
-    ix_data.extend_from_slice(&lp_shares.to_le_bytes());
-    ix_data.extend_from_slice(&min_a.to_le_bytes());
-    ix_data.extend_from_slice(&min_b.to_le_bytes());
+    let mut ixs = protocol_config_migration_ixs(&client, &payer.pubkey(), &program_id)?;
+    ixs.push(sdk_ix::initialize_governance_ix(&program_id, &payer.pubkey(), &vote_mint, quorum, voting_period));
+    let sig = sign_and_send(&client, &ixs, &payer, &[&payer])
+        .context("initialize_governance transaction failed — is the keypair the protocol admin?")?;
+
+    let (governance, _) = sdk_ix::derive_governance(&program_id);
+    let summary = json!({
+        "status":        "ok",
+        "command":       "governance-init",
+        "governance":    governance.to_string(),
+        "vote_mint":     vote_mint.to_string(),
+        "quorum":        quorum,
+        "voting_period": voting_period,
+        "tx":            sig.to_string(),
+    });
+    notify::record(&summary);
+    if json_output {
+        println!("{summary}");
+    } else {
+        println!("─── Governance Initialized ───────────────────────────────────────");
+        println!("  Governance       {governance}");
+        println!("  Vote mint        {} ({vote_mint})", resolve_symbol(&vote_mint));
+        println!("  Quorum           {quorum}");
+        println!("  Voting period    {voting_period}s");
+        println!("  Transaction      {sig}");
+    }
+    Ok(())
+}
 
-    let ix = Instruction {
-        program_id,
-        data: ix_data,
-        accounts: vec![
-            AccountMeta::new(payer.pubkey(),          true),
-            AccountMeta::new(pool_pda,                false),
-            AccountMeta::new_readonly(pool_auth,      false),
-            AccountMeta::new(position_pda,            false),
-            AccountMeta::new(pool.token_a_vault,      false),
-            AccountMeta::new(pool.token_b_vault,      false),
-            AccountMeta::new(ata_a,                   false),
-            AccountMeta::new(ata_b,                   false),
-            AccountMeta::new_readonly(Pubkey::from_str(TOKEN_PROGRAM_ID)?, false),
-        ],
-    };
+fn cmd_governance_propose(
+    rpc_url: &str,
+    keypair_path: &str,
+    action: GovernanceAction,
+    json_output: bool,
+) -> Result<()> {
+    let payer      = load_keypair(keypair_path)?;
+    let program_id = Pubkey::from_str(PROGRAM_ID)?;
+    let client     = rpc(rpc_url);
 
+    let governance = fetch_governance(&client, &program_id)?;
+    let id = governance.proposal_count;
+    let (proposal, _) = sdk_ix::derive_proposal(id, &program_id);
+    let ix = sdk_ix::create_proposal_ix(&program_id, &payer.pubkey(), id, action);
     let sig = sign_and_send(&client, &[ix], &payer, &[&payer])
-        .context("remove_liquidity transaction failed")?;
+        .context("create_proposal transaction failed")?;
+
+    let summary = json!({
+        "status":        "ok",
+        "command":       "governance-propose",
+        "id":            id,
+        "proposal":      proposal.to_string(),
+        "action":        action,
+        "voting_period": governance.voting_period_secs,
+        "tx":            sig.to_string(),
+    });
+    notify::record(&summary);
+    if json_output {
+        println!("{summary}");
+    } else {
+        println!("─── Proposal Created ─────────────────────────────────────────────");
+        println!("  Id               {id}");
+        println!("  Proposal         {proposal}");
+        println!("  Action           {}", governance_action_label(&action));
+        println!("  Voting closes    in {}s", governance.voting_period_secs);
+        println!("  Transaction      {sig}");
+    }
+    Ok(())
+}
+
+fn cmd_governance_proposals(rpc_url: &str, json_output: bool) -> Result<()> {
+    let client = a2a_swap_sdk::A2ASwapClient::new(rpc_url)
+        .with_program_id(Pubkey::from_str(PROGRAM_ID)?);
+    let runtime   = tokio::runtime::Builder::new_current_thread().enable_all().build()?;
+    let proposals = runtime.block_on(client.proposals())?;
 
     if json_output {
         println!("{}", json!({
-            "status":     "ok",
-            "command":    "remove-liquidity",
-            "pair":       pair,
-            "pool":       pool_pda.to_string(),
-            "position":   position_pda.to_string(),
-            "lp_shares":  lp_shares,
-            "expected_a": expected_a,
-            "expected_b": expected_b,
-            "min_a":      min_a,
-            "min_b":      min_b,
-            "tx":         sig.to_string(),
+            "status":    "ok",
+            "command":   "governance-proposals",
+            "count":     proposals.len(),
+            "proposals": proposals,
         }));
-    } else {
-        println!("─── Liquidity Removed ────────────────────────────────────────────");
-        println!("  Pair             {pair}");
-        println!("  Pool             {pool_pda}");
-        println!("  Position         {position_pda}");
-        println!("  LP shares burnt  {:>20}", lp_shares);
-        println!("  Expected A       {:>20}  (token A, atomic units)", expected_a);
-        println!("  Expected B       {:>20}  (token B, atomic units)", expected_b);
-        if min_a > 0 || min_b > 0 {
-            println!("  Min A guard      {:>20}", min_a);
-            println!("  Min B guard      {:>20}", min_b);
-        }
-        println!("  Transaction      {sig}");
+        return Ok(());
+    }
+    if proposals.is_empty() {
+        println!("No proposals yet. Create one with `a2a-swap governance propose`.");
+        return Ok(());
+    }
+    println!("─── Proposals ({}) ────────────────────────────────────────────────", proposals.len());
+    for p in &proposals {
+        let status = match (p.executed, p.closed, p.passing) {
+            (true, _, _)         => "executed",
+            (false, true, true)  => "passed — ready to execute",
+            (false, true, false) => "rejected",
+            (false, false, true) => "open, passing",
+            (false, false, false) => "open, failing",
+        };
         println!();
-        println!("  Run `a2a-swap claim-fees --pair {pair}` to collect any accrued fees.");
+        println!("  [{}] {}", p.id, governance_action_label(&p.action));
+        println!("      Status        {status}");
+        println!("      For           {:>20}", p.votes_for);
+        println!("      Against       {:>20}", p.votes_against);
+        println!("      Voting        {}", voting_window(p.voting_ends_at));
+        println!("      Proposer      {}", p.proposer);
     }
     Ok(())
 }
 
-// ─── claim-fees ───────────────────────────────────────────────────────────────
-
-fn cmd_claim_fees(
+fn cmd_governance_vote(
     rpc_url: &str,
     keypair_path: &str,
-    pair: &str,
+    id: u64,
+    support: bool,
+    amount: u64,
     json_output: bool,
 ) -> Result<()> {
+    if amount == 0 {
+        return Err(input_error!("--amount must be > 0"));
+    }
     let payer      = load_keypair(keypair_path)?;
     let program_id = Pubkey::from_str(PROGRAM_ID)?;
     let client     = rpc(rpc_url);
 
-    let (pool_pda, pool_auth, pool, mint_a, mint_b) =
-        find_pool_by_pair(&client, pair, &program_id)?;
-
-    let (position_pda, _) = Pubkey::find_program_address(
-        &[POSITION_SEED, pool_pda.as_ref(), payer.pubkey().as_ref()],
-        &program_id,
-    );
-
-    let pos_acct = client.get_account(&position_pda)
-        .with_context(|| format!(
-            "No position found for this keypair in pool '{pair}'.\n  \
-             Run `a2a-swap my-positions` to see your LP positions."
-        ))?;
-    let pos = parse_position(&pos_acct.data)?;
+    let governance  = fetch_governance(&client, &program_id)?;
+    let voter_token = derive_ata(&payer.pubkey(), &governance.vote_mint);
+    let ix = sdk_ix::cast_vote_ix(&program_id, &payer.pubkey(), &voter_token, id, support, amount);
+    let sig = sign_and_send(&client, &[ix], &payer, &[&payer])
+        .context("cast_vote transaction failed — is voting still open, and is this your first vote on it?")?;
+
+    let summary = json!({
+        "status":  "ok",
+        "command": "governance-vote",
+        "id":      id,
+        "support": support,
+        "amount":  amount,
+        "tx":      sig.to_string(),
+    });
+    notify::record(&summary);
+    if json_output {
+        println!("{summary}");
+    } else {
+        println!("─── Vote Cast ────────────────────────────────────────────────────");
+        println!("  Proposal         {id}");
+        println!("  Vote             {}", if support { "for" } else { "against" });
+        println!("  Escrowed         {amount}");
+        println!("  Transaction      {sig}");
+        println!();
+        println!("  Reclaim the tokens after voting closes: a2a-swap governance withdraw --proposal {id}");
+    }
+    Ok(())
+}
 
-    // Pre-flight: compute fees so we can show them even if zero
-    let (fees_a, fees_b) = pending_fees(&pos, &pool);
+fn cmd_governance_execute(rpc_url: &str, keypair_path: &str, id: u64, json_output: bool) -> Result<()> {
+    let payer      = load_keypair(keypair_path)?;
+    let program_id = Pubkey::from_str(PROGRAM_ID)?;
+    let client     = rpc(rpc_url);
 
-    if fees_a == 0 && fees_b == 0 {
-        if json_output {
-            println!("{}", json!({
-                "status":   "ok",
-                "command":  "claim-fees",
-                "pair":     pair,
-                "pool":     pool_pda.to_string(),
-                "position": position_pda.to_string(),
-                "fees_a":   0,
-                "fees_b":   0,
-                "note":     "No fees to claim",
-            }));
-        } else {
-            println!("─── Claim Fees ───────────────────────────────────────────────────");
-            println!("  Pair       {pair}");
-            println!("  Position   {position_pda}");
-            println!();
-            println!("  No fees to claim for this position.");
-        }
-        return Ok(());
+    let governance = fetch_governance(&client, &program_id)?;
+    let (address, _) = sdk_ix::derive_proposal(id, &program_id);
+    let account = client.get_account(&address)
+        .with_context(|| format!("No proposal {id}. Run `a2a-swap governance proposals` to list them."))?;
+    let proposal = a2a_swap_sdk::state::parse_proposal(&account.data)?;
+    if proposal.executed {
+        return Err(anyhow!("Proposal {id} has already been executed."));
+    }
+    let now = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs() as i64);
+    if now < proposal.voting_ends_at {
+        return Err(anyhow!("Voting on proposal {id} is still open — it {}.", voting_window(proposal.voting_ends_at)));
+    }
+    if !proposal.passed(governance.quorum) {
+        return Err(anyhow!(
+            "Proposal {id} did not pass (for {} / against {}, quorum {}).",
+            proposal.votes_for, proposal.votes_against, governance.quorum
+        ));
     }
 
-    let ata_a = derive_ata(&payer.pubkey(), &mint_a);
-    let ata_b = derive_ata(&payer.pubkey(), &mint_b);
+    let mut ixs = protocol_config_migration_ixs(&client, &payer.pubkey(), &program_id)?;
+    ixs.push(sdk_ix::execute_proposal_ix(&program_id, id, proposal.action.pool().as_ref()));
+    let sig = sign_and_send(&client, &ixs, &payer, &[&payer])
+        .context("execute_proposal transaction failed")?;
+
+    let summary = json!({
+        "status":  "ok",
+        "command": "governance-execute",
+        "id":      id,
+        "action":  proposal.action,
+        "tx":      sig.to_string(),
+    });
+    notify::record(&summary);
+    if json_output {
+        println!("{summary}");
+    } else {
+        println!("─── Proposal Executed ────────────────────────────────────────────");
+        println!("  Proposal         {id}");
+        println!("  Action           {}", governance_action_label(&proposal.action));
+        println!("  Transaction      {sig}");
+    }
+    Ok(())
+}
 
-    let ix_data = anchor_disc("global", "claim_fees").to_vec();
+fn cmd_governance_withdraw(rpc_url: &str, keypair_path: &str, id: u64, json_output: bool) -> Result<()> {
+    let payer      = load_keypair(keypair_path)?;
+    let program_id = Pubkey::from_str(PROGRAM_ID)?;
+    let client     = rpc(rpc_url);
 
-    let ix = Instruction {
-        program_id,
-        data: ix_data,
-        accounts: vec![
-            AccountMeta::new(payer.pubkey(),          true),
-            AccountMeta::new(pool_pda,                false),
-            AccountMeta::new_readonly(pool_auth,      false),
-            AccountMeta::new(position_pda,            false),
-            AccountMeta::new(pool.token_a_vault,      false),
-            AccountMeta::new(pool.token_b_vault,      false),
-            AccountMeta::new(ata_a,                   false),
-            AccountMeta::new(ata_b,                   false),
-            AccountMeta::new_readonly(Pubkey::from_str(TOKEN_PROGRAM_ID)?, false),
-        ],
-    };
+    let governance  = fetch_governance(&client, &program_id)?;
+    let (proposal, _)    = sdk_ix::derive_proposal(id, &program_id);
+    let (vote_record, _) = sdk_ix::derive_vote_record(&proposal, &payer.pubkey(), &program_id);
+    let account = client.get_account(&vote_record)
+        .with_context(|| format!("No vote by this keypair on proposal {id}."))?;
+    let vote = a2a_swap_sdk::state::parse_vote_record(&account.data)?;
 
+    let voter_token = derive_ata(&payer.pubkey(), &governance.vote_mint);
+    let ix = sdk_ix::withdraw_vote_ix(&program_id, &payer.pubkey(), &voter_token, id);
     let sig = sign_and_send(&client, &[ix], &payer, &[&payer])
-        .context("claim_fees transaction failed")?;
-
+        .context("withdraw_vote transaction failed — has voting closed?")?;
+
+    let summary = json!({
+        "status":   "ok",
+        "command":  "governance-withdraw",
+        "id":       id,
+        "amount":   vote.amount,
+        "tx":       sig.to_string(),
+    });
+    notify::record(&summary);
     if json_output {
-        println!("{}", json!({
-            "status":        "ok",
-            "command":       "claim-fees",
-            "pair":          pair,
-            "pool":          pool_pda.to_string(),
-            "position":      position_pda.to_string(),
-            "fees_a":        fees_a,
-            "fees_b":        fees_b,
-            "auto_compound": pos.auto_compound,
-            "tx":            sig.to_string(),
-        }));
+        println!("{summary}");
     } else {
-        let mode = if pos.auto_compound {
-            "auto-compounded → LP shares"
-        } else {
-            "transferred to agent wallet"
-        };
-        println!("─── Fees Claimed ─────────────────────────────────────────────────");
-        println!("  Pair             {pair}");
-        println!("  Pool             {pool_pda}");
-        println!("  Position         {position_pda}");
-        println!("  Fees A           {:>20}  (token A, atomic units)", fees_a);
-        println!("  Fees B           {:>20}  (token B, atomic units)", fees_b);
-        println!("  Mode             {mode}");
+        println!("─── Vote Withdrawn ───────────────────────────────────────────────");
+        println!("  Proposal         {id}");
+        println!("  Returned         {}", vote.amount);
         println!("  Transaction      {sig}");
     }
     Ok(())
@@ -2057,6 +7559,7 @@ fn cmd_claim_fees(
 
 // ─── remove (ergonomic alias: --percentage or --amount) ──────────────────────
 
+#[allow(clippy::too_many_arguments)]
 fn cmd_remove(
     rpc_url: &str,
     keypair_path: &str,
@@ -2089,13 +7592,13 @@ fn cmd_remove(
     // Resolve LP shares from --percentage or --amount
     let lp_shares: u64 = if let Some(pct) = percentage {
         if pct <= 0.0 || pct > 100.0 {
-            return Err(anyhow!(
+            return Err(input_error!(
                 "--percentage must be between 0 (exclusive) and 100 (inclusive). Got: {pct}"
             ));
         }
         let shares = (pos.lp_shares as f64 * pct / 100.0).round() as u64;
         if shares == 0 {
-            return Err(anyhow!(
+            return Err(input_error!(
                 "Computed 0 shares from {pct}% of {} LP shares — nothing to remove.",
                 pos.lp_shares
             ));
@@ -2104,7 +7607,7 @@ fn cmd_remove(
     } else if let Some(amt) = amount {
         amt
     } else {
-        return Err(anyhow!(
+        return Err(input_error!(
             "Provide either --percentage <0-100> or --amount <LP_SHARES>.\n  \
              Example: a2a-swap remove --pair {pair} --percentage 100\n  \
              Example: a2a-swap remove --pair {pair} --amount 1000000"
@@ -2112,11 +7615,11 @@ fn cmd_remove(
     };
 
     if pos.lp_shares < lp_shares {
-        return Err(anyhow!(
+        return Err(CliError::InsufficientBalance(format!(
             "Requested {} LP shares but position only holds {}.\n  \
              Run `a2a-swap my-positions` to see your current balance.",
             lp_shares, pos.lp_shares
-        ));
+        )).into());
     }
 
     let pct_of_position = if pos.lp_shares > 0 {
@@ -2124,8 +7627,7 @@ fn cmd_remove(
     } else { 0.0 };
 
     // Pre-compute expected amounts (mirrors on-chain math)
-    let reserve_a = parse_token_amount(&client.get_account(&pool.token_a_vault)?.data)?;
-    let reserve_b = parse_token_amount(&client.get_account(&pool.token_b_vault)?.data)?;
+    let (reserve_a, reserve_b) = pool_reserves(&client, &pool)?;
     let expected_a = if pool.lp_supply > 0 {
         (lp_shares as u128 * reserve_a as u128 / pool.lp_supply as u128) as u64
     } else { 0 };
@@ -2136,12 +7638,9 @@ fn cmd_remove(
     let ata_a = derive_ata(&payer.pubkey(), &mint_a);
     let ata_b = derive_ata(&payer.pubkey(), &mint_b);
 
-    let mut ix_data = anchor_disc("global", "remove_liquidity").to_vec();
-    ix_data.extend_from_slice(&lp_shares.to_le_bytes());
-    ix_data.extend_from_slice(&min_a.to_le_bytes());
-    ix_data.extend_from_slice(&min_b.to_le_bytes());
+    let ix_data = ix::RemoveLiquidity { lp_shares, min_a, min_b }.data();
 
-    let ix = Instruction {
+    let mut ix = Instruction {
         program_id,
         data: ix_data,
         accounts: vec![
@@ -2156,25 +7655,36 @@ fn cmd_remove(
             AccountMeta::new_readonly(Pubkey::from_str(TOKEN_PROGRAM_ID)?, false),
         ],
     };
+    // A full exit burns the position's receipt NFT, if it has one.
+    let (receipt, metas) = receipt_accounts(&position_pda, &program_id)?;
+    let burn_receipt = lp_shares == pos.lp_shares && client.get_account(&receipt).is_ok();
+    if burn_receipt {
+        ix.accounts.extend(metas);
+    }
 
-    let sig = sign_and_send(&client, &[ix], &payer, &[&payer])
+    let mut ixs = migration_ixs(&payer.pubkey(), &pool_pda, &pool, Some((&position_pda, &pos)))?;
+    ixs.push(ix);
+    let sig = sign_and_send(&client, &ixs, &payer, &[&payer])
         .context("remove_liquidity transaction failed")?;
 
+    let summary = json!({
+        "status":          "ok",
+        "command":         "remove",
+        "pair":            pair,
+        "pool":            pool_pda.to_string(),
+        "position":        position_pda.to_string(),
+        "lp_shares":       lp_shares,
+        "pct_of_position": pct_of_position,
+        "expected_a":      expected_a,
+        "expected_b":      expected_b,
+        "min_a":           min_a,
+        "min_b":           min_b,
+        "receipt_burned":  burn_receipt.then(|| receipt.to_string()),
+        "tx":              sig.to_string(),
+    });
+    notify::record(&summary);
     if json_output {
-        println!("{}", json!({
-            "status":          "ok",
-            "command":         "remove",
-            "pair":            pair,
-            "pool":            pool_pda.to_string(),
-            "position":        position_pda.to_string(),
-            "lp_shares":       lp_shares,
-            "pct_of_position": pct_of_position,
-            "expected_a":      expected_a,
-            "expected_b":      expected_b,
-            "min_a":           min_a,
-            "min_b":           min_b,
-            "tx":              sig.to_string(),
-        }));
+        println!("{summary}");
     } else {
         println!("─── Liquidity Removed ────────────────────────────────────────────");
         println!("  Pair             {pair}");
@@ -2187,6 +7697,9 @@ fn cmd_remove(
             println!("  Min A guard      {:>20}", min_a);
             println!("  Min B guard      {:>20}", min_b);
         }
+        if burn_receipt {
+            println!("  Receipt burned   {receipt}");
+        }
         println!("  Transaction      {sig}");
         println!();
         println!("  Run `a2a-swap claim-fees --pair {pair}` to collect any accrued fees.");
@@ -2267,7 +7780,7 @@ fn cmd_claim_fees_all(
         let ata_a = derive_ata(&payer.pubkey(), &pool_state.token_a_mint);
         let ata_b = derive_ata(&payer.pubkey(), &pool_state.token_b_mint);
 
-        let ix_data = anchor_disc("global", "claim_fees").to_vec();
+        let ix_data = ix::ClaimFees.data();
         let ix = Instruction {
             program_id,
             data: ix_data,
@@ -2284,7 +7797,9 @@ fn cmd_claim_fees_all(
             ],
         };
 
-        match sign_and_send(&client, &[ix], &payer, &[&payer]) {
+        let mut ixs = migration_ixs(&payer.pubkey(), &pos.pool, pool_state, Some((position_pda, pos)))?;
+        ixs.push(ix);
+        match sign_and_send(&client, &ixs, &payer, &[&payer]) {
             Ok(sig) => {
                 total_a = total_a.saturating_add(fees_a);
                 total_b = total_b.saturating_add(fees_b);
@@ -2314,27 +7829,197 @@ fn cmd_claim_fees_all(
         }
     }
 
+    let summary = json!({
+        "status":       "ok",
+        "command":      "claim-fees",
+        "agent":        payer.pubkey().to_string(),
+        "claimed":      results,
+        "skipped":      skipped,
+        "total_fees_a": total_a,
+        "total_fees_b": total_b,
+    });
+    if !results.is_empty() {
+        notify::record(&summary);
+    }
+    if json_output {
+        println!("{summary}");
+    } else if results.is_empty() {
+        println!("  No fees to claim across {} position(s).", positions.len());
+    } else {
+        println!("  ─── Totals ───────────────────────────────────────");
+        println!("  Total fees A     {:>20}  ({} position(s) claimed)", total_a, results.len());
+        println!("  Total fees B     {:>20}  ({} position(s) claimed)", total_b, results.len());
+        if skipped > 0 {
+            println!("  Skipped          {skipped}  (no fees or error)");
+        }
+    }
+    Ok(())
+}
+
+// ─── approver ─────────────────────────────────────────────────────────────────
+
+fn cmd_approver_telegram(
+    rpc_url: &str,
+    keypair_path: &str,
+    signer: Option<&str>,
+    chat: i64,
+    allow: &[i64],
+    bot_token: &str,
+) -> Result<()> {
+    let approver: Box<dyn Signer> = match signer {
+        Some(uri) => Box::new(RemoteSigner::from_uri(uri).with_context(|| format!("--signer {uri}"))?),
+        None      => Box::new(load_keypair(keypair_path)?),
+    };
+    let program_id = Pubkey::from_str(PROGRAM_ID)?;
+    let bot        = telegram::Bot::new(bot_token)?;
+    telegram::serve(&rpc(rpc_url), &bot, chat, allow, approver.as_ref(), &program_id)
+}
+
+// ─── dev bootstrap ────────────────────────────────────────────────────────────
+
+#[allow(clippy::too_many_arguments)]
+fn cmd_dev_bootstrap(
+    rpc_url: &str,
+    keypair_path: &str,
+    decimals: u8,
+    mint_amount: u64,
+    amount_a: u64,
+    amount_b: u64,
+    fee_rate_bps: u16,
+    airdrop_sol: f64,
+    json_output: bool,
+) -> Result<()> {
+    if !is_local_rpc(rpc_url) {
+        return Err(input_error!(
+            "`dev bootstrap` only runs against a local validator (got {}).\n  \
+             Example: a2a-swap dev bootstrap --rpc-url http://127.0.0.1:8899",
+            rpc_url
+        ));
+    }
+    if !(1..=100).contains(&fee_rate_bps) {
+        return Err(input_error!(
+            "--fee-bps {} is out of range. Allowed: 1–100 (0.01%–1.00%).",
+            fee_rate_bps
+        ));
+    }
+    if amount_a == 0 || amount_b == 0 {
+        return Err(input_error!("--amount-a and --amount-b must be > 0"));
+    }
+    if amount_a > mint_amount || amount_b > mint_amount {
+        return Err(input_error!(
+            "Seed amounts exceed --mint-amount {} — raise --mint-amount or lower the seed.",
+            mint_amount
+        ));
+    }
+    if !(0.0..=1_000.0).contains(&airdrop_sol) {
+        return Err(input_error!("--airdrop must be between 0 and 1000 SOL"));
+    }
+
+    let payer      = load_keypair(keypair_path)?;
+    let owner      = payer.pubkey();
+    let program_id = Pubkey::from_str(PROGRAM_ID)?;
+    let client     = rpc(rpc_url);
+
+    // ── 1. Fund the keypair ───────────────────────────────────────────────────
+    let target  = (airdrop_sol * 1e9).round() as u64;
+    let balance = client.get_balance(&owner)
+        .context("Failed to reach the local validator — is solana-test-validator running?")?;
+    let airdrop_sig = if balance < target {
+        let sig = client.request_airdrop(&owner, target - balance)
+            .context("Airdrop failed")?;
+        client.poll_for_signature(&sig).context("Airdrop did not confirm")?;
+        Some(sig)
+    } else {
+        None
+    };
+
+    // ── 2. Test mints + funded ATAs ───────────────────────────────────────────
+    let mint_a = Keypair::new();
+    let mint_b = Keypair::new();
+    let ata_a  = derive_ata(&owner, &mint_a.pubkey());
+    let ata_b  = derive_ata(&owner, &mint_b.pubkey());
+    let rent   = client.get_minimum_balance_for_rent_exemption(MINT_LEN as usize)?;
+    let token_prog = Pubkey::from_str(TOKEN_PROGRAM_ID)?;
+
+    let mut ixs = Vec::with_capacity(8);
+    for (mint, ata) in [(&mint_a, &ata_a), (&mint_b, &ata_b)] {
+        ixs.push(create_account_ix(&owner, &mint.pubkey(), rent, MINT_LEN, &token_prog)?);
+        ixs.push(initialize_mint2_ix(&mint.pubkey(), &owner, decimals)?);
+        ixs.push(create_ata_idempotent_ix(&owner, ata, &owner, &mint.pubkey())?);
+        ixs.push(mint_to_ix(&mint.pubkey(), ata, &owner, mint_amount)?);
+    }
+    let mint_sig = sign_and_send(&client, &ixs, &payer, &[&payer, &mint_a, &mint_b])
+        .context("Mint setup transaction failed")?;
+
+    // ── 3. Pool + seed liquidity (one transaction) ────────────────────────────
+    let (pool_pda, _) = Pubkey::find_program_address(
+        &[POOL_SEED, mint_a.pubkey().as_ref(), mint_b.pubkey().as_ref()], &program_id);
+    let (position_pda, _) = Pubkey::find_program_address(
+        &[POSITION_SEED, pool_pda.as_ref(), owner.as_ref()], &program_id);
+    let vault_a = Keypair::new();
+    let vault_b = Keypair::new();
+
+    let ixs = [
+        initialize_pool_ix(
+            &owner, &mint_a.pubkey(), &mint_b.pubkey(),
+            &vault_a.pubkey(), &vault_b.pubkey(), fee_rate_bps,
+            CurveKind::ConstantProduct,
+        )?,
+        provide_liquidity_ix(
+            &owner, &pool_pda, &vault_a.pubkey(), &vault_b.pubkey(), &ata_a, &ata_b,
+            amount_a, amount_b, false, 0,
+        )?,
+    ];
+    let pool_sig = sign_and_send(&client, &ixs, &payer, &[&payer, &vault_a, &vault_b])
+        .context("initialize_pool + provide_liquidity transaction failed — \
+                  is the program deployed to the local validator?")?;
+
+    let pair = format!("{}-{}", mint_a.pubkey(), mint_b.pubkey());
+
     if json_output {
         println!("{}", json!({
             "status":       "ok",
-            "command":      "claim-fees",
-            "agent":        payer.pubkey().to_string(),
-            "claimed":      results,
-            "skipped":      skipped,
-            "total_fees_a": total_a,
-            "total_fees_b": total_b,
+            "command":      "dev bootstrap",
+            "pair":         pair,
+            "token_a_mint": mint_a.pubkey().to_string(),
+            "token_b_mint": mint_b.pubkey().to_string(),
+            "decimals":     decimals,
+            "ata_a":        ata_a.to_string(),
+            "ata_b":        ata_b.to_string(),
+            "pool":         pool_pda.to_string(),
+            "vault_a":      vault_a.pubkey().to_string(),
+            "vault_b":      vault_b.pubkey().to_string(),
+            "position":     position_pda.to_string(),
+            "fee_rate_bps": fee_rate_bps,
+            "amount_a":     amount_a,
+            "amount_b":     amount_b,
+            "balance_a":    mint_amount - amount_a,
+            "balance_b":    mint_amount - amount_b,
+            "airdrop_tx":   airdrop_sig.map(|s| s.to_string()),
+            "mint_tx":      mint_sig.to_string(),
+            "pool_tx":      pool_sig.to_string(),
         }));
     } else {
-        if results.is_empty() {
-            println!("  No fees to claim across {} position(s).", positions.len());
-        } else {
-            println!("  ─── Totals ───────────────────────────────────────");
-            println!("  Total fees A     {:>20}  ({} position(s) claimed)", total_a, results.len());
-            println!("  Total fees B     {:>20}  ({} position(s) claimed)", total_b, results.len());
-            if skipped > 0 {
-                println!("  Skipped          {skipped}  (no fees or error)");
-            }
+        println!("─── Localnet Bootstrapped ─────────────────────────────────────────");
+        println!("  Token A          {}", mint_a.pubkey());
+        println!("  Token B          {}", mint_b.pubkey());
+        println!("  Decimals         {decimals}");
+        println!("  Pool PDA         {pool_pda}");
+        println!("  Position         {position_pda}");
+        println!("  Fee rate         {fee_rate_bps} bps  ({:.2}% per swap)", fee_rate_bps as f64 / 100.0);
+        println!("  Seeded A         {:>20}", amount_a);
+        println!("  Seeded B         {:>20}", amount_b);
+        println!("  Wallet A         {:>20}", mint_amount - amount_a);
+        println!("  Wallet B         {:>20}", mint_amount - amount_b);
+        if let Some(sig) = airdrop_sig {
+            println!("  Airdrop          {sig}");
         }
+        println!("  Mint tx          {mint_sig}");
+        println!("  Pool tx          {pool_sig}");
+        println!();
+        println!("  Try it:");
+        println!("    a2a-swap simulate --rpc-url {rpc_url} \\");
+        println!("      --in {} --out {} --amount 1000000", mint_a.pubkey(), mint_b.pubkey());
     }
     Ok(())
 }
@@ -2354,7 +8039,7 @@ fn find_pool_by_pair(
             &[POOL_SEED, ma.as_ref(), mb.as_ref()],
             program_id,
         );
-        if let Ok(acct) = client.get_account(&pda) {
+        if let Some(acct) = fetch_account(client, &pda)? {
             if let Ok(pool) = parse_pool(&acct.data) {
                 let (auth, _) = Pubkey::find_program_address(
                     &[POOL_AUTHORITY_SEED, pda.as_ref()],
@@ -2364,18 +8049,18 @@ fn find_pool_by_pair(
             }
         }
     }
-    Err(anyhow!(
+    Err(CliError::PoolNotFound(format!(
         "No pool found for pair '{pair}'.\n  \
          Run `a2a-swap pool-info --pair {pair}` to verify the pool exists,\n  \
          or `a2a-swap create-pool --pair {pair} --initial-price <P>` to create one."
-    ))
+    )).into())
 }
 
 /// Parse `"TOKEN_A-TOKEN_B"` into `(sym_a, sym_b, mint_a, mint_b)`.
 fn parse_pair(pair: &str) -> Result<(&str, &str, Pubkey, Pubkey)> {
     let parts: Vec<&str> = pair.splitn(2, '-').collect();
     if parts.len() != 2 || parts[0].is_empty() || parts[1].is_empty() {
-        return Err(anyhow!(
+        return Err(input_error!(
             "--pair must be TOKEN_A-TOKEN_B (e.g. SOL-USDC or <mintA>-<mintB>). Got: '{}'",
             pair
         ));
@@ -2384,17 +8069,47 @@ fn parse_pair(pair: &str) -> Result<(&str, &str, Pubkey, Pubkey)> {
     let mint_a = resolve_mint(sym_a).context("pair: token A")?;
     let mint_b = resolve_mint(sym_b).context("pair: token B")?;
     if mint_a == mint_b {
-        return Err(anyhow!("Token A and token B in --pair must be different."));
+        return Err(input_error!("Token A and token B in --pair must be different."));
     }
     Ok((sym_a, sym_b, mint_a, mint_b))
 }
 
+/// `true` for loopback RPC URLs (`localhost`, `127.0.0.1`, `0.0.0.0`, `[::1]`).
+fn is_local_rpc(url: &str) -> bool {
+    let host = url
+        .split("://")
+        .nth(1)
+        .unwrap_or(url)
+        .split(['/', '?'])
+        .next()
+        .unwrap_or("");
+    let host = host.rsplit_once(':').map_or(host, |(h, port)| {
+        if port.chars().all(|c| c.is_ascii_digit()) { h } else { host }
+    });
+    matches!(host, "localhost" | "127.0.0.1" | "0.0.0.0" | "[::1]")
+}
+
 /// Build a confirmed RPC client.
 fn rpc(url: &str) -> RpcClient {
     RpcClient::new_with_commitment(url.to_string(), CommitmentConfig::confirmed())
 }
 
+/// Compute-unit price (micro-lamports) resolved from --priority-fee / profile.
+static PRIORITY_FEE: OnceLock<u64> = OnceLock::new();
+
+/// ComputeBudget setComputeUnitPrice (ix 3).
+fn set_compute_unit_price_ix(micro_lamports: u64) -> Result<Instruction> {
+    let mut data = vec![3u8];
+    data.extend_from_slice(&micro_lamports.to_le_bytes());
+    Ok(Instruction {
+        program_id: Pubkey::from_str(COMPUTE_BUDGET_PROGRAM_ID)?,
+        accounts: vec![],
+        data,
+    })
+}
+
 /// Sign and confirm a transaction with `signers` (payer must be first).
+/// Prepends a compute-unit price instruction when a priority fee is set.
 fn sign_and_send(
     client: &RpcClient,
     instructions: &[Instruction],
@@ -2403,14 +8118,85 @@ fn sign_and_send(
 ) -> Result<solana_sdk::signature::Signature> {
     let blockhash = client.get_latest_blockhash()
         .context("Failed to fetch recent blockhash — check your RPC endpoint")?;
-    let tx = Transaction::new_signed_with_payer(
-        instructions,
-        Some(&payer.pubkey()),
-        signers,
-        blockhash,
-    );
-    client.send_and_confirm_transaction(&tx)
-        .map_err(|e| anyhow!("Transaction failed: {}\n  Check your token balances and RPC connectivity.", e))
+    let instructions = with_priority_fee(instructions)?;
+    let tx = Transaction::new_signed_with_payer(&instructions, Some(&payer.pubkey()), signers, blockhash);
+    client.send_and_confirm_transaction(&tx).map_err(|e| {
+        // Keep the program's own error code for `--json` to report.
+        let program = e.get_transaction_error().and_then(|err| {
+            A2AErrorCode::from_transaction_error(&err, &instructions, &Pubkey::from_str(PROGRAM_ID).ok()?)
+        });
+        let cause = match program {
+            Some(code) => a2a_swap_sdk::Error::Program(code),
+            None       => a2a_swap_sdk::Error::from(e),
+        };
+        anyhow::Error::new(cause)
+            .context("Transaction failed (check your token balances and RPC connectivity)")
+    })
+}
+
+/// Fail with the SDK's `InsufficientBalance` before sending a swap `payer`
+/// can't fund: its input token account must hold `amount_in`, and its wallet
+/// the fee for `signatures`, the rent of the wSOL accounts the swap opens
+/// and, for SOL input, the lamports it wraps.
+fn check_swap_funds(
+    client: &RpcClient,
+    payer: &Pubkey,
+    mint_in: &Pubkey,
+    mint_out: &Pubkey,
+    amount_in: u64,
+    signatures: u64,
+) -> Result<()> {
+    let wsol_mint = Pubkey::from_str(WSOL_MINT)?;
+    let ata_in = derive_ata(payer, mint_in);
+    let accounts = client.get_multiple_accounts_with_commitment(&[*payer, ata_in], client.commitment())?.value;
+    let lamports = accounts[0].as_ref().map_or(0, |a| a.lamports);
+    let balance  = accounts[1].as_ref().and_then(|a| parse_token_amount(&a.data).ok());
+
+    // The wSOL input account unless it exists, and the wSOL output account,
+    // opened and closed around the swap.
+    let new_accounts = (*mint_in == wsol_mint && accounts[1].is_none()) as u64 + (*mint_out == wsol_mint) as u64;
+    let rent = match new_accounts {
+        0 => 0,
+        n => client.get_minimum_balance_for_rent_exemption(TOKEN_ACCOUNT_LEN)? * n,
+    };
+    let mut lamports_needed = LAMPORTS_PER_SIGNATURE * signatures + priority_fee_lamports() + rent;
+    if *mint_in == wsol_mint {
+        lamports_needed += amount_in;
+    }
+    let token_in = (*mint_in != wsol_mint).then_some((&ata_in, balance));
+    Ok(check_funds(payer, lamports, lamports_needed, token_in, amount_in)?)
+}
+
+/// The most the priority fee can add to a transaction, in lamports: the
+/// compute-unit price at the 1.4M compute-unit ceiling.
+fn priority_fee_lamports() -> u64 {
+    let micro_lamports = PRIORITY_FEE.get().copied().unwrap_or(0);
+    (micro_lamports as u128 * 1_400_000).div_ceil(1_000_000) as u64
+}
+
+/// Build the transaction `sign_and_send` would, signed by `payer` only; the
+/// approver adds its signature (see `telegram`).
+fn sign_for_approval(
+    client: &RpcClient,
+    instructions: &[Instruction],
+    payer: &Keypair,
+) -> Result<Transaction> {
+    let blockhash = client.get_latest_blockhash()
+        .context("Failed to fetch recent blockhash — check your RPC endpoint")?;
+    let mut tx = Transaction::new_with_payer(&with_priority_fee(instructions)?, Some(&payer.pubkey()));
+    tx.try_partial_sign(&[payer], blockhash)?;
+    Ok(tx)
+}
+
+/// `instructions`, preceded by the compute-unit price when one is set.
+fn with_priority_fee(instructions: &[Instruction]) -> Result<Vec<Instruction>> {
+    let mut all = Vec::with_capacity(instructions.len() + 1);
+    match PRIORITY_FEE.get() {
+        Some(&fee) if fee > 0 => all.push(set_compute_unit_price_ix(fee)?),
+        _ => {}
+    }
+    all.extend_from_slice(instructions);
+    Ok(all)
 }
 
 /// Collect unique pool Pubkeys from a position list, preserving encounter order.
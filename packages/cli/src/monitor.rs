@@ -0,0 +1,227 @@
+//! `a2a-swap monitor --config monitor.toml` — the rules file and the
+//! condition checks.
+//!
+//! ```toml
+//! interval = 60            # seconds between checks
+//! window   = 3600          # seconds of pool history for fee APR and reserve drops
+//! # notify = "slack://…"   # default: --notify / profile notify
+//!
+//! [[rule]]
+//! pair             = "SOL-USDC"  # omit to match every position
+//! il_pct           = 5.0         # impermanent loss beyond 5%
+//! min_fee_apr      = 10.0        # pool fee APR below 10%
+//! reserve_drop_pct = 30.0        # either reserve down 30% from its high in the window
+//! action           = "remove"    # notify (default) | claim-fees | remove
+//! ```
+//!
+//! A condition fires once when it becomes true and re-arms when it clears.
+//! Fee APR is `(Δfees_a / reserve_a + Δfees_b / reserve_b) / 2`, annualized
+//! over the oldest sample still in the window, so it needs two checks before
+//! it reports anything.
+
+use std::collections::{HashSet, VecDeque};
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use serde::Deserialize;
+use solana_sdk::pubkey::Pubkey;
+
+use crate::error::input_error;
+
+const SECS_PER_YEAR: f64 = 365.0 * 24.0 * 3600.0;
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct MonitorFile {
+    /// Seconds between checks.
+    #[serde(default = "default_interval")]
+    pub interval: u64,
+    /// Seconds of pool history kept for `min_fee_apr` and `reserve_drop_pct`.
+    #[serde(default = "default_window")]
+    pub window: u64,
+    /// Notification target; overrides `--notify`.
+    #[serde(default)]
+    pub notify: Option<String>,
+    #[serde(rename = "rule")]
+    pub rules: Vec<Rule>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Rule {
+    /// `A-B`, as in `--pair`; `None` matches every position.
+    #[serde(default)]
+    pub pair: Option<String>,
+    /// Fire when impermanent loss exceeds this many percent.
+    #[serde(default)]
+    pub il_pct: Option<f64>,
+    /// Fire when the pool's fee APR drops below this many percent.
+    #[serde(default)]
+    pub min_fee_apr: Option<f64>,
+    /// Fire when either reserve is this many percent below its high in the window.
+    #[serde(default)]
+    pub reserve_drop_pct: Option<f64>,
+    #[serde(default)]
+    pub action: Action,
+}
+
+/// What a firing rule does besides notifying.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Action {
+    #[default]
+    Notify,
+    /// Claim the position's fees.
+    ClaimFees,
+    /// Withdraw the whole position.
+    Remove,
+}
+
+impl Action {
+    pub fn label(self) -> &'static str {
+        match self {
+            Action::Notify    => "notify",
+            Action::ClaimFees => "claim-fees",
+            Action::Remove    => "remove",
+        }
+    }
+}
+
+fn default_interval() -> u64 {
+    60
+}
+
+fn default_window() -> u64 {
+    3_600
+}
+
+impl MonitorFile {
+    pub fn load(path: &Path) -> Result<Self> {
+        let text = std::fs::read_to_string(path)
+            .with_context(|| format!("reading monitor config {}", path.display()))?;
+        let file: Self = toml::from_str(&text)
+            .with_context(|| format!("parsing monitor config {}", path.display()))?;
+        if file.interval == 0 {
+            return Err(input_error!("monitor config: interval must be at least 1 second."));
+        }
+        if file.rules.is_empty() {
+            return Err(input_error!("monitor config: add at least one [[rule]]."));
+        }
+        if let Some(i) = file.rules.iter().position(|r| {
+            r.il_pct.is_none() && r.min_fee_apr.is_none() && r.reserve_drop_pct.is_none()
+        }) {
+            return Err(input_error!(
+                "monitor config: rule {} needs il_pct, min_fee_apr or reserve_drop_pct.", i + 1
+            ));
+        }
+        Ok(file)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Condition {
+    ImpermanentLoss,
+    FeeApr,
+    ReserveDrop,
+}
+
+impl Condition {
+    pub fn name(self) -> &'static str {
+        match self {
+            Condition::ImpermanentLoss => "il",
+            Condition::FeeApr          => "fee_apr",
+            Condition::ReserveDrop     => "reserve_drop",
+        }
+    }
+}
+
+/// One read of a pool.
+#[derive(Debug, Clone, Copy)]
+pub struct Sample {
+    /// Unix seconds.
+    pub at:        u64,
+    pub reserve_a: u64,
+    pub reserve_b: u64,
+    /// Lifetime LP fees per side.
+    pub fees_a:    u64,
+    pub fees_b:    u64,
+}
+
+/// A pool's samples within the window, oldest first.
+#[derive(Debug, Default)]
+pub struct PoolHistory {
+    samples: VecDeque<Sample>,
+}
+
+impl PoolHistory {
+    /// Add `sample` and drop samples older than `window` seconds.
+    pub fn push(&mut self, sample: Sample, window: u64) {
+        self.samples.push_back(sample);
+        while self.samples.front().is_some_and(|s| sample.at.saturating_sub(s.at) > window) {
+            self.samples.pop_front();
+        }
+    }
+
+    /// Annualized LP fee yield, in percent, since the oldest sample.
+    pub fn fee_apr_pct(&self) -> Option<f64> {
+        let (first, last) = (self.samples.front()?, self.samples.back()?);
+        let elapsed = last.at.saturating_sub(first.at);
+        if elapsed == 0 || last.reserve_a == 0 || last.reserve_b == 0 {
+            return None;
+        }
+        let yield_a = last.fees_a.saturating_sub(first.fees_a) as f64 / last.reserve_a as f64;
+        let yield_b = last.fees_b.saturating_sub(first.fees_b) as f64 / last.reserve_b as f64;
+        Some((yield_a + yield_b) / 2.0 * SECS_PER_YEAR / elapsed as f64 * 100.0)
+    }
+
+    /// How far the latest reserves sit below their window high, in percent
+    /// (the larger of the two sides).
+    pub fn reserve_drop_pct(&self) -> Option<f64> {
+        let last = self.samples.back()?;
+        let drop = |high: u64, now: u64| if high == 0 { 0.0 } else { (1.0 - now as f64 / high as f64) * 100.0 };
+        let high_a = self.samples.iter().map(|s| s.reserve_a).max()?;
+        let high_b = self.samples.iter().map(|s| s.reserve_b).max()?;
+        Some(drop(high_a, last.reserve_a).max(drop(high_b, last.reserve_b)))
+    }
+}
+
+/// A condition that is true now: `(condition, value, threshold)`.
+pub type Breach = (Condition, f64, f64);
+
+/// The conditions of `rule` that hold, given the position's IL (percent,
+/// positive = loss) and its pool's history.
+pub fn breaches(rule: &Rule, il_pct: Option<f64>, pool: &PoolHistory) -> Vec<Breach> {
+    let mut out = Vec::new();
+    if let (Some(limit), Some(il)) = (rule.il_pct, il_pct) {
+        if il > limit {
+            out.push((Condition::ImpermanentLoss, il, limit));
+        }
+    }
+    if let (Some(floor), Some(apr)) = (rule.min_fee_apr, pool.fee_apr_pct()) {
+        if apr < floor {
+            out.push((Condition::FeeApr, apr, floor));
+        }
+    }
+    if let (Some(limit), Some(drop)) = (rule.reserve_drop_pct, pool.reserve_drop_pct()) {
+        if drop > limit {
+            out.push((Condition::ReserveDrop, drop, limit));
+        }
+    }
+    out
+}
+
+/// Which `(position, rule, condition)` triples have fired and not yet cleared.
+#[derive(Debug, Default)]
+pub struct Fired(HashSet<(Pubkey, usize, Condition)>);
+
+impl Fired {
+    /// Record this check's breaches for `position` under rule `rule`;
+    /// returns the ones that just started. Conditions no longer breached re-arm.
+    pub fn update(&mut self, position: Pubkey, rule: usize, now: &[Breach]) -> Vec<Breach> {
+        self.0.retain(|(p, r, c)| *p != position || *r != rule || now.iter().any(|b| b.0 == *c));
+        now.iter()
+            .filter(|b| self.0.insert((position, rule, b.0)))
+            .copied()
+            .collect()
+    }
+}
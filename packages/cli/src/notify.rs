@@ -0,0 +1,203 @@
+//! `--notify` — post the result of each transaction-sending command to Slack,
+//! Discord or any JSON webhook.
+//!
+//! Targets:
+//!
+//! ```text
+//! slack://hooks.slack.com/services/T…/B…/…   Slack incoming webhook
+//! slack://T…/B…/…                            same, short form
+//! discord://discord.com/api/webhooks/ID/TOKEN  Discord webhook
+//! discord://ID/TOKEN                         same, short form
+//! https://hooks.slack.com/…                  Slack, detected by host
+//! https://discord.com/api/webhooks/…         Discord, detected by host
+//! https://…  (anything else)                 raw JSON event
+//! ```
+//!
+//! Commands that send a transaction (`create-pool`, `provide`, `convert`,
+//! `remove`, `remove-liquidity`, `claim-fees`) record their result with
+//! [`record`]; `main` then calls [`send`] with the outcome. Slack and Discord
+//! get a one-line message rendered from [`TEMPLATES`], or the result JSON in a
+//! code block when no template fits. `monitor` sends each alert with
+//! [`alert`] as it fires. A notification that cannot be delivered prints a
+//! warning and never changes the command's exit status.
+
+use std::sync::Mutex;
+use std::time::Duration;
+
+use anyhow::Result;
+use serde_json::{json, Value};
+
+use crate::error::input_error;
+
+/// Delivery request timeout.
+const TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Discord rejects messages longer than this.
+const DISCORD_MAX_CHARS: usize = 2_000;
+
+/// Message templates per command, tried in order; the first whose `{field}`
+/// placeholders all exist in the result is used.
+const TEMPLATES: &[(&str, &str)] = &[
+    ("convert",          "Swap executed: {amount_in} {token_in} → ~{estimated_out} {token_out} \
+                          (min {min_amount_out}, impact {price_impact_pct}%) · tx {tx}"),
+    ("rebalance",        "Rebalanced: {amount_in} {token_in} → ~{estimated_out} {token_out} \
+                          (min {min_amount_out}, impact {price_impact_pct}%) · tx {tx}"),
+    ("provide",          "Liquidity provided to {pair}: {amount_a} A + {amount_b} B \
+                          · position {position} · tx {tx}"),
+    ("remove",           "Liquidity removed from {pair}: {lp_shares} LP shares \
+                          → ~{expected_a} A + ~{expected_b} B · tx {tx}"),
+    ("remove-liquidity", "Liquidity removed from {pair}: {lp_shares} LP shares \
+                          → ~{expected_a} A + ~{expected_b} B · tx {tx}"),
+    ("claim-fees",       "Fees claimed on {pair}: {fees_a} A + {fees_b} B · tx {tx}"),
+    ("claim-fees",       "Fees claimed for {agent}: {total_fees_a} A + {total_fees_b} B in total"),
+    ("create-pool",      "Pool created for {pair}: {pool} · {fee_rate_bps} bps · tx {tx}"),
+    ("monitor",          "Position {position} on {pair}: {condition} at {value} \
+                          (threshold {threshold}) · action {action}"),
+];
+
+/// Result of the running command, set by [`record`].
+static RESULT: Mutex<Option<Value>> = Mutex::new(None);
+
+/// Where notifications go.
+#[derive(Debug, Clone)]
+pub enum Target {
+    Slack(String),
+    Discord(String),
+    /// Any other webhook: receives the event as JSON.
+    Json(String),
+}
+
+impl Target {
+    pub fn parse(target: &str) -> Result<Target> {
+        if let Some(rest) = target.strip_prefix("slack://") {
+            let rest = rest.trim_start_matches('/');
+            return Ok(if rest.starts_with("hooks.slack.com/") {
+                Target::Slack(format!("https://{rest}"))
+            } else {
+                Target::Slack(format!("https://hooks.slack.com/services/{rest}"))
+            });
+        }
+        if let Some(rest) = target.strip_prefix("discord://") {
+            let rest = rest.trim_start_matches('/');
+            return Ok(if rest.starts_with("discord.com/") || rest.starts_with("discordapp.com/") {
+                Target::Discord(format!("https://{rest}"))
+            } else {
+                Target::Discord(format!("https://discord.com/api/webhooks/{rest}"))
+            });
+        }
+        let Some(rest) = target.strip_prefix("https://").or(target.strip_prefix("http://")) else {
+            return Err(input_error!(
+                "Invalid notify target `{target}`.\n  \
+                 Use slack://…, discord://… or an http(s):// webhook URL."
+            ));
+        };
+        let host = rest.split(['/', '?', '#']).next().unwrap_or_default();
+        Ok(match host {
+            "hooks.slack.com" => Target::Slack(target.to_string()),
+            "discord.com" | "discordapp.com" if rest.contains("/api/webhooks/") => {
+                Target::Discord(target.to_string())
+            }
+            _ => Target::Json(target.to_string()),
+        })
+    }
+
+    fn url(&self) -> &str {
+        match self {
+            Target::Slack(u) | Target::Discord(u) | Target::Json(u) => u,
+        }
+    }
+}
+
+/// Record the running command's result (its `--json` output) for [`send`].
+pub fn record(result: &Value) {
+    if let Ok(mut slot) = RESULT.lock() {
+        *slot = Some(result.clone());
+    }
+}
+
+/// Notify `target` of how `command` ended. A success that recorded nothing
+/// (e.g. `claim-fees` with no fees) sends nothing.
+pub fn send(target: &Target, command: &str, outcome: &Result<()>) {
+    let result = RESULT.lock().ok().and_then(|mut slot| slot.take());
+    let body = match (outcome, result) {
+        (Ok(()), None)          => return,
+        (Ok(()), Some(result))  => payload(target, command, Ok(&result)),
+        (Err(e), _)             => payload(target, command, Err(&format!("{e:#}"))),
+    };
+    post(target, &body);
+}
+
+/// Notify `target` of a `monitor` alert (one of its `--json` lines).
+pub fn alert(target: &Target, alert: &Value) {
+    post(target, &payload(target, "monitor", Ok(alert)));
+}
+
+fn post(target: &Target, body: &Value) {
+    let sent = reqwest::blocking::Client::builder()
+        .timeout(TIMEOUT)
+        .build()
+        .and_then(|client| client.post(target.url()).json(body).send())
+        .and_then(|res| res.error_for_status());
+    if let Err(e) = sent {
+        eprintln!("Warning: --notify delivery failed: {e}");
+    }
+}
+
+/// The request body for `target`.
+fn payload(target: &Target, command: &str, outcome: std::result::Result<&Value, &String>) -> Value {
+    let text = match outcome {
+        Ok(result) => message(command, result),
+        Err(error) => format!("a2a-swap {command} failed: {error}"),
+    };
+    match target {
+        Target::Slack(_)   => json!({ "text": text }),
+        Target::Discord(_) => json!({ "content": truncate(&text, DISCORD_MAX_CHARS) }),
+        Target::Json(_)    => match outcome {
+            Ok(result) => json!({ "command": command, "status": "ok", "result": result }),
+            Err(error) => json!({ "command": command, "status": "error", "error": error }),
+        },
+    }
+}
+
+/// The first template for `command` that renders, else the result as JSON.
+fn message(command: &str, result: &Value) -> String {
+    TEMPLATES
+        .iter()
+        .filter(|(cmd, _)| *cmd == command)
+        .find_map(|(_, template)| render(template, result))
+        .unwrap_or_else(|| {
+            let pretty = serde_json::to_string_pretty(result).unwrap_or_default();
+            format!("a2a-swap {command}:\n```\n{pretty}\n```")
+        })
+}
+
+/// Fill `{field}` placeholders from `result`; `None` if any field is missing.
+fn render(template: &str, result: &Value) -> Option<String> {
+    let mut out = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(start) = rest.find('{') {
+        let end = start + rest[start..].find('}')?;
+        out.push_str(&rest[..start]);
+        out.push_str(&field(result.get(&rest[start + 1..end])?)?);
+        rest = &rest[end + 1..];
+    }
+    out.push_str(rest);
+    Some(out)
+}
+
+fn field(value: &Value) -> Option<String> {
+    match value {
+        Value::String(s) => Some(s.clone()),
+        Value::Bool(b)   => Some(b.to_string()),
+        Value::Number(n) if n.is_f64() => n.as_f64().map(|f| format!("{f:.4}")),
+        Value::Number(n) => Some(n.to_string()),
+        _ => None,
+    }
+}
+
+fn truncate(text: &str, max: usize) -> String {
+    match text.char_indices().nth(max - 1) {
+        Some((i, _)) => format!("{}…", &text[..i]),
+        None         => text.to_string(),
+    }
+}
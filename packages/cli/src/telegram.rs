@@ -0,0 +1,338 @@
+//! Telegram approval channel for `approve_and_execute`.
+//!
+//! Two halves share one bot:
+//!
+//! - **Agent** (`convert --approval-mode telegram`): builds the
+//!   `approve_and_execute` transaction, signs it as the agent and posts it to
+//!   the bot chat with Approve / Reject buttons ([`request_approval`]). It
+//!   then waits for the transaction to land, or for its blockhash to expire.
+//! - **Signer** (`a2a-swap approver telegram`): holds the approver key and is
+//!   the only process reading the bot's updates ([`serve`]). On Approve from
+//!   an allowed user it checks the transaction, co-signs and submits it; on
+//!   Reject it closes the request.
+//!
+//! The partially signed transaction travels in the message itself (last line,
+//! `tx: <base64>`), so the signer needs no inbound port. A request is only
+//! good for the lifetime of its blockhash (~60–90 s) and until its
+//! `expires_at_slot`; the signer will not co-sign past either.
+
+use std::time::{Duration, Instant};
+
+use anyhow::{anyhow, Context, Result};
+use base64::Engine as _;
+use serde::Deserialize;
+use serde_json::{json, Value};
+use solana_client::rpc_client::RpcClient;
+use solana_sdk::{
+    pubkey::Pubkey,
+    signature::{Signature, Signer},
+    transaction::Transaction,
+};
+
+use a2a_swap_core::{ix::ApproveAndExecute, Instruction as _};
+
+/// Long-poll timeout for `getUpdates`, in seconds.
+const LONG_POLL_SECS: u64 = 30;
+
+/// How often the agent checks whether its transaction has landed.
+const STATUS_POLL: Duration = Duration::from_secs(2);
+
+/// Prefix of the message line that carries the transaction.
+const TX_PREFIX: &str = "tx: ";
+
+const APPROVE: &str = "approve";
+const REJECT: &str = "reject";
+
+// ─── Bot API ──────────────────────────────────────────────────────────────────
+
+/// Minimal Telegram Bot API client.
+pub struct Bot {
+    token:  String,
+    client: reqwest::blocking::Client,
+}
+
+#[derive(Deserialize)]
+struct Reply<T> {
+    ok:          bool,
+    result:      Option<T>,
+    description: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct Update {
+    update_id:      i64,
+    callback_query: Option<CallbackQuery>,
+}
+
+#[derive(Deserialize)]
+struct CallbackQuery {
+    id:      String,
+    from:    User,
+    message: Option<Message>,
+    data:    Option<String>,
+}
+
+#[derive(Deserialize)]
+struct User {
+    id:         i64,
+    first_name: String,
+    username:   Option<String>,
+}
+
+impl User {
+    fn label(&self) -> String {
+        match &self.username {
+            Some(u) => format!("@{u}"),
+            None    => self.first_name.clone(),
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct Message {
+    message_id: i64,
+    chat:       Chat,
+    text:       Option<String>,
+}
+
+#[derive(Deserialize)]
+struct Chat {
+    id: i64,
+}
+
+impl Bot {
+    pub fn new(token: &str) -> Result<Bot> {
+        let client = reqwest::blocking::Client::builder()
+            .timeout(Duration::from_secs(LONG_POLL_SECS + 10))
+            .build()?;
+        Ok(Bot { token: token.to_string(), client })
+    }
+
+    fn call<T: for<'de> Deserialize<'de>>(&self, method: &str, body: &Value) -> Result<T> {
+        let reply: Reply<T> = self.client
+            .post(format!("https://api.telegram.org/bot{}/{method}", self.token))
+            .json(body)
+            .send()
+            .and_then(|r| r.json())
+            .with_context(|| format!("Telegram {method} request failed"))?;
+        match (reply.ok, reply.result) {
+            (true, Some(result)) => Ok(result),
+            _ => Err(anyhow!(
+                "Telegram {method} failed: {}",
+                reply.description.unwrap_or_else(|| "no description".into())
+            )),
+        }
+    }
+
+    fn send_proposal(&self, chat: &str, text: &str) -> Result<Message> {
+        self.call("sendMessage", &json!({
+            "chat_id": chat,
+            "text":    text,
+            "reply_markup": { "inline_keyboard": [[
+                { "text": "✅ Approve", "callback_data": APPROVE },
+                { "text": "❌ Reject",  "callback_data": REJECT },
+            ]]},
+        }))
+    }
+
+    /// Replace the message's buttons with a closing line.
+    fn close(&self, chat: i64, message_id: i64, text: &str) -> Result<()> {
+        self.call::<Value>("editMessageText", &json!({
+            "chat_id":    chat,
+            "message_id": message_id,
+            "text":       text,
+        }))
+        .map(drop)
+    }
+
+    fn answer(&self, callback_id: &str, text: &str) {
+        let _ = self.call::<Value>("answerCallbackQuery", &json!({
+            "callback_query_id": callback_id,
+            "text":              text,
+        }));
+    }
+}
+
+// ─── Agent side ───────────────────────────────────────────────────────────────
+
+/// Post `tx` (signed by the agent, waiting on the approver) to `chat` with
+/// `summary`, then wait until it confirms or its blockhash expires.
+pub fn request_approval(
+    client:  &RpcClient,
+    bot:     &Bot,
+    chat:    &str,
+    tx:      &Transaction,
+    summary: &str,
+) -> Result<Signature> {
+    let sig = tx.signatures[0];
+    let encoded = base64::engine::general_purpose::STANDARD.encode(bincode::serialize(tx)?);
+    let text = format!("{summary}\n\n{TX_PREFIX}{encoded}");
+    let msg = bot.send_proposal(chat, &text)?;
+    eprintln!("[approval] mode=telegram  posted to chat {chat} — waiting for approval");
+
+    let started = Instant::now();
+    loop {
+        if let Some(status) = client.get_signature_status(&sig)? {
+            return status
+                .map(|()| sig)
+                .map_err(|e| anyhow!("Approved swap failed on-chain: {e}"));
+        }
+        if !client.is_blockhash_valid(&tx.message.recent_blockhash, client.commitment())? {
+            // A last status check: the signer may have landed it just in time.
+            if let Some(status) = client.get_signature_status(&sig)? {
+                return status.map(|()| sig).map_err(|e| anyhow!("Approved swap failed on-chain: {e}"));
+            }
+            let _ = bot.close(msg.chat.id, msg.message_id, &format!("{summary}\n\n⌛ Expired — not approved in time."));
+            return Err(anyhow!(
+                "Swap was not approved within {}s (rejected, or no answer before the blockhash expired).",
+                started.elapsed().as_secs()
+            ));
+        }
+        std::thread::sleep(STATUS_POLL);
+    }
+}
+
+// ─── Signer side ──────────────────────────────────────────────────────────────
+
+/// Answer Approve / Reject presses in `chat` from the users in `allow` until
+/// interrupted, co-signing approved transactions with `approver` (a keypair,
+/// or a Ledger that asks for confirmation on the device).
+pub fn serve(
+    client:     &RpcClient,
+    bot:        &Bot,
+    chat:       i64,
+    allow:      &[i64],
+    approver:   &dyn Signer,
+    program_id: &Pubkey,
+) -> Result<()> {
+    eprintln!(
+        "[approver] signing as {} for chat {chat}; allowed users: {allow:?}",
+        approver.pubkey()
+    );
+    let mut offset = 0;
+    loop {
+        let updates: Vec<Update> = match bot.call("getUpdates", &json!({
+            "offset":          offset,
+            "timeout":         LONG_POLL_SECS,
+            "allowed_updates": ["callback_query"],
+        })) {
+            Ok(u) => u,
+            Err(e) => {
+                eprintln!("[approver] {e:#}");
+                std::thread::sleep(Duration::from_secs(5));
+                continue;
+            }
+        };
+        for update in updates {
+            offset = update.update_id + 1;
+            if let Some(q) = update.callback_query {
+                handle(client, bot, chat, allow, approver, program_id, q);
+            }
+        }
+    }
+}
+
+fn handle(
+    client:     &RpcClient,
+    bot:        &Bot,
+    chat:       i64,
+    allow:      &[i64],
+    approver:   &dyn Signer,
+    program_id: &Pubkey,
+    q:          CallbackQuery,
+) {
+    let Some(msg) = q.message.as_ref().filter(|m| m.chat.id == chat) else { return };
+    if !allow.contains(&q.from.id) {
+        bot.answer(&q.id, "You are not allowed to approve swaps.");
+        return;
+    }
+    let text = msg.text.as_deref().unwrap_or_default();
+    let summary = text.split(&format!("\n\n{TX_PREFIX}")).next().unwrap_or_default();
+    let who = q.from.label();
+
+    let outcome = match q.data.as_deref() {
+        Some(APPROVE) => match co_sign(client, text, approver, program_id) {
+            Ok(sig) => {
+                eprintln!("[approver] approved by {who}: {sig}");
+                format!("✅ Approved by {who}\ntx {sig}")
+            }
+            Err(e) => {
+                eprintln!("[approver] approval by {who} failed: {e:#}");
+                format!("⚠️ Approved by {who}, but the swap failed: {e:#}")
+            }
+        },
+        Some(REJECT) => {
+            eprintln!("[approver] rejected by {who}");
+            format!("❌ Rejected by {who}")
+        }
+        _ => return,
+    };
+    bot.answer(&q.id, outcome.lines().next().unwrap_or_default());
+    if let Err(e) = bot.close(msg.chat.id, msg.message_id, &format!("{summary}\n\n{outcome}")) {
+        eprintln!("[approver] {e:#}");
+    }
+}
+
+/// Decode the transaction from the message, check it, sign and submit it.
+fn co_sign(client: &RpcClient, text: &str, approver: &dyn Signer, program_id: &Pubkey) -> Result<Signature> {
+    let encoded = text
+        .lines()
+        .rev()
+        .find_map(|l| l.strip_prefix(TX_PREFIX))
+        .ok_or_else(|| anyhow!("message carries no transaction"))?;
+    let bytes = base64::engine::general_purpose::STANDARD
+        .decode(encoded.trim())
+        .context("transaction is not valid base64")?;
+    let mut tx: Transaction = bincode::deserialize(&bytes).context("transaction does not decode")?;
+
+    let approval = check(&tx, &approver.pubkey(), program_id)?;
+    if approval.expires_at_slot > 0 {
+        let slot = client.get_slot()?;
+        if slot > approval.expires_at_slot {
+            return Err(anyhow!(
+                "approval expired at slot {} (now {slot})",
+                approval.expires_at_slot
+            ));
+        }
+    }
+    tx.try_partial_sign(&[approver], tx.message.recent_blockhash)?;
+    Ok(client.send_and_confirm_transaction(&tx)?)
+}
+
+/// Refuse anything but an agent-signed `approve_and_execute` that uses the
+/// approver key for nothing else; returns its arguments.
+fn check(tx: &Transaction, approver: &Pubkey, program_id: &Pubkey) -> Result<ApproveAndExecute> {
+    let keys = &tx.message.account_keys;
+    let signers = tx.message.header.num_required_signatures as usize;
+    let idx = keys
+        .iter()
+        .position(|k| k == approver)
+        .filter(|&i| i < signers)
+        .ok_or_else(|| anyhow!("transaction does not ask this approver to sign"))?;
+    if idx == 0 {
+        return Err(anyhow!("the approver must not pay the transaction fee"));
+    }
+
+    let mut approvals = Vec::new();
+    for ix in &tx.message.instructions {
+        if !ix.accounts.contains(&(idx as u8)) {
+            continue;
+        }
+        let args = ApproveAndExecute::from_data(&ix.data)
+            .ok()
+            .filter(|_| keys.get(ix.program_id_index as usize) == Some(program_id))
+            .filter(|_| ix.accounts.get(1) == Some(&(idx as u8)))
+            .filter(|_| !ix.accounts.iter().enumerate().any(|(n, &a)| n != 1 && a == idx as u8))
+            .ok_or_else(|| anyhow!("transaction uses the approver key outside approve_and_execute"))?;
+        approvals.push(args);
+    }
+    if approvals.len() != 1 {
+        return Err(anyhow!("expected exactly one approve_and_execute, found {}", approvals.len()));
+    }
+
+    let verified = tx.verify_with_results();
+    if verified.iter().enumerate().any(|(i, ok)| i != idx && !ok) {
+        return Err(anyhow!("agent signature is missing or invalid"));
+    }
+    Ok(approvals.remove(0))
+}
@@ -0,0 +1,383 @@
+//! Generates `$OUT_DIR/idl.rs` from `idl/a2a_swap.json` (written by
+//! `scripts/build-idl.sh`): a Rust type with a `Layout` impl for every IDL
+//! type, an `Account` impl for every account, and an argument struct with an
+//! `Instruction` impl for every instruction, in `ix`.
+
+use serde_json::Value;
+use std::{collections::HashMap, env, fmt::Write, fs, path::Path};
+
+const IDL_PATH: &str = "idl/a2a_swap.json";
+
+fn main() {
+    println!("cargo:rerun-if-changed={IDL_PATH}");
+    println!("cargo:rerun-if-changed=build.rs");
+
+    let idl: Value = serde_json::from_str(
+        &fs::read_to_string(IDL_PATH).unwrap_or_else(|e| panic!("{IDL_PATH}: {e}")),
+    )
+    .unwrap_or_else(|e| panic!("{IDL_PATH}: {e}"));
+
+    let types = idl["types"].as_array().expect("IDL has no `types`");
+    let defs: HashMap<&str, &Value> = types
+        .iter()
+        .map(|t| (t["name"].as_str().expect("type without a name"), &t["type"]))
+        .collect();
+    let accounts: HashMap<&str, &Value> = idl["accounts"]
+        .as_array()
+        .expect("IDL has no `accounts`")
+        .iter()
+        .map(|a| (a["name"].as_str().expect("account without a name"), &a["discriminator"]))
+        .collect();
+
+    let mut out = String::new();
+    writeln!(out, "// Generated by build.rs from {IDL_PATH}. Do not edit.").unwrap();
+    writeln!(out).unwrap();
+    writeln!(out, "/// Program ID the IDL was built for.").unwrap();
+    writeln!(out, "pub const PROGRAM_ID: &str = {:?};", idl["address"].as_str().unwrap()).unwrap();
+
+    for t in types {
+        let name = t["name"].as_str().unwrap();
+        out.push('\n');
+        push_docs(&mut out, "", &t["docs"]);
+        let def = &t["type"];
+        match def["kind"].as_str() {
+            Some("struct") => push_struct(&mut out, "", name, fields(def), &defs),
+            Some("enum") => push_enum(&mut out, name, def, &defs),
+            kind => panic!("type {name}: unsupported kind {kind:?}"),
+        }
+        if let Some(disc) = accounts.get(name) {
+            writeln!(out).unwrap();
+            writeln!(out, "impl Account for {name} {{").unwrap();
+            writeln!(out, "    const NAME: &'static str = {name:?};").unwrap();
+            writeln!(out, "    const DISCRIMINATOR: [u8; 8] = {};", bytes(disc)).unwrap();
+            writeln!(out, "}}").unwrap();
+            if fixed_size(def, &defs) {
+                writeln!(out).unwrap();
+                writeln!(out, "impl {name} {{").unwrap();
+                writeln!(out, "    /// Account size in bytes, discriminator included.").unwrap();
+                writeln!(out, "    pub const LEN: usize = {};", 8 + size(def, &defs)).unwrap();
+                writeln!(out, "}}").unwrap();
+            }
+        }
+    }
+
+    writeln!(out).unwrap();
+    writeln!(out, "/// Instruction arguments, one struct per program instruction.").unwrap();
+    writeln!(out, "pub mod ix {{").unwrap();
+    writeln!(out, "    use super::*;").unwrap();
+    for ix in idl["instructions"].as_array().expect("IDL has no `instructions`") {
+        let name = ix["name"].as_str().unwrap();
+        let ty = camel(name);
+        let args = ix["args"].as_array().unwrap();
+        out.push('\n');
+        push_docs(&mut out, "    ", &ix["docs"]);
+        push_struct(&mut out, "    ", &ty, args, &defs);
+        writeln!(out).unwrap();
+        writeln!(out, "    impl Instruction for {ty} {{").unwrap();
+        writeln!(out, "        const NAME: &'static str = {name:?};").unwrap();
+        writeln!(out, "        const DISCRIMINATOR: [u8; 8] = {};", bytes(&ix["discriminator"]))
+            .unwrap();
+        writeln!(out, "        const ACCOUNTS: &'static [AccountSpec] = &[").unwrap();
+        for acc in ix["accounts"].as_array().unwrap() {
+            assert!(acc.get("accounts").is_none(), "{name}: nested account groups are unsupported");
+            writeln!(
+                out,
+                "            AccountSpec {{ name: {:?}, writable: {}, signer: {}, optional: {} }},",
+                acc["name"].as_str().unwrap(),
+                flag(acc, "writable"),
+                flag(acc, "signer"),
+                flag(acc, "optional"),
+            )
+            .unwrap();
+        }
+        writeln!(out, "        ];").unwrap();
+        writeln!(out, "    }}").unwrap();
+    }
+    writeln!(out, "}}").unwrap();
+
+    let dest = Path::new(&env::var("OUT_DIR").unwrap()).join("idl.rs");
+    fs::write(dest, out).unwrap();
+}
+
+fn fields(def: &Value) -> &[Value] {
+    def["fields"].as_array().map(Vec::as_slice).unwrap_or(&[])
+}
+
+fn flag(v: &Value, key: &str) -> bool {
+    v[key].as_bool().unwrap_or(false)
+}
+
+fn bytes(v: &Value) -> String {
+    let b: Vec<String> = v
+        .as_array()
+        .expect("discriminator is not an array")
+        .iter()
+        .map(|x| x.as_u64().unwrap().to_string())
+        .collect();
+    format!("[{}]", b.join(", "))
+}
+
+fn camel(snake: &str) -> String {
+    snake
+        .split('_')
+        .map(|w| {
+            let mut c = w.chars();
+            c.next().map(|f| f.to_ascii_uppercase().to_string() + c.as_str()).unwrap_or_default()
+        })
+        .collect()
+}
+
+fn push_docs(out: &mut String, indent: &str, docs: &Value) {
+    for line in docs.as_array().map(Vec::as_slice).unwrap_or(&[]) {
+        let line = line.as_str().unwrap();
+        if line.is_empty() {
+            writeln!(out, "{indent}///").unwrap();
+        } else {
+            writeln!(out, "{indent}/// {line}").unwrap();
+        }
+    }
+}
+
+fn derives(copy: bool) -> &'static str {
+    if copy {
+        "#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]"
+    } else {
+        "#[derive(Debug, Clone, PartialEq, Eq, Default)]"
+    }
+}
+
+fn push_struct(
+    out: &mut String,
+    indent: &str,
+    name: &str,
+    fields: &[Value],
+    defs: &HashMap<&str, &Value>,
+) {
+    let copy = fields.iter().all(|f| fixed_size(&f["type"], defs));
+    writeln!(out, "{indent}{}", derives(copy)).unwrap();
+    if fields.is_empty() {
+        writeln!(out, "{indent}pub struct {name};").unwrap();
+    } else {
+        writeln!(out, "{indent}pub struct {name} {{").unwrap();
+        for f in fields {
+            push_docs(out, &format!("{indent}    "), &f["docs"]);
+            writeln!(out, "{indent}    pub {}: {},", f["name"].as_str().unwrap(), rust_type(&f["type"]))
+                .unwrap();
+        }
+        writeln!(out, "{indent}}}").unwrap();
+    }
+
+    let size: usize = fields.iter().map(|f| size(&f["type"], defs)).sum();
+    writeln!(out).unwrap();
+    writeln!(out, "{indent}impl Layout for {name} {{").unwrap();
+    writeln!(out, "{indent}    const SIZE: usize = {size};").unwrap();
+    writeln!(out).unwrap();
+    if fields.is_empty() {
+        writeln!(out, "{indent}    fn decode(_r: &mut Reader<'_>) -> Result<Self, DecodeError> {{").unwrap();
+        writeln!(out, "{indent}        Ok(Self)").unwrap();
+        writeln!(out, "{indent}    }}").unwrap();
+        writeln!(out).unwrap();
+        writeln!(out, "{indent}    fn encode(&self, _out: &mut Vec<u8>) {{}}").unwrap();
+    } else {
+        writeln!(out, "{indent}    fn decode(r: &mut Reader<'_>) -> Result<Self, DecodeError> {{").unwrap();
+        writeln!(out, "{indent}        Ok(Self {{").unwrap();
+        for f in fields {
+            writeln!(out, "{indent}            {}: Layout::decode(r)?,", f["name"].as_str().unwrap())
+                .unwrap();
+        }
+        writeln!(out, "{indent}        }})").unwrap();
+        writeln!(out, "{indent}    }}").unwrap();
+        writeln!(out).unwrap();
+        writeln!(out, "{indent}    fn encode(&self, out: &mut Vec<u8>) {{").unwrap();
+        for f in fields {
+            writeln!(out, "{indent}        self.{}.encode(out);", f["name"].as_str().unwrap()).unwrap();
+        }
+        writeln!(out, "{indent}    }}").unwrap();
+    }
+    writeln!(out, "{indent}}}").unwrap();
+}
+
+fn push_enum(out: &mut String, name: &str, def: &Value, defs: &HashMap<&str, &Value>) {
+    let variants = def["variants"].as_array().expect("enum without variants");
+    assert!(variants.len() <= 256, "{name}: too many variants for a u8 tag");
+    let vfields = |v: &Value| -> Vec<(String, Value)> {
+        fields(v)
+            .iter()
+            .map(|f| {
+                let n = f["name"].as_str().unwrap_or_else(|| panic!("{name}: tuple variants are unsupported"));
+                (n.to_string(), f["type"].clone())
+            })
+            .collect()
+    };
+    let copy = variants.iter().all(|v| vfields(v).iter().all(|(_, t)| fixed_size(t, defs)));
+    let first_is_unit = vfields(&variants[0]).is_empty();
+
+    if first_is_unit {
+        writeln!(out, "{}", derives(copy)).unwrap();
+    } else {
+        writeln!(out, "{}", derives(copy).replace(", Default", "")).unwrap();
+    }
+    writeln!(out, "pub enum {name} {{").unwrap();
+    for (i, v) in variants.iter().enumerate() {
+        push_docs(out, "    ", &v["docs"]);
+        if i == 0 && first_is_unit {
+            writeln!(out, "    #[default]").unwrap();
+        }
+        let vname = v["name"].as_str().unwrap();
+        let fs = vfields(v);
+        if fs.is_empty() {
+            writeln!(out, "    {vname},").unwrap();
+        } else {
+            let body: Vec<String> = fs.iter().map(|(n, t)| format!("{n}: {}", rust_type(t))).collect();
+            writeln!(out, "    {vname} {{ {} }},", body.join(", ")).unwrap();
+        }
+    }
+    writeln!(out, "}}").unwrap();
+
+    if !first_is_unit {
+        let fs = vfields(&variants[0]);
+        let body: Vec<String> = fs.iter().map(|(n, _)| format!("{n}: Default::default()")).collect();
+        writeln!(out).unwrap();
+        writeln!(out, "impl Default for {name} {{").unwrap();
+        writeln!(out, "    fn default() -> Self {{").unwrap();
+        writeln!(out, "        Self::{} {{ {} }}", variants[0]["name"].as_str().unwrap(), body.join(", "))
+            .unwrap();
+        writeln!(out, "    }}").unwrap();
+        writeln!(out, "}}").unwrap();
+    }
+
+    writeln!(out).unwrap();
+    writeln!(out, "impl Layout for {name} {{").unwrap();
+    writeln!(out, "    const SIZE: usize = {};", size(def, defs)).unwrap();
+    writeln!(out).unwrap();
+    writeln!(out, "    fn decode(r: &mut Reader<'_>) -> Result<Self, DecodeError> {{").unwrap();
+    writeln!(out, "        let offset = r.offset();").unwrap();
+    writeln!(out, "        let tag = u8::decode(r)?;").unwrap();
+    writeln!(out, "        if r.is_exhausted() {{").unwrap();
+    writeln!(out, "            return Ok(Self::default());").unwrap();
+    writeln!(out, "        }}").unwrap();
+    writeln!(out, "        match tag {{").unwrap();
+    for (i, v) in variants.iter().enumerate() {
+        let vname = v["name"].as_str().unwrap();
+        let fs = vfields(v);
+        if fs.is_empty() {
+            writeln!(out, "            {i} => Ok(Self::{vname}),").unwrap();
+        } else {
+            let body: Vec<String> = fs.iter().map(|(n, _)| format!("{n}: Layout::decode(r)?")).collect();
+            writeln!(out, "            {i} => Ok(Self::{vname} {{ {} }}),", body.join(", ")).unwrap();
+        }
+    }
+    writeln!(out, "            tag => Err(DecodeError::UnknownVariant {{ type_name: {name:?}, offset, tag }}),")
+        .unwrap();
+    writeln!(out, "        }}").unwrap();
+    writeln!(out, "    }}").unwrap();
+    writeln!(out).unwrap();
+    writeln!(out, "    fn encode(&self, out: &mut Vec<u8>) {{").unwrap();
+    writeln!(out, "        match self {{").unwrap();
+    for (i, v) in variants.iter().enumerate() {
+        let vname = v["name"].as_str().unwrap();
+        let fs = vfields(v);
+        if fs.is_empty() {
+            writeln!(out, "            Self::{vname} => out.push({i}),").unwrap();
+        } else {
+            let names: Vec<&str> = fs.iter().map(|(n, _)| n.as_str()).collect();
+            writeln!(out, "            Self::{vname} {{ {} }} => {{", names.join(", ")).unwrap();
+            writeln!(out, "                out.push({i});").unwrap();
+            for n in names {
+                writeln!(out, "                {n}.encode(out);").unwrap();
+            }
+            writeln!(out, "            }}").unwrap();
+        }
+    }
+    writeln!(out, "        }}").unwrap();
+    writeln!(out, "    }}").unwrap();
+    writeln!(out, "}}").unwrap();
+}
+
+fn rust_type(ty: &Value) -> String {
+    if let Some(p) = ty.as_str() {
+        return match p {
+            "bool" | "u8" | "i8" | "u16" | "i16" | "u32" | "i32" | "u64" | "i64" | "u128" | "i128" => {
+                p.to_string()
+            }
+            "pubkey" => "Pubkey".to_string(),
+            other => panic!("unsupported IDL type {other}"),
+        };
+    }
+    if let Some(inner) = ty.get("vec") {
+        return format!("Vec<{}>", rust_type(inner));
+    }
+    if let Some((inner, len)) = array(ty) {
+        return format!("[{}; {len}]", rust_type(inner));
+    }
+    if let Some(d) = ty.get("defined") {
+        return d["name"].as_str().unwrap().to_string();
+    }
+    panic!("unsupported IDL type {ty}")
+}
+
+/// Element type and `N` for `{"array": [T, N]}`.
+fn array(ty: &Value) -> Option<(&Value, usize)> {
+    let arr = ty.get("array")?.as_array()?;
+    Some((&arr[0], arr[1].as_u64().unwrap() as usize))
+}
+
+/// Encoded size with every enum at its widest variant and every vector empty.
+fn size(ty: &Value, defs: &HashMap<&str, &Value>) -> usize {
+    if let Some(p) = ty.as_str() {
+        return match p {
+            "bool" | "u8" | "i8" => 1,
+            "u16" | "i16" => 2,
+            "u32" | "i32" => 4,
+            "u64" | "i64" => 8,
+            "u128" | "i128" => 16,
+            "pubkey" => 32,
+            other => panic!("unsupported IDL type {other}"),
+        };
+    }
+    if ty.get("vec").is_some() {
+        return 4;
+    }
+    if let Some((inner, len)) = array(ty) {
+        return size(inner, defs) * len;
+    }
+    if let Some(d) = ty.get("defined") {
+        return size(defs[d["name"].as_str().unwrap()], defs);
+    }
+    match ty["kind"].as_str() {
+        Some("struct") => fields(ty).iter().map(|f| size(&f["type"], defs)).sum(),
+        Some("enum") => {
+            1 + ty["variants"]
+                .as_array()
+                .unwrap()
+                .iter()
+                .map(|v| fields(v).iter().map(|f| size(&f["type"], defs)).sum::<usize>())
+                .max()
+                .unwrap_or(0)
+        }
+        _ => panic!("unsupported IDL type {ty}"),
+    }
+}
+
+/// Whether every value of `ty` encodes to the same number of bytes or fewer
+/// (no vectors anywhere inside).
+fn fixed_size(ty: &Value, defs: &HashMap<&str, &Value>) -> bool {
+    if ty.is_string() {
+        return true;
+    }
+    if ty.get("vec").is_some() {
+        return false;
+    }
+    if let Some((inner, _)) = array(ty) {
+        return fixed_size(inner, defs);
+    }
+    if let Some(d) = ty.get("defined") {
+        return fixed_size(defs[d["name"].as_str().unwrap()], defs);
+    }
+    let all = |fs: &[Value]| fs.iter().all(|f| fixed_size(&f["type"], defs));
+    match ty["kind"].as_str() {
+        Some("struct") => all(fields(ty)),
+        Some("enum") => ty["variants"].as_array().unwrap().iter().all(|v| all(fields(v))),
+        _ => panic!("unsupported IDL type {ty}"),
+    }
+}
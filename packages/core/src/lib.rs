@@ -0,0 +1,350 @@
+//! A2A-Swap account and instruction layouts, generated from the program's
+//! Anchor IDL.
+//!
+//! `idl/a2a_swap.json` is the build artifact `scripts/build-idl.sh` writes
+//! from `programs/a2a-swap`; `build.rs` turns it into a Rust type per IDL type
+//! and account, and an argument struct per instruction in [`ix`]. The SDK,
+//! the CLI and the Worker decode accounts and encode instruction data through
+//! these types instead of hand-maintained byte offsets, so a layout change in
+//! the program reaches all three by regenerating the IDL.
+//!
+//! ```
+//! use a2a_swap_core::{ix, Account, CurveKind, Instruction, Pool};
+//!
+//! let data = ix::InitializePool { fee_rate_bps: 30, curve: CurveKind::ConstantProduct }.data();
+//! assert_eq!(data[..8], ix::InitializePool::DISCRIMINATOR);
+//!
+//! let pool = Pool { fee_rate_bps: 30, ..Default::default() };
+//! assert_eq!(Pool::from_account_data(&pool.to_account_data()), Ok(pool));
+//! ```
+//!
+//! # Forward compatibility
+//!
+//! The program only ever appends fields to an account, so decoding is lenient
+//! at the end of the data: a field the account is too short to hold decodes as
+//! its default (zero, `false`, the first enum variant), as does everything
+//! after it, and bytes past the last known field are ignored. Accounts written
+//! before a field existed therefore decode with that field at its default, and
+//! accounts written by a newer program decode with the fields this crate
+//! knows about. Callers that need a minimum size check it themselves.
+//!
+//! # Math and PDAs
+//!
+//! [`math`] is the SDK's swap, fee and StableSwap arithmetic and [`pda`] its
+//! address derivation, so agents that cannot link Solana's client crates —
+//! the Worker, browsers, other wasm hosts — get the exact numbers the SDK and
+//! the program produce.
+//!
+//! Public keys are raw `[u8; 32]`. The crate is `no_std` (it needs `alloc`)
+//! and depends only on `sha2` and `curve25519-dalek`, so it builds for
+//! `wasm32-unknown-unknown` as well as native targets. The default `std`
+//! feature only adds `std::error::Error` impls.
+
+#![no_std]
+
+extern crate alloc;
+#[cfg(feature = "std")]
+extern crate std;
+
+use alloc::vec::Vec;
+use core::fmt;
+
+pub mod math;
+pub mod pda;
+pub mod tokens;
+
+include!(concat!(env!("OUT_DIR"), "/idl.rs"));
+
+/// The IDL this crate was generated from, as JSON — what
+/// `scripts/publish-idl.sh` writes to the program's IDL account.
+pub const IDL_JSON: &str = include_str!("../idl/a2a_swap.json");
+
+/// A Solana public key as raw bytes.
+pub type Pubkey = [u8; 32];
+
+/// [`PROGRAM_ID`] as raw bytes.
+pub const PROGRAM_ADDRESS: Pubkey = pubkey(PROGRAM_ID);
+
+/// Decode a base58 public key at compile time.
+///
+/// # Panics
+///
+/// If `b58` is not valid base58 or does not decode to exactly 32 bytes; in a
+/// `const` this is a compile error.
+pub const fn pubkey(b58: &str) -> Pubkey {
+    const ALPHABET: &[u8; 58] = b"123456789ABCDEFGHJKLMNPQRSTUVWXYZabcdefghijkmnopqrstuvwxyz";
+    let s = b58.as_bytes();
+    let mut out = [0u8; 32];
+    let mut leading_ones = 0;
+    while leading_ones < s.len() && s[leading_ones] == b'1' {
+        leading_ones += 1;
+    }
+    let mut i = 0;
+    while i < s.len() {
+        let mut digit = 0;
+        while digit < 58 && ALPHABET[digit] != s[i] {
+            digit += 1;
+        }
+        assert!(digit < 58, "invalid base58 character");
+        let mut carry = digit as u32;
+        let mut j = 32;
+        while j > 0 {
+            j -= 1;
+            carry += out[j] as u32 * 58;
+            out[j] = carry as u8;
+            carry >>= 8;
+        }
+        assert!(carry == 0, "base58 key longer than 32 bytes");
+        i += 1;
+    }
+    let mut zeros = 0;
+    while zeros < 32 && out[zeros] == 0 {
+        zeros += 1;
+    }
+    // Each leading `1` is a leading zero byte; any other zero byte means the
+    // key encodes fewer than 32 bytes.
+    assert!(zeros == leading_ones, "base58 key shorter than 32 bytes");
+    out
+}
+
+// ─── Layout ───────────────────────────────────────────────────────────────────
+
+/// A type with a Borsh encoding, as Anchor lays it out on-chain.
+pub trait Layout: Sized {
+    /// Encoded size with every enum at its widest variant and every vector
+    /// empty.
+    const SIZE: usize;
+
+    /// Read `Self` from `r`; see the [crate docs](crate#forward-compatibility)
+    /// for how data that ends early decodes.
+    fn decode(r: &mut Reader<'_>) -> Result<Self, DecodeError>;
+
+    /// Append the encoding of `self` to `out`.
+    fn encode(&self, out: &mut Vec<u8>);
+}
+
+/// A program account: a [`Layout`] behind an 8-byte Anchor discriminator.
+pub trait Account: Layout {
+    /// Account type name in the IDL.
+    const NAME: &'static str;
+    /// `sha256("account:{NAME}")[..8]`.
+    const DISCRIMINATOR: [u8; 8];
+
+    /// Decode raw account data, discriminator included.
+    fn from_account_data(data: &[u8]) -> Result<Self, DecodeError> {
+        if !data.starts_with(&Self::DISCRIMINATOR) {
+            return Err(DecodeError::Discriminator { name: Self::NAME });
+        }
+        let mut r = Reader::new(data);
+        r.read::<8>();
+        Self::decode(&mut r)
+    }
+
+    /// Encode as account data, discriminator included, without padding.
+    fn to_account_data(&self) -> Vec<u8> {
+        let mut out = Self::DISCRIMINATOR.to_vec();
+        self.encode(&mut out);
+        out
+    }
+}
+
+/// An instruction's arguments.
+pub trait Instruction: Layout {
+    /// Instruction name in the IDL.
+    const NAME: &'static str;
+    /// `sha256("global:{NAME}")[..8]`.
+    const DISCRIMINATOR: [u8; 8];
+    /// The instruction's accounts, in the order the program expects them.
+    const ACCOUNTS: &'static [AccountSpec];
+
+    /// Instruction data: discriminator followed by the arguments.
+    fn data(&self) -> Vec<u8> {
+        let mut out = Self::DISCRIMINATOR.to_vec();
+        self.encode(&mut out);
+        out
+    }
+
+    /// Decode instruction data. Unlike accounts, every argument must be present.
+    fn from_data(data: &[u8]) -> Result<Self, DecodeError> {
+        if !data.starts_with(&Self::DISCRIMINATOR) {
+            return Err(DecodeError::Discriminator { name: Self::NAME });
+        }
+        let mut r = Reader::new(data);
+        r.read::<8>();
+        let ix = Self::decode(&mut r)?;
+        if r.is_exhausted() {
+            return Err(DecodeError::Truncated { offset: data.len() });
+        }
+        Ok(ix)
+    }
+}
+
+/// One entry of an instruction's account list.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AccountSpec {
+    pub name:     &'static str,
+    pub writable: bool,
+    pub signer:   bool,
+    /// May be omitted from the end of the account list.
+    pub optional: bool,
+}
+
+// ─── Reader ───────────────────────────────────────────────────────────────────
+
+/// Cursor over encoded bytes. Reads past the end return zeros and mark the
+/// reader exhausted, which is how missing trailing fields decode as defaults.
+#[derive(Debug, Clone)]
+pub struct Reader<'a> {
+    data:      &'a [u8],
+    offset:    usize,
+    exhausted: bool,
+}
+
+impl<'a> Reader<'a> {
+    pub fn new(data: &'a [u8]) -> Self {
+        Self { data, offset: 0, exhausted: false }
+    }
+
+    /// Bytes consumed so far.
+    pub fn offset(&self) -> usize {
+        self.offset
+    }
+
+    /// Whether a read has run past the end of the data.
+    pub fn is_exhausted(&self) -> bool {
+        self.exhausted
+    }
+
+    /// The next `N` bytes, or zeros once the data runs out.
+    pub fn read<const N: usize>(&mut self) -> [u8; N] {
+        match self.data.get(self.offset..self.offset + N) {
+            Some(b) if !self.exhausted => {
+                self.offset += N;
+                b.try_into().unwrap()
+            }
+            _ => {
+                self.exhausted = true;
+                self.offset = self.data.len();
+                [0; N]
+            }
+        }
+    }
+}
+
+/// Why bytes could not be decoded.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DecodeError {
+    /// The data does not start with the account's or instruction's
+    /// discriminator.
+    Discriminator { name: &'static str },
+    /// An enum tag the IDL does not define.
+    UnknownVariant { type_name: &'static str, offset: usize, tag: u8 },
+    /// A vector, or instruction data, ends before its declared contents.
+    Truncated { offset: usize },
+}
+
+impl DecodeError {
+    /// Byte offset the error refers to.
+    pub fn offset(&self) -> usize {
+        match self {
+            DecodeError::Discriminator { .. } => 0,
+            DecodeError::UnknownVariant { offset, .. } | DecodeError::Truncated { offset } => *offset,
+        }
+    }
+}
+
+impl fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DecodeError::Discriminator { name } => write!(f, "not a {name}: discriminator mismatch"),
+            DecodeError::UnknownVariant { type_name, offset, tag } => {
+                write!(f, "unknown {type_name} tag {tag} at offset {offset}")
+            }
+            DecodeError::Truncated { offset } => write!(f, "data truncated at offset {offset}"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for DecodeError {}
+
+// ─── Primitive layouts ────────────────────────────────────────────────────────
+
+macro_rules! int_layout {
+    ($($t:ty),*) => {$(
+        impl Layout for $t {
+            const SIZE: usize = core::mem::size_of::<$t>();
+
+            fn decode(r: &mut Reader<'_>) -> Result<Self, DecodeError> {
+                Ok(<$t>::from_le_bytes(r.read()))
+            }
+
+            fn encode(&self, out: &mut Vec<u8>) {
+                out.extend_from_slice(&self.to_le_bytes());
+            }
+        }
+    )*};
+}
+
+int_layout!(u8, i8, u16, i16, u32, i32, u64, i64, u128, i128);
+
+impl Layout for bool {
+    const SIZE: usize = 1;
+
+    fn decode(r: &mut Reader<'_>) -> Result<Self, DecodeError> {
+        Ok(r.read::<1>()[0] != 0)
+    }
+
+    fn encode(&self, out: &mut Vec<u8>) {
+        out.push(*self as u8);
+    }
+}
+
+/// Fixed-length arrays: `Pubkey`, IDL `[u8; N]` fields such as hashes, and
+/// arrays of other layouts such as the protocol's fee tiers.
+impl<T: Layout + Copy + Default, const N: usize> Layout for [T; N] {
+    const SIZE: usize = T::SIZE * N;
+
+    fn decode(r: &mut Reader<'_>) -> Result<Self, DecodeError> {
+        let mut out = [T::default(); N];
+        for x in &mut out {
+            *x = T::decode(r)?;
+        }
+        Ok(out)
+    }
+
+    fn encode(&self, out: &mut Vec<u8>) {
+        for x in self {
+            x.encode(out);
+        }
+    }
+}
+
+impl<T: Layout> Layout for Vec<T> {
+    const SIZE: usize = 4;
+
+    /// A missing length decodes as empty; a length the data cannot hold is
+    /// an error rather than a run of default elements.
+    fn decode(r: &mut Reader<'_>) -> Result<Self, DecodeError> {
+        let offset = r.offset();
+        let len = u32::decode(r)? as usize;
+        if r.is_exhausted() {
+            return Ok(Vec::new());
+        }
+        let mut v = Vec::with_capacity(len.min(r.data.len() - r.offset));
+        for _ in 0..len {
+            v.push(T::decode(r)?);
+            if r.is_exhausted() {
+                return Err(DecodeError::Truncated { offset });
+            }
+        }
+        Ok(v)
+    }
+
+    fn encode(&self, out: &mut Vec<u8>) {
+        (self.len() as u32).encode(out);
+        for x in self {
+            x.encode(out);
+        }
+    }
+}
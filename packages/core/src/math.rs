@@ -0,0 +1,568 @@
+//! Fee, swap and StableSwap math.
+//!
+//! Mirrors the on-chain arithmetic exactly so off-chain estimates match
+//! on-chain results. The SDK's `math` module and the Worker both call into
+//! this one.
+
+use core::fmt;
+
+use crate::{CurveKind, DynamicFee, Pool, Position};
+
+// ─── Constants ────────────────────────────────────────────────────────────────
+
+/// Default protocol fee numerator: 0.020% = 20 / 100_000, until the on-chain
+/// `ProtocolConfig` sets its own.
+pub const PROTOCOL_FEE_BPS: u128 = 20;
+/// Protocol fee denominator.
+pub const PROTOCOL_FEE_DENOMINATOR: u128 = 100_000;
+/// Basis-point denominator for LP fee.
+pub const BPS_DENOMINATOR: u128 = 10_000;
+/// Default share of the protocol fee paid to a swap's referrer: 20% =
+/// 2_000 / 10_000, until `ProtocolConfig` sets its own.
+pub const REFERRAL_SHARE_BPS: u128 = 2_000;
+/// Newton iterations for the StableSwap invariant (mirrors `STABLE_MAX_ITERATIONS`).
+const STABLE_MAX_ITERATIONS: usize = 64;
+/// Dynamic fees: accumulated price impact halves every this many seconds.
+pub const VOLATILITY_HALF_LIFE_SECS: i64 = 300;
+/// Dynamic fees: bps of accumulated price impact per extra bps of LP fee.
+pub const VOLATILITY_FEE_DIVISOR: u32 = 10;
+
+// ─── Errors ───────────────────────────────────────────────────────────────────
+
+/// Why a calculation has no answer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MathError {
+    /// An intermediate value overflowed, or an iteration did not converge.
+    Overflow,
+    /// A reserve the calculation needs is empty.
+    NoLiquidity,
+    /// An argument is outside the range the calculation accepts.
+    InvalidArgument,
+}
+
+impl fmt::Display for MathError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            MathError::Overflow        => "arithmetic overflow",
+            MathError::NoLiquidity     => "pool has no liquidity",
+            MathError::InvalidArgument => "argument out of range",
+        })
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for MathError {}
+
+type Result<T> = core::result::Result<T, MathError>;
+
+// ─── Swap quotes ──────────────────────────────────────────────────────────────
+
+/// Fee and slippage breakdown of one swap.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SwapQuote {
+    /// Protocol fee taken from `amount_in`, out of [`PROTOCOL_FEE_DENOMINATOR`].
+    pub protocol_fee:     u64,
+    /// `amount_in − protocol_fee`.
+    pub net_pool_input:   u64,
+    /// LP fee taken from `net_pool_input`; stays in the vault.
+    pub lp_fee:           u64,
+    /// `net_pool_input − lp_fee`; the amount that moves the curve.
+    pub after_fees:       u64,
+    /// Output the curve pays for `after_fees`.
+    pub amount_out:       u64,
+    /// Pure curve slippage, in percent.
+    pub price_impact_pct: f64,
+}
+
+/// Quote a swap of `amount_in` against `reserve_in` / `reserve_out`.
+///
+/// `fee_rate_bps` is the LP fee (see [`effective_fee_bps`] for dynamic-fee
+/// pools) and `protocol_fee_bps` is out of [`PROTOCOL_FEE_DENOMINATOR`].
+pub fn quote_swap(
+    curve:            CurveKind,
+    fee_rate_bps:     u16,
+    reserve_in:       u64,
+    reserve_out:      u64,
+    amount_in:        u64,
+    protocol_fee_bps: u64,
+) -> Result<SwapQuote> {
+    if reserve_in == 0 || reserve_out == 0 {
+        return Err(MathError::NoLiquidity);
+    }
+    let in_u128 = amount_in as u128;
+
+    let protocol_fee = in_u128
+        .checked_mul(protocol_fee_bps as u128)
+        .ok_or(MathError::Overflow)?
+        / PROTOCOL_FEE_DENOMINATOR;
+
+    let net_pool_input = in_u128
+        .checked_sub(protocol_fee)
+        .ok_or(MathError::Overflow)?;
+
+    let lp_fee = net_pool_input
+        .checked_mul(fee_rate_bps as u128)
+        .ok_or(MathError::Overflow)?
+        / BPS_DENOMINATOR;
+
+    let after_fees = net_pool_input
+        .checked_sub(lp_fee)
+        .ok_or(MathError::Overflow)?;
+
+    let r_in  = reserve_in  as u128;
+    let r_out = reserve_out as u128;
+
+    let (amount_out, price_impact_pct) = match curve {
+        CurveKind::ConstantProduct => {
+            let out = r_out
+                .checked_mul(after_fees)
+                .ok_or(MathError::Overflow)?
+                .checked_div(r_in.checked_add(after_fees).ok_or(MathError::Overflow)?)
+                .ok_or(MathError::Overflow)? as u64;
+            (out, after_fees as f64 / (r_in as f64 + after_fees as f64) * 100.0)
+        }
+        CurveKind::StableSwap { amp } => {
+            let (out, impact_bps) = stable_swap_out(after_fees, r_in, r_out, amp)?;
+            (out, impact_bps as f64 / 100.0)
+        }
+    };
+
+    Ok(SwapQuote {
+        protocol_fee:   protocol_fee as u64,
+        net_pool_input: net_pool_input as u64,
+        lp_fee:         lp_fee as u64,
+        after_fees:     after_fees as u64,
+        amount_out,
+        price_impact_pct,
+    })
+}
+
+// ─── Dynamic fees ─────────────────────────────────────────────────────────────
+
+impl DynamicFee {
+    /// Whether volatility-driven fees are configured (`max_fee_bps != 0`).
+    pub fn is_enabled(&self) -> bool {
+        self.max_fee_bps != 0
+    }
+}
+
+/// `volatility_bps` decayed to `now`: halved once per elapsed half-life.
+pub fn decayed_volatility(df: &DynamicFee, now: i64) -> u32 {
+    let half_lives = now.saturating_sub(df.last_update_ts).max(0) / VOLATILITY_HALF_LIFE_SECS;
+    df.volatility_bps.checked_shr(half_lives.min(32) as u32).unwrap_or(0)
+}
+
+/// LP fee (bps) a swap at unix time `now` pays on a pool with base fee
+/// `fee_rate_bps` and dynamic-fee settings `df` — the on-chain
+/// `effective_fee_bps`.
+pub fn dynamic_fee_bps(fee_rate_bps: u16, df: &DynamicFee, now: i64) -> u16 {
+    if !df.is_enabled() {
+        return fee_rate_bps;
+    }
+    let premium = decayed_volatility(df, now) / VOLATILITY_FEE_DIVISOR;
+    let fee = (fee_rate_bps as u32).saturating_add(premium);
+    fee.clamp(df.min_fee_bps as u32, df.max_fee_bps as u32) as u16
+}
+
+/// LP fee (bps) a swap on `pool` at unix time `now` pays. Equals
+/// `fee_rate_bps` unless dynamic fees are on, in which case a volatility
+/// premium is added and the result clamped to `[min_fee_bps, max_fee_bps]`.
+pub fn effective_fee_bps(pool: &Pool, now: i64) -> u16 {
+    dynamic_fee_bps(pool.fee_rate_bps, &pool.dynamic_fee, now)
+}
+
+// ─── Price impact and referrals ───────────────────────────────────────────────
+
+/// Pure price impact in basis points for any curve, rounded down — the value
+/// the on-chain `compute_swap` checks against `max_price_impact_bps`.
+pub fn curve_price_impact_bps(
+    curve:       CurveKind,
+    after_fees:  u64,
+    reserve_in:  u64,
+    reserve_out: u64,
+) -> Result<u64> {
+    match curve {
+        CurveKind::ConstantProduct => Ok(price_impact_bps(after_fees, reserve_in)),
+        CurveKind::StableSwap { amp } => Ok(stable_swap_out(
+            after_fees as u128, reserve_in as u128, reserve_out as u128, amp,
+        )?.1),
+    }
+}
+
+/// Constant-product price impact in basis points, rounded down:
+/// `after_fees × 10_000 / (reserve_in + after_fees)`.
+pub fn price_impact_bps(after_fees: u64, reserve_in: u64) -> u64 {
+    let denom = reserve_in as u128 + after_fees as u128;
+    if denom == 0 {
+        return 0;
+    }
+    (after_fees as u128 * BPS_DENOMINATOR / denom) as u64
+}
+
+/// Part of `protocol_fee` a swap's referrer receives, rounded down so the
+/// treasury keeps any dust. Mirrors the on-chain `referral_fee`.
+pub fn referral_fee(protocol_fee: u64, referral_share_bps: u64) -> u64 {
+    (protocol_fee as u128 * (referral_share_bps as u128).min(BPS_DENOMINATOR) / BPS_DENOMINATOR)
+        as u64
+}
+
+// ─── StableSwap ───────────────────────────────────────────────────────────────
+//
+// Two-coin Curve invariant with Ann = A · nⁿ = 4A:
+//   Ann·(x + y) + D = Ann·D + D³ / (4·x·y)
+// Mirrors programs/a2a-swap/src/instructions/fee_math.rs step for step.
+
+/// StableSwap invariant D for reserves `x`, `y`.
+pub fn stable_invariant(x: u128, y: u128, amp: u64) -> Result<u128> {
+    let sum = x.checked_add(y).ok_or(MathError::Overflow)?;
+    if sum == 0 {
+        return Ok(0);
+    }
+    let ann = amp as u128 * 4;
+    let mut d = sum;
+    for _ in 0..STABLE_MAX_ITERATIONS {
+        let mut d_p = d;
+        for r in [x, y] {
+            d_p = d_p
+                .checked_mul(d)
+                .ok_or(MathError::Overflow)?
+                / r.checked_mul(2).ok_or(MathError::Overflow)?.max(1);
+        }
+        let prev = d;
+        let num = ann
+            .checked_mul(sum)
+            .and_then(|v| v.checked_add(d_p.checked_mul(2)?))
+            .and_then(|v| v.checked_mul(d))
+            .ok_or(MathError::Overflow)?;
+        let den = (ann - 1)
+            .checked_mul(d)
+            .and_then(|v| v.checked_add(d_p.checked_mul(3)?))
+            .ok_or(MathError::Overflow)?;
+        d = num / den;
+        if d.abs_diff(prev) <= 1 {
+            return Ok(d);
+        }
+    }
+    Err(MathError::Overflow)
+}
+
+/// The other StableSwap reserve once one side is `x_new`, holding D fixed.
+pub fn stable_y(x_new: u128, d: u128, amp: u64) -> Result<u128> {
+    if x_new == 0 {
+        return Err(MathError::NoLiquidity);
+    }
+    let ann = amp as u128 * 4;
+    let c = d.checked_mul(d).ok_or(MathError::Overflow)? / (x_new * 2);
+    let c = c.checked_mul(d).ok_or(MathError::Overflow)? / (ann * 2);
+    let b = x_new + d / ann;
+    let mut y = d;
+    for _ in 0..STABLE_MAX_ITERATIONS {
+        let prev = y;
+        let num = y
+            .checked_mul(y)
+            .and_then(|v| v.checked_add(c))
+            .ok_or(MathError::Overflow)?;
+        let den = (y * 2 + b)
+            .checked_sub(d)
+            .filter(|&v| v > 0)
+            .ok_or(MathError::Overflow)?;
+        y = num / den;
+        if y.abs_diff(prev) <= 1 {
+            return Ok(y);
+        }
+    }
+    Err(MathError::Overflow)
+}
+
+/// StableSwap `(amount_out, price_impact_bps)` for `after_fees` entering the pool.
+///
+/// One unit is held back from the output, as on-chain. Impact is the
+/// shortfall against `after_fees` at the pre-trade marginal price.
+pub fn stable_swap_out(
+    after_fees:  u128,
+    reserve_in:  u128,
+    reserve_out: u128,
+    amp:         u64,
+) -> Result<(u64, u64)> {
+    let d = stable_invariant(reserve_in, reserve_out, amp)?;
+    let x_new = reserve_in.checked_add(after_fees).ok_or(MathError::Overflow)?;
+    let y_new = stable_y(x_new, d, amp)?;
+    let amount_out = reserve_out.saturating_sub(y_new).saturating_sub(1);
+
+    let t = stable_price_term(reserve_in, reserve_out, d, amp)?;
+    let ideal_out = after_fees
+        .checked_mul(t + reserve_out)
+        .ok_or(MathError::Overflow)?
+        / (t + reserve_in);
+    let impact_bps = if ideal_out == 0 {
+        0
+    } else {
+        ideal_out.saturating_sub(amount_out) * BPS_DENOMINATOR / ideal_out
+    };
+    Ok((amount_out as u64, impact_bps as u64))
+}
+
+/// `t = 16A·(xy/D)² / D`; the marginal price of x in y is `(t + y) / (t + x)`.
+fn stable_price_term(x: u128, y: u128, d: u128, amp: u64) -> Result<u128> {
+    if d == 0 {
+        return Ok(0);
+    }
+    let k = x.checked_mul(y).ok_or(MathError::Overflow)? / d;
+    Ok((amp as u128 * 16)
+        .checked_mul(k)
+        .and_then(|v| v.checked_mul(k))
+        .ok_or(MathError::Overflow)?
+        / d)
+}
+
+/// Marginal price of token A in token B (raw atomic units) for any curve.
+/// `0.0` when either reserve is empty.
+pub fn spot_price(curve: CurveKind, reserve_a: u64, reserve_b: u64) -> f64 {
+    if reserve_a == 0 || reserve_b == 0 {
+        return 0.0;
+    }
+    match curve {
+        CurveKind::ConstantProduct => reserve_b as f64 / reserve_a as f64,
+        CurveKind::StableSwap { amp } => {
+            let (x, y) = (reserve_a as u128, reserve_b as u128);
+            match stable_invariant(x, y, amp).and_then(|d| stable_price_term(x, y, d, amp)) {
+                Ok(t)  => (t + y) as f64 / (t + x) as f64,
+                Err(_) => reserve_b as f64 / reserve_a as f64,
+            }
+        }
+    }
+}
+
+// ─── Reverse quotes ───────────────────────────────────────────────────────────
+
+/// Smallest `amount_in` whose swap output is at least `amount_out` on a
+/// constant-product pool.
+///
+/// Reverses both fee legs of [`quote_swap`] with round-up semantics, so
+/// `quote_swap(.., amount_in_for_exact_out(..)?, ..).amount_out >= amount_out`
+/// always holds and one unit less would fall short. Each step inverts a
+/// floor-rounded forward step exactly:
+///
+/// ```text
+/// after_fees     = ceil(amount_out × reserve_in / (reserve_out − amount_out))
+/// net_pool_input = floor((after_fees − 1) × 10_000 / (10_000 − fee_rate_bps)) + 1
+/// amount_in      = floor((net_pool_input − 1) × 100_000 / (100_000 − protocol_fee_bps)) + 1
+/// ```
+///
+/// [`MathError::InvalidArgument`] when `amount_out` is not below
+/// `reserve_out` or a fee is not below its denominator;
+/// [`MathError::Overflow`] when the required input does not fit in a `u64`.
+pub fn amount_in_for_exact_out(
+    reserve_in:       u64,
+    reserve_out:      u64,
+    fee_rate_bps:     u16,
+    protocol_fee_bps: u64,
+    amount_out:       u64,
+) -> Result<u64> {
+    curve_amount_in_for_exact_out(
+        CurveKind::ConstantProduct, reserve_in, reserve_out, fee_rate_bps, protocol_fee_bps, amount_out,
+    )
+}
+
+/// [`amount_in_for_exact_out`] for any curve — the input the on-chain
+/// `swap_exact_out` charges.
+///
+/// StableSwap has no closed-form inverse: the curve leg solves for the input
+/// reserve that leaves `reserve_out − amount_out − 1` (one unit is held back,
+/// as in [`stable_swap_out`]), then steps up until the forward quote reaches
+/// `amount_out`. The result always suffices but may exceed the smallest
+/// sufficient input by the Newton solver's rounding.
+pub fn curve_amount_in_for_exact_out(
+    curve:            CurveKind,
+    reserve_in:       u64,
+    reserve_out:      u64,
+    fee_rate_bps:     u16,
+    protocol_fee_bps: u64,
+    amount_out:       u64,
+) -> Result<u64> {
+    if reserve_in == 0 || reserve_out == 0 {
+        return Err(MathError::NoLiquidity);
+    }
+    if amount_out >= reserve_out
+        || fee_rate_bps as u128 >= BPS_DENOMINATOR
+        || protocol_fee_bps as u128 >= PROTOCOL_FEE_DENOMINATOR
+    {
+        return Err(MathError::InvalidArgument);
+    }
+    if amount_out == 0 {
+        return Ok(0);
+    }
+
+    let after_fees = match curve {
+        // Curve leg: after_fees × (reserve_out − amount_out) ≥ amount_out × reserve_in
+        CurveKind::ConstantProduct => div_ceil(
+            amount_out as u128 * reserve_in as u128,
+            (reserve_out - amount_out) as u128,
+        ),
+        CurveKind::StableSwap { amp } => stable_after_fees_for_out(
+            amount_out as u128, reserve_in as u128, reserve_out as u128, amp,
+        )?,
+    };
+
+    // LP fee leg: net − floor(net × fee / 10_000) ≥ after_fees
+    let net_pool_input = invert_fee_floor(
+        after_fees,
+        BPS_DENOMINATOR - fee_rate_bps as u128,
+        BPS_DENOMINATOR,
+    )?;
+
+    // Protocol fee leg: amount_in − floor(amount_in × fee / 100_000) ≥ net_pool_input
+    let amount_in = invert_fee_floor(
+        net_pool_input,
+        PROTOCOL_FEE_DENOMINATOR - protocol_fee_bps as u128,
+        PROTOCOL_FEE_DENOMINATOR,
+    )?;
+
+    u64::try_from(amount_in).map_err(|_| MathError::Overflow)
+}
+
+/// `after_fees` whose StableSwap output is at least `amount_out`: the input
+/// reserve [`stable_y`] gives for the target output reserve, then doubling
+/// steps up while the forward [`stable_swap_out`] still falls short.
+fn stable_after_fees_for_out(
+    amount_out:  u128,
+    reserve_in:  u128,
+    reserve_out: u128,
+    amp:         u64,
+) -> Result<u128> {
+    let y_new = reserve_out
+        .checked_sub(amount_out + 1)
+        .filter(|&y| y > 0)
+        .ok_or(MathError::InvalidArgument)?;
+    let d = stable_invariant(reserve_in, reserve_out, amp)?;
+    let x_new = stable_y(y_new, d, amp)?;
+    let mut after_fees = x_new.saturating_sub(reserve_in).max(1);
+    let mut step = 1u128;
+    for _ in 0..STABLE_MAX_ITERATIONS {
+        if stable_swap_out(after_fees, reserve_in, reserve_out, amp)?.0 as u128 >= amount_out {
+            return Ok(after_fees);
+        }
+        after_fees = after_fees.checked_add(step).ok_or(MathError::Overflow)?;
+        step = step.saturating_mul(2);
+    }
+    Err(MathError::Overflow)
+}
+
+/// Smallest `x` with `x − floor(x × (den − keep) / den) ≥ target`.
+///
+/// The left side equals `ceil(x × keep / den)` and is non-decreasing in `x`,
+/// so the answer is `floor((target − 1) × den / keep) + 1` (or 0 for target 0).
+fn invert_fee_floor(target: u128, keep: u128, den: u128) -> Result<u128> {
+    if target == 0 {
+        return Ok(0);
+    }
+    (target - 1)
+        .checked_mul(den)
+        .map(|v| v / keep + 1)
+        .ok_or(MathError::Overflow)
+}
+
+fn div_ceil(n: u128, d: u128) -> u128 {
+    n / d + u128::from(n % d != 0)
+}
+
+// ─── Pending fees ─────────────────────────────────────────────────────────────
+
+/// Fees `lp_shares` accrued while the pool's fee growth moved from
+/// `checkpoint` to `fee_growth_global`, both Q64.64.
+///
+/// Mirrors the on-chain `accrue_fees`:
+/// `pending = lp_shares × (fee_growth_global − checkpoint) >> 64`
+pub fn pending_fee(lp_shares: u64, checkpoint: u128, fee_growth_global: u128) -> u64 {
+    let delta = fee_growth_global.saturating_sub(checkpoint);
+    ((lp_shares as u128).saturating_mul(delta) >> 64) as u64
+}
+
+/// `(pending_a, pending_b)` accrued since `pos` was last synced with `pool`.
+pub fn pending_fees_for_position(pos: &Position, pool: &Pool) -> (u64, u64) {
+    (
+        pending_fee(pos.lp_shares, pos.fee_growth_checkpoint_a, pool.fee_growth_global_a),
+        pending_fee(pos.lp_shares, pos.fee_growth_checkpoint_b, pool.fee_growth_global_b),
+    )
+}
+
+// ─── Liquidity ────────────────────────────────────────────────────────────────
+
+/// LP shares minted for depositing `amount_a` / `amount_b`.
+///
+/// Mirrors the on-chain `lp_shares_for_deposit`: `isqrt(a × b)` for the
+/// first deposit, otherwise the smaller of `amount × lp_supply / reserve`
+/// over both sides.
+pub fn lp_shares_for_deposit(
+    amount_a:  u64,
+    amount_b:  u64,
+    lp_supply: u64,
+    reserve_a: u64,
+    reserve_b: u64,
+) -> Result<u64> {
+    if lp_supply == 0 {
+        let product = (amount_a as u128).checked_mul(amount_b as u128).ok_or(MathError::Overflow)?;
+        return Ok(isqrt(product) as u64);
+    }
+    if reserve_a == 0 || reserve_b == 0 {
+        return Err(MathError::NoLiquidity);
+    }
+    let shares = |amount: u64, reserve: u64| {
+        (amount as u128)
+            .checked_mul(lp_supply as u128)
+            .map(|v| v / reserve as u128)
+            .ok_or(MathError::Overflow)
+    };
+    Ok(shares(amount_a, reserve_a)?.min(shares(amount_b, reserve_b)?) as u64)
+}
+
+/// The other side of a deposit of `amount` against `reserve_from`, rounded
+/// up like the on-chain `proportional_amount`.
+pub fn proportional_amount(amount: u64, reserve_from: u64, reserve_to: u64) -> Result<u64> {
+    if reserve_from == 0 || reserve_to == 0 {
+        return Err(MathError::NoLiquidity);
+    }
+    let other = div_ceil(
+        (amount as u128).checked_mul(reserve_to as u128).ok_or(MathError::Overflow)?,
+        reserve_from as u128,
+    );
+    u64::try_from(other).map_err(|_| MathError::Overflow)
+}
+
+/// Integer square root, rounded down (Babylonian method, as on-chain).
+fn isqrt(n: u128) -> u128 {
+    if n == 0 {
+        return 0;
+    }
+    let mut x = n;
+    let mut y = (x >> 1) + (x & 1);
+    while y < x {
+        x = y;
+        y = (y + n / y) >> 1;
+    }
+    x
+}
+
+// ─── Valuation ────────────────────────────────────────────────────────────────
+
+/// Underlying `(amount_a, amount_b)` of `lp_shares` — their pro-rata share of
+/// the reserves, floored like the on-chain `remove_liquidity`.
+pub fn lp_underlying(lp_shares: u64, lp_supply: u64, reserve_a: u64, reserve_b: u64) -> (u64, u64) {
+    if lp_supply == 0 {
+        return (0, 0);
+    }
+    let share = |reserve: u64| (lp_shares as u128 * reserve as u128 / lp_supply as u128) as u64;
+    (share(reserve_a), share(reserve_b))
+}
+
+/// Value of `amount` of an asset in quote units at the pool's spot price
+/// `reserve_quote / reserve_asset`, floored and saturating at `u64::MAX`.
+///
+/// `None` when the asset side of the pool is empty (no price).
+pub fn spot_value(amount: u64, reserve_asset: u64, reserve_quote: u64) -> Option<u64> {
+    if reserve_asset == 0 {
+        return None;
+    }
+    let value = amount as u128 * reserve_quote as u128 / reserve_asset as u128;
+    Some(u64::try_from(value).unwrap_or(u64::MAX))
+}
@@ -0,0 +1,223 @@
+//! Program-derived addresses.
+//!
+//! The same derivation `solana_program::pubkey::Pubkey::find_program_address`
+//! performs — SHA-256 of the seeds, bump, program ID and
+//! `"ProgramDerivedAddress"`, skipping candidates on the Ed25519 curve — on
+//! `sha2` and `curve25519-dalek` alone, so it also runs in wasm.
+//!
+//! Seeds mirror `programs/a2a-swap/src/constants.rs`. Pool mints are not
+//! sorted: a pair's pool lives at whichever ordering it was created with.
+
+use curve25519_dalek::edwards::CompressedEdwardsY;
+use sha2::{Digest, Sha256};
+
+use crate::{pubkey, Pubkey};
+
+// ─── Well-known program IDs ───────────────────────────────────────────────────
+
+/// SPL Token program.
+pub const TOKEN_PROGRAM_ID: Pubkey = pubkey("TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA");
+/// Token-2022 program.
+pub const TOKEN_2022_PROGRAM_ID: Pubkey = pubkey("TokenzQdBNbLqP5VEhdkAS6EPFLC1PHnBqCXEpPxuEb");
+/// Associated Token Account program.
+pub const ATA_PROGRAM_ID: Pubkey = pubkey("ATokenGPvbdGVxr1b2hvZbsiqW5xWH25efTNsLJA8knL");
+/// Upgradeable BPF loader, owner of every program's `ProgramData` account.
+pub const BPF_LOADER_UPGRADEABLE_ID: Pubkey = pubkey("BPFLoaderUpgradeab1e11111111111111111111111");
+
+// ─── Seeds ────────────────────────────────────────────────────────────────────
+
+pub const POOL_SEED:           &[u8] = b"pool";
+pub const POSITION_SEED:       &[u8] = b"position";
+pub const POOL_AUTHORITY_SEED: &[u8] = b"pool_authority";
+pub const TREASURY_SEED:       &[u8] = b"treasury";
+pub const RECEIPT_SEED:        &[u8] = b"receipt";
+pub const RANGE_POOL_SEED:     &[u8] = b"range_pool";
+pub const RANGE_POSITION_SEED: &[u8] = b"range_position";
+pub const DELEGATE_SEED:       &[u8] = b"delegate";
+pub const SWAP_COMMITMENT_SEED: &[u8] = b"swap_commitment";
+pub const GOVERNANCE_SEED:     &[u8] = b"governance";
+pub const PROPOSAL_SEED:       &[u8] = b"proposal";
+pub const VOTE_SEED:           &[u8] = b"vote";
+pub const VOTE_VAULT_SEED:     &[u8] = b"vote_vault";
+pub const AGENT_VOLUME_SEED:   &[u8] = b"agent_volume";
+pub const INTENT_NONCE_SEED:   &[u8] = b"intent_nonce";
+pub const TRADER_PASS_SEED:    &[u8] = b"trader_pass";
+/// Seed of a locker program's signing PDA, derived under the locker.
+pub const LOCK_AUTHORITY_SEED: &[u8] = b"lock_authority";
+
+/// Longest single seed the runtime accepts.
+pub const MAX_SEED_LEN: usize = 32;
+/// Most seeds the runtime accepts, bump included.
+pub const MAX_SEEDS: usize = 16;
+
+// ─── Derivation ───────────────────────────────────────────────────────────────
+
+/// The address `seeds` (bump included) derive under `program_id`, or `None`
+/// if the seeds are over the runtime's limits or the hash lands on the curve.
+pub fn create_program_address(seeds: &[&[u8]], program_id: &Pubkey) -> Option<Pubkey> {
+    if seeds.len() > MAX_SEEDS || seeds.iter().any(|s| s.len() > MAX_SEED_LEN) {
+        return None;
+    }
+    let mut h = Sha256::new();
+    for seed in seeds {
+        h.update(seed);
+    }
+    h.update(program_id);
+    h.update(b"ProgramDerivedAddress");
+    let candidate: Pubkey = h.finalize().into();
+    (!is_on_curve(&candidate)).then_some(candidate)
+}
+
+/// The canonical PDA for `seeds` under `program_id` and its bump: the first
+/// bump from 255 down whose address is off the curve.
+///
+/// # Panics
+///
+/// If no bump yields a valid address, as `find_program_address` does.
+pub fn find_program_address(seeds: &[&[u8]], program_id: &Pubkey) -> (Pubkey, u8) {
+    try_find_program_address(seeds, program_id)
+        .expect("Unable to find a viable program address bump seed")
+}
+
+/// [`find_program_address`] without the panic.
+pub fn try_find_program_address(seeds: &[&[u8]], program_id: &Pubkey) -> Option<(Pubkey, u8)> {
+    if seeds.len() >= MAX_SEEDS {
+        return None;
+    }
+    for bump in (0..=u8::MAX).rev() {
+        let bump_seed = [bump];
+        let mut with_bump = [&[][..]; MAX_SEEDS];
+        with_bump[..seeds.len()].copy_from_slice(seeds);
+        with_bump[seeds.len()] = &bump_seed;
+        if let Some(address) = create_program_address(&with_bump[..=seeds.len()], program_id) {
+            return Some((address, bump));
+        }
+    }
+    None
+}
+
+/// Whether `bytes` decompress to an Ed25519 point; PDAs must not.
+pub fn is_on_curve(bytes: &Pubkey) -> bool {
+    CompressedEdwardsY(*bytes).decompress().is_some()
+}
+
+// ─── Program accounts ─────────────────────────────────────────────────────────
+
+/// Derive the pool PDA for the given mint pair.
+pub fn derive_pool(mint_a: &Pubkey, mint_b: &Pubkey, program_id: &Pubkey) -> (Pubkey, u8) {
+    find_program_address(&[POOL_SEED, mint_a, mint_b], program_id)
+}
+
+/// Derive the pool-authority PDA that signs for vault transfers.
+pub fn derive_pool_authority(pool: &Pubkey, program_id: &Pubkey) -> (Pubkey, u8) {
+    find_program_address(&[POOL_AUTHORITY_SEED, pool], program_id)
+}
+
+/// Derive the per-agent position PDA for a pool.
+pub fn derive_position(pool: &Pubkey, owner: &Pubkey, program_id: &Pubkey) -> (Pubkey, u8) {
+    find_program_address(&[POSITION_SEED, pool, owner], program_id)
+}
+
+/// Derive the LP receipt asset (Metaplex Core) for a position.
+pub fn derive_receipt(position: &Pubkey, program_id: &Pubkey) -> (Pubkey, u8) {
+    find_program_address(&[RECEIPT_SEED, position], program_id)
+}
+
+/// Derive the concentrated-liquidity pool PDA for the given mint pair.
+pub fn derive_range_pool(mint_a: &Pubkey, mint_b: &Pubkey, program_id: &Pubkey) -> (Pubkey, u8) {
+    find_program_address(&[RANGE_POOL_SEED, mint_a, mint_b], program_id)
+}
+
+/// Derive the range-position PDA — one per owner and `[tick_lower, tick_upper)`.
+pub fn derive_range_position(
+    pool:       &Pubkey,
+    owner:      &Pubkey,
+    tick_lower: i32,
+    tick_upper: i32,
+    program_id: &Pubkey,
+) -> (Pubkey, u8) {
+    find_program_address(
+        &[
+            RANGE_POSITION_SEED,
+            pool,
+            owner,
+            &tick_lower.to_le_bytes(),
+            &tick_upper.to_le_bytes(),
+        ],
+        program_id,
+    )
+}
+
+/// Derive the global treasury PDA.
+pub fn derive_treasury(program_id: &Pubkey) -> (Pubkey, u8) {
+    find_program_address(&[TREASURY_SEED], program_id)
+}
+
+/// Derive the session-key `Delegate` PDA — one per owner token account.
+pub fn derive_delegate(owner: &Pubkey, token_account: &Pubkey, program_id: &Pubkey) -> (Pubkey, u8) {
+    find_program_address(&[DELEGATE_SEED, owner, token_account], program_id)
+}
+
+/// Derive the commit-reveal `SwapCommitment` PDA — one per agent per pool.
+pub fn derive_swap_commitment(pool: &Pubkey, agent: &Pubkey, program_id: &Pubkey) -> (Pubkey, u8) {
+    find_program_address(&[SWAP_COMMITMENT_SEED, pool, agent], program_id)
+}
+
+/// Derive the global `Governance` PDA.
+pub fn derive_governance(program_id: &Pubkey) -> (Pubkey, u8) {
+    find_program_address(&[GOVERNANCE_SEED], program_id)
+}
+
+/// Derive the governance vote vault, which escrows every voter's tokens.
+pub fn derive_vote_vault(program_id: &Pubkey) -> (Pubkey, u8) {
+    find_program_address(&[VOTE_VAULT_SEED], program_id)
+}
+
+/// Derive the `Proposal` PDA for proposal number `id`.
+pub fn derive_proposal(id: u64, program_id: &Pubkey) -> (Pubkey, u8) {
+    find_program_address(&[PROPOSAL_SEED, &id.to_le_bytes()], program_id)
+}
+
+/// Derive a voter's `VoteRecord` PDA on a proposal.
+pub fn derive_vote_record(proposal: &Pubkey, voter: &Pubkey, program_id: &Pubkey) -> (Pubkey, u8) {
+    find_program_address(&[VOTE_SEED, proposal, voter], program_id)
+}
+
+/// Derive an agent's `AgentVolume` PDA (fee tier volume tracking).
+pub fn derive_agent_volume(agent: &Pubkey, program_id: &Pubkey) -> (Pubkey, u8) {
+    find_program_address(&[AGENT_VOLUME_SEED, agent], program_id)
+}
+
+/// Derive an agent's `IntentNonce` PDA (signed swap intents).
+pub fn derive_intent_nonce(agent: &Pubkey, program_id: &Pubkey) -> (Pubkey, u8) {
+    find_program_address(&[INTENT_NONCE_SEED, agent], program_id)
+}
+
+/// Derive a trader's `TraderPass` PDA on a permissioned pool.
+pub fn derive_trader_pass(pool: &Pubkey, trader: &Pubkey, program_id: &Pubkey) -> (Pubkey, u8) {
+    find_program_address(&[TRADER_PASS_SEED, pool, trader], program_id)
+}
+
+// ─── Other programs' accounts ─────────────────────────────────────────────────
+
+/// Derive the PDA a locker program signs `lock_position` /
+/// `unlock_position` with, under `locker_program`.
+pub fn derive_lock_authority(locker_program: &Pubkey) -> (Pubkey, u8) {
+    find_program_address(&[LOCK_AUTHORITY_SEED], locker_program)
+}
+
+/// Derive the Associated Token Account for a wallet + mint.
+pub fn derive_ata(wallet: &Pubkey, mint: &Pubkey) -> Pubkey {
+    find_program_address(&[wallet, &TOKEN_PROGRAM_ID, mint], &ATA_PROGRAM_ID).0
+}
+
+/// Derive the Associated Token Account for a wallet + mint owned by
+/// `token_program` ([`TOKEN_PROGRAM_ID`] or [`TOKEN_2022_PROGRAM_ID`]).
+pub fn derive_ata_for_program(wallet: &Pubkey, mint: &Pubkey, token_program: &Pubkey) -> Pubkey {
+    find_program_address(&[wallet, token_program, mint], &ATA_PROGRAM_ID).0
+}
+
+/// Derive the upgradeable-loader `ProgramData` account of `program_id`.
+pub fn derive_program_data(program_id: &Pubkey) -> Pubkey {
+    find_program_address(&[program_id], &BPF_LOADER_UPGRADEABLE_ID).0
+}
@@ -0,0 +1,45 @@
+//! Built-in token symbols (mainnet-beta).
+//!
+//! The one table of symbols every front end recognises without a token list:
+//! the SDK's `TokenRegistry` starts from it, the CLI resolves `--in SOL`
+//! through that, and the Worker maps `tokenIn: "USDC"` with [`known_token`].
+
+/// A token with a well-known symbol.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct KnownToken {
+    /// Ticker, upper case.
+    pub symbol:   &'static str,
+    /// Base58 mint address.
+    pub mint:     &'static str,
+    /// Mint decimals.
+    pub decimals: u8,
+    /// Display name.
+    pub name:     &'static str,
+}
+
+/// SOL (as wrapped SOL), USDC and USDT.
+pub const KNOWN_TOKENS: &[KnownToken] = &[
+    KnownToken {
+        symbol:   "SOL",
+        mint:     "So11111111111111111111111111111111111111112",
+        decimals: 9,
+        name:     "Wrapped SOL",
+    },
+    KnownToken {
+        symbol:   "USDC",
+        mint:     "EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v",
+        decimals: 6,
+        name:     "USD Coin",
+    },
+    KnownToken {
+        symbol:   "USDT",
+        mint:     "Es9vMFrzaCERmJfrF4H2FYD4KCoNkY11McCe8BenwNYB",
+        decimals: 6,
+        name:     "USDT",
+    },
+];
+
+/// Look up a built-in token by symbol, case-insensitively.
+pub fn known_token(symbol: &str) -> Option<&'static KnownToken> {
+    KNOWN_TOKENS.iter().find(|t| t.symbol.eq_ignore_ascii_case(symbol))
+}
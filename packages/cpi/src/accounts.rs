@@ -0,0 +1,178 @@
+//! Account structs for [`crate::cpi`], one per instruction, with fields in
+//! the program's order.
+//!
+//! Writable and signer flags are the IDL's (`a2a_swap_core::ix`), not the
+//! `AccountInfo`s', so a PDA signing through `signer_seeds` is marked a
+//! signer without being one in the outer transaction.
+
+use anchor_lang::prelude::*;
+use a2a_swap_core::{ix, AccountSpec, Instruction};
+
+use crate::ID;
+
+/// Metas for `accounts` against the IDL's `specs`. A missing optional
+/// account is passed as the program ID, which Anchor reads as `None`;
+/// missing optionals at the end are left off.
+fn metas(specs: &[AccountSpec], accounts: &[Option<&AccountInfo<'_>>]) -> Vec<AccountMeta> {
+    debug_assert_eq!(specs.len(), accounts.len());
+    let used = accounts.iter().rposition(Option::is_some).map_or(0, |i| i + 1);
+    specs
+        .iter()
+        .zip(accounts)
+        .take(used)
+        .map(|(spec, account)| match account {
+            Some(info) if spec.writable => AccountMeta::new(*info.key, spec.signer),
+            Some(info) => AccountMeta::new_readonly(*info.key, spec.signer),
+            None => AccountMeta::new_readonly(ID, false),
+        })
+        .collect()
+}
+
+/// Accounts for instructions without optional accounts: the struct, its
+/// metas against `$ix`'s IDL accounts, and its infos in the same order.
+macro_rules! cpi_accounts {
+    ($(#[$doc:meta])* $name:ident => $ix:ty { $($(#[$fdoc:meta])* $field:ident),+ $(,)? }) => {
+        $(#[$doc])*
+        #[derive(Clone)]
+        pub struct $name<'info> {
+            $($(#[$fdoc])* pub $field: AccountInfo<'info>,)+
+        }
+
+        impl ToAccountMetas for $name<'_> {
+            fn to_account_metas(&self, _is_signer: Option<bool>) -> Vec<AccountMeta> {
+                metas(<$ix>::ACCOUNTS, &[$(Some(&self.$field)),+])
+            }
+        }
+
+        impl<'info> ToAccountInfos<'info> for $name<'info> {
+            fn to_account_infos(&self) -> Vec<AccountInfo<'info>> {
+                vec![$(self.$field.clone()),+]
+            }
+        }
+    };
+}
+
+/// Accounts for [`crate::cpi::swap`].
+#[derive(Clone)]
+pub struct Swap<'info> {
+    /// Owner of the token accounts; signs (a PDA signs through the context's seeds).
+    pub agent:             AccountInfo<'info>,
+    pub pool:              AccountInfo<'info>,
+    pub pool_authority:    AccountInfo<'info>,
+    pub token_a_vault:     AccountInfo<'info>,
+    pub token_b_vault:     AccountInfo<'info>,
+    /// The agent's account for the token sold.
+    pub agent_token_in:    AccountInfo<'info>,
+    /// The agent's account for the token bought.
+    pub agent_token_out:   AccountInfo<'info>,
+    /// [`crate::pda::treasury`].
+    pub treasury:          AccountInfo<'info>,
+    /// The treasury's associated token account for the token sold.
+    pub treasury_token_in: AccountInfo<'info>,
+    pub token_program:     AccountInfo<'info>,
+    /// Referrer's account for the token sold, paid part of the protocol fee.
+    pub referrer_token:    Option<AccountInfo<'info>>,
+    /// The agent's [`crate::pda::agent_volume`], for fee discount tiers.
+    pub agent_volume:      Option<AccountInfo<'info>>,
+    /// The agent's [`crate::pda::trader_pass`], required on permissioned pools.
+    pub trader_pass:       Option<AccountInfo<'info>>,
+    /// The agent's token account of the pool's gate mint, required on
+    /// token-gated pools.
+    pub gate_token:        Option<AccountInfo<'info>>,
+}
+
+impl ToAccountMetas for Swap<'_> {
+    fn to_account_metas(&self, _is_signer: Option<bool>) -> Vec<AccountMeta> {
+        metas(ix::Swap::ACCOUNTS, &[
+            Some(&self.agent),
+            Some(&self.pool),
+            Some(&self.pool_authority),
+            Some(&self.token_a_vault),
+            Some(&self.token_b_vault),
+            Some(&self.agent_token_in),
+            Some(&self.agent_token_out),
+            Some(&self.treasury),
+            Some(&self.treasury_token_in),
+            Some(&self.token_program),
+            self.referrer_token.as_ref(),
+            self.agent_volume.as_ref(),
+            self.trader_pass.as_ref(),
+            self.gate_token.as_ref(),
+        ])
+    }
+}
+
+impl<'info> ToAccountInfos<'info> for Swap<'info> {
+    fn to_account_infos(&self) -> Vec<AccountInfo<'info>> {
+        let mut infos = vec![
+            self.agent.clone(),
+            self.pool.clone(),
+            self.pool_authority.clone(),
+            self.token_a_vault.clone(),
+            self.token_b_vault.clone(),
+            self.agent_token_in.clone(),
+            self.agent_token_out.clone(),
+            self.treasury.clone(),
+            self.treasury_token_in.clone(),
+            self.token_program.clone(),
+        ];
+        infos.extend(self.referrer_token.iter().cloned());
+        infos.extend(self.agent_volume.iter().cloned());
+        infos.extend(self.trader_pass.iter().cloned());
+        infos.extend(self.gate_token.iter().cloned());
+        infos
+    }
+}
+
+cpi_accounts! {
+    /// Accounts for [`crate::cpi::provide_liquidity`]. `agent` pays for and
+    /// owns the position; pass LP receipt accounts as remaining accounts.
+    ProvideLiquidity => ix::ProvideLiquidity {
+        agent, pool, pool_authority,
+        /// [`crate::pda::position`] for the pool and agent; created on first deposit.
+        position,
+        token_a_vault, token_b_vault, agent_token_a, agent_token_b, token_program, system_program, rent,
+    }
+}
+
+cpi_accounts! {
+    /// Accounts for [`crate::cpi::remove_liquidity`].
+    RemoveLiquidity => ix::RemoveLiquidity {
+        agent, pool, pool_authority, position, token_a_vault, token_b_vault, agent_token_a, agent_token_b,
+        token_program,
+    }
+}
+
+cpi_accounts! {
+    /// Accounts for [`crate::cpi::claim_fees`].
+    ClaimFees => ix::ClaimFees {
+        agent, pool, pool_authority, position, token_a_vault, token_b_vault, agent_token_a, agent_token_b,
+        token_program,
+    }
+}
+
+cpi_accounts! {
+    /// Accounts for [`crate::cpi::lock_position`].
+    LockPosition => ix::LockPosition {
+        /// The position's owner; signs in the outer transaction.
+        owner,
+        position,
+        /// [`crate::pda::lock_authority`] of `locker_program`.
+        locker_authority,
+        /// The calling program.
+        locker_program,
+        /// [`crate::pda::treasury`].
+        treasury,
+    }
+}
+
+cpi_accounts! {
+    /// Accounts for [`crate::cpi::unlock_position`].
+    UnlockPosition => ix::UnlockPosition {
+        position,
+        /// [`crate::pda::lock_authority`] of `locker_program`.
+        locker_authority,
+        /// The calling program; must be the one that locked the position.
+        locker_program,
+    }
+}
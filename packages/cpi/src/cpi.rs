@@ -0,0 +1,105 @@
+//! CPI calls into A2A-Swap. Each encodes its arguments through the IDL and
+//! invokes `ctx.program` with the context's accounts, remaining accounts and
+//! signer seeds.
+
+use anchor_lang::{
+    prelude::*,
+    solana_program::{instruction::Instruction, program::invoke_signed},
+};
+use a2a_swap_core::{ix, Instruction as _};
+
+use crate::accounts::{
+    ClaimFees, LockPosition, ProvideLiquidity, RemoveLiquidity, Swap, UnlockPosition,
+};
+
+fn invoke<'info, T: ToAccountMetas + ToAccountInfos<'info>>(
+    ctx: CpiContext<'_, '_, '_, 'info, T>,
+    data: Vec<u8>,
+) -> Result<()> {
+    let ix = Instruction {
+        program_id: ctx.program.key(),
+        accounts:   ctx.to_account_metas(None),
+        data,
+    };
+    invoke_signed(&ix, &ctx.to_account_infos(), ctx.signer_seeds).map_err(Into::into)
+}
+
+/// Sell exactly `amount_in` for at least `min_amount_out`; `a_to_b` sells
+/// the pool's token A. `max_price_impact_bps` caps the price impact (0 = no
+/// cap); the swap fails after `deadline_unix` (0 = no deadline).
+pub fn swap<'info>(
+    ctx: CpiContext<'_, '_, '_, 'info, Swap<'info>>,
+    amount_in: u64,
+    min_amount_out: u64,
+    a_to_b: bool,
+    max_price_impact_bps: u16,
+    deadline_unix: i64,
+) -> Result<()> {
+    invoke(ctx, ix::Swap { amount_in, min_amount_out, a_to_b, max_price_impact_bps, deadline_unix }.data())
+}
+
+/// Deposit up to `amount_a` / `amount_b` for at least `min_lp` LP shares.
+/// `auto_compound` reinvests the position's fees once they reach
+/// `compound_threshold`.
+pub fn provide_liquidity<'info>(
+    ctx: CpiContext<'_, '_, '_, 'info, ProvideLiquidity<'info>>,
+    amount_a: u64,
+    amount_b: u64,
+    min_lp: u64,
+    auto_compound: bool,
+    compound_threshold: u64,
+) -> Result<()> {
+    invoke(
+        ctx,
+        ix::ProvideLiquidity { amount_a, amount_b, min_lp, auto_compound, compound_threshold }.data(),
+    )
+}
+
+/// Deposit `amount` of token A (`fixed_a`) or token B; A2A-Swap computes
+/// the other side from its reserves and fails above `max_other` (0 = no
+/// cap). Takes the same accounts as [`provide_liquidity`].
+#[allow(clippy::too_many_arguments)]
+pub fn provide_liquidity_proportional<'info>(
+    ctx: CpiContext<'_, '_, '_, 'info, ProvideLiquidity<'info>>,
+    amount: u64,
+    fixed_a: bool,
+    max_other: u64,
+    min_lp: u64,
+    auto_compound: bool,
+    compound_threshold: u64,
+) -> Result<()> {
+    let args = ix::ProvideLiquidityProportional {
+        amount, fixed_a, max_other, min_lp, auto_compound, compound_threshold,
+    };
+    invoke(ctx, args.data())
+}
+
+/// Burn `lp_shares` for at least `min_a` / `min_b` of the pool's tokens.
+pub fn remove_liquidity<'info>(
+    ctx: CpiContext<'_, '_, '_, 'info, RemoveLiquidity<'info>>,
+    lp_shares: u64,
+    min_a: u64,
+    min_b: u64,
+) -> Result<()> {
+    invoke(ctx, ix::RemoveLiquidity { lp_shares, min_a, min_b }.data())
+}
+
+/// Pay out the position's accrued fees, or compound them if it
+/// auto-compounds.
+pub fn claim_fees<'info>(ctx: CpiContext<'_, '_, '_, 'info, ClaimFees<'info>>) -> Result<()> {
+    invoke(ctx, ix::ClaimFees.data())
+}
+
+/// Hold the owner's position as collateral for the calling program, which
+/// must be one of the protocol's approved lockers. The owner signs in the
+/// outer transaction; `locker_authority` signs through the context's seeds.
+pub fn lock_position<'info>(ctx: CpiContext<'_, '_, '_, 'info, LockPosition<'info>>) -> Result<()> {
+    invoke(ctx, ix::LockPosition.data())
+}
+
+/// Release a position the calling program locked.
+pub fn unlock_position<'info>(
+    ctx: CpiContext<'_, '_, '_, 'info, UnlockPosition<'info>>,
+) -> Result<()> {
+    invoke(ctx, ix::UnlockPosition.data())
+}
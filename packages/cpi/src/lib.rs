@@ -0,0 +1,57 @@
+//! Compose with A2A-Swap from another Anchor program.
+//!
+//! [`cpi`] has one function per composable instruction, taking a
+//! [`CpiContext`](anchor_lang::context::CpiContext) over the matching
+//! [`accounts`] struct, the same shape Anchor generates for a program's own
+//! `cpi` feature. Instruction data and account flags come from
+//! `a2a-swap-core`, which is generated from the program's IDL, so this crate
+//! never copies the program's account structs and links nothing but
+//! `anchor-lang`.
+//!
+//! ```ignore
+//! use a2a_swap_cpi::{accounts::Swap, cpi, A2ASwap};
+//!
+//! // A vault PDA selling token A out of its own token account.
+//! let seeds: &[&[&[u8]]] = &[&[b"vault", &[ctx.bumps.vault]]];
+//! let swap = Swap {
+//!     agent:             ctx.accounts.vault.to_account_info(),
+//!     pool:              ctx.accounts.pool.to_account_info(),
+//!     pool_authority:    ctx.accounts.pool_authority.to_account_info(),
+//!     token_a_vault:     ctx.accounts.token_a_vault.to_account_info(),
+//!     token_b_vault:     ctx.accounts.token_b_vault.to_account_info(),
+//!     agent_token_in:    ctx.accounts.vault_token_a.to_account_info(),
+//!     agent_token_out:   ctx.accounts.vault_token_b.to_account_info(),
+//!     treasury:          ctx.accounts.treasury.to_account_info(),
+//!     treasury_token_in: ctx.accounts.treasury_token_a.to_account_info(),
+//!     token_program:     ctx.accounts.token_program.to_account_info(),
+//!     referrer_token:    None,
+//!     agent_volume:      None,
+//!     trader_pass:       None,
+//!     gate_token:        None,
+//! };
+//! let program = ctx.accounts.a2a_swap.to_account_info();
+//! cpi::swap(CpiContext::new_with_signer(program, swap, seeds), amount_in, min_out, true, 300, 0)?;
+//! ```
+//!
+//! Take the program in your own accounts struct as
+//! `Program<'info, A2ASwap>`, and derive the A2A-Swap accounts it needs with
+//! [`pda`].
+
+use anchor_lang::prelude::*;
+
+pub mod accounts;
+pub mod cpi;
+pub mod pda;
+
+/// The A2A-Swap program ID.
+pub const ID: Pubkey = Pubkey::new_from_array(a2a_swap_core::PROGRAM_ADDRESS);
+
+/// Marker for `Program<'info, A2ASwap>`.
+#[derive(Debug, Clone, Copy)]
+pub struct A2ASwap;
+
+impl Id for A2ASwap {
+    fn id() -> Pubkey {
+        ID
+    }
+}
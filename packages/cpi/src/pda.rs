@@ -0,0 +1,47 @@
+//! A2A-Swap program addresses, derived with the runtime's
+//! `find_program_address` (a syscall on-chain).
+
+use anchor_lang::prelude::Pubkey;
+use a2a_swap_core::pda::{
+    AGENT_VOLUME_SEED, LOCK_AUTHORITY_SEED, POOL_AUTHORITY_SEED, POOL_SEED, POSITION_SEED,
+    TRADER_PASS_SEED, TREASURY_SEED,
+};
+
+use crate::ID;
+
+/// Pool for `mint_a` / `mint_b`, in the order the pool was created with.
+pub fn pool(mint_a: &Pubkey, mint_b: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[POOL_SEED, mint_a.as_ref(), mint_b.as_ref()], &ID)
+}
+
+/// The PDA that owns `pool`'s vaults.
+pub fn pool_authority(pool: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[POOL_AUTHORITY_SEED, pool.as_ref()], &ID)
+}
+
+/// `owner`'s LP position in `pool`.
+pub fn position(pool: &Pubkey, owner: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[POSITION_SEED, pool.as_ref(), owner.as_ref()], &ID)
+}
+
+/// The protocol treasury, which also holds the `ProtocolConfig`.
+pub fn treasury() -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[TREASURY_SEED], &ID)
+}
+
+/// `agent`'s volume tracker for fee discount tiers.
+pub fn agent_volume(agent: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[AGENT_VOLUME_SEED, agent.as_ref()], &ID)
+}
+
+/// `trader`'s pass on the permissioned `pool`, from `register_trader`.
+pub fn trader_pass(pool: &Pubkey, trader: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[TRADER_PASS_SEED, pool.as_ref(), trader.as_ref()], &ID)
+}
+
+/// The calling program's signing PDA for [`crate::cpi::lock_position`] /
+/// [`crate::cpi::unlock_position`]; pass your own program ID. Sign with
+/// `&[LOCK_AUTHORITY_SEED, &[bump]]`.
+pub fn lock_authority(locker_program: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[LOCK_AUTHORITY_SEED], locker_program)
+}
@@ -30,7 +30,7 @@ const CAPABILITY_CARD = {
   },
   capabilities: {
     streaming: false,
-    pushNotifications: false,
+    pushNotifications: true,
     autonomousExecution: true,
     approvalMode: true,
     autoCompound: true,
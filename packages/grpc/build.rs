@@ -0,0 +1,8 @@
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    println!("cargo:rerun-if-changed=proto");
+    let descriptors = protox::compile(["a2a_swap.proto"], ["proto"])?;
+    tonic_prost_build::configure()
+        .build_client(true)
+        .compile_fds(descriptors)?;
+    Ok(())
+}
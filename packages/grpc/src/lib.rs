@@ -0,0 +1,21 @@
+//! gRPC front end for the A2A-Swap Rust SDK.
+//!
+//! `a2a-swap-grpc` serves the `a2a_swap.v1.A2ASwap` service from
+//! `proto/a2a_swap.proto` — Simulate, Convert, PoolInfo, Positions and a
+//! streaming PoolUpdates — straight from a Solana RPC endpoint, for agent
+//! stacks that want a typed, low-latency interface on their own
+//! infrastructure instead of the hosted HTTP API. Generate a client in any
+//! language from the proto, or use [`proto::a2a_swap_client`] from Rust.
+//!
+//! The server never holds keys: Convert returns an unsigned transaction for
+//! the agent to sign.
+
+pub mod service;
+
+/// Types and client / server stubs generated from `proto/a2a_swap.proto`.
+#[allow(clippy::all)]
+pub mod proto {
+    tonic::include_proto!("a2a_swap.v1");
+}
+
+pub use service::A2ASwapService;
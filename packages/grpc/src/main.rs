@@ -0,0 +1,50 @@
+//! `a2a-swap-grpc` — serve the A2A-Swap gRPC API.
+//!
+//! ```text
+//! a2a-swap-grpc --listen 0.0.0.0:50051 --rpc-url https://my-node.example
+//! ```
+
+use std::net::SocketAddr;
+use std::str::FromStr;
+
+use a2a_swap_grpc::{proto::a2a_swap_server::A2aSwapServer, A2ASwapService};
+use anyhow::{Context, Result};
+use clap::Parser;
+use solana_sdk::pubkey::Pubkey;
+
+#[derive(Parser)]
+#[command(name = "a2a-swap-grpc", version, about = "A2A-Swap gRPC server")]
+struct Args {
+    /// Address to listen on
+    #[arg(long, value_name = "ADDR", env = "A2A_GRPC_LISTEN", default_value = "127.0.0.1:50051")]
+    listen: SocketAddr,
+
+    /// Solana JSON-RPC endpoint
+    #[arg(long, value_name = "URL", env = "A2A_RPC_URL",
+          default_value = "https://api.mainnet-beta.solana.com")]
+    rpc_url: String,
+
+    /// A2A-Swap program ID [default: mainnet deployment]
+    #[arg(long, value_name = "PUBKEY", env = "A2A_PROGRAM_ID")]
+    program_id: Option<String>,
+}
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    let args = Args::parse();
+    let program_id = args.program_id
+        .as_deref()
+        .map(Pubkey::from_str)
+        .transpose()
+        .context("invalid --program-id")?;
+
+    let service = A2ASwapService::new(args.rpc_url, program_id);
+    eprintln!("a2a-swap-grpc listening on {}", args.listen);
+    tonic::transport::Server::builder()
+        .add_service(A2aSwapServer::new(service))
+        .serve_with_shutdown(args.listen, async {
+            let _ = tokio::signal::ctrl_c().await;
+        })
+        .await?;
+    Ok(())
+}
@@ -0,0 +1,322 @@
+//! [`A2ASwapService`] — the `A2ASwap` gRPC service over [`A2ASwapClient`].
+
+use std::str::FromStr;
+use std::sync::Arc;
+use std::time::Duration;
+
+use a2a_swap_sdk::{
+    A2ASwapClient, CurveKind, Error, ErrorCode, FeeSummary, PoolInfo, PositionInfo, SimulateParams,
+    ProtectionLevel, SimulateResult, SwapParams,
+};
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_sdk::{pubkey::Pubkey, transaction::Transaction};
+use tokio::sync::mpsc;
+use tokio_stream::wrappers::ReceiverStream;
+use tonic::{metadata::MetadataValue, Code, Request, Response, Status};
+
+use crate::proto::{self, a2a_swap_server::A2aSwap};
+
+/// Poll interval for `PoolUpdates` when the request leaves it at 0.
+pub const DEFAULT_POLL_INTERVAL: Duration = Duration::from_secs(2);
+/// Shortest poll interval a `PoolUpdates` request may ask for.
+pub const MIN_POLL_INTERVAL: Duration = Duration::from_millis(400);
+
+/// Response metadata key carrying the SDK's [`ErrorCode`] on failures.
+pub const ERROR_CODE_METADATA: &str = "a2a-error-code";
+
+/// The `A2ASwap` service. Cheap to clone; clones share one SDK client.
+#[derive(Clone)]
+pub struct A2ASwapService {
+    client:  Arc<A2ASwapClient>,
+    rpc_url: String,
+}
+
+impl A2ASwapService {
+    /// Serve from `rpc_url` against the mainnet program, or `program_id`.
+    pub fn new(rpc_url: impl Into<String>, program_id: Option<Pubkey>) -> Self {
+        let rpc_url = rpc_url.into();
+        let mut client = A2ASwapClient::new(rpc_url.clone());
+        if let Some(program_id) = program_id {
+            client = client.with_program_id(program_id);
+        }
+        Self { client: Arc::new(client), rpc_url }
+    }
+}
+
+#[tonic::async_trait]
+impl A2aSwap for A2ASwapService {
+    async fn simulate(
+        &self,
+        request: Request<proto::SimulateRequest>,
+    ) -> Result<Response<proto::SimulateResponse>, Status> {
+        let req = request.into_inner();
+        let params = SimulateParams {
+            mint_in:   pubkey(&req.mint_in, "mint_in")?,
+            mint_out:  pubkey(&req.mint_out, "mint_out")?,
+            amount_in: req.amount_in,
+        };
+        let sim = self.client.simulate(params).await.map_err(status)?;
+        Ok(Response::new(simulate_response(sim)))
+    }
+
+    async fn convert(
+        &self,
+        request: Request<proto::ConvertRequest>,
+    ) -> Result<Response<proto::ConvertResponse>, Status> {
+        let req = request.into_inner();
+        let agent = pubkey(&req.agent, "agent")?;
+        let params = SwapParams {
+            mint_in:              pubkey(&req.mint_in, "mint_in")?,
+            mint_out:             pubkey(&req.mint_out, "mint_out")?,
+            amount_in:            req.amount_in,
+            max_slippage_bps:     bps(req.max_slippage_bps, "max_slippage_bps")?,
+            min_amount_out:       req.min_amount_out,
+            max_price_impact_bps: bps(req.max_price_impact_bps, "max_price_impact_bps")?,
+            deadline_unix:        req.deadline_unix,
+            referrer:             req.referrer.as_deref().map(|r| pubkey(r, "referrer")).transpose()?,
+            protection:           ProtectionLevel::None,
+        };
+        if params.amount_in == 0 {
+            return Err(invalid("amount_in must be positive"));
+        }
+        let plan = self.client.build_convert(&agent, params).await.map_err(status)?;
+
+        let blockhash = RpcClient::new(self.rpc_url.clone())
+            .get_latest_blockhash()
+            .await
+            .map_err(|e| status(e.into()))?;
+        let mut tx = Transaction::new_with_payer(&plan.instructions, Some(&agent));
+        tx.message.recent_blockhash = blockhash;
+        let transaction = bincode::serialize(&tx).map_err(|e| Status::internal(e.to_string()))?;
+
+        Ok(Response::new(proto::ConvertResponse {
+            transaction,
+            instructions:   plan.instructions.into_iter().map(instruction).collect(),
+            pool:           plan.pool.to_string(),
+            a_to_b:         plan.a_to_b,
+            amount_in:      plan.amount_in,
+            estimated_out:  plan.estimated_out,
+            min_amount_out: plan.min_amount_out,
+            referral_fee:   plan.referral_fee,
+            blockhash:      blockhash.to_string(),
+        }))
+    }
+
+    async fn pool_info(
+        &self,
+        request: Request<proto::PoolInfoRequest>,
+    ) -> Result<Response<proto::PoolInfoResponse>, Status> {
+        let req = request.into_inner();
+        let (mint_a, mint_b) = (pubkey(&req.mint_a, "mint_a")?, pubkey(&req.mint_b, "mint_b")?);
+        let info = self.client.pool_info(mint_a, mint_b).await.map_err(status)?;
+        Ok(Response::new(pool_info_response(info)))
+    }
+
+    async fn positions(
+        &self,
+        request: Request<proto::PositionsRequest>,
+    ) -> Result<Response<proto::PositionsResponse>, Status> {
+        let req = request.into_inner();
+        let owner = pubkey(&req.owner, "owner")?;
+        let summary = if req.include_entry {
+            let positions = self.client.my_positions(&owner).await.map_err(status)?;
+            FeeSummary {
+                total_fees_a: positions.iter().map(|p| p.total_fees_a).sum(),
+                total_fees_b: positions.iter().map(|p| p.total_fees_b).sum(),
+                positions,
+            }
+        } else {
+            self.client.my_fees(&owner).await.map_err(status)?
+        };
+        Ok(Response::new(proto::PositionsResponse {
+            positions:    summary.positions.into_iter().map(position).collect(),
+            total_fees_a: summary.total_fees_a,
+            total_fees_b: summary.total_fees_b,
+        }))
+    }
+
+    type PoolUpdatesStream = ReceiverStream<Result<proto::PoolInfoResponse, Status>>;
+
+    /// Polls `pool_info` and sends a message whenever the result differs from
+    /// the last one sent. Ends on the first error, after sending it.
+    async fn pool_updates(
+        &self,
+        request: Request<proto::PoolUpdatesRequest>,
+    ) -> Result<Response<Self::PoolUpdatesStream>, Status> {
+        let req = request.into_inner();
+        let (mint_a, mint_b) = (pubkey(&req.mint_a, "mint_a")?, pubkey(&req.mint_b, "mint_b")?);
+        let interval = match req.interval_ms {
+            0  => DEFAULT_POLL_INTERVAL,
+            ms => Duration::from_millis(ms as u64).max(MIN_POLL_INTERVAL),
+        };
+
+        // Fail the call itself, not the stream, if the pool does not exist.
+        let first = pool_info_response(self.client.pool_info(mint_a, mint_b).await.map_err(status)?);
+
+        let (tx, rx) = mpsc::channel(16);
+        let client = Arc::clone(&self.client);
+        tokio::spawn(async move {
+            let mut last = first.clone();
+            if tx.send(Ok(first)).await.is_err() {
+                return;
+            }
+            let mut ticker = tokio::time::interval(interval);
+            ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+            ticker.tick().await;
+            loop {
+                tokio::select! {
+                    _ = ticker.tick() => {}
+                    _ = tx.closed() => return,
+                }
+                match client.pool_info(mint_a, mint_b).await {
+                    Ok(info) => {
+                        let update = pool_info_response(info);
+                        if update != last {
+                            last = update.clone();
+                            if tx.send(Ok(update)).await.is_err() {
+                                return;
+                            }
+                        }
+                    }
+                    Err(e) => {
+                        let _ = tx.send(Err(status(e))).await;
+                        return;
+                    }
+                }
+            }
+        });
+        Ok(Response::new(ReceiverStream::new(rx)))
+    }
+}
+
+// ─── Errors ───────────────────────────────────────────────────────────────────
+
+/// gRPC status for an SDK error, with its [`ErrorCode`] in the
+/// [`ERROR_CODE_METADATA`] metadata.
+pub fn status(e: Error) -> Status {
+    let code = e.code();
+    let grpc = match code {
+        ErrorCode::PoolNotFound => Code::NotFound,
+        ErrorCode::InvalidArgument | ErrorCode::AmountBRequired | ErrorCode::AmountBZero => {
+            Code::InvalidArgument
+        }
+        ErrorCode::NoLiquidity
+        | ErrorCode::SlippageExceeded
+        | ErrorCode::PriceImpactExceeded
+        | ErrorCode::InsufficientBalance
+        | ErrorCode::ProgramError => Code::FailedPrecondition,
+        ErrorCode::MathOverflow => Code::OutOfRange,
+        ErrorCode::RpcError => Code::Unavailable,
+        _ => Code::Internal,
+    };
+    let mut status = Status::new(grpc, e.to_string());
+    status
+        .metadata_mut()
+        .insert(ERROR_CODE_METADATA, MetadataValue::from_static(code.as_str()));
+    status
+}
+
+fn invalid(message: impl Into<String>) -> Status {
+    status(Error::InvalidArgument(message.into()))
+}
+
+fn pubkey(s: &str, field: &str) -> Result<Pubkey, Status> {
+    Pubkey::from_str(s).map_err(|_| invalid(format!("{field} is not a valid public key: {s:?}")))
+}
+
+fn bps(value: u32, field: &str) -> Result<u16, Status> {
+    match u16::try_from(value) {
+        Ok(bps) if bps <= 10_000 => Ok(bps),
+        _ => Err(invalid(format!("{field} must be at most 10000, got {value}"))),
+    }
+}
+
+// ─── Conversions ──────────────────────────────────────────────────────────────
+
+fn simulate_response(sim: SimulateResult) -> proto::SimulateResponse {
+    proto::SimulateResponse {
+        pool:             sim.pool.to_string(),
+        a_to_b:           sim.a_to_b,
+        amount_in:        sim.amount_in,
+        protocol_fee:     sim.protocol_fee,
+        net_pool_input:   sim.net_pool_input,
+        lp_fee:           sim.lp_fee,
+        after_fees:       sim.after_fees,
+        estimated_out:    sim.estimated_out,
+        effective_rate:   sim.effective_rate,
+        price_impact_pct: sim.price_impact_pct,
+        fee_rate_bps:     sim.fee_rate_bps.into(),
+        reserve_in:       sim.reserve_in,
+        reserve_out:      sim.reserve_out,
+    }
+}
+
+fn pool_info_response(info: PoolInfo) -> proto::PoolInfoResponse {
+    proto::PoolInfoResponse {
+        pool:              info.pool.to_string(),
+        mint_a:            info.mint_a.to_string(),
+        mint_b:            info.mint_b.to_string(),
+        vault_a:           info.vault_a.to_string(),
+        vault_b:           info.vault_b.to_string(),
+        reserve_a:         info.reserve_a,
+        reserve_b:         info.reserve_b,
+        lp_supply:         info.lp_supply,
+        fee_rate_bps:      info.fee_rate_bps.into(),
+        effective_fee_bps: info.effective_fee_bps.into(),
+        dynamic_fee:       Some(proto::DynamicFee {
+            min_fee_bps:    info.dynamic_fee.min_fee_bps.into(),
+            max_fee_bps:    info.dynamic_fee.max_fee_bps.into(),
+            volatility_bps: info.dynamic_fee.volatility_bps,
+            last_update_ts: info.dynamic_fee.last_update_ts,
+        }),
+        protocol_fee_bps:  info.protocol_fee_bps.into(),
+        stable_amp:        match info.curve {
+            CurveKind::ConstantProduct    => None,
+            CurveKind::StableSwap { amp } => Some(amp),
+        },
+        volume_a:          info.volume_a,
+        volume_b:          info.volume_b,
+        fees_collected_a:  info.fees_collected_a,
+        fees_collected_b:  info.fees_collected_b,
+        spot_price:        info.spot_price,
+    }
+}
+
+fn position(p: PositionInfo) -> proto::Position {
+    proto::Position {
+        address:            p.address.to_string(),
+        pool:               p.pool.to_string(),
+        owner:              p.owner.to_string(),
+        lp_shares:          p.lp_shares,
+        fees_owed_a:        p.fees_owed_a,
+        fees_owed_b:        p.fees_owed_b,
+        pending_fees_a:     p.pending_fees_a,
+        pending_fees_b:     p.pending_fees_b,
+        total_fees_a:       p.total_fees_a,
+        total_fees_b:       p.total_fees_b,
+        auto_compound:      p.auto_compound,
+        compound_threshold: p.compound_threshold,
+        entry:              p.entry.map(|e| proto::PositionEntry {
+            deposited_a: e.deposited_a,
+            deposited_b: e.deposited_b,
+            entry_price: e.entry_price,
+        }),
+        current_price:      p.current_price,
+        il_pct:             p.il_pct,
+    }
+}
+
+fn instruction(ix: solana_sdk::instruction::Instruction) -> proto::Instruction {
+    proto::Instruction {
+        program_id: ix.program_id.to_string(),
+        accounts:   ix.accounts
+            .into_iter()
+            .map(|a| proto::AccountMeta {
+                pubkey:      a.pubkey.to_string(),
+                is_signer:   a.is_signer,
+                is_writable: a.is_writable,
+            })
+            .collect(),
+        data:       ix.data,
+    }
+}
@@ -0,0 +1,80 @@
+//! SDK errors surface as gRPC statuses carrying the SDK's error code.
+//!
+//! No RPC: every request here fails validation before the service calls out.
+
+use a2a_swap_grpc::{
+    proto::{self, a2a_swap_server::A2aSwap},
+    service::{status, ERROR_CODE_METADATA},
+    A2ASwapService,
+};
+use a2a_swap_sdk::Error;
+use solana_sdk::pubkey::Pubkey;
+use tonic::{Code, Request, Status};
+
+fn service() -> A2ASwapService {
+    // Unroutable: a request that reaches the RPC fails the test.
+    A2ASwapService::new("http://127.0.0.1:1", None)
+}
+
+fn error_code(status: &Status) -> &str {
+    status.metadata().get(ERROR_CODE_METADATA).unwrap().to_str().unwrap()
+}
+
+#[test]
+fn sdk_errors_map_to_grpc_codes() {
+    let (a, b) = (Pubkey::new_unique(), Pubkey::new_unique());
+    let s = status(Error::PoolNotFound(a, b));
+    assert_eq!(s.code(), Code::NotFound);
+    assert_eq!(error_code(&s), "POOL_NOT_FOUND");
+
+    let s = status(Error::SlippageExceeded { estimated: 10, min: 11 });
+    assert_eq!(s.code(), Code::FailedPrecondition);
+    assert_eq!(error_code(&s), "SLIPPAGE_EXCEEDED");
+
+    let s = status(Error::InvalidArgument("x".into()));
+    assert_eq!(s.code(), Code::InvalidArgument);
+    assert_eq!(error_code(&s), "INVALID_ARGUMENT");
+}
+
+#[tokio::test]
+async fn malformed_requests_are_invalid_argument() {
+    let svc = service();
+    let mint = Pubkey::new_unique().to_string();
+
+    let err = svc
+        .simulate(Request::new(proto::SimulateRequest {
+            mint_in:   "not-a-key".into(),
+            mint_out:  mint.clone(),
+            amount_in: 1,
+        }))
+        .await
+        .unwrap_err();
+    assert_eq!(err.code(), Code::InvalidArgument);
+    assert_eq!(error_code(&err), "INVALID_ARGUMENT");
+    assert!(err.message().contains("mint_in"));
+
+    let convert = |f: fn(&mut proto::ConvertRequest)| {
+        let mut req = proto::ConvertRequest {
+            mint_in:   mint.clone(),
+            mint_out:  Pubkey::new_unique().to_string(),
+            amount_in: 1,
+            agent:     Pubkey::new_unique().to_string(),
+            ..Default::default()
+        };
+        f(&mut req);
+        svc.convert(Request::new(req))
+    };
+    for err in [
+        convert(|r| r.amount_in = 0).await.unwrap_err(),
+        convert(|r| r.max_slippage_bps = 10_001).await.unwrap_err(),
+        convert(|r| r.referrer = Some("nope".into())).await.unwrap_err(),
+    ] {
+        assert_eq!(err.code(), Code::InvalidArgument, "{err:?}");
+    }
+
+    let err = svc
+        .positions(Request::new(proto::PositionsRequest { owner: String::new(), include_entry: false }))
+        .await
+        .unwrap_err();
+    assert_eq!(err.code(), Code::InvalidArgument);
+}
@@ -0,0 +1,117 @@
+//! `a2a-swap-relayer` — submit agents' signed swap intents and pay the fee.
+//!
+//! ```text
+//! a2a-swap-relayer --listen 0.0.0.0:8080 --keypair relayer.json --rpc-url https://my-node.example
+//! ```
+//!
+//! `POST /relay` takes a [`SignedIntent`] as JSON (as produced by
+//! `A2ASwapClient::sign_intent`) and answers with the swap's signature and
+//! fill. Intents with a bad signature, a stale nonce or past their expiry
+//! are refused before anything is sent. `GET /health` reports the relayer
+//! key.
+
+use std::net::SocketAddr;
+use std::str::FromStr;
+use std::sync::Arc;
+
+use a2a_swap_sdk::{intent::SignedIntent, keystore, A2ASwapClient, Error};
+use anyhow::{Context, Result};
+use axum::{extract::State, http::StatusCode, routing::{get, post}, Json, Router};
+use clap::Parser;
+use serde_json::{json, Value};
+use solana_sdk::{pubkey::Pubkey, signature::Keypair, signer::Signer};
+
+#[derive(Parser)]
+#[command(name = "a2a-swap-relayer", version, about = "A2A-Swap intent relayer")]
+struct Args {
+    /// Address to listen on
+    #[arg(long, value_name = "ADDR", env = "A2A_RELAYER_LISTEN", default_value = "127.0.0.1:8080")]
+    listen: SocketAddr,
+
+    /// Solana JSON-RPC endpoint
+    #[arg(long, value_name = "URL", env = "A2A_RPC_URL",
+          default_value = "https://api.mainnet-beta.solana.com")]
+    rpc_url: String,
+
+    /// A2A-Swap program ID [default: mainnet deployment]
+    #[arg(long, value_name = "PUBKEY", env = "A2A_PROGRAM_ID")]
+    program_id: Option<String>,
+
+    /// Relayer keypair or keystore; pays fees and rent. Keystores are
+    /// unlocked with A2A_KEYSTORE_PASSPHRASE
+    #[arg(long, value_name = "PATH", env = "A2A_RELAYER_KEYPAIR")]
+    keypair: String,
+}
+
+struct Relayer {
+    client: A2ASwapClient,
+    signer: Keypair,
+}
+
+type Reply = (StatusCode, Json<Value>);
+
+async fn relay(State(relayer): State<Arc<Relayer>>, Json(signed): Json<SignedIntent>) -> Reply {
+    // Client futures borrow a `&dyn Signer`, which is not `Send`, so each
+    // relay runs to completion on a blocking thread.
+    let handle = tokio::runtime::Handle::current();
+    let relayed = tokio::task::spawn_blocking(move || {
+        handle.block_on(relayer.client.relay_intent(&relayer.signer, &signed))
+    })
+    .await;
+    let relayed = match relayed {
+        Ok(relayed) => relayed,
+        Err(e) => return (StatusCode::INTERNAL_SERVER_ERROR, Json(json!({ "error": e.to_string() }))),
+    };
+    match relayed {
+        Ok(result) => (StatusCode::OK, Json(json!({
+            "signature":             result.signature,
+            "pool":                  result.pool.to_string(),
+            "amount_in":             result.amount_in,
+            "min_amount_out":        result.min_amount_out,
+            "actual_out":            result.actual_out,
+            "realized_slippage_bps": result.realized_slippage_bps,
+        }))),
+        Err(e) => {
+            // Refusals are the caller's to fix; anything else is ours or the RPC's.
+            let status = match e {
+                Error::Program(_) | Error::InvalidArgument(_) => StatusCode::UNPROCESSABLE_ENTITY,
+                _ => StatusCode::BAD_GATEWAY,
+            };
+            (status, Json(json!({ "error": e.to_string() })))
+        }
+    }
+}
+
+async fn health(State(relayer): State<Arc<Relayer>>) -> Json<Value> {
+    Json(json!({ "ok": true, "relayer": relayer.signer.pubkey().to_string() }))
+}
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    let args = Args::parse();
+    let program_id = args.program_id
+        .as_deref()
+        .map(Pubkey::from_str)
+        .transpose()
+        .context("invalid --program-id")?;
+    let signer = keystore::read_keypair(&args.keypair, keystore::passphrase_from_env().as_deref())
+        .with_context(|| format!("cannot load relayer keypair '{}'", args.keypair))?;
+
+    let mut client = A2ASwapClient::new(args.rpc_url);
+    if let Some(program_id) = program_id {
+        client = client.with_program_id(program_id);
+    }
+    eprintln!("a2a-swap-relayer {} listening on {}", signer.pubkey(), args.listen);
+    let app = Router::new()
+        .route("/relay", post(relay))
+        .route("/health", get(health))
+        .with_state(Arc::new(Relayer { client, signer }));
+
+    let listener = tokio::net::TcpListener::bind(args.listen).await?;
+    axum::serve(listener, app)
+        .with_graceful_shutdown(async {
+            let _ = tokio::signal::ctrl_c().await;
+        })
+        .await?;
+    Ok(())
+}
@@ -0,0 +1,127 @@
+//! `simulate` latency against a local keep-alive JSON-RPC mock, with a fresh
+//! client per call (a new connection each time) and one shared client.
+//!
+//! ```text
+//! cargo bench -p a2a-swap-sdk --bench simulate
+//! ```
+
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::TcpListener;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use a2a_swap_core::Account as _;
+use a2a_swap_sdk::{instructions::derive_pool, state::POOL_VERSION, A2ASwapClient, SimulateParams};
+use base64::{engine::general_purpose::STANDARD, Engine as _};
+use serde_json::{json, Value};
+use solana_sdk::pubkey::Pubkey;
+
+const CALLS: usize = 200;
+
+/// Serve `getMultipleAccounts` over HTTP/1.1 keep-alive: `pool` answers
+/// with `data`, every other account is missing. Returns the URL and the connection count.
+fn serve(pool: Pubkey, data: Vec<u8>) -> (String, Arc<AtomicUsize>) {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("http://{}", listener.local_addr().unwrap());
+    let connections = Arc::new(AtomicUsize::new(0));
+    let accepted = connections.clone();
+    std::thread::spawn(move || {
+        for stream in listener.incoming() {
+            accepted.fetch_add(1, Ordering::Relaxed);
+            let data = data.clone();
+            std::thread::spawn(move || {
+                let stream = stream.unwrap();
+                stream.set_nodelay(true).unwrap();
+                let mut reader = BufReader::new(stream);
+                loop {
+                    let mut len = 0;
+                    loop {
+                        let mut header = String::new();
+                        if reader.read_line(&mut header).unwrap_or(0) == 0 {
+                            return;
+                        }
+                        if header.trim_end().is_empty() {
+                            break;
+                        }
+                        if let Some((name, value)) = header.trim_end().split_once(": ") {
+                            if name.eq_ignore_ascii_case("content-length") {
+                                len = value.parse().unwrap();
+                            }
+                        }
+                    }
+                    let mut body = vec![0; len];
+                    reader.read_exact(&mut body).unwrap();
+                    let request: Value = serde_json::from_slice(&body).unwrap();
+                    let account = |key: &Value| if key == &pool.to_string() {
+                        json!({ "data": [STANDARD.encode(&data), "base64"], "executable": false,
+                                "lamports": 1, "owner": Pubkey::default().to_string(), "rentEpoch": 0 })
+                    } else {
+                        Value::Null
+                    };
+                    let value: Value = request["params"][0].as_array().unwrap().iter().map(account).collect();
+                    let response = json!({
+                        "jsonrpc": "2.0", "id": request["id"],
+                        "result": { "context": { "slot": 1 }, "value": value },
+                    }).to_string();
+                    let reply = format!(
+                        "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\n\r\n{response}",
+                        response.len()
+                    );
+                    if reader.get_mut().write_all(reply.as_bytes()).is_err() {
+                        return;
+                    }
+                }
+            });
+        }
+    });
+    (url, connections)
+}
+
+fn report(label: &str, mut samples: Vec<Duration>, connections: usize) {
+    samples.sort();
+    let mean = samples.iter().sum::<Duration>() / samples.len() as u32;
+    let pct = |p: usize| samples[(samples.len() * p / 100).min(samples.len() - 1)];
+    println!(
+        "{label:<24} mean {mean:>10.2?}  p50 {:>10.2?}  p99 {:>10.2?}  connections {connections}",
+        pct(50),
+        pct(99),
+    );
+}
+
+#[tokio::main(flavor = "current_thread")]
+async fn main() {
+    let program = Pubkey::new_unique();
+    let (mint_a, mint_b) = (Pubkey::new_unique(), Pubkey::new_unique());
+    let (pool, _) = derive_pool(&mint_a, &mint_b, &program);
+    let data = a2a_swap_core::Pool {
+        token_a_mint: mint_a.to_bytes(),
+        token_b_mint: mint_b.to_bytes(),
+        lp_supply:    1_000_000,
+        fee_rate_bps: 30,
+        reserve_a:    1_000_000_000,
+        reserve_b:    4_000_000_000,
+        version:      POOL_VERSION,
+        ..Default::default()
+    }.to_account_data();
+    let (url, connections) = serve(pool, data);
+    let params = SimulateParams { mint_in: mint_a, mint_out: mint_b, amount_in: 1_000_000 };
+
+    let mut samples = Vec::with_capacity(CALLS);
+    for _ in 0..CALLS {
+        let started = Instant::now();
+        let client = A2ASwapClient::new(&url).with_program_id(program);
+        client.simulate(params.clone()).await.unwrap();
+        samples.push(started.elapsed());
+    }
+    report("fresh client per call", samples, connections.swap(0, Ordering::Relaxed));
+
+    let client = A2ASwapClient::new(&url).with_program_id(program);
+    let mut samples = Vec::with_capacity(CALLS);
+    for _ in 0..CALLS {
+        let started = Instant::now();
+        client.simulate(params.clone()).await.unwrap();
+        samples.push(started.elapsed());
+    }
+    report("shared client", samples, connections.load(Ordering::Relaxed));
+}
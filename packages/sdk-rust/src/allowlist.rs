@@ -0,0 +1,129 @@
+//! Trader allowlists for permissioned pools.
+//!
+//! A pool's creator restricts swaps to a set of traders by setting the
+//! pool's `allowlist_root` with
+//! [`set_pool_allowlist_ix`](crate::instructions::set_pool_allowlist_ix) —
+//! the root of an [`AllowlistTree`] over the traders' wallets. Each trader
+//! then proves membership once with
+//! [`register_trader_ix`](crate::instructions::register_trader_ix), passing
+//! its [`AllowlistTree::proof`]; the program stores a `TraderPass` and every
+//! swap on the pool from then on carries it as a trailing account
+//! ([`with_trader_pass`](crate::instructions::with_trader_pass)).
+//!
+//! The tree hashes exactly as the program does:
+//!
+//! ```text
+//! leaf = sha256("a2a-swap:allowlist" ‖ trader)
+//! node = sha256(min(left, right) ‖ max(left, right))
+//! ```
+//!
+//! so a proof is just the sibling hashes from the leaf up. Changing the root
+//! invalidates every pass; traders still on the new list register again.
+//!
+//! ```
+//! use a2a_swap_sdk::allowlist::{verify_proof, AllowlistTree};
+//! use solana_sdk::pubkey::Pubkey;
+//!
+//! let traders: Vec<Pubkey> = (0..5).map(|_| Pubkey::new_unique()).collect();
+//! let tree = AllowlistTree::new(&traders).unwrap();
+//! let proof = tree.proof(&traders[3]).unwrap();
+//! assert!(verify_proof(&tree.root(), &traders[3], &proof));
+//! ```
+
+use sha2::{Digest, Sha256};
+use solana_sdk::pubkey::Pubkey;
+
+use crate::error::{Error, Result};
+
+/// Domain tag hashed in front of every leaf, so a leaf can't pass for a node.
+pub const ALLOWLIST_LEAF_TAG: &[u8] = b"a2a-swap:allowlist";
+
+/// Leaf of `trader` in an allowlist tree.
+pub fn leaf(trader: &Pubkey) -> [u8; 32] {
+    Sha256::new().chain_update(ALLOWLIST_LEAF_TAG).chain_update(trader).finalize().into()
+}
+
+fn node(a: &[u8; 32], b: &[u8; 32]) -> [u8; 32] {
+    let (lo, hi) = if a <= b { (a, b) } else { (b, a) };
+    Sha256::new().chain_update(lo).chain_update(hi).finalize().into()
+}
+
+/// Whether `proof` leads from `trader`'s leaf to `root` — the program's
+/// check in `register_trader`.
+pub fn verify_proof(root: &[u8; 32], trader: &Pubkey, proof: &[[u8; 32]]) -> bool {
+    proof.iter().fold(leaf(trader), |acc, sibling| node(&acc, sibling)) == *root
+}
+
+/// Merkle tree over a pool's allowed traders.
+///
+/// Leaves are sorted and deduplicated, so the same set of traders always
+/// gives the same root whatever order it is listed in. A level with an odd
+/// node out carries it up unpaired.
+#[derive(Debug, Clone)]
+pub struct AllowlistTree {
+    /// `levels[0]` is the sorted leaves, the last level the root alone.
+    levels: Vec<Vec<[u8; 32]>>,
+}
+
+impl AllowlistTree {
+    /// Build the tree over `traders`. Fails on an empty list: an all-zero
+    /// root is what opens a pool to everyone, so clear the allowlist
+    /// instead.
+    pub fn new(traders: &[Pubkey]) -> Result<Self> {
+        let mut leaves: Vec<[u8; 32]> = traders.iter().map(leaf).collect();
+        leaves.sort_unstable();
+        leaves.dedup();
+        if leaves.is_empty() {
+            return Err(Error::InvalidArgument("allowlist has no traders".into()));
+        }
+        let mut levels = vec![leaves];
+        while levels.last().is_some_and(|l| l.len() > 1) {
+            let next = levels
+                .last()
+                .unwrap()
+                .chunks(2)
+                .map(|pair| match pair {
+                    [a, b] => node(a, b),
+                    [a] => *a,
+                    _ => unreachable!(),
+                })
+                .collect();
+            levels.push(next);
+        }
+        Ok(Self { levels })
+    }
+
+    /// The root to set as the pool's `allowlist_root`.
+    pub fn root(&self) -> [u8; 32] {
+        self.levels.last().unwrap()[0]
+    }
+
+    /// Number of distinct traders in the tree.
+    pub fn len(&self) -> usize {
+        self.levels[0].len()
+    }
+
+    /// Always `false`: [`AllowlistTree::new`] rejects an empty list.
+    pub fn is_empty(&self) -> bool {
+        self.levels[0].is_empty()
+    }
+
+    /// Whether `trader` is in the tree.
+    pub fn contains(&self, trader: &Pubkey) -> bool {
+        self.levels[0].binary_search(&leaf(trader)).is_ok()
+    }
+
+    /// `trader`'s proof for `register_trader`, or `None` if it isn't in the
+    /// tree.
+    pub fn proof(&self, trader: &Pubkey) -> Option<Vec<[u8; 32]>> {
+        let mut index = self.levels[0].binary_search(&leaf(trader)).ok()?;
+        let mut proof = Vec::new();
+        for level in &self.levels[..self.levels.len() - 1] {
+            if let Some(sibling) = level.get(index ^ 1) {
+                proof.push(*sibling);
+            }
+            index /= 2;
+        }
+        Some(proof)
+    }
+}
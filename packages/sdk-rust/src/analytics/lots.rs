@@ -0,0 +1,498 @@
+//! Tax lots and realized gains per token, FIFO or LIFO.
+//!
+//! A [`LotTracker`] keeps the open lots of every token an owner holds, each
+//! with what it cost in one quote token, and matches every disposal against
+//! them — oldest lot first ([`LotMethod::Fifo`]) or newest first
+//! ([`LotMethod::Lifo`]). It replays the records [`export`](crate::export)
+//! rebuilds from the owner's history:
+//!
+//! - a [`TradeRecord`] disposes of `mint_in` and acquires `mint_out`, both
+//!   at the swap's value — the quote side when the quote token is in the
+//!   pair, otherwise the last price the tracker saw for either token;
+//! - a [`LiquidityRecord`] deposit disposes of both tokens and acquires the
+//!   pool's LP shares (tracked under the pool address) at their combined
+//!   value; a withdrawal is the reverse. In a pair with the quote token,
+//!   each side is valued at the quote side, as deposits go in at the pool
+//!   ratio;
+//! - a [`FeeRecord`] is income: claimed fees are acquired at their value,
+//!   compounded fees as LP shares.
+//!
+//! The quote token is the unit of account and has no lots. Events that
+//! can't be valued are listed in [`LotTracker::skipped`]. Each disposal
+//! yields one [`Disposal`] per lot it drew on, plus one without a cost basis
+//! for any part no lot covers — tokens acquired before the history starts.
+//! [`LotTracker::summary`] totals them per token.
+//!
+//! ```rust,no_run
+//! # use a2a_swap_sdk::{analytics::lots::LotMethod, export, A2ASwapClient};
+//! # async fn run(client: A2ASwapClient, owner: solana_sdk::pubkey::Pubkey, usdc: solana_sdk::pubkey::Pubkey) -> a2a_swap_sdk::Result<()> {
+//! let lots = client.realized_gains(&owner, None, LotMethod::Fifo, usdc).await?;
+//! for token in lots.summary() {
+//!     println!("{}: realized {} (unmatched {})", token.asset, token.realized_gain, token.unmatched_quantity);
+//! }
+//! std::fs::write("disposals.csv", export::to_csv(&lots.disposals)).unwrap();
+//! # Ok(())
+//! # }
+//! ```
+
+use std::collections::{BTreeMap, HashMap, HashSet, VecDeque};
+use std::fmt;
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+use solana_sdk::pubkey::Pubkey;
+
+use crate::error::{Error, Result};
+use crate::export::{
+    Cell, Column, ColumnType, FeeKind, FeeRecord, LiquidityKind, LiquidityRecord, Record, TradeRecord,
+};
+
+/// Which open lot a disposal draws on first.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LotMethod {
+    /// Oldest lot first.
+    #[default]
+    Fifo,
+    /// Newest lot first.
+    Lifo,
+}
+
+impl FromStr for LotMethod {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_ascii_lowercase().as_str() {
+            "fifo" => Ok(LotMethod::Fifo),
+            "lifo" => Ok(LotMethod::Lifo),
+            _ => Err(Error::InvalidArgument(format!("unknown lot method `{s}` (expected fifo or lifo)"))),
+        }
+    }
+}
+
+impl fmt::Display for LotMethod {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            LotMethod::Fifo => "fifo",
+            LotMethod::Lifo => "lifo",
+        })
+    }
+}
+
+/// An open lot: tokens acquired together and what is left of their cost.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Lot {
+    /// Token mint, or the pool address for LP shares.
+    pub asset: Pubkey,
+    /// Atomic units still held.
+    pub quantity: u64,
+    /// Cost of `quantity`, in quote atomic units.
+    pub cost: u64,
+    pub acquired_at: Option<i64>,
+    /// Transaction that acquired the lot.
+    pub signature: String,
+}
+
+/// One disposal matched against one lot (or against none).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Disposal {
+    /// Token mint, or the pool address for LP shares.
+    pub asset: Pubkey,
+    pub quantity: u64,
+    /// Share of the disposal's value, in quote atomic units.
+    pub proceeds: u64,
+    /// Cost of the lot units disposed of; `None` when no lot was left.
+    pub cost_basis: Option<u64>,
+    /// `proceeds - cost_basis`.
+    pub gain: Option<i64>,
+    pub acquired_at: Option<i64>,
+    pub disposed_at: Option<i64>,
+    /// `disposed_at - acquired_at`, in seconds.
+    pub held_secs: Option<i64>,
+    /// Transaction that acquired the lot.
+    pub acquired_signature: Option<String>,
+    /// Transaction that disposed of it.
+    pub signature: String,
+}
+
+impl Record for Disposal {
+    const COLUMNS: &'static [Column] = &[
+        Column { name: "asset", ty: ColumnType::Str },
+        Column { name: "quantity", ty: ColumnType::U64 },
+        Column { name: "proceeds", ty: ColumnType::U64 },
+        Column { name: "cost_basis", ty: ColumnType::U64 },
+        Column { name: "gain", ty: ColumnType::I64 },
+        Column { name: "acquired_at", ty: ColumnType::I64 },
+        Column { name: "disposed_at", ty: ColumnType::I64 },
+        Column { name: "held_secs", ty: ColumnType::I64 },
+        Column { name: "acquired_signature", ty: ColumnType::Str },
+        Column { name: "signature", ty: ColumnType::Str },
+    ];
+
+    fn row(&self) -> Vec<Cell> {
+        vec![
+            self.asset.into(),
+            self.quantity.into(),
+            self.proceeds.into(),
+            self.cost_basis.into(),
+            self.gain.into(),
+            self.acquired_at.into(),
+            self.disposed_at.into(),
+            self.held_secs.into(),
+            self.acquired_signature.clone().into(),
+            self.signature.clone().into(),
+        ]
+    }
+}
+
+/// Realized gains and open lots of one token, from [`LotTracker::summary`].
+/// Values are in quote atomic units.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GainSummary {
+    /// Token mint, or the pool address for LP shares.
+    pub asset: Pubkey,
+    /// `asset` is a pool's LP shares.
+    pub lp_shares: bool,
+    /// Units disposed of against a lot.
+    pub disposed: u64,
+    /// Proceeds of those units.
+    pub proceeds: u64,
+    pub cost_basis: u64,
+    /// `proceeds - cost_basis`.
+    pub realized_gain: i64,
+    /// Units disposed of with no lot to match, and their proceeds.
+    pub unmatched_quantity: u64,
+    pub unmatched_proceeds: u64,
+    /// Fee income received in `asset`, valued when received.
+    pub income: u64,
+    /// Units still held in open lots, and their cost.
+    pub open_quantity: u64,
+    pub open_cost: u64,
+}
+
+impl Record for GainSummary {
+    const COLUMNS: &'static [Column] = &[
+        Column { name: "asset", ty: ColumnType::Str },
+        Column { name: "lp_shares", ty: ColumnType::Bool },
+        Column { name: "disposed", ty: ColumnType::U64 },
+        Column { name: "proceeds", ty: ColumnType::U64 },
+        Column { name: "cost_basis", ty: ColumnType::U64 },
+        Column { name: "realized_gain", ty: ColumnType::I64 },
+        Column { name: "unmatched_quantity", ty: ColumnType::U64 },
+        Column { name: "unmatched_proceeds", ty: ColumnType::U64 },
+        Column { name: "income", ty: ColumnType::U64 },
+        Column { name: "open_quantity", ty: ColumnType::U64 },
+        Column { name: "open_cost", ty: ColumnType::U64 },
+    ];
+
+    fn row(&self) -> Vec<Cell> {
+        vec![
+            self.asset.into(),
+            self.lp_shares.into(),
+            self.disposed.into(),
+            self.proceeds.into(),
+            self.cost_basis.into(),
+            self.realized_gain.into(),
+            self.unmatched_quantity.into(),
+            self.unmatched_proceeds.into(),
+            self.income.into(),
+            self.open_quantity.into(),
+            self.open_cost.into(),
+        ]
+    }
+}
+
+/// Lot book of one owner, valued in one quote token — see the
+/// [module docs](self).
+#[derive(Debug, Clone)]
+pub struct LotTracker {
+    method:     LotMethod,
+    quote_mint: Pubkey,
+    lots:       HashMap<Pubkey, VecDeque<Lot>>,
+    /// Last quote atomic units per atomic unit seen for each asset.
+    prices:     HashMap<Pubkey, f64>,
+    income:     HashMap<Pubkey, u64>,
+    lp_pools:   HashSet<Pubkey>,
+    /// Every disposal, in order.
+    pub disposals: Vec<Disposal>,
+    /// Signatures of events that couldn't be valued in the quote token.
+    pub skipped: Vec<String>,
+}
+
+impl LotTracker {
+    pub fn new(method: LotMethod, quote_mint: Pubkey) -> Self {
+        Self {
+            method,
+            quote_mint,
+            lots:      HashMap::new(),
+            prices:    HashMap::new(),
+            income:    HashMap::new(),
+            lp_pools:  HashSet::new(),
+            disposals: Vec::new(),
+            skipped:   Vec::new(),
+        }
+    }
+
+    pub fn method(&self) -> LotMethod {
+        self.method
+    }
+
+    pub fn quote_mint(&self) -> Pubkey {
+        self.quote_mint
+    }
+
+    /// Record every event, ordered by slot (stable: within a slot, trades,
+    /// then liquidity, then fees).
+    pub fn replay(&mut self, trades: &[TradeRecord], liquidity: &[LiquidityRecord], fees: &[FeeRecord]) {
+        enum Event<'a> {
+            Trade(&'a TradeRecord),
+            Liquidity(&'a LiquidityRecord),
+            Fee(&'a FeeRecord),
+        }
+        let mut events: Vec<(u64, Event)> = trades
+            .iter()
+            .map(|t| (t.slot, Event::Trade(t)))
+            .chain(liquidity.iter().map(|l| (l.slot, Event::Liquidity(l))))
+            .chain(fees.iter().map(|f| (f.slot, Event::Fee(f))))
+            .collect();
+        events.sort_by_key(|&(slot, _)| slot);
+        for (_, event) in events {
+            match event {
+                Event::Trade(t)     => self.record_trade(t),
+                Event::Liquidity(l) => self.record_liquidity(l),
+                Event::Fee(f)       => self.record_fee(f),
+            }
+        }
+    }
+
+    /// Record a swap, valued as in the [module docs](self).
+    pub fn record_trade(&mut self, t: &TradeRecord) {
+        let value = if t.mint_in == self.quote_mint {
+            Some(t.amount_in)
+        } else if t.mint_out == self.quote_mint {
+            Some(t.amount_out)
+        } else {
+            self.value_of(t.mint_out, t.amount_out).or_else(|| self.value_of(t.mint_in, t.amount_in))
+        };
+        match value {
+            Some(value) => self.record_trade_valued(t, value),
+            None        => self.skipped.push(t.signature.clone()),
+        }
+    }
+
+    /// Record a swap worth `value` quote atomic units.
+    pub fn record_trade_valued(&mut self, t: &TradeRecord, value: u64) {
+        self.dispose(t.mint_in, t.amount_in, value, t.timestamp, &t.signature);
+        self.acquire(t.mint_out, t.amount_out, value, t.timestamp, &t.signature);
+    }
+
+    /// Record an LP deposit or withdrawal, valued as in the
+    /// [module docs](self).
+    pub fn record_liquidity(&mut self, l: &LiquidityRecord) {
+        let value = if l.mint_a == self.quote_mint {
+            Some((l.amount_a, l.amount_a))
+        } else if l.mint_b == self.quote_mint {
+            Some((l.amount_b, l.amount_b))
+        } else {
+            match (self.value_of(l.mint_a, l.amount_a), self.value_of(l.mint_b, l.amount_b)) {
+                (Some(a), Some(b)) => Some((a, b)),
+                (Some(v), None) | (None, Some(v)) => Some((v, v)),
+                (None, None) => None,
+            }
+        };
+        match value {
+            Some((value_a, value_b)) => self.record_liquidity_valued(l, value_a, value_b),
+            None => self.skipped.push(l.signature.clone()),
+        }
+    }
+
+    /// Record an LP deposit or withdrawal whose token A and token B legs are
+    /// worth `value_a` and `value_b` quote atomic units.
+    pub fn record_liquidity_valued(&mut self, l: &LiquidityRecord, value_a: u64, value_b: u64) {
+        self.lp_pools.insert(l.pool);
+        let value = value_a.saturating_add(value_b);
+        let (at, sig) = (l.timestamp, l.signature.as_str());
+        match l.kind {
+            LiquidityKind::Provided => {
+                self.dispose(l.mint_a, l.amount_a, value_a, at, sig);
+                self.dispose(l.mint_b, l.amount_b, value_b, at, sig);
+                self.acquire(l.pool, l.lp_shares, value, at, sig);
+            }
+            LiquidityKind::Removed => {
+                self.dispose(l.pool, l.lp_shares, value, at, sig);
+                self.acquire(l.mint_a, l.amount_a, value_a, at, sig);
+                self.acquire(l.mint_b, l.amount_b, value_b, at, sig);
+            }
+        }
+    }
+
+    /// Record fee income. Each side is valued at the quote token or its
+    /// last seen price; with neither, the payout is skipped.
+    pub fn record_fee(&mut self, f: &FeeRecord) {
+        match (self.value_of(f.mint_a, f.amount_a), self.value_of(f.mint_b, f.amount_b)) {
+            (Some(value_a), Some(value_b)) => self.record_fee_valued(f, value_a, value_b),
+            _ => self.skipped.push(f.signature.clone()),
+        }
+    }
+
+    /// Record fee income worth `value_a` + `value_b` quote atomic units.
+    pub fn record_fee_valued(&mut self, f: &FeeRecord, value_a: u64, value_b: u64) {
+        let (at, sig) = (f.timestamp, f.signature.as_str());
+        match (f.kind, f.lp_shares) {
+            (FeeKind::Compounded, Some(shares)) => {
+                self.lp_pools.insert(f.pool);
+                let value = value_a.saturating_add(value_b);
+                *self.income.entry(f.pool).or_default() += value;
+                self.acquire(f.pool, shares, value, at, sig);
+            }
+            _ => {
+                for (mint, amount, value) in [(f.mint_a, f.amount_a, value_a), (f.mint_b, f.amount_b, value_b)] {
+                    if amount > 0 {
+                        *self.income.entry(mint).or_default() += value;
+                        self.acquire(mint, amount, value, at, sig);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Open a lot of `quantity` `asset` costing `cost` quote atomic units.
+    /// The quote token has no lots.
+    pub fn acquire(&mut self, asset: Pubkey, quantity: u64, cost: u64, at: Option<i64>, signature: &str) {
+        if asset == self.quote_mint || quantity == 0 {
+            return;
+        }
+        self.prices.insert(asset, cost as f64 / quantity as f64);
+        self.lots.entry(asset).or_default().push_back(Lot {
+            asset,
+            quantity,
+            cost,
+            acquired_at: at,
+            signature: signature.to_string(),
+        });
+    }
+
+    /// Dispose of `quantity` `asset` for `proceeds` quote atomic units,
+    /// drawing on open lots by the tracker's [`LotMethod`].
+    pub fn dispose(&mut self, asset: Pubkey, quantity: u64, proceeds: u64, at: Option<i64>, signature: &str) {
+        if asset == self.quote_mint || quantity == 0 {
+            return;
+        }
+        self.prices.insert(asset, proceeds as f64 / quantity as f64);
+        let lots = self.lots.entry(asset).or_default();
+        let (mut left, mut proceeds_left) = (quantity, proceeds);
+        while left > 0 {
+            let lot = match self.method {
+                LotMethod::Fifo => lots.front_mut(),
+                LotMethod::Lifo => lots.back_mut(),
+            };
+            let Some(lot) = lot else { break };
+            let take = left.min(lot.quantity);
+            let cost = mul_div(lot.cost, take, lot.quantity);
+            let part = if take == left { proceeds_left } else { mul_div(proceeds, take, quantity) };
+            lot.quantity -= take;
+            lot.cost -= cost;
+            left -= take;
+            proceeds_left -= part;
+            self.disposals.push(Disposal {
+                asset,
+                quantity:           take,
+                proceeds:           part,
+                cost_basis:         Some(cost),
+                gain:               Some(part as i64 - cost as i64),
+                acquired_at:        lot.acquired_at,
+                disposed_at:        at,
+                held_secs:          at.zip(lot.acquired_at).map(|(d, a)| d - a),
+                acquired_signature: Some(lot.signature.clone()),
+                signature:          signature.to_string(),
+            });
+            if lot.quantity == 0 {
+                match self.method {
+                    LotMethod::Fifo => lots.pop_front(),
+                    LotMethod::Lifo => lots.pop_back(),
+                };
+            }
+        }
+        if left > 0 {
+            self.disposals.push(Disposal {
+                asset,
+                quantity:           left,
+                proceeds:           proceeds_left,
+                cost_basis:         None,
+                gain:               None,
+                acquired_at:        None,
+                disposed_at:        at,
+                held_secs:          None,
+                acquired_signature: None,
+                signature:          signature.to_string(),
+            });
+        }
+    }
+
+    /// Open lots of every asset, oldest first per asset.
+    pub fn open_lots(&self) -> impl Iterator<Item = &Lot> {
+        self.lots.values().flatten()
+    }
+
+    /// Realized gains, income and open lots per asset, ordered by asset.
+    pub fn summary(&self) -> Vec<GainSummary> {
+        let mut out: BTreeMap<Pubkey, GainSummary> = BTreeMap::new();
+        for d in &self.disposals {
+            let s = summary_entry(&mut out, &self.lp_pools, d.asset);
+            match d.cost_basis {
+                Some(cost) => {
+                    s.disposed += d.quantity;
+                    s.proceeds += d.proceeds;
+                    s.cost_basis += cost;
+                    s.realized_gain += d.proceeds as i64 - cost as i64;
+                }
+                None => {
+                    s.unmatched_quantity += d.quantity;
+                    s.unmatched_proceeds += d.proceeds;
+                }
+            }
+        }
+        for (&asset, &value) in &self.income {
+            summary_entry(&mut out, &self.lp_pools, asset).income += value;
+        }
+        for lot in self.open_lots() {
+            let s = summary_entry(&mut out, &self.lp_pools, lot.asset);
+            s.open_quantity += lot.quantity;
+            s.open_cost += lot.cost;
+        }
+        out.into_values().collect()
+    }
+
+    /// `amount` of `asset` in quote atomic units, at the last price seen.
+    fn value_of(&self, asset: Pubkey, amount: u64) -> Option<u64> {
+        if asset == self.quote_mint || amount == 0 {
+            return Some(amount);
+        }
+        self.prices.get(&asset).map(|p| (p * amount as f64).round() as u64)
+    }
+}
+
+fn summary_entry<'a>(
+    out:      &'a mut BTreeMap<Pubkey, GainSummary>,
+    lp_pools: &HashSet<Pubkey>,
+    asset:    Pubkey,
+) -> &'a mut GainSummary {
+    out.entry(asset).or_insert_with(|| GainSummary {
+        asset,
+        lp_shares:          lp_pools.contains(&asset),
+        disposed:           0,
+        proceeds:           0,
+        cost_basis:         0,
+        realized_gain:      0,
+        unmatched_quantity: 0,
+        unmatched_proceeds: 0,
+        income:             0,
+        open_quantity:      0,
+        open_cost:          0,
+    })
+}
+
+/// `v × num / den`, in u128.
+fn mul_div(v: u64, num: u64, den: u64) -> u64 {
+    (v as u128 * num as u128 / den as u128) as u64
+}
@@ -0,0 +1,214 @@
+//! Execution-quality analytics across a session of swaps, and tax lots
+//! across an owner's history ([`lots`]).
+//!
+//! [`ExecutionLog`] collects the outcome of every
+//! [`convert`](crate::A2ASwapClient::convert) an agent makes — the realized
+//! fill from [`SwapResult`] or the [`Error`] it failed with — and summarizes
+//! realized vs estimated output, slippage, network fee drag and failure rate.
+//! Export it as JSON or CSV to benchmark A2A-Swap against other venues.
+//!
+//! ```rust,no_run
+//! # use a2a_swap_sdk::{analytics::ExecutionLog, A2ASwapClient, SwapParams};
+//! # async fn run(client: A2ASwapClient, payer: solana_sdk::signature::Keypair, params: SwapParams) {
+//! let mut log = ExecutionLog::new();
+//! log.record(&client.convert(&payer, params).await);
+//! // … more swaps …
+//! let summary = log.summary();
+//! println!("p95 slippage {:?} bps, {:.1}% failed", summary.p95_slippage_bps, summary.failure_rate * 100.0);
+//! std::fs::write("session.csv", log.to_csv()).unwrap();
+//! # }
+//! ```
+
+pub mod lots;
+
+use serde::{Deserialize, Serialize};
+use solana_sdk::pubkey::Pubkey;
+
+use crate::error::{Error, Result};
+use crate::math::unix_now;
+use crate::types::SwapResult;
+
+/// One swap attempt in an [`ExecutionLog`].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ExecutionRecord {
+    /// Unix time the outcome was recorded.
+    pub timestamp: i64,
+    /// Last transaction signature (`None` for a failure).
+    pub signature: Option<String>,
+    /// Pool the swap went through (`None` for a failure).
+    pub pool: Option<Pubkey>,
+    /// Tokens sold.
+    pub amount_in: u64,
+    /// Pre-flight estimate of tokens received.
+    pub estimated_out: u64,
+    /// Tokens actually received, when the fill could be read back.
+    pub actual_out: Option<u64>,
+    /// Realized shortfall vs `estimated_out` in basis points.
+    pub realized_slippage_bps: Option<f64>,
+    /// Network fee in lamports.
+    pub fee_paid_lamports: Option<u64>,
+    /// Stable [`ErrorCode`](crate::ErrorCode) string for a failed swap.
+    pub error: Option<String>,
+}
+
+/// Aggregates over an [`ExecutionLog`].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ExecutionSummary {
+    /// Swap attempts recorded.
+    pub attempts: u64,
+    /// Attempts that failed.
+    pub failures: u64,
+    /// `failures / attempts` (`0.0` with no attempts).
+    pub failure_rate: f64,
+    /// Tokens sold by confirmed swaps.
+    pub total_in: u64,
+    /// Estimated output of the swaps whose fill was read back.
+    pub total_estimated_out: u64,
+    /// Actual output of the same swaps.
+    pub total_actual_out: u64,
+    /// Volume-weighted realized slippage: `total_actual_out` vs
+    /// `total_estimated_out` in basis points (`None` without fills).
+    pub weighted_slippage_bps: Option<f64>,
+    /// Mean of the per-swap realized slippage.
+    pub mean_slippage_bps: Option<f64>,
+    /// 95th percentile of the per-swap realized slippage.
+    pub p95_slippage_bps: Option<f64>,
+    /// Worst per-swap realized slippage.
+    pub max_slippage_bps: Option<f64>,
+    /// Network fees paid by confirmed swaps, in lamports.
+    pub total_fee_lamports: u64,
+    /// Mean network fee per confirmed swap whose fee is known.
+    pub mean_fee_lamports: Option<f64>,
+}
+
+/// Session log of swap outcomes — see the [module docs](self).
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ExecutionLog {
+    /// Every recorded attempt, in order.
+    pub records: Vec<ExecutionRecord>,
+}
+
+const CSV_HEADER: &str = "timestamp,signature,pool,amount_in,estimated_out,actual_out,\
+                          realized_slippage_bps,fee_paid_lamports,error";
+
+impl ExecutionLog {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record the outcome of a [`convert`](crate::A2ASwapClient::convert)
+    /// call, success or failure. A failure is recorded with `amount_in = 0`;
+    /// use [`record_failure`](Self::record_failure) to keep the amount.
+    pub fn record(&mut self, outcome: &Result<SwapResult>) {
+        match outcome {
+            Ok(result) => self.record_swap(result),
+            Err(e)     => self.record_failure(0, e),
+        }
+    }
+
+    /// Record a confirmed swap.
+    pub fn record_swap(&mut self, result: &SwapResult) {
+        self.records.push(ExecutionRecord {
+            timestamp:             unix_now(),
+            signature:             Some(result.signature.clone()),
+            pool:                  Some(result.pool),
+            amount_in:             result.amount_in,
+            estimated_out:         result.estimated_out,
+            actual_out:            result.actual_out,
+            realized_slippage_bps: result.realized_slippage_bps,
+            fee_paid_lamports:     result.actual_fee_paid,
+            error:                 None,
+        });
+    }
+
+    /// Record a failed swap of `amount_in` (pass `0` if unknown).
+    pub fn record_failure(&mut self, amount_in: u64, error: &Error) {
+        self.records.push(ExecutionRecord {
+            timestamp:             unix_now(),
+            signature:             None,
+            pool:                  None,
+            amount_in,
+            estimated_out:         0,
+            actual_out:            None,
+            realized_slippage_bps: None,
+            fee_paid_lamports:     None,
+            error:                 Some(error.code().as_str().to_string()),
+        });
+    }
+
+    /// Aggregate the session.
+    pub fn summary(&self) -> ExecutionSummary {
+        let ok: Vec<&ExecutionRecord> = self.records.iter().filter(|r| r.error.is_none()).collect();
+        let attempts = self.records.len() as u64;
+        let failures = attempts - ok.len() as u64;
+
+        let filled: Vec<(u64, u64)> = ok
+            .iter()
+            .filter_map(|r| r.actual_out.map(|out| (r.estimated_out, out)))
+            .collect();
+        let total_estimated_out = filled.iter().map(|&(e, _)| e).sum();
+        let total_actual_out = filled.iter().map(|&(_, a)| a).sum();
+
+        let mut slippage: Vec<f64> = ok.iter().filter_map(|r| r.realized_slippage_bps).collect();
+        slippage.sort_by(f64::total_cmp);
+        let fees: Vec<u64> = ok.iter().filter_map(|r| r.fee_paid_lamports).collect();
+        let total_fee_lamports = fees.iter().sum();
+
+        ExecutionSummary {
+            attempts,
+            failures,
+            failure_rate: if attempts == 0 { 0.0 } else { failures as f64 / attempts as f64 },
+            total_in: ok.iter().map(|r| r.amount_in).sum(),
+            total_estimated_out,
+            total_actual_out,
+            weighted_slippage_bps: (!filled.is_empty())
+                .then(|| crate::metrics::slippage_bps(total_estimated_out, total_actual_out)),
+            mean_slippage_bps: mean(&slippage),
+            p95_slippage_bps: percentile(&slippage, 0.95),
+            max_slippage_bps: slippage.last().copied(),
+            total_fee_lamports,
+            mean_fee_lamports: (!fees.is_empty())
+                .then(|| total_fee_lamports as f64 / fees.len() as f64),
+        }
+    }
+
+    /// The records and summary as one JSON object:
+    /// `{ "summary": {…}, "records": [ … ] }`.
+    pub fn to_json(&self) -> serde_json::Value {
+        serde_json::json!({ "summary": self.summary(), "records": self.records })
+    }
+
+    /// One CSV row per record, with a header line. Missing values are empty.
+    pub fn to_csv(&self) -> String {
+        fn opt<T: ToString>(v: &Option<T>) -> String {
+            v.as_ref().map(T::to_string).unwrap_or_default()
+        }
+        let mut out = String::from(CSV_HEADER);
+        out.push('\n');
+        for r in &self.records {
+            out.push_str(&format!(
+                "{},{},{},{},{},{},{},{},{}\n",
+                r.timestamp,
+                opt(&r.signature),
+                opt(&r.pool),
+                r.amount_in,
+                r.estimated_out,
+                opt(&r.actual_out),
+                opt(&r.realized_slippage_bps),
+                opt(&r.fee_paid_lamports),
+                opt(&r.error),
+            ));
+        }
+        out
+    }
+}
+
+fn mean(sorted: &[f64]) -> Option<f64> {
+    (!sorted.is_empty()).then(|| sorted.iter().sum::<f64>() / sorted.len() as f64)
+}
+
+/// Nearest-rank percentile of an ascending slice.
+fn percentile(sorted: &[f64], p: f64) -> Option<f64> {
+    let rank = (p * sorted.len() as f64).ceil() as usize;
+    sorted.get(rank.clamp(1, sorted.len().max(1)) - 1).copied()
+}
@@ -0,0 +1,264 @@
+//! Backtesting strategies over recorded pool history.
+//!
+//! History is a list of [`HistoryEvent`]s for one pool — full snapshots,
+//! reserve states (e.g. from `sync` or an indexer) and other traders' swaps,
+//! which are replayed through [`simulate_snapshot`](crate::math::simulate_snapshot),
+//! the same math as [`simulate_detailed`](crate::math::simulate_detailed).
+//! [`updates`] folds it into the [`PoolUpdate`]s a [`Strategy`] sees, and
+//! [`Backtest`] ticks the strategy through them on a backtest
+//! [`Runner`], so every fill is quoted against the recorded reserves.
+//!
+//! The resulting [`BacktestReport`] has every trade with its fees and price
+//! impact, the strategy's net flows and their P&L marked to the final price.
+//!
+//! ```rust,no_run
+//! # use a2a_swap_sdk::{backtest::{self, Backtest, HistoryEvent}, strategies::dca::DcaStrategy, PoolInfo};
+//! # async fn run(start: PoolInfo, jsonl: &str) -> a2a_swap_sdk::Result<()> {
+//! let history = HistoryEvent::from_jsonl(jsonl)?;
+//! let mut dca = DcaStrategy::new(start.mint_b, start.mint_a, 1_000_000, 3_600);
+//! let report = Backtest::new().run(&mut dca, backtest::updates(start, &history)?).await?;
+//! println!("{} trades, P&L {:.0}, fees {:.0}", report.trades.len(), report.pnl, report.fees_paid);
+//! # Ok(())
+//! # }
+//! ```
+
+use serde::{Deserialize, Serialize};
+use solana_sdk::pubkey::Pubkey;
+
+use crate::error::{Error, Result};
+use crate::math::{apply_simulated_swap, set_reserves, simulate_snapshot};
+use crate::strategies::{Action, Policy, PoolUpdate, RunEvent, Runner, Strategy};
+use crate::types::PoolInfo;
+
+// ─── History ──────────────────────────────────────────────────────────────────
+
+/// One recorded change to a pool.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum HistoryEvent {
+    /// The whole pool state at `timestamp`.
+    Snapshot(Box<PoolUpdate>),
+    /// Vault reserves at `timestamp`; everything else carries over.
+    Reserves { timestamp: i64, reserve_a: u64, reserve_b: u64 },
+    /// A swap someone else made at `timestamp`, replayed against the
+    /// reserves before it.
+    Swap { timestamp: i64, a_to_b: bool, amount_in: u64 },
+}
+
+impl HistoryEvent {
+    /// Parse one JSON event per line; blank lines are skipped.
+    pub fn from_jsonl(jsonl: &str) -> Result<Vec<Self>> {
+        jsonl
+            .lines()
+            .enumerate()
+            .filter(|(_, line)| !line.trim().is_empty())
+            .map(|(i, line)| {
+                serde_json::from_str(line)
+                    .map_err(|e| Error::InvalidArgument(format!("history line {}: {e}", i + 1)))
+            })
+            .collect()
+    }
+}
+
+/// Fold `events`, in order, into the pool update after each one, starting
+/// from `start`. The LP fee stays at the last snapshot's `effective_fee_bps`.
+pub fn updates(start: PoolInfo, events: &[HistoryEvent]) -> Result<Vec<PoolUpdate>> {
+    let mut info = start;
+    let mut out = Vec::with_capacity(events.len());
+    for event in events {
+        let timestamp = match event {
+            HistoryEvent::Snapshot(update) => {
+                info = update.info.clone();
+                update.timestamp
+            }
+            HistoryEvent::Reserves { timestamp, reserve_a, reserve_b } => {
+                set_reserves(&mut info, *reserve_a, *reserve_b);
+                *timestamp
+            }
+            HistoryEvent::Swap { timestamp, a_to_b, amount_in } => {
+                let sim = simulate_snapshot(&info, *amount_in, *a_to_b)?;
+                apply_simulated_swap(&mut info, &sim)?;
+                *timestamp
+            }
+        };
+        out.push(PoolUpdate { info: info.clone(), timestamp });
+    }
+    Ok(out)
+}
+
+// ─── Report ───────────────────────────────────────────────────────────────────
+
+/// One filled action in a [`BacktestReport`].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BacktestTrade {
+    /// Timestamp of the update the trade was made at.
+    pub timestamp: i64,
+    pub mint_in: Pubkey,
+    pub mint_out: Pubkey,
+    pub amount_in: u64,
+    pub amount_out: u64,
+    /// LP fee, in `mint_in`.
+    pub lp_fee: u64,
+    /// Protocol fee, in `mint_in`.
+    pub protocol_fee: u64,
+    /// Pure curve slippage in basis points, fees excluded.
+    pub price_impact_bps: f64,
+}
+
+/// Outcome of a [`Backtest`] run.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BacktestReport {
+    /// [`Strategy::name`] of the strategy tested.
+    pub strategy: String,
+    /// Updates replayed.
+    pub ticks: u64,
+    pub trades: Vec<BacktestTrade>,
+    /// Actions a [`Policy`] refused.
+    pub rejected: u64,
+    /// Actions that failed their quote or pre-flight checks.
+    pub failed: u64,
+    /// Mint P&L is valued in — the pool's token B unless
+    /// [`Backtest::value_in`] picked the other.
+    pub quote_mint: Pubkey,
+    /// The pool's other token.
+    pub base_mint: Pubkey,
+    /// Net base / quote moved into the wallet: negative for what was spent.
+    pub net_base: i128,
+    pub net_quote: i128,
+    /// Quote atomic units per base atomic unit at the last update.
+    pub final_price: f64,
+    /// `net_quote + net_base × final_price`, in quote atomic units.
+    pub pnl: f64,
+    /// LP and protocol fees paid, valued in quote atomic units at the price
+    /// of the update each trade was made at.
+    pub fees_paid: f64,
+    /// Volume-weighted price impact across trades (`None` without trades).
+    pub mean_price_impact_bps: Option<f64>,
+    pub max_price_impact_bps: Option<f64>,
+}
+
+impl BacktestReport {
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).unwrap()
+    }
+}
+
+// ─── Engine ───────────────────────────────────────────────────────────────────
+
+/// Replays pool updates through a [`Strategy`] on a backtest [`Runner`] —
+/// see the [module docs](self).
+pub struct Backtest<'a> {
+    runner:     Runner<'a>,
+    quote_mint: Option<Pubkey>,
+}
+
+impl Default for Backtest<'_> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<'a> Backtest<'a> {
+    pub fn new() -> Self {
+        Self { runner: Runner::backtest(), quote_mint: None }
+    }
+
+    /// Screen every action through `policy`, as [`Runner::with_policy`].
+    pub fn with_policy(mut self, policy: impl Policy + Send + 'a) -> Self {
+        self.runner = self.runner.with_policy(policy);
+        self
+    }
+
+    /// Value P&L and fees in `mint` — either of the pool's tokens.
+    pub fn value_in(mut self, mint: Pubkey) -> Self {
+        self.quote_mint = Some(mint);
+        self
+    }
+
+    /// Tick `strategy` on each of `updates` and report. All updates must be
+    /// of the same pool; an empty history is an error.
+    pub async fn run<S: Strategy + ?Sized>(
+        &mut self,
+        strategy: &mut S,
+        updates:  impl IntoIterator<Item = PoolUpdate>,
+    ) -> Result<BacktestReport> {
+        let reports = self.runner.replay(strategy, updates).await;
+        let Some(last) = reports.last() else {
+            return Err(Error::InvalidArgument("backtest: no pool history to replay".into()));
+        };
+        let (mint_a, mint_b) = (last.update.info.mint_a, last.update.info.mint_b);
+        let quote_mint = self.quote_mint.unwrap_or(mint_b);
+        let base_mint = if quote_mint == mint_b {
+            mint_a
+        } else if quote_mint == mint_a {
+            mint_b
+        } else {
+            return Err(Error::InvalidArgument(format!("backtest: {quote_mint} is not in the pool")));
+        };
+        let final_price = last.update.price_of(base_mint).ok_or(Error::NoLiquidity)?;
+
+        let mut report = BacktestReport {
+            strategy: strategy.name().to_string(),
+            ticks: reports.len() as u64,
+            trades: Vec::new(),
+            rejected: 0,
+            failed: 0,
+            quote_mint,
+            base_mint,
+            net_base: 0,
+            net_quote: 0,
+            final_price,
+            pnl: 0.0,
+            fees_paid: 0.0,
+            mean_price_impact_bps: None,
+            max_price_impact_bps: None,
+        };
+        let mut impact_volume = 0.0;
+        for tick in &reports {
+            for event in &tick.events {
+                let (Action::Swap(params), fill, quote) = match event {
+                    RunEvent::Filled { action, fill, quote: Some(quote) } => (action, fill, quote),
+                    RunEvent::Filled { .. } => continue,
+                    RunEvent::Rejected { .. } => {
+                        report.rejected += 1;
+                        continue;
+                    }
+                    RunEvent::Failed { .. } => {
+                        report.failed += 1;
+                        continue;
+                    }
+                };
+                let fees = (quote.lp_fee + quote.protocol_fee) as f64;
+                if params.mint_in == base_mint {
+                    report.net_base -= fill.amount_in as i128;
+                    report.net_quote += fill.amount_out as i128;
+                    report.fees_paid += fees * tick.update.price_of(base_mint).unwrap_or(final_price);
+                } else {
+                    report.net_quote -= fill.amount_in as i128;
+                    report.net_base += fill.amount_out as i128;
+                    report.fees_paid += fees;
+                }
+                let price_impact_bps = quote.price_impact_pct * 100.0;
+                impact_volume += price_impact_bps * fill.amount_in as f64;
+                report.max_price_impact_bps =
+                    Some(report.max_price_impact_bps.map_or(price_impact_bps, |m| m.max(price_impact_bps)));
+                report.trades.push(BacktestTrade {
+                    timestamp:    tick.update.timestamp,
+                    mint_in:      params.mint_in,
+                    mint_out:     params.mint_out,
+                    amount_in:    fill.amount_in,
+                    amount_out:   fill.amount_out,
+                    lp_fee:       quote.lp_fee,
+                    protocol_fee: quote.protocol_fee,
+                    price_impact_bps,
+                });
+            }
+        }
+        let volume: u64 = report.trades.iter().map(|t| t.amount_in).sum();
+        if volume > 0 {
+            report.mean_price_impact_bps = Some(impact_volume / volume as f64);
+        }
+        report.pnl = report.net_quote as f64 + report.net_base as f64 * final_price;
+        Ok(report)
+    }
+}
@@ -0,0 +1,226 @@
+//! Slot-scoped account cache for [`A2ASwapClient`](crate::A2ASwapClient).
+//!
+//! An agent that quotes the same pair several times a second reads the same
+//! pool, treasury and vault accounts over and over, although they can only
+//! change once per slot. With an [`AccountCache`] attached through
+//! [`A2ASwapClient::with_account_cache`](crate::A2ASwapClient::with_account_cache),
+//! those reads are answered locally while they are still current:
+//!
+//! - an entry is tagged with the slot of the `getMultipleAccounts` response
+//!   that returned it, and is dropped as soon as a response from a newer slot
+//!   is seen or [`max_age`](AccountCache::with_max_age) (one slot, 400 ms, by
+//!   default) has passed; a read with any such key fetches all of its keys
+//!   again, so one quote never mixes slots;
+//! - accounts passed to [`watch`](AccountCache::watch) are kept current by an
+//!   `accountSubscribe` WebSocket subscription instead, and stay cached for as
+//!   long as it runs;
+//! - every writable account of a transaction the client sends is evicted.
+//!
+//! Position and history reads are never cached.
+//!
+//! ```rust,no_run
+//! # use a2a_swap_sdk::{cache::AccountCache, A2ASwapClient, SimulateParams};
+//! # use solana_sdk::pubkey::Pubkey;
+//! # #[tokio::main]
+//! # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! # let (pool, vault_a, vault_b) = (Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique());
+//! # let (sol, usdc) = (Pubkey::new_unique(), Pubkey::new_unique());
+//! let cache  = AccountCache::new();
+//! let client = A2ASwapClient::mainnet().with_account_cache(cache.clone());
+//!
+//! // Push pool and vault updates instead of polling for them.
+//! let _watch = cache.watch("wss://api.mainnet-beta.solana.com", &[pool, vault_a, vault_b]).await?;
+//! loop {
+//!     let sim = client.simulate(SimulateParams { mint_in: sol, mint_out: usdc, amount_in: 1_000_000 }).await?;
+//!     // ...
+//! #   break;
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::{Duration, Instant};
+
+use futures_util::stream::{select_all, StreamExt};
+use solana_account_decoder_client_types::UiAccountEncoding;
+use solana_client::{nonblocking::pubsub_client::PubsubClient, rpc_config::RpcAccountInfoConfig};
+use solana_sdk::{commitment_config::CommitmentConfig, pubkey::Pubkey};
+use tokio::{sync::oneshot, task::JoinHandle};
+
+use crate::error::{Error, Result};
+
+/// How long an unwatched entry is served without a newer slot being seen —
+/// one 400 ms slot.
+pub const DEFAULT_MAX_AGE: Duration = Duration::from_millis(400);
+
+/// Account data shared by every client it is attached to; clones share it.
+#[derive(Clone)]
+pub struct AccountCache {
+    state:   Arc<Mutex<CacheState>>,
+    max_age: Duration,
+}
+
+#[derive(Default)]
+struct CacheState {
+    /// Newest slot any response or notification has reported.
+    slot:    u64,
+    entries: HashMap<Pubkey, Entry>,
+    /// Keys with a live `accountSubscribe`.
+    watched: HashSet<Pubkey>,
+}
+
+struct Entry {
+    /// `None` for an account that does not exist.
+    data:    Option<Vec<u8>>,
+    slot:    u64,
+    fetched: Instant,
+}
+
+impl Default for AccountCache {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl AccountCache {
+    /// An empty cache with [`DEFAULT_MAX_AGE`].
+    pub fn new() -> Self {
+        Self { state: Arc::default(), max_age: DEFAULT_MAX_AGE }
+    }
+
+    /// Serve unwatched entries for at most `max_age`; `Duration::ZERO` only
+    /// keeps watched accounts.
+    pub fn with_max_age(mut self, max_age: Duration) -> Self {
+        self.max_age = max_age;
+        self
+    }
+
+    /// Newest slot seen in a response or notification.
+    pub fn slot(&self) -> u64 {
+        self.lock().slot
+    }
+
+    /// Number of entries held, current or not.
+    pub fn len(&self) -> usize {
+        self.lock().entries.len()
+    }
+
+    /// `true` when nothing is cached.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Drop `key` so the next read fetches it.
+    pub fn invalidate(&self, key: &Pubkey) {
+        self.lock().entries.remove(key);
+    }
+
+    /// Drop every entry.
+    pub fn clear(&self) {
+        self.lock().entries.clear();
+    }
+
+    /// Subscribe to `keys` over the WebSocket endpoint `ws_url` and keep
+    /// their entries current from the notifications, in a background task.
+    ///
+    /// Returns once every subscription is confirmed. The subscriptions end
+    /// when the returned [`CacheWatch`] is dropped or the connection closes;
+    /// the keys then fall back to slot-scoped caching. Needs a Tokio runtime.
+    pub async fn watch(&self, ws_url: &str, keys: &[Pubkey]) -> Result<CacheWatch> {
+        let config = RpcAccountInfoConfig {
+            encoding:   Some(UiAccountEncoding::Base64),
+            commitment: Some(CommitmentConfig::confirmed()),
+            ..RpcAccountInfoConfig::default()
+        };
+        let (ws_url, keys) = (ws_url.to_string(), keys.to_vec());
+        let (ready_tx, ready_rx) = oneshot::channel();
+        let cache = self.clone();
+        let watched = keys.clone();
+        let task = tokio::spawn(async move {
+            let client = match PubsubClient::new(&ws_url).await {
+                Ok(client) => client,
+                Err(e) => return drop(ready_tx.send(Err(format!("connect {ws_url}: {e}")))),
+            };
+            let mut streams = Vec::with_capacity(keys.len());
+            for key in &keys {
+                match client.account_subscribe(key, Some(config.clone())).await {
+                    Ok((stream, _unsubscribe)) => streams.push(stream.map(move |resp| (*key, resp))),
+                    Err(e) => return drop(ready_tx.send(Err(format!("accountSubscribe {key}: {e}")))),
+                }
+            }
+            cache.lock().watched.extend(keys.iter().copied());
+            let _ = ready_tx.send(Ok(()));
+
+            let mut updates = select_all(streams);
+            while let Some((key, resp)) = updates.next().await {
+                cache.update(key, resp.context.slot, resp.value.data.decode());
+            }
+            cache.unwatch(&keys);
+        });
+        match ready_rx.await {
+            Ok(Ok(()))  => Ok(CacheWatch { cache: self.clone(), keys: watched, task }),
+            Ok(Err(e))  => Err(Error::Subscription(e)),
+            Err(_)      => Err(Error::Subscription("subscription task ended".into())),
+        }
+    }
+
+    /// Current data for each of `keys`, in order; `None` where the entry is
+    /// missing or stale.
+    pub(crate) fn get(&self, keys: &[Pubkey]) -> Vec<Option<Option<Vec<u8>>>> {
+        let state = self.lock();
+        keys.iter()
+            .map(|key| {
+                let entry = state.entries.get(key)?;
+                let current = state.watched.contains(key)
+                    || (entry.slot >= state.slot && entry.fetched.elapsed() < self.max_age);
+                current.then(|| entry.data.clone())
+            })
+            .collect()
+    }
+
+    /// Record `data` for `key` as read at `slot`, unless a newer read is held.
+    pub(crate) fn update(&self, key: Pubkey, slot: u64, data: Option<Vec<u8>>) {
+        let mut state = self.lock();
+        state.slot = state.slot.max(slot);
+        if state.entries.get(&key).is_some_and(|e| e.slot > slot) {
+            return;
+        }
+        state.entries.insert(key, Entry { data, slot, fetched: Instant::now() });
+    }
+
+    fn unwatch(&self, keys: &[Pubkey]) {
+        let mut state = self.lock();
+        for key in keys {
+            state.watched.remove(key);
+            state.entries.remove(key);
+        }
+    }
+
+    fn lock(&self) -> MutexGuard<'_, CacheState> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+/// Handle to the subscriptions started by [`AccountCache::watch`]; dropping
+/// it unsubscribes.
+pub struct CacheWatch {
+    cache: AccountCache,
+    keys:  Vec<Pubkey>,
+    task:  JoinHandle<()>,
+}
+
+impl CacheWatch {
+    /// `false` once the connection has closed.
+    pub fn is_active(&self) -> bool {
+        !self.task.is_finished()
+    }
+}
+
+impl Drop for CacheWatch {
+    fn drop(&mut self) {
+        self.task.abort();
+        self.cache.unwatch(&self.keys);
+    }
+}
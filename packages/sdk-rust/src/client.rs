@@ -1,33 +1,82 @@
 //! [`A2ASwapClient`] — the main entry point for agent integrations.
 
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::str::FromStr;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
 
 use solana_client::{
+    client_error::{ClientError, ClientErrorKind},
     nonblocking::rpc_client::RpcClient,
+    rpc_client::{GetConfirmedSignaturesForAddress2Config, RpcClientConfig},
     rpc_config::{RpcAccountInfoConfig, RpcProgramAccountsConfig},
     rpc_filter::{Memcmp, MemcmpEncodedBytes, RpcFilterType},
+    rpc_request::RpcRequest,
 };
+use futures_util::future::{try_join, try_join_all};
+use solana_account_decoder_client_types::{UiAccountEncoding, UiDataSliceConfig};
+use solana_rpc_client::http_sender::HttpSender;
 use solana_sdk::{
     commitment_config::CommitmentConfig,
-    hash::hash,
     instruction::{AccountMeta, Instruction},
     pubkey::Pubkey,
     signature::{Keypair, Signature, Signer},
     transaction::Transaction,
 };
 
+use a2a_swap_core::{ix, Instruction as _};
+
 use crate::{
+    allowlist::{verify_proof, AllowlistTree},
+    cache::AccountCache,
     error::{Error, Result},
+    analytics::lots::{LotMethod, LotTracker},
+    export::{apply_cost_basis, decode_tx, FeeRecord, LiquidityRecord, PositionRecord, TradeRecord},
     instructions::{
-        ata_program_id, derive_ata, derive_pool, derive_pool_authority, derive_position,
-        derive_treasury, initialize_pool_ix, provide_liquidity_ix, spl_token_id, swap_ix,
+        approve_intents_ix, ata_program_id, cancel_swap_commitment_ix, cast_vote_ix, close_pool_ix, close_position_ix, commit_swap_ix,
+        create_delegate_ix, create_proposal_ix, derive_agent_volume, derive_ata, derive_ata_for_program, derive_delegate, derive_pool, derive_pool_authority,
+        derive_proposal as derive_governance_proposal, execute_proposal_ix, initialize_governance_ix,
+        migrate_protocol_config_ix, withdraw_vote_ix, derive_position, derive_range_pool, derive_range_position,
+        derive_receipt, derive_treasury, initialize_pool_ix, initialize_range_pool_ix,
+        migrate_pool_ix, migrate_position_ix, open_agent_volume_ix, set_pool_allowlist_ix, set_pool_gate_ix, provide_liquidity_ix, provide_liquidity_proportional_ix, provide_range_liquidity_ix,
+        receipt_accounts, register_trader_ix, reveal_swap_ix, revoke_delegate_ix, rotate_delegate_ix, spl_token_id,
+        swap_as_delegate_ix, swap_commitment_hash, swap_exact_out_ix, swap_ix,
+        update_position_settings_ix, with_agent_volume, with_gate_token, with_trader_pass,
+    },
+    math::{
+        check_swap_funds, compute_amount_b, curve_amount_in_for_exact_out, curve_price_impact_bps, effective_fee_bps,
+        impermanent_loss, lp_shares_for_deposit, lp_underlying, referral_fee, resolve_min_amount_out, simulate_detailed, split_tranches, spot_price,
+        spot_value, tranche_min_amount_out, unix_now, LAMPORTS_PER_SIGNATURE,
+    },
+    intent::{SignedIntent, SwapIntent},
+    metrics,
+    multisig::{
+        derive_proposal, derive_transaction, derive_vault, parse_multisig_transaction_index,
+        parse_vault_transaction, proposal_approve_ix, proposal_create_ix,
+        vault_transaction_create_ix, vault_transaction_execute_ix, VaultMessage,
+    },
+    trace,
+    program_error::A2AErrorCode,
+    reader::{
+        self, accounts_page_params, parse_accounts_page, AccountReader, AccountsPage, PoolQuote, ProgramScan,
+        DEFAULT_PROGRAM_ID, DEVNET_RPC, LOCALNET_RPC, MAINNET_RPC,
     },
-    math::{pending_fees_for_position, simulate_detailed},
-    state::{parse_pool, parse_position, parse_token_amount, PoolState, PositionState},
+    rebalancer::{plan_rebalance, RebalanceOutcome, RebalanceParams},
+    range_math::{self, amounts_for_liquidity, liquidity_for_amounts, sqrt_price_at_tick},
+    solana_pay,
+    state::{
+        parse_delegate, parse_pool, parse_position, parse_proposal, parse_range_pool, parse_token_amount, DelegateState,
+        GovernanceAction, PoolState, PositionState, RangePoolState, POOL_VERSION, POSITION_VERSION, PROTOCOL_CONFIG_LEN,
+        TOKEN_ACCOUNT_LEN,
+    },
+    vault::{self, VaultPool, VaultState},
     types::{
-        CreatePoolParams, CreatePoolResult, FeeSummary, PoolInfo, PositionInfo, ProvideParams,
-        ProvideResult, SimulateParams, SimulateResult, SwapParams, SwapResult,
+        CapabilityCard, CreateAndSeedParams, CreateAndSeedResult, CreatePoolParams, CreatePoolResult, CreateProposalResult,
+        CreateRangePoolParams, CreateRangePoolResult,
+        DelegateParams, DelegateResult, ExactOutParams, FeeSummary, MultisigProposal,
+        PoolInfo, Portfolio, PortfolioHolding, PortfolioPosition, PositionEntry, PositionInfo,
+        PositionPage, PositionQuery, PositionSettingsParams, ProposalInfo, ProvideParams, ProvideRangeParams, ProvideRangeResult, ProvideResult, SimulateParams,
+        ProtectionLevel, SimulateProvideResult, SimulateRemoveResult, SimulateResult, SwapParams, SwapPlan, SwapResult, TierInfo,
     },
 };
 
@@ -87,11 +136,105 @@ fn system_transfer_ix(from: &Pubkey, to: &Pubkey, lamports: u64) -> Instruction
     }
 }
 
+/// Push `swap` onto `instructions`, wrapping `wrap_amount` lamports into the
+/// agent's wSOL account first when `mint_in` is SOL, and creating then
+/// closing the wSOL output account around it when `mint_out` is SOL.
+fn push_wrapped_swap(
+    instructions: &mut Vec<Instruction>,
+    agent:        &Pubkey,
+    mint_in:      &Pubkey,
+    mint_out:     &Pubkey,
+    wrap_amount:  u64,
+    swap:         Instruction,
+) {
+    let wsol_mint = Pubkey::from_str(WSOL_MINT).unwrap();
+    let agent_token_in  = derive_ata(agent, mint_in);
+    let agent_token_out = derive_ata(agent, mint_out);
+
+    // If mint_in is SOL: wrap native SOL → wSOL ATA before the swap.
+    if *mint_in == wsol_mint {
+        instructions.push(create_ata_idempotent_ix(agent, &agent_token_in, agent, &wsol_mint));
+        instructions.push(system_transfer_ix(agent, &agent_token_in, wrap_amount));
+        instructions.push(sync_native_ix(&agent_token_in));
+    }
+
+    // If mint_out is SOL: ensure the wSOL output ATA exists before the swap.
+    if *mint_out == wsol_mint {
+        instructions.push(create_ata_idempotent_ix(agent, &agent_token_out, agent, &wsol_mint));
+    }
+
+    instructions.push(swap);
+
+    // If mint_out is SOL: close the wSOL ATA and return lamports as native SOL.
+    if *mint_out == wsol_mint {
+        instructions.push(close_account_ix(&agent_token_out, agent, agent));
+    }
+}
+
 // ─── Constants ────────────────────────────────────────────────────────────────
 
-const DEFAULT_PROGRAM_ID: &str = "8XJfG4mHqRZjByAd7HxHdEALfB8jVtJVQsdhGEmysTFq";
-const DEVNET_RPC:  &str = "https://api.devnet.solana.com";
-const MAINNET_RPC: &str = "https://api.mainnet-beta.solana.com";
+/// Pre-flight result shared by [`A2ASwapClient::plan_convert`] and
+/// [`A2ASwapClient::convert_as_delegate`].
+struct ConvertQuote {
+    pool:               Pubkey,
+    pool_state:         PoolState,
+    a_to_b:             bool,
+    estimated_out:      u64,
+    min_amount_out:     u64,
+    protocol_fee:       u64,
+    referral_share_bps: u64,
+    impact_bps:         u64,
+}
+
+// ─── Connection ───────────────────────────────────────────────────────────────
+
+/// HTTP settings for the client's RPC connection pool.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RpcConfig {
+    /// Whole-request timeout, connect included.
+    pub timeout:           Duration,
+    /// TCP + TLS handshake timeout.
+    pub connect_timeout:   Duration,
+    /// How long an idle pooled connection is kept for reuse.
+    pub pool_idle_timeout: Duration,
+    /// TCP keep-alive probe interval on pooled connections; `None` disables it.
+    pub tcp_keepalive:     Option<Duration>,
+    /// Offer HTTP/2 during the TLS handshake (`https://` endpoints only);
+    /// `false` pins HTTP/1.1.
+    pub http2:             bool,
+}
+
+impl Default for RpcConfig {
+    fn default() -> Self {
+        Self {
+            timeout:           Duration::from_secs(30),
+            connect_timeout:   Duration::from_secs(10),
+            pool_idle_timeout: Duration::from_secs(90),
+            tcp_keepalive:     Some(Duration::from_secs(60)),
+            http2:             false,
+        }
+    }
+}
+
+impl RpcConfig {
+    /// A `confirmed`-commitment [`RpcClient`] for `url` over a pool built from `self`.
+    fn connect(&self, url: &str) -> RpcClient {
+        let mut http = reqwest::Client::builder()
+            .default_headers(HttpSender::default_headers())
+            .timeout(self.timeout)
+            .connect_timeout(self.connect_timeout)
+            .pool_idle_timeout(self.pool_idle_timeout)
+            .tcp_keepalive(self.tcp_keepalive);
+        if !self.http2 {
+            http = http.http1_only();
+        }
+        let http = http.build().expect("build RPC HTTP client");
+        RpcClient::new_sender(
+            HttpSender::new_with_client(url, http),
+            RpcClientConfig::with_commitment(CommitmentConfig::confirmed()),
+        )
+    }
+}
 
 // ─── Client ───────────────────────────────────────────────────────────────────
 
@@ -113,17 +256,35 @@ const MAINNET_RPC: &str = "https://api.mainnet-beta.solana.com";
 /// # Ok(())
 /// # }
 /// ```
+///
+/// The client holds one [`RpcClient`] for its lifetime, so connections are
+/// kept alive between calls; clones share it.
+#[derive(Clone)]
 pub struct A2ASwapClient {
     rpc_url:    String,
+    rpc_config: RpcConfig,
+    rpc:        Arc<RpcClient>,
+    /// `(url, client)` for program-account scans, if not `rpc`.
+    index:      Option<(String, Arc<RpcClient>)>,
+    scan:       ProgramScan,
     program_id: Pubkey,
+    cache:      Option<AccountCache>,
+    api_url:    String,
 }
 
 impl A2ASwapClient {
-    /// Create a client pointing at any RPC endpoint.
+    /// Create a client pointing at any RPC endpoint, with [`RpcConfig::default`].
     pub fn new(rpc_url: impl Into<String>) -> Self {
+        let rpc_url = rpc_url.into();
         Self {
-            rpc_url:    rpc_url.into(),
+            rpc_config: RpcConfig::default(),
+            rpc:        Arc::new(RpcConfig::default().connect(&rpc_url)),
+            rpc_url,
+            index:      None,
+            scan:       ProgramScan::default(),
             program_id: Pubkey::from_str(DEFAULT_PROGRAM_ID).unwrap(),
+            cache:      None,
+            api_url:    solana_pay::DEFAULT_API_URL.into(),
         }
     }
 
@@ -137,21 +298,70 @@ impl A2ASwapClient {
         Self::new(MAINNET_RPC)
     }
 
+    /// Pre-configured client for a local `solana-test-validator`.
+    pub fn localnet() -> Self {
+        Self::new(LOCALNET_RPC)
+    }
+
     /// Override the program ID (useful for locally deployed programs in tests).
     pub fn with_program_id(mut self, program_id: Pubkey) -> Self {
         self.program_id = program_id;
         self
     }
 
+    /// Reconnect with other timeouts, keep-alive or HTTP/2 settings.
+    pub fn with_rpc_config(mut self, config: RpcConfig) -> Self {
+        self.rpc_config = config;
+        self.rpc = Arc::new(config.connect(&self.rpc_url));
+        if let Some((url, index)) = &mut self.index {
+            *index = Arc::new(config.connect(url));
+        }
+        self
+    }
+
+    /// How positions and pools are scanned — see [`ProgramScan`].
+    pub fn with_program_scan(mut self, scan: ProgramScan) -> Self {
+        self.scan = scan;
+        self
+    }
+
+    /// Send program-account scans to `index_url` (a private node or a
+    /// self-hosted indexer speaking the same JSON-RPC methods); everything
+    /// else stays on the main endpoint.
+    pub fn with_index_url(mut self, index_url: impl Into<String>) -> Self {
+        let url = index_url.into();
+        self.index = Some((url.clone(), Arc::new(self.rpc_config.connect(&url))));
+        self
+    }
+
+    /// Answer pool, treasury and vault reads from `cache` while they are
+    /// current — see [`cache`](crate::cache).
+    pub fn with_account_cache(mut self, cache: AccountCache) -> Self {
+        self.cache = Some(cache);
+        self
+    }
+
+    /// Point [`solana_pay_link`](Self::solana_pay_link) at a self-hosted
+    /// HTTP API instead of [`solana_pay::DEFAULT_API_URL`].
+    pub fn with_api_url(mut self, api_url: impl Into<String>) -> Self {
+        self.api_url = api_url.into();
+        self
+    }
+
     // ── Write operations ──────────────────────────────────────────────────────
 
-    /// Create a new constant-product pool.
+    /// Create a new pool with the curve in `params.curve`.
     ///
     /// Fresh keypairs for `vault_a` and `vault_b` are generated internally and
     /// returned in the result — no need to provide them.
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        name = "a2a_swap.create_pool", skip_all, err,
+        fields(mint_a = %params.mint_a, mint_b = %params.mint_b, fee_rate_bps = params.fee_rate_bps,
+               pool = tracing::field::Empty, signature = tracing::field::Empty),
+    ))]
     pub async fn create_pool(
         &self,
-        payer:  &Keypair,
+        payer:  &dyn Signer,
         params: CreatePoolParams,
     ) -> Result<CreatePoolResult> {
         let rpc = self.rpc();
@@ -169,8 +379,11 @@ impl A2ASwapClient {
             &vault_a.pubkey(),
             &vault_b.pubkey(),
             params.fee_rate_bps,
+            params.curve,
         );
-        let sig = self.sign_and_send(&rpc, &[ix], payer, &[&vault_a, &vault_b]).await?;
+        trace::record("pool", pool);
+        let sig = self.sign_and_send(rpc, &[ix], payer, &[&vault_a, &vault_b], "create_pool").await?;
+        trace::record("signature", sig);
 
         Ok(CreatePoolResult {
             signature:    sig.to_string(),
@@ -181,33 +394,44 @@ impl A2ASwapClient {
             mint_a:       params.mint_a,
             mint_b:       params.mint_b,
             fee_rate_bps: params.fee_rate_bps,
+            curve:        params.curve,
         })
     }
 
     /// Deposit tokens into a pool and receive LP shares.
     ///
     /// The pool is auto-discovered for the given mint pair (both orderings are
-    /// tried).  If `params.amount_b` is `None` the SDK fetches live reserves
-    /// and computes the proportional amount automatically; `Some(n)` overrides.
+    /// tried).  If `params.amount_b` is `None` it sends
+    /// `provide_liquidity_proportional`, so the program computes the other
+    /// side from the reserves at execution; `Some(n)` deposits exactly `n`.
+    ///
+    /// `amount_a_max` / `amount_b_max` are checked here against the amounts
+    /// at the current reserves, failing early with `DepositExceedsMax`. With
+    /// `amount_b` left out the program checks the computed side again as
+    /// `max_other`, and the returned `amount_a` / `amount_b` are that same
+    /// estimate; explicit amounts are exact, so there is nothing to recheck.
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        name = "a2a_swap.provide_liquidity", skip_all, err,
+        fields(mint_a = %params.mint_a, mint_b = %params.mint_b, amount_a = params.amount_a,
+               pool = tracing::field::Empty, signature = tracing::field::Empty),
+    ))]
     pub async fn provide_liquidity(
         &self,
-        payer:  &Keypair,
+        payer:  &dyn Signer,
         params: ProvideParams,
     ) -> Result<ProvideResult> {
         let rpc = self.rpc();
 
-        let (pool_addr, pool_state, a_to_b) =
-            self.find_pool_inner(&rpc, &params.mint_a, &params.mint_b).await?;
+        let PoolQuote { pool: pool_addr, state: pool_state, a_to_b, reserve_a, reserve_b, .. } =
+            self.find_pool_inner(&params.mint_a, &params.mint_b).await?;
+        trace::record("pool", pool_addr);
         let (pool_authority, _) = derive_pool_authority(&pool_addr, &self.program_id);
         let (position, _)       = derive_position(&pool_addr, &payer.pubkey(), &self.program_id);
 
-        let reserve_a = parse_token_amount(&rpc.get_account_data(&pool_state.token_a_vault).await?)?;
-        let reserve_b = parse_token_amount(&rpc.get_account_data(&pool_state.token_b_vault).await?)?;
-
         // Map user mint ordering → pool ordering.
         // a_to_b = true  → params.mint_a is pool.token_a_mint
         // a_to_b = false → params.mint_a is pool.token_b_mint
-        let (amount_pool_a, amount_pool_b, ata_pool_a, ata_pool_b) = if a_to_b {
+        let (amount_pool_a, amount_pool_b, ata_pool_a, ata_pool_b, max_pool_a, max_pool_b) = if a_to_b {
             let b = compute_amount_b(
                 params.amount_a, params.amount_b,
                 reserve_a, reserve_b, pool_state.lp_supply,
@@ -216,6 +440,7 @@ impl A2ASwapClient {
                 params.amount_a, b,
                 derive_ata(&payer.pubkey(), &params.mint_a),
                 derive_ata(&payer.pubkey(), &params.mint_b),
+                params.amount_a_max, params.amount_b_max,
             )
         } else {
             // params.mint_a = pool.token_b_mint; compute pool.token_a_mint amount
@@ -228,77 +453,636 @@ impl A2ASwapClient {
                 params.amount_a,     // amount going to vault_b (pool.token_b_mint = params.mint_a)
                 derive_ata(&payer.pubkey(), &params.mint_b), // ata for pool.token_a_mint
                 derive_ata(&payer.pubkey(), &params.mint_a), // ata for pool.token_b_mint
+                params.amount_b_max,
+                params.amount_a_max,
+            )
+        };
+        if max_pool_a.is_some_and(|max| amount_pool_a > max)
+            || max_pool_b.is_some_and(|max| amount_pool_b > max)
+        {
+            return Err(Error::Program(A2AErrorCode::DepositExceedsMax));
+        }
+
+        let mut ix = if params.amount_b.is_none() {
+            // params.amount_a is fixed; the program sizes the other side.
+            let max_other = if a_to_b { max_pool_b } else { max_pool_a };
+            provide_liquidity_proportional_ix(
+                &self.program_id,
+                &payer.pubkey(),
+                &pool_addr,
+                &pool_authority,
+                &position,
+                &pool_state.token_a_vault,
+                &pool_state.token_b_vault,
+                &ata_pool_a,
+                &ata_pool_b,
+                params.amount_a,
+                a_to_b,
+                max_other.unwrap_or(0),
+                params.min_lp,
+                params.auto_compound,
+                params.compound_threshold,
+            )
+        } else {
+            provide_liquidity_ix(
+                &self.program_id,
+                &payer.pubkey(),
+                &pool_addr,
+                &pool_authority,
+                &position,
+                &pool_state.token_a_vault,
+                &pool_state.token_b_vault,
+                &ata_pool_a,
+                &ata_pool_b,
+                amount_pool_a,
+                amount_pool_b,
+                params.min_lp,
+                params.auto_compound,
+                params.compound_threshold,
             )
         };
+        let receipt = params.mint_receipt.then(|| {
+            ix.accounts.extend(receipt_accounts(&position, &self.program_id));
+            derive_receipt(&position, &self.program_id).0
+        });
+        let existing = self.fetch_position_state(rpc, &position).await?;
+        let lp_before = existing.as_ref().map_or(0, |p| p.lp_shares);
+        let mut instructions = self.migration_ixs(
+            &payer.pubkey(), &pool_addr, &pool_state, existing.as_ref().map(|p| (&position, p)),
+        );
+        instructions.push(ix);
+        let sig = self.sign_and_send(rpc, &instructions, payer, &[], "provide_liquidity").await?;
+        trace::record("signature", sig);
+
+        let share = fetch_position_share(rpc, &position, &pool_addr).await;
+        Ok(ProvideResult {
+            signature:      sig.to_string(),
+            pool:           pool_addr,
+            position,
+            amount_a:       amount_pool_a,
+            amount_b:       amount_pool_b,
+            receipt,
+            lp_minted:      share.and_then(|(lp, _)| lp.checked_sub(lp_before)),
+            lp_shares:      share.map(|(lp, _)| lp),
+            pool_share_pct: share.map(|(_, pct)| pct),
+        })
+    }
+
+    /// Turn auto-compound on or off, or change its threshold, on the payer's
+    /// position in the `mint_a` / `mint_b` pool — without depositing.
+    ///
+    /// Migrates a position on an older layout first. Returns the signature.
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        name = "a2a_swap.update_position_settings", skip_all, err,
+        fields(mint_a = %params.mint_a, mint_b = %params.mint_b, auto_compound = params.auto_compound,
+               pool = tracing::field::Empty, signature = tracing::field::Empty),
+    ))]
+    pub async fn update_position_settings(
+        &self,
+        payer:  &dyn Signer,
+        params: PositionSettingsParams,
+    ) -> Result<String> {
+        let rpc = self.rpc();
+        let PoolQuote { pool: pool_addr, state: pool_state, .. } =
+            self.find_pool_inner(&params.mint_a, &params.mint_b).await?;
+        trace::record("pool", pool_addr);
+        let (position, _) = derive_position(&pool_addr, &payer.pubkey(), &self.program_id);
+        let Some(existing) = self.fetch_position_state(rpc, &position).await? else {
+            return Err(Error::InvalidArgument(format!(
+                "no position for {} in pool {pool_addr}", payer.pubkey()
+            )));
+        };
 
-        let ix = provide_liquidity_ix(
+        let mut instructions =
+            self.migration_ixs(&payer.pubkey(), &pool_addr, &pool_state, Some((&position, &existing)));
+        instructions.push(update_position_settings_ix(
             &self.program_id,
             &payer.pubkey(),
+            &position,
+            params.auto_compound,
+            params.compound_threshold,
+        ));
+        let sig = self.sign_and_send(rpc, &instructions, payer, &[], "update_position_settings").await?;
+        trace::record("signature", sig);
+        Ok(sig.to_string())
+    }
+
+    /// Close the payer's position in the `mint_a` / `mint_b` pool and reclaim
+    /// its rent. The position must be empty: remove its liquidity and claim
+    /// its fees first, or this fails with [`A2AErrorCode::PositionNotEmpty`]
+    /// before anything is sent.
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        name = "a2a_swap.close_position", skip_all, err,
+        fields(mint_a = %mint_a, mint_b = %mint_b,
+               pool = tracing::field::Empty, signature = tracing::field::Empty),
+    ))]
+    pub async fn close_position(&self, payer: &dyn Signer, mint_a: Pubkey, mint_b: Pubkey) -> Result<String> {
+        let rpc = self.rpc();
+        let PoolQuote { pool: pool_addr, state: pool_state, .. } = self.find_pool_inner(&mint_a, &mint_b).await?;
+        trace::record("pool", pool_addr);
+        let (position, _) = derive_position(&pool_addr, &payer.pubkey(), &self.program_id);
+        let Some(account) = rpc.get_account_with_commitment(&position, rpc.commitment()).await?.value else {
+            return Err(Error::InvalidArgument(format!(
+                "no position for {} in pool {pool_addr}", payer.pubkey()
+            )));
+        };
+        let state = parse_position(&account.data)?;
+        if state.lp_shares > 0 || state.fees_owed_a > 0 || state.fees_owed_b > 0 {
+            return Err(Error::Program(A2AErrorCode::PositionNotEmpty));
+        }
+
+        let mut instructions = self.migration_ixs(&payer.pubkey(), &pool_addr, &pool_state, Some((&position, &state)));
+        instructions.push(close_position_ix(&self.program_id, &payer.pubkey(), &position, &pool_addr));
+        let sig = self.sign_and_send(rpc, &instructions, payer, &[], "close_position").await?;
+        trace::record("signature", sig);
+        Ok(sig.to_string())
+    }
+
+    /// Close the empty `mint_a` / `mint_b` pool and its vaults, reclaiming
+    /// their rent. The payer must be the pool's creator or the protocol
+    /// config admin; the pool must have no LP shares, no reserves and no open
+    /// positions, or this fails with [`A2AErrorCode::PoolNotEmpty`] before
+    /// anything is sent. Dust left in the vaults goes to the payer's
+    /// associated token accounts.
+    ///
+    /// Pools created before positions were counted can never be closed.
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        name = "a2a_swap.close_pool", skip_all, err,
+        fields(mint_a = %mint_a, mint_b = %mint_b,
+               pool = tracing::field::Empty, signature = tracing::field::Empty),
+    ))]
+    pub async fn close_pool(&self, payer: &dyn Signer, mint_a: Pubkey, mint_b: Pubkey) -> Result<String> {
+        let rpc = self.rpc();
+        let PoolQuote { pool: pool_addr, state, .. } = self.find_pool_inner(&mint_a, &mint_b).await?;
+        trace::record("pool", pool_addr);
+        if !state.is_closable() {
+            return Err(Error::Program(A2AErrorCode::PoolNotEmpty));
+        }
+
+        let closer = payer.pubkey();
+        let mut instructions = self.migration_ixs(&closer, &pool_addr, &state, None);
+        let (pool_authority, _) = derive_pool_authority(&pool_addr, &self.program_id);
+        // Vault dust is swept to the closer's ATAs, created here if missing.
+        let closer_token_a = derive_ata(&closer, &state.token_a_mint);
+        let closer_token_b = derive_ata(&closer, &state.token_b_mint);
+        instructions.push(create_ata_idempotent_ix(&closer, &closer_token_a, &closer, &state.token_a_mint));
+        instructions.push(create_ata_idempotent_ix(&closer, &closer_token_b, &closer, &state.token_b_mint));
+        instructions.push(close_pool_ix(
+            &self.program_id,
+            &closer,
             &pool_addr,
             &pool_authority,
+            &state.token_a_vault,
+            &state.token_b_vault,
+            &closer_token_a,
+            &closer_token_b,
+            state.creator != closer,
+        ));
+        let sig = self.sign_and_send(rpc, &instructions, payer, &[], "close_pool").await?;
+        trace::record("signature", sig);
+        Ok(sig.to_string())
+    }
+
+    /// Restrict swaps on the pool for `mint_a` / `mint_b` to `traders`, or
+    /// open it to everyone again with an empty list. Only the pool's creator
+    /// may; each listed trader then registers once with
+    /// [`register_trader`](Self::register_trader) and the proof from
+    /// [`AllowlistTree::proof`]. Replacing the list makes every trader
+    /// register again.
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        name = "a2a_swap.set_pool_allowlist", skip_all, err,
+        fields(mint_a = %mint_a, mint_b = %mint_b, traders = traders.len(),
+               pool = tracing::field::Empty, signature = tracing::field::Empty),
+    ))]
+    pub async fn set_pool_allowlist(
+        &self,
+        creator: &dyn Signer,
+        mint_a:  Pubkey,
+        mint_b:  Pubkey,
+        traders: &[Pubkey],
+    ) -> Result<String> {
+        let rpc = self.rpc();
+        let PoolQuote { pool: pool_addr, state, .. } = self.find_pool_inner(&mint_a, &mint_b).await?;
+        trace::record("pool", pool_addr);
+        if state.creator != creator.pubkey() {
+            return Err(Error::Program(A2AErrorCode::Unauthorized));
+        }
+        let root = if traders.is_empty() { [0; 32] } else { AllowlistTree::new(traders)?.root() };
+
+        let ix = set_pool_allowlist_ix(&self.program_id, &creator.pubkey(), &pool_addr, root);
+        let sig = self.sign_and_send(rpc, &[ix], creator, &[], "set_pool_allowlist").await?;
+        trace::record("signature", sig);
+        Ok(sig.to_string())
+    }
+
+    /// Register `trader` on the permissioned pool for `mint_a` / `mint_b`
+    /// with its allowlist `proof`, so its swaps there carry a valid
+    /// `TraderPass`. The proof is checked against the pool's current root
+    /// before anything is sent; the trader pays the pass's rent.
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        name = "a2a_swap.register_trader", skip_all, err,
+        fields(mint_a = %mint_a, mint_b = %mint_b,
+               pool = tracing::field::Empty, signature = tracing::field::Empty),
+    ))]
+    pub async fn register_trader(
+        &self,
+        trader: &dyn Signer,
+        mint_a: Pubkey,
+        mint_b: Pubkey,
+        proof:  Vec<[u8; 32]>,
+    ) -> Result<String> {
+        let rpc = self.rpc();
+        let PoolQuote { pool: pool_addr, state, .. } = self.find_pool_inner(&mint_a, &mint_b).await?;
+        trace::record("pool", pool_addr);
+        if !state.is_permissioned() || !verify_proof(&state.allowlist_root, &trader.pubkey(), &proof) {
+            return Err(Error::Program(A2AErrorCode::InvalidAllowlistProof));
+        }
+
+        let ix = register_trader_ix(&self.program_id, &trader.pubkey(), &pool_addr, proof);
+        let sig = self.sign_and_send(rpc, &[ix], trader, &[], "register_trader").await?;
+        trace::record("signature", sig);
+        Ok(sig.to_string())
+    }
+
+    /// Require traders on the pool for `mint_a` / `mint_b` to hold
+    /// `gate_mint` — e.g. a soulbound compliance credential, under SPL Token
+    /// or Token-2022 — to swap, or lift the gate with `None`. Only the pool's
+    /// creator may. Swaps through this client attach the trader's gate token
+    /// themselves.
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        name = "a2a_swap.set_pool_gate", skip_all, err,
+        fields(mint_a = %mint_a, mint_b = %mint_b, gate_mint = ?gate_mint,
+               pool = tracing::field::Empty, signature = tracing::field::Empty),
+    ))]
+    pub async fn set_pool_gate(
+        &self,
+        creator:   &dyn Signer,
+        mint_a:    Pubkey,
+        mint_b:    Pubkey,
+        gate_mint: Option<Pubkey>,
+    ) -> Result<String> {
+        let rpc = self.rpc();
+        let PoolQuote { pool: pool_addr, state, .. } = self.find_pool_inner(&mint_a, &mint_b).await?;
+        trace::record("pool", pool_addr);
+        if state.creator != creator.pubkey() {
+            return Err(Error::Program(A2AErrorCode::Unauthorized));
+        }
+
+        let mut ixs = self.migration_ixs(&creator.pubkey(), &pool_addr, &state, None);
+        ixs.push(set_pool_gate_ix(&self.program_id, &creator.pubkey(), &pool_addr, &gate_mint.unwrap_or_default()));
+        let sig = self.sign_and_send(rpc, &ixs, creator, &[], "set_pool_gate").await?;
+        trace::record("signature", sig);
+        Ok(sig.to_string())
+    }
+
+    /// Create a pool and make its first deposit in one transaction.
+    ///
+    /// With [`create_pool`](Self::create_pool) followed by
+    /// [`provide_liquidity`](Self::provide_liquidity), the pool is visible on
+    /// chain empty and without a price in between, and whoever deposits first
+    /// sets it. Here `initialize_pool` and `provide_liquidity` land together,
+    /// so the pool's first observable state already has `amount_b / amount_a`
+    /// as its price.
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        name = "a2a_swap.create_and_seed_pool", skip_all, err,
+        fields(mint_a = %params.pool.mint_a, mint_b = %params.pool.mint_b,
+               fee_rate_bps = params.pool.fee_rate_bps, amount_a = params.amount_a, amount_b = params.amount_b,
+               pool = tracing::field::Empty, signature = tracing::field::Empty),
+    ))]
+    pub async fn create_and_seed_pool(
+        &self,
+        payer:  &dyn Signer,
+        params: CreateAndSeedParams,
+    ) -> Result<CreateAndSeedResult> {
+        if params.amount_a == 0 || params.amount_b == 0 {
+            return Err(Error::InvalidArgument(
+                "create_and_seed_pool needs non-zero amount_a and amount_b".into(),
+            ));
+        }
+        let rpc = self.rpc();
+        let CreatePoolParams { mint_a, mint_b, fee_rate_bps, curve } = params.pool;
+
+        let vault_a = Keypair::new();
+        let vault_b = Keypair::new();
+        let (pool, _)           = derive_pool(&mint_a, &mint_b, &self.program_id);
+        let (pool_authority, _) = derive_pool_authority(&pool, &self.program_id);
+        let (position, _)       = derive_position(&pool, &payer.pubkey(), &self.program_id);
+        trace::record("pool", pool);
+
+        let init = initialize_pool_ix(
+            &self.program_id,
+            &payer.pubkey(),
+            &mint_a,
+            &mint_b,
+            &vault_a.pubkey(),
+            &vault_b.pubkey(),
+            fee_rate_bps,
+            curve,
+        );
+        let mut seed = provide_liquidity_ix(
+            &self.program_id,
+            &payer.pubkey(),
+            &pool,
+            &pool_authority,
             &position,
-            &pool_state.token_a_vault,
-            &pool_state.token_b_vault,
-            &ata_pool_a,
-            &ata_pool_b,
-            amount_pool_a,
-            amount_pool_b,
+            &vault_a.pubkey(),
+            &vault_b.pubkey(),
+            &derive_ata(&payer.pubkey(), &mint_a),
+            &derive_ata(&payer.pubkey(), &mint_b),
+            params.amount_a,
+            params.amount_b,
             params.min_lp,
             params.auto_compound,
             params.compound_threshold,
         );
-        let sig = self.sign_and_send(&rpc, &[ix], payer, &[]).await?;
-
-        Ok(ProvideResult {
-            signature: sig.to_string(),
-            pool:      pool_addr,
-            position,
-            amount_a:  amount_pool_a,
-            amount_b:  amount_pool_b,
+        let receipt = params.mint_receipt.then(|| {
+            seed.accounts.extend(receipt_accounts(&position, &self.program_id));
+            derive_receipt(&position, &self.program_id).0
+        });
+        let lp = lp_shares_for_deposit(params.amount_a, params.amount_b, 0, 0, 0).ok();
+        let sig = self
+            .sign_and_send(rpc, &[init, seed], payer, &[&vault_a, &vault_b], "create_and_seed_pool")
+            .await?;
+        trace::record("signature", sig);
+
+        Ok(CreateAndSeedResult {
+            pool: CreatePoolResult {
+                signature:    sig.to_string(),
+                pool,
+                pool_authority,
+                vault_a:      vault_a.pubkey(),
+                vault_b:      vault_b.pubkey(),
+                mint_a,
+                mint_b,
+                fee_rate_bps,
+                curve,
+            },
+            liquidity: ProvideResult {
+                signature:      sig.to_string(),
+                pool,
+                position,
+                amount_a:       params.amount_a,
+                amount_b:       params.amount_b,
+                receipt,
+                // The seed is the pool's first deposit: the position holds
+                // the whole supply.
+                lp_minted:      lp,
+                lp_shares:      lp,
+                pool_share_pct: lp.map(|_| 100.0),
+            },
         })
     }
 
     /// Swap one token for another.
     ///
     /// The pool is auto-discovered for the given mint pair.
-    /// Pass `max_slippage_bps = 0` to disable the slippage guard.
-    pub async fn convert(&self, payer: &Keypair, params: SwapParams) -> Result<SwapResult> {
+    /// The slippage guard is [`SwapParams::max_slippage_bps`] or an explicit
+    /// [`SwapParams::min_amount_out`] (not both; neither disables it), and
+    /// `max_price_impact_bps = 0` disables the price-impact cap. With
+    /// [`SwapParams::protection`] set, a large swap is sent as randomized
+    /// tranches — see [`ProtectionLevel`]. Fails with
+    /// [`Error::InsufficientBalance`] before anything is sent when the
+    /// agent's input token account is missing or holds less than
+    /// `amount_in`, or its wallet cannot pay the fee and rent.
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        name = "a2a_swap.convert", skip_all, err,
+        fields(mint_in = %params.mint_in, mint_out = %params.mint_out, amount_in = params.amount_in,
+               pool = tracing::field::Empty, signature = tracing::field::Empty),
+    ))]
+    pub async fn convert(&self, payer: &dyn Signer, params: SwapParams) -> Result<SwapResult> {
         let rpc = self.rpc();
+        match params.protection {
+            ProtectionLevel::None => {}
+            ProtectionLevel::CommitReveal => return self.convert_commit_reveal(rpc, payer, params).await,
+            _ => return self.convert_protected(rpc, payer, params).await,
+        }
+        let (mint_in, mint_out) = (params.mint_in, params.mint_out);
+        let (plan, vault_out) = self.plan_convert(rpc, &payer.pubkey(), params).await?;
+        self.check_swap_funds(rpc, &payer.pubkey(), &mint_in, &mint_out, plan.amount_in, 1).await?;
 
-        let (pool_addr, pool_state, a_to_b) =
-            self.find_pool_inner(&rpc, &params.mint_in, &params.mint_out).await?;
-        let (pool_authority, _) = derive_pool_authority(&pool_addr, &self.program_id);
+        let sent = self.sign_and_send(rpc, &plan.instructions, payer, &[], "swap").await;
+        metrics::swap_submitted(sent.is_ok());
+        let sig = sent?;
+        trace::record("signature", sig);
 
-        let reserve_a = parse_token_amount(&rpc.get_account_data(&pool_state.token_a_vault).await?)?;
-        let reserve_b = parse_token_amount(&rpc.get_account_data(&pool_state.token_b_vault).await?)?;
-        let (reserve_in, reserve_out) = if a_to_b { (reserve_a, reserve_b) } else { (reserve_b, reserve_a) };
+        let fill = fetch_fill(rpc, &sig, &vault_out).await;
+        Ok(SwapResult {
+            signature:      sig.to_string(),
+            pool:           plan.pool,
+            amount_in:      plan.amount_in,
+            estimated_out:  plan.estimated_out,
+            min_amount_out: plan.min_amount_out,
+            referral_fee:   plan.referral_fee,
+            a_to_b:         plan.a_to_b,
+            actual_out:     None,
+            actual_fee_paid: None,
+            realized_slippage_bps: None,
+            tranche_signatures: Vec::new(),
+        }
+        .with_fill(fill))
+    }
 
-        let sim = simulate_detailed(
-            pool_addr, &pool_state, reserve_in, reserve_out, params.amount_in, a_to_b,
-        )?;
+    /// [`convert`](Self::convert) under [`SwapParams::protection`]: size the
+    /// tranches from the whole swap's price impact, then send them a random
+    /// 1–3 slots apart, each re-quoted with the tighter slippage bound, and
+    /// stop once a filled tranche realizes more slippage than allowed.
+    async fn convert_protected(
+        &self,
+        rpc:    &RpcClient,
+        payer:  &dyn Signer,
+        params: SwapParams,
+    ) -> Result<SwapResult> {
+        let level = params.protection;
+        let impact_bps = self.quote_convert(&params).await?.impact_bps;
+        let parts = split_tranches(params.amount_in, level.tranches_for(impact_bps), random_u64());
+        self.check_swap_funds(
+            rpc, &payer.pubkey(), &params.mint_in, &params.mint_out, params.amount_in, parts.len() as u64,
+        ).await?;
+
+        let mut result = SwapResult {
+            signature:      String::new(),
+            pool:           Pubkey::default(),
+            amount_in:      0,
+            estimated_out:  0,
+            min_amount_out: 0,
+            referral_fee:   0,
+            a_to_b:         false,
+            actual_out:     None,
+            actual_fee_paid: None,
+            realized_slippage_bps: None,
+            tranche_signatures: Vec::new(),
+        };
+        let mut filled = Some(Fill { out: 0, fee: 0 });
+        let mut last_slot = 0;
+        for (i, &amount_in) in parts.iter().enumerate() {
+            if i > 0 {
+                wait_for_slot(rpc, last_slot + 1 + random_u64() % 3).await?;
+            }
+            let tranche = SwapParams {
+                amount_in,
+                max_slippage_bps: level.tranche_slippage_bps(params.max_slippage_bps),
+                min_amount_out: params
+                    .min_amount_out
+                    .map(|min| tranche_min_amount_out(min, amount_in, params.amount_in)),
+                protection: ProtectionLevel::None,
+                ..params.clone()
+            };
+            let (plan, vault_out) = self.plan_convert(rpc, &payer.pubkey(), tranche).await?;
+
+            let sent = self.sign_and_send(rpc, &plan.instructions, payer, &[], "swap").await;
+            metrics::swap_submitted(sent.is_ok());
+            let sig = sent?;
+            trace::record("signature", sig);
+            last_slot = rpc.get_slot().await?;
+
+            // If the fill can't be read, assume the worst one the program allowed.
+            let fill = fetch_fill(rpc, &sig, &vault_out).await;
+            let realized = fill.as_ref().map_or(plan.min_amount_out, |f| f.out);
+            let slippage = metrics::slippage_bps(plan.estimated_out, realized);
+            filled = filled.zip(fill).map(|(a, b)| Fill { out: a.out + b.out, fee: a.fee + b.fee });
+
+            result.signature = sig.to_string();
+            result.pool = plan.pool;
+            result.a_to_b = plan.a_to_b;
+            result.amount_in += plan.amount_in;
+            result.estimated_out += plan.estimated_out;
+            result.min_amount_out += plan.min_amount_out;
+            result.referral_fee += plan.referral_fee;
+            result.tranche_signatures.push(sig.to_string());
+
+            if i + 1 < parts.len() && slippage > level.abort_slippage_bps() as f64 {
+                metrics::slippage(slippage);
+                return Err(Error::ProtectionAborted {
+                    realized_bps: slippage.round() as i64,
+                    max_bps:      level.abort_slippage_bps(),
+                    filled_in:    result.amount_in,
+                    amount_in:    params.amount_in,
+                    signatures:   result.tranche_signatures,
+                });
+            }
+        }
+        Ok(result.with_fill(filled))
+    }
 
-        let min_amount_out = if params.max_slippage_bps == 0 {
-            0
+    /// [`convert`](Self::convert) under [`ProtectionLevel::CommitReveal`]:
+    /// send `commit_swap` with the hash of the planned swap, wait for a later
+    /// slot, then send the plan with its swap replaced by `reveal_swap`. A
+    /// failed reveal closes the commitment again before returning the error.
+    /// `reveal_swap` carries no deadline, so it is checked here before the
+    /// reveal is sent.
+    async fn convert_commit_reveal(
+        &self,
+        rpc:    &RpcClient,
+        payer:  &dyn Signer,
+        params: SwapParams,
+    ) -> Result<SwapResult> {
+        let agent = payer.pubkey();
+        let (mut plan, vault_out) =
+            self.plan_convert(rpc, &agent, SwapParams { referrer: None, ..params }).await?;
+        self.check_swap_funds(rpc, &agent, &params.mint_in, &params.mint_out, plan.amount_in, 2).await?;
+        let at = plan.instructions.iter()
+            .position(|i| i.program_id == self.program_id && i.data.starts_with(&ix::Swap::DISCRIMINATOR))
+            .expect("plan_convert always includes the swap");
+        let salt: [u8; 32] = rand::random();
+        let (commit, reveal) = commit_reveal_pair(&self.program_id, &plan.instructions[at], salt)?;
+        plan.instructions[at] = reveal;
+
+        let committed = self.sign_and_send(rpc, &[commit], payer, &[], "commit_swap").await?;
+        wait_for_slot(rpc, rpc.get_slot().await? + 1).await?;
+
+        let sent = if params.deadline_unix != 0 && unix_now() > params.deadline_unix {
+            Err(Error::Program(A2AErrorCode::DeadlineExceeded))
         } else {
-            sim.estimated_out
-                .saturating_sub(sim.estimated_out * params.max_slippage_bps as u64 / 10_000)
+            self.sign_and_send(rpc, &plan.instructions, payer, &[], "swap").await
+        };
+        metrics::swap_submitted(sent.is_ok());
+        let sig = match sent {
+            Ok(sig) => sig,
+            Err(e) => {
+                let cancel = cancel_swap_commitment_ix(&self.program_id, &agent, &plan.pool);
+                // Best effort: the reveal may have landed despite the error.
+                let _ = self.sign_and_send(rpc, &[cancel], payer, &[], "cancel_swap_commitment").await;
+                return Err(e);
+            }
         };
 
-        if params.max_slippage_bps > 0 && sim.estimated_out < min_amount_out {
-            return Err(Error::SlippageExceeded {
-                estimated: sim.estimated_out,
-                min:       min_amount_out,
+        let fill = fetch_fill(rpc, &sig, &vault_out).await;
+        Ok(SwapResult {
+            signature:      sig.to_string(),
+            pool:           plan.pool,
+            amount_in:      plan.amount_in,
+            estimated_out:  plan.estimated_out,
+            min_amount_out: plan.min_amount_out,
+            referral_fee:   0,
+            a_to_b:         plan.a_to_b,
+            actual_out:     None,
+            actual_fee_paid: None,
+            realized_slippage_bps: None,
+            tranche_signatures: vec![committed.to_string(), sig.to_string()],
+        }
+        .with_fill(fill))
+    }
+
+    /// Swap for exactly `params.amount_out` of `mint_out`, paying whatever
+    /// input the pool's curve requires up to `params.max_amount_in` — e.g. to
+    /// settle an invoice.
+    ///
+    /// The pre-flight quote fails with [`Error::MaxInputExceeded`] before
+    /// anything is sent; the program recomputes the input against live
+    /// reserves and enforces the same cap. For SOL input, `max_amount_in` is
+    /// wrapped and any unspent wSOL stays in the agent's wSOL account.
+    /// Balances are checked first as in [`convert`](Self::convert), against
+    /// the quoted input (the wrapped cap for SOL).
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        name = "a2a_swap.convert_exact_out", skip_all, err,
+        fields(mint_in = %params.mint_in, mint_out = %params.mint_out, amount_out = params.amount_out,
+               pool = tracing::field::Empty, signature = tracing::field::Empty),
+    ))]
+    pub async fn convert_exact_out(&self, payer: &dyn Signer, params: ExactOutParams) -> Result<SwapResult> {
+        if params.amount_out == 0 {
+            return Err(Error::InvalidArgument("amount_out must be > 0".into()));
+        }
+        let rpc = self.rpc();
+        let agent = payer.pubkey();
+        let quote = self.find_pool_inner(&params.mint_in, &params.mint_out).await?;
+        let (reserve_in, reserve_out) = quote.reserves_in_out();
+        let PoolQuote { pool: pool_addr, state: pool_state, a_to_b, protocol_fee_bps, referral_share_bps, .. } = quote;
+        trace::record("pool", pool_addr);
+
+        let amount_in = curve_amount_in_for_exact_out(
+            pool_state.curve, reserve_in, reserve_out, effective_fee_bps(&pool_state, unix_now()),
+            protocol_fee_bps, params.amount_out,
+        )?;
+        if amount_in > params.max_amount_in {
+            return Err(Error::MaxInputExceeded { required: amount_in, max: params.max_amount_in });
+        }
+        let sim = simulate_detailed(
+            pool_addr, &pool_state, reserve_in, reserve_out, amount_in, protocol_fee_bps, a_to_b,
+        )?;
+        let impact_bps = curve_price_impact_bps(
+            pool_state.curve, sim.after_fees, sim.reserve_in, sim.reserve_out,
+        )?;
+        if params.max_price_impact_bps > 0 && impact_bps > params.max_price_impact_bps as u64 {
+            return Err(Error::PriceImpactExceeded {
+                impact_bps,
+                max_bps: params.max_price_impact_bps,
             });
         }
 
-        let agent_token_in  = derive_ata(&payer.pubkey(), &params.mint_in);
-        let agent_token_out = derive_ata(&payer.pubkey(), &params.mint_out);
+        let (pool_authority, _) = derive_pool_authority(&pool_addr, &self.program_id);
+        let agent_token_in  = derive_ata(&agent, &params.mint_in);
+        let agent_token_out = derive_ata(&agent, &params.mint_out);
         let (treasury, _)   = derive_treasury(&self.program_id);
         let treasury_token_in = derive_ata(&treasury, &params.mint_in);
+        let referrer_token  = params.referrer.map(|r| derive_ata(&r, &params.mint_in));
+        let referral_fee    = match referrer_token {
+            Some(_) => referral_fee(sim.protocol_fee, referral_share_bps),
+            None    => 0,
+        };
 
-        let swap_instruction = swap_ix(
+        let swap_instruction = swap_exact_out_ix(
             &self.program_id,
-            &payer.pubkey(),
+            &agent,
             &pool_addr,
             &pool_authority,
             &pool_state.token_a_vault,
@@ -307,266 +1091,2078 @@ impl A2ASwapClient {
             &agent_token_out,
             &treasury,
             &treasury_token_in,
-            params.amount_in,
-            min_amount_out,
+            referrer_token.as_ref(),
+            params.amount_out,
+            params.max_amount_in,
             a_to_b,
+            params.max_price_impact_bps,
+        );
+        let swap_instruction = self.tracked_swap(rpc, &agent, swap_instruction).await?;
+        let swap_instruction = pass_if_permissioned(swap_instruction, &pool_addr, &pool_state, &agent);
+        let swap_instruction = self.gated_swap(rpc, swap_instruction, &pool_state, &agent).await?;
+        let mut instructions = self.migration_ixs(&agent, &pool_addr, &pool_state, None);
+        push_wrapped_swap(
+            &mut instructions, &agent, &params.mint_in, &params.mint_out, params.max_amount_in,
+            swap_instruction,
         );
 
-        let wsol_mint = Pubkey::from_str(WSOL_MINT).unwrap();
-        let mut instructions: Vec<Instruction> = Vec::new();
-
-        // If mint_in is SOL: wrap native SOL → wSOL ATA before the swap.
-        if params.mint_in == wsol_mint {
-            instructions.push(create_ata_idempotent_ix(&payer.pubkey(), &agent_token_in, &payer.pubkey(), &wsol_mint));
-            instructions.push(system_transfer_ix(&payer.pubkey(), &agent_token_in, params.amount_in));
-            instructions.push(sync_native_ix(&agent_token_in));
-        }
-
-        // If mint_out is SOL: ensure the wSOL output ATA exists before the swap.
-        if params.mint_out == wsol_mint {
-            instructions.push(create_ata_idempotent_ix(&payer.pubkey(), &agent_token_out, &payer.pubkey(), &wsol_mint));
-        }
-
-        instructions.push(swap_instruction);
-
-        // If mint_out is SOL: close the wSOL ATA and return lamports as native SOL.
-        if params.mint_out == wsol_mint {
-            instructions.push(close_account_ix(&agent_token_out, &payer.pubkey(), &payer.pubkey()));
-        }
+        // SOL input wraps the whole cap; a token account only pays the quote.
+        let drawn = if params.mint_in == Pubkey::from_str(WSOL_MINT).unwrap() { params.max_amount_in } else { amount_in };
+        self.check_swap_funds(rpc, &agent, &params.mint_in, &params.mint_out, drawn, 1).await?;
 
-        let sig = self.sign_and_send(&rpc, &instructions, payer, &[]).await?;
+        let sent = self.sign_and_send(rpc, &instructions, payer, &[], "swap").await;
+        metrics::swap_submitted(sent.is_ok());
+        let sig = sent?;
+        trace::record("signature", sig);
 
+        let vault_out = if a_to_b { pool_state.token_b_vault } else { pool_state.token_a_vault };
+        let fill = fetch_fill(rpc, &sig, &vault_out).await;
         Ok(SwapResult {
             signature:      sig.to_string(),
             pool:           pool_addr,
-            amount_in:      params.amount_in,
-            estimated_out:  sim.estimated_out,
-            min_amount_out,
+            amount_in,
+            estimated_out:  params.amount_out,
+            min_amount_out: params.amount_out,
+            referral_fee,
             a_to_b,
-        })
+            actual_out:     None,
+            actual_fee_paid: None,
+            realized_slippage_bps: None,
+            tranche_signatures: Vec::new(),
+        }
+        .with_fill(fill))
     }
 
-    // ── Read operations ───────────────────────────────────────────────────────
-
-    /// Simulate a swap without submitting a transaction.
+    /// Build the instructions [`convert`](Self::convert) would send for
+    /// `agent`, without signing or sending them.
     ///
-    /// Returns a full fee and slippage breakdown including `protocol_fee`,
-    /// `lp_fee`, `estimated_out`, and `price_impact_pct`.
-    pub async fn simulate(&self, params: SimulateParams) -> Result<SimulateResult> {
+    /// Runs the same pre-flight simulation and slippage / price-impact checks.
+    /// Put the instructions in a transaction with `agent` as fee payer and a
+    /// recent blockhash, and have the agent sign it.
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        name = "a2a_swap.build_convert", skip_all, err,
+        fields(mint_in = %params.mint_in, mint_out = %params.mint_out, amount_in = params.amount_in,
+               pool = tracing::field::Empty),
+    ))]
+    pub async fn build_convert(&self, agent: &Pubkey, params: SwapParams) -> Result<SwapPlan> {
         let rpc = self.rpc();
+        Ok(self.plan_convert(rpc, agent, params).await?.0)
+    }
 
-        let (pool_addr, pool_state, a_to_b) =
-            self.find_pool_inner(&rpc, &params.mint_in, &params.mint_out).await?;
+    /// [`build_convert`](Self::build_convert), plus the output vault for
+    /// realized-slippage metrics.
+    async fn plan_convert(
+        &self,
+        rpc:    &RpcClient,
+        agent:  &Pubkey,
+        params: SwapParams,
+    ) -> Result<(SwapPlan, Pubkey)> {
+        if params.deadline_unix != 0 && unix_now() > params.deadline_unix {
+            return Err(Error::Program(A2AErrorCode::DeadlineExceeded));
+        }
+        let ConvertQuote {
+            pool: pool_addr, pool_state, a_to_b, estimated_out, min_amount_out, protocol_fee,
+            referral_share_bps, ..
+        } = self.quote_convert(&params).await?;
+        let (pool_authority, _) = derive_pool_authority(&pool_addr, &self.program_id);
 
-        let reserve_a = parse_token_amount(&rpc.get_account_data(&pool_state.token_a_vault).await?)?;
-        let reserve_b = parse_token_amount(&rpc.get_account_data(&pool_state.token_b_vault).await?)?;
-        let (reserve_in, reserve_out) = if a_to_b { (reserve_a, reserve_b) } else { (reserve_b, reserve_a) };
+        let agent_token_in  = derive_ata(agent, &params.mint_in);
+        let agent_token_out = derive_ata(agent, &params.mint_out);
+        let (treasury, _)   = derive_treasury(&self.program_id);
+        let treasury_token_in = derive_ata(&treasury, &params.mint_in);
+        let referrer_token  = params.referrer.map(|r| derive_ata(&r, &params.mint_in));
+        let referral_fee    = match referrer_token {
+            Some(_) => referral_fee(protocol_fee, referral_share_bps),
+            None    => 0,
+        };
 
-        simulate_detailed(pool_addr, &pool_state, reserve_in, reserve_out, params.amount_in, a_to_b)
-    }
+        let swap_instruction = swap_ix(
+            &self.program_id,
+            agent,
+            &pool_addr,
+            &pool_authority,
+            &pool_state.token_a_vault,
+            &pool_state.token_b_vault,
+            &agent_token_in,
+            &agent_token_out,
+            &treasury,
+            &treasury_token_in,
+            referrer_token.as_ref(),
+            params.amount_in,
+            min_amount_out,
+            a_to_b,
+            params.max_price_impact_bps,
+            params.deadline_unix,
+        );
+        let swap_instruction = self.tracked_swap(rpc, agent, swap_instruction).await?;
+        let swap_instruction = pass_if_permissioned(swap_instruction, &pool_addr, &pool_state, agent);
+        let swap_instruction = self.gated_swap(rpc, swap_instruction, &pool_state, agent).await?;
+
+        let mut instructions: Vec<Instruction> = self.migration_ixs(agent, &pool_addr, &pool_state, None);
+        push_wrapped_swap(
+            &mut instructions, agent, &params.mint_in, &params.mint_out, params.amount_in,
+            swap_instruction,
+        );
 
-    /// Fetch pool state plus current reserves and spot price.
-    pub async fn pool_info(&self, mint_a: Pubkey, mint_b: Pubkey) -> Result<PoolInfo> {
-        let rpc = self.rpc();
+        let vault_out = if a_to_b { pool_state.token_b_vault } else { pool_state.token_a_vault };
+        let plan = SwapPlan {
+            instructions,
+            pool:      pool_addr,
+            amount_in: params.amount_in,
+            estimated_out,
+            min_amount_out,
+            referral_fee,
+            a_to_b,
+        };
+        Ok((plan, vault_out))
+    }
 
-        let (pool_addr, pool_state, _) =
-            self.find_pool_inner(&rpc, &mint_a, &mint_b).await?;
+    /// Find the pool for `params` and run the pre-flight simulation and
+    /// slippage / price-impact checks shared by every swap path.
+    async fn quote_convert(&self, params: &SwapParams) -> Result<ConvertQuote> {
+        let quote = self.find_pool_inner(&params.mint_in, &params.mint_out).await?;
+        let (reserve_in, reserve_out) = quote.reserves_in_out();
+        let PoolQuote { pool: pool_addr, state: pool_state, a_to_b, protocol_fee_bps, referral_share_bps, .. } = quote;
+        trace::record("pool", pool_addr);
+        let sim = simulate_detailed(
+            pool_addr, &pool_state, reserve_in, reserve_out, params.amount_in, protocol_fee_bps,
+            a_to_b,
+        )?;
 
-        let reserve_a = parse_token_amount(&rpc.get_account_data(&pool_state.token_a_vault).await?)?;
-        let reserve_b = parse_token_amount(&rpc.get_account_data(&pool_state.token_b_vault).await?)?;
+        let min_amount_out =
+            resolve_min_amount_out(sim.estimated_out, params.max_slippage_bps, params.min_amount_out)?;
 
-        let spot_price = if reserve_a == 0 { 0.0 } else { reserve_b as f64 / reserve_a as f64 };
+        let impact_bps = curve_price_impact_bps(
+            pool_state.curve, sim.after_fees, sim.reserve_in, sim.reserve_out,
+        )?;
+        if params.max_price_impact_bps > 0 && impact_bps > params.max_price_impact_bps as u64 {
+            return Err(Error::PriceImpactExceeded {
+                impact_bps,
+                max_bps: params.max_price_impact_bps,
+            });
+        }
 
-        Ok(PoolInfo {
-            pool:         pool_addr,
-            mint_a:       pool_state.token_a_mint,
-            mint_b:       pool_state.token_b_mint,
-            vault_a:      pool_state.token_a_vault,
-            vault_b:      pool_state.token_b_vault,
-            reserve_a,
-            reserve_b,
-            lp_supply:    pool_state.lp_supply,
-            fee_rate_bps: pool_state.fee_rate_bps,
-            spot_price,
+        Ok(ConvertQuote {
+            pool: pool_addr,
+            pool_state,
+            a_to_b,
+            estimated_out: sim.estimated_out,
+            min_amount_out,
+            protocol_fee: sim.protocol_fee,
+            referral_share_bps,
+            impact_bps,
         })
     }
 
-    /// Fetch all LP positions owned by `owner` with pending fee calculations.
-    pub async fn my_positions(&self, owner: &Pubkey) -> Result<Vec<PositionInfo>> {
-        let rpc = self.rpc();
-        let positions = self.fetch_positions(&rpc, owner).await?;
-
-        // Batch-fetch unique pool accounts in one RPC call.
-        let pool_keys: Vec<Pubkey> = {
-            let mut v: Vec<Pubkey> = positions.iter().map(|(_, p)| p.pool).collect();
-            v.sort();
-            v.dedup();
-            v
-        };
-        let pool_accounts = rpc.get_multiple_accounts(&pool_keys).await?;
-        let pools: HashMap<Pubkey, PoolState> = pool_keys
-            .iter()
-            .zip(pool_accounts.iter())
-            .filter_map(|(k, maybe)| {
-                let acc = maybe.as_ref()?;
-                parse_pool(&acc.data).ok().map(|p| (*k, p))
-            })
-            .collect();
+    // ── Session keys ──────────────────────────────────────────────────────────
 
-        Ok(positions
-            .into_iter()
-            .map(|(addr, pos)| {
-                let (pending_a, pending_b) = pools
-                    .get(&pos.pool)
-                    .map(|pool| pending_fees_for_position(&pos, pool))
-                    .unwrap_or((0, 0));
-                PositionInfo {
-                    address:            addr,
-                    pool:               pos.pool,
-                    owner:              pos.owner,
-                    lp_shares:          pos.lp_shares,
-                    fees_owed_a:        pos.fees_owed_a,
-                    fees_owed_b:        pos.fees_owed_b,
-                    pending_fees_a:     pending_a,
-                    pending_fees_b:     pending_b,
-                    total_fees_a:       pos.fees_owed_a.saturating_add(pending_a),
-                    total_fees_b:       pos.fees_owed_b.saturating_add(pending_b),
-                    auto_compound:      pos.auto_compound,
-                    compound_threshold: pos.compound_threshold,
-                }
-            })
-            .collect())
+    /// Let `params.delegate` swap out of `owner`'s token account for
+    /// `params.mint` — at most `max_amount_in` in total, until `expires_at`.
+    ///
+    /// The owner signs only this (and later rotate / revoke); swaps are then
+    /// signed by the delegate with [`convert_as_delegate`](Self::convert_as_delegate),
+    /// so the funded key can stay cold. Uses the owner's associated token
+    /// account for the mint, which must exist.
+    pub async fn create_delegate(&self, owner: &dyn Signer, params: DelegateParams) -> Result<DelegateResult> {
+        self.set_delegate(owner, params, false).await
     }
 
-    /// Aggregate fee totals across all positions owned by `owner`.
-    pub async fn my_fees(&self, owner: &Pubkey) -> Result<FeeSummary> {
-        let positions = self.my_positions(owner).await?;
-        let total_a = positions.iter().map(|p| p.total_fees_a).sum();
-        let total_b = positions.iter().map(|p| p.total_fees_b).sum();
-        Ok(FeeSummary { positions, total_fees_a: total_a, total_fees_b: total_b })
+    /// Move an existing session to `params.delegate` with a new cap and
+    /// expiry. The previous key stops working and the spent counter resets.
+    pub async fn rotate_delegate(&self, owner: &dyn Signer, params: DelegateParams) -> Result<DelegateResult> {
+        self.set_delegate(owner, params, true).await
     }
 
-    // ── Private helpers ───────────────────────────────────────────────────────
+    /// End the session on `owner`'s `mint` token account and reclaim its rent.
+    pub async fn revoke_delegate(&self, owner: &dyn Signer, mint: Pubkey) -> Result<String> {
+        let rpc = self.rpc();
+        let owner_token = derive_ata(&owner.pubkey(), &mint);
+        let ix = revoke_delegate_ix(&self.program_id, &owner.pubkey(), &owner_token);
+        let sig = self.sign_and_send(rpc, &[ix], owner, &[], "revoke_delegate").await?;
+        Ok(sig.to_string())
+    }
 
-    fn rpc(&self) -> RpcClient {
-        RpcClient::new_with_commitment(self.rpc_url.clone(), CommitmentConfig::confirmed())
+    /// The session on `owner`'s `mint` token account.
+    pub async fn delegate_info(&self, owner: &Pubkey, mint: Pubkey) -> Result<DelegateState> {
+        let rpc = self.rpc();
+        let (address, _) = derive_delegate(owner, &derive_ata(owner, &mint), &self.program_id);
+        let data = rpc.get_account_data(&address).await.map_err(|_| {
+            Error::InvalidArgument(format!("no delegate for {owner} on mint {mint}"))
+        })?;
+        parse_delegate(&data)
     }
 
-    async fn sign_and_send(
+    /// [`convert`](Self::convert) with `owner`'s tokens, signed and paid for
+    /// by the session key `delegate`. Output goes to the owner's associated
+    /// token account for `mint_out`, created by the delegate if missing.
+    ///
+    /// The session's expiry and remaining cap are checked before sending and
+    /// fail as [`A2AErrorCode::DelegateExpired`] /
+    /// [`A2AErrorCode::DelegateCapExceeded`], as on-chain. Native SOL is not
+    /// wrapped: the owner's wSOL account must already hold the input.
+    /// `params.referrer` is not supported and `params.protection` is ignored.
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        name = "a2a_swap.convert_as_delegate", skip_all, err,
+        fields(owner = %owner, mint_in = %params.mint_in, mint_out = %params.mint_out,
+               amount_in = params.amount_in, pool = tracing::field::Empty,
+               signature = tracing::field::Empty),
+    ))]
+    pub async fn convert_as_delegate(
         &self,
-        rpc:          &RpcClient,
-        instructions: &[Instruction],
-        payer:        &Keypair,
-        extra:        &[&Keypair],
-    ) -> Result<Signature> {
-        let blockhash = rpc.get_latest_blockhash().await?;
-        let mut signers: Vec<&dyn Signer> = vec![payer];
-        signers.extend(extra.iter().map(|k| k as &dyn Signer));
-        let tx = Transaction::new_signed_with_payer(
-            instructions,
-            Some(&payer.pubkey()),
-            &signers,
-            blockhash,
+        delegate: &dyn Signer,
+        owner:    &Pubkey,
+        params:   SwapParams,
+    ) -> Result<SwapResult> {
+        if params.referrer.is_some() {
+            return Err(Error::InvalidArgument("delegated swaps do not pay a referrer".into()));
+        }
+        let session = self.delegate_info(owner, params.mint_in).await?;
+        if session.delegate != delegate.pubkey() {
+            return Err(Error::Program(A2AErrorCode::DelegateMismatch));
+        }
+        if unix_now() > session.expires_at {
+            return Err(Error::Program(A2AErrorCode::DelegateExpired));
+        }
+        if params.amount_in > session.remaining() {
+            return Err(Error::Program(A2AErrorCode::DelegateCapExceeded));
+        }
+
+        let rpc = self.rpc();
+        let ConvertQuote { pool, pool_state, a_to_b, estimated_out, min_amount_out, .. } =
+            self.quote_convert(&params).await?;
+        let (pool_authority, _) = derive_pool_authority(&pool, &self.program_id);
+        let owner_token_out   = derive_ata(owner, &params.mint_out);
+        let (treasury, _)     = derive_treasury(&self.program_id);
+        let treasury_token_in = derive_ata(&treasury, &params.mint_in);
+
+        let mut instructions = self.migration_ixs(&delegate.pubkey(), &pool, &pool_state, None);
+        instructions.push(create_ata_idempotent_ix(
+            &delegate.pubkey(), &owner_token_out, owner, &params.mint_out,
+        ));
+        let swap = swap_as_delegate_ix(
+            &self.program_id,
+            &delegate.pubkey(),
+            owner,
+            &pool,
+            &pool_authority,
+            &pool_state.token_a_vault,
+            &pool_state.token_b_vault,
+            &session.token_account,
+            &owner_token_out,
+            &treasury,
+            &treasury_token_in,
+            params.amount_in,
+            min_amount_out,
+            a_to_b,
+            params.max_price_impact_bps,
+        );
+        let swap = self.tracked_swap(rpc, owner, swap).await?;
+        let swap = pass_if_permissioned(swap, &pool, &pool_state, owner);
+        instructions.push(self.gated_swap(rpc, swap, &pool_state, owner).await?);
+
+        let sent = self.sign_and_send(rpc, &instructions, delegate, &[], "swap_as_delegate").await;
+        metrics::swap_submitted(sent.is_ok());
+        let sig = sent?;
+
+        let vault_out = if a_to_b { pool_state.token_b_vault } else { pool_state.token_a_vault };
+        let fill = fetch_fill(rpc, &sig, &vault_out).await;
+
+        Ok(SwapResult {
+            signature: sig.to_string(),
+            pool,
+            amount_in: params.amount_in,
+            estimated_out,
+            min_amount_out,
+            referral_fee: 0,
+            a_to_b,
+            actual_out: None,
+            actual_fee_paid: None,
+            realized_slippage_bps: None,
+            tranche_signatures: Vec::new(),
+        }
+        .with_fill(fill))
+    }
+
+    /// Shared body of [`create_delegate`](Self::create_delegate) and
+    /// [`rotate_delegate`](Self::rotate_delegate).
+    async fn set_delegate(
+        &self,
+        owner:  &dyn Signer,
+        params: DelegateParams,
+        rotate: bool,
+    ) -> Result<DelegateResult> {
+        if params.max_amount_in == 0 {
+            return Err(Error::InvalidArgument("max_amount_in must be greater than zero".into()));
+        }
+        if params.expires_at <= unix_now() {
+            return Err(Error::InvalidArgument("expires_at must be in the future".into()));
+        }
+        let rpc = self.rpc();
+        let owner_token = derive_ata(&owner.pubkey(), &params.mint);
+        let (delegate_account, _) = derive_delegate(&owner.pubkey(), &owner_token, &self.program_id);
+        let build = if rotate { rotate_delegate_ix } else { create_delegate_ix };
+        let op = if rotate { "rotate_delegate" } else { "create_delegate" };
+        let ix = build(
+            &self.program_id,
+            &owner.pubkey(),
+            &owner_token,
+            &params.delegate,
+            params.max_amount_in,
+            params.expires_at,
+        );
+        let sig = self.sign_and_send(rpc, &[ix], owner, &[], op).await?;
+
+        Ok(DelegateResult {
+            signature: sig.to_string(),
+            delegate_account,
+            token_account: owner_token,
+            delegate: params.delegate,
+            max_amount_in: params.max_amount_in,
+            expires_at: params.expires_at,
+        })
+    }
+
+    // ── Wallet-signed swaps ───────────────────────────────────────────────────
+
+    /// A Solana Pay transaction-request link for `params`, to hand a swap to
+    /// a human approver or a wallet-holding user instead of signing it: the
+    /// wallet fetches the transaction from the HTTP API's `/pay/convert`
+    /// (see [`with_api_url`](Self::with_api_url)) for its own account and
+    /// signs it there. Render the `solana:` string as a QR code for mobile.
+    ///
+    /// Nothing is read or sent. `min_amount_out`, `max_price_impact_bps` and
+    /// protection are not supported — see [`solana_pay::swap_request_url`].
+    pub fn solana_pay_link(&self, params: &SwapParams) -> Result<String> {
+        solana_pay::swap_link(&self.api_url, params)
+    }
+
+    // ── Relayed swap intents ──────────────────────────────────────────────────
+
+    /// The nonce `agent`'s next swap intent must carry: 0 before its first
+    /// relayed swap, then one more per executed intent.
+    pub async fn intent_nonce(&self, agent: &Pubkey) -> Result<u64> {
+        reader::fetch_intent_nonce(&self.accounts(), &self.program_id, agent).await
+    }
+
+    /// Let relayed swap intents spend up to `amount` of `agent`'s `mint`:
+    /// an SPL approval of the agent's `IntentNonce` PDA on its associated
+    /// token account. Replaces any earlier approval on that account.
+    pub async fn approve_intents(&self, agent: &dyn Signer, mint: Pubkey, amount: u64) -> Result<String> {
+        let rpc = self.rpc();
+        let token_account = derive_ata(&agent.pubkey(), &mint);
+        let ix = approve_intents_ix(&self.program_id, &agent.pubkey(), &token_account, amount);
+        let sig = self.sign_and_send(rpc, &[ix], agent, &[], "approve_intents").await?;
+        Ok(sig.to_string())
+    }
+
+    /// Quote `params` and sign it as a swap intent valid for `ttl_secs`,
+    /// for a relayer to submit with [`relay_intent`](Self::relay_intent).
+    /// Nothing is sent. The intent carries the agent's current nonce, so
+    /// signing another before this one runs makes one of them fail.
+    /// `params.referrer` is not supported and `params.protection` is ignored.
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        name = "a2a_swap.sign_intent", skip_all, err,
+        fields(mint_in = %params.mint_in, mint_out = %params.mint_out,
+               amount_in = params.amount_in, pool = tracing::field::Empty),
+    ))]
+    pub async fn sign_intent(&self, agent: &dyn Signer, params: &SwapParams, ttl_secs: i64) -> Result<SignedIntent> {
+        if params.referrer.is_some() {
+            return Err(Error::InvalidArgument("relayed swaps do not pay a referrer".into()));
+        }
+        if ttl_secs <= 0 {
+            return Err(Error::InvalidArgument("ttl_secs must be greater than zero".into()));
+        }
+        let ConvertQuote { pool, a_to_b, min_amount_out, .. } = self.quote_convert(params).await?;
+        trace::record("pool", pool);
+        let nonce = self.intent_nonce(&agent.pubkey()).await?;
+        SwapIntent {
+            pool,
+            amount_in: params.amount_in,
+            min_amount_out,
+            a_to_b,
+            max_price_impact_bps: params.max_price_impact_bps,
+            nonce,
+            expires_at: unix_now() + ttl_secs,
+        }
+        .sign(&self.program_id, agent)
+    }
+
+    /// Submit `signed` as `swap_with_intent`, with `relayer` paying the fee
+    /// and the rent of the agent's `IntentNonce` and output token account
+    /// when they are missing.
+    ///
+    /// The signature, expiry and nonce are checked before sending; a bad
+    /// one fails as [`A2AErrorCode::InvalidIntentSignature`],
+    /// [`A2AErrorCode::IntentExpired`] or [`A2AErrorCode::InvalidIntentNonce`],
+    /// as on-chain, so a relayer never pays for an intent that cannot run.
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        name = "a2a_swap.relay_intent", skip_all, err,
+        fields(agent = %signed.agent, pool = %signed.intent.pool,
+               amount_in = signed.intent.amount_in, signature = tracing::field::Empty),
+    ))]
+    pub async fn relay_intent(&self, relayer: &dyn Signer, signed: &SignedIntent) -> Result<SwapResult> {
+        let intent = signed.intent;
+        if !signed.verify(&self.program_id) {
+            return Err(Error::Program(A2AErrorCode::InvalidIntentSignature));
+        }
+        if unix_now() > intent.expires_at {
+            return Err(Error::Program(A2AErrorCode::IntentExpired));
+        }
+        if self.intent_nonce(&signed.agent).await? != intent.nonce {
+            return Err(Error::Program(A2AErrorCode::InvalidIntentNonce));
+        }
+
+        let rpc = self.rpc();
+        let data = self.accounts().multiple_account_data(&[intent.pool]).await?.pop().flatten();
+        let pool_state = match data {
+            Some(data) if !data.is_empty() => parse_pool(&data)?,
+            _ => return Err(Error::InvalidArgument(format!("no pool at {}", intent.pool))),
+        };
+        let (mint_out, vault_out) = if intent.a_to_b {
+            (pool_state.token_b_mint, pool_state.token_b_vault)
+        } else {
+            (pool_state.token_a_mint, pool_state.token_a_vault)
+        };
+
+        let mut instructions = self.migration_ixs(&relayer.pubkey(), &intent.pool, &pool_state, None);
+        instructions.push(create_ata_idempotent_ix(
+            &relayer.pubkey(), &derive_ata(&signed.agent, &mint_out), &signed.agent, &mint_out,
+        ));
+        let [verify, swap] = signed.instructions(&self.program_id, &relayer.pubkey(), &pool_state);
+        instructions.push(verify);
+        let swap = self.tracked_swap(rpc, &signed.agent, swap).await?;
+        instructions.push(self.gated_swap(rpc, swap, &pool_state, &signed.agent).await?);
+
+        let sent = self.sign_and_send(rpc, &instructions, relayer, &[], "swap_with_intent").await;
+        metrics::swap_submitted(sent.is_ok());
+        let sig = sent?;
+        trace::record("signature", sig);
+        let fill = fetch_fill(rpc, &sig, &vault_out).await;
+
+        Ok(SwapResult {
+            signature: sig.to_string(),
+            pool: intent.pool,
+            amount_in: intent.amount_in,
+            estimated_out: intent.min_amount_out,
+            min_amount_out: intent.min_amount_out,
+            referral_fee: 0,
+            a_to_b: intent.a_to_b,
+            actual_out: None,
+            actual_fee_paid: None,
+            realized_slippage_bps: None,
+            tranche_signatures: Vec::new(),
+        }
+        .with_fill(fill))
+    }
+
+    // ── A2A-Vault ─────────────────────────────────────────────────────────────
+
+    /// Read the A2A-Vault account at `vault`.
+    pub async fn vault(&self, vault: &Pubkey) -> Result<VaultState> {
+        match self.accounts().multiple_account_data(&[*vault]).await?.pop().flatten() {
+            Some(data) if !data.is_empty() => vault::parse_vault(&data),
+            _ => Err(Error::InvalidArgument(format!("no vault at {vault}"))),
+        }
+    }
+
+    /// The vault and its pools with their non-base mints, as `deposit` and
+    /// `withdraw` pass them.
+    async fn vault_with_pools(&self, vault_key: &Pubkey) -> Result<(VaultState, Vec<VaultPool>)> {
+        let state = self.vault(vault_key).await?;
+        let data = self.accounts().multiple_account_data(&state.pools).await?;
+        let pools = state
+            .pools
+            .iter()
+            .zip(data)
+            .map(|(pool, data)| {
+                let data = data.ok_or_else(|| Error::InvalidArgument(format!("no pool at {pool}")))?;
+                let p = parse_pool(&data)?;
+                let other_mint = if p.token_a_mint == state.base_mint { p.token_b_mint } else { p.token_a_mint };
+                Ok(VaultPool { pool: *pool, other_mint })
+            })
+            .collect::<Result<Vec<_>>>()?;
+        Ok((state, pools))
+    }
+
+    /// Deposit `amount` of the vault's base token from `depositor`'s
+    /// associated token account for at least `min_shares` vault shares,
+    /// creating its share account if needed.
+    pub async fn vault_deposit(
+        &self,
+        depositor:  &dyn Signer,
+        vault_key:  Pubkey,
+        amount:     u64,
+        min_shares: u64,
+    ) -> Result<String> {
+        let rpc = self.rpc();
+        let owner = depositor.pubkey();
+        let (state, pools) = self.vault_with_pools(&vault_key).await?;
+        let ixs = [
+            create_ata_idempotent_ix(&owner, &derive_ata(&owner, &state.share_mint), &owner, &state.share_mint),
+            vault::deposit_ix(
+                &vault::vault_program_id(), &self.program_id, &owner, &vault_key, &state, &pools, amount, min_shares,
+            ),
+        ];
+        let sig = self.sign_and_send(rpc, &ixs, depositor, &[], "vault_deposit").await?;
+        Ok(sig.to_string())
+    }
+
+    /// Burn `shares` of `owner`'s vault shares for at least `min_amount` of
+    /// the base token, paid to its associated token account. Fails with
+    /// the vault's `InsufficientIdle` when the manager has too little base
+    /// outside LP positions.
+    pub async fn vault_withdraw(
+        &self,
+        owner:      &dyn Signer,
+        vault_key:  Pubkey,
+        shares:     u64,
+        min_amount: u64,
+    ) -> Result<String> {
+        let rpc = self.rpc();
+        let key = owner.pubkey();
+        let (state, pools) = self.vault_with_pools(&vault_key).await?;
+        let ixs = [
+            create_ata_idempotent_ix(&key, &derive_ata(&key, &state.base_mint), &key, &state.base_mint),
+            vault::withdraw_ix(
+                &vault::vault_program_id(), &self.program_id, &key, &vault_key, &state, &pools, shares, min_amount,
+            ),
+        ];
+        let sig = self.sign_and_send(rpc, &ixs, owner, &[], "vault_withdraw").await?;
+        Ok(sig.to_string())
+    }
+
+    // ── Squads multisig ───────────────────────────────────────────────────────
+
+    /// Propose a [`convert`](Self::convert) from vault `vault_index` of the
+    /// Squads multisig `multisig`, and approve it as `member`.
+    ///
+    /// One transaction, signed and paid for by `member` (Initiate and Vote
+    /// permissions): `vault_transaction_create`, `proposal_create`,
+    /// `proposal_approve`. The swap's pre-flight and `min_amount_out` are
+    /// computed now, against the vault's token accounts; if the price moves
+    /// past the slippage guard before execution, the executed swap fails and
+    /// a new proposal is needed. Other members vote with
+    /// [`approve_multisig`](Self::approve_multisig).
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        name = "a2a_swap.propose_multisig_convert", skip_all, err,
+        fields(multisig = %multisig, vault_index, mint_in = %params.mint_in,
+               mint_out = %params.mint_out, amount_in = params.amount_in,
+               pool = tracing::field::Empty, signature = tracing::field::Empty),
+    ))]
+    pub async fn propose_multisig_convert(
+        &self,
+        member:      &dyn Signer,
+        multisig:    &Pubkey,
+        vault_index: u8,
+        params:      SwapParams,
+    ) -> Result<MultisigProposal> {
+        let rpc = self.rpc();
+        let (vault, _) = derive_vault(multisig, vault_index);
+        let plan = self.build_convert(&vault, params).await?;
+        let message = VaultMessage::compile(&vault, &plan.instructions)?;
+
+        let index = parse_multisig_transaction_index(&rpc.get_account_data(multisig).await?)? + 1;
+        let ixs = [
+            vault_transaction_create_ix(
+                multisig, &member.pubkey(), &member.pubkey(), index, vault_index, &message,
+                Some("a2a-swap convert"),
+            ),
+            proposal_create_ix(multisig, &member.pubkey(), &member.pubkey(), index, false),
+            proposal_approve_ix(multisig, &member.pubkey(), index, None),
+        ];
+        let sig = self.sign_and_send(rpc, &ixs, member, &[], "multisig_propose").await?;
+
+        Ok(MultisigProposal {
+            signature:         sig.to_string(),
+            multisig:          *multisig,
+            vault,
+            transaction_index: index,
+            transaction:       derive_transaction(multisig, index).0,
+            proposal:          derive_proposal(multisig, index).0,
+            pool:              plan.pool,
+            amount_in:         plan.amount_in,
+            estimated_out:     plan.estimated_out,
+            min_amount_out:    plan.min_amount_out,
+            a_to_b:            plan.a_to_b,
+        })
+    }
+
+    /// Approve transaction `transaction_index` of `multisig` as `member`.
+    pub async fn approve_multisig(
+        &self,
+        member:            &dyn Signer,
+        multisig:          &Pubkey,
+        transaction_index: u64,
+    ) -> Result<String> {
+        let rpc = self.rpc();
+        let ix = proposal_approve_ix(multisig, &member.pubkey(), transaction_index, None);
+        let sig = self.sign_and_send(rpc, &[ix], member, &[], "multisig_approve").await?;
+        Ok(sig.to_string())
+    }
+
+    /// Execute approved transaction `transaction_index` of `multisig` as
+    /// `member`. The stored vault transaction is read back to pass its
+    /// accounts, so this works for proposals made by any client.
+    pub async fn execute_multisig(
+        &self,
+        member:            &dyn Signer,
+        multisig:          &Pubkey,
+        transaction_index: u64,
+    ) -> Result<String> {
+        let rpc = self.rpc();
+        let (transaction, _) = derive_transaction(multisig, transaction_index);
+        let stored = parse_vault_transaction(&rpc.get_account_data(&transaction).await?)?;
+        let ix = vault_transaction_execute_ix(multisig, &member.pubkey(), transaction_index, &stored.message);
+        let sig = self.sign_and_send(rpc, &[ix], member, &[], "multisig_execute").await?;
+        Ok(sig.to_string())
+    }
+
+    // ── Range pools ───────────────────────────────────────────────────────────
+
+    /// Create a concentrated-liquidity pool starting at `params.initial_tick`.
+    ///
+    /// Vault keypairs are generated internally, as in [`Self::create_pool`].
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        name = "a2a_swap.create_range_pool", skip_all, err,
+        fields(mint_a = %params.mint_a, mint_b = %params.mint_b, tick_spacing = params.tick_spacing,
+               pool = tracing::field::Empty, signature = tracing::field::Empty),
+    ))]
+    pub async fn create_range_pool(
+        &self,
+        payer:  &dyn Signer,
+        params: CreateRangePoolParams,
+    ) -> Result<CreateRangePoolResult> {
+        sqrt_price_at_tick(params.initial_tick)?;
+        let rpc = self.rpc();
+
+        let vault_a = Keypair::new();
+        let vault_b = Keypair::new();
+        let (pool, _)           = derive_range_pool(&params.mint_a, &params.mint_b, &self.program_id);
+        let (pool_authority, _) = derive_pool_authority(&pool, &self.program_id);
+
+        let ix = initialize_range_pool_ix(
+            &self.program_id,
+            &payer.pubkey(),
+            &params.mint_a,
+            &params.mint_b,
+            &vault_a.pubkey(),
+            &vault_b.pubkey(),
+            params.fee_rate_bps,
+            params.tick_spacing,
+            params.initial_tick,
         );
-        Ok(rpc.send_and_confirm_transaction(&tx).await?)
+        trace::record("pool", pool);
+        let sig = self
+            .sign_and_send(rpc, &[ix], payer, &[&vault_a, &vault_b], "create_range_pool")
+            .await?;
+
+        Ok(CreateRangePoolResult {
+            signature:    sig.to_string(),
+            pool,
+            pool_authority,
+            vault_a:      vault_a.pubkey(),
+            vault_b:      vault_b.pubkey(),
+            fee_rate_bps: params.fee_rate_bps,
+            tick_spacing: params.tick_spacing,
+            initial_tick: params.initial_tick,
+        })
+    }
+
+    /// Deposit into the `[tick_lower, tick_upper)` range of a range pool.
+    ///
+    /// Quotes the liquidity `amount_a_max` / `amount_b_max` buy at the live
+    /// price; the program mints at most that and transfers only what it
+    /// needs — token A alone above the range, token B alone below it.
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        name = "a2a_swap.provide_range_liquidity", skip_all, err,
+        fields(mint_a = %params.mint_a, mint_b = %params.mint_b,
+               tick_lower = params.tick_lower, tick_upper = params.tick_upper,
+               pool = tracing::field::Empty, signature = tracing::field::Empty),
+    ))]
+    pub async fn provide_range_liquidity(
+        &self,
+        payer:  &dyn Signer,
+        params: ProvideRangeParams,
+    ) -> Result<ProvideRangeResult> {
+        let rpc = self.rpc();
+
+        let (pool_addr, _) = derive_range_pool(&params.mint_a, &params.mint_b, &self.program_id);
+        let pool = rpc
+            .get_account_data(&pool_addr)
+            .await
+            .ok()
+            .and_then(|data| parse_range_pool(&data).ok())
+            .ok_or(Error::PoolNotFound(params.mint_a, params.mint_b))?;
+        trace::record("pool", pool_addr);
+
+        let spacing = pool.tick_spacing as i32;
+        if params.tick_lower >= params.tick_upper
+            || params.tick_lower % spacing != 0
+            || params.tick_upper % spacing != 0
+        {
+            return Err(Error::InvalidArgument(format!(
+                "range [{}, {}) must be ordered multiples of tick spacing {spacing}",
+                params.tick_lower, params.tick_upper,
+            )));
+        }
+        let sqrt_lo = sqrt_price_at_tick(params.tick_lower)?;
+        let sqrt_hi = sqrt_price_at_tick(params.tick_upper)?;
+        let liquidity = liquidity_for_amounts(
+            pool.sqrt_price_x64, sqrt_lo, sqrt_hi, params.amount_a_max, params.amount_b_max,
+        )?;
+        if liquidity == 0 {
+            return Err(Error::InvalidArgument(
+                "amounts are too small to fund any liquidity in this range".into(),
+            ));
+        }
+        let (amount_a, amount_b) =
+            amounts_for_liquidity(pool.sqrt_price_x64, sqrt_lo, sqrt_hi, liquidity, true)?;
+        let min_liquidity = if params.max_slippage_bps == 0 {
+            0
+        } else {
+            range_math::mul_div(
+                liquidity, 10_000 - params.max_slippage_bps.min(10_000) as u128, 10_000,
+            )?
+        };
+
+        let (position, _) = derive_range_position(
+            &pool_addr, &payer.pubkey(), params.tick_lower, params.tick_upper, &self.program_id,
+        );
+        let ix = provide_range_liquidity_ix(
+            &self.program_id,
+            &payer.pubkey(),
+            &pool_addr,
+            &pool.token_a_vault,
+            &pool.token_b_vault,
+            &derive_ata(&payer.pubkey(), &params.mint_a),
+            &derive_ata(&payer.pubkey(), &params.mint_b),
+            params.tick_lower,
+            params.tick_upper,
+            params.amount_a_max,
+            params.amount_b_max,
+            min_liquidity,
+        );
+        let sig = self.sign_and_send(rpc, &[ix], payer, &[], "provide_range_liquidity").await?;
+
+        Ok(ProvideRangeResult {
+            signature: sig.to_string(),
+            pool:      pool_addr,
+            position,
+            liquidity,
+            amount_a,
+            amount_b,
+        })
+    }
+
+    /// Simulate a swap against the range pool for a mint pair, walking its
+    /// ticks exactly as `swap_range` would. No transaction is sent.
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        name = "a2a_swap.simulate_range", skip_all, err,
+        fields(mint_in = %params.mint_in, mint_out = %params.mint_out, amount_in = params.amount_in,
+               pool = tracing::field::Empty),
+    ))]
+    pub async fn simulate_range(&self, params: SimulateParams) -> Result<SimulateResult> {
+        let started = Instant::now();
+        let rpc = self.rpc();
+        let result = match self.find_range_pool_inner(rpc, &params.mint_in, &params.mint_out).await {
+            Ok((pool_addr, pool, a_to_b)) => match self.fetch_protocol_fees(rpc).await {
+                Ok((protocol_fee_bps, _)) => range_math::simulate_range(
+                    pool_addr, &pool, params.amount_in, protocol_fee_bps, a_to_b,
+                ),
+                Err(e) => Err(e),
+            },
+            Err(e) => Err(e),
+        };
+        metrics::simulate(started.elapsed());
+        result
+    }
+
+    // ── Rebalancing ───────────────────────────────────────────────────────────
+
+    /// One rebalancing round for `payer`'s holdings of `params.mint_a` and
+    /// `params.mint_b` — see [`rebalancer`](crate::rebalancer).
+    ///
+    /// Values the wallet balances (native SOL counted as wSOL) at the pool's
+    /// spot price and, when the drift exceeds `params.band_bps`, sends the
+    /// rebalancing swap through [`convert`](Self::convert). Network fees come
+    /// out of the same SOL, so keep some outside the target when rebalancing SOL.
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        name = "a2a_swap.rebalance", skip_all, err,
+        fields(mint_a = %params.mint_a, mint_b = %params.mint_b, pool = tracing::field::Empty),
+    ))]
+    pub async fn rebalance(&self, payer: &dyn Signer, params: &RebalanceParams) -> Result<RebalanceOutcome> {
+        let rpc = self.rpc();
+        let owner = payer.pubkey();
+        let quote = self.find_pool_inner(&params.mint_a, &params.mint_b).await?;
+        trace::record("pool", quote.pool);
+
+        let (reserve_a, reserve_b) = quote.reserves_in_out();
+        let pool_state = quote.state;
+        let price = spot_price(pool_state.curve, reserve_a, reserve_b);
+
+        let mut balances = self.fetch_token_balances(rpc, &owner).await?;
+        let lamports = rpc.get_balance(&owner).await?;
+        *balances.entry(Pubkey::from_str(WSOL_MINT).unwrap()).or_default() += lamports;
+        let balance = |mint: &Pubkey| balances.get(mint).copied().unwrap_or(0);
+
+        let plan = plan_rebalance(
+            balance(&params.mint_a), balance(&params.mint_b), price, params.target, params.band_bps,
+        )?;
+        let swap = match plan.trade {
+            None        => None,
+            Some(trade) => Some(self.convert(payer, params.swap_params(trade)).await?),
+        };
+        Ok(RebalanceOutcome { plan, swap })
+    }
+
+    /// Run [`rebalance`](Self::rebalance) every `every`, passing each round's
+    /// outcome to `on_round`; stops when `on_round` returns `false`. A failed
+    /// round does not stop the schedule unless `on_round` says so.
+    pub async fn rebalance_every(
+        &self,
+        payer:        &dyn Signer,
+        params:       &RebalanceParams,
+        every:        std::time::Duration,
+        mut on_round: impl FnMut(&Result<RebalanceOutcome>) -> bool,
+    ) {
+        loop {
+            let outcome = self.rebalance(payer, params).await;
+            if !on_round(&outcome) {
+                return;
+            }
+            tokio::time::sleep(every).await;
+        }
+    }
+
+    // ── Governance ────────────────────────────────────────────────────────────
+
+    /// Hand the protocol config to holders of `vote_mint`: from then on only
+    /// proposals passed with at least `quorum` votes (atomic units), each
+    /// open for `voting_period_secs`, change the protocol fee, pool fee
+    /// bounds or pause switches. `admin` must be the current config admin;
+    /// this cannot be undone.
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        name = "a2a_swap.initialize_governance", skip_all, err,
+        fields(vote_mint = %vote_mint, quorum, voting_period_secs, signature = tracing::field::Empty),
+    ))]
+    pub async fn initialize_governance(
+        &self,
+        admin:              &dyn Signer,
+        vote_mint:          Pubkey,
+        quorum:             u64,
+        voting_period_secs: i64,
+    ) -> Result<String> {
+        let rpc = self.rpc();
+        let mut instructions = self.config_migration_ix(rpc, &admin.pubkey()).await?;
+        instructions.push(initialize_governance_ix(
+            &self.program_id, &admin.pubkey(), &vote_mint, quorum, voting_period_secs,
+        ));
+        let sig = self.sign_and_send(rpc, &instructions, admin, &[], "initialize_governance").await?;
+        trace::record("signature", sig);
+        Ok(sig.to_string())
+    }
+
+    /// Propose `action`; anyone may. Voting opens immediately and runs for
+    /// the governance's voting period.
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        name = "a2a_swap.create_proposal", skip_all, err,
+        fields(action = ?action, proposal = tracing::field::Empty, signature = tracing::field::Empty),
+    ))]
+    pub async fn create_proposal(&self, proposer: &dyn Signer, action: GovernanceAction) -> Result<CreateProposalResult> {
+        let rpc = self.rpc();
+        let governance = reader::fetch_governance(&self.accounts(), &self.program_id).await?;
+        let id = governance.proposal_count;
+        let (proposal, _) = derive_governance_proposal(id, &self.program_id);
+        trace::record("proposal", proposal);
+
+        let ix = create_proposal_ix(&self.program_id, &proposer.pubkey(), id, action);
+        let sig = self.sign_and_send(rpc, &[ix], proposer, &[], "create_proposal").await?;
+        trace::record("signature", sig);
+        Ok(CreateProposalResult {
+            signature:      sig.to_string(),
+            id,
+            proposal,
+            voting_ends_at: unix_now() + governance.voting_period_secs,
+        })
+    }
+
+    /// Vote for (`support`) or against proposal `id` with `amount` vote
+    /// tokens from `voter`'s associated token account. The tokens stay in
+    /// the vote vault until [`withdraw_vote`](Self::withdraw_vote) after
+    /// voting closes; each wallet votes once per proposal.
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        name = "a2a_swap.vote", skip_all, err,
+        fields(id, support, amount, signature = tracing::field::Empty),
+    ))]
+    pub async fn vote(&self, voter: &dyn Signer, id: u64, support: bool, amount: u64) -> Result<String> {
+        if amount == 0 {
+            return Err(Error::Program(A2AErrorCode::ZeroAmount));
+        }
+        let rpc = self.rpc();
+        let governance = reader::fetch_governance(&self.accounts(), &self.program_id).await?;
+        let voter_token = derive_ata(&voter.pubkey(), &governance.vote_mint);
+        let ix = cast_vote_ix(&self.program_id, &voter.pubkey(), &voter_token, id, support, amount);
+        let sig = self.sign_and_send(rpc, &[ix], voter, &[], "vote").await?;
+        trace::record("signature", sig);
+        Ok(sig.to_string())
+    }
+
+    /// Apply passed proposal `id` to the protocol config, or to the pool a
+    /// pool action names; anyone may once voting has closed. Fails with [`A2AErrorCode::VotingNotEnded`] or
+    /// [`A2AErrorCode::ProposalNotPassed`] before anything is sent.
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        name = "a2a_swap.execute_proposal", skip_all, err,
+        fields(id, signature = tracing::field::Empty),
+    ))]
+    pub async fn execute_proposal(&self, payer: &dyn Signer, id: u64) -> Result<String> {
+        let rpc = self.rpc();
+        let governance = reader::fetch_governance(&self.accounts(), &self.program_id).await?;
+        let (address, _) = derive_governance_proposal(id, &self.program_id);
+        let proposal = parse_proposal(&rpc.get_account_data(&address).await.map_err(|_| {
+            Error::InvalidArgument(format!("no proposal {id}"))
+        })?)?;
+        if proposal.executed {
+            return Err(Error::Program(A2AErrorCode::ProposalAlreadyExecuted));
+        }
+        if unix_now() < proposal.voting_ends_at {
+            return Err(Error::Program(A2AErrorCode::VotingNotEnded));
+        }
+        if !proposal.passed(governance.quorum) {
+            return Err(Error::Program(A2AErrorCode::ProposalNotPassed));
+        }
+
+        let mut instructions = self.config_migration_ix(rpc, &payer.pubkey()).await?;
+        instructions.push(execute_proposal_ix(&self.program_id, id, proposal.action.pool().as_ref()));
+        let sig = self.sign_and_send(rpc, &instructions, payer, &[], "execute_proposal").await?;
+        trace::record("signature", sig);
+        Ok(sig.to_string())
+    }
+
+    /// Return `voter`'s escrowed vote tokens on proposal `id` to their
+    /// associated token account and reclaim the ballot's rent, once voting
+    /// has closed.
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        name = "a2a_swap.withdraw_vote", skip_all, err,
+        fields(id, signature = tracing::field::Empty),
+    ))]
+    pub async fn withdraw_vote(&self, voter: &dyn Signer, id: u64) -> Result<String> {
+        let rpc = self.rpc();
+        let governance = reader::fetch_governance(&self.accounts(), &self.program_id).await?;
+        let voter_token = derive_ata(&voter.pubkey(), &governance.vote_mint);
+        let ix = withdraw_vote_ix(&self.program_id, &voter.pubkey(), &voter_token, id);
+        let sig = self.sign_and_send(rpc, &[ix], voter, &[], "withdraw_vote").await?;
+        trace::record("signature", sig);
+        Ok(sig.to_string())
+    }
+
+    // ── Read operations ───────────────────────────────────────────────────────
+
+    /// Simulate a swap without submitting a transaction.
+    ///
+    /// Returns a full fee and slippage breakdown including `protocol_fee`,
+    /// `lp_fee`, `estimated_out`, and `price_impact_pct`.
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        name = "a2a_swap.simulate", skip_all, err,
+        fields(mint_in = %params.mint_in, mint_out = %params.mint_out, amount_in = params.amount_in,
+               pool = tracing::field::Empty),
+    ))]
+    pub async fn simulate(&self, params: SimulateParams) -> Result<SimulateResult> {
+        let started = Instant::now();
+        let result  = self.simulate_inner(params).await;
+        metrics::simulate(started.elapsed());
+        result
+    }
+
+    async fn simulate_inner(&self, params: SimulateParams) -> Result<SimulateResult> {
+        reader::simulate(&self.accounts(), &self.program_id, params).await
+    }
+
+    /// [`simulate`](Self::simulate) for several input sizes at once — one
+    /// pool read, then one quote per entry of `amounts`, in order. Compare
+    /// `estimated_out`, `effective_rate` and `price_impact_pct` across the
+    /// results to pick a trade size.
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        name = "a2a_swap.simulate_ladder", skip_all, err,
+        fields(mint_in = %mint_in, mint_out = %mint_out, sizes = amounts.len(),
+               pool = tracing::field::Empty),
+    ))]
+    pub async fn simulate_ladder(
+        &self,
+        mint_in:  Pubkey,
+        mint_out: Pubkey,
+        amounts:  &[u64],
+    ) -> Result<Vec<SimulateResult>> {
+        let started = Instant::now();
+        let result  = reader::simulate_ladder(&self.accounts(), &self.program_id, &mint_in, &mint_out, amounts).await;
+        metrics::simulate(started.elapsed());
+        result
+    }
+
+    /// Preview [`provide_liquidity`](Self::provide_liquidity) with the same
+    /// `params`: the amounts it would take, the LP shares it would mint, their
+    /// share of the pool and the price either side. No transaction is sent.
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        name = "a2a_swap.simulate_provide", skip_all, err,
+        fields(mint_a = %params.mint_a, mint_b = %params.mint_b, amount_a = params.amount_a,
+               pool = tracing::field::Empty),
+    ))]
+    pub async fn simulate_provide(&self, params: &ProvideParams) -> Result<SimulateProvideResult> {
+        reader::simulate_provide(&self.accounts(), &self.program_id, params).await
+    }
+
+    /// Preview burning `lp_shares` of the `mint_a` / `mint_b` pool (either
+    /// ordering): the tokens returned, the share of the pool they are and the
+    /// price after. No transaction is sent.
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        name = "a2a_swap.simulate_remove", skip_all, err,
+        fields(mint_a = %mint_a, mint_b = %mint_b, lp_shares, pool = tracing::field::Empty),
+    ))]
+    pub async fn simulate_remove(
+        &self,
+        mint_a:    Pubkey,
+        mint_b:    Pubkey,
+        lp_shares: u64,
+    ) -> Result<SimulateRemoveResult> {
+        reader::simulate_remove(&self.accounts(), &self.program_id, &mint_a, &mint_b, lp_shares).await
+    }
+
+    /// Fetch pool state plus current reserves and spot price.
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        name = "a2a_swap.pool_info", skip_all, err,
+        fields(mint_a = %mint_a, mint_b = %mint_b, pool = tracing::field::Empty),
+    ))]
+    pub async fn pool_info(&self, mint_a: Pubkey, mint_b: Pubkey) -> Result<PoolInfo> {
+        reader::pool_info(&self.accounts(), &self.program_id, &mint_a, &mint_b).await
+    }
+
+    /// [`pool_info`](Self::pool_info) plus the pool's TVL in `quote_mint`
+    /// (each side priced through a direct pool against the quote) and its
+    /// creation slot and time, read from its oldest transaction. Those stay
+    /// `None` for pools with more than 10 000 transactions.
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        name = "a2a_swap.pool_info_in", skip_all, err,
+        fields(mint_a = %mint_a, mint_b = %mint_b, quote = %quote_mint, pool = tracing::field::Empty),
+    ))]
+    pub async fn pool_info_in(&self, mint_a: Pubkey, mint_b: Pubkey, quote_mint: Pubkey) -> Result<PoolInfo> {
+        let mut info = reader::pool_info_in(&self.accounts(), &self.program_id, &mint_a, &mint_b, &quote_mint).await?;
+        if let Some((slot, block_time)) = self.fetch_creation(self.rpc(), &info.pool).await? {
+            info.created_slot = Some(slot);
+            info.created_at   = block_time;
+        }
+        Ok(info)
+    }
+
+    /// Every constant-product pool of the program, by address, with reserves
+    /// and spot price. Scanned as [`with_program_scan`](Self::with_program_scan)
+    /// says, on the [`with_index_url`](Self::with_index_url) endpoint if set.
+    pub async fn list_pools(&self) -> Result<Vec<PoolInfo>> {
+        reader::list_pools(self.scanner(), &self.accounts(), &self.program_id, self.scan).await
+    }
+
+    /// Every governance proposal, newest first, with its tally and whether
+    /// it passes under the governance quorum. Scanned like
+    /// [`list_pools`](Self::list_pools).
+    pub async fn proposals(&self) -> Result<Vec<ProposalInfo>> {
+        reader::list_proposals(self.scanner(), &self.accounts(), &self.program_id, self.scan).await
     }
 
-    /// Try both PDA orderings for a mint pair; return `(pool_addr, state, a_to_b)`.
+    /// The program's Anchor IDL, as published on-chain with `anchor idl init`
+    /// (`scripts/publish-idl.sh`), decompressed into its JSON. Fails with
+    /// [`Error::InvalidArgument`] when the program has none.
     ///
-    /// `a_to_b = true` means `mint_in` (first arg) is the pool's `token_a_mint`.
+    /// For agents and tools that build their own instructions or decode
+    /// accounts without this crate; the SDK itself uses the IDL it was built
+    /// with.
+    #[cfg_attr(feature = "tracing", tracing::instrument(name = "a2a_swap.fetch_idl", skip_all, err))]
+    pub async fn fetch_idl(&self) -> Result<serde_json::Value> {
+        reader::fetch_idl(&self.accounts(), &self.program_id).await
+    }
+
+    /// What the deployed program offers right now: its instructions, the
+    /// features they provide and whether governance has paused them, and
+    /// the live protocol fees and pool fee bounds.
+    ///
+    /// Built from the on-chain IDL and `ProtocolConfig` on every call, so
+    /// agents discovering the service see the program as deployed; falls
+    /// back to the IDL this SDK was built with when none is published.
+    #[cfg_attr(feature = "tracing", tracing::instrument(name = "a2a_swap.capability_card", skip_all, err))]
+    pub async fn capability_card(&self) -> Result<CapabilityCard> {
+        reader::fetch_capability_card(&self.accounts(), &self.program_id).await
+    }
+
+    /// `agent`'s protocol fee tier: tracked volume, the tier reached and its
+    /// discount, and the next tier up. See [`open_agent_volume`](Self::open_agent_volume).
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        name = "a2a_swap.my_tier", skip_all, err, fields(agent = %agent),
+    ))]
+    pub async fn my_tier(&self, agent: &Pubkey) -> Result<TierInfo> {
+        reader::fetch_tier(&self.accounts(), &self.program_id, agent).await
+    }
+
+    /// Start tracking `agent`'s swap volume for protocol fee discount tiers.
+    /// The agent pays the account's rent; from then on [`convert`](Self::convert)
+    /// and [`convert_exact_out`](Self::convert_exact_out) pass the account
+    /// automatically.
+    pub async fn open_agent_volume(&self, agent: &dyn Signer) -> Result<String> {
+        let rpc = self.rpc();
+        let ix = open_agent_volume_ix(&self.program_id, &agent.pubkey());
+        let sig = self.sign_and_send(rpc, &[ix], agent, &[], "open_agent_volume").await?;
+        Ok(sig.to_string())
+    }
+
+    /// Fetch all LP positions owned by `owner` with pending fees and
+    /// impermanent loss.
+    ///
+    /// The entry price for IL is rebuilt from each position account's
+    /// transaction history — one `getSignaturesForAddress` plus one
+    /// `getTransaction` per deposit / withdrawal. Use [`my_fees`](Self::my_fees)
+    /// when only fee balances are needed.
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        name = "a2a_swap.my_positions", skip_all, err,
+        fields(owner = %owner, positions = tracing::field::Empty),
+    ))]
+    pub async fn my_positions(&self, owner: &Pubkey) -> Result<Vec<PositionInfo>> {
+        self.positions_inner(owner, true).await
+    }
+
+    /// [`my_positions`](Self::my_positions) filtered, sorted and paged as
+    /// `query` says. Transaction history is only read for the positions on
+    /// the returned page, so wallets with hundreds of positions can be
+    /// walked a page at a time.
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        name = "a2a_swap.my_positions_page", skip_all, err,
+        fields(owner = %owner, positions = tracing::field::Empty),
+    ))]
+    pub async fn my_positions_page(&self, owner: &Pubkey, query: &PositionQuery) -> Result<PositionPage> {
+        let rpc = self.rpc();
+        let positions = reader::fetch_positions(self.scanner(), &self.program_id, owner, self.scan).await?;
+        let mut page = reader::positions_page(rpc, positions, query).await?;
+        let entries = try_join_all(page.positions.iter().map(|p| self.fetch_entry(rpc, &p.address))).await?;
+        set_entries(&mut page.positions, entries);
+        Ok(page)
+    }
+
+    /// Aggregate fee totals across all positions owned by `owner`.
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        name = "a2a_swap.my_fees", skip_all, err, fields(owner = %owner),
+    ))]
+    pub async fn my_fees(&self, owner: &Pubkey) -> Result<FeeSummary> {
+        let positions = self.positions_inner(owner, false).await?;
+        let total_a = positions.iter().map(|p| p.total_fees_a).sum();
+        let total_b = positions.iter().map(|p| p.total_fees_b).sum();
+        Ok(FeeSummary { positions, total_fees_a: total_a, total_fees_b: total_b })
+    }
+
+    /// Value everything `owner` holds in `quote_mint` atomic units: wallet
+    /// token balances (native SOL counted as wSOL), each LP position's share
+    /// of the reserves, and its claimable fees.
+    ///
+    /// Prices are spot (`reserve_quote / reserve_asset`) from A2A-Swap pools
+    /// that pair the asset directly with `quote_mint`. Assets without such a
+    /// pool are listed in [`Portfolio::unpriced`] and left out of the totals.
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        name = "a2a_swap.portfolio", skip_all, err,
+        fields(owner = %owner, quote_mint = %quote_mint, total_value = tracing::field::Empty),
+    ))]
+    pub async fn portfolio(&self, owner: &Pubkey, quote_mint: Pubkey) -> Result<Portfolio> {
+        let rpc = self.rpc();
+
+        let mut balances = self.fetch_token_balances(rpc, owner).await?;
+        let lamports = rpc.get_balance(owner).await?;
+        let wsol = Pubkey::from_str(WSOL_MINT).unwrap();
+        *balances.entry(wsol).or_default() += lamports;
+        balances.retain(|_, amount| *amount > 0);
+
+        let positions = self.positions_inner(owner, false).await?;
+        let mut pool_keys: Vec<Pubkey> = positions.iter().map(|p| p.pool).collect();
+        pool_keys.sort();
+        pool_keys.dedup();
+        let mut pools = self.fetch_pools_with_reserves(&pool_keys).await?;
+
+        // Price every mint we hold against the quote through a direct pool.
+        let mut mints: Vec<Pubkey> = balances.keys().copied()
+            .chain(pools.values().flat_map(|(s, ..)| [s.token_a_mint, s.token_b_mint]))
+            .filter(|m| *m != quote_mint)
+            .collect();
+        mints.sort();
+        mints.dedup();
+        let candidates: Vec<Pubkey> = mints.iter()
+            .flat_map(|m| [
+                derive_pool(m, &quote_mint, &self.program_id).0,
+                derive_pool(&quote_mint, m, &self.program_id).0,
+            ])
+            .filter(|k| !pools.contains_key(k))
+            .collect();
+        pools.extend(self.fetch_pools_with_reserves(&candidates).await?);
+
+        // mint → (reserve_asset, reserve_quote)
+        let mut prices: HashMap<Pubkey, (u64, u64)> = HashMap::new();
+        for (state, reserve_a, reserve_b) in pools.values() {
+            if state.token_b_mint == quote_mint && *reserve_a > 0 {
+                prices.insert(state.token_a_mint, (*reserve_a, *reserve_b));
+            } else if state.token_a_mint == quote_mint && *reserve_b > 0 {
+                prices.insert(state.token_b_mint, (*reserve_b, *reserve_a));
+            }
+        }
+        let value_of = |mint: &Pubkey, amount: u64| -> Option<u64> {
+            if *mint == quote_mint {
+                return Some(amount);
+            }
+            let (reserve_asset, reserve_quote) = prices.get(mint)?;
+            spot_value(amount, *reserve_asset, *reserve_quote)
+        };
+
+        let mut unpriced: Vec<Pubkey> = Vec::new();
+        let mut note_unpriced = |mint: &Pubkey| {
+            if !unpriced.contains(mint) {
+                unpriced.push(*mint);
+            }
+        };
+
+        let holdings: Vec<PortfolioHolding> = balances.into_iter()
+            .map(|(mint, amount)| {
+                let value = value_of(&mint, amount);
+                if value.is_none() {
+                    note_unpriced(&mint);
+                }
+                PortfolioHolding { mint, amount, value }
+            })
+            .collect();
+
+        let positions: Vec<PortfolioPosition> = positions.into_iter()
+            .filter_map(|p| {
+                let (state, reserve_a, reserve_b) = pools.get(&p.pool)?;
+                let (amount_a, amount_b) =
+                    lp_underlying(p.lp_shares, state.lp_supply, *reserve_a, *reserve_b);
+                let value_a = value_of(&state.token_a_mint, amount_a.saturating_add(p.total_fees_a));
+                let value_b = value_of(&state.token_b_mint, amount_b.saturating_add(p.total_fees_b));
+                if value_a.is_none() {
+                    note_unpriced(&state.token_a_mint);
+                }
+                if value_b.is_none() {
+                    note_unpriced(&state.token_b_mint);
+                }
+                Some(PortfolioPosition {
+                    address:   p.address,
+                    pool:      p.pool,
+                    mint_a:    state.token_a_mint,
+                    mint_b:    state.token_b_mint,
+                    lp_shares: p.lp_shares,
+                    amount_a,
+                    amount_b,
+                    fees_a:    p.total_fees_a,
+                    fees_b:    p.total_fees_b,
+                    value:     value_a.zip(value_b).map(|(a, b)| a.saturating_add(b)),
+                })
+            })
+            .collect();
+
+        let wallet_value: u64 = holdings.iter().filter_map(|h| h.value).fold(0, u64::saturating_add);
+        let positions_value: u64 = positions.iter().filter_map(|p| p.value).fold(0, u64::saturating_add);
+        let total_value = wallet_value.saturating_add(positions_value);
+        trace::record("total_value", total_value);
+
+        Ok(Portfolio {
+            owner: *owner,
+            quote_mint,
+            holdings,
+            positions,
+            wallet_value,
+            positions_value,
+            total_value,
+            unpriced,
+        })
+    }
+
+    /// Swaps `owner` made since unix time `since` (all history when `None`),
+    /// oldest first, with a running average cost basis per pool — see
+    /// [`export`](crate::export).
+    ///
+    /// Walks `owner`'s transaction signatures: one `getTransaction` per
+    /// signature in the window.
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        name = "a2a_swap.export_trades", skip_all, err, fields(owner = %owner),
+    ))]
+    pub async fn export_trades(&self, owner: &Pubkey, since: Option<i64>) -> Result<Vec<TradeRecord>> {
+        let mut trades = self.history_records(owner, since).await?.trades;
+        apply_cost_basis(&mut trades);
+        Ok(trades)
+    }
+
+    /// `owner`'s LP positions as flat records: underlying amounts, claimable
+    /// fees and the deposit cost basis from [`my_positions`](Self::my_positions).
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        name = "a2a_swap.export_positions", skip_all, err, fields(owner = %owner),
+    ))]
+    pub async fn export_positions(&self, owner: &Pubkey) -> Result<Vec<PositionRecord>> {
+        let positions = self.positions_inner(owner, true).await?;
+        let pool_keys: Vec<Pubkey> = positions.iter().map(|p| p.pool).collect();
+        let pools = self.fetch_pools_with_reserves(&pool_keys).await?;
+
+        Ok(positions
+            .into_iter()
+            .filter_map(|p| {
+                let (state, reserve_a, reserve_b) = pools.get(&p.pool)?;
+                let (amount_a, amount_b) = lp_underlying(p.lp_shares, *reserve_a, *reserve_b, state.lp_supply);
+                Some(PositionRecord {
+                    address:       p.address,
+                    pool:          p.pool,
+                    mint_a:        state.token_a_mint,
+                    mint_b:        state.token_b_mint,
+                    lp_shares:     p.lp_shares,
+                    amount_a,
+                    amount_b,
+                    fees_a:        p.total_fees_a,
+                    fees_b:        p.total_fees_b,
+                    deposited_a:   p.entry.as_ref().map(|e| e.deposited_a),
+                    deposited_b:   p.entry.as_ref().map(|e| e.deposited_b),
+                    entry_price:   p.entry.as_ref().map(|e| e.entry_price),
+                    current_price: p.current_price,
+                    il_pct:        p.il_pct,
+                })
+            })
+            .collect())
+    }
+
+    /// LP fees `owner` claimed or auto-compounded since unix time `since`
+    /// (all history when `None`), oldest first — realized fee income.
+    ///
+    /// Walks `owner`'s transaction signatures, as
+    /// [`export_trades`](Self::export_trades).
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        name = "a2a_swap.export_fees", skip_all, err, fields(owner = %owner),
+    ))]
+    pub async fn export_fees(&self, owner: &Pubkey, since: Option<i64>) -> Result<Vec<FeeRecord>> {
+        Ok(self.history_records(owner, since).await?.fees)
+    }
+
+    /// LP deposits and withdrawals `owner` made since unix time `since` (all
+    /// history when `None`), oldest first.
+    ///
+    /// Walks `owner`'s transaction signatures, as
+    /// [`export_trades`](Self::export_trades).
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        name = "a2a_swap.export_liquidity", skip_all, err, fields(owner = %owner),
+    ))]
+    pub async fn export_liquidity(&self, owner: &Pubkey, since: Option<i64>) -> Result<Vec<LiquidityRecord>> {
+        Ok(self.history_records(owner, since).await?.liquidity)
+    }
+
+    /// Tax lots and realized gains of `owner`'s swaps, LP deposits /
+    /// withdrawals and fee income since unix time `since`, valued in
+    /// `quote_mint` and matched by `method` — see [`analytics::lots`](crate::analytics::lots).
+    ///
+    /// Walks `owner`'s transaction signatures once, as
+    /// [`export_trades`](Self::export_trades). Tokens acquired before
+    /// `since` have no lots, so their sales show as unmatched.
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        name = "a2a_swap.realized_gains", skip_all, err, fields(owner = %owner),
+    ))]
+    pub async fn realized_gains(
+        &self,
+        owner:      &Pubkey,
+        since:      Option<i64>,
+        method:     LotMethod,
+        quote_mint: Pubkey,
+    ) -> Result<LotTracker> {
+        let records = self.history_records(owner, since).await?;
+        let mut tracker = LotTracker::new(method, quote_mint);
+        tracker.replay(&records.trades, &records.liquidity, &records.fees);
+        Ok(tracker)
+    }
+
+    // ── Private helpers ───────────────────────────────────────────────────────
+
+    pub(crate) fn rpc_url(&self) -> &str {
+        &self.rpc_url
+    }
+
+    pub(crate) fn rpc(&self) -> &RpcClient {
+        &self.rpc
+    }
+
+    /// Program-account scans: the index endpoint if one is set.
+    fn scanner(&self) -> &RpcClient {
+        self.index.as_ref().map_or(&self.rpc, |(_, index)| index)
+    }
+
+    /// Pool, treasury and vault reads, through the account cache if one is set.
+    fn accounts(&self) -> CachedReader<'_> {
+        CachedReader { rpc: &self.rpc, cache: self.cache.as_ref() }
+    }
+
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        name = "a2a_swap.sign_and_send", level = "debug", skip_all, err,
+        fields(op, instructions = instructions.len(), signature = tracing::field::Empty),
+    ))]
+    pub(crate) async fn sign_and_send(
+        &self,
+        rpc:          &RpcClient,
+        instructions: &[Instruction],
+        payer:        &dyn Signer,
+        extra:        &[&dyn Signer],
+        op:           &'static str,
+    ) -> Result<Signature> {
+        let blockhash = rpc.get_latest_blockhash().await?;
+        let mut signers: Vec<&dyn Signer> = vec![payer];
+        signers.extend_from_slice(extra);
+        let tx = Transaction::new_signed_with_payer(
+            instructions,
+            Some(&payer.pubkey()),
+            &signers,
+            blockhash,
+        );
+        let started = Instant::now();
+        let sent    = rpc.send_and_confirm_transaction(&tx).await;
+        metrics::confirmation(op, started.elapsed(), sent.is_ok());
+        if let Some(cache) = &self.cache {
+            // Even a failed confirmation may have landed.
+            instructions.iter()
+                .flat_map(|ix| &ix.accounts)
+                .filter(|meta| meta.is_writable)
+                .for_each(|meta| cache.invalidate(&meta.pubkey));
+        }
+        if let Ok(sig) = &sent {
+            trace::record("signature", sig);
+        }
+        sent.map_err(|e| {
+            // Surface the program's own error codes as typed errors.
+            e.get_transaction_error()
+                .and_then(|tx_err| {
+                    A2AErrorCode::from_transaction_error(&tx_err, instructions, &self.program_id)
+                })
+                .map_or_else(|| Error::from(e), Error::Program)
+        })
+    }
+
+    /// The pool for a mint pair with its reserves and the protocol fees —
+    /// see [`reader::find_pool`].
     async fn find_pool_inner(
+        &self,
+        mint_in:  &Pubkey,
+        mint_out: &Pubkey,
+    ) -> Result<PoolQuote> {
+        reader::find_pool(&self.accounts(), &self.program_id, mint_in, mint_out).await
+    }
+
+    /// Range-pool counterpart of `find_pool_inner`; same `a_to_b` convention.
+    async fn find_range_pool_inner(
         &self,
         rpc:      &RpcClient,
         mint_in:  &Pubkey,
         mint_out: &Pubkey,
-    ) -> Result<(Pubkey, PoolState, bool)> {
-        let (pool_ab, _) = derive_pool(mint_in, mint_out, &self.program_id);
-        if let Ok(data) = rpc.get_account_data(&pool_ab).await {
-            if let Ok(state) = parse_pool(&data) {
-                return Ok((pool_ab, state, true));
+    ) -> Result<(Pubkey, RangePoolState, bool)> {
+        for (a, b, a_to_b) in [(mint_in, mint_out, true), (mint_out, mint_in, false)] {
+            let (pool, _) = derive_range_pool(a, b, &self.program_id);
+            if let Ok(data) = rpc.get_account_data(&pool).await {
+                if let Ok(state) = parse_range_pool(&data) {
+                    trace::record("pool", pool);
+                    return Ok((pool, state, a_to_b));
+                }
             }
         }
+        Err(Error::PoolNotFound(*mint_in, *mint_out))
+    }
+
+    async fn fetch_protocol_fees(&self, rpc: &RpcClient) -> Result<(u64, u64)> {
+        reader::fetch_protocol_fees(rpc, &self.program_id).await
+    }
+
+    /// Positions with pending fees; with `with_entry`, also the entry
+    /// snapshot, current price and impermanent loss.
+    async fn positions_inner(&self, owner: &Pubkey, with_entry: bool) -> Result<Vec<PositionInfo>> {
+        let rpc = self.rpc();
+        let positions = reader::fetch_positions(self.scanner(), &self.program_id, owner, self.scan).await?;
+        if !with_entry {
+            return reader::position_infos(rpc, positions, false).await;
+        }
 
-        let (pool_ba, _) = derive_pool(mint_out, mint_in, &self.program_id);
-        if let Ok(data) = rpc.get_account_data(&pool_ba).await {
-            if let Ok(state) = parse_pool(&data) {
-                return Ok((pool_ba, state, false));
+        // Pool reads and each position's history are independent; run them together.
+        let addresses: Vec<Pubkey> = positions.iter().map(|(addr, _)| *addr).collect();
+        let entries = try_join_all(addresses.iter().map(|addr| self.fetch_entry(rpc, addr)));
+        let (mut infos, entries) = try_join(reader::position_infos(rpc, positions, true), entries).await?;
+        set_entries(&mut infos, entries);
+        Ok(infos)
+    }
+
+    /// Rebuild a position's deposit snapshot from the `Liquidity provided` /
+    /// `Liquidity removed` / `Fees auto-compounded` logs of the transactions
+    /// that touched it, oldest first. Deposits before the position was last
+    /// emptied are dropped.
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        name = "a2a_swap.fetch_entry", level = "debug", skip_all, err, fields(position = %position),
+    ))]
+    async fn fetch_entry(&self, rpc: &RpcClient, position: &Pubkey) -> Result<Option<PositionEntry>> {
+        let sigs = rpc.get_signatures_for_address(position).await?;
+
+        let (mut lp, mut deposited_a, mut deposited_b) = (0u64, 0u64, 0u64);
+        for sig in sigs.iter().rev().filter(|s| s.err.is_none()) {
+            let params = serde_json::json!([
+                sig.signature,
+                { "encoding": "json", "commitment": "confirmed", "maxSupportedTransactionVersion": 0 },
+            ]);
+            let tx: serde_json::Value = rpc.send(RpcRequest::GetTransaction, params).await?;
+            let logs = tx["meta"]["logMessages"].as_array().into_iter().flatten();
+            for line in logs.filter_map(|l| l.as_str()?.strip_prefix("Program log: ")) {
+                let field = |key: &str| -> u64 {
+                    line.split_whitespace()
+                        .find_map(|kv| kv.strip_prefix(key)?.strip_prefix('=')?.parse().ok())
+                        .unwrap_or(0)
+                };
+                if line.starts_with("Liquidity provided: ") {
+                    lp = lp.saturating_add(field("lp"));
+                    deposited_a = deposited_a.saturating_add(field("a"));
+                    deposited_b = deposited_b.saturating_add(field("b"));
+                } else if line.starts_with("Liquidity removed: ") {
+                    lp = lp.saturating_sub(field("lp"));
+                    if lp == 0 {
+                        (deposited_a, deposited_b) = (0, 0);
+                    }
+                } else if line.starts_with("Fees auto-compounded: ") {
+                    lp = lp.saturating_add(field("new_lp"));
+                }
             }
         }
 
-        Err(Error::PoolNotFound(*mint_in, *mint_out))
+        Ok((deposited_a > 0 && deposited_b > 0).then(|| PositionEntry {
+            deposited_a,
+            deposited_b,
+            entry_price: deposited_b as f64 / deposited_a as f64,
+        }))
     }
 
-    /// Fetch all `Position` accounts owned by `owner` via `getProgramAccounts`.
-    async fn fetch_positions(
+    /// SPL Token balances held by `owner`, summed per mint.
+    async fn fetch_token_balances(
         &self,
         rpc:   &RpcClient,
         owner: &Pubkey,
-    ) -> Result<Vec<(Pubkey, PositionState)>> {
-        let disc = account_disc("Position");
-
-        let config = RpcProgramAccountsConfig {
-            filters: Some(vec![
-                RpcFilterType::DataSize(138),
-                RpcFilterType::Memcmp(Memcmp::new(
-                    0,
-                    MemcmpEncodedBytes::Bytes(disc.to_vec()),
-                )),
-                RpcFilterType::Memcmp(Memcmp::new(
-                    8,
-                    MemcmpEncodedBytes::Bytes(owner.to_bytes().to_vec()),
-                )),
-            ]),
-            account_config: RpcAccountInfoConfig { ..Default::default() },
-            ..Default::default()
-        };
+    ) -> Result<BTreeMap<Pubkey, u64>> {
+        let params = serde_json::json!([
+            owner.to_string(),
+            { "programId": spl_token_id().to_string() },
+            { "encoding": "jsonParsed" },
+        ]);
+        let resp: serde_json::Value = rpc.send(RpcRequest::GetTokenAccountsByOwner, params).await?;
+
+        let mut balances = BTreeMap::new();
+        for acct in resp["value"].as_array().into_iter().flatten() {
+            let info = &acct["account"]["data"]["parsed"]["info"];
+            let mint = info["mint"].as_str().and_then(|m| Pubkey::from_str(m).ok());
+            let amount = info["tokenAmount"]["amount"].as_str().and_then(|a| a.parse::<u64>().ok());
+            if let (Some(mint), Some(amount)) = (mint, amount) {
+                let total: &mut u64 = balances.entry(mint).or_default();
+                *total = total.saturating_add(amount);
+            }
+        }
+        Ok(balances)
+    }
 
-        let raw = rpc
-            .get_program_accounts_with_config(&self.program_id, config)
-            .await?;
+    async fn fetch_pools_with_reserves(
+        &self,
+        keys: &[Pubkey],
+    ) -> Result<HashMap<Pubkey, (PoolState, u64, u64)>> {
+        reader::fetch_pools_with_reserves(&self.accounts(), keys).await
+    }
 
-        Ok(raw
+    /// Slot and block time of the oldest transaction that touched `pool`;
+    /// `None` when it is more than ten pages of signatures back.
+    async fn fetch_creation(&self, rpc: &RpcClient, pool: &Pubkey) -> Result<Option<(u64, Option<i64>)>> {
+        const PAGE: usize = 1_000;
+        const MAX_PAGES: usize = 10;
+        let mut before = None;
+        let mut oldest = None;
+        for _ in 0..MAX_PAGES {
+            let page = rpc.get_signatures_for_address_with_config(pool, GetConfirmedSignaturesForAddress2Config {
+                before,
+                until:      None,
+                limit:      Some(PAGE),
+                commitment: Some(CommitmentConfig::confirmed()),
+            }).await?;
+            let Some(last) = page.last() else { return Ok(oldest) };
+            oldest = Some((last.slot, last.block_time));
+            if page.len() < PAGE {
+                return Ok(oldest);
+            }
+            before = Some(Signature::from_str(&last.signature)
+                .map_err(|e| Error::InvalidArgument(format!("signature {}: {e}", last.signature)))?);
+        }
+        Ok(None)
+    }
+
+    /// Successful transactions that touched `owner` since unix time `since`,
+    /// oldest first. Signatures without a block time are kept.
+    async fn fetch_history(&self, rpc: &RpcClient, owner: &Pubkey, since: Option<i64>) -> Result<Vec<HistoryTx>> {
+        const PAGE: usize = 1_000;
+        let mut out = Vec::new();
+        let mut before = None;
+        'pages: loop {
+            let page = rpc.get_signatures_for_address_with_config(owner, GetConfirmedSignaturesForAddress2Config {
+                before,
+                until:      None,
+                limit:      Some(PAGE),
+                commitment: Some(CommitmentConfig::confirmed()),
+            }).await?;
+            let Some(last) = page.last() else { break };
+            before = Some(Signature::from_str(&last.signature)
+                .map_err(|e| Error::InvalidArgument(format!("signature {}: {e}", last.signature)))?);
+            let full = page.len() == PAGE;
+
+            for sig in page {
+                if since.is_some_and(|s| sig.block_time.is_some_and(|t| t < s)) {
+                    break 'pages;
+                }
+                if sig.err.is_some() {
+                    continue;
+                }
+                let params = serde_json::json!([
+                    sig.signature,
+                    { "encoding": "json", "commitment": "confirmed", "maxSupportedTransactionVersion": 0 },
+                ]);
+                let tx: serde_json::Value = rpc.send(RpcRequest::GetTransaction, params).await?;
+                out.push(HistoryTx { signature: sig.signature, slot: sig.slot, block_time: sig.block_time, tx });
+            }
+            if !full {
+                break;
+            }
+        }
+        out.reverse();
+        Ok(out)
+    }
+
+    /// Trades, fee payouts and LP deposits / withdrawals in `owner`'s
+    /// history since `since`, oldest first. Trades carry no cost basis yet.
+    async fn history_records(&self, owner: &Pubkey, since: Option<i64>) -> Result<HistoryRecords> {
+        let rpc = self.rpc();
+        let history = self.fetch_history(rpc, owner, since).await?;
+        let decoded: Vec<_> = history.iter().map(|h| (h, decode_tx(&h.tx, &self.program_id))).collect();
+
+        let pool_keys: Vec<Pubkey> = decoded
+            .iter()
+            .flat_map(|(_, d)| {
+                let swaps = d.swaps.iter().map(|(p, _)| *p);
+                let fees = d.fees.iter().map(|(p, _)| *p);
+                swaps.chain(fees).chain(d.liquidity.iter().map(|(p, _)| *p))
+            })
+            .collect();
+        let mints = self.fetch_pool_mints(rpc, pool_keys).await?;
+
+        let mut out = HistoryRecords::default();
+        for (h, d) in decoded {
+            let network_fee_lamports = h.tx["meta"]["fee"].as_u64().unwrap_or(0);
+            for (pool, s) in d.swaps {
+                let Some(&(mint_a, mint_b)) = mints.get(&pool) else { continue };
+                let (a, b) = if s.a_to_b { (s.amount_in, s.amount_out) } else { (s.amount_out, s.amount_in) };
+                out.trades.push(TradeRecord {
+                    timestamp:      h.block_time,
+                    signature:      h.signature.clone(),
+                    slot:           h.slot,
+                    pool,
+                    mint_in:        if s.a_to_b { mint_a } else { mint_b },
+                    mint_out:       if s.a_to_b { mint_b } else { mint_a },
+                    a_to_b:         s.a_to_b,
+                    amount_in:      s.amount_in,
+                    amount_out:     s.amount_out,
+                    protocol_fee:   s.protocol_fee,
+                    lp_fee:         s.lp_fee,
+                    network_fee_lamports,
+                    price_b_per_a:  if a == 0 { 0.0 } else { b as f64 / a as f64 },
+                    cost_basis_b:   None,
+                    realized_pnl_b: None,
+                });
+            }
+            for (pool, f) in d.fees {
+                let Some(&(mint_a, mint_b)) = mints.get(&pool) else { continue };
+                out.fees.push(FeeRecord {
+                    timestamp: h.block_time,
+                    signature: h.signature.clone(),
+                    slot:      h.slot,
+                    pool,
+                    mint_a,
+                    mint_b,
+                    kind:      f.kind,
+                    amount_a:  f.amount_a,
+                    amount_b:  f.amount_b,
+                    lp_shares: f.lp_shares,
+                });
+            }
+            for (pool, l) in d.liquidity {
+                let Some(&(mint_a, mint_b)) = mints.get(&pool) else { continue };
+                out.liquidity.push(LiquidityRecord {
+                    timestamp: h.block_time,
+                    signature: h.signature.clone(),
+                    slot:      h.slot,
+                    pool,
+                    mint_a,
+                    mint_b,
+                    kind:      l.kind,
+                    lp_shares: l.lp_shares,
+                    amount_a:  l.amount_a,
+                    amount_b:  l.amount_b,
+                });
+            }
+        }
+        Ok(out)
+    }
+
+    /// `(mint_a, mint_b)` of each pool in `keys`; missing pools are skipped.
+    async fn fetch_pool_mints(&self, rpc: &RpcClient, mut keys: Vec<Pubkey>) -> Result<HashMap<Pubkey, (Pubkey, Pubkey)>> {
+        keys.sort();
+        keys.dedup();
+        if keys.is_empty() {
+            return Ok(HashMap::new());
+        }
+        let accounts = rpc.get_multiple_accounts(&keys).await?;
+        Ok(keys
             .into_iter()
-            .filter_map(|(pk, acc)| parse_position(&acc.data).ok().map(|p| (pk, p)))
+            .zip(accounts)
+            .filter_map(|(k, acc)| parse_pool(&acc?.data).ok().map(|p| (k, (p.token_a_mint, p.token_b_mint))))
             .collect())
     }
+
+    /// `migrate_protocol_config` if the treasury's config predates the pause
+    /// switches or fee tiers, so `Account<ProtocolConfig>` instructions can load it.
+    async fn config_migration_ix(&self, rpc: &RpcClient, payer: &Pubkey) -> Result<Vec<Instruction>> {
+        let (treasury, _) = derive_treasury(&self.program_id);
+        let account = rpc.get_account_with_commitment(&treasury, rpc.commitment()).await?.value;
+        Ok(match account {
+            Some(account) if account.data.len() < PROTOCOL_CONFIG_LEN => {
+                vec![migrate_protocol_config_ix(&self.program_id, payer)]
+            }
+            _ => Vec::new(),
+        })
+    }
+
+    /// [`check_swap_funds`] for `agent` swapping `amount_in` of `mint_in` in
+    /// `transactions` transactions: its input token account must hold the
+    /// input, and its wallet the fees, the rent of the wSOL accounts the swap
+    /// opens and, for SOL input, the lamports it wraps.
+    async fn check_swap_funds(
+        &self,
+        rpc:          &RpcClient,
+        agent:        &Pubkey,
+        mint_in:      &Pubkey,
+        mint_out:     &Pubkey,
+        amount_in:    u64,
+        transactions: u64,
+    ) -> Result<()> {
+        let wsol = Pubkey::from_str(WSOL_MINT).unwrap();
+        let token_in = derive_ata(agent, mint_in);
+        let accounts = rpc
+            .get_multiple_accounts_with_commitment(&[*agent, token_in], rpc.commitment())
+            .await?
+            .value;
+        let lamports = accounts[0].as_ref().map_or(0, |a| a.lamports);
+        let balance  = accounts[1].as_ref().and_then(|a| parse_token_amount(&a.data).ok());
+
+        // The wSOL input account unless it exists, and the wSOL output
+        // account, opened and closed around the swap.
+        let new_accounts = (*mint_in == wsol && accounts[1].is_none()) as u64 + (*mint_out == wsol) as u64;
+        let rent = match new_accounts {
+            0 => 0,
+            n => rpc.get_minimum_balance_for_rent_exemption(TOKEN_ACCOUNT_LEN).await? * n,
+        };
+        let mut lamports_needed = LAMPORTS_PER_SIGNATURE * transactions + rent;
+        if *mint_in == wsol {
+            lamports_needed += amount_in;
+        }
+        let token_in = (*mint_in != wsol).then_some((&token_in, balance));
+        check_swap_funds(agent, lamports, lamports_needed, token_in, amount_in)
+    }
+
+    /// A swap with the agent's `AgentVolume` added when the account exists,
+    /// so the swap counts towards its fee tier and gets the discount.
+    async fn tracked_swap(&self, rpc: &RpcClient, agent: &Pubkey, swap: Instruction) -> Result<Instruction> {
+        let (address, _) = derive_agent_volume(agent, &self.program_id);
+        let account = rpc.get_account_with_commitment(&address, rpc.commitment()).await?.value;
+        Ok(if account.is_some() { with_agent_volume(swap, agent) } else { swap })
+    }
+
+    /// `swap` with `holder`'s token account of the pool's gate mint appended
+    /// when the pool is token-gated. Fails with `GateTokenRequired` before
+    /// anything is sent when `holder` holds none.
+    async fn gated_swap(
+        &self,
+        rpc:        &RpcClient,
+        swap:       Instruction,
+        pool_state: &PoolState,
+        holder:     &Pubkey,
+    ) -> Result<Instruction> {
+        if !pool_state.is_gated() {
+            return Ok(swap);
+        }
+        let mint = rpc.get_account_with_commitment(&pool_state.gate_mint, rpc.commitment()).await?.value;
+        let Some(mint) = mint else {
+            return Err(Error::Program(A2AErrorCode::GateTokenRequired));
+        };
+        let gate_token = derive_ata_for_program(holder, &pool_state.gate_mint, &mint.owner);
+        let account = rpc.get_account_with_commitment(&gate_token, rpc.commitment()).await?.value;
+        match account {
+            Some(account) if parse_token_amount(&account.data).is_ok_and(|a| a > 0) => Ok(with_gate_token(swap, &gate_token)),
+            _ => Err(Error::Program(A2AErrorCode::GateTokenRequired)),
+        }
+    }
+
+    /// `migrate_pool` / `migrate_position` instructions for whichever of the
+    /// pool and `position` (an existing one, with its fetched state) predate
+    /// the current layout, so the program can load them. Empty once both
+    /// are current.
+    fn migration_ixs(
+        &self,
+        payer:      &Pubkey,
+        pool:       &Pubkey,
+        pool_state: &PoolState,
+        position:   Option<(&Pubkey, &PositionState)>,
+    ) -> Vec<Instruction> {
+        let mut ixs = Vec::new();
+        if pool_state.version < POOL_VERSION {
+            ixs.push(migrate_pool_ix(&self.program_id, payer, pool));
+        }
+        if let Some((position, state)) = position {
+            if state.version < POSITION_VERSION {
+                ixs.push(migrate_position_ix(&self.program_id, payer, position));
+            }
+        }
+        ixs
+    }
+
+    /// The `position` account's state, or `None` while it doesn't exist.
+    async fn fetch_position_state(&self, rpc: &RpcClient, position: &Pubkey) -> Result<Option<PositionState>> {
+        rpc.get_account_with_commitment(position, rpc.commitment())
+            .await?
+            .value
+            .map(|account| parse_position(&account.data))
+            .transpose()
+    }
+}
+
+/// [`RpcClient`] reads answered from an [`AccountCache`] when it holds a
+/// current entry for every key; otherwise all of them are fetched in one
+/// call, so a quote never mixes accounts from different slots.
+struct CachedReader<'a> {
+    rpc:   &'a RpcClient,
+    cache: Option<&'a AccountCache>,
+}
+
+impl AccountReader for CachedReader<'_> {
+    async fn multiple_account_data(&self, keys: &[Pubkey]) -> Result<Vec<Option<Vec<u8>>>> {
+        let Some(cache) = self.cache else {
+            return self.rpc.multiple_account_data(keys).await;
+        };
+        if let Some(hit) = cache.get(keys).into_iter().collect::<Option<Vec<_>>>() {
+            metrics::cache(keys.len(), 0);
+            return Ok(hit);
+        }
+        metrics::cache(0, keys.len());
+        let resp = self.rpc.get_multiple_accounts_with_commitment(keys, self.rpc.commitment()).await?;
+        let data: Vec<Option<Vec<u8>>> = resp.value.into_iter().map(|a| a.map(|a| a.data)).collect();
+        for (key, data) in keys.iter().zip(&data) {
+            cache.update(*key, resp.context.slot, data.clone());
+        }
+        Ok(data)
+    }
+
+    async fn program_accounts(&self, program: &Pubkey, memcmp: &[(usize, &[u8])]) -> Result<Vec<(Pubkey, Vec<u8>)>> {
+        self.rpc.program_accounts(program, memcmp).await
+    }
+
+    async fn program_keys(&self, program: &Pubkey, memcmp: &[(usize, &[u8])]) -> Result<Vec<Pubkey>> {
+        self.rpc.program_keys(program, memcmp).await
+    }
+
+    async fn program_accounts_page(
+        &self,
+        program: &Pubkey,
+        memcmp:  &[(usize, &[u8])],
+        limit:   usize,
+        cursor:  Option<String>,
+    ) -> Result<AccountsPage> {
+        self.rpc.program_accounts_page(program, memcmp, limit, cursor).await
+    }
+}
+
+impl AccountReader for RpcClient {
+    async fn multiple_account_data(&self, keys: &[Pubkey]) -> Result<Vec<Option<Vec<u8>>>> {
+        let accounts = self.get_multiple_accounts(keys).await?;
+        Ok(accounts.into_iter().map(|a| a.map(|a| a.data)).collect())
+    }
+
+    async fn program_accounts(&self, program: &Pubkey, memcmp: &[(usize, &[u8])]) -> Result<Vec<(Pubkey, Vec<u8>)>> {
+        let raw = self.get_program_accounts_with_config(program, program_accounts_config(memcmp, None)).await?;
+        Ok(raw.into_iter().map(|(pk, acc)| (pk, acc.data)).collect())
+    }
+
+    async fn program_keys(&self, program: &Pubkey, memcmp: &[(usize, &[u8])]) -> Result<Vec<Pubkey>> {
+        let keys_only = UiDataSliceConfig { offset: 0, length: 0 };
+        let raw = self.get_program_accounts_with_config(program, program_accounts_config(memcmp, Some(keys_only))).await?;
+        Ok(raw.into_iter().map(|(pk, _)| pk).collect())
+    }
+
+    async fn program_accounts_page(
+        &self,
+        program: &Pubkey,
+        memcmp:  &[(usize, &[u8])],
+        limit:   usize,
+        cursor:  Option<String>,
+    ) -> Result<AccountsPage> {
+        let method = RpcRequest::Custom { method: "getProgramAccountsV2" };
+        let result: serde_json::Value = self.send(method, accounts_page_params(program, memcmp, limit, cursor)).await?;
+        parse_accounts_page(&result).ok_or_else(|| {
+            ClientError::from(ClientErrorKind::Custom("getProgramAccountsV2: malformed page".into())).into()
+        })
+    }
+}
+
+fn program_accounts_config(memcmp: &[(usize, &[u8])], data_slice: Option<UiDataSliceConfig>) -> RpcProgramAccountsConfig {
+    RpcProgramAccountsConfig {
+        filters: Some(memcmp
+            .iter()
+            .map(|(offset, bytes)| RpcFilterType::Memcmp(Memcmp::new(*offset, MemcmpEncodedBytes::Bytes(bytes.to_vec()))))
+            .collect()),
+        account_config: RpcAccountInfoConfig {
+            // The node's base58 default refuses accounts over 128 bytes.
+            encoding: Some(UiAccountEncoding::Base64),
+            data_slice,
+            ..Default::default()
+        },
+        ..Default::default()
+    }
 }
 
 // ─── Utilities ────────────────────────────────────────────────────────────────
 
-/// Anchor account discriminator: `sha256("account:{TypeName}")[..8]`.
-fn account_disc(type_name: &str) -> [u8; 8] {
-    let h = hash(format!("account:{type_name}").as_bytes());
-    h.to_bytes()[..8].try_into().unwrap()
+/// What a confirmed swap actually did, read back from its transaction.
+struct Fill {
+    /// Tokens that left the output vault.
+    out: u64,
+    /// Network fee in lamports.
+    fee: u64,
 }
 
-/// Compute proportional `amount_b` for `provide_liquidity`.
-///
-/// - If `amount_b` is `Some`, return it unchanged.
-/// - If the pool is empty (`lp_supply == 0`), `amount_b` is required.
-/// - Otherwise, compute proportionally: `amount_b = amount_a × reserve_b / reserve_a`.
-fn compute_amount_b(
-    amount_a:  u64,
-    amount_b:  Option<u64>,
-    reserve_a: u64,
-    reserve_b: u64,
-    lp_supply: u64,
-) -> Result<u64> {
-    if let Some(b) = amount_b {
-        return Ok(b);
-    }
-    if lp_supply == 0 {
-        return Err(Error::AmountBRequired);
-    }
-    if reserve_a == 0 {
-        return Err(Error::NoLiquidity);
-    }
-    let b = (amount_a as u128)
-        .checked_mul(reserve_b as u128)
-        .ok_or(Error::MathOverflow)?
-        / reserve_a as u128;
-    if b == 0 {
-        return Err(Error::AmountBZero);
-    }
-    Ok(b as u64)
+impl SwapResult {
+    /// Fill in the realized fields (and the slippage metric) from `fill`.
+    fn with_fill(mut self, fill: Option<Fill>) -> Self {
+        if let Some(fill) = fill {
+            let slippage = metrics::slippage_bps(self.estimated_out, fill.out);
+            metrics::slippage(slippage);
+            self.actual_out = Some(fill.out);
+            self.actual_fee_paid = Some(fill.fee);
+            self.realized_slippage_bps = Some(slippage);
+        }
+        self
+    }
+}
+
+/// Attach each position's rebuilt deposit snapshot and the IL it implies at
+/// the current price.
+fn set_entries(infos: &mut [PositionInfo], entries: Vec<Option<PositionEntry>>) {
+    for (info, entry) in infos.iter_mut().zip(entries) {
+        info.il_pct = entry.as_ref()
+            .zip(info.current_price)
+            .map(|(e, price)| impermanent_loss(e.entry_price, price) * 100.0);
+        info.entry = entry;
+    }
+}
+
+/// Read a confirmed swap's fill from its token balances: the pre/post delta
+/// of `vault_out` and the network fee. Best effort — `None` if the
+/// transaction can't be fetched or doesn't touch the vault, rather than
+/// failing a swap that already landed.
+async fn fetch_fill(rpc: &RpcClient, signature: &Signature, vault_out: &Pubkey) -> Option<Fill> {
+    let params = serde_json::json!([
+        signature.to_string(),
+        { "encoding": "json", "commitment": "confirmed", "maxSupportedTransactionVersion": 0 },
+    ]);
+    let tx: serde_json::Value = rpc.send(RpcRequest::GetTransaction, params).await.ok()?;
+
+    let vault = vault_out.to_string();
+    let index = tx["transaction"]["message"]["accountKeys"]
+        .as_array()?
+        .iter()
+        .position(|k| k.as_str() == Some(vault.as_str()))? as u64;
+    let balance = |field: &str| -> Option<u64> {
+        tx["meta"][field]
+            .as_array()?
+            .iter()
+            .find(|b| b["accountIndex"].as_u64() == Some(index))?["uiTokenAmount"]["amount"]
+            .as_str()?
+            .parse()
+            .ok()
+    };
+    Some(Fill {
+        out: balance("preTokenBalances")?.checked_sub(balance("postTokenBalances")?)?,
+        fee: tx["meta"]["fee"].as_u64()?,
+    })
+}
+
+/// Read a position's LP shares and its percentage of the pool's LP supply
+/// after a deposit. Best effort — `None` if either account can't be fetched
+/// or parsed, rather than failing a deposit that already landed.
+async fn fetch_position_share(rpc: &RpcClient, position: &Pubkey, pool: &Pubkey) -> Option<(u64, f64)> {
+    let mut accounts = rpc.get_multiple_accounts(&[*position, *pool]).await.ok()?.into_iter();
+    let position = parse_position(&accounts.next().flatten()?.data).ok()?;
+    let pool = parse_pool(&accounts.next().flatten()?.data).ok()?;
+    let pct = if pool.lp_supply == 0 {
+        0.0
+    } else {
+        position.lp_shares as f64 / pool.lp_supply as f64 * 100.0
+    };
+    Some((position.lp_shares, pct))
+}
+
+/// Fresh randomness for tranche sizes and spacing — unpredictable to an
+/// observer, not cryptographic.
+fn random_u64() -> u64 {
+    use std::hash::{BuildHasher, Hasher};
+    std::collections::hash_map::RandomState::new().build_hasher().finish()
+}
+
+/// The `commit_swap` / `reveal_swap` pair for a built `swap` instruction:
+/// the same arguments and accounts, committed under `salt`.
+fn commit_reveal_pair(program_id: &Pubkey, swap: &Instruction, salt: [u8; 32]) -> Result<(Instruction, Instruction)> {
+    let args = ix::Swap::from_data(&swap.data)
+        .map_err(|e| Error::InvalidArgument(format!("not a swap instruction: {e}")))?;
+    let key = |i: usize| swap.accounts[i].pubkey;
+    let (agent, pool) = (key(0), key(1));
+    let hash = swap_commitment_hash(
+        args.amount_in, args.min_amount_out, args.a_to_b, args.max_price_impact_bps, &salt,
+    );
+    let mut reveal = reveal_swap_ix(
+        program_id, &agent, &pool, &key(2), &key(3), &key(4), &key(5), &key(6), &key(7), &key(8),
+        args.amount_in, args.min_amount_out, args.a_to_b, args.max_price_impact_bps, salt,
+    );
+    // The agent volume, trader pass and gate token follow the referrer
+    // slot; carry them over.
+    reveal.accounts.extend(swap.accounts.iter().skip(11).cloned());
+    Ok((commit_swap_ix(program_id, &agent, &pool, hash), reveal))
+}
+
+/// `swap` with `trader`'s `TraderPass` appended when `pool` only lets
+/// allowlisted traders swap.
+fn pass_if_permissioned(swap: Instruction, pool: &Pubkey, pool_state: &PoolState, trader: &Pubkey) -> Instruction {
+    if pool_state.is_permissioned() { with_trader_pass(swap, pool, trader) } else { swap }
+}
+
+/// Poll until the cluster reaches `slot`.
+async fn wait_for_slot(rpc: &RpcClient, slot: u64) -> Result<()> {
+    while rpc.get_slot().await? < slot {
+        tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+    }
+    Ok(())
+}
+
+/// Records decoded by [`A2ASwapClient::history_records`].
+#[derive(Default)]
+struct HistoryRecords {
+    trades:    Vec<TradeRecord>,
+    fees:      Vec<FeeRecord>,
+    liquidity: Vec<LiquidityRecord>,
+}
+
+/// One transaction from [`A2ASwapClient::fetch_history`].
+struct HistoryTx {
+    signature:  String,
+    slot:       u64,
+    block_time: Option<i64>,
+    /// `getTransaction` result, `"encoding": "json"`.
+    tx:         serde_json::Value,
 }
@@ -2,13 +2,35 @@
 
 use solana_sdk::pubkey::Pubkey;
 
+pub use crate::error_code::ErrorCode;
+use crate::program_error::A2AErrorCode;
+
 /// All errors returned by the A2A-Swap SDK.
 #[derive(Debug, thiserror::Error)]
 pub enum Error {
     // ── RPC / network ────────────────────────────────────────────────────────
     /// A Solana JSON-RPC call failed.
+    ///
+    /// Boxed because `ClientError` is large and would bloat every `Result`.
+    #[cfg(feature = "rpc")]
     #[error("RPC error: {0}")]
-    Rpc(#[from] solana_client::client_error::ClientError),
+    Rpc(#[from] Box<solana_client::client_error::ClientError>),
+
+    /// A WebSocket subscription from [`AccountCache::watch`](crate::cache::AccountCache::watch)
+    /// could not be set up.
+    #[cfg(feature = "rpc")]
+    #[error("Subscription error: {0}")]
+    Subscription(String),
+
+    /// A JSON-RPC call from [`ReadOnlyClient`](crate::ReadOnlyClient) failed.
+    #[cfg(feature = "minimal-rpc")]
+    #[error("JSON-RPC error: {0}")]
+    JsonRpc(String),
+
+    /// The A2A-Swap program rejected the transaction with one of its own
+    /// error codes (e.g. `0x1771` = `SlippageExceeded`).
+    #[error("Program error {0}")]
+    Program(A2AErrorCode),
 
     // ── Pool discovery ───────────────────────────────────────────────────────
     /// No pool exists for the given mint pair in either PDA ordering.
@@ -34,6 +56,34 @@ pub enum Error {
     #[error("Slippage guard triggered: estimated_out={estimated}, min_amount_out={min}")]
     SlippageExceeded { estimated: u64, min: u64 },
 
+    /// An exact-output swap would need more input than the caller's cap.
+    #[error("Input cap triggered: required amount_in={required}, max_amount_in={max}")]
+    MaxInputExceeded { required: u64, max: u64 },
+
+    /// The trade would move the pool price further than the caller's cap.
+    #[error("Price impact cap triggered: impact={impact_bps} bps, max_price_impact_bps={max_bps}")]
+    PriceImpactExceeded { impact_bps: u64, max_bps: u16 },
+
+    /// A protected swap stopped after a tranche realized more slippage than
+    /// its [`ProtectionLevel`](crate::ProtectionLevel) allows. The tranches in
+    /// `signatures` stay filled; the rest of `amount_in` was not sent.
+    #[error("Protection aborted after {} tranche(s): realized slippage {realized_bps} bps > \
+             {max_bps} bps; filled {filled_in} of {amount_in}", signatures.len())]
+    ProtectionAborted {
+        realized_bps: i64,
+        max_bps:      u16,
+        filled_in:    u64,
+        amount_in:    u64,
+        signatures:   Vec<String>,
+    },
+
+    // ── Funds ────────────────────────────────────────────────────────────────
+    /// The agent cannot pay for the transaction: `account` — its input token
+    /// account, or its wallet for lamports — holds `available` of the
+    /// `needed` atomic units. Checked before anything is sent.
+    #[error("Insufficient balance in {account}: needs {needed}, holds {available}")]
+    InsufficientBalance { account: Pubkey, needed: u64, available: u64 },
+
     // ── Arithmetic ───────────────────────────────────────────────────────────
     #[error("Integer overflow in fee / swap math")]
     MathOverflow,
@@ -48,5 +98,56 @@ pub enum Error {
     InvalidArgument(String),
 }
 
+impl Error {
+    /// Stable machine-readable code — the same string the HTTP API returns.
+    pub fn code(&self) -> ErrorCode {
+        match self {
+            #[cfg(feature = "rpc")]
+            Error::Rpc(_)                      => ErrorCode::RpcError,
+            #[cfg(feature = "rpc")]
+            Error::Subscription(_)             => ErrorCode::RpcError,
+            #[cfg(feature = "minimal-rpc")]
+            Error::JsonRpc(_)                  => ErrorCode::RpcError,
+            Error::Program(code)               => code.error_code(),
+            Error::PoolNotFound(..)            => ErrorCode::PoolNotFound,
+            Error::NoLiquidity                 => ErrorCode::NoLiquidity,
+            Error::AmountBRequired             => ErrorCode::AmountBRequired,
+            Error::AmountBZero                 => ErrorCode::AmountBZero,
+            Error::SlippageExceeded { .. }     => ErrorCode::SlippageExceeded,
+            Error::MaxInputExceeded { .. }     => ErrorCode::SlippageExceeded,
+            Error::PriceImpactExceeded { .. }  => ErrorCode::PriceImpactExceeded,
+            Error::ProtectionAborted { .. }    => ErrorCode::SlippageExceeded,
+            Error::InsufficientBalance { .. }  => ErrorCode::InsufficientBalance,
+            Error::MathOverflow                => ErrorCode::MathOverflow,
+            Error::ParseError { .. }           => ErrorCode::AccountParseError,
+            Error::InvalidArgument(_)          => ErrorCode::InvalidArgument,
+        }
+    }
+}
+
+#[cfg(feature = "rpc")]
+impl From<solana_client::client_error::ClientError> for Error {
+    fn from(e: solana_client::client_error::ClientError) -> Self {
+        Error::Rpc(Box::new(e))
+    }
+}
+
+impl From<a2a_swap_core::DecodeError> for Error {
+    fn from(e: a2a_swap_core::DecodeError) -> Self {
+        Error::ParseError { offset: e.offset(), reason: e.to_string() }
+    }
+}
+
+impl From<a2a_swap_core::math::MathError> for Error {
+    fn from(e: a2a_swap_core::math::MathError) -> Self {
+        use a2a_swap_core::math::MathError;
+        match e {
+            MathError::Overflow        => Error::MathOverflow,
+            MathError::NoLiquidity     => Error::NoLiquidity,
+            MathError::InvalidArgument => Error::InvalidArgument(e.to_string()),
+        }
+    }
+}
+
 /// Convenience alias so every module can write `Result<T>`.
 pub type Result<T> = std::result::Result<T, Error>;
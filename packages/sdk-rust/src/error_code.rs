@@ -0,0 +1,117 @@
+//! Stable, machine-readable error codes.
+//!
+//! This file is also compiled into the Cloudflare Worker (`packages/api`,
+//! included via `#[path]`) so HTTP and SDK callers branch on the same strings.
+//! Keep it dependency-free — it must build for wasm32 without solana-sdk.
+//!
+//! Codes are part of the public contract: add new ones, never rename.
+
+/// Machine-readable error code shared by the SDK [`Error`](crate::Error) type
+/// and the HTTP API's `{ "error": { "code": ... } }` envelope.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ErrorCode {
+    // ── SDK / protocol ───────────────────────────────────────────────────────
+    /// A Solana JSON-RPC call failed.
+    RpcError,
+    /// No pool exists for the mint pair in either PDA ordering.
+    PoolNotFound,
+    /// The pool has no reserves to trade against.
+    NoLiquidity,
+    /// First deposit into an empty pool needs an explicit `amount_b`.
+    AmountBRequired,
+    /// Proportional `amount_b` rounded down to zero.
+    AmountBZero,
+    /// Output would fall below the caller's minimum.
+    SlippageExceeded,
+    /// Price impact exceeds the caller's cap.
+    PriceImpactExceeded,
+    /// Integer overflow in fee / swap math.
+    MathOverflow,
+    /// Raw account bytes could not be decoded.
+    AccountParseError,
+    /// A parameter value is out of range or malformed.
+    InvalidArgument,
+    /// The on-chain program rejected the transaction for a reason with no
+    /// more specific code (see `details` / the SDK's `Error::Program`).
+    ProgramError,
+    /// The agent's wallet or input token account holds less than the
+    /// transaction needs.
+    InsufficientBalance,
+
+    // ── HTTP API only ────────────────────────────────────────────────────────
+    /// The request body or query string is missing or not valid JSON.
+    InvalidRequest,
+    /// A token symbol could not be resolved to a mint.
+    UnknownToken,
+    /// No route matches the request path.
+    RouteNotFound,
+    /// The endpoint is disabled on this deployment.
+    NotAvailable,
+    /// An `Idempotency-Key` was reused with a different request body.
+    IdempotencyKeyReused,
+    /// Unexpected server-side failure.
+    InternalError,
+    /// The requested resource (a position, a webhook) does not exist.
+    NotFound,
+}
+
+impl ErrorCode {
+    /// Every code, in declaration order.
+    pub const ALL: &'static [ErrorCode] = &[
+        ErrorCode::RpcError,
+        ErrorCode::PoolNotFound,
+        ErrorCode::NoLiquidity,
+        ErrorCode::AmountBRequired,
+        ErrorCode::AmountBZero,
+        ErrorCode::SlippageExceeded,
+        ErrorCode::PriceImpactExceeded,
+        ErrorCode::MathOverflow,
+        ErrorCode::AccountParseError,
+        ErrorCode::InvalidArgument,
+        ErrorCode::ProgramError,
+        ErrorCode::InsufficientBalance,
+        ErrorCode::InvalidRequest,
+        ErrorCode::UnknownToken,
+        ErrorCode::RouteNotFound,
+        ErrorCode::NotAvailable,
+        ErrorCode::IdempotencyKeyReused,
+        ErrorCode::InternalError,
+        ErrorCode::NotFound,
+    ];
+
+    /// The wire string, e.g. `"POOL_NOT_FOUND"`.
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            ErrorCode::RpcError            => "RPC_ERROR",
+            ErrorCode::PoolNotFound        => "POOL_NOT_FOUND",
+            ErrorCode::NoLiquidity         => "NO_LIQUIDITY",
+            ErrorCode::AmountBRequired     => "AMOUNT_B_REQUIRED",
+            ErrorCode::AmountBZero         => "AMOUNT_B_ZERO",
+            ErrorCode::SlippageExceeded    => "SLIPPAGE_EXCEEDED",
+            ErrorCode::PriceImpactExceeded => "PRICE_IMPACT_EXCEEDED",
+            ErrorCode::MathOverflow        => "MATH_OVERFLOW",
+            ErrorCode::AccountParseError   => "ACCOUNT_PARSE_ERROR",
+            ErrorCode::InvalidArgument     => "INVALID_ARGUMENT",
+            ErrorCode::ProgramError        => "PROGRAM_ERROR",
+            ErrorCode::InsufficientBalance => "INSUFFICIENT_BALANCE",
+            ErrorCode::InvalidRequest      => "INVALID_REQUEST",
+            ErrorCode::UnknownToken        => "UNKNOWN_TOKEN",
+            ErrorCode::RouteNotFound       => "ROUTE_NOT_FOUND",
+            ErrorCode::NotAvailable        => "NOT_AVAILABLE",
+            ErrorCode::IdempotencyKeyReused => "IDEMPOTENCY_KEY_REUSED",
+            ErrorCode::InternalError       => "INTERNAL_ERROR",
+            ErrorCode::NotFound            => "NOT_FOUND",
+        }
+    }
+
+    /// Parse a wire string back into a code.
+    pub fn parse(s: &str) -> Option<ErrorCode> {
+        ErrorCode::ALL.iter().copied().find(|c| c.as_str() == s)
+    }
+}
+
+impl core::fmt::Display for ErrorCode {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
@@ -0,0 +1,677 @@
+//! Accountant-friendly exports of trades, LP positions and fee income.
+//!
+//! [`A2ASwapClient::export_trades`](crate::A2ASwapClient::export_trades),
+//! [`export_positions`](crate::A2ASwapClient::export_positions) and
+//! [`export_fees`](crate::A2ASwapClient::export_fees) return typed records
+//! rebuilt from the owner's transaction history (the program's swap and fee
+//! logs) and live positions; [`export_liquidity`](crate::A2ASwapClient::export_liquidity)
+//! adds the LP deposits and withdrawals. [`to_csv`] and [`to_parquet`] write any of them
+//! as a flat table, one row per record.
+//!
+//! Each [`TradeRecord`] carries a running average cost basis per pool, in
+//! token B: buys of token A add to the basis at what they cost, and a sale
+//! of A releases the basis of what it sold and realizes the difference.
+//! Token A acquired outside the exported window has no known basis, so only
+//! the part of a sale covered by earlier exported buys is matched.
+//!
+//! ```rust,no_run
+//! # use a2a_swap_sdk::{export, A2ASwapClient};
+//! # async fn run(client: A2ASwapClient, owner: solana_sdk::pubkey::Pubkey) -> a2a_swap_sdk::Result<()> {
+//! let trades = client.export_trades(&owner, Some(1_735_689_600)).await?; // since 2025-01-01
+//! std::fs::write("trades.csv", export::to_csv(&trades)).unwrap();
+//! std::fs::write("trades.parquet", export::to_parquet(&trades)).unwrap();
+//! # Ok(())
+//! # }
+//! ```
+
+mod parquet;
+
+use std::collections::HashMap;
+use std::fmt;
+use std::str::FromStr;
+
+#[cfg(feature = "rpc")]
+use a2a_swap_core::{
+    ix::{ApproveAndExecute, ClaimFees, ProvideLiquidity, RemoveLiquidity, Swap, SwapAsDelegate, SwapExactOut},
+    AccountSpec, Instruction as _,
+};
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "rpc")]
+use solana_sdk::bs58;
+use solana_sdk::pubkey::Pubkey;
+
+use crate::error::{Error, Result};
+
+// ─── Formats ──────────────────────────────────────────────────────────────────
+
+/// File format for [`ExportFormat::encode`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ExportFormat {
+    #[default]
+    Csv,
+    Parquet,
+}
+
+impl ExportFormat {
+    /// Encode `records` in this format.
+    pub fn encode<R: Record>(self, records: &[R]) -> Vec<u8> {
+        match self {
+            ExportFormat::Csv     => to_csv(records).into_bytes(),
+            ExportFormat::Parquet => to_parquet(records),
+        }
+    }
+
+    /// File extension, without the dot.
+    pub fn extension(self) -> &'static str {
+        match self {
+            ExportFormat::Csv     => "csv",
+            ExportFormat::Parquet => "parquet",
+        }
+    }
+}
+
+impl FromStr for ExportFormat {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_ascii_lowercase().as_str() {
+            "csv"     => Ok(ExportFormat::Csv),
+            "parquet" => Ok(ExportFormat::Parquet),
+            _ => Err(Error::InvalidArgument(format!("unknown export format `{s}` (expected csv or parquet)"))),
+        }
+    }
+}
+
+impl fmt::Display for ExportFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.extension())
+    }
+}
+
+/// Column type of a [`Record`] field.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ColumnType {
+    Bool,
+    I64,
+    U64,
+    F64,
+    Str,
+}
+
+/// One column of a [`Record`] table.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Column {
+    pub name: &'static str,
+    pub ty:   ColumnType,
+}
+
+const fn col(name: &'static str, ty: ColumnType) -> Column {
+    Column { name, ty }
+}
+
+/// One value of a [`Record`] row; `Null` for a missing optional field.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Cell {
+    Null,
+    Bool(bool),
+    I64(i64),
+    U64(u64),
+    F64(f64),
+    Str(String),
+}
+
+impl<T: Into<Cell>> From<Option<T>> for Cell {
+    fn from(v: Option<T>) -> Self {
+        v.map_or(Cell::Null, Into::into)
+    }
+}
+
+impl From<bool> for Cell {
+    fn from(v: bool) -> Self {
+        Cell::Bool(v)
+    }
+}
+
+impl From<i64> for Cell {
+    fn from(v: i64) -> Self {
+        Cell::I64(v)
+    }
+}
+
+impl From<u64> for Cell {
+    fn from(v: u64) -> Self {
+        Cell::U64(v)
+    }
+}
+
+impl From<f64> for Cell {
+    fn from(v: f64) -> Self {
+        Cell::F64(v)
+    }
+}
+
+impl From<String> for Cell {
+    fn from(v: String) -> Self {
+        Cell::Str(v)
+    }
+}
+
+impl From<Pubkey> for Cell {
+    fn from(v: Pubkey) -> Self {
+        Cell::Str(v.to_string())
+    }
+}
+
+impl fmt::Display for Cell {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Cell::Null    => Ok(()),
+            Cell::Bool(v) => write!(f, "{v}"),
+            Cell::I64(v)  => write!(f, "{v}"),
+            Cell::U64(v)  => write!(f, "{v}"),
+            Cell::F64(v)  => write!(f, "{v}"),
+            Cell::Str(v) if v.contains([',', '"', '\n']) => write!(f, "\"{}\"", v.replace('"', "\"\"")),
+            Cell::Str(v)  => f.write_str(v),
+        }
+    }
+}
+
+/// A flat record [`to_csv`] / [`to_parquet`] can write.
+pub trait Record {
+    /// The table's columns, in row order.
+    const COLUMNS: &'static [Column];
+
+    /// This record's cells, one per column.
+    fn row(&self) -> Vec<Cell>;
+}
+
+/// One CSV row per record under a header line. Missing values are empty.
+pub fn to_csv<R: Record>(records: &[R]) -> String {
+    let mut out = R::COLUMNS.iter().map(|c| c.name).collect::<Vec<_>>().join(",");
+    out.push('\n');
+    for record in records {
+        let row: Vec<String> = record.row().iter().map(Cell::to_string).collect();
+        out.push_str(&row.join(","));
+        out.push('\n');
+    }
+    out
+}
+
+/// A Parquet file with one row per record (uncompressed, all columns
+/// nullable; `u64` columns are `INT64` annotated `UINT_64`).
+pub fn to_parquet<R: Record>(records: &[R]) -> Vec<u8> {
+    let rows: Vec<Vec<Cell>> = records.iter().map(Record::row).collect();
+    parquet::write(R::COLUMNS, &rows)
+}
+
+// ─── Records ──────────────────────────────────────────────────────────────────
+
+/// One swap the owner made, from
+/// [`export_trades`](crate::A2ASwapClient::export_trades).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TradeRecord {
+    /// Block time (unix seconds), when the node reports one.
+    pub timestamp: Option<i64>,
+    pub signature: String,
+    pub slot: u64,
+    pub pool: Pubkey,
+    pub mint_in: Pubkey,
+    pub mint_out: Pubkey,
+    /// `true` when token A was sold.
+    pub a_to_b: bool,
+    pub amount_in: u64,
+    pub amount_out: u64,
+    /// Protocol fee, in `mint_in`.
+    pub protocol_fee: u64,
+    /// LP fee, in `mint_in`.
+    pub lp_fee: u64,
+    /// Transaction fee in lamports, shared by every swap in the transaction.
+    pub network_fee_lamports: u64,
+    /// Realized token B per token A (atomic units).
+    pub price_b_per_a: f64,
+    /// Token B cost basis of the token A this trade moved: what a buy paid,
+    /// or the average-cost basis a sale released. `None` for a sale with no
+    /// earlier buy to match.
+    pub cost_basis_b: Option<u64>,
+    /// For a sale: proceeds of the matched part less `cost_basis_b`, in
+    /// token B atomic units.
+    pub realized_pnl_b: Option<i64>,
+}
+
+impl Record for TradeRecord {
+    const COLUMNS: &'static [Column] = &[
+        col("timestamp", ColumnType::I64),
+        col("signature", ColumnType::Str),
+        col("slot", ColumnType::U64),
+        col("pool", ColumnType::Str),
+        col("mint_in", ColumnType::Str),
+        col("mint_out", ColumnType::Str),
+        col("a_to_b", ColumnType::Bool),
+        col("amount_in", ColumnType::U64),
+        col("amount_out", ColumnType::U64),
+        col("protocol_fee", ColumnType::U64),
+        col("lp_fee", ColumnType::U64),
+        col("network_fee_lamports", ColumnType::U64),
+        col("price_b_per_a", ColumnType::F64),
+        col("cost_basis_b", ColumnType::U64),
+        col("realized_pnl_b", ColumnType::I64),
+    ];
+
+    fn row(&self) -> Vec<Cell> {
+        vec![
+            self.timestamp.into(),
+            self.signature.clone().into(),
+            self.slot.into(),
+            self.pool.into(),
+            self.mint_in.into(),
+            self.mint_out.into(),
+            self.a_to_b.into(),
+            self.amount_in.into(),
+            self.amount_out.into(),
+            self.protocol_fee.into(),
+            self.lp_fee.into(),
+            self.network_fee_lamports.into(),
+            self.price_b_per_a.into(),
+            self.cost_basis_b.into(),
+            self.realized_pnl_b.into(),
+        ]
+    }
+}
+
+/// One open LP position, from
+/// [`export_positions`](crate::A2ASwapClient::export_positions).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PositionRecord {
+    pub address: Pubkey,
+    pub pool: Pubkey,
+    pub mint_a: Pubkey,
+    pub mint_b: Pubkey,
+    pub lp_shares: u64,
+    /// Token A the shares would withdraw now.
+    pub amount_a: u64,
+    /// Token B the shares would withdraw now.
+    pub amount_b: u64,
+    /// Claimable token A fees, owed + pending.
+    pub fees_a: u64,
+    /// Claimable token B fees, owed + pending.
+    pub fees_b: u64,
+    /// Cost basis: token A deposited since the position was last emptied.
+    pub deposited_a: Option<u64>,
+    /// Cost basis: token B deposited since the position was last emptied.
+    pub deposited_b: Option<u64>,
+    /// `deposited_b / deposited_a`.
+    pub entry_price: Option<f64>,
+    /// Pool spot price, token B per token A.
+    pub current_price: Option<f64>,
+    /// Impermanent loss vs. holding the deposit, in percent.
+    pub il_pct: Option<f64>,
+}
+
+impl Record for PositionRecord {
+    const COLUMNS: &'static [Column] = &[
+        col("address", ColumnType::Str),
+        col("pool", ColumnType::Str),
+        col("mint_a", ColumnType::Str),
+        col("mint_b", ColumnType::Str),
+        col("lp_shares", ColumnType::U64),
+        col("amount_a", ColumnType::U64),
+        col("amount_b", ColumnType::U64),
+        col("fees_a", ColumnType::U64),
+        col("fees_b", ColumnType::U64),
+        col("deposited_a", ColumnType::U64),
+        col("deposited_b", ColumnType::U64),
+        col("entry_price", ColumnType::F64),
+        col("current_price", ColumnType::F64),
+        col("il_pct", ColumnType::F64),
+    ];
+
+    fn row(&self) -> Vec<Cell> {
+        vec![
+            self.address.into(),
+            self.pool.into(),
+            self.mint_a.into(),
+            self.mint_b.into(),
+            self.lp_shares.into(),
+            self.amount_a.into(),
+            self.amount_b.into(),
+            self.fees_a.into(),
+            self.fees_b.into(),
+            self.deposited_a.into(),
+            self.deposited_b.into(),
+            self.entry_price.into(),
+            self.current_price.into(),
+            self.il_pct.into(),
+        ]
+    }
+}
+
+/// How LP fee income left the pool.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FeeKind {
+    /// Transferred to the owner's wallet.
+    Claimed,
+    /// Reinvested as new LP shares.
+    Compounded,
+}
+
+impl fmt::Display for FeeKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            FeeKind::Claimed    => "claimed",
+            FeeKind::Compounded => "compounded",
+        })
+    }
+}
+
+/// One fee claim or auto-compound, from
+/// [`export_fees`](crate::A2ASwapClient::export_fees).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FeeRecord {
+    /// Block time (unix seconds), when the node reports one.
+    pub timestamp: Option<i64>,
+    pub signature: String,
+    pub slot: u64,
+    pub pool: Pubkey,
+    pub mint_a: Pubkey,
+    pub mint_b: Pubkey,
+    pub kind: FeeKind,
+    pub amount_a: u64,
+    pub amount_b: u64,
+    /// LP shares minted by a compound.
+    pub lp_shares: Option<u64>,
+}
+
+impl Record for FeeRecord {
+    const COLUMNS: &'static [Column] = &[
+        col("timestamp", ColumnType::I64),
+        col("signature", ColumnType::Str),
+        col("slot", ColumnType::U64),
+        col("pool", ColumnType::Str),
+        col("mint_a", ColumnType::Str),
+        col("mint_b", ColumnType::Str),
+        col("kind", ColumnType::Str),
+        col("amount_a", ColumnType::U64),
+        col("amount_b", ColumnType::U64),
+        col("lp_shares", ColumnType::U64),
+    ];
+
+    fn row(&self) -> Vec<Cell> {
+        vec![
+            self.timestamp.into(),
+            self.signature.clone().into(),
+            self.slot.into(),
+            self.pool.into(),
+            self.mint_a.into(),
+            self.mint_b.into(),
+            self.kind.to_string().into(),
+            self.amount_a.into(),
+            self.amount_b.into(),
+            self.lp_shares.into(),
+        ]
+    }
+}
+
+/// Direction of a [`LiquidityRecord`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LiquidityKind {
+    /// Tokens deposited, LP shares minted.
+    Provided,
+    /// LP shares burned, tokens withdrawn.
+    Removed,
+}
+
+impl fmt::Display for LiquidityKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            LiquidityKind::Provided => "provided",
+            LiquidityKind::Removed  => "removed",
+        })
+    }
+}
+
+/// One LP deposit or withdrawal, from
+/// [`export_liquidity`](crate::A2ASwapClient::export_liquidity).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LiquidityRecord {
+    /// Block time (unix seconds), when the node reports one.
+    pub timestamp: Option<i64>,
+    pub signature: String,
+    pub slot: u64,
+    pub pool: Pubkey,
+    pub mint_a: Pubkey,
+    pub mint_b: Pubkey,
+    pub kind: LiquidityKind,
+    pub lp_shares: u64,
+    pub amount_a: u64,
+    pub amount_b: u64,
+}
+
+impl Record for LiquidityRecord {
+    const COLUMNS: &'static [Column] = &[
+        col("timestamp", ColumnType::I64),
+        col("signature", ColumnType::Str),
+        col("slot", ColumnType::U64),
+        col("pool", ColumnType::Str),
+        col("mint_a", ColumnType::Str),
+        col("mint_b", ColumnType::Str),
+        col("kind", ColumnType::Str),
+        col("lp_shares", ColumnType::U64),
+        col("amount_a", ColumnType::U64),
+        col("amount_b", ColumnType::U64),
+    ];
+
+    fn row(&self) -> Vec<Cell> {
+        vec![
+            self.timestamp.into(),
+            self.signature.clone().into(),
+            self.slot.into(),
+            self.pool.into(),
+            self.mint_a.into(),
+            self.mint_b.into(),
+            self.kind.to_string().into(),
+            self.lp_shares.into(),
+            self.amount_a.into(),
+            self.amount_b.into(),
+        ]
+    }
+}
+
+// ─── Cost basis ───────────────────────────────────────────────────────────────
+
+/// Fill `cost_basis_b` / `realized_pnl_b` on `trades`, oldest first, with
+/// a running average cost per pool — see the [module docs](self).
+pub fn apply_cost_basis(trades: &mut [TradeRecord]) {
+    // Per pool: token A held from exported buys and its total cost in B.
+    let mut held: HashMap<Pubkey, (u64, u64)> = HashMap::new();
+    for t in trades.iter_mut() {
+        let (qty_a, cost_b) = held.entry(t.pool).or_default();
+        if !t.a_to_b {
+            *qty_a = qty_a.saturating_add(t.amount_out);
+            *cost_b = cost_b.saturating_add(t.amount_in);
+            t.cost_basis_b = Some(t.amount_in);
+            t.realized_pnl_b = None;
+            continue;
+        }
+        let matched = t.amount_in.min(*qty_a);
+        if matched == 0 {
+            (t.cost_basis_b, t.realized_pnl_b) = (None, None);
+            continue;
+        }
+        let basis = (*cost_b as u128 * matched as u128 / *qty_a as u128) as u64;
+        let proceeds = (t.amount_out as u128 * matched as u128 / t.amount_in as u128) as u64;
+        *qty_a -= matched;
+        *cost_b -= basis;
+        t.cost_basis_b = Some(basis);
+        t.realized_pnl_b = Some(proceeds as i64 - basis as i64);
+    }
+}
+
+// ─── Log decoding ─────────────────────────────────────────────────────────────
+
+/// A swap decoded from the program's `Swap:` / `Approved swap:` /
+/// `Delegated swap:` log line.
+#[cfg(feature = "rpc")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct SwapLog {
+    pub amount_in:    u64,
+    pub protocol_fee: u64,
+    pub lp_fee:       u64,
+    pub amount_out:   u64,
+    pub a_to_b:       bool,
+}
+
+/// A fee payout decoded from a `claim_fees` log line.
+#[cfg(feature = "rpc")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct FeeLog {
+    pub kind:      FeeKind,
+    pub amount_a:  u64,
+    pub amount_b:  u64,
+    pub lp_shares: Option<u64>,
+}
+
+/// An LP deposit / withdrawal decoded from a `Liquidity provided:` /
+/// `Liquidity removed:` log line.
+#[cfg(feature = "rpc")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct LiquidityLog {
+    pub kind:      LiquidityKind,
+    pub lp_shares: u64,
+    pub amount_a:  u64,
+    pub amount_b:  u64,
+}
+
+/// Program events of one transaction, each paired with its pool.
+#[cfg(feature = "rpc")]
+#[derive(Debug, Default)]
+pub(crate) struct DecodedTx {
+    pub swaps:     Vec<(Pubkey, SwapLog)>,
+    pub fees:      Vec<(Pubkey, FeeLog)>,
+    pub liquidity: Vec<(Pubkey, LiquidityLog)>,
+}
+
+/// Swaps, fee payouts and LP deposits / withdrawals in a `getTransaction` (`"encoding": "json"`)
+/// result.
+///
+/// Pools come from the top-level program instructions of each kind, matched
+/// in order with that kind's log lines; events reached through CPI from
+/// another program are not attributed and are skipped.
+#[cfg(feature = "rpc")]
+pub(crate) fn decode_tx(tx: &serde_json::Value, program_id: &Pubkey) -> DecodedTx {
+    let msg = &tx["transaction"]["message"];
+    let loaded = &tx["meta"]["loadedAddresses"];
+    let keys: Vec<Pubkey> = [&msg["accountKeys"], &loaded["writable"], &loaded["readonly"]]
+        .into_iter()
+        .filter_map(|v| v.as_array())
+        .flatten()
+        .filter_map(|k| Pubkey::from_str(k.as_str()?).ok())
+        .collect();
+
+    let pool_at = |accounts: &[AccountSpec]| accounts.iter().position(|a| a.name == "pool");
+    let (mut swap_pools, mut fee_pools, mut liquidity_pools) = (Vec::new(), Vec::new(), Vec::new());
+    for ix in msg["instructions"].as_array().into_iter().flatten() {
+        let Some(program) = ix["programIdIndex"].as_u64().and_then(|i| keys.get(i as usize)) else { continue };
+        if program != program_id {
+            continue;
+        }
+        let Some(data) = ix["data"].as_str().and_then(|d| bs58::decode(d).into_vec().ok()) else { continue };
+        let (pools, index) = match data.get(..8) {
+            Some(d) if d == Swap::DISCRIMINATOR              => (&mut swap_pools, pool_at(Swap::ACCOUNTS)),
+            Some(d) if d == SwapExactOut::DISCRIMINATOR      => (&mut swap_pools, pool_at(SwapExactOut::ACCOUNTS)),
+            Some(d) if d == ApproveAndExecute::DISCRIMINATOR => (&mut swap_pools, pool_at(ApproveAndExecute::ACCOUNTS)),
+            Some(d) if d == SwapAsDelegate::DISCRIMINATOR    => (&mut swap_pools, pool_at(SwapAsDelegate::ACCOUNTS)),
+            Some(d) if d == ClaimFees::DISCRIMINATOR         => (&mut fee_pools, pool_at(ClaimFees::ACCOUNTS)),
+            Some(d) if d == ProvideLiquidity::DISCRIMINATOR  => (&mut liquidity_pools, pool_at(ProvideLiquidity::ACCOUNTS)),
+            Some(d) if d == RemoveLiquidity::DISCRIMINATOR   => (&mut liquidity_pools, pool_at(RemoveLiquidity::ACCOUNTS)),
+            _ => continue,
+        };
+        let pool = index
+            .and_then(|i| ix["accounts"].get(i)?.as_u64())
+            .and_then(|k| keys.get(k as usize).copied());
+        pools.push(pool.unwrap_or_default());
+    }
+
+    // A claim with nothing to pay out logs `No fees to claim`: it keeps its
+    // place in the order but yields no record.
+    let (mut swaps, mut fees, mut liquidity) = (Vec::new(), Vec::new(), Vec::new());
+    let logs = tx["meta"]["logMessages"].as_array().into_iter().flatten().filter_map(|l| l.as_str());
+    for line in logs.filter_map(|l| l.strip_prefix("Program log: ")) {
+        if let Some(swap) = parse_swap_log(line) {
+            swaps.push(swap);
+        } else if let Some(fee) = parse_fee_log(line) {
+            fees.push(Some(fee));
+        } else if let Some(lp) = parse_liquidity_log(line) {
+            liquidity.push(lp);
+        } else if line == "No fees to claim" {
+            fees.push(None);
+        }
+    }
+    DecodedTx {
+        swaps:     swap_pools.into_iter().zip(swaps).collect(),
+        fees:      fee_pools.into_iter().zip(fees).filter_map(|(pool, fee)| Some((pool, fee?))).collect(),
+        liquidity: liquidity_pools.into_iter().zip(liquidity).collect(),
+    }
+}
+
+/// Value of `key=` in a space-separated `key=value` line.
+#[cfg(feature = "rpc")]
+fn field<T: FromStr>(line: &str, key: &str) -> Option<T> {
+    line.split_whitespace()
+        .find_map(|kv| kv.strip_prefix(key)?.strip_prefix('='))?
+        .parse()
+        .ok()
+}
+
+#[cfg(feature = "rpc")]
+fn parse_swap_log(line: &str) -> Option<SwapLog> {
+    let rest = line
+        .strip_prefix("Swap: ")
+        .or_else(|| line.strip_prefix("Approved swap: "))
+        .or_else(|| line.strip_prefix("Delegated swap: "))?;
+    Some(SwapLog {
+        amount_in:    field(rest, "in")?,
+        protocol_fee: field(rest, "protocol_fee")?,
+        lp_fee:       field(rest, "lp_fee")?,
+        amount_out:   field(rest, "out")?,
+        a_to_b:       field(rest, "a_to_b")?,
+    })
+}
+
+#[cfg(feature = "rpc")]
+fn parse_fee_log(line: &str) -> Option<FeeLog> {
+    if let Some(rest) = line.strip_prefix("Fees auto-compounded: ") {
+        return Some(FeeLog {
+            kind:      FeeKind::Compounded,
+            amount_a:  field(rest, "a")?,
+            amount_b:  field(rest, "b")?,
+            lp_shares: Some(field(rest, "new_lp")?),
+        });
+    }
+    let rest = line
+        .strip_prefix("Fees claimed: ")
+        .or_else(|| line.strip_prefix("Compound yielded 0 LP shares"))?;
+    Some(FeeLog {
+        kind:      FeeKind::Claimed,
+        amount_a:  field(rest, "a")?,
+        amount_b:  field(rest, "b")?,
+        lp_shares: None,
+    })
+}
+
+#[cfg(feature = "rpc")]
+fn parse_liquidity_log(line: &str) -> Option<LiquidityLog> {
+    let (kind, rest) = if let Some(rest) = line.strip_prefix("Liquidity provided: ") {
+        (LiquidityKind::Provided, rest)
+    } else {
+        (LiquidityKind::Removed, line.strip_prefix("Liquidity removed: ")?)
+    };
+    Some(LiquidityLog {
+        kind,
+        lp_shares: field(rest, "lp")?,
+        amount_a:  field(rest, "a")?,
+        amount_b:  field(rest, "b")?,
+    })
+}
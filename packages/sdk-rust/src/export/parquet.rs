@@ -0,0 +1,241 @@
+//! Minimal Parquet writer: one row group, one uncompressed PLAIN data page
+//! per column, every column `OPTIONAL`. Enough for the flat record tables
+//! [`export`](super) writes, and readable by pandas / pyarrow, DuckDB, Polars
+//! and Spark — without pulling in Arrow.
+//!
+//! Layout and metadata follow the format spec
+//! (<https://github.com/apache/parquet-format>); the footer is Thrift
+//! compact-protocol encoded by hand below.
+
+use super::{Cell, Column, ColumnType};
+
+const MAGIC: &[u8; 4] = b"PAR1";
+
+// parquet.thrift enum values.
+const TYPE_BOOLEAN: i32 = 0;
+const TYPE_INT64: i32 = 2;
+const TYPE_DOUBLE: i32 = 5;
+const TYPE_BYTE_ARRAY: i32 = 6;
+const CONVERTED_UTF8: i32 = 0;
+const CONVERTED_UINT_64: i32 = 18;
+const REPETITION_OPTIONAL: i32 = 1;
+const ENCODING_PLAIN: i32 = 0;
+const ENCODING_RLE: i32 = 3;
+const CODEC_UNCOMPRESSED: i32 = 0;
+const PAGE_DATA: i32 = 0;
+
+/// Encode `rows` (each `columns.len()` cells) as a Parquet file.
+pub(super) fn write(columns: &[Column], rows: &[Vec<Cell>]) -> Vec<u8> {
+    let mut file = MAGIC.to_vec();
+    let mut chunks = Vec::with_capacity(columns.len());
+    for (i, column) in columns.iter().enumerate() {
+        let page = data_page(column.ty, rows.iter().map(|r| &r[i]));
+        let mut header = Compact::default();
+        header.struct_begin();
+        header.i32_field(1, PAGE_DATA);
+        header.i32_field(2, page.len() as i32);
+        header.i32_field(3, page.len() as i32);
+        header.field(5, STRUCT);
+        header.struct_begin();
+        header.i32_field(1, rows.len() as i32);
+        header.i32_field(2, ENCODING_PLAIN);
+        header.i32_field(3, ENCODING_RLE);
+        header.i32_field(4, ENCODING_RLE);
+        header.struct_end();
+        header.struct_end();
+
+        let offset = file.len() as i64;
+        let size = (header.out.len() + page.len()) as i64;
+        file.extend_from_slice(&header.out);
+        file.extend_from_slice(&page);
+        chunks.push((offset, size));
+    }
+
+    let mut meta = Compact::default();
+    meta.struct_begin();
+    meta.i32_field(1, 1);
+    meta.list_field(2, STRUCT, columns.len() + 1);
+    meta.struct_begin();
+    meta.binary_field(4, b"schema");
+    meta.i32_field(5, columns.len() as i32);
+    meta.struct_end();
+    for column in columns {
+        let (physical, converted) = match column.ty {
+            ColumnType::Bool => (TYPE_BOOLEAN, None),
+            ColumnType::I64  => (TYPE_INT64, None),
+            ColumnType::U64  => (TYPE_INT64, Some(CONVERTED_UINT_64)),
+            ColumnType::F64  => (TYPE_DOUBLE, None),
+            ColumnType::Str  => (TYPE_BYTE_ARRAY, Some(CONVERTED_UTF8)),
+        };
+        meta.struct_begin();
+        meta.i32_field(1, physical);
+        meta.i32_field(3, REPETITION_OPTIONAL);
+        meta.binary_field(4, column.name.as_bytes());
+        if let Some(converted) = converted {
+            meta.i32_field(6, converted);
+        }
+        meta.struct_end();
+    }
+    meta.i64_field(3, rows.len() as i64);
+    meta.list_field(4, STRUCT, 1);
+    meta.struct_begin();
+    meta.list_field(1, STRUCT, columns.len());
+    for (column, &(offset, size)) in columns.iter().zip(&chunks) {
+        meta.struct_begin();
+        meta.i64_field(2, offset);
+        meta.field(3, STRUCT);
+        meta.struct_begin();
+        meta.i32_field(1, match column.ty {
+            ColumnType::Bool => TYPE_BOOLEAN,
+            ColumnType::I64 | ColumnType::U64 => TYPE_INT64,
+            ColumnType::F64 => TYPE_DOUBLE,
+            ColumnType::Str => TYPE_BYTE_ARRAY,
+        });
+        meta.list_field(2, I32, 2);
+        meta.varint(zigzag(ENCODING_PLAIN as i64));
+        meta.varint(zigzag(ENCODING_RLE as i64));
+        meta.list_field(3, BINARY, 1);
+        meta.binary(column.name.as_bytes());
+        meta.i32_field(4, CODEC_UNCOMPRESSED);
+        meta.i64_field(5, rows.len() as i64);
+        meta.i64_field(6, size);
+        meta.i64_field(7, size);
+        meta.i64_field(9, offset);
+        meta.struct_end();
+        meta.struct_end();
+    }
+    meta.i64_field(2, chunks.iter().map(|&(_, size)| size).sum());
+    meta.i64_field(3, rows.len() as i64);
+    meta.struct_end();
+    meta.binary_field(6, concat!("a2a-swap-sdk ", env!("CARGO_PKG_VERSION")).as_bytes());
+    meta.struct_end();
+
+    file.extend_from_slice(&meta.out);
+    file.extend_from_slice(&(meta.out.len() as u32).to_le_bytes());
+    file.extend_from_slice(MAGIC);
+    file
+}
+
+/// Definition levels (RLE, length-prefixed) then the non-null values, PLAIN.
+fn data_page<'a>(ty: ColumnType, cells: impl Iterator<Item = &'a Cell>) -> Vec<u8> {
+    let cells: Vec<&Cell> = cells.collect();
+
+    // RLE runs of the 0 / 1 definition level, bit width 1.
+    let mut levels = Vec::new();
+    let mut rest = &cells[..];
+    while let Some(first) = rest.first() {
+        let defined = !matches!(first, Cell::Null);
+        let run = rest.iter().take_while(|c| matches!(c, Cell::Null) != defined).count();
+        push_varint(&mut levels, (run as u64) << 1);
+        levels.push(defined as u8);
+        rest = &rest[run..];
+    }
+    let mut page = (levels.len() as u32).to_le_bytes().to_vec();
+    page.extend_from_slice(&levels);
+
+    let mut bits = Vec::new();
+    for cell in cells {
+        match (ty, cell) {
+            (_, Cell::Null) => {}
+            (ColumnType::Bool, Cell::Bool(b)) => bits.push(*b),
+            (ColumnType::I64, Cell::I64(v)) => page.extend_from_slice(&v.to_le_bytes()),
+            (ColumnType::U64, Cell::U64(v)) => page.extend_from_slice(&v.to_le_bytes()),
+            (ColumnType::F64, Cell::F64(v)) => page.extend_from_slice(&v.to_le_bytes()),
+            (ColumnType::Str, Cell::Str(s)) => {
+                page.extend_from_slice(&(s.len() as u32).to_le_bytes());
+                page.extend_from_slice(s.as_bytes());
+            }
+            (ty, cell) => unreachable!("{cell:?} in a {ty:?} column"),
+        }
+    }
+    // Booleans are bit-packed, least significant bit first.
+    for byte in bits.chunks(8) {
+        page.push(byte.iter().enumerate().fold(0, |acc, (i, &b)| acc | ((b as u8) << i)));
+    }
+    page
+}
+
+// ─── Thrift compact protocol ──────────────────────────────────────────────────
+
+const I32: u8 = 5;
+const I64: u8 = 6;
+const BINARY: u8 = 8;
+const LIST: u8 = 9;
+const STRUCT: u8 = 12;
+
+#[derive(Default)]
+struct Compact {
+    out: Vec<u8>,
+    /// Last field id of each open struct.
+    last: Vec<i16>,
+}
+
+impl Compact {
+    fn struct_begin(&mut self) {
+        self.last.push(0);
+    }
+
+    fn struct_end(&mut self) {
+        self.out.push(0);
+        self.last.pop();
+    }
+
+    fn field(&mut self, id: i16, ty: u8) {
+        let last = self.last.last_mut().expect("field outside a struct");
+        let delta = id - *last;
+        if (1..=15).contains(&delta) {
+            self.out.push(((delta as u8) << 4) | ty);
+        } else {
+            self.out.push(ty);
+            push_varint(&mut self.out, zigzag(id as i64));
+        }
+        *last = id;
+    }
+
+    fn i32_field(&mut self, id: i16, v: i32) {
+        self.field(id, I32);
+        self.varint(zigzag(v as i64));
+    }
+
+    fn i64_field(&mut self, id: i16, v: i64) {
+        self.field(id, I64);
+        self.varint(zigzag(v));
+    }
+
+    fn binary_field(&mut self, id: i16, v: &[u8]) {
+        self.field(id, BINARY);
+        self.binary(v);
+    }
+
+    fn list_field(&mut self, id: i16, elem: u8, len: usize) {
+        self.field(id, LIST);
+        if len < 15 {
+            self.out.push(((len as u8) << 4) | elem);
+        } else {
+            self.out.push(0xF0 | elem);
+            self.varint(len as u64);
+        }
+    }
+
+    fn binary(&mut self, v: &[u8]) {
+        self.varint(v.len() as u64);
+        self.out.extend_from_slice(v);
+    }
+
+    fn varint(&mut self, v: u64) {
+        push_varint(&mut self.out, v);
+    }
+}
+
+fn zigzag(v: i64) -> u64 {
+    ((v << 1) ^ (v >> 63)) as u64
+}
+
+/// ULEB128.
+fn push_varint(out: &mut Vec<u8>, mut v: u64) {
+    while v >= 0x80 {
+        out.push((v as u8) | 0x80);
+        v >>= 7;
+    }
+    out.push(v as u8);
+}
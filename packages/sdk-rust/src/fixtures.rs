@@ -0,0 +1,258 @@
+//! Localnet fixtures — a funded wallet and a seeded pool in one call.
+//!
+//! Intended for agent developers running `solana-test-validator` with the
+//! program deployed. [`bootstrap`] airdrops SOL, creates two fresh test mints
+//! (payer is mint authority), mints balances into the payer's ATAs, creates a
+//! pool for the pair, and seeds it with initial liquidity.
+//!
+//! ```rust,no_run
+//! # use a2a_swap_sdk::{fixtures::{bootstrap, BootstrapParams}, A2ASwapClient};
+//! # use solana_sdk::signature::Keypair;
+//! # #[tokio::main]
+//! # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! let client = A2ASwapClient::localnet();
+//! let payer  = Keypair::new();
+//! let fx = bootstrap(&client, &payer, BootstrapParams::default()).await?;
+//! println!("pool {} — {} / {}", fx.pool.pool, fx.mint_a, fx.mint_b);
+//! # Ok(())
+//! # }
+//! ```
+
+use serde::{Deserialize, Serialize};
+use solana_sdk::{
+    instruction::{AccountMeta, Instruction},
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+};
+
+use crate::{
+    client::A2ASwapClient,
+    error::{Error, Result},
+    instructions::{ata_program_id, derive_ata, spl_token_id},
+    state::CurveKind,
+    types::{CreatePoolParams, CreatePoolResult, ProvideParams, ProvideResult},
+};
+
+/// SPL Token `Mint` account size.
+const MINT_LEN: u64 = 82;
+
+// ─── Parameters / result ──────────────────────────────────────────────────────
+
+/// Parameters for [`bootstrap`].
+#[derive(Debug, Clone)]
+pub struct BootstrapParams {
+    /// Decimals for both test mints.
+    pub decimals: u8,
+    /// Amount of each test token minted to the payer (atomic units).
+    pub mint_amount: u64,
+    /// Token A deposited as initial liquidity (atomic units).
+    pub seed_a: u64,
+    /// Token B deposited as initial liquidity (atomic units). Sets the price.
+    pub seed_b: u64,
+    /// LP fee rate for the new pool in basis points (1–100).
+    pub fee_rate_bps: u16,
+    /// Airdrop the payer up to this many lamports. `0` skips the airdrop.
+    pub airdrop_lamports: u64,
+}
+
+impl Default for BootstrapParams {
+    /// 6-decimal mints, 1 000 000 of each token minted, 100 000 / 100 000
+    /// seeded at 0.30%, and a 10 SOL airdrop.
+    fn default() -> Self {
+        Self {
+            decimals:         6,
+            mint_amount:      1_000_000_000_000,
+            seed_a:           100_000_000_000,
+            seed_b:           100_000_000_000,
+            fee_rate_bps:     30,
+            airdrop_lamports: 10_000_000_000,
+        }
+    }
+}
+
+/// Result of [`bootstrap`].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BootstrapResult {
+    /// Airdrop signature, or `None` if the payer was already funded.
+    pub airdrop_signature: Option<String>,
+    /// Signature of the mint-creation / mint-to transaction.
+    pub mint_signature: String,
+    /// Test mint used as the pool's token A.
+    pub mint_a: Pubkey,
+    /// Test mint used as the pool's token B.
+    pub mint_b: Pubkey,
+    /// Payer's token A account (balance = `mint_amount − seed_a`).
+    pub ata_a: Pubkey,
+    /// Payer's token B account (balance = `mint_amount − seed_b`).
+    pub ata_b: Pubkey,
+    /// The created pool.
+    pub pool: CreatePoolResult,
+    /// The seeding deposit.
+    pub liquidity: ProvideResult,
+}
+
+// ─── Bootstrap ────────────────────────────────────────────────────────────────
+
+/// Create test mints, fund the payer, and seed a pool on a local validator.
+///
+/// Refuses to run unless the client points at a loopback RPC — the airdrop
+/// and throwaway mints only make sense against `solana-test-validator`.
+pub async fn bootstrap(
+    client: &A2ASwapClient,
+    payer:  &Keypair,
+    params: BootstrapParams,
+) -> Result<BootstrapResult> {
+    if !is_local_rpc(client.rpc_url()) {
+        return Err(Error::InvalidArgument(format!(
+            "fixtures only run against a local validator, got RPC {}",
+            client.rpc_url()
+        )));
+    }
+    if params.seed_a == 0 || params.seed_b == 0 {
+        return Err(Error::InvalidArgument("seed_a and seed_b must be > 0".into()));
+    }
+    if params.seed_a > params.mint_amount || params.seed_b > params.mint_amount {
+        return Err(Error::InvalidArgument("seed amounts exceed mint_amount".into()));
+    }
+
+    let rpc = client.rpc();
+    let owner = payer.pubkey();
+
+    // 1. Fund the payer.
+    let balance = rpc.get_balance(&owner).await?;
+    let airdrop_signature = if balance < params.airdrop_lamports {
+        let sig = rpc
+            .request_airdrop(&owner, params.airdrop_lamports - balance)
+            .await?;
+        rpc.poll_for_signature(&sig).await?;
+        Some(sig.to_string())
+    } else {
+        None
+    };
+
+    // 2. Two fresh mints with the payer's ATAs holding `mint_amount` each.
+    let mint_a = Keypair::new();
+    let mint_b = Keypair::new();
+    let rent = rpc.get_minimum_balance_for_rent_exemption(MINT_LEN as usize).await?;
+
+    let mut ixs = Vec::with_capacity(8);
+    for mint in [&mint_a, &mint_b] {
+        let ata = derive_ata(&owner, &mint.pubkey());
+        ixs.push(create_account_ix(&owner, &mint.pubkey(), rent, MINT_LEN, &spl_token_id()));
+        ixs.push(initialize_mint2_ix(&mint.pubkey(), &owner, params.decimals));
+        ixs.push(create_ata_ix(&owner, &ata, &owner, &mint.pubkey()));
+        ixs.push(mint_to_ix(&mint.pubkey(), &ata, &owner, params.mint_amount));
+    }
+    let mint_sig = client.sign_and_send(rpc, &ixs, payer, &[&mint_a, &mint_b], "fixtures").await?;
+
+    // 3. Pool + seed liquidity.
+    let pool = client
+        .create_pool(payer, CreatePoolParams {
+            mint_a:       mint_a.pubkey(),
+            mint_b:       mint_b.pubkey(),
+            fee_rate_bps: params.fee_rate_bps,
+            curve:        CurveKind::ConstantProduct,
+        })
+        .await?;
+    let liquidity = client
+        .provide_liquidity(payer, ProvideParams {
+            mint_a:             mint_a.pubkey(),
+            mint_b:             mint_b.pubkey(),
+            amount_a:           params.seed_a,
+            amount_b:           Some(params.seed_b),
+            amount_a_max:       None,
+            amount_b_max:       None,
+            auto_compound:      false,
+            compound_threshold: 0,
+            min_lp:             0,
+            mint_receipt:       false,
+        })
+        .await?;
+
+    Ok(BootstrapResult {
+        airdrop_signature,
+        mint_signature: mint_sig.to_string(),
+        mint_a:         mint_a.pubkey(),
+        mint_b:         mint_b.pubkey(),
+        ata_a:          derive_ata(&owner, &mint_a.pubkey()),
+        ata_b:          derive_ata(&owner, &mint_b.pubkey()),
+        pool,
+        liquidity,
+    })
+}
+
+/// `true` for loopback RPC URLs (`localhost`, `127.0.0.1`, `0.0.0.0`, `[::1]`).
+pub fn is_local_rpc(url: &str) -> bool {
+    let host = url
+        .split("://")
+        .nth(1)
+        .unwrap_or(url)
+        .split(['/', '?'])
+        .next()
+        .unwrap_or("");
+    let host = host.rsplit_once(':').map_or(host, |(h, port)| {
+        if port.chars().all(|c| c.is_ascii_digit()) { h } else { host }
+    });
+    matches!(host, "localhost" | "127.0.0.1" | "0.0.0.0" | "[::1]")
+}
+
+// ─── Raw instruction builders ─────────────────────────────────────────────────
+
+/// SystemProgram.createAccount (ix 0).
+fn create_account_ix(from: &Pubkey, to: &Pubkey, lamports: u64, space: u64, owner: &Pubkey) -> Instruction {
+    let mut data = vec![0u8, 0, 0, 0];
+    data.extend_from_slice(&lamports.to_le_bytes());
+    data.extend_from_slice(&space.to_le_bytes());
+    data.extend_from_slice(owner.as_ref());
+    Instruction {
+        program_id: Pubkey::default(),  // system program
+        accounts: vec![
+            AccountMeta::new(*from, true),
+            AccountMeta::new(*to, true),
+        ],
+        data,
+    }
+}
+
+/// initializeMint2 (SPL Token ix 20) — no freeze authority.
+fn initialize_mint2_ix(mint: &Pubkey, authority: &Pubkey, decimals: u8) -> Instruction {
+    let mut data = vec![20u8, decimals];
+    data.extend_from_slice(authority.as_ref());
+    data.push(0);  // COption::None for freeze authority
+    Instruction {
+        program_id: spl_token_id(),
+        accounts: vec![AccountMeta::new(*mint, false)],
+        data,
+    }
+}
+
+/// createAssociatedTokenAccountIdempotent.
+fn create_ata_ix(payer: &Pubkey, ata: &Pubkey, owner: &Pubkey, mint: &Pubkey) -> Instruction {
+    Instruction {
+        program_id: ata_program_id(),
+        accounts: vec![
+            AccountMeta::new(*payer, true),
+            AccountMeta::new(*ata, false),
+            AccountMeta::new_readonly(*owner, false),
+            AccountMeta::new_readonly(*mint, false),
+            AccountMeta::new_readonly(Pubkey::default(), false),  // system program
+            AccountMeta::new_readonly(spl_token_id(), false),
+        ],
+        data: vec![1],  // 1 = CreateIdempotent
+    }
+}
+
+/// mintTo (SPL Token ix 7).
+fn mint_to_ix(mint: &Pubkey, dest: &Pubkey, authority: &Pubkey, amount: u64) -> Instruction {
+    let mut data = vec![7u8];
+    data.extend_from_slice(&amount.to_le_bytes());
+    Instruction {
+        program_id: spl_token_id(),
+        accounts: vec![
+            AccountMeta::new(*mint, false),
+            AccountMeta::new(*dest, false),
+            AccountMeta::new_readonly(*authority, true),
+        ],
+        data,
+    }
+}
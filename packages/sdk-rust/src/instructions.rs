@@ -4,77 +4,178 @@
 //! for signing and submission.  Account order mirrors the Anchor
 //! `#[derive(Accounts)]` structs in the on-chain program exactly.
 //!
-//! Anchor instruction discriminators: `sha256("global:{name}")[..8]`.
-//! Anchor account discriminators:    `sha256("account:{TypeName}")[..8]`.
+//! Instruction data is encoded by the IDL-generated argument structs in
+//! [`a2a_swap_core::ix`], discriminator included.
 
 use solana_sdk::{
+    ed25519_program,
     instruction::{AccountMeta, Instruction},
     pubkey::Pubkey,
+    signature::Signature,
     sysvar,
 };
+use sha2::{Digest, Sha256};
 use std::str::FromStr;
 
+use a2a_swap_core::{ix, pda, Instruction as _};
+
+use crate::state::{CurveKind, GovernanceAction};
+
 // ─── Well-known program IDs ───────────────────────────────────────────────────
 
 pub(crate) fn spl_token_id() -> Pubkey {
-    Pubkey::from_str("TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA").unwrap()
+    Pubkey::new_from_array(pda::TOKEN_PROGRAM_ID)
+}
+
+/// Token-2022, which owns many credential / soulbound mints used as pool
+/// gates.
+pub fn token_2022_program_id() -> Pubkey {
+    Pubkey::new_from_array(pda::TOKEN_2022_PROGRAM_ID)
 }
 
+#[cfg_attr(not(feature = "rpc"), allow(dead_code))]
 pub(crate) fn ata_program_id() -> Pubkey {
-    Pubkey::from_str("ATokenGPvbdGVxr1b2hvZbsiqW5xWH25efTNsLJA8knL").unwrap()
+    Pubkey::new_from_array(pda::ATA_PROGRAM_ID)
+}
+
+/// Metaplex Core — owns the optional LP receipt assets.
+pub fn mpl_core_program_id() -> Pubkey {
+    Pubkey::from_str("CoREENxT6tW1HoK8ypY1SxRMZTcVPm7R94rH4PZNhX7d").unwrap()
 }
 
 // ─── PDA seeds (mirrors programs/a2a-swap/src/constants.rs) ──────────────────
 
-pub const POOL_SEED:           &[u8] = b"pool";
-pub const POSITION_SEED:       &[u8] = b"position";
-pub const POOL_AUTHORITY_SEED: &[u8] = b"pool_authority";
-pub const TREASURY_SEED:       &[u8] = b"treasury";
+pub use a2a_swap_core::pda::{
+    AGENT_VOLUME_SEED, DELEGATE_SEED, GOVERNANCE_SEED, INTENT_NONCE_SEED, LOCK_AUTHORITY_SEED, POOL_AUTHORITY_SEED,
+    POOL_SEED, POSITION_SEED, PROPOSAL_SEED, RANGE_POOL_SEED, RANGE_POSITION_SEED, RECEIPT_SEED, SWAP_COMMITMENT_SEED,
+    TRADER_PASS_SEED, TREASURY_SEED, VOTE_SEED, VOTE_VAULT_SEED,
+};
 
 // ─── PDA derivation helpers ───────────────────────────────────────────────────
+//
+// Thin wrappers over a2a_swap_core::pda, which derives on raw key bytes.
+
+fn to_pubkey((key, bump): ([u8; 32], u8)) -> (Pubkey, u8) {
+    (Pubkey::new_from_array(key), bump)
+}
 
 /// Derive the pool PDA for the given mint pair.
 pub fn derive_pool(mint_a: &Pubkey, mint_b: &Pubkey, program_id: &Pubkey) -> (Pubkey, u8) {
-    Pubkey::find_program_address(
-        &[POOL_SEED, mint_a.as_ref(), mint_b.as_ref()],
-        program_id,
-    )
+    to_pubkey(pda::derive_pool(&mint_a.to_bytes(), &mint_b.to_bytes(), &program_id.to_bytes()))
 }
 
 /// Derive the pool-authority PDA that signs for vault transfers.
 pub fn derive_pool_authority(pool: &Pubkey, program_id: &Pubkey) -> (Pubkey, u8) {
-    Pubkey::find_program_address(&[POOL_AUTHORITY_SEED, pool.as_ref()], program_id)
+    to_pubkey(pda::derive_pool_authority(&pool.to_bytes(), &program_id.to_bytes()))
 }
 
 /// Derive the per-agent position PDA for a pool.
 pub fn derive_position(pool: &Pubkey, owner: &Pubkey, program_id: &Pubkey) -> (Pubkey, u8) {
-    Pubkey::find_program_address(
-        &[POSITION_SEED, pool.as_ref(), owner.as_ref()],
-        program_id,
-    )
+    to_pubkey(pda::derive_position(&pool.to_bytes(), &owner.to_bytes(), &program_id.to_bytes()))
+}
+
+/// Derive the LP receipt asset (Metaplex Core) for a position.
+pub fn derive_receipt(position: &Pubkey, program_id: &Pubkey) -> (Pubkey, u8) {
+    to_pubkey(pda::derive_receipt(&position.to_bytes(), &program_id.to_bytes()))
+}
+
+/// Derive the concentrated-liquidity pool PDA for the given mint pair.
+pub fn derive_range_pool(mint_a: &Pubkey, mint_b: &Pubkey, program_id: &Pubkey) -> (Pubkey, u8) {
+    to_pubkey(pda::derive_range_pool(&mint_a.to_bytes(), &mint_b.to_bytes(), &program_id.to_bytes()))
+}
+
+/// Derive the range-position PDA — one per owner and `[tick_lower, tick_upper)`.
+pub fn derive_range_position(
+    pool:       &Pubkey,
+    owner:      &Pubkey,
+    tick_lower: i32,
+    tick_upper: i32,
+    program_id: &Pubkey,
+) -> (Pubkey, u8) {
+    to_pubkey(pda::derive_range_position(
+        &pool.to_bytes(),
+        &owner.to_bytes(),
+        tick_lower,
+        tick_upper,
+        &program_id.to_bytes(),
+    ))
 }
 
 /// Derive the global treasury PDA.
 pub fn derive_treasury(program_id: &Pubkey) -> (Pubkey, u8) {
-    Pubkey::find_program_address(&[TREASURY_SEED], program_id)
+    to_pubkey(pda::derive_treasury(&program_id.to_bytes()))
+}
+
+/// Address of the program's Anchor IDL account, as `anchor idl init` creates
+/// it: seeded `"anchor:idl"` from the program's base PDA (no seeds).
+pub fn derive_idl_address(program_id: &Pubkey) -> Pubkey {
+    let (base, _) = Pubkey::find_program_address(&[], program_id);
+    Pubkey::create_with_seed(&base, "anchor:idl", program_id).expect("seed is within MAX_SEED_LEN")
+}
+
+/// Derive the session-key `Delegate` PDA for an owner's token account.
+pub fn derive_delegate(owner: &Pubkey, token_account: &Pubkey, program_id: &Pubkey) -> (Pubkey, u8) {
+    to_pubkey(pda::derive_delegate(&owner.to_bytes(), &token_account.to_bytes(), &program_id.to_bytes()))
+}
+
+/// Derive an agent's commit-reveal `SwapCommitment` PDA on a pool.
+pub fn derive_swap_commitment(pool: &Pubkey, agent: &Pubkey, program_id: &Pubkey) -> (Pubkey, u8) {
+    to_pubkey(pda::derive_swap_commitment(&pool.to_bytes(), &agent.to_bytes(), &program_id.to_bytes()))
+}
+
+/// Derive the global `Governance` PDA.
+pub fn derive_governance(program_id: &Pubkey) -> (Pubkey, u8) {
+    to_pubkey(pda::derive_governance(&program_id.to_bytes()))
+}
+
+/// Derive the governance vote vault, which escrows every voter's tokens.
+pub fn derive_vote_vault(program_id: &Pubkey) -> (Pubkey, u8) {
+    to_pubkey(pda::derive_vote_vault(&program_id.to_bytes()))
+}
+
+/// Derive the `Proposal` PDA for proposal number `id`.
+pub fn derive_proposal(id: u64, program_id: &Pubkey) -> (Pubkey, u8) {
+    to_pubkey(pda::derive_proposal(id, &program_id.to_bytes()))
+}
+
+/// Derive a voter's `VoteRecord` PDA on a proposal.
+pub fn derive_vote_record(proposal: &Pubkey, voter: &Pubkey, program_id: &Pubkey) -> (Pubkey, u8) {
+    to_pubkey(pda::derive_vote_record(&proposal.to_bytes(), &voter.to_bytes(), &program_id.to_bytes()))
+}
+
+/// Derive an agent's `AgentVolume` PDA (fee tier volume tracking).
+pub fn derive_agent_volume(agent: &Pubkey, program_id: &Pubkey) -> (Pubkey, u8) {
+    to_pubkey(pda::derive_agent_volume(&agent.to_bytes(), &program_id.to_bytes()))
+}
+
+/// Derive an agent's `IntentNonce` PDA: the replay guard for its signed swap
+/// intents, and the SPL delegate that spends its input tokens.
+pub fn derive_intent_nonce(agent: &Pubkey, program_id: &Pubkey) -> (Pubkey, u8) {
+    to_pubkey(pda::derive_intent_nonce(&agent.to_bytes(), &program_id.to_bytes()))
+}
+
+/// Derive a trader's `TraderPass` PDA on a permissioned pool, created by
+/// [`register_trader_ix`].
+pub fn derive_trader_pass(pool: &Pubkey, trader: &Pubkey, program_id: &Pubkey) -> (Pubkey, u8) {
+    to_pubkey(pda::derive_trader_pass(&pool.to_bytes(), &trader.to_bytes(), &program_id.to_bytes()))
+}
+
+/// Derive the PDA `locker_program` signs `lock_position` / `unlock_position`
+/// with; derived under the locker, not A2A-Swap.
+pub fn derive_lock_authority(locker_program: &Pubkey) -> (Pubkey, u8) {
+    to_pubkey(pda::derive_lock_authority(&locker_program.to_bytes()))
 }
 
 /// Derive the Associated Token Account for a wallet + mint.
 pub fn derive_ata(wallet: &Pubkey, mint: &Pubkey) -> Pubkey {
-    let token_prog = spl_token_id();
-    Pubkey::find_program_address(
-        &[wallet.as_ref(), token_prog.as_ref(), mint.as_ref()],
-        &ata_program_id(),
-    )
-    .0
+    Pubkey::new_from_array(pda::derive_ata(&wallet.to_bytes(), &mint.to_bytes()))
 }
 
-// ─── Discriminator ────────────────────────────────────────────────────────────
-
-fn disc(name: &str) -> [u8; 8] {
-    let preimage = format!("global:{name}");
-    let h = solana_sdk::hash::hash(preimage.as_bytes());
-    h.to_bytes()[..8].try_into().unwrap()
+/// Derive the Associated Token Account for a wallet + mint owned by
+/// `token_program` — SPL Token or [`token_2022_program_id`], e.g. for a
+/// pool's gate mint.
+pub fn derive_ata_for_program(wallet: &Pubkey, mint: &Pubkey, token_program: &Pubkey) -> Pubkey {
+    Pubkey::new_from_array(pda::derive_ata_for_program(&wallet.to_bytes(), &mint.to_bytes(), &token_program.to_bytes()))
 }
 
 // ─── initialize_pool ─────────────────────────────────────────────────────────
@@ -84,6 +185,7 @@ fn disc(name: &str) -> [u8; 8] {
 /// `vault_a` and `vault_b` must be fresh keypairs — they will be initialised
 /// as SPL token accounts owned by `pool_authority`.  Both must be included as
 /// additional signers when the transaction is submitted.
+#[allow(clippy::too_many_arguments)]
 pub fn initialize_pool_ix(
     program_id:   &Pubkey,
     creator:      &Pubkey,
@@ -92,12 +194,12 @@ pub fn initialize_pool_ix(
     vault_a:      &Pubkey,
     vault_b:      &Pubkey,
     fee_rate_bps: u16,
+    curve:        CurveKind,
 ) -> Instruction {
     let (pool, _)           = derive_pool(mint_a, mint_b, program_id);
     let (pool_authority, _) = derive_pool_authority(&pool, program_id);
 
-    let mut data = disc("initialize_pool").to_vec();
-    data.extend_from_slice(&fee_rate_bps.to_le_bytes());
+    let data = ix::InitializePool { fee_rate_bps, curve: curve.into() }.data();
 
     Instruction {
         program_id: *program_id,
@@ -112,11 +214,420 @@ pub fn initialize_pool_ix(
             AccountMeta::new_readonly(spl_token_id(), false),
             AccountMeta::new_readonly(Pubkey::default(), false), // system program
             AccountMeta::new_readonly(sysvar::rent::ID, false),
+            AccountMeta::new_readonly(derive_treasury(program_id).0, false), // fee bounds, pause
+        ],
+        data,
+    }
+}
+
+// ─── migrate_pool ────────────────────────────────────────────────────────────
+
+/// Build the permissionless `migrate_pool` instruction, which grows a pool
+/// created under an older layout (`PoolState::version < POOL_VERSION`) to
+/// the current one. `payer` tops up rent.
+pub fn migrate_pool_ix(program_id: &Pubkey, payer: &Pubkey, pool: &Pubkey) -> Instruction {
+    Instruction {
+        program_id: *program_id,
+        accounts: vec![
+            AccountMeta::new(*payer, true),   // mut + signer
+            AccountMeta::new(*pool,  false),  // mut (resized)
+            AccountMeta::new_readonly(Pubkey::default(), false), // system program
+        ],
+        data: ix::MigratePool.data(),
+    }
+}
+
+/// Build the permissionless `migrate_position` instruction, the `Position`
+/// counterpart of [`migrate_pool_ix`].
+pub fn migrate_position_ix(program_id: &Pubkey, payer: &Pubkey, position: &Pubkey) -> Instruction {
+    Instruction {
+        program_id: *program_id,
+        accounts: vec![
+            AccountMeta::new(*payer,    true),   // mut + signer
+            AccountMeta::new(*position, false),  // mut (resized)
+            AccountMeta::new_readonly(Pubkey::default(), false), // system program
+        ],
+        data: ix::MigratePosition.data(),
+    }
+}
+
+// ─── sync ────────────────────────────────────────────────────────────────────
+
+/// Build the permissionless `sync` instruction, which sets a pool's tracked
+/// reserves to its vault balances (folding in tokens sent straight to the
+/// vaults).
+pub fn sync_ix(program_id: &Pubkey, pool: &Pubkey, vault_a: &Pubkey, vault_b: &Pubkey) -> Instruction {
+    Instruction {
+        program_id: *program_id,
+        accounts: vec![
+            AccountMeta::new(*pool,             false),  // mut
+            AccountMeta::new_readonly(*vault_a, false),
+            AccountMeta::new_readonly(*vault_b, false),
+        ],
+        data: ix::Sync.data(),
+    }
+}
+
+// ─── Protocol admin ──────────────────────────────────────────────────────────
+
+/// Derive the upgradeable-loader `ProgramData` account of `program_id`,
+/// whose upgrade authority may initialize the protocol config.
+pub fn derive_program_data(program_id: &Pubkey) -> Pubkey {
+    Pubkey::new_from_array(pda::derive_program_data(&program_id.to_bytes()))
+}
+
+/// Build the one-time `initialize_protocol_config` instruction. `admin` must
+/// be the program's upgrade authority and becomes the config admin.
+pub fn initialize_protocol_config_ix(
+    program_id:         &Pubkey,
+    admin:              &Pubkey,
+    protocol_fee_bps:   u16,
+    referral_share_bps: u16,
+    treasury_authority: &Pubkey,
+) -> Instruction {
+    let data = ix::InitializeProtocolConfig {
+        protocol_fee_bps,
+        referral_share_bps,
+        treasury_authority: treasury_authority.to_bytes(),
+    }
+    .data();
+
+    Instruction {
+        program_id: *program_id,
+        accounts: vec![
+            AccountMeta::new(*admin,                        true),   // signer, payer
+            AccountMeta::new(derive_treasury(program_id).0, false),  // protocol_config (init)
+            AccountMeta::new_readonly(*program_id,          false),
+            AccountMeta::new_readonly(derive_program_data(program_id), false),
+            AccountMeta::new_readonly(Pubkey::default(), false),      // system program
+        ],
+        data,
+    }
+}
+
+/// Build the admin-only `update_protocol_config` instruction. Every field is
+/// replaced; pass the current values for the ones that should not change.
+pub fn update_protocol_config_ix(
+    program_id:         &Pubkey,
+    admin:              &Pubkey,
+    new_admin:          &Pubkey,
+    treasury_authority: &Pubkey,
+    protocol_fee_bps:   u16,
+    referral_share_bps: u16,
+) -> Instruction {
+    let data = ix::UpdateProtocolConfig {
+        admin:              new_admin.to_bytes(),
+        treasury_authority: treasury_authority.to_bytes(),
+        protocol_fee_bps,
+        referral_share_bps,
+    }
+    .data();
+
+    Instruction {
+        program_id: *program_id,
+        accounts: vec![
+            AccountMeta::new_readonly(*admin,               true),   // signer
+            AccountMeta::new(derive_treasury(program_id).0, false),  // protocol_config
+        ],
+        data,
+    }
+}
+
+/// Build the admin-only `set_fee_tier` instruction: fee tier `index` gives
+/// agents whose `AgentVolume` has reached `min_volume` `discount_bps` (out
+/// of `10_000`) off the protocol fee. `discount_bps = 0` clears the tier.
+pub fn set_fee_tier_ix(
+    program_id:   &Pubkey,
+    admin:        &Pubkey,
+    index:        u8,
+    min_volume:   u64,
+    discount_bps: u16,
+) -> Instruction {
+    Instruction {
+        program_id: *program_id,
+        accounts: vec![
+            AccountMeta::new_readonly(*admin,               true),   // signer
+            AccountMeta::new(derive_treasury(program_id).0, false),  // protocol_config
+        ],
+        data: ix::SetFeeTier { index, min_volume, discount_bps }.data(),
+    }
+}
+
+/// Build the admin-only `set_locker` instruction: approve `program` to lock
+/// positions as collateral in slot `index`; the default pubkey clears it.
+pub fn set_locker_ix(program_id: &Pubkey, admin: &Pubkey, index: u8, program: &Pubkey) -> Instruction {
+    Instruction {
+        program_id: *program_id,
+        accounts: vec![
+            AccountMeta::new_readonly(*admin,               true),   // signer
+            AccountMeta::new(derive_treasury(program_id).0, false),  // protocol_config
+        ],
+        data: ix::SetLocker { index, program: program.to_bytes() }.data(),
+    }
+}
+
+/// Build the `collect_protocol_fees` instruction, moving `amount` (0 = all)
+/// from the treasury's `treasury_token` account to `destination`.
+pub fn collect_protocol_fees_ix(
+    program_id:         &Pubkey,
+    treasury_authority: &Pubkey,
+    treasury_token:     &Pubkey,
+    destination:        &Pubkey,
+    amount:             u64,
+) -> Instruction {
+    let data = ix::CollectProtocolFees { amount }.data();
+
+    Instruction {
+        program_id: *program_id,
+        accounts: vec![
+            AccountMeta::new_readonly(*treasury_authority,           true),   // signer
+            AccountMeta::new_readonly(derive_treasury(program_id).0, false),  // protocol_config
+            AccountMeta::new(*treasury_token,                        false),
+            AccountMeta::new(*destination,                           false),
+            AccountMeta::new_readonly(spl_token_id(),                false),
+        ],
+        data,
+    }
+}
+
+/// Build the admin-only `configure_dynamic_fee` instruction. `admin` must be
+/// the protocol config admin; `max_fee_bps = 0` disables dynamic fees.
+pub fn configure_dynamic_fee_ix(
+    program_id:  &Pubkey,
+    admin:       &Pubkey,
+    pool:        &Pubkey,
+    min_fee_bps: u16,
+    max_fee_bps: u16,
+) -> Instruction {
+    let data = ix::ConfigureDynamicFee { min_fee_bps, max_fee_bps }.data();
+
+    Instruction {
+        program_id: *program_id,
+        accounts: vec![
+            AccountMeta::new_readonly(*admin,                        true),   // signer
+            AccountMeta::new(*pool,                                  false),  // mut
+            AccountMeta::new_readonly(derive_treasury(program_id).0, false),  // protocol_config
+        ],
+        data,
+    }
+}
+
+/// Build the admin-only `configure_circuit_breaker` instruction. `admin` must
+/// be the protocol config admin; `max_move_bps = 0` disables the breaker.
+pub fn configure_circuit_breaker_ix(
+    program_id:   &Pubkey,
+    admin:        &Pubkey,
+    pool:         &Pubkey,
+    max_move_bps: u16,
+) -> Instruction {
+    let data = ix::ConfigureCircuitBreaker { max_move_bps }.data();
+
+    Instruction {
+        program_id: *program_id,
+        accounts: vec![
+            AccountMeta::new_readonly(*admin,                        true),   // signer
+            AccountMeta::new(*pool,                                  false),  // mut
+            AccountMeta::new_readonly(derive_treasury(program_id).0, false),  // protocol_config
         ],
         data,
     }
 }
 
+/// Build the `close_pool` instruction for an empty pool, refunding the pool's
+/// and both vaults' rent to `closer`. `closer` must be the pool's creator, or
+/// the protocol config admin with `as_admin = true` (which passes the config).
+/// Untracked dust left in the vaults is swept to `closer_token_a` /
+/// `closer_token_b`, the closer's accounts for the pool's two mints.
+#[allow(clippy::too_many_arguments)]
+pub fn close_pool_ix(
+    program_id:     &Pubkey,
+    closer:         &Pubkey,
+    pool:           &Pubkey,
+    pool_authority: &Pubkey,
+    vault_a:        &Pubkey,
+    vault_b:        &Pubkey,
+    closer_token_a: &Pubkey,
+    closer_token_b: &Pubkey,
+    as_admin:       bool,
+) -> Instruction {
+    // Anchor reads an omitted optional account as the program id.
+    let protocol_config = if as_admin { derive_treasury(program_id).0 } else { *program_id };
+
+    Instruction {
+        program_id: *program_id,
+        accounts: vec![
+            AccountMeta::new(*closer,                   true),   // mut + signer (rent refund)
+            AccountMeta::new(*pool,                     false),  // mut (closed)
+            AccountMeta::new_readonly(*pool_authority,  false),
+            AccountMeta::new(*vault_a,                  false),  // mut (closed)
+            AccountMeta::new(*vault_b,                  false),  // mut (closed)
+            AccountMeta::new(*closer_token_a,           false),  // mut (dust)
+            AccountMeta::new(*closer_token_b,           false),  // mut (dust)
+            AccountMeta::new_readonly(protocol_config,  false),  // optional
+            AccountMeta::new_readonly(spl_token_id(),   false),
+        ],
+        data: ix::ClosePool {}.data(),
+    }
+}
+
+/// Build the `set_pool_allowlist` instruction: the pool's creator sets its
+/// trader allowlist to `merkle_root` (see [`crate::allowlist::AllowlistTree`]),
+/// or opens the pool to everyone again with `[0; 32]`. A new root
+/// invalidates every trader's existing pass.
+pub fn set_pool_allowlist_ix(program_id: &Pubkey, creator: &Pubkey, pool: &Pubkey, merkle_root: [u8; 32]) -> Instruction {
+    Instruction {
+        program_id: *program_id,
+        accounts: vec![
+            AccountMeta::new_readonly(*creator, true),   // signer
+            AccountMeta::new(*pool,             false),  // mut
+        ],
+        data: ix::SetPoolAllowlist { merkle_root }.data(),
+    }
+}
+
+/// Build the `set_pool_gate` instruction: the pool's creator requires
+/// traders to hold `gate_mint` to swap, or lifts the gate with
+/// `Pubkey::default()`. See [`with_gate_token`].
+pub fn set_pool_gate_ix(program_id: &Pubkey, creator: &Pubkey, pool: &Pubkey, gate_mint: &Pubkey) -> Instruction {
+    Instruction {
+        program_id: *program_id,
+        accounts: vec![
+            AccountMeta::new_readonly(*creator, true),   // signer
+            AccountMeta::new(*pool,             false),  // mut
+        ],
+        data: ix::SetPoolGate { gate_mint: gate_mint.to_bytes() }.data(),
+    }
+}
+
+/// Build the permissionless `migrate_protocol_config` instruction, which
+/// grows a config from before the pause switches and pool fee bounds to
+/// the current layout. `payer` tops up rent.
+pub fn migrate_protocol_config_ix(program_id: &Pubkey, payer: &Pubkey) -> Instruction {
+    Instruction {
+        program_id: *program_id,
+        accounts: vec![
+            AccountMeta::new(*payer,                        true),   // mut + signer
+            AccountMeta::new(derive_treasury(program_id).0, false),  // protocol_config (resized)
+            AccountMeta::new_readonly(Pubkey::default(),    false),  // system program
+        ],
+        data: ix::MigrateProtocolConfig.data(),
+    }
+}
+
+// ─── Governance ──────────────────────────────────────────────────────────────
+
+/// Build the admin-only, one-time `initialize_governance` instruction. The
+/// Governance PDA becomes the config admin; from then on only proposals
+/// passed by `vote_mint` holders change the protocol config.
+pub fn initialize_governance_ix(
+    program_id:         &Pubkey,
+    admin:              &Pubkey,
+    vote_mint:          &Pubkey,
+    quorum:             u64,
+    voting_period_secs: i64,
+) -> Instruction {
+    Instruction {
+        program_id: *program_id,
+        accounts: vec![
+            AccountMeta::new(*admin,                          true),   // mut + signer (rent)
+            AccountMeta::new(derive_treasury(program_id).0,   false),  // protocol_config
+            AccountMeta::new(derive_governance(program_id).0, false),  // init
+            AccountMeta::new_readonly(*vote_mint,             false),
+            AccountMeta::new(derive_vote_vault(program_id).0, false),  // init
+            AccountMeta::new_readonly(spl_token_id(),         false),
+            AccountMeta::new_readonly(Pubkey::default(),      false),  // system program
+            AccountMeta::new_readonly(sysvar::rent::ID,       false),
+        ],
+        data: ix::InitializeGovernance { quorum, voting_period_secs }.data(),
+    }
+}
+
+/// Build `create_proposal` for proposal number `id`, which must be the
+/// governance's current `proposal_count`.
+pub fn create_proposal_ix(
+    program_id: &Pubkey,
+    proposer:   &Pubkey,
+    id:         u64,
+    action:     GovernanceAction,
+) -> Instruction {
+    Instruction {
+        program_id: *program_id,
+        accounts: vec![
+            AccountMeta::new(*proposer,                         true),   // mut + signer (rent)
+            AccountMeta::new(derive_governance(program_id).0,   false),  // mut (proposal_count)
+            AccountMeta::new(derive_proposal(id, program_id).0, false),  // init
+            AccountMeta::new_readonly(Pubkey::default(),        false),  // system program
+        ],
+        data: ix::CreateProposal { action: action.into() }.data(),
+    }
+}
+
+/// Build `cast_vote`, escrowing `amount` vote tokens from `voter_token`
+/// for (`support`) or against proposal `id`.
+pub fn cast_vote_ix(
+    program_id:  &Pubkey,
+    voter:       &Pubkey,
+    voter_token: &Pubkey,
+    id:          u64,
+    support:     bool,
+    amount:      u64,
+) -> Instruction {
+    let (proposal, _)    = derive_proposal(id, program_id);
+    let (vote_record, _) = derive_vote_record(&proposal, voter, program_id);
+
+    Instruction {
+        program_id: *program_id,
+        accounts: vec![
+            AccountMeta::new(*voter,                                   true),   // mut + signer (rent)
+            AccountMeta::new_readonly(derive_governance(program_id).0, false),
+            AccountMeta::new(proposal,                                 false),  // mut (tally)
+            AccountMeta::new(vote_record,                              false),  // init
+            AccountMeta::new(*voter_token,                             false),  // mut
+            AccountMeta::new(derive_vote_vault(program_id).0,          false),  // mut
+            AccountMeta::new_readonly(spl_token_id(),                  false),
+            AccountMeta::new_readonly(Pubkey::default(),               false),  // system program
+        ],
+        data: ix::CastVote { support, amount }.data(),
+    }
+}
+
+/// Build the permissionless `execute_proposal` for proposal `id`. `pool` is
+/// the pool a pool action names ([`GovernanceAction::pool`]); `None` for
+/// the others.
+pub fn execute_proposal_ix(program_id: &Pubkey, id: u64, pool: Option<&Pubkey>) -> Instruction {
+    let mut accounts = vec![
+        AccountMeta::new_readonly(derive_governance(program_id).0, false),
+        AccountMeta::new(derive_proposal(id, program_id).0,        false),  // mut (executed)
+        AccountMeta::new(derive_treasury(program_id).0,            false),  // protocol_config
+    ];
+    // Optional trailing account — omitted entirely for config actions.
+    if let Some(pool) = pool {
+        accounts.push(AccountMeta::new(*pool, false));  // mut
+    }
+    Instruction { program_id: *program_id, accounts, data: ix::ExecuteProposal.data() }
+}
+
+/// Build `withdraw_vote`, returning `voter`'s escrowed tokens on proposal
+/// `id` to `voter_token` once voting has closed.
+pub fn withdraw_vote_ix(program_id: &Pubkey, voter: &Pubkey, voter_token: &Pubkey, id: u64) -> Instruction {
+    let (proposal, _)    = derive_proposal(id, program_id);
+    let (vote_record, _) = derive_vote_record(&proposal, voter, program_id);
+
+    Instruction {
+        program_id: *program_id,
+        accounts: vec![
+            AccountMeta::new(*voter,                                   true),   // mut + signer (rent refund)
+            AccountMeta::new_readonly(derive_governance(program_id).0, false),
+            AccountMeta::new_readonly(proposal,                        false),
+            AccountMeta::new(vote_record,                              false),  // mut (closed)
+            AccountMeta::new(*voter_token,                             false),  // mut
+            AccountMeta::new(derive_vote_vault(program_id).0,          false),  // mut
+            AccountMeta::new_readonly(spl_token_id(),                  false),
+        ],
+        data: ix::WithdrawVote.data(),
+    }
+}
+
 // ─── provide_liquidity ────────────────────────────────────────────────────────
 
 /// Build the `provide_liquidity` instruction.
@@ -141,35 +652,273 @@ pub fn provide_liquidity_ix(
     auto_compound:      bool,
     compound_threshold: u64,
 ) -> Instruction {
-    let mut data = disc("provide_liquidity").to_vec();
-    data.extend_from_slice(&amount_a.to_le_bytes());
-    data.extend_from_slice(&amount_b.to_le_bytes());
-    data.extend_from_slice(&min_lp.to_le_bytes());
-    data.push(auto_compound as u8);
-    data.extend_from_slice(&compound_threshold.to_le_bytes());
+    let data = ix::ProvideLiquidity {
+        amount_a,
+        amount_b,
+        min_lp,
+        auto_compound,
+        compound_threshold,
+    }
+    .data();
+
+    let accounts = provide_accounts(
+        agent, pool, pool_authority, position, vault_a, vault_b, agent_token_a, agent_token_b,
+    );
+    Instruction { program_id: *program_id, accounts, data }
+}
+
+/// Build the `provide_liquidity_proportional` instruction: deposit `amount`
+/// of token A (`fixed_a`) or token B, and let the program compute the other
+/// side from the reserves at execution.
+///
+/// Data is [`ix::ProvideLiquidityProportional`]; the computed side is
+/// rejected above `max_other` (0 = no cap). Accounts are the same as
+/// [`provide_liquidity_ix`].
+#[allow(clippy::too_many_arguments)]
+pub fn provide_liquidity_proportional_ix(
+    program_id:         &Pubkey,
+    agent:              &Pubkey,
+    pool:               &Pubkey,
+    pool_authority:     &Pubkey,
+    position:           &Pubkey,
+    vault_a:            &Pubkey,
+    vault_b:            &Pubkey,
+    agent_token_a:      &Pubkey,
+    agent_token_b:      &Pubkey,
+    amount:             u64,
+    fixed_a:            bool,
+    max_other:          u64,
+    min_lp:             u64,
+    auto_compound:      bool,
+    compound_threshold: u64,
+) -> Instruction {
+    let data = ix::ProvideLiquidityProportional {
+        amount,
+        fixed_a,
+        max_other,
+        min_lp,
+        auto_compound,
+        compound_threshold,
+    }
+    .data();
+    let accounts = provide_accounts(
+        agent, pool, pool_authority, position, vault_a, vault_b, agent_token_a, agent_token_b,
+    );
+    Instruction { program_id: *program_id, accounts, data }
+}
 
+/// The `ProvideLiquidity` accounts shared by `provide_liquidity` and
+/// `provide_liquidity_proportional`.
+#[allow(clippy::too_many_arguments)]
+fn provide_accounts(
+    agent:          &Pubkey,
+    pool:           &Pubkey,
+    pool_authority: &Pubkey,
+    position:       &Pubkey,
+    vault_a:        &Pubkey,
+    vault_b:        &Pubkey,
+    agent_token_a:  &Pubkey,
+    agent_token_b:  &Pubkey,
+) -> Vec<AccountMeta> {
+    vec![
+        AccountMeta::new(*agent,            true),   // mut + signer
+        AccountMeta::new(*pool,             false),  // mut
+        AccountMeta::new_readonly(*pool_authority, false),
+        AccountMeta::new(*position,         false),  // mut PDA (init_if_needed)
+        AccountMeta::new(*vault_a,          false),  // mut
+        AccountMeta::new(*vault_b,          false),  // mut
+        AccountMeta::new(*agent_token_a,    false),  // mut
+        AccountMeta::new(*agent_token_b,    false),  // mut
+        AccountMeta::new_readonly(spl_token_id(), false),
+        AccountMeta::new_readonly(Pubkey::default(), false), // system program
+        AccountMeta::new_readonly(sysvar::rent::ID, false),
+    ]
+}
+
+/// Trailing accounts that opt `provide_liquidity` / `remove_liquidity` into
+/// the LP receipt NFT: minted on deposit if missing, burned when a withdrawal
+/// empties the position.
+pub fn receipt_accounts(position: &Pubkey, program_id: &Pubkey) -> [AccountMeta; 2] {
+    let (receipt, _) = derive_receipt(position, program_id);
+    [
+        AccountMeta::new(receipt, false),  // mut
+        AccountMeta::new_readonly(mpl_core_program_id(), false),
+    ]
+}
+
+/// Build the `update_position_settings` instruction: set `agent`'s position
+/// to `auto_compound` with `compound_threshold` without depositing. The
+/// position must be on the current layout (see [`migrate_position_ix`]).
+pub fn update_position_settings_ix(
+    program_id:         &Pubkey,
+    agent:              &Pubkey,
+    position:           &Pubkey,
+    auto_compound:      bool,
+    compound_threshold: u64,
+) -> Instruction {
     Instruction {
         program_id: *program_id,
         accounts: vec![
-            AccountMeta::new(*agent,            true),   // mut + signer
-            AccountMeta::new(*pool,             false),  // mut
-            AccountMeta::new_readonly(*pool_authority, false),
-            AccountMeta::new(*position,         false),  // mut PDA (init_if_needed)
-            AccountMeta::new(*vault_a,          false),  // mut
-            AccountMeta::new(*vault_b,          false),  // mut
-            AccountMeta::new(*agent_token_a,    false),  // mut
-            AccountMeta::new(*agent_token_b,    false),  // mut
-            AccountMeta::new_readonly(spl_token_id(), false),
-            AccountMeta::new_readonly(Pubkey::default(), false), // system program
-            AccountMeta::new_readonly(sysvar::rent::ID, false),
+            AccountMeta::new_readonly(*agent, true),   // signer
+            AccountMeta::new(*position,       false),  // mut PDA
         ],
-        data,
+        data: ix::UpdatePositionSettings { auto_compound, compound_threshold }.data(),
     }
 }
 
-// ─── swap ─────────────────────────────────────────────────────────────────────
+/// Build the `close_position` instruction: close `agent`'s empty position
+/// (no LP shares, no owed fees) in `pool` and return its rent to `agent`.
+pub fn close_position_ix(program_id: &Pubkey, agent: &Pubkey, position: &Pubkey, pool: &Pubkey) -> Instruction {
+    Instruction {
+        program_id: *program_id,
+        accounts: vec![
+            AccountMeta::new(*agent,    true),   // mut + signer (rent refund)
+            AccountMeta::new(*position, false),  // mut (closed)
+            AccountMeta::new(*pool,     false),  // mut (open_positions)
+        ],
+        data: ix::ClosePosition {}.data(),
+    }
+}
 
-/// Validate swap parameters to catch common errors early.
+/// Build the `lock_position` instruction: `owner` locks its `position` as
+/// collateral for `locker_program`, which must be an approved locker. The
+/// locker's [`derive_lock_authority`] PDA must also sign, so in practice the
+/// locker issues this by CPI (see the `a2a-swap-cpi` crate).
+pub fn lock_position_ix(
+    program_id:     &Pubkey,
+    owner:          &Pubkey,
+    position:       &Pubkey,
+    locker_program: &Pubkey,
+) -> Instruction {
+    Instruction {
+        program_id: *program_id,
+        accounts: vec![
+            AccountMeta::new_readonly(*owner,                                   true),   // signer
+            AccountMeta::new(*position,                                         false),  // mut
+            AccountMeta::new_readonly(derive_lock_authority(locker_program).0, true),   // signer (PDA)
+            AccountMeta::new_readonly(*locker_program,                          false),
+            AccountMeta::new_readonly(derive_treasury(program_id).0,            false),  // protocol_config
+        ],
+        data: ix::LockPosition {}.data(),
+    }
+}
+
+/// Build the `unlock_position` instruction: `locker_program`, which locked
+/// `position`, releases it. Signed by the locker's [`derive_lock_authority`]
+/// PDA alone.
+pub fn unlock_position_ix(program_id: &Pubkey, position: &Pubkey, locker_program: &Pubkey) -> Instruction {
+    Instruction {
+        program_id: *program_id,
+        accounts: vec![
+            AccountMeta::new(*position,                                         false),  // mut
+            AccountMeta::new_readonly(derive_lock_authority(locker_program).0, true),   // signer (PDA)
+            AccountMeta::new_readonly(*locker_program,                          false),
+        ],
+        data: ix::UnlockPosition {}.data(),
+    }
+}
+
+// ─── swap ─────────────────────────────────────────────────────────────────────
+
+/// Build the `open_agent_volume` instruction: create `agent`'s
+/// `AgentVolume` account (the agent pays its rent) so its swaps count
+/// towards the protocol's fee tiers. See [`with_agent_volume`].
+pub fn open_agent_volume_ix(program_id: &Pubkey, agent: &Pubkey) -> Instruction {
+    Instruction {
+        program_id: *program_id,
+        accounts: vec![
+            AccountMeta::new(*agent,                                   true),   // signer, payer
+            AccountMeta::new(derive_agent_volume(agent, program_id).0, false),  // init
+            AccountMeta::new_readonly(Pubkey::default(),               false),  // system program
+        ],
+        data: ix::OpenAgentVolume {}.data(),
+    }
+}
+
+/// Add the agent's `AgentVolume` account to a swap instruction —
+/// [`swap_ix`], [`swap_exact_out_ix`], [`swap_as_delegate_ix`] (the owner
+/// trades), [`swap_with_intent_ix`], [`approve_and_execute_ix`] or
+/// [`reveal_swap_ix`] — so the swap counts towards the agent's volume and
+/// pays its tier's discounted protocol fee. The account must exist (see
+/// [`open_agent_volume_ix`]). Swaps without a referrer get the program ID
+/// in the referrer's slot, which the program reads as absent.
+pub fn with_agent_volume(mut swap: Instruction, agent: &Pubkey) -> Instruction {
+    let agent_volume = derive_agent_volume(agent, &swap.program_id).0;
+    set_optional(&mut swap, "agent_volume", AccountMeta::new(agent_volume, false));  // mut
+    swap
+}
+
+/// Build the `register_trader` instruction: prove `trader` is on the
+/// permissioned pool's allowlist with `proof` (from
+/// [`crate::allowlist::AllowlistTree::proof`]) and create or refresh its
+/// `TraderPass`; the trader pays the rent. See [`with_trader_pass`].
+pub fn register_trader_ix(program_id: &Pubkey, trader: &Pubkey, pool: &Pubkey, proof: Vec<[u8; 32]>) -> Instruction {
+    Instruction {
+        program_id: *program_id,
+        accounts: vec![
+            AccountMeta::new(*trader,                                       true),   // signer, payer
+            AccountMeta::new_readonly(*pool,                                false),
+            AccountMeta::new(derive_trader_pass(pool, trader, program_id).0, false),  // init_if_needed
+            AccountMeta::new_readonly(Pubkey::default(),                    false),  // system program
+        ],
+        data: ix::RegisterTrader { proof }.data(),
+    }
+}
+
+/// Add `trader`'s `TraderPass` on `pool` to a swap instruction —
+/// [`swap_ix`], [`swap_exact_out_ix`], [`swap_as_delegate_ix`] (the owner
+/// trades), [`swap_with_intent_ix`], [`approve_and_execute_ix`] or
+/// [`reveal_swap_ix`] — as permissioned pools require. Any missing referrer
+/// or agent volume slot before it gets the program ID, which the program
+/// reads as absent.
+pub fn with_trader_pass(mut swap: Instruction, pool: &Pubkey, trader: &Pubkey) -> Instruction {
+    let pass = derive_trader_pass(pool, trader, &swap.program_id).0;
+    set_optional(&mut swap, "trader_pass", AccountMeta::new_readonly(pass, false));
+    swap
+}
+
+/// Add `gate_token` — the trader's token account of a token-gated pool's
+/// gate mint, holding a balance (e.g. from [`derive_ata_for_program`]) — to
+/// any swap instruction [`with_trader_pass`] takes, as those pools require.
+/// Any missing optional slot before it gets the program ID, which the
+/// program reads as absent.
+pub fn with_gate_token(mut swap: Instruction, gate_token: &Pubkey) -> Instruction {
+    set_optional(&mut swap, "gate_token", AccountMeta::new_readonly(*gate_token, false));
+    swap
+}
+
+/// Put `meta` in the swap's optional `name` slot, replacing what is there;
+/// optional slots before it that are still missing get the program ID,
+/// which the program reads as absent. So the `with_*` helpers apply in any
+/// order.
+fn set_optional(swap: &mut Instruction, name: &str, meta: AccountMeta) {
+    let slot = optional_slot(&swap.data[..8], name).unwrap_or(swap.accounts.len());
+    while swap.accounts.len() < slot {
+        swap.accounts.push(AccountMeta::new_readonly(swap.program_id, false));  // absent
+    }
+    match swap.accounts.get_mut(slot) {
+        Some(existing) => *existing = meta,
+        None => swap.accounts.push(meta),
+    }
+}
+
+/// Position of the optional account `name` in the IDL accounts of the swap
+/// instruction with this discriminator.
+fn optional_slot(discriminator: &[u8], name: &str) -> Option<usize> {
+    fn slot<I: a2a_swap_core::Instruction>(discriminator: &[u8], name: &str) -> Option<usize> {
+        (discriminator == I::DISCRIMINATOR)
+            .then(|| I::ACCOUNTS.iter().position(|a| a.name == name))
+            .flatten()
+    }
+    slot::<ix::Swap>(discriminator, name)
+        .or_else(|| slot::<ix::SwapExactOut>(discriminator, name))
+        .or_else(|| slot::<ix::SwapAsDelegate>(discriminator, name))
+        .or_else(|| slot::<ix::SwapWithIntent>(discriminator, name))
+        .or_else(|| slot::<ix::ApproveAndExecute>(discriminator, name))
+        .or_else(|| slot::<ix::RevealSwap>(discriminator, name))
+}
+
+/// Validate swap parameters to catch common errors early.
 ///
 /// Checks:
 /// - amount_in must be > 0
@@ -195,18 +944,17 @@ fn validate_swap_params(amount_in: u64, min_amount_out: u64) {
 
 /// Build the `swap` instruction.
 ///
-/// Byte layout (25 bytes total):
-/// - offset 0-7:   discriminator (sha256("global:swap")[0..8])
-/// - offset 8-15:  amount_in (u64, little-endian)
-/// - offset 16-23: min_amount_out (u64, little-endian)
-/// - offset 24:    a_to_b (bool: 1 = A→B, 0 = B→A)
-///
-/// ⚠️ CRITICAL: Parameter order must match Anchor handler:
-///   handler(ctx, amount_in: u64, min_amount_out: u64, a_to_b: bool)
-///   Wrong order causes cryptic SlippageExceeded errors.
+/// Data is [`ix::Swap`] — `amount_in`, `min_amount_out`, `a_to_b`
+/// (true = A→B), `max_price_impact_bps` (0 = no cap) and `deadline_unix`,
+/// the last unix time the swap may execute (0 = no deadline; later fails
+/// with `DeadlineExceeded`) — generated from the program IDL, so the
+/// argument order always matches the handler.
 ///
 /// Pass `pool.token_a_vault` and `pool.token_b_vault` regardless of swap
 /// direction — the program reads `a_to_b` to determine which transfers to make.
+///
+/// `referrer_token`, if given, must be a token account for the input mint;
+/// it receives the config's referral share of the protocol fee.
 #[allow(clippy::too_many_arguments)]
 pub fn swap_ix(
     program_id:        &Pubkey,
@@ -219,29 +967,649 @@ pub fn swap_ix(
     agent_token_out:   &Pubkey,
     treasury:          &Pubkey,
     treasury_token_in: &Pubkey,
+    referrer_token:    Option<&Pubkey>,
     amount_in:         u64,
     min_amount_out:    u64,
     a_to_b:            bool,
+    max_price_impact_bps: u16,
+    deadline_unix:     i64,
 ) -> Instruction {
     // Validate parameters before building instruction
     validate_swap_params(amount_in, min_amount_out);
 
-    let mut data = disc("swap").to_vec();
-    data.extend_from_slice(&amount_in.to_le_bytes());
-    data.extend_from_slice(&min_amount_out.to_le_bytes());
-    data.push(a_to_b as u8);
+    let data = ix::Swap { amount_in, min_amount_out, a_to_b, max_price_impact_bps, deadline_unix }.data();
+    let accounts = swap_accounts(
+        agent, pool, pool_authority, vault_a, vault_b, agent_token_in, agent_token_out, treasury,
+        treasury_token_in, referrer_token,
+    );
+    Instruction { program_id: *program_id, accounts, data }
+}
+
+/// Build the `swap_exact_out` instruction: receive exactly `amount_out`,
+/// paying whatever input the program computes up to `max_amount_in`.
+///
+/// Data is [`ix::SwapExactOut`] — `amount_out`, `max_amount_in`, `a_to_b`
+/// and `max_price_impact_bps` (0 = no cap). Accounts are the same as
+/// [`swap_ix`].
+#[allow(clippy::too_many_arguments)]
+pub fn swap_exact_out_ix(
+    program_id:        &Pubkey,
+    agent:             &Pubkey,
+    pool:              &Pubkey,
+    pool_authority:    &Pubkey,
+    vault_a:           &Pubkey,
+    vault_b:           &Pubkey,
+    agent_token_in:    &Pubkey,
+    agent_token_out:   &Pubkey,
+    treasury:          &Pubkey,
+    treasury_token_in: &Pubkey,
+    referrer_token:    Option<&Pubkey>,
+    amount_out:        u64,
+    max_amount_in:     u64,
+    a_to_b:            bool,
+    max_price_impact_bps: u16,
+) -> Instruction {
+    let data = ix::SwapExactOut { amount_out, max_amount_in, a_to_b, max_price_impact_bps }.data();
+    let accounts = swap_accounts(
+        agent, pool, pool_authority, vault_a, vault_b, agent_token_in, agent_token_out, treasury,
+        treasury_token_in, referrer_token,
+    );
+    Instruction { program_id: *program_id, accounts, data }
+}
+
+/// The `Swap` accounts shared by `swap` and `swap_exact_out`.
+#[allow(clippy::too_many_arguments)]
+fn swap_accounts(
+    agent:             &Pubkey,
+    pool:              &Pubkey,
+    pool_authority:    &Pubkey,
+    vault_a:           &Pubkey,
+    vault_b:           &Pubkey,
+    agent_token_in:    &Pubkey,
+    agent_token_out:   &Pubkey,
+    treasury:          &Pubkey,
+    treasury_token_in: &Pubkey,
+    referrer_token:    Option<&Pubkey>,
+) -> Vec<AccountMeta> {
+    let mut accounts = vec![
+        AccountMeta::new(*agent,              true),   // mut + signer
+        AccountMeta::new(*pool,               false),  // mut (fee_growth update)
+        AccountMeta::new_readonly(*pool_authority, false),
+        AccountMeta::new(*vault_a,            false),  // mut
+        AccountMeta::new(*vault_b,            false),  // mut
+        AccountMeta::new(*agent_token_in,     false),  // mut
+        AccountMeta::new(*agent_token_out,    false),  // mut
+        AccountMeta::new_readonly(*treasury,  false),
+        AccountMeta::new(*treasury_token_in,  false),  // mut
+        AccountMeta::new_readonly(spl_token_id(), false),
+    ];
+    // Optional trailing account — omitted entirely when there is no referrer.
+    if let Some(referrer_token) = referrer_token {
+        accounts.push(AccountMeta::new(*referrer_token, false));  // mut
+    }
+    accounts
+}
+
+/// Build the `approve_and_execute` instruction: a swap that `approver` must
+/// co-sign alongside `agent`.
+///
+/// Data is [`ix::ApproveAndExecute`] — `amount_in`, `min_amount_out`,
+/// `a_to_b` and `expires_at_slot`, the last slot the approval may land in
+/// (0 = no expiry; later fails with `ApprovalExpired`). Set it a short way
+/// past the current slot so a signed approval cannot be replayed into a
+/// worse market. `deadline_unix` bounds it in unix time the same way
+/// (0 = no deadline; later fails with `DeadlineExceeded`).
+/// `max_price_impact_bps` caps the curve impact as in [`swap_ix`] (0 = no
+/// cap). Accounts are those of [`swap_ix`] plus the approver, with no
+/// referrer.
+#[allow(clippy::too_many_arguments)]
+pub fn approve_and_execute_ix(
+    program_id:        &Pubkey,
+    agent:             &Pubkey,
+    approver:          &Pubkey,
+    pool:              &Pubkey,
+    pool_authority:    &Pubkey,
+    vault_a:           &Pubkey,
+    vault_b:           &Pubkey,
+    agent_token_in:    &Pubkey,
+    agent_token_out:   &Pubkey,
+    treasury:          &Pubkey,
+    treasury_token_in: &Pubkey,
+    amount_in:         u64,
+    min_amount_out:    u64,
+    a_to_b:            bool,
+    expires_at_slot:   u64,
+    deadline_unix:     i64,
+    max_price_impact_bps: u16,
+) -> Instruction {
+    validate_swap_params(amount_in, min_amount_out);
+
+    let data = ix::ApproveAndExecute {
+        amount_in, min_amount_out, a_to_b, expires_at_slot, deadline_unix, max_price_impact_bps,
+    }.data();
+
+    let accounts = vec![
+        AccountMeta::new(*agent,              true),   // mut + signer
+        AccountMeta::new_readonly(*approver,  true),   // signer
+        AccountMeta::new(*pool,               false),  // mut (fee_growth update)
+        AccountMeta::new_readonly(*pool_authority, false),
+        AccountMeta::new(*vault_a,            false),  // mut
+        AccountMeta::new(*vault_b,            false),  // mut
+        AccountMeta::new(*agent_token_in,     false),  // mut
+        AccountMeta::new(*agent_token_out,    false),  // mut
+        AccountMeta::new_readonly(*treasury,  false),
+        AccountMeta::new(*treasury_token_in,  false),  // mut
+        AccountMeta::new_readonly(spl_token_id(), false),
+    ];
+
+    Instruction { program_id: *program_id, accounts, data }
+}
+
+// ─── Commit-reveal swaps ──────────────────────────────────────────────────────
+
+/// The hash `commit_swap` stores and `reveal_swap` checks: sha256 over
+/// `amount_in`, `min_amount_out`, `max_price_impact_bps` (little-endian),
+/// `a_to_b` as one byte, and `salt`. Keep the salt secret and random until
+/// the reveal — it is all that stops the parameters being guessed.
+pub fn swap_commitment_hash(
+    amount_in:            u64,
+    min_amount_out:       u64,
+    a_to_b:               bool,
+    max_price_impact_bps: u16,
+    salt:                 &[u8; 32],
+) -> [u8; 32] {
+    let mut hasher = Sha256::new();
+    hasher.update(amount_in.to_le_bytes());
+    hasher.update(min_amount_out.to_le_bytes());
+    hasher.update(max_price_impact_bps.to_le_bytes());
+    hasher.update([a_to_b as u8]);
+    hasher.update(salt);
+    hasher.finalize().into()
+}
+
+/// Build the `commit_swap` instruction: store `hash` (see
+/// [`swap_commitment_hash`]) in the agent's commitment PDA for `pool`.
+/// The agent pays the rent and gets it back on reveal or cancel.
+pub fn commit_swap_ix(program_id: &Pubkey, agent: &Pubkey, pool: &Pubkey, hash: [u8; 32]) -> Instruction {
+    let (commitment, _) = derive_swap_commitment(pool, agent, program_id);
+
+    Instruction {
+        program_id: *program_id,
+        accounts: vec![
+            AccountMeta::new(*agent,             true),   // mut + signer (rent payer)
+            AccountMeta::new_readonly(*pool,     false),
+            AccountMeta::new(commitment,         false),  // init
+            AccountMeta::new_readonly(Pubkey::default(), false), // system program
+        ],
+        data: ix::CommitSwap { hash }.data(),
+    }
+}
+
+/// Build the `reveal_swap` instruction: execute the swap committed with
+/// [`commit_swap_ix`] and close the commitment. The arguments and `salt`
+/// must be the ones hashed at commit time, and the transaction must land in
+/// a later slot, at most `MAX_REVEAL_DELAY_SLOTS` (150) after the commit.
+/// Accounts are those of [`swap_ix`] plus the commitment, with no referrer.
+#[allow(clippy::too_many_arguments)]
+pub fn reveal_swap_ix(
+    program_id:        &Pubkey,
+    agent:             &Pubkey,
+    pool:              &Pubkey,
+    pool_authority:    &Pubkey,
+    vault_a:           &Pubkey,
+    vault_b:           &Pubkey,
+    agent_token_in:    &Pubkey,
+    agent_token_out:   &Pubkey,
+    treasury:          &Pubkey,
+    treasury_token_in: &Pubkey,
+    amount_in:         u64,
+    min_amount_out:    u64,
+    a_to_b:            bool,
+    max_price_impact_bps: u16,
+    salt:              [u8; 32],
+) -> Instruction {
+    validate_swap_params(amount_in, min_amount_out);
+
+    let (commitment, _) = derive_swap_commitment(pool, agent, program_id);
+    let data = ix::RevealSwap { amount_in, min_amount_out, a_to_b, max_price_impact_bps, salt }.data();
+
+    let accounts = vec![
+        AccountMeta::new(*agent,              true),   // mut + signer (rent refund)
+        AccountMeta::new(*pool,               false),  // mut (fee_growth update)
+        AccountMeta::new(commitment,          false),  // mut (closed)
+        AccountMeta::new_readonly(*pool_authority, false),
+        AccountMeta::new(*vault_a,            false),  // mut
+        AccountMeta::new(*vault_b,            false),  // mut
+        AccountMeta::new(*agent_token_in,     false),  // mut
+        AccountMeta::new(*agent_token_out,    false),  // mut
+        AccountMeta::new_readonly(*treasury,  false),
+        AccountMeta::new(*treasury_token_in,  false),  // mut
+        AccountMeta::new_readonly(spl_token_id(), false),
+    ];
+
+    Instruction { program_id: *program_id, accounts, data }
+}
+
+/// Build the `cancel_swap_commitment` instruction: close the agent's
+/// unrevealed commitment on `pool` and refund its rent.
+pub fn cancel_swap_commitment_ix(program_id: &Pubkey, agent: &Pubkey, pool: &Pubkey) -> Instruction {
+    let (commitment, _) = derive_swap_commitment(pool, agent, program_id);
+
+    Instruction {
+        program_id: *program_id,
+        accounts: vec![
+            AccountMeta::new(*agent,     true),   // mut + signer (rent refund)
+            AccountMeta::new(commitment, false),  // mut (closed)
+        ],
+        data: ix::CancelSwapCommitment {}.data(),
+    }
+}
+
+// ─── Session keys ─────────────────────────────────────────────────────────────
+
+/// Build the `create_delegate` instruction: `owner` lets `delegate` swap out
+/// of `owner_token`, up to `max_amount_in` in total, until the unix
+/// timestamp `expires_at`. The Delegate PDA becomes the SPL delegate of
+/// `owner_token` for the cap.
+pub fn create_delegate_ix(
+    program_id:    &Pubkey,
+    owner:         &Pubkey,
+    owner_token:   &Pubkey,
+    delegate:      &Pubkey,
+    max_amount_in: u64,
+    expires_at:    i64,
+) -> Instruction {
+    let (delegate_account, _) = derive_delegate(owner, owner_token, program_id);
+    let data = ix::CreateDelegate { delegate: delegate.to_bytes(), max_amount_in, expires_at }.data();
+
+    Instruction {
+        program_id: *program_id,
+        accounts: vec![
+            AccountMeta::new(*owner,                true),   // mut + signer (rent)
+            AccountMeta::new(delegate_account,      false),  // mut PDA (init)
+            AccountMeta::new(*owner_token,          false),  // mut (SPL approve)
+            AccountMeta::new_readonly(spl_token_id(), false),
+            AccountMeta::new_readonly(Pubkey::default(), false), // system program
+        ],
+        data,
+    }
+}
+
+/// Build the `rotate_delegate` instruction: hand the session on
+/// `owner_token` to `delegate` with a fresh cap and expiry. `spent` resets.
+pub fn rotate_delegate_ix(
+    program_id:    &Pubkey,
+    owner:         &Pubkey,
+    owner_token:   &Pubkey,
+    delegate:      &Pubkey,
+    max_amount_in: u64,
+    expires_at:    i64,
+) -> Instruction {
+    let (delegate_account, _) = derive_delegate(owner, owner_token, program_id);
+    let data = ix::RotateDelegate { delegate: delegate.to_bytes(), max_amount_in, expires_at }.data();
+
+    Instruction {
+        program_id: *program_id,
+        accounts: vec![
+            AccountMeta::new_readonly(*owner,       true),   // signer
+            AccountMeta::new(delegate_account,      false),  // mut
+            AccountMeta::new(*owner_token,          false),  // mut (SPL approve)
+            AccountMeta::new_readonly(spl_token_id(), false),
+        ],
+        data,
+    }
+}
+
+/// Build the `revoke_delegate` instruction: drop the SPL delegation on
+/// `owner_token` and close the Delegate account, refunding rent to `owner`.
+pub fn revoke_delegate_ix(program_id: &Pubkey, owner: &Pubkey, owner_token: &Pubkey) -> Instruction {
+    let (delegate_account, _) = derive_delegate(owner, owner_token, program_id);
+
+    Instruction {
+        program_id: *program_id,
+        accounts: vec![
+            AccountMeta::new(*owner,                true),   // mut + signer (rent refund)
+            AccountMeta::new(delegate_account,      false),  // mut (closed)
+            AccountMeta::new(*owner_token,          false),  // mut (SPL revoke)
+            AccountMeta::new_readonly(spl_token_id(), false),
+        ],
+        data: ix::RevokeDelegate {}.data(),
+    }
+}
+
+/// Build the `swap_as_delegate` instruction: [`swap_ix`] signed by the
+/// session key `delegate`, paid from the owner's `owner_token_in` (the
+/// Delegate's token account) into the owner's `owner_token_out`. No referrer.
+#[allow(clippy::too_many_arguments)]
+pub fn swap_as_delegate_ix(
+    program_id:        &Pubkey,
+    delegate:          &Pubkey,
+    owner:             &Pubkey,
+    pool:              &Pubkey,
+    pool_authority:    &Pubkey,
+    vault_a:           &Pubkey,
+    vault_b:           &Pubkey,
+    owner_token_in:    &Pubkey,
+    owner_token_out:   &Pubkey,
+    treasury:          &Pubkey,
+    treasury_token_in: &Pubkey,
+    amount_in:         u64,
+    min_amount_out:    u64,
+    a_to_b:            bool,
+    max_price_impact_bps: u16,
+) -> Instruction {
+    validate_swap_params(amount_in, min_amount_out);
+
+    let (delegate_account, _) = derive_delegate(owner, owner_token_in, program_id);
+    let data = ix::SwapAsDelegate { amount_in, min_amount_out, a_to_b, max_price_impact_bps }.data();
+
+    let accounts = vec![
+        AccountMeta::new(*delegate,           true),   // mut + signer (fee payer)
+        AccountMeta::new_readonly(*owner,     false),
+        AccountMeta::new(delegate_account,    false),  // mut (spent)
+        AccountMeta::new(*pool,               false),  // mut (fee_growth update)
+        AccountMeta::new_readonly(*pool_authority, false),
+        AccountMeta::new(*vault_a,            false),  // mut
+        AccountMeta::new(*vault_b,            false),  // mut
+        AccountMeta::new(*owner_token_in,     false),  // mut
+        AccountMeta::new(*owner_token_out,    false),  // mut
+        AccountMeta::new_readonly(*treasury,  false),
+        AccountMeta::new(*treasury_token_in,  false),  // mut
+        AccountMeta::new_readonly(spl_token_id(), false),
+    ];
+
+    Instruction { program_id: *program_id, accounts, data }
+}
+
+// ─── Relayed swap intents ─────────────────────────────────────────────────────
+
+/// Prefix of every swap intent message (mirrors the program's `INTENT_DOMAIN`).
+pub const INTENT_DOMAIN: &[u8] = b"a2a-swap:swap_with_intent:v1";
+
+/// The message an agent signs for `swap_with_intent`: [`INTENT_DOMAIN`],
+/// the program, agent and pool keys, then `amount_in`, `min_amount_out`,
+/// `a_to_b` as one byte, `max_price_impact_bps`, `nonce` and `expires_at`
+/// (integers little-endian). See [`crate::intent::SwapIntent`].
+#[allow(clippy::too_many_arguments)]
+pub fn swap_intent_message(
+    program_id:           &Pubkey,
+    agent:                &Pubkey,
+    pool:                 &Pubkey,
+    amount_in:            u64,
+    min_amount_out:       u64,
+    a_to_b:               bool,
+    max_price_impact_bps: u16,
+    nonce:                u64,
+    expires_at:           i64,
+) -> Vec<u8> {
+    let mut message = Vec::with_capacity(INTENT_DOMAIN.len() + 96 + 35);
+    message.extend_from_slice(INTENT_DOMAIN);
+    message.extend_from_slice(program_id.as_ref());
+    message.extend_from_slice(agent.as_ref());
+    message.extend_from_slice(pool.as_ref());
+    message.extend_from_slice(&amount_in.to_le_bytes());
+    message.extend_from_slice(&min_amount_out.to_le_bytes());
+    message.push(a_to_b as u8);
+    message.extend_from_slice(&max_price_impact_bps.to_le_bytes());
+    message.extend_from_slice(&nonce.to_le_bytes());
+    message.extend_from_slice(&expires_at.to_le_bytes());
+    message
+}
+
+/// Build an ed25519 program instruction checking `signature` by `signer`
+/// over `message`, with all three stored in the instruction itself — the
+/// form `swap_with_intent` accepts as the instruction right before it.
+pub fn ed25519_verify_ix(signer: &Pubkey, signature: &Signature, message: &[u8]) -> Instruction {
+    // [count, padding, seven u16 offsets, then key(32), signature(64), message]
+    const KEY_OFFSET: u16 = 16;
+    const SIGNATURE_OFFSET: u16 = KEY_OFFSET + 32;
+    const MESSAGE_OFFSET: u16 = SIGNATURE_OFFSET + 64;
+    let mut data = vec![1, 0];
+    for offset in [
+        SIGNATURE_OFFSET, u16::MAX,
+        KEY_OFFSET, u16::MAX,
+        MESSAGE_OFFSET, message.len() as u16, u16::MAX,
+    ] {
+        data.extend_from_slice(&offset.to_le_bytes());
+    }
+    data.extend_from_slice(signer.as_ref());
+    data.extend_from_slice(signature.as_ref());
+    data.extend_from_slice(message);
+
+    Instruction { program_id: ed25519_program::id(), accounts: Vec::new(), data }
+}
+
+/// Build an SPL Token `Approve` letting the agent's `IntentNonce` PDA spend
+/// up to `amount` from `token_account`, so relayers can execute the agent's
+/// intents. Approving again replaces the allowance; each relayed swap
+/// spends from it.
+pub fn approve_intents_ix(program_id: &Pubkey, agent: &Pubkey, token_account: &Pubkey, amount: u64) -> Instruction {
+    let (intent_nonce, _) = derive_intent_nonce(agent, program_id);
+    let mut data = vec![4];  // 4 = Approve
+    data.extend_from_slice(&amount.to_le_bytes());
+
+    Instruction {
+        program_id: spl_token_id(),
+        accounts: vec![
+            AccountMeta::new(*token_account,          false),  // mut
+            AccountMeta::new_readonly(intent_nonce,   false),  // delegate
+            AccountMeta::new_readonly(*agent,         true),   // owner
+        ],
+        data,
+    }
+}
+
+/// Build the `swap_with_intent` instruction: execute the agent's signed
+/// intent, signed and paid for by `relayer`. It must directly follow the
+/// [`ed25519_verify_ix`] of the agent's signature over
+/// [`swap_intent_message`] with the same arguments. No referrer.
+#[allow(clippy::too_many_arguments)]
+pub fn swap_with_intent_ix(
+    program_id:        &Pubkey,
+    relayer:           &Pubkey,
+    agent:             &Pubkey,
+    pool:              &Pubkey,
+    pool_authority:    &Pubkey,
+    vault_a:           &Pubkey,
+    vault_b:           &Pubkey,
+    agent_token_in:    &Pubkey,
+    agent_token_out:   &Pubkey,
+    treasury:          &Pubkey,
+    treasury_token_in: &Pubkey,
+    amount_in:         u64,
+    min_amount_out:    u64,
+    a_to_b:            bool,
+    max_price_impact_bps: u16,
+    nonce:             u64,
+    expires_at:        i64,
+) -> Instruction {
+    validate_swap_params(amount_in, min_amount_out);
+
+    let (intent_nonce, _) = derive_intent_nonce(agent, program_id);
+    let data = ix::SwapWithIntent {
+        amount_in, min_amount_out, a_to_b, max_price_impact_bps, nonce, expires_at,
+    }
+    .data();
+
+    let accounts = vec![
+        AccountMeta::new(*relayer,            true),   // mut + signer (fee payer, rent)
+        AccountMeta::new_readonly(*agent,     false),
+        AccountMeta::new(intent_nonce,        false),  // mut (init_if_needed, nonce)
+        AccountMeta::new(*pool,               false),  // mut (fee_growth update)
+        AccountMeta::new_readonly(*pool_authority, false),
+        AccountMeta::new(*vault_a,            false),  // mut
+        AccountMeta::new(*vault_b,            false),  // mut
+        AccountMeta::new(*agent_token_in,     false),  // mut
+        AccountMeta::new(*agent_token_out,    false),  // mut
+        AccountMeta::new_readonly(*treasury,  false),
+        AccountMeta::new(*treasury_token_in,  false),  // mut
+        AccountMeta::new_readonly(sysvar::instructions::id(), false),
+        AccountMeta::new_readonly(spl_token_id(), false),
+        AccountMeta::new_readonly(Pubkey::default(), false), // system program
+    ];
+
+    Instruction { program_id: *program_id, accounts, data }
+}
+
+// ─── Range pools ──────────────────────────────────────────────────────────────
+
+/// Build the `initialize_range_pool` instruction. The pool starts at price
+/// `1.0001^initial_tick`; `vault_a` / `vault_b` must be fresh signer
+/// keypairs, as for [`initialize_pool_ix`].
+#[allow(clippy::too_many_arguments)]
+pub fn initialize_range_pool_ix(
+    program_id:   &Pubkey,
+    creator:      &Pubkey,
+    mint_a:       &Pubkey,
+    mint_b:       &Pubkey,
+    vault_a:      &Pubkey,
+    vault_b:      &Pubkey,
+    fee_rate_bps: u16,
+    tick_spacing: u16,
+    initial_tick: i32,
+) -> Instruction {
+    let (pool, _)           = derive_range_pool(mint_a, mint_b, program_id);
+    let (pool_authority, _) = derive_pool_authority(&pool, program_id);
+
+    let data = ix::InitializeRangePool { fee_rate_bps, tick_spacing, initial_tick }.data();
+
+    Instruction {
+        program_id: *program_id,
+        accounts: vec![
+            AccountMeta::new(*creator,               true),   // mut + signer
+            AccountMeta::new_readonly(*mint_a,        false),
+            AccountMeta::new_readonly(*mint_b,        false),
+            AccountMeta::new(pool,                    false),  // mut PDA (init)
+            AccountMeta::new_readonly(pool_authority, false),
+            AccountMeta::new(*vault_a,               true),   // mut + signer (init)
+            AccountMeta::new(*vault_b,               true),   // mut + signer (init)
+            AccountMeta::new_readonly(spl_token_id(), false),
+            AccountMeta::new_readonly(Pubkey::default(), false), // system program
+            AccountMeta::new_readonly(sysvar::rent::ID, false),
+            AccountMeta::new_readonly(derive_treasury(program_id).0, false), // fee bounds, pause
+        ],
+        data,
+    }
+}
+
+/// Build the `provide_range_liquidity` instruction. The position PDA is
+/// derived from `agent` and the tick bounds.
+#[allow(clippy::too_many_arguments)]
+pub fn provide_range_liquidity_ix(
+    program_id:    &Pubkey,
+    agent:         &Pubkey,
+    pool:          &Pubkey,
+    vault_a:       &Pubkey,
+    vault_b:       &Pubkey,
+    agent_token_a: &Pubkey,
+    agent_token_b: &Pubkey,
+    tick_lower:    i32,
+    tick_upper:    i32,
+    amount_a_max:  u64,
+    amount_b_max:  u64,
+    min_liquidity: u128,
+) -> Instruction {
+    let (position, _) = derive_range_position(pool, agent, tick_lower, tick_upper, program_id);
+
+    let data = ix::ProvideRangeLiquidity {
+        tick_lower,
+        tick_upper,
+        amount_a_max,
+        amount_b_max,
+        min_liquidity,
+    }
+    .data();
+
+    Instruction {
+        program_id: *program_id,
+        accounts: vec![
+            AccountMeta::new(*agent,         true),   // mut + signer
+            AccountMeta::new(*pool,          false),  // mut
+            AccountMeta::new(position,       false),  // mut PDA (init_if_needed)
+            AccountMeta::new(*vault_a,       false),  // mut
+            AccountMeta::new(*vault_b,       false),  // mut
+            AccountMeta::new(*agent_token_a, false),  // mut
+            AccountMeta::new(*agent_token_b, false),  // mut
+            AccountMeta::new_readonly(spl_token_id(), false),
+            AccountMeta::new_readonly(Pubkey::default(), false), // system program
+            AccountMeta::new_readonly(sysvar::rent::ID, false),
+        ],
+        data,
+    }
+}
+
+/// Build the `remove_range_liquidity` instruction. Pays out the withdrawn
+/// liquidity plus all fees owed; `liquidity = 0` collects fees only.
+#[allow(clippy::too_many_arguments)]
+pub fn remove_range_liquidity_ix(
+    program_id:    &Pubkey,
+    agent:         &Pubkey,
+    pool:          &Pubkey,
+    position:      &Pubkey,
+    vault_a:       &Pubkey,
+    vault_b:       &Pubkey,
+    agent_token_a: &Pubkey,
+    agent_token_b: &Pubkey,
+    liquidity:     u128,
+    min_a:         u64,
+    min_b:         u64,
+) -> Instruction {
+    let (pool_authority, _) = derive_pool_authority(pool, program_id);
+
+    let data = ix::RemoveRangeLiquidity { liquidity, min_a, min_b }.data();
+
+    Instruction {
+        program_id: *program_id,
+        accounts: vec![
+            AccountMeta::new(*agent,         true),   // mut + signer
+            AccountMeta::new(*pool,          false),  // mut
+            AccountMeta::new_readonly(pool_authority, false),
+            AccountMeta::new(*position,      false),  // mut
+            AccountMeta::new(*vault_a,       false),  // mut
+            AccountMeta::new(*vault_b,       false),  // mut
+            AccountMeta::new(*agent_token_a, false),  // mut
+            AccountMeta::new(*agent_token_b, false),  // mut
+            AccountMeta::new_readonly(spl_token_id(), false),
+        ],
+        data,
+    }
+}
+
+/// Build the `swap_range` instruction. Accounts are ordered as in
+/// [`swap_ix`], with the pool-authority and treasury PDAs derived here;
+/// `agent_token_in` must hold token A when `a_to_b`.
+#[allow(clippy::too_many_arguments)]
+pub fn swap_range_ix(
+    program_id:        &Pubkey,
+    agent:             &Pubkey,
+    pool:              &Pubkey,
+    vault_a:           &Pubkey,
+    vault_b:           &Pubkey,
+    agent_token_in:    &Pubkey,
+    agent_token_out:   &Pubkey,
+    treasury_token_in: &Pubkey,
+    amount_in:         u64,
+    min_amount_out:    u64,
+    a_to_b:            bool,
+    max_price_impact_bps: u16,
+) -> Instruction {
+    validate_swap_params(amount_in, min_amount_out);
+    let (pool_authority, _) = derive_pool_authority(pool, program_id);
+    let (treasury, _)       = derive_treasury(program_id);
+
+    let data = ix::SwapRange { amount_in, min_amount_out, a_to_b, max_price_impact_bps }.data();
 
     Instruction {
         program_id: *program_id,
         accounts: vec![
             AccountMeta::new(*agent,              true),   // mut + signer
-            AccountMeta::new(*pool,               false),  // mut (fee_growth update)
-            AccountMeta::new_readonly(*pool_authority, false),
+            AccountMeta::new(*pool,               false),  // mut (price / ticks)
+            AccountMeta::new_readonly(pool_authority, false),
             AccountMeta::new(*vault_a,            false),  // mut
             AccountMeta::new(*vault_b,            false),  // mut
             AccountMeta::new(*agent_token_in,     false),  // mut
             AccountMeta::new(*agent_token_out,    false),  // mut
-            AccountMeta::new_readonly(*treasury,  false),
+            AccountMeta::new_readonly(treasury,   false),
             AccountMeta::new(*treasury_token_in,  false),  // mut
             AccountMeta::new_readonly(spl_token_id(), false),
         ],
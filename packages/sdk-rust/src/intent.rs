@@ -0,0 +1,173 @@
+//! Signed swap intents: gasless swaps submitted by a relayer.
+//!
+//! The agent signs a [`SwapIntent`] off-chain with [`SwapIntent::sign`] and
+//! hands the [`SignedIntent`] to a relayer (`a2a-swap-relayer`, or anyone
+//! else), which submits it as `swap_with_intent` and pays the transaction
+//! fee. The program checks the agent's ed25519 signature, the intent's
+//! `expires_at` and its `nonce` (the agent's `IntentNonce`, bumped by every
+//! executed intent), so each intent runs at most once.
+//!
+//! Input tokens move through an SPL delegation: the agent approves its
+//! `IntentNonce` PDA on the input token account once, with
+//! [`approve_intents_ix`](crate::instructions::approve_intents_ix) or
+//! [`A2ASwapClient::approve_intents`](crate::A2ASwapClient::approve_intents),
+//! and relayed swaps spend from that allowance. Output always goes to the
+//! agent's own token account.
+//!
+//! ```no_run
+//! # async fn run(client: a2a_swap_sdk::A2ASwapClient, agent: solana_sdk::signature::Keypair,
+//! #              relayer: solana_sdk::signature::Keypair, params: a2a_swap_sdk::SwapParams)
+//! #              -> a2a_swap_sdk::Result<()> {
+//! // Agent: quote, sign an intent good for two minutes, hand it over as JSON.
+//! let signed = client.sign_intent(&agent, &params, 120).await?;
+//! let body = serde_json::to_string(&signed).unwrap();
+//!
+//! // Relayer: check and submit it, paying the fee.
+//! let signed = serde_json::from_str(&body).unwrap();
+//! let result = client.relay_intent(&relayer, &signed).await?;
+//! # Ok(()) }
+//! ```
+
+use std::{fmt::Display, str::FromStr};
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use solana_sdk::{
+    instruction::Instruction,
+    pubkey::Pubkey,
+    signature::{Signature, Signer},
+};
+
+use crate::{
+    error::{Error, Result},
+    instructions::{
+        derive_ata, derive_pool_authority, derive_treasury, ed25519_verify_ix, swap_intent_message,
+        swap_with_intent_ix, with_trader_pass,
+    },
+    state::PoolState,
+};
+
+/// A swap the agent authorizes a relayer to execute once, before
+/// `expires_at`. Mirrors the arguments of `swap_with_intent`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SwapIntent {
+    /// Pool to swap in.
+    #[serde(with = "display_fromstr")]
+    pub pool:                 Pubkey,
+    /// Exact input, in atomic units of the input token.
+    pub amount_in:            u64,
+    /// Least output the agent accepts.
+    pub min_amount_out:       u64,
+    /// `true` sells token A for token B.
+    pub a_to_b:               bool,
+    /// Largest price impact accepted (0 = no cap).
+    pub max_price_impact_bps: u16,
+    /// The agent's current intent nonce; see
+    /// [`A2ASwapClient::intent_nonce`](crate::A2ASwapClient::intent_nonce).
+    pub nonce:                u64,
+    /// Unix timestamp after which the intent fails.
+    pub expires_at:           i64,
+}
+
+impl SwapIntent {
+    /// The bytes `agent` signs for this intent under `program_id`.
+    pub fn message(&self, program_id: &Pubkey, agent: &Pubkey) -> Vec<u8> {
+        swap_intent_message(
+            program_id,
+            agent,
+            &self.pool,
+            self.amount_in,
+            self.min_amount_out,
+            self.a_to_b,
+            self.max_price_impact_bps,
+            self.nonce,
+            self.expires_at,
+        )
+    }
+
+    /// Sign the intent as `agent`. Nothing is sent; pass the result to a
+    /// relayer.
+    pub fn sign(self, program_id: &Pubkey, agent: &dyn Signer) -> Result<SignedIntent> {
+        let agent_key = agent.pubkey();
+        let signature = agent
+            .try_sign_message(&self.message(program_id, &agent_key))
+            .map_err(|e| Error::InvalidArgument(format!("could not sign swap intent: {e}")))?;
+        Ok(SignedIntent { agent: agent_key, intent: self, signature })
+    }
+}
+
+/// A [`SwapIntent`] with the agent's signature: everything a relayer needs.
+/// Serializes keys and the signature as base58 strings.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SignedIntent {
+    #[serde(with = "display_fromstr")]
+    pub agent:     Pubkey,
+    #[serde(flatten)]
+    pub intent:    SwapIntent,
+    #[serde(with = "display_fromstr")]
+    pub signature: Signature,
+}
+
+impl SignedIntent {
+    /// Whether the signature is the agent's over this intent under
+    /// `program_id`. Relayers should check before paying for a transaction.
+    pub fn verify(&self, program_id: &Pubkey) -> bool {
+        self.signature.verify(self.agent.as_ref(), &self.intent.message(program_id, &self.agent))
+    }
+
+    /// The ed25519 check and the `swap_with_intent` that follows it, for
+    /// `relayer` to sign. `pool` is the state of `intent.pool`; input and
+    /// output are the agent's associated token accounts, and the output
+    /// account must already exist. On a permissioned pool the agent's
+    /// `TraderPass` is appended.
+    pub fn instructions(&self, program_id: &Pubkey, relayer: &Pubkey, pool: &PoolState) -> [Instruction; 2] {
+        let SwapIntent { pool: pool_addr, a_to_b, .. } = self.intent;
+        let (mint_in, mint_out) = if a_to_b {
+            (pool.token_a_mint, pool.token_b_mint)
+        } else {
+            (pool.token_b_mint, pool.token_a_mint)
+        };
+        let (pool_authority, _) = derive_pool_authority(&pool_addr, program_id);
+        let (treasury, _) = derive_treasury(program_id);
+        let message = self.intent.message(program_id, &self.agent);
+
+        let swap = swap_with_intent_ix(
+            program_id,
+            relayer,
+            &self.agent,
+            &pool_addr,
+            &pool_authority,
+            &pool.token_a_vault,
+            &pool.token_b_vault,
+            &derive_ata(&self.agent, &mint_in),
+            &derive_ata(&self.agent, &mint_out),
+            &treasury,
+            &derive_ata(&treasury, &mint_in),
+            self.intent.amount_in,
+            self.intent.min_amount_out,
+            a_to_b,
+            self.intent.max_price_impact_bps,
+            self.intent.nonce,
+            self.intent.expires_at,
+        );
+        let swap = if pool.is_permissioned() { with_trader_pass(swap, &pool_addr, &self.agent) } else { swap };
+        [ed25519_verify_ix(&self.agent, &self.signature, &message), swap]
+    }
+}
+
+/// Serde through `Display` / `FromStr`: base58 for keys and signatures.
+mod display_fromstr {
+    use super::*;
+
+    pub fn serialize<T: Display, S: Serializer>(value: &T, s: S) -> std::result::Result<S::Ok, S::Error> {
+        s.collect_str(value)
+    }
+
+    pub fn deserialize<'de, T, D>(d: D) -> std::result::Result<T, D::Error>
+    where
+        T: FromStr,
+        T::Err: Display,
+        D: Deserializer<'de>,
+    {
+        String::deserialize(d)?.parse().map_err(serde::de::Error::custom)
+    }
+}
@@ -0,0 +1,273 @@
+//! Passphrase-encrypted keypair files.
+//!
+//! A Solana `id.json` holds the secret key in the clear. A [`Keystore`]
+//! holds the same 64 keypair bytes sealed under a passphrase: scrypt
+//! stretches the passphrase into a 256-bit key and AES-256-GCM-SIV encrypts
+//! with the public key as associated data, so a wrong passphrase or an
+//! edited file fails to open instead of yielding some other key. The public
+//! key stays readable without the passphrase.
+//!
+//! ```json
+//! {
+//!   "version": 1,
+//!   "pubkey": "7xKX…",
+//!   "kdf": { "name": "scrypt", "log_n": 15, "r": 8, "p": 1, "salt": "<base64>" },
+//!   "cipher": { "name": "aes-256-gcm-siv", "nonce": "<base64>" },
+//!   "ciphertext": "<base64>"
+//! }
+//! ```
+//!
+//! [`read_keypair`] opens either kind of file, so plain `id.json` keypairs
+//! keep working wherever a keystore is accepted:
+//!
+//! ```rust,no_run
+//! # use a2a_swap_sdk::keystore::{self, Keystore};
+//! # use solana_sdk::signature::Keypair;
+//! # fn run() -> a2a_swap_sdk::Result<()> {
+//! let sealed = Keystore::encrypt(&Keypair::new(), "correct horse")?;
+//! std::fs::write("agent.keystore.json", sealed.to_json()).unwrap();
+//!
+//! // Passphrase from A2A_KEYSTORE_PASSPHRASE; ignored for plain id.json files.
+//! let agent = keystore::read_keypair("agent.keystore.json", keystore::passphrase_from_env().as_deref())?;
+//! # Ok(()) }
+//! ```
+
+use std::path::Path;
+
+use aes_gcm_siv::{
+    aead::{Aead, KeyInit, Payload},
+    Aes256GcmSiv, Nonce,
+};
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine as _};
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+use solana_sdk::{
+    pubkey::Pubkey,
+    signature::Keypair,
+    signer::{keypair, Signer},
+};
+use zeroize::Zeroizing;
+
+use crate::error::{Error, Result};
+
+/// Environment variable [`passphrase_from_env`] reads.
+pub const PASSPHRASE_ENV: &str = "A2A_KEYSTORE_PASSPHRASE";
+
+/// Keystore format version written by [`Keystore::encrypt`].
+pub const KEYSTORE_VERSION: u32 = 1;
+
+const KDF_NAME: &str = "scrypt";
+const CIPHER_NAME: &str = "aes-256-gcm-siv";
+const SALT_LEN: usize = 32;
+const NONCE_LEN: usize = 12;
+
+// ─── scrypt ──────────────────────────────────────────────────────────────────
+
+/// scrypt cost parameters (RFC 7914): `N = 2^log_n`, block size `r`,
+/// parallelism `p`. Memory use is `128 · r · N` bytes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ScryptParams {
+    pub log_n: u8,
+    pub r:     u32,
+    pub p:     u32,
+}
+
+impl ScryptParams {
+    /// `N = 2^15, r = 8, p = 1`: 32 MiB, a fraction of a second per unlock.
+    pub const DEFAULT: Self = Self { log_n: 15, r: 8, p: 1 };
+
+    /// Largest memory cost accepted, so a crafted file can't exhaust RAM.
+    const MAX_MEMORY: u64 = 1 << 30;
+
+    fn validate(&self) -> Result<()> {
+        let block = 128 * u64::from(self.r);
+        if self.log_n == 0 || self.log_n >= 32 || self.r == 0 || self.p == 0
+            || block << self.log_n > Self::MAX_MEMORY
+            || block * u64::from(self.p) > Self::MAX_MEMORY
+        {
+            return Err(Error::InvalidArgument(format!(
+                "scrypt parameters out of range: log_n={} r={} p={} (at most {} MiB)",
+                self.log_n, self.r, self.p, Self::MAX_MEMORY >> 20
+            )));
+        }
+        Ok(())
+    }
+}
+
+impl Default for ScryptParams {
+    fn default() -> Self {
+        Self::DEFAULT
+    }
+}
+
+/// Derive `out.len()` bytes from `passphrase` and `salt` with scrypt.
+pub fn scrypt(passphrase: &[u8], salt: &[u8], params: ScryptParams, out: &mut [u8]) -> Result<()> {
+    params.validate()?;
+    let invalid = |e: &dyn std::fmt::Display| Error::InvalidArgument(format!("scrypt: {e}"));
+    let params = scrypt::Params::new(params.log_n, params.r, params.p, out.len()).map_err(|e| invalid(&e))?;
+    scrypt::scrypt(passphrase, salt, &params, out).map_err(|e| invalid(&e))
+}
+
+// ─── Keystore ────────────────────────────────────────────────────────────────
+
+/// A keypair encrypted under a passphrase — see the [module docs](self).
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Keystore {
+    pub version:    u32,
+    /// Base-58 public key, readable without the passphrase.
+    pub pubkey:     String,
+    pub kdf:        KdfParams,
+    pub cipher:     CipherParams,
+    /// Base64 of the sealed 64 keypair bytes and the 16-byte tag.
+    pub ciphertext: String,
+}
+
+/// `kdf` section of a [`Keystore`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct KdfParams {
+    /// Always `scrypt`.
+    pub name:   String,
+    #[serde(flatten)]
+    pub params: ScryptParams,
+    /// Base64 salt.
+    pub salt:   String,
+}
+
+/// `cipher` section of a [`Keystore`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CipherParams {
+    /// Always `aes-256-gcm-siv`.
+    pub name:  String,
+    /// Base64 96-bit nonce.
+    pub nonce: String,
+}
+
+impl Keystore {
+    /// Seal `keypair` under `passphrase` with [`ScryptParams::DEFAULT`].
+    pub fn encrypt(keypair: &Keypair, passphrase: &str) -> Result<Self> {
+        Self::encrypt_with(keypair, passphrase, ScryptParams::DEFAULT)
+    }
+
+    /// Seal `keypair` under `passphrase` with explicit scrypt costs.
+    pub fn encrypt_with(keypair: &Keypair, passphrase: &str, params: ScryptParams) -> Result<Self> {
+        let mut salt  = [0u8; SALT_LEN];
+        let mut nonce = [0u8; NONCE_LEN];
+        rand::thread_rng().fill_bytes(&mut salt);
+        rand::thread_rng().fill_bytes(&mut nonce);
+
+        let pubkey = keypair.pubkey();
+        let secret = Zeroizing::new(keypair.to_bytes());
+        let ciphertext = cipher(passphrase, &salt, params)?
+            .encrypt(Nonce::from_slice(&nonce), Payload { msg: &secret[..], aad: pubkey.as_ref() })
+            .map_err(|_| Error::InvalidArgument("keystore encryption failed".into()))?;
+        Ok(Self {
+            version:    KEYSTORE_VERSION,
+            pubkey:     pubkey.to_string(),
+            kdf:        KdfParams { name: KDF_NAME.into(), params, salt: BASE64.encode(salt) },
+            cipher:     CipherParams { name: CIPHER_NAME.into(), nonce: BASE64.encode(nonce) },
+            ciphertext: BASE64.encode(ciphertext),
+        })
+    }
+
+    /// Open the keystore. Fails on a wrong passphrase or a modified file.
+    pub fn decrypt(&self, passphrase: &str) -> Result<Keypair> {
+        if self.version != KEYSTORE_VERSION || self.kdf.name != KDF_NAME || self.cipher.name != CIPHER_NAME {
+            return Err(Error::InvalidArgument(format!(
+                "unsupported keystore (version {}, {} / {})",
+                self.version, self.kdf.name, self.cipher.name
+            )));
+        }
+        let pubkey = self.pubkey()?;
+        let salt       = decode_field("kdf.salt", &self.kdf.salt)?;
+        let nonce      = decode_field("cipher.nonce", &self.cipher.nonce)?;
+        let ciphertext = decode_field("ciphertext", &self.ciphertext)?;
+        if nonce.len() != NONCE_LEN {
+            return Err(Error::InvalidArgument(format!("keystore nonce must be {NONCE_LEN} bytes")));
+        }
+
+        let secret = Zeroizing::new(
+            cipher(passphrase, &salt, self.kdf.params)?
+                .decrypt(Nonce::from_slice(&nonce), Payload { msg: &ciphertext, aad: pubkey.as_ref() })
+                .map_err(|_| Error::InvalidArgument("wrong passphrase, or the keystore was modified".into()))?,
+        );
+        let keypair = Keypair::try_from(&secret[..])
+            .map_err(|e| Error::InvalidArgument(format!("keystore holds an invalid keypair: {e}")))?;
+        if keypair.pubkey() != pubkey {
+            return Err(Error::InvalidArgument("keystore keypair does not match its pubkey".into()));
+        }
+        Ok(keypair)
+    }
+
+    /// The public key, without decrypting.
+    pub fn pubkey(&self) -> Result<Pubkey> {
+        self.pubkey.parse()
+            .map_err(|_| Error::InvalidArgument(format!("invalid keystore pubkey `{}`", self.pubkey)))
+    }
+
+    /// Parse a keystore file's contents.
+    pub fn from_json(json: &str) -> Result<Self> {
+        serde_json::from_str(json).map_err(|e| Error::InvalidArgument(format!("invalid keystore: {e}")))
+    }
+
+    /// Pretty-printed JSON, ready to write to disk.
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).expect("keystore serializes")
+    }
+}
+
+fn cipher(passphrase: &str, salt: &[u8], params: ScryptParams) -> Result<Aes256GcmSiv> {
+    let mut key = Zeroizing::new([0u8; 32]);
+    scrypt(passphrase.as_bytes(), salt, params, &mut key[..])?;
+    Ok(Aes256GcmSiv::new_from_slice(&key[..]).expect("32-byte key"))
+}
+
+fn decode_field(name: &str, value: &str) -> Result<Vec<u8>> {
+    BASE64.decode(value).map_err(|e| Error::InvalidArgument(format!("keystore {name}: {e}")))
+}
+
+// ─── Loading helpers ─────────────────────────────────────────────────────────
+
+/// Whether `json` is a [`Keystore`] rather than a plain `id.json` byte array.
+pub fn is_keystore(json: &str) -> bool {
+    json.trim_start().starts_with('{')
+}
+
+/// A keypair from the contents of either a keystore or a plain `id.json`.
+/// `passphrase` is required for keystores and ignored otherwise.
+pub fn keypair_from_json(json: &str, passphrase: Option<&str>) -> Result<Keypair> {
+    if !is_keystore(json) {
+        return keypair::read_keypair(&mut json.as_bytes())
+            .map_err(|e| Error::InvalidArgument(format!("invalid keypair file: {e}")));
+    }
+    let keystore = Keystore::from_json(json)?;
+    let passphrase = passphrase.ok_or_else(|| Error::InvalidArgument(format!(
+        "keystore for {} is encrypted; supply a passphrase (e.g. via {PASSPHRASE_ENV})",
+        keystore.pubkey
+    )))?;
+    keystore.decrypt(passphrase)
+}
+
+/// Read a keystore or plain `id.json` from `path` — see [`keypair_from_json`].
+pub fn read_keypair(path: impl AsRef<Path>, passphrase: Option<&str>) -> Result<Keypair> {
+    keypair_from_json(&read(path.as_ref())?, passphrase)
+}
+
+/// The public key stored at `path`, without decrypting a keystore.
+pub fn read_pubkey(path: impl AsRef<Path>) -> Result<Pubkey> {
+    let json = read(path.as_ref())?;
+    if is_keystore(&json) {
+        Keystore::from_json(&json)?.pubkey()
+    } else {
+        keypair_from_json(&json, None).map(|k| k.pubkey())
+    }
+}
+
+/// The passphrase in [`PASSPHRASE_ENV`], if set.
+pub fn passphrase_from_env() -> Option<String> {
+    std::env::var(PASSPHRASE_ENV).ok()
+}
+
+fn read(path: &Path) -> Result<String> {
+    std::fs::read_to_string(path)
+        .map_err(|e| Error::InvalidArgument(format!("cannot read {}: {e}", path.display())))
+}
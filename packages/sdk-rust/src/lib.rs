@@ -7,7 +7,7 @@
 //! # Quick Start
 //!
 //! ```rust,no_run
-//! use a2a_swap_sdk::{A2ASwapClient, SimulateParams, SwapParams};
+//! use a2a_swap_sdk::{A2ASwapClient, ProtectionLevel, SimulateParams, SwapParams};
 //! use solana_sdk::{pubkey::Pubkey, signature::Keypair};
 //! use std::str::FromStr;
 //!
@@ -25,12 +25,17 @@
 //!     }).await?;
 //!     println!("Estimated out: {}  price_impact: {:.2}%", sim.estimated_out, sim.price_impact_pct);
 //!
-//!     // 2. Execute with 0.5% max slippage
+//!     // 2. Execute with 0.5% max slippage and a 3% price-impact cap
 //!     let result = client.convert(&keypair, SwapParams {
-//!         mint_in:          sol,
-//!         mint_out:         usdc,
-//!         amount_in:        1_000_000_000,
-//!         max_slippage_bps: 50,
+//!         mint_in:              sol,
+//!         mint_out:             usdc,
+//!         amount_in:            1_000_000_000,
+//!         max_slippage_bps:     50,
+//!         min_amount_out:       None,
+//!         max_price_impact_bps: 300,
+//!         deadline_unix:        0,
+//!         referrer:             None,
+//!         protection:           ProtectionLevel::None,
 //!     }).await?;
 //!     println!("Swapped! tx: {}", result.signature);
 //!
@@ -43,20 +48,104 @@
 //! | Method | Description |
 //! |--------|-------------|
 //! | [`A2ASwapClient::create_pool`] | Create a new pool for a mint pair |
-//! | [`A2ASwapClient::provide_liquidity`] | Deposit tokens, receive LP shares |
-//! | [`A2ASwapClient::convert`] | Atomic token swap |
+//! | [`A2ASwapClient::provide_liquidity`] | Deposit tokens, receive LP shares (optionally an LP receipt NFT) |
+//! | [`A2ASwapClient::update_position_settings`] | Turn a position's auto-compound on / off or change its threshold without depositing |
+//! | [`A2ASwapClient::close_position`] | Close an empty position and reclaim its rent |
+//! | [`A2ASwapClient::close_pool`] | Close an empty pool and its vaults (creator or admin) and reclaim their rent |
+//! | [`A2ASwapClient::set_pool_allowlist`] | Make a pool permissioned: only traders in a merkle allowlist may swap; [`A2ASwapClient::register_trader`] proves membership — see [`allowlist`] |
+//! | [`A2ASwapClient::set_pool_gate`] | Token-gate a pool: only holders of a credential mint (SBT / attestation) may swap |
+//! | [`A2ASwapClient::create_and_seed_pool`] | Create a pool and make its first deposit in one transaction, so it is never seen empty |
+//! | [`A2ASwapClient::convert`] | Atomic token swap; [`ProtectionLevel`] tranches it or sends it commit-reveal against sandwiching |
+//! | [`A2ASwapClient::convert_exact_out`] | Swap for an exact output, capped by `max_amount_in` |
 //! | [`A2ASwapClient::simulate`] | Off-chain fee + slippage breakdown |
-//! | [`A2ASwapClient::pool_info`] | Pool reserves, price, fee rate |
+//! | [`A2ASwapClient::simulate_ladder`] | The same breakdown for several sizes from one pool read |
+//! | [`A2ASwapClient::simulate_provide`] / [`simulate_remove`](A2ASwapClient::simulate_remove) | LP shares minted or tokens returned, pool share and price after |
+//! | [`A2ASwapClient::create_range_pool`] | Create a concentrated-liquidity pool |
+//! | [`A2ASwapClient::provide_range_liquidity`] | Deposit into a price range of a range pool |
+//! | [`A2ASwapClient::simulate_range`] | Tick-by-tick swap preview on a range pool |
+//! | [`A2ASwapClient::pool_info`] | Pool reserves, price, base and effective fee rate |
+//! | [`A2ASwapClient::pool_info_in`] | The same, plus LP share value, TVL in a quote token and creation slot |
 //! | [`A2ASwapClient::my_positions`] | All LP positions for an owner |
+//! | [`A2ASwapClient::my_positions_page`] | The same, filtered by pool / LP shares / auto-compound, sorted by fees or value, and paged |
 //! | [`A2ASwapClient::my_fees`] | Aggregated claimable fees |
+//! | [`A2ASwapClient::list_pools`] | Every pool with reserves and price; scans page through capped RPCs or an index endpoint — see [`ProgramScan`] |
+//! | [`A2ASwapClient::portfolio`] | Wallet + LP positions + fees valued in a quote token |
+//! | [`A2ASwapClient::propose_multisig_convert`] | Route a swap through a Squads v4 multisig vault — see [`multisig`] |
+//! | [`A2ASwapClient::solana_pay_link`] | A Solana Pay `solana:` link / QR payload for a swap, so a human signs it from a mobile wallet — see [`solana_pay`] |
+//! | [`A2ASwapClient::sign_intent`] | Sign a swap intent for a relayer to submit gaslessly; [`A2ASwapClient::relay_intent`] submits one — see [`intent`] |
+//! | [`A2ASwapClient::vault_deposit`] | Deposit into an A2A-Vault for shares in its agent-managed LP positions; [`A2ASwapClient::vault_withdraw`] redeems them — see [`vault`] |
+//! | [`A2ASwapClient::my_tier`] | An agent's protocol fee tier from its tracked volume; [`A2ASwapClient::open_agent_volume`] opts in |
+//! | [`A2ASwapClient::proposals`] | Governance proposals over the protocol fee, pool fee bounds and pause switches; [`A2ASwapClient::vote`] escrows vote tokens on one |
+//! | [`A2ASwapClient::fetch_idl`] | The program's Anchor IDL, read from its on-chain IDL account |
+//! | [`A2ASwapClient::capability_card`] | What the deployed program offers now — instructions, features, paused operations, live fees — for agent discovery |
+//! | [`A2ASwapClient::rebalance`] | Restore a target value ratio between two holdings once it drifts past a band — see [`rebalancer`] |
+//! | [`strategies::Runner`] | Run a [`strategies::Strategy`] (grid, DCA, rebalance or your own) live, as a dry run, or as a backtest, behind risk policies |
+//! | [`backtest::Backtest`] | Replay recorded pool history through a strategy — trades, fees, price impact and P&L |
+//! | [`strategies::grid`] | Grid trading: a ladder of buy / sell levels around the pool price, emulated with swaps |
+//! | [`A2ASwapClient::export_trades`] | Trades, positions and fee income as typed records with cost basis, to CSV / Parquet — see [`export`] |
+//! | [`analytics::lots::LotTracker`] | FIFO / LIFO tax lots across swaps, LP deposits / withdrawals and fee income, with realized gains per token |
+//! | [`analytics::ExecutionLog`] | Session execution quality — realized slippage, fee drag, failure rate — as JSON / CSV |
+//! | [`tokens::TokenRegistry`] | Symbol ↔ mint ↔ decimals, from the built-ins, the Solana token list or your own JSON |
+//! | [`signer::RemoteSigner`] | Sign on a Ledger (`ledger://?key=0`), Turnkey or Privy wherever a keypair would — every client method takes any `Signer` |
+//! | [`keystore::Keystore`] | Passphrase-encrypted keypair files (scrypt + AES-256-GCM-SIV); [`keystore::read_keypair`] also opens plain `id.json` |
+//! | [`cache::AccountCache`] | Slot-scoped pool / vault cache for high-frequency quoting, kept current over WebSocket with [`cache::AccountCache::watch`] |
+//! | [`fixtures::bootstrap`] | Localnet: test mints + seeded pool in one call |
+//! | [`ReadOnlyClient`] | `simulate`, `simulate_ladder`, `simulate_provide`, `simulate_remove`, `pool_info`, `pool_info_in`, `list_pools`, `proposals`, `my_tier`, `intent_nonce`, `fetch_idl`, `capability_card`, `my_positions`, `my_positions_page`, `my_fees` over plain JSON-RPC, without `solana-client` (`minimal-rpc`) |
+//!
+//! # Cargo features
+//!
+//! | Feature | Description |
+//! |---------|-------------|
+//! | `rpc` (default) | [`A2ASwapClient`] and everything built on it — [`fixtures`], [`strategies`], [`backtest`] — over `solana-client` |
+//! | `minimal-rpc` | [`ReadOnlyClient`]: quotes, pool state and positions through two JSON-RPC methods over `reqwest`; with `default-features = false` the `solana-client` tree is not built |
+//! | `metrics` | Swap / confirmation / simulate / slippage metrics via the [`metrics`](https://docs.rs/metrics) facade — see [`mod@metrics`] |
+//! | `tracing` | [`tracing`](https://docs.rs/tracing) spans on every client method (`a2a_swap.convert`, `a2a_swap.find_pool`, `a2a_swap.fetch_reserves`, `a2a_swap.sign_and_send`, …) carrying `pool` / `signature` fields |
+//! | `turnkey` | [`signer::turnkey::TurnkeySigner`]: keys held by Turnkey, requests stamped with an Ed25519 API key |
+//! | `privy` | [`signer::privy::PrivySigner`]: Privy server wallets, signed through the wallet RPC API |
 
+pub mod allowlist;
+pub mod analytics;
+#[cfg(feature = "rpc")]
+pub mod backtest;
+#[cfg(feature = "rpc")]
+pub mod cache;
+#[cfg(feature = "rpc")]
 pub mod client;
 pub mod error;
+mod error_code;
+pub mod export;
+#[cfg(feature = "rpc")]
+pub mod fixtures;
 pub mod instructions;
+pub mod intent;
+pub mod keystore;
 pub mod math;
+pub mod metrics;
+#[cfg(feature = "minimal-rpc")]
+pub mod minimal_rpc;
+pub mod multisig;
+pub mod program_error;
+pub mod range_math;
+pub mod rebalancer;
+#[cfg(any(feature = "rpc", feature = "minimal-rpc"))]
+mod reader;
+pub mod signer;
+pub mod solana_pay;
 pub mod state;
+#[cfg(feature = "rpc")]
+pub mod strategies;
+mod trace;
+pub mod tokens;
 pub mod types;
+pub mod vault;
 
-pub use client::A2ASwapClient;
-pub use error::{Error, Result};
+#[cfg(feature = "rpc")]
+pub use client::{A2ASwapClient, RpcConfig};
+pub use error::{Error, ErrorCode, Result};
+#[cfg(feature = "minimal-rpc")]
+pub use minimal_rpc::ReadOnlyClient;
+#[cfg(any(feature = "rpc", feature = "minimal-rpc"))]
+pub use reader::ProgramScan;
+pub use program_error::A2AErrorCode;
+pub use state::{CurveKind, GovernanceAction};
 pub use types::*;
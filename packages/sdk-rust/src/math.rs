@@ -1,66 +1,94 @@
 //! Fee constants and simulation math.
 //!
 //! Mirrors the on-chain arithmetic exactly so off-chain estimates match on-chain results.
+//! The arithmetic itself lives in [`a2a_swap_core::math`], shared with the
+//! HTTP API's wasm Worker; this module adapts it to the SDK's types and
+//! [`Error`].
+
+use a2a_swap_core::math as core_math;
 
 use crate::error::{Error, Result};
-use crate::state::{PoolState, PositionState};
-use crate::types::SimulateResult;
+use crate::state::{CurveKind, DynamicFee, PoolState, PositionState};
+use crate::types::{PoolInfo, SimulateProvideResult, SimulateRemoveResult, SimulateResult};
 use solana_sdk::pubkey::Pubkey;
 
+pub use a2a_swap_core::math::{
+    lp_underlying, price_impact_bps, referral_fee, spot_value, BPS_DENOMINATOR,
+    PROTOCOL_FEE_DENOMINATOR, REFERRAL_SHARE_BPS, VOLATILITY_FEE_DIVISOR,
+    VOLATILITY_HALF_LIFE_SECS,
+};
+
 // ─── Constants ────────────────────────────────────────────────────────────────
 
-/// Protocol fee numerator: 0.020% = 20 / 100_000.
-pub const PROTOCOL_FEE_BPS: u128 = 20;
-/// Protocol fee denominator.
-pub const PROTOCOL_FEE_DENOMINATOR: u128 = 100_000;
-/// Basis-point denominator for LP fee.
-pub const BPS_DENOMINATOR: u128 = 10_000;
+/// Default protocol fee numerator: 0.020% = 20 / 100_000. The live value is
+/// [`ProtocolConfigState::protocol_fee_bps`](crate::state::ProtocolConfigState)
+/// once the config is initialized on-chain.
+pub const PROTOCOL_FEE_BPS: u128 = core_math::PROTOCOL_FEE_BPS;
 
 // ─── Simulation ───────────────────────────────────────────────────────────────
 
 /// Full fee and slippage breakdown for a hypothetical swap.
 ///
 /// All inputs are pre-fetched on-chain values; no RPC calls are made here.
+/// The LP fee is the pool's [`effective_fee_bps`] as of the local clock;
+/// `protocol_fee_bps` is out of [`PROTOCOL_FEE_DENOMINATOR`].
 pub fn simulate_detailed(
-    pool_addr:   Pubkey,
-    pool:        &PoolState,
-    reserve_in:  u64,
-    reserve_out: u64,
-    amount_in:   u64,
-    a_to_b:      bool,
+    pool_addr:        Pubkey,
+    pool:             &PoolState,
+    reserve_in:       u64,
+    reserve_out:      u64,
+    amount_in:        u64,
+    protocol_fee_bps: u64,
+    a_to_b:           bool,
 ) -> Result<SimulateResult> {
-    let in_u128 = amount_in as u128;
-
-    if reserve_in == 0 || reserve_out == 0 {
-        return Err(Error::NoLiquidity);
-    }
-
-    let protocol_fee = in_u128
-        .checked_mul(PROTOCOL_FEE_BPS)
-        .ok_or(Error::MathOverflow)?
-        / PROTOCOL_FEE_DENOMINATOR;
-
-    let net_pool_input = in_u128
-        .checked_sub(protocol_fee)
-        .ok_or(Error::MathOverflow)?;
-
-    let lp_fee = net_pool_input
-        .checked_mul(pool.fee_rate_bps as u128)
-        .ok_or(Error::MathOverflow)?
-        / BPS_DENOMINATOR;
+    let fee_rate_bps = effective_fee_bps(pool, unix_now());
+    simulate_at_fee(
+        pool_addr, pool.curve, fee_rate_bps, reserve_in, reserve_out, amount_in, protocol_fee_bps, a_to_b,
+    )
+}
 
-    let after_fees = net_pool_input
-        .checked_sub(lp_fee)
-        .ok_or(Error::MathOverflow)?;
+/// [`simulate_detailed`] against a [`PoolInfo`] snapshot, at the LP fee the
+/// snapshot recorded rather than the local clock's — for quoting recorded
+/// pool history.
+pub fn simulate_snapshot(info: &PoolInfo, amount_in: u64, a_to_b: bool) -> Result<SimulateResult> {
+    let (reserve_in, reserve_out) = if a_to_b {
+        (info.reserve_a, info.reserve_b)
+    } else {
+        (info.reserve_b, info.reserve_a)
+    };
+    simulate_at_fee(
+        info.pool, info.curve, info.effective_fee_bps, reserve_in, reserve_out, amount_in,
+        info.protocol_fee_bps as u64, a_to_b,
+    )
+}
 
-    let r_in  = reserve_in  as u128;
-    let r_out = reserve_out as u128;
+/// Move `info`'s reserves as the swap `sim` quoted against it would: the
+/// protocol fee leaves the pool, the LP fee stays in the input vault.
+#[cfg(feature = "rpc")]
+pub(crate) fn apply_simulated_swap(info: &mut PoolInfo, sim: &SimulateResult) -> Result<()> {
+    let reserve_in  = sim.reserve_in.checked_add(sim.net_pool_input).ok_or(Error::MathOverflow)?;
+    let reserve_out = sim.reserve_out.checked_sub(sim.estimated_out).ok_or(Error::MathOverflow)?;
+    let (reserve_a, reserve_b) =
+        if sim.a_to_b { (reserve_in, reserve_out) } else { (reserve_out, reserve_in) };
+    set_reserves(info, reserve_a, reserve_b);
+    Ok(())
+}
 
-    let estimated_out = r_out
-        .checked_mul(after_fees)
-        .ok_or(Error::MathOverflow)?
-        .checked_div(r_in.checked_add(after_fees).ok_or(Error::MathOverflow)?)
-        .ok_or(Error::MathOverflow)? as u64;
+#[allow(clippy::too_many_arguments)]
+fn simulate_at_fee(
+    pool_addr:        Pubkey,
+    curve:            CurveKind,
+    fee_rate_bps:     u16,
+    reserve_in:       u64,
+    reserve_out:      u64,
+    amount_in:        u64,
+    protocol_fee_bps: u64,
+    a_to_b:           bool,
+) -> Result<SimulateResult> {
+    let quote = core_math::quote_swap(
+        curve.into(), fee_rate_bps, reserve_in, reserve_out, amount_in, protocol_fee_bps,
+    )?;
+    let estimated_out = quote.amount_out;
 
     let effective_rate = if amount_in == 0 {
         0.0
@@ -68,26 +96,279 @@ pub fn simulate_detailed(
         estimated_out as f64 / amount_in as f64
     };
 
-    let price_impact_pct =
-        after_fees as f64 / (r_in as f64 + after_fees as f64) * 100.0;
-
     Ok(SimulateResult {
         pool: pool_addr,
         a_to_b,
         amount_in,
-        protocol_fee:    protocol_fee as u64,
-        net_pool_input:  net_pool_input as u64,
-        lp_fee:          lp_fee as u64,
-        after_fees:      after_fees as u64,
+        protocol_fee:    quote.protocol_fee,
+        net_pool_input:  quote.net_pool_input,
+        lp_fee:          quote.lp_fee,
+        after_fees:      quote.after_fees,
         estimated_out,
         effective_rate,
-        price_impact_pct,
-        fee_rate_bps:    pool.fee_rate_bps,
+        price_impact_pct: quote.price_impact_pct,
+        fee_rate_bps,
         reserve_in,
         reserve_out,
     })
 }
 
+// ─── Liquidity ────────────────────────────────────────────────────────────────
+
+/// LP shares minted for depositing `amount_a` / `amount_b` into a pool with
+/// `lp_supply` shares over `reserve_a` / `reserve_b` — `isqrt(a × b)` for the
+/// first deposit, otherwise the smaller ratio against the reserves.
+pub fn lp_shares_for_deposit(
+    amount_a:  u64,
+    amount_b:  u64,
+    lp_supply: u64,
+    reserve_a: u64,
+    reserve_b: u64,
+) -> Result<u64> {
+    Ok(core_math::lp_shares_for_deposit(amount_a, amount_b, lp_supply, reserve_a, reserve_b)?)
+}
+
+/// The other side of a deposit of `amount` against `reserve_from`, rounded
+/// up as `provide_liquidity_proportional` sizes it on-chain.
+pub fn proportional_amount(amount: u64, reserve_from: u64, reserve_to: u64) -> Result<u64> {
+    Ok(core_math::proportional_amount(amount, reserve_from, reserve_to)?)
+}
+
+/// Expected outcome of depositing `amount_a` / `amount_b` (pool ordering)
+/// into `pool` at reserves `reserve_a` / `reserve_b`: the LP shares minted,
+/// their share of the pool and the spot price either side.
+pub fn simulate_provide_detailed(
+    pool_addr: Pubkey,
+    pool:      &PoolState,
+    reserve_a: u64,
+    reserve_b: u64,
+    amount_a:  u64,
+    amount_b:  u64,
+) -> Result<SimulateProvideResult> {
+    let lp_minted = lp_shares_for_deposit(amount_a, amount_b, pool.lp_supply, reserve_a, reserve_b)?;
+    let lp_supply_after = pool.lp_supply.checked_add(lp_minted).ok_or(Error::MathOverflow)?;
+    let reserve_a_after = reserve_a.checked_add(amount_a).ok_or(Error::MathOverflow)?;
+    let reserve_b_after = reserve_b.checked_add(amount_b).ok_or(Error::MathOverflow)?;
+    Ok(SimulateProvideResult {
+        pool: pool_addr,
+        amount_a,
+        amount_b,
+        lp_minted,
+        lp_supply_after,
+        pool_share_pct: share_pct(lp_minted, lp_supply_after),
+        price_before:   spot_price(pool.curve, reserve_a, reserve_b),
+        price_after:    spot_price(pool.curve, reserve_a_after, reserve_b_after),
+    })
+}
+
+/// Expected outcome of burning `lp_shares` of `pool` at reserves
+/// `reserve_a` / `reserve_b`: the tokens returned, floored like the on-chain
+/// `remove_liquidity`, and the spot price either side.
+pub fn simulate_remove_detailed(
+    pool_addr: Pubkey,
+    pool:      &PoolState,
+    reserve_a: u64,
+    reserve_b: u64,
+    lp_shares: u64,
+) -> Result<SimulateRemoveResult> {
+    if lp_shares == 0 || lp_shares > pool.lp_supply {
+        return Err(Error::InvalidArgument(format!(
+            "lp_shares must be between 1 and the pool's LP supply ({})", pool.lp_supply,
+        )));
+    }
+    let (amount_a, amount_b) = lp_underlying(lp_shares, pool.lp_supply, reserve_a, reserve_b);
+    Ok(SimulateRemoveResult {
+        pool: pool_addr,
+        lp_shares,
+        amount_a,
+        amount_b,
+        lp_supply_after: pool.lp_supply - lp_shares,
+        pool_share_pct:  share_pct(lp_shares, pool.lp_supply),
+        price_before:    spot_price(pool.curve, reserve_a, reserve_b),
+        price_after:     spot_price(pool.curve, reserve_a - amount_a, reserve_b - amount_b),
+    })
+}
+
+/// Proportional `amount_b` for a deposit of `amount_a`, or `amount_b` itself
+/// when given. Errors with [`Error::AmountBRequired`] for an empty pool,
+/// which needs both amounts to set its price.
+#[cfg(any(feature = "rpc", feature = "minimal-rpc"))]
+pub(crate) fn compute_amount_b(
+    amount_a:  u64,
+    amount_b:  Option<u64>,
+    reserve_a: u64,
+    reserve_b: u64,
+    lp_supply: u64,
+) -> Result<u64> {
+    if let Some(b) = amount_b {
+        return Ok(b);
+    }
+    if lp_supply == 0 {
+        return Err(Error::AmountBRequired);
+    }
+    if reserve_a == 0 {
+        return Err(Error::NoLiquidity);
+    }
+    let b = (amount_a as u128)
+        .checked_mul(reserve_b as u128)
+        .ok_or(Error::MathOverflow)?
+        .div_ceil(reserve_a as u128);
+    if b == 0 {
+        return Err(Error::AmountBZero);
+    }
+    u64::try_from(b).map_err(|_| Error::MathOverflow)
+}
+
+/// `part` as a percentage of `whole`; `0.0` when `whole` is zero.
+fn share_pct(part: u64, whole: u64) -> f64 {
+    if whole == 0 { 0.0 } else { part as f64 / whole as f64 * 100.0 }
+}
+
+// ─── Dynamic fees ─────────────────────────────────────────────────────────────
+
+/// `volatility_bps` decayed to `now`: halved once per elapsed half-life.
+pub fn decayed_volatility(df: &DynamicFee, now: i64) -> u32 {
+    core_math::decayed_volatility(&(*df).into(), now)
+}
+
+/// LP fee (bps) a swap at unix time `now` pays — the on-chain
+/// `effective_fee_bps`. Equals `fee_rate_bps` unless dynamic fees are on,
+/// in which case a volatility premium is added and the result clamped to
+/// `[min_fee_bps, max_fee_bps]`.
+pub fn effective_fee_bps(pool: &PoolState, now: i64) -> u16 {
+    core_math::dynamic_fee_bps(pool.fee_rate_bps, &pool.dynamic_fee.into(), now)
+}
+
+pub(crate) fn unix_now() -> i64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs() as i64)
+}
+
+/// Pure price impact in basis points for any curve, rounded down — the value
+/// the on-chain `compute_swap` checks against `max_price_impact_bps`.
+pub fn curve_price_impact_bps(
+    curve:       CurveKind,
+    after_fees:  u64,
+    reserve_in:  u64,
+    reserve_out: u64,
+) -> Result<u64> {
+    Ok(core_math::curve_price_impact_bps(curve.into(), after_fees, reserve_in, reserve_out)?)
+}
+
+// ─── StableSwap ───────────────────────────────────────────────────────────────
+//
+// Two-coin Curve invariant with Ann = A · nⁿ = 4A:
+//   Ann·(x + y) + D = Ann·D + D³ / (4·x·y)
+// Mirrors programs/a2a-swap/src/instructions/fee_math.rs step for step.
+
+/// StableSwap invariant D for reserves `x`, `y`.
+pub fn stable_invariant(x: u128, y: u128, amp: u64) -> Result<u128> {
+    Ok(core_math::stable_invariant(x, y, amp)?)
+}
+
+/// The other StableSwap reserve once one side is `x_new`, holding D fixed.
+pub fn stable_y(x_new: u128, d: u128, amp: u64) -> Result<u128> {
+    Ok(core_math::stable_y(x_new, d, amp)?)
+}
+
+/// StableSwap `(amount_out, price_impact_bps)` for `after_fees` entering the pool.
+///
+/// One unit is held back from the output, as on-chain. Impact is the
+/// shortfall against `after_fees` at the pre-trade marginal price.
+pub fn stable_swap_out(
+    after_fees:  u128,
+    reserve_in:  u128,
+    reserve_out: u128,
+    amp:         u64,
+) -> Result<(u64, u64)> {
+    Ok(core_math::stable_swap_out(after_fees, reserve_in, reserve_out, amp)?)
+}
+
+/// Marginal price of token A in token B (raw atomic units) for any curve.
+/// `0.0` when either reserve is empty.
+pub fn spot_price(curve: CurveKind, reserve_a: u64, reserve_b: u64) -> f64 {
+    core_math::spot_price(curve.into(), reserve_a, reserve_b)
+}
+
+/// Reserve behind one LP share (raw atomic units); `0.0` with no supply.
+pub fn lp_share_value(reserve: u64, lp_supply: u64) -> f64 {
+    if lp_supply == 0 { 0.0 } else { reserve as f64 / lp_supply as f64 }
+}
+
+/// Move `info` to new reserves, with the spot price and share values that follow.
+pub(crate) fn set_reserves(info: &mut PoolInfo, reserve_a: u64, reserve_b: u64) {
+    (info.reserve_a, info.reserve_b) = (reserve_a, reserve_b);
+    info.spot_price       = spot_price(info.curve, reserve_a, reserve_b);
+    info.lp_share_value_a = lp_share_value(reserve_a, info.lp_supply);
+    info.lp_share_value_b = lp_share_value(reserve_b, info.lp_supply);
+}
+
+// ─── Reverse simulation ───────────────────────────────────────────────────────
+
+/// Smallest `amount_in` whose swap output is at least `amount_out` on a
+/// constant-product pool.
+///
+/// Reverses both fee legs of [`simulate_detailed`] with round-up semantics, so
+/// `simulate_detailed(amount_in_for_exact_out(..)?).estimated_out >= amount_out`
+/// always holds and one unit less would fall short.  Each step inverts a
+/// floor-rounded forward step exactly:
+///
+/// ```text
+/// after_fees     = ceil(amount_out × reserve_in / (reserve_out − amount_out))
+/// net_pool_input = floor((after_fees − 1) × 10_000 / (10_000 − fee_rate_bps)) + 1
+/// amount_in      = floor((net_pool_input − 1) × 100_000 / (100_000 − protocol_fee_bps)) + 1
+/// ```
+///
+/// All intermediates are `u128`; returns [`Error::MathOverflow`] when the
+/// required input does not fit in a `u64`.
+pub fn amount_in_for_exact_out(
+    reserve_in:       u64,
+    reserve_out:      u64,
+    fee_rate_bps:     u16,
+    protocol_fee_bps: u64,
+    amount_out:       u64,
+) -> Result<u64> {
+    curve_amount_in_for_exact_out(
+        CurveKind::ConstantProduct, reserve_in, reserve_out, fee_rate_bps, protocol_fee_bps, amount_out,
+    )
+}
+
+/// [`amount_in_for_exact_out`] for any curve — the input the on-chain
+/// `swap_exact_out` charges. On a StableSwap pool the result always covers
+/// `amount_out` but may exceed the smallest sufficient input by Newton
+/// rounding.
+pub fn curve_amount_in_for_exact_out(
+    curve:            CurveKind,
+    reserve_in:       u64,
+    reserve_out:      u64,
+    fee_rate_bps:     u16,
+    protocol_fee_bps: u64,
+    amount_out:       u64,
+) -> Result<u64> {
+    if reserve_in == 0 || reserve_out == 0 {
+        return Err(Error::NoLiquidity);
+    }
+    if amount_out >= reserve_out {
+        return Err(Error::InvalidArgument(format!(
+            "amount_out {amount_out} must be below reserve_out {reserve_out}"
+        )));
+    }
+    if fee_rate_bps as u128 >= BPS_DENOMINATOR {
+        return Err(Error::InvalidArgument(format!(
+            "fee_rate_bps {fee_rate_bps} must be below {BPS_DENOMINATOR}"
+        )));
+    }
+    if protocol_fee_bps as u128 >= PROTOCOL_FEE_DENOMINATOR {
+        return Err(Error::InvalidArgument(format!(
+            "protocol_fee_bps {protocol_fee_bps} must be below {PROTOCOL_FEE_DENOMINATOR}"
+        )));
+    }
+    Ok(core_math::curve_amount_in_for_exact_out(
+        curve.into(), reserve_in, reserve_out, fee_rate_bps, protocol_fee_bps, amount_out,
+    )?)
+}
+
 // ─── Pending fees ─────────────────────────────────────────────────────────────
 
 /// Compute `(pending_a, pending_b)` accrued since the position was last synced.
@@ -95,14 +376,137 @@ pub fn simulate_detailed(
 /// Mirrors the on-chain `accrue_fees` function:
 /// `pending = lp_shares × (fee_growth_global − checkpoint) >> 64`
 pub fn pending_fees_for_position(pos: &PositionState, pool: &PoolState) -> (u64, u64) {
-    let delta_a = pool
-        .fee_growth_global_a
-        .saturating_sub(pos.fee_growth_checkpoint_a);
-    let delta_b = pool
-        .fee_growth_global_b
-        .saturating_sub(pos.fee_growth_checkpoint_b);
-
-    let pending_a = ((pos.lp_shares as u128).saturating_mul(delta_a) >> 64) as u64;
-    let pending_b = ((pos.lp_shares as u128).saturating_mul(delta_b) >> 64) as u64;
-    (pending_a, pending_b)
+    (
+        core_math::pending_fee(pos.lp_shares, pos.fee_growth_checkpoint_a, pool.fee_growth_global_a),
+        core_math::pending_fee(pos.lp_shares, pos.fee_growth_checkpoint_b, pool.fee_growth_global_b),
+    )
+}
+
+// ─── Impermanent loss ─────────────────────────────────────────────────────────
+
+/// Impermanent loss of a constant-product LP position after the pool price
+/// moves from `entry_price` to `current_price` (same units, e.g. B per A).
+///
+/// `2·√r / (1 + r) − 1` with `r = current / entry`: the position's value
+/// relative to simply holding the deposit, minus one, fees excluded. Always
+/// `≤ 0` — `-0.0572` means 5.72 % less than holding (a 2× price move).
+/// `0.0` when either price is not positive.
+pub fn impermanent_loss(entry_price: f64, current_price: f64) -> f64 {
+    if entry_price <= 0.0 || current_price <= 0.0 {
+        return 0.0;
+    }
+    let r = current_price / entry_price;
+    2.0 * r.sqrt() / (1.0 + r) - 1.0
+}
+
+// ─── Slippage ─────────────────────────────────────────────────────────────────
+
+/// Minimum output allowing at most `max_slippage_bps` below `estimated_out`:
+/// `estimated_out − ⌊estimated_out × max_slippage_bps / 10_000⌋`, so the
+/// minimum rounds up and the guard never tolerates more than stated.
+/// `0` bps disables the guard (returns `0`); above 10_000 is rejected.
+pub fn min_amount_out_for_slippage(estimated_out: u64, max_slippage_bps: u16) -> Result<u64> {
+    if max_slippage_bps as u128 > BPS_DENOMINATOR {
+        return Err(Error::InvalidArgument(format!(
+            "max_slippage_bps must be at most 10000, got {max_slippage_bps}"
+        )));
+    }
+    if max_slippage_bps == 0 {
+        return Ok(0);
+    }
+    let slack = estimated_out as u128 * max_slippage_bps as u128 / BPS_DENOMINATOR;
+    Ok(estimated_out - slack as u64)
+}
+
+/// The `min_amount_out` a swap submits: the caller's explicit
+/// `min_amount_out`, or else one derived from `max_slippage_bps` — setting
+/// both is rejected. An explicit minimum above `estimated_out` fails with
+/// [`Error::SlippageExceeded`], since the swap would revert on-chain anyway.
+pub fn resolve_min_amount_out(
+    estimated_out:    u64,
+    max_slippage_bps: u16,
+    min_amount_out:   Option<u64>,
+) -> Result<u64> {
+    match min_amount_out {
+        Some(_) if max_slippage_bps != 0 => Err(Error::InvalidArgument(
+            "set either min_amount_out or max_slippage_bps, not both".into(),
+        )),
+        Some(min) if estimated_out < min => {
+            Err(Error::SlippageExceeded { estimated: estimated_out, min })
+        }
+        Some(min) => Ok(min),
+        None => min_amount_out_for_slippage(estimated_out, max_slippage_bps),
+    }
+}
+
+// ─── Balance pre-flight ───────────────────────────────────────────────────────
+
+/// Base fee the cluster charges per transaction signature, in lamports.
+pub const LAMPORTS_PER_SIGNATURE: u64 = 5_000;
+
+/// Check that an agent can pay for a swap before it is sent, instead of
+/// letting the cluster reject it with an opaque token or system error.
+///
+/// `wallet` holds `lamports` and must cover `lamports_needed`: the network
+/// fee, the rent of accounts the transaction creates and, for SOL input, the
+/// lamports it wraps. `token_in` is the agent's input token account with its
+/// balance (`None` while the account does not exist), which must hold
+/// `amount_in`; pass `None` for SOL input. Fails with
+/// [`Error::InsufficientBalance`] for the first account that falls short.
+pub fn check_swap_funds(
+    wallet:          &Pubkey,
+    lamports:        u64,
+    lamports_needed: u64,
+    token_in:        Option<(&Pubkey, Option<u64>)>,
+    amount_in:       u64,
+) -> Result<()> {
+    if let Some((account, balance)) = token_in {
+        let available = balance.unwrap_or(0);
+        if balance.is_none() || available < amount_in {
+            return Err(Error::InsufficientBalance { account: *account, needed: amount_in, available });
+        }
+    }
+    if lamports < lamports_needed {
+        return Err(Error::InsufficientBalance { account: *wallet, needed: lamports_needed, available: lamports });
+    }
+    Ok(())
+}
+
+// ─── Swap protection ──────────────────────────────────────────────────────────
+
+/// Split `amount_in` into at most `tranches` non-zero parts that sum to it,
+/// each within roughly ±25 % of an even share. The sizes are drawn from
+/// `seed`, so equal seeds give equal splits; the client seeds it randomly
+/// per swap so tranche sizes cannot be predicted from the total.
+pub fn split_tranches(amount_in: u64, tranches: u64, seed: u64) -> Vec<u64> {
+    let n = tranches.clamp(1, amount_in.max(1));
+    let mut state = seed | 1;
+    let weights: Vec<u128> = (0..n)
+        .map(|_| {
+            // xorshift64
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            75 + (state % 51) as u128
+        })
+        .collect();
+    let total: u128 = weights.iter().sum();
+
+    let mut parts: Vec<u64> = weights[..weights.len() - 1]
+        .iter()
+        .map(|w| (amount_in as u128 * w / total) as u64)
+        .collect();
+    parts.push(amount_in - parts.iter().sum::<u64>());
+    parts.retain(|&p| p > 0);
+    parts
+}
+
+/// A tranche's share of a whole swap's explicit `min_amount_out`:
+/// `⌈min_amount_out × tranche_in / amount_in⌉`, so the tranches' minimums
+/// together never fall below the caller's.
+pub fn tranche_min_amount_out(min_amount_out: u64, tranche_in: u64, amount_in: u64) -> u64 {
+    if amount_in == 0 {
+        return min_amount_out;
+    }
+    (min_amount_out as u128 * tranche_in as u128).div_ceil(amount_in as u128) as u64
 }
@@ -0,0 +1,96 @@
+//! Client metrics (`metrics` feature).
+//!
+//! With the feature enabled, [`A2ASwapClient`](crate::A2ASwapClient) records
+//! through the [`metrics`](https://docs.rs/metrics) facade. Install any
+//! recorder — e.g. `metrics-exporter-prometheus` — to scrape them; with no
+//! recorder installed the calls are no-ops. Without the feature the hooks
+//! compile away entirely.
+//!
+//! | Metric | Type | Labels |
+//! |--------|------|--------|
+//! | `a2a_swap_swaps_submitted_total` | counter | `status` = `confirmed` \| `failed` |
+//! | `a2a_swap_confirmation_seconds` | histogram | `op` = `swap` \| `create_pool` \| `provide_liquidity` \| `fixtures`, `status` |
+//! | `a2a_swap_simulate_seconds` | histogram | — |
+//! | `a2a_swap_slippage_realized_bps` | histogram | — |
+//! | `a2a_swap_cache_lookups_total` | counter | `result` = `hit` \| `miss` |
+//!
+//! Realized slippage is the [`SwapResult::realized_slippage_bps`](crate::SwapResult::realized_slippage_bps)
+//! the client reads back from every confirmed swap; swaps whose fill could
+//! not be read are not recorded.
+
+#![cfg_attr(not(feature = "rpc"), allow(dead_code))]
+
+use std::time::Duration;
+
+/// Swap transactions sent by [`convert`](crate::A2ASwapClient::convert).
+pub const SWAPS_SUBMITTED: &str = "a2a_swap_swaps_submitted_total";
+/// Send-to-confirmation time for every transaction the client signs.
+pub const CONFIRMATION_SECONDS: &str = "a2a_swap_confirmation_seconds";
+/// Wall time of [`simulate`](crate::A2ASwapClient::simulate), RPC included.
+pub const SIMULATE_SECONDS: &str = "a2a_swap_simulate_seconds";
+/// Realized output shortfall vs. the pre-trade estimate, in basis points.
+pub const SLIPPAGE_REALIZED_BPS: &str = "a2a_swap_slippage_realized_bps";
+/// Account reads answered by / missed in an [`AccountCache`](crate::cache::AccountCache).
+pub const CACHE_LOOKUPS: &str = "a2a_swap_cache_lookups_total";
+
+/// Shortfall of `realized` below `estimated` in basis points.
+///
+/// Positive means the swap returned less than simulated (reserves moved
+/// against the agent); negative means it returned more.
+pub fn slippage_bps(estimated: u64, realized: u64) -> f64 {
+    if estimated == 0 {
+        return 0.0;
+    }
+    (estimated as f64 - realized as f64) * 10_000.0 / estimated as f64
+}
+
+#[cfg(feature = "metrics")]
+fn status(ok: bool) -> &'static str {
+    if ok { "confirmed" } else { "failed" }
+}
+
+// ─── Hooks called by the client ───────────────────────────────────────────────
+
+#[cfg(feature = "metrics")]
+pub(crate) fn swap_submitted(ok: bool) {
+    metrics::counter!(SWAPS_SUBMITTED, "status" => status(ok)).increment(1);
+}
+
+#[cfg(feature = "metrics")]
+pub(crate) fn confirmation(op: &'static str, elapsed: Duration, ok: bool) {
+    metrics::histogram!(CONFIRMATION_SECONDS, "op" => op, "status" => status(ok))
+        .record(elapsed.as_secs_f64());
+}
+
+#[cfg(feature = "metrics")]
+pub(crate) fn simulate(elapsed: Duration) {
+    metrics::histogram!(SIMULATE_SECONDS).record(elapsed.as_secs_f64());
+}
+
+/// Record the realized slippage of a confirmed swap.
+#[cfg(feature = "metrics")]
+pub(crate) fn slippage(bps: f64) {
+    metrics::histogram!(SLIPPAGE_REALIZED_BPS).record(bps);
+}
+
+/// Record `hits` and `misses` of one cached multi-account read.
+#[cfg(feature = "metrics")]
+pub(crate) fn cache(hits: usize, misses: usize) {
+    metrics::counter!(CACHE_LOOKUPS, "result" => "hit").increment(hits as u64);
+    metrics::counter!(CACHE_LOOKUPS, "result" => "miss").increment(misses as u64);
+}
+
+#[cfg(not(feature = "metrics"))]
+pub(crate) fn swap_submitted(_ok: bool) {}
+
+#[cfg(not(feature = "metrics"))]
+pub(crate) fn confirmation(_op: &'static str, _elapsed: Duration, _ok: bool) {}
+
+#[cfg(not(feature = "metrics"))]
+pub(crate) fn simulate(_elapsed: Duration) {}
+
+#[cfg(not(feature = "metrics"))]
+pub(crate) fn slippage(_bps: f64) {}
+
+#[cfg(not(feature = "metrics"))]
+pub(crate) fn cache(_hits: usize, _misses: usize) {}
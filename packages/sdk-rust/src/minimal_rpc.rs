@@ -0,0 +1,357 @@
+//! Read-only client over plain HTTP JSON-RPC (`minimal-rpc` feature).
+//!
+//! [`ReadOnlyClient`] quotes swaps and reads pools and positions with the
+//! same results as [`A2ASwapClient`](crate::A2ASwapClient), but speaks to the
+//! node through two JSON-RPC methods (`getMultipleAccounts`,
+//! `getProgramAccounts`, or `getProgramAccountsV2` with
+//! [`ProgramScan::Cursor`]) over `reqwest`. Build with
+//! `default-features = false, features = ["minimal-rpc"]` to drop
+//! `solana-client` and its dependency tree entirely.
+//!
+//! ```rust,no_run
+//! # use a2a_swap_sdk::{ReadOnlyClient, SimulateParams};
+//! # use solana_sdk::pubkey::Pubkey;
+//! # use std::str::FromStr;
+//! # #[tokio::main]
+//! # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! let client = ReadOnlyClient::devnet();
+//! let sol  = Pubkey::from_str("So11111111111111111111111111111111111111112")?;
+//! let usdc = Pubkey::from_str("EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v")?;
+//! let sim  = client.simulate(SimulateParams {
+//!     mint_in: sol, mint_out: usdc, amount_in: 1_000_000_000,
+//! }).await?;
+//! println!("Estimated out: {}", sim.estimated_out);
+//! # Ok(())
+//! # }
+//! ```
+
+use std::str::FromStr;
+use std::time::Instant;
+
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine as _};
+use serde_json::{json, Value};
+use solana_sdk::pubkey::Pubkey;
+
+use crate::{
+    error::{Error, Result},
+    metrics,
+    reader::{
+        self, accounts_page_params, memcmp_filters, parse_accounts_page, AccountReader, AccountsPage, ProgramScan,
+        DEFAULT_PROGRAM_ID, DEVNET_RPC, LOCALNET_RPC, MAINNET_RPC,
+    },
+    types::{
+        CapabilityCard, FeeSummary, PoolInfo, PositionInfo, PositionPage, PositionQuery, ProposalInfo, ProvideParams, SimulateParams,
+        SimulateProvideResult, SimulateRemoveResult, SimulateResult, TierInfo,
+    },
+};
+
+/// Decode a `getProgramAccounts` result into `(address, data)` pairs.
+fn program_account_list(method: &str, result: &Value) -> Result<Vec<(Pubkey, Vec<u8>)>> {
+    result
+        .as_array()
+        .ok_or_else(|| Error::JsonRpc(format!("{method}: no result")))?
+        .iter()
+        .map(|entry| {
+            let key = entry["pubkey"]
+                .as_str()
+                .and_then(|k| Pubkey::from_str(k).ok())
+                .ok_or_else(|| Error::JsonRpc(format!("{method}: bad pubkey")))?;
+            Ok((key, account_data(&entry["account"])?.unwrap_or_default()))
+        })
+        .collect()
+}
+
+/// Minimal Solana JSON-RPC transport: account reads only, `confirmed`
+/// commitment, base64 account data.
+struct MinimalRpc {
+    http: reqwest::Client,
+    url:  String,
+}
+
+impl MinimalRpc {
+    async fn call(&self, method: &str, params: Value) -> Result<Value> {
+        let body = json!({ "jsonrpc": "2.0", "id": 1, "method": method, "params": params });
+        let failed = |e: &dyn std::fmt::Display| Error::JsonRpc(format!("{method}: {e}"));
+        let text = self.http
+            .post(&self.url)
+            .header("Content-Type", "application/json")
+            .body(body.to_string())
+            .send()
+            .await
+            .and_then(|r| r.error_for_status())
+            .map_err(|e| failed(&e))?
+            .text()
+            .await
+            .map_err(|e| failed(&e))?;
+        let reply: Value = serde_json::from_str(&text).map_err(|e| failed(&e))?;
+        if let Some(err) = reply.get("error") {
+            return Err(Error::JsonRpc(format!(
+                "{method}: {}",
+                err["message"].as_str().unwrap_or("unknown error")
+            )));
+        }
+        Ok(reply["result"].clone())
+    }
+}
+
+/// Data of a `UiAccount` in base64 encoding; `None` for `null`.
+fn account_data(account: &Value) -> Result<Option<Vec<u8>>> {
+    if account.is_null() {
+        return Ok(None);
+    }
+    account["data"][0]
+        .as_str()
+        .and_then(|d| BASE64.decode(d).ok())
+        .map(Some)
+        .ok_or_else(|| Error::JsonRpc("account data is not base64".into()))
+}
+
+impl AccountReader for MinimalRpc {
+    async fn multiple_account_data(&self, keys: &[Pubkey]) -> Result<Vec<Option<Vec<u8>>>> {
+        let keys: Vec<String> = keys.iter().map(Pubkey::to_string).collect();
+        let result = self.call("getMultipleAccounts", json!([
+            keys,
+            { "encoding": "base64", "commitment": "confirmed" },
+        ])).await?;
+        result["value"]
+            .as_array()
+            .ok_or_else(|| Error::JsonRpc("getMultipleAccounts: no value".into()))?
+            .iter()
+            .map(account_data)
+            .collect()
+    }
+
+    async fn program_accounts(&self, program: &Pubkey, memcmp: &[(usize, &[u8])]) -> Result<Vec<(Pubkey, Vec<u8>)>> {
+        let result = self.call("getProgramAccounts", json!([
+            program.to_string(),
+            { "encoding": "base64", "commitment": "confirmed", "filters": memcmp_filters(memcmp) },
+        ])).await?;
+        program_account_list("getProgramAccounts", &result)
+    }
+
+    async fn program_keys(&self, program: &Pubkey, memcmp: &[(usize, &[u8])]) -> Result<Vec<Pubkey>> {
+        let result = self.call("getProgramAccounts", json!([
+            program.to_string(),
+            {
+                "encoding": "base64", "commitment": "confirmed", "filters": memcmp_filters(memcmp),
+                "dataSlice": { "offset": 0, "length": 0 },
+            },
+        ])).await?;
+        Ok(program_account_list("getProgramAccounts", &result)?.into_iter().map(|(key, _)| key).collect())
+    }
+
+    async fn program_accounts_page(
+        &self,
+        program: &Pubkey,
+        memcmp:  &[(usize, &[u8])],
+        limit:   usize,
+        cursor:  Option<String>,
+    ) -> Result<AccountsPage> {
+        let result = self.call("getProgramAccountsV2", accounts_page_params(program, memcmp, limit, cursor)).await?;
+        parse_accounts_page(&result).ok_or_else(|| Error::JsonRpc("getProgramAccountsV2: malformed page".into()))
+    }
+}
+
+// ─── Client ───────────────────────────────────────────────────────────────────
+
+/// Read-only A2A-Swap client: quotes, pool state and LP positions, no signing.
+pub struct ReadOnlyClient {
+    rpc:        MinimalRpc,
+    /// Endpoint for program-account scans, if not `rpc`.
+    index:      Option<MinimalRpc>,
+    scan:       ProgramScan,
+    program_id: Pubkey,
+}
+
+impl ReadOnlyClient {
+    /// Connect to any Solana RPC endpoint.
+    pub fn new(rpc_url: impl Into<String>) -> Self {
+        Self {
+            rpc:        MinimalRpc { http: reqwest::Client::new(), url: rpc_url.into() },
+            index:      None,
+            scan:       ProgramScan::default(),
+            program_id: Pubkey::from_str(DEFAULT_PROGRAM_ID).unwrap(),
+        }
+    }
+
+    /// Convenience constructor for Solana devnet.
+    pub fn devnet() -> Self {
+        Self::new(DEVNET_RPC)
+    }
+
+    /// Convenience constructor for Solana mainnet-beta.
+    pub fn mainnet() -> Self {
+        Self::new(MAINNET_RPC)
+    }
+
+    /// Convenience constructor for a local test validator.
+    pub fn localnet() -> Self {
+        Self::new(LOCALNET_RPC)
+    }
+
+    /// Override the program ID (e.g. for a local deployment).
+    pub fn with_program_id(mut self, program_id: Pubkey) -> Self {
+        self.program_id = program_id;
+        self
+    }
+
+    /// How positions and pools are scanned — see [`ProgramScan`].
+    pub fn with_program_scan(mut self, scan: ProgramScan) -> Self {
+        self.scan = scan;
+        self
+    }
+
+    /// Send program-account scans to `index_url` (a private node or a
+    /// self-hosted indexer speaking the same JSON-RPC methods); everything
+    /// else stays on the main endpoint.
+    pub fn with_index_url(mut self, index_url: impl Into<String>) -> Self {
+        self.index = Some(MinimalRpc { http: self.rpc.http.clone(), url: index_url.into() });
+        self
+    }
+
+    /// Off-chain quote — see [`A2ASwapClient::simulate`](crate::A2ASwapClient::simulate).
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        name = "a2a_swap.simulate", skip_all, err,
+        fields(mint_in = %params.mint_in, mint_out = %params.mint_out, amount_in = params.amount_in,
+               pool = tracing::field::Empty),
+    ))]
+    pub async fn simulate(&self, params: SimulateParams) -> Result<SimulateResult> {
+        let started = Instant::now();
+        let result  = reader::simulate(&self.rpc, &self.program_id, params).await;
+        metrics::simulate(started.elapsed());
+        result
+    }
+
+    /// Quotes for several input sizes against one read of the pool, in order.
+    pub async fn simulate_ladder(
+        &self,
+        mint_in:  Pubkey,
+        mint_out: Pubkey,
+        amounts:  &[u64],
+    ) -> Result<Vec<SimulateResult>> {
+        let started = Instant::now();
+        let result  = reader::simulate_ladder(&self.rpc, &self.program_id, &mint_in, &mint_out, amounts).await;
+        metrics::simulate(started.elapsed());
+        result
+    }
+
+    /// Deposit preview — see [`A2ASwapClient::simulate_provide`](crate::A2ASwapClient::simulate_provide).
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        name = "a2a_swap.simulate_provide", skip_all, err,
+        fields(mint_a = %params.mint_a, mint_b = %params.mint_b, amount_a = params.amount_a,
+               pool = tracing::field::Empty),
+    ))]
+    pub async fn simulate_provide(&self, params: &ProvideParams) -> Result<SimulateProvideResult> {
+        reader::simulate_provide(&self.rpc, &self.program_id, params).await
+    }
+
+    /// Withdrawal preview — see [`A2ASwapClient::simulate_remove`](crate::A2ASwapClient::simulate_remove).
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        name = "a2a_swap.simulate_remove", skip_all, err,
+        fields(mint_a = %mint_a, mint_b = %mint_b, lp_shares, pool = tracing::field::Empty),
+    ))]
+    pub async fn simulate_remove(
+        &self,
+        mint_a:    Pubkey,
+        mint_b:    Pubkey,
+        lp_shares: u64,
+    ) -> Result<SimulateRemoveResult> {
+        reader::simulate_remove(&self.rpc, &self.program_id, &mint_a, &mint_b, lp_shares).await
+    }
+
+    /// Pool state plus current reserves and spot price.
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        name = "a2a_swap.pool_info", skip_all, err,
+        fields(mint_a = %mint_a, mint_b = %mint_b, pool = tracing::field::Empty),
+    ))]
+    pub async fn pool_info(&self, mint_a: Pubkey, mint_b: Pubkey) -> Result<PoolInfo> {
+        reader::pool_info(&self.rpc, &self.program_id, &mint_a, &mint_b).await
+    }
+
+    /// [`pool_info`](Self::pool_info) plus the pool's TVL in `quote_mint`.
+    /// `created_slot` stays `None`: it needs `getSignaturesForAddress`.
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        name = "a2a_swap.pool_info_in", skip_all, err,
+        fields(mint_a = %mint_a, mint_b = %mint_b, quote = %quote_mint, pool = tracing::field::Empty),
+    ))]
+    pub async fn pool_info_in(&self, mint_a: Pubkey, mint_b: Pubkey, quote_mint: Pubkey) -> Result<PoolInfo> {
+        reader::pool_info_in(&self.rpc, &self.program_id, &mint_a, &mint_b, &quote_mint).await
+    }
+
+    /// Every constant-product pool of the program, by address, with reserves
+    /// and spot price.
+    pub async fn list_pools(&self) -> Result<Vec<PoolInfo>> {
+        reader::list_pools(self.scanner(), &self.rpc, &self.program_id, self.scan).await
+    }
+
+    /// Every governance proposal, newest first, with its tally and outcome.
+    pub async fn proposals(&self) -> Result<Vec<ProposalInfo>> {
+        reader::list_proposals(self.scanner(), &self.rpc, &self.program_id, self.scan).await
+    }
+
+    /// `agent`'s protocol fee tier; see [`A2ASwapClient::my_tier`](crate::A2ASwapClient::my_tier).
+    pub async fn my_tier(&self, agent: &Pubkey) -> Result<TierInfo> {
+        reader::fetch_tier(&self.rpc, &self.program_id, agent).await
+    }
+
+    /// The nonce `agent`'s next swap intent must carry; see
+    /// [`A2ASwapClient::intent_nonce`](crate::A2ASwapClient::intent_nonce).
+    pub async fn intent_nonce(&self, agent: &Pubkey) -> Result<u64> {
+        reader::fetch_intent_nonce(&self.rpc, &self.program_id, agent).await
+    }
+
+    /// The program's Anchor IDL; see
+    /// [`A2ASwapClient::fetch_idl`](crate::A2ASwapClient::fetch_idl).
+    pub async fn fetch_idl(&self) -> Result<serde_json::Value> {
+        reader::fetch_idl(&self.rpc, &self.program_id).await
+    }
+
+    /// The program's live capability card; see
+    /// [`A2ASwapClient::capability_card`](crate::A2ASwapClient::capability_card).
+    pub async fn capability_card(&self) -> Result<CapabilityCard> {
+        reader::fetch_capability_card(&self.rpc, &self.program_id).await
+    }
+
+    /// All LP positions owned by `owner`, with pending fees and each pool's
+    /// current price.
+    ///
+    /// `entry` and `il_pct` are always `None`: rebuilding the entry price
+    /// needs transaction history, which only
+    /// [`A2ASwapClient::my_positions`](crate::A2ASwapClient::my_positions) reads.
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        name = "a2a_swap.my_positions", skip_all, err,
+        fields(owner = %owner, positions = tracing::field::Empty),
+    ))]
+    pub async fn my_positions(&self, owner: &Pubkey) -> Result<Vec<PositionInfo>> {
+        let positions = reader::fetch_positions(self.scanner(), &self.program_id, owner, self.scan).await?;
+        reader::position_infos(&self.rpc, positions, true).await
+    }
+
+    /// [`my_positions`](Self::my_positions) filtered, sorted and paged as
+    /// `query` says; see
+    /// [`A2ASwapClient::my_positions_page`](crate::A2ASwapClient::my_positions_page).
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        name = "a2a_swap.my_positions_page", skip_all, err,
+        fields(owner = %owner, positions = tracing::field::Empty),
+    ))]
+    pub async fn my_positions_page(&self, owner: &Pubkey, query: &PositionQuery) -> Result<PositionPage> {
+        let positions = reader::fetch_positions(self.scanner(), &self.program_id, owner, self.scan).await?;
+        reader::positions_page(&self.rpc, positions, query).await
+    }
+
+    /// Aggregate fee totals across all positions owned by `owner`.
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        name = "a2a_swap.my_fees", skip_all, err, fields(owner = %owner),
+    ))]
+    pub async fn my_fees(&self, owner: &Pubkey) -> Result<FeeSummary> {
+        let positions = reader::fetch_positions(self.scanner(), &self.program_id, owner, self.scan).await?;
+        let positions = reader::position_infos(&self.rpc, positions, false).await?;
+        let total_a = positions.iter().map(|p| p.total_fees_a).sum();
+        let total_b = positions.iter().map(|p| p.total_fees_b).sum();
+        Ok(FeeSummary { positions, total_fees_a: total_a, total_fees_b: total_b })
+    }
+
+    fn scanner(&self) -> &MinimalRpc {
+        self.index.as_ref().unwrap_or(&self.rpc)
+    }
+}
@@ -0,0 +1,439 @@
+//! [Squads v4](https://github.com/Squads-Protocol/v4) multisig helpers.
+//!
+//! Wraps A2A-Swap instructions into a Squads vault transaction so a
+//! DAO-owned agent can route treasury swaps through its existing multisig:
+//!
+//! 1. [`vault_transaction_create_ix`] stores the swap, with the vault PDA as
+//!    the signer, under the multisig's next transaction index;
+//! 2. [`proposal_create_ix`] opens a proposal for it and
+//!    [`proposal_approve_ix`] records each member's vote;
+//! 3. once the threshold (and any time lock) is met,
+//!    [`vault_transaction_execute_ix`] runs it, the vault signing by PDA.
+//!
+//! [`A2ASwapClient::propose_multisig_convert`],
+//! [`A2ASwapClient::approve_multisig`] and
+//! [`A2ASwapClient::execute_multisig`] do all of this for a
+//! [`convert`](A2ASwapClient::convert).
+//!
+//! Only the vault may sign the wrapped instructions; ephemeral signers and
+//! address lookup tables are not supported.
+//!
+//! [`A2ASwapClient::propose_multisig_convert`]: crate::A2ASwapClient::propose_multisig_convert
+//! [`A2ASwapClient::approve_multisig`]: crate::A2ASwapClient::approve_multisig
+//! [`A2ASwapClient::execute_multisig`]: crate::A2ASwapClient::execute_multisig
+//! [`A2ASwapClient::convert`]: crate::A2ASwapClient::convert
+
+use std::str::FromStr;
+
+use solana_sdk::{
+    hash::hash,
+    instruction::{AccountMeta, Instruction},
+    pubkey::Pubkey,
+};
+
+use crate::error::{Error, Result};
+
+// ─── Program + seeds ──────────────────────────────────────────────────────────
+
+/// Squads v4 multisig program.
+pub fn squads_program_id() -> Pubkey {
+    Pubkey::from_str("SQDS4ep65T869zMMBKyuUq6aD6EgTu8psMjkvj52pCf").unwrap()
+}
+
+const SEED_PREFIX:      &[u8] = b"multisig";
+const SEED_VAULT:       &[u8] = b"vault";
+const SEED_TRANSACTION: &[u8] = b"transaction";
+const SEED_PROPOSAL:    &[u8] = b"proposal";
+
+/// Derive vault `vault_index` of `multisig` — the account that holds the
+/// treasury and signs executed transactions.
+pub fn derive_vault(multisig: &Pubkey, vault_index: u8) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[SEED_PREFIX, multisig.as_ref(), SEED_VAULT, &[vault_index]],
+        &squads_program_id(),
+    )
+}
+
+/// Derive the vault transaction account for `transaction_index`.
+pub fn derive_transaction(multisig: &Pubkey, transaction_index: u64) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[SEED_PREFIX, multisig.as_ref(), SEED_TRANSACTION, &transaction_index.to_le_bytes()],
+        &squads_program_id(),
+    )
+}
+
+/// Derive the proposal account for `transaction_index`.
+pub fn derive_proposal(multisig: &Pubkey, transaction_index: u64) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            SEED_PREFIX,
+            multisig.as_ref(),
+            SEED_TRANSACTION,
+            &transaction_index.to_le_bytes(),
+            SEED_PROPOSAL,
+        ],
+        &squads_program_id(),
+    )
+}
+
+// ─── Vault transaction message ────────────────────────────────────────────────
+
+/// An instruction inside a [`VaultMessage`], by index into its account keys.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VaultInstruction {
+    pub program_id_index: u8,
+    pub account_indexes:  Vec<u8>,
+    pub data:             Vec<u8>,
+}
+
+/// The transaction a vault will execute, compiled the way Squads stores it:
+/// signers first (writable, then read-only), then writable and read-only
+/// non-signers.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VaultMessage {
+    pub num_signers:              u8,
+    pub num_writable_signers:     u8,
+    pub num_writable_non_signers: u8,
+    pub account_keys:             Vec<Pubkey>,
+    pub instructions:             Vec<VaultInstruction>,
+}
+
+impl VaultMessage {
+    /// Compile `instructions` for execution by `vault`, which becomes the
+    /// only signer. Fails if any instruction needs another signer.
+    pub fn compile(vault: &Pubkey, instructions: &[Instruction]) -> Result<VaultMessage> {
+        // (key, signer, writable) in first-seen order, flags merged.
+        let mut keys: Vec<(Pubkey, bool, bool)> = vec![(*vault, true, true)];
+        let mut note = |key: Pubkey, signer: bool, writable: bool| {
+            match keys.iter_mut().find(|(k, _, _)| *k == key) {
+                Some(entry) => {
+                    entry.1 |= signer;
+                    entry.2 |= writable;
+                }
+                None => keys.push((key, signer, writable)),
+            }
+        };
+        for ix in instructions {
+            for meta in &ix.accounts {
+                note(meta.pubkey, meta.is_signer, meta.is_writable);
+            }
+            note(ix.program_id, false, false);
+        }
+        if let Some((key, ..)) = keys.iter().find(|(k, signer, _)| *signer && k != vault) {
+            return Err(Error::InvalidArgument(format!(
+                "{key} must sign, but only the vault can sign a multisig transaction"
+            )));
+        }
+
+        // Stable sort keeps first-seen order within each group.
+        keys.sort_by_key(|&(_, signer, writable)| match (signer, writable) {
+            (true, true)   => 0,
+            (true, false)  => 1,
+            (false, true)  => 2,
+            (false, false) => 3,
+        });
+        if keys.len() > u8::MAX as usize {
+            return Err(Error::InvalidArgument(format!(
+                "{} accounts do not fit in one vault transaction", keys.len()
+            )));
+        }
+        let count = |f: fn(&(Pubkey, bool, bool)) -> bool| keys.iter().filter(|k| f(k)).count() as u8;
+        let account_keys: Vec<Pubkey> = keys.iter().map(|(k, ..)| *k).collect();
+        let index_of = |key: &Pubkey| account_keys.iter().position(|k| k == key).unwrap() as u8;
+
+        Ok(VaultMessage {
+            num_signers:              count(|k| k.1),
+            num_writable_signers:     count(|k| k.1 && k.2),
+            num_writable_non_signers: count(|k| !k.1 && k.2),
+            instructions: instructions
+                .iter()
+                .map(|ix| VaultInstruction {
+                    program_id_index: index_of(&ix.program_id),
+                    account_indexes:  ix.accounts.iter().map(|m| index_of(&m.pubkey)).collect(),
+                    data:             ix.data.clone(),
+                })
+                .collect(),
+            account_keys,
+        })
+    }
+
+    /// Whether account `index` is writable.
+    pub fn is_writable(&self, index: usize) -> bool {
+        let signers = self.num_signers as usize;
+        if index < signers {
+            index < self.num_writable_signers as usize
+        } else {
+            index - signers < self.num_writable_non_signers as usize
+        }
+    }
+
+    /// Squads' `TransactionMessage` wire format, the `transaction_message`
+    /// argument of `vault_transaction_create`: u8 counts, u8-prefixed key and
+    /// instruction lists, u16-prefixed instruction data, no lookup tables.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut out = vec![
+            self.num_signers,
+            self.num_writable_signers,
+            self.num_writable_non_signers,
+            self.account_keys.len() as u8,
+        ];
+        for key in &self.account_keys {
+            out.extend_from_slice(key.as_ref());
+        }
+        out.push(self.instructions.len() as u8);
+        for ix in &self.instructions {
+            out.push(ix.program_id_index);
+            out.push(ix.account_indexes.len() as u8);
+            out.extend_from_slice(&ix.account_indexes);
+            out.extend_from_slice(&(ix.data.len() as u16).to_le_bytes());
+            out.extend_from_slice(&ix.data);
+        }
+        out.push(0); // address_table_lookups
+        out
+    }
+}
+
+// ─── Accounts ─────────────────────────────────────────────────────────────────
+
+/// Read `transaction_index` — the index of the latest vault transaction —
+/// from a Squads `Multisig` account. The next transaction uses this + 1.
+///
+/// Layout: `disc(8) create_key(32) config_authority(32) threshold(2)
+/// time_lock(4) transaction_index(8) …`
+pub fn parse_multisig_transaction_index(data: &[u8]) -> Result<u64> {
+    read_u64(data, 78)
+}
+
+/// Deserialized Squads `VaultTransaction` account.
+#[derive(Debug, Clone)]
+pub struct VaultTransactionState {
+    pub multisig:    Pubkey,
+    pub creator:     Pubkey,
+    pub index:       u64,
+    pub vault_index: u8,
+    pub message:     VaultMessage,
+}
+
+/// Deserialize a Squads `VaultTransaction` account.
+///
+/// Layout: `disc(8) multisig(32) creator(32) index(8) bump(1) vault_index(1)
+/// vault_bump(1) ephemeral_signer_bumps(vec<u8>) message`, where the
+/// message is the Borsh form of [`VaultMessage`] (u32-prefixed vectors)
+/// followed by its lookup tables.
+pub fn parse_vault_transaction(data: &[u8]) -> Result<VaultTransactionState> {
+    let mut r = Reader { data, pos: 8 };
+    let multisig    = r.pubkey()?;
+    let creator     = r.pubkey()?;
+    let index       = r.u64()?;
+    let _bump       = r.u8()?;
+    let vault_index = r.u8()?;
+    let _vault_bump = r.u8()?;
+    let ephemeral   = r.len()?;
+    r.bytes(ephemeral)?;
+
+    let num_signers              = r.u8()?;
+    let num_writable_signers     = r.u8()?;
+    let num_writable_non_signers = r.u8()?;
+    let account_keys = (0..r.len()?).map(|_| r.pubkey()).collect::<Result<Vec<_>>>()?;
+    let instructions = (0..r.len()?)
+        .map(|_| {
+            let program_id_index = r.u8()?;
+            let n = r.len()?;
+            let account_indexes = r.bytes(n)?.to_vec();
+            let n = r.len()?;
+            let data = r.bytes(n)?.to_vec();
+            Ok(VaultInstruction { program_id_index, account_indexes, data })
+        })
+        .collect::<Result<Vec<_>>>()?;
+    if r.len()? != 0 {
+        return Err(Error::InvalidArgument(
+            "vault transactions with address lookup tables are not supported".into(),
+        ));
+    }
+
+    Ok(VaultTransactionState {
+        multisig,
+        creator,
+        index,
+        vault_index,
+        message: VaultMessage {
+            num_signers,
+            num_writable_signers,
+            num_writable_non_signers,
+            account_keys,
+            instructions,
+        },
+    })
+}
+
+struct Reader<'a> {
+    data: &'a [u8],
+    pos:  usize,
+}
+
+impl Reader<'_> {
+    fn bytes(&mut self, n: usize) -> Result<&[u8]> {
+        let out = self.data.get(self.pos..self.pos + n).ok_or_else(|| Error::ParseError {
+            offset: self.pos,
+            reason: format!("VaultTransaction account ends before byte {}", self.pos + n),
+        })?;
+        self.pos += n;
+        Ok(out)
+    }
+
+    fn u8(&mut self) -> Result<u8> {
+        Ok(self.bytes(1)?[0])
+    }
+
+    fn u64(&mut self) -> Result<u64> {
+        let v = read_u64(self.data, self.pos)?;
+        self.pos += 8;
+        Ok(v)
+    }
+
+    fn len(&mut self) -> Result<usize> {
+        Ok(u32::from_le_bytes(self.bytes(4)?.try_into().unwrap()) as usize)
+    }
+
+    fn pubkey(&mut self) -> Result<Pubkey> {
+        Ok(Pubkey::new_from_array(self.bytes(32)?.try_into().unwrap()))
+    }
+}
+
+fn read_u64(data: &[u8], offset: usize) -> Result<u64> {
+    data.get(offset..offset + 8)
+        .map(|b| u64::from_le_bytes(b.try_into().unwrap()))
+        .ok_or_else(|| Error::ParseError {
+            offset,
+            reason: format!("account is {} bytes; need at least {}", data.len(), offset + 8),
+        })
+}
+
+// ─── Instructions ─────────────────────────────────────────────────────────────
+
+/// Anchor discriminator `sha256("global:{name}")[..8]`.
+fn discriminator(name: &str) -> Vec<u8> {
+    hash(format!("global:{name}").as_bytes()).to_bytes()[..8].to_vec()
+}
+
+/// Borsh `Option<String>`.
+fn push_memo(data: &mut Vec<u8>, memo: Option<&str>) {
+    match memo {
+        None => data.push(0),
+        Some(memo) => {
+            data.push(1);
+            data.extend_from_slice(&(memo.len() as u32).to_le_bytes());
+            data.extend_from_slice(memo.as_bytes());
+        }
+    }
+}
+
+/// Build `vault_transaction_create`: store `message` as transaction
+/// `transaction_index` (the multisig's current index + 1). `creator` needs
+/// the Initiate permission; `rent_payer` funds the account.
+pub fn vault_transaction_create_ix(
+    multisig:          &Pubkey,
+    creator:           &Pubkey,
+    rent_payer:        &Pubkey,
+    transaction_index: u64,
+    vault_index:       u8,
+    message:           &VaultMessage,
+    memo:              Option<&str>,
+) -> Instruction {
+    let message = message.to_bytes();
+    let mut data = discriminator("vault_transaction_create");
+    data.push(vault_index);
+    data.push(0); // ephemeral_signers
+    data.extend_from_slice(&(message.len() as u32).to_le_bytes());
+    data.extend_from_slice(&message);
+    push_memo(&mut data, memo);
+
+    Instruction {
+        program_id: squads_program_id(),
+        accounts: vec![
+            AccountMeta::new(*multisig, false),
+            AccountMeta::new(derive_transaction(multisig, transaction_index).0, false),
+            AccountMeta::new_readonly(*creator, true),
+            AccountMeta::new(*rent_payer, true),
+            AccountMeta::new_readonly(Pubkey::default(), false), // system program
+        ],
+        data,
+    }
+}
+
+/// Build `proposal_create` for transaction `transaction_index`. A `draft`
+/// proposal must be activated before members can vote.
+pub fn proposal_create_ix(
+    multisig:          &Pubkey,
+    creator:           &Pubkey,
+    rent_payer:        &Pubkey,
+    transaction_index: u64,
+    draft:             bool,
+) -> Instruction {
+    let mut data = discriminator("proposal_create");
+    data.extend_from_slice(&transaction_index.to_le_bytes());
+    data.push(draft as u8);
+
+    Instruction {
+        program_id: squads_program_id(),
+        accounts: vec![
+            AccountMeta::new_readonly(*multisig, false),
+            AccountMeta::new(derive_proposal(multisig, transaction_index).0, false),
+            AccountMeta::new_readonly(*creator, true),
+            AccountMeta::new(*rent_payer, true),
+            AccountMeta::new_readonly(Pubkey::default(), false), // system program
+        ],
+        data,
+    }
+}
+
+/// Build `proposal_approve`: `member` (Vote permission) approves
+/// transaction `transaction_index`.
+pub fn proposal_approve_ix(
+    multisig:          &Pubkey,
+    member:            &Pubkey,
+    transaction_index: u64,
+    memo:              Option<&str>,
+) -> Instruction {
+    let mut data = discriminator("proposal_approve");
+    push_memo(&mut data, memo);
+
+    Instruction {
+        program_id: squads_program_id(),
+        accounts: vec![
+            AccountMeta::new_readonly(*multisig, false),
+            AccountMeta::new(*member, true),
+            AccountMeta::new(derive_proposal(multisig, transaction_index).0, false),
+        ],
+        data,
+    }
+}
+
+/// Build `vault_transaction_execute` for an approved transaction. `message`
+/// must be the stored one (see [`parse_vault_transaction`]); its accounts
+/// follow as remaining accounts, none of them signing — the vault signs by
+/// PDA. `member` needs the Execute permission.
+pub fn vault_transaction_execute_ix(
+    multisig:          &Pubkey,
+    member:            &Pubkey,
+    transaction_index: u64,
+    message:           &VaultMessage,
+) -> Instruction {
+    let mut accounts = vec![
+        AccountMeta::new_readonly(*multisig, false),
+        AccountMeta::new(derive_proposal(multisig, transaction_index).0, false),
+        AccountMeta::new_readonly(derive_transaction(multisig, transaction_index).0, false),
+        AccountMeta::new_readonly(*member, true),
+    ];
+    accounts.extend(message.account_keys.iter().enumerate().map(|(i, key)| AccountMeta {
+        pubkey:      *key,
+        is_signer:   false,
+        is_writable: message.is_writable(i),
+    }));
+
+    Instruction {
+        program_id: squads_program_id(),
+        accounts,
+        data: discriminator("vault_transaction_execute"),
+    }
+}
@@ -0,0 +1,371 @@
+//! Typed mirror of the on-chain `A2AError` enum.
+//!
+//! Anchor reports `#[error_code]` variants as `InstructionError::Custom(6000 + n)`
+//! in declaration order. Keep this table in the same order as
+//! `programs/a2a-swap/src/error.rs` — `programs/a2a-swap/tests/errors.rs`
+//! fails if the two drift.
+
+use crate::error_code::ErrorCode;
+use solana_sdk::{
+    instruction::{Instruction, InstructionError},
+    transaction::TransactionError,
+};
+
+/// Anchor's offset for user-defined error codes.
+pub const ERROR_CODE_OFFSET: u32 = 6000;
+
+/// An error raised by the A2A-Swap program itself.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum A2AErrorCode {
+    /// `6000` (`0x1770`)
+    InsufficientLiquidity,
+    /// `6001` (`0x1771`)
+    SlippageExceeded,
+    /// `6002` (`0x1772`)
+    ZeroAmount,
+    /// `6003` (`0x1773`)
+    MathOverflow,
+    /// `6004` (`0x1774`)
+    InvalidFeeRate,
+    /// `6005` (`0x1775`)
+    MintMismatch,
+    /// `6006` (`0x1776`)
+    InvalidMoltAsset,
+    /// `6007` (`0x1777`)
+    MoltAgentMismatch,
+    /// `6008` (`0x1778`)
+    PriceImpactExceeded,
+    /// `6009` (`0x1779`)
+    InvalidReceipt,
+    /// `6010` (`0x177a`)
+    InvalidAmplification,
+    /// `6011` (`0x177b`)
+    CurveNotConverged,
+    /// `6012` (`0x177c`)
+    InvalidTickRange,
+    /// `6013` (`0x177d`)
+    TickCapacityExceeded,
+    /// `6014` (`0x177e`)
+    Unauthorized,
+    /// `6015` (`0x177f`)
+    InvalidProtocolFee,
+    /// `6016` (`0x1780`)
+    InvalidReferralShare,
+    /// `6017` (`0x1781`)
+    ApprovalExpired,
+    /// `6018` (`0x1782`)
+    DelegateExpired,
+    /// `6019` (`0x1783`)
+    DelegateCapExceeded,
+    /// `6020` (`0x1784`)
+    DelegateMismatch,
+    /// `6021` (`0x1785`)
+    InvalidTreasuryAccount,
+    /// `6022` (`0x1786`)
+    MaxInputExceeded,
+    /// `6023` (`0x1787`)
+    PriceMoveExceeded,
+    /// `6024` (`0x1788`)
+    InvalidPriceMoveLimit,
+    /// `6025` (`0x1789`)
+    CommitmentMismatch,
+    /// `6026` (`0x178a`)
+    CommitmentNotReady,
+    /// `6027` (`0x178b`)
+    CommitmentExpired,
+    /// `6028` (`0x178c`)
+    PositionNotEmpty,
+    /// `6029` (`0x178d`)
+    PoolNotEmpty,
+    /// `6030` (`0x178e`)
+    Paused,
+    /// `6031` (`0x178f`)
+    InvalidGovernanceParams,
+    /// `6032` (`0x1790`)
+    VotingClosed,
+    /// `6033` (`0x1791`)
+    VotingNotEnded,
+    /// `6034` (`0x1792`)
+    ProposalNotPassed,
+    /// `6035` (`0x1793`)
+    ProposalAlreadyExecuted,
+    /// `6036` (`0x1794`)
+    InvalidFeeTier,
+    /// `6037` (`0x1795`)
+    IntentExpired,
+    /// `6038` (`0x1796`)
+    InvalidIntentNonce,
+    /// `6039` (`0x1797`)
+    InvalidIntentSignature,
+    /// `6040` (`0x1798`)
+    PositionLocked,
+    /// `6041` (`0x1799`)
+    LockerNotApproved,
+    /// `6042` (`0x179a`)
+    TraderNotAllowed,
+    /// `6043` (`0x179b`)
+    InvalidAllowlistProof,
+    /// `6044` (`0x179c`)
+    GateTokenRequired,
+    /// `6045` (`0x179d`)
+    DeadlineExceeded,
+    /// `6046` (`0x179e`)
+    DepositExceedsMax,
+    /// `6047` (`0x179f`)
+    TokenOwnerMismatch,
+}
+
+impl A2AErrorCode {
+    /// Every variant, in on-chain declaration order.
+    pub const ALL: &'static [A2AErrorCode] = &[
+        A2AErrorCode::InsufficientLiquidity,
+        A2AErrorCode::SlippageExceeded,
+        A2AErrorCode::ZeroAmount,
+        A2AErrorCode::MathOverflow,
+        A2AErrorCode::InvalidFeeRate,
+        A2AErrorCode::MintMismatch,
+        A2AErrorCode::InvalidMoltAsset,
+        A2AErrorCode::MoltAgentMismatch,
+        A2AErrorCode::PriceImpactExceeded,
+        A2AErrorCode::InvalidReceipt,
+        A2AErrorCode::InvalidAmplification,
+        A2AErrorCode::CurveNotConverged,
+        A2AErrorCode::InvalidTickRange,
+        A2AErrorCode::TickCapacityExceeded,
+        A2AErrorCode::Unauthorized,
+        A2AErrorCode::InvalidProtocolFee,
+        A2AErrorCode::InvalidReferralShare,
+        A2AErrorCode::ApprovalExpired,
+        A2AErrorCode::DelegateExpired,
+        A2AErrorCode::DelegateCapExceeded,
+        A2AErrorCode::DelegateMismatch,
+        A2AErrorCode::InvalidTreasuryAccount,
+        A2AErrorCode::MaxInputExceeded,
+        A2AErrorCode::PriceMoveExceeded,
+        A2AErrorCode::InvalidPriceMoveLimit,
+        A2AErrorCode::CommitmentMismatch,
+        A2AErrorCode::CommitmentNotReady,
+        A2AErrorCode::CommitmentExpired,
+        A2AErrorCode::PositionNotEmpty,
+        A2AErrorCode::PoolNotEmpty,
+        A2AErrorCode::Paused,
+        A2AErrorCode::InvalidGovernanceParams,
+        A2AErrorCode::VotingClosed,
+        A2AErrorCode::VotingNotEnded,
+        A2AErrorCode::ProposalNotPassed,
+        A2AErrorCode::ProposalAlreadyExecuted,
+        A2AErrorCode::InvalidFeeTier,
+        A2AErrorCode::IntentExpired,
+        A2AErrorCode::InvalidIntentNonce,
+        A2AErrorCode::InvalidIntentSignature,
+        A2AErrorCode::PositionLocked,
+        A2AErrorCode::LockerNotApproved,
+        A2AErrorCode::TraderNotAllowed,
+        A2AErrorCode::InvalidAllowlistProof,
+        A2AErrorCode::GateTokenRequired,
+        A2AErrorCode::DeadlineExceeded,
+        A2AErrorCode::DepositExceedsMax,
+        A2AErrorCode::TokenOwnerMismatch,
+    ];
+
+    /// Look up a raw `InstructionError::Custom` code.
+    pub fn from_code(code: u32) -> Option<A2AErrorCode> {
+        let idx = code.checked_sub(ERROR_CODE_OFFSET)? as usize;
+        A2AErrorCode::ALL.get(idx).copied()
+    }
+
+    /// Raw custom error code as reported on-chain (e.g. `6001`).
+    pub fn code(self) -> u32 {
+        ERROR_CODE_OFFSET + self as u32
+    }
+
+    /// Variant name as declared on-chain.
+    pub fn name(self) -> &'static str {
+        match self {
+            A2AErrorCode::InsufficientLiquidity => "InsufficientLiquidity",
+            A2AErrorCode::SlippageExceeded      => "SlippageExceeded",
+            A2AErrorCode::ZeroAmount            => "ZeroAmount",
+            A2AErrorCode::MathOverflow          => "MathOverflow",
+            A2AErrorCode::InvalidFeeRate        => "InvalidFeeRate",
+            A2AErrorCode::MintMismatch          => "MintMismatch",
+            A2AErrorCode::InvalidMoltAsset      => "InvalidMoltAsset",
+            A2AErrorCode::MoltAgentMismatch     => "MoltAgentMismatch",
+            A2AErrorCode::PriceImpactExceeded   => "PriceImpactExceeded",
+            A2AErrorCode::InvalidReceipt        => "InvalidReceipt",
+            A2AErrorCode::InvalidAmplification  => "InvalidAmplification",
+            A2AErrorCode::CurveNotConverged     => "CurveNotConverged",
+            A2AErrorCode::InvalidTickRange      => "InvalidTickRange",
+            A2AErrorCode::TickCapacityExceeded  => "TickCapacityExceeded",
+            A2AErrorCode::Unauthorized          => "Unauthorized",
+            A2AErrorCode::InvalidProtocolFee    => "InvalidProtocolFee",
+            A2AErrorCode::InvalidReferralShare  => "InvalidReferralShare",
+            A2AErrorCode::ApprovalExpired       => "ApprovalExpired",
+            A2AErrorCode::DelegateExpired       => "DelegateExpired",
+            A2AErrorCode::DelegateCapExceeded   => "DelegateCapExceeded",
+            A2AErrorCode::DelegateMismatch      => "DelegateMismatch",
+            A2AErrorCode::InvalidTreasuryAccount => "InvalidTreasuryAccount",
+            A2AErrorCode::MaxInputExceeded      => "MaxInputExceeded",
+            A2AErrorCode::PriceMoveExceeded     => "PriceMoveExceeded",
+            A2AErrorCode::InvalidPriceMoveLimit => "InvalidPriceMoveLimit",
+            A2AErrorCode::CommitmentMismatch    => "CommitmentMismatch",
+            A2AErrorCode::CommitmentNotReady    => "CommitmentNotReady",
+            A2AErrorCode::CommitmentExpired     => "CommitmentExpired",
+            A2AErrorCode::PositionNotEmpty      => "PositionNotEmpty",
+            A2AErrorCode::PoolNotEmpty          => "PoolNotEmpty",
+            A2AErrorCode::Paused                => "Paused",
+            A2AErrorCode::InvalidGovernanceParams => "InvalidGovernanceParams",
+            A2AErrorCode::VotingClosed          => "VotingClosed",
+            A2AErrorCode::VotingNotEnded        => "VotingNotEnded",
+            A2AErrorCode::ProposalNotPassed     => "ProposalNotPassed",
+            A2AErrorCode::ProposalAlreadyExecuted => "ProposalAlreadyExecuted",
+            A2AErrorCode::InvalidFeeTier        => "InvalidFeeTier",
+            A2AErrorCode::IntentExpired         => "IntentExpired",
+            A2AErrorCode::InvalidIntentNonce    => "InvalidIntentNonce",
+            A2AErrorCode::InvalidIntentSignature => "InvalidIntentSignature",
+            A2AErrorCode::PositionLocked        => "PositionLocked",
+            A2AErrorCode::LockerNotApproved     => "LockerNotApproved",
+            A2AErrorCode::TraderNotAllowed      => "TraderNotAllowed",
+            A2AErrorCode::InvalidAllowlistProof => "InvalidAllowlistProof",
+            A2AErrorCode::GateTokenRequired     => "GateTokenRequired",
+            A2AErrorCode::DeadlineExceeded      => "DeadlineExceeded",
+            A2AErrorCode::DepositExceedsMax     => "DepositExceedsMax",
+            A2AErrorCode::TokenOwnerMismatch    => "TokenOwnerMismatch",
+        }
+    }
+
+    /// Human-readable message — identical to the program's `#[msg(...)]`.
+    pub fn message(self) -> &'static str {
+        match self {
+            A2AErrorCode::InsufficientLiquidity => "Pool has insufficient liquidity",
+            A2AErrorCode::SlippageExceeded      => "Output below minimum — slippage exceeded",
+            A2AErrorCode::ZeroAmount            => "Amount must be greater than zero",
+            A2AErrorCode::MathOverflow          => "Math overflow",
+            A2AErrorCode::InvalidFeeRate        => "Fee rate is outside the allowed bounds",
+            A2AErrorCode::MintMismatch          => "Token mint does not match pool",
+            A2AErrorCode::InvalidMoltAsset      => "Asset is not from Molt collection",
+            A2AErrorCode::MoltAgentMismatch     => "Executor does not match Molt agent PDA",
+            A2AErrorCode::PriceImpactExceeded   => "Price impact exceeds the caller's cap",
+            A2AErrorCode::InvalidReceipt        => "Receipt accounts do not match the position",
+            A2AErrorCode::InvalidAmplification  => "StableSwap amplification must be 1–10000",
+            A2AErrorCode::CurveNotConverged     => "StableSwap invariant did not converge",
+            A2AErrorCode::InvalidTickRange      => "Tick range is invalid for this pool",
+            A2AErrorCode::TickCapacityExceeded  => "Range pool has no free tick slots",
+            A2AErrorCode::Unauthorized          => "Signer is not the protocol admin",
+            A2AErrorCode::InvalidProtocolFee    => "Protocol fee exceeds the 1% cap",
+            A2AErrorCode::InvalidReferralShare  => "Referral share exceeds 100% of the protocol fee",
+            A2AErrorCode::ApprovalExpired       => "Approval has expired",
+            A2AErrorCode::DelegateExpired       => "Delegate has expired",
+            A2AErrorCode::DelegateCapExceeded   => "Swap exceeds the delegate's remaining cap",
+            A2AErrorCode::DelegateMismatch      => "Signer is not this account's delegate",
+            A2AErrorCode::InvalidTreasuryAccount => "Treasury token account is not the treasury's ATA",
+            A2AErrorCode::MaxInputExceeded      => "Required input exceeds max_amount_in",
+            A2AErrorCode::PriceMoveExceeded     => "Swap moves the price beyond the pool's per-slot limit",
+            A2AErrorCode::InvalidPriceMoveLimit => "Circuit breaker limit must be at most 10000 bps",
+            A2AErrorCode::CommitmentMismatch    => "Revealed swap does not match the commitment",
+            A2AErrorCode::CommitmentNotReady    => "Commitment cannot be revealed until a later slot",
+            A2AErrorCode::CommitmentExpired     => "Swap commitment has expired",
+            A2AErrorCode::PositionNotEmpty      => "Position still holds LP shares or unclaimed fees",
+            A2AErrorCode::PoolNotEmpty          => "Pool still has LP shares, tracked reserves or open positions",
+            A2AErrorCode::Paused                => "This operation is paused by governance",
+            A2AErrorCode::InvalidGovernanceParams => "Invalid governance parameters",
+            A2AErrorCode::VotingClosed          => "Voting on this proposal has closed",
+            A2AErrorCode::VotingNotEnded        => "Voting on this proposal is still open",
+            A2AErrorCode::ProposalNotPassed     => "Proposal did not pass",
+            A2AErrorCode::ProposalAlreadyExecuted => "Proposal has already been executed",
+            A2AErrorCode::InvalidFeeTier        => "Invalid fee tier",
+            A2AErrorCode::IntentExpired         => "Swap intent has expired",
+            A2AErrorCode::InvalidIntentNonce    => "Swap intent nonce does not match",
+            A2AErrorCode::InvalidIntentSignature => "Swap intent signature is missing or does not match",
+            A2AErrorCode::PositionLocked        => "Position is locked as collateral",
+            A2AErrorCode::LockerNotApproved     => "Program may not lock or unlock this position",
+            A2AErrorCode::TraderNotAllowed      => "Trader is not on the pool's allowlist",
+            A2AErrorCode::InvalidAllowlistProof => "Allowlist proof does not match the pool's root",
+            A2AErrorCode::GateTokenRequired     => "Swap requires holding the pool's gate token",
+            A2AErrorCode::DeadlineExceeded      => "Swap deadline has passed",
+            A2AErrorCode::DepositExceedsMax     => "Deposit exceeds the caller's max amount",
+            A2AErrorCode::TokenOwnerMismatch    => "Token account is not owned by the expected wallet",
+        }
+    }
+
+    /// The shared [`ErrorCode`] agents branch on — program failures that
+    /// match an SDK pre-flight check report the same code.
+    pub fn error_code(self) -> ErrorCode {
+        match self {
+            A2AErrorCode::InsufficientLiquidity => ErrorCode::NoLiquidity,
+            A2AErrorCode::SlippageExceeded
+            | A2AErrorCode::MaxInputExceeded
+            | A2AErrorCode::DepositExceedsMax   => ErrorCode::SlippageExceeded,
+            A2AErrorCode::MathOverflow          => ErrorCode::MathOverflow,
+            A2AErrorCode::PriceImpactExceeded
+            | A2AErrorCode::PriceMoveExceeded   => ErrorCode::PriceImpactExceeded,
+            A2AErrorCode::ZeroAmount
+            | A2AErrorCode::InvalidFeeRate
+            | A2AErrorCode::MintMismatch
+            | A2AErrorCode::TokenOwnerMismatch
+            | A2AErrorCode::InvalidReceipt
+            | A2AErrorCode::InvalidAmplification
+            | A2AErrorCode::InvalidTickRange
+            | A2AErrorCode::InvalidProtocolFee
+            | A2AErrorCode::InvalidReferralShare
+            | A2AErrorCode::InvalidTreasuryAccount
+            | A2AErrorCode::InvalidPriceMoveLimit
+            | A2AErrorCode::CommitmentMismatch
+            | A2AErrorCode::InvalidGovernanceParams
+            | A2AErrorCode::InvalidFeeTier
+            | A2AErrorCode::InvalidIntentSignature
+            | A2AErrorCode::InvalidAllowlistProof => ErrorCode::InvalidArgument,
+            A2AErrorCode::InvalidMoltAsset
+            | A2AErrorCode::MoltAgentMismatch
+            | A2AErrorCode::CurveNotConverged
+            | A2AErrorCode::TickCapacityExceeded
+            | A2AErrorCode::Unauthorized
+            | A2AErrorCode::ApprovalExpired
+            | A2AErrorCode::DelegateExpired
+            | A2AErrorCode::DelegateCapExceeded
+            | A2AErrorCode::DelegateMismatch
+            | A2AErrorCode::CommitmentNotReady
+            | A2AErrorCode::CommitmentExpired
+            | A2AErrorCode::PositionNotEmpty
+            | A2AErrorCode::PoolNotEmpty
+            | A2AErrorCode::Paused
+            | A2AErrorCode::VotingClosed
+            | A2AErrorCode::VotingNotEnded
+            | A2AErrorCode::ProposalNotPassed
+            | A2AErrorCode::ProposalAlreadyExecuted
+            | A2AErrorCode::IntentExpired
+            | A2AErrorCode::DeadlineExceeded
+            | A2AErrorCode::InvalidIntentNonce
+            | A2AErrorCode::PositionLocked
+            | A2AErrorCode::LockerNotApproved
+            | A2AErrorCode::TraderNotAllowed
+            | A2AErrorCode::GateTokenRequired   => ErrorCode::ProgramError,
+        }
+    }
+
+    /// Extract an A2A-Swap program error from a failed transaction.
+    ///
+    /// Only custom codes raised by an instruction whose program is
+    /// `program_id` are mapped — SPL Token's `Custom(1)` etc. are ignored.
+    pub fn from_transaction_error(
+        err:          &TransactionError,
+        instructions: &[Instruction],
+        program_id:   &solana_sdk::pubkey::Pubkey,
+    ) -> Option<A2AErrorCode> {
+        match err {
+            TransactionError::InstructionError(idx, InstructionError::Custom(code)) => {
+                let ix = instructions.get(*idx as usize)?;
+                if ix.program_id != *program_id {
+                    return None;
+                }
+                A2AErrorCode::from_code(*code)
+            }
+            _ => None,
+        }
+    }
+}
+
+impl std::fmt::Display for A2AErrorCode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} ({:#x}): {}", self.name(), self.code(), self.message())
+    }
+}
@@ -0,0 +1,478 @@
+//! Concentrated-liquidity (range pool) math.
+//!
+//! Mirrors `programs/a2a-swap/src/instructions/range_math.rs` exactly, so
+//! quotes, deposits and pending fees match the program to the unit.
+//! Prices are `sqrt(token B per token A)` in Q64.64; tick `t` is price
+//! `1.0001^t`.
+
+use crate::error::{Error, Result};
+use crate::math::{BPS_DENOMINATOR, PROTOCOL_FEE_DENOMINATOR};
+use crate::state::{RangePoolState, RangePositionState, RangeTick};
+use crate::types::SimulateResult;
+use solana_sdk::pubkey::Pubkey;
+
+// ─── Constants ────────────────────────────────────────────────────────────────
+
+/// Lowest tick a range may start at.
+pub const MIN_TICK: i32 = -443_636;
+/// Highest tick a range may end at.
+pub const MAX_TICK: i32 = 443_636;
+/// Q64.64 fixed-point one.
+pub const Q64: u128 = 1 << 64;
+
+/// 2⁶⁴ / √1.0001^(2^i), i = 0..19.
+const INV_SQRT_POW: [u128; 19] = [
+    0xfffcb933bd6fad37,
+    0xfff97272373d4132,
+    0xfff2e50f5f656932,
+    0xffe5caca7e10e4e6,
+    0xffcb9843d60f6159,
+    0xff973b41fa98c081,
+    0xff2ea16466c96a38,
+    0xfe5dee046a99a2a8,
+    0xfcbe86c7900a88ae,
+    0xf987a7253ac41317,
+    0xf3392b0822b70005,
+    0xe7159475a2c29b74,
+    0xd097f3bdfd2022b8,
+    0xa9f746462d870fdf,
+    0x70d869a156d2a1b8,
+    0x31be135f97d08fd9,
+    0x09aa508b5b7a84e1,
+    0x005d6af8dedb8119,
+    0x00002216e584f5fa,
+];
+
+// ─── 256-bit intermediates ────────────────────────────────────────────────────
+
+fn mul_full(a: u128, b: u128) -> (u128, u128) {
+    const MASK: u128 = u64::MAX as u128;
+    let (a1, a0) = (a >> 64, a & MASK);
+    let (b1, b0) = (b >> 64, b & MASK);
+    let p00 = a0 * b0;
+    let p01 = a0 * b1;
+    let p10 = a1 * b0;
+    let mid = (p00 >> 64) + (p01 & MASK) + (p10 & MASK);
+    let lo = (p00 & MASK) | (mid << 64);
+    let hi = a1 * b1 + (p01 >> 64) + (p10 >> 64) + (mid >> 64);
+    (hi, lo)
+}
+
+fn mul_div_rem(a: u128, b: u128, denom: u128) -> Result<(u128, u128)> {
+    if denom == 0 {
+        return Err(Error::MathOverflow);
+    }
+    let (hi, lo) = mul_full(a, b);
+    if hi == 0 {
+        return Ok((lo / denom, lo % denom));
+    }
+    if hi >= denom {
+        return Err(Error::MathOverflow);
+    }
+    let (mut q, mut r) = (0u128, hi);
+    for i in (0..128).rev() {
+        let carry = r >> 127;
+        r = (r << 1) | ((lo >> i) & 1);
+        q <<= 1;
+        if carry == 1 || r >= denom {
+            r = r.wrapping_sub(denom);
+            q |= 1;
+        }
+    }
+    Ok((q, r))
+}
+
+/// `floor(a · b / denom)` with a 256-bit intermediate.
+pub fn mul_div(a: u128, b: u128, denom: u128) -> Result<u128> {
+    Ok(mul_div_rem(a, b, denom)?.0)
+}
+
+/// `ceil(a · b / denom)` with a 256-bit intermediate.
+pub fn mul_div_ceil(a: u128, b: u128, denom: u128) -> Result<u128> {
+    let (q, r) = mul_div_rem(a, b, denom)?;
+    if r == 0 { Ok(q) } else { q.checked_add(1).ok_or(Error::MathOverflow) }
+}
+
+// ─── Tick math ────────────────────────────────────────────────────────────────
+
+/// `√1.0001^tick` in Q64.64.
+pub fn sqrt_price_at_tick(tick: i32) -> Result<u128> {
+    if !(MIN_TICK..=MAX_TICK).contains(&tick) {
+        return Err(Error::InvalidArgument(format!(
+            "tick {tick} outside {MIN_TICK}..={MAX_TICK}"
+        )));
+    }
+    let abs = tick.unsigned_abs();
+    let mut ratio = Q64;
+    for (i, k) in INV_SQRT_POW.iter().enumerate() {
+        if abs & (1 << i) != 0 {
+            ratio = (ratio * k) >> 64;
+        }
+    }
+    Ok(if tick > 0 { u128::MAX / ratio } else { ratio })
+}
+
+/// Greatest tick whose sqrt price is `<= sqrt_price_x64`.
+pub fn tick_at_sqrt_price(sqrt_price_x64: u128) -> Result<i32> {
+    if sqrt_price_x64 < sqrt_price_at_tick(MIN_TICK)?
+        || sqrt_price_x64 > sqrt_price_at_tick(MAX_TICK)?
+    {
+        return Err(Error::InvalidArgument(format!(
+            "sqrt price {sqrt_price_x64} outside the tick range"
+        )));
+    }
+    let (mut lo, mut hi) = (MIN_TICK, MAX_TICK);
+    while lo < hi {
+        let mid = lo + (hi - lo + 1) / 2;
+        if sqrt_price_at_tick(mid)? <= sqrt_price_x64 {
+            lo = mid;
+        } else {
+            hi = mid - 1;
+        }
+    }
+    Ok(lo)
+}
+
+/// Price (token B per token A, atomic units) at a tick, as a float.
+pub fn price_at_tick(tick: i32) -> f64 {
+    1.0001f64.powi(tick)
+}
+
+/// Nearest tick at or below `price` that is a multiple of `tick_spacing` —
+/// a convenience for picking range bounds from human prices.
+pub fn tick_for_price(price: f64, tick_spacing: u16) -> Result<i32> {
+    if !(price.is_finite() && price > 0.0) || tick_spacing == 0 {
+        return Err(Error::InvalidArgument(format!("price {price} / spacing {tick_spacing}")));
+    }
+    let spacing = tick_spacing as i32;
+    let tick = (price.ln() / 1.0001f64.ln()).floor() as i32;
+    Ok(tick.clamp(MIN_TICK, MAX_TICK).div_euclid(spacing) * spacing)
+}
+
+// ─── Amounts ↔ liquidity ──────────────────────────────────────────────────────
+
+/// Token A spanned by `liquidity` between two sqrt prices (lo <= hi).
+pub fn amount_a_delta(sqrt_lo: u128, sqrt_hi: u128, liquidity: u128, round_up: bool) -> Result<u128> {
+    if round_up {
+        mul_div_ceil(mul_div_ceil(liquidity, Q64, sqrt_lo)?, sqrt_hi - sqrt_lo, sqrt_hi)
+    } else {
+        mul_div(mul_div(liquidity, Q64, sqrt_lo)?, sqrt_hi - sqrt_lo, sqrt_hi)
+    }
+}
+
+/// Token B spanned by `liquidity` between two sqrt prices (lo <= hi).
+pub fn amount_b_delta(sqrt_lo: u128, sqrt_hi: u128, liquidity: u128, round_up: bool) -> Result<u128> {
+    if round_up {
+        mul_div_ceil(liquidity, sqrt_hi - sqrt_lo, Q64)
+    } else {
+        mul_div(liquidity, sqrt_hi - sqrt_lo, Q64)
+    }
+}
+
+/// Largest liquidity `amount_a` / `amount_b` can fund in `[sqrt_lo, sqrt_hi)`
+/// at `sqrt_price` — what `provide_range_liquidity` will mint.
+pub fn liquidity_for_amounts(
+    sqrt_price: u128,
+    sqrt_lo:    u128,
+    sqrt_hi:    u128,
+    amount_a:   u64,
+    amount_b:   u64,
+) -> Result<u128> {
+    let from_a = |lo: u128| -> Result<u128> {
+        mul_div(mul_div(amount_a as u128, sqrt_hi, sqrt_hi - lo)?, lo, Q64)
+    };
+    let from_b = |hi: u128| -> Result<u128> { mul_div(amount_b as u128, Q64, hi - sqrt_lo) };
+
+    if sqrt_price <= sqrt_lo {
+        from_a(sqrt_lo)
+    } else if sqrt_price >= sqrt_hi {
+        from_b(sqrt_hi)
+    } else {
+        Ok(from_a(sqrt_price)?.min(from_b(sqrt_price)?))
+    }
+}
+
+/// Token amounts `liquidity` represents in `[sqrt_lo, sqrt_hi)` at
+/// `sqrt_price`. Round up for deposits, down for withdrawals.
+pub fn amounts_for_liquidity(
+    sqrt_price: u128,
+    sqrt_lo:    u128,
+    sqrt_hi:    u128,
+    liquidity:  u128,
+    round_up:   bool,
+) -> Result<(u64, u64)> {
+    let (a, b) = if sqrt_price <= sqrt_lo {
+        (amount_a_delta(sqrt_lo, sqrt_hi, liquidity, round_up)?, 0)
+    } else if sqrt_price >= sqrt_hi {
+        (0, amount_b_delta(sqrt_lo, sqrt_hi, liquidity, round_up)?)
+    } else {
+        (
+            amount_a_delta(sqrt_price, sqrt_hi, liquidity, round_up)?,
+            amount_b_delta(sqrt_lo, sqrt_price, liquidity, round_up)?,
+        )
+    };
+    Ok((
+        u64::try_from(a).map_err(|_| Error::MathOverflow)?,
+        u64::try_from(b).map_err(|_| Error::MathOverflow)?,
+    ))
+}
+
+// ─── Swap ─────────────────────────────────────────────────────────────────────
+
+struct SwapStep {
+    sqrt_price_next: u128,
+    amount_in:       u128,
+    amount_out:      u128,
+}
+
+fn swap_step(
+    sqrt_price:  u128,
+    sqrt_target: u128,
+    liquidity:   u128,
+    remaining:   u128,
+    a_to_b:      bool,
+) -> Result<SwapStep> {
+    if a_to_b {
+        let max_in = amount_a_delta(sqrt_target, sqrt_price, liquidity, true)?;
+        let (next, amount_in) = if remaining >= max_in {
+            (sqrt_target, max_in)
+        } else {
+            let denom = liquidity
+                .checked_add(mul_div(remaining, sqrt_price, Q64)?)
+                .ok_or(Error::MathOverflow)?;
+            (mul_div_ceil(liquidity, sqrt_price, denom)?.max(sqrt_target), remaining)
+        };
+        let amount_out = amount_b_delta(next, sqrt_price, liquidity, false)?;
+        Ok(SwapStep { sqrt_price_next: next, amount_in, amount_out })
+    } else {
+        let max_in = amount_b_delta(sqrt_price, sqrt_target, liquidity, true)?;
+        let (next, amount_in) = if remaining >= max_in {
+            (sqrt_target, max_in)
+        } else {
+            let next = sqrt_price
+                .checked_add(mul_div(remaining, Q64, liquidity)?)
+                .ok_or(Error::MathOverflow)?;
+            (next.min(sqrt_target), remaining)
+        };
+        let amount_out = amount_a_delta(sqrt_price, next, liquidity, false)?;
+        Ok(SwapStep { sqrt_price_next: next, amount_in, amount_out })
+    }
+}
+
+fn next_initialized_tick(pool: &RangePoolState, a_to_b: bool) -> Option<usize> {
+    let above = pool.ticks.partition_point(|t| t.index <= pool.tick_current);
+    if a_to_b {
+        above.checked_sub(1)
+    } else {
+        (above < pool.ticks.len()).then_some(above)
+    }
+}
+
+fn cross_tick(pool: &mut RangePoolState, i: usize, a_to_b: bool) -> Result<()> {
+    let (fg_a, fg_b) = (pool.fee_growth_global_a, pool.fee_growth_global_b);
+    let tick = &mut pool.ticks[i];
+    tick.fee_growth_outside_a = fg_a.wrapping_sub(tick.fee_growth_outside_a);
+    tick.fee_growth_outside_b = fg_b.wrapping_sub(tick.fee_growth_outside_b);
+    let net = if a_to_b { -tick.liquidity_net } else { tick.liquidity_net };
+    let index = tick.index;
+    pool.liquidity = pool.liquidity.checked_add_signed(net).ok_or(Error::MathOverflow)?;
+    pool.tick_current = if a_to_b { index - 1 } else { index };
+    Ok(())
+}
+
+/// Apply a swap to `pool` in place, exactly as `swap_range` does on-chain,
+/// and return `(amount_out, price_impact_bps)`.
+///
+/// Fails with [`Error::NoLiquidity`] when the ranges run out before
+/// `amount_in` is used up, leaving `pool` partially updated.
+pub fn apply_range_swap(
+    pool:             &mut RangePoolState,
+    amount_in:        u64,
+    protocol_fee_bps: u64,
+    a_to_b:           bool,
+) -> Result<(u64, u64)> {
+    let (_, _, lp_fee, after_fees) = split_fees(amount_in, protocol_fee_bps, pool.fee_rate_bps)?;
+
+    let start_price = pool.sqrt_price_x64;
+    let mut remaining = after_fees;
+    let mut fee_left = lp_fee;
+    let mut amount_out: u128 = 0;
+
+    while remaining > 0 {
+        let next = next_initialized_tick(pool, a_to_b);
+        let target = match next {
+            Some(i) => sqrt_price_at_tick(pool.ticks[i].index)?,
+            None => sqrt_price_at_tick(if a_to_b { MIN_TICK } else { MAX_TICK })?,
+        };
+        let step = swap_step(pool.sqrt_price_x64, target, pool.liquidity, remaining, a_to_b)?;
+        remaining -= step.amount_in;
+        amount_out = amount_out.checked_add(step.amount_out).ok_or(Error::MathOverflow)?;
+
+        if step.amount_in > 0 && pool.liquidity > 0 {
+            let fee = if remaining == 0 {
+                fee_left
+            } else {
+                mul_div(lp_fee, step.amount_in, after_fees)?.min(fee_left)
+            };
+            fee_left -= fee;
+            let growth = mul_div(fee, Q64, pool.liquidity)?;
+            if a_to_b {
+                pool.fee_growth_global_a = pool.fee_growth_global_a.wrapping_add(growth);
+            } else {
+                pool.fee_growth_global_b = pool.fee_growth_global_b.wrapping_add(growth);
+            }
+        }
+
+        pool.sqrt_price_x64 = step.sqrt_price_next;
+        match next {
+            Some(i) if step.sqrt_price_next == target => cross_tick(pool, i, a_to_b)?,
+            None if step.sqrt_price_next == target => {
+                if remaining > 0 {
+                    return Err(Error::NoLiquidity);
+                }
+                pool.tick_current = tick_at_sqrt_price(target)?;
+            }
+            _ => pool.tick_current = tick_at_sqrt_price(step.sqrt_price_next)?,
+        }
+    }
+
+    let amount_out = u64::try_from(amount_out).map_err(|_| Error::MathOverflow)?;
+    let ideal_out = if a_to_b {
+        mul_div(mul_div(after_fees, start_price, Q64)?, start_price, Q64)?
+    } else {
+        mul_div(mul_div(after_fees, Q64, start_price)?, Q64, start_price)?
+    };
+    let impact_bps = if ideal_out > amount_out as u128 {
+        (ideal_out - amount_out as u128) * BPS_DENOMINATOR / ideal_out
+    } else {
+        0
+    };
+    Ok((amount_out, impact_bps as u64))
+}
+
+/// `(protocol_fee, net_pool_input, lp_fee, after_fees)` — same split as
+/// constant-product pools.
+fn split_fees(
+    amount_in:        u64,
+    protocol_fee_bps: u64,
+    fee_rate_bps:     u16,
+) -> Result<(u128, u128, u128, u128)> {
+    let in_u128 = amount_in as u128;
+    let protocol_fee = in_u128
+        .checked_mul(protocol_fee_bps as u128)
+        .ok_or(Error::MathOverflow)?
+        / PROTOCOL_FEE_DENOMINATOR;
+    let net_pool_input = in_u128 - protocol_fee;
+    let lp_fee = net_pool_input
+        .checked_mul(fee_rate_bps as u128)
+        .ok_or(Error::MathOverflow)?
+        / BPS_DENOMINATOR;
+    Ok((protocol_fee, net_pool_input, lp_fee, net_pool_input - lp_fee))
+}
+
+/// Active liquidity expressed as constant-product reserves at the current
+/// price: `(L / √p, L · √p)`, saturating at `u64::MAX`.
+pub fn virtual_reserves(pool: &RangePoolState) -> (u64, u64) {
+    let a = mul_div(pool.liquidity, Q64, pool.sqrt_price_x64).unwrap_or(u128::MAX);
+    let b = mul_div(pool.liquidity, pool.sqrt_price_x64, Q64).unwrap_or(u128::MAX);
+    (a.min(u64::MAX as u128) as u64, b.min(u64::MAX as u128) as u64)
+}
+
+/// Range-aware counterpart of [`crate::math::simulate_detailed`].
+///
+/// Walks the pool's ticks exactly as `swap_range` would. `reserve_in` /
+/// `reserve_out` in the result are the [`virtual_reserves`] of the liquidity
+/// active before the trade; an output that rounds to zero is quoted as 0.
+pub fn simulate_range(
+    pool_addr:        Pubkey,
+    pool:             &RangePoolState,
+    amount_in:        u64,
+    protocol_fee_bps: u64,
+    a_to_b:           bool,
+) -> Result<SimulateResult> {
+    let (protocol_fee, net_pool_input, lp_fee, after_fees) =
+        split_fees(amount_in, protocol_fee_bps, pool.fee_rate_bps)?;
+    let (virtual_a, virtual_b) = virtual_reserves(pool);
+    let (reserve_in, reserve_out) = if a_to_b { (virtual_a, virtual_b) } else { (virtual_b, virtual_a) };
+
+    let (estimated_out, impact_bps) = if after_fees == 0 {
+        (0, 0)
+    } else {
+        apply_range_swap(&mut pool.clone(), amount_in, protocol_fee_bps, a_to_b)?
+    };
+    let effective_rate = if amount_in == 0 {
+        0.0
+    } else {
+        estimated_out as f64 / amount_in as f64
+    };
+
+    Ok(SimulateResult {
+        pool: pool_addr,
+        a_to_b,
+        amount_in,
+        protocol_fee:     protocol_fee as u64,
+        net_pool_input:   net_pool_input as u64,
+        lp_fee:           lp_fee as u64,
+        after_fees:       after_fees as u64,
+        estimated_out,
+        effective_rate,
+        price_impact_pct: impact_bps as f64 / 100.0,
+        fee_rate_bps:     pool.fee_rate_bps,
+        reserve_in,
+        reserve_out,
+    })
+}
+
+// ─── Positions ────────────────────────────────────────────────────────────────
+
+/// Fee growth per unit of liquidity earned inside `[tick_lower, tick_upper)`.
+pub fn fee_growth_inside(pool: &RangePoolState, tick_lower: i32, tick_upper: i32) -> (u128, u128) {
+    let outside = |index: i32| {
+        pool.ticks
+            .iter()
+            .find(|t| t.index == index)
+            .map_or((0, 0), |t: &RangeTick| (t.fee_growth_outside_a, t.fee_growth_outside_b))
+    };
+    let (g_a, g_b) = (pool.fee_growth_global_a, pool.fee_growth_global_b);
+    let (lo_a, lo_b) = outside(tick_lower);
+    let (hi_a, hi_b) = outside(tick_upper);
+    let (below_a, below_b) = if pool.tick_current >= tick_lower {
+        (lo_a, lo_b)
+    } else {
+        (g_a.wrapping_sub(lo_a), g_b.wrapping_sub(lo_b))
+    };
+    let (above_a, above_b) = if pool.tick_current < tick_upper {
+        (hi_a, hi_b)
+    } else {
+        (g_a.wrapping_sub(hi_a), g_b.wrapping_sub(hi_b))
+    };
+    (
+        g_a.wrapping_sub(below_a).wrapping_sub(above_a),
+        g_b.wrapping_sub(below_b).wrapping_sub(above_b),
+    )
+}
+
+/// Fees a range position could collect right now: `fees_owed` plus growth
+/// inside its range since the last sync. Returns `(fees_a, fees_b)`.
+pub fn pending_range_fees(position: &RangePositionState, pool: &RangePoolState) -> (u64, u64) {
+    let (inside_a, inside_b) = fee_growth_inside(pool, position.tick_lower, position.tick_upper);
+    let accrued = |inside: u128, last: u128| {
+        mul_div(position.liquidity, inside.wrapping_sub(last), Q64).unwrap_or(0) as u64
+    };
+    (
+        position.fees_owed_a.saturating_add(accrued(inside_a, position.fee_growth_inside_last_a)),
+        position.fees_owed_b.saturating_add(accrued(inside_b, position.fee_growth_inside_last_b)),
+    )
+}
+
+/// Token amounts a range position would withdraw at the pool's current
+/// price, excluding fees. Returns `(amount_a, amount_b)`.
+pub fn range_position_amounts(position: &RangePositionState, pool: &RangePoolState) -> Result<(u64, u64)> {
+    amounts_for_liquidity(
+        pool.sqrt_price_x64,
+        sqrt_price_at_tick(position.tick_lower)?,
+        sqrt_price_at_tick(position.tick_upper)?,
+        position.liquidity,
+        false,
+    )
+}
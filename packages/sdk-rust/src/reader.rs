@@ -0,0 +1,791 @@
+//! Account reads behind the read-only operations.
+//!
+//! Quoting, pool info and positions only read accounts, so they are written
+//! once against [`AccountReader`] and shared by
+//! [`A2ASwapClient`](crate::A2ASwapClient) (over `solana-client`) and
+//! [`ReadOnlyClient`](crate::minimal_rpc::ReadOnlyClient) (plain JSON-RPC).
+
+use std::collections::HashMap;
+
+use a2a_swap_core::Account as _;
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine as _};
+use serde_json::{json, Value};
+use solana_sdk::pubkey::Pubkey;
+
+use crate::{
+    error::{Error, Result},
+    instructions::{derive_agent_volume, derive_governance, derive_idl_address, derive_intent_nonce, derive_pool, derive_treasury},
+    math::{
+        compute_amount_b, effective_fee_bps, lp_share_value, lp_underlying, pending_fees_for_position, simulate_detailed,
+        simulate_provide_detailed, simulate_remove_detailed, spot_price, spot_value, unix_now, PROTOCOL_FEE_BPS,
+        REFERRAL_SHARE_BPS,
+    },
+    state::{
+        parse_agent_volume, parse_governance, parse_idl_account, parse_intent_nonce, parse_pool, parse_position, parse_proposal, parse_protocol_config,
+        parse_token_amount, GovernanceState, PoolState, PositionState,
+    },
+    trace,
+    types::{
+        CapabilityCard, PoolInfo, PoolTvl, PositionInfo, PositionPage, PositionQuery, PositionSort, ProposalInfo,
+        ProvideParams, SimulateParams, SimulateProvideResult, SimulateRemoveResult, SimulateResult, TierInfo,
+    },
+};
+
+pub(crate) const DEFAULT_PROGRAM_ID: &str = "8XJfG4mHqRZjByAd7HxHdEALfB8jVtJVQsdhGEmysTFq";
+pub(crate) const DEVNET_RPC:   &str = "https://api.devnet.solana.com";
+pub(crate) const MAINNET_RPC:  &str = "https://api.mainnet-beta.solana.com";
+pub(crate) const LOCALNET_RPC: &str = "http://127.0.0.1:8899";
+
+/// One page of a cursor scan: `(address, data)` matches and the next cursor.
+pub(crate) type AccountsPage = (Vec<(Pubkey, Vec<u8>)>, Option<String>);
+
+/// Most keys one `getMultipleAccounts` call accepts.
+pub(crate) const MAX_MULTIPLE_ACCOUNTS: usize = 100;
+
+/// The account queries the read-only operations need.
+pub(crate) trait AccountReader {
+    /// Data of each of `keys`, in order; `None` where an account does not exist.
+    async fn multiple_account_data(&self, keys: &[Pubkey]) -> Result<Vec<Option<Vec<u8>>>>;
+
+    /// `(address, data)` of every account of `program` whose bytes at each
+    /// `(offset, bytes)` match.
+    async fn program_accounts(&self, program: &Pubkey, memcmp: &[(usize, &[u8])]) -> Result<Vec<(Pubkey, Vec<u8>)>>;
+
+    /// Addresses of the same accounts, without their data (a zero-length
+    /// `dataSlice`).
+    async fn program_keys(&self, program: &Pubkey, memcmp: &[(usize, &[u8])]) -> Result<Vec<Pubkey>>;
+
+    /// One `getProgramAccountsV2` page of at most `limit` matches after
+    /// `cursor`, and the cursor of the next page if there is one.
+    async fn program_accounts_page(
+        &self,
+        program: &Pubkey,
+        memcmp:  &[(usize, &[u8])],
+        limit:   usize,
+        cursor:  Option<String>,
+    ) -> Result<AccountsPage>;
+}
+
+/// How a client scans program accounts — `my_positions`, `my_fees` and
+/// `list_pools`.
+///
+/// Many hosted RPC endpoints cap or reject large `getProgramAccounts`
+/// responses. The paged modes keep every response small; the client's
+/// `with_index_url` can also send scans to an endpoint without those caps
+/// (a private node or a self-hosted indexer) while everything else stays on
+/// the main RPC.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ProgramScan {
+    /// One `getProgramAccounts` returning every match with its data.
+    #[default]
+    Single,
+    /// `getProgramAccounts` for the addresses only, then their data through
+    /// `getMultipleAccounts`, `page_size` accounts (at most 100) per call.
+    Paged { page_size: usize },
+    /// Cursor-paginated `getProgramAccountsV2` (Helius), `limit` matches per page.
+    Cursor { limit: usize },
+}
+
+/// `getProgramAccounts` `memcmp` filters for `(offset, bytes)` pairs.
+pub(crate) fn memcmp_filters(memcmp: &[(usize, &[u8])]) -> Vec<Value> {
+    memcmp
+        .iter()
+        .map(|(offset, bytes)| json!({
+            "memcmp": { "offset": offset, "bytes": BASE64.encode(bytes), "encoding": "base64" },
+        }))
+        .collect()
+}
+
+/// `getProgramAccountsV2` params for one page.
+pub(crate) fn accounts_page_params(
+    program: &Pubkey,
+    memcmp:  &[(usize, &[u8])],
+    limit:   usize,
+    cursor:  Option<String>,
+) -> Value {
+    let mut config = json!({
+        "encoding": "base64", "commitment": "confirmed", "filters": memcmp_filters(memcmp), "limit": limit,
+    });
+    if let Some(cursor) = cursor {
+        config["paginationKey"] = json!(cursor);
+    }
+    json!([program.to_string(), config])
+}
+
+/// Matches and next cursor of a `getProgramAccountsV2` result; `None` if it
+/// is malformed.
+pub(crate) fn parse_accounts_page(result: &Value) -> Option<AccountsPage> {
+    let accounts = result["accounts"]
+        .as_array()?
+        .iter()
+        .map(|entry| {
+            let key = entry["pubkey"].as_str()?.parse().ok()?;
+            let data = BASE64.decode(entry["account"]["data"][0].as_str()?).ok()?;
+            Some((key, data))
+        })
+        .collect::<Option<_>>()?;
+    Some((accounts, result["paginationKey"].as_str().map(str::to_string)))
+}
+
+/// Every account of `program` matching `memcmp`, scanned as `scan` says.
+#[cfg_attr(feature = "tracing", tracing::instrument(
+    name = "a2a_swap.scan_program", level = "debug", skip_all, err,
+    fields(scan = ?scan, accounts = tracing::field::Empty),
+))]
+pub(crate) async fn scan_program(
+    reader:  &impl AccountReader,
+    program: &Pubkey,
+    memcmp:  &[(usize, &[u8])],
+    scan:    ProgramScan,
+) -> Result<Vec<(Pubkey, Vec<u8>)>> {
+    let accounts = match scan {
+        ProgramScan::Single => reader.program_accounts(program, memcmp).await?,
+        ProgramScan::Paged { page_size } => {
+            let keys = reader.program_keys(program, memcmp).await?;
+            let mut accounts = Vec::with_capacity(keys.len());
+            for page in keys.chunks(page_size.clamp(1, MAX_MULTIPLE_ACCOUNTS)) {
+                let data = reader.multiple_account_data(page).await?;
+                // Closed or rewritten since the address scan: drop what no longer matches.
+                accounts.extend(page.iter()
+                    .zip(data)
+                    .filter_map(|(key, data)| Some((*key, data?)))
+                    .filter(|(_, data)| memcmp.iter().all(|(offset, bytes)| {
+                        data.get(*offset..offset + bytes.len()) == Some(*bytes)
+                    })));
+            }
+            accounts
+        }
+        ProgramScan::Cursor { limit } => {
+            let mut accounts = Vec::new();
+            let mut cursor = None;
+            loop {
+                let (page, next) = reader.program_accounts_page(program, memcmp, limit.max(1), cursor).await?;
+                accounts.extend(page);
+                match next {
+                    Some(next) => cursor = Some(next),
+                    None => break,
+                }
+            }
+            accounts
+        }
+    };
+    trace::record("accounts", accounts.len());
+    Ok(accounts)
+}
+
+/// A constant-product pool with the reserves it prices against and the
+/// live protocol fees — everything a quote reads.
+pub(crate) struct PoolQuote {
+    pub pool:               Pubkey,
+    pub state:              PoolState,
+    /// `mint_in` is the pool's `token_a_mint`.
+    pub a_to_b:             bool,
+    pub reserve_a:          u64,
+    pub reserve_b:          u64,
+    pub protocol_fee_bps:   u64,
+    #[cfg_attr(not(feature = "rpc"), allow(dead_code))]
+    pub referral_share_bps: u64,
+}
+
+impl PoolQuote {
+    /// `(reserve_in, reserve_out)` in swap direction.
+    pub fn reserves_in_out(&self) -> (u64, u64) {
+        if self.a_to_b { (self.reserve_a, self.reserve_b) } else { (self.reserve_b, self.reserve_a) }
+    }
+}
+
+/// The constant-product pool for a mint pair, in either PDA ordering, with
+/// its reserves and the protocol fees.
+///
+/// Both pool PDAs and the treasury come back from one `getMultipleAccounts`;
+/// a pool whose reserves are not tracked yet costs one more for its vaults,
+/// whose addresses are only known from the pool account.
+#[cfg_attr(feature = "tracing", tracing::instrument(
+    name = "a2a_swap.find_pool", level = "debug", skip_all, err,
+    fields(mint_in = %mint_in, mint_out = %mint_out, pool = tracing::field::Empty, a_to_b = tracing::field::Empty),
+))]
+pub(crate) async fn find_pool(
+    reader:     &impl AccountReader,
+    program_id: &Pubkey,
+    mint_in:    &Pubkey,
+    mint_out:   &Pubkey,
+) -> Result<PoolQuote> {
+    let (pool_ab, _) = derive_pool(mint_in, mint_out, program_id);
+    let (pool_ba, _) = derive_pool(mint_out, mint_in, program_id);
+    let (treasury, _) = derive_treasury(program_id);
+    let mut accounts = reader.multiple_account_data(&[pool_ab, pool_ba, treasury]).await?.into_iter();
+    let (ab, ba, config) = (accounts.next().flatten(), accounts.next().flatten(), accounts.next().flatten());
+
+    let (pool, state, a_to_b) = [(pool_ab, ab, true), (pool_ba, ba, false)]
+        .into_iter()
+        .find_map(|(pool, data, a_to_b)| Some((pool, parse_pool(&data?).ok()?, a_to_b)))
+        .ok_or(Error::PoolNotFound(*mint_in, *mint_out))?;
+    trace::record("pool", pool);
+    trace::record("a_to_b", a_to_b);
+
+    let (protocol_fee_bps, referral_share_bps) = protocol_fees(config.as_deref())?;
+    let (reserve_a, reserve_b) = fetch_reserves(reader, &pool, &state).await?;
+    Ok(PoolQuote { pool, state, a_to_b, reserve_a, reserve_b, protocol_fee_bps, referral_share_bps })
+}
+
+/// `(reserve_a, reserve_b)` the program prices against — see
+/// [`PoolState::reserves`]. Both vaults are read in one call, and only for
+/// untracked reserves.
+#[cfg_attr(feature = "tracing", tracing::instrument(
+    name = "a2a_swap.fetch_reserves", level = "debug", skip_all, err,
+    fields(pool = %pool, reserve_a = tracing::field::Empty, reserve_b = tracing::field::Empty),
+))]
+#[cfg_attr(not(feature = "tracing"), allow(unused_variables))]
+async fn fetch_reserves(
+    reader: &impl AccountReader,
+    pool:   &Pubkey,
+    state:  &PoolState,
+) -> Result<(u64, u64)> {
+    let (reserve_a, reserve_b) = if !state.reserves_tracked {
+        let vaults = reader.multiple_account_data(&[state.token_a_vault, state.token_b_vault]).await?;
+        let vault = |i: usize| parse_token_amount(vaults.get(i).and_then(Option::as_deref).unwrap_or_default());
+        state.reserves(vault(0)?, vault(1)?)
+    } else {
+        (state.reserve_a, state.reserve_b)
+    };
+    trace::record("reserve_a", reserve_a);
+    trace::record("reserve_b", reserve_b);
+    Ok((reserve_a, reserve_b))
+}
+
+/// Live `(protocol_fee_bps, referral_share_bps)` from the `ProtocolConfig`
+/// at the treasury PDA — see [`protocol_fees`].
+pub(crate) async fn fetch_protocol_fees(reader: &impl AccountReader, program_id: &Pubkey) -> Result<(u64, u64)> {
+    let (treasury, _) = derive_treasury(program_id);
+    let config = reader.multiple_account_data(&[treasury]).await?.pop().flatten();
+    protocol_fees(config.as_deref())
+}
+
+/// `(protocol_fee_bps, referral_share_bps)` from the treasury's account
+/// data, or [`PROTOCOL_FEE_BPS`] / [`REFERRAL_SHARE_BPS`] while that account
+/// has not been created.
+fn protocol_fees(treasury: Option<&[u8]>) -> Result<(u64, u64)> {
+    match treasury {
+        Some(data) if !data.is_empty() => {
+            let config = parse_protocol_config(data)?;
+            Ok((config.protocol_fee_bps as u64, config.referral_share_bps as u64))
+        }
+        _ => Ok((PROTOCOL_FEE_BPS as u64, REFERRAL_SHARE_BPS as u64)),
+    }
+}
+
+/// Pool states for `keys` with their reserves; pools that don't parse are
+/// left out. Vaults are read, in a second call, only for pools whose
+/// reserves are untracked.
+pub(crate) async fn fetch_pools_with_reserves(
+    reader: &impl AccountReader,
+    keys:   &[Pubkey],
+) -> Result<HashMap<Pubkey, (PoolState, u64, u64)>> {
+    if keys.is_empty() {
+        return Ok(HashMap::new());
+    }
+    let accounts = reader.multiple_account_data(keys).await?;
+    let states: Vec<(Pubkey, PoolState)> = keys
+        .iter()
+        .zip(accounts)
+        .filter_map(|(k, data)| parse_pool(&data?).ok().map(|p| (*k, p)))
+        .collect();
+    Ok(with_reserves(reader, states).await?.into_iter().collect())
+}
+
+/// `states` with their reserves, in order; vaults are read in pages, only
+/// for pools whose reserves are untracked.
+async fn with_reserves(
+    reader: &impl AccountReader,
+    states: Vec<(Pubkey, PoolState)>,
+) -> Result<Vec<(Pubkey, (PoolState, u64, u64))>> {
+    let untracked = |s: &PoolState| !s.reserves_tracked;
+    let vaults: Vec<Pubkey> = states
+        .iter()
+        .filter(|(_, s)| untracked(s))
+        .flat_map(|(_, s)| [s.token_a_vault, s.token_b_vault])
+        .collect();
+    let mut vault_accounts = Vec::with_capacity(vaults.len());
+    for page in vaults.chunks(MAX_MULTIPLE_ACCOUNTS) {
+        vault_accounts.extend(reader.multiple_account_data(page).await?);
+    }
+    let mut vault_reserves = vault_accounts
+        .iter()
+        .map(|data| data.as_deref().and_then(|d| parse_token_amount(d).ok()).unwrap_or(0));
+
+    Ok(states
+        .into_iter()
+        .map(|(key, state)| {
+            let (vault_a, vault_b) = if untracked(&state) {
+                (vault_reserves.next().unwrap_or(0), vault_reserves.next().unwrap_or(0))
+            } else {
+                (0, 0)
+            };
+            let (reserve_a, reserve_b) = state.reserves(vault_a, vault_b);
+            (key, (state, reserve_a, reserve_b))
+        })
+        .collect())
+}
+
+/// Every position account owned by `owner`.
+#[cfg_attr(feature = "tracing", tracing::instrument(
+    name = "a2a_swap.fetch_positions", level = "debug", skip_all, err, fields(owner = %owner),
+))]
+pub(crate) async fn fetch_positions(
+    reader:     &impl AccountReader,
+    program_id: &Pubkey,
+    owner:      &Pubkey,
+    scan:       ProgramScan,
+) -> Result<Vec<(Pubkey, PositionState)>> {
+    // No size filter: positions are 139 bytes, or 138 until `migrate_position` runs.
+    let filters = [(0, &a2a_swap_core::Position::DISCRIMINATOR[..]), (8, owner.as_ref())];
+    Ok(scan_program(reader, program_id, &filters, scan)
+        .await?
+        .into_iter()
+        .filter_map(|(pk, data)| parse_position(&data).ok().map(|p| (pk, p)))
+        .collect())
+}
+
+/// Off-chain quote for a swap through the pair's pool.
+pub(crate) async fn simulate(
+    reader:     &impl AccountReader,
+    program_id: &Pubkey,
+    params:     SimulateParams,
+) -> Result<SimulateResult> {
+    let quote = find_pool(reader, program_id, &params.mint_in, &params.mint_out).await?;
+    let (reserve_in, reserve_out) = quote.reserves_in_out();
+    simulate_detailed(
+        quote.pool, &quote.state, reserve_in, reserve_out, params.amount_in, quote.protocol_fee_bps,
+        quote.a_to_b,
+    )
+}
+
+/// Off-chain quotes for several input sizes against one read of the pair's
+/// pool, in the order given.
+pub(crate) async fn simulate_ladder(
+    reader:     &impl AccountReader,
+    program_id: &Pubkey,
+    mint_in:    &Pubkey,
+    mint_out:   &Pubkey,
+    amounts:    &[u64],
+) -> Result<Vec<SimulateResult>> {
+    if amounts.is_empty() {
+        return Err(Error::InvalidArgument("amounts must not be empty".into()));
+    }
+    let quote = find_pool(reader, program_id, mint_in, mint_out).await?;
+    let (reserve_in, reserve_out) = quote.reserves_in_out();
+    amounts
+        .iter()
+        .map(|&amount_in| simulate_detailed(
+            quote.pool, &quote.state, reserve_in, reserve_out, amount_in, quote.protocol_fee_bps,
+            quote.a_to_b,
+        ))
+        .collect()
+}
+
+/// Expected outcome of the deposit `params` describe, against one read of
+/// the pair's pool. Amounts come back in pool ordering.
+pub(crate) async fn simulate_provide(
+    reader:     &impl AccountReader,
+    program_id: &Pubkey,
+    params:     &ProvideParams,
+) -> Result<SimulateProvideResult> {
+    let quote = find_pool(reader, program_id, &params.mint_a, &params.mint_b).await?;
+    let (reserve_fixed, reserve_other) = quote.reserves_in_out();
+    let other = compute_amount_b(
+        params.amount_a, params.amount_b, reserve_fixed, reserve_other, quote.state.lp_supply,
+    )?;
+    let (amount_a, amount_b) = if quote.a_to_b { (params.amount_a, other) } else { (other, params.amount_a) };
+    simulate_provide_detailed(quote.pool, &quote.state, quote.reserve_a, quote.reserve_b, amount_a, amount_b)
+}
+
+/// Expected outcome of burning `lp_shares` of the pair's pool. Amounts come
+/// back in pool ordering.
+pub(crate) async fn simulate_remove(
+    reader:     &impl AccountReader,
+    program_id: &Pubkey,
+    mint_a:     &Pubkey,
+    mint_b:     &Pubkey,
+    lp_shares:  u64,
+) -> Result<SimulateRemoveResult> {
+    let quote = find_pool(reader, program_id, mint_a, mint_b).await?;
+    simulate_remove_detailed(quote.pool, &quote.state, quote.reserve_a, quote.reserve_b, lp_shares)
+}
+
+/// Pool state plus current reserves and spot price.
+pub(crate) async fn pool_info(
+    reader:     &impl AccountReader,
+    program_id: &Pubkey,
+    mint_a:     &Pubkey,
+    mint_b:     &Pubkey,
+) -> Result<PoolInfo> {
+    let PoolQuote { pool, state, reserve_a, reserve_b, protocol_fee_bps, .. } =
+        find_pool(reader, program_id, mint_a, mint_b).await?;
+    Ok(to_pool_info(pool, &state, reserve_a, reserve_b, protocol_fee_bps))
+}
+
+/// [`pool_info`] with `tvl` valued in `quote_mint`: each side priced through
+/// its direct pool against the quote, or through this pool when it pairs
+/// with the quote. One more call for those pools.
+pub(crate) async fn pool_info_in(
+    reader:     &impl AccountReader,
+    program_id: &Pubkey,
+    mint_a:     &Pubkey,
+    mint_b:     &Pubkey,
+    quote_mint: &Pubkey,
+) -> Result<PoolInfo> {
+    let mut info = pool_info(reader, program_id, mint_a, mint_b).await?;
+    let candidates: Vec<Pubkey> = [info.mint_a, info.mint_b]
+        .iter()
+        .filter(|m| *m != quote_mint)
+        .flat_map(|m| [derive_pool(m, quote_mint, program_id).0, derive_pool(quote_mint, m, program_id).0])
+        .filter(|k| *k != info.pool)
+        .collect();
+    let pools = fetch_pools_with_reserves(reader, &candidates).await?;
+
+    // mint → (reserve_asset, reserve_quote)
+    let mut prices: HashMap<Pubkey, (u64, u64)> = HashMap::new();
+    let pairs = pools
+        .values()
+        .map(|(s, reserve_a, reserve_b)| (s.token_a_mint, s.token_b_mint, *reserve_a, *reserve_b))
+        .chain([(info.mint_a, info.mint_b, info.reserve_a, info.reserve_b)]);
+    for (a, b, reserve_a, reserve_b) in pairs {
+        if b == *quote_mint && reserve_a > 0 {
+            prices.insert(a, (reserve_a, reserve_b));
+        } else if a == *quote_mint && reserve_b > 0 {
+            prices.insert(b, (reserve_b, reserve_a));
+        }
+    }
+    let value_of = |mint: &Pubkey, amount: u64| -> Option<u64> {
+        if mint == quote_mint {
+            return Some(amount);
+        }
+        let (reserve_asset, reserve_quote) = prices.get(mint)?;
+        spot_value(amount, *reserve_asset, *reserve_quote)
+    };
+    info.tvl = value_of(&info.mint_a, info.reserve_a)
+        .zip(value_of(&info.mint_b, info.reserve_b))
+        .map(|(value_a, value_b)| PoolTvl {
+            quote_mint: *quote_mint,
+            value_a,
+            value_b,
+            total: value_a.saturating_add(value_b),
+        });
+    Ok(info)
+}
+
+/// Every constant-product pool of the program, by address, with reserves
+/// and spot price. Pools are scanned on `scanner` as `scan` says; their
+/// vaults and the protocol fees are read through `reader`.
+#[cfg_attr(feature = "tracing", tracing::instrument(
+    name = "a2a_swap.list_pools", skip_all, err, fields(accounts = tracing::field::Empty),
+))]
+pub(crate) async fn list_pools(
+    scanner:    &impl AccountReader,
+    reader:     &impl AccountReader,
+    program_id: &Pubkey,
+    scan:       ProgramScan,
+) -> Result<Vec<PoolInfo>> {
+    // No size filter: pools are 269 bytes, or smaller until `migrate_pool` runs.
+    let filters = [(0, &a2a_swap_core::Pool::DISCRIMINATOR[..])];
+    let mut states: Vec<(Pubkey, PoolState)> = scan_program(scanner, program_id, &filters, scan)
+        .await?
+        .into_iter()
+        .filter_map(|(pk, data)| parse_pool(&data).ok().map(|p| (pk, p)))
+        .collect();
+    states.sort_by_key(|(pk, _)| *pk);
+    let (protocol_fee_bps, _) = fetch_protocol_fees(reader, program_id).await?;
+    Ok(with_reserves(reader, states)
+        .await?
+        .into_iter()
+        .map(|(pool, (state, reserve_a, reserve_b))| to_pool_info(pool, &state, reserve_a, reserve_b, protocol_fee_bps))
+        .collect())
+}
+
+fn to_pool_info(pool: Pubkey, state: &PoolState, reserve_a: u64, reserve_b: u64, protocol_fee_bps: u64) -> PoolInfo {
+    PoolInfo {
+        pool,
+        mint_a:       state.token_a_mint,
+        mint_b:       state.token_b_mint,
+        vault_a:      state.token_a_vault,
+        vault_b:      state.token_b_vault,
+        reserve_a,
+        reserve_b,
+        lp_supply:    state.lp_supply,
+        fee_rate_bps: state.fee_rate_bps,
+        effective_fee_bps: effective_fee_bps(state, unix_now()),
+        dynamic_fee:  state.dynamic_fee,
+        circuit_breaker: state.circuit_breaker,
+        permissioned: state.is_permissioned(),
+        gate_mint:    state.is_gated().then_some(state.gate_mint),
+        protocol_fee_bps: protocol_fee_bps as u16,
+        curve:        state.curve,
+        volume_a:     state.volume_a,
+        volume_b:     state.volume_b,
+        fees_collected_a: state.fees_collected_a,
+        fees_collected_b: state.fees_collected_b,
+        spot_price:   spot_price(state.curve, reserve_a, reserve_b),
+        lp_share_value_a: lp_share_value(reserve_a, state.lp_supply),
+        lp_share_value_b: lp_share_value(reserve_b, state.lp_supply),
+        tvl:          None,
+        created_slot: None,
+        created_at:   None,
+    }
+}
+
+/// `positions` filtered, sorted and cut to one page as `query` says, with
+/// pending fees, current price and value; `entry` and `il_pct` are left
+/// `None`. One call for their pools, plus for vaults of untracked pools.
+pub(crate) async fn positions_page(
+    reader:    &impl AccountReader,
+    positions: Vec<(Pubkey, PositionState)>,
+    query:     &PositionQuery,
+) -> Result<PositionPage> {
+    let start = match &query.cursor {
+        Some(cursor) => cursor
+            .parse::<usize>()
+            .map_err(|_| Error::InvalidArgument(format!("invalid position cursor `{cursor}`")))?,
+        None => 0,
+    };
+    if query.limit == Some(0) {
+        return Err(Error::InvalidArgument("limit must be at least 1".into()));
+    }
+
+    let positions: Vec<(Pubkey, PositionState)> = positions
+        .into_iter()
+        .filter(|(_, p)| {
+            query.pool.map_or(true, |pool| p.pool == pool)
+                && p.lp_shares >= query.min_lp_shares
+                && query.auto_compound.map_or(true, |on| p.auto_compound == on)
+        })
+        .collect();
+    let mut infos = position_infos(reader, positions, true).await?;
+    match query.sort {
+        PositionSort::Address => infos.sort_by_key(|p| p.address),
+        PositionSort::PendingFees => {
+            let fees_b = |p: &PositionInfo| {
+                p.total_fees_a as f64 * p.current_price.unwrap_or(0.0) + p.total_fees_b as f64
+            };
+            infos.sort_by(|x, y| fees_b(y).total_cmp(&fees_b(x)));
+        }
+        PositionSort::Value => infos.sort_by_key(|p| std::cmp::Reverse(p.value_b)),
+    }
+
+    let total = infos.len();
+    let start = start.min(total);
+    let end = query.limit.map_or(total, |limit| start.saturating_add(limit).min(total));
+    Ok(PositionPage {
+        positions:   infos.drain(start..end).collect(),
+        total,
+        next_cursor: (end < total).then(|| end.to_string()),
+    })
+}
+
+/// [`PositionInfo`]s for fetched `positions`, with pending fees and, with
+/// `with_price`, each pool's current price; `entry` and `il_pct` are left
+/// `None`. One call for their pools, plus
+/// for vaults when `with_price` needs untracked reserves.
+pub(crate) async fn position_infos(
+    reader:     &impl AccountReader,
+    positions:  Vec<(Pubkey, PositionState)>,
+    with_price: bool,
+) -> Result<Vec<PositionInfo>> {
+    trace::record("positions", positions.len());
+
+    // Batch-fetch unique pool accounts (and, for prices, their vaults).
+    let pool_keys: Vec<Pubkey> = {
+        let mut v: Vec<Pubkey> = positions.iter().map(|(_, p)| p.pool).collect();
+        v.sort();
+        v.dedup();
+        v
+    };
+    let pools: HashMap<Pubkey, (PoolState, u64, u64)> = if with_price {
+        fetch_pools_with_reserves(reader, &pool_keys).await?
+    } else {
+        let pool_accounts = reader.multiple_account_data(&pool_keys).await?;
+        pool_keys
+            .iter()
+            .zip(pool_accounts)
+            .filter_map(|(k, data)| parse_pool(&data?).ok().map(|p| (*k, (p, 0, 0))))
+            .collect()
+    };
+
+    Ok(positions
+        .into_iter()
+        .map(|(addr, pos)| {
+            let pool = pools.get(&pos.pool);
+            let (pending_a, pending_b) = pool
+                .map(|(state, ..)| pending_fees_for_position(&pos, state))
+                .unwrap_or((0, 0));
+            let current_price = pool
+                .filter(|_| with_price)
+                .map(|(_, ra, rb)| if *ra == 0 { 0.0 } else { *rb as f64 / *ra as f64 });
+            let total_fees_a = pos.fees_owed_a.saturating_add(pending_a);
+            let total_fees_b = pos.fees_owed_b.saturating_add(pending_b);
+            let value_b = pool.filter(|_| with_price).and_then(|(state, ra, rb)| {
+                let (amount_a, amount_b) = lp_underlying(pos.lp_shares, state.lp_supply, *ra, *rb);
+                let value_a = spot_value(amount_a.saturating_add(total_fees_a), *ra, *rb)?;
+                Some(value_a.saturating_add(amount_b).saturating_add(total_fees_b))
+            });
+            PositionInfo {
+                address:            addr,
+                pool:               pos.pool,
+                owner:              pos.owner,
+                lp_shares:          pos.lp_shares,
+                fees_owed_a:        pos.fees_owed_a,
+                fees_owed_b:        pos.fees_owed_b,
+                pending_fees_a:     pending_a,
+                pending_fees_b:     pending_b,
+                total_fees_a,
+                total_fees_b,
+                auto_compound:      pos.auto_compound,
+                compound_threshold: pos.compound_threshold,
+                locked:             pos.locked_by.is_some(),
+                locked_by:          pos.locked_by,
+                entry:              None,
+                current_price,
+                il_pct:             None,
+                value_b,
+            }
+        })
+        .collect())
+}
+
+/// The program's `Governance` account; `InvalidArgument` until
+/// `initialize_governance` has run.
+pub(crate) async fn fetch_governance(reader: &impl AccountReader, program_id: &Pubkey) -> Result<GovernanceState> {
+    let (governance, _) = derive_governance(program_id);
+    match reader.multiple_account_data(&[governance]).await?.pop().flatten() {
+        Some(data) if !data.is_empty() => parse_governance(&data),
+        _ => Err(Error::InvalidArgument("governance is not initialized".into())),
+    }
+}
+
+/// `agent`'s fee tier: its `AgentVolume` against the config's fee tiers.
+/// An agent without the account reports zero volume and no tier, as does
+/// every agent before the config exists.
+pub(crate) async fn fetch_tier(
+    reader:     &impl AccountReader,
+    program_id: &Pubkey,
+    agent:      &Pubkey,
+) -> Result<TierInfo> {
+    let (address, _) = derive_agent_volume(agent, program_id);
+    let (treasury, _) = derive_treasury(program_id);
+    let mut data = reader.multiple_account_data(&[address, treasury]).await?.into_iter();
+    let volume = match data.next().flatten() {
+        Some(d) if !d.is_empty() => Some(parse_agent_volume(&d)?),
+        _ => None,
+    };
+    let config = match data.next().flatten() {
+        Some(d) if !d.is_empty() => Some(parse_protocol_config(&d)?),
+        _ => None,
+    };
+
+    let (traded, swaps) = volume.map_or((0, 0), |v| (v.volume, v.swaps));
+    let (tier, discount_bps, protocol_fee_bps, next_tier) = match config {
+        Some(c) => {
+            // Untracked swaps pay the full fee whatever tier 0 requires.
+            let tier = volume.and_then(|v| c.tier_for(v.volume));
+            let discount = tier.map_or(0, |i| c.fee_tiers[i].discount_bps);
+            let fee = if tier.is_some() { c.protocol_fee_bps_for(traded) } else { c.protocol_fee_bps };
+            let next = c
+                .fee_tiers
+                .iter()
+                .filter(|t| t.discount_bps > discount && (tier.is_none() || t.min_volume > traded))
+                .min_by_key(|t| t.min_volume)
+                .copied();
+            (tier.map(|i| i as u8), discount, fee, next)
+        }
+        None => (None, 0, PROTOCOL_FEE_BPS as u16, None),
+    };
+    Ok(TierInfo {
+        agent: *agent,
+        address,
+        tracking: volume.is_some(),
+        volume: traded,
+        swaps,
+        tier,
+        discount_bps,
+        protocol_fee_bps,
+        next_tier,
+    })
+}
+
+/// The nonce `agent`'s next swap intent must carry: 0 until its
+/// `IntentNonce` exists.
+pub(crate) async fn fetch_intent_nonce(reader: &impl AccountReader, program_id: &Pubkey, agent: &Pubkey) -> Result<u64> {
+    let (address, _) = derive_intent_nonce(agent, program_id);
+    match reader.multiple_account_data(&[address]).await?.pop().flatten() {
+        Some(data) if !data.is_empty() => Ok(parse_intent_nonce(&data)?.nonce),
+        _ => Ok(0),
+    }
+}
+
+/// The program's Anchor IDL, read from its on-chain IDL account.
+pub(crate) async fn fetch_idl(reader: &impl AccountReader, program_id: &Pubkey) -> Result<Value> {
+    let address = derive_idl_address(program_id);
+    match reader.multiple_account_data(&[address]).await?.pop().flatten() {
+        Some(data) if !data.is_empty() => parse_idl_account(&data),
+        _ => Err(Error::InvalidArgument(format!("no IDL published for {program_id} (expected at {address})"))),
+    }
+}
+
+/// The program's capability card: its on-chain IDL (or, when none is
+/// published, the one this crate was built with) and live protocol config,
+/// read together.
+pub(crate) async fn fetch_capability_card(reader: &impl AccountReader, program_id: &Pubkey) -> Result<CapabilityCard> {
+    let (treasury, _) = derive_treasury(program_id);
+    let mut data = reader
+        .multiple_account_data(&[derive_idl_address(program_id), treasury])
+        .await?
+        .into_iter();
+    let (idl, on_chain) = match data.next().flatten() {
+        Some(d) if !d.is_empty() => (parse_idl_account(&d)?, true),
+        _ => (bundled_idl(), false),
+    };
+    let config = match data.next().flatten() {
+        Some(d) if !d.is_empty() => Some(parse_protocol_config(&d)?),
+        _ => None,
+    };
+    Ok(CapabilityCard::new(*program_id, &idl, on_chain, config.as_ref()))
+}
+
+/// The IDL `a2a-swap-core` was generated from.
+fn bundled_idl() -> Value {
+    // build.rs of a2a-swap-core already parsed it.
+    serde_json::from_str(a2a_swap_core::IDL_JSON).expect("bundled IDL is JSON")
+}
+
+/// Every governance proposal, newest first, with its outcome under the
+/// governance quorum. Proposals are scanned on `scanner` as `scan` says.
+#[cfg_attr(feature = "tracing", tracing::instrument(
+    name = "a2a_swap.proposals", skip_all, err, fields(accounts = tracing::field::Empty),
+))]
+pub(crate) async fn list_proposals(
+    scanner:    &impl AccountReader,
+    reader:     &impl AccountReader,
+    program_id: &Pubkey,
+    scan:       ProgramScan,
+) -> Result<Vec<ProposalInfo>> {
+    let governance = fetch_governance(reader, program_id).await?;
+    let filters = [(0, &a2a_swap_core::Proposal::DISCRIMINATOR[..])];
+    let now = unix_now();
+    let mut proposals: Vec<ProposalInfo> = scan_program(scanner, program_id, &filters, scan)
+        .await?
+        .into_iter()
+        .filter_map(|(address, data)| parse_proposal(&data).ok().map(|p| ProposalInfo {
+            address,
+            id:             p.id,
+            proposer:       p.proposer,
+            action:         p.action,
+            votes_for:      p.votes_for,
+            votes_against:  p.votes_against,
+            voting_ends_at: p.voting_ends_at,
+            closed:         now >= p.voting_ends_at,
+            passing:        p.passed(governance.quorum),
+            executed:       p.executed,
+        }))
+        .collect();
+    proposals.sort_by(|a, b| b.id.cmp(&a.id));
+    Ok(proposals)
+}
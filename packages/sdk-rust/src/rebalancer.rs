@@ -0,0 +1,260 @@
+//! Keep two holdings at a target value ratio.
+//!
+//! [`plan_rebalance`] values both balances in token B at the pool's spot
+//! price and, once the value share of token A drifts outside the band around
+//! the target, sizes the swap that brings it back.
+//! [`rebalance`](crate::A2ASwapClient::rebalance) reads the wallet and pool,
+//! plans, and sends the swap through [`convert`](crate::A2ASwapClient::convert);
+//! [`rebalance_every`](crate::A2ASwapClient::rebalance_every) repeats it on a
+//! fixed interval.
+//!
+//! ```rust,no_run
+//! # use a2a_swap_sdk::{rebalancer::RebalanceParams, A2ASwapClient};
+//! # async fn run(client: A2ASwapClient, payer: solana_sdk::signature::Keypair,
+//! #              sol: solana_sdk::pubkey::Pubkey, usdc: solana_sdk::pubkey::Pubkey) -> a2a_swap_sdk::Result<()> {
+//! let params = RebalanceParams {
+//!     mint_a:               sol,
+//!     mint_b:               usdc,
+//!     target:               "50:50".parse()?,
+//!     band_bps:             500,   // act once the SOL share drifts more than 5 points
+//!     max_slippage_bps:     50,
+//!     max_price_impact_bps: 300,
+//!     referrer:             None,
+//! };
+//! let outcome = client.rebalance(&payer, &params).await?;
+//! println!("drift {:.0} bps, swapped: {}", outcome.plan.drift_bps, outcome.swap.is_some());
+//! # Ok(()) }
+//! ```
+//!
+//! The swap is sized at spot, so fees and price impact leave the new ratio
+//! slightly short of the target — well inside any practical band.
+//!
+//! [`RebalanceStrategy`] runs the same logic under a
+//! [`Runner`](crate::strategies::Runner), for dry runs and backtests.
+
+use std::fmt;
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+use solana_sdk::pubkey::Pubkey;
+
+use crate::error::{Error, Result};
+#[cfg(feature = "rpc")]
+use crate::strategies::{Action, Fill, PoolUpdate, Strategy};
+use crate::types::{ProtectionLevel, SwapParams, SwapResult};
+
+/// Basis points in a whole.
+const BPS: f64 = 10_000.0;
+
+/// Target value ratio between token A and token B, e.g. `50:50` or `70:30`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TargetRatio {
+    /// Weight of token A.
+    pub weight_a: u32,
+    /// Weight of token B.
+    pub weight_b: u32,
+}
+
+impl TargetRatio {
+    /// Token A's target share of the combined value, in basis points.
+    pub fn share_a_bps(self) -> f64 {
+        self.weight_a as f64 * BPS / (self.weight_a as f64 + self.weight_b as f64)
+    }
+}
+
+impl FromStr for TargetRatio {
+    type Err = Error;
+
+    /// Parse `A:B` with non-negative integer weights, not both zero.
+    fn from_str(s: &str) -> Result<Self> {
+        let bad = || Error::InvalidArgument(format!("target ratio '{s}' — use A:B, e.g. 50:50 or 70:30"));
+        let (a, b) = s.split_once(':').ok_or_else(bad)?;
+        let weight_a = a.trim().parse().map_err(|_| bad())?;
+        let weight_b = b.trim().parse().map_err(|_| bad())?;
+        if weight_a == 0 && weight_b == 0 {
+            return Err(bad());
+        }
+        Ok(Self { weight_a, weight_b })
+    }
+}
+
+impl fmt::Display for TargetRatio {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}", self.weight_a, self.weight_b)
+    }
+}
+
+/// Parameters for [`A2ASwapClient::rebalance`](crate::A2ASwapClient::rebalance).
+#[derive(Debug, Clone)]
+pub struct RebalanceParams {
+    /// First holding. Native SOL and wSOL both count toward a wSOL mint.
+    pub mint_a: Pubkey,
+    /// Second holding; a pool must pair it with `mint_a`.
+    pub mint_b: Pubkey,
+    /// Target value ratio of `mint_a` to `mint_b`.
+    pub target: TargetRatio,
+    /// Tolerated drift of token A's value share from the target, in basis
+    /// points of the combined value (`500` = 5 points). No swap inside it.
+    pub band_bps: u16,
+    /// Slippage guard for the rebalancing swap, as in
+    /// [`SwapParams::max_slippage_bps`](crate::SwapParams::max_slippage_bps).
+    pub max_slippage_bps: u16,
+    /// Price-impact cap for the rebalancing swap (`0` = no cap).
+    pub max_price_impact_bps: u16,
+    /// Integrator wallet for the referral share of the protocol fee.
+    pub referrer: Option<Pubkey>,
+}
+
+impl RebalanceParams {
+    /// The swap that executes `trade`.
+    pub fn swap_params(&self, trade: RebalanceTrade) -> SwapParams {
+        let (mint_in, mint_out) = if trade.a_to_b {
+            (self.mint_a, self.mint_b)
+        } else {
+            (self.mint_b, self.mint_a)
+        };
+        SwapParams {
+            mint_in,
+            mint_out,
+            amount_in:            trade.amount_in,
+            max_slippage_bps:     self.max_slippage_bps,
+            min_amount_out:       None,
+            max_price_impact_bps: self.max_price_impact_bps,
+            deadline_unix:        0,
+            referrer:             self.referrer,
+            protection:           ProtectionLevel::None,
+        }
+    }
+}
+
+/// Swap that restores the target ratio.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RebalanceTrade {
+    /// `true` sells token A for token B.
+    pub a_to_b: bool,
+    /// Amount of the sold token, in its atomic units.
+    pub amount_in: u64,
+}
+
+/// Where the holdings stand against the target — see [`plan_rebalance`].
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct RebalancePlan {
+    /// Token A's balance valued in token B atomic units.
+    pub value_a: f64,
+    /// Token B's balance.
+    pub value_b: f64,
+    /// Token A's current share of the combined value, in basis points.
+    pub share_a_bps: f64,
+    /// Token A's target share, in basis points.
+    pub target_share_a_bps: f64,
+    /// `share_a_bps − target_share_a_bps`: positive when A is overweight.
+    pub drift_bps: f64,
+    /// The swap to send, or `None` while the drift is inside the band.
+    pub trade: Option<RebalanceTrade>,
+}
+
+/// Result of one [`rebalance`](crate::A2ASwapClient::rebalance) round.
+#[derive(Debug, Clone)]
+pub struct RebalanceOutcome {
+    /// The plan the round acted on.
+    pub plan: RebalancePlan,
+    /// The confirmed swap, if the drift was outside the band.
+    pub swap: Option<SwapResult>,
+}
+
+/// Plan a rebalance of `balance_a` / `balance_b` at `price` token B atomic
+/// units per token A atomic unit (the pool's spot price).
+///
+/// Trades only when `|drift_bps| > band_bps`, selling the overweight token
+/// for exactly the value that moves the share back to the target at `price`.
+/// [`Error::NoLiquidity`] when `price` is not a positive finite number;
+/// [`Error::InvalidArgument`] when `band_bps` is above `10_000`.
+pub fn plan_rebalance(
+    balance_a: u64,
+    balance_b: u64,
+    price:     f64,
+    target:    TargetRatio,
+    band_bps:  u16,
+) -> Result<RebalancePlan> {
+    if !(price.is_finite() && price > 0.0) {
+        return Err(Error::NoLiquidity);
+    }
+    if band_bps as f64 > BPS {
+        return Err(Error::InvalidArgument(format!("band_bps {band_bps} must be at most 10000")));
+    }
+
+    let value_a = balance_a as f64 * price;
+    let value_b = balance_b as f64;
+    let total = value_a + value_b;
+    let target_share_a_bps = target.share_a_bps();
+    if total == 0.0 {
+        return Ok(RebalancePlan {
+            value_a, value_b, share_a_bps: 0.0, target_share_a_bps, drift_bps: 0.0, trade: None,
+        });
+    }
+    let share_a_bps = value_a * BPS / total;
+    let drift_bps = share_a_bps - target_share_a_bps;
+
+    let trade = if drift_bps.abs() <= band_bps as f64 {
+        None
+    } else {
+        // Value (in token B units) to move from the overweight side.
+        let excess = drift_bps.abs() / BPS * total;
+        let (a_to_b, amount_in) = if drift_bps > 0.0 {
+            (true, (excess / price).min(balance_a as f64) as u64)
+        } else {
+            (false, excess.min(balance_b as f64) as u64)
+        };
+        (amount_in > 0).then_some(RebalanceTrade { a_to_b, amount_in })
+    };
+
+    Ok(RebalancePlan { value_a, value_b, share_a_bps, target_share_a_bps, drift_bps, trade })
+}
+
+/// Rebalancing as a [`Strategy`] over balances it keeps itself.
+///
+/// Starts from the given balances and moves them by each fill, since a
+/// [`PoolUpdate`] carries no wallet state. Reset `balance_a` / `balance_b`
+/// when the wallet changes outside the runner.
+#[cfg(feature = "rpc")]
+#[derive(Debug, Clone)]
+pub struct RebalanceStrategy {
+    pub params: RebalanceParams,
+    pub balance_a: u64,
+    pub balance_b: u64,
+}
+
+#[cfg(feature = "rpc")]
+impl RebalanceStrategy {
+    pub fn new(params: RebalanceParams, balance_a: u64, balance_b: u64) -> Self {
+        Self { params, balance_a, balance_b }
+    }
+}
+
+#[cfg(feature = "rpc")]
+impl Strategy for RebalanceStrategy {
+    fn name(&self) -> &str {
+        "rebalance"
+    }
+
+    fn on_tick(&mut self, update: &PoolUpdate) -> Vec<Action> {
+        let Some(price) = update.price_of(self.params.mint_a) else {
+            return Vec::new();
+        };
+        match plan_rebalance(self.balance_a, self.balance_b, price, self.params.target, self.params.band_bps) {
+            Ok(RebalancePlan { trade: Some(trade), .. }) => vec![Action::Swap(self.params.swap_params(trade))],
+            _ => Vec::new(),
+        }
+    }
+
+    fn on_fill(&mut self, action: &Action, fill: &Fill) {
+        let Action::Swap(swap) = action;
+        if swap.mint_in == self.params.mint_a {
+            self.balance_a = self.balance_a.saturating_sub(fill.amount_in);
+            self.balance_b = self.balance_b.saturating_add(fill.amount_out);
+        } else {
+            self.balance_b = self.balance_b.saturating_sub(fill.amount_in);
+            self.balance_a = self.balance_a.saturating_add(fill.amount_out);
+        }
+    }
+}
@@ -0,0 +1,449 @@
+//! Signers whose keys live outside this process.
+//!
+//! [`RemoteSigner`] implements [`Signer`], so it signs wherever a
+//! [`Keypair`](solana_sdk::signature::Keypair) would — most usefully as the
+//! approver co-signing an `approve_and_execute` from cold storage. Pick one
+//! by URI with [`RemoteSigner::from_uri`]:
+//!
+//! | URI | Signer |
+//! |-----|--------|
+//! | `ledger://` | [`LedgerSigner`] at `m/44'/501'` |
+//! | `ledger://?key=0` | … at `m/44'/501'/0'` |
+//! | `ledger://?key=0/1` | … at `m/44'/501'/0'/1'` |
+//! | `turnkey://ORGANIZATION_ID/ADDRESS` | [`turnkey::TurnkeySigner`], API key from `TURNKEY_API_PRIVATE_KEY` (feature `turnkey`) |
+//! | `privy://WALLET_ID` | [`privy::PrivySigner`], app credentials from `PRIVY_APP_ID` / `PRIVY_APP_SECRET` (feature `privy`) |
+//!
+//! [`LedgerSigner`] talks to the Ledger Solana app (1.0 or later) over USB
+//! HID. Every signature is confirmed on the device, and swaps through this
+//! program are not decoded by the app, so "blind signing" must be enabled
+//! in its settings. HID access uses Linux `hidraw` (`/dev/hidraw*`; the
+//! udev rules from Ledger grant it); on other platforms supply a
+//! [`HidDevice`] to [`LedgerSigner::with_device`].
+//!
+//! The Turnkey and Privy backends keep the key in the provider's enclave
+//! and sign over HTTPS, so a hosted agent holds only API credentials. Any
+//! policy the provider enforces applies to every transaction. Like all
+//! signers they go straight into the client:
+//!
+//! ```rust,ignore
+//! let agent = RemoteSigner::from_uri("privy://my-wallet-id")?;
+//! client.convert(&agent, params).await?;
+//! ```
+//!
+//! ```rust,no_run
+//! # use a2a_swap_sdk::signer::RemoteSigner;
+//! # use solana_sdk::{signer::Signer, transaction::Transaction};
+//! # fn run(mut tx: Transaction) -> Result<(), Box<dyn std::error::Error>> {
+//! let approver = RemoteSigner::from_uri("ledger://?key=0")?;
+//! tx.try_partial_sign(&[&approver], tx.message.recent_blockhash)?;
+//! # Ok(())
+//! # }
+//! ```
+
+use std::fmt;
+use std::sync::Mutex;
+
+#[cfg(feature = "privy")]
+pub mod privy;
+#[cfg(feature = "turnkey")]
+pub mod turnkey;
+
+use solana_sdk::{
+    pubkey::Pubkey,
+    signature::Signature,
+    signer::{Signer, SignerError},
+};
+
+// ─── URI selection ────────────────────────────────────────────────────────────
+
+/// A signer chosen by URI — see the [module docs](self).
+#[derive(Debug)]
+pub enum RemoteSigner {
+    Ledger(LedgerSigner),
+    #[cfg(feature = "turnkey")]
+    Turnkey(Box<turnkey::TurnkeySigner>),
+    #[cfg(feature = "privy")]
+    Privy(privy::PrivySigner),
+}
+
+impl RemoteSigner {
+    /// Connect to the signer `uri` names.
+    pub fn from_uri(uri: &str) -> Result<Self, SignerError> {
+        let unsupported = || SignerError::InvalidInput(format!(
+            "unsupported signer `{uri}` (expected ledger://[?key=ACCOUNT[/CHANGE]], \
+             turnkey://ORGANIZATION_ID/ADDRESS or privy://WALLET_ID)"
+        ));
+        let (scheme, rest) = uri.split_once("://").ok_or_else(unsupported)?;
+        match scheme {
+            "ledger" => Ok(RemoteSigner::Ledger(LedgerSigner::open(&parse_ledger_path(rest)?)?)),
+            #[cfg(feature = "turnkey")]
+            "turnkey" => Ok(RemoteSigner::Turnkey(Box::new(turnkey::TurnkeySigner::from_uri(rest)?))),
+            #[cfg(not(feature = "turnkey"))]
+            "turnkey" => Err(missing_feature("turnkey")),
+            #[cfg(feature = "privy")]
+            "privy" => Ok(RemoteSigner::Privy(privy::PrivySigner::from_uri(rest)?)),
+            #[cfg(not(feature = "privy"))]
+            "privy" => Err(missing_feature("privy")),
+            _ => Err(unsupported()),
+        }
+    }
+
+    fn inner(&self) -> &dyn Signer {
+        match self {
+            RemoteSigner::Ledger(l) => l,
+            #[cfg(feature = "turnkey")]
+            RemoteSigner::Turnkey(t) => t.as_ref(),
+            #[cfg(feature = "privy")]
+            RemoteSigner::Privy(p) => p,
+        }
+    }
+}
+
+#[cfg(not(all(feature = "turnkey", feature = "privy")))]
+fn missing_feature(name: &str) -> SignerError {
+    SignerError::InvalidInput(format!("{name}:// signers need a2a-swap-sdk's `{name}` feature"))
+}
+
+impl Signer for RemoteSigner {
+    fn try_pubkey(&self) -> Result<Pubkey, SignerError> {
+        self.inner().try_pubkey()
+    }
+
+    fn try_sign_message(&self, message: &[u8]) -> Result<Signature, SignerError> {
+        self.inner().try_sign_message(message)
+    }
+
+    fn is_interactive(&self) -> bool {
+        self.inner().is_interactive()
+    }
+}
+
+/// BIP-44 path of a `ledger://` URI after the scheme: `m/44'/501'`, then the
+/// `key=` components, all hardened.
+fn parse_ledger_path(rest: &str) -> Result<Vec<u32>, SignerError> {
+    let rest = rest.trim_start_matches('/');
+    let mut path = vec![harden(44), harden(501)];
+    if rest.is_empty() {
+        return Ok(path);
+    }
+    let bad = || SignerError::InvalidInput(format!("ledger://{rest}: expected ?key=ACCOUNT[/CHANGE]"));
+    let key = rest.strip_prefix("?key=").ok_or_else(bad)?;
+    for part in key.trim_end_matches('\'').split('/') {
+        let index: u32 = part.trim_end_matches('\'').parse().map_err(|_| bad())?;
+        if index >= HARDENED || path.len() == 4 {
+            return Err(bad());
+        }
+        path.push(harden(index));
+    }
+    Ok(path)
+}
+
+const HARDENED: u32 = 0x8000_0000;
+
+fn harden(index: u32) -> u32 {
+    index | HARDENED
+}
+
+// ─── HTTP signers ─────────────────────────────────────────────────────────────
+
+/// Run an HTTP signing request to completion from the synchronous
+/// [`Signer`] methods. It gets its own thread and runtime, so this works
+/// whether or not the caller is itself inside a Tokio runtime.
+#[cfg(any(feature = "turnkey", feature = "privy"))]
+fn block_on<T, F>(request: impl FnOnce() -> F + Send) -> Result<T, SignerError>
+where
+    T: Send,
+    F: std::future::Future<Output = Result<T, SignerError>>,
+{
+    std::thread::scope(|scope| {
+        scope.spawn(|| {
+            tokio::runtime::Builder::new_current_thread()
+                .enable_all()
+                .build()
+                .map_err(|e| SignerError::Custom(format!("signer runtime: {e}")))?
+                .block_on(request())
+        })
+        .join()
+        .unwrap_or_else(|_| Err(SignerError::Custom("signer thread panicked".into())))
+    })
+}
+
+/// HTTP client for the signer backends. Each signature runs on a fresh
+/// runtime (see [`block_on`]), so pooled connections would outlive theirs.
+#[cfg(any(feature = "turnkey", feature = "privy"))]
+fn http_client() -> reqwest::Client {
+    reqwest::Client::builder().pool_max_idle_per_host(0).build().expect("HTTP client")
+}
+
+/// Read an HTTP reply, mapping transport failures and non-2xx statuses.
+#[cfg(any(feature = "turnkey", feature = "privy"))]
+async fn reply_json(provider: &str, sent: reqwest::Result<reqwest::Response>) -> Result<serde_json::Value, SignerError> {
+    let response = sent.map_err(|e| SignerError::Connection(format!("{provider}: {e}")))?;
+    let status = response.status();
+    let body = response.text().await.map_err(|e| SignerError::Connection(format!("{provider}: {e}")))?;
+    if !status.is_success() {
+        return Err(SignerError::Custom(format!("{provider} returned {status}: {}", body.trim())));
+    }
+    serde_json::from_str(&body).map_err(|e| SignerError::Protocol(format!("{provider} reply: {e}")))
+}
+
+// ─── Ledger ───────────────────────────────────────────────────────────────────
+
+/// USB vendor id of Ledger devices.
+pub const LEDGER_VID: u16 = 0x2c97;
+
+/// One USB HID endpoint exchanging 64-byte reports.
+pub trait HidDevice: Send {
+    fn write(&mut self, report: &[u8; HID_REPORT_LEN]) -> std::io::Result<()>;
+    fn read(&mut self) -> std::io::Result<[u8; HID_REPORT_LEN]>;
+}
+
+pub const HID_REPORT_LEN: usize = 64;
+
+// Solana app APDUs.
+const CLA: u8 = 0xe0;
+const INS_GET_PUBKEY: u8 = 0x05;
+const INS_SIGN_MESSAGE: u8 = 0x06;
+const P1_NON_CONFIRM: u8 = 0x00;
+const P1_CONFIRM: u8 = 0x01;
+const P2_EXTEND: u8 = 0x01;
+const P2_MORE: u8 = 0x02;
+const MAX_CHUNK: usize = 255;
+
+// Ledger HID transport framing.
+const CHANNEL: u16 = 0x0101;
+const TAG_APDU: u8 = 0x05;
+
+/// The Ledger Solana app at one derivation path — see the
+/// [module docs](self).
+pub struct LedgerSigner {
+    device: Mutex<Box<dyn HidDevice>>,
+    path:   Vec<u32>,
+    pubkey: Pubkey,
+}
+
+impl fmt::Debug for LedgerSigner {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("LedgerSigner").field("path", &self.path).field("pubkey", &self.pubkey).finish()
+    }
+}
+
+impl LedgerSigner {
+    /// Open the first Ledger on a `hidraw` node and read the key at `path`
+    /// (BIP-44 indices, hardened bit included).
+    pub fn open(path: &[u32]) -> Result<Self, SignerError> {
+        Self::with_device(hidraw::open_ledger()?, path)
+    }
+
+    /// Use `device` as the Ledger and read the key at `path`.
+    pub fn with_device(device: Box<dyn HidDevice>, path: &[u32]) -> Result<Self, SignerError> {
+        let mut signer = Self { device: Mutex::new(device), path: path.to_vec(), pubkey: Pubkey::default() };
+        let reply = signer.exchange(INS_GET_PUBKEY, P1_NON_CONFIRM, 0, &serialize_path(path))?;
+        signer.pubkey = Pubkey::try_from(reply.as_slice())
+            .map_err(|_| SignerError::Protocol(format!("public key reply is {} bytes", reply.len())))?;
+        Ok(signer)
+    }
+
+    /// Derivation path, hardened bit included.
+    pub fn derivation_path(&self) -> &[u32] {
+        &self.path
+    }
+
+    /// Send one APDU and return its reply data, status word checked.
+    fn exchange(&self, ins: u8, p1: u8, p2: u8, data: &[u8]) -> Result<Vec<u8>, SignerError> {
+        let mut apdu = vec![CLA, ins, p1, p2, data.len() as u8];
+        apdu.extend_from_slice(data);
+        let mut device = self.device.lock().map_err(|_| SignerError::Connection("Ledger lock poisoned".into()))?;
+        for report in frame(&apdu) {
+            device.write(&report).map_err(connection)?;
+        }
+        let mut reply = Unframe::default();
+        let apdu = loop {
+            if let Some(apdu) = reply.push(&device.read().map_err(connection)?)? {
+                break apdu;
+            }
+        };
+        let (data, status) = apdu.split_at(apdu.len().checked_sub(2).ok_or_else(|| {
+            SignerError::Protocol("reply has no status word".into())
+        })?);
+        match u16::from_be_bytes([status[0], status[1]]) {
+            0x9000 => Ok(data.to_vec()),
+            0x6985 => Err(SignerError::UserCancel("rejected on the Ledger".into())),
+            0x5515 => Err(SignerError::Connection("the Ledger is locked — unlock it".into())),
+            0x6511 | 0x6d00 | 0x6e00 | 0x6e01 => {
+                Err(SignerError::Connection("open the Solana app on the Ledger".into()))
+            }
+            sw @ (0x6a80..=0x6a8f | 0x6808) => Err(SignerError::Protocol(format!(
+                "the Solana app refused the message ({sw:#06x}) — enable blind signing in its settings"
+            ))),
+            sw => Err(SignerError::Protocol(format!("Ledger status {sw:#06x}"))),
+        }
+    }
+}
+
+impl Signer for LedgerSigner {
+    fn try_pubkey(&self) -> Result<Pubkey, SignerError> {
+        Ok(self.pubkey)
+    }
+
+    /// Sign `message` on the device, which shows it for confirmation.
+    fn try_sign_message(&self, message: &[u8]) -> Result<Signature, SignerError> {
+        // One signer, its path, then the message, split across APDUs.
+        let mut first = vec![1];
+        first.extend_from_slice(&serialize_path(&self.path));
+        let room = MAX_CHUNK - first.len();
+        let (head, tail) = message.split_at(message.len().min(room));
+        first.extend_from_slice(head);
+
+        let chunks: Vec<&[u8]> = tail.chunks(MAX_CHUNK).collect();
+        let p2 = if chunks.is_empty() { 0 } else { P2_MORE };
+        let mut reply = self.exchange(INS_SIGN_MESSAGE, P1_CONFIRM, p2, &first)?;
+        for (i, chunk) in chunks.iter().enumerate() {
+            let more = if i + 1 < chunks.len() { P2_MORE } else { 0 };
+            reply = self.exchange(INS_SIGN_MESSAGE, P1_CONFIRM, P2_EXTEND | more, chunk)?;
+        }
+        Signature::try_from(reply.as_slice())
+            .map_err(|_| SignerError::Protocol(format!("signature reply is {} bytes", reply.len())))
+    }
+
+    fn is_interactive(&self) -> bool {
+        true
+    }
+}
+
+fn serialize_path(path: &[u32]) -> Vec<u8> {
+    let mut out = vec![path.len() as u8];
+    for index in path {
+        out.extend_from_slice(&index.to_be_bytes());
+    }
+    out
+}
+
+fn connection(e: std::io::Error) -> SignerError {
+    SignerError::Connection(format!("Ledger HID: {e}"))
+}
+
+/// Split an APDU into HID reports: channel, tag, sequence number, then (in
+/// the first report only) the APDU length, then data; zero-padded.
+pub fn frame(apdu: &[u8]) -> Vec<[u8; HID_REPORT_LEN]> {
+    let mut reports = Vec::new();
+    let mut rest = apdu;
+    let mut seq: u16 = 0;
+    loop {
+        let mut report = [0u8; HID_REPORT_LEN];
+        report[..2].copy_from_slice(&CHANNEL.to_be_bytes());
+        report[2] = TAG_APDU;
+        report[3..5].copy_from_slice(&seq.to_be_bytes());
+        let mut at = 5;
+        if seq == 0 {
+            report[5..7].copy_from_slice(&(apdu.len() as u16).to_be_bytes());
+            at = 7;
+        }
+        let n = rest.len().min(HID_REPORT_LEN - at);
+        report[at..at + n].copy_from_slice(&rest[..n]);
+        rest = &rest[n..];
+        reports.push(report);
+        if rest.is_empty() {
+            return reports;
+        }
+        seq += 1;
+    }
+}
+
+/// Reassembles APDUs from the HID reports of [`frame`], one after another.
+#[derive(Debug, Default)]
+pub struct Unframe {
+    len:  usize,
+    seq:  u16,
+    data: Vec<u8>,
+}
+
+impl Unframe {
+    /// Add the next report; the whole APDU once it is complete.
+    pub fn push(&mut self, report: &[u8; HID_REPORT_LEN]) -> Result<Option<Vec<u8>>, SignerError> {
+        if report[..2] != CHANNEL.to_be_bytes() || report[2] != TAG_APDU {
+            return Err(SignerError::Protocol("unexpected HID report header".into()));
+        }
+        if u16::from_be_bytes([report[3], report[4]]) != self.seq {
+            return Err(SignerError::Protocol("HID reports out of sequence".into()));
+        }
+        let body = if self.seq == 0 {
+            self.len = u16::from_be_bytes([report[5], report[6]]) as usize;
+            &report[7..]
+        } else {
+            &report[5..]
+        };
+        self.seq += 1;
+        let n = body.len().min(self.len - self.data.len());
+        self.data.extend_from_slice(&body[..n]);
+        if self.data.len() < self.len {
+            return Ok(None);
+        }
+        Ok(Some(std::mem::take(self).data))
+    }
+}
+
+// ─── hidraw ───────────────────────────────────────────────────────────────────
+
+#[cfg(target_os = "linux")]
+mod hidraw {
+    use std::fs::{self, File};
+    use std::io::{Read, Write};
+
+    use solana_sdk::signer::SignerError;
+
+    use super::{connection, HidDevice, HID_REPORT_LEN, LEDGER_VID};
+
+    pub struct Hidraw(File);
+
+    impl HidDevice for Hidraw {
+        fn write(&mut self, report: &[u8; HID_REPORT_LEN]) -> std::io::Result<()> {
+            // Report id 0 (the device has unnumbered reports), then the report.
+            let mut buf = [0u8; HID_REPORT_LEN + 1];
+            buf[1..].copy_from_slice(report);
+            self.0.write_all(&buf)
+        }
+
+        fn read(&mut self) -> std::io::Result<[u8; HID_REPORT_LEN]> {
+            let mut report = [0u8; HID_REPORT_LEN];
+            self.0.read_exact(&mut report)?;
+            Ok(report)
+        }
+    }
+
+    /// The first Ledger's generic HID interface (`input0`; the others are
+    /// FIDO / keyboard).
+    pub fn open_ledger() -> Result<Box<dyn HidDevice>, SignerError> {
+        let vendor = format!("HID_ID=0003:{:08X}:", LEDGER_VID);
+        let mut nodes: Vec<_> = fs::read_dir("/sys/class/hidraw")
+            .map_err(connection)?
+            .filter_map(|entry| {
+                let name = entry.ok()?.file_name().into_string().ok()?;
+                let uevent = fs::read_to_string(format!("/sys/class/hidraw/{name}/device/uevent")).ok()?;
+                uevent.lines().any(|l| l.starts_with(&vendor)).then(|| {
+                    let generic = uevent.lines().any(|l| l.starts_with("HID_PHYS=") && l.ends_with("/input0"));
+                    (!generic, name)
+                })
+            })
+            .collect();
+        nodes.sort();
+        let (_, name) = nodes.into_iter().next().ok_or(SignerError::NoDeviceFound)?;
+        let file = File::options()
+            .read(true)
+            .write(true)
+            .open(format!("/dev/{name}"))
+            .map_err(|e| SignerError::Connection(format!("/dev/{name}: {e} (install Ledger's udev rules)")))?;
+        Ok(Box::new(Hidraw(file)))
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+mod hidraw {
+    use solana_sdk::signer::SignerError;
+
+    pub fn open_ledger() -> Result<Box<dyn super::HidDevice>, SignerError> {
+        Err(SignerError::Connection(
+            "Ledger HID is built in on Linux only; pass a HidDevice to LedgerSigner::with_device".into(),
+        ))
+    }
+}
@@ -0,0 +1,137 @@
+//! [Privy](https://www.privy.io) server wallet signer (feature `privy`).
+//!
+//! Signs through the wallet RPC endpoint (`signMessage`) with the app's
+//! id and secret. Wallets with an owner or policy that needs an
+//! authorization signature are not supported.
+//!
+//! ```rust,no_run
+//! # use a2a_swap_sdk::signer::privy::PrivySigner;
+//! # fn run() -> Result<(), Box<dyn std::error::Error>> {
+//! // PRIVY_APP_ID / PRIVY_APP_SECRET from the Privy dashboard.
+//! let agent = PrivySigner::from_env("wallet-id")?;
+//! # Ok(()) }
+//! ```
+
+use std::fmt;
+
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine as _};
+use serde_json::{json, Value};
+use solana_sdk::{
+    pubkey::Pubkey,
+    signature::Signature,
+    signer::{Signer, SignerError},
+};
+
+use super::{block_on, http_client, reply_json};
+
+/// Privy's production API.
+pub const PRIVY_API_URL: &str = "https://api.privy.io";
+
+/// Environment variables read by [`PrivySigner::from_env`].
+pub const APP_ID_ENV: &str = "PRIVY_APP_ID";
+pub const APP_SECRET_ENV: &str = "PRIVY_APP_SECRET";
+
+/// A Privy server wallet on Solana.
+pub struct PrivySigner {
+    http:       reqwest::Client,
+    base_url:   String,
+    app_id:     String,
+    app_secret: String,
+    wallet_id:  String,
+    address:    Pubkey,
+}
+
+impl fmt::Debug for PrivySigner {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("PrivySigner")
+            .field("app_id", &self.app_id)
+            .field("wallet_id", &self.wallet_id)
+            .field("address", &self.address)
+            .finish()
+    }
+}
+
+impl PrivySigner {
+    /// Look up `wallet_id` at [`PRIVY_API_URL`] and sign as it.
+    pub fn connect(app_id: &str, app_secret: &str, wallet_id: &str) -> Result<Self, SignerError> {
+        Self::connect_to(PRIVY_API_URL, app_id, app_secret, wallet_id)
+    }
+
+    /// [`connect`](Self::connect) against another API base URL.
+    pub fn connect_to(base_url: &str, app_id: &str, app_secret: &str, wallet_id: &str) -> Result<Self, SignerError> {
+        let mut signer = Self {
+            http:       http_client(),
+            base_url:   base_url.trim_end_matches('/').to_string(),
+            app_id:     app_id.into(),
+            app_secret: app_secret.into(),
+            wallet_id:  wallet_id.into(),
+            address:    Pubkey::default(),
+        };
+        let wallet = block_on(|| signer.request(reqwest::Method::GET, "", None))?;
+        if wallet["chain_type"] != "solana" {
+            return Err(SignerError::InvalidInput(format!(
+                "Privy wallet {wallet_id} is on {}, not solana",
+                wallet["chain_type"].as_str().unwrap_or("an unknown chain")
+            )));
+        }
+        signer.address = wallet["address"].as_str()
+            .and_then(|a| a.parse().ok())
+            .ok_or_else(|| SignerError::Protocol("Privy wallet has no Solana address".into()))?;
+        Ok(signer)
+    }
+
+    /// [`connect`](Self::connect) with the app credentials from
+    /// [`APP_ID_ENV`] and [`APP_SECRET_ENV`].
+    pub fn from_env(wallet_id: &str) -> Result<Self, SignerError> {
+        let var = |name: &str| std::env::var(name)
+            .map_err(|_| SignerError::InvalidInput(format!("{name} is not set")));
+        Self::connect(&var(APP_ID_ENV)?, &var(APP_SECRET_ENV)?, wallet_id)
+    }
+
+    /// `WALLET_ID` from a `privy://` URI.
+    pub(super) fn from_uri(rest: &str) -> Result<Self, SignerError> {
+        let wallet_id = rest.trim_end_matches('/');
+        if wallet_id.is_empty() || wallet_id.contains('/') {
+            return Err(SignerError::InvalidInput(format!("privy://{rest}: expected WALLET_ID")));
+        }
+        Self::from_env(wallet_id)
+    }
+
+    /// The Privy wallet id.
+    pub fn wallet_id(&self) -> &str {
+        &self.wallet_id
+    }
+
+    async fn request(&self, method: reqwest::Method, route: &str, body: Option<Value>) -> Result<Value, SignerError> {
+        let mut request = self.http
+            .request(method, format!("{}/v1/wallets/{}{route}", self.base_url, self.wallet_id))
+            .basic_auth(&self.app_id, Some(&self.app_secret))
+            .header("privy-app-id", &self.app_id);
+        if let Some(body) = body {
+            request = request.header("Content-Type", "application/json").body(body.to_string());
+        }
+        reply_json("Privy", request.send().await).await
+    }
+}
+
+impl Signer for PrivySigner {
+    fn try_pubkey(&self) -> Result<Pubkey, SignerError> {
+        Ok(self.address)
+    }
+
+    fn try_sign_message(&self, message: &[u8]) -> Result<Signature, SignerError> {
+        let reply = block_on(|| self.request(reqwest::Method::POST, "/rpc", Some(json!({
+            "method": "signMessage",
+            "params": { "message": BASE64.encode(message), "encoding": "base64" },
+        }))))?;
+        let signature = reply["data"]["signature"].as_str()
+            .and_then(|s| BASE64.decode(s).ok())
+            .ok_or_else(|| SignerError::Protocol("Privy reply has no signature".into()))?;
+        Signature::try_from(signature.as_slice())
+            .map_err(|_| SignerError::Protocol(format!("Privy signature is {} bytes", signature.len())))
+    }
+
+    fn is_interactive(&self) -> bool {
+        false
+    }
+}
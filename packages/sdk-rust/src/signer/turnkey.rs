@@ -0,0 +1,191 @@
+//! [Turnkey](https://www.turnkey.com) signer (feature `turnkey`).
+//!
+//! Each signature is a `SIGN_RAW_PAYLOAD` activity on the organization,
+//! authenticated by stamping the request body with an Ed25519 API key
+//! (create one with curve `API_KEY_CURVE_ED25519`). Activities that a policy
+//! holds for consensus fail rather than wait, since the transaction's
+//! blockhash would expire first.
+//!
+//! ```rust,no_run
+//! # use a2a_swap_sdk::signer::turnkey::TurnkeySigner;
+//! # fn run() -> Result<(), Box<dyn std::error::Error>> {
+//! // TURNKEY_API_PRIVATE_KEY holds the API key's 32-byte secret, hex.
+//! let agent = TurnkeySigner::from_env("org-id", "7xKX…".parse()?)?;
+//! # Ok(()) }
+//! ```
+
+use std::fmt;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine as _};
+use serde_json::{json, Value};
+use solana_sdk::{
+    pubkey::Pubkey,
+    signature::{Keypair, Signature},
+    signer::{Signer, SignerError},
+};
+
+use super::{block_on, http_client, reply_json};
+
+/// Turnkey's production API.
+pub const TURNKEY_API_URL: &str = "https://api.turnkey.com";
+
+/// Environment variable holding the API key's secret, hex-encoded.
+pub const API_PRIVATE_KEY_ENV: &str = "TURNKEY_API_PRIVATE_KEY";
+
+const STAMP_SCHEME: &str = "SIGNATURE_SCHEME_TK_API_ED25519";
+const COMPLETED: &str = "ACTIVITY_STATUS_COMPLETED";
+const PENDING: &str = "ACTIVITY_STATUS_PENDING";
+/// Polls of a pending activity, `POLL_INTERVAL` apart.
+const POLLS: u32 = 20;
+const POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+/// A Solana address held in a Turnkey organization.
+pub struct TurnkeySigner {
+    http:            reqwest::Client,
+    base_url:        String,
+    organization_id: String,
+    sign_with:       Pubkey,
+    api_key:         Keypair,
+}
+
+impl fmt::Debug for TurnkeySigner {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TurnkeySigner")
+            .field("organization_id", &self.organization_id)
+            .field("sign_with", &self.sign_with)
+            .field("api_public_key", &self.api_key.pubkey())
+            .finish()
+    }
+}
+
+impl TurnkeySigner {
+    /// Sign as `sign_with` in `organization_id`, authenticating with the
+    /// Ed25519 API key whose 32-byte secret is `api_private_key`.
+    pub fn new(organization_id: impl Into<String>, sign_with: Pubkey, api_private_key: [u8; 32]) -> Self {
+        Self {
+            http:            http_client(),
+            base_url:        TURNKEY_API_URL.into(),
+            organization_id: organization_id.into(),
+            sign_with,
+            api_key:         Keypair::new_from_array(api_private_key),
+        }
+    }
+
+    /// [`new`](Self::new) with the API key from [`API_PRIVATE_KEY_ENV`].
+    pub fn from_env(organization_id: impl Into<String>, sign_with: Pubkey) -> Result<Self, SignerError> {
+        let secret = std::env::var(API_PRIVATE_KEY_ENV)
+            .map_err(|_| SignerError::InvalidInput(format!("{API_PRIVATE_KEY_ENV} is not set")))?;
+        let secret = decode_hex(secret.trim())
+            .and_then(|bytes| <[u8; 32]>::try_from(bytes).ok())
+            .ok_or_else(|| SignerError::InvalidInput(format!("{API_PRIVATE_KEY_ENV} must be 32 bytes of hex")))?;
+        Ok(Self::new(organization_id, sign_with, secret))
+    }
+
+    /// `ORGANIZATION_ID/ADDRESS` from a `turnkey://` URI.
+    pub(super) fn from_uri(rest: &str) -> Result<Self, SignerError> {
+        let bad = || SignerError::InvalidInput(format!("turnkey://{rest}: expected ORGANIZATION_ID/ADDRESS"));
+        let (organization_id, address) = rest.split_once('/').ok_or_else(bad)?;
+        let sign_with = address.trim_end_matches('/').parse().map_err(|_| bad())?;
+        if organization_id.is_empty() {
+            return Err(bad());
+        }
+        Self::from_env(organization_id, sign_with)
+    }
+
+    /// Send requests to `url` instead of [`TURNKEY_API_URL`].
+    pub fn with_base_url(mut self, url: impl Into<String>) -> Self {
+        self.base_url = url.into().trim_end_matches('/').to_string();
+        self
+    }
+
+    /// `X-Stamp` header for `body`: the API public key and its signature of
+    /// the exact body bytes, as base64url JSON.
+    fn stamp(&self, body: &str) -> String {
+        let stamp = json!({
+            "publicKey": encode_hex(self.api_key.pubkey().as_ref()),
+            "scheme":    STAMP_SCHEME,
+            "signature": encode_hex(self.api_key.sign_message(body.as_bytes()).as_ref()),
+        });
+        URL_SAFE_NO_PAD.encode(stamp.to_string())
+    }
+
+    async fn post(&self, route: &str, body: Value) -> Result<Value, SignerError> {
+        let body = body.to_string();
+        let sent = self.http
+            .post(format!("{}{route}", self.base_url))
+            .header("Content-Type", "application/json")
+            .header("X-Stamp", self.stamp(&body))
+            .body(body)
+            .send()
+            .await;
+        reply_json("Turnkey", sent).await
+    }
+
+    async fn sign(&self, message: &[u8]) -> Result<Signature, SignerError> {
+        let timestamp_ms = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_millis();
+        let mut activity = self.post("/public/v1/submit/sign_raw_payload", json!({
+            "type":           "ACTIVITY_TYPE_SIGN_RAW_PAYLOAD_V2",
+            "timestampMs":    timestamp_ms.to_string(),
+            "organizationId": self.organization_id,
+            "parameters": {
+                "signWith":     self.sign_with.to_string(),
+                "payload":      encode_hex(message),
+                "encoding":     "PAYLOAD_ENCODING_HEXADECIMAL",
+                "hashFunction": "HASH_FUNCTION_NOT_APPLICABLE",
+            },
+        })).await?["activity"].take();
+
+        for _ in 0..POLLS {
+            if activity["status"] != PENDING {
+                break;
+            }
+            tokio::time::sleep(POLL_INTERVAL).await;
+            activity = self.post("/public/v1/query/get_activity", json!({
+                "organizationId": self.organization_id,
+                "activityId":     activity["id"],
+            })).await?["activity"].take();
+        }
+        if activity["status"] != COMPLETED {
+            return Err(SignerError::Custom(format!(
+                "Turnkey activity {} ended as {}",
+                activity["id"].as_str().unwrap_or("?"),
+                activity["status"].as_str().unwrap_or("unknown")
+            )));
+        }
+        let result = &activity["result"]["signRawPayloadResult"];
+        let signature = [&result["r"], &result["s"]]
+            .iter()
+            .map(|half| half.as_str().and_then(decode_hex))
+            .collect::<Option<Vec<_>>>()
+            .map(|halves| halves.concat())
+            .ok_or_else(|| SignerError::Protocol("Turnkey reply has no r / s".into()))?;
+        Signature::try_from(signature.as_slice())
+            .map_err(|_| SignerError::Protocol(format!("Turnkey signature is {} bytes", signature.len())))
+    }
+}
+
+impl Signer for TurnkeySigner {
+    fn try_pubkey(&self) -> Result<Pubkey, SignerError> {
+        Ok(self.sign_with)
+    }
+
+    fn try_sign_message(&self, message: &[u8]) -> Result<Signature, SignerError> {
+        block_on(|| self.sign(message))
+    }
+
+    fn is_interactive(&self) -> bool {
+        false
+    }
+}
+
+fn encode_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{b:02x}")).collect()
+}
+
+fn decode_hex(hex: &str) -> Option<Vec<u8>> {
+    if hex.len() % 2 != 0 {
+        return None;
+    }
+    (0..hex.len()).step_by(2).map(|i| u8::from_str_radix(hex.get(i..i + 2)?, 16).ok()).collect()
+}
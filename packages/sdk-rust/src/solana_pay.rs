@@ -0,0 +1,102 @@
+//! Solana Pay transaction requests for swaps.
+//!
+//! An agent that may not sign a swap itself can hand it to a human: the
+//! `solana:` link from [`swap_link`] (or
+//! [`A2ASwapClient::solana_pay_link`](crate::A2ASwapClient::solana_pay_link))
+//! opens in any Solana Pay wallet, or renders as a QR code for a phone to
+//! scan. The wallet asks the API's `/pay/convert` endpoint for the swap
+//! transaction built for the wallet's own account, shows it, and signs and
+//! sends it from there — the agent never holds the key.
+//!
+//! The link carries the swap itself; the quote and `min_amount_out` are
+//! taken when the wallet fetches the transaction, not when the link is made.
+//!
+//! ```
+//! # use a2a_swap_sdk::{solana_pay, ProtectionLevel, SwapParams};
+//! # use solana_sdk::pubkey::Pubkey;
+//! let params = SwapParams {
+//!     mint_in:              Pubkey::new_unique(),
+//!     mint_out:             Pubkey::new_unique(),
+//!     amount_in:            1_000_000_000,
+//!     max_slippage_bps:     50,
+//!     min_amount_out:       None,
+//!     max_price_impact_bps: 0,
+//!     deadline_unix:        0,
+//!     referrer:             None,
+//!     protection:           ProtectionLevel::None,
+//! };
+//! let link = solana_pay::swap_link(solana_pay::DEFAULT_API_URL, &params)?;
+//! assert!(link.starts_with("solana:https%3A%2F%2F"));
+//! # Ok::<(), a2a_swap_sdk::Error>(())
+//! ```
+
+use crate::{
+    error::{Error, Result},
+    types::{ProtectionLevel, SwapParams},
+};
+
+/// The hosted A2A-Swap HTTP API.
+pub const DEFAULT_API_URL: &str = "https://a2a-swap-api.a2a-swap.workers.dev";
+
+/// The `https://` transaction-request endpoint for a swap: `api_url`'s
+/// `/pay/convert` with the swap in the query string.
+///
+/// The endpoint builds the same transaction as `/convert`, so it takes the
+/// same options: `min_amount_out`, `max_price_impact_bps` and protected
+/// swaps are not supported and fail with [`Error::InvalidArgument`].
+pub fn swap_request_url(api_url: &str, params: &SwapParams) -> Result<String> {
+    if params.amount_in == 0 {
+        return Err(Error::InvalidArgument("amount_in must be greater than zero".into()));
+    }
+    if params.max_slippage_bps > 10_000 {
+        return Err(Error::InvalidArgument("max_slippage_bps must be at most 10000".into()));
+    }
+    if params.min_amount_out.is_some() {
+        return Err(Error::InvalidArgument(
+            "a Solana Pay link cannot carry min_amount_out; use max_slippage_bps".into(),
+        ));
+    }
+    if params.max_price_impact_bps != 0 {
+        return Err(Error::InvalidArgument("a Solana Pay link cannot carry max_price_impact_bps".into()));
+    }
+    if params.protection != ProtectionLevel::None {
+        return Err(Error::InvalidArgument("a Solana Pay link sends one unprotected swap".into()));
+    }
+
+    let mut url = format!(
+        "{}/pay/convert?tokenIn={}&tokenOut={}&amount={}&slippageBps={}",
+        api_url.trim_end_matches('/'),
+        params.mint_in,
+        params.mint_out,
+        params.amount_in,
+        params.max_slippage_bps,
+    );
+    if params.deadline_unix != 0 {
+        url.push_str(&format!("&deadlineUnix={}", params.deadline_unix));
+    }
+    if let Some(referrer) = params.referrer {
+        url.push_str(&format!("&referrer={referrer}"));
+    }
+    Ok(url)
+}
+
+/// The `solana:` link for a swap — [`swap_request_url`], percent-encoded as
+/// the Solana Pay spec requires for a URL with a query string. Use it as an
+/// `href` or as a QR code's payload.
+pub fn swap_link(api_url: &str, params: &SwapParams) -> Result<String> {
+    Ok(format!("solana:{}", percent_encode(&swap_request_url(api_url, params)?)))
+}
+
+/// Percent-encode everything but RFC 3986 unreserved characters, like
+/// JavaScript's `encodeURIComponent`.
+fn percent_encode(s: &str) -> String {
+    let mut out = String::with_capacity(s.len() * 3);
+    for b in s.bytes() {
+        if b.is_ascii_alphanumeric() || matches!(b, b'-' | b'_' | b'.' | b'~') {
+            out.push(b as char);
+        } else {
+            out.push_str(&format!("%{b:02X}"));
+        }
+    }
+    out
+}
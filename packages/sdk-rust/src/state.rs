@@ -1,11 +1,68 @@
 //! On-chain account deserialization.
 //!
-//! Parses raw account bytes for `Pool` (212 bytes) and `Position` (138 bytes).
-//! Byte offsets mirror the Anchor `#[account]` layout exactly.
+//! Parses raw account bytes for `Pool` (396 bytes; 212, 221, 237, 269, 270,
+//! 286, 300, 332 or 364 before `migrate_pool`), `Position` (171 bytes; 138
+//! or 139 before `migrate_position`), `RangePool` (4 598 bytes) and
+//! `RangePosition` (145 bytes).
+//! Decoding goes through the IDL-generated layouts in [`a2a_swap_core`];
+//! the types here add `Pubkey`s and serde on top.
+//!
+//! `Pool` and `Position` carry a layout `version` (0 for accounts from before
+//! versioning). New fields are only ever appended, so parsing is
+//! forward-compatible: an account written by a newer program version parses
+//! here with the fields this crate knows about, and fields an older account
+//! lacks take their zero / disabled defaults.
+
+use std::io::Read;
 
+use a2a_swap_core::{Account, Layout};
+use flate2::read::ZlibDecoder;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
 use solana_sdk::pubkey::Pubkey;
 use crate::error::{Error, Result};
 
+// ─── Curve ────────────────────────────────────────────────────────────────────
+
+/// Swap invariant of a pool — mirrors the program's `CurveKind`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum CurveKind {
+    /// `x · y = k`.
+    #[default]
+    ConstantProduct,
+    /// Curve-style StableSwap for correlated pairs; `amp` is the
+    /// amplification coefficient A (1–10 000).
+    StableSwap { amp: u64 },
+}
+
+impl CurveKind {
+    /// Borsh encoding used in the `initialize_pool` instruction and `Pool` account.
+    pub fn to_bytes(self) -> Vec<u8> {
+        let mut out = Vec::new();
+        a2a_swap_core::CurveKind::from(self).encode(&mut out);
+        out
+    }
+}
+
+impl From<a2a_swap_core::CurveKind> for CurveKind {
+    fn from(c: a2a_swap_core::CurveKind) -> Self {
+        match c {
+            a2a_swap_core::CurveKind::ConstantProduct     => CurveKind::ConstantProduct,
+            a2a_swap_core::CurveKind::StableSwap { amp } => CurveKind::StableSwap { amp },
+        }
+    }
+}
+
+impl From<CurveKind> for a2a_swap_core::CurveKind {
+    fn from(c: CurveKind) -> Self {
+        match c {
+            CurveKind::ConstantProduct     => a2a_swap_core::CurveKind::ConstantProduct,
+            CurveKind::StableSwap { amp } => a2a_swap_core::CurveKind::StableSwap { amp },
+        }
+    }
+}
+
 // ─── Pool ─────────────────────────────────────────────────────────────────────
 
 /// Deserialized `Pool` account state.
@@ -15,7 +72,32 @@ use crate::error::{Error, Result};
 /// authority(32)  authority_bump(1)  token_a_mint(32)  token_b_mint(32)
 /// token_a_vault(32)  token_b_vault(32)  lp_supply(8)  fee_rate_bps(2)
 /// fee_growth_global_a(16)  fee_growth_global_b(16)  bump(1)  = 212 bytes
+/// curve(1 + 8)                                                 = 221 bytes
+/// dynamic_fee(16)                                              = 237 bytes
+/// volume_a(8)  volume_b(8)  fees_collected_a(8)  fees_collected_b(8) = 269 bytes
+/// version(1)                                                   = 270 bytes
+/// reserve_a(8)  reserve_b(8)                                   = 286 bytes
+/// circuit_breaker(14)                                          = 300 bytes
+/// creator(32)                                                  = 332 bytes
+/// allowlist_root(32)                                           = 364 bytes
+/// gate_mint(32)                                                = 396 bytes
+/// open_positions(8)                                            = 404 bytes
+/// reserves_tracked(1)                                          = 405 bytes
 /// ```
+///
+/// `curve` is Borsh-encoded, so the fields after it start right after the
+/// bytes it uses: `dynamic_fee` at offset 213 for constant-product pools,
+/// 221 for StableSwap, and the counters 16 bytes later.
+///
+/// Pools created before `curve` / `dynamic_fee` / the counters / `version` /
+/// the reserves / `circuit_breaker` / `creator` / `allowlist_root` /
+/// `gate_mint` / `open_positions` / `reserves_tracked` existed are 212 /
+/// 221 / 237 / 269 / 270 / 286 / 300 / 332 / 364 / 396 / 404 bytes until
+/// `migrate_pool` grows them; all sizes parse, with the missing fields as
+/// [`CurveKind::ConstantProduct`], dynamic fees and the circuit breaker
+/// disabled, zero counters and reserves, no creator, no allowlist, no
+/// gate, no counted positions, and version 0. `reserves_tracked` reads as
+/// `migrate_pool` will set it.
 #[derive(Debug, Clone)]
 pub struct PoolState {
     pub token_a_mint:        Pubkey,
@@ -28,26 +110,236 @@ pub struct PoolState {
     pub fee_growth_global_a: u128,
     /// Cumulative fee-per-LP-share for token B, Q64.64 fixed-point.
     pub fee_growth_global_b: u128,
+    /// Swap invariant.
+    pub curve:               CurveKind,
+    /// Volatility-driven fee bounds; see [`crate::math::effective_fee_bps`].
+    pub dynamic_fee:         DynamicFee,
+    /// Lifetime swap input in token A (atomic units, before fees).
+    pub volume_a:            u64,
+    /// Lifetime swap input in token B (atomic units, before fees).
+    pub volume_b:            u64,
+    /// Lifetime LP fees retained in the token A vault.
+    pub fees_collected_a:    u64,
+    /// Lifetime LP fees retained in the token B vault.
+    pub fees_collected_b:    u64,
+    /// Layout version; below [`POOL_VERSION`] means `migrate_pool` is due.
+    pub version:             u8,
+    /// Tracked token A reserve the program prices against; see [`reserves`](Self::reserves).
+    pub reserve_a:           u64,
+    /// Tracked token B reserve.
+    pub reserve_b:           u64,
+    /// Per-slot price move limit.
+    pub circuit_breaker:     CircuitBreaker,
+    /// Wallet that created the pool and may `close_pool` it once empty;
+    /// `Pubkey::default()` when not recorded.
+    pub creator:             Pubkey,
+    /// Merkle root of the traders allowed to swap (see
+    /// [`crate::allowlist`]); all zeros when the pool is open to everyone.
+    pub allowlist_root:      [u8; 32],
+    /// Mint traders must hold to swap (e.g. a compliance credential);
+    /// `Pubkey::default()` when swaps are ungated.
+    pub gate_mint:           Pubkey,
+    /// Positions opened and not yet closed; [`POOL_UNCOUNTED_POSITIONS`]
+    /// once a pool from before the count is migrated. `close_pool` needs 0.
+    pub open_positions:      u64,
+    /// Whether `reserve_a` / `reserve_b` hold the reserves; false for a pool
+    /// migrated from before they were tracked and not touched since.
+    pub reserves_tracked:    bool,
+}
+
+impl PoolState {
+    /// Whether only allowlisted traders may swap, each through its
+    /// [`TraderPassState`].
+    pub fn is_permissioned(&self) -> bool {
+        self.allowlist_root != [0; 32]
+    }
+
+    /// Whether traders must hold [`gate_mint`](Self::gate_mint) to swap.
+    pub fn is_gated(&self) -> bool {
+        self.gate_mint != Pubkey::default()
+    }
+
+    /// Whether `close_pool` would accept the pool: no LP shares, tracked
+    /// reserves or open positions. A pool still on a layout from before
+    /// positions were counted never is, since migrating it marks the count
+    /// [`POOL_UNCOUNTED_POSITIONS`].
+    pub fn is_closable(&self) -> bool {
+        self.version >= POOL_OPEN_POSITIONS_VERSION
+            && self.lp_supply == 0
+            && self.reserve_a == 0
+            && self.reserve_b == 0
+            && self.open_positions == 0
+    }
+
+    /// The `(reserve_a, reserve_b)` swaps and deposits price against, given
+    /// the vault balances — the program's `Pool::reserves`. Tokens sent
+    /// straight to a vault don't count until `sync`; a pool whose reserves
+    /// aren't [tracked](Self::reserves_tracked) uses the vault balances.
+    pub fn reserves(&self, vault_a: u64, vault_b: u64) -> (u64, u64) {
+        if self.reserves_tracked {
+            (self.reserve_a, self.reserve_b)
+        } else {
+            (vault_a, vault_b)
+        }
+    }
+}
+
+/// Admin-set LP fee bounds and the volatility accumulator that moves the fee
+/// between them — mirrors the program's `DynamicFee`. All zero (the default)
+/// means dynamic fees are off and swaps pay `fee_rate_bps`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub struct DynamicFee {
+    pub min_fee_bps:    u16,
+    /// `0` = disabled.
+    pub max_fee_bps:    u16,
+    /// Summed price impact of recent swaps (bps) as of `last_update_ts`.
+    pub volatility_bps: u32,
+    /// Unix timestamp of the last swap that updated the accumulator.
+    pub last_update_ts: i64,
+}
+
+impl DynamicFee {
+    /// Whether the pool's LP fee floats with volatility.
+    pub fn is_enabled(&self) -> bool {
+        self.max_fee_bps > 0
+    }
+}
+
+impl From<a2a_swap_core::DynamicFee> for DynamicFee {
+    fn from(f: a2a_swap_core::DynamicFee) -> Self {
+        DynamicFee {
+            min_fee_bps:    f.min_fee_bps,
+            max_fee_bps:    f.max_fee_bps,
+            volatility_bps: f.volatility_bps,
+            last_update_ts: f.last_update_ts,
+        }
+    }
+}
+
+impl From<DynamicFee> for a2a_swap_core::DynamicFee {
+    fn from(f: DynamicFee) -> Self {
+        a2a_swap_core::DynamicFee {
+            min_fee_bps:    f.min_fee_bps,
+            max_fee_bps:    f.max_fee_bps,
+            volatility_bps: f.volatility_bps,
+            last_update_ts: f.last_update_ts,
+        }
+    }
+}
+
+/// Admin-set cap on the net price impact a pool's swaps may add up to within
+/// one slot — mirrors the program's `CircuitBreaker`. A swap that takes
+/// `|moved_bps|` past `max_move_bps` fails with `PriceMoveExceeded`. All
+/// zero (the default) means no limit.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub struct CircuitBreaker {
+    /// `0` = disabled.
+    pub max_move_bps: u16,
+    /// Net signed price impact (bps) of the swaps in `slot`: A→B swaps
+    /// count negative, B→A positive.
+    pub moved_bps:    i32,
+    /// Slot `moved_bps` was tallied in.
+    pub slot:         u64,
+}
+
+impl CircuitBreaker {
+    /// Whether swaps on the pool are capped per slot.
+    pub fn is_enabled(&self) -> bool {
+        self.max_move_bps > 0
+    }
+
+    /// Price impact (bps) a swap in direction `a_to_b` may still have in
+    /// `slot` before the breaker trips; `None` when disabled.
+    pub fn headroom_bps(&self, a_to_b: bool, slot: u64) -> Option<u64> {
+        if !self.is_enabled() {
+            return None;
+        }
+        let moved = if self.slot == slot { self.moved_bps as i64 } else { 0 };
+        let max = self.max_move_bps as i64;
+        Some(if a_to_b { max + moved } else { max - moved }.max(0) as u64)
+    }
 }
 
+impl From<a2a_swap_core::CircuitBreaker> for CircuitBreaker {
+    fn from(c: a2a_swap_core::CircuitBreaker) -> Self {
+        CircuitBreaker {
+            max_move_bps: c.max_move_bps,
+            moved_bps:    c.moved_bps,
+            slot:         c.slot,
+        }
+    }
+}
+
+/// Current `Pool` account size.
+pub const POOL_LEN: usize = a2a_swap_core::Pool::LEN;
+/// Current `Pool` layout version.
+pub const POOL_VERSION: u8 = 8;
+/// `Pool` account size before `curve` was added.
+pub const POOL_LEGACY_LEN: usize = 212;
+/// `Pool` account size before `dynamic_fee` was added.
+pub const POOL_CURVE_LEN: usize = 221;
+/// `Pool` account size before the volume and fee counters were added.
+pub const POOL_DYNAMIC_FEE_LEN: usize = 237;
+/// `Pool` account size before `version` was added.
+pub const POOL_STATS_LEN: usize = 269;
+/// `Pool` account size before the tracked reserves were added.
+pub const POOL_VERSION_LEN: usize = 270;
+/// `Pool` account size before `circuit_breaker` was added.
+pub const POOL_RESERVES_LEN: usize = 286;
+/// `Pool` account size before `creator` was added.
+pub const POOL_CIRCUIT_BREAKER_LEN: usize = 300;
+/// `Pool` account size before `allowlist_root` was added.
+pub const POOL_CREATOR_LEN: usize = 332;
+/// `Pool` account size before `gate_mint` was added.
+pub const POOL_ALLOWLIST_LEN: usize = 364;
+/// `Pool` account size before `open_positions` was added.
+pub const POOL_GATE_LEN: usize = 396;
+/// `open_positions` of a migrated pool whose positions predate the count;
+/// such a pool can't be closed.
+pub const POOL_UNCOUNTED_POSITIONS: u64 = u64::MAX;
+/// First `Pool` layout version that counts `open_positions`.
+pub const POOL_OPEN_POSITIONS_VERSION: u8 = 7;
+/// `Pool` account size before `reserves_tracked` was added.
+pub const POOL_OPEN_POSITIONS_LEN: usize = 404;
+
 /// Deserialize a `Pool` account from raw bytes.
 pub fn parse_pool(data: &[u8]) -> Result<PoolState> {
-    const EXPECTED: usize = 212;
-    if data.len() < EXPECTED {
+    if data.len() < POOL_LEGACY_LEN {
         return Err(Error::ParseError {
             offset: 0,
-            reason: format!("Pool account is {} bytes; expected {}", data.len(), EXPECTED),
+            reason: format!("Pool account is {} bytes; expected {}", data.len(), POOL_LEN),
         });
     }
+    let p = a2a_swap_core::Pool::from_account_data(data)?;
     Ok(PoolState {
-        token_a_mint:        read_pubkey(data, 41)?,
-        token_b_mint:        read_pubkey(data, 73)?,
-        token_a_vault:       read_pubkey(data, 105)?,
-        token_b_vault:       read_pubkey(data, 137)?,
-        lp_supply:           read_u64(data, 169)?,
-        fee_rate_bps:        read_u16(data, 177)?,
-        fee_growth_global_a: read_u128(data, 179)?,
-        fee_growth_global_b: read_u128(data, 195)?,
+        token_a_mint:        p.token_a_mint.into(),
+        token_b_mint:        p.token_b_mint.into(),
+        token_a_vault:       p.token_a_vault.into(),
+        token_b_vault:       p.token_b_vault.into(),
+        lp_supply:           p.lp_supply,
+        fee_rate_bps:        p.fee_rate_bps,
+        fee_growth_global_a: p.fee_growth_global_a,
+        fee_growth_global_b: p.fee_growth_global_b,
+        curve:               p.curve.into(),
+        dynamic_fee:         p.dynamic_fee.into(),
+        volume_a:            p.volume_a,
+        volume_b:            p.volume_b,
+        fees_collected_a:    p.fees_collected_a,
+        fees_collected_b:    p.fees_collected_b,
+        version:             p.version,
+        reserve_a:           p.reserve_a,
+        reserve_b:           p.reserve_b,
+        circuit_breaker:     p.circuit_breaker.into(),
+        creator:             p.creator.into(),
+        allowlist_root:      p.allowlist_root,
+        gate_mint:           p.gate_mint.into(),
+        open_positions:      p.open_positions,
+        // Older layouts read as `migrate_pool` will set the flag.
+        reserves_tracked:    if data.len() > POOL_OPEN_POSITIONS_LEN {
+            p.reserves_tracked
+        } else {
+            p.reserve_a != 0 || p.reserve_b != 0 || p.lp_supply == 0
+        },
     })
 }
 
@@ -61,7 +353,13 @@ pub fn parse_pool(data: &[u8]) -> Result<PoolState> {
 /// fee_growth_checkpoint_a(16)  fee_growth_checkpoint_b(16)
 /// fees_owed_a(8)  fees_owed_b(8)  auto_compound(1)  compound_threshold(8)  bump(1)
 /// = 138 bytes
+/// version(1)  = 139 bytes
+/// locked_by(32)  = 171 bytes
 /// ```
+///
+/// Positions created before `version` existed are 138 bytes, and version-1
+/// positions 139 bytes, until `migrate_position` grows them; they parse with
+/// their version and unlocked.
 #[derive(Debug, Clone)]
 pub struct PositionState {
     pub owner:                   Pubkey,
@@ -77,32 +375,753 @@ pub struct PositionState {
     pub fees_owed_b:             u64,
     pub auto_compound:           bool,
     pub compound_threshold:      u64,
+    /// Layout version; below [`POSITION_VERSION`] means `migrate_position` is due.
+    pub version:                 u8,
+    /// Approved program holding the position as collateral; while set it
+    /// can't be withdrawn from or closed. `None` when unlocked.
+    pub locked_by:               Option<Pubkey>,
 }
 
+/// Current `Position` account size.
+pub const POSITION_LEN: usize = a2a_swap_core::Position::LEN;
+/// Current `Position` layout version.
+pub const POSITION_VERSION: u8 = 2;
+/// `Position` account size before `version` was added.
+pub const POSITION_LEGACY_LEN: usize = 138;
+
 /// Deserialize a `Position` account from raw bytes.
 pub fn parse_position(data: &[u8]) -> Result<PositionState> {
-    const EXPECTED: usize = 138;
-    if data.len() < EXPECTED {
+    if data.len() < POSITION_LEGACY_LEN {
         return Err(Error::ParseError {
             offset: 0,
-            reason: format!("Position account is {} bytes; expected {}", data.len(), EXPECTED),
+            reason: format!("Position account is {} bytes; expected {}", data.len(), POSITION_LEN),
         });
     }
+    let p = a2a_swap_core::Position::from_account_data(data)?;
     Ok(PositionState {
-        owner:                   read_pubkey(data, 8)?,
-        pool:                    read_pubkey(data, 40)?,
-        lp_shares:               read_u64(data, 72)?,
-        fee_growth_checkpoint_a: read_u128(data, 80)?,
-        fee_growth_checkpoint_b: read_u128(data, 96)?,
-        fees_owed_a:             read_u64(data, 112)?,
-        fees_owed_b:             read_u64(data, 120)?,
-        auto_compound:           data[128] != 0,
-        compound_threshold:      read_u64(data, 129)?,
+        owner:                   p.owner.into(),
+        pool:                    p.pool.into(),
+        lp_shares:               p.lp_shares,
+        fee_growth_checkpoint_a: p.fee_growth_checkpoint_a,
+        fee_growth_checkpoint_b: p.fee_growth_checkpoint_b,
+        fees_owed_a:             p.fees_owed_a,
+        fees_owed_b:             p.fees_owed_b,
+        auto_compound:           p.auto_compound,
+        compound_threshold:      p.compound_threshold,
+        version:                 p.version,
+        locked_by:               Some(Pubkey::from(p.locked_by)).filter(|k| *k != Pubkey::default()),
     })
 }
 
+// ─── RangePool ────────────────────────────────────────────────────────────────
+
+/// One initialized tick of a [`RangePoolState`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct RangeTick {
+    pub index:                i32,
+    /// Liquidity added when the price crosses this tick upwards.
+    pub liquidity_net:        i128,
+    /// Total liquidity referencing this tick.
+    pub liquidity_gross:      u128,
+    /// Fee growth on the far side of this tick from the current price, Q64.64.
+    pub fee_growth_outside_a: u128,
+    /// Fee growth on the far side of this tick from the current price, Q64.64.
+    pub fee_growth_outside_b: u128,
+}
+
+impl From<a2a_swap_core::Tick> for RangeTick {
+    fn from(t: a2a_swap_core::Tick) -> Self {
+        RangeTick {
+            index:                t.index,
+            liquidity_net:        t.liquidity_net,
+            liquidity_gross:      t.liquidity_gross,
+            fee_growth_outside_a: t.fee_growth_outside_a,
+            fee_growth_outside_b: t.fee_growth_outside_b,
+        }
+    }
+}
+
+/// Deserialized `RangePool` (concentrated-liquidity pool) account state.
+///
+/// Layout (after 8-byte Anchor discriminator):
+/// ```text
+/// authority(32)  authority_bump(1)  token_a_mint(32)  token_b_mint(32)
+/// token_a_vault(32)  token_b_vault(32)  fee_rate_bps(2)  tick_spacing(2)
+/// sqrt_price_x64(16)  tick_current(4)  liquidity(16)
+/// fee_growth_global_a(16)  fee_growth_global_b(16)  bump(1)
+/// ticks: len(4) + len × [index(4) liquidity_net(16) liquidity_gross(16)
+///                        fee_growth_outside_a(16) fee_growth_outside_b(16)]
+/// ```
+#[derive(Debug, Clone)]
+pub struct RangePoolState {
+    pub token_a_mint:        Pubkey,
+    pub token_b_mint:        Pubkey,
+    pub token_a_vault:       Pubkey,
+    pub token_b_vault:       Pubkey,
+    pub fee_rate_bps:        u16,
+    /// Range bounds must be multiples of this.
+    pub tick_spacing:        u16,
+    /// sqrt(price of token A in token B), Q64.64.
+    pub sqrt_price_x64:      u128,
+    /// Greatest tick at or below the current price.
+    pub tick_current:        i32,
+    /// Liquidity of the ranges containing the current price.
+    pub liquidity:           u128,
+    /// Cumulative fee per unit of liquidity for token A, Q64.64 (wrapping).
+    pub fee_growth_global_a: u128,
+    /// Cumulative fee per unit of liquidity for token B, Q64.64 (wrapping).
+    pub fee_growth_global_b: u128,
+    /// Initialized ticks, sorted by index.
+    pub ticks:               Vec<RangeTick>,
+}
+
+/// Allocated `RangePool` account size (room for [`MAX_RANGE_TICKS`] ticks).
+pub const RANGE_POOL_LEN: usize = RANGE_POOL_HEADER_LEN + MAX_RANGE_TICKS * a2a_swap_core::Tick::SIZE;
+/// Initialized ticks a range pool can hold.
+pub const MAX_RANGE_TICKS: usize = 64;
+/// `RangePool` size with no ticks: discriminator, fixed fields, tick count.
+const RANGE_POOL_HEADER_LEN: usize = 8 + a2a_swap_core::RangePool::SIZE;
+
+/// Deserialize a `RangePool` account from raw bytes.
+pub fn parse_range_pool(data: &[u8]) -> Result<RangePoolState> {
+    if data.len() < RANGE_POOL_HEADER_LEN {
+        return Err(Error::ParseError {
+            offset: 0,
+            reason: format!("RangePool account is {} bytes; expected {}", data.len(), RANGE_POOL_LEN),
+        });
+    }
+    let p = a2a_swap_core::RangePool::from_account_data(data)?;
+    if p.ticks.len() > MAX_RANGE_TICKS {
+        return Err(Error::ParseError {
+            offset: RANGE_POOL_HEADER_LEN - 4,
+            reason: format!("RangePool claims {} ticks", p.ticks.len()),
+        });
+    }
+    Ok(RangePoolState {
+        token_a_mint:        p.token_a_mint.into(),
+        token_b_mint:        p.token_b_mint.into(),
+        token_a_vault:       p.token_a_vault.into(),
+        token_b_vault:       p.token_b_vault.into(),
+        fee_rate_bps:        p.fee_rate_bps,
+        tick_spacing:        p.tick_spacing,
+        sqrt_price_x64:      p.sqrt_price_x64,
+        tick_current:        p.tick_current,
+        liquidity:           p.liquidity,
+        fee_growth_global_a: p.fee_growth_global_a,
+        fee_growth_global_b: p.fee_growth_global_b,
+        ticks:               p.ticks.into_iter().map(RangeTick::from).collect(),
+    })
+}
+
+// ─── RangePosition ────────────────────────────────────────────────────────────
+
+/// Deserialized `RangePosition` account state.
+///
+/// Layout (after 8-byte Anchor discriminator):
+/// ```text
+/// owner(32)  pool(32)  tick_lower(4)  tick_upper(4)  liquidity(16)
+/// fee_growth_inside_last_a(16)  fee_growth_inside_last_b(16)
+/// fees_owed_a(8)  fees_owed_b(8)  bump(1)
+/// = 145 bytes
+/// ```
+#[derive(Debug, Clone)]
+pub struct RangePositionState {
+    pub owner:                    Pubkey,
+    pub pool:                     Pubkey,
+    pub tick_lower:               i32,
+    pub tick_upper:               i32,
+    pub liquidity:                u128,
+    /// Fee growth inside the range at last sync (for pending-fee calculation).
+    pub fee_growth_inside_last_a: u128,
+    /// Fee growth inside the range at last sync (for pending-fee calculation).
+    pub fee_growth_inside_last_b: u128,
+    /// Fees already accounted for on-chain but not yet transferred.
+    pub fees_owed_a:              u64,
+    /// Fees already accounted for on-chain but not yet transferred.
+    pub fees_owed_b:              u64,
+}
+
+/// `RangePosition` account size.
+pub const RANGE_POSITION_LEN: usize = a2a_swap_core::RangePosition::LEN;
+
+/// Deserialize a `RangePosition` account from raw bytes.
+pub fn parse_range_position(data: &[u8]) -> Result<RangePositionState> {
+    if data.len() < RANGE_POSITION_LEN {
+        return Err(Error::ParseError {
+            offset: 0,
+            reason: format!(
+                "RangePosition account is {} bytes; expected {}", data.len(), RANGE_POSITION_LEN,
+            ),
+        });
+    }
+    let p = a2a_swap_core::RangePosition::from_account_data(data)?;
+    Ok(RangePositionState {
+        owner:                    p.owner.into(),
+        pool:                     p.pool.into(),
+        tick_lower:               p.tick_lower,
+        tick_upper:               p.tick_upper,
+        liquidity:                p.liquidity,
+        fee_growth_inside_last_a: p.fee_growth_inside_last_a,
+        fee_growth_inside_last_b: p.fee_growth_inside_last_b,
+        fees_owed_a:              p.fees_owed_a,
+        fees_owed_b:              p.fees_owed_b,
+    })
+}
+
+// ─── ProtocolConfig ───────────────────────────────────────────────────────────
+
+/// Deserialized `ProtocolConfig` account state, stored at the treasury PDA.
+///
+/// Layout (after 8-byte Anchor discriminator):
+/// ```text
+/// admin(32)  treasury_authority(32)  protocol_fee_bps(2)  referral_share_bps(2)  bump(1)
+/// = 77 bytes
+/// paused(1)  min_pool_fee_bps(2)  max_pool_fee_bps(2)  = 82 bytes
+/// fee_tiers(4 × (min_volume(8) discount_bps(2)))       = 122 bytes
+/// lockers(4 × 32)                                       = 250 bytes
+/// ```
+///
+/// Configs from before the pause switches (77 bytes), the fee tiers
+/// (82 bytes) or the lockers (122 bytes) stay that size until
+/// `migrate_protocol_config` grows them; they parse unpaused, with the
+/// default pool fee bounds, no tiers and no lockers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ProtocolConfigState {
+    /// Signer allowed to change the config and tune dynamic fees; the
+    /// Governance PDA once governance is initialized.
+    pub admin:              Pubkey,
+    /// Signer allowed to withdraw accrued protocol fees.
+    pub treasury_authority: Pubkey,
+    /// Protocol fee out of [`PROTOCOL_FEE_DENOMINATOR`](crate::math::PROTOCOL_FEE_DENOMINATOR).
+    pub protocol_fee_bps:   u16,
+    /// Share of the protocol fee paid to a swap's referrer, out of `10_000`.
+    pub referral_share_bps: u16,
+    /// `PAUSE_*` bits of the operations governance has switched off.
+    pub paused:             u8,
+    /// Lowest `fee_rate_bps` a new pool may use; see [`Self::pool_fee_bounds`].
+    pub min_pool_fee_bps:   u16,
+    /// Highest `fee_rate_bps` a new pool may use; see [`Self::pool_fee_bounds`].
+    pub max_pool_fee_bps:   u16,
+    /// Protocol fee discounts for agents with an `AgentVolume` account.
+    pub fee_tiers:          [FeeTier; FEE_TIER_COUNT],
+    /// Programs approved to lock positions as collateral; the default
+    /// pubkey is an empty slot.
+    pub lockers:            [Pubkey; LOCKER_COUNT],
+}
+
+impl ProtocolConfigState {
+    /// Inclusive `fee_rate_bps` range new pools must use; 1–100 while
+    /// governance has never set bounds.
+    pub fn pool_fee_bounds(&self) -> (u16, u16) {
+        if self.max_pool_fee_bps == 0 {
+            (MIN_POOL_FEE_BPS, MAX_POOL_FEE_BPS)
+        } else {
+            (self.min_pool_fee_bps, self.max_pool_fee_bps)
+        }
+    }
+
+    /// Whether any of the `PAUSE_*` bits in `op` is set.
+    pub fn is_paused(&self, op: u8) -> bool {
+        self.paused & op != 0
+    }
+
+    /// Index of the tier an agent with `volume` traded is in: the one with
+    /// the largest discount among those whose `min_volume` it has reached.
+    /// `None` below every set tier.
+    pub fn tier_for(&self, volume: u64) -> Option<usize> {
+        self.fee_tiers
+            .iter()
+            .enumerate()
+            .filter(|(_, t)| t.discount_bps > 0 && volume >= t.min_volume)
+            .max_by_key(|(_, t)| t.discount_bps)
+            .map(|(i, _)| i)
+    }
+
+    /// Protocol fee (out of `100_000`) an agent with `volume` traded pays
+    /// when it swaps with its `AgentVolume` account.
+    pub fn protocol_fee_bps_for(&self, volume: u64) -> u16 {
+        let discount = self.tier_for(volume).map_or(0, |i| self.fee_tiers[i].discount_bps);
+        (self.protocol_fee_bps as u32 * 10_000u32.saturating_sub(discount as u32) / 10_000) as u16
+    }
+
+    /// Whether `program` may lock positions.
+    pub fn is_locker(&self, program: &Pubkey) -> bool {
+        *program != Pubkey::default() && self.lockers.contains(program)
+    }
+}
+
+/// One step of the protocol fee discount schedule — mirrors the program's
+/// `FeeTier`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FeeTier {
+    /// Lifetime `AgentVolume::volume` needed for this tier.
+    pub min_volume:   u64,
+    /// Share of the protocol fee waived, out of `10_000`; 0 = unset.
+    pub discount_bps: u16,
+}
+
+/// Number of tiers in [`ProtocolConfigState::fee_tiers`].
+pub const FEE_TIER_COUNT: usize = 4;
+
+/// Number of slots in [`ProtocolConfigState::lockers`].
+pub const LOCKER_COUNT: usize = 4;
+
+/// Swaps of every kind are refused.
+pub const PAUSE_SWAPS: u8 = 1;
+/// `initialize_pool` and `initialize_range_pool` are refused.
+pub const PAUSE_POOL_CREATION: u8 = 2;
+/// Every pausable operation.
+pub const PAUSE_ALL: u8 = PAUSE_SWAPS | PAUSE_POOL_CREATION;
+/// Default lowest pool fee while governance has set no bounds.
+pub const MIN_POOL_FEE_BPS: u16 = 1;
+/// Default highest pool fee while governance has set no bounds.
+pub const MAX_POOL_FEE_BPS: u16 = 100;
+
+/// Current `ProtocolConfig` account size.
+pub const PROTOCOL_CONFIG_LEN: usize = a2a_swap_core::ProtocolConfig::LEN;
+/// `ProtocolConfig` account size before the pause switches and pool fee bounds.
+pub const PROTOCOL_CONFIG_LEGACY_LEN: usize = 77;
+
+/// Deserialize a `ProtocolConfig` account from raw bytes.
+pub fn parse_protocol_config(data: &[u8]) -> Result<ProtocolConfigState> {
+    if data.len() < PROTOCOL_CONFIG_LEGACY_LEN {
+        return Err(Error::ParseError {
+            offset: 0,
+            reason: format!(
+                "ProtocolConfig account is {} bytes; expected {}",
+                data.len(),
+                PROTOCOL_CONFIG_LEN
+            ),
+        });
+    }
+    let p = a2a_swap_core::ProtocolConfig::from_account_data(data)?;
+    let fee_tiers = p.fee_tiers.map(|t| FeeTier { min_volume: t.min_volume, discount_bps: t.discount_bps });
+    Ok(ProtocolConfigState {
+        admin:              p.admin.into(),
+        treasury_authority: p.treasury_authority.into(),
+        protocol_fee_bps:   p.protocol_fee_bps,
+        referral_share_bps: p.referral_share_bps,
+        paused:             p.paused,
+        min_pool_fee_bps:   p.min_pool_fee_bps,
+        max_pool_fee_bps:   p.max_pool_fee_bps,
+        fee_tiers,
+        lockers:            p.lockers.map(Pubkey::from),
+    })
+}
+
+// ─── AgentVolume ──────────────────────────────────────────────────────────────
+
+/// Deserialized `AgentVolume` account state: an agent's opt-in swap volume,
+/// at [`derive_agent_volume`](crate::instructions::derive_agent_volume).
+///
+/// Layout (after 8-byte Anchor discriminator):
+/// ```text
+/// agent(32)  volume(8)  swaps(8)  bump(1)
+/// = 57 bytes
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AgentVolumeState {
+    pub agent:  Pubkey,
+    /// Sum of `amount_in` over tracked swaps, in atomic units of each
+    /// swap's input token.
+    pub volume: u64,
+    /// Tracked swaps so far.
+    pub swaps:  u64,
+}
+
+/// Byte length of an `AgentVolume` account.
+pub const AGENT_VOLUME_LEN: usize = a2a_swap_core::AgentVolume::LEN;
+
+/// Deserialize an `AgentVolume` account from raw bytes.
+pub fn parse_agent_volume(data: &[u8]) -> Result<AgentVolumeState> {
+    if data.len() < AGENT_VOLUME_LEN {
+        return Err(Error::ParseError {
+            offset: 0,
+            reason: format!("AgentVolume account is {} bytes; expected {}", data.len(), AGENT_VOLUME_LEN),
+        });
+    }
+    let v = a2a_swap_core::AgentVolume::from_account_data(data)?;
+    Ok(AgentVolumeState { agent: v.agent.into(), volume: v.volume, swaps: v.swaps })
+}
+
+/// Deserialized `IntentNonce` account state: the replay guard for an
+/// agent's signed swap intents, at
+/// [`derive_intent_nonce`](crate::instructions::derive_intent_nonce).
+///
+/// Layout (after 8-byte Anchor discriminator):
+/// ```text
+/// agent(32)  nonce(8)  bump(1)
+/// = 49 bytes
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct IntentNonceState {
+    pub agent: Pubkey,
+    /// Nonce the agent's next intent must carry.
+    pub nonce: u64,
+}
+
+/// Byte length of an `IntentNonce` account.
+pub const INTENT_NONCE_LEN: usize = a2a_swap_core::IntentNonce::LEN;
+
+/// Deserialize an `IntentNonce` account from raw bytes.
+pub fn parse_intent_nonce(data: &[u8]) -> Result<IntentNonceState> {
+    if data.len() < INTENT_NONCE_LEN {
+        return Err(Error::ParseError {
+            offset: 0,
+            reason: format!("IntentNonce account is {} bytes; expected {}", data.len(), INTENT_NONCE_LEN),
+        });
+    }
+    let n = a2a_swap_core::IntentNonce::from_account_data(data)?;
+    Ok(IntentNonceState { agent: n.agent.into(), nonce: n.nonce })
+}
+
+/// Deserialized `TraderPass` account state: a trader's proven place on a
+/// permissioned pool's allowlist, at
+/// [`derive_trader_pass`](crate::instructions::derive_trader_pass).
+///
+/// Layout (after 8-byte Anchor discriminator):
+/// ```text
+/// pool(32)  trader(32)  root(32)  bump(1)
+/// = 105 bytes
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TraderPassState {
+    pub pool:   Pubkey,
+    pub trader: Pubkey,
+    /// Allowlist root the trader's proof was checked against; the pass is
+    /// only accepted while this is still the pool's root.
+    pub root:   [u8; 32],
+}
+
+impl TraderPassState {
+    /// Whether swaps on `pool` accept this pass.
+    pub fn is_valid_for(&self, pool: &PoolState) -> bool {
+        pool.is_permissioned() && self.root == pool.allowlist_root
+    }
+}
+
+/// Byte length of a `TraderPass` account.
+pub const TRADER_PASS_LEN: usize = a2a_swap_core::TraderPass::LEN;
+
+/// Deserialize a `TraderPass` account from raw bytes.
+pub fn parse_trader_pass(data: &[u8]) -> Result<TraderPassState> {
+    if data.len() < TRADER_PASS_LEN {
+        return Err(Error::ParseError {
+            offset: 0,
+            reason: format!("TraderPass account is {} bytes; expected {}", data.len(), TRADER_PASS_LEN),
+        });
+    }
+    let t = a2a_swap_core::TraderPass::from_account_data(data)?;
+    Ok(TraderPassState { pool: t.pool.into(), trader: t.trader.into(), root: t.root })
+}
+
+// ─── Governance ───────────────────────────────────────────────────────────────
+
+/// Change a passed proposal makes to the protocol config or one pool —
+/// mirrors the program's `GovernanceAction`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "action", rename_all = "snake_case")]
+pub enum GovernanceAction {
+    /// Set the protocol fee (out of `100_000`) and the referrer's share of
+    /// it (out of `10_000`).
+    SetProtocolFee { protocol_fee_bps: u16, referral_share_bps: u16 },
+    /// Set the inclusive `fee_rate_bps` range for new pools (at most 1 000).
+    SetPoolFeeBounds { min_fee_bps: u16, max_fee_bps: u16 },
+    /// Replace the `PAUSE_*` bits.
+    SetPaused { paused: u8 },
+    /// Replace fee tier `index` (below [`FEE_TIER_COUNT`]); a zero
+    /// `discount_bps` (out of `10_000`) clears it.
+    SetFeeTier { index: u8, min_volume: u64, discount_bps: u16 },
+    /// Replace locker slot `index` (below [`LOCKER_COUNT`]); the default
+    /// pubkey clears it.
+    SetLocker { index: u8, program: Pubkey },
+    /// Bound `pool`'s volatility-driven LP fee (at most 100 bps); a zero
+    /// `max_fee_bps` turns it off.
+    ConfigureDynamicFee { pool: Pubkey, min_fee_bps: u16, max_fee_bps: u16 },
+    /// Cap `pool`'s net price move per slot (out of `10_000`); zero turns
+    /// it off.
+    ConfigureCircuitBreaker { pool: Pubkey, max_move_bps: u16 },
+}
+
+impl GovernanceAction {
+    /// The pool the action changes, which `execute_proposal` must be passed.
+    pub fn pool(&self) -> Option<Pubkey> {
+        match *self {
+            GovernanceAction::ConfigureDynamicFee { pool, .. }
+            | GovernanceAction::ConfigureCircuitBreaker { pool, .. } => Some(pool),
+            _ => None,
+        }
+    }
+}
+
+impl From<a2a_swap_core::GovernanceAction> for GovernanceAction {
+    fn from(a: a2a_swap_core::GovernanceAction) -> Self {
+        use a2a_swap_core::GovernanceAction as A;
+        match a {
+            A::SetProtocolFee { protocol_fee_bps, referral_share_bps } =>
+                GovernanceAction::SetProtocolFee { protocol_fee_bps, referral_share_bps },
+            A::SetPoolFeeBounds { min_fee_bps, max_fee_bps } =>
+                GovernanceAction::SetPoolFeeBounds { min_fee_bps, max_fee_bps },
+            A::SetPaused { paused } => GovernanceAction::SetPaused { paused },
+            A::SetFeeTier { index, min_volume, discount_bps } =>
+                GovernanceAction::SetFeeTier { index, min_volume, discount_bps },
+            A::SetLocker { index, program } =>
+                GovernanceAction::SetLocker { index, program: program.into() },
+            A::ConfigureDynamicFee { pool, min_fee_bps, max_fee_bps } =>
+                GovernanceAction::ConfigureDynamicFee { pool: pool.into(), min_fee_bps, max_fee_bps },
+            A::ConfigureCircuitBreaker { pool, max_move_bps } =>
+                GovernanceAction::ConfigureCircuitBreaker { pool: pool.into(), max_move_bps },
+        }
+    }
+}
+
+impl From<GovernanceAction> for a2a_swap_core::GovernanceAction {
+    fn from(a: GovernanceAction) -> Self {
+        use a2a_swap_core::GovernanceAction as A;
+        match a {
+            GovernanceAction::SetProtocolFee { protocol_fee_bps, referral_share_bps } =>
+                A::SetProtocolFee { protocol_fee_bps, referral_share_bps },
+            GovernanceAction::SetPoolFeeBounds { min_fee_bps, max_fee_bps } =>
+                A::SetPoolFeeBounds { min_fee_bps, max_fee_bps },
+            GovernanceAction::SetPaused { paused } => A::SetPaused { paused },
+            GovernanceAction::SetFeeTier { index, min_volume, discount_bps } =>
+                A::SetFeeTier { index, min_volume, discount_bps },
+            GovernanceAction::SetLocker { index, program } =>
+                A::SetLocker { index, program: program.to_bytes() },
+            GovernanceAction::ConfigureDynamicFee { pool, min_fee_bps, max_fee_bps } =>
+                A::ConfigureDynamicFee { pool: pool.to_bytes(), min_fee_bps, max_fee_bps },
+            GovernanceAction::ConfigureCircuitBreaker { pool, max_move_bps } =>
+                A::ConfigureCircuitBreaker { pool: pool.to_bytes(), max_move_bps },
+        }
+    }
+}
+
+/// Deserialized `Governance` account state.
+///
+/// Layout (after 8-byte Anchor discriminator):
+/// ```text
+/// vote_mint(32)  quorum(8)  voting_period_secs(8)  proposal_count(8)  bump(1)
+/// = 65 bytes
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct GovernanceState {
+    /// Token voters escrow; one atomic unit is one vote.
+    pub vote_mint:          Pubkey,
+    /// Least total votes (for + against) a proposal needs to pass.
+    pub quorum:             u64,
+    /// How long each proposal is open for votes.
+    pub voting_period_secs: i64,
+    /// Proposals created so far; the id of the next one.
+    pub proposal_count:     u64,
+}
+
+/// Byte length of a `Governance` account.
+pub const GOVERNANCE_LEN: usize = a2a_swap_core::Governance::LEN;
+
+/// Deserialize a `Governance` account from raw bytes.
+pub fn parse_governance(data: &[u8]) -> Result<GovernanceState> {
+    if data.len() < GOVERNANCE_LEN {
+        return Err(Error::ParseError {
+            offset: 0,
+            reason: format!("Governance account is {} bytes; expected {}", data.len(), GOVERNANCE_LEN),
+        });
+    }
+    let g = a2a_swap_core::Governance::from_account_data(data)?;
+    Ok(GovernanceState {
+        vote_mint:          g.vote_mint.into(),
+        quorum:             g.quorum,
+        voting_period_secs: g.voting_period_secs,
+        proposal_count:     g.proposal_count,
+    })
+}
+
+/// Deserialized `Proposal` account state.
+///
+/// Layout (after 8-byte Anchor discriminator):
+/// ```text
+/// id(8)  proposer(32)  action(37)  votes_for(8)  votes_against(8)
+/// voting_ends_at(8)  executed(1)  bump(1)
+/// = 111 bytes
+/// ```
+///
+/// Proposals created before `SetFeeTier` (79 bytes), `SetLocker`
+/// (86 bytes) or `ConfigureDynamicFee` (108 bytes) existed have a shorter
+/// action slot; their actions fit in it and parse the same.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ProposalState {
+    pub id:             u64,
+    pub proposer:       Pubkey,
+    pub action:         GovernanceAction,
+    pub votes_for:      u64,
+    pub votes_against:  u64,
+    /// Unix timestamp voting closes.
+    pub voting_ends_at: i64,
+    pub executed:       bool,
+}
+
+impl ProposalState {
+    /// Whether the proposal passes under `quorum`: more votes for than
+    /// against, and at least `quorum` votes cast.
+    pub fn passed(&self, quorum: u64) -> bool {
+        self.votes_for > self.votes_against
+            && self.votes_for.saturating_add(self.votes_against) >= quorum
+    }
+}
+
+/// Byte length of a `Proposal` account.
+pub const PROPOSAL_LEN: usize = a2a_swap_core::Proposal::LEN;
+/// Byte length of a `Proposal` created before `SetFeeTier`.
+pub const PROPOSAL_LEGACY_LEN: usize = 79;
+
+/// Deserialize a `Proposal` account from raw bytes.
+pub fn parse_proposal(data: &[u8]) -> Result<ProposalState> {
+    if data.len() < PROPOSAL_LEGACY_LEN {
+        return Err(Error::ParseError {
+            offset: 0,
+            reason: format!("Proposal account is {} bytes; expected {}", data.len(), PROPOSAL_LEN),
+        });
+    }
+    let p = a2a_swap_core::Proposal::from_account_data(data)?;
+    Ok(ProposalState {
+        id:             p.id,
+        proposer:       p.proposer.into(),
+        action:         p.action.into(),
+        votes_for:      p.votes_for,
+        votes_against:  p.votes_against,
+        voting_ends_at: p.voting_ends_at,
+        executed:       p.executed,
+    })
+}
+
+/// Deserialized `VoteRecord` account state.
+///
+/// Layout (after 8-byte Anchor discriminator):
+/// ```text
+/// proposal(32)  voter(32)  support(1)  amount(8)  bump(1)
+/// = 82 bytes
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct VoteRecordState {
+    pub proposal: Pubkey,
+    pub voter:    Pubkey,
+    /// `true` for, `false` against.
+    pub support:  bool,
+    /// Escrowed vote tokens, and the ballot's weight.
+    pub amount:   u64,
+}
+
+/// Byte length of a `VoteRecord` account.
+pub const VOTE_RECORD_LEN: usize = a2a_swap_core::VoteRecord::LEN;
+
+/// Deserialize a `VoteRecord` account from raw bytes.
+pub fn parse_vote_record(data: &[u8]) -> Result<VoteRecordState> {
+    if data.len() < VOTE_RECORD_LEN {
+        return Err(Error::ParseError {
+            offset: 0,
+            reason: format!("VoteRecord account is {} bytes; expected {}", data.len(), VOTE_RECORD_LEN),
+        });
+    }
+    let v = a2a_swap_core::VoteRecord::from_account_data(data)?;
+    Ok(VoteRecordState {
+        proposal: v.proposal.into(),
+        voter:    v.voter.into(),
+        support:  v.support,
+        amount:   v.amount,
+    })
+}
+
+// ─── Delegate ─────────────────────────────────────────────────────────────────
+
+/// Deserialized `Delegate` (session key) account state.
+///
+/// Layout (after 8-byte Anchor discriminator):
+/// ```text
+/// owner(32)  delegate(32)  token_account(32)  max_amount_in(8)  spent(8)
+/// expires_at(8)  bump(1)
+/// = 129 bytes
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DelegateState {
+    /// Wallet whose tokens the session spends.
+    pub owner:         Pubkey,
+    /// Session key allowed to call `swap_as_delegate`.
+    pub delegate:      Pubkey,
+    /// Owner token account swaps are paid from.
+    pub token_account: Pubkey,
+    /// Total `amount_in` the session may spend.
+    pub max_amount_in: u64,
+    /// `amount_in` spent so far.
+    pub spent:         u64,
+    /// Unix timestamp after which the session is refused.
+    pub expires_at:    i64,
+}
+
+impl DelegateState {
+    /// Input the session may still spend.
+    pub fn remaining(&self) -> u64 {
+        self.max_amount_in.saturating_sub(self.spent)
+    }
+}
+
+/// Byte length of a `Delegate` account.
+pub const DELEGATE_LEN: usize = a2a_swap_core::Delegate::LEN;
+
+/// Deserialize a `Delegate` account from raw bytes.
+pub fn parse_delegate(data: &[u8]) -> Result<DelegateState> {
+    if data.len() < DELEGATE_LEN {
+        return Err(Error::ParseError {
+            offset: 0,
+            reason: format!("Delegate account is {} bytes; expected {}", data.len(), DELEGATE_LEN),
+        });
+    }
+    let d = a2a_swap_core::Delegate::from_account_data(data)?;
+    Ok(DelegateState {
+        owner:         d.owner.into(),
+        delegate:      d.delegate.into(),
+        token_account: d.token_account.into(),
+        max_amount_in: d.max_amount_in,
+        spent:         d.spent,
+        expires_at:    d.expires_at,
+    })
+}
+
+// ─── Anchor IDL account ───────────────────────────────────────────────────────
+
+/// Decode the program's on-chain Anchor IDL account (at
+/// [`derive_idl_address`](crate::instructions::derive_idl_address)) into
+/// the IDL JSON.
+///
+/// Layout: `discriminator(8) authority(32) data_len(u32) data(data_len)`,
+/// where `data` is the zlib-compressed IDL.
+pub fn parse_idl_account(data: &[u8]) -> Result<serde_json::Value> {
+    const HEADER: usize = 8 + 32 + 4;
+    if data.len() < HEADER {
+        return Err(Error::ParseError {
+            offset: 0,
+            reason: format!("IDL account is {} bytes; need at least {HEADER}", data.len()),
+        });
+    }
+    if data[..8] != Sha256::digest(b"account:IdlAccount")[..8] {
+        return Err(Error::ParseError { offset: 0, reason: "not an Anchor IDL account".into() });
+    }
+    let len = u32::from_le_bytes(data[40..HEADER].try_into().unwrap()) as usize;
+    let compressed = data.get(HEADER..HEADER + len).ok_or_else(|| Error::ParseError {
+        offset: 40,
+        reason: format!("IDL data length {len} runs past the {}-byte account", data.len()),
+    })?;
+    let mut json = Vec::new();
+    ZlibDecoder::new(compressed)
+        .read_to_end(&mut json)
+        .map_err(|e| Error::ParseError { offset: HEADER, reason: format!("IDL data does not inflate: {e}") })?;
+    serde_json::from_slice(&json)
+        .map_err(|e| Error::ParseError { offset: HEADER, reason: format!("IDL is not JSON: {e}") })
+}
+
 // ─── SPL token account ────────────────────────────────────────────────────────
 
+/// Size of a packed SPL token account, for its rent.
+pub const TOKEN_ACCOUNT_LEN: usize = 165;
+
 /// Read the `amount` field from a packed SPL token account.
 ///
 /// Token account layout: `mint(32) owner(32) amount(8) …`
@@ -118,33 +1137,9 @@ pub fn parse_token_amount(data: &[u8]) -> Result<u64> {
 
 // ─── Byte-slice primitives ────────────────────────────────────────────────────
 
-pub(crate) fn read_pubkey(data: &[u8], offset: usize) -> Result<Pubkey> {
-    let b: [u8; 32] = data[offset..offset + 32]
-        .try_into()
-        .map_err(|_| Error::ParseError {
-            offset,
-            reason: "slice too short for Pubkey (32 bytes)".into(),
-        })?;
-    Ok(Pubkey::from(b))
-}
-
-pub(crate) fn read_u16(data: &[u8], offset: usize) -> Result<u16> {
-    let b: [u8; 2] = data[offset..offset + 2]
-        .try_into()
-        .map_err(|_| Error::ParseError { offset, reason: "slice too short for u16".into() })?;
-    Ok(u16::from_le_bytes(b))
-}
-
-pub(crate) fn read_u64(data: &[u8], offset: usize) -> Result<u64> {
+fn read_u64(data: &[u8], offset: usize) -> Result<u64> {
     let b: [u8; 8] = data[offset..offset + 8]
         .try_into()
         .map_err(|_| Error::ParseError { offset, reason: "slice too short for u64".into() })?;
     Ok(u64::from_le_bytes(b))
 }
-
-pub(crate) fn read_u128(data: &[u8], offset: usize) -> Result<u128> {
-    let b: [u8; 16] = data[offset..offset + 16]
-        .try_into()
-        .map_err(|_| Error::ParseError { offset, reason: "slice too short for u128".into() })?;
-    Ok(u128::from_le_bytes(b))
-}
@@ -0,0 +1,85 @@
+//! Dollar-cost averaging: buy a fixed amount on a fixed interval.
+//!
+//! [`DcaStrategy`] asks for one swap of `amount_in` whenever `interval_secs`
+//! have passed since its last fill (at the first tick before any), by the
+//! update's timestamp — so a replay over history buys on the historical
+//! schedule. A failed buy is retried at the next tick.
+
+use solana_sdk::pubkey::Pubkey;
+
+use super::{Action, Fill, PoolUpdate, Strategy};
+use crate::types::{ProtectionLevel, SwapParams};
+
+/// A recurring buy of `mint_out` with `mint_in`.
+#[derive(Debug, Clone)]
+pub struct DcaStrategy {
+    /// Token spent.
+    pub mint_in: Pubkey,
+    /// Token bought.
+    pub mint_out: Pubkey,
+    /// `mint_in` atomic units per buy.
+    pub amount_in: u64,
+    /// Seconds between buys.
+    pub interval_secs: i64,
+    /// Slippage guard for each buy.
+    pub max_slippage_bps: u16,
+    /// Price-impact cap for each buy (`0` = no cap).
+    pub max_price_impact_bps: u16,
+    /// Buys left; `None` runs forever.
+    pub buys_left: Option<u32>,
+    /// Timestamp of the last filled buy.
+    pub last_buy: Option<i64>,
+    /// Timestamp of the tick that asked for the buy in flight.
+    asked_at: i64,
+}
+
+impl DcaStrategy {
+    pub fn new(mint_in: Pubkey, mint_out: Pubkey, amount_in: u64, interval_secs: i64) -> Self {
+        Self {
+            mint_in,
+            mint_out,
+            amount_in,
+            interval_secs,
+            max_slippage_bps:     50,
+            max_price_impact_bps: 0,
+            buys_left:            None,
+            last_buy:             None,
+            asked_at:             0,
+        }
+    }
+}
+
+impl Strategy for DcaStrategy {
+    fn name(&self) -> &str {
+        "dca"
+    }
+
+    fn on_tick(&mut self, update: &PoolUpdate) -> Vec<Action> {
+        let due = match self.last_buy {
+            Some(t) => update.timestamp - t >= self.interval_secs,
+            None    => true,
+        };
+        if !due || self.buys_left == Some(0) || self.amount_in == 0 {
+            return Vec::new();
+        }
+        self.asked_at = update.timestamp;
+        vec![Action::Swap(SwapParams {
+            mint_in:              self.mint_in,
+            mint_out:             self.mint_out,
+            amount_in:            self.amount_in,
+            max_slippage_bps:     self.max_slippage_bps,
+            min_amount_out:       None,
+            max_price_impact_bps: self.max_price_impact_bps,
+            deadline_unix:        0,
+            referrer:             None,
+            protection:           ProtectionLevel::None,
+        })]
+    }
+
+    fn on_fill(&mut self, _action: &Action, _fill: &Fill) {
+        self.last_buy = Some(self.asked_at);
+        if let Some(n) = &mut self.buys_left {
+            *n = n.saturating_sub(1);
+        }
+    }
+}
@@ -0,0 +1,322 @@
+//! Grid trading: a ladder of buy and sell levels around the pool price.
+//!
+//! A2A-Swap has no resting orders, so the grid is emulated with market
+//! swaps. Levels sit at `center × (1 + spacing)^i` for `i` in
+//! `−levels..=levels`, priced in quote atomic units per base atomic unit.
+//! The level of the last fill is the *anchor*: levels above it sell one
+//! `level_size` of base, levels below it buy one. Each [`step`] reads the
+//! pool's spot price and fills every level the price has crossed since the
+//! anchor, nearest first; selling at a level re-arms the buy one level
+//! below, and vice versa, so every round trip earns the spacing.
+//!
+//! Every swap carries a per-level minimum output — the level's own price
+//! less `max_slippage_bps` — so no level fills worse than that, however far
+//! the pool has moved. A level whose pre-flight quote falls short is left
+//! armed for the next step. `max_levels_per_step` caps how many levels one
+//! gap can fill.
+//!
+//! [`GridState`] is plain serde data. Save it after every step
+//! ([`to_json`](GridState::to_json)) to resume the grid after a restart.
+//! [`GridStrategy`] runs the same grid under a [`Runner`](super::Runner),
+//! for dry runs and backtests.
+//!
+//! ```rust,no_run
+//! # use a2a_swap_sdk::{strategies::grid::{self, GridConfig, GridState}, A2ASwapClient};
+//! # async fn run(client: A2ASwapClient, payer: solana_sdk::signature::Keypair,
+//! #              sol: solana_sdk::pubkey::Pubkey, usdc: solana_sdk::pubkey::Pubkey) -> a2a_swap_sdk::Result<()> {
+//! let config = GridConfig {
+//!     base_mint:            sol,
+//!     quote_mint:           usdc,
+//!     levels:               5,
+//!     spacing_bps:          100,          // 1% between levels
+//!     level_size:           100_000_000,  // 0.1 SOL per level
+//!     max_slippage_bps:     30,
+//!     max_price_impact_bps: 100,
+//!     max_levels_per_step:  2,
+//!     referrer:             None,
+//! };
+//! let mut state = GridState::new(&config, grid::pool_price(&client, &config).await?)?;
+//! loop {
+//!     for fill in grid::step(&client, &payer, &config, &mut state).await? {
+//!         println!("{:?} level {} → {:?}", fill.side, fill.level, fill.signature);
+//!     }
+//!     std::fs::write("grid-state.json", state.to_json()).unwrap();
+//!     tokio::time::sleep(std::time::Duration::from_secs(30)).await;
+//! }
+//! # }
+//! ```
+
+use std::collections::VecDeque;
+
+use serde::{Deserialize, Serialize};
+use solana_sdk::{pubkey::Pubkey, signer::Signer};
+
+use super::{oriented_price, Action, Fill, PoolUpdate, Strategy};
+use crate::client::A2ASwapClient;
+use crate::error::{Error, Result};
+use crate::math::unix_now;
+use crate::types::{ProtectionLevel, SwapParams};
+
+/// Basis points in a whole.
+const BPS: f64 = 10_000.0;
+
+/// Static grid parameters.
+#[derive(Debug, Clone)]
+pub struct GridConfig {
+    /// Token the grid buys and sells.
+    pub base_mint: Pubkey,
+    /// Token prices are quoted in; a pool must pair it with `base_mint`.
+    pub quote_mint: Pubkey,
+    /// Levels on each side of the center.
+    pub levels: u32,
+    /// Distance between neighbouring levels in basis points (1–10_000).
+    pub spacing_bps: u16,
+    /// Base atomic units bought or sold per level.
+    pub level_size: u64,
+    /// How far below its level's price a fill may come out, in basis points.
+    pub max_slippage_bps: u16,
+    /// Price-impact cap for each level's swap (`0` = no cap).
+    pub max_price_impact_bps: u16,
+    /// Most levels filled in one [`step`] (`0` = no limit).
+    pub max_levels_per_step: u32,
+    /// Integrator wallet for the referral share of the protocol fee.
+    pub referrer: Option<Pubkey>,
+}
+
+/// Direction of a level's swap.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum GridSide {
+    /// Quote → base.
+    Buy,
+    /// Base → quote.
+    Sell,
+}
+
+/// A level the price has crossed, ready to send — see [`GridState::pending`].
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct GridOrder {
+    /// Index into [`GridState::level_prices`].
+    pub level: usize,
+    pub side: GridSide,
+    /// The level's price, quote per base atomic unit.
+    pub level_price: f64,
+    /// Tokens sold: `level_size` base for a sell, its quote value for a buy.
+    pub amount_in: u64,
+    /// Least the swap may return: the level's price less `max_slippage_bps`.
+    pub min_amount_out: u64,
+}
+
+/// A confirmed level swap.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GridFill {
+    pub level: usize,
+    pub side: GridSide,
+    pub level_price: f64,
+    pub amount_in: u64,
+    /// Tokens received — see [`Fill::amount_out`].
+    pub amount_out: u64,
+    /// `None` for a dry-run or backtest fill.
+    pub signature: Option<String>,
+    /// Unix time of the fill — the pool update's when run under a
+    /// [`Runner`](super::Runner).
+    pub timestamp: i64,
+}
+
+/// A running grid: level prices, the anchor, and every fill so far.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GridState {
+    /// Level prices in ascending order; the center is the middle one.
+    pub level_prices: Vec<f64>,
+    /// Level of the last fill (the center before any).
+    pub anchor: usize,
+    /// Fills in execution order.
+    pub fills: Vec<GridFill>,
+}
+
+impl GridState {
+    /// A fresh grid centred on `center_price` (quote per base atomic unit).
+    pub fn new(config: &GridConfig, center_price: f64) -> Result<Self> {
+        if !(center_price.is_finite() && center_price > 0.0) {
+            return Err(Error::NoLiquidity);
+        }
+        if config.levels == 0 || config.level_size == 0 {
+            return Err(Error::InvalidArgument("grid needs at least one level and a non-zero level_size".into()));
+        }
+        if config.spacing_bps == 0 || config.spacing_bps as f64 > BPS {
+            return Err(Error::InvalidArgument(format!(
+                "spacing_bps {} must be within 1–10000", config.spacing_bps
+            )));
+        }
+        let step = 1.0 + config.spacing_bps as f64 / BPS;
+        let n = config.levels as i32;
+        Ok(Self {
+            level_prices: (-n..=n).map(|i| center_price * step.powi(i)).collect(),
+            anchor:       config.levels as usize,
+            fills:        Vec::new(),
+        })
+    }
+
+    /// Levels to fill at `price`, nearest the anchor first: sells for every
+    /// level above the anchor at or below `price`, else buys for every level
+    /// below it at or above `price`, up to `max_levels_per_step`.
+    pub fn pending(&self, config: &GridConfig, price: f64) -> Vec<GridOrder> {
+        let cap = match config.max_levels_per_step {
+            0 => usize::MAX,
+            n => n as usize,
+        };
+        let sells = (self.anchor + 1..self.level_prices.len())
+            .take_while(|&k| price >= self.level_prices[k])
+            .map(|k| self.order(config, k, GridSide::Sell));
+        let orders: Vec<GridOrder> = sells.take(cap).collect();
+        if !orders.is_empty() {
+            return orders;
+        }
+        (0..self.anchor)
+            .rev()
+            .take_while(|&k| price <= self.level_prices[k])
+            .map(|k| self.order(config, k, GridSide::Buy))
+            .take(cap)
+            .collect()
+    }
+
+    /// Record an order executed at `timestamp` and move the anchor to its level.
+    pub fn record(&mut self, order: &GridOrder, fill: &Fill, timestamp: i64) -> GridFill {
+        let fill = GridFill {
+            level:       order.level,
+            side:        order.side,
+            level_price: order.level_price,
+            amount_in:   fill.amount_in,
+            amount_out:  fill.amount_out,
+            signature:   fill.signature.clone(),
+            timestamp,
+        };
+        self.anchor = order.level;
+        self.fills.push(fill.clone());
+        fill
+    }
+
+    /// Net `(base, quote)` the grid has moved into the wallet across all
+    /// fills: negative for what it spent.
+    pub fn net_flows(&self) -> (i128, i128) {
+        self.fills.iter().fold((0, 0), |(base, quote), f| match f.side {
+            GridSide::Sell => (base - f.amount_in as i128, quote + f.amount_out as i128),
+            GridSide::Buy  => (base + f.amount_out as i128, quote - f.amount_in as i128),
+        })
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).unwrap()
+    }
+
+    pub fn from_json(json: &str) -> Result<Self> {
+        let state: Self = serde_json::from_str(json)
+            .map_err(|e| Error::InvalidArgument(format!("grid state: {e}")))?;
+        if state.anchor >= state.level_prices.len() {
+            return Err(Error::InvalidArgument("grid state: anchor is outside the levels".into()));
+        }
+        Ok(state)
+    }
+
+    fn order(&self, config: &GridConfig, level: usize, side: GridSide) -> GridOrder {
+        let level_price = self.level_prices[level];
+        let keep = 1.0 - config.max_slippage_bps as f64 / BPS;
+        let size = config.level_size as f64;
+        let (amount_in, min_amount_out) = match side {
+            GridSide::Sell => (config.level_size, (size * level_price * keep) as u64),
+            GridSide::Buy  => ((size * level_price).ceil() as u64, (size * keep) as u64),
+        };
+        GridOrder { level, side, level_price, amount_in, min_amount_out }
+    }
+}
+
+impl GridConfig {
+    /// The swap that executes `order`: its own `min_amount_out`, no
+    /// percentage slippage.
+    pub fn swap_params(&self, order: &GridOrder) -> SwapParams {
+        let (mint_in, mint_out) = match order.side {
+            GridSide::Sell => (self.base_mint, self.quote_mint),
+            GridSide::Buy  => (self.quote_mint, self.base_mint),
+        };
+        SwapParams {
+            mint_in,
+            mint_out,
+            amount_in:            order.amount_in,
+            max_slippage_bps:     0,
+            min_amount_out:       Some(order.min_amount_out),
+            max_price_impact_bps: self.max_price_impact_bps,
+            deadline_unix:        0,
+            referrer:             self.referrer,
+            protection:           ProtectionLevel::None,
+        }
+    }
+}
+
+/// The pool's spot price in quote atomic units per base atomic unit.
+pub async fn pool_price(client: &A2ASwapClient, config: &GridConfig) -> Result<f64> {
+    let info = client.pool_info(config.base_mint, config.quote_mint).await?;
+    oriented_price(&info, &config.base_mint).ok_or(Error::NoLiquidity)
+}
+
+/// Fill the levels the pool price has crossed, recording each in `state`.
+///
+/// Stops at the first level whose pre-flight quote misses its minimum (it
+/// stays armed). Any other error is returned, with the fills before it
+/// already recorded in `state`.
+pub async fn step(
+    client: &A2ASwapClient,
+    payer:  &dyn Signer,
+    config: &GridConfig,
+    state:  &mut GridState,
+) -> Result<Vec<GridFill>> {
+    let price = pool_price(client, config).await?;
+    let mut fills = Vec::new();
+    for order in state.pending(config, price) {
+        match client.convert(payer, config.swap_params(&order)).await {
+            Ok(result) => fills.push(state.record(&order, &Fill::from(&result), unix_now())),
+            Err(Error::SlippageExceeded { .. }) => break,
+            Err(e) => return Err(e),
+        }
+    }
+    Ok(fills)
+}
+
+/// A grid as a [`Strategy`]: each tick asks for the orders
+/// [`GridState::pending`] returns at the update's price, and each fill is
+/// recorded in `state`.
+#[derive(Debug, Clone)]
+pub struct GridStrategy {
+    pub config: GridConfig,
+    pub state: GridState,
+    /// Orders from the last tick not yet filled, in action order.
+    in_flight: VecDeque<GridOrder>,
+    /// Timestamp of the last tick.
+    ticked_at: i64,
+}
+
+impl GridStrategy {
+    pub fn new(config: GridConfig, state: GridState) -> Self {
+        Self { config, state, in_flight: VecDeque::new(), ticked_at: 0 }
+    }
+}
+
+impl Strategy for GridStrategy {
+    fn name(&self) -> &str {
+        "grid"
+    }
+
+    fn on_tick(&mut self, update: &PoolUpdate) -> Vec<Action> {
+        let Some(price) = update.price_of(self.config.base_mint) else {
+            return Vec::new();
+        };
+        self.ticked_at = update.timestamp;
+        self.in_flight = self.state.pending(&self.config, price).into();
+        self.in_flight.iter().map(|o| Action::Swap(self.config.swap_params(o))).collect()
+    }
+
+    fn on_fill(&mut self, _action: &Action, fill: &Fill) {
+        if let Some(order) = self.in_flight.pop_front() {
+            self.state.record(&order, fill, self.ticked_at);
+        }
+    }
+}
@@ -0,0 +1,209 @@
+//! Trading strategies that run on top of [`A2ASwapClient`](crate::A2ASwapClient).
+//!
+//! A [`Strategy`] sees one [`PoolUpdate`] per tick and answers with the
+//! [`Action`]s to take; the [`Runner`] feeds it pool updates, screens each
+//! action through its [`Policy`]s and executes the rest as live swaps,
+//! dry-run simulations against the chain, or backtest fills quoted locally
+//! from the update itself (see [`Runner`]). Fills are
+//! reported back through [`Strategy::on_fill`], so the same strategy code
+//! drives all three.
+//!
+//! Built in:
+//!
+//! - [`grid`] — a ladder of buy / sell levels around the pool price,
+//!   emulated with market swaps ([`grid::GridStrategy`]).
+//! - [`dca`] — a fixed buy on a fixed interval ([`dca::DcaStrategy`]).
+//! - [`RebalanceStrategy`](crate::rebalancer::RebalanceStrategy) — hold two
+//!   tokens at a target value ratio.
+//!
+//! A plugin is any type implementing [`Strategy`]:
+//!
+//! ```rust,no_run
+//! # use a2a_swap_sdk::{strategies::{Action, PoolUpdate, Runner, Strategy}, A2ASwapClient,
+//! #                    ProtectionLevel, SwapParams};
+//! # use solana_sdk::pubkey::Pubkey;
+//! /// Sell a fixed amount of A whenever its price clears a threshold.
+//! struct TakeProfit { mint_a: Pubkey, mint_b: Pubkey, above: f64, amount: u64 }
+//!
+//! impl Strategy for TakeProfit {
+//!     fn name(&self) -> &str { "take-profit" }
+//!
+//!     fn on_tick(&mut self, update: &PoolUpdate) -> Vec<Action> {
+//!         match update.price_of(self.mint_a) {
+//!             Some(price) if price > self.above => vec![Action::Swap(SwapParams {
+//!                 mint_in:              self.mint_a,
+//!                 mint_out:             self.mint_b,
+//!                 amount_in:            self.amount,
+//!                 max_slippage_bps:     50,
+//!                 min_amount_out:       None,
+//!                 max_price_impact_bps: 0,
+//!                 deadline_unix:        0,
+//!                 referrer:             None,
+//!                 protection:           ProtectionLevel::None,
+//!             })],
+//!             _ => Vec::new(),
+//!         }
+//!     }
+//! }
+//!
+//! # async fn run(client: A2ASwapClient, sol: Pubkey, usdc: Pubkey) -> a2a_swap_sdk::Result<()> {
+//! let mut strategy = TakeProfit { mint_a: sol, mint_b: usdc, above: 0.2, amount: 1_000_000_000 };
+//! // Dry run: simulate against the live pool every 30 s, send nothing.
+//! Runner::dry_run(&client)
+//!     .run(&mut strategy, sol, usdc, std::time::Duration::from_secs(30), |report| {
+//!         println!("{report:?}");
+//!         true
+//!     })
+//!     .await
+//! # }
+//! ```
+
+pub mod dca;
+pub mod grid;
+mod runner;
+
+pub use runner::{Runner, RunEvent, TickReport};
+
+use serde::{Deserialize, Serialize};
+use solana_sdk::pubkey::Pubkey;
+
+use crate::client::A2ASwapClient;
+use crate::error::Result;
+use crate::math::unix_now;
+use crate::types::{PoolInfo, SwapParams, SwapResult};
+
+/// One observation of a pool, as a strategy sees it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PoolUpdate {
+    /// The pool's state at `timestamp`.
+    pub info: PoolInfo,
+    /// Unix time of the observation — wall clock when live, the recorded
+    /// time when replaying history.
+    pub timestamp: i64,
+}
+
+impl PoolUpdate {
+    /// Read the pool for `mint_a` / `mint_b` now.
+    pub async fn fetch(client: &A2ASwapClient, mint_a: Pubkey, mint_b: Pubkey) -> Result<Self> {
+        Ok(Self { info: client.pool_info(mint_a, mint_b).await?, timestamp: unix_now() })
+    }
+
+    /// Spot price of `base` in the pool's other token (atomic units of the
+    /// other token per atomic unit of `base`). `None` when `base` is not in
+    /// the pool or the pool is empty.
+    pub fn price_of(&self, base: Pubkey) -> Option<f64> {
+        oriented_price(&self.info, &base)
+    }
+}
+
+/// `info.spot_price` turned to price `base`.
+pub(crate) fn oriented_price(info: &PoolInfo, base: &Pubkey) -> Option<f64> {
+    let price = if *base == info.mint_a {
+        info.spot_price
+    } else if *base == info.mint_b {
+        1.0 / info.spot_price
+    } else {
+        return None;
+    };
+    (price.is_finite() && price > 0.0).then_some(price)
+}
+
+/// What a strategy asks the [`Runner`] to do.
+#[derive(Debug, Clone)]
+pub enum Action {
+    /// Swap as [`A2ASwapClient::convert`] would.
+    Swap(SwapParams),
+}
+
+/// An executed [`Action`] — confirmed, simulated or backtested.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Fill {
+    /// Tokens sold.
+    pub amount_in: u64,
+    /// Tokens received: the realized output when live (the pre-flight
+    /// estimate if it could not be read back), else the quoted output.
+    pub amount_out: u64,
+    /// Transaction signature; `None` in dry-run and backtest modes.
+    pub signature: Option<String>,
+}
+
+impl From<&SwapResult> for Fill {
+    fn from(result: &SwapResult) -> Self {
+        Self {
+            amount_in:  result.amount_in,
+            amount_out: result.actual_out.unwrap_or(result.estimated_out),
+            signature:  Some(result.signature.clone()),
+        }
+    }
+}
+
+/// A trading strategy the [`Runner`] can drive.
+pub trait Strategy {
+    /// Label for logs and reports.
+    fn name(&self) -> &str;
+
+    /// Actions to take at `update`, in order.
+    fn on_tick(&mut self, update: &PoolUpdate) -> Vec<Action>;
+
+    /// `action`, from the last [`on_tick`](Self::on_tick), was executed. The
+    /// runner executes a tick's actions in order and stops at the first that
+    /// is refused or fails, so fills always arrive for a prefix of them.
+    fn on_fill(&mut self, _action: &Action, _fill: &Fill) {}
+}
+
+/// A risk check every action passes before the [`Runner`] executes it.
+pub trait Policy {
+    /// `Err(reason)` to refuse `action` at `update`.
+    fn check(&mut self, action: &Action, update: &PoolUpdate) -> std::result::Result<(), String>;
+
+    /// `action` was executed; update any running totals.
+    fn on_fill(&mut self, _action: &Action, _fill: &Fill) {}
+}
+
+/// Caps what the runner may sell of one token: per swap and in total.
+#[derive(Debug, Clone)]
+pub struct SpendCap {
+    mint:     Pubkey,
+    per_swap: u64,
+    total:    u64,
+    spent:    u64,
+}
+
+impl SpendCap {
+    /// At most `per_swap` of `mint` in one swap and `total` over the run
+    /// (`0` = no cap for either).
+    pub fn new(mint: Pubkey, per_swap: u64, total: u64) -> Self {
+        Self { mint, per_swap, total, spent: 0 }
+    }
+
+    /// How much of the mint the runner has sold so far.
+    pub fn spent(&self) -> u64 {
+        self.spent
+    }
+}
+
+impl Policy for SpendCap {
+    fn check(&mut self, action: &Action, _update: &PoolUpdate) -> std::result::Result<(), String> {
+        let Action::Swap(params) = action;
+        if params.mint_in != self.mint {
+            return Ok(());
+        }
+        if self.per_swap > 0 && params.amount_in > self.per_swap {
+            return Err(format!("amount_in {} exceeds the per-swap cap {}", params.amount_in, self.per_swap));
+        }
+        if self.total > 0 && self.spent.saturating_add(params.amount_in) > self.total {
+            return Err(format!(
+                "amount_in {} would take spending past the cap {} ({} spent)",
+                params.amount_in, self.total, self.spent,
+            ));
+        }
+        Ok(())
+    }
+
+    fn on_fill(&mut self, action: &Action, fill: &Fill) {
+        let Action::Swap(params) = action;
+        if params.mint_in == self.mint {
+            self.spent = self.spent.saturating_add(fill.amount_in);
+        }
+    }
+}
@@ -0,0 +1,210 @@
+//! Executes strategy actions: live, dry-run or backtest.
+
+use std::time::Duration;
+
+use solana_sdk::{pubkey::Pubkey, signer::Signer};
+
+use super::{Action, Fill, Policy, PoolUpdate, Strategy};
+use crate::client::A2ASwapClient;
+use crate::error::{Error, Result};
+use crate::math::{
+    apply_simulated_swap, curve_price_impact_bps, resolve_min_amount_out, simulate_snapshot,
+};
+use crate::state::CurveKind;
+use crate::types::{PoolInfo, SimulateParams, SimulateResult, SwapParams};
+
+enum Mode<'a> {
+    Live { client: &'a A2ASwapClient, payer: &'a dyn Signer },
+    DryRun { client: &'a A2ASwapClient },
+    Backtest,
+}
+
+/// What happened to one [`Action`].
+#[derive(Debug)]
+pub enum RunEvent {
+    /// `quote` is the pre-trade simulation the fill was priced from —
+    /// `None` for a live swap.
+    Filled { action: Action, fill: Fill, quote: Option<SimulateResult> },
+    /// A [`Policy`] refused it; the reason is the policy's.
+    Rejected { action: Action, reason: String },
+    /// Execution failed — on-chain, in pre-flight checks or in the quote.
+    Failed { action: Action, error: Error },
+}
+
+/// One tick: the update the strategy saw and what came of its actions.
+#[derive(Debug)]
+pub struct TickReport {
+    pub update: PoolUpdate,
+    pub events: Vec<RunEvent>,
+}
+
+/// Drives a [`Strategy`]: feeds it pool updates, screens its actions through
+/// the [`Policy`]s and executes what passes.
+///
+/// - [`live`](Self::live) sends each swap through [`A2ASwapClient::convert`].
+/// - [`dry_run`](Self::dry_run) quotes each swap with
+///   [`A2ASwapClient::simulate`] and applies the same slippage and
+///   price-impact checks, but sends nothing; actions in one tick are quoted
+///   against the same chain state.
+/// - [`backtest`](Self::backtest) needs no RPC: each swap is quoted from the
+///   update's own reserves and fees, and later actions in the tick see the
+///   reserves the earlier ones left. [`Backtest`](crate::backtest::Backtest)
+///   wraps this mode with recorded history and a P&L report.
+pub struct Runner<'a> {
+    mode:     Mode<'a>,
+    policies: Vec<Box<dyn Policy + Send + 'a>>,
+}
+
+impl<'a> Runner<'a> {
+    pub fn live(client: &'a A2ASwapClient, payer: &'a dyn Signer) -> Self {
+        Self { mode: Mode::Live { client, payer }, policies: Vec::new() }
+    }
+
+    pub fn dry_run(client: &'a A2ASwapClient) -> Self {
+        Self { mode: Mode::DryRun { client }, policies: Vec::new() }
+    }
+
+    pub fn backtest() -> Self {
+        Self { mode: Mode::Backtest, policies: Vec::new() }
+    }
+
+    /// Screen every action through `policy`, after the policies added before it.
+    pub fn with_policy(mut self, policy: impl Policy + Send + 'a) -> Self {
+        self.policies.push(Box::new(policy));
+        self
+    }
+
+    /// Run `strategy` on one update. Actions execute in order; the first
+    /// refused or failed one ends the tick.
+    pub async fn tick<S: Strategy + ?Sized>(&mut self, strategy: &mut S, update: &PoolUpdate) -> Vec<RunEvent> {
+        let mut pool = update.info.clone();
+        let mut events = Vec::new();
+        for action in strategy.on_tick(update) {
+            if let Some(reason) = self.policies.iter_mut().find_map(|p| p.check(&action, update).err()) {
+                events.push(RunEvent::Rejected { action, reason });
+                break;
+            }
+            match self.execute(&action, &mut pool).await {
+                Ok((fill, quote)) => {
+                    for policy in &mut self.policies {
+                        policy.on_fill(&action, &fill);
+                    }
+                    strategy.on_fill(&action, &fill);
+                    events.push(RunEvent::Filled { action, fill, quote });
+                }
+                Err(error) => {
+                    events.push(RunEvent::Failed { action, error });
+                    break;
+                }
+            }
+        }
+        events
+    }
+
+    /// Poll the `mint_a` / `mint_b` pool every `every` and tick `strategy`
+    /// on each read, passing the result to `on_tick`; stops when `on_tick`
+    /// returns `false`. A failed read does not stop the loop unless
+    /// `on_tick` says so. Not available in backtest mode — use
+    /// [`replay`](Self::replay).
+    pub async fn run<S: Strategy + ?Sized>(
+        &mut self,
+        strategy:    &mut S,
+        mint_a:      Pubkey,
+        mint_b:      Pubkey,
+        every:       Duration,
+        mut on_tick: impl FnMut(&Result<TickReport>) -> bool,
+    ) -> Result<()> {
+        let client = match self.mode {
+            Mode::Live { client, .. } | Mode::DryRun { client } => client,
+            Mode::Backtest => {
+                return Err(Error::InvalidArgument(
+                    "a backtest runs on recorded updates — use Runner::replay".into(),
+                ));
+            }
+        };
+        loop {
+            let report = match PoolUpdate::fetch(client, mint_a, mint_b).await {
+                Ok(update) => {
+                    let events = self.tick(strategy, &update).await;
+                    Ok(TickReport { update, events })
+                }
+                Err(e) => Err(e),
+            };
+            if !on_tick(&report) {
+                return Ok(());
+            }
+            tokio::time::sleep(every).await;
+        }
+    }
+
+    /// Tick `strategy` on each of `updates` in turn — a backtest over
+    /// recorded pool history in backtest mode.
+    pub async fn replay<S: Strategy + ?Sized>(
+        &mut self,
+        strategy: &mut S,
+        updates:  impl IntoIterator<Item = PoolUpdate>,
+    ) -> Vec<TickReport> {
+        let mut reports = Vec::new();
+        for update in updates {
+            let events = self.tick(strategy, &update).await;
+            reports.push(TickReport { update, events });
+        }
+        reports
+    }
+
+    async fn execute(&self, action: &Action, pool: &mut PoolInfo) -> Result<(Fill, Option<SimulateResult>)> {
+        let Action::Swap(params) = action;
+        match self.mode {
+            Mode::Live { client, payer } => Ok((Fill::from(&client.convert(payer, params.clone()).await?), None)),
+            Mode::DryRun { client } => {
+                let sim = client.simulate(SimulateParams {
+                    mint_in:   params.mint_in,
+                    mint_out:  params.mint_out,
+                    amount_in: params.amount_in,
+                }).await?;
+                check_swap(params, pool.curve, sim.after_fees, sim.reserve_in, sim.reserve_out, sim.estimated_out)?;
+                let fill = Fill { amount_in: params.amount_in, amount_out: sim.estimated_out, signature: None };
+                Ok((fill, Some(sim)))
+            }
+            Mode::Backtest => {
+                let sim = backtest_swap(params, pool)?;
+                let fill = Fill { amount_in: params.amount_in, amount_out: sim.estimated_out, signature: None };
+                Ok((fill, Some(sim)))
+            }
+        }
+    }
+}
+
+/// The pre-flight checks [`A2ASwapClient::convert`] applies: the price-impact
+/// cap and the slippage guard.
+fn check_swap(
+    params:        &SwapParams,
+    curve:         CurveKind,
+    after_fees:    u64,
+    reserve_in:    u64,
+    reserve_out:   u64,
+    estimated_out: u64,
+) -> Result<()> {
+    let impact_bps = curve_price_impact_bps(curve, after_fees, reserve_in, reserve_out)?;
+    if params.max_price_impact_bps > 0 && impact_bps > params.max_price_impact_bps as u64 {
+        return Err(Error::PriceImpactExceeded { impact_bps, max_bps: params.max_price_impact_bps });
+    }
+    resolve_min_amount_out(estimated_out, params.max_slippage_bps, params.min_amount_out)?;
+    Ok(())
+}
+
+/// Quote `params` against `pool` and move its reserves as the swap would.
+fn backtest_swap(params: &SwapParams, pool: &mut PoolInfo) -> Result<SimulateResult> {
+    let a_to_b = if (params.mint_in, params.mint_out) == (pool.mint_a, pool.mint_b) {
+        true
+    } else if (params.mint_in, params.mint_out) == (pool.mint_b, pool.mint_a) {
+        false
+    } else {
+        return Err(Error::PoolNotFound(params.mint_in, params.mint_out));
+    };
+    let sim = simulate_snapshot(pool, params.amount_in, a_to_b)?;
+    check_swap(params, pool.curve, sim.after_fees, sim.reserve_in, sim.reserve_out, sim.estimated_out)?;
+
+    apply_simulated_swap(pool, &sim)?;
+    Ok(sim)
+}
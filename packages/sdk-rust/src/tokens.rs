@@ -0,0 +1,221 @@
+//! Token symbol ↔ mint ↔ decimals registry.
+//!
+//! [`TokenRegistry::builtin`] knows SOL, USDC and USDT
+//! ([`a2a_swap_core::tokens::KNOWN_TOKENS`], the table the Worker uses too).
+//! Extend it from the [Solana token list](SOLANA_TOKEN_LIST_URL) or any
+//! JSON file in the same shape, and with single tokens via
+//! [`add`](TokenRegistry::add):
+//!
+//! ```rust,no_run
+//! # use a2a_swap_sdk::tokens::{TokenRegistry, SOLANA_TOKEN_LIST_URL};
+//! # async fn run() -> a2a_swap_sdk::Result<()> {
+//! let mut tokens = TokenRegistry::builtin();
+//! tokens.extend(TokenRegistry::fetch(SOLANA_TOKEN_LIST_URL).await?);
+//! let bonk = tokens.resolve_mint("BONK")?;
+//! # Ok(()) }
+//! ```
+//!
+//! Accepted JSON: a token list object (`{ "tokens": [...] }`) or a bare
+//! array, each entry with `symbol`, `address` (or `mint`), `decimals` and an
+//! optional `name`. Entries with a `chainId` other than 101 (mainnet-beta)
+//! are skipped. [`to_json`](TokenRegistry::to_json) writes the same format,
+//! so a registry can be saved, shipped with the Worker, and loaded back.
+
+use std::collections::HashMap;
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+use solana_sdk::pubkey::Pubkey;
+
+use crate::error::{Error, Result};
+
+/// The community-maintained Solana token list.
+pub const SOLANA_TOKEN_LIST_URL: &str =
+    "https://raw.githubusercontent.com/solana-labs/token-list/main/src/tokens/solana.tokenlist.json";
+
+/// `chainId` of mainnet-beta in token lists.
+const MAINNET_CHAIN_ID: u64 = 101;
+
+/// One token in a [`TokenRegistry`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TokenInfo {
+    /// Ticker as listed (lookups are case-insensitive).
+    pub symbol:   String,
+    /// Mint address.
+    pub mint:     Pubkey,
+    /// Mint decimals.
+    pub decimals: u8,
+    /// Display name, if known.
+    pub name:     Option<String>,
+}
+
+/// Token list entry on the wire.
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ListEntry {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    chain_id: Option<u64>,
+    #[serde(alias = "mint")]
+    address:  String,
+    symbol:   String,
+    decimals: u8,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    name:     Option<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum ListFile {
+    List { tokens: Vec<ListEntry> },
+    Bare(Vec<ListEntry>),
+}
+
+/// Symbol ↔ mint ↔ decimals lookups — see the [module docs](self).
+#[derive(Debug, Clone, Default)]
+pub struct TokenRegistry {
+    tokens:    Vec<TokenInfo>,
+    by_symbol: HashMap<String, usize>,
+    by_mint:   HashMap<Pubkey, usize>,
+}
+
+impl TokenRegistry {
+    /// A registry with no tokens.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The built-in mainnet-beta symbols: SOL, USDC, USDT.
+    pub fn builtin() -> Self {
+        let mut registry = Self::new();
+        for t in a2a_swap_core::tokens::KNOWN_TOKENS {
+            registry.add(TokenInfo {
+                symbol:   t.symbol.to_string(),
+                mint:     Pubkey::from_str(t.mint).unwrap(),
+                decimals: t.decimals,
+                name:     Some(t.name.to_string()),
+            });
+        }
+        registry
+    }
+
+    /// Parse a token list (see the [module docs](self)). When a symbol or
+    /// mint appears more than once, the first entry wins.
+    pub fn from_json(json: &str) -> Result<Self> {
+        let file: ListFile = serde_json::from_str(json)
+            .map_err(|e| Error::InvalidArgument(format!("token list: {e}")))?;
+        let entries = match file {
+            ListFile::List { tokens } => tokens,
+            ListFile::Bare(tokens)    => tokens,
+        };
+
+        let mut registry = Self::new();
+        for entry in entries {
+            if entry.chain_id.is_some_and(|c| c != MAINNET_CHAIN_ID) {
+                continue;
+            }
+            let mint = Pubkey::from_str(&entry.address).map_err(|_| {
+                Error::InvalidArgument(format!("token list: bad mint {} for {}", entry.address, entry.symbol))
+            })?;
+            let key = entry.symbol.to_uppercase();
+            if registry.by_symbol.contains_key(&key) || registry.by_mint.contains_key(&mint) {
+                continue;
+            }
+            registry.by_symbol.insert(key, registry.tokens.len());
+            registry.by_mint.insert(mint, registry.tokens.len());
+            registry.tokens.push(TokenInfo {
+                symbol:   entry.symbol,
+                mint,
+                decimals: entry.decimals,
+                name:     entry.name,
+            });
+        }
+        Ok(registry)
+    }
+
+    /// Download and parse a token list, e.g. [`SOLANA_TOKEN_LIST_URL`].
+    pub async fn fetch(url: &str) -> Result<Self> {
+        let fetch_err = |e: reqwest::Error| Error::InvalidArgument(format!("token list {url}: {e}"));
+        let text = reqwest::get(url)
+            .await
+            .and_then(reqwest::Response::error_for_status)
+            .map_err(fetch_err)?
+            .text()
+            .await
+            .map_err(fetch_err)?;
+        Self::from_json(&text)
+    }
+
+    /// Add a token, replacing any entry with the same symbol or mint.
+    pub fn add(&mut self, token: TokenInfo) {
+        self.tokens.retain(|t| !t.symbol.eq_ignore_ascii_case(&token.symbol) && t.mint != token.mint);
+        self.tokens.push(token);
+        self.reindex();
+    }
+
+    /// Add every token of `other`; its entries replace same-symbol or
+    /// same-mint entries here.
+    pub fn extend(&mut self, other: TokenRegistry) {
+        for token in other.tokens {
+            self.add(token);
+        }
+    }
+
+    /// Look a token up by symbol (case-insensitive) or base58 mint.
+    pub fn get(&self, symbol_or_mint: &str) -> Option<&TokenInfo> {
+        self.by_symbol
+            .get(&symbol_or_mint.to_uppercase())
+            .or_else(|| self.by_mint.get(&Pubkey::from_str(symbol_or_mint).ok()?))
+            .map(|&i| &self.tokens[i])
+    }
+
+    /// Look a token up by mint.
+    pub fn by_mint(&self, mint: &Pubkey) -> Option<&TokenInfo> {
+        self.by_mint.get(mint).map(|&i| &self.tokens[i])
+    }
+
+    /// Mint for a known symbol, or any base58 address as-is.
+    pub fn resolve_mint(&self, symbol_or_mint: &str) -> Result<Pubkey> {
+        if let Some(token) = self.by_symbol.get(&symbol_or_mint.to_uppercase()) {
+            return Ok(self.tokens[*token].mint);
+        }
+        Pubkey::from_str(symbol_or_mint).map_err(|_| {
+            Error::InvalidArgument(format!(
+                "unknown token '{symbol_or_mint}' — use a known symbol or a base-58 mint address"
+            ))
+        })
+    }
+
+    /// Every token, in insertion order.
+    pub fn iter(&self) -> impl Iterator<Item = &TokenInfo> {
+        self.tokens.iter()
+    }
+
+    pub fn len(&self) -> usize {
+        self.tokens.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.tokens.is_empty()
+    }
+
+    /// The registry as a token list that [`from_json`](Self::from_json) reads back.
+    pub fn to_json(&self) -> String {
+        let tokens: Vec<ListEntry> = self
+            .tokens
+            .iter()
+            .map(|t| ListEntry {
+                chain_id: Some(MAINNET_CHAIN_ID),
+                address:  t.mint.to_string(),
+                symbol:   t.symbol.clone(),
+                decimals: t.decimals,
+                name:     t.name.clone(),
+            })
+            .collect();
+        serde_json::to_string_pretty(&serde_json::json!({ "tokens": tokens })).unwrap()
+    }
+
+    fn reindex(&mut self) {
+        self.by_symbol = self.tokens.iter().enumerate().map(|(i, t)| (t.symbol.to_uppercase(), i)).collect();
+        self.by_mint = self.tokens.iter().enumerate().map(|(i, t)| (t.mint, i)).collect();
+    }
+}
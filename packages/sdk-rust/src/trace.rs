@@ -0,0 +1,15 @@
+//! `tracing` helpers (`tracing` feature).
+//!
+//! Client methods open spans via `#[cfg_attr(feature = "tracing", instrument)]`
+//! with `pool` / `signature` declared empty; [`record`] fills them in once
+//! known. Without the feature it compiles to nothing.
+
+use std::fmt::Display;
+
+/// Record `value` on the current span's pre-declared `field`.
+#[allow(unused_variables)]
+#[cfg_attr(not(any(feature = "rpc", feature = "minimal-rpc")), allow(dead_code))]
+pub(crate) fn record(field: &'static str, value: impl Display) {
+    #[cfg(feature = "tracing")]
+    tracing::Span::current().record(field, tracing::field::display(value));
+}
@@ -1,7 +1,17 @@
 //! Parameter and result types for every SDK operation.
 
+use std::collections::BTreeMap;
+
 use serde::{Deserialize, Serialize};
-use solana_sdk::pubkey::Pubkey;
+use solana_sdk::{instruction::Instruction, pubkey::Pubkey};
+
+use crate::{
+    math::{PROTOCOL_FEE_BPS, PROTOCOL_FEE_DENOMINATOR, REFERRAL_SHARE_BPS},
+    state::{
+        CircuitBreaker, CurveKind, DynamicFee, FeeTier, GovernanceAction, ProtocolConfigState,
+        MAX_POOL_FEE_BPS, MIN_POOL_FEE_BPS, PAUSE_POOL_CREATION, PAUSE_SWAPS,
+    },
+};
 
 // ─── Input parameters ─────────────────────────────────────────────────────────
 
@@ -12,8 +22,32 @@ pub struct CreatePoolParams {
     pub mint_a: Pubkey,
     /// Second token mint (defines "token B" for the pool PDA seed).
     pub mint_b: Pubkey,
-    /// LP fee rate in basis points. Range: 1–100 (0.01%–1.00%). Typical: 30 (0.30%).
+    /// LP fee rate in basis points, within the protocol's pool fee bounds
+    /// (1–100, i.e. 0.01%–1.00%, until governance changes them). Typical: 30 (0.30%).
     pub fee_rate_bps: u16,
+    /// Swap invariant. Use [`CurveKind::StableSwap`] for correlated pairs
+    /// such as USDC/USDT; it cannot be changed after creation.
+    pub curve: CurveKind,
+}
+
+/// Parameters for [`A2ASwapClient::create_and_seed_pool`].
+#[derive(Debug, Clone)]
+pub struct CreateAndSeedParams {
+    /// The pool to create; `pool.mint_a` / `pool.mint_b` fix the token order.
+    pub pool: CreatePoolParams,
+    /// Amount of `pool.mint_a` to deposit (atomic units).
+    pub amount_a: u64,
+    /// Amount of `pool.mint_b` to deposit (atomic units). With `amount_a`
+    /// this sets the initial price.
+    pub amount_b: u64,
+    /// Re-invest accrued fees into LP shares instead of sending them to your wallet.
+    pub auto_compound: bool,
+    /// Minimum combined fee balance before an auto-compound fires; `0` compounds every claim.
+    pub compound_threshold: u64,
+    /// Minimum LP shares to accept. `0` means no slippage guard on LP minting.
+    pub min_lp: u64,
+    /// Mint a Metaplex Core receipt NFT for the position.
+    pub mint_receipt: bool,
 }
 
 /// Parameters for [`A2ASwapClient::provide_liquidity`].
@@ -27,11 +61,19 @@ pub struct ProvideParams {
     pub amount_a: u64,
     /// Amount of token B to deposit (atomic units).
     ///
-    /// - `None` when the pool already has liquidity: the SDK fetches live reserves
-    ///   and computes `amount_b = amount_a × reserve_b / reserve_a`.
+    /// - `None` when the pool already has liquidity: the program computes
+    ///   `amount_b = ⌈amount_a × reserve_b / reserve_a⌉` from the reserves at
+    ///   execution (`provide_liquidity_proportional`), so it can't drift.
     /// - `Some(n)` when the pool is empty (first deposit) — this sets the initial price.
     ///   Also accepted when you want to override the proportional amount.
     pub amount_b: Option<u64>,
+    /// Most of `mint_a` the deposit may take; above it the deposit fails
+    /// with `DepositExceedsMax`. `None` leaves it uncapped.
+    pub amount_a_max: Option<u64>,
+    /// Most of `mint_b` the deposit may take — guards the proportional
+    /// `amount_b` against reserves that moved since the agent's quote.
+    /// `None` leaves it uncapped.
+    pub amount_b_max: Option<u64>,
     /// Re-invest accrued fees into LP shares instead of sending them to your wallet.
     pub auto_compound: bool,
     /// Minimum combined fee balance (fees_a + fees_b, atomic units) before an
@@ -39,6 +81,23 @@ pub struct ProvideParams {
     pub compound_threshold: u64,
     /// Minimum LP shares to accept. `0` means no slippage guard on LP minting.
     pub min_lp: u64,
+    /// Mint a Metaplex Core receipt NFT for the position (once; later deposits
+    /// reuse it). The program burns it when the position is fully withdrawn.
+    pub mint_receipt: bool,
+}
+
+/// Parameters for [`A2ASwapClient::update_position_settings`].
+#[derive(Debug, Clone)]
+pub struct PositionSettingsParams {
+    /// One mint of the pool; either ordering finds it.
+    pub mint_a: Pubkey,
+    /// The other mint of the pool.
+    pub mint_b: Pubkey,
+    /// Re-invest accrued fees into LP shares instead of sending them to your wallet.
+    pub auto_compound: bool,
+    /// Minimum combined fee balance (fees_a + fees_b, atomic units) before an
+    /// auto-compound fires. `0` means "compound every time fees are claimed".
+    pub compound_threshold: u64,
 }
 
 /// Parameters for [`A2ASwapClient::convert`].
@@ -50,11 +109,135 @@ pub struct SwapParams {
     pub mint_out: Pubkey,
     /// Amount of the input token to sell (atomic units).
     pub amount_in: u64,
-    /// Maximum acceptable slippage in basis points (e.g. `50` = 0.5%).
-    /// Applied to the pre-flight simulation estimate:
-    /// `min_amount_out = estimated_out × (1 − max_slippage_bps / 10_000)`.
-    /// Set to `0` to disable the slippage guard.
+    /// Maximum acceptable slippage in basis points (e.g. `50` = 0.5%, at
+    /// most `10_000`). Applied to the pre-flight simulation estimate:
+    /// `min_amount_out = estimated_out × (1 − max_slippage_bps / 10_000)`,
+    /// rounded up. Set to `0` to disable the slippage guard, or when passing
+    /// [`min_amount_out`](Self::min_amount_out) instead.
     pub max_slippage_bps: u16,
+    /// Exact minimum output to submit, instead of deriving it from
+    /// `max_slippage_bps` (which must then be `0`). A pre-flight estimate
+    /// already below it fails with
+    /// [`Error::SlippageExceeded`](crate::Error::SlippageExceeded) before
+    /// anything is sent. Protected swaps split it pro rata across tranches.
+    pub min_amount_out: Option<u64>,
+    /// Hard cap on pure price impact in basis points (e.g. `300` = 3%).
+    /// Checked client-side before sending and again by the on-chain program.
+    /// Unlike the slippage guard this does not depend on the estimate, so it
+    /// rejects oversized trades in thin pools. Set to `0` to disable.
+    pub max_price_impact_bps: u16,
+    /// Last unix time the swap may execute. A transaction still in flight
+    /// or in a retry queue after it fails with `DeadlineExceeded` instead of
+    /// filling at a stale price; already past, the swap is not sent. Protected
+    /// swaps give every tranche the same deadline. Set to `0` to disable.
+    pub deadline_unix: i64,
+    /// Integrator wallet that receives the referral share of the protocol
+    /// fee, paid into its associated token account for `mint_in` (which must
+    /// already exist). `None` sends the whole protocol fee to the treasury.
+    pub referrer: Option<Pubkey>,
+    /// Anti-sandwich mode. [`ProtectionLevel::None`] sends one transaction.
+    pub protection: ProtectionLevel,
+}
+
+/// Parameters for [`A2ASwapClient::convert_exact_out`].
+#[derive(Debug, Clone)]
+pub struct ExactOutParams {
+    /// Mint of the token you are selling.
+    pub mint_in: Pubkey,
+    /// Mint of the token you want to receive.
+    pub mint_out: Pubkey,
+    /// Exact amount of the output token to receive (atomic units).
+    pub amount_out: u64,
+    /// Most input the swap may spend. The program computes the input from
+    /// live reserves and fees and rejects the swap if it is higher.
+    pub max_amount_in: u64,
+    /// Hard cap on pure price impact in basis points. Set to `0` to disable.
+    pub max_price_impact_bps: u16,
+    /// Integrator wallet that receives the referral share of the protocol
+    /// fee, as in [`SwapParams::referrer`].
+    pub referrer: Option<Pubkey>,
+}
+
+/// Anti-sandwich mode for [`A2ASwapClient::convert`], set through
+/// [`SwapParams::protection`].
+///
+/// A protected swap whose price impact reaches the level's threshold is split
+/// into randomly sized tranches, each sent a random 1–3 slots after the last.
+/// Every tranche is re-quoted against live reserves with a tighter slippage
+/// bound, and the rest of the swap is abandoned
+/// ([`Error::ProtectionAborted`](crate::Error::ProtectionAborted)) once a
+/// confirmed tranche realizes more slippage than the level allows.
+///
+/// [`ProtectionLevel::CommitReveal`] instead hides the swap until it
+/// executes: its parameters are committed as a hash in one slot and revealed
+/// in a later one (the program's `commit_swap` / `reveal_swap`).
+/// [`build_convert`](crate::A2ASwapClient::build_convert) ignores it.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ProtectionLevel {
+    /// One transaction, guarded only by `max_slippage_bps`.
+    #[default]
+    None,
+    /// Split from 30 bps of price impact into at most 4 tranches; each
+    /// tranche gets half of `max_slippage_bps`; abort above 50 bps realized.
+    Standard,
+    /// Split from 10 bps of price impact into at most 8 tranches; each
+    /// tranche gets a quarter of `max_slippage_bps`; abort above 20 bps realized.
+    Strict,
+    /// Two transactions: `commit_swap` with the hash of the swap's
+    /// parameters, then `reveal_swap` executing it in a later slot (within
+    /// 150 slots). Not split; pays no referrer.
+    CommitReveal,
+}
+
+impl ProtectionLevel {
+    /// Most tranches a swap is split into.
+    pub fn max_tranches(self) -> u64 {
+        match self {
+            ProtectionLevel::None
+            | ProtectionLevel::CommitReveal => 1,
+            ProtectionLevel::Standard => 4,
+            ProtectionLevel::Strict   => 8,
+        }
+    }
+
+    /// Price impact (bps) each tranche is sized to stay near; a swap below
+    /// it is sent in one piece.
+    pub fn tranche_impact_bps(self) -> u64 {
+        match self {
+            ProtectionLevel::None
+            | ProtectionLevel::CommitReveal => u64::MAX,
+            ProtectionLevel::Standard => 30,
+            ProtectionLevel::Strict   => 10,
+        }
+    }
+
+    /// Tranche count for a swap with the given whole-trade price impact.
+    pub fn tranches_for(self, impact_bps: u64) -> u64 {
+        impact_bps.div_ceil(self.tranche_impact_bps()).clamp(1, self.max_tranches())
+    }
+
+    /// Per-tranche slippage bound derived from the swap's `max_slippage_bps`.
+    /// A non-zero bound never tightens to zero (which would disable it).
+    pub fn tranche_slippage_bps(self, max_slippage_bps: u16) -> u16 {
+        let divisor = match self {
+            ProtectionLevel::None
+            | ProtectionLevel::CommitReveal => 1,
+            ProtectionLevel::Standard => 2,
+            ProtectionLevel::Strict   => 4,
+        };
+        if max_slippage_bps == 0 { 0 } else { (max_slippage_bps / divisor).max(1) }
+    }
+
+    /// Realized slippage (bps) of a filled tranche that aborts the rest.
+    pub fn abort_slippage_bps(self) -> u16 {
+        match self {
+            ProtectionLevel::None
+            | ProtectionLevel::CommitReveal => u16::MAX,
+            ProtectionLevel::Standard => 50,
+            ProtectionLevel::Strict   => 20,
+        }
+    }
 }
 
 /// Parameters for [`A2ASwapClient::simulate`].
@@ -68,6 +251,57 @@ pub struct SimulateParams {
     pub amount_in: u64,
 }
 
+/// Parameters for [`A2ASwapClient::create_range_pool`].
+#[derive(Debug, Clone)]
+pub struct CreateRangePoolParams {
+    /// First token mint (defines "token A" for the pool PDA seed).
+    pub mint_a: Pubkey,
+    /// Second token mint (defines "token B" for the pool PDA seed).
+    pub mint_b: Pubkey,
+    /// LP fee rate in basis points. Range: 1–100.
+    pub fee_rate_bps: u16,
+    /// Range bounds must be multiples of this (1–1000). Typical: 1 for
+    /// stable pairs, 60 for volatile ones.
+    pub tick_spacing: u16,
+    /// Starting price as a tick: price (token B per token A, atomic units)
+    /// = `1.0001^initial_tick`. See [`range_math::tick_for_price`](crate::range_math::tick_for_price).
+    pub initial_tick: i32,
+}
+
+/// Parameters for [`A2ASwapClient::provide_range_liquidity`].
+#[derive(Debug, Clone)]
+pub struct ProvideRangeParams {
+    /// Pool token A mint — range pools are addressed in pool order.
+    pub mint_a: Pubkey,
+    /// Pool token B mint.
+    pub mint_b: Pubkey,
+    /// Lower bound of the price range (inclusive), a multiple of the pool's tick spacing.
+    pub tick_lower: i32,
+    /// Upper bound of the price range (exclusive), a multiple of the pool's tick spacing.
+    pub tick_upper: i32,
+    /// Most token A to deposit (atomic units).
+    pub amount_a_max: u64,
+    /// Most token B to deposit (atomic units).
+    pub amount_b_max: u64,
+    /// Maximum acceptable slippage in basis points, applied to the liquidity
+    /// quoted from the current price. `0` disables the guard.
+    pub max_slippage_bps: u16,
+}
+
+/// Parameters for [`A2ASwapClient::create_delegate`] and
+/// [`A2ASwapClient::rotate_delegate`].
+#[derive(Debug, Clone)]
+pub struct DelegateParams {
+    /// Session key that will sign [`A2ASwapClient::convert_as_delegate`].
+    pub delegate: Pubkey,
+    /// Mint the session may sell, from the owner's associated token account.
+    pub mint: Pubkey,
+    /// Total input the session may spend (atomic units).
+    pub max_amount_in: u64,
+    /// Unix timestamp after which the session is refused.
+    pub expires_at: i64,
+}
+
 // ─── Result types ─────────────────────────────────────────────────────────────
 
 /// Result of [`A2ASwapClient::create_pool`].
@@ -89,6 +323,8 @@ pub struct CreatePoolResult {
     pub mint_b: Pubkey,
     /// LP fee rate that was set (basis points).
     pub fee_rate_bps: u16,
+    /// Swap invariant the pool was created with.
+    pub curve: CurveKind,
 }
 
 /// Result of [`A2ASwapClient::provide_liquidity`].
@@ -100,10 +336,131 @@ pub struct ProvideResult {
     pub pool: Pubkey,
     /// Agent's position account (created on first deposit, updated thereafter).
     pub position: Pubkey,
-    /// Actual token A deposited.
+    /// Token A deposited, in pool ordering.
     pub amount_a: u64,
-    /// Actual token B deposited (may differ from input if proportionally computed).
+    /// Token B deposited, in pool ordering. A side the program computed
+    /// on-chain is given at the reserves read before sending.
     pub amount_b: u64,
+    /// LP receipt asset, when `mint_receipt` was set.
+    pub receipt: Option<Pubkey>,
+    /// LP shares this deposit minted: the position's shares after less
+    /// before. Read back after confirmation; `None` if the position or pool
+    /// could not be fetched.
+    #[serde(default)]
+    pub lp_minted: Option<u64>,
+    /// The position's total LP shares after the deposit.
+    #[serde(default)]
+    pub lp_shares: Option<u64>,
+    /// The position's share of the pool's LP supply after the deposit, in
+    /// percent.
+    #[serde(default)]
+    pub pool_share_pct: Option<f64>,
+}
+
+/// Result of [`A2ASwapClient::create_and_seed_pool`]. Both parts carry the
+/// same signature: the pool was created and seeded in one transaction.
+#[derive(Debug, Clone)]
+pub struct CreateAndSeedResult {
+    /// The created pool.
+    pub pool: CreatePoolResult,
+    /// The seeding deposit.
+    pub liquidity: ProvideResult,
+}
+
+/// Result of [`A2ASwapClient::create_range_pool`].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreateRangePoolResult {
+    /// Confirmed transaction signature.
+    pub signature: String,
+    /// On-chain range pool PDA address.
+    pub pool: Pubkey,
+    /// Pool authority PDA that owns the token vaults.
+    pub pool_authority: Pubkey,
+    /// Token A vault account (fresh keypair generated by this call).
+    pub vault_a: Pubkey,
+    /// Token B vault account (fresh keypair generated by this call).
+    pub vault_b: Pubkey,
+    /// LP fee rate that was set (basis points).
+    pub fee_rate_bps: u16,
+    /// Tick spacing that was set.
+    pub tick_spacing: u16,
+    /// Starting tick.
+    pub initial_tick: i32,
+}
+
+/// Result of [`A2ASwapClient::provide_range_liquidity`].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProvideRangeResult {
+    /// Confirmed transaction signature.
+    pub signature: String,
+    /// Range pool that received the liquidity.
+    pub pool: Pubkey,
+    /// Range position PDA for this owner and tick range.
+    pub position: Pubkey,
+    /// Liquidity quoted at the pre-flight price.
+    pub liquidity: u128,
+    /// Token A the quoted liquidity needs (atomic units).
+    pub amount_a: u64,
+    /// Token B the quoted liquidity needs (atomic units).
+    pub amount_b: u64,
+}
+
+/// Result of [`A2ASwapClient::create_delegate`] and
+/// [`A2ASwapClient::rotate_delegate`].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DelegateResult {
+    /// Confirmed transaction signature.
+    pub signature: String,
+    /// Delegate PDA holding the session.
+    pub delegate_account: Pubkey,
+    /// Owner token account the session spends from.
+    pub token_account: Pubkey,
+    /// Session key now in effect.
+    pub delegate: Pubkey,
+    /// Total input the session may spend.
+    pub max_amount_in: u64,
+    /// Unix timestamp after which the session is refused.
+    pub expires_at: i64,
+}
+
+/// Result of [`A2ASwapClient::create_proposal`].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreateProposalResult {
+    /// Confirmed transaction signature.
+    pub signature: String,
+    /// Proposal id, for voting on and executing it.
+    pub id: u64,
+    /// Proposal PDA address.
+    pub proposal: Pubkey,
+    /// Unix timestamp voting closes, estimated from the local clock.
+    pub voting_ends_at: i64,
+}
+
+/// Result of [`A2ASwapClient::propose_multisig_convert`].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MultisigProposal {
+    /// Confirmed signature of the create + propose + approve transaction.
+    pub signature: String,
+    /// Squads multisig account.
+    pub multisig: Pubkey,
+    /// Vault that will sign and pay for the swap.
+    pub vault: Pubkey,
+    /// Index to pass to `approve_multisig` / `execute_multisig`.
+    pub transaction_index: u64,
+    /// Squads vault transaction account.
+    pub transaction: Pubkey,
+    /// Squads proposal account.
+    pub proposal: Pubkey,
+    /// Pool the swap will use.
+    pub pool: Pubkey,
+    /// Tokens the vault will sell.
+    pub amount_in: u64,
+    /// Pre-flight estimate of tokens received, at proposal time.
+    pub estimated_out: u64,
+    /// Minimum tokens the executed swap accepts.
+    pub min_amount_out: u64,
+    /// `true` = token A → token B; `false` = token B → token A.
+    pub a_to_b: bool,
 }
 
 /// Result of [`A2ASwapClient::convert`].
@@ -117,8 +474,51 @@ pub struct SwapResult {
     pub amount_in: u64,
     /// Pre-flight simulation estimate of tokens received.
     pub estimated_out: u64,
-    /// Minimum tokens the on-chain program would accept (slippage guard).
+    /// `min_amount_out` the swap instruction was submitted with — the
+    /// explicit [`SwapParams::min_amount_out`] or the one derived from
+    /// `max_slippage_bps`; summed over tranches for a protected swap.
     pub min_amount_out: u64,
+    /// Part of the protocol fee paid to [`SwapParams::referrer`] (`0` without one).
+    pub referral_fee: u64,
+    /// `true` = token A → token B; `false` = token B → token A.
+    pub a_to_b: bool,
+    /// Tokens actually received, read back from the confirmed transaction's
+    /// token balances (the output vault's pre/post delta). `None` if the
+    /// transaction could not be fetched.
+    #[serde(default)]
+    pub actual_out: Option<u64>,
+    /// Network fee the transaction paid, in lamports (priority fee included).
+    #[serde(default)]
+    pub actual_fee_paid: Option<u64>,
+    /// Shortfall of `actual_out` below `estimated_out` in basis points —
+    /// negative when the swap returned more than estimated.
+    #[serde(default)]
+    pub realized_slippage_bps: Option<f64>,
+    /// Every tranche's signature, in order, when [`SwapParams::protection`]
+    /// split the swap, or the commit and reveal signatures under
+    /// [`ProtectionLevel::CommitReveal`] (`signature` is the last one);
+    /// empty otherwise.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tranche_signatures: Vec<String>,
+}
+
+/// Unsigned swap from [`A2ASwapClient::build_convert`] — the instructions
+/// [`A2ASwapClient::convert`] would sign, for an agent that signs elsewhere.
+#[derive(Debug, Clone)]
+pub struct SwapPlan {
+    /// Pending migrations, wSOL wrapping / unwrapping and the swap, in order.
+    /// The agent is the only signer and should pay the transaction fee.
+    pub instructions: Vec<Instruction>,
+    /// Pool the swap goes through.
+    pub pool: Pubkey,
+    /// Tokens sold.
+    pub amount_in: u64,
+    /// Pre-flight simulation estimate of tokens received.
+    pub estimated_out: u64,
+    /// Minimum tokens the on-chain program will accept (slippage guard).
+    pub min_amount_out: u64,
+    /// Part of the protocol fee paid to [`SwapParams::referrer`] (`0` without one).
+    pub referral_fee: u64,
     /// `true` = token A → token B; `false` = token B → token A.
     pub a_to_b: bool,
 }
@@ -134,7 +534,7 @@ pub struct SimulateResult {
     pub a_to_b: bool,
     /// Input amount (atomic units).
     pub amount_in: u64,
-    /// Protocol fee skimmed from `amount_in` (0.020%, sent to treasury).
+    /// Protocol fee skimmed from `amount_in` (0.020% by default, sent to treasury).
     pub protocol_fee: u64,
     /// `amount_in − protocol_fee` — what the pool receives as gross input.
     pub net_pool_input: u64,
@@ -149,7 +549,8 @@ pub struct SimulateResult {
     /// Pure AMM slippage: `after_fees / (reserve_in + after_fees) × 100`.
     /// Does not include fee cost — purely the price-curve effect.
     pub price_impact_pct: f64,
-    /// LP fee rate of this pool (basis points).
+    /// LP fee rate charged (basis points) — the pool's effective rate,
+    /// which floats with volatility when dynamic fees are on.
     pub fee_rate_bps: u16,
     /// Input-side vault reserve (atomic units).
     pub reserve_in: u64,
@@ -157,6 +558,55 @@ pub struct SimulateResult {
     pub reserve_out: u64,
 }
 
+/// Expected outcome of a deposit, from [`A2ASwapClient::simulate_provide`].
+///
+/// Amounts are in pool ordering, like [`ProvideResult`]; prices are token A
+/// in token B (raw atomic units), `0.0` for an empty pool.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SimulateProvideResult {
+    /// Pool the deposit would go to.
+    pub pool: Pubkey,
+    /// Token A the deposit would take.
+    pub amount_a: u64,
+    /// Token B the deposit would take — the proportional amount at the
+    /// current reserves when `amount_b` was left out.
+    pub amount_b: u64,
+    /// LP shares the deposit would mint.
+    pub lp_minted: u64,
+    /// Pool LP supply after the deposit.
+    pub lp_supply_after: u64,
+    /// `lp_minted` as a percentage of `lp_supply_after`.
+    pub pool_share_pct: f64,
+    /// Spot price before the deposit.
+    pub price_before: f64,
+    /// Spot price after the deposit — unchanged up to rounding, except on a
+    /// first deposit, which sets it.
+    pub price_after: f64,
+}
+
+/// Expected outcome of a withdrawal, from [`A2ASwapClient::simulate_remove`].
+///
+/// Amounts are in pool ordering; prices as in [`SimulateProvideResult`].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SimulateRemoveResult {
+    /// Pool the shares would be burned from.
+    pub pool: Pubkey,
+    /// LP shares burned.
+    pub lp_shares: u64,
+    /// Token A returned, rounded down as on-chain.
+    pub amount_a: u64,
+    /// Token B returned, rounded down as on-chain.
+    pub amount_b: u64,
+    /// Pool LP supply after the withdrawal.
+    pub lp_supply_after: u64,
+    /// `lp_shares` as a percentage of the pool's LP supply before it.
+    pub pool_share_pct: f64,
+    /// Spot price before the withdrawal.
+    pub price_before: f64,
+    /// Spot price after the withdrawal; `0.0` once the pool is emptied.
+    pub price_after: f64,
+}
+
 /// Pool state snapshot from [`A2ASwapClient::pool_info`].
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PoolInfo {
@@ -176,11 +626,71 @@ pub struct PoolInfo {
     pub reserve_b: u64,
     /// Total LP shares outstanding.
     pub lp_supply: u64,
-    /// Trading fee rate (basis points).
+    /// Base trading fee rate (basis points).
     pub fee_rate_bps: u16,
-    /// Spot price: `reserve_b / reserve_a` in raw atomic units.
-    /// `0.0` when the pool is empty.
+    /// LP fee a swap would pay right now (basis points): `fee_rate_bps`
+    /// plus any volatility premium, within the dynamic fee bounds.
+    pub effective_fee_bps: u16,
+    /// Dynamic fee bounds and volatility accumulator (disabled when
+    /// `max_fee_bps == 0`).
+    pub dynamic_fee: DynamicFee,
+    /// Per-slot price move limit (disabled when `max_move_bps == 0`);
+    /// swaps past it fail with `PriceMoveExceeded`.
+    pub circuit_breaker: CircuitBreaker,
+    /// Whether only allowlisted traders may swap; see [`crate::allowlist`].
+    #[serde(default)]
+    pub permissioned: bool,
+    /// Mint a trader must hold to swap (e.g. a compliance credential), or
+    /// `None` when swaps are ungated.
+    #[serde(default)]
+    pub gate_mint: Option<Pubkey>,
+    /// Protocol fee every swap pays, out of `100_000` — the on-chain
+    /// `ProtocolConfig` value, or the default until it is initialized.
+    pub protocol_fee_bps: u16,
+    /// Swap invariant.
+    pub curve: CurveKind,
+    /// Lifetime swap input in token A (atomic units, before fees).
+    pub volume_a: u64,
+    /// Lifetime swap input in token B (atomic units, before fees).
+    pub volume_b: u64,
+    /// Lifetime LP fees retained in the token A vault.
+    pub fees_collected_a: u64,
+    /// Lifetime LP fees retained in the token B vault.
+    pub fees_collected_b: u64,
+    /// Marginal price of A in B, in raw atomic units — `reserve_b / reserve_a`
+    /// for constant-product pools. `0.0` when the pool is empty.
     pub spot_price: f64,
+    /// Token A behind one LP share: `reserve_a / lp_supply` (`0.0` with no supply).
+    #[serde(default)]
+    pub lp_share_value_a: f64,
+    /// Token B behind one LP share: `reserve_b / lp_supply` (`0.0` with no supply).
+    #[serde(default)]
+    pub lp_share_value_b: f64,
+    /// Both reserves valued in a quote token. Only set by
+    /// [`A2ASwapClient::pool_info_in`], and only when both sides are priced.
+    #[serde(default)]
+    pub tvl: Option<PoolTvl>,
+    /// Slot of the pool's oldest transaction — its `create_pool`. Only set by
+    /// [`A2ASwapClient::pool_info_in`].
+    #[serde(default)]
+    pub created_slot: Option<u64>,
+    /// Block time of that transaction (unix seconds), when the RPC reports one.
+    #[serde(default)]
+    pub created_at: Option<i64>,
+}
+
+/// A pool's reserves valued in `quote_mint`, each side at the spot price of
+/// its direct pool against the quote (or of this pool, when it pairs with it).
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct PoolTvl {
+    /// Mint every value is denominated in.
+    pub quote_mint: Pubkey,
+    /// `reserve_a` in the quote token (atomic units).
+    pub value_a: u64,
+    /// `reserve_b` in the quote token (atomic units).
+    pub value_b: u64,
+    /// `value_a + value_b`.
+    pub total: u64,
 }
 
 /// Single LP position summary from [`A2ASwapClient::my_positions`] /
@@ -211,6 +721,85 @@ pub struct PositionInfo {
     pub auto_compound: bool,
     /// Minimum fee balance to trigger an auto-compound (0 = always).
     pub compound_threshold: u64,
+    /// Whether an approved program holds the position as collateral; while
+    /// it does, liquidity can't be removed and the position can't be closed.
+    #[serde(default)]
+    pub locked: bool,
+    /// The program that locked the position, if any.
+    #[serde(default)]
+    pub locked_by: Option<Pubkey>,
+    /// What was deposited, derived from the position's transaction history.
+    /// `None` from [`A2ASwapClient::my_fees`], or when no deposit is found.
+    pub entry: Option<PositionEntry>,
+    /// Current pool spot price (`reserve_b / reserve_a`, atomic units).
+    /// `None` from [`A2ASwapClient::my_fees`].
+    pub current_price: Option<f64>,
+    /// Impermanent loss vs. holding the deposit, in percent (≤ 0), from
+    /// [`math::impermanent_loss`](crate::math::impermanent_loss).
+    /// `None` when `entry` is unknown.
+    pub il_pct: Option<f64>,
+    /// The position's share of the reserves plus its claimable fees, in
+    /// token B at `current_price` (atomic units). `None` from
+    /// [`A2ASwapClient::my_fees`], or while the pool is empty.
+    #[serde(default)]
+    pub value_b: Option<u64>,
+}
+
+/// Filters, order and page size for [`A2ASwapClient::my_positions_page`].
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PositionQuery {
+    /// Only positions in this pool.
+    pub pool: Option<Pubkey>,
+    /// Only positions holding at least this many LP shares.
+    pub min_lp_shares: u64,
+    /// Only positions with (`true`) or without (`false`) auto-compound.
+    pub auto_compound: Option<bool>,
+    pub sort: PositionSort,
+    /// Positions per page; `None` returns every match.
+    pub limit: Option<usize>,
+    /// [`PositionPage::next_cursor`] of the previous page.
+    pub cursor: Option<String>,
+}
+
+/// Order of [`PositionPage::positions`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PositionSort {
+    /// By position address.
+    #[default]
+    Address,
+    /// Largest claimable fees first, token A counted in token B at the pool price.
+    PendingFees,
+    /// Largest [`PositionInfo::value_b`] first.
+    Value,
+}
+
+/// One page of positions from [`A2ASwapClient::my_positions_page`].
+///
+/// The cursor is an offset into the sorted matches, so positions whose
+/// fees or value move between calls may repeat or be skipped under
+/// [`PositionSort::PendingFees`] / [`PositionSort::Value`].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PositionPage {
+    pub positions: Vec<PositionInfo>,
+    /// Positions matching the filters, across all pages.
+    pub total: usize,
+    /// Pass as [`PositionQuery::cursor`] for the next page; `None` on the last.
+    pub next_cursor: Option<String>,
+}
+
+/// Deposit snapshot for a [`PositionInfo`], rebuilt from the program's
+/// `Liquidity provided` / `Liquidity removed` logs on the position account.
+///
+/// Counts deposits since the position was last fully withdrawn.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PositionEntry {
+    /// Token A deposited (atomic units).
+    pub deposited_a: u64,
+    /// Token B deposited (atomic units).
+    pub deposited_b: u64,
+    /// Deposit-weighted entry price: `deposited_b / deposited_a`.
+    pub entry_price: f64,
 }
 
 /// Aggregated fee summary across all positions, from [`A2ASwapClient::my_fees`].
@@ -223,3 +812,225 @@ pub struct FeeSummary {
     /// Sum of `total_fees_b` across all positions.
     pub total_fees_b: u64,
 }
+
+/// One wallet balance in a [`Portfolio`].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PortfolioHolding {
+    /// Token mint (native SOL is reported as the wSOL mint).
+    pub mint: Pubkey,
+    /// Balance (atomic units).
+    pub amount: u64,
+    /// Value in quote atomic units; `None` if no pool prices this mint.
+    pub value: Option<u64>,
+}
+
+/// One LP position in a [`Portfolio`], at its share of the current reserves.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PortfolioPosition {
+    /// On-chain position PDA address.
+    pub address: Pubkey,
+    /// Pool this position belongs to.
+    pub pool: Pubkey,
+    /// Token A mint.
+    pub mint_a: Pubkey,
+    /// Token B mint.
+    pub mint_b: Pubkey,
+    /// LP shares held by this position.
+    pub lp_shares: u64,
+    /// Token A the shares would withdraw now (atomic units).
+    pub amount_a: u64,
+    /// Token B the shares would withdraw now (atomic units).
+    pub amount_b: u64,
+    /// Claimable token A fees, owed + pending.
+    pub fees_a: u64,
+    /// Claimable token B fees, owed + pending.
+    pub fees_b: u64,
+    /// Value of underlying plus fees in quote atomic units; `None` if either
+    /// token is unpriced.
+    pub value: Option<u64>,
+}
+
+/// Consolidated valuation from [`A2ASwapClient::portfolio`].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Portfolio {
+    /// Wallet that was valued.
+    pub owner: Pubkey,
+    /// Mint every `value` is denominated in.
+    pub quote_mint: Pubkey,
+    /// Non-zero wallet balances, one per mint.
+    pub holdings: Vec<PortfolioHolding>,
+    /// LP positions with underlying amounts and fees.
+    pub positions: Vec<PortfolioPosition>,
+    /// Sum of priced `holdings` values.
+    pub wallet_value: u64,
+    /// Sum of priced `positions` values.
+    pub positions_value: u64,
+    /// `wallet_value + positions_value`.
+    pub total_value: u64,
+    /// Mints with no direct pool against `quote_mint` — excluded from the totals.
+    pub unpriced: Vec<Pubkey>,
+}
+
+/// Governance proposal from [`A2ASwapClient::proposals`].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProposalInfo {
+    /// Proposal PDA address.
+    pub address: Pubkey,
+    /// Sequential id; pass to `vote`, `execute_proposal` and `withdraw_vote`.
+    pub id: u64,
+    /// Wallet that created the proposal.
+    pub proposer: Pubkey,
+    /// Config change applied if the proposal passes.
+    pub action: GovernanceAction,
+    /// Vote tokens cast for.
+    pub votes_for: u64,
+    /// Vote tokens cast against.
+    pub votes_against: u64,
+    /// Unix timestamp voting closes.
+    pub voting_ends_at: i64,
+    /// Voting has closed.
+    pub closed: bool,
+    /// More votes for than against, with the governance quorum reached —
+    /// the outcome so far while voting is open.
+    pub passing: bool,
+    /// The action has been applied.
+    pub executed: bool,
+}
+
+/// An agent's protocol fee tier, from [`A2ASwapClient::my_tier`].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TierInfo {
+    /// The agent wallet.
+    pub agent: Pubkey,
+    /// `AgentVolume` PDA address.
+    pub address: Pubkey,
+    /// Whether the `AgentVolume` account exists. Swaps only count, and only
+    /// get a discount, once it does.
+    pub tracking: bool,
+    /// Lifetime tracked `amount_in`, in atomic units of each swap's input token.
+    pub volume: u64,
+    /// Tracked swaps.
+    pub swaps: u64,
+    /// Index of the tier reached, `None` below every tier.
+    pub tier: Option<u8>,
+    /// Discount off the protocol fee, out of `10_000`.
+    pub discount_bps: u16,
+    /// Protocol fee the agent pays with the discount, out of `100_000`.
+    pub protocol_fee_bps: u16,
+    /// Cheapest tier with a larger discount than the current one, if any.
+    pub next_tier: Option<FeeTier>,
+}
+
+/// What the deployed program offers right now, for agent discovery; from
+/// [`A2ASwapClient::capability_card`].
+///
+/// Built from the program's IDL and its `ProtocolConfig` rather than a fixed
+/// description, so a feature appears once its instruction is deployed and
+/// reads as unavailable while governance has it paused.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CapabilityCard {
+    /// Program name from the IDL metadata.
+    pub name: String,
+    /// Program version from the IDL metadata.
+    pub version: String,
+    pub program_id: Pubkey,
+    /// Whether the IDL came from the program's on-chain IDL account; `false`
+    /// when none is published and the card uses the IDL this SDK was built with.
+    pub idl_on_chain: bool,
+    /// Every instruction the program exposes, in IDL order.
+    pub instructions: Vec<String>,
+    /// Each optional feature and whether it can be used now: its instruction
+    /// is deployed and no pause switch covers it.
+    pub capabilities: BTreeMap<String, bool>,
+    /// Whether the `ProtocolConfig` account exists. Until it does, the fees
+    /// below are the program's defaults and nothing is paused.
+    pub config_initialized: bool,
+    /// Protocol fee out of `protocol_fee_denominator`, before tier discounts.
+    pub protocol_fee_bps: u16,
+    pub protocol_fee_denominator: u32,
+    /// Share of the protocol fee paid to a swap's referrer, out of `10_000`.
+    pub referral_share_bps: u16,
+    /// Lowest `fee_rate_bps` a new pool may use.
+    pub min_pool_fee_bps: u16,
+    /// Highest `fee_rate_bps` a new pool may use.
+    pub max_pool_fee_bps: u16,
+    /// Protocol fee discount tiers that are set, cheapest volume first.
+    pub fee_tiers: Vec<FeeTier>,
+    /// Governance has switched off swaps of every kind.
+    pub swaps_paused: bool,
+    /// Governance has switched off `initialize_pool` and `initialize_range_pool`.
+    pub pool_creation_paused: bool,
+}
+
+/// Optional features, the instruction that provides each, and the pause
+/// switch that turns it off (0 = none).
+const CARD_FEATURES: &[(&str, &str, u8)] = &[
+    ("swap",                  "swap",                           PAUSE_SWAPS),
+    ("exact_out_swaps",       "swap_exact_out",                 PAUSE_SWAPS),
+    ("commit_reveal",         "commit_swap",                    PAUSE_SWAPS),
+    ("approval_mode",         "approve_and_execute",            PAUSE_SWAPS),
+    ("delegated_swaps",       "swap_as_delegate",               PAUSE_SWAPS),
+    ("signed_intents",        "swap_with_intent",               PAUSE_SWAPS),
+    ("pool_creation",         "initialize_pool",                PAUSE_POOL_CREATION),
+    ("range_pools",           "initialize_range_pool",          0),
+    ("liquidity",             "provide_liquidity",              0),
+    ("proportional_deposits", "provide_liquidity_proportional", 0),
+    ("position_locks",        "lock_position",                  0),
+    ("fee_tiers",             "open_agent_volume",              0),
+    ("permissioned_pools",    "set_pool_allowlist",             0),
+    ("token_gated_pools",     "set_pool_gate",                  0),
+    ("dynamic_fees",          "configure_dynamic_fee",          0),
+    ("governance",            "create_proposal",                0),
+];
+
+impl CapabilityCard {
+    /// Build the card from an Anchor IDL and the protocol config, `None`
+    /// before the config is initialized.
+    pub fn new(
+        program_id:   Pubkey,
+        idl:          &serde_json::Value,
+        idl_on_chain: bool,
+        config:       Option<&ProtocolConfigState>,
+    ) -> Self {
+        let instructions: Vec<String> = idl["instructions"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .filter_map(|ix| ix["name"].as_str().map(str::to_owned))
+            .collect();
+        let paused = config.map_or(0, |c| c.paused);
+        let capabilities = CARD_FEATURES
+            .iter()
+            .map(|&(feature, ix, pause)| {
+                let deployed = instructions.iter().any(|name| name == ix);
+                (feature.to_owned(), deployed && paused & pause == 0)
+            })
+            .collect();
+
+        let (min_pool_fee_bps, max_pool_fee_bps) =
+            config.map_or((MIN_POOL_FEE_BPS, MAX_POOL_FEE_BPS), |c| c.pool_fee_bounds());
+        let mut fee_tiers: Vec<FeeTier> = config
+            .map(|c| c.fee_tiers.iter().filter(|t| t.discount_bps > 0).copied().collect())
+            .unwrap_or_default();
+        fee_tiers.sort_by_key(|t| t.min_volume);
+
+        let metadata = |key: &str| idl["metadata"][key].as_str().unwrap_or_default().to_owned();
+        Self {
+            name: metadata("name"),
+            version: metadata("version"),
+            program_id,
+            idl_on_chain,
+            instructions,
+            capabilities,
+            config_initialized: config.is_some(),
+            protocol_fee_bps: config.map_or(PROTOCOL_FEE_BPS as u16, |c| c.protocol_fee_bps),
+            protocol_fee_denominator: PROTOCOL_FEE_DENOMINATOR as u32,
+            referral_share_bps: config.map_or(REFERRAL_SHARE_BPS as u16, |c| c.referral_share_bps),
+            min_pool_fee_bps,
+            max_pool_fee_bps,
+            fee_tiers,
+            swaps_paused: paused & PAUSE_SWAPS != 0,
+            pool_creation_paused: paused & PAUSE_POOL_CREATION != 0,
+        }
+    }
+}
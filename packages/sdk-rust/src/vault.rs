@@ -0,0 +1,462 @@
+//! A2A-Vault: pooled LP shares over A2A-Swap, run by an agent strategy.
+//!
+//! The vault program (`programs/a2a-vault`) takes deposits in one base token
+//! and mints vault shares at its net asset value (NAV); a manager moves the
+//! vault's assets between idle tokens and LP positions in up to four A2A-Swap
+//! pools that contain the base token. Depositors only ever call
+//! [`deposit_ix`] / [`withdraw_ix`] (or [`A2ASwapClient::vault_deposit`] /
+//! [`A2ASwapClient::vault_withdraw`]); the manager's agent uses
+//! [`provide_liquidity_ix`], [`remove_liquidity_ix`] and [`swap_ix`], and
+//! anyone may crank [`compound_ix`].
+//!
+//! All vault assets belong to a system-owned authority PDA
+//! ([`derive_vault_authority`]), which is the A2A-Swap agent of every vault
+//! position and pays their rent, so the manager keeps it funded with SOL.
+//! Its token accounts are associated token accounts, which the manager
+//! creates before moving tokens into them.
+//!
+//! Deposits and withdrawals pass `[pool, position, other_token]` for every
+//! vault pool so the program can price shares; [`nav_accounts`] builds them.
+//! Withdrawals are paid from idle base tokens only.
+//!
+//! [`A2ASwapClient::vault_deposit`]: crate::A2ASwapClient::vault_deposit
+//! [`A2ASwapClient::vault_withdraw`]: crate::A2ASwapClient::vault_withdraw
+
+use std::str::FromStr;
+
+use sha2::{Digest, Sha256};
+use solana_sdk::{
+    instruction::{AccountMeta, Instruction},
+    pubkey::Pubkey,
+    sysvar,
+};
+
+use crate::{
+    error::{Error, Result},
+    instructions::{derive_ata, derive_pool_authority, derive_position, derive_treasury, spl_token_id},
+    state::{PoolState, PositionState},
+};
+
+/// Vault program ID — same on localnet, devnet and mainnet.
+pub const VAULT_PROGRAM_ID: &str = "47rcd1WG3Ep2tfvuQqvxeXkftPBov9jvjYE6xvYU4XaE";
+
+/// [`VAULT_PROGRAM_ID`] as a [`Pubkey`].
+pub fn vault_program_id() -> Pubkey {
+    Pubkey::from_str(VAULT_PROGRAM_ID).expect("valid vault program ID")
+}
+
+// ─── PDA seeds (mirrors programs/a2a-vault/src/constants.rs) ─────────────────
+
+pub const VAULT_SEED: &[u8] = b"vault";
+pub const VAULT_AUTHORITY_SEED: &[u8] = b"vault_authority";
+pub const SHARE_MINT_SEED: &[u8] = b"share_mint";
+
+/// Pools one vault may hold positions in.
+pub const MAX_POOLS: usize = 4;
+
+/// Virtual shares / base tokens on both sides of the share price; see
+/// [`shares_for_deposit`].
+const VIRTUAL_SHARES: u128 = 1;
+const VIRTUAL_ASSETS: u128 = 1;
+
+/// Derive the vault PDA for a manager and base mint.
+pub fn derive_vault(manager: &Pubkey, base_mint: &Pubkey, vault_program_id: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[VAULT_SEED, manager.as_ref(), base_mint.as_ref()], vault_program_id)
+}
+
+/// Derive the vault's authority: owns its tokens and A2A-Swap positions.
+pub fn derive_vault_authority(vault: &Pubkey, vault_program_id: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[VAULT_AUTHORITY_SEED, vault.as_ref()], vault_program_id)
+}
+
+/// Derive the vault's share mint.
+pub fn derive_share_mint(vault: &Pubkey, vault_program_id: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[SHARE_MINT_SEED, vault.as_ref()], vault_program_id)
+}
+
+// ─── State ────────────────────────────────────────────────────────────────────
+
+/// Vault account size, discriminator included.
+pub const VAULT_LEN: usize = 8 + 32 * 4 + 32 * MAX_POOLS + 4;
+
+/// Decoded `Vault` account.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VaultState {
+    /// Runs the strategy.
+    pub manager:         Pubkey,
+    /// Token deposits and withdrawals are made in, and NAV is priced in.
+    pub base_mint:       Pubkey,
+    pub share_mint:      Pubkey,
+    /// [`derive_vault_authority`] of the vault.
+    pub authority:       Pubkey,
+    /// The vault's pools, in the order deposits and withdrawals pass them.
+    pub pools:           Vec<Pubkey>,
+    pub bump:            u8,
+    pub authority_bump:  u8,
+    pub share_mint_bump: u8,
+}
+
+/// Anchor's 8-byte discriminator for `namespace:name`.
+fn discriminator(namespace: &str, name: &str) -> [u8; 8] {
+    let hash = Sha256::digest(format!("{namespace}:{name}"));
+    hash[..8].try_into().expect("sha256 is 32 bytes")
+}
+
+/// Decode a `Vault` account's data.
+pub fn parse_vault(data: &[u8]) -> Result<VaultState> {
+    if data.len() < VAULT_LEN {
+        return Err(Error::ParseError {
+            offset: 0,
+            reason: format!("Vault account is {} bytes; expected {}", data.len(), VAULT_LEN),
+        });
+    }
+    if data[..8] != discriminator("account", "Vault") {
+        return Err(Error::ParseError { offset: 0, reason: "not a Vault account".into() });
+    }
+    let key = |at: usize| Pubkey::new_from_array(data[at..at + 32].try_into().expect("32 bytes"));
+    let pool_count = data[264] as usize;
+    if pool_count > MAX_POOLS {
+        return Err(Error::ParseError { offset: 264, reason: format!("pool_count {pool_count} above {MAX_POOLS}") });
+    }
+    Ok(VaultState {
+        manager:         key(8),
+        base_mint:       key(40),
+        share_mint:      key(72),
+        authority:       key(104),
+        pools:           (0..pool_count).map(|i| key(136 + 32 * i)).collect(),
+        bump:            data[265],
+        authority_bump:  data[266],
+        share_mint_bump: data[267],
+    })
+}
+
+// ─── Share math (mirrors programs/a2a-vault/src/instructions/nav.rs) ─────────
+
+/// Shares minted for depositing `amount` into a vault worth `nav` with
+/// `share_supply` shares out, rounded down. One virtual share and base token
+/// sit on each side of the price, so donations cannot inflate it against the
+/// first depositor. `None` on overflow.
+pub fn shares_for_deposit(amount: u64, share_supply: u64, nav: u64) -> Option<u64> {
+    let shares = (amount as u128).checked_mul(share_supply as u128 + VIRTUAL_SHARES)? / (nav as u128 + VIRTUAL_ASSETS);
+    u64::try_from(shares).ok()
+}
+
+/// Base tokens paid for burning `shares`, rounded down. `None` on overflow.
+pub fn amount_for_shares(shares: u64, share_supply: u64, nav: u64) -> Option<u64> {
+    let amount = (shares as u128).checked_mul(nav as u128 + VIRTUAL_ASSETS)? / (share_supply as u128 + VIRTUAL_SHARES);
+    u64::try_from(amount).ok()
+}
+
+/// Base-token value of the vault's holding in one pool: `position` (its
+/// liquidity, owed fees and fees accrued since its last sync) plus
+/// `idle_other` of the pool's other token, priced at the pool's tracked
+/// reserves. The program's NAV is idle base plus this for every pool.
+pub fn pool_holding_value(
+    pool: &PoolState,
+    position: Option<&PositionState>,
+    idle_other: u64,
+    base_mint: &Pubkey,
+) -> u128 {
+    let (a, b) = position.map_or((0, 0), |p| {
+        let shares = p.lp_shares as u128;
+        let (lp_a, lp_b) = if pool.lp_supply == 0 {
+            (0, 0)
+        } else {
+            (
+                shares * pool.reserve_a as u128 / pool.lp_supply as u128,
+                shares * pool.reserve_b as u128 / pool.lp_supply as u128,
+            )
+        };
+        let pending = |global: u128, checkpoint: u128| shares.saturating_mul(global.saturating_sub(checkpoint)) >> 64;
+        (
+            lp_a + p.fees_owed_a as u128 + pending(pool.fee_growth_global_a, p.fee_growth_checkpoint_a),
+            lp_b + p.fees_owed_b as u128 + pending(pool.fee_growth_global_b, p.fee_growth_checkpoint_b),
+        )
+    });
+    let (base, other, reserve_base, reserve_other) = if pool.token_a_mint == *base_mint {
+        (a, b, pool.reserve_a, pool.reserve_b)
+    } else {
+        (b, a, pool.reserve_b, pool.reserve_a)
+    };
+    let other = other + idle_other as u128;
+    let other_in_base = if reserve_other == 0 { 0 } else { other * reserve_base as u128 / reserve_other as u128 };
+    base + other_in_base
+}
+
+// ─── Instructions ─────────────────────────────────────────────────────────────
+
+/// A vault pool and the mint of its non-base token, which the program needs
+/// to find the authority's idle balance of it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct VaultPool {
+    pub pool:       Pubkey,
+    pub other_mint: Pubkey,
+}
+
+/// `[pool, position, other_token]` for every vault pool, in the vault's
+/// order: the remaining accounts of `deposit` and `withdraw`.
+pub fn nav_accounts(vault: &VaultState, pools: &[VaultPool], program_id: &Pubkey) -> Vec<AccountMeta> {
+    pools
+        .iter()
+        .flat_map(|p| {
+            [
+                AccountMeta::new_readonly(p.pool, false),
+                AccountMeta::new_readonly(derive_position(&p.pool, &vault.authority, program_id).0, false),
+                AccountMeta::new_readonly(derive_ata(&vault.authority, &p.other_mint), false),
+            ]
+        })
+        .collect()
+}
+
+/// Build `initialize_vault`: `manager` creates and runs a vault for
+/// `base_mint` over `pools` (1–4 A2A-Swap pools containing `base_mint`, each
+/// with its circuit breaker on).
+pub fn initialize_vault_ix(
+    vault_program_id: &Pubkey,
+    manager:          &Pubkey,
+    base_mint:        &Pubkey,
+    pools:            &[Pubkey],
+) -> Instruction {
+    let (vault, _)      = derive_vault(manager, base_mint, vault_program_id);
+    let (authority, _)  = derive_vault_authority(&vault, vault_program_id);
+    let (share_mint, _) = derive_share_mint(&vault, vault_program_id);
+
+    let mut accounts = vec![
+        AccountMeta::new(*manager,                    true),   // mut + signer
+        AccountMeta::new_readonly(*base_mint,         false),
+        AccountMeta::new(vault,                       false),  // init
+        AccountMeta::new_readonly(authority,          false),
+        AccountMeta::new(share_mint,                  false),  // init
+        AccountMeta::new(derive_ata(&authority, base_mint), false), // init_if_needed
+        AccountMeta::new_readonly(spl_token_id(),     false),
+        AccountMeta::new_readonly(crate::instructions::ata_program_id(), false),
+        AccountMeta::new_readonly(Pubkey::default(),  false),  // system program
+    ];
+    accounts.extend(pools.iter().map(|p| AccountMeta::new_readonly(*p, false)));
+
+    Instruction {
+        program_id: *vault_program_id,
+        accounts,
+        data: discriminator("global", "initialize_vault").to_vec(),
+    }
+}
+
+/// Build `deposit`: `amount` base tokens from `depositor`'s associated token
+/// account for at least `min_shares`, minted to its share token account.
+#[allow(clippy::too_many_arguments)]
+pub fn deposit_ix(
+    vault_program_id: &Pubkey,
+    program_id:       &Pubkey,
+    depositor:        &Pubkey,
+    vault_key:        &Pubkey,
+    vault:            &VaultState,
+    pools:            &[VaultPool],
+    amount:           u64,
+    min_shares:       u64,
+) -> Instruction {
+    let mut data = discriminator("global", "deposit").to_vec();
+    data.extend_from_slice(&amount.to_le_bytes());
+    data.extend_from_slice(&min_shares.to_le_bytes());
+
+    let mut accounts = vec![
+        AccountMeta::new_readonly(*depositor,        true),
+        AccountMeta::new_readonly(*vault_key,        false),
+        AccountMeta::new_readonly(vault.authority,   false),
+        AccountMeta::new(vault.share_mint,           false),
+        AccountMeta::new(derive_ata(&vault.authority, &vault.base_mint), false),
+        AccountMeta::new(derive_ata(depositor, &vault.base_mint),        false),
+        AccountMeta::new(derive_ata(depositor, &vault.share_mint),       false),
+        AccountMeta::new_readonly(spl_token_id(),    false),
+    ];
+    accounts.extend(nav_accounts(vault, pools, program_id));
+    Instruction { program_id: *vault_program_id, accounts, data }
+}
+
+/// Build `withdraw`: burn `shares` from `owner`'s share account for at least
+/// `min_amount` base tokens, paid to its associated token account.
+#[allow(clippy::too_many_arguments)]
+pub fn withdraw_ix(
+    vault_program_id: &Pubkey,
+    program_id:       &Pubkey,
+    owner:            &Pubkey,
+    vault_key:        &Pubkey,
+    vault:            &VaultState,
+    pools:            &[VaultPool],
+    shares:           u64,
+    min_amount:       u64,
+) -> Instruction {
+    let mut data = discriminator("global", "withdraw").to_vec();
+    data.extend_from_slice(&shares.to_le_bytes());
+    data.extend_from_slice(&min_amount.to_le_bytes());
+
+    let mut accounts = vec![
+        AccountMeta::new_readonly(*owner,            true),
+        AccountMeta::new_readonly(*vault_key,        false),
+        AccountMeta::new_readonly(vault.authority,   false),
+        AccountMeta::new(vault.share_mint,           false),
+        AccountMeta::new(derive_ata(&vault.authority, &vault.base_mint), false),
+        AccountMeta::new(derive_ata(owner, &vault.share_mint),           false),
+        AccountMeta::new(derive_ata(owner, &vault.base_mint),            false),
+        AccountMeta::new_readonly(spl_token_id(),    false),
+    ];
+    accounts.extend(nav_accounts(vault, pools, program_id));
+    Instruction { program_id: *vault_program_id, accounts, data }
+}
+
+/// The A2A-Swap accounts shared by the position instructions, with the
+/// authority's associated token accounts for the pool's mints.
+fn position_accounts(program_id: &Pubkey, vault: &VaultState, pool_key: &Pubkey, pool: &PoolState) -> Vec<AccountMeta> {
+    vec![
+        AccountMeta::new(*pool_key, false),
+        AccountMeta::new_readonly(derive_pool_authority(pool_key, program_id).0, false),
+        AccountMeta::new(derive_position(pool_key, &vault.authority, program_id).0, false),
+        AccountMeta::new(pool.token_a_vault, false),
+        AccountMeta::new(pool.token_b_vault, false),
+        AccountMeta::new(derive_ata(&vault.authority, &pool.token_a_mint), false),
+        AccountMeta::new(derive_ata(&vault.authority, &pool.token_b_mint), false),
+    ]
+}
+
+/// Build the manager's `provide_liquidity`: deposit the vault's idle tokens
+/// into its position in `pool_key`.
+#[allow(clippy::too_many_arguments)]
+pub fn provide_liquidity_ix(
+    vault_program_id:   &Pubkey,
+    program_id:         &Pubkey,
+    vault_key:          &Pubkey,
+    vault:              &VaultState,
+    pool_key:           &Pubkey,
+    pool:               &PoolState,
+    amount_a:           u64,
+    amount_b:           u64,
+    min_lp:             u64,
+    auto_compound:      bool,
+    compound_threshold: u64,
+) -> Instruction {
+    let mut data = discriminator("global", "provide_liquidity").to_vec();
+    data.extend_from_slice(&amount_a.to_le_bytes());
+    data.extend_from_slice(&amount_b.to_le_bytes());
+    data.extend_from_slice(&min_lp.to_le_bytes());
+    data.push(auto_compound as u8);
+    data.extend_from_slice(&compound_threshold.to_le_bytes());
+
+    let mut accounts = vec![
+        AccountMeta::new_readonly(vault.manager, true),
+        AccountMeta::new_readonly(*vault_key,    false),
+        AccountMeta::new(vault.authority,        false),
+    ];
+    accounts.extend(position_accounts(program_id, vault, pool_key, pool));
+    accounts.extend([
+        AccountMeta::new_readonly(spl_token_id(),    false),
+        AccountMeta::new_readonly(Pubkey::default(), false), // system program
+        AccountMeta::new_readonly(sysvar::rent::ID,  false),
+        AccountMeta::new_readonly(*program_id,       false),
+    ]);
+    Instruction { program_id: *vault_program_id, accounts, data }
+}
+
+/// Build the manager's `remove_liquidity`: burn `lp_shares` of the vault's
+/// position in `pool_key` back into idle tokens.
+#[allow(clippy::too_many_arguments)]
+pub fn remove_liquidity_ix(
+    vault_program_id: &Pubkey,
+    program_id:       &Pubkey,
+    vault_key:        &Pubkey,
+    vault:            &VaultState,
+    pool_key:         &Pubkey,
+    pool:             &PoolState,
+    lp_shares:        u64,
+    min_a:            u64,
+    min_b:            u64,
+) -> Instruction {
+    let mut data = discriminator("global", "remove_liquidity").to_vec();
+    data.extend_from_slice(&lp_shares.to_le_bytes());
+    data.extend_from_slice(&min_a.to_le_bytes());
+    data.extend_from_slice(&min_b.to_le_bytes());
+
+    let mut accounts = vec![
+        AccountMeta::new_readonly(vault.manager, true),
+        AccountMeta::new_readonly(*vault_key,    false),
+        AccountMeta::new(vault.authority,        false),
+    ];
+    accounts.extend(position_accounts(program_id, vault, pool_key, pool));
+    accounts.extend([
+        AccountMeta::new_readonly(spl_token_id(), false),
+        AccountMeta::new_readonly(*program_id,    false),
+    ]);
+    Instruction { program_id: *vault_program_id, accounts, data }
+}
+
+/// Build the manager's `swap`: sell `amount_in` of the vault's idle tokens
+/// in `pool_key`; `a_to_b` sells token A.
+#[allow(clippy::too_many_arguments)]
+pub fn swap_ix(
+    vault_program_id:     &Pubkey,
+    program_id:           &Pubkey,
+    vault_key:            &Pubkey,
+    vault:                &VaultState,
+    pool_key:             &Pubkey,
+    pool:                 &PoolState,
+    amount_in:            u64,
+    min_amount_out:       u64,
+    a_to_b:               bool,
+    max_price_impact_bps: u16,
+) -> Instruction {
+    let mut data = discriminator("global", "swap").to_vec();
+    data.extend_from_slice(&amount_in.to_le_bytes());
+    data.extend_from_slice(&min_amount_out.to_le_bytes());
+    data.push(a_to_b as u8);
+    data.extend_from_slice(&max_price_impact_bps.to_le_bytes());
+
+    let (mint_in, mint_out) = if a_to_b {
+        (pool.token_a_mint, pool.token_b_mint)
+    } else {
+        (pool.token_b_mint, pool.token_a_mint)
+    };
+    let (treasury, _) = derive_treasury(program_id);
+    Instruction {
+        program_id: *vault_program_id,
+        accounts: vec![
+            AccountMeta::new_readonly(vault.manager, true),
+            AccountMeta::new_readonly(*vault_key,    false),
+            AccountMeta::new(vault.authority,        false),
+            AccountMeta::new(*pool_key,              false),
+            AccountMeta::new_readonly(derive_pool_authority(pool_key, program_id).0, false),
+            AccountMeta::new(pool.token_a_vault,     false),
+            AccountMeta::new(pool.token_b_vault,     false),
+            AccountMeta::new(derive_ata(&vault.authority, &mint_in),  false),
+            AccountMeta::new(derive_ata(&vault.authority, &mint_out), false),
+            AccountMeta::new_readonly(treasury,      false),
+            AccountMeta::new(derive_ata(&treasury, &mint_in), false),
+            AccountMeta::new_readonly(spl_token_id(), false),
+            AccountMeta::new_readonly(*program_id,   false),
+        ],
+        data,
+    }
+}
+
+/// Build the permissionless `compound`: claim the vault position's fees in
+/// `pool_key`, reinvesting them if the position auto-compounds.
+pub fn compound_ix(
+    vault_program_id: &Pubkey,
+    program_id:       &Pubkey,
+    vault_key:        &Pubkey,
+    vault:            &VaultState,
+    pool_key:         &Pubkey,
+    pool:             &PoolState,
+) -> Instruction {
+    let mut accounts = vec![
+        AccountMeta::new_readonly(*vault_key, false),
+        AccountMeta::new(vault.authority,     false),
+    ];
+    accounts.extend(position_accounts(program_id, vault, pool_key, pool));
+    accounts.extend([
+        AccountMeta::new_readonly(spl_token_id(), false),
+        AccountMeta::new_readonly(*program_id,    false),
+    ]);
+    Instruction {
+        program_id: *vault_program_id,
+        accounts,
+        data: discriminator("global", "compound").to_vec(),
+    }
+}
@@ -0,0 +1,66 @@
+//! Session aggregation and export in `a2a_swap_sdk::analytics`.
+
+use a2a_swap_sdk::{analytics::ExecutionLog, Error, SwapResult};
+use solana_sdk::pubkey::Pubkey;
+
+fn swap(estimated_out: u64, actual_out: Option<u64>, fee: u64) -> SwapResult {
+    SwapResult {
+        signature:             "sig".into(),
+        pool:                  Pubkey::new_unique(),
+        amount_in:             1_000,
+        estimated_out,
+        min_amount_out:        estimated_out * 99 / 100,
+        referral_fee:          0,
+        a_to_b:                true,
+        actual_out,
+        actual_fee_paid:       Some(fee),
+        realized_slippage_bps: actual_out
+            .map(|out| a2a_swap_sdk::metrics::slippage_bps(estimated_out, out)),
+        tranche_signatures:    Vec::new(),
+    }
+}
+
+#[test]
+fn summary_weights_slippage_and_counts_failures() {
+    let mut log = ExecutionLog::new();
+    log.record(&Ok(swap(10_000, Some(9_900), 5_000)));  // 100 bps
+    log.record(&Ok(swap(30_000, Some(30_000), 7_000))); // 0 bps
+    log.record(&Ok(swap(5_000, None, 5_000)));          // fill unreadable
+    log.record(&Err(Error::NoLiquidity));
+
+    let s = log.summary();
+    assert_eq!((s.attempts, s.failures), (4, 1));
+    assert_eq!(s.failure_rate, 0.25);
+    assert_eq!(s.total_in, 3_000);
+    assert_eq!((s.total_estimated_out, s.total_actual_out), (40_000, 39_900));
+    assert_eq!(s.weighted_slippage_bps, Some(25.0));
+    assert_eq!(s.mean_slippage_bps, Some(50.0));
+    assert_eq!((s.p95_slippage_bps, s.max_slippage_bps), (Some(100.0), Some(100.0)));
+    assert_eq!(s.total_fee_lamports, 17_000);
+}
+
+#[test]
+fn empty_log_has_no_rates() {
+    let s = ExecutionLog::new().summary();
+    assert_eq!((s.attempts, s.failure_rate), (0, 0.0));
+    assert_eq!((s.weighted_slippage_bps, s.p95_slippage_bps, s.mean_fee_lamports), (None, None, None));
+}
+
+#[test]
+fn csv_and_json_exports() {
+    let mut log = ExecutionLog::new();
+    log.record(&Ok(swap(10_000, Some(9_950), 5_000)));
+    log.record_failure(2_000, &Error::SlippageExceeded { estimated: 1, min: 2 });
+
+    let csv = log.to_csv();
+    let lines: Vec<&str> = csv.lines().collect();
+    assert_eq!(lines.len(), 3);
+    let columns = lines[0].split(',').count();
+    assert!(lines.iter().all(|l| l.split(',').count() == columns));
+    assert!(lines[1].ends_with(",10000,9950,50,5000,"));
+    assert!(lines[2].ends_with(",2000,0,,,,SLIPPAGE_EXCEEDED"));
+
+    let json = log.to_json();
+    assert_eq!(json["summary"]["attempts"], 2);
+    assert_eq!(json["records"][1]["error"], "SLIPPAGE_EXCEEDED");
+}
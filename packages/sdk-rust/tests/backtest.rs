@@ -0,0 +1,116 @@
+//! History replay and reports in `a2a_swap_sdk::backtest`.
+
+use a2a_swap_sdk::{
+    backtest::{self, Backtest, HistoryEvent},
+    state::{CircuitBreaker, DynamicFee},
+    strategies::{dca::DcaStrategy, SpendCap},
+    CurveKind, Error, PoolInfo,
+};
+use solana_sdk::pubkey::Pubkey;
+
+fn pool(reserve_a: u64, reserve_b: u64) -> PoolInfo {
+    PoolInfo {
+        pool: Pubkey::new_unique(),
+        mint_a: Pubkey::new_unique(),
+        mint_b: Pubkey::new_unique(),
+        vault_a: Pubkey::new_unique(),
+        vault_b: Pubkey::new_unique(),
+        reserve_a,
+        reserve_b,
+        lp_supply: 0,
+        fee_rate_bps: 30,
+        effective_fee_bps: 30,
+        dynamic_fee: DynamicFee::default(),
+        circuit_breaker: CircuitBreaker::default(),
+        permissioned: false,
+        gate_mint: None,
+        protocol_fee_bps: 0,
+        curve: CurveKind::ConstantProduct,
+        volume_a: 0,
+        volume_b: 0,
+        fees_collected_a: 0,
+        fees_collected_b: 0,
+        spot_price: reserve_b as f64 / reserve_a as f64,
+        lp_share_value_a: 0.0,
+        lp_share_value_b: 0.0,
+        tvl: None,
+        created_slot: None,
+        created_at: None,
+    }
+}
+
+#[test]
+fn history_folds_into_updates() {
+    let jsonl = r#"
+{"type":"reserves","timestamp":10,"reserve_a":2000000,"reserve_b":1000000}
+
+{"type":"swap","timestamp":20,"a_to_b":false,"amount_in":10000}
+"#;
+    let events = HistoryEvent::from_jsonl(jsonl).unwrap();
+    let updates = backtest::updates(pool(1_000_000, 1_000_000), &events).unwrap();
+    assert_eq!(updates.len(), 2);
+    assert_eq!((updates[0].timestamp, updates[0].info.spot_price), (10, 0.5));
+    // 10_000 B in (0.3 % LP fee stays in the vault) → 19_743 A out.
+    let after = &updates[1].info;
+    assert_eq!((after.reserve_a, after.reserve_b), (2_000_000 - 19_743, 1_010_000));
+
+    assert!(matches!(
+        HistoryEvent::from_jsonl("{\"type\":\"nope\"}"),
+        Err(Error::InvalidArgument(m)) if m.starts_with("history line 1")
+    ));
+}
+
+#[tokio::test]
+async fn report_marks_net_flows_to_the_final_price() {
+    let start = pool(1_000_000, 1_000_000);
+    let (a, b) = (start.mint_a, start.mint_b);
+    let events = [
+        HistoryEvent::Reserves { timestamp: 0, reserve_a: 1_000_000, reserve_b: 1_000_000 },
+        HistoryEvent::Reserves { timestamp: 60, reserve_a: 1_000_000, reserve_b: 1_000_000 },
+        HistoryEvent::Reserves { timestamp: 120, reserve_a: 1_000_000, reserve_b: 2_000_000 },
+    ];
+    let updates = backtest::updates(start.clone(), &events).unwrap();
+
+    // Buy A with 10_000 B every minute: two buys at 1.0, then A doubles.
+    let mut dca = DcaStrategy::new(b, a, 10_000, 60);
+    dca.buys_left = Some(2);
+    let report = Backtest::new().run(&mut dca, updates).await.unwrap();
+    assert_eq!((report.strategy.as_str(), report.ticks, report.trades.len()), ("dca", 3, 2));
+    assert_eq!((report.base_mint, report.quote_mint), (a, b));
+    assert_eq!((report.net_base, report.net_quote), (2 * 9_871, -20_000));
+    assert_eq!(report.final_price, 2.0);
+    assert_eq!(report.pnl, -20_000.0 + 2.0 * 19_742.0);
+    assert_eq!(report.fees_paid, 60.0);
+    // 9_970 after fees into 1_000_000.
+    assert!((report.mean_price_impact_bps.unwrap() - 98.716).abs() < 0.001);
+    assert_eq!(report.trades[0].lp_fee, 30);
+
+    // Valued in A: 19_742 A held, 20_000 B spent at 0.5 A each.
+    let mut dca = DcaStrategy::new(b, a, 10_000, 60);
+    dca.buys_left = Some(2);
+    let updates = backtest::updates(start.clone(), &events).unwrap();
+    let report = Backtest::new().value_in(a).run(&mut dca, updates.clone()).await.unwrap();
+    assert_eq!((report.base_mint, report.final_price), (b, 0.5));
+    assert_eq!(report.pnl, 19_742.0 - 20_000.0 * 0.5);
+
+    let outsider = Backtest::new().value_in(Pubkey::new_unique()).run(&mut dca, updates).await;
+    assert!(matches!(outsider, Err(Error::InvalidArgument(_))));
+}
+
+#[tokio::test]
+async fn counts_rejections_and_refuses_empty_history() {
+    let start = pool(1_000_000, 1_000_000);
+    let (a, b) = (start.mint_a, start.mint_b);
+    let events: Vec<_> = (0..3)
+        .map(|t| HistoryEvent::Reserves { timestamp: t, reserve_a: 1_000_000, reserve_b: 1_000_000 })
+        .collect();
+    let mut dca = DcaStrategy::new(b, a, 1_000, 0);
+    let report = Backtest::new()
+        .with_policy(SpendCap::new(b, 0, 1_500))
+        .run(&mut dca, backtest::updates(start, &events).unwrap())
+        .await
+        .unwrap();
+    assert_eq!((report.trades.len(), report.rejected, report.failed), (1, 2, 0));
+
+    assert!(matches!(Backtest::new().run(&mut dca, []).await, Err(Error::InvalidArgument(_))));
+}
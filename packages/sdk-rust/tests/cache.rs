@@ -0,0 +1,160 @@
+//! `AccountCache` in front of `A2ASwapClient` against a local mock JSON-RPC node.
+
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::TcpListener;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use a2a_swap_core::Account as _;
+use a2a_swap_sdk::{
+    cache::AccountCache,
+    instructions::{derive_pool, derive_treasury},
+    A2ASwapClient, SimulateParams,
+};
+use base64::{engine::general_purpose::STANDARD, Engine as _};
+use serde_json::{json, Value};
+use solana_sdk::pubkey::Pubkey;
+
+/// Serve `getMultipleAccounts` with `pool`'s data at `pool_key`, one request
+/// per connection; each response reports the next slot. Returns the URL and
+/// the keys of every request so far.
+fn serve(pool_key: Pubkey, pool: Vec<u8>) -> (String, Arc<Mutex<Vec<Vec<String>>>>) {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("http://{}", listener.local_addr().unwrap());
+    let seen = Arc::new(Mutex::new(Vec::new()));
+    let log = seen.clone();
+    std::thread::spawn(move || {
+        for stream in listener.incoming() {
+            let mut reader = BufReader::new(stream.unwrap());
+            let mut len = 0;
+            loop {
+                let mut header = String::new();
+                reader.read_line(&mut header).unwrap();
+                if header.trim_end().is_empty() {
+                    break;
+                }
+                if let Some((name, value)) = header.trim_end().split_once(": ") {
+                    if name.eq_ignore_ascii_case("content-length") {
+                        len = value.parse().unwrap();
+                    }
+                }
+            }
+            let mut body = vec![0; len];
+            reader.read_exact(&mut body).unwrap();
+            let request: Value = serde_json::from_slice(&body).unwrap();
+            assert_eq!(request["method"], "getMultipleAccounts");
+            let keys: Vec<String> = serde_json::from_value(request["params"][0].clone()).unwrap();
+            let accounts: Vec<Value> = keys.iter().map(|key| {
+                if key == &pool_key.to_string() {
+                    json!({ "data": [STANDARD.encode(&pool), "base64"], "executable": false, "lamports": 1,
+                            "owner": Pubkey::default().to_string(), "rentEpoch": 0, "space": pool.len() })
+                } else {
+                    Value::Null
+                }
+            }).collect();
+            let slot = {
+                let mut log = log.lock().unwrap();
+                log.push(keys);
+                log.len()
+            };
+            let result = json!({ "context": { "slot": slot }, "value": accounts });
+            let response = json!({ "jsonrpc": "2.0", "id": request["id"], "result": result }).to_string();
+            write!(
+                reader.get_mut(),
+                "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{response}",
+                response.len()
+            ).unwrap();
+        }
+    });
+    (url, seen)
+}
+
+struct Fixture {
+    client:   A2ASwapClient,
+    cache:    AccountCache,
+    seen:     Arc<Mutex<Vec<Vec<String>>>>,
+    params:   SimulateParams,
+    pool:     Pubkey,
+    reversed: Pubkey,
+    treasury: Pubkey,
+}
+
+fn fixture(cache: AccountCache) -> Fixture {
+    let program = Pubkey::new_unique();
+    let (mint_a, mint_b) = (Pubkey::new_unique(), Pubkey::new_unique());
+    let (pool, _) = derive_pool(&mint_a, &mint_b, &program);
+    let data = a2a_swap_core::Pool {
+        token_a_mint:  mint_a.to_bytes(),
+        token_b_mint:  mint_b.to_bytes(),
+        token_a_vault: Pubkey::new_unique().to_bytes(),
+        token_b_vault: Pubkey::new_unique().to_bytes(),
+        lp_supply:     1_000_000,
+        fee_rate_bps:  30,
+        reserve_a:     1_000_000,
+        reserve_b:     4_000_000,
+        version:       a2a_swap_sdk::state::POOL_VERSION,
+        ..Default::default()
+    }.to_account_data();
+    let (url, seen) = serve(pool, data);
+    Fixture {
+        client:   A2ASwapClient::new(url).with_program_id(program).with_account_cache(cache.clone()),
+        cache,
+        seen,
+        params:   SimulateParams { mint_in: mint_a, mint_out: mint_b, amount_in: 10_000 },
+        pool,
+        reversed: derive_pool(&mint_b, &mint_a, &program).0,
+        treasury: derive_treasury(&program).0,
+    }
+}
+
+fn keys(keys: &[Pubkey]) -> Vec<String> {
+    keys.iter().map(Pubkey::to_string).collect()
+}
+
+#[tokio::test]
+async fn repeated_quotes_in_a_slot_read_once() {
+    let f = fixture(AccountCache::new().with_max_age(Duration::from_secs(60)));
+    let first = f.client.simulate(f.params.clone()).await.unwrap();
+    for _ in 0..9 {
+        let again = f.client.simulate(f.params.clone()).await.unwrap();
+        assert_eq!(again.estimated_out, first.estimated_out);
+    }
+    assert_eq!(*f.seen.lock().unwrap(), vec![keys(&[f.pool, f.reversed, f.treasury])]);
+    assert_eq!((f.cache.len(), f.cache.slot()), (3, 1));
+}
+
+#[tokio::test]
+async fn a_stale_key_refetches_the_whole_read() {
+    let f = fixture(AccountCache::new().with_max_age(Duration::from_secs(60)));
+    f.client.simulate(f.params.clone()).await.unwrap();
+
+    // Pool and treasury come back from the same slot, or not at all.
+    f.cache.invalidate(&f.pool);
+    f.client.simulate(f.params.clone()).await.unwrap();
+    f.client.simulate(f.params.clone()).await.unwrap();
+
+    let all = keys(&[f.pool, f.reversed, f.treasury]);
+    assert_eq!(*f.seen.lock().unwrap(), vec![all.clone(), all]);
+    assert_eq!(f.cache.slot(), 2);
+}
+
+#[tokio::test]
+async fn a_newer_slot_retires_older_entries() {
+    let f = fixture(AccountCache::new().with_max_age(Duration::from_secs(60)));
+    f.client.simulate(f.params.clone()).await.unwrap();
+    // A read of other accounts comes back from slot 2, which makes the
+    // slot-1 entries stale.
+    f.client.pool_info(Pubkey::new_unique(), Pubkey::new_unique()).await.unwrap_err();
+    f.client.simulate(f.params.clone()).await.unwrap();
+    f.client.simulate(f.params.clone()).await.unwrap();
+    assert_eq!(f.seen.lock().unwrap().len(), 3);
+}
+
+#[tokio::test]
+async fn zero_max_age_reads_every_time() {
+    let f = fixture(AccountCache::new().with_max_age(Duration::ZERO));
+    for _ in 0..3 {
+        f.client.simulate(f.params.clone()).await.unwrap();
+    }
+    assert_eq!(f.seen.lock().unwrap().len(), 3);
+}
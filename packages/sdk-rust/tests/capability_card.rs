@@ -0,0 +1,72 @@
+//! The capability card in `a2a_swap_sdk::CapabilityCard`.
+//!
+//! No RPC: builds cards from the checked-in IDL and hand-made protocol
+//! configs.
+
+use a2a_swap_sdk::{
+    state::{FeeTier, ProtocolConfigState, FEE_TIER_COUNT, LOCKER_COUNT, PAUSE_SWAPS},
+    CapabilityCard,
+};
+use solana_sdk::pubkey::Pubkey;
+
+fn idl() -> serde_json::Value {
+    serde_json::from_str(a2a_swap_core::IDL_JSON).unwrap()
+}
+
+fn config() -> ProtocolConfigState {
+    ProtocolConfigState {
+        admin:              Pubkey::new_unique(),
+        treasury_authority: Pubkey::new_unique(),
+        protocol_fee_bps:   15,
+        referral_share_bps: 1_000,
+        paused:             0,
+        min_pool_fee_bps:   5,
+        max_pool_fee_bps:   50,
+        fee_tiers:          [FeeTier::default(); FEE_TIER_COUNT],
+        lockers:            [Pubkey::default(); LOCKER_COUNT],
+    }
+}
+
+#[test]
+fn card_defaults_before_the_config_exists() {
+    let card = CapabilityCard::new(Pubkey::new_unique(), &idl(), false, None);
+    assert_eq!(card.name, "a2a_swap");
+    assert!(card.instructions.iter().any(|ix| ix == "swap_with_intent"));
+    assert!(card.capabilities.values().all(|&on| on));
+    assert!(!card.config_initialized);
+    assert_eq!((card.protocol_fee_bps, card.referral_share_bps), (20, 2_000));
+    assert_eq!((card.min_pool_fee_bps, card.max_pool_fee_bps), (1, 100));
+    assert!(!card.swaps_paused && !card.pool_creation_paused);
+}
+
+#[test]
+fn card_follows_the_config_and_pause_switches() {
+    let mut config = config();
+    config.paused = PAUSE_SWAPS;
+    config.fee_tiers[0] = FeeTier { min_volume: 5_000, discount_bps: 2_500 };
+    config.fee_tiers[2] = FeeTier { min_volume: 1_000, discount_bps: 1_000 };
+
+    let card = CapabilityCard::new(Pubkey::new_unique(), &idl(), true, Some(&config));
+    assert!(card.config_initialized);
+    assert_eq!((card.protocol_fee_bps, card.referral_share_bps), (15, 1_000));
+    assert_eq!((card.min_pool_fee_bps, card.max_pool_fee_bps), (5, 50));
+    assert_eq!(card.fee_tiers.iter().map(|t| t.min_volume).collect::<Vec<_>>(), [1_000, 5_000]);
+
+    assert!(card.swaps_paused && !card.pool_creation_paused);
+    assert!(!card.capabilities["swap"] && !card.capabilities["signed_intents"]);
+    assert!(card.capabilities["pool_creation"] && card.capabilities["liquidity"]);
+}
+
+#[test]
+fn card_lists_only_deployed_features() {
+    let mut idl = idl();
+    idl["instructions"]
+        .as_array_mut()
+        .unwrap()
+        .retain(|ix| !ix["name"].as_str().unwrap().starts_with("swap_"));
+
+    let card = CapabilityCard::new(Pubkey::new_unique(), &idl, true, Some(&config()));
+    assert!(card.capabilities["swap"]);
+    assert!(!card.capabilities["exact_out_swaps"] && !card.capabilities["delegated_swaps"]);
+    assert!(!card.instructions.iter().any(|ix| ix == "swap_range"));
+}
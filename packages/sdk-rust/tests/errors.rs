@@ -0,0 +1,64 @@
+//! Contract tests for the stable error codes shared with the HTTP API.
+
+use a2a_swap_sdk::{Error, ErrorCode};
+use std::collections::HashSet;
+
+#[test]
+fn codes_round_trip_and_are_unique() {
+    let mut seen = HashSet::new();
+    for &code in ErrorCode::ALL {
+        let s = code.as_str();
+        assert!(seen.insert(s), "duplicate code {s}");
+        assert!(
+            s.chars().all(|c| c.is_ascii_uppercase() || c == '_'),
+            "{s} is not SCREAMING_SNAKE_CASE"
+        );
+        assert_eq!(ErrorCode::parse(s), Some(code));
+    }
+    assert_eq!(ErrorCode::parse("NOPE"), None);
+}
+
+#[test]
+fn sdk_errors_map_to_codes() {
+    assert_eq!(Error::NoLiquidity.code(), ErrorCode::NoLiquidity);
+    assert_eq!(Error::MathOverflow.code().as_str(), "MATH_OVERFLOW");
+    assert_eq!(
+        Error::PriceImpactExceeded { impact_bps: 500, max_bps: 300 }.code().to_string(),
+        "PRICE_IMPACT_EXCEEDED"
+    );
+    assert_eq!(
+        Error::InsufficientBalance { account: Default::default(), needed: 2, available: 1 }.code().as_str(),
+        "INSUFFICIENT_BALANCE"
+    );
+}
+
+#[test]
+fn program_errors_only_map_from_our_program() {
+    use a2a_swap_sdk::A2AErrorCode;
+    use solana_sdk::{
+        instruction::{Instruction, InstructionError},
+        pubkey::Pubkey,
+        transaction::TransactionError,
+    };
+
+    let program_id = Pubkey::new_unique();
+    let ixs = [
+        Instruction::new_with_bytes(Pubkey::new_unique(), &[], vec![]), // e.g. SPL Token
+        Instruction::new_with_bytes(program_id, &[], vec![]),
+    ];
+    let custom = |idx, code| TransactionError::InstructionError(idx, InstructionError::Custom(code));
+
+    assert_eq!(
+        A2AErrorCode::from_transaction_error(&custom(1, 0x1771), &ixs, &program_id),
+        Some(A2AErrorCode::SlippageExceeded)
+    );
+    assert_eq!(A2AErrorCode::from_transaction_error(&custom(0, 0x1771), &ixs, &program_id), None);
+    assert_eq!(A2AErrorCode::from_transaction_error(&custom(1, 42), &ixs, &program_id), None);
+
+    let err = Error::Program(A2AErrorCode::SlippageExceeded);
+    assert_eq!(err.code(), ErrorCode::SlippageExceeded);
+    assert_eq!(
+        err.to_string(),
+        "Program error SlippageExceeded (0x1771): Output below minimum — slippage exceeded"
+    );
+}
@@ -0,0 +1,96 @@
+//! Cost basis and CSV / Parquet encoding in `a2a_swap_sdk::export`.
+
+use a2a_swap_sdk::{
+    export::{apply_cost_basis, to_csv, to_parquet, ExportFormat, FeeKind, FeeRecord, Record, TradeRecord},
+    Error,
+};
+use solana_sdk::pubkey::Pubkey;
+
+fn trade(pool: Pubkey, a_to_b: bool, amount_in: u64, amount_out: u64) -> TradeRecord {
+    TradeRecord {
+        timestamp: Some(1_700_000_000),
+        signature: "sig".into(),
+        slot: 1,
+        pool,
+        mint_in: Pubkey::default(),
+        mint_out: Pubkey::default(),
+        a_to_b,
+        amount_in,
+        amount_out,
+        protocol_fee: 0,
+        lp_fee: 0,
+        network_fee_lamports: 5_000,
+        price_b_per_a: 0.0,
+        cost_basis_b: None,
+        realized_pnl_b: None,
+    }
+}
+
+#[test]
+fn average_cost_basis_per_pool() {
+    let (pool, other) = (Pubkey::new_unique(), Pubkey::new_unique());
+    let mut trades = vec![
+        trade(pool, false, 1_000, 100),  // buy 100 A for 1_000 B
+        trade(pool, false, 3_000, 100),  // buy 100 A for 3_000 B → avg 20 B/A
+        trade(other, true, 50, 500),     // sale in another pool: nothing to match
+        trade(pool, true, 50, 1_500),    // sell 50 A for 1_500 B
+        trade(pool, true, 200, 4_000),   // sell 200 A, only 150 held
+    ];
+    apply_cost_basis(&mut trades);
+    let got: Vec<_> = trades.iter().map(|t| (t.cost_basis_b, t.realized_pnl_b)).collect();
+    assert_eq!(got, [
+        (Some(1_000), None),
+        (Some(3_000), None),
+        (None, None),
+        (Some(1_000), Some(500)),
+        // 150 of 200 matched at 20 B/A; proceeds 3_000 for the matched part.
+        (Some(3_000), Some(0)),
+    ]);
+}
+
+#[test]
+fn csv_has_a_header_and_empty_nulls() {
+    let fee = FeeRecord {
+        timestamp: None,
+        signature: "a,\"b\"".into(),
+        slot: 7,
+        pool: Pubkey::default(),
+        mint_a: Pubkey::default(),
+        mint_b: Pubkey::default(),
+        kind: FeeKind::Compounded,
+        amount_a: 1,
+        amount_b: 2,
+        lp_shares: Some(3),
+    };
+    let csv = to_csv(&[fee]);
+    let mut lines = csv.lines();
+    assert_eq!(lines.next().unwrap(), "timestamp,signature,slot,pool,mint_a,mint_b,kind,amount_a,amount_b,lp_shares");
+    let zero = Pubkey::default().to_string();
+    assert_eq!(lines.next().unwrap(), format!(",\"a,\"\"b\"\"\",7,{zero},{zero},{zero},compounded,1,2,3"));
+    assert_eq!(lines.next(), None);
+}
+
+#[test]
+fn parquet_file_frames_its_footer() {
+    let trades = vec![trade(Pubkey::new_unique(), false, 1, 2), trade(Pubkey::new_unique(), true, 3, 4)];
+    for file in [to_parquet(&trades), to_parquet::<TradeRecord>(&[])] {
+        assert_eq!(&file[..4], b"PAR1");
+        assert_eq!(&file[file.len() - 4..], b"PAR1");
+        let footer = u32::from_le_bytes(file[file.len() - 8..file.len() - 4].try_into().unwrap()) as usize;
+        assert!(footer > 0 && footer + 12 <= file.len());
+        // Every column name is in the schema.
+        let meta = &file[file.len() - 8 - footer..file.len() - 8];
+        for column in TradeRecord::COLUMNS {
+            assert!(meta.windows(column.name.len()).any(|w| w == column.name.as_bytes()), "{}", column.name);
+        }
+    }
+    assert_eq!(ExportFormat::Parquet.encode(&trades), to_parquet(&trades));
+}
+
+#[test]
+fn format_parses() {
+    assert_eq!("CSV".parse::<ExportFormat>().unwrap(), ExportFormat::Csv);
+    assert_eq!("parquet".parse::<ExportFormat>().unwrap().extension(), "parquet");
+    assert!(matches!("xlsx".parse::<ExportFormat>(), Err(Error::InvalidArgument(_))));
+}
+
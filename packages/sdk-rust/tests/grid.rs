@@ -0,0 +1,95 @@
+//! Level ladder, order selection and state in `a2a_swap_sdk::strategies::grid`.
+
+use a2a_swap_sdk::{
+    strategies::{
+        grid::{GridConfig, GridSide, GridState},
+        Fill,
+    },
+    Error,
+};
+use solana_sdk::pubkey::Pubkey;
+
+fn config(levels: u32, max_levels_per_step: u32) -> GridConfig {
+    GridConfig {
+        base_mint:            Pubkey::new_unique(),
+        quote_mint:           Pubkey::new_unique(),
+        levels,
+        spacing_bps:          1_000, // 10% between levels
+        level_size:           1_000,
+        max_slippage_bps:     100,
+        max_price_impact_bps: 0,
+        max_levels_per_step,
+        referrer:             None,
+    }
+}
+
+fn filled(amount_in: u64, amount_out: u64) -> Fill {
+    Fill { amount_in, amount_out, signature: Some("sig".into()) }
+}
+
+#[test]
+fn ladder_is_geometric_around_the_center() {
+    let state = GridState::new(&config(2, 0), 2.0).unwrap();
+    let expected = [2.0 / 1.21, 2.0 / 1.1, 2.0, 2.2, 2.42];
+    assert_eq!(state.level_prices.len(), 5);
+    for (got, want) in state.level_prices.iter().zip(expected) {
+        assert!((got - want).abs() < 1e-9, "{got} vs {want}");
+    }
+    assert_eq!(state.anchor, 2);
+    assert!(state.pending(&config(2, 0), 2.1).is_empty());
+}
+
+#[test]
+fn rejects_bad_grids() {
+    assert!(matches!(GridState::new(&config(2, 0), 0.0), Err(Error::NoLiquidity)));
+    assert!(matches!(GridState::new(&config(0, 0), 1.0), Err(Error::InvalidArgument(_))));
+    let mut wide = config(2, 0);
+    wide.spacing_bps = 10_001;
+    assert!(matches!(GridState::new(&wide, 1.0), Err(Error::InvalidArgument(_))));
+}
+
+#[test]
+fn sells_crossed_levels_nearest_first_up_to_the_cap() {
+    let cfg = config(3, 2);
+    let state = GridState::new(&cfg, 1.0).unwrap();
+    // 1.35 is past 1.1, 1.21 and 1.331 — the cap keeps the nearest two.
+    let orders = state.pending(&cfg, 1.35);
+    assert_eq!(orders.iter().map(|o| o.level).collect::<Vec<_>>(), [4, 5]);
+    let sell = orders[0];
+    assert_eq!(sell.side, GridSide::Sell);
+    assert_eq!(sell.amount_in, 1_000);
+    assert_eq!(sell.min_amount_out, 1_089); // 1_000 × 1.1 × 0.99
+}
+
+#[test]
+fn buys_below_the_anchor_after_a_sell() {
+    let cfg = config(2, 0);
+    let mut state = GridState::new(&cfg, 1.0).unwrap();
+    let sell = state.pending(&cfg, 1.1)[0];
+    let fill = state.record(&sell, &filled(1_000, 1_100), 42);
+    assert_eq!((fill.level, fill.amount_out, fill.timestamp), (3, 1_100, 42));
+    assert_eq!(state.anchor, 3);
+
+    // Back at the center: the level below the anchor re-arms as a buy.
+    let orders = state.pending(&cfg, 1.0);
+    assert_eq!(orders.len(), 1);
+    let buy = orders[0];
+    assert_eq!((buy.level, buy.side), (2, GridSide::Buy));
+    assert_eq!(buy.amount_in, 1_000); // 1_000 base at 1.0
+    assert_eq!(buy.min_amount_out, 990);
+
+    state.record(&buy, &filled(1_000, 995), 43);
+    assert_eq!(state.net_flows(), (-5, 100));
+}
+
+#[test]
+fn state_round_trips_through_json() {
+    let cfg = config(2, 0);
+    let mut state = GridState::new(&cfg, 3.0).unwrap();
+    let sell = state.pending(&cfg, 3.5)[0];
+    state.record(&sell, &filled(1_000, 3_300), 42);
+    assert_eq!(GridState::from_json(&state.to_json()).unwrap(), state);
+
+    state.anchor = 9;
+    assert!(matches!(GridState::from_json(&state.to_json()), Err(Error::InvalidArgument(_))));
+}
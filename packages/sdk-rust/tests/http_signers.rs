@@ -0,0 +1,122 @@
+//! Turnkey and Privy signers against a local mock of each API.
+#![cfg(all(feature = "turnkey", feature = "privy"))]
+
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::TcpListener;
+use std::thread::JoinHandle;
+
+use a2a_swap_sdk::signer::{privy::PrivySigner, turnkey::TurnkeySigner};
+use base64::{engine::general_purpose::{STANDARD, URL_SAFE_NO_PAD}, Engine as _};
+use serde_json::{json, Value};
+use solana_sdk::{signature::{Keypair, Signature}, signer::Signer};
+
+/// One request as the mock saw it: request line, lower-cased headers, body.
+struct Request {
+    line:    String,
+    headers: Vec<(String, String)>,
+    body:    String,
+}
+
+impl Request {
+    fn header(&self, name: &str) -> &str {
+        self.headers.iter().find(|(n, _)| n == name).map(|(_, v)| v.as_str()).unwrap_or_default()
+    }
+}
+
+/// Answer one connection per reply, in order; join for the requests.
+fn serve(replies: Vec<Value>) -> (String, JoinHandle<Vec<Request>>) {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("http://{}", listener.local_addr().unwrap());
+    let handle = std::thread::spawn(move || {
+        replies.into_iter().map(|reply| {
+            let (stream, _) = listener.accept().unwrap();
+            let mut reader = BufReader::new(stream);
+            let mut line = String::new();
+            reader.read_line(&mut line).unwrap();
+            let mut headers = Vec::new();
+            loop {
+                let mut header = String::new();
+                reader.read_line(&mut header).unwrap();
+                let Some((name, value)) = header.trim_end().split_once(": ") else { break };
+                headers.push((name.to_ascii_lowercase(), value.to_string()));
+            }
+            let len = headers.iter().find(|(n, _)| n == "content-length").map_or(0, |(_, v)| v.parse().unwrap());
+            let mut body = vec![0; len];
+            reader.read_exact(&mut body).unwrap();
+            let reply = reply.to_string();
+            write!(
+                reader.get_mut(),
+                "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{reply}",
+                reply.len()
+            ).unwrap();
+            Request { line: line.trim_end().into(), headers, body: String::from_utf8(body).unwrap() }
+        }).collect()
+    });
+    (url, handle)
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{b:02x}")).collect()
+}
+
+#[test]
+fn turnkey_stamps_requests_and_waits_for_pending_activities() {
+    let wallet = Keypair::new();
+    let message = b"transaction message";
+    let signature = wallet.sign_message(message);
+    let (r, s) = signature.as_ref().split_at(32);
+    let (url, mock) = serve(vec![
+        json!({ "activity": { "id": "act-1", "status": "ACTIVITY_STATUS_PENDING" } }),
+        json!({ "activity": { "id": "act-1", "status": "ACTIVITY_STATUS_COMPLETED",
+            "result": { "signRawPayloadResult": { "r": hex(r), "s": hex(s), "v": "00" } } } }),
+    ]);
+
+    let api_key = Keypair::new();
+    let signer = TurnkeySigner::new("org-1", wallet.pubkey(), *api_key.secret_bytes()).with_base_url(url);
+    assert_eq!(signer.pubkey(), wallet.pubkey());
+    assert_eq!(signer.try_sign_message(message).unwrap(), signature);
+
+    let requests = mock.join().unwrap();
+    assert_eq!(requests[0].line, "POST /public/v1/submit/sign_raw_payload HTTP/1.1");
+    assert_eq!(requests[1].line, "POST /public/v1/query/get_activity HTTP/1.1");
+    let body: Value = serde_json::from_str(&requests[0].body).unwrap();
+    assert_eq!(body["organizationId"], "org-1");
+    assert_eq!(body["parameters"]["signWith"], wallet.pubkey().to_string());
+    assert_eq!(body["parameters"]["payload"], hex(message));
+
+    for request in &requests {
+        let stamp: Value = serde_json::from_slice(&URL_SAFE_NO_PAD.decode(request.header("x-stamp")).unwrap()).unwrap();
+        assert_eq!(stamp["publicKey"], hex(api_key.pubkey().as_ref()));
+        let bytes: Vec<u8> = (0..128).step_by(2)
+            .map(|i| u8::from_str_radix(&stamp["signature"].as_str().unwrap()[i..i + 2], 16).unwrap())
+            .collect();
+        let stamp_sig = Signature::try_from(bytes.as_slice()).unwrap();
+        assert!(stamp_sig.verify(api_key.pubkey().as_ref(), request.body.as_bytes()));
+    }
+}
+
+#[tokio::test]
+async fn privy_signs_from_inside_a_runtime() {
+    let wallet = Keypair::new();
+    let message = b"transaction message";
+    let signature = wallet.sign_message(message);
+    let (url, mock) = serve(vec![
+        json!({ "id": "w-1", "address": wallet.pubkey().to_string(), "chain_type": "solana" }),
+        json!({ "method": "signMessage", "data": { "signature": STANDARD.encode(signature), "encoding": "base64" } }),
+    ]);
+
+    // The client calls `Signer` synchronously from async code.
+    let signer = PrivySigner::connect_to(&url, "app-1", "secret", "w-1").unwrap();
+    assert_eq!(signer.pubkey(), wallet.pubkey());
+    assert_eq!(signer.try_sign_message(message).unwrap(), signature);
+
+    let requests = mock.join().unwrap();
+    assert_eq!(requests[0].line, "GET /v1/wallets/w-1 HTTP/1.1");
+    assert_eq!(requests[1].line, "POST /v1/wallets/w-1/rpc HTTP/1.1");
+    for request in &requests {
+        assert_eq!(request.header("authorization"), format!("Basic {}", STANDARD.encode("app-1:secret")));
+        assert_eq!(request.header("privy-app-id"), "app-1");
+    }
+    let body: Value = serde_json::from_str(&requests[1].body).unwrap();
+    assert_eq!(body["params"]["message"], STANDARD.encode(message));
+}
@@ -0,0 +1,47 @@
+//! The on-chain Anchor IDL account in `a2a_swap_sdk::state::parse_idl_account`.
+//!
+//! No RPC: packs the checked-in IDL the way `anchor idl init` does and reads
+//! it back.
+
+use std::io::Write;
+
+use a2a_swap_sdk::{state::parse_idl_account, Error};
+use flate2::{write::ZlibEncoder, Compression};
+use sha2::{Digest, Sha256};
+use solana_sdk::pubkey::Pubkey;
+
+const IDL: &str = include_str!("../../core/idl/a2a_swap.json");
+
+/// `discriminator authority data_len data`, with room to spare after it as
+/// the program leaves for upgrades.
+fn idl_account(json: &[u8]) -> Vec<u8> {
+    let mut encoder = ZlibEncoder::new(Vec::new(), Compression::default());
+    encoder.write_all(json).unwrap();
+    let compressed = encoder.finish().unwrap();
+
+    let mut data = Sha256::digest(b"account:IdlAccount")[..8].to_vec();
+    data.extend_from_slice(Pubkey::new_unique().as_ref());
+    data.extend_from_slice(&(compressed.len() as u32).to_le_bytes());
+    data.extend_from_slice(&compressed);
+    data.resize(data.len() + 256, 0);
+    data
+}
+
+#[test]
+fn idl_account_round_trips() {
+    let idl = parse_idl_account(&idl_account(IDL.as_bytes())).unwrap();
+    assert_eq!(idl, serde_json::from_str::<serde_json::Value>(IDL).unwrap());
+}
+
+#[test]
+fn idl_account_rejects_other_accounts() {
+    let mut data = idl_account(IDL.as_bytes());
+    data[0] ^= 1;
+    assert!(matches!(parse_idl_account(&data), Err(Error::ParseError { .. })));
+    assert!(matches!(parse_idl_account(&data[..20]), Err(Error::ParseError { .. })));
+
+    // Length past the end of the account.
+    let mut data = idl_account(IDL.as_bytes());
+    data[40..44].copy_from_slice(&u32::MAX.to_le_bytes());
+    assert!(matches!(parse_idl_account(&data), Err(Error::ParseError { .. })));
+}
@@ -0,0 +1,130 @@
+//! Signed swap intents in `a2a_swap_sdk::intent`.
+//!
+//! No RPC: signs an intent, checks the signature survives the relayer's JSON
+//! round trip and breaks on any change, and inspects the instructions a
+//! relayer would send.
+
+use a2a_swap_sdk::{
+    instructions::{derive_ata, derive_intent_nonce, derive_trader_pass},
+    intent::{SignedIntent, SwapIntent},
+    state::{CircuitBreaker, CurveKind, DynamicFee, PoolState},
+};
+use solana_sdk::{
+    ed25519_program,
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+};
+
+fn intent() -> SwapIntent {
+    SwapIntent {
+        pool:                 Pubkey::new_unique(),
+        amount_in:            1_000_000,
+        min_amount_out:       990_000,
+        a_to_b:               true,
+        max_price_impact_bps: 150,
+        nonce:                3,
+        expires_at:           1_700_000_000,
+    }
+}
+
+fn pool() -> PoolState {
+    PoolState {
+        token_a_mint:        Pubkey::new_unique(),
+        token_b_mint:        Pubkey::new_unique(),
+        token_a_vault:       Pubkey::new_unique(),
+        token_b_vault:       Pubkey::new_unique(),
+        lp_supply:           1,
+        fee_rate_bps:        30,
+        fee_growth_global_a: 0,
+        fee_growth_global_b: 0,
+        curve:               CurveKind::ConstantProduct,
+        dynamic_fee:         DynamicFee::default(),
+        volume_a:            0,
+        volume_b:            0,
+        fees_collected_a:    0,
+        fees_collected_b:    0,
+        version:             1,
+        reserve_a:           0,
+        reserve_b:           0,
+        circuit_breaker:     CircuitBreaker::default(),
+        creator:             Pubkey::default(),
+        allowlist_root:      [0; 32],
+        gate_mint:           Pubkey::default(),
+        open_positions:      0,
+        reserves_tracked:    true,
+    }
+}
+
+#[test]
+fn signed_intents_verify_only_as_signed() {
+    let (program, agent) = (Pubkey::new_unique(), Keypair::new());
+    let signed = intent().sign(&program, &agent).unwrap();
+    assert_eq!(signed.agent, agent.pubkey());
+    assert!(signed.verify(&program));
+    assert!(!signed.verify(&Pubkey::new_unique()));
+
+    let mut replayed = signed.clone();
+    replayed.intent.nonce += 1;
+    assert!(!replayed.verify(&program));
+    let mut raised = signed.clone();
+    raised.intent.amount_in += 1;
+    assert!(!raised.verify(&program));
+    let mut impostor = signed;
+    impostor.agent = Keypair::new().pubkey();
+    assert!(!impostor.verify(&program));
+}
+
+#[test]
+fn signed_intents_round_trip_as_base58_json() {
+    let program = Pubkey::new_unique();
+    let signed = intent().sign(&program, &Keypair::new()).unwrap();
+    let json = serde_json::to_value(&signed).unwrap();
+    assert_eq!(json["agent"], signed.agent.to_string());
+    assert_eq!(json["pool"], signed.intent.pool.to_string());
+    assert_eq!(json["signature"], signed.signature.to_string());
+    assert_eq!(json["nonce"], 3);
+
+    let back: SignedIntent = serde_json::from_value(json).unwrap();
+    assert_eq!(back, signed);
+    assert!(back.verify(&program));
+    assert!(serde_json::from_str::<SignedIntent>(r#"{"agent":"not base58"}"#).is_err());
+}
+
+#[test]
+fn relayed_instructions_spend_the_agents_accounts() {
+    let (program, relayer, agent) = (Pubkey::new_unique(), Pubkey::new_unique(), Keypair::new());
+    let pool = pool();
+    let signed = intent().sign(&program, &agent).unwrap();
+    let [verify, swap] = signed.instructions(&program, &relayer, &pool);
+
+    assert_eq!(verify.program_id, ed25519_program::ID);
+    assert!(verify.accounts.is_empty());
+    assert_eq!(swap.program_id, program);
+    assert_eq!(swap.accounts[0].pubkey, relayer);
+    assert!(swap.accounts[0].is_signer);
+    assert_eq!(swap.accounts[1].pubkey, agent.pubkey());
+    assert!(!swap.accounts[1].is_signer);
+    assert_eq!(swap.accounts[2].pubkey, derive_intent_nonce(&agent.pubkey(), &program).0);
+    assert_eq!(swap.accounts[7].pubkey, derive_ata(&agent.pubkey(), &pool.token_a_mint));
+    assert_eq!(swap.accounts[8].pubkey, derive_ata(&agent.pubkey(), &pool.token_b_mint));
+    assert_eq!(swap.accounts[11].pubkey, solana_sdk::sysvar::instructions::id());
+}
+
+#[test]
+fn relayed_intents_on_permissioned_pools_carry_the_agents_pass() {
+    let (program, relayer, agent) = (Pubkey::new_unique(), Pubkey::new_unique(), Keypair::new());
+    let signed = intent().sign(&program, &agent).unwrap();
+    let open = pool();
+    let [_, swap] = signed.instructions(&program, &relayer, &open);
+    let required = swap.accounts.len();
+
+    let permissioned = PoolState { allowlist_root: [1; 32], ..pool() };
+    let [_, swap] = signed.instructions(&program, &relayer, &permissioned);
+    // The agent volume slot before the pass reads as absent.
+    assert_eq!(swap.accounts.len(), required + 2);
+    assert_eq!(swap.accounts[required].pubkey, program);
+    assert_eq!(
+        swap.accounts[required + 1].pubkey,
+        derive_trader_pass(&signed.intent.pool, &agent.pubkey(), &program).0,
+    );
+}
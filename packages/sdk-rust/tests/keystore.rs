@@ -0,0 +1,70 @@
+//! scrypt and encrypted keypair files in `a2a_swap_sdk::keystore`.
+
+use a2a_swap_sdk::{
+    keystore::{keypair_from_json, read_pubkey, scrypt, Keystore, ScryptParams},
+    Error,
+};
+use solana_sdk::{signature::Keypair, signer::Signer};
+
+/// Cheap enough for debug-build tests.
+const FAST: ScryptParams = ScryptParams { log_n: 4, r: 8, p: 1 };
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{b:02x}")).collect()
+}
+
+#[test]
+fn scrypt_matches_rfc_7914() {
+    let mut out = [0u8; 64];
+    scrypt(b"", b"", ScryptParams { log_n: 4, r: 1, p: 1 }, &mut out).unwrap();
+    assert_eq!(hex(&out), "77d6576238657b203b19ca42c18a0497f16b4844e3074ae8dfdffa3fede21442\
+                           fcd0069ded0948f8326a753a0fc81f17e8d3e0fb2e0d3628cf35e20c38d18906");
+
+    scrypt(b"password", b"NaCl", ScryptParams { log_n: 10, r: 8, p: 16 }, &mut out).unwrap();
+    assert_eq!(hex(&out), "fdbabe1c9d3472007856e7190d01e9fe7c6ad7cbc8237830e77376634b373162\
+                           2eaf30d92e22a3886ff109279d9830dac727afb94a83ee6d8360cbdfa2cc0640");
+
+    let huge = ScryptParams { log_n: 24, r: 8, p: 1 };
+    assert!(matches!(scrypt(b"", b"", huge, &mut out), Err(Error::InvalidArgument(_))));
+}
+
+#[test]
+fn keystore_round_trips_and_rejects_tampering() {
+    let keypair = Keypair::new();
+    let sealed = Keystore::encrypt_with(&keypair, "hunter2", FAST).unwrap();
+    assert_eq!(sealed.pubkey().unwrap(), keypair.pubkey());
+    assert!(!sealed.to_json().contains(&keypair.to_base58_string()));
+
+    let json = sealed.to_json();
+    let opened = keypair_from_json(&json, Some("hunter2")).unwrap();
+    assert_eq!(opened.to_bytes(), keypair.to_bytes());
+
+    assert!(matches!(keypair_from_json(&json, Some("hunter3")), Err(Error::InvalidArgument(_))));
+    assert!(matches!(keypair_from_json(&json, None), Err(Error::InvalidArgument(_))));
+
+    // The pubkey is bound to the ciphertext.
+    let mut swapped = Keystore::from_json(&json).unwrap();
+    swapped.pubkey = Keypair::new().pubkey().to_string();
+    assert!(swapped.decrypt("hunter2").is_err());
+
+    // Fresh salt and nonce every time.
+    assert_ne!(Keystore::encrypt_with(&keypair, "hunter2", FAST).unwrap().ciphertext, sealed.ciphertext);
+}
+
+#[test]
+fn plain_id_json_still_loads() {
+    let keypair = Keypair::new();
+    let id_json = serde_json::to_string(&keypair.to_bytes().to_vec()).unwrap();
+    let opened = keypair_from_json(&id_json, None).unwrap();
+    assert_eq!(opened.pubkey(), keypair.pubkey());
+    assert_eq!(keypair_from_json(&id_json, Some("ignored")).unwrap().pubkey(), keypair.pubkey());
+
+    let dir = std::env::temp_dir().join(format!("a2a-keystore-{}", keypair.pubkey()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let (plain, sealed) = (dir.join("id.json"), dir.join("id.keystore.json"));
+    std::fs::write(&plain, &id_json).unwrap();
+    std::fs::write(&sealed, Keystore::encrypt_with(&keypair, "pw", FAST).unwrap().to_json()).unwrap();
+    assert_eq!(read_pubkey(&plain).unwrap(), keypair.pubkey());
+    assert_eq!(read_pubkey(&sealed).unwrap(), keypair.pubkey());
+    std::fs::remove_dir_all(dir).unwrap();
+}
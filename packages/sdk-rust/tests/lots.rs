@@ -0,0 +1,117 @@
+//! Lot matching and gain summaries in `a2a_swap_sdk::analytics::lots`.
+
+use a2a_swap_sdk::{
+    analytics::lots::{LotMethod, LotTracker},
+    export::{FeeKind, FeeRecord, LiquidityKind, LiquidityRecord, TradeRecord},
+    Error,
+};
+use solana_sdk::pubkey::Pubkey;
+
+fn trade(slot: u64, mint_in: Pubkey, amount_in: u64, mint_out: Pubkey, amount_out: u64) -> TradeRecord {
+    TradeRecord {
+        timestamp:            Some(slot as i64 * 100),
+        signature:            format!("trade-{slot}"),
+        slot,
+        pool:                 Pubkey::default(),
+        mint_in,
+        mint_out,
+        a_to_b:               true,
+        amount_in,
+        amount_out,
+        protocol_fee:         0,
+        lp_fee:               0,
+        network_fee_lamports: 0,
+        price_b_per_a:        0.0,
+        cost_basis_b:         None,
+        realized_pnl_b:       None,
+    }
+}
+
+#[test]
+fn fifo_and_lifo_match_different_lots() {
+    let (x, usdc) = (Pubkey::new_unique(), Pubkey::new_unique());
+    let trades = [
+        trade(1, usdc, 1_000, x, 100), // 10 per X
+        trade(2, usdc, 3_000, x, 100), // 30 per X
+        trade(3, x, 150, usdc, 3_000), // sell 150 at 20
+    ];
+
+    let mut fifo = LotTracker::new(LotMethod::Fifo, usdc);
+    fifo.replay(&trades, &[], &[]);
+    let got: Vec<_> = fifo.disposals.iter().map(|d| (d.quantity, d.proceeds, d.cost_basis, d.held_secs)).collect();
+    assert_eq!(got, [(100, 2_000, Some(1_000), Some(200)), (50, 1_000, Some(1_500), Some(100))]);
+    let s = &fifo.summary()[0];
+    assert_eq!((s.asset, s.realized_gain, s.open_quantity, s.open_cost), (x, 500, 50, 1_500));
+
+    let mut lifo = LotTracker::new(LotMethod::Lifo, usdc);
+    lifo.replay(&trades, &[], &[]);
+    let s = &lifo.summary()[0];
+    assert_eq!((s.disposed, s.proceeds, s.cost_basis), (150, 3_000, 3_500));
+    assert_eq!((s.realized_gain, s.open_quantity, s.open_cost), (-500, 50, 500));
+}
+
+#[test]
+fn lp_entries_exits_and_compounds_move_lots() {
+    let (x, usdc, pool) = (Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique());
+    let lp = |slot, kind, lp_shares, amount_a, amount_b| LiquidityRecord {
+        timestamp: None,
+        signature: format!("lp-{slot}"),
+        slot,
+        pool,
+        mint_a: x,
+        mint_b: usdc,
+        kind,
+        lp_shares,
+        amount_a,
+        amount_b,
+    };
+    let liquidity = [
+        // X held from before the history: its disposal has no lot.
+        lp(1, LiquidityKind::Provided, 50, 100, 1_000),
+        lp(3, LiquidityKind::Removed, 25, 60, 900),
+    ];
+    let fees = [FeeRecord {
+        timestamp: None,
+        signature: "fee".into(),
+        slot:      4,
+        pool,
+        mint_a:    x,
+        mint_b:    usdc,
+        kind:      FeeKind::Compounded,
+        amount_a:  10,
+        amount_b:  0,
+        lp_shares: Some(5),
+    }];
+    let mut lots = LotTracker::new(LotMethod::Fifo, usdc);
+    lots.replay(&[], &liquidity, &fees);
+    assert!(lots.skipped.is_empty());
+
+    let summary = lots.summary();
+    let of = |asset| summary.iter().find(|s| s.asset == asset).unwrap();
+    let shares = of(pool);
+    assert!(shares.lp_shares);
+    // 25 of 50 shares costing 2_000 sold for 1_800; 5 compounded at 15 per X.
+    assert_eq!((shares.disposed, shares.cost_basis, shares.realized_gain), (25, 1_000, 800));
+    assert_eq!((shares.income, shares.open_quantity, shares.open_cost), (150, 30, 1_150));
+    let x = of(x);
+    assert_eq!((x.unmatched_quantity, x.unmatched_proceeds, x.disposed), (100, 1_000, 0));
+    assert_eq!((x.open_quantity, x.open_cost), (60, 900));
+    assert_eq!(summary.len(), 2);
+}
+
+#[test]
+fn unvalued_events_are_skipped() {
+    let (x, y, usdc) = (Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique());
+    let mut lots = LotTracker::new(LotMethod::Fifo, usdc);
+    lots.record_trade(&trade(1, x, 10, y, 20));
+    assert_eq!(lots.skipped, ["trade-1"]);
+
+    // Once Y has a price, an X → Y swap is valued through it.
+    lots.record_trade(&trade(2, usdc, 40, y, 20));
+    lots.record_trade(&trade(3, x, 10, y, 10));
+    let y_lots: Vec<_> = lots.open_lots().filter(|l| l.asset == y).map(|l| l.cost).collect();
+    assert_eq!(y_lots, [40, 20]);
+
+    assert_eq!("LIFO".parse::<LotMethod>().unwrap(), LotMethod::Lifo);
+    assert!(matches!("hifo".parse::<LotMethod>(), Err(Error::InvalidArgument(_))));
+}
@@ -0,0 +1,622 @@
+//! Property tests for `a2a_swap_sdk::math`.
+//!
+//! Pure arithmetic only — no RPC, no validator. Also checks the wasm-friendly
+//! PDA derivation in `a2a_swap_core` against solana_sdk, and the
+//! `a2a-swap-wasm` instruction builders against the SDK's.
+
+use a2a_swap_sdk::{
+    math::{
+        amount_in_for_exact_out, check_swap_funds, effective_fee_bps, impermanent_loss, lp_underlying,
+        min_amount_out_for_slippage, pending_fees_for_position, resolve_min_amount_out,
+        simulate_detailed, simulate_provide_detailed, simulate_remove_detailed, split_tranches, spot_price, spot_value, stable_invariant,
+        tranche_min_amount_out, PROTOCOL_FEE_BPS,
+    },
+    range_math::{
+        amounts_for_liquidity, liquidity_for_amounts, price_at_tick, sqrt_price_at_tick,
+        tick_for_price,
+    },
+    state::{CircuitBreaker, CurveKind, DynamicFee, PoolState, PositionState},
+    Error, ProtectionLevel,
+};
+use proptest::prelude::*;
+use solana_sdk::pubkey::Pubkey;
+
+fn pool(fee_rate_bps: u16) -> PoolState {
+    PoolState {
+        token_a_mint:        Pubkey::new_unique(),
+        token_b_mint:        Pubkey::new_unique(),
+        token_a_vault:       Pubkey::new_unique(),
+        token_b_vault:       Pubkey::new_unique(),
+        lp_supply:           1,
+        fee_rate_bps,
+        fee_growth_global_a: 0,
+        fee_growth_global_b: 0,
+        curve:               CurveKind::ConstantProduct,
+        dynamic_fee:         DynamicFee::default(),
+        volume_a:            0,
+        volume_b:            0,
+        fees_collected_a:    0,
+        fees_collected_b:    0,
+        version:             1,
+        reserve_a:           0,
+        reserve_b:           0,
+        circuit_breaker:     CircuitBreaker::default(),
+        creator:             Pubkey::default(),
+        allowlist_root:      [0; 32],
+        gate_mint:           Pubkey::default(),
+        open_positions:      0,
+        reserves_tracked:    true,
+    }
+}
+
+/// Default protocol fee, as charged before `ProtocolConfig` is initialized.
+const PROTOCOL_FEE: u64 = PROTOCOL_FEE_BPS as u64;
+
+fn out_for(pool: &PoolState, protocol_fee: u64, reserve_in: u64, reserve_out: u64, amount_in: u64) -> u64 {
+    simulate_detailed(Pubkey::default(), pool, reserve_in, reserve_out, amount_in, protocol_fee, true)
+        .unwrap()
+        .estimated_out
+}
+
+// ─── simulate_detailed ───────────────────────────────────────────────────────
+
+proptest! {
+    /// Fees and curve input partition `amount_in` exactly, and k never drops.
+    #[test]
+    fn simulate_conserves_input_and_k(
+        reserve_in   in 1u64..=u64::MAX >> 1,
+        reserve_out  in 1u64..=u64::MAX >> 1,
+        amount_in    in 0u64..=u64::MAX >> 1,
+        fee_rate_bps in 1u16..=100,
+        protocol_fee in 0u64..=1_000,
+    ) {
+        let sim = simulate_detailed(
+            Pubkey::default(), &pool(fee_rate_bps), reserve_in, reserve_out, amount_in,
+            protocol_fee, true,
+        ).unwrap();
+        prop_assert_eq!(sim.protocol_fee + sim.lp_fee + sim.after_fees, amount_in);
+        prop_assert_eq!(sim.net_pool_input, amount_in - sim.protocol_fee);
+        prop_assert!(sim.estimated_out < reserve_out);
+
+        let k_before = reserve_in as u128 * reserve_out as u128;
+        let k_after = (reserve_in as u128 + sim.net_pool_input as u128)
+            * (reserve_out - sim.estimated_out) as u128;
+        prop_assert!(k_after >= k_before);
+    }
+}
+
+// ─── StableSwap ──────────────────────────────────────────────────────────────
+
+fn stable_pool(amp: u64) -> PoolState {
+    PoolState { curve: CurveKind::StableSwap { amp }, ..pool(30) }
+}
+
+proptest! {
+    /// At balanced reserves a stable pool pays at least what constant-product
+    /// does, and never more than the input after fees.
+    #[test]
+    fn stable_beats_constant_product_when_balanced(
+        reserve   in 1_000_000u64..=1u64 << 50,
+        amount_in in 1u64..=1u64 << 40,
+        amp       in 1u64..=10_000,
+    ) {
+        let amount_in = amount_in.min(reserve / 2);
+        let stable = simulate_detailed(
+            Pubkey::default(), &stable_pool(amp), reserve, reserve, amount_in, PROTOCOL_FEE, true,
+        ).unwrap();
+        let cp = out_for(&pool(30), PROTOCOL_FEE, reserve, reserve, amount_in);
+        prop_assert!(stable.estimated_out + 1 >= cp);
+        prop_assert!(stable.estimated_out <= stable.after_fees);
+    }
+
+    /// The spot price of a balanced stable pool is 1, and it moves with the
+    /// imbalance in the same direction as constant-product.
+    #[test]
+    fn stable_spot_price_tracks_imbalance(
+        reserve in 1_000u64..=1u64 << 50,
+        amp     in 1u64..=10_000,
+    ) {
+        let curve = CurveKind::StableSwap { amp };
+        prop_assert!((spot_price(curve, reserve, reserve) - 1.0).abs() < 1e-9);
+        prop_assert!(spot_price(curve, reserve, reserve * 2) > 1.0);
+        prop_assert!(spot_price(curve, reserve * 2, reserve) < 1.0);
+    }
+}
+
+#[test]
+fn stable_swap_near_parity_at_high_amp() {
+    let reserve = 1_000_000_000_000; // 1M tokens at 6 decimals
+    let sim = simulate_detailed(
+        Pubkey::default(), &stable_pool(100), reserve, reserve, 1_000_000_000, PROTOCOL_FEE, true,
+    )
+    .unwrap();
+    // 1000 in: everything after fees comes out, less under one basis point.
+    assert!(sim.estimated_out <= sim.after_fees);
+    assert!(
+        sim.estimated_out as u128 * 10_000 >= sim.after_fees as u128 * 9_999,
+        "{} of {}", sim.estimated_out, sim.after_fees,
+    );
+    assert!(sim.price_impact_pct < 0.01);
+}
+
+#[test]
+fn stable_invariant_of_balanced_pool_is_the_sum() {
+    for amp in [1, 100, 10_000] {
+        assert_eq!(stable_invariant(500, 500, amp).unwrap(), 1_000);
+        assert_eq!(stable_invariant(0, 0, amp).unwrap(), 0);
+    }
+}
+
+// ─── Dynamic fees ────────────────────────────────────────────────────────────
+
+#[test]
+fn effective_fee_adds_decaying_volatility_premium() {
+    let mut p = pool(30);
+    assert_eq!(effective_fee_bps(&p, 1_000), 30);
+
+    p.dynamic_fee = DynamicFee {
+        min_fee_bps:    10,
+        max_fee_bps:    60,
+        volatility_bps: 200,
+        last_update_ts: 1_000,
+    };
+    assert_eq!(effective_fee_bps(&p, 1_000), 50);  // 30 + 200 / 10
+    assert_eq!(effective_fee_bps(&p, 1_299), 50);  // still inside the first half-life
+    assert_eq!(effective_fee_bps(&p, 1_300), 40);  // 30 + 100 / 10
+    assert_eq!(effective_fee_bps(&p, 100_000), 30);
+
+    p.dynamic_fee.volatility_bps = 10_000;
+    assert_eq!(effective_fee_bps(&p, 1_000), 60);  // capped at max
+    p.fee_rate_bps = 5;
+    assert_eq!(effective_fee_bps(&p, 100_000), 10); // floored at min
+}
+
+// ─── pending_fees_for_position ───────────────────────────────────────────────
+
+proptest! {
+    /// A Q64.64 growth of `fee / supply` pays the full supply at most `fee`,
+    /// and at most one token less.
+    #[test]
+    fn pending_fees_rounding_is_bounded(
+        lp_fee    in 0u64..=u64::MAX,
+        lp_supply in 1u64..=u64::MAX,
+        split     in 0u64..=u64::MAX,
+    ) {
+        // Same divide-first construction as the program's fee_growth_delta.
+        let (q, r) = (lp_fee as u128 / lp_supply as u128, lp_fee as u128 % lp_supply as u128);
+        let delta = (q << 64) + (r << 64) / lp_supply as u128;
+        let mut pool = pool(30);
+        pool.lp_supply = lp_supply;
+        pool.fee_growth_global_a = delta;
+
+        let pending = |lp_shares: u64| {
+            let pos = PositionState {
+                owner:                   Pubkey::default(),
+                pool:                    Pubkey::default(),
+                lp_shares,
+                fee_growth_checkpoint_a: 0,
+                fee_growth_checkpoint_b: 0,
+                fees_owed_a:             0,
+                fees_owed_b:             0,
+                auto_compound:           false,
+                compound_threshold:      0,
+                version:                 1,
+                locked_by:               None,
+            };
+            pending_fees_for_position(&pos, &pool).0
+        };
+
+        let whole = pending(lp_supply);
+        prop_assert!(whole <= lp_fee);
+        prop_assert!(lp_fee - whole <= 1);
+
+        let a = (split as u128 % (lp_supply as u128 + 1)) as u64;
+        prop_assert!(pending(a) as u128 + pending(lp_supply - a) as u128 <= whole as u128);
+    }
+}
+
+// ─── amount_in_for_exact_out ──────────────────────────────────────────────────
+
+proptest! {
+    /// The reverse quote always buys at least `amount_out`, and is minimal.
+    #[test]
+    fn reverse_quote_is_sufficient_and_minimal(
+        reserve_in   in 1u64..=u64::MAX,
+        reserve_out  in 2u64..=u64::MAX,
+        fee_rate_bps in 1u16..=100,
+        frac         in 1u64..=1_000_000,
+        protocol_fee in 0u64..=1_000,
+    ) {
+        let amount_out = ((reserve_out as u128 - 1) * frac as u128 / 1_000_000) as u64;
+        let pool = pool(fee_rate_bps);
+        match amount_in_for_exact_out(reserve_in, reserve_out, fee_rate_bps, protocol_fee, amount_out) {
+            Ok(amount_in) => {
+                prop_assert!(out_for(&pool, protocol_fee, reserve_in, reserve_out, amount_in) >= amount_out);
+                if amount_in > 0 {
+                    prop_assert!(
+                        out_for(&pool, protocol_fee, reserve_in, reserve_out, amount_in - 1) < amount_out
+                    );
+                }
+            }
+            Err(Error::MathOverflow) => {
+                // Required input exceeds u64 — even u64::MAX must fall short
+                // (or the forward math itself overflows u128).
+                let max = simulate_detailed(
+                    Pubkey::default(), &pool, reserve_in, reserve_out, u64::MAX, protocol_fee, true,
+                );
+                if let Ok(sim) = max {
+                    prop_assert!(sim.estimated_out < amount_out);
+                }
+            }
+            Err(e) => prop_assert!(false, "unexpected error: {e}"),
+        }
+    }
+
+    /// Realistic pool sizes never overflow and round-trip through simulate.
+    #[test]
+    fn reverse_quote_round_trips_for_realistic_pools(
+        reserve_in   in 1_000u64..=1_000_000_000_000_000,
+        reserve_out  in 1_000u64..=1_000_000_000_000_000,
+        fee_rate_bps in 1u16..=100,
+        amount_in    in 1u64..=1_000_000_000_000,
+    ) {
+        let pool = pool(fee_rate_bps);
+        let out = out_for(&pool, PROTOCOL_FEE, reserve_in, reserve_out, amount_in);
+        let back = amount_in_for_exact_out(reserve_in, reserve_out, fee_rate_bps, PROTOCOL_FEE, out)
+            .unwrap();
+        prop_assert!(back <= amount_in);
+        prop_assert!(out_for(&pool, PROTOCOL_FEE, reserve_in, reserve_out, back) >= out);
+    }
+}
+
+#[test]
+fn reverse_quote_rejects_unreachable_outputs() {
+    assert!(matches!(
+        amount_in_for_exact_out(1_000, 1_000, 30, PROTOCOL_FEE, 1_000),
+        Err(Error::InvalidArgument(_))
+    ));
+    assert!(matches!(
+        amount_in_for_exact_out(1_000, 1_000, 30, 100_000, 1),
+        Err(Error::InvalidArgument(_))
+    ));
+    assert!(matches!(
+        amount_in_for_exact_out(0, 1_000, 30, PROTOCOL_FEE, 1),
+        Err(Error::NoLiquidity)
+    ));
+    assert_eq!(amount_in_for_exact_out(1_000, 1_000, 30, PROTOCOL_FEE, 0).unwrap(), 0);
+}
+
+// ─── Valuation ───────────────────────────────────────────────────────────────
+
+proptest! {
+    /// Shares never withdraw more than the reserves, and all shares withdraw everything.
+    #[test]
+    fn lp_underlying_is_bounded_by_reserves(
+        lp_supply in 1u64..=u64::MAX,
+        shares    in 0u64..=u64::MAX,
+        reserve_a in 0u64..=u64::MAX,
+        reserve_b in 0u64..=u64::MAX,
+    ) {
+        let shares = shares.min(lp_supply);
+        let (a, b) = lp_underlying(shares, lp_supply, reserve_a, reserve_b);
+        prop_assert!(a <= reserve_a && b <= reserve_b);
+        prop_assert_eq!(lp_underlying(lp_supply, lp_supply, reserve_a, reserve_b), (reserve_a, reserve_b));
+    }
+}
+
+#[test]
+fn spot_value_uses_reserve_ratio() {
+    // 1 SOL (1e9) at 150 USDC (150e6) per SOL.
+    assert_eq!(spot_value(1_000_000_000, 1_000_000_000_000, 150_000_000_000), Some(150_000_000));
+    assert_eq!(spot_value(1, 0, 1_000), None);
+    assert_eq!(spot_value(u64::MAX, 1, u64::MAX), Some(u64::MAX));
+    assert_eq!(lp_underlying(10, 0, 1_000, 1_000), (0, 0));
+}
+
+#[test]
+fn liquidity_simulations_round_trip() {
+    let mut state = pool(30);
+    state.lp_supply = 0;
+    let first = simulate_provide_detailed(Pubkey::default(), &state, 0, 0, 400, 100).unwrap();
+    assert_eq!((first.lp_minted, first.lp_supply_after), (200, 200));
+    assert_eq!((first.pool_share_pct, first.price_before, first.price_after), (100.0, 0.0, 0.25));
+
+    state.lp_supply = 200;
+    let second = simulate_provide_detailed(Pubkey::default(), &state, 400, 100, 200, 60).unwrap();
+    assert_eq!((second.lp_minted, second.lp_supply_after), (100, 300));
+    assert!((second.pool_share_pct - 100.0 / 3.0).abs() < 1e-9);
+
+    let out = simulate_remove_detailed(Pubkey::default(), &state, 400, 100, 50).unwrap();
+    assert_eq!((out.amount_a, out.amount_b, out.lp_supply_after), (100, 25, 150));
+    assert_eq!((out.pool_share_pct, out.price_after), (25.0, 0.25));
+    let all = simulate_remove_detailed(Pubkey::default(), &state, 400, 100, 200).unwrap();
+    assert_eq!((all.amount_a, all.amount_b, all.price_after), (400, 100, 0.0));
+    assert!(matches!(
+        simulate_remove_detailed(Pubkey::default(), &state, 400, 100, 201),
+        Err(Error::InvalidArgument(_)),
+    ));
+}
+
+// ─── Impermanent loss ────────────────────────────────────────────────────────
+
+proptest! {
+    /// IL is never a gain, and a move of ×k costs the same as ×1/k.
+    #[test]
+    fn impermanent_loss_is_non_positive_and_symmetric(
+        entry in 1e-9f64..1e9,
+        k     in 1e-3f64..1e3,
+    ) {
+        let il = impermanent_loss(entry, entry * k);
+        prop_assert!(il <= 1e-12 && il > -1.0);
+        prop_assert!((il - impermanent_loss(entry, entry / k)).abs() < 1e-9);
+    }
+}
+
+#[test]
+fn impermanent_loss_known_values() {
+    assert_eq!(impermanent_loss(150.0, 150.0), 0.0);
+    assert!((impermanent_loss(1.0, 2.0) + 0.057_191).abs() < 1e-6);
+    assert!((impermanent_loss(1.0, 4.0) + 0.2).abs() < 1e-12);
+    assert_eq!(impermanent_loss(0.0, 2.0), 0.0);
+}
+
+// ─── Slippage ────────────────────────────────────────────────────────────────
+
+proptest! {
+    /// The derived minimum never allows more than the stated slippage and
+    /// never exceeds the estimate.
+    #[test]
+    fn slippage_min_is_tight_and_bounded(
+        estimated_out    in any::<u64>(),
+        max_slippage_bps in 1u16..=10_000,
+    ) {
+        let min = min_amount_out_for_slippage(estimated_out, max_slippage_bps).unwrap();
+        prop_assert!(min <= estimated_out);
+        let slack = (estimated_out - min) as u128;
+        prop_assert!(slack * 10_000 <= estimated_out as u128 * max_slippage_bps as u128);
+        prop_assert!((slack + 1) * 10_000 > estimated_out as u128 * max_slippage_bps as u128);
+    }
+
+    /// Tranche minimums round up, so together they cover the whole swap's.
+    #[test]
+    fn tranche_mins_cover_the_whole_min(
+        amount_in in 1u64..=u64::MAX >> 1,
+        min       in any::<u64>(),
+        tranches  in 1u64..=8,
+        seed      in any::<u64>(),
+    ) {
+        let parts = split_tranches(amount_in, tranches, seed);
+        let total: u128 = parts.iter().map(|&p| tranche_min_amount_out(min, p, amount_in) as u128).sum();
+        prop_assert!(total >= min as u128);
+        prop_assert!(total < min as u128 + parts.len() as u128);
+    }
+}
+
+#[test]
+fn slippage_min_rounding_edges() {
+    // 0 bps disables the guard; 100% accepts anything.
+    assert_eq!(min_amount_out_for_slippage(1_000, 0).unwrap(), 0);
+    assert_eq!(min_amount_out_for_slippage(1_000, 10_000).unwrap(), 0);
+    // 0.5% of 199 is 0.995: nothing may be given up, the minimum stays 199.
+    assert_eq!(min_amount_out_for_slippage(199, 50).unwrap(), 199);
+    assert_eq!(min_amount_out_for_slippage(200, 50).unwrap(), 199);
+    assert_eq!(min_amount_out_for_slippage(1, 9_999).unwrap(), 1);
+    assert_eq!(min_amount_out_for_slippage(0, 50).unwrap(), 0);
+    // No overflow at the top of the range.
+    assert_eq!(min_amount_out_for_slippage(u64::MAX, 1).unwrap(), u64::MAX - u64::MAX / 10_000);
+    assert!(matches!(min_amount_out_for_slippage(1_000, 10_001), Err(Error::InvalidArgument(_))));
+}
+
+#[test]
+fn explicit_min_amount_out_is_validated() {
+    assert_eq!(resolve_min_amount_out(1_000, 50, None).unwrap(), 995);
+    assert_eq!(resolve_min_amount_out(1_000, 0, Some(990)).unwrap(), 990);
+    assert_eq!(resolve_min_amount_out(1_000, 0, Some(1_000)).unwrap(), 1_000);
+    assert_eq!(resolve_min_amount_out(1_000, 0, Some(0)).unwrap(), 0);
+    assert!(matches!(
+        resolve_min_amount_out(1_000, 0, Some(1_001)),
+        Err(Error::SlippageExceeded { estimated: 1_000, min: 1_001 })
+    ));
+    assert!(matches!(resolve_min_amount_out(1_000, 50, Some(990)), Err(Error::InvalidArgument(_))));
+    assert!(matches!(resolve_min_amount_out(1_000, 20_000, None), Err(Error::InvalidArgument(_))));
+}
+
+// ─── Balance pre-flight ──────────────────────────────────────────────────────
+
+#[test]
+fn swap_funds_name_the_account_that_falls_short() {
+    let wallet = Pubkey::new_unique();
+    let ata = Pubkey::new_unique();
+    assert!(check_swap_funds(&wallet, 5_000, 5_000, Some((&ata, Some(100))), 100).is_ok());
+    // SOL input: everything is drawn from the wallet.
+    assert!(check_swap_funds(&wallet, 1_005_000, 1_005_000, None, 1_000_000).is_ok());
+
+    let short = |r| match r {
+        Err(Error::InsufficientBalance { account, needed, available }) => Some((account, needed, available)),
+        _ => None,
+    };
+    assert_eq!(short(check_swap_funds(&wallet, 5_000, 5_000, Some((&ata, None)), 100)), Some((ata, 100, 0)));
+    assert_eq!(short(check_swap_funds(&wallet, 5_000, 5_000, Some((&ata, Some(99))), 100)), Some((ata, 100, 99)));
+    assert_eq!(short(check_swap_funds(&wallet, 4_999, 5_000, Some((&ata, Some(100))), 100)), Some((wallet, 5_000, 4_999)));
+    assert_eq!(short(check_swap_funds(&wallet, 1_000_000, 1_005_000, None, 1_000_000)), Some((wallet, 1_005_000, 1_000_000)));
+}
+
+// ─── Swap protection ─────────────────────────────────────────────────────────
+
+proptest! {
+    /// Tranches are non-zero, sum to the input and stay near an even share.
+    #[test]
+    fn split_tranches_partitions_input(
+        amount_in in 1u64..=u64::MAX >> 1,
+        tranches  in 1u64..=8,
+        seed      in any::<u64>(),
+    ) {
+        let parts = split_tranches(amount_in, tranches, seed);
+        prop_assert!(!parts.is_empty() && parts.len() as u64 <= tranches);
+        prop_assert!(parts.iter().all(|&p| p > 0));
+        prop_assert_eq!(parts.iter().sum::<u64>(), amount_in);
+        if amount_in >= 1_000_000 {
+            let even = amount_in / tranches;
+            prop_assert!(parts.iter().all(|&p| p < even * 2), "{parts:?}");
+        }
+    }
+}
+
+#[test]
+fn protection_levels_scale_tranches_with_impact() {
+    assert_eq!(ProtectionLevel::None.tranches_for(5_000), 1);
+    assert_eq!(ProtectionLevel::Standard.tranches_for(20), 1);
+    assert_eq!(ProtectionLevel::Standard.tranches_for(61), 3);
+    assert_eq!(ProtectionLevel::Strict.tranches_for(5_000), 8);
+    assert_eq!(ProtectionLevel::Strict.tranche_slippage_bps(100), 25);
+    assert_eq!(ProtectionLevel::Strict.tranche_slippage_bps(2), 1);
+    assert_eq!(ProtectionLevel::Standard.tranche_slippage_bps(0), 0);
+    assert_eq!(ProtectionLevel::CommitReveal.tranches_for(5_000), 1);
+    assert_eq!(split_tranches(1_000, 4, 7), split_tranches(1_000, 4, 7));
+}
+
+// ─── Range pools ──────────────────────────────────────────────────────────────
+
+proptest! {
+    /// Liquidity quoted from a deposit never needs more than was offered.
+    #[test]
+    fn range_liquidity_fits_within_deposit(
+        tick     in -10_000i32..10_000,
+        lower    in -10_000i32..10_000,
+        width    in 1i32..10_000,
+        amount_a in 1_000u64..1_000_000_000_000,
+        amount_b in 1_000u64..1_000_000_000_000,
+    ) {
+        let price = sqrt_price_at_tick(tick).unwrap();
+        let lo = sqrt_price_at_tick(lower).unwrap();
+        let hi = sqrt_price_at_tick(lower + width).unwrap();
+        let liquidity = liquidity_for_amounts(price, lo, hi, amount_a, amount_b).unwrap();
+        let (a, b) = amounts_for_liquidity(price, lo, hi, liquidity, true).unwrap();
+        prop_assert!(a <= amount_a, "a {a} > {amount_a}");
+        prop_assert!(b <= amount_b, "b {b} > {amount_b}");
+    }
+
+    /// `tick_for_price` lands on the spacing grid at or just below the price.
+    #[test]
+    fn tick_for_price_rounds_down_to_spacing(
+        tick    in -100_000i32..100_000,
+        spacing in 1u16..=1_000,
+    ) {
+        let price = price_at_tick(tick) * 1.000_05;
+        let t = tick_for_price(price, spacing).unwrap();
+        prop_assert_eq!(t % spacing as i32, 0);
+        prop_assert!(t <= tick && tick - t < spacing as i32);
+    }
+}
+
+#[test]
+fn tick_for_price_rejects_bad_input() {
+    assert!(matches!(tick_for_price(0.0, 1), Err(Error::InvalidArgument(_))));
+    assert!(matches!(tick_for_price(f64::NAN, 1), Err(Error::InvalidArgument(_))));
+    assert!(matches!(tick_for_price(1.0, 0), Err(Error::InvalidArgument(_))));
+}
+
+// ─── PDA derivation ──────────────────────────────────────────────────────────
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(64))]
+
+    /// The wasm-friendly derivation in a2a-swap-core finds the same address
+    /// and bump as solana_sdk.
+    #[test]
+    fn core_pda_matches_solana_sdk(
+        seeds   in prop::collection::vec(prop::collection::vec(any::<u8>(), 0..=32), 0..4),
+        program in any::<[u8; 32]>(),
+    ) {
+        let seeds: Vec<&[u8]> = seeds.iter().map(Vec::as_slice).collect();
+        let (key, bump) = a2a_swap_core::pda::find_program_address(&seeds, &program);
+        let expected = Pubkey::find_program_address(&seeds, &Pubkey::new_from_array(program));
+        prop_assert_eq!((Pubkey::new_from_array(key), bump), expected);
+    }
+
+    /// `a2a_swap_core::pubkey` decodes what solana_sdk encodes.
+    #[test]
+    fn core_pubkey_decodes_base58(bytes in any::<[u8; 32]>()) {
+        let b58 = Pubkey::new_from_array(bytes).to_string();
+        prop_assert_eq!(a2a_swap_core::pubkey(&b58), bytes);
+    }
+}
+
+#[test]
+fn core_program_ids_match() {
+    use a2a_swap_core::pda;
+    use std::str::FromStr;
+    let id = |s: &str| Pubkey::from_str(s).unwrap().to_bytes();
+    assert_eq!(a2a_swap_core::PROGRAM_ADDRESS, id(a2a_swap_core::PROGRAM_ID));
+    assert_eq!(pda::TOKEN_PROGRAM_ID, id("TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA"));
+    assert_eq!(pda::BPF_LOADER_UPGRADEABLE_ID, id("BPFLoaderUpgradeab1e11111111111111111111111"));
+    assert_eq!(a2a_swap_core::pubkey("11111111111111111111111111111111"), [0; 32]);
+}
+
+// ─── wasm instruction builders ───────────────────────────────────────────────
+
+fn assert_same_ix(
+    wasm: a2a_swap_wasm::instructions::TransactionInstruction,
+    sdk:  solana_sdk::instruction::Instruction,
+) {
+    assert_eq!(wasm.program_id, sdk.program_id.to_string());
+    assert_eq!(wasm.data, sdk.data);
+    let keys: Vec<_> = sdk.accounts.iter().map(|a| (a.pubkey.to_string(), a.is_signer, a.is_writable)).collect();
+    let wasm_keys: Vec<_> = wasm.keys.into_iter().map(|a| (a.pubkey, a.is_signer, a.is_writable)).collect();
+    assert_eq!(wasm_keys, keys);
+}
+
+#[test]
+fn wasm_builders_match_sdk() {
+    use a2a_swap_sdk::instructions::*;
+    use a2a_swap_wasm::instructions as wasm;
+
+    let program = Pubkey::new_from_array(a2a_swap_core::PROGRAM_ADDRESS);
+    let [agent, referrer, mint_a, mint_b, vault_a, vault_b] = [1u8, 2, 3, 4, 5, 6].map(|b| Pubkey::new_from_array([b; 32]));
+    let (pool, _) = derive_pool(&mint_a, &mint_b, &program);
+    let (authority, _) = derive_pool_authority(&pool, &program);
+    let (position, _) = derive_position(&pool, &agent, &program);
+    let (treasury, _) = derive_treasury(&program);
+    let state = a2a_swap_core::Pool {
+        token_a_mint:  mint_a.to_bytes(),
+        token_b_mint:  mint_b.to_bytes(),
+        token_a_vault: vault_a.to_bytes(),
+        token_b_vault: vault_b.to_bytes(),
+        ..Default::default()
+    };
+    let b = |k: &Pubkey| k.to_bytes();
+
+    // B→A with a referrer.
+    assert_same_ix(
+        wasm::swap(&b(&program), &b(&agent), &b(&pool), &state, &b(&mint_b), 1_000, 900, 50, 1_700_000_000, Some(&b(&referrer))).unwrap(),
+        swap_ix(
+            &program, &agent, &pool, &authority, &vault_a, &vault_b,
+            &derive_ata(&agent, &mint_b), &derive_ata(&agent, &mint_a),
+            &treasury, &derive_ata(&treasury, &mint_b),
+            Some(&derive_ata(&referrer, &mint_b)),
+            1_000, 900, false, 50, 1_700_000_000,
+        ),
+    );
+    assert!(wasm::swap(&b(&program), &b(&agent), &b(&pool), &state, &b(&referrer), 1, 0, 0, 0, None).is_err());
+
+    assert_same_ix(
+        wasm::provide_liquidity(&b(&program), &b(&agent), &b(&pool), &state, 10, 20, 5, true, 7),
+        provide_liquidity_ix(
+            &program, &agent, &pool, &authority, &position, &vault_a, &vault_b,
+            &derive_ata(&agent, &mint_a), &derive_ata(&agent, &mint_b),
+            10, 20, 5, true, 7,
+        ),
+    );
+    assert_same_ix(
+        wasm::provide_liquidity_proportional(&b(&program), &b(&agent), &b(&pool), &state, 10, false, 20, 5, true, 7),
+        provide_liquidity_proportional_ix(
+            &program, &agent, &pool, &authority, &position, &vault_a, &vault_b,
+            &derive_ata(&agent, &mint_a), &derive_ata(&agent, &mint_b),
+            10, false, 20, 5, true, 7,
+        ),
+    );
+    assert_same_ix(
+        wasm::migrate_position(&b(&program), &b(&agent), &b(&position)),
+        migrate_position_ix(&program, &agent, &position),
+    );
+}
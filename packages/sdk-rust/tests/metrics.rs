@@ -0,0 +1,22 @@
+//! Metric names are scraped by dashboards — keep them stable.
+
+use a2a_swap_sdk::metrics::{
+    slippage_bps, CACHE_LOOKUPS, CONFIRMATION_SECONDS, SIMULATE_SECONDS, SLIPPAGE_REALIZED_BPS,
+    SWAPS_SUBMITTED,
+};
+
+#[test]
+fn metric_names_are_prometheus_safe() {
+    for name in [SWAPS_SUBMITTED, CONFIRMATION_SECONDS, SIMULATE_SECONDS, SLIPPAGE_REALIZED_BPS, CACHE_LOOKUPS] {
+        assert!(name.starts_with("a2a_swap_"), "{name}");
+        assert!(name.chars().all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_'), "{name}");
+    }
+}
+
+#[test]
+fn slippage_sign_and_scale() {
+    assert_eq!(slippage_bps(10_000, 9_950), 50.0);
+    assert_eq!(slippage_bps(10_000, 10_000), 0.0);
+    assert_eq!(slippage_bps(10_000, 10_100), -100.0);
+    assert_eq!(slippage_bps(0, 5), 0.0);
+}
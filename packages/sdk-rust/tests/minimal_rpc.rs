@@ -0,0 +1,248 @@
+//! `ReadOnlyClient` against a local mock JSON-RPC node.
+#![cfg(feature = "minimal-rpc")]
+
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::TcpListener;
+use std::thread::JoinHandle;
+
+use a2a_swap_core::Account as _;
+use a2a_swap_sdk::{
+    instructions::{derive_pool, derive_treasury},
+    PositionPage, PositionQuery, PositionSort, ReadOnlyClient, SimulateParams,
+};
+use base64::{engine::general_purpose::STANDARD, Engine as _};
+use serde_json::{json, Value};
+use solana_sdk::pubkey::Pubkey;
+
+/// Answer `requests` JSON-RPC calls, one per connection, with `reply`'s
+/// `result`; join for the request bodies.
+fn serve(requests: usize, reply: impl Fn(&Value) -> Value + Send + 'static) -> (String, JoinHandle<Vec<Value>>) {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("http://{}", listener.local_addr().unwrap());
+    let handle = std::thread::spawn(move || {
+        (0..requests).map(|_| {
+            let (stream, _) = listener.accept().unwrap();
+            let mut reader = BufReader::new(stream);
+            let mut len = 0;
+            loop {
+                let mut header = String::new();
+                reader.read_line(&mut header).unwrap();
+                if header.trim_end().is_empty() {
+                    break;
+                }
+                if let Some((name, value)) = header.trim_end().split_once(": ") {
+                    if name.eq_ignore_ascii_case("content-length") {
+                        len = value.parse().unwrap();
+                    }
+                }
+            }
+            let mut body = vec![0; len];
+            reader.read_exact(&mut body).unwrap();
+            let request: Value = serde_json::from_slice(&body).unwrap();
+            let response = json!({ "jsonrpc": "2.0", "id": request["id"], "result": reply(&request) }).to_string();
+            write!(
+                reader.get_mut(),
+                "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{response}",
+                response.len()
+            ).unwrap();
+            request
+        }).collect()
+    });
+    (url, handle)
+}
+
+fn ui_account(data: &[u8]) -> Value {
+    json!({ "data": [STANDARD.encode(data), "base64"], "executable": false, "lamports": 1,
+            "owner": Pubkey::default().to_string(), "rentEpoch": 0, "space": data.len() })
+}
+
+fn context(value: Value) -> Value {
+    json!({ "context": { "slot": 1 }, "value": value })
+}
+
+struct Fixture {
+    program: Pubkey,
+    mint_a:  Pubkey,
+    mint_b:  Pubkey,
+    pool:    Pubkey,
+    data:    Vec<u8>,
+}
+
+fn fixture() -> Fixture {
+    let program = Pubkey::new_unique();
+    let (mint_a, mint_b) = (Pubkey::new_unique(), Pubkey::new_unique());
+    let (pool, _) = derive_pool(&mint_a, &mint_b, &program);
+    let data = a2a_swap_core::Pool {
+        token_a_mint:  mint_a.to_bytes(),
+        token_b_mint:  mint_b.to_bytes(),
+        token_a_vault: Pubkey::new_unique().to_bytes(),
+        token_b_vault: Pubkey::new_unique().to_bytes(),
+        lp_supply:     1_000_000,
+        fee_rate_bps:  30,
+        reserve_a:     1_000_000,
+        reserve_b:     4_000_000,
+        version:       a2a_swap_sdk::state::POOL_VERSION,
+        ..Default::default()
+    }.to_account_data();
+    Fixture { program, mint_a, mint_b, pool, data }
+}
+
+#[tokio::test]
+async fn quotes_and_pool_info_take_one_call_each() {
+    let f = fixture();
+    let (treasury, _) = derive_treasury(&f.program);
+    let (pool, data) = (f.pool, f.data.clone());
+    let (url, mock) = serve(2, move |req| {
+        assert_eq!(req["method"], "getMultipleAccounts");
+        assert_eq!(req["params"][1]["encoding"], "base64");
+        let accounts = req["params"][0].as_array().unwrap().iter().map(|key| {
+            if key == &pool.to_string() { ui_account(&data) } else { Value::Null }
+        });
+        context(accounts.collect())
+    });
+    let client = ReadOnlyClient::new(url).with_program_id(f.program);
+
+    let info = client.pool_info(f.mint_a, f.mint_b).await.unwrap();
+    assert_eq!(info.pool, f.pool);
+    assert_eq!((info.reserve_a, info.reserve_b), (1_000_000, 4_000_000));
+    assert_eq!(info.spot_price, 4.0);
+
+    // B → A finds the same pool through the other PDA ordering.
+    let sim = client.simulate(SimulateParams { mint_in: f.mint_b, mint_out: f.mint_a, amount_in: 10_000 }).await.unwrap();
+    assert_eq!(sim.pool, f.pool);
+    assert!(sim.estimated_out > 0 && sim.estimated_out < 2_500);
+
+    // Both PDA orderings and the treasury in a single request per call.
+    let requests = mock.join().unwrap();
+    let reversed = derive_pool(&f.mint_b, &f.mint_a, &f.program).0;
+    let keys = |i: usize| requests[i]["params"][0].clone();
+    assert_eq!(keys(0), json!([f.pool, reversed, treasury].map(|k| k.to_string())));
+    assert_eq!(keys(1), json!([reversed, f.pool, treasury].map(|k| k.to_string())));
+}
+
+#[tokio::test]
+async fn pool_info_in_values_reserves_in_the_quote() {
+    let f = fixture();
+    let (pool, data) = (f.pool, f.data.clone());
+    let (url, mock) = serve(4, move |req| {
+        let accounts = req["params"][0].as_array().unwrap().iter().map(|key| {
+            if key == &pool.to_string() { ui_account(&data) } else { Value::Null }
+        });
+        context(accounts.collect())
+    });
+    let client = ReadOnlyClient::new(url).with_program_id(f.program);
+
+    // Quoted in B, A is priced through the pool itself: 1e6 A ≈ 4e6 B.
+    let info = client.pool_info_in(f.mint_a, f.mint_b, f.mint_b).await.unwrap();
+    assert_eq!((info.lp_share_value_a, info.lp_share_value_b), (1.0, 4.0));
+    let tvl = info.tvl.unwrap();
+    assert_eq!((tvl.quote_mint, tvl.value_a, tvl.value_b, tvl.total), (f.mint_b, 4_000_000, 4_000_000, 8_000_000));
+    assert_eq!(info.created_slot, None);
+
+    // Neither side has a pool against an unrelated quote.
+    let info = client.pool_info_in(f.mint_a, f.mint_b, Pubkey::new_unique()).await.unwrap();
+    assert!(info.tvl.is_none());
+    mock.join().unwrap();
+}
+
+#[tokio::test]
+async fn ladder_quotes_every_size_from_one_call() {
+    let f = fixture();
+    let (pool, data) = (f.pool, f.data.clone());
+    let (url, mock) = serve(1, move |req| {
+        let accounts = req["params"][0].as_array().unwrap().iter().map(|key| {
+            if key == &pool.to_string() { ui_account(&data) } else { Value::Null }
+        });
+        context(accounts.collect())
+    });
+    let client = ReadOnlyClient::new(url).with_program_id(f.program);
+
+    let ladder = client.simulate_ladder(f.mint_a, f.mint_b, &[1_000, 10_000, 100_000]).await.unwrap();
+    assert_eq!(ladder.iter().map(|s| s.amount_in).collect::<Vec<_>>(), [1_000, 10_000, 100_000]);
+    // Bigger trades get more out, at a worse rate and a bigger impact.
+    for pair in ladder.windows(2) {
+        assert!(pair[1].estimated_out > pair[0].estimated_out);
+        assert!(pair[1].effective_rate < pair[0].effective_rate);
+        assert!(pair[1].price_impact_pct > pair[0].price_impact_pct);
+    }
+    assert_eq!(mock.join().unwrap().len(), 1);
+
+    let err = ReadOnlyClient::new("http://127.0.0.1:9").simulate_ladder(f.mint_a, f.mint_b, &[]).await.unwrap_err();
+    assert!(matches!(err, a2a_swap_sdk::Error::InvalidArgument(_)));
+}
+
+#[tokio::test]
+async fn positions_and_fees_over_json_rpc() {
+    let f = fixture();
+    let owner = Pubkey::new_unique();
+    let position = Pubkey::new_unique();
+    let position_data = a2a_swap_core::Position {
+        owner:       owner.to_bytes(),
+        pool:        f.pool.to_bytes(),
+        lp_shares:   500,
+        fees_owed_a: 7,
+        fees_owed_b: 9,
+        version:     a2a_swap_sdk::state::POSITION_VERSION,
+        ..Default::default()
+    }.to_account_data();
+    let pool_data = f.data.clone();
+    let (url, mock) = serve(2, move |req| match req["method"].as_str().unwrap() {
+        "getProgramAccounts" => json!([{ "pubkey": position.to_string(), "account": ui_account(&position_data) }]),
+        "getMultipleAccounts" => context(json!([ui_account(&pool_data)])),
+        other => panic!("unexpected {other}"),
+    });
+    let client = ReadOnlyClient::new(url).with_program_id(f.program);
+
+    let fees = client.my_fees(&owner).await.unwrap();
+    assert_eq!(fees.positions.len(), 1);
+    assert_eq!(fees.positions[0].address, position);
+    assert_eq!((fees.total_fees_a, fees.total_fees_b), (7, 9));
+
+    let requests = mock.join().unwrap();
+    assert_eq!(requests[0]["params"][0], f.program.to_string());
+    let filters = &requests[0]["params"][1]["filters"];
+    assert_eq!(filters[0]["memcmp"]["bytes"], STANDARD.encode(a2a_swap_core::Position::DISCRIMINATOR));
+    assert_eq!(filters[1]["memcmp"]["offset"], 8);
+    assert_eq!(filters[1]["memcmp"]["bytes"], STANDARD.encode(owner));
+}
+
+#[tokio::test]
+async fn positions_page_filters_sorts_and_pages() {
+    let f = fixture();
+    let owner = Pubkey::new_unique();
+    let accounts: Vec<Value> = [(100, false), (900, false), (500, true)].iter().map(|&(lp_shares, auto_compound)| {
+        let data = a2a_swap_core::Position {
+            owner:   owner.to_bytes(),
+            pool:    f.pool.to_bytes(),
+            lp_shares,
+            auto_compound,
+            version: a2a_swap_sdk::state::POSITION_VERSION,
+            ..Default::default()
+        }.to_account_data();
+        json!({ "pubkey": Pubkey::new_unique().to_string(), "account": ui_account(&data) })
+    }).collect();
+    let pool_data = f.data.clone();
+    let (url, mock) = serve(8, move |req| match req["method"].as_str().unwrap() {
+        "getProgramAccounts" => json!(accounts),
+        "getMultipleAccounts" => context(json!([ui_account(&pool_data)])),
+        other => panic!("unexpected {other}"),
+    });
+    let client = ReadOnlyClient::new(url).with_program_id(f.program);
+    let lp = |page: &PositionPage| page.positions.iter().map(|p| p.lp_shares).collect::<Vec<_>>();
+
+    let mut query = PositionQuery { sort: PositionSort::Value, limit: Some(2), ..Default::default() };
+    let first = client.my_positions_page(&owner, &query).await.unwrap();
+    assert_eq!((lp(&first), first.total), (vec![900, 500], 3));
+    // 900 shares of 1e6 hold 900 A + 3_600 B; A is worth 4 B.
+    assert_eq!(first.positions[0].value_b, Some(7_200));
+
+    query.cursor = first.next_cursor;
+    let last = client.my_positions_page(&owner, &query).await.unwrap();
+    assert_eq!((lp(&last), last.next_cursor), (vec![100], None));
+
+    let on = PositionQuery { auto_compound: Some(true), ..Default::default() };
+    assert_eq!(lp(&client.my_positions_page(&owner, &on).await.unwrap()), vec![500]);
+    let big = PositionQuery { min_lp_shares: 400, sort: PositionSort::Value, ..Default::default() };
+    assert_eq!(lp(&client.my_positions_page(&owner, &big).await.unwrap()), vec![900, 500]);
+    mock.join().unwrap();
+}
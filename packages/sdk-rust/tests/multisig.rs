@@ -0,0 +1,113 @@
+//! Squads vault transaction encoding in `a2a_swap_sdk::multisig`.
+//!
+//! No RPC: compiles a swap for a vault, checks the wire format Squads
+//! expects, and reads it back from a hand-built `VaultTransaction` account.
+
+use a2a_swap_sdk::{
+    instructions::swap_ix,
+    multisig::{
+        derive_vault, parse_multisig_transaction_index, parse_vault_transaction,
+        vault_transaction_execute_ix, VaultMessage,
+    },
+    Error,
+};
+use solana_sdk::{
+    instruction::{AccountMeta, Instruction},
+    pubkey::Pubkey,
+};
+
+fn vault_swap(vault: &Pubkey) -> Instruction {
+    let k = || Pubkey::new_unique();
+    swap_ix(
+        &k(), vault, &k(), &k(), &k(), &k(), &k(), &k(), &k(), &k(), None, 1_000, 900, true, 0, 0,
+    )
+}
+
+/// The Borsh `VaultTransaction` account Squads would store for `message`.
+fn stored(multisig: &Pubkey, index: u64, message: &VaultMessage) -> Vec<u8> {
+    let mut out = vec![0u8; 8];
+    out.extend_from_slice(multisig.as_ref());
+    out.extend_from_slice(Pubkey::new_unique().as_ref()); // creator
+    out.extend_from_slice(&index.to_le_bytes());
+    out.extend_from_slice(&[255, 0, 254]); // bump, vault_index, vault_bump
+    out.extend_from_slice(&0u32.to_le_bytes()); // ephemeral_signer_bumps
+    out.extend_from_slice(&[
+        message.num_signers,
+        message.num_writable_signers,
+        message.num_writable_non_signers,
+    ]);
+    out.extend_from_slice(&(message.account_keys.len() as u32).to_le_bytes());
+    for key in &message.account_keys {
+        out.extend_from_slice(key.as_ref());
+    }
+    out.extend_from_slice(&(message.instructions.len() as u32).to_le_bytes());
+    for ix in &message.instructions {
+        out.push(ix.program_id_index);
+        out.extend_from_slice(&(ix.account_indexes.len() as u32).to_le_bytes());
+        out.extend_from_slice(&ix.account_indexes);
+        out.extend_from_slice(&(ix.data.len() as u32).to_le_bytes());
+        out.extend_from_slice(&ix.data);
+    }
+    out.extend_from_slice(&0u32.to_le_bytes()); // address_table_lookups
+    out
+}
+
+#[test]
+fn compile_puts_the_vault_first_and_programs_last() {
+    let multisig = Pubkey::new_unique();
+    let (vault, _) = derive_vault(&multisig, 0);
+    let ix = vault_swap(&vault);
+    let message = VaultMessage::compile(&vault, std::slice::from_ref(&ix)).unwrap();
+
+    assert_eq!(message.account_keys[0], vault);
+    assert_eq!((message.num_signers, message.num_writable_signers), (1, 1));
+    // Writable: pool, both vaults, both agent accounts, treasury token account.
+    assert_eq!(message.num_writable_non_signers, 6);
+    assert_eq!(message.account_keys.len(), ix.accounts.len() + 1);
+    assert_eq!(*message.account_keys.last().unwrap(), ix.program_id);
+
+    let compiled = &message.instructions[0];
+    for (meta, &i) in ix.accounts.iter().zip(&compiled.account_indexes) {
+        assert_eq!(message.account_keys[i as usize], meta.pubkey);
+        assert_eq!(message.is_writable(i as usize), meta.is_writable, "{}", meta.pubkey);
+    }
+
+    let bytes = message.to_bytes();
+    assert_eq!(&bytes[..4], &[1, 1, 6, message.account_keys.len() as u8]);
+    assert_eq!(bytes.last(), Some(&0), "no lookup tables");
+}
+
+#[test]
+fn compile_rejects_other_signers() {
+    let vault = Pubkey::new_unique();
+    let mut ix = vault_swap(&vault);
+    ix.accounts.push(AccountMeta::new_readonly(Pubkey::new_unique(), true));
+    assert!(matches!(VaultMessage::compile(&vault, &[ix]), Err(Error::InvalidArgument(_))));
+}
+
+#[test]
+fn stored_transaction_round_trips_into_execute() {
+    let multisig = Pubkey::new_unique();
+    let (vault, _) = derive_vault(&multisig, 0);
+    let message = VaultMessage::compile(&vault, &[vault_swap(&vault)]).unwrap();
+
+    let parsed = parse_vault_transaction(&stored(&multisig, 7, &message)).unwrap();
+    assert_eq!(parsed.multisig, multisig);
+    assert_eq!(parsed.index, 7);
+    assert_eq!(parsed.message, message);
+
+    let member = Pubkey::new_unique();
+    let exec = vault_transaction_execute_ix(&multisig, &member, 7, &parsed.message);
+    let remaining = &exec.accounts[4..];
+    assert_eq!(remaining.len(), message.account_keys.len());
+    assert!(remaining.iter().all(|m| !m.is_signer), "the vault signs by PDA");
+    assert!(remaining[0].is_writable && remaining[0].pubkey == vault);
+}
+
+#[test]
+fn multisig_transaction_index_offset() {
+    let mut data = vec![0u8; 120];
+    data[78..86].copy_from_slice(&41u64.to_le_bytes());
+    assert_eq!(parse_multisig_transaction_index(&data).unwrap(), 41);
+    assert!(parse_multisig_transaction_index(&data[..80]).is_err());
+}
@@ -0,0 +1,53 @@
+//! Drift and trade sizing in `a2a_swap_sdk::rebalancer`.
+
+use a2a_swap_sdk::{
+    rebalancer::{plan_rebalance, RebalanceTrade, TargetRatio},
+    Error,
+};
+
+fn ratio(s: &str) -> TargetRatio {
+    s.parse().unwrap()
+}
+
+#[test]
+fn target_ratio_parses_and_round_trips() {
+    assert_eq!(ratio("70:30"), TargetRatio { weight_a: 70, weight_b: 30 });
+    assert_eq!(ratio(" 1 : 3 ").share_a_bps(), 2_500.0);
+    assert_eq!(ratio("100:0").to_string(), "100:0");
+    for bad in ["50", "50:50:0", "0:0", "-1:2", "a:b"] {
+        assert!(matches!(bad.parse::<TargetRatio>(), Err(Error::InvalidArgument(_))), "{bad}");
+    }
+}
+
+#[test]
+fn no_trade_inside_the_band() {
+    // 1_000 A at 2 B each = 2_000 B vs 1_800 B → A share 5_263 bps, 263 over 50:50.
+    let plan = plan_rebalance(1_000, 1_800, 2.0, ratio("50:50"), 300).unwrap();
+    assert!((plan.drift_bps - 263.157).abs() < 0.01);
+    assert_eq!(plan.trade, None);
+}
+
+#[test]
+fn sells_the_overweight_side_back_to_target() {
+    // A worth 3_000 B, B 1_000 → sell 1_000 B of value = 500 A.
+    let plan = plan_rebalance(1_500, 1_000, 2.0, ratio("50:50"), 500).unwrap();
+    assert_eq!(plan.share_a_bps, 7_500.0);
+    assert_eq!(plan.trade, Some(RebalanceTrade { a_to_b: true, amount_in: 500 }));
+
+    // 70:30 with everything in B → buy 7_000 B of A.
+    let plan = plan_rebalance(0, 10_000, 4.0, ratio("70:30"), 100).unwrap();
+    assert_eq!(plan.drift_bps, -7_000.0);
+    assert_eq!(plan.trade, Some(RebalanceTrade { a_to_b: false, amount_in: 7_000 }));
+
+    // 100:0 sells all of B, never more than is held.
+    let plan = plan_rebalance(10, 999, 3.0, ratio("100:0"), 0).unwrap();
+    assert_eq!(plan.trade, Some(RebalanceTrade { a_to_b: false, amount_in: 999 }));
+}
+
+#[test]
+fn rejects_bad_inputs_and_ignores_empty_wallets() {
+    assert!(matches!(plan_rebalance(1, 1, 0.0, ratio("50:50"), 100), Err(Error::NoLiquidity)));
+    assert!(matches!(plan_rebalance(1, 1, f64::NAN, ratio("50:50"), 100), Err(Error::NoLiquidity)));
+    assert!(matches!(plan_rebalance(1, 1, 1.0, ratio("50:50"), 10_001), Err(Error::InvalidArgument(_))));
+    assert_eq!(plan_rebalance(0, 0, 1.0, ratio("50:50"), 100).unwrap().trade, None);
+}
@@ -0,0 +1,159 @@
+//! `ProgramScan` modes and the index endpoint against local mock JSON-RPC nodes.
+
+use std::collections::HashMap;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::TcpListener;
+use std::sync::{Arc, Mutex};
+
+use a2a_swap_core::Account as _;
+use a2a_swap_sdk::{A2ASwapClient, ProgramScan};
+use base64::{engine::general_purpose::STANDARD, Engine as _};
+use serde_json::{json, Value};
+use solana_sdk::pubkey::Pubkey;
+
+type Log = Arc<Mutex<Vec<Value>>>;
+
+/// Answer JSON-RPC calls, one per connection, with `reply`'s `result`.
+/// Returns the URL and every request so far.
+fn serve(reply: impl Fn(&Value) -> Value + Send + 'static) -> (String, Log) {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("http://{}", listener.local_addr().unwrap());
+    let seen = Log::default();
+    let log = seen.clone();
+    std::thread::spawn(move || {
+        for stream in listener.incoming() {
+            let mut reader = BufReader::new(stream.unwrap());
+            let mut len = 0;
+            loop {
+                let mut header = String::new();
+                reader.read_line(&mut header).unwrap();
+                if header.trim_end().is_empty() {
+                    break;
+                }
+                if let Some((name, value)) = header.trim_end().split_once(": ") {
+                    if name.eq_ignore_ascii_case("content-length") {
+                        len = value.parse().unwrap();
+                    }
+                }
+            }
+            let mut body = vec![0; len];
+            reader.read_exact(&mut body).unwrap();
+            let request: Value = serde_json::from_slice(&body).unwrap();
+            let response = json!({ "jsonrpc": "2.0", "id": request["id"], "result": reply(&request) }).to_string();
+            log.lock().unwrap().push(request);
+            write!(
+                reader.get_mut(),
+                "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{response}",
+                response.len()
+            ).unwrap();
+        }
+    });
+    (url, seen)
+}
+
+fn ui_account(data: &[u8]) -> Value {
+    json!({ "data": [STANDARD.encode(data), "base64"], "executable": false, "lamports": 1,
+            "owner": Pubkey::default().to_string(), "rentEpoch": 0, "space": data.len() })
+}
+
+fn keyed(key: &Pubkey, data: &[u8]) -> Value {
+    json!({ "pubkey": key.to_string(), "account": ui_account(data) })
+}
+
+/// `getMultipleAccounts` over `accounts`; unknown keys are `null`.
+fn multiple(accounts: &HashMap<String, Vec<u8>>, req: &Value) -> Value {
+    let value: Vec<Value> = req["params"][0].as_array().unwrap().iter()
+        .map(|key| accounts.get(key.as_str().unwrap()).map_or(Value::Null, |data| ui_account(data)))
+        .collect();
+    json!({ "context": { "slot": 1 }, "value": value })
+}
+
+fn pool_data(reserve_a: u64, reserve_b: u64) -> Vec<u8> {
+    a2a_swap_core::Pool {
+        token_a_mint:  Pubkey::new_unique().to_bytes(),
+        token_b_mint:  Pubkey::new_unique().to_bytes(),
+        token_a_vault: Pubkey::new_unique().to_bytes(),
+        token_b_vault: Pubkey::new_unique().to_bytes(),
+        lp_supply:     1_000,
+        fee_rate_bps:  30,
+        reserve_a,
+        reserve_b,
+        version:       a2a_swap_sdk::state::POOL_VERSION,
+        ..Default::default()
+    }.to_account_data()
+}
+
+#[tokio::test]
+async fn paged_scan_reads_addresses_then_pages_of_data() {
+    let program = Pubkey::new_unique();
+    let pools: Vec<(Pubkey, Vec<u8>)> = (1..=3).map(|i| (Pubkey::new_unique(), pool_data(i * 100, i * 400))).collect();
+    let closed = Pubkey::new_unique();
+    let keys: Vec<Pubkey> = pools.iter().map(|(k, _)| *k).chain([closed]).collect();
+    let accounts: HashMap<String, Vec<u8>> = pools.iter().map(|(k, d)| (k.to_string(), d.clone())).collect();
+    let (url, seen) = serve(move |req| match req["method"].as_str().unwrap() {
+        "getProgramAccounts" => keys.iter().map(|k| keyed(k, &[])).collect(),
+        "getMultipleAccounts" => multiple(&accounts, req),
+        other => panic!("unexpected {other}"),
+    });
+    let client = A2ASwapClient::new(url)
+        .with_program_id(program)
+        .with_program_scan(ProgramScan::Paged { page_size: 2 });
+
+    let listed = client.list_pools().await.unwrap();
+    let mut expected: Vec<Pubkey> = pools.iter().map(|(k, _)| *k).collect();
+    expected.sort();
+    assert_eq!(listed.iter().map(|p| p.pool).collect::<Vec<_>>(), expected);
+    assert!(listed.iter().all(|p| p.spot_price == 4.0));
+
+    let requests = seen.lock().unwrap();
+    let scan = &requests[0]["params"][1];
+    assert_eq!(scan["dataSlice"], json!({ "offset": 0, "length": 0 }));
+    assert_eq!(scan["encoding"], "base64");
+    assert_eq!(scan["filters"][0]["memcmp"]["offset"], 0);
+    let pages: Vec<usize> = requests[1..3].iter().map(|r| r["params"][0].as_array().unwrap().len()).collect();
+    assert_eq!(pages, [2, 2]);
+}
+
+#[tokio::test]
+async fn cursor_scan_on_the_index_endpoint() {
+    let program = Pubkey::new_unique();
+    let owner = Pubkey::new_unique();
+    let pool = Pubkey::new_unique();
+    let position = |fees: u64| a2a_swap_core::Position {
+        owner:       owner.to_bytes(),
+        pool:        pool.to_bytes(),
+        lp_shares:   10,
+        fees_owed_a: fees,
+        fees_owed_b: fees,
+        version:     a2a_swap_sdk::state::POSITION_VERSION,
+        ..Default::default()
+    }.to_account_data();
+    let (first, second) = (position(1), position(2));
+    let (index_url, index) = serve(move |req| {
+        assert_eq!(req["method"], "getProgramAccountsV2");
+        match req["params"][1]["paginationKey"].as_str() {
+            None => json!({ "accounts": [keyed(&Pubkey::new_unique(), &first)], "paginationKey": "page-2" }),
+            Some("page-2") => json!({ "accounts": [keyed(&Pubkey::new_unique(), &second)], "paginationKey": null }),
+            Some(other) => panic!("unexpected cursor {other}"),
+        }
+    });
+    let accounts = HashMap::from([(pool.to_string(), pool_data(100, 400))]);
+    let (url, main) = serve(move |req| {
+        assert_eq!(req["method"], "getMultipleAccounts");
+        multiple(&accounts, req)
+    });
+    let client = A2ASwapClient::new(url)
+        .with_program_id(program)
+        .with_program_scan(ProgramScan::Cursor { limit: 1 })
+        .with_index_url(index_url);
+
+    let fees = client.my_fees(&owner).await.unwrap();
+    assert_eq!(fees.positions.len(), 2);
+    assert_eq!((fees.total_fees_a, fees.total_fees_b), (3, 3));
+
+    let index = index.lock().unwrap();
+    assert_eq!(index.len(), 2);
+    assert_eq!(index[0]["params"][1]["limit"], 1);
+    assert_eq!(index[0]["params"][1]["filters"][1]["memcmp"]["bytes"], STANDARD.encode(owner));
+    assert_eq!(main.lock().unwrap().len(), 1);
+}
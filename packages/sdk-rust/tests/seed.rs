@@ -0,0 +1,149 @@
+//! `create_and_seed_pool` against a local mock JSON-RPC node.
+
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::TcpListener;
+use std::sync::{Arc, Mutex};
+
+use a2a_swap_core::{ix, Instruction as _};
+use a2a_swap_sdk::{A2ASwapClient, CreateAndSeedParams, CreatePoolParams, CurveKind, Error};
+use base64::{engine::general_purpose::STANDARD, Engine as _};
+use serde_json::{json, Value};
+use solana_sdk::{pubkey::Pubkey, signature::{Keypair, Signature}};
+
+/// Answer JSON-RPC calls, one per connection, and keep every raw
+/// transaction sent with `sendTransaction`.
+fn serve() -> (String, Arc<Mutex<Vec<Vec<u8>>>>) {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("http://{}", listener.local_addr().unwrap());
+    let sent = Arc::new(Mutex::new(Vec::new()));
+    let log = sent.clone();
+    std::thread::spawn(move || {
+        for stream in listener.incoming() {
+            let mut reader = BufReader::new(stream.unwrap());
+            let mut len = 0;
+            loop {
+                let mut header = String::new();
+                reader.read_line(&mut header).unwrap();
+                if header.trim_end().is_empty() {
+                    break;
+                }
+                if let Some((name, value)) = header.trim_end().split_once(": ") {
+                    if name.eq_ignore_ascii_case("content-length") {
+                        len = value.parse().unwrap();
+                    }
+                }
+            }
+            let mut body = vec![0; len];
+            reader.read_exact(&mut body).unwrap();
+            let request: Value = serde_json::from_slice(&body).unwrap();
+            let result = match request["method"].as_str().unwrap() {
+                "getLatestBlockhash" => json!({ "context": { "slot": 1 },
+                    "value": { "blockhash": Pubkey::new_unique().to_string(), "lastValidBlockHeight": 100 } }),
+                "sendTransaction" => {
+                    let tx = STANDARD.decode(request["params"][0].as_str().unwrap()).unwrap();
+                    let signature = Signature::try_from(&tx[1..65]).unwrap().to_string();
+                    log.lock().unwrap().push(tx);
+                    json!(signature)
+                }
+                "getSignatureStatuses" => json!({ "context": { "slot": 1 }, "value": [{
+                    "slot": 1, "confirmations": null, "err": null, "status": { "Ok": null },
+                    "confirmationStatus": "finalized" }] }),
+                "getVersion" => json!({ "solana-core": "2.1.0", "feature-set": 0 }),
+                other => panic!("unexpected {other}"),
+            };
+            let response = json!({ "jsonrpc": "2.0", "id": request["id"], "result": result }).to_string();
+            write!(
+                reader.get_mut(),
+                "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{response}",
+                response.len()
+            ).unwrap();
+        }
+    });
+    (url, sent)
+}
+
+/// Read a compact-u16 length at `*at`.
+fn compact_len(tx: &[u8], at: &mut usize) -> usize {
+    let (mut len, mut shift) = (0, 0);
+    loop {
+        let byte = tx[*at];
+        *at += 1;
+        len |= ((byte & 0x7f) as usize) << shift;
+        if byte & 0x80 == 0 {
+            return len;
+        }
+        shift += 7;
+    }
+}
+
+/// Signature count, and each instruction's program id and data, of a
+/// legacy wire transaction.
+fn decode(tx: &[u8]) -> (usize, Vec<(Pubkey, Vec<u8>)>) {
+    let mut at = 0;
+    let signatures = compact_len(tx, &mut at);
+    at += 64 * signatures + 3;
+    let keys: Vec<Pubkey> = (0..compact_len(tx, &mut at))
+        .map(|i| Pubkey::try_from(&tx[at + 32 * i..at + 32 * (i + 1)]).unwrap())
+        .collect();
+    at += 32 * keys.len() + 32;
+    let instructions = (0..compact_len(tx, &mut at))
+        .map(|_| {
+            let program = keys[tx[at] as usize];
+            at += 1;
+            at += compact_len(tx, &mut at);
+            let len = compact_len(tx, &mut at);
+            at += len;
+            (program, tx[at - len..at].to_vec())
+        })
+        .collect();
+    (signatures, instructions)
+}
+
+fn params(amount_a: u64, amount_b: u64) -> CreateAndSeedParams {
+    CreateAndSeedParams {
+        pool: CreatePoolParams {
+            mint_a:       Pubkey::new_unique(),
+            mint_b:       Pubkey::new_unique(),
+            fee_rate_bps: 30,
+            curve:        CurveKind::ConstantProduct,
+        },
+        amount_a,
+        amount_b,
+        auto_compound:      false,
+        compound_threshold: 0,
+        min_lp:             0,
+        mint_receipt:       false,
+    }
+}
+
+#[tokio::test]
+async fn pool_is_created_and_seeded_in_one_transaction() {
+    let program = Pubkey::new_unique();
+    let (url, sent) = serve();
+    let client = A2ASwapClient::new(url).with_program_id(program);
+
+    let result = client.create_and_seed_pool(&Keypair::new(), params(1_000, 185_000)).await.unwrap();
+    assert_eq!(result.pool.signature, result.liquidity.signature);
+    assert_eq!(result.liquidity.pool, result.pool.pool);
+    assert_eq!((result.liquidity.amount_a, result.liquidity.amount_b), (1_000, 185_000));
+
+    let sent = sent.lock().unwrap();
+    assert_eq!(sent.len(), 1);
+    let (signatures, instructions) = decode(&sent[0]);
+    // Payer and both fresh vault keypairs.
+    assert_eq!(signatures, 3);
+    assert_eq!(instructions.len(), 2);
+    assert!(instructions.iter().all(|(p, _)| *p == program));
+    assert_eq!(instructions[0].1[..8], ix::InitializePool::DISCRIMINATOR);
+    assert_eq!(instructions[1].1[..8], ix::ProvideLiquidity::DISCRIMINATOR);
+}
+
+#[tokio::test]
+async fn zero_seed_amount_is_rejected_before_sending() {
+    let (url, sent) = serve();
+    let client = A2ASwapClient::new(url);
+
+    let err = client.create_and_seed_pool(&Keypair::new(), params(1_000, 0)).await.unwrap_err();
+    assert!(matches!(err, Error::InvalidArgument(_)));
+    assert!(sent.lock().unwrap().is_empty());
+}
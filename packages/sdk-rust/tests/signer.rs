@@ -0,0 +1,132 @@
+//! Ledger APDU framing and signing in `a2a_swap_sdk::signer`, against an
+//! emulated device.
+
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+
+use a2a_swap_sdk::signer::{frame, HidDevice, LedgerSigner, RemoteSigner, Unframe, HID_REPORT_LEN};
+use solana_sdk::{
+    signature::Keypair,
+    signer::{Signer, SignerError},
+};
+
+/// Answers the Solana app's GET_PUBKEY / SIGN_MESSAGE with `key`, or with
+/// `status` when set. Records every APDU it receives.
+struct FakeLedger {
+    key:     Keypair,
+    status:  Option<u16>,
+    apdus:   Arc<Mutex<Vec<Vec<u8>>>>,
+    pending: Unframe,
+    message: Vec<u8>,
+    out:     VecDeque<[u8; HID_REPORT_LEN]>,
+}
+
+impl FakeLedger {
+    fn new(key: Keypair, status: Option<u16>) -> (Self, Arc<Mutex<Vec<Vec<u8>>>>) {
+        let apdus = Arc::new(Mutex::new(Vec::new()));
+        let ledger = Self {
+            key,
+            status,
+            apdus: apdus.clone(),
+            pending: Unframe::default(),
+            message: Vec::new(),
+            out: VecDeque::new(),
+        };
+        (ledger, apdus)
+    }
+
+    fn answer(&mut self, apdu: &[u8]) -> Vec<u8> {
+        let (ins, p2, data) = (apdu[1], apdu[3], &apdu[5..]);
+        assert_eq!((apdu[0], apdu[4] as usize), (0xe0, data.len()));
+        let mut reply = match (ins, p2 & 0x01 != 0) {
+            (0x05, _) => self.key.pubkey().to_bytes().to_vec(),
+            (0x06, extend) => {
+                if !extend {
+                    // One signer, depth, path, then the message.
+                    assert_eq!(data[0], 1);
+                    self.message = data[2 + 4 * data[1] as usize..].to_vec();
+                } else {
+                    self.message.extend_from_slice(data);
+                }
+                if p2 & 0x02 != 0 {
+                    Vec::new()
+                } else {
+                    self.key.sign_message(&self.message).as_ref().to_vec()
+                }
+            }
+            _ => panic!("unexpected instruction {ins:#x}"),
+        };
+        if ins == 0x06 {
+            if let Some(status) = self.status {
+                return status.to_be_bytes().to_vec();
+            }
+        }
+        reply.extend_from_slice(&0x9000u16.to_be_bytes());
+        reply
+    }
+}
+
+impl HidDevice for FakeLedger {
+    fn write(&mut self, report: &[u8; HID_REPORT_LEN]) -> std::io::Result<()> {
+        if let Some(apdu) = self.pending.push(report).unwrap() {
+            self.apdus.lock().unwrap().push(apdu.clone());
+            let reply = self.answer(&apdu);
+            self.out.extend(frame(&reply));
+        }
+        Ok(())
+    }
+
+    fn read(&mut self) -> std::io::Result<[u8; HID_REPORT_LEN]> {
+        Ok(self.out.pop_front().expect("read with no reply pending"))
+    }
+}
+
+const PATH: [u32; 3] = [0x8000_002c, 0x8000_01f5, 0x8000_0000];
+
+#[test]
+fn frames_round_trip_across_reports() {
+    let apdu: Vec<u8> = (0..=200).collect();
+    let reports = frame(&apdu);
+    assert_eq!(reports.len(), 4); // 57 + 59 + 59 + 26
+    assert_eq!(&reports[1][..5], &[0x01, 0x01, 0x05, 0x00, 0x01]);
+    let mut unframe = Unframe::default();
+    let got: Vec<_> = reports.iter().map(|r| unframe.push(r).unwrap()).collect();
+    assert!(got[..3].iter().all(Option::is_none));
+    assert_eq!(got[3].as_deref(), Some(apdu.as_slice()));
+
+    let mut unframe = Unframe::default();
+    assert!(matches!(unframe.push(&reports[1]), Err(SignerError::Protocol(_))));
+}
+
+#[test]
+fn signs_long_messages_in_chunks() {
+    let key = Keypair::new();
+    let pubkey = key.pubkey();
+    let (ledger, apdus) = FakeLedger::new(key, None);
+    let signer = LedgerSigner::with_device(Box::new(ledger), &PATH).unwrap();
+    assert_eq!((signer.pubkey(), signer.derivation_path()), (pubkey, &PATH[..]));
+
+    let message: Vec<u8> = (0..700u32).map(|i| i as u8).collect();
+    let sig = signer.try_sign_message(&message).unwrap();
+    assert!(sig.verify(pubkey.as_ref(), &message));
+
+    // GET_PUBKEY, then 700 bytes over 3 SIGN_MESSAGE APDUs: 241 + 255 + 204.
+    let p2: Vec<u8> = apdus.lock().unwrap().iter().map(|a| a[3]).collect();
+    assert_eq!(p2, [0x00, 0x02, 0x03, 0x01]);
+    assert_eq!(apdus.lock().unwrap()[1].len(), 5 + 255);
+}
+
+#[test]
+fn maps_device_refusals() {
+    let (ledger, _) = FakeLedger::new(Keypair::new(), Some(0x6985));
+    let signer = LedgerSigner::with_device(Box::new(ledger), &PATH).unwrap();
+    assert!(matches!(signer.try_sign_message(b"swap"), Err(SignerError::UserCancel(_))));
+
+    let (ledger, _) = FakeLedger::new(Keypair::new(), Some(0x6a81));
+    let signer = LedgerSigner::with_device(Box::new(ledger), &PATH).unwrap();
+    assert!(matches!(signer.try_sign_message(b"swap"), Err(SignerError::Protocol(m)) if m.contains("blind signing")));
+
+    for uri in ["usb://ledger", "ledger://?key=x", "ledger://?key=0/0/0", "ledger://?account=1"] {
+        assert!(matches!(RemoteSigner::from_uri(uri), Err(SignerError::InvalidInput(_))), "{uri}");
+    }
+}
@@ -0,0 +1,57 @@
+//! Solana Pay swap links in `a2a_swap_sdk::solana_pay`.
+//!
+//! No RPC: builds links and checks the encoded request URL and the options a
+//! link cannot carry.
+
+use a2a_swap_sdk::{solana_pay, Error, ProtectionLevel, SwapParams};
+use solana_sdk::pubkey::Pubkey;
+
+fn params() -> SwapParams {
+    SwapParams {
+        mint_in:              Pubkey::new_unique(),
+        mint_out:             Pubkey::new_unique(),
+        amount_in:            1_000_000,
+        max_slippage_bps:     50,
+        min_amount_out:       None,
+        max_price_impact_bps: 0,
+        deadline_unix:        0,
+        referrer:             None,
+        protection:           ProtectionLevel::None,
+    }
+}
+
+#[test]
+fn link_encodes_the_request_url() {
+    let mut p = params();
+    p.deadline_unix = 1_700_000_000;
+    p.referrer = Some(Pubkey::new_unique());
+
+    let url = solana_pay::swap_request_url("https://api.example.com/", &p).unwrap();
+    assert_eq!(
+        url,
+        format!(
+            "https://api.example.com/pay/convert?tokenIn={}&tokenOut={}&amount=1000000&slippageBps=50\
+             &deadlineUnix=1700000000&referrer={}",
+            p.mint_in, p.mint_out, p.referrer.unwrap(),
+        ),
+    );
+
+    let link = solana_pay::swap_link("https://api.example.com/", &p).unwrap();
+    let encoded = link.strip_prefix("solana:").unwrap();
+    assert!(!encoded.contains(['/', '?', '&', '=', ':']));
+    assert!(encoded.starts_with("https%3A%2F%2Fapi.example.com%2Fpay%2Fconvert%3FtokenIn%3D"));
+}
+
+#[test]
+fn link_rejects_options_the_endpoint_cannot_carry() {
+    let reject = |p: SwapParams| {
+        assert!(matches!(
+            solana_pay::swap_link(solana_pay::DEFAULT_API_URL, &p),
+            Err(Error::InvalidArgument(_)),
+        ));
+    };
+    reject(SwapParams { amount_in: 0, ..params() });
+    reject(SwapParams { min_amount_out: Some(1), max_slippage_bps: 0, ..params() });
+    reject(SwapParams { max_price_impact_bps: 300, ..params() });
+    reject(SwapParams { protection: ProtectionLevel::CommitReveal, ..params() });
+}
@@ -0,0 +1,119 @@
+//! `Strategy` / `Runner` plumbing in `a2a_swap_sdk::strategies`, run in backtest mode.
+
+use a2a_swap_sdk::{
+    rebalancer::{RebalanceParams, RebalanceStrategy},
+    state::{CircuitBreaker, DynamicFee},
+    strategies::{dca::DcaStrategy, PoolUpdate, RunEvent, Runner, SpendCap},
+    CurveKind, PoolInfo,
+};
+use solana_sdk::pubkey::Pubkey;
+
+fn update(mint_a: Pubkey, mint_b: Pubkey, reserve_a: u64, reserve_b: u64, timestamp: i64) -> PoolUpdate {
+    PoolUpdate {
+        info: PoolInfo {
+            pool: Pubkey::new_unique(),
+            mint_a,
+            mint_b,
+            vault_a: Pubkey::new_unique(),
+            vault_b: Pubkey::new_unique(),
+            reserve_a,
+            reserve_b,
+            lp_supply: 0,
+            fee_rate_bps: 30,
+            effective_fee_bps: 30,
+            dynamic_fee: DynamicFee::default(),
+            circuit_breaker: CircuitBreaker::default(),
+            permissioned: false,
+            gate_mint: None,
+            protocol_fee_bps: 0,
+            curve: CurveKind::ConstantProduct,
+            volume_a: 0,
+            volume_b: 0,
+            fees_collected_a: 0,
+            fees_collected_b: 0,
+            spot_price: reserve_b as f64 / reserve_a as f64,
+            lp_share_value_a: 0.0,
+            lp_share_value_b: 0.0,
+            tvl: None,
+            created_slot: None,
+            created_at: None,
+        },
+        timestamp,
+    }
+}
+
+fn fills(events: &[RunEvent]) -> usize {
+    events.iter().filter(|e| matches!(e, RunEvent::Filled { .. })).count()
+}
+
+#[test]
+fn price_of_orients_to_the_base_mint() {
+    let (a, b) = (Pubkey::new_unique(), Pubkey::new_unique());
+    let u = update(a, b, 1_000, 4_000, 0);
+    assert_eq!(u.price_of(a), Some(4.0));
+    assert_eq!(u.price_of(b), Some(0.25));
+    assert_eq!(u.price_of(Pubkey::new_unique()), None);
+    assert_eq!(update(a, b, 0, 0, 0).price_of(a), None);
+}
+
+#[tokio::test]
+async fn dca_buys_on_the_update_schedule() {
+    let (usdc, sol) = (Pubkey::new_unique(), Pubkey::new_unique());
+    let mut dca = DcaStrategy::new(usdc, sol, 1_000, 60);
+    dca.buys_left = Some(2);
+
+    let history = [0, 30, 60, 90, 120, 180].map(|t| update(usdc, sol, 1_000_000, 1_000_000, t));
+    let reports = Runner::backtest().replay(&mut dca, history).await;
+    let bought: Vec<i64> = reports.iter().filter(|r| fills(&r.events) == 1).map(|r| r.update.timestamp).collect();
+    assert_eq!(bought, [0, 60]);
+    assert_eq!((dca.buys_left, dca.last_buy), (Some(0), Some(60)));
+}
+
+#[tokio::test]
+async fn backtest_quotes_from_the_update_and_moves_its_reserves() {
+    let (usdc, sol) = (Pubkey::new_unique(), Pubkey::new_unique());
+    let mut dca = DcaStrategy::new(usdc, sol, 10_000, 0);
+    let events = Runner::backtest().tick(&mut dca, &update(usdc, sol, 1_000_000, 1_000_000, 0)).await;
+    let [RunEvent::Filled { fill, .. }] = events.as_slice() else { panic!("{events:?}") };
+    // 10_000 in less the 0.3 % fee, x·y = k.
+    assert_eq!((fill.amount_in, fill.amount_out, fill.signature.clone()), (10_000, 9_871, None));
+
+    // A price-impact cap the swap breaks fails it, and no fill is recorded.
+    dca.max_price_impact_bps = 50;
+    let events = Runner::backtest().tick(&mut dca, &update(usdc, sol, 1_000_000, 1_000_000, 1)).await;
+    assert!(matches!(events.as_slice(), [RunEvent::Failed { .. }]), "{events:?}");
+    assert_eq!(dca.last_buy, Some(0));
+}
+
+#[tokio::test]
+async fn spend_cap_rejects_past_the_total() {
+    let (usdc, sol) = (Pubkey::new_unique(), Pubkey::new_unique());
+    let mut dca = DcaStrategy::new(usdc, sol, 1_000, 0);
+    let mut runner = Runner::backtest().with_policy(SpendCap::new(usdc, 0, 2_500));
+    let history = (0..4).map(|t| update(usdc, sol, 1_000_000, 1_000_000, t));
+    let reports = runner.replay(&mut dca, history).await;
+    assert_eq!(reports.iter().map(|r| fills(&r.events)).sum::<usize>(), 2);
+    assert!(matches!(reports[2].events.as_slice(), [RunEvent::Rejected { .. }]));
+}
+
+#[tokio::test]
+async fn rebalance_strategy_tracks_its_balances_through_fills() {
+    let (a, b) = (Pubkey::new_unique(), Pubkey::new_unique());
+    let params = RebalanceParams {
+        mint_a:               a,
+        mint_b:               b,
+        target:               "50:50".parse().unwrap(),
+        band_bps:             500,
+        max_slippage_bps:     100,
+        max_price_impact_bps: 0,
+        referrer:             None,
+    };
+    let mut strategy = RebalanceStrategy::new(params, 1_000, 0);
+    let events = Runner::backtest().tick(&mut strategy, &update(a, b, 1_000_000, 1_000_000, 0)).await;
+    assert_eq!(fills(&events), 1);
+    assert_eq!(strategy.balance_a, 500);
+    assert!(strategy.balance_b > 490 && strategy.balance_b < 500, "{}", strategy.balance_b);
+
+    // Back inside the band: nothing to do.
+    assert!(Runner::backtest().tick(&mut strategy, &update(a, b, 1_000_000, 1_000_000, 1)).await.is_empty());
+}
@@ -0,0 +1,52 @@
+//! Symbol / mint lookups and token-list parsing in `a2a_swap_sdk::tokens`.
+
+use a2a_swap_sdk::tokens::{TokenInfo, TokenRegistry};
+use solana_sdk::pubkey::Pubkey;
+
+const BONK: &str = "DezXAZ8z7PnrnRJjz3wXBoRgixCa6xjnB7YaB1pPB263";
+
+#[test]
+fn builtin_resolves_symbols_and_addresses() {
+    let tokens = TokenRegistry::builtin();
+    let usdc = tokens.get("usdc").unwrap();
+    assert_eq!((usdc.symbol.as_str(), usdc.decimals), ("USDC", 6));
+    assert_eq!(tokens.by_mint(&usdc.mint).unwrap().symbol, "USDC");
+    assert_eq!(tokens.get(&usdc.mint.to_string()), Some(usdc));
+
+    let any = Pubkey::new_unique();
+    assert_eq!(tokens.resolve_mint(&any.to_string()).unwrap(), any);
+    assert!(tokens.resolve_mint("NOPE").is_err());
+}
+
+#[test]
+fn token_list_skips_other_chains_and_keeps_first_duplicate() {
+    let json = format!(
+        r#"{{ "name": "test", "tokens": [
+            {{ "chainId": 101, "address": "{BONK}", "symbol": "BONK", "decimals": 5, "name": "Bonk" }},
+            {{ "chainId": 103, "address": "{}", "symbol": "DEV", "decimals": 9 }},
+            {{ "chainId": 101, "address": "{}", "symbol": "bonk", "decimals": 9 }}
+        ] }}"#,
+        Pubkey::new_unique(),
+        Pubkey::new_unique(),
+    );
+    let list = TokenRegistry::from_json(&json).unwrap();
+    assert_eq!(list.len(), 1);
+    assert_eq!(list.get("Bonk").unwrap().decimals, 5);
+
+    let back = TokenRegistry::from_json(&list.to_json()).unwrap();
+    assert_eq!(back.iter().collect::<Vec<_>>(), list.iter().collect::<Vec<_>>());
+}
+
+#[test]
+fn add_and_extend_replace_by_symbol_or_mint() {
+    let mut tokens = TokenRegistry::builtin();
+    let mint = Pubkey::new_unique();
+    tokens.add(TokenInfo { symbol: "USDC".into(), mint, decimals: 2, name: None });
+    assert_eq!(tokens.resolve_mint("usdc").unwrap(), mint);
+    assert_eq!(tokens.len(), 3);
+
+    let bare = format!(r#"[{{ "mint": "{mint}", "symbol": "FAKE", "decimals": 2 }}]"#);
+    tokens.extend(TokenRegistry::from_json(&bare).unwrap());
+    assert_eq!(tokens.by_mint(&mint).unwrap().symbol, "FAKE");
+    assert!(tokens.get("USDC").is_none());
+}
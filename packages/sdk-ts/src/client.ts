@@ -13,7 +13,9 @@ import {
   type Commitment,
 } from '@solana/web3.js';
 import { parsePool, parsePosition, parseTokenAmount } from './state';
-import { computeAmountB, pendingFeesForPosition, simulateDetailed } from './math';
+import {
+  computeAmountB, pendingFeesForPosition, simulateDetailed, simulateProvideDetailed, simulateRemoveDetailed,
+} from './math';
 import {
   ASSOCIATED_TOKEN_PROGRAM_ID,
   TOKEN_PROGRAM_ID,
@@ -26,6 +28,7 @@ import {
   deriveTreasury,
   initializePoolIx,
   provideLiquidityIx,
+  provideLiquidityProportionalIx,
   removeLiquidityIx,
   swapIx,
 } from './instructions';
@@ -33,6 +36,7 @@ import type {
   ClaimFeesResult,
   CreatePoolParams,
   CreatePoolResult,
+  CurveKind,
   FeeSummary,
   PoolInfo,
   PositionInfo,
@@ -41,6 +45,8 @@ import type {
   RemoveLiquidityParams,
   RemoveLiquidityResult,
   SimulateParams,
+  SimulateProvideResult,
+  SimulateRemoveResult,
   SimulateResult,
   SwapParams,
   SwapResult,
@@ -165,7 +171,8 @@ export class A2ASwapClient {
   // ── Write operations ──────────────────────────────────────────────────────
 
   /**
-   * Create a new constant-product pool.
+   * Create a new pool — constant-product unless `params.curve` asks for
+   * StableSwap.
    *
    * Fresh keypairs for `vaultA` and `vaultB` are generated internally —
    * no need to provide them.
@@ -177,6 +184,7 @@ export class A2ASwapClient {
     params: CreatePoolParams,
   ): Promise<CreatePoolResult> {
     const signer = this.resolveSigner(payer);
+    const curve: CurveKind = params.curve ?? { constantProduct: {} };
 
     const vaultA = Keypair.generate();
     const vaultB = Keypair.generate();
@@ -191,6 +199,7 @@ export class A2ASwapClient {
       vaultA.publicKey,
       vaultB.publicKey,
       params.feeRateBps,
+      curve,
     );
     const sig = await this.signAndSend([ix], signer, [vaultA, vaultB]);
 
@@ -203,6 +212,7 @@ export class A2ASwapClient {
       mintA:         params.mintA,
       mintB:         params.mintB,
       feeRateBps:    params.feeRateBps,
+      curve,
     };
   }
 
@@ -210,8 +220,12 @@ export class A2ASwapClient {
    * Deposit tokens into a pool and receive LP shares.
    *
    * The pool is auto-discovered (both mint orderings are tried).
-   * If `params.amountB` is `undefined`, the SDK fetches live reserves and
-   * computes the proportional amount automatically.
+   * If `params.amountB` is `undefined`, it sends
+   * `provide_liquidity_proportional` and the program computes the other side
+   * from its reserves when the transaction lands; the returned amounts are
+   * the estimate at the reserves read before sending. `amountAMax` /
+   * `amountBMax` are checked against that estimate, and the program checks
+   * the side it computes again; explicit amounts are exact.
    *
    * @param payer - Agent keypair funding the deposit.
    */
@@ -235,6 +249,7 @@ export class A2ASwapClient {
     // aToB = false → params.mintA is pool.tokenBMint
     let amountPoolA: bigint, amountPoolB: bigint;
     let ataPoolA: PublicKey, ataPoolB: PublicKey;
+    let maxPoolA: bigint | undefined, maxPoolB: bigint | undefined;
 
     if (aToB) {
       amountPoolA = params.amountA;
@@ -243,6 +258,7 @@ export class A2ASwapClient {
       );
       ataPoolA = deriveAta(signer.publicKey, params.mintA);
       ataPoolB = deriveAta(signer.publicKey, params.mintB);
+      [maxPoolA, maxPoolB] = [params.amountAMax, params.amountBMax];
     } else {
       // params.mintA = pool.tokenBMint; compute the pool.tokenAMint amount
       const poolAAmount = computeAmountB(
@@ -252,32 +268,63 @@ export class A2ASwapClient {
       amountPoolB = params.amountA;
       ataPoolA = deriveAta(signer.publicKey, params.mintB); // ata for pool.tokenAMint
       ataPoolB = deriveAta(signer.publicKey, params.mintA); // ata for pool.tokenBMint
+      [maxPoolA, maxPoolB] = [params.amountBMax, params.amountAMax];
+    }
+    if ((maxPoolA !== undefined && amountPoolA > maxPoolA) ||
+        (maxPoolB !== undefined && amountPoolB > maxPoolB)) {
+      throw new Error(
+        `Deposit exceeds max: needs ${amountPoolA} / ${amountPoolB}, caps ${maxPoolA ?? '-'} / ${maxPoolB ?? '-'}`,
+      );
     }
 
-    const ix = provideLiquidityIx(
-      this.programId,
-      signer.publicKey,
-      poolAddr,
-      poolAuthority,
-      position,
-      poolState.tokenAVault,
-      poolState.tokenBVault,
-      ataPoolA,
-      ataPoolB,
-      amountPoolA,
-      amountPoolB,
-      params.minLp ?? 0n,
-      params.autoCompound ?? false,
-      params.compoundThreshold ?? 0n,
-    );
+    const ix = params.amountB === undefined
+      ? provideLiquidityProportionalIx(
+        this.programId,
+        signer.publicKey,
+        poolAddr,
+        poolAuthority,
+        position,
+        poolState.tokenAVault,
+        poolState.tokenBVault,
+        ataPoolA,
+        ataPoolB,
+        params.amountA,
+        aToB,
+        (aToB ? maxPoolB : maxPoolA) ?? 0n,
+        params.minLp ?? 0n,
+        params.autoCompound ?? false,
+        params.compoundThreshold ?? 0n,
+      )
+      : provideLiquidityIx(
+        this.programId,
+        signer.publicKey,
+        poolAddr,
+        poolAuthority,
+        position,
+        poolState.tokenAVault,
+        poolState.tokenBVault,
+        ataPoolA,
+        ataPoolB,
+        amountPoolA,
+        amountPoolB,
+        params.minLp ?? 0n,
+        params.autoCompound ?? false,
+        params.compoundThreshold ?? 0n,
+      );
+    const before = await this.connection.getAccountInfo(position);
+    const lpBefore = before ? parsePosition(Buffer.from(before.data)).lpShares : 0n;
     const sig = await this.signAndSend([ix], signer, []);
+    const share = await this.fetchPositionShare(position, poolAddr);
 
     return {
-      signature: sig,
-      pool:      poolAddr,
+      signature:    sig,
+      pool:         poolAddr,
       position,
-      amountA:   amountPoolA,
-      amountB:   amountPoolB,
+      amountA:      amountPoolA,
+      amountB:      amountPoolB,
+      lpMinted:     share ? share.lpShares - lpBefore : undefined,
+      lpShares:     share?.lpShares,
+      poolSharePct: share?.poolSharePct,
     };
   }
 
@@ -337,6 +384,8 @@ export class A2ASwapClient {
       params.amountIn,
       minAmountOut,
       aToB,
+      0,
+      BigInt(params.deadlineUnix ?? 0),
     );
 
     const instructions: TransactionInstruction[] = [];
@@ -489,6 +538,34 @@ export class A2ASwapClient {
     return simulateDetailed(poolAddr, poolState, reserveIn, reserveOut, params.amountIn, aToB);
   }
 
+  /**
+   * Preview `provideLiquidity` with the same `params`: the amounts it would
+   * take (pool ordering), the LP shares it would mint, their share of the
+   * pool and the price either side. No transaction is sent.
+   */
+  async simulateProvide(params: ProvideParams): Promise<SimulateProvideResult> {
+    const { poolAddr, poolState, aToB } = await this.findPool(params.mintA, params.mintB);
+    const reserveA = await this.fetchTokenAmount(poolState.tokenAVault);
+    const reserveB = await this.fetchTokenAmount(poolState.tokenBVault);
+
+    const [amountA, amountB] = aToB
+      ? [params.amountA, computeAmountB(params.amountA, params.amountB, reserveA, reserveB, poolState.lpSupply)]
+      : [computeAmountB(params.amountA, params.amountB, reserveB, reserveA, poolState.lpSupply), params.amountA];
+    return simulateProvideDetailed(poolAddr, poolState, reserveA, reserveB, amountA, amountB);
+  }
+
+  /**
+   * Preview burning `lpShares` of the `mintA` / `mintB` pool (either
+   * ordering): the tokens returned, their share of the pool and the price
+   * after. No transaction is sent.
+   */
+  async simulateRemove(mintA: PublicKey, mintB: PublicKey, lpShares: bigint): Promise<SimulateRemoveResult> {
+    const { poolAddr, poolState } = await this.findPool(mintA, mintB);
+    const reserveA = await this.fetchTokenAmount(poolState.tokenAVault);
+    const reserveB = await this.fetchTokenAmount(poolState.tokenBVault);
+    return simulateRemoveDetailed(poolAddr, poolState, reserveA, reserveB, lpShares);
+  }
+
   /**
    * Fetch all Pool accounts deployed under this program.
    *
@@ -690,6 +767,27 @@ export class A2ASwapClient {
     return parseTokenAmount(Buffer.from(info.data));
   }
 
+  /**
+   * Read a position's LP shares and its percentage of the pool's LP supply.
+   * Best effort — `undefined` if either account can't be fetched or parsed,
+   * rather than failing a deposit that already landed.
+   */
+  private async fetchPositionShare(
+    position: PublicKey,
+    pool:     PublicKey,
+  ): Promise<{ lpShares: bigint; poolSharePct: number } | undefined> {
+    try {
+      const [posInfo, poolInfo] = await this.connection.getMultipleAccountsInfo([position, pool]);
+      if (!posInfo || !poolInfo) return undefined;
+      const { lpShares } = parsePosition(Buffer.from(posInfo.data));
+      const { lpSupply } = parsePool(Buffer.from(poolInfo.data));
+      const poolSharePct = lpSupply === 0n ? 0 : Number(lpShares) / Number(lpSupply) * 100;
+      return { lpShares, poolSharePct };
+    } catch {
+      return undefined;
+    }
+  }
+
   /**
    * Fetch all `Position` accounts owned by `owner` via `getProgramAccounts`.
    */
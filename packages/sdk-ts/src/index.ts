@@ -44,6 +44,7 @@ export type { A2ASwapConfig } from './client';
 
 // Types
 export type {
+  CurveKind,
   CreatePoolParams,
   CreatePoolResult,
   ProvideParams,
@@ -52,6 +53,8 @@ export type {
   SwapResult,
   SimulateParams,
   SimulateResult,
+  SimulateProvideResult,
+  SimulateRemoveResult,
   RemoveLiquidityParams,
   RemoveLiquidityResult,
   ClaimFeesResult,
@@ -66,12 +69,19 @@ export {
   derivePoolAuthority,
   derivePosition,
   deriveTreasury,
+  deriveDelegate,
   deriveAta,
   initializePoolIx,
   provideLiquidityIx,
+  provideLiquidityProportionalIx,
   removeLiquidityIx,
   claimFeesIx,
   swapIx,
+  approveAndExecuteIx,
+  createDelegateIx,
+  rotateDelegateIx,
+  revokeDelegateIx,
+  swapAsDelegateIx,
   instructionDisc,
   accountDisc,
 } from './instructions';
@@ -79,12 +89,16 @@ export {
 export {
   parsePool,
   parsePosition,
+  parseDelegate,
   parseTokenAmount,
 } from './state';
-export type { PoolState, PositionState } from './state';
+export type { PoolState, PositionState, DelegateState } from './state';
 
 export {
   simulateDetailed,
+  simulateProvideDetailed,
+  simulateRemoveDetailed,
+  lpSharesForDeposit,
   pendingFeesForPosition,
   computeAmountB,
   PROTOCOL_FEE_BPS,
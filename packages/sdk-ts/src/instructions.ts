@@ -18,6 +18,7 @@ import {
   TOKEN_PROGRAM_ID,
 } from '@solana/spl-token';
 import { createHash } from 'crypto';
+import type { CurveKind } from './types';
 
 // ─── Well-known constants ─────────────────────────────────────────────────────
 
@@ -59,6 +60,7 @@ const POOL_SEED           = Buffer.from('pool');
 const POSITION_SEED       = Buffer.from('position');
 const POOL_AUTHORITY_SEED = Buffer.from('pool_authority');
 const TREASURY_SEED       = Buffer.from('treasury');
+const DELEGATE_SEED       = Buffer.from('delegate');
 
 // ─── PDA derivation ───────────────────────────────────────────────────────────
 
@@ -99,6 +101,18 @@ export function deriveTreasury(programId: PublicKey): PublicKey {
   return PublicKey.findProgramAddressSync([TREASURY_SEED], programId)[0];
 }
 
+/** Derive the session-key `Delegate` PDA — one per owner token account. */
+export function deriveDelegate(
+  owner:        PublicKey,
+  tokenAccount: PublicKey,
+  programId:    PublicKey,
+): PublicKey {
+  return PublicKey.findProgramAddressSync(
+    [DELEGATE_SEED, owner.toBuffer(), tokenAccount.toBuffer()],
+    programId,
+  )[0];
+}
+
 /** Derive the Associated Token Account for a wallet + mint. */
 export function deriveAta(wallet: PublicKey, mint: PublicKey): PublicKey {
   return getAssociatedTokenAddressSync(mint, wallet, /* allowOwnerOffCurve */ true);
@@ -127,6 +141,9 @@ export function accountDisc(typeName: string): Buffer {
  *
  * `vaultA` and `vaultB` must be fresh keypairs.  Include them as additional
  * signers when submitting the transaction.
+ *
+ * Data: discriminator, `fee_rate_bps` (u16), then the Borsh `CurveKind` —
+ * variant byte 0 (ConstantProduct) or 1 (StableSwap) followed by `amp` (u64).
  */
 export function initializePoolIx(
   programId:   PublicKey,
@@ -136,13 +153,17 @@ export function initializePoolIx(
   vaultA:      PublicKey,
   vaultB:      PublicKey,
   feeRateBps:  number,
+  curve:       CurveKind = { constantProduct: {} },
 ): TransactionInstruction {
   const pool          = derivePool(mintA, mintB, programId);
   const poolAuthority = derivePoolAuthority(pool, programId);
 
-  const data = Buffer.alloc(8 + 2);
+  const amp  = 'stableSwap' in curve ? curve.stableSwap.amp : null;
+  const data = Buffer.alloc(8 + 2 + 1 + (amp === null ? 0 : 8));
   instructionDisc('initialize_pool').copy(data, 0);
   data.writeUInt16LE(feeRateBps, 8);
+  data.writeUInt8(amp === null ? 0 : 1, 10);
+  if (amp !== null) data.writeBigUInt64LE(amp, 11);
 
   const keys: AccountMeta[] = [
     { pubkey: creator,           isSigner: true,  isWritable: true  },
@@ -155,6 +176,7 @@ export function initializePoolIx(
     { pubkey: TOKEN_PROGRAM_ID,  isSigner: false, isWritable: false },
     { pubkey: SystemProgram.programId, isSigner: false, isWritable: false },
     { pubkey: SYSVAR_RENT_PUBKEY,      isSigner: false, isWritable: false },
+    { pubkey: deriveTreasury(programId), isSigner: false, isWritable: false }, // fee bounds, pause
   ];
 
   return new TransactionInstruction({ programId, keys, data });
@@ -205,6 +227,53 @@ export function provideLiquidityIx(
   return new TransactionInstruction({ programId, keys, data });
 }
 
+/**
+ * Build the `provide_liquidity_proportional` instruction: deposit `amount`
+ * of token A (`fixedA`) or token B, and let the program compute the other
+ * side from its reserves when the transaction lands, rounded up and capped
+ * by `maxOther` (0 = no cap). Same accounts as `provideLiquidityIx`.
+ *
+ * Byte layout (42 bytes total):
+ * - offset 0-7:   discriminator (sha256("global:provide_liquidity_proportional")[0..8])
+ * - offset 8-15:  amount (u64, little-endian)
+ * - offset 16:    fixed_a (bool)
+ * - offset 17-24: max_other (u64, little-endian)
+ * - offset 25-32: min_lp (u64, little-endian)
+ * - offset 33:    auto_compound (bool)
+ * - offset 34-41: compound_threshold (u64, little-endian)
+ */
+export function provideLiquidityProportionalIx(
+  programId:         PublicKey,
+  agent:             PublicKey,
+  pool:              PublicKey,
+  poolAuthority:     PublicKey,
+  position:          PublicKey,
+  vaultA:            PublicKey,
+  vaultB:            PublicKey,
+  agentTokenA:       PublicKey,
+  agentTokenB:       PublicKey,
+  amount:            bigint,
+  fixedA:            boolean,
+  maxOther:          bigint,
+  minLp:             bigint,
+  autoCompound:      boolean,
+  compoundThreshold: bigint,
+): TransactionInstruction {
+  const ix = provideLiquidityIx(
+    programId, agent, pool, poolAuthority, position, vaultA, vaultB, agentTokenA, agentTokenB,
+    amount, 0n, minLp, autoCompound, compoundThreshold,
+  );
+  const data = Buffer.alloc(42);
+  instructionDisc('provide_liquidity_proportional').copy(data, 0);
+  data.writeBigUInt64LE(amount,            8);
+  data.writeUInt8(fixedA ? 1 : 0,         16);
+  data.writeBigUInt64LE(maxOther,         17);
+  data.writeBigUInt64LE(minLp,            25);
+  data.writeUInt8(autoCompound ? 1 : 0,   33);
+  data.writeBigUInt64LE(compoundThreshold, 34);
+  return new TransactionInstruction({ programId, keys: ix.keys, data });
+}
+
 // ─── remove_liquidity ────────────────────────────────────────────────────────
 
 /** Build the `remove_liquidity` instruction. */
@@ -306,14 +375,17 @@ function validateSwapParams(amountIn: bigint, minAmountOut: bigint): void {
 /**
  * Build the `swap` instruction.
  *
- * Byte layout (25 bytes total):
+ * Byte layout (35 bytes total):
  * - offset 0-7:   discriminator (sha256("global:swap")[0..8])
  * - offset 8-15:  amount_in (u64, little-endian)
  * - offset 16-23: min_amount_out (u64, little-endian)
  * - offset 24:    a_to_b (bool: 1 = A→B, 0 = B→A)
+ * - offset 25-26: max_price_impact_bps (u16, little-endian; 0 = no cap)
+ * - offset 27-34: deadline_unix (i64, little-endian; 0 = no deadline)
  *
  * ⚠️ CRITICAL: Parameter order must match Anchor handler:
- *   handler(ctx, amount_in: u64, min_amount_out: u64, a_to_b: bool)
+ *   handler(ctx, amount_in: u64, min_amount_out: u64, a_to_b: bool,
+ *           max_price_impact_bps: u16, deadline_unix: i64)
  *   Wrong order causes cryptic SlippageExceeded errors.
  */
 export function swapIx(
@@ -330,16 +402,20 @@ export function swapIx(
   amountIn:        bigint,
   minAmountOut:    bigint,
   aToB:            boolean,
+  maxPriceImpactBps = 0,
+  deadlineUnix = 0n,
 ): TransactionInstruction {
   // Validate parameters before building instruction
   validateSwapParams(amountIn, minAmountOut);
 
-  // 8 disc + 8 + 8 + 1 = 25 bytes
-  const data = Buffer.alloc(25);
+  // 8 disc + 8 + 8 + 1 + 2 + 8 = 35 bytes
+  const data = Buffer.alloc(35);
   instructionDisc('swap').copy(data, 0);
   data.writeBigUInt64LE(amountIn,    8);
   data.writeBigUInt64LE(minAmountOut, 16);
   data.writeUInt8(aToB ? 1 : 0,     24);
+  data.writeUInt16LE(maxPriceImpactBps, 25);
+  data.writeBigInt64LE(deadlineUnix, 27);
 
   const keys: AccountMeta[] = [
     { pubkey: agent,            isSigner: true,  isWritable: true  },
@@ -356,3 +432,203 @@ export function swapIx(
 
   return new TransactionInstruction({ programId, keys, data });
 }
+
+/**
+ * Build the `approve_and_execute` instruction — a swap that `approver` must
+ * co-sign alongside `agent`.
+ *
+ * Byte layout (43 bytes total):
+ * - offset 0-7:   discriminator (sha256("global:approve_and_execute")[0..8])
+ * - offset 8-15:  amount_in (u64, little-endian)
+ * - offset 16-23: min_amount_out (u64, little-endian)
+ * - offset 24:    a_to_b (bool: 1 = A→B, 0 = B→A)
+ * - offset 25-32: expires_at_slot (u64, little-endian; 0 = no expiry)
+ * - offset 33-40: deadline_unix (i64, little-endian; 0 = no deadline)
+ * - offset 41-42: max_price_impact_bps (u16, little-endian; 0 = no cap)
+ *
+ * After `expiresAtSlot` the program rejects the swap with `ApprovalExpired`,
+ * and after `deadlineUnix` with `DeadlineExceeded`, so a signed approval
+ * can't be submitted later into a worse market. Past `maxPriceImpactBps`
+ * it fails with `PriceImpactExceeded`, as `swap` does.
+ */
+export function approveAndExecuteIx(
+  programId:       PublicKey,
+  agent:           PublicKey,
+  approver:        PublicKey,
+  pool:            PublicKey,
+  poolAuthority:   PublicKey,
+  vaultA:          PublicKey,
+  vaultB:          PublicKey,
+  agentTokenIn:    PublicKey,
+  agentTokenOut:   PublicKey,
+  treasury:        PublicKey,
+  treasuryTokenIn: PublicKey,
+  amountIn:        bigint,
+  minAmountOut:    bigint,
+  aToB:            boolean,
+  expiresAtSlot = 0n,
+  deadlineUnix = 0n,
+  maxPriceImpactBps = 0,
+): TransactionInstruction {
+  validateSwapParams(amountIn, minAmountOut);
+
+  // 8 disc + 8 + 8 + 1 + 8 + 8 + 2 = 43 bytes
+  const data = Buffer.alloc(43);
+  instructionDisc('approve_and_execute').copy(data, 0);
+  data.writeBigUInt64LE(amountIn,      8);
+  data.writeBigUInt64LE(minAmountOut,  16);
+  data.writeUInt8(aToB ? 1 : 0,        24);
+  data.writeBigUInt64LE(expiresAtSlot, 25);
+  data.writeBigInt64LE(deadlineUnix,   33);
+  data.writeUInt16LE(maxPriceImpactBps, 41);
+
+  const keys: AccountMeta[] = [
+    { pubkey: agent,            isSigner: true,  isWritable: true  },
+    { pubkey: approver,         isSigner: true,  isWritable: false },
+    { pubkey: pool,             isSigner: false, isWritable: true  },
+    { pubkey: poolAuthority,    isSigner: false, isWritable: false },
+    { pubkey: vaultA,           isSigner: false, isWritable: true  },
+    { pubkey: vaultB,           isSigner: false, isWritable: true  },
+    { pubkey: agentTokenIn,     isSigner: false, isWritable: true  },
+    { pubkey: agentTokenOut,    isSigner: false, isWritable: true  },
+    { pubkey: treasury,         isSigner: false, isWritable: false },
+    { pubkey: treasuryTokenIn,  isSigner: false, isWritable: true  },
+    { pubkey: TOKEN_PROGRAM_ID, isSigner: false, isWritable: false },
+  ];
+
+  return new TransactionInstruction({ programId, keys, data });
+}
+
+// ─── Session keys ─────────────────────────────────────────────────────────────
+
+/**
+ * Build `create_delegate` or `rotate_delegate` — both take
+ * `delegate(32) max_amount_in(u64) expires_at(i64)` after the discriminator.
+ */
+function delegateIx(
+  name:        'create_delegate' | 'rotate_delegate',
+  programId:   PublicKey,
+  owner:       PublicKey,
+  ownerToken:  PublicKey,
+  delegate:    PublicKey,
+  maxAmountIn: bigint,
+  expiresAt:   bigint,
+): TransactionInstruction {
+  // 8 disc + 32 + 8 + 8 = 56 bytes
+  const data = Buffer.alloc(56);
+  instructionDisc(name).copy(data, 0);
+  delegate.toBuffer().copy(data, 8);
+  data.writeBigUInt64LE(maxAmountIn, 40);
+  data.writeBigInt64LE(expiresAt,    48);
+
+  const create = name === 'create_delegate';
+  const keys: AccountMeta[] = [
+    { pubkey: owner,                                        isSigner: true,  isWritable: create },
+    { pubkey: deriveDelegate(owner, ownerToken, programId), isSigner: false, isWritable: true   },
+    { pubkey: ownerToken,                                   isSigner: false, isWritable: true   },
+    { pubkey: TOKEN_PROGRAM_ID,                             isSigner: false, isWritable: false  },
+  ];
+  if (create) {
+    keys.push({ pubkey: SystemProgram.programId, isSigner: false, isWritable: false });
+  }
+
+  return new TransactionInstruction({ programId, keys, data });
+}
+
+/**
+ * Build the `create_delegate` instruction: `owner` lets the session key
+ * `delegate` swap out of `ownerToken`, up to `maxAmountIn` in total, until
+ * the unix timestamp `expiresAt` (seconds).
+ */
+export function createDelegateIx(
+  programId:   PublicKey,
+  owner:       PublicKey,
+  ownerToken:  PublicKey,
+  delegate:    PublicKey,
+  maxAmountIn: bigint,
+  expiresAt:   bigint,
+): TransactionInstruction {
+  return delegateIx('create_delegate', programId, owner, ownerToken, delegate, maxAmountIn, expiresAt);
+}
+
+/**
+ * Build the `rotate_delegate` instruction: hand the session on `ownerToken`
+ * to `delegate` with a new cap and expiry. The spent counter resets.
+ */
+export function rotateDelegateIx(
+  programId:   PublicKey,
+  owner:       PublicKey,
+  ownerToken:  PublicKey,
+  delegate:    PublicKey,
+  maxAmountIn: bigint,
+  expiresAt:   bigint,
+): TransactionInstruction {
+  return delegateIx('rotate_delegate', programId, owner, ownerToken, delegate, maxAmountIn, expiresAt);
+}
+
+/**
+ * Build the `revoke_delegate` instruction: end the session on `ownerToken`
+ * and refund the Delegate account's rent to `owner`.
+ */
+export function revokeDelegateIx(
+  programId:  PublicKey,
+  owner:      PublicKey,
+  ownerToken: PublicKey,
+): TransactionInstruction {
+  const keys: AccountMeta[] = [
+    { pubkey: owner,                                        isSigner: true,  isWritable: true  },
+    { pubkey: deriveDelegate(owner, ownerToken, programId), isSigner: false, isWritable: true  },
+    { pubkey: ownerToken,                                   isSigner: false, isWritable: true  },
+    { pubkey: TOKEN_PROGRAM_ID,                             isSigner: false, isWritable: false },
+  ];
+  return new TransactionInstruction({ programId, keys, data: instructionDisc('revoke_delegate') });
+}
+
+/**
+ * Build the `swap_as_delegate` instruction — `swapIx` signed by the session
+ * key `delegate`, paid from `ownerTokenIn` (the Delegate's token account)
+ * into `ownerTokenOut`. Same 27-byte data layout as `swap`.
+ */
+export function swapAsDelegateIx(
+  programId:       PublicKey,
+  delegate:        PublicKey,
+  owner:           PublicKey,
+  pool:            PublicKey,
+  poolAuthority:   PublicKey,
+  vaultA:          PublicKey,
+  vaultB:          PublicKey,
+  ownerTokenIn:    PublicKey,
+  ownerTokenOut:   PublicKey,
+  treasury:        PublicKey,
+  treasuryTokenIn: PublicKey,
+  amountIn:        bigint,
+  minAmountOut:    bigint,
+  aToB:            boolean,
+  maxPriceImpactBps = 0,
+): TransactionInstruction {
+  validateSwapParams(amountIn, minAmountOut);
+
+  const data = Buffer.alloc(27);
+  instructionDisc('swap_as_delegate').copy(data, 0);
+  data.writeBigUInt64LE(amountIn,    8);
+  data.writeBigUInt64LE(minAmountOut, 16);
+  data.writeUInt8(aToB ? 1 : 0,     24);
+  data.writeUInt16LE(maxPriceImpactBps, 25);
+
+  const keys: AccountMeta[] = [
+    { pubkey: delegate,                                       isSigner: true,  isWritable: true  },
+    { pubkey: owner,                                          isSigner: false, isWritable: false },
+    { pubkey: deriveDelegate(owner, ownerTokenIn, programId), isSigner: false, isWritable: true  },
+    { pubkey: pool,                                           isSigner: false, isWritable: true  },
+    { pubkey: poolAuthority,                                  isSigner: false, isWritable: false },
+    { pubkey: vaultA,                                         isSigner: false, isWritable: true  },
+    { pubkey: vaultB,                                         isSigner: false, isWritable: true  },
+    { pubkey: ownerTokenIn,                                   isSigner: false, isWritable: true  },
+    { pubkey: ownerTokenOut,                                  isSigner: false, isWritable: true  },
+    { pubkey: treasury,                                       isSigner: false, isWritable: false },
+    { pubkey: treasuryTokenIn,                                isSigner: false, isWritable: true  },
+    { pubkey: TOKEN_PROGRAM_ID,                               isSigner: false, isWritable: false },
+  ];
+
+  return new TransactionInstruction({ programId, keys, data });
+}
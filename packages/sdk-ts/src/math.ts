@@ -7,7 +7,7 @@
 
 import type { PublicKey } from '@solana/web3.js';
 import type { PoolState, PositionState } from './state';
-import type { SimulateResult } from './types';
+import type { SimulateProvideResult, SimulateRemoveResult, SimulateResult } from './types';
 
 // ─── Constants ────────────────────────────────────────────────────────────────
 
@@ -67,6 +67,102 @@ export function simulateDetailed(
   };
 }
 
+// ─── Liquidity simulation ─────────────────────────────────────────────────────
+
+/** Integer square root, rounded down — the on-chain `isqrt`. */
+function isqrt(n: bigint): bigint {
+  if (n === 0n) return 0n;
+  let x = n;
+  let y = (x >> 1n) + (x & 1n);
+  while (y < x) {
+    x = y;
+    y = (y + n / y) >> 1n;
+  }
+  return x;
+}
+
+/** Token B per token A in raw units; 0 when either side is empty. */
+function spotPrice(reserveA: bigint, reserveB: bigint): number {
+  return reserveA === 0n || reserveB === 0n ? 0 : Number(reserveB) / Number(reserveA);
+}
+
+function sharePct(part: bigint, whole: bigint): number {
+  return whole === 0n ? 0 : Number(part) / Number(whole) * 100;
+}
+
+/**
+ * LP shares minted for depositing `amountA` / `amountB`: `isqrt(a × b)` for
+ * the first deposit, otherwise the smaller ratio against the reserves.
+ * Mirrors the on-chain `lp_shares_for_deposit`.
+ */
+export function lpSharesForDeposit(
+  amountA:  bigint,
+  amountB:  bigint,
+  lpSupply: bigint,
+  reserveA: bigint,
+  reserveB: bigint,
+): bigint {
+  if (lpSupply === 0n) return isqrt(amountA * amountB);
+  if (reserveA === 0n || reserveB === 0n) throw new Error('Pool has no liquidity');
+  const lpA = (amountA * lpSupply) / reserveA;
+  const lpB = (amountB * lpSupply) / reserveB;
+  return lpA < lpB ? lpA : lpB;
+}
+
+/**
+ * Expected outcome of depositing `amountA` / `amountB` (pool ordering) at
+ * reserves `reserveA` / `reserveB`.
+ */
+export function simulateProvideDetailed(
+  poolAddr: PublicKey,
+  pool:     PoolState,
+  reserveA: bigint,
+  reserveB: bigint,
+  amountA:  bigint,
+  amountB:  bigint,
+): SimulateProvideResult {
+  const lpMinted      = lpSharesForDeposit(amountA, amountB, pool.lpSupply, reserveA, reserveB);
+  const lpSupplyAfter = pool.lpSupply + lpMinted;
+  return {
+    pool:         poolAddr,
+    amountA,
+    amountB,
+    lpMinted,
+    lpSupplyAfter,
+    poolSharePct: sharePct(lpMinted, lpSupplyAfter),
+    priceBefore:  spotPrice(reserveA, reserveB),
+    priceAfter:   spotPrice(reserveA + amountA, reserveB + amountB),
+  };
+}
+
+/**
+ * Expected outcome of burning `lpShares` at reserves `reserveA` / `reserveB`,
+ * floored like the on-chain `remove_liquidity`.
+ */
+export function simulateRemoveDetailed(
+  poolAddr: PublicKey,
+  pool:     PoolState,
+  reserveA: bigint,
+  reserveB: bigint,
+  lpShares: bigint,
+): SimulateRemoveResult {
+  if (lpShares <= 0n || lpShares > pool.lpSupply) {
+    throw new Error(`lpShares must be between 1 and the pool's LP supply (${pool.lpSupply})`);
+  }
+  const amountA = (lpShares * reserveA) / pool.lpSupply;
+  const amountB = (lpShares * reserveB) / pool.lpSupply;
+  return {
+    pool:          poolAddr,
+    lpShares,
+    amountA,
+    amountB,
+    lpSupplyAfter: pool.lpSupply - lpShares,
+    poolSharePct:  sharePct(lpShares, pool.lpSupply),
+    priceBefore:   spotPrice(reserveA, reserveB),
+    priceAfter:    spotPrice(reserveA - amountA, reserveB - amountB),
+  };
+}
+
 // ─── Pending fees ─────────────────────────────────────────────────────────────
 
 /**
@@ -97,7 +193,7 @@ export function pendingFeesForPosition(
  * Compute proportional `amountB` for `provideLiquidity`.
  *
  * Returns `amountB` unchanged if provided; otherwise computes:
- * `amountB = amountA × reserveB / reserveA`.
+ * `amountB = ⌈amountA × reserveB / reserveA⌉`, rounded up like the program.
  *
  * Throws if the pool is empty and `amountB` is not provided.
  */
@@ -115,7 +211,8 @@ export function computeAmountB(
   if (reserveA === 0n) {
     throw new Error('Pool has no liquidity');
   }
-  const b = (amountA * reserveB) / reserveA;
+  // Rounded up, as provide_liquidity_proportional does on-chain.
+  const b = (amountA * reserveB + reserveA - 1n) / reserveA;
   if (b === 0n) {
     throw new Error('Computed amountB = 0 — deposit amountA is too small; pass amountB explicitly');
   }
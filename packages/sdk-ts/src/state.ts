@@ -95,6 +95,44 @@ export function parsePosition(data: Buffer): PositionState {
   };
 }
 
+// ─── Delegate ─────────────────────────────────────────────────────────────────
+
+/**
+ * Deserialized `Delegate` (session key) account.
+ *
+ * Layout (after 8-byte discriminator):
+ * ```
+ * owner(32)  delegate(32)  token_account(32)  max_amount_in(8)  spent(8)
+ * expires_at(8)  bump(1)  = 129 bytes
+ * ```
+ */
+export interface DelegateState {
+  owner: PublicKey;
+  /** Session key allowed to call `swap_as_delegate`. */
+  delegate: PublicKey;
+  /** Owner token account swaps are paid from. */
+  tokenAccount: PublicKey;
+  maxAmountIn: bigint;
+  spent: bigint;
+  /** Unix timestamp (seconds) after which the session is refused. */
+  expiresAt: bigint;
+}
+
+/** Deserialize a `Delegate` account from raw account data. */
+export function parseDelegate(data: Buffer): DelegateState {
+  if (data.length < 129) {
+    throw new Error(`Delegate account is ${data.length} bytes; expected at least 129`);
+  }
+  return {
+    owner:        new PublicKey(data.subarray(8, 40)),
+    delegate:     new PublicKey(data.subarray(40, 72)),
+    tokenAccount: new PublicKey(data.subarray(72, 104)),
+    maxAmountIn:  data.readBigUInt64LE(104),
+    spent:        data.readBigUInt64LE(112),
+    expiresAt:    data.readBigInt64LE(120),
+  };
+}
+
 // ─── SPL token account ────────────────────────────────────────────────────────
 
 /**
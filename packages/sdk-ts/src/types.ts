@@ -7,6 +7,15 @@ import type { PublicKey } from '@solana/web3.js';
 
 // ─── Input parameters ─────────────────────────────────────────────────────────
 
+/**
+ * Swap invariant, fixed when the pool is created — the program's `CurveKind`
+ * in Anchor's JSON form. `amp` is the StableSwap amplification coefficient
+ * (1–10 000).
+ */
+export type CurveKind =
+  | { constantProduct: Record<string, never> }
+  | { stableSwap: { amp: bigint } };
+
 /** Parameters for {@link A2ASwapClient.createPool}. */
 export interface CreatePoolParams {
   /** First token mint — defines "token A" in the pool PDA seed. */
@@ -18,6 +27,11 @@ export interface CreatePoolParams {
    * Typical: `30` (0.30%).
    */
   feeRateBps: number;
+  /**
+   * Swap curve. Default: `{ constantProduct: {} }`; use
+   * `{ stableSwap: { amp } }` for correlated pairs such as USDC/USDT.
+   */
+  curve?: CurveKind;
 }
 
 /** Parameters for {@link A2ASwapClient.provideLiquidity}. */
@@ -30,10 +44,18 @@ export interface ProvideParams {
   amountA: bigint;
   /**
    * Amount of token B to deposit.
-   * - `undefined` when the pool has liquidity: SDK auto-computes proportionally.
+   * - `undefined` when the pool has liquidity: the program computes it from
+   *   its reserves at execution (`provide_liquidity_proportional`).
    * - Set explicitly for the first deposit (this sets the initial price).
    */
   amountB?: bigint;
+  /** Most of `mintA` the deposit may take. Default: uncapped. */
+  amountAMax?: bigint;
+  /**
+   * Most of `mintB` the deposit may take, checked on-chain when the program
+   * computes `amountB` — guards it against reserves that moved. Default: uncapped.
+   */
+  amountBMax?: bigint;
   /** Re-invest accrued fees into LP shares instead of claiming them. Default: `false`. */
   autoCompound?: boolean;
   /**
@@ -58,6 +80,11 @@ export interface SwapParams {
    * `0` disables the slippage guard. Default: `50`.
    */
   maxSlippageBps?: number;
+  /**
+   * Last unix time (seconds) the swap may execute; the program rejects it
+   * afterwards with `DeadlineExceeded`. `0` disables it. Default: `0`.
+   */
+  deadlineUnix?: number;
 }
 
 /** Parameters for {@link A2ASwapClient.simulate}. */
@@ -87,6 +114,7 @@ export interface CreatePoolResult {
   mintA: PublicKey;
   mintB: PublicKey;
   feeRateBps: number;
+  curve: CurveKind;
 }
 
 /** Result of {@link A2ASwapClient.provideLiquidity}. */
@@ -101,6 +129,15 @@ export interface ProvideResult {
   amountA: bigint;
   /** Actual token B deposited (may differ if proportionally computed). */
   amountB: bigint;
+  /**
+   * LP shares this deposit minted, read back from the position after
+   * confirmation. `undefined` if the position or pool could not be fetched.
+   */
+  lpMinted?: bigint;
+  /** The position's total LP shares after the deposit. */
+  lpShares?: bigint;
+  /** The position's share of the pool's LP supply after the deposit, in percent. */
+  poolSharePct?: number;
 }
 
 /** Result of {@link A2ASwapClient.convert}. */
@@ -152,6 +189,49 @@ export interface SimulateResult {
   reserveOut: bigint;
 }
 
+/**
+ * Expected outcome of a deposit, from `simulateProvide`. Amounts are in pool
+ * ordering; prices are token A in token B (raw units), 0 for an empty pool.
+ */
+export interface SimulateProvideResult {
+  /** Pool the deposit would go to. */
+  pool: PublicKey;
+  /** Token A the deposit would take. */
+  amountA: bigint;
+  /** Token B the deposit would take (proportional when `amountB` was omitted). */
+  amountB: bigint;
+  /** LP shares the deposit would mint. */
+  lpMinted: bigint;
+  /** Pool LP supply after the deposit. */
+  lpSupplyAfter: bigint;
+  /** `lpMinted` as a percentage of `lpSupplyAfter`. */
+  poolSharePct: number;
+  /** Spot price before the deposit. */
+  priceBefore: number;
+  /** Spot price after the deposit. */
+  priceAfter: number;
+}
+
+/** Expected outcome of a withdrawal, from `simulateRemove`. Amounts in pool ordering. */
+export interface SimulateRemoveResult {
+  /** Pool the shares would be burned from. */
+  pool: PublicKey;
+  /** LP shares burned. */
+  lpShares: bigint;
+  /** Token A returned, rounded down as on-chain. */
+  amountA: bigint;
+  /** Token B returned, rounded down as on-chain. */
+  amountB: bigint;
+  /** Pool LP supply after the withdrawal. */
+  lpSupplyAfter: bigint;
+  /** `lpShares` as a percentage of the LP supply before it. */
+  poolSharePct: number;
+  /** Spot price before the withdrawal. */
+  priceBefore: number;
+  /** Spot price after the withdrawal; 0 once the pool is emptied. */
+  priceAfter: number;
+}
+
 /** Pool state snapshot from {@link A2ASwapClient.poolInfo}. */
 export interface PoolInfo {
   /** Pool PDA address. */
@@ -22,7 +22,7 @@ const CAPABILITY_CARD = {
   },
   capabilities: {
     streaming: false,
-    pushNotifications: false,
+    pushNotifications: true,
     autonomousExecution: true,
     approvalMode: true,
     autoCompound: true,
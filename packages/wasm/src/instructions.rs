@@ -0,0 +1,218 @@
+//! Instruction builders on raw key bytes.
+//!
+//! Account order, writability and signer flags come from the IDL
+//! ([`Instruction::ACCOUNTS`]) and data from the generated argument structs in
+//! [`a2a_swap_core::ix`], so these match the SDK's builders without a copy of
+//! its account lists.
+//!
+//! LP receipt NFTs are not supported here: their accounts are remaining
+//! accounts outside the IDL. Use the SDK or the TS SDK for receipts.
+
+use std::fmt;
+
+use a2a_swap_core::{ix, pda, pubkey, Instruction, Pool, Pubkey};
+use serde::Serialize;
+
+const SYSTEM_PROGRAM_ID: Pubkey = [0; 32];
+const RENT_SYSVAR_ID: Pubkey = pubkey("SysvarRent111111111111111111111111111111111");
+
+/// An instruction in the shape `@solana/web3.js`'s `TransactionInstruction`
+/// takes, with keys as base58 strings.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TransactionInstruction {
+    pub program_id: String,
+    pub keys:       Vec<AccountMeta>,
+    #[serde(with = "serde_bytes")]
+    pub data:       Vec<u8>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AccountMeta {
+    pub pubkey:      String,
+    pub is_signer:   bool,
+    pub is_writable: bool,
+}
+
+/// The input mint of a swap is neither of the pool's mints.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MintNotInPool;
+
+impl fmt::Display for MintNotInPool {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("mint_in is neither of the pool's mints")
+    }
+}
+
+impl std::error::Error for MintNotInPool {}
+
+/// `args` with `accounts` in IDL order; trailing optional accounts may be left
+/// out.
+fn instruction<I: Instruction>(program_id: &Pubkey, args: &I, accounts: &[Pubkey]) -> TransactionInstruction {
+    debug_assert!(accounts.len() <= I::ACCOUNTS.len(), "too many accounts for {}", I::NAME);
+    debug_assert!(
+        I::ACCOUNTS[accounts.len()..].iter().all(|a| a.optional),
+        "missing required accounts for {}",
+        I::NAME,
+    );
+    TransactionInstruction {
+        program_id: b58(program_id),
+        keys: I::ACCOUNTS
+            .iter()
+            .zip(accounts)
+            .map(|(spec, key)| AccountMeta {
+                pubkey:      b58(key),
+                is_signer:   spec.signer,
+                is_writable: spec.writable,
+            })
+            .collect(),
+        data: args.data(),
+    }
+}
+
+pub(crate) fn b58(key: &Pubkey) -> String {
+    bs58::encode(key).into_string()
+}
+
+// ─── Swap ─────────────────────────────────────────────────────────────────────
+
+/// Build `swap` for `amount_in` of `mint_in` on the pool at `pool_address`.
+/// Token accounts are the agent's, the treasury's and (if given) the
+/// referrer's associated token accounts.
+#[allow(clippy::too_many_arguments)]
+pub fn swap(
+    program_id:           &Pubkey,
+    agent:                &Pubkey,
+    pool_address:         &Pubkey,
+    pool:                 &Pool,
+    mint_in:              &Pubkey,
+    amount_in:            u64,
+    min_amount_out:       u64,
+    max_price_impact_bps: u16,
+    deadline_unix:        i64,
+    referrer:             Option<&Pubkey>,
+) -> Result<TransactionInstruction, MintNotInPool> {
+    let a_to_b = if *mint_in == pool.token_a_mint {
+        true
+    } else if *mint_in == pool.token_b_mint {
+        false
+    } else {
+        return Err(MintNotInPool);
+    };
+    let mint_out = if a_to_b { pool.token_b_mint } else { pool.token_a_mint };
+    let (pool_authority, _) = pda::derive_pool_authority(pool_address, program_id);
+    let (treasury, _) = pda::derive_treasury(program_id);
+
+    let mut accounts = vec![
+        *agent,
+        *pool_address,
+        pool_authority,
+        pool.token_a_vault,
+        pool.token_b_vault,
+        pda::derive_ata(agent, mint_in),
+        pda::derive_ata(agent, &mint_out),
+        treasury,
+        pda::derive_ata(&treasury, mint_in),
+        pda::TOKEN_PROGRAM_ID,
+    ];
+    if let Some(referrer) = referrer {
+        accounts.push(pda::derive_ata(referrer, mint_in));
+    }
+    let args = ix::Swap { amount_in, min_amount_out, a_to_b, max_price_impact_bps, deadline_unix };
+    Ok(instruction(program_id, &args, &accounts))
+}
+
+// ─── Liquidity ────────────────────────────────────────────────────────────────
+
+/// Accounts `provide_liquidity`, `remove_liquidity` and `claim_fees` share,
+/// in their IDL order.
+fn liquidity_accounts(program_id: &Pubkey, agent: &Pubkey, pool_address: &Pubkey, pool: &Pool) -> [Pubkey; 9] {
+    let (pool_authority, _) = pda::derive_pool_authority(pool_address, program_id);
+    let (position, _) = pda::derive_position(pool_address, agent, program_id);
+    [
+        *agent,
+        *pool_address,
+        pool_authority,
+        position,
+        pool.token_a_vault,
+        pool.token_b_vault,
+        pda::derive_ata(agent, &pool.token_a_mint),
+        pda::derive_ata(agent, &pool.token_b_mint),
+        pda::TOKEN_PROGRAM_ID,
+    ]
+}
+
+/// Build `provide_liquidity` for the agent's position in the pool.
+#[allow(clippy::too_many_arguments)]
+pub fn provide_liquidity(
+    program_id:         &Pubkey,
+    agent:              &Pubkey,
+    pool_address:       &Pubkey,
+    pool:               &Pool,
+    amount_a:           u64,
+    amount_b:           u64,
+    min_lp:             u64,
+    auto_compound:      bool,
+    compound_threshold: u64,
+) -> TransactionInstruction {
+    let mut accounts = liquidity_accounts(program_id, agent, pool_address, pool).to_vec();
+    accounts.extend([SYSTEM_PROGRAM_ID, RENT_SYSVAR_ID]);
+    let args = ix::ProvideLiquidity { amount_a, amount_b, min_lp, auto_compound, compound_threshold };
+    instruction(program_id, &args, &accounts)
+}
+
+/// Build `provide_liquidity_proportional`: deposit `amount` of token A
+/// (`fixed_a`) or token B and let the program size the other side.
+#[allow(clippy::too_many_arguments)]
+pub fn provide_liquidity_proportional(
+    program_id:         &Pubkey,
+    agent:              &Pubkey,
+    pool_address:       &Pubkey,
+    pool:               &Pool,
+    amount:             u64,
+    fixed_a:            bool,
+    max_other:          u64,
+    min_lp:             u64,
+    auto_compound:      bool,
+    compound_threshold: u64,
+) -> TransactionInstruction {
+    let mut accounts = liquidity_accounts(program_id, agent, pool_address, pool).to_vec();
+    accounts.extend([SYSTEM_PROGRAM_ID, RENT_SYSVAR_ID]);
+    let args = ix::ProvideLiquidityProportional {
+        amount, fixed_a, max_other, min_lp, auto_compound, compound_threshold,
+    };
+    instruction(program_id, &args, &accounts)
+}
+
+/// Build `remove_liquidity` burning `lp_shares` of the agent's position.
+pub fn remove_liquidity(
+    program_id:   &Pubkey,
+    agent:        &Pubkey,
+    pool_address: &Pubkey,
+    pool:         &Pool,
+    lp_shares:    u64,
+    min_a:        u64,
+    min_b:        u64,
+) -> TransactionInstruction {
+    let accounts = liquidity_accounts(program_id, agent, pool_address, pool);
+    instruction(program_id, &ix::RemoveLiquidity { lp_shares, min_a, min_b }, &accounts)
+}
+
+/// Build `claim_fees` for the agent's position.
+pub fn claim_fees(program_id: &Pubkey, agent: &Pubkey, pool_address: &Pubkey, pool: &Pool) -> TransactionInstruction {
+    let accounts = liquidity_accounts(program_id, agent, pool_address, pool);
+    instruction(program_id, &ix::ClaimFees, &accounts)
+}
+
+// ─── Migrations ───────────────────────────────────────────────────────────────
+
+/// Build `migrate_pool`, growing an older pool to the current layout.
+pub fn migrate_pool(program_id: &Pubkey, payer: &Pubkey, pool_address: &Pubkey) -> TransactionInstruction {
+    instruction(program_id, &ix::MigratePool, &[*payer, *pool_address, SYSTEM_PROGRAM_ID])
+}
+
+/// Build `migrate_position`, growing an older position to the current layout.
+pub fn migrate_position(program_id: &Pubkey, payer: &Pubkey, position: &Pubkey) -> TransactionInstruction {
+    instruction(program_id, &ix::MigratePosition, &[*payer, *position, SYSTEM_PROGRAM_ID])
+}
@@ -0,0 +1,442 @@
+//! A2A-Swap for JavaScript, via wasm-bindgen.
+//!
+//! Quote swaps, derive PDAs and build instructions locally — in a browser
+//! extension, a Node agent or any other wasm host — without calling the
+//! hosted API. Everything runs on [`a2a_swap_core`], the layouts, fee math and
+//! PDA derivation the Rust SDK itself uses, so quotes match the SDK's and
+//! instructions match its builders.
+//!
+//! ```text
+//! wasm-pack build packages/wasm --target web      # or nodejs / bundler
+//! ```
+//!
+//! ```js
+//! import init, { derivePool, simulate, swapInstruction } from 'a2a-swap-wasm';
+//!
+//! await init();
+//! const { address } = derivePool(SOL, USDC);
+//! const poolData    = (await connection.getAccountInfo(new PublicKey(address))).data;
+//! const quote       = simulate(poolData, reserveIn, reserveOut, 1_000_000_000n);
+//! const ix          = swapInstruction(agent, address, poolData, SOL, 1_000_000_000n,
+//!                                     quote.estimatedOut * 995n / 1000n);
+//! ```
+//!
+//! Amounts are `bigint`; keys are base58 strings. Instructions come back as
+//! `{ programId, keys: [{ pubkey, isSigner, isWritable }], data: Uint8Array }`
+//! — map the key strings to `PublicKey`s to pass them to `@solana/web3.js`.
+//! Every function that takes a trailing `programId` defaults it to the
+//! mainnet deployment.
+
+use a2a_swap_core::{math, pda, Account as _, CurveKind, Pool, Position, Pubkey, PROGRAM_ADDRESS};
+use serde::Serialize;
+use wasm_bindgen::prelude::*;
+
+pub mod instructions;
+
+use instructions::b58;
+
+// ─── Conversions ──────────────────────────────────────────────────────────────
+
+fn to_js<T: Serialize>(value: &T) -> Result<JsValue, JsError> {
+    let serializer = serde_wasm_bindgen::Serializer::new().serialize_large_number_types_as_bigints(true);
+    Ok(value.serialize(&serializer)?)
+}
+
+fn key(b58: &str, what: &str) -> Result<Pubkey, JsError> {
+    bs58::decode(b58)
+        .into_vec()
+        .ok()
+        .and_then(|v| Pubkey::try_from(v).ok())
+        .ok_or_else(|| JsError::new(&format!("invalid {what}: {b58}")))
+}
+
+fn program(program_id: Option<String>) -> Result<Pubkey, JsError> {
+    program_id.map_or(Ok(PROGRAM_ADDRESS), |p| key(&p, "programId"))
+}
+
+/// Pools from before curves existed are at least 212 bytes; see
+/// `a2a_swap_core`'s forward-compatibility notes. Layouts from before
+/// `reserves_tracked` (404 bytes or less) get it as `migrate_pool` sets it.
+fn pool(data: &[u8]) -> Result<Pool, JsError> {
+    if data.len() < 212 {
+        return Err(JsError::new(&format!("pool account too short: {} bytes", data.len())));
+    }
+    let mut pool = Pool::from_account_data(data)?;
+    if data.len() <= 404 {
+        pool.reserves_tracked = pool.reserve_a != 0 || pool.reserve_b != 0 || pool.lp_supply == 0;
+    }
+    Ok(pool)
+}
+
+// ─── Accounts ─────────────────────────────────────────────────────────────────
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct PoolView {
+    token_a_mint:        String,
+    token_b_mint:        String,
+    token_a_vault:       String,
+    token_b_vault:       String,
+    lp_supply:           u64,
+    fee_rate_bps:        u16,
+    fee_growth_global_a: u128,
+    fee_growth_global_b: u128,
+    /// `null` for constant-product pools.
+    stable_amp:          Option<u64>,
+    min_fee_bps:         u16,
+    max_fee_bps:         u16,
+    volume_a:            u64,
+    volume_b:            u64,
+    fees_collected_a:    u64,
+    fees_collected_b:    u64,
+    version:             u8,
+    /// Tracked reserves swaps price against once `reservesTracked`; until
+    /// then the vault balances apply instead.
+    reserve_a:           u64,
+    reserve_b:           u64,
+    reserves_tracked:    bool,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct PositionView {
+    owner:                   String,
+    pool:                    String,
+    lp_shares:               u64,
+    fee_growth_checkpoint_a: u128,
+    fee_growth_checkpoint_b: u128,
+    fees_owed_a:             u64,
+    fees_owed_b:             u64,
+    auto_compound:           bool,
+    compound_threshold:      u64,
+    version:                 u8,
+}
+
+/// The mainnet program ID.
+#[wasm_bindgen(js_name = programId)]
+pub fn program_id() -> String {
+    a2a_swap_core::PROGRAM_ID.to_string()
+}
+
+/// Decode a `Pool` account's data.
+#[wasm_bindgen(js_name = decodePool)]
+pub fn decode_pool(data: &[u8]) -> Result<JsValue, JsError> {
+    let p = pool(data)?;
+    to_js(&PoolView {
+        token_a_mint:        b58(&p.token_a_mint),
+        token_b_mint:        b58(&p.token_b_mint),
+        token_a_vault:       b58(&p.token_a_vault),
+        token_b_vault:       b58(&p.token_b_vault),
+        lp_supply:           p.lp_supply,
+        fee_rate_bps:        p.fee_rate_bps,
+        fee_growth_global_a: p.fee_growth_global_a,
+        fee_growth_global_b: p.fee_growth_global_b,
+        stable_amp:          match p.curve {
+            CurveKind::ConstantProduct    => None,
+            CurveKind::StableSwap { amp } => Some(amp),
+        },
+        min_fee_bps:         p.dynamic_fee.min_fee_bps,
+        max_fee_bps:         p.dynamic_fee.max_fee_bps,
+        volume_a:            p.volume_a,
+        volume_b:            p.volume_b,
+        fees_collected_a:    p.fees_collected_a,
+        fees_collected_b:    p.fees_collected_b,
+        version:             p.version,
+        reserve_a:           p.reserve_a,
+        reserve_b:           p.reserve_b,
+        reserves_tracked:    p.reserves_tracked,
+    })
+}
+
+/// Decode a `Position` account's data.
+#[wasm_bindgen(js_name = decodePosition)]
+pub fn decode_position(data: &[u8]) -> Result<JsValue, JsError> {
+    if data.len() < 138 {
+        return Err(JsError::new(&format!("position account too short: {} bytes", data.len())));
+    }
+    let p = Position::from_account_data(data)?;
+    to_js(&PositionView {
+        owner:                   b58(&p.owner),
+        pool:                    b58(&p.pool),
+        lp_shares:               p.lp_shares,
+        fee_growth_checkpoint_a: p.fee_growth_checkpoint_a,
+        fee_growth_checkpoint_b: p.fee_growth_checkpoint_b,
+        fees_owed_a:             p.fees_owed_a,
+        fees_owed_b:             p.fees_owed_b,
+        auto_compound:           p.auto_compound,
+        compound_threshold:      p.compound_threshold,
+        version:                 p.version,
+    })
+}
+
+// ─── Math ─────────────────────────────────────────────────────────────────────
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SimulateView {
+    amount_in:        u64,
+    protocol_fee:     u64,
+    net_pool_input:   u64,
+    lp_fee:           u64,
+    after_fees:       u64,
+    estimated_out:    u64,
+    effective_rate:   f64,
+    price_impact_pct: f64,
+    /// LP fee charged, after any dynamic-fee premium.
+    fee_rate_bps:     u16,
+}
+
+/// Fee and slippage breakdown for swapping `amountIn` into a pool, as the
+/// SDK's `simulate_detailed` computes it.
+///
+/// `reserveIn` / `reserveOut` are the pool's `reserveA` / `reserveB` in swap
+/// direction, or the vault balances while both are 0.
+/// `protocolFeeBps` (out of 100 000) defaults to the built-in 20 — pass the
+/// live `ProtocolConfig` value when it exists. `now` (unix seconds) sets the
+/// clock for dynamic fees and defaults to the current time.
+#[wasm_bindgen]
+pub fn simulate(
+    pool_data:        &[u8],
+    reserve_in:       u64,
+    reserve_out:      u64,
+    amount_in:        u64,
+    protocol_fee_bps: Option<u64>,
+    now:              Option<i64>,
+) -> Result<JsValue, JsError> {
+    let p = pool(pool_data)?;
+    let now = now.unwrap_or_else(|| (js_sys::Date::now() / 1000.0) as i64);
+    let fee_rate_bps = math::effective_fee_bps(&p, now);
+    let protocol_fee_bps = protocol_fee_bps.unwrap_or(math::PROTOCOL_FEE_BPS as u64);
+    let q = math::quote_swap(p.curve, fee_rate_bps, reserve_in, reserve_out, amount_in, protocol_fee_bps)?;
+    to_js(&SimulateView {
+        amount_in,
+        protocol_fee:     q.protocol_fee,
+        net_pool_input:   q.net_pool_input,
+        lp_fee:           q.lp_fee,
+        after_fees:       q.after_fees,
+        estimated_out:    q.amount_out,
+        effective_rate:   if amount_in == 0 { 0.0 } else { q.amount_out as f64 / amount_in as f64 },
+        price_impact_pct: q.price_impact_pct,
+        fee_rate_bps,
+    })
+}
+
+/// Smallest input that yields at least `amountOut` on a constant-product pool.
+#[wasm_bindgen(js_name = amountInForExactOut)]
+pub fn amount_in_for_exact_out(
+    reserve_in:       u64,
+    reserve_out:      u64,
+    fee_rate_bps:     u16,
+    protocol_fee_bps: u64,
+    amount_out:       u64,
+) -> Result<u64, JsError> {
+    Ok(math::amount_in_for_exact_out(reserve_in, reserve_out, fee_rate_bps, protocol_fee_bps, amount_out)?)
+}
+
+/// Fees `(a, b)` a position has accrued since it last synced with its pool.
+#[wasm_bindgen(js_name = pendingFees)]
+pub fn pending_fees(position_data: &[u8], pool_data: &[u8]) -> Result<Vec<u64>, JsError> {
+    let pos = Position::from_account_data(position_data)?;
+    let (a, b) = math::pending_fees_for_position(&pos, &pool(pool_data)?);
+    Ok(vec![a, b])
+}
+
+// ─── PDAs ─────────────────────────────────────────────────────────────────────
+
+#[derive(Serialize)]
+struct PdaView {
+    address: String,
+    bump:    u8,
+}
+
+fn pda_js((address, bump): (Pubkey, u8)) -> Result<JsValue, JsError> {
+    to_js(&PdaView { address: b58(&address), bump })
+}
+
+/// `{ address, bump }` of the pool for `mintA` / `mintB` in that order.
+/// Pools are not sorted by mint: try both orders.
+#[wasm_bindgen(js_name = derivePool)]
+pub fn derive_pool(mint_a: &str, mint_b: &str, program_id: Option<String>) -> Result<JsValue, JsError> {
+    pda_js(pda::derive_pool(&key(mint_a, "mintA")?, &key(mint_b, "mintB")?, &program(program_id)?))
+}
+
+/// `{ address, bump }` of the PDA that owns a pool's vaults.
+#[wasm_bindgen(js_name = derivePoolAuthority)]
+pub fn derive_pool_authority(pool: &str, program_id: Option<String>) -> Result<JsValue, JsError> {
+    pda_js(pda::derive_pool_authority(&key(pool, "pool")?, &program(program_id)?))
+}
+
+/// `{ address, bump }` of `owner`'s LP position in `pool`.
+#[wasm_bindgen(js_name = derivePosition)]
+pub fn derive_position(pool: &str, owner: &str, program_id: Option<String>) -> Result<JsValue, JsError> {
+    pda_js(pda::derive_position(&key(pool, "pool")?, &key(owner, "owner")?, &program(program_id)?))
+}
+
+/// `{ address, bump }` of the treasury, which also holds `ProtocolConfig`.
+#[wasm_bindgen(js_name = deriveTreasury)]
+pub fn derive_treasury(program_id: Option<String>) -> Result<JsValue, JsError> {
+    pda_js(pda::derive_treasury(&program(program_id)?))
+}
+
+/// Associated token account of `wallet` for `mint`.
+#[wasm_bindgen(js_name = deriveAta)]
+pub fn derive_ata(wallet: &str, mint: &str) -> Result<String, JsError> {
+    Ok(b58(&pda::derive_ata(&key(wallet, "wallet")?, &key(mint, "mint")?)))
+}
+
+// ─── Instructions ─────────────────────────────────────────────────────────────
+
+/// `swap` of `amountIn` of `mintIn` on `pool`, whose account data is
+/// `poolData`. `maxPriceImpactBps` defaults to 0 (no cap) and `deadlineUnix`,
+/// the last unix second the swap may execute in, to 0 (no deadline);
+/// `referrer`, a wallet, receives the referral share into its `mintIn` token
+/// account.
+///
+/// Pools below the current layout version must be migrated first — see
+/// [`migrate_pool_instruction`] and `decodePool(..).version`.
+#[allow(clippy::too_many_arguments)]
+#[wasm_bindgen(js_name = swapInstruction)]
+pub fn swap_instruction(
+    agent:                &str,
+    pool:                 &str,
+    pool_data:            &[u8],
+    mint_in:              &str,
+    amount_in:            u64,
+    min_amount_out:       u64,
+    max_price_impact_bps: Option<u16>,
+    deadline_unix:        Option<i64>,
+    referrer:             Option<String>,
+    program_id:           Option<String>,
+) -> Result<JsValue, JsError> {
+    let referrer = referrer.map(|r| key(&r, "referrer")).transpose()?;
+    let ix = instructions::swap(
+        &program(program_id)?,
+        &key(agent, "agent")?,
+        &key(pool, "pool")?,
+        &self::pool(pool_data)?,
+        &key(mint_in, "mintIn")?,
+        amount_in,
+        min_amount_out,
+        max_price_impact_bps.unwrap_or(0),
+        deadline_unix.unwrap_or(0),
+        referrer.as_ref(),
+    )?;
+    to_js(&ix)
+}
+
+/// `provide_liquidity` into `agent`'s position in `pool`. Without an LP
+/// receipt; `minLp` defaults to 0 and auto-compound to off.
+#[allow(clippy::too_many_arguments)]
+#[wasm_bindgen(js_name = provideLiquidityInstruction)]
+pub fn provide_liquidity_instruction(
+    agent:              &str,
+    pool:               &str,
+    pool_data:          &[u8],
+    amount_a:           u64,
+    amount_b:           u64,
+    min_lp:             Option<u64>,
+    auto_compound:      Option<bool>,
+    compound_threshold: Option<u64>,
+    program_id:         Option<String>,
+) -> Result<JsValue, JsError> {
+    to_js(&instructions::provide_liquidity(
+        &program(program_id)?,
+        &key(agent, "agent")?,
+        &key(pool, "pool")?,
+        &self::pool(pool_data)?,
+        amount_a,
+        amount_b,
+        min_lp.unwrap_or(0),
+        auto_compound.unwrap_or(false),
+        compound_threshold.unwrap_or(0),
+    ))
+}
+
+/// `provide_liquidity_proportional` into `agent`'s position in `pool`:
+/// `amount` of token A (`fixedA`, default true) or token B, with the other
+/// side computed on-chain and capped by `maxOther` (default 0 = uncapped).
+#[allow(clippy::too_many_arguments)]
+#[wasm_bindgen(js_name = provideLiquidityProportionalInstruction)]
+pub fn provide_liquidity_proportional_instruction(
+    agent:              &str,
+    pool:               &str,
+    pool_data:          &[u8],
+    amount:             u64,
+    fixed_a:            Option<bool>,
+    max_other:          Option<u64>,
+    min_lp:             Option<u64>,
+    auto_compound:      Option<bool>,
+    compound_threshold: Option<u64>,
+    program_id:         Option<String>,
+) -> Result<JsValue, JsError> {
+    to_js(&instructions::provide_liquidity_proportional(
+        &program(program_id)?,
+        &key(agent, "agent")?,
+        &key(pool, "pool")?,
+        &self::pool(pool_data)?,
+        amount,
+        fixed_a.unwrap_or(true),
+        max_other.unwrap_or(0),
+        min_lp.unwrap_or(0),
+        auto_compound.unwrap_or(false),
+        compound_threshold.unwrap_or(0),
+    ))
+}
+
+/// `remove_liquidity` of `lpShares` from `agent`'s position in `pool`.
+#[wasm_bindgen(js_name = removeLiquidityInstruction)]
+pub fn remove_liquidity_instruction(
+    agent:      &str,
+    pool:       &str,
+    pool_data:  &[u8],
+    lp_shares:  u64,
+    min_a:      Option<u64>,
+    min_b:      Option<u64>,
+    program_id: Option<String>,
+) -> Result<JsValue, JsError> {
+    to_js(&instructions::remove_liquidity(
+        &program(program_id)?,
+        &key(agent, "agent")?,
+        &key(pool, "pool")?,
+        &self::pool(pool_data)?,
+        lp_shares,
+        min_a.unwrap_or(0),
+        min_b.unwrap_or(0),
+    ))
+}
+
+/// `claim_fees` for `agent`'s position in `pool`.
+#[wasm_bindgen(js_name = claimFeesInstruction)]
+pub fn claim_fees_instruction(
+    agent:      &str,
+    pool:       &str,
+    pool_data:  &[u8],
+    program_id: Option<String>,
+) -> Result<JsValue, JsError> {
+    to_js(&instructions::claim_fees(
+        &program(program_id)?,
+        &key(agent, "agent")?,
+        &key(pool, "pool")?,
+        &self::pool(pool_data)?,
+    ))
+}
+
+/// `migrate_pool`: grow `pool` to the current layout, `payer` covering rent.
+#[wasm_bindgen(js_name = migratePoolInstruction)]
+pub fn migrate_pool_instruction(payer: &str, pool: &str, program_id: Option<String>) -> Result<JsValue, JsError> {
+    to_js(&instructions::migrate_pool(&program(program_id)?, &key(payer, "payer")?, &key(pool, "pool")?))
+}
+
+/// `migrate_position`: grow `position` to the current layout.
+#[wasm_bindgen(js_name = migratePositionInstruction)]
+pub fn migrate_position_instruction(
+    payer:      &str,
+    position:   &str,
+    program_id: Option<String>,
+) -> Result<JsValue, JsError> {
+    to_js(&instructions::migrate_position(
+        &program(program_id)?,
+        &key(payer, "payer")?,
+        &key(position, "position")?,
+    ))
+}
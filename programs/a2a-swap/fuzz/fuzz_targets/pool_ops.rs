@@ -0,0 +1,15 @@
+//! `cargo hfuzz run pool_ops` — see the crate docs for the invariants.
+
+use a2a_swap_fuzz::{run, Input};
+use arbitrary::{Arbitrary, Unstructured};
+use honggfuzz::fuzz;
+
+fn main() {
+    loop {
+        fuzz!(|data: &[u8]| {
+            if let Ok(input) = Input::arbitrary_take_rest(Unstructured::new(data)) {
+                run(input);
+            }
+        });
+    }
+}
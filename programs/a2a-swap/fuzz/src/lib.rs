@@ -0,0 +1,360 @@
+//! Stateful model of a constant-product / StableSwap pool for fuzzing.
+//!
+//! An [`Input`] is a pool config plus a sequence of [`Op`]s — deposits,
+//! withdrawals, swaps, fee claims, direct vault donations and `sync` —
+//! applied the way the instruction handlers apply them, through the
+//! program's own math (`compute_swap`, `record_swap_reserves`,
+//! `lp_shares_for_deposit`, `accrue_fees`, …). Token transfers become vault
+//! balance changes; an op that fails leaves the state untouched, as a failed
+//! transaction would.
+//!
+//! After every op [`Harness::check`] asserts the pool's invariants, and the
+//! run ends by withdrawing every position. Any panic is a finding:
+//!
+//! - `lp_supply` equals the sum of the positions' shares;
+//! - tracked reserves never exceed the vault balances, so every payout
+//!   the handlers compute can be transferred;
+//! - a swap never decreases the curve invariant;
+//! - a donation never moves the reserves a tracked pool prices against;
+//! - LPs never claim more fees than swaps paid in;
+//! - every position can always withdraw, and a fully withdrawn pool holds
+//!   no tracked reserves.
+//!
+//! Account constraints (vault mints and owners, the treasury ATA, signers)
+//! are enforced by Anchor before a handler runs and are covered by the
+//! integration tests in `tests/a2a-swap.ts`, not here.
+
+use a2a_swap::{
+    constants::{POOL_VERSION, POSITION_VERSION, PROTOCOL_FEE_BPS},
+    error::A2AError,
+    instructions::{
+        fee_math::{compute_swap, record_swap_reserves, record_swap_stats, stable_invariant},
+        provide_liquidity::{accrue_fees, lp_shares_for_deposit},
+        remove_liquidity::amounts_for_lp_shares,
+    },
+    state::{CircuitBreaker, CurveKind, DynamicFee, Pool, Position},
+};
+use anchor_lang::prelude::*;
+use arbitrary::Arbitrary;
+
+/// Independent LP positions the model tracks.
+pub const LPS: usize = 4;
+
+/// A fuzz case: the pool's config and the ops run against it.
+#[derive(Arbitrary, Debug)]
+pub struct Input {
+    /// LP fee, folded into 1–100 bps like `initialize_pool` requires
+    pub fee_rate_bps: u16,
+    /// StableSwap amplification, folded into MIN_AMP..=MAX_AMP;
+    /// `None` = constant product
+    pub amp: Option<u16>,
+    pub ops: Vec<Op>,
+}
+
+/// One instruction (or plain token transfer) against the pool.
+#[derive(Arbitrary, Debug, Clone, Copy)]
+pub enum Op {
+    Provide { lp: u8, amount_a: u64, amount_b: u64 },
+    Remove { lp: u8, lp_shares: u64 },
+    Swap { amount_in: u64, a_to_b: bool },
+    Claim { lp: u8 },
+    /// Tokens sent straight to a vault, bypassing the program
+    Donate { to_a: bool, amount: u64 },
+    Sync,
+}
+
+/// Pool, positions and vault balances as the handlers see them.
+#[derive(Clone)]
+pub struct Harness {
+    pub pool: Pool,
+    pub positions: [Position; LPS],
+    pub vault_a: u64,
+    pub vault_b: u64,
+    /// Fees paid out by `claim_fees`, per token
+    pub claimed_a: u64,
+    pub claimed_b: u64,
+}
+
+/// Run one fuzz case to completion, panicking on any broken invariant.
+pub fn run(input: Input) {
+    let curve = match input.amp {
+        Some(amp) => CurveKind::StableSwap { amp: amp as u64 % 10_000 + 1 },
+        None => CurveKind::ConstantProduct,
+    };
+    let mut h = Harness::new(input.fee_rate_bps % 100 + 1, curve);
+    for op in input.ops {
+        h.apply(op);
+    }
+    h.drain();
+}
+
+impl Harness {
+    pub fn new(fee_rate_bps: u16, curve: CurveKind) -> Self {
+        let pool = Pool {
+            authority:           Pubkey::new_unique(),
+            authority_bump:      254,
+            token_a_mint:        Pubkey::new_unique(),
+            token_b_mint:        Pubkey::new_unique(),
+            token_a_vault:       Pubkey::new_unique(),
+            token_b_vault:       Pubkey::new_unique(),
+            lp_supply:           0,
+            fee_rate_bps,
+            fee_growth_global_a: 0,
+            fee_growth_global_b: 0,
+            bump:                255,
+            curve,
+            dynamic_fee:         DynamicFee::default(),
+            volume_a:            0,
+            volume_b:            0,
+            fees_collected_a:    0,
+            fees_collected_b:    0,
+            version:             POOL_VERSION,
+            reserve_a:           0,
+            reserve_b:           0,
+            circuit_breaker:     CircuitBreaker::default(),
+            creator:             Pubkey::new_unique(),
+            allowlist_root:      [0; 32],
+            gate_mint:           Pubkey::default(),
+            open_positions:      0,
+            reserves_tracked:    true,
+        };
+        let position = Position {
+            owner:                   Pubkey::default(),
+            pool:                    Pubkey::default(),
+            lp_shares:               0,
+            fee_growth_checkpoint_a: 0,
+            fee_growth_checkpoint_b: 0,
+            fees_owed_a:             0,
+            fees_owed_b:             0,
+            auto_compound:           false,
+            compound_threshold:      0,
+            bump:                    0,
+            version:                 POSITION_VERSION,
+        };
+        Self {
+            pool,
+            positions: std::array::from_fn(|_| position.clone()),
+            vault_a: 0,
+            vault_b: 0,
+            claimed_a: 0,
+            claimed_b: 0,
+        }
+    }
+
+    /// Apply `op` if it succeeds, then check the invariants against the
+    /// state before it.
+    pub fn apply(&mut self, op: Op) {
+        let mut next = self.clone();
+        if next.step(op).is_ok() {
+            next.check(self, op);
+            *self = next;
+        }
+    }
+
+    /// The handler for `op`. May leave `self` half-updated on error —
+    /// [`apply`](Self::apply) discards it then.
+    fn step(&mut self, op: Op) -> Result<()> {
+        match op {
+            Op::Provide { lp, amount_a, amount_b } => self.provide(lp as usize % LPS, amount_a, amount_b),
+            Op::Remove { lp, lp_shares } => self.remove(lp as usize % LPS, lp_shares).map(|_| ()),
+            Op::Swap { amount_in, a_to_b } => self.swap(amount_in, a_to_b),
+            Op::Claim { lp } => self.claim(lp as usize % LPS),
+            Op::Donate { to_a, amount } => {
+                let vault = if to_a { &mut self.vault_a } else { &mut self.vault_b };
+                *vault = vault.checked_add(amount).ok_or(A2AError::MathOverflow)?;
+                Ok(())
+            }
+            Op::Sync => {
+                self.pool.set_reserves(self.vault_a, self.vault_b);
+                Ok(())
+            }
+        }
+    }
+
+    fn reserves(&self) -> (u64, u64) {
+        self.pool.reserves(self.vault_a, self.vault_b)
+    }
+
+    /// `provide_liquidity` (without the receipt NFT).
+    fn provide(&mut self, lp: usize, amount_a: u64, amount_b: u64) -> Result<()> {
+        require!(amount_a > 0 && amount_b > 0, A2AError::ZeroAmount);
+        let (reserve_a, reserve_b) = self.reserves();
+        let lp_minted =
+            lp_shares_for_deposit(amount_a, amount_b, self.pool.lp_supply, reserve_a, reserve_b)?;
+        require!(lp_minted > 0, A2AError::ZeroAmount);
+
+        let pos = &mut self.positions[lp];
+        if pos.lp_shares > 0 {
+            accrue_fees(pos, self.pool.fee_growth_global_a, self.pool.fee_growth_global_b)?;
+        } else {
+            pos.fee_growth_checkpoint_a = self.pool.fee_growth_global_a;
+            pos.fee_growth_checkpoint_b = self.pool.fee_growth_global_b;
+            pos.fees_owed_a = 0;
+            pos.fees_owed_b = 0;
+        }
+        pos.lp_shares = pos.lp_shares.checked_add(lp_minted).ok_or(A2AError::MathOverflow)?;
+
+        self.pool.lp_supply = self.pool.lp_supply.checked_add(lp_minted).ok_or(A2AError::MathOverflow)?;
+        self.pool.set_reserves(
+            reserve_a.checked_add(amount_a).ok_or(A2AError::MathOverflow)?,
+            reserve_b.checked_add(amount_b).ok_or(A2AError::MathOverflow)?,
+        );
+        self.vault_a = self.vault_a.checked_add(amount_a).ok_or(A2AError::MathOverflow)?;
+        self.vault_b = self.vault_b.checked_add(amount_b).ok_or(A2AError::MathOverflow)?;
+        Ok(())
+    }
+
+    /// `remove_liquidity` (without the receipt NFT); returns the amounts paid.
+    fn remove(&mut self, lp: usize, lp_shares: u64) -> Result<(u64, u64)> {
+        require!(lp_shares > 0, A2AError::ZeroAmount);
+        require!(self.positions[lp].lp_shares >= lp_shares, A2AError::InsufficientLiquidity);
+        let (reserve_a, reserve_b) = self.reserves();
+        let (amount_a, amount_b) =
+            amounts_for_lp_shares(lp_shares, self.pool.lp_supply, reserve_a, reserve_b)?;
+
+        let pos = &mut self.positions[lp];
+        accrue_fees(pos, self.pool.fee_growth_global_a, self.pool.fee_growth_global_b)?;
+        pos.lp_shares -= lp_shares;
+
+        self.pool.lp_supply -= lp_shares;
+        self.pool.set_reserves(reserve_a - amount_a, reserve_b - amount_b);
+        self.pay_out(amount_a, amount_b)?;
+        Ok((amount_a, amount_b))
+    }
+
+    /// `swap` with the default protocol fee and no referrer.
+    fn swap(&mut self, amount_in: u64, a_to_b: bool) -> Result<()> {
+        require!(amount_in > 0, A2AError::ZeroAmount);
+        let (reserve_a, reserve_b) = self.reserves();
+        require!(reserve_a > 0 && reserve_b > 0, A2AError::InsufficientLiquidity);
+        let (reserve_in, reserve_out) = if a_to_b {
+            (reserve_a as u128, reserve_b as u128)
+        } else {
+            (reserve_b as u128, reserve_a as u128)
+        };
+
+        let sa = compute_swap(
+            amount_in,
+            PROTOCOL_FEE_BPS,
+            self.pool.fee_rate_bps,
+            self.pool.curve,
+            reserve_in,
+            reserve_out,
+            self.pool.lp_supply,
+            0,
+        )?;
+        record_swap_stats(&mut self.pool, a_to_b, amount_in, sa.lp_fee);
+        record_swap_reserves(&mut self.pool, a_to_b, reserve_in, reserve_out, &sa)?;
+        let (vault_in, vault_out) = if a_to_b {
+            (&mut self.vault_a, &mut self.vault_b)
+        } else {
+            (&mut self.vault_b, &mut self.vault_a)
+        };
+        *vault_in = vault_in.checked_add(sa.net_pool_input).ok_or(A2AError::MathOverflow)?;
+        *vault_out = vault_out.checked_sub(sa.amount_out).ok_or(A2AError::InsufficientLiquidity)?;
+
+        let fee_growth = if a_to_b {
+            &mut self.pool.fee_growth_global_a
+        } else {
+            &mut self.pool.fee_growth_global_b
+        };
+        *fee_growth = fee_growth.saturating_add(sa.fee_growth_delta);
+        Ok(())
+    }
+
+    /// `claim_fees` for a position without auto-compound.
+    fn claim(&mut self, lp: usize) -> Result<()> {
+        let (reserve_a, reserve_b) = self.reserves();
+        let pos = &mut self.positions[lp];
+        accrue_fees(pos, self.pool.fee_growth_global_a, self.pool.fee_growth_global_b)?;
+        let (fees_a, fees_b) = (pos.fees_owed_a, pos.fees_owed_b);
+        pos.fees_owed_a = 0;
+        pos.fees_owed_b = 0;
+
+        self.pool.set_reserves(reserve_a.saturating_sub(fees_a), reserve_b.saturating_sub(fees_b));
+        self.pay_out(fees_a, fees_b)?;
+        self.claimed_a = self.claimed_a.saturating_add(fees_a);
+        self.claimed_b = self.claimed_b.saturating_add(fees_b);
+        Ok(())
+    }
+
+    /// PDA-signed vault transfers; fail like SPL Token does when short.
+    fn pay_out(&mut self, amount_a: u64, amount_b: u64) -> Result<()> {
+        self.vault_a = self.vault_a.checked_sub(amount_a).ok_or(A2AError::InsufficientLiquidity)?;
+        self.vault_b = self.vault_b.checked_sub(amount_b).ok_or(A2AError::InsufficientLiquidity)?;
+        Ok(())
+    }
+
+    /// Invariants after `op` took the pool from `prev` to `self`.
+    pub fn check(&self, prev: &Harness, op: Op) {
+        let shares: u64 = self.positions.iter().map(|p| p.lp_shares).sum();
+        assert_eq!(self.pool.lp_supply, shares, "lp_supply drifted after {op:?}");
+
+        if self.pool.reserves_tracked {
+            assert!(
+                self.pool.reserve_a <= self.vault_a && self.pool.reserve_b <= self.vault_b,
+                "tracked reserves ({}, {}) exceed vaults ({}, {}) after {op:?}",
+                self.pool.reserve_a, self.pool.reserve_b, self.vault_a, self.vault_b,
+            );
+        }
+
+        assert!(
+            self.claimed_a <= self.pool.fees_collected_a && self.claimed_b <= self.pool.fees_collected_b,
+            "claimed more fees than swaps paid after {op:?}",
+        );
+
+        match op {
+            Op::Swap { a_to_b, .. } => {
+                let (before_a, before_b) = prev.reserves();
+                let (after_a, after_b) = self.reserves();
+                match self.pool.curve {
+                    CurveKind::ConstantProduct => assert!(
+                        after_a as u128 * after_b as u128 >= before_a as u128 * before_b as u128,
+                        "k decreased on swap a_to_b={a_to_b}",
+                    ),
+                    CurveKind::StableSwap { amp } => {
+                        // Reserves the Newton solver can't handle are out of
+                        // scope; it stops within 1 of D, hence the slack.
+                        if let (Ok(d_before), Ok(d_after)) = (
+                            stable_invariant(before_a as u128, before_b as u128, amp),
+                            stable_invariant(after_a as u128, after_b as u128, amp),
+                        ) {
+                            assert!(
+                                d_after + 1 >= d_before,
+                                "D decreased on swap a_to_b={a_to_b}: {d_before} -> {d_after}",
+                            );
+                        }
+                    }
+                }
+            }
+            Op::Donate { .. } if prev.pool.reserves_tracked => {
+                assert_eq!(prev.reserves(), self.reserves(), "donation moved tracked reserves");
+            }
+            _ => {}
+        }
+    }
+
+    /// Withdraw every position; each must succeed.
+    pub fn drain(&mut self) {
+        let funded = self.pool.lp_supply > 0;
+        for lp in 0..LPS {
+            let lp_shares = self.positions[lp].lp_shares;
+            if lp_shares == 0 {
+                continue;
+            }
+            let prev = self.clone();
+            if let Err(e) = self.remove(lp, lp_shares) {
+                panic!("position {lp} can't withdraw {lp_shares} shares: {e}");
+            }
+            self.check(&prev, Op::Remove { lp: lp as u8, lp_shares });
+        }
+        assert_eq!(self.pool.lp_supply, 0);
+        if funded {
+            assert_eq!(
+                (self.pool.reserve_a, self.pool.reserve_b),
+                (0, 0),
+                "fully withdrawn pool still tracks reserves",
+            );
+        }
+    }
+}
@@ -5,17 +5,98 @@ pub const POOL_SEED: &[u8] = b"pool";
 pub const POSITION_SEED: &[u8] = b"position";
 pub const POOL_AUTHORITY_SEED: &[u8] = b"pool_authority";
 pub const TREASURY_SEED: &[u8] = b"treasury";
+pub const RECEIPT_SEED: &[u8] = b"receipt";
+pub const RANGE_POOL_SEED: &[u8] = b"range_pool";
+pub const RANGE_POSITION_SEED: &[u8] = b"range_position";
+pub const DELEGATE_SEED: &[u8] = b"delegate";
+pub const SWAP_COMMITMENT_SEED: &[u8] = b"swap_commitment";
+pub const GOVERNANCE_SEED: &[u8] = b"governance";
+pub const PROPOSAL_SEED: &[u8] = b"proposal";
+pub const VOTE_SEED: &[u8] = b"vote";
+pub const VOTE_VAULT_SEED: &[u8] = b"vote_vault";
+pub const AGENT_VOLUME_SEED: &[u8] = b"agent_volume";
+pub const INTENT_NONCE_SEED: &[u8] = b"intent_nonce";
+/// Seed of the PDA, under an approved locker program, that signs
+/// `lock_position` / `unlock_position` CPIs
+pub const LOCK_AUTHORITY_SEED: &[u8] = b"lock_authority";
+pub const TRADER_PASS_SEED: &[u8] = b"trader_pass";
+
+/// Prefix of a permissioned pool's allowlist leaves: sha256(tag ‖ trader)
+pub const ALLOWLIST_LEAF_TAG: &[u8] = b"a2a-swap:allowlist";
+
+/// Account layout versions written to new and migrated `Pool` / `Position`
+/// accounts. Bump when appending fields; `migrate_pool` / `migrate_position`
+/// bring older accounts up to date. Accounts from before versioning read as 0.
+pub const POOL_VERSION: u8 = 8;
+pub const POSITION_VERSION: u8 = 2;
 
 /// Default LP fee: 0.30 %
 pub const FEE_RATE_DEFAULT_BPS: u16 = 30;
 
+/// Range a new pool's LP fee must fall in until governance sets its own
+pub const MIN_POOL_FEE_BPS: u16 = 1;
+pub const MAX_POOL_FEE_BPS: u16 = 100;
+
+/// Highest LP fee governance may allow new pools: 10%
+pub const POOL_FEE_CAP_BPS: u16 = 1_000;
+
+/// `ProtocolConfig::paused` bits. Withdrawals and fee claims are never paused.
+pub const PAUSE_SWAPS: u8 = 1 << 0;
+pub const PAUSE_POOL_CREATION: u8 = 1 << 1;
+pub const PAUSE_ALL: u8 = PAUSE_SWAPS | PAUSE_POOL_CREATION;
+
 /// Denominator for basis-point math (u128 to avoid up-cast noise)
 pub const BPS_DENOMINATOR: u128 = 10_000;
 
-/// Protocol fee: 0.02% (20 / 100_000)
+/// Protocol fee: 0.02% (20 / 100_000). Swaps use `ProtocolConfig::protocol_fee_bps`
+/// once the config is initialized; this is the default before that.
 pub const PROTOCOL_FEE_BPS: u64 = 20;
 pub const PROTOCOL_FEE_DENOMINATOR: u128 = 100_000;
 
+/// Highest protocol fee the admin can set: 1% (1_000 / 100_000)
+pub const MAX_PROTOCOL_FEE_BPS: u16 = 1_000;
+
+/// Share of the protocol fee paid to a swap's referrer: 20% (2_000 / BPS_DENOMINATOR).
+/// `ProtocolConfig::referral_share_bps` overrides it once initialized.
+pub const REFERRAL_SHARE_BPS: u64 = 2_000;
+
+/// Protocol fee discount tiers in `ProtocolConfig::fee_tiers`
+pub const FEE_TIER_COUNT: usize = 4;
+
+/// Programs that may lock positions, in `ProtocolConfig::lockers`
+pub const LOCKER_COUNT: usize = 4;
+
+/// Dynamic fees: accumulated price impact halves every this many seconds
+pub const VOLATILITY_HALF_LIFE_SECS: i64 = 300;
+
+/// Dynamic fees: bps of accumulated price impact per extra bps of LP fee
+pub const VOLATILITY_FEE_DIVISOR: u32 = 10;
+
+/// Commit-reveal swaps: a commitment must be revealed within this many
+/// slots of `commit_swap` (~60 s at 400 ms slots)
+pub const MAX_REVEAL_DELAY_SLOTS: u64 = 150;
+
+/// Prefix of the message an agent signs for `swap_with_intent`, so the
+/// signature can't be replayed as some other protocol's message
+pub const INTENT_DOMAIN: &[u8] = b"a2a-swap:swap_with_intent:v1";
+
+/// StableSwap amplification bounds (A)
+pub const MIN_AMP: u64 = 1;
+pub const MAX_AMP: u64 = 10_000;
+
+/// Newton iterations for the StableSwap invariant; converges in < 10 for sane pools
+pub const STABLE_MAX_ITERATIONS: usize = 64;
+
+/// Range pool tick bounds: price = 1.0001^tick, sqrt price kept in Q64.64
+pub const MIN_TICK: i32 = -443_636;
+pub const MAX_TICK: i32 = 443_636;
+
+/// Largest tick spacing a range pool may be created with
+pub const MAX_TICK_SPACING: u16 = 1_000;
+
+/// Initialized ticks a single range pool can hold (two per distinct range)
+pub const MAX_RANGE_TICKS: usize = 64;
+
 /// Q64.64 fixed-point scale (fee growth accumulators)
 pub const Q64: u128 = 1u128 << 64;
 
@@ -40,3 +121,10 @@ pub fn derive_molt_agent_pda(asset_key: &Pubkey) -> (Pubkey, u8) {
         &MOLT_EXECUTE_PROGRAM,
     )
 }
+
+/// Metaplex Core program — owns the optional LP receipt assets
+pub const MPL_CORE_PROGRAM: Pubkey = pubkey!("CoREENxT6tW1HoK8ypY1SxRMZTcVPm7R94rH4PZNhX7d");
+
+/// Receipt asset metadata; the URI is suffixed with the position address
+pub const RECEIPT_NAME: &str = "A2A-Swap LP Position";
+pub const RECEIPT_URI_BASE: &str = "https://a2a-swap-api.a2a-swap.workers.dev/receipt/";
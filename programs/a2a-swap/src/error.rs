@@ -10,7 +10,9 @@ pub enum A2AError {
     ZeroAmount,
     #[msg("Math overflow")]
     MathOverflow,
-    #[msg("Fee rate must be 1–100 bps")]
+    /// Pool fee outside the protocol's bounds (1–100 bps unless governance
+    /// changed them), or pool fee bounds that are out of order or above the cap
+    #[msg("Fee rate is outside the allowed bounds")]
     InvalidFeeRate,
     #[msg("Token mint does not match pool")]
     MintMismatch,
@@ -20,4 +22,125 @@ pub enum A2AError {
     /// Executor does not match derived Molt agent PDA
     #[msg("Executor does not match Molt agent PDA")]
     MoltAgentMismatch,
+    #[msg("Price impact exceeds the caller's cap")]
+    PriceImpactExceeded,
+    /// Receipt asset or Metaplex Core program account does not match
+    #[msg("Receipt accounts do not match the position")]
+    InvalidReceipt,
+    #[msg("StableSwap amplification must be 1–10000")]
+    InvalidAmplification,
+    /// StableSwap Newton iteration did not converge
+    #[msg("StableSwap invariant did not converge")]
+    CurveNotConverged,
+    /// Bounds out of order, off the pool's tick spacing, or beyond MIN/MAX_TICK
+    #[msg("Tick range is invalid for this pool")]
+    InvalidTickRange,
+    #[msg("Range pool has no free tick slots")]
+    TickCapacityExceeded,
+    /// Admin instructions require the program's upgrade authority
+    #[msg("Signer is not the protocol admin")]
+    Unauthorized,
+    #[msg("Protocol fee exceeds the 1% cap")]
+    InvalidProtocolFee,
+    #[msg("Referral share exceeds 100% of the protocol fee")]
+    InvalidReferralShare,
+    /// approve_and_execute landed after its `expires_at_slot`
+    #[msg("Approval has expired")]
+    ApprovalExpired,
+    /// swap_as_delegate after the delegate's `expires_at`, or a delegate
+    /// created with an expiry in the past
+    #[msg("Delegate has expired")]
+    DelegateExpired,
+    #[msg("Swap exceeds the delegate's remaining cap")]
+    DelegateCapExceeded,
+    #[msg("Signer is not this account's delegate")]
+    DelegateMismatch,
+    /// Swap fee destination is not the treasury PDA's associated token account
+    #[msg("Treasury token account is not the treasury's ATA")]
+    InvalidTreasuryAccount,
+    /// Exact-output swap would need more input than the caller's cap
+    #[msg("Required input exceeds max_amount_in")]
+    MaxInputExceeded,
+    /// Net price impact within the current slot exceeds the pool's circuit breaker
+    #[msg("Swap moves the price beyond the pool's per-slot limit")]
+    PriceMoveExceeded,
+    #[msg("Circuit breaker limit must be at most 10000 bps")]
+    InvalidPriceMoveLimit,
+    /// reveal_swap parameters or salt do not hash to the stored commitment
+    #[msg("Revealed swap does not match the commitment")]
+    CommitmentMismatch,
+    /// reveal_swap in the same slot as commit_swap
+    #[msg("Commitment cannot be revealed until a later slot")]
+    CommitmentNotReady,
+    /// reveal_swap more than MAX_REVEAL_DELAY_SLOTS after commit_swap
+    #[msg("Swap commitment has expired")]
+    CommitmentExpired,
+    /// close_position on a position that still has LP shares or owed fees
+    #[msg("Position still holds LP shares or unclaimed fees")]
+    PositionNotEmpty,
+    /// close_pool on a pool with LP shares, tracked reserves or open positions
+    #[msg("Pool still has LP shares, tracked reserves or open positions")]
+    PoolNotEmpty,
+    /// Swap or pool creation while governance has it switched off
+    #[msg("This operation is paused by governance")]
+    Paused,
+    /// Zero quorum or voting period, unknown pause bits, or a pool proposal
+    /// executed without its pool
+    #[msg("Invalid governance parameters")]
+    InvalidGovernanceParams,
+    #[msg("Voting on this proposal has closed")]
+    VotingClosed,
+    /// execute_proposal or withdraw_vote before the proposal's voting_ends_at
+    #[msg("Voting on this proposal is still open")]
+    VotingNotEnded,
+    /// Fewer votes for than against, or below quorum
+    #[msg("Proposal did not pass")]
+    ProposalNotPassed,
+    #[msg("Proposal has already been executed")]
+    ProposalAlreadyExecuted,
+    /// Fee tier index past FEE_TIER_COUNT, or a discount above 100%
+    #[msg("Invalid fee tier")]
+    InvalidFeeTier,
+    /// swap_with_intent after the intent's expires_at
+    #[msg("Swap intent has expired")]
+    IntentExpired,
+    /// Intent nonce is not the agent's IntentNonce::nonce (already used or skipped)
+    #[msg("Swap intent nonce does not match")]
+    InvalidIntentNonce,
+    /// No ed25519 verification of the agent's signature over this exact
+    /// intent right before swap_with_intent
+    #[msg("Swap intent signature is missing or does not match")]
+    InvalidIntentSignature,
+    /// remove_liquidity or close_position on a position an approved
+    /// program has locked as collateral
+    #[msg("Position is locked as collateral")]
+    PositionLocked,
+    /// lock_position from a program not in ProtocolConfig::lockers, or
+    /// unlock_position from a program other than the one that locked it
+    #[msg("Program may not lock or unlock this position")]
+    LockerNotApproved,
+    /// Swap on a permissioned pool without a TraderPass for the pool's
+    /// current allowlist
+    #[msg("Trader is not on the pool's allowlist")]
+    TraderNotAllowed,
+    /// register_trader with a merkle proof that doesn't lead to the pool's
+    /// allowlist root
+    #[msg("Allowlist proof does not match the pool's root")]
+    InvalidAllowlistProof,
+    /// Swap on a token-gated pool without a token account of the pool's
+    /// gate mint that the trader owns and holds a balance in
+    #[msg("Swap requires holding the pool's gate token")]
+    GateTokenRequired,
+    /// swap / approve_and_execute landing after the caller's
+    /// `deadline_unix`
+    #[msg("Swap deadline has passed")]
+    DeadlineExceeded,
+    /// provide_liquidity_proportional computing more of the other token than
+    /// the caller's `max_other`
+    #[msg("Deposit exceeds the caller's max amount")]
+    DepositExceedsMax,
+    /// swap_as_delegate or close_pool given a token account that isn't the
+    /// owner's / closer's
+    #[msg("Token account is not owned by the expected wallet")]
+    TokenOwnerMismatch,
 }
@@ -1,16 +1,95 @@
 #![allow(ambiguous_glob_reexports)]
 
 pub mod fee_math;
+pub mod range_math;
+pub mod receipt;
+pub mod allowlist;
 pub mod initialize_pool;
+pub mod migrate_pool;
+pub mod migrate_position;
+pub mod sync;
+pub mod configure_dynamic_fee;
+pub mod configure_circuit_breaker;
+pub mod close_pool;
+pub mod set_pool_allowlist;
+pub mod set_pool_gate;
+pub mod migrate_protocol_config;
+pub mod initialize_protocol_config;
+pub mod update_protocol_config;
+pub mod set_fee_tier;
+pub mod set_locker;
+pub mod collect_protocol_fees;
+pub mod initialize_governance;
+pub mod create_proposal;
+pub mod cast_vote;
+pub mod execute_proposal;
+pub mod withdraw_vote;
 pub mod provide_liquidity;
+pub mod provide_liquidity_proportional;
 pub mod remove_liquidity;
 pub mod claim_fees;
+pub mod update_position_settings;
+pub mod close_position;
+pub mod lock_position;
+pub mod unlock_position;
 pub mod swap;
+pub mod swap_exact_out;
+pub mod open_agent_volume;
+pub mod register_trader;
 pub mod approve_and_execute;
+pub mod create_delegate;
+pub mod rotate_delegate;
+pub mod revoke_delegate;
+pub mod swap_as_delegate;
+pub mod swap_with_intent;
+pub mod commit_swap;
+pub mod reveal_swap;
+pub mod cancel_swap_commitment;
+pub mod initialize_range_pool;
+pub mod provide_range_liquidity;
+pub mod remove_range_liquidity;
+pub mod swap_range;
 
 pub use initialize_pool::*;
+pub use migrate_pool::*;
+pub use migrate_position::*;
+pub use sync::*;
+pub use configure_dynamic_fee::*;
+pub use configure_circuit_breaker::*;
+pub use close_pool::*;
+pub use set_pool_allowlist::*;
+pub use set_pool_gate::*;
+pub use migrate_protocol_config::*;
+pub use initialize_protocol_config::*;
+pub use update_protocol_config::*;
+pub use set_fee_tier::*;
+pub use set_locker::*;
+pub use collect_protocol_fees::*;
+pub use initialize_governance::*;
+pub use create_proposal::*;
+pub use cast_vote::*;
+pub use execute_proposal::*;
+pub use withdraw_vote::*;
 pub use provide_liquidity::*;
 pub use remove_liquidity::*;
 pub use claim_fees::*;
+pub use update_position_settings::*;
+pub use close_position::*;
+pub use lock_position::*;
+pub use unlock_position::*;
 pub use swap::*;
+pub use open_agent_volume::*;
+pub use register_trader::*;
 pub use approve_and_execute::*;
+pub use create_delegate::*;
+pub use rotate_delegate::*;
+pub use revoke_delegate::*;
+pub use swap_as_delegate::*;
+pub use swap_with_intent::*;
+pub use commit_swap::*;
+pub use reveal_swap::*;
+pub use cancel_swap_commitment::*;
+pub use initialize_range_pool::*;
+pub use provide_range_liquidity::*;
+pub use remove_range_liquidity::*;
+pub use swap_range::*;
@@ -0,0 +1,42 @@
+use anchor_lang::prelude::*;
+use solana_sha256_hasher::hashv;
+use crate::{constants::*, error::A2AError, state::{Pool, TraderPass}};
+
+// ─── Permissioned pools ────────────────────────────────────────────────────
+// A pool's creator may restrict swaps to a set of traders by setting
+// `Pool::allowlist_root`, the root of a merkle tree over the traders:
+//
+//   leaf = sha256(ALLOWLIST_LEAF_TAG ‖ trader)
+//   node = sha256(min(left, right) ‖ max(left, right))
+//
+// Sorting each pair means a proof is just the sibling hashes, leaf to root.
+// Leaves hash 50 bytes and nodes 64, so a node can't pass for a leaf.
+// Traders prove membership once with `register_trader`, which stores a
+// TraderPass; the swaps check the pass against the current root.
+
+/// Leaf of `trader` in an allowlist tree.
+pub fn allowlist_leaf(trader: &Pubkey) -> [u8; 32] {
+    hashv(&[ALLOWLIST_LEAF_TAG, trader.as_ref()]).to_bytes()
+}
+
+/// Whether `proof` leads from `trader`'s leaf to `root`.
+pub fn verify_allowlist_proof(root: &[u8; 32], trader: &Pubkey, proof: &[[u8; 32]]) -> bool {
+    let computed = proof.iter().fold(allowlist_leaf(trader), |node, sibling| {
+        let (lo, hi) = if node <= *sibling { (node, *sibling) } else { (*sibling, node) };
+        hashv(&[&lo, &hi]).to_bytes()
+    });
+    computed == *root
+}
+
+/// Fail with `TraderNotAllowed` if `pool` is permissioned and `trader_pass`
+/// (already checked to be the trader's PDA for this pool) is missing or was
+/// registered against an older allowlist. Open pools accept any trader.
+pub fn require_allowed_trader(pool: &Pool, trader_pass: Option<&TraderPass>) -> Result<()> {
+    if pool.is_permissioned() {
+        require!(
+            trader_pass.is_some_and(|p| p.root == pool.allowlist_root),
+            A2AError::TraderNotAllowed
+        );
+    }
+    Ok(())
+}
@@ -1,16 +1,28 @@
 use anchor_lang::prelude::*;
-use anchor_spl::token::{self, Token, TokenAccount, Transfer};
-use crate::{constants::*, error::A2AError, state::Pool};
-use super::fee_math::compute_swap;
+use anchor_spl::associated_token::get_associated_token_address;
+use anchor_spl::token::{Token, TokenAccount};
+use anchor_spl::token_interface;
+use crate::{constants::*, error::A2AError, state::{AgentVolume, Pool, TraderPass}};
+use super::allowlist::require_allowed_trader;
+use super::fee_math::{compute_swap, effective_fee_bps, require_not_paused, tiered_protocol_fee_bps};
+use super::swap::{settle, SettleAccounts};
 
 /// Optional human-approval hook.
-/// Identical to `swap` (including the 0.020% protocol fee) but requires BOTH
+/// Identical to `swap` (including the protocol fee and the `agent_volume`
+/// tier discount, but no referral share) but requires BOTH
 /// the agent AND a designated approver to sign the transaction.
 /// The approver's signature IS the approval — no on-chain pending state.
 ///
+/// `expires_at_slot` bounds when that approval can be used: after that slot
+/// the swap fails with `ApprovalExpired`, so a signed approval cannot be
+/// held back and submitted later into a worse market. 0 = no expiry.
+/// `deadline_unix` does the same in wall-clock time for the whole
+/// transaction (0 = no deadline), failing with `DeadlineExceeded`.
+/// `max_price_impact_bps` caps the curve impact as in `swap` (0 = no cap).
+///
 /// Usage:
 ///   1. Agent builds the transaction and adds their signature.
-///   2. Sends the partially-signed tx to the approver (via webhook/Slack).
+///   2. Sends the partially-signed tx to the approver (via webhook/Telegram).
 ///   3. Approver validates, adds their signature, and submits.
 ///
 /// With --approval-mode none (default), use the plain `swap` instruction instead.
@@ -19,11 +31,28 @@ pub fn handler(
     amount_in: u64,
     min_amount_out: u64,
     a_to_b: bool,
+    expires_at_slot: u64,
+    deadline_unix: i64,
+    max_price_impact_bps: u16,
 ) -> Result<()> {
     require!(amount_in > 0, A2AError::ZeroAmount);
+    require_not_paused(&ctx.accounts.treasury, PAUSE_SWAPS)?;
+    require_allowed_trader(&ctx.accounts.pool, ctx.accounts.trader_pass.as_deref().map(|p| &**p))?;
+    let clock = Clock::get()?;
+    require!(
+        expires_at_slot == 0 || clock.slot <= expires_at_slot,
+        A2AError::ApprovalExpired
+    );
+    require!(
+        deadline_unix == 0 || clock.unix_timestamp <= deadline_unix,
+        A2AError::DeadlineExceeded
+    );
 
-    let reserve_a = ctx.accounts.token_a_vault.amount as u128;
-    let reserve_b = ctx.accounts.token_b_vault.amount as u128;
+    let (reserve_a, reserve_b) = ctx
+        .accounts
+        .pool
+        .reserves(ctx.accounts.token_a_vault.amount, ctx.accounts.token_b_vault.amount);
+    let (reserve_a, reserve_b) = (reserve_a as u128, reserve_b as u128);
     require!(reserve_a > 0 && reserve_b > 0, A2AError::InsufficientLiquidity);
 
     let (reserve_in, reserve_out) = if a_to_b {
@@ -32,111 +61,48 @@ pub fn handler(
         (reserve_b, reserve_a)
     };
 
+    let now = clock.unix_timestamp;
+    let fee_rate_bps = effective_fee_bps(
+        ctx.accounts.pool.fee_rate_bps,
+        &ctx.accounts.pool.dynamic_fee,
+        now,
+    );
+    let agent_volume = ctx.accounts.agent_volume.as_deref().map(|v| &**v);
     let sa = compute_swap(
         amount_in,
-        ctx.accounts.pool.fee_rate_bps,
+        tiered_protocol_fee_bps(&ctx.accounts.treasury, agent_volume)?,
+        fee_rate_bps,
+        ctx.accounts.pool.curve,
         reserve_in,
         reserve_out,
         ctx.accounts.pool.lp_supply,
         min_amount_out,
     )?;
 
-    // ── Update fee_growth_global ─────────────────────────────────────────────
-    if sa.fee_growth_delta > 0 {
-        let pool = &mut ctx.accounts.pool;
-        if a_to_b {
-            pool.fee_growth_global_a = pool.fee_growth_global_a.saturating_add(sa.fee_growth_delta);
-        } else {
-            pool.fee_growth_global_b = pool.fee_growth_global_b.saturating_add(sa.fee_growth_delta);
-        }
-    }
-
-    let pool_key = ctx.accounts.pool.key();
-    let authority_bump = ctx.accounts.pool.authority_bump;
-    let seeds: &[&[u8]] = &[POOL_AUTHORITY_SEED, pool_key.as_ref(), &[authority_bump]];
-    let signer = &[seeds];
-
-    if a_to_b {
-        // 1. Protocol fee → treasury
-        if sa.protocol_fee > 0 {
-            token::transfer(
-                CpiContext::new(
-                    ctx.accounts.token_program.to_account_info(),
-                    Transfer {
-                        from: ctx.accounts.agent_token_in.to_account_info(),
-                        to: ctx.accounts.treasury_token_in.to_account_info(),
-                        authority: ctx.accounts.agent.to_account_info(),
-                    },
-                ),
-                sa.protocol_fee,
-            )?;
-        }
-        // 2. Net input → vault_a
-        token::transfer(
-            CpiContext::new(
-                ctx.accounts.token_program.to_account_info(),
-                Transfer {
-                    from: ctx.accounts.agent_token_in.to_account_info(),
-                    to: ctx.accounts.token_a_vault.to_account_info(),
-                    authority: ctx.accounts.agent.to_account_info(),
-                },
-            ),
-            sa.net_pool_input,
-        )?;
-        // 3. Output: vault_b → agent
-        token::transfer(
-            CpiContext::new_with_signer(
-                ctx.accounts.token_program.to_account_info(),
-                Transfer {
-                    from: ctx.accounts.token_b_vault.to_account_info(),
-                    to: ctx.accounts.agent_token_out.to_account_info(),
-                    authority: ctx.accounts.pool_authority.to_account_info(),
-                },
-                signer,
-            ),
-            sa.amount_out,
-        )?;
-    } else {
-        // 1. Protocol fee → treasury
-        if sa.protocol_fee > 0 {
-            token::transfer(
-                CpiContext::new(
-                    ctx.accounts.token_program.to_account_info(),
-                    Transfer {
-                        from: ctx.accounts.agent_token_in.to_account_info(),
-                        to: ctx.accounts.treasury_token_in.to_account_info(),
-                        authority: ctx.accounts.agent.to_account_info(),
-                    },
-                ),
-                sa.protocol_fee,
-            )?;
-        }
-        // 2. Net input → vault_b
-        token::transfer(
-            CpiContext::new(
-                ctx.accounts.token_program.to_account_info(),
-                Transfer {
-                    from: ctx.accounts.agent_token_in.to_account_info(),
-                    to: ctx.accounts.token_b_vault.to_account_info(),
-                    authority: ctx.accounts.agent.to_account_info(),
-                },
-            ),
-            sa.net_pool_input,
-        )?;
-        // 3. Output: vault_a → agent
-        token::transfer(
-            CpiContext::new_with_signer(
-                ctx.accounts.token_program.to_account_info(),
-                Transfer {
-                    from: ctx.accounts.token_a_vault.to_account_info(),
-                    to: ctx.accounts.agent_token_out.to_account_info(),
-                    authority: ctx.accounts.pool_authority.to_account_info(),
-                },
-                signer,
-            ),
-            sa.amount_out,
-        )?;
-    }
+    settle(
+        SettleAccounts {
+            pool: &mut ctx.accounts.pool,
+            pool_authority: ctx.accounts.pool_authority.to_account_info(),
+            token_a_vault: ctx.accounts.token_a_vault.to_account_info(),
+            token_b_vault: ctx.accounts.token_b_vault.to_account_info(),
+            token_in: ctx.accounts.agent_token_in.to_account_info(),
+            token_out: ctx.accounts.agent_token_out.to_account_info(),
+            in_authority: ctx.accounts.agent.to_account_info(),
+            in_signer_seeds: None,
+            treasury: ctx.accounts.treasury.to_account_info(),
+            treasury_token_in: ctx.accounts.treasury_token_in.to_account_info(),
+            token_program: ctx.accounts.token_program.to_account_info(),
+            referrer_token: None,
+            agent_volume: ctx.accounts.agent_volume.as_deref_mut().map(|v| &mut **v),
+        },
+        a_to_b,
+        amount_in,
+        reserve_in,
+        reserve_out,
+        now,
+        &sa,
+        max_price_impact_bps,
+    )?;
 
     msg!(
         "Approved swap: agent={} approver={} in={} protocol_fee={} lp_fee={} out={} a_to_b={}",
@@ -160,7 +126,10 @@ pub struct ApproveAndExecute<'info> {
     /// The human (or co-agent) approver — must also sign
     pub approver: Signer<'info>,
 
-    #[account(mut)]
+    #[account(
+        mut,
+        constraint = !pool.is_gated() || gate_token.is_some() @ A2AError::GateTokenRequired,
+    )]
     pub pool: Account<'info, Pool>,
 
     /// CHECK: PDA vault authority
@@ -173,12 +142,16 @@ pub struct ApproveAndExecute<'info> {
     #[account(
         mut,
         constraint = token_a_vault.key() == pool.token_a_vault @ A2AError::MintMismatch,
+        constraint = token_a_vault.mint == pool.token_a_mint @ A2AError::MintMismatch,
+        constraint = token_a_vault.owner == pool_authority.key() @ A2AError::MintMismatch,
     )]
     pub token_a_vault: Box<Account<'info, TokenAccount>>,
 
     #[account(
         mut,
         constraint = token_b_vault.key() == pool.token_b_vault @ A2AError::MintMismatch,
+        constraint = token_b_vault.mint == pool.token_b_mint @ A2AError::MintMismatch,
+        constraint = token_b_vault.owner == pool_authority.key() @ A2AError::MintMismatch,
     )]
     pub token_b_vault: Box<Account<'info, TokenAccount>>,
 
@@ -201,17 +174,47 @@ pub struct ApproveAndExecute<'info> {
     )]
     pub agent_token_out: Box<Account<'info, TokenAccount>>,
 
-    /// CHECK: Global treasury PDA
+    /// CHECK: Global treasury PDA — owns treasury token accounts; holds the
+    /// ProtocolConfig once initialized, parsed in the handler
     #[account(seeds = [TREASURY_SEED], bump)]
     pub treasury: UncheckedAccount<'info>,
 
-    /// Treasury's token account for the input token
+    /// Treasury's associated token account for the input token
     #[account(
         mut,
         constraint = treasury_token_in.owner == treasury.key() @ A2AError::MintMismatch,
         constraint = treasury_token_in.mint == agent_token_in.mint @ A2AError::MintMismatch,
+        constraint = treasury_token_in.key()
+            == get_associated_token_address(&treasury.key(), &agent_token_in.mint)
+            @ A2AError::InvalidTreasuryAccount,
     )]
     pub treasury_token_in: Box<Account<'info, TokenAccount>>,
 
     pub token_program: Program<'info, Token>,
+
+    /// Optional: the agent's AgentVolume, for fee tier discounts. May be
+    /// omitted entirely.
+    #[account(
+        mut,
+        seeds = [AGENT_VOLUME_SEED, agent.key().as_ref()],
+        bump = agent_volume.bump,
+    )]
+    pub agent_volume: Option<Box<Account<'info, AgentVolume>>>,
+
+    /// Optional: the agent's TraderPass, required on permissioned pools.
+    /// May be omitted entirely on open pools.
+    #[account(
+        seeds = [TRADER_PASS_SEED, pool.key().as_ref(), agent.key().as_ref()],
+        bump = trader_pass.bump,
+    )]
+    pub trader_pass: Option<Box<Account<'info, TraderPass>>>,
+
+    /// Optional: the agent's token account of the pool's gate mint (SPL
+    /// Token or Token-2022), required on token-gated pools.
+    #[account(
+        constraint = gate_token.mint == pool.gate_mint @ A2AError::GateTokenRequired,
+        constraint = gate_token.owner == agent.key() @ A2AError::GateTokenRequired,
+        constraint = gate_token.amount > 0 @ A2AError::GateTokenRequired,
+    )]
+    pub gate_token: Option<Box<InterfaceAccount<'info, token_interface::TokenAccount>>>,
 }
@@ -0,0 +1,28 @@
+use anchor_lang::prelude::*;
+use crate::{constants::*, state::SwapCommitment};
+
+/// Close an unrevealed commitment — expired, or no longer wanted — and
+/// return its rent to the agent, freeing the slot for a new `commit_swap`.
+pub fn handler(ctx: Context<CancelSwapCommitment>) -> Result<()> {
+    msg!(
+        "Swap commitment cancelled: agent={} pool={} committed_slot={}",
+        ctx.accounts.agent.key(),
+        ctx.accounts.commitment.pool,
+        ctx.accounts.commitment.commit_slot
+    );
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct CancelSwapCommitment<'info> {
+    #[account(mut)]
+    pub agent: Signer<'info>,
+
+    #[account(
+        mut,
+        close = agent,
+        seeds = [SWAP_COMMITMENT_SEED, commitment.pool.as_ref(), agent.key().as_ref()],
+        bump = commitment.bump,
+    )]
+    pub commitment: Account<'info, SwapCommitment>,
+}
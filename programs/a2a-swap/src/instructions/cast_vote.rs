@@ -0,0 +1,91 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Token, TokenAccount, Transfer};
+use crate::{
+    constants::*,
+    error::A2AError,
+    state::{Governance, Proposal, VoteRecord},
+};
+
+/// Vote on an open proposal with `amount` vote tokens, moved from the
+/// voter's token account into the vote vault until `withdraw_vote`. One
+/// ballot per voter per proposal; escrowing the tokens keeps them from being
+/// counted again from another wallet while the vote is open.
+pub fn handler(ctx: Context<CastVote>, support: bool, amount: u64) -> Result<()> {
+    require!(amount > 0, A2AError::ZeroAmount);
+    let proposal = &mut ctx.accounts.proposal;
+    require!(
+        Clock::get()?.unix_timestamp < proposal.voting_ends_at,
+        A2AError::VotingClosed
+    );
+
+    token::transfer(
+        CpiContext::new(
+            ctx.accounts.token_program.to_account_info(),
+            Transfer {
+                from: ctx.accounts.voter_token.to_account_info(),
+                to: ctx.accounts.vote_vault.to_account_info(),
+                authority: ctx.accounts.voter.to_account_info(),
+            },
+        ),
+        amount,
+    )?;
+
+    let tally = if support { &mut proposal.votes_for } else { &mut proposal.votes_against };
+    *tally = tally.checked_add(amount).ok_or(A2AError::MathOverflow)?;
+
+    let record = &mut ctx.accounts.vote_record;
+    record.proposal = proposal.key();
+    record.voter = ctx.accounts.voter.key();
+    record.support = support;
+    record.amount = amount;
+    record.bump = ctx.bumps.vote_record;
+
+    msg!(
+        "Vote on proposal {}: {} {} by {} (for={} against={})",
+        proposal.id,
+        if support { "for" } else { "against" },
+        amount,
+        record.voter,
+        proposal.votes_for,
+        proposal.votes_against
+    );
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct CastVote<'info> {
+    #[account(mut)]
+    pub voter: Signer<'info>,
+
+    #[account(seeds = [GOVERNANCE_SEED], bump = governance.bump)]
+    pub governance: Account<'info, Governance>,
+
+    #[account(
+        mut,
+        seeds = [PROPOSAL_SEED, proposal.id.to_le_bytes().as_ref()],
+        bump = proposal.bump,
+    )]
+    pub proposal: Account<'info, Proposal>,
+
+    #[account(
+        init,
+        payer = voter,
+        space = VoteRecord::LEN,
+        seeds = [VOTE_SEED, proposal.key().as_ref(), voter.key().as_ref()],
+        bump,
+    )]
+    pub vote_record: Account<'info, VoteRecord>,
+
+    #[account(
+        mut,
+        constraint = voter_token.owner == voter.key(),
+        constraint = voter_token.mint == governance.vote_mint @ A2AError::MintMismatch,
+    )]
+    pub voter_token: Account<'info, TokenAccount>,
+
+    #[account(mut, seeds = [VOTE_VAULT_SEED], bump)]
+    pub vote_vault: Account<'info, TokenAccount>,
+
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
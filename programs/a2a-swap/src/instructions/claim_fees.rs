@@ -11,8 +11,10 @@ pub fn handler(ctx: Context<ClaimFees>) -> Result<()> {
     // Read state before mutable borrows
     let fg_a = ctx.accounts.pool.fee_growth_global_a;
     let fg_b = ctx.accounts.pool.fee_growth_global_b;
-    let reserve_a = ctx.accounts.token_a_vault.amount;
-    let reserve_b = ctx.accounts.token_b_vault.amount;
+    let (reserve_a, reserve_b) = ctx
+        .accounts
+        .pool
+        .reserves(ctx.accounts.token_a_vault.amount, ctx.accounts.token_b_vault.amount);
     let lp_supply = ctx.accounts.pool.lp_supply;
     let pool_key = ctx.accounts.pool.key();
     let authority_bump = ctx.accounts.pool.authority_bump;
@@ -87,6 +89,7 @@ pub fn handler(ctx: Context<ClaimFees>) -> Result<()> {
         // ── Manual claim: transfer fees out of vaults ─────────────────────
         ctx.accounts.position.fees_owed_a = 0;
         ctx.accounts.position.fees_owed_b = 0;
+        ctx.accounts.pool.set_reserves(reserve_a.saturating_sub(fees_a), reserve_b.saturating_sub(fees_b));
 
         let seeds: &[&[u8]] = &[POOL_AUTHORITY_SEED, pool_key.as_ref(), &[authority_bump]];
         let signer = &[seeds];
@@ -0,0 +1,119 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, CloseAccount, Token, TokenAccount, Transfer};
+use crate::{constants::*, error::A2AError, state::{Pool, ProtocolConfig}};
+
+/// Close a dead pool: its two vaults and the pool account, with all their
+/// rent going to the signer. The signer must be the pool's recorded creator
+/// or `ProtocolConfig::admin` (pass the config for the latter).
+///
+/// The pool must be empty — no LP shares, no tracked reserves and no open
+/// positions — so no LP's tokens or unclaimed fees are stranded: every
+/// position has to be closed, which needs its fees claimed, first. Pools
+/// migrated from before positions were counted can't be closed. Whatever
+/// the vaults still hold is then untracked (rounding dust, or tokens sent
+/// straight to a vault) and is swept to the closer's token accounts before
+/// the vaults are closed.
+pub fn handler(ctx: Context<ClosePool>) -> Result<()> {
+    let pool = &ctx.accounts.pool;
+    let closer = ctx.accounts.closer.key();
+    let is_creator = pool.creator != Pubkey::default() && pool.creator == closer;
+    let is_admin = ctx
+        .accounts
+        .protocol_config
+        .as_ref()
+        .is_some_and(|config| config.admin == closer);
+    require!(is_creator || is_admin, A2AError::Unauthorized);
+    require!(pool.is_closable(), A2AError::PoolNotEmpty);
+
+    let pool_key = pool.key();
+    let seeds: &[&[u8]] = &[POOL_AUTHORITY_SEED, pool_key.as_ref(), &[pool.authority_bump]];
+    let signer = &[seeds];
+    let vaults = [
+        (&ctx.accounts.token_a_vault, &ctx.accounts.closer_token_a),
+        (&ctx.accounts.token_b_vault, &ctx.accounts.closer_token_b),
+    ];
+    for (vault, dust_to) in vaults {
+        if vault.amount > 0 {
+            token::transfer(
+                CpiContext::new_with_signer(
+                    ctx.accounts.token_program.to_account_info(),
+                    Transfer {
+                        from: vault.to_account_info(),
+                        to: dust_to.to_account_info(),
+                        authority: ctx.accounts.pool_authority.to_account_info(),
+                    },
+                    signer,
+                ),
+                vault.amount,
+            )?;
+        }
+        token::close_account(CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            CloseAccount {
+                account: vault.to_account_info(),
+                destination: ctx.accounts.closer.to_account_info(),
+                authority: ctx.accounts.pool_authority.to_account_info(),
+            },
+            signer,
+        ))?;
+    }
+
+    msg!(
+        "Pool closed: {} by {} swept a={} b={}",
+        pool_key,
+        closer,
+        ctx.accounts.token_a_vault.amount,
+        ctx.accounts.token_b_vault.amount
+    );
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct ClosePool<'info> {
+    #[account(mut)]
+    pub closer: Signer<'info>,
+
+    #[account(mut, close = closer)]
+    pub pool: Account<'info, Pool>,
+
+    /// CHECK: PDA vault authority
+    #[account(
+        seeds = [POOL_AUTHORITY_SEED, pool.key().as_ref()],
+        bump = pool.authority_bump,
+    )]
+    pub pool_authority: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        constraint = token_a_vault.key() == pool.token_a_vault @ A2AError::MintMismatch,
+    )]
+    pub token_a_vault: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        constraint = token_b_vault.key() == pool.token_b_vault @ A2AError::MintMismatch,
+    )]
+    pub token_b_vault: Box<Account<'info, TokenAccount>>,
+
+    /// Closer's token A account; receives whatever token_a_vault still holds
+    #[account(
+        mut,
+        constraint = closer_token_a.mint == pool.token_a_mint @ A2AError::MintMismatch,
+        constraint = closer_token_a.owner == closer.key() @ A2AError::TokenOwnerMismatch,
+    )]
+    pub closer_token_a: Box<Account<'info, TokenAccount>>,
+
+    /// Closer's token B account; receives whatever token_b_vault still holds
+    #[account(
+        mut,
+        constraint = closer_token_b.mint == pool.token_b_mint @ A2AError::MintMismatch,
+        constraint = closer_token_b.owner == closer.key() @ A2AError::TokenOwnerMismatch,
+    )]
+    pub closer_token_b: Box<Account<'info, TokenAccount>>,
+
+    /// Required when the admin, rather than the creator, closes the pool
+    #[account(seeds = [TREASURY_SEED], bump = protocol_config.bump)]
+    pub protocol_config: Option<Account<'info, ProtocolConfig>>,
+
+    pub token_program: Program<'info, Token>,
+}
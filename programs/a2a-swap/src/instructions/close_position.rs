@@ -0,0 +1,39 @@
+use anchor_lang::prelude::*;
+use crate::{constants::*, error::A2AError, state::{Pool, Position}};
+
+/// Close an empty position and return its rent to the owner. The position
+/// must hold no LP shares and no owed fees — remove liquidity and claim
+/// fees first (with no shares, nothing further accrues). A position locked
+/// as collateral can't be closed until its locker unlocks it. The pool
+/// counts it out of `open_positions`.
+pub fn handler(ctx: Context<ClosePosition>) -> Result<()> {
+    let pos = &ctx.accounts.position;
+    require!(
+        pos.lp_shares == 0 && pos.fees_owed_a == 0 && pos.fees_owed_b == 0,
+        A2AError::PositionNotEmpty
+    );
+
+    ctx.accounts.pool.position_closed();
+
+    msg!("Position closed: {} owner={}", pos.key(), ctx.accounts.agent.key());
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct ClosePosition<'info> {
+    #[account(mut)]
+    pub agent: Signer<'info>,
+
+    #[account(
+        mut,
+        close = agent,
+        seeds = [POSITION_SEED, position.pool.as_ref(), agent.key().as_ref()],
+        bump = position.bump,
+        constraint = position.owner == agent.key() @ A2AError::Unauthorized,
+        constraint = !position.is_locked() @ A2AError::PositionLocked,
+    )]
+    pub position: Account<'info, Position>,
+
+    #[account(mut, address = position.pool)]
+    pub pool: Account<'info, Pool>,
+}
@@ -0,0 +1,54 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Token, TokenAccount, Transfer};
+use crate::{constants::*, error::A2AError, state::ProtocolConfig};
+
+/// Move protocol fees out of one of the treasury's token accounts.
+/// Signed by `treasury_authority`; `amount = 0` sweeps the whole balance.
+pub fn handler(ctx: Context<CollectProtocolFees>, amount: u64) -> Result<()> {
+    let amount = if amount == 0 { ctx.accounts.treasury_token.amount } else { amount };
+    require!(amount > 0, A2AError::ZeroAmount);
+
+    let bump = ctx.accounts.protocol_config.bump;
+    let seeds: &[&[u8]] = &[TREASURY_SEED, &[bump]];
+    token::transfer(
+        CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            Transfer {
+                from: ctx.accounts.treasury_token.to_account_info(),
+                to: ctx.accounts.destination.to_account_info(),
+                authority: ctx.accounts.protocol_config.to_account_info(),
+            },
+            &[seeds],
+        ),
+        amount,
+    )?;
+
+    msg!("Protocol fees collected: {} of {}", amount, ctx.accounts.treasury_token.mint);
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct CollectProtocolFees<'info> {
+    pub treasury_authority: Signer<'info>,
+
+    #[account(
+        seeds = [TREASURY_SEED],
+        bump = protocol_config.bump,
+        has_one = treasury_authority @ A2AError::Unauthorized,
+    )]
+    pub protocol_config: Account<'info, ProtocolConfig>,
+
+    #[account(
+        mut,
+        constraint = treasury_token.owner == protocol_config.key() @ A2AError::MintMismatch,
+    )]
+    pub treasury_token: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        constraint = destination.mint == treasury_token.mint @ A2AError::MintMismatch,
+    )]
+    pub destination: Box<Account<'info, TokenAccount>>,
+
+    pub token_program: Program<'info, Token>,
+}
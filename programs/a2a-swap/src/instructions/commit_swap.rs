@@ -0,0 +1,70 @@
+use anchor_lang::prelude::*;
+use solana_sha256_hasher::hashv;
+use crate::{constants::*, state::{Pool, SwapCommitment}};
+
+/// First half of a commit-reveal swap: store `hash`, the
+/// `commitment_hash` of the swap the agent will make, and the current slot.
+///
+/// Nothing about the trade's size or direction is visible until
+/// `reveal_swap`, which must land in a later slot and within
+/// `MAX_REVEAL_DELAY_SLOTS`, so a searcher watching the commit has nothing to
+/// position around. The agent pays the commitment's rent and gets it back
+/// when it is revealed or cancelled; an agent has at most one open
+/// commitment per pool.
+pub fn handler(ctx: Context<CommitSwap>, hash: [u8; 32]) -> Result<()> {
+    let commit_slot = Clock::get()?.slot;
+    let c = &mut ctx.accounts.commitment;
+    c.agent = ctx.accounts.agent.key();
+    c.pool = ctx.accounts.pool.key();
+    c.hash = hash;
+    c.commit_slot = commit_slot;
+    c.bump = ctx.bumps.commitment;
+
+    msg!(
+        "Swap committed: agent={} pool={} slot={}",
+        ctx.accounts.agent.key(),
+        ctx.accounts.pool.key(),
+        commit_slot
+    );
+    Ok(())
+}
+
+/// The hash `commit_swap` expects: sha256 over `amount_in`,
+/// `min_amount_out`, `max_price_impact_bps` (little-endian), `a_to_b` as one
+/// byte, and a 32-byte secret salt that keeps small parameter spaces from
+/// being brute-forced.
+pub fn commitment_hash(
+    amount_in: u64,
+    min_amount_out: u64,
+    a_to_b: bool,
+    max_price_impact_bps: u16,
+    salt: &[u8; 32],
+) -> [u8; 32] {
+    hashv(&[
+        &amount_in.to_le_bytes()[..],
+        &min_amount_out.to_le_bytes(),
+        &max_price_impact_bps.to_le_bytes(),
+        &[a_to_b as u8],
+        salt,
+    ])
+    .to_bytes()
+}
+
+#[derive(Accounts)]
+pub struct CommitSwap<'info> {
+    #[account(mut)]
+    pub agent: Signer<'info>,
+
+    pub pool: Account<'info, Pool>,
+
+    #[account(
+        init,
+        payer = agent,
+        space = SwapCommitment::LEN,
+        seeds = [SWAP_COMMITMENT_SEED, pool.key().as_ref(), agent.key().as_ref()],
+        bump,
+    )]
+    pub commitment: Account<'info, SwapCommitment>,
+
+    pub system_program: Program<'info, System>,
+}
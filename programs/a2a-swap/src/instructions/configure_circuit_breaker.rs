@@ -0,0 +1,43 @@
+use anchor_lang::prelude::*;
+use crate::{constants::*, error::A2AError, state::{CircuitBreaker, Pool, ProtocolConfig}};
+
+/// Cap how far swaps may move a pool's price within one slot, or turn the
+/// circuit breaker off with `max_move_bps = 0`. Admin-only: the signer must
+/// be `ProtocolConfig::admin`.
+///
+/// The limit is on the net price impact of the slot's swaps, in bps, so a
+/// trade that reverses an earlier one frees room again. Reconfiguring
+/// clears the current slot's tally.
+pub fn handler(ctx: Context<ConfigureCircuitBreaker>, max_move_bps: u16) -> Result<()> {
+    validate_circuit_breaker(max_move_bps)?;
+
+    let pool = &mut ctx.accounts.pool;
+    pool.circuit_breaker = CircuitBreaker { max_move_bps, ..CircuitBreaker::default() };
+    if max_move_bps == 0 {
+        msg!("Circuit breaker disabled: {}", pool.key());
+    } else {
+        msg!("Circuit breaker set: {} max_move={}bps per slot", pool.key(), max_move_bps);
+    }
+    Ok(())
+}
+
+/// Bounds shared with the `ConfigureCircuitBreaker` governance action.
+pub fn validate_circuit_breaker(max_move_bps: u16) -> Result<()> {
+    require!(max_move_bps as u128 <= BPS_DENOMINATOR, A2AError::InvalidPriceMoveLimit);
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct ConfigureCircuitBreaker<'info> {
+    pub admin: Signer<'info>,
+
+    #[account(mut)]
+    pub pool: Account<'info, Pool>,
+
+    #[account(
+        seeds = [TREASURY_SEED],
+        bump = protocol_config.bump,
+        has_one = admin @ A2AError::Unauthorized,
+    )]
+    pub protocol_config: Account<'info, ProtocolConfig>,
+}
@@ -0,0 +1,54 @@
+use anchor_lang::prelude::*;
+use crate::{constants::*, error::A2AError, state::{DynamicFee, Pool, ProtocolConfig}};
+
+/// Set the bounds a pool's LP fee may move within as volatility rises and
+/// falls, or turn dynamic fees off with `max_fee_bps = 0`. Admin-only: the
+/// signer must be `ProtocolConfig::admin`.
+///
+/// Reconfiguring resets the volatility accumulator, so the new bounds start
+/// from the pool's base `fee_rate_bps`.
+pub fn handler(ctx: Context<ConfigureDynamicFee>, min_fee_bps: u16, max_fee_bps: u16) -> Result<()> {
+    validate_dynamic_fee(min_fee_bps, max_fee_bps)?;
+
+    let pool = &mut ctx.accounts.pool;
+    pool.dynamic_fee = dynamic_fee(min_fee_bps, max_fee_bps, Clock::get()?.unix_timestamp);
+    if max_fee_bps == 0 {
+        msg!("Dynamic fee disabled: {}", pool.key());
+    } else {
+        msg!("Dynamic fee set: {} min={}bps max={}bps", pool.key(), min_fee_bps, max_fee_bps);
+    }
+    Ok(())
+}
+
+/// Bounds shared with the `ConfigureDynamicFee` governance action.
+pub fn validate_dynamic_fee(min_fee_bps: u16, max_fee_bps: u16) -> Result<()> {
+    require!(
+        max_fee_bps == 0 || ((1..=max_fee_bps).contains(&min_fee_bps) && max_fee_bps <= 100),
+        A2AError::InvalidFeeRate
+    );
+    Ok(())
+}
+
+/// The `DynamicFee` validated bounds configure at `now`; `max_fee_bps = 0`
+/// turns it off.
+pub fn dynamic_fee(min_fee_bps: u16, max_fee_bps: u16, now: i64) -> DynamicFee {
+    if max_fee_bps == 0 {
+        return DynamicFee::default();
+    }
+    DynamicFee { min_fee_bps, max_fee_bps, volatility_bps: 0, last_update_ts: now }
+}
+
+#[derive(Accounts)]
+pub struct ConfigureDynamicFee<'info> {
+    pub admin: Signer<'info>,
+
+    #[account(mut)]
+    pub pool: Account<'info, Pool>,
+
+    #[account(
+        seeds = [TREASURY_SEED],
+        bump = protocol_config.bump,
+        has_one = admin @ A2AError::Unauthorized,
+    )]
+    pub protocol_config: Account<'info, ProtocolConfig>,
+}
@@ -0,0 +1,72 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Approve, Token, TokenAccount};
+use crate::{constants::*, error::A2AError, state::Delegate};
+
+/// Grant `delegate` a session to swap out of `owner_token`: at most
+/// `max_amount_in` in total, until the unix timestamp `expires_at`.
+///
+/// The owner signs once here; the Delegate PDA becomes the SPL delegate of
+/// `owner_token` for `max_amount_in`, and `swap_as_delegate` spends through
+/// it. Swap output always lands in the owner's own token accounts.
+pub fn handler(
+    ctx: Context<CreateDelegate>,
+    delegate: Pubkey,
+    max_amount_in: u64,
+    expires_at: i64,
+) -> Result<()> {
+    require!(max_amount_in > 0, A2AError::ZeroAmount);
+    require!(expires_at > Clock::get()?.unix_timestamp, A2AError::DelegateExpired);
+
+    let d = &mut ctx.accounts.delegate_account;
+    d.owner = ctx.accounts.owner.key();
+    d.delegate = delegate;
+    d.token_account = ctx.accounts.owner_token.key();
+    d.max_amount_in = max_amount_in;
+    d.spent = 0;
+    d.expires_at = expires_at;
+    d.bump = ctx.bumps.delegate_account;
+
+    token::approve(
+        CpiContext::new(
+            ctx.accounts.token_program.to_account_info(),
+            Approve {
+                to: ctx.accounts.owner_token.to_account_info(),
+                delegate: ctx.accounts.delegate_account.to_account_info(),
+                authority: ctx.accounts.owner.to_account_info(),
+            },
+        ),
+        max_amount_in,
+    )?;
+
+    msg!(
+        "Delegate created: owner={} delegate={} token={} cap={} expires_at={}",
+        ctx.accounts.owner.key(),
+        delegate,
+        ctx.accounts.owner_token.key(),
+        max_amount_in,
+        expires_at
+    );
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct CreateDelegate<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(
+        init,
+        payer = owner,
+        space = Delegate::LEN,
+        seeds = [DELEGATE_SEED, owner.key().as_ref(), owner_token.key().as_ref()],
+        bump,
+    )]
+    pub delegate_account: Account<'info, Delegate>,
+
+    /// Token account the delegate will spend from
+    #[account(mut, constraint = owner_token.owner == owner.key())]
+    pub owner_token: Account<'info, TokenAccount>,
+
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
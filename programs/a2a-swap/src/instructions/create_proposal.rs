@@ -0,0 +1,102 @@
+use anchor_lang::prelude::*;
+use crate::{
+    constants::*,
+    error::A2AError,
+    state::{Governance, GovernanceAction, Proposal},
+};
+use super::{
+    configure_circuit_breaker::validate_circuit_breaker, configure_dynamic_fee::validate_dynamic_fee,
+    set_fee_tier::validate_fee_tier, set_locker::validate_locker,
+};
+
+/// Open a proposal to apply `action` to the protocol config or a pool. Anyone may
+/// propose (the proposer pays the proposal's rent); voting runs for the
+/// governance's `voting_period_secs` from now. The action is validated here
+/// with the same bounds `execute_proposal` would apply, so a proposal that
+/// could never take effect is rejected up front.
+pub fn handler(ctx: Context<CreateProposal>, action: GovernanceAction) -> Result<()> {
+    validate_action(&action)?;
+
+    let governance = &mut ctx.accounts.governance;
+    let voting_ends_at = Clock::get()?
+        .unix_timestamp
+        .checked_add(governance.voting_period_secs)
+        .ok_or(A2AError::MathOverflow)?;
+
+    let proposal = &mut ctx.accounts.proposal;
+    proposal.id = governance.proposal_count;
+    proposal.proposer = ctx.accounts.proposer.key();
+    proposal.action = action;
+    proposal.votes_for = 0;
+    proposal.votes_against = 0;
+    proposal.voting_ends_at = voting_ends_at;
+    proposal.executed = false;
+    proposal.bump = ctx.bumps.proposal;
+
+    governance.proposal_count = governance
+        .proposal_count
+        .checked_add(1)
+        .ok_or(A2AError::MathOverflow)?;
+
+    msg!(
+        "Proposal {} created by {}: {:?}, voting ends at {}",
+        proposal.id,
+        proposal.proposer,
+        action,
+        voting_ends_at
+    );
+    Ok(())
+}
+
+/// Bounds every `GovernanceAction` must respect.
+pub fn validate_action(action: &GovernanceAction) -> Result<()> {
+    match *action {
+        GovernanceAction::SetProtocolFee { protocol_fee_bps, referral_share_bps } => {
+            require!(protocol_fee_bps <= MAX_PROTOCOL_FEE_BPS, A2AError::InvalidProtocolFee);
+            require!(
+                referral_share_bps as u128 <= BPS_DENOMINATOR,
+                A2AError::InvalidReferralShare
+            );
+        }
+        GovernanceAction::SetPoolFeeBounds { min_fee_bps, max_fee_bps } => {
+            require!(
+                min_fee_bps >= 1 && min_fee_bps <= max_fee_bps && max_fee_bps <= POOL_FEE_CAP_BPS,
+                A2AError::InvalidFeeRate
+            );
+        }
+        GovernanceAction::SetPaused { paused } => {
+            require!(paused & !PAUSE_ALL == 0, A2AError::InvalidGovernanceParams);
+        }
+        GovernanceAction::SetFeeTier { index, discount_bps, .. } => {
+            validate_fee_tier(index, discount_bps)?;
+        }
+        GovernanceAction::SetLocker { index, .. } => validate_locker(index)?,
+        GovernanceAction::ConfigureDynamicFee { min_fee_bps, max_fee_bps, .. } => {
+            validate_dynamic_fee(min_fee_bps, max_fee_bps)?;
+        }
+        GovernanceAction::ConfigureCircuitBreaker { max_move_bps, .. } => {
+            validate_circuit_breaker(max_move_bps)?;
+        }
+    }
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct CreateProposal<'info> {
+    #[account(mut)]
+    pub proposer: Signer<'info>,
+
+    #[account(mut, seeds = [GOVERNANCE_SEED], bump = governance.bump)]
+    pub governance: Account<'info, Governance>,
+
+    #[account(
+        init,
+        payer = proposer,
+        space = Proposal::LEN,
+        seeds = [PROPOSAL_SEED, governance.proposal_count.to_le_bytes().as_ref()],
+        bump,
+    )]
+    pub proposal: Account<'info, Proposal>,
+
+    pub system_program: Program<'info, System>,
+}
@@ -0,0 +1,91 @@
+use anchor_lang::prelude::*;
+use crate::{
+    constants::*,
+    error::A2AError,
+    state::{CircuitBreaker, FeeTier, Governance, GovernanceAction, Pool, Proposal, ProtocolConfig},
+};
+use super::configure_dynamic_fee::dynamic_fee;
+
+/// Apply a passed proposal to the protocol config. Permissionless once
+/// voting has closed; a proposal passes with more votes for than against
+/// and at least the governance's `quorum` votes cast, and runs only once.
+///
+/// `ConfigureDynamicFee` and `ConfigureCircuitBreaker` change a pool
+/// instead, and need that pool passed as `pool`. The config must already
+/// be at `ProtocolConfig::LEN` (`migrate_protocol_config`).
+pub fn handler(ctx: Context<ExecuteProposal>) -> Result<()> {
+    let proposal = &mut ctx.accounts.proposal;
+    require!(!proposal.executed, A2AError::ProposalAlreadyExecuted);
+    require!(
+        Clock::get()?.unix_timestamp >= proposal.voting_ends_at,
+        A2AError::VotingNotEnded
+    );
+    require!(proposal.passed(ctx.accounts.governance.quorum), A2AError::ProposalNotPassed);
+
+    let config = &mut ctx.accounts.protocol_config;
+    match proposal.action {
+        GovernanceAction::SetProtocolFee { protocol_fee_bps, referral_share_bps } => {
+            config.protocol_fee_bps = protocol_fee_bps;
+            config.referral_share_bps = referral_share_bps;
+        }
+        GovernanceAction::SetPoolFeeBounds { min_fee_bps, max_fee_bps } => {
+            config.min_pool_fee_bps = min_fee_bps;
+            config.max_pool_fee_bps = max_fee_bps;
+        }
+        GovernanceAction::SetPaused { paused } => config.paused = paused,
+        GovernanceAction::SetFeeTier { index, min_volume, discount_bps } => {
+            config.fee_tiers[index as usize] = FeeTier { min_volume, discount_bps };
+        }
+        GovernanceAction::SetLocker { index, program } => config.lockers[index as usize] = program,
+        GovernanceAction::ConfigureDynamicFee { pool, min_fee_bps, max_fee_bps } => {
+            let now = Clock::get()?.unix_timestamp;
+            target_pool(&mut ctx.accounts.pool, pool)?.dynamic_fee =
+                dynamic_fee(min_fee_bps, max_fee_bps, now);
+        }
+        GovernanceAction::ConfigureCircuitBreaker { pool, max_move_bps } => {
+            target_pool(&mut ctx.accounts.pool, pool)?.circuit_breaker =
+                CircuitBreaker { max_move_bps, ..CircuitBreaker::default() };
+        }
+    }
+    proposal.executed = true;
+
+    msg!("Proposal {} executed: {:?}", proposal.id, proposal.action);
+    Ok(())
+}
+
+/// The pool a pool action names; the proposal cannot run without it.
+fn target_pool<'a, 'info>(
+    pool: &'a mut Option<Account<'info, Pool>>,
+    key: Pubkey,
+) -> Result<&'a mut Account<'info, Pool>> {
+    match pool {
+        Some(pool) if pool.key() == key => Ok(pool),
+        _ => err!(A2AError::InvalidGovernanceParams),
+    }
+}
+
+#[derive(Accounts)]
+pub struct ExecuteProposal<'info> {
+    #[account(seeds = [GOVERNANCE_SEED], bump = governance.bump)]
+    pub governance: Account<'info, Governance>,
+
+    #[account(
+        mut,
+        seeds = [PROPOSAL_SEED, proposal.id.to_le_bytes().as_ref()],
+        bump = proposal.bump,
+    )]
+    pub proposal: Account<'info, Proposal>,
+
+    #[account(
+        mut,
+        seeds = [TREASURY_SEED],
+        bump = protocol_config.bump,
+        constraint = protocol_config.admin == governance.key() @ A2AError::Unauthorized,
+    )]
+    pub protocol_config: Account<'info, ProtocolConfig>,
+
+    /// The pool a `ConfigureDynamicFee` or `ConfigureCircuitBreaker`
+    /// proposal names; omit for the other actions
+    #[account(mut)]
+    pub pool: Option<Account<'info, Pool>>,
+}
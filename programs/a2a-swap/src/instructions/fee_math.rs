@@ -1,5 +1,9 @@
 use anchor_lang::prelude::*;
-use crate::{constants::*, error::A2AError};
+use crate::{
+    constants::*,
+    error::A2AError,
+    state::{AgentVolume, CircuitBreaker, CurveKind, DynamicFee, Pool, ProtocolConfig},
+};
 
 /// Result of swap fee and output calculations, shared by `swap` and
 /// `approve_and_execute`.
@@ -12,21 +16,30 @@ pub struct SwapAmounts {
     pub lp_fee: u128,
     /// Tokens sent to the agent from the output vault.
     pub amount_out: u64,
+    /// Pure curve impact in basis points: shortfall of amount_out against
+    /// after_fees at the pre-trade spot price. For x·y=k this is
+    /// after_fees / (reserve_in + after_fees).
+    pub price_impact_bps: u64,
     /// Q64.64 delta to add to fee_growth_global for the input token.
     pub fee_growth_delta: u128,
 }
 
-/// Compute protocol fee, LP fee, constant-product output, and fee-growth delta.
+/// Compute protocol fee, LP fee, curve output, and fee-growth delta.
 ///
 /// * `amount_in`      – raw token amount the agent is selling
+/// * `protocol_fee_bps` – protocol fee out of PROTOCOL_FEE_DENOMINATOR
 /// * `fee_rate_bps`   – pool LP fee rate in basis points
+/// * `curve`          – pool invariant (`pool.curve`)
 /// * `reserve_in`     – vault balance for the input token (u128)
 /// * `reserve_out`    – vault balance for the output token (u128)
 /// * `lp_supply`      – total LP shares outstanding
 /// * `min_amount_out` – slippage guard; returns `SlippageExceeded` if violated
+#[allow(clippy::too_many_arguments)]
 pub fn compute_swap(
     amount_in: u64,
+    protocol_fee_bps: u64,
     fee_rate_bps: u16,
+    curve: CurveKind,
     reserve_in: u128,
     reserve_out: u128,
     lp_supply: u64,
@@ -35,10 +48,10 @@ pub fn compute_swap(
     let in_u128 = amount_in as u128;
     let fee_bps = fee_rate_bps as u128;
 
-    // ── Protocol fee (0.020%) ────────────────────────────────────────────────
+    // ── Protocol fee (ProtocolConfig, default 0.020%) ───────────────────────
     // Taken from amount_in before anything reaches the pool.
     let protocol_fee = in_u128
-        .checked_mul(PROTOCOL_FEE_BPS as u128)
+        .checked_mul(protocol_fee_bps as u128)
         .ok_or(A2AError::MathOverflow)?
         / PROTOCOL_FEE_DENOMINATOR;
     let net_pool_input = in_u128 - protocol_fee; // protocol_fee < in_u128 always
@@ -51,14 +64,25 @@ pub fn compute_swap(
         / BPS_DENOMINATOR;
     let after_fees = net_pool_input - lp_fee; // portion used in k formula
 
-    // ── Constant-product output: dy = y * dx_net / (x + dx_net) ─────────────
-    let amount_out = reserve_out
-        .checked_mul(after_fees)
-        .ok_or(A2AError::MathOverflow)?
-        / reserve_in
-            .checked_add(after_fees)
-            .ok_or(A2AError::MathOverflow)?;
-    let amount_out = amount_out as u64;
+    let (amount_out, price_impact_bps) = match curve {
+        CurveKind::ConstantProduct => {
+            // ── Constant-product output: dy = y * dx_net / (x + dx_net) ─────
+            let amount_out = reserve_out
+                .checked_mul(after_fees)
+                .ok_or(A2AError::MathOverflow)?
+                / reserve_in
+                    .checked_add(after_fees)
+                    .ok_or(A2AError::MathOverflow)?;
+
+            // ── Price impact (bps) — excludes fee cost, same as the SDK ─────
+            let price_impact_bps = after_fees
+                .checked_mul(BPS_DENOMINATOR)
+                .ok_or(A2AError::MathOverflow)?
+                / (reserve_in + after_fees);
+            (amount_out as u64, price_impact_bps as u64)
+        }
+        CurveKind::StableSwap { amp } => stable_swap_out(after_fees, reserve_in, reserve_out, amp)?,
+    };
 
     require!(amount_out >= min_amount_out, A2AError::SlippageExceeded);
     require!(amount_out > 0, A2AError::ZeroAmount);
@@ -81,6 +105,366 @@ pub fn compute_swap(
         net_pool_input: net_pool_input as u64,
         lp_fee,
         amount_out,
+        price_impact_bps,
         fee_growth_delta,
     })
 }
+
+/// The treasury PDA's `ProtocolConfig`, or `None` before it is initialized.
+/// A config not yet grown by `migrate_protocol_config` reads with the
+/// appended fields zeroed: nothing paused and the default pool fee bounds.
+fn protocol_config(treasury: &AccountInfo) -> Result<Option<ProtocolConfig>> {
+    if treasury.data_is_empty() {
+        return Ok(None);
+    }
+    require_keys_eq!(*treasury.owner, crate::ID, ErrorCode::AccountOwnedByWrongProgram);
+    let mut data = treasury.try_borrow_data()?.to_vec();
+    if data.len() < ProtocolConfig::LEN {
+        data.resize(ProtocolConfig::LEN, 0);
+    }
+    let config = ProtocolConfig::try_deserialize(&mut &data[..])?;
+    Ok(Some(config))
+}
+
+/// Fail with `Paused` if governance has switched off `op` (a PAUSE_* bit).
+/// Nothing is paused before the config exists.
+pub fn require_not_paused(treasury: &AccountInfo, op: u8) -> Result<()> {
+    let paused = protocol_config(treasury)?.is_some_and(|c| c.is_paused(op));
+    require!(!paused, A2AError::Paused);
+    Ok(())
+}
+
+/// Fail with `InvalidFeeRate` unless `fee_rate_bps` is within the pool fee
+/// bounds: the config's once governance sets them, MIN_POOL_FEE_BPS to
+/// MAX_POOL_FEE_BPS before.
+pub fn require_pool_fee_in_bounds(treasury: &AccountInfo, fee_rate_bps: u16) -> Result<()> {
+    let (min, max) = protocol_config(treasury)?
+        .map_or((MIN_POOL_FEE_BPS, MAX_POOL_FEE_BPS), |c| c.pool_fee_bounds());
+    require!((min..=max).contains(&fee_rate_bps), A2AError::InvalidFeeRate);
+    Ok(())
+}
+
+/// Fail with `LockerNotApproved` unless `program` is in the config's
+/// `lockers`. No program may lock positions before the config exists.
+pub fn require_approved_locker(treasury: &AccountInfo, program: &Pubkey) -> Result<()> {
+    let approved = protocol_config(treasury)?.is_some_and(|c| c.is_locker(program));
+    require!(approved, A2AError::LockerNotApproved);
+    Ok(())
+}
+
+/// Protocol fee (out of PROTOCOL_FEE_DENOMINATOR) read from the treasury
+/// PDA: its `ProtocolConfig` once initialized, PROTOCOL_FEE_BPS before.
+pub fn protocol_fee_bps(treasury: &AccountInfo) -> Result<u64> {
+    Ok(protocol_config(treasury)?.map_or(PROTOCOL_FEE_BPS, |c| c.protocol_fee_bps as u64))
+}
+
+/// [`protocol_fee_bps`] less the discount of the tier `agent_volume` has
+/// reached in the config's `fee_tiers`; the full fee without an account.
+pub fn tiered_protocol_fee_bps(
+    treasury: &AccountInfo,
+    agent_volume: Option<&AgentVolume>,
+) -> Result<u64> {
+    let Some(config) = protocol_config(treasury)? else {
+        return Ok(PROTOCOL_FEE_BPS);
+    };
+    let discount_bps = agent_volume.map_or(0, |v| config.fee_discount_bps(v.volume));
+    Ok(discounted_fee_bps(config.protocol_fee_bps as u64, discount_bps))
+}
+
+/// `fee_bps` with `discount_bps` (out of BPS_DENOMINATOR) taken off,
+/// rounded down in the agent's favour.
+pub fn discounted_fee_bps(fee_bps: u64, discount_bps: u16) -> u64 {
+    let kept = BPS_DENOMINATOR.saturating_sub(discount_bps as u128);
+    (fee_bps as u128 * kept / BPS_DENOMINATOR) as u64
+}
+
+/// Referrer's share of the protocol fee (out of BPS_DENOMINATOR), read like
+/// [`protocol_fee_bps`]: REFERRAL_SHARE_BPS until the config exists.
+pub fn referral_share_bps(treasury: &AccountInfo) -> Result<u64> {
+    Ok(protocol_config(treasury)?.map_or(REFERRAL_SHARE_BPS, |c| c.referral_share_bps as u64))
+}
+
+/// Part of `protocol_fee` paid to the referrer, rounded down so the
+/// treasury keeps any dust. Never exceeds `protocol_fee`.
+pub fn referral_fee(protocol_fee: u64, referral_share_bps: u64) -> u64 {
+    (protocol_fee as u128 * referral_share_bps.min(BPS_DENOMINATOR as u64) as u128
+        / BPS_DENOMINATOR) as u64
+}
+
+// ─── Dynamic fees ──────────────────────────────────────────────────────────
+
+/// `volatility_bps` decayed to `now`: halved once per elapsed half-life.
+pub fn decayed_volatility(df: &DynamicFee, now: i64) -> u32 {
+    let half_lives = now.saturating_sub(df.last_update_ts).max(0) / VOLATILITY_HALF_LIFE_SECS;
+    df.volatility_bps.checked_shr(half_lives.min(32) as u32).unwrap_or(0)
+}
+
+/// LP fee a swap pays at `now`: `fee_rate_bps` when dynamic fees are off,
+/// otherwise the base fee plus a volatility premium, clamped to the bounds.
+pub fn effective_fee_bps(fee_rate_bps: u16, df: &DynamicFee, now: i64) -> u16 {
+    if df.max_fee_bps == 0 {
+        return fee_rate_bps;
+    }
+    let premium = decayed_volatility(df, now) / VOLATILITY_FEE_DIVISOR;
+    let fee = (fee_rate_bps as u32).saturating_add(premium);
+    fee.clamp(df.min_fee_bps as u32, df.max_fee_bps as u32) as u16
+}
+
+/// Fold a swap's price impact into the accumulator. No-op when disabled,
+/// so pools without dynamic fees never write to it.
+pub fn record_volatility(df: &mut DynamicFee, price_impact_bps: u64, now: i64) {
+    if df.max_fee_bps == 0 {
+        return;
+    }
+    let impact = u32::try_from(price_impact_bps).unwrap_or(u32::MAX);
+    df.volatility_bps = decayed_volatility(df, now).saturating_add(impact);
+    df.last_update_ts = now;
+}
+
+/// Add a swap's price impact to the circuit breaker's net move for `slot`
+/// (starting over in a new slot) and fail with `PriceMoveExceeded` once it
+/// exceeds the limit. A→B swaps lower the B-per-A price, so they count
+/// negative and B→A positive. No-op when disabled.
+pub fn record_price_move(
+    cb: &mut CircuitBreaker,
+    a_to_b: bool,
+    price_impact_bps: u64,
+    slot: u64,
+) -> Result<()> {
+    if cb.max_move_bps == 0 {
+        return Ok(());
+    }
+    if cb.slot != slot {
+        cb.slot = slot;
+        cb.moved_bps = 0;
+    }
+    let impact = i32::try_from(price_impact_bps).unwrap_or(i32::MAX);
+    cb.moved_bps = if a_to_b {
+        cb.moved_bps.saturating_sub(impact)
+    } else {
+        cb.moved_bps.saturating_add(impact)
+    };
+    require!(
+        cb.moved_bps.unsigned_abs() <= cb.max_move_bps as u32,
+        A2AError::PriceMoveExceeded
+    );
+    Ok(())
+}
+
+/// Add a swap to the pool's lifetime counters, on the input token's side.
+/// Saturating, so a counter that somehow fills up never blocks swaps.
+pub fn record_swap_stats(pool: &mut Pool, a_to_b: bool, amount_in: u64, lp_fee: u128) {
+    let lp_fee = u64::try_from(lp_fee).unwrap_or(u64::MAX);
+    let (volume, fees) = if a_to_b {
+        (&mut pool.volume_a, &mut pool.fees_collected_a)
+    } else {
+        (&mut pool.volume_b, &mut pool.fees_collected_b)
+    };
+    *volume = volume.saturating_add(amount_in);
+    *fees = fees.saturating_add(lp_fee);
+}
+
+/// Move the pool's tracked reserves by a swap priced at `reserve_in` /
+/// `reserve_out`: the net pool input (LP fee included, it stays in the
+/// vault) comes in, `amount_out` goes out.
+pub fn record_swap_reserves(
+    pool: &mut Pool,
+    a_to_b: bool,
+    reserve_in: u128,
+    reserve_out: u128,
+    sa: &SwapAmounts,
+) -> Result<()> {
+    let new_in = u64::try_from(reserve_in + sa.net_pool_input as u128)
+        .map_err(|_| A2AError::MathOverflow)?;
+    let new_out = u64::try_from(reserve_out - sa.amount_out as u128)
+        .map_err(|_| A2AError::MathOverflow)?;
+    if a_to_b {
+        pool.set_reserves(new_in, new_out);
+    } else {
+        pool.set_reserves(new_out, new_in);
+    }
+    Ok(())
+}
+
+// ─── StableSwap ────────────────────────────────────────────────────────────
+// Two-coin Curve invariant, with Ann = A · nⁿ = 4A:
+//   Ann·(x + y) + D = Ann·D + D³ / (4·x·y)
+// Solved by Newton's method exactly as in Curve's `get_D` / `get_y`, in u128
+// with floor division. Overflows (MathOverflow) once D² no longer fits, i.e.
+// reserves around 1e19 atomic units — far beyond any real stable pair.
+
+/// Invariant D for reserves `x`, `y`.
+pub fn stable_invariant(x: u128, y: u128, amp: u64) -> Result<u128> {
+    let sum = x.checked_add(y).ok_or(A2AError::MathOverflow)?;
+    if sum == 0 {
+        return Ok(0);
+    }
+    let ann = amp as u128 * 4;
+    let mut d = sum;
+    for _ in 0..STABLE_MAX_ITERATIONS {
+        // d_p = D³ / (4xy), built up one factor at a time
+        let mut d_p = d;
+        for r in [x, y] {
+            d_p = d_p
+                .checked_mul(d)
+                .ok_or(A2AError::MathOverflow)?
+                / r.checked_mul(2).ok_or(A2AError::MathOverflow)?.max(1);
+        }
+        let prev = d;
+        let num = ann
+            .checked_mul(sum)
+            .and_then(|v| v.checked_add(d_p.checked_mul(2)?))
+            .and_then(|v| v.checked_mul(d))
+            .ok_or(A2AError::MathOverflow)?;
+        let den = (ann - 1)
+            .checked_mul(d)
+            .and_then(|v| v.checked_add(d_p.checked_mul(3)?))
+            .ok_or(A2AError::MathOverflow)?;
+        d = num / den;
+        if d.abs_diff(prev) <= 1 {
+            return Ok(d);
+        }
+    }
+    err!(A2AError::CurveNotConverged)
+}
+
+/// The other reserve once one side is `x_new`, holding D fixed.
+pub fn stable_y(x_new: u128, d: u128, amp: u64) -> Result<u128> {
+    require!(x_new > 0, A2AError::InsufficientLiquidity);
+    let ann = amp as u128 * 4;
+    let c = d
+        .checked_mul(d)
+        .ok_or(A2AError::MathOverflow)?
+        / (x_new * 2);
+    let c = c
+        .checked_mul(d)
+        .ok_or(A2AError::MathOverflow)?
+        / (ann * 2);
+    let b = x_new + d / ann;
+    let mut y = d;
+    for _ in 0..STABLE_MAX_ITERATIONS {
+        let prev = y;
+        let num = y
+            .checked_mul(y)
+            .and_then(|v| v.checked_add(c))
+            .ok_or(A2AError::MathOverflow)?;
+        let den = (y * 2 + b)
+            .checked_sub(d)
+            .filter(|&v| v > 0)
+            .ok_or(A2AError::MathOverflow)?;
+        y = num / den;
+        if y.abs_diff(prev) <= 1 {
+            return Ok(y);
+        }
+    }
+    err!(A2AError::CurveNotConverged)
+}
+
+/// StableSwap output and price impact for `after_fees` entering the pool.
+///
+/// One unit is held back from the output (as Curve does) so rounding in the
+/// Newton solution never favours the trader. Impact compares the output with
+/// `after_fees` at the pre-trade marginal price
+/// `(t + y) / (t + x)`, where `t = 4·Ann·(xy/D)² / D`.
+pub fn stable_swap_out(
+    after_fees: u128,
+    reserve_in: u128,
+    reserve_out: u128,
+    amp: u64,
+) -> Result<(u64, u64)> {
+    let d = stable_invariant(reserve_in, reserve_out, amp)?;
+    let x_new = reserve_in.checked_add(after_fees).ok_or(A2AError::MathOverflow)?;
+    let y_new = stable_y(x_new, d, amp)?;
+    let amount_out = reserve_out.saturating_sub(y_new).saturating_sub(1);
+
+    let k = reserve_in
+        .checked_mul(reserve_out)
+        .ok_or(A2AError::MathOverflow)?
+        / d;
+    let t = (amp as u128 * 16)
+        .checked_mul(k)
+        .and_then(|v| v.checked_mul(k))
+        .ok_or(A2AError::MathOverflow)?
+        / d;
+    let ideal_out = after_fees
+        .checked_mul(t + reserve_out)
+        .ok_or(A2AError::MathOverflow)?
+        / (t + reserve_in);
+    let price_impact_bps = if ideal_out == 0 {
+        0
+    } else {
+        ideal_out.saturating_sub(amount_out) * BPS_DENOMINATOR / ideal_out
+    };
+    Ok((amount_out as u64, price_impact_bps as u64))
+}
+
+// ─── Exact-output swaps ────────────────────────────────────────────────────
+
+/// Input `swap_exact_out` charges for `amount_out`: the curve leg inverted,
+/// then both fee legs with round-up semantics, so `compute_swap` on the
+/// result yields at least `amount_out`. Exact for x·y=k; StableSwap steps up
+/// from the Newton solution until the forward output is reached. Mirrors
+/// `a2a_swap_core::math::curve_amount_in_for_exact_out` step for step.
+pub fn amount_in_for_exact_out(
+    curve: CurveKind,
+    reserve_in: u128,
+    reserve_out: u128,
+    fee_rate_bps: u16,
+    protocol_fee_bps: u64,
+    amount_out: u64,
+) -> Result<u64> {
+    let out = amount_out as u128;
+    require!(out < reserve_out, A2AError::InsufficientLiquidity);
+
+    let after_fees = match curve {
+        // after_fees × (reserve_out − amount_out) ≥ amount_out × reserve_in
+        CurveKind::ConstantProduct => {
+            let n = out.checked_mul(reserve_in).ok_or(A2AError::MathOverflow)?;
+            let d = reserve_out - out;
+            n / d + u128::from(n % d != 0)
+        }
+        CurveKind::StableSwap { amp } => {
+            // One unit is held back from the output, as in `stable_swap_out`
+            let y_new = reserve_out
+                .checked_sub(out + 1)
+                .filter(|&y| y > 0)
+                .ok_or(A2AError::InsufficientLiquidity)?;
+            let d = stable_invariant(reserve_in, reserve_out, amp)?;
+            let mut after_fees = stable_y(y_new, d, amp)?.saturating_sub(reserve_in).max(1);
+            let mut step = 1u128;
+            let mut found = false;
+            for _ in 0..STABLE_MAX_ITERATIONS {
+                if stable_swap_out(after_fees, reserve_in, reserve_out, amp)?.0 >= amount_out {
+                    found = true;
+                    break;
+                }
+                after_fees = after_fees.checked_add(step).ok_or(A2AError::MathOverflow)?;
+                step = step.saturating_mul(2);
+            }
+            require!(found, A2AError::MathOverflow);
+            after_fees
+        }
+    };
+
+    // net_pool_input − floor(net_pool_input × fee / 10_000) ≥ after_fees
+    let net_pool_input =
+        invert_fee_floor(after_fees, BPS_DENOMINATOR - fee_rate_bps as u128, BPS_DENOMINATOR)?;
+    // amount_in − floor(amount_in × fee / 100_000) ≥ net_pool_input
+    let amount_in = invert_fee_floor(
+        net_pool_input,
+        PROTOCOL_FEE_DENOMINATOR - protocol_fee_bps as u128,
+        PROTOCOL_FEE_DENOMINATOR,
+    )?;
+    u64::try_from(amount_in).map_err(|_| error!(A2AError::MathOverflow))
+}
+
+/// Smallest `x` with `x − floor(x × (den − keep) / den) ≥ target`.
+fn invert_fee_floor(target: u128, keep: u128, den: u128) -> Result<u128> {
+    if target == 0 {
+        return Ok(0);
+    }
+    (target - 1)
+        .checked_mul(den)
+        .map(|v| v / keep + 1)
+        .ok_or(error!(A2AError::MathOverflow))
+}
@@ -0,0 +1,74 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{Mint, Token, TokenAccount};
+use crate::{constants::*, error::A2AError, state::{Governance, ProtocolConfig}};
+
+/// Hand the protocol config to token-holder governance. Admin-only and
+/// one-time: creates the Governance PDA and its vote vault, and makes the
+/// Governance PDA the config's `admin`, so no single key can change the
+/// protocol fee, pool fee bounds or pause switches afterwards — only a
+/// proposal that passes with at least `quorum` votes of `vote_mint`.
+///
+/// A pool's dynamic fee and circuit breaker then change through
+/// `ConfigureDynamicFee` and `ConfigureCircuitBreaker` proposals instead of
+/// the admin-signed instructions. A config from before the pause switches
+/// existed needs `migrate_protocol_config` first.
+pub fn handler(ctx: Context<InitializeGovernance>, quorum: u64, voting_period_secs: i64) -> Result<()> {
+    require!(quorum > 0 && voting_period_secs > 0, A2AError::InvalidGovernanceParams);
+
+    let governance = &mut ctx.accounts.governance;
+    governance.vote_mint = ctx.accounts.vote_mint.key();
+    governance.quorum = quorum;
+    governance.voting_period_secs = voting_period_secs;
+    governance.proposal_count = 0;
+    governance.bump = ctx.bumps.governance;
+
+    ctx.accounts.protocol_config.admin = governance.key();
+
+    msg!(
+        "Governance initialized: vote_mint={} quorum={} voting_period={}s",
+        governance.vote_mint,
+        quorum,
+        voting_period_secs
+    );
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct InitializeGovernance<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [TREASURY_SEED],
+        bump = protocol_config.bump,
+        has_one = admin @ A2AError::Unauthorized,
+    )]
+    pub protocol_config: Account<'info, ProtocolConfig>,
+
+    #[account(
+        init,
+        payer = admin,
+        space = Governance::LEN,
+        seeds = [GOVERNANCE_SEED],
+        bump,
+    )]
+    pub governance: Account<'info, Governance>,
+
+    pub vote_mint: Account<'info, Mint>,
+
+    /// Holds every voter's escrowed vote tokens
+    #[account(
+        init,
+        payer = admin,
+        token::mint = vote_mint,
+        token::authority = governance,
+        seeds = [VOTE_VAULT_SEED],
+        bump,
+    )]
+    pub vote_vault: Account<'info, TokenAccount>,
+
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
@@ -1,12 +1,19 @@
 use anchor_lang::prelude::*;
 use anchor_spl::token::{Mint, Token, TokenAccount};
-use crate::{constants::*, error::A2AError, state::Pool};
+use crate::{constants::*, error::A2AError, state::{CircuitBreaker, CurveKind, DynamicFee, Pool}};
+use super::fee_math::{require_not_paused, require_pool_fee_in_bounds};
 
-/// Create a new constant-product pool.
+/// Create a new pool with the given swap curve.
 /// The PDA authority owns both vaults — no human key controls the funds.
-/// Any agent may create a pool; the creator sets the fee tier (1–100 bps).
-pub fn handler(ctx: Context<InitializePool>, fee_rate_bps: u16) -> Result<()> {
-    require!(fee_rate_bps >= 1 && fee_rate_bps <= 100, A2AError::InvalidFeeRate);
+/// Any agent may create a pool; the creator sets the fee tier (1–100 bps
+/// unless governance has set other bounds) and, for StableSwap, the
+/// amplification coefficient. Fails while governance has paused pool creation.
+pub fn handler(ctx: Context<InitializePool>, fee_rate_bps: u16, curve: CurveKind) -> Result<()> {
+    require_not_paused(&ctx.accounts.treasury, PAUSE_POOL_CREATION)?;
+    require_pool_fee_in_bounds(&ctx.accounts.treasury, fee_rate_bps)?;
+    if let CurveKind::StableSwap { amp } = curve {
+        require!((MIN_AMP..=MAX_AMP).contains(&amp), A2AError::InvalidAmplification);
+    }
 
     let pool = &mut ctx.accounts.pool;
     pool.authority = ctx.accounts.pool_authority.key();
@@ -20,13 +27,35 @@ pub fn handler(ctx: Context<InitializePool>, fee_rate_bps: u16) -> Result<()> {
     pool.fee_growth_global_a = 0;
     pool.fee_growth_global_b = 0;
     pool.bump = ctx.bumps.pool;
+    pool.curve = curve;
+    pool.dynamic_fee = DynamicFee::default();
+    pool.volume_a = 0;
+    pool.volume_b = 0;
+    pool.fees_collected_a = 0;
+    pool.fees_collected_b = 0;
+    pool.version = POOL_VERSION;
+    pool.reserve_a = 0;
+    pool.reserve_b = 0;
+    pool.circuit_breaker = CircuitBreaker::default();
+    pool.creator = ctx.accounts.creator.key();
+    pool.open_positions = 0;
+    pool.reserves_tracked = true;
 
-    msg!(
-        "Pool created: {}/{} fee={}bps",
-        ctx.accounts.token_a_mint.key(),
-        ctx.accounts.token_b_mint.key(),
-        fee_rate_bps
-    );
+    match curve {
+        CurveKind::ConstantProduct => msg!(
+            "Pool created: {}/{} fee={}bps",
+            ctx.accounts.token_a_mint.key(),
+            ctx.accounts.token_b_mint.key(),
+            fee_rate_bps
+        ),
+        CurveKind::StableSwap { amp } => msg!(
+            "Pool created: {}/{} fee={}bps curve=stable amp={}",
+            ctx.accounts.token_a_mint.key(),
+            ctx.accounts.token_b_mint.key(),
+            fee_rate_bps,
+            amp
+        ),
+    }
     Ok(())
 }
 
@@ -73,4 +102,9 @@ pub struct InitializePool<'info> {
     pub token_program: Program<'info, Token>,
     pub system_program: Program<'info, System>,
     pub rent: Sysvar<'info, Rent>,
+
+    /// CHECK: Global treasury PDA; holds the ProtocolConfig (pool fee
+    /// bounds, pause switches) once initialized, parsed in the handler
+    #[account(seeds = [TREASURY_SEED], bump)]
+    pub treasury: UncheckedAccount<'info>,
 }
@@ -0,0 +1,58 @@
+use anchor_lang::prelude::*;
+use crate::{constants::*, error::A2AError, program::A2aSwap, state::ProtocolConfig};
+
+/// Create the global `ProtocolConfig` at the treasury PDA. One-time and
+/// gated on the program's upgrade authority, who becomes the first admin.
+/// Until this runs, swaps charge the compiled-in PROTOCOL_FEE_BPS.
+pub fn handler(
+    ctx: Context<InitializeProtocolConfig>,
+    protocol_fee_bps: u16,
+    referral_share_bps: u16,
+    treasury_authority: Pubkey,
+) -> Result<()> {
+    require!(protocol_fee_bps <= MAX_PROTOCOL_FEE_BPS, A2AError::InvalidProtocolFee);
+    require!(referral_share_bps as u128 <= BPS_DENOMINATOR, A2AError::InvalidReferralShare);
+
+    let config = &mut ctx.accounts.protocol_config;
+    config.admin = ctx.accounts.admin.key();
+    config.treasury_authority = treasury_authority;
+    config.protocol_fee_bps = protocol_fee_bps;
+    config.referral_share_bps = referral_share_bps;
+    config.bump = ctx.bumps.protocol_config;
+
+    msg!(
+        "Protocol config created: admin={} treasury_authority={} fee={}/{} referral_share={}bps",
+        config.admin,
+        treasury_authority,
+        protocol_fee_bps,
+        PROTOCOL_FEE_DENOMINATOR,
+        referral_share_bps
+    );
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct InitializeProtocolConfig<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    #[account(
+        init,
+        payer = admin,
+        space = ProtocolConfig::LEN,
+        seeds = [TREASURY_SEED],
+        bump,
+    )]
+    pub protocol_config: Account<'info, ProtocolConfig>,
+
+    #[account(constraint = program.programdata_address()? == Some(program_data.key()))]
+    pub program: Program<'info, A2aSwap>,
+
+    #[account(
+        constraint = program_data.upgrade_authority_address == Some(admin.key())
+            @ A2AError::Unauthorized,
+    )]
+    pub program_data: Account<'info, ProgramData>,
+
+    pub system_program: Program<'info, System>,
+}
@@ -0,0 +1,101 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{Mint, Token, TokenAccount};
+use crate::{constants::*, error::A2AError, state::RangePool};
+use super::{
+    fee_math::{require_not_paused, require_pool_fee_in_bounds},
+    range_math::sqrt_price_at_tick,
+};
+
+/// Create a concentrated-liquidity pool starting at price 1.0001^initial_tick
+/// (token B per token A, atomic units). As with `initialize_pool`, the PDA
+/// authority owns both vaults and any agent may create one.
+pub fn handler(
+    ctx: Context<InitializeRangePool>,
+    fee_rate_bps: u16,
+    tick_spacing: u16,
+    initial_tick: i32,
+) -> Result<()> {
+    require_not_paused(&ctx.accounts.treasury, PAUSE_POOL_CREATION)?;
+    require_pool_fee_in_bounds(&ctx.accounts.treasury, fee_rate_bps)?;
+    require!(
+        (1..=MAX_TICK_SPACING).contains(&tick_spacing),
+        A2AError::InvalidTickRange
+    );
+
+    let pool = &mut ctx.accounts.pool;
+    pool.authority = ctx.accounts.pool_authority.key();
+    pool.authority_bump = ctx.bumps.pool_authority;
+    pool.token_a_mint = ctx.accounts.token_a_mint.key();
+    pool.token_b_mint = ctx.accounts.token_b_mint.key();
+    pool.token_a_vault = ctx.accounts.token_a_vault.key();
+    pool.token_b_vault = ctx.accounts.token_b_vault.key();
+    pool.fee_rate_bps = fee_rate_bps;
+    pool.tick_spacing = tick_spacing;
+    pool.sqrt_price_x64 = sqrt_price_at_tick(initial_tick)?;
+    pool.tick_current = initial_tick;
+    pool.liquidity = 0;
+    pool.fee_growth_global_a = 0;
+    pool.fee_growth_global_b = 0;
+    pool.bump = ctx.bumps.pool;
+    pool.ticks = Vec::new();
+
+    msg!(
+        "Range pool created: {}/{} fee={}bps spacing={} tick={}",
+        ctx.accounts.token_a_mint.key(),
+        ctx.accounts.token_b_mint.key(),
+        fee_rate_bps,
+        tick_spacing,
+        initial_tick
+    );
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct InitializeRangePool<'info> {
+    #[account(mut)]
+    pub creator: Signer<'info>,
+
+    pub token_a_mint: Account<'info, Mint>,
+    pub token_b_mint: Account<'info, Mint>,
+
+    #[account(
+        init,
+        payer = creator,
+        space = RangePool::LEN,
+        seeds = [RANGE_POOL_SEED, token_a_mint.key().as_ref(), token_b_mint.key().as_ref()],
+        bump,
+    )]
+    pub pool: Box<Account<'info, RangePool>>,
+
+    /// CHECK: PDA vault authority — owns both vaults, holds no data
+    #[account(
+        seeds = [POOL_AUTHORITY_SEED, pool.key().as_ref()],
+        bump,
+    )]
+    pub pool_authority: UncheckedAccount<'info>,
+
+    #[account(
+        init,
+        payer = creator,
+        token::mint = token_a_mint,
+        token::authority = pool_authority,
+    )]
+    pub token_a_vault: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        init,
+        payer = creator,
+        token::mint = token_b_mint,
+        token::authority = pool_authority,
+    )]
+    pub token_b_vault: Box<Account<'info, TokenAccount>>,
+
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+
+    /// CHECK: Global treasury PDA; holds the ProtocolConfig (pool fee
+    /// bounds, pause switches) once initialized, parsed in the handler
+    #[account(seeds = [TREASURY_SEED], bump)]
+    pub treasury: UncheckedAccount<'info>,
+}
@@ -0,0 +1,48 @@
+use anchor_lang::prelude::*;
+use crate::{constants::*, error::A2AError, state::Position};
+use super::fee_math::require_approved_locker;
+
+/// Lock a position as collateral for an approved external program (a lender,
+/// say) without transferring it. Both the owner and the program must sign:
+/// the program through its `[LOCK_AUTHORITY_SEED]` PDA, so this is normally
+/// reached by CPI from the locker.
+///
+/// While locked, the position can't be withdrawn from (`remove_liquidity`)
+/// or closed; the owner can still add liquidity, claim fees and change its
+/// settings. Only the locking program can release it (`unlock_position`).
+pub fn handler(ctx: Context<LockPosition>) -> Result<()> {
+    let locker = ctx.accounts.locker_program.key();
+    require_approved_locker(&ctx.accounts.treasury, &locker)?;
+
+    let position = &mut ctx.accounts.position;
+    position.locked_by = locker;
+
+    msg!("Position locked: {} by={}", position.key(), locker);
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct LockPosition<'info> {
+    pub owner: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [POSITION_SEED, position.pool.as_ref(), owner.key().as_ref()],
+        bump = position.bump,
+        constraint = position.owner == owner.key() @ A2AError::Unauthorized,
+        constraint = !position.is_locked() @ A2AError::PositionLocked,
+    )]
+    pub position: Account<'info, Position>,
+
+    /// The locker program's signing PDA
+    #[account(seeds = [LOCK_AUTHORITY_SEED], bump, seeds::program = locker_program.key())]
+    pub locker_authority: Signer<'info>,
+
+    /// CHECK: only its key is used — checked against ProtocolConfig::lockers
+    /// and as the program `locker_authority` is derived under
+    pub locker_program: UncheckedAccount<'info>,
+
+    /// CHECK: Global treasury PDA; holds the ProtocolConfig, parsed in the handler
+    #[account(seeds = [TREASURY_SEED], bump)]
+    pub treasury: UncheckedAccount<'info>,
+}
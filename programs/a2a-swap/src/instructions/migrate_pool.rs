@@ -0,0 +1,93 @@
+use anchor_lang::prelude::*;
+use anchor_lang::system_program::{self, Transfer};
+use anchor_lang::Discriminator;
+use crate::{constants::POOL_VERSION, state::Pool};
+
+/// Bring a pool created under an older layout up to the current one: grow
+/// it to `Pool::LEN` and stamp `version = POOL_VERSION`. Fields added since
+/// are zeroed, which decodes as `CurveKind::ConstantProduct`, dynamic fees
+/// disabled, counters starting from zero, untracked reserves (taken from
+/// the vaults on the next swap or deposit), no circuit breaker, no
+/// recorded creator, no allowlist and no gate token, so the pool's
+/// behaviour is unchanged. Positions opened before `open_positions` existed
+/// can't be counted, so such a pool is marked `UNCOUNTED_POSITIONS` and can
+/// never be closed. Reserves from before `reserves_tracked` count as
+/// tracked once either is non-zero, or the pool has no LP shares left to
+/// price. Permissionless: the caller only tops up rent.
+/// A no-op for pools that are already current.
+pub fn handler(ctx: Context<MigratePool>) -> Result<()> {
+    let pool = ctx.accounts.pool.to_account_info();
+    require!(
+        pool.try_borrow_data()?.starts_with(Pool::DISCRIMINATOR)
+            && (pool.data_len() >= Pool::LEN || Pool::PREVIOUS_LENS.contains(&pool.data_len())),
+        ErrorCode::AccountDiscriminatorMismatch
+    );
+
+    let len_before = pool.data_len();
+    if len_before < Pool::LEN {
+        grow_account(
+            &ctx.accounts.payer,
+            &pool,
+            &ctx.accounts.system_program,
+            Pool::LEN,
+        )?;
+    }
+
+    let mut state = Pool::try_deserialize(&mut &pool.try_borrow_data()?[..])?;
+    if state.version >= POOL_VERSION {
+        return Ok(());
+    }
+    let from = state.version;
+    state.version = POOL_VERSION;
+    if len_before <= Pool::GATE_LEN {
+        state.open_positions = Pool::UNCOUNTED_POSITIONS;
+    }
+    if len_before <= Pool::OPEN_POSITIONS_LEN {
+        state.reserves_tracked =
+            state.reserve_a != 0 || state.reserve_b != 0 || state.lp_supply == 0;
+    }
+    state.try_serialize(&mut &mut pool.try_borrow_mut_data()?[..])?;
+
+    msg!("Pool migrated: {} v{} -> v{}", pool.key(), from, POOL_VERSION);
+    Ok(())
+}
+
+/// Top `account` up to rent exemption at `len` bytes from `payer`, then
+/// resize it. New bytes are zeroed.
+pub(crate) fn grow_account<'info>(
+    payer: &Signer<'info>,
+    account: &AccountInfo<'info>,
+    system_program: &Program<'info, System>,
+    len: usize,
+) -> Result<()> {
+    let shortfall = Rent::get()?
+        .minimum_balance(len)
+        .saturating_sub(account.lamports());
+    if shortfall > 0 {
+        system_program::transfer(
+            CpiContext::new(
+                system_program.to_account_info(),
+                Transfer {
+                    from: payer.to_account_info(),
+                    to: account.clone(),
+                },
+            ),
+            shortfall,
+        )?;
+    }
+    account.resize(len)?;
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct MigratePool<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    /// CHECK: legacy pools don't deserialize as `Pool` until migrated;
+    /// ownership and discriminator are checked in the handler
+    #[account(mut, owner = crate::ID)]
+    pub pool: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
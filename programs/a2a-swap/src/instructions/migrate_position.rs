@@ -0,0 +1,54 @@
+use anchor_lang::prelude::*;
+use anchor_lang::Discriminator;
+use crate::{constants::POSITION_VERSION, state::Position};
+use super::migrate_pool::grow_account;
+
+/// Bring a position created under an older layout up to the current one:
+/// grow it to `Position::LEN` and stamp `version = POSITION_VERSION`. Shares
+/// and fee checkpoints are untouched; appended fields start zeroed, so a
+/// migrated position is unlocked. Permissionless, like `migrate_pool`:
+/// the caller only tops up rent. A no-op for positions that are already
+/// current.
+pub fn handler(ctx: Context<MigratePosition>) -> Result<()> {
+    let position = ctx.accounts.position.to_account_info();
+    require!(
+        position.try_borrow_data()?.starts_with(Position::DISCRIMINATOR)
+            && (position.data_len() >= Position::LEN
+                || position.data_len() == Position::V1_LEN
+                || position.data_len() == Position::LEGACY_LEN),
+        ErrorCode::AccountDiscriminatorMismatch
+    );
+
+    if position.data_len() < Position::LEN {
+        grow_account(
+            &ctx.accounts.payer,
+            &position,
+            &ctx.accounts.system_program,
+            Position::LEN,
+        )?;
+    }
+
+    let mut state = Position::try_deserialize(&mut &position.try_borrow_data()?[..])?;
+    if state.version >= POSITION_VERSION {
+        return Ok(());
+    }
+    let from = state.version;
+    state.version = POSITION_VERSION;
+    state.try_serialize(&mut &mut position.try_borrow_mut_data()?[..])?;
+
+    msg!("Position migrated: {} v{} -> v{}", position.key(), from, POSITION_VERSION);
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct MigratePosition<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    /// CHECK: legacy positions don't deserialize as `Position` until
+    /// migrated; ownership and discriminator are checked in the handler
+    #[account(mut, owner = crate::ID)]
+    pub position: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
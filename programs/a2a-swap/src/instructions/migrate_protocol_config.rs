@@ -0,0 +1,45 @@
+use anchor_lang::prelude::*;
+use anchor_lang::Discriminator;
+use crate::{constants::TREASURY_SEED, state::ProtocolConfig};
+use super::migrate_pool::grow_account;
+
+/// Grow a `ProtocolConfig` created before the pause switches, pool fee
+/// bounds, fee tiers or lockers existed to `ProtocolConfig::LEN`. The new
+/// fields are zeroed: nothing paused, the default 1–100 bps pool fee bounds,
+/// no fee discounts and no approved lockers, so behaviour is unchanged. Permissionless: the caller only tops
+/// up rent. A no-op once the config is current.
+pub fn handler(ctx: Context<MigrateProtocolConfig>) -> Result<()> {
+    let config = ctx.accounts.protocol_config.to_account_info();
+    require!(
+        config.try_borrow_data()?.starts_with(ProtocolConfig::DISCRIMINATOR)
+            && config.data_len() >= ProtocolConfig::LEGACY_LEN,
+        ErrorCode::AccountDiscriminatorMismatch
+    );
+    let old_len = config.data_len();
+    if old_len >= ProtocolConfig::LEN {
+        return Ok(());
+    }
+
+    grow_account(
+        &ctx.accounts.payer,
+        &config,
+        &ctx.accounts.system_program,
+        ProtocolConfig::LEN,
+    )?;
+
+    msg!("Protocol config migrated: {} -> {} bytes", old_len, ProtocolConfig::LEN);
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct MigrateProtocolConfig<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    /// CHECK: a legacy config doesn't deserialize as `ProtocolConfig` until
+    /// migrated; ownership and discriminator are checked in the handler
+    #[account(mut, owner = crate::ID, seeds = [TREASURY_SEED], bump)]
+    pub protocol_config: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
@@ -0,0 +1,35 @@
+use anchor_lang::prelude::*;
+use crate::{constants::*, state::AgentVolume};
+
+/// Start tracking `agent`'s swap volume for protocol fee discount tiers.
+///
+/// Opt-in: swaps only count, and only get the tier discount, when the
+/// account is passed as `swap`'s trailing `agent_volume`. Volume starts at
+/// zero; nothing from before the account existed is counted.
+pub fn handler(ctx: Context<OpenAgentVolume>) -> Result<()> {
+    let v = &mut ctx.accounts.agent_volume;
+    v.agent = ctx.accounts.agent.key();
+    v.volume = 0;
+    v.swaps = 0;
+    v.bump = ctx.bumps.agent_volume;
+
+    msg!("Agent volume opened: agent={}", v.agent);
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct OpenAgentVolume<'info> {
+    #[account(mut)]
+    pub agent: Signer<'info>,
+
+    #[account(
+        init,
+        payer = agent,
+        space = AgentVolume::LEN,
+        seeds = [AGENT_VOLUME_SEED, agent.key().as_ref()],
+        bump,
+    )]
+    pub agent_volume: Account<'info, AgentVolume>,
+
+    pub system_program: Program<'info, System>,
+}
@@ -1,6 +1,7 @@
 use anchor_lang::prelude::*;
 use anchor_spl::token::{self, Token, TokenAccount, Transfer};
 use crate::{constants::*, error::A2AError, state::{Pool, Position}};
+use super::receipt::{mint_receipt, receipt_accounts};
 
 // ─── Fee accrual ───────────────────────────────────────────────────────────
 // Call before any change to position.lp_shares.
@@ -36,7 +37,8 @@ pub fn isqrt(n: u128) -> u128 {
         return 0;
     }
     let mut x = n;
-    let mut y = (x + 1) >> 1;
+    // ceil(n / 2) without the `n + 1` overflow at u128::MAX
+    let mut y = (x >> 1) + (x & 1);
     while y < x {
         x = y;
         y = (y + n / y) >> 1;
@@ -44,12 +46,62 @@ pub fn isqrt(n: u128) -> u128 {
     x
 }
 
+// ─── LP share math ────────────────────────────────────────────────────────
+/// LP shares minted for depositing `amount_a` / `amount_b`: sqrt(a * b) for
+/// the first deposit, otherwise proportional to the smaller of the two
+/// ratios against the reserves, so a lopsided deposit can't dilute LPs.
+pub fn lp_shares_for_deposit(
+    amount_a: u64,
+    amount_b: u64,
+    lp_supply: u64,
+    reserve_a: u64,
+    reserve_b: u64,
+) -> Result<u64> {
+    if lp_supply == 0 {
+        let product = (amount_a as u128)
+            .checked_mul(amount_b as u128)
+            .ok_or(A2AError::MathOverflow)?;
+        return Ok(isqrt(product) as u64);
+    }
+    require!(reserve_a > 0 && reserve_b > 0, A2AError::InsufficientLiquidity);
+    let lp_a = (amount_a as u128)
+        .checked_mul(lp_supply as u128)
+        .ok_or(A2AError::MathOverflow)?
+        / reserve_a as u128;
+    let lp_b = (amount_b as u128)
+        .checked_mul(lp_supply as u128)
+        .ok_or(A2AError::MathOverflow)?
+        / reserve_b as u128;
+    Ok(lp_a.min(lp_b) as u64)
+}
+
+/// `amount` fits under a caller's cap, where a cap of 0 means none.
+pub fn within_max(amount: u64, max: u64) -> bool {
+    max == 0 || amount <= max
+}
+
+/// The other side of a deposit of `amount` against `reserve_from` at the
+/// pool's current ratio, rounded up so the deposit never dilutes LPs.
+pub fn proportional_amount(amount: u64, reserve_from: u64, reserve_to: u64) -> Result<u64> {
+    require!(reserve_from > 0 && reserve_to > 0, A2AError::InsufficientLiquidity);
+    let other = (amount as u128)
+        .checked_mul(reserve_to as u128)
+        .ok_or(A2AError::MathOverflow)?
+        .div_ceil(reserve_from as u128);
+    u64::try_from(other).map_err(|_| error!(A2AError::MathOverflow))
+}
+
 // ─── Handler ──────────────────────────────────────────────────────────────
 /// Add liquidity. Mints LP shares proportional to the deposit.
 /// First depositor sets the initial price via their amount_a / amount_b ratio.
 /// auto_compound: if true, claim_fees reinvests rather than transfers.
-pub fn handler(
-    ctx: Context<ProvideLiquidity>,
+/// Optional remaining accounts `[receipt, mpl_core_program]` mint an LP
+/// receipt NFT to the agent if the position doesn't have one yet.
+/// The deposit is exactly `amount_a` / `amount_b`; to have the program size
+/// one side from the reserves at execution, with a cap, use
+/// `provide_liquidity_proportional`.
+pub fn handler<'info>(
+    ctx: Context<'_, '_, 'info, 'info, ProvideLiquidity<'info>>,
     amount_a: u64,
     amount_b: u64,
     min_lp: u64,
@@ -57,35 +109,32 @@ pub fn handler(
     compound_threshold: u64,
 ) -> Result<()> {
     require!(amount_a > 0 && amount_b > 0, A2AError::ZeroAmount);
+    deposit(ctx, amount_a, amount_b, min_lp, auto_compound, compound_threshold)
+}
 
+/// Deposit exactly `amount_a` / `amount_b`: mint the LP shares, update the
+/// position and pool, move the tokens and mint a receipt if asked.
+/// Shared by `provide_liquidity` and `provide_liquidity_proportional`.
+pub(crate) fn deposit<'info>(
+    ctx: Context<'_, '_, 'info, 'info, ProvideLiquidity<'info>>,
+    amount_a: u64,
+    amount_b: u64,
+    min_lp: u64,
+    auto_compound: bool,
+    compound_threshold: u64,
+) -> Result<()> {
     // Read pool state into locals before any mutable borrows
     let lp_supply = ctx.accounts.pool.lp_supply;
-    let reserve_a = ctx.accounts.token_a_vault.amount;
-    let reserve_b = ctx.accounts.token_b_vault.amount;
+    let (reserve_a, reserve_b) = ctx
+        .accounts
+        .pool
+        .reserves(ctx.accounts.token_a_vault.amount, ctx.accounts.token_b_vault.amount);
     let fg_a = ctx.accounts.pool.fee_growth_global_a;
     let fg_b = ctx.accounts.pool.fee_growth_global_b;
+    // A freshly created position account is still all zeros
+    let opened = ctx.accounts.position.owner == Pubkey::default();
 
-    // Compute LP shares to mint
-    let lp_minted: u64 = if lp_supply == 0 {
-        // First deposit: LP = sqrt(a * b)
-        let product = (amount_a as u128)
-            .checked_mul(amount_b as u128)
-            .ok_or(A2AError::MathOverflow)?;
-        isqrt(product) as u64
-    } else {
-        require!(reserve_a > 0 && reserve_b > 0, A2AError::InsufficientLiquidity);
-        // Proportional to smaller ratio to prevent dilution
-        let lp_a = (amount_a as u128)
-            .checked_mul(lp_supply as u128)
-            .ok_or(A2AError::MathOverflow)?
-            / reserve_a as u128;
-        let lp_b = (amount_b as u128)
-            .checked_mul(lp_supply as u128)
-            .ok_or(A2AError::MathOverflow)?
-            / reserve_b as u128;
-        lp_a.min(lp_b) as u64
-    };
-
+    let lp_minted = lp_shares_for_deposit(amount_a, amount_b, lp_supply, reserve_a, reserve_b)?;
     require!(lp_minted > 0, A2AError::ZeroAmount);
     require!(lp_minted >= min_lp, A2AError::SlippageExceeded);
 
@@ -103,6 +152,7 @@ pub fn handler(
             pos.fees_owed_a = 0;
             pos.fees_owed_b = 0;
             pos.bump = ctx.bumps.position;
+            pos.version = POSITION_VERSION;
         }
         pos.lp_shares = pos
             .lp_shares
@@ -112,10 +162,18 @@ pub fn handler(
         pos.compound_threshold = compound_threshold;
     }
 
-    // Update pool LP supply
-    ctx.accounts.pool.lp_supply = lp_supply
+    // Update pool LP supply and reserves
+    let pool = &mut ctx.accounts.pool;
+    pool.lp_supply = lp_supply
         .checked_add(lp_minted)
         .ok_or(A2AError::MathOverflow)?;
+    pool.set_reserves(
+        reserve_a.checked_add(amount_a).ok_or(A2AError::MathOverflow)?,
+        reserve_b.checked_add(amount_b).ok_or(A2AError::MathOverflow)?,
+    );
+    if opened {
+        pool.position_opened();
+    }
 
     // Transfer tokens from agent into vaults
     token::transfer(
@@ -141,6 +199,25 @@ pub fn handler(
         amount_b,
     )?;
 
+    let position_key = ctx.accounts.position.key();
+    if let Some((receipt, mpl_core, bump)) =
+        receipt_accounts(ctx.remaining_accounts, &position_key)?
+    {
+        if receipt.data_is_empty() {
+            mint_receipt(
+                receipt,
+                mpl_core,
+                bump,
+                &position_key,
+                &ctx.accounts.pool.key(),
+                &ctx.accounts.agent.to_account_info(),
+                &ctx.accounts.pool_authority.to_account_info(),
+                &ctx.accounts.system_program.to_account_info(),
+            )?;
+            msg!("Receipt minted: {}", receipt.key());
+        }
+    }
+
     msg!(
         "Liquidity provided: lp={} a={} b={} auto_compound={}",
         lp_minted, amount_a, amount_b, auto_compound
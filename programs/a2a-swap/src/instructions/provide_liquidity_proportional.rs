@@ -0,0 +1,40 @@
+use anchor_lang::prelude::*;
+use crate::error::A2AError;
+use super::provide_liquidity::{deposit, proportional_amount, within_max, ProvideLiquidity};
+
+/// Add liquidity naming one side only: the program computes the other from
+/// the pool's reserves at execution, so the deposit can't drift off the
+/// current ratio between the agent's quote and landing.
+///
+/// `amount` is token A when `fixed_a`, token B otherwise; the other token's
+/// amount is rounded up and rejected above `max_other` (0 = no cap). Same
+/// accounts, LP math and receipt handling as `provide_liquidity`. The pool
+/// must already have liquidity — the first deposit sets the price and needs
+/// both amounts.
+#[allow(clippy::too_many_arguments)]
+pub fn handler<'info>(
+    ctx: Context<'_, '_, 'info, 'info, ProvideLiquidity<'info>>,
+    amount: u64,
+    fixed_a: bool,
+    max_other: u64,
+    min_lp: u64,
+    auto_compound: bool,
+    compound_threshold: u64,
+) -> Result<()> {
+    require!(amount > 0, A2AError::ZeroAmount);
+    require!(ctx.accounts.pool.lp_supply > 0, A2AError::InsufficientLiquidity);
+
+    let (reserve_a, reserve_b) = ctx
+        .accounts
+        .pool
+        .reserves(ctx.accounts.token_a_vault.amount, ctx.accounts.token_b_vault.amount);
+    let (amount_a, amount_b) = if fixed_a {
+        (amount, proportional_amount(amount, reserve_a, reserve_b)?)
+    } else {
+        (proportional_amount(amount, reserve_b, reserve_a)?, amount)
+    };
+    let other = if fixed_a { amount_b } else { amount_a };
+    require!(within_max(other, max_other), A2AError::DepositExceedsMax);
+
+    deposit(ctx, amount_a, amount_b, min_lp, auto_compound, compound_threshold)
+}
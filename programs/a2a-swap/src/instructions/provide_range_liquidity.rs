@@ -0,0 +1,130 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Token, TokenAccount, Transfer};
+use crate::{constants::*, error::A2AError, state::{RangePool, RangePosition}};
+use super::range_math::{
+    amounts_for_liquidity, check_tick_range, liquidity_for_amounts, sqrt_price_at_tick,
+    update_position,
+};
+
+/// Add liquidity to the [tick_lower, tick_upper) range of a range pool.
+/// Mints the most liquidity `amount_a_max` / `amount_b_max` can fund at the
+/// current price and transfers only what that liquidity needs: token A
+/// alone above the range, token B alone below it, both inside it.
+/// Earlier fees on the same position are synced into fees_owed first.
+pub fn handler(
+    ctx: Context<ProvideRangeLiquidity>,
+    tick_lower: i32,
+    tick_upper: i32,
+    amount_a_max: u64,
+    amount_b_max: u64,
+    min_liquidity: u128,
+) -> Result<()> {
+    let pool = &mut ctx.accounts.pool;
+    check_tick_range(tick_lower, tick_upper, pool.tick_spacing)?;
+
+    let sqrt_lo = sqrt_price_at_tick(tick_lower)?;
+    let sqrt_hi = sqrt_price_at_tick(tick_upper)?;
+    let liquidity = liquidity_for_amounts(
+        pool.sqrt_price_x64, sqrt_lo, sqrt_hi, amount_a_max, amount_b_max,
+    )?;
+    require!(liquidity > 0, A2AError::ZeroAmount);
+    require!(liquidity >= min_liquidity, A2AError::SlippageExceeded);
+    let (amount_a, amount_b) =
+        amounts_for_liquidity(pool.sqrt_price_x64, sqrt_lo, sqrt_hi, liquidity, true)?;
+    require!(
+        amount_a <= amount_a_max && amount_b <= amount_b_max,
+        A2AError::SlippageExceeded
+    );
+
+    let pos = &mut ctx.accounts.position;
+    if pos.owner == Pubkey::default() {
+        // New position — initialise fields
+        pos.owner = ctx.accounts.agent.key();
+        pos.pool = pool.key();
+        pos.tick_lower = tick_lower;
+        pos.tick_upper = tick_upper;
+        pos.bump = ctx.bumps.position;
+    }
+    let delta = i128::try_from(liquidity).map_err(|_| A2AError::MathOverflow)?;
+    update_position(pool, pos, delta)?;
+
+    for (amount, from, to) in [
+        (amount_a, &ctx.accounts.agent_token_a, &ctx.accounts.token_a_vault),
+        (amount_b, &ctx.accounts.agent_token_b, &ctx.accounts.token_b_vault),
+    ] {
+        if amount > 0 {
+            token::transfer(
+                CpiContext::new(
+                    ctx.accounts.token_program.to_account_info(),
+                    Transfer {
+                        from: from.to_account_info(),
+                        to: to.to_account_info(),
+                        authority: ctx.accounts.agent.to_account_info(),
+                    },
+                ),
+                amount,
+            )?;
+        }
+    }
+
+    msg!(
+        "Range liquidity provided: liquidity={} a={} b={} range=[{},{})",
+        liquidity, amount_a, amount_b, tick_lower, tick_upper
+    );
+    Ok(())
+}
+
+#[derive(Accounts)]
+#[instruction(tick_lower: i32, tick_upper: i32)]
+pub struct ProvideRangeLiquidity<'info> {
+    #[account(mut)]
+    pub agent: Signer<'info>,
+
+    #[account(mut)]
+    pub pool: Box<Account<'info, RangePool>>,
+
+    #[account(
+        init_if_needed,
+        payer = agent,
+        space = RangePosition::LEN,
+        seeds = [
+            RANGE_POSITION_SEED,
+            pool.key().as_ref(),
+            agent.key().as_ref(),
+            &tick_lower.to_le_bytes(),
+            &tick_upper.to_le_bytes(),
+        ],
+        bump,
+    )]
+    pub position: Box<Account<'info, RangePosition>>,
+
+    #[account(
+        mut,
+        constraint = token_a_vault.key() == pool.token_a_vault @ A2AError::MintMismatch,
+    )]
+    pub token_a_vault: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        constraint = token_b_vault.key() == pool.token_b_vault @ A2AError::MintMismatch,
+    )]
+    pub token_b_vault: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        constraint = agent_token_a.mint == pool.token_a_mint @ A2AError::MintMismatch,
+        constraint = agent_token_a.owner == agent.key(),
+    )]
+    pub agent_token_a: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        constraint = agent_token_b.mint == pool.token_b_mint @ A2AError::MintMismatch,
+        constraint = agent_token_b.owner == agent.key(),
+    )]
+    pub agent_token_b: Box<Account<'info, TokenAccount>>,
+
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
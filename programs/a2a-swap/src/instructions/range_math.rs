@@ -0,0 +1,497 @@
+use anchor_lang::prelude::*;
+use crate::{
+    constants::*,
+    error::A2AError,
+    state::{RangePool, RangePosition, Tick},
+};
+
+// ─── Concentrated liquidity ────────────────────────────────────────────────
+// Uniswap-v3-style math on Q64.64 sqrt prices. For liquidity L over a range
+// [√p_lo, √p_hi]:
+//   token A = L · (√p_hi − √p_lo) / (√p_lo · √p_hi)
+//   token B = L · (√p_hi − √p_lo)
+// Amounts paid into the pool round up, amounts paid out round down.
+// The SDK mirrors this file in packages/sdk-rust/src/range_math.rs.
+
+/// 2⁶⁴ / √1.0001^(2^i), i = 0..19 — enough bits for |tick| <= MAX_TICK.
+const INV_SQRT_POW: [u128; 19] = [
+    0xfffcb933bd6fad37,
+    0xfff97272373d4132,
+    0xfff2e50f5f656932,
+    0xffe5caca7e10e4e6,
+    0xffcb9843d60f6159,
+    0xff973b41fa98c081,
+    0xff2ea16466c96a38,
+    0xfe5dee046a99a2a8,
+    0xfcbe86c7900a88ae,
+    0xf987a7253ac41317,
+    0xf3392b0822b70005,
+    0xe7159475a2c29b74,
+    0xd097f3bdfd2022b8,
+    0xa9f746462d870fdf,
+    0x70d869a156d2a1b8,
+    0x31be135f97d08fd9,
+    0x09aa508b5b7a84e1,
+    0x005d6af8dedb8119,
+    0x00002216e584f5fa,
+];
+
+// ─── 256-bit intermediates ─────────────────────────────────────────────────
+
+/// Full 256-bit product as (high, low) halves.
+fn mul_full(a: u128, b: u128) -> (u128, u128) {
+    const MASK: u128 = u64::MAX as u128;
+    let (a1, a0) = (a >> 64, a & MASK);
+    let (b1, b0) = (b >> 64, b & MASK);
+    let p00 = a0 * b0;
+    let p01 = a0 * b1;
+    let p10 = a1 * b0;
+    let mid = (p00 >> 64) + (p01 & MASK) + (p10 & MASK);
+    let lo = (p00 & MASK) | (mid << 64);
+    let hi = a1 * b1 + (p01 >> 64) + (p10 >> 64) + (mid >> 64);
+    (hi, lo)
+}
+
+/// `(a · b / denom, a · b % denom)` without intermediate overflow.
+fn mul_div_rem(a: u128, b: u128, denom: u128) -> Result<(u128, u128)> {
+    require!(denom > 0, A2AError::MathOverflow);
+    let (hi, lo) = mul_full(a, b);
+    if hi == 0 {
+        return Ok((lo / denom, lo % denom));
+    }
+    // Quotient must fit in 128 bits.
+    require!(hi < denom, A2AError::MathOverflow);
+    let (mut q, mut r) = (0u128, hi);
+    for i in (0..128).rev() {
+        let carry = r >> 127;
+        r = (r << 1) | ((lo >> i) & 1);
+        q <<= 1;
+        if carry == 1 || r >= denom {
+            r = r.wrapping_sub(denom);
+            q |= 1;
+        }
+    }
+    Ok((q, r))
+}
+
+/// floor(a · b / denom)
+pub fn mul_div(a: u128, b: u128, denom: u128) -> Result<u128> {
+    Ok(mul_div_rem(a, b, denom)?.0)
+}
+
+/// ceil(a · b / denom)
+pub fn mul_div_ceil(a: u128, b: u128, denom: u128) -> Result<u128> {
+    let (q, r) = mul_div_rem(a, b, denom)?;
+    if r == 0 {
+        Ok(q)
+    } else {
+        q.checked_add(1).ok_or(error!(A2AError::MathOverflow))
+    }
+}
+
+// ─── Tick math ─────────────────────────────────────────────────────────────
+
+/// √1.0001^tick in Q64.64.
+pub fn sqrt_price_at_tick(tick: i32) -> Result<u128> {
+    require!((MIN_TICK..=MAX_TICK).contains(&tick), A2AError::InvalidTickRange);
+    let abs = tick.unsigned_abs();
+    // Start from 1.0 and multiply in 1/√1.0001^(2^i) for each set bit.
+    // ratio <= 2⁶⁴ and every constant < 2⁶⁴, so the product fits in u128.
+    let mut ratio = Q64;
+    for (i, k) in INV_SQRT_POW.iter().enumerate() {
+        if abs & (1 << i) != 0 {
+            ratio = (ratio * k) >> 64;
+        }
+    }
+    Ok(if tick > 0 { u128::MAX / ratio } else { ratio })
+}
+
+/// Greatest tick whose sqrt price is <= `sqrt_price_x64`.
+pub fn tick_at_sqrt_price(sqrt_price_x64: u128) -> Result<i32> {
+    require!(
+        sqrt_price_x64 >= sqrt_price_at_tick(MIN_TICK)?
+            && sqrt_price_x64 <= sqrt_price_at_tick(MAX_TICK)?,
+        A2AError::InvalidTickRange
+    );
+    let (mut lo, mut hi) = (MIN_TICK, MAX_TICK);
+    while lo < hi {
+        let mid = lo + (hi - lo + 1) / 2;
+        if sqrt_price_at_tick(mid)? <= sqrt_price_x64 {
+            lo = mid;
+        } else {
+            hi = mid - 1;
+        }
+    }
+    Ok(lo)
+}
+
+/// Bounds are ordered, on the pool's spacing and within MIN/MAX_TICK.
+pub fn check_tick_range(tick_lower: i32, tick_upper: i32, tick_spacing: u16) -> Result<()> {
+    let spacing = tick_spacing as i32;
+    require!(
+        tick_lower < tick_upper
+            && tick_lower >= MIN_TICK
+            && tick_upper <= MAX_TICK
+            && tick_lower % spacing == 0
+            && tick_upper % spacing == 0,
+        A2AError::InvalidTickRange
+    );
+    Ok(())
+}
+
+// ─── Amounts ↔ liquidity ───────────────────────────────────────────────────
+
+/// Token A spanned by `liquidity` between two sqrt prices (lo <= hi).
+pub fn amount_a_delta(sqrt_lo: u128, sqrt_hi: u128, liquidity: u128, round_up: bool) -> Result<u128> {
+    if round_up {
+        mul_div_ceil(mul_div_ceil(liquidity, Q64, sqrt_lo)?, sqrt_hi - sqrt_lo, sqrt_hi)
+    } else {
+        mul_div(mul_div(liquidity, Q64, sqrt_lo)?, sqrt_hi - sqrt_lo, sqrt_hi)
+    }
+}
+
+/// Token B spanned by `liquidity` between two sqrt prices (lo <= hi).
+pub fn amount_b_delta(sqrt_lo: u128, sqrt_hi: u128, liquidity: u128, round_up: bool) -> Result<u128> {
+    if round_up {
+        mul_div_ceil(liquidity, sqrt_hi - sqrt_lo, Q64)
+    } else {
+        mul_div(liquidity, sqrt_hi - sqrt_lo, Q64)
+    }
+}
+
+/// Largest liquidity `amount_a` / `amount_b` can fund in [sqrt_lo, sqrt_hi)
+/// at the current price. Only token A is needed above the range, only B below.
+pub fn liquidity_for_amounts(
+    sqrt_price: u128,
+    sqrt_lo: u128,
+    sqrt_hi: u128,
+    amount_a: u64,
+    amount_b: u64,
+) -> Result<u128> {
+    // L = a · √hi / (√hi − √lo) · √lo, floored at each step so that
+    // amount_a_delta(.., L, true) never exceeds a.
+    let from_a = |lo: u128| -> Result<u128> {
+        mul_div(mul_div(amount_a as u128, sqrt_hi, sqrt_hi - lo)?, lo, Q64)
+    };
+    let from_b = |hi: u128| -> Result<u128> { mul_div(amount_b as u128, Q64, hi - sqrt_lo) };
+
+    if sqrt_price <= sqrt_lo {
+        from_a(sqrt_lo)
+    } else if sqrt_price >= sqrt_hi {
+        from_b(sqrt_hi)
+    } else {
+        Ok(from_a(sqrt_price)?.min(from_b(sqrt_price)?))
+    }
+}
+
+/// Token amounts `liquidity` represents in [sqrt_lo, sqrt_hi) at the current price.
+pub fn amounts_for_liquidity(
+    sqrt_price: u128,
+    sqrt_lo: u128,
+    sqrt_hi: u128,
+    liquidity: u128,
+    round_up: bool,
+) -> Result<(u64, u64)> {
+    let (a, b) = if sqrt_price <= sqrt_lo {
+        (amount_a_delta(sqrt_lo, sqrt_hi, liquidity, round_up)?, 0)
+    } else if sqrt_price >= sqrt_hi {
+        (0, amount_b_delta(sqrt_lo, sqrt_hi, liquidity, round_up)?)
+    } else {
+        (
+            amount_a_delta(sqrt_price, sqrt_hi, liquidity, round_up)?,
+            amount_b_delta(sqrt_lo, sqrt_price, liquidity, round_up)?,
+        )
+    };
+    Ok((
+        u64::try_from(a).map_err(|_| A2AError::MathOverflow)?,
+        u64::try_from(b).map_err(|_| A2AError::MathOverflow)?,
+    ))
+}
+
+// ─── Swap step ─────────────────────────────────────────────────────────────
+
+pub struct SwapStep {
+    pub sqrt_price_next: u128,
+    pub amount_in: u128,
+    pub amount_out: u128,
+}
+
+/// Move from `sqrt_price` toward `sqrt_target` with constant `liquidity`,
+/// consuming at most `remaining` input. A→B lowers the price.
+pub fn swap_step(
+    sqrt_price: u128,
+    sqrt_target: u128,
+    liquidity: u128,
+    remaining: u128,
+    a_to_b: bool,
+) -> Result<SwapStep> {
+    if a_to_b {
+        let max_in = amount_a_delta(sqrt_target, sqrt_price, liquidity, true)?;
+        let (next, amount_in) = if remaining >= max_in {
+            (sqrt_target, max_in)
+        } else {
+            // √p' = L·√p / (L + Δa·√p), rounded up so the pool keeps the dust.
+            let denom = liquidity
+                .checked_add(mul_div(remaining, sqrt_price, Q64)?)
+                .ok_or(A2AError::MathOverflow)?;
+            let next = mul_div_ceil(liquidity, sqrt_price, denom)?;
+            (next.max(sqrt_target), remaining)
+        };
+        let amount_out = amount_b_delta(next, sqrt_price, liquidity, false)?;
+        Ok(SwapStep { sqrt_price_next: next, amount_in, amount_out })
+    } else {
+        let max_in = amount_b_delta(sqrt_price, sqrt_target, liquidity, true)?;
+        let (next, amount_in) = if remaining >= max_in {
+            (sqrt_target, max_in)
+        } else {
+            // √p' = √p + Δb / L, rounded down.
+            let next = sqrt_price
+                .checked_add(mul_div(remaining, Q64, liquidity)?)
+                .ok_or(A2AError::MathOverflow)?;
+            (next.min(sqrt_target), remaining)
+        };
+        let amount_out = amount_a_delta(sqrt_price, next, liquidity, false)?;
+        Ok(SwapStep { sqrt_price_next: next, amount_in, amount_out })
+    }
+}
+
+// ─── Swap ──────────────────────────────────────────────────────────────────
+
+/// Result of a range-pool swap; the fee split matches `fee_math::compute_swap`.
+pub struct RangeSwapAmounts {
+    /// Protocol fee taken from amount_in (sent to treasury).
+    pub protocol_fee: u64,
+    /// Net amount entering the vault (amount_in − protocol_fee).
+    pub net_pool_input: u64,
+    /// LP fee, credited to the in-range liquidity of each step it was earned in.
+    pub lp_fee: u64,
+    /// Tokens sent to the agent from the output vault.
+    pub amount_out: u64,
+    /// Shortfall of amount_out against after_fees at the pre-trade price.
+    pub price_impact_bps: u64,
+}
+
+/// Swap `amount_in` through `pool`, crossing ticks as needed, and update
+/// its price, active liquidity, ticks and fee growth in place.
+/// Fails with `InsufficientLiquidity` if the ranges run out before the
+/// input is used up; `pool` is left partially updated on error, which the
+/// failed instruction discards.
+pub fn compute_range_swap(
+    pool: &mut RangePool,
+    amount_in: u64,
+    protocol_fee_bps: u64,
+    a_to_b: bool,
+    min_amount_out: u64,
+) -> Result<RangeSwapAmounts> {
+    let in_u128 = amount_in as u128;
+    let protocol_fee = in_u128
+        .checked_mul(protocol_fee_bps as u128)
+        .ok_or(A2AError::MathOverflow)?
+        / PROTOCOL_FEE_DENOMINATOR;
+    let net_pool_input = in_u128 - protocol_fee;
+    let lp_fee = net_pool_input
+        .checked_mul(pool.fee_rate_bps as u128)
+        .ok_or(A2AError::MathOverflow)?
+        / BPS_DENOMINATOR;
+    let after_fees = net_pool_input - lp_fee;
+
+    let start_price = pool.sqrt_price_x64;
+    let mut remaining = after_fees;
+    let mut fee_left = lp_fee;
+    let mut amount_out: u128 = 0;
+
+    while remaining > 0 {
+        let next = next_initialized_tick(pool, a_to_b);
+        let target = match next {
+            Some(i) => sqrt_price_at_tick(pool.ticks[i].index)?,
+            None => sqrt_price_at_tick(if a_to_b { MIN_TICK } else { MAX_TICK })?,
+        };
+        let step = swap_step(pool.sqrt_price_x64, target, pool.liquidity, remaining, a_to_b)?;
+        remaining -= step.amount_in;
+        amount_out = amount_out.checked_add(step.amount_out).ok_or(A2AError::MathOverflow)?;
+
+        // LP fee in proportion to the input this step's liquidity absorbed;
+        // the last step takes the rounding remainder.
+        if step.amount_in > 0 && pool.liquidity > 0 {
+            let fee = if remaining == 0 {
+                fee_left
+            } else {
+                mul_div(lp_fee, step.amount_in, after_fees)?.min(fee_left)
+            };
+            fee_left -= fee;
+            let growth = mul_div(fee, Q64, pool.liquidity)?;
+            if a_to_b {
+                pool.fee_growth_global_a = pool.fee_growth_global_a.wrapping_add(growth);
+            } else {
+                pool.fee_growth_global_b = pool.fee_growth_global_b.wrapping_add(growth);
+            }
+        }
+
+        pool.sqrt_price_x64 = step.sqrt_price_next;
+        match next {
+            Some(i) if step.sqrt_price_next == target => cross_tick(pool, i, a_to_b)?,
+            None if step.sqrt_price_next == target => {
+                require!(remaining == 0, A2AError::InsufficientLiquidity);
+                pool.tick_current = tick_at_sqrt_price(target)?;
+            }
+            _ => pool.tick_current = tick_at_sqrt_price(step.sqrt_price_next)?,
+        }
+    }
+
+    let amount_out = u64::try_from(amount_out).map_err(|_| A2AError::MathOverflow)?;
+    require!(amount_out >= min_amount_out, A2AError::SlippageExceeded);
+    require!(amount_out > 0, A2AError::ZeroAmount);
+
+    // after_fees at the starting price: p = √p² in token B per token A.
+    let ideal_out = if a_to_b {
+        mul_div(mul_div(after_fees, start_price, Q64)?, start_price, Q64)?
+    } else {
+        mul_div(mul_div(after_fees, Q64, start_price)?, Q64, start_price)?
+    };
+    let price_impact_bps = if ideal_out > amount_out as u128 {
+        (ideal_out - amount_out as u128) * BPS_DENOMINATOR / ideal_out
+    } else {
+        0
+    };
+
+    Ok(RangeSwapAmounts {
+        protocol_fee: protocol_fee as u64,
+        net_pool_input: net_pool_input as u64,
+        lp_fee: lp_fee as u64,
+        amount_out,
+        price_impact_bps: price_impact_bps as u64,
+    })
+}
+
+/// Position in `pool.ticks` of the next tick a swap would cross.
+fn next_initialized_tick(pool: &RangePool, a_to_b: bool) -> Option<usize> {
+    let above = pool.ticks.partition_point(|t| t.index <= pool.tick_current);
+    if a_to_b {
+        above.checked_sub(1)
+    } else {
+        (above < pool.ticks.len()).then_some(above)
+    }
+}
+
+fn cross_tick(pool: &mut RangePool, i: usize, a_to_b: bool) -> Result<()> {
+    let (fg_a, fg_b) = (pool.fee_growth_global_a, pool.fee_growth_global_b);
+    let tick = &mut pool.ticks[i];
+    tick.fee_growth_outside_a = fg_a.wrapping_sub(tick.fee_growth_outside_a);
+    tick.fee_growth_outside_b = fg_b.wrapping_sub(tick.fee_growth_outside_b);
+    let net = if a_to_b { -tick.liquidity_net } else { tick.liquidity_net };
+    let index = tick.index;
+    pool.liquidity = pool
+        .liquidity
+        .checked_add_signed(net)
+        .ok_or(A2AError::MathOverflow)?;
+    pool.tick_current = if a_to_b { index - 1 } else { index };
+    Ok(())
+}
+
+// ─── Positions ─────────────────────────────────────────────────────────────
+
+/// Fee growth per unit of liquidity earned inside [tick_lower, tick_upper).
+pub fn fee_growth_inside(pool: &RangePool, tick_lower: i32, tick_upper: i32) -> (u128, u128) {
+    let outside = |index: i32| {
+        pool.ticks
+            .iter()
+            .find(|t| t.index == index)
+            .map_or((0, 0), |t| (t.fee_growth_outside_a, t.fee_growth_outside_b))
+    };
+    let (g_a, g_b) = (pool.fee_growth_global_a, pool.fee_growth_global_b);
+    let (lo_a, lo_b) = outside(tick_lower);
+    let (hi_a, hi_b) = outside(tick_upper);
+    let (below_a, below_b) = if pool.tick_current >= tick_lower {
+        (lo_a, lo_b)
+    } else {
+        (g_a.wrapping_sub(lo_a), g_b.wrapping_sub(lo_b))
+    };
+    let (above_a, above_b) = if pool.tick_current < tick_upper {
+        (hi_a, hi_b)
+    } else {
+        (g_a.wrapping_sub(hi_a), g_b.wrapping_sub(hi_b))
+    };
+    (
+        g_a.wrapping_sub(below_a).wrapping_sub(above_a),
+        g_b.wrapping_sub(below_b).wrapping_sub(above_b),
+    )
+}
+
+/// Add `delta` liquidity to one boundary, initializing it if needed.
+fn update_tick(pool: &mut RangePool, index: i32, delta: i128, upper: bool) -> Result<()> {
+    let i = match pool.ticks.binary_search_by_key(&index, |t| t.index) {
+        Ok(i) => i,
+        Err(i) => {
+            require!(pool.ticks.len() < MAX_RANGE_TICKS, A2AError::TickCapacityExceeded);
+            // By convention all growth so far happened below the tick.
+            let (a, b) = if index <= pool.tick_current {
+                (pool.fee_growth_global_a, pool.fee_growth_global_b)
+            } else {
+                (0, 0)
+            };
+            pool.ticks.insert(
+                i,
+                Tick { index, fee_growth_outside_a: a, fee_growth_outside_b: b, ..Tick::default() },
+            );
+            i
+        }
+    };
+    let tick = &mut pool.ticks[i];
+    tick.liquidity_gross = tick
+        .liquidity_gross
+        .checked_add_signed(delta)
+        .ok_or(A2AError::MathOverflow)?;
+    tick.liquidity_net = if upper {
+        tick.liquidity_net.checked_sub(delta)
+    } else {
+        tick.liquidity_net.checked_add(delta)
+    }
+    .ok_or(A2AError::MathOverflow)?;
+    Ok(())
+}
+
+/// Apply `delta` liquidity to `position`: syncs its fees, updates both
+/// boundary ticks (freeing unreferenced ones) and the pool's active
+/// liquidity. Call with `delta = 0` to sync fees only.
+pub fn update_position(
+    pool: &mut RangePool,
+    position: &mut RangePosition,
+    delta: i128,
+) -> Result<()> {
+    let (lower, upper) = (position.tick_lower, position.tick_upper);
+    if delta != 0 {
+        update_tick(pool, lower, delta, false)?;
+        update_tick(pool, upper, delta, true)?;
+    }
+
+    let (inside_a, inside_b) = fee_growth_inside(pool, lower, upper);
+    let owed_a = mul_div(
+        position.liquidity,
+        inside_a.wrapping_sub(position.fee_growth_inside_last_a),
+        Q64,
+    )?;
+    let owed_b = mul_div(
+        position.liquidity,
+        inside_b.wrapping_sub(position.fee_growth_inside_last_b),
+        Q64,
+    )?;
+    position.fees_owed_a = position.fees_owed_a.saturating_add(owed_a as u64);
+    position.fees_owed_b = position.fees_owed_b.saturating_add(owed_b as u64);
+    position.fee_growth_inside_last_a = inside_a;
+    position.fee_growth_inside_last_b = inside_b;
+    position.liquidity = position
+        .liquidity
+        .checked_add_signed(delta)
+        .ok_or(A2AError::MathOverflow)?;
+
+    if (lower..upper).contains(&pool.tick_current) {
+        pool.liquidity = pool
+            .liquidity
+            .checked_add_signed(delta)
+            .ok_or(A2AError::MathOverflow)?;
+    }
+    if delta < 0 {
+        pool.ticks.retain(|t| t.liquidity_gross > 0);
+    }
+    Ok(())
+}
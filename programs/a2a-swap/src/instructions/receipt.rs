@@ -0,0 +1,132 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::{
+    instruction::{AccountMeta, Instruction},
+    program::invoke_signed,
+};
+use crate::{constants::*, error::A2AError};
+
+// ─── Optional LP receipt ───────────────────────────────────────────────────
+// `provide_liquidity` and `remove_liquidity` accept two trailing accounts,
+// `[receipt (mut), mpl_core_program]`. When present, the first deposit mints
+// a Metaplex Core asset at PDA [RECEIPT_SEED, position] to the agent, and a
+// withdrawal that empties the position burns it. The receipt is a pointer to
+// the position for wallets and indexers — the position itself stays bound to
+// its owner, so holding the asset does not grant withdrawal rights.
+//
+// mpl-core's own crate doesn't build against the Solana version Anchor 0.32
+// pins, so CreateV1 / BurnV1 are encoded by hand (borsh, one-byte enum tags).
+
+const CREATE_V1: u8 = 0;
+const BURN_V1: u8 = 12;
+const PLUGIN_ATTRIBUTES: u8 = 6;
+const PLUGIN_PERMANENT_BURN_DELEGATE: u8 = 8;
+const AUTHORITY_UPDATE_AUTHORITY: u8 = 2;
+
+/// `[receipt, mpl_core_program]` from the remaining accounts, or `None` when
+/// the caller didn't opt in. Errors if the receipt isn't the position's PDA.
+pub fn receipt_accounts<'a, 'info>(
+    remaining: &'a [AccountInfo<'info>],
+    position: &Pubkey,
+) -> Result<Option<(&'a AccountInfo<'info>, &'a AccountInfo<'info>, u8)>> {
+    let [receipt, mpl_core, ..] = remaining else {
+        return Ok(None);
+    };
+    let (expected, bump) =
+        Pubkey::find_program_address(&[RECEIPT_SEED, position.as_ref()], &crate::ID);
+    require_keys_eq!(receipt.key(), expected, A2AError::InvalidReceipt);
+    require_keys_eq!(mpl_core.key(), MPL_CORE_PROGRAM, A2AError::InvalidReceipt);
+    require!(receipt.is_writable, A2AError::InvalidReceipt);
+    Ok(Some((receipt, mpl_core, bump)))
+}
+
+fn put_str(data: &mut Vec<u8>, s: &str) {
+    data.extend_from_slice(&(s.len() as u32).to_le_bytes());
+    data.extend_from_slice(s.as_bytes());
+}
+
+/// Mint the receipt asset to `owner`. `pool_authority` is its update
+/// authority and permanent burn delegate, so the program can burn it later
+/// without the holder's signature.
+#[allow(clippy::too_many_arguments)]
+pub fn mint_receipt<'info>(
+    receipt: &AccountInfo<'info>,
+    mpl_core: &AccountInfo<'info>,
+    receipt_bump: u8,
+    position: &Pubkey,
+    pool: &Pubkey,
+    owner: &AccountInfo<'info>,
+    pool_authority: &AccountInfo<'info>,
+    system_program: &AccountInfo<'info>,
+) -> Result<()> {
+    let mut data = vec![CREATE_V1, 0]; // data_state = AccountState
+    put_str(&mut data, RECEIPT_NAME);
+    put_str(&mut data, &format!("{RECEIPT_URI_BASE}{position}"));
+
+    data.push(1); // plugins: Some
+    data.extend_from_slice(&2u32.to_le_bytes());
+    data.push(PLUGIN_ATTRIBUTES);
+    data.extend_from_slice(&2u32.to_le_bytes());
+    put_str(&mut data, "position");
+    put_str(&mut data, &position.to_string());
+    put_str(&mut data, "pool");
+    put_str(&mut data, &pool.to_string());
+    data.extend_from_slice(&[1, AUTHORITY_UPDATE_AUTHORITY]);
+    data.push(PLUGIN_PERMANENT_BURN_DELEGATE);
+    data.extend_from_slice(&[1, AUTHORITY_UPDATE_AUTHORITY]);
+
+    // Unused optional accounts are filled with the Core program ID.
+    let ix = Instruction {
+        program_id: MPL_CORE_PROGRAM,
+        accounts: vec![
+            AccountMeta::new(receipt.key(), true),
+            AccountMeta::new_readonly(MPL_CORE_PROGRAM, false), // collection
+            AccountMeta::new_readonly(MPL_CORE_PROGRAM, false), // authority = payer
+            AccountMeta::new(owner.key(), true),                // payer
+            AccountMeta::new_readonly(owner.key(), false),      // owner
+            AccountMeta::new_readonly(pool_authority.key(), false),
+            AccountMeta::new_readonly(system_program.key(), false),
+            AccountMeta::new_readonly(MPL_CORE_PROGRAM, false), // log wrapper
+        ],
+        data,
+    };
+    invoke_signed(
+        &ix,
+        &[
+            receipt.clone(),
+            mpl_core.clone(),
+            owner.clone(),
+            pool_authority.clone(),
+            system_program.clone(),
+        ],
+        &[&[RECEIPT_SEED, position.as_ref(), &[receipt_bump]]],
+    )?;
+    Ok(())
+}
+
+/// Burn the receipt as its permanent burn delegate; rent goes to `payer`.
+pub fn burn_receipt<'info>(
+    receipt: &AccountInfo<'info>,
+    mpl_core: &AccountInfo<'info>,
+    payer: &AccountInfo<'info>,
+    pool_authority: &AccountInfo<'info>,
+    authority_seeds: &[&[u8]],
+) -> Result<()> {
+    let ix = Instruction {
+        program_id: MPL_CORE_PROGRAM,
+        accounts: vec![
+            AccountMeta::new(receipt.key(), false),
+            AccountMeta::new_readonly(MPL_CORE_PROGRAM, false), // collection
+            AccountMeta::new(payer.key(), true),
+            AccountMeta::new_readonly(pool_authority.key(), true),
+            AccountMeta::new_readonly(MPL_CORE_PROGRAM, false), // system program
+            AccountMeta::new_readonly(MPL_CORE_PROGRAM, false), // log wrapper
+        ],
+        data: vec![BURN_V1, 0], // compression_proof: None
+    };
+    invoke_signed(
+        &ix,
+        &[receipt.clone(), mpl_core.clone(), payer.clone(), pool_authority.clone()],
+        &[authority_seeds],
+    )?;
+    Ok(())
+}
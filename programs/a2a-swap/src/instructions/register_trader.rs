@@ -0,0 +1,46 @@
+use anchor_lang::prelude::*;
+use crate::{constants::*, error::A2AError, state::{Pool, TraderPass}};
+use super::allowlist::verify_allowlist_proof;
+
+/// Prove the signer is on a permissioned pool's allowlist and record it in
+/// the trader's TraderPass (created on first use; the trader pays its rent).
+/// `proof` is the sibling hashes from the trader's leaf up to the root.
+///
+/// Swaps on the pool then pass the TraderPass as their optional
+/// `trader_pass` account. Re-register after the creator changes the root.
+pub fn handler(ctx: Context<RegisterTrader>, proof: Vec<[u8; 32]>) -> Result<()> {
+    let pool = &ctx.accounts.pool;
+    let trader = ctx.accounts.trader.key();
+    require!(
+        pool.is_permissioned() && verify_allowlist_proof(&pool.allowlist_root, &trader, &proof),
+        A2AError::InvalidAllowlistProof
+    );
+
+    let pass = &mut ctx.accounts.trader_pass;
+    pass.pool = pool.key();
+    pass.trader = trader;
+    pass.root = pool.allowlist_root;
+    pass.bump = ctx.bumps.trader_pass;
+
+    msg!("Trader registered: {} pool={}", trader, pool.key());
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct RegisterTrader<'info> {
+    #[account(mut)]
+    pub trader: Signer<'info>,
+
+    pub pool: Account<'info, Pool>,
+
+    #[account(
+        init_if_needed,
+        payer = trader,
+        space = TraderPass::LEN,
+        seeds = [TRADER_PASS_SEED, pool.key().as_ref(), trader.key().as_ref()],
+        bump,
+    )]
+    pub trader_pass: Account<'info, TraderPass>,
+
+    pub system_program: Program<'info, System>,
+}
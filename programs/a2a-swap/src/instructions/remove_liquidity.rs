@@ -2,11 +2,34 @@ use anchor_lang::prelude::*;
 use anchor_spl::token::{self, Token, TokenAccount, Transfer};
 use crate::{constants::*, error::A2AError, state::{Pool, Position}};
 use super::provide_liquidity::accrue_fees;
+use super::receipt::{burn_receipt, receipt_accounts};
+
+/// Tokens returned for burning `lp_shares` of `lp_supply`: the same share
+/// of each reserve, rounded down in the pool's favour.
+pub fn amounts_for_lp_shares(
+    lp_shares: u64,
+    lp_supply: u64,
+    reserve_a: u64,
+    reserve_b: u64,
+) -> Result<(u64, u64)> {
+    require!(lp_supply > 0, A2AError::InsufficientLiquidity);
+    let amount_a = (lp_shares as u128)
+        .checked_mul(reserve_a as u128)
+        .ok_or(A2AError::MathOverflow)?
+        / lp_supply as u128;
+    let amount_b = (lp_shares as u128)
+        .checked_mul(reserve_b as u128)
+        .ok_or(A2AError::MathOverflow)?
+        / lp_supply as u128;
+    Ok((amount_a as u64, amount_b as u64))
+}
 
 /// Burn LP shares and withdraw proportional tokens from the pool.
 /// Fees are synced first; auto-compound does NOT trigger here (call claim_fees).
-pub fn handler(
-    ctx: Context<RemoveLiquidity>,
+/// Optional remaining accounts `[receipt, mpl_core_program]` burn the LP
+/// receipt NFT once the position's shares reach zero.
+pub fn handler<'info>(
+    ctx: Context<'_, '_, 'info, 'info, RemoveLiquidity<'info>>,
     lp_shares: u64,
     min_a: u64,
     min_b: u64,
@@ -19,26 +42,16 @@ pub fn handler(
 
     // Read state before mutable borrows
     let lp_supply = ctx.accounts.pool.lp_supply;
-    let reserve_a = ctx.accounts.token_a_vault.amount;
-    let reserve_b = ctx.accounts.token_b_vault.amount;
+    let (reserve_a, reserve_b) = ctx
+        .accounts
+        .pool
+        .reserves(ctx.accounts.token_a_vault.amount, ctx.accounts.token_b_vault.amount);
     let fg_a = ctx.accounts.pool.fee_growth_global_a;
     let fg_b = ctx.accounts.pool.fee_growth_global_b;
     let pool_key = ctx.accounts.pool.key();
     let authority_bump = ctx.accounts.pool.authority_bump;
 
-    require!(lp_supply > 0, A2AError::InsufficientLiquidity);
-
-    // Proportional amounts to return
-    let amount_a = (lp_shares as u128)
-        .checked_mul(reserve_a as u128)
-        .ok_or(A2AError::MathOverflow)?
-        / lp_supply as u128;
-    let amount_b = (lp_shares as u128)
-        .checked_mul(reserve_b as u128)
-        .ok_or(A2AError::MathOverflow)?
-        / lp_supply as u128;
-    let amount_a = amount_a as u64;
-    let amount_b = amount_b as u64;
+    let (amount_a, amount_b) = amounts_for_lp_shares(lp_shares, lp_supply, reserve_a, reserve_b)?;
 
     require!(amount_a >= min_a, A2AError::SlippageExceeded);
     require!(amount_b >= min_b, A2AError::SlippageExceeded);
@@ -50,8 +63,10 @@ pub fn handler(
         pos.lp_shares = pos.lp_shares.saturating_sub(lp_shares);
     }
 
-    // Reduce pool LP supply
-    ctx.accounts.pool.lp_supply = lp_supply.saturating_sub(lp_shares);
+    // Reduce pool LP supply and reserves
+    let pool = &mut ctx.accounts.pool;
+    pool.lp_supply = lp_supply.saturating_sub(lp_shares);
+    pool.set_reserves(reserve_a - amount_a, reserve_b - amount_b);
 
     // Transfer tokens from vaults to agent (PDA-signed)
     let seeds: &[&[u8]] = &[POOL_AUTHORITY_SEED, pool_key.as_ref(), &[authority_bump]];
@@ -86,6 +101,24 @@ pub fn handler(
         )?;
     }
 
+    if ctx.accounts.position.lp_shares == 0 {
+        let position_key = ctx.accounts.position.key();
+        if let Some((receipt, mpl_core, _)) =
+            receipt_accounts(ctx.remaining_accounts, &position_key)?
+        {
+            if !receipt.data_is_empty() {
+                burn_receipt(
+                    receipt,
+                    mpl_core,
+                    &ctx.accounts.agent.to_account_info(),
+                    &ctx.accounts.pool_authority.to_account_info(),
+                    seeds,
+                )?;
+                msg!("Receipt burned: {}", receipt.key());
+            }
+        }
+    }
+
     msg!("Liquidity removed: lp={} a={} b={}", lp_shares, amount_a, amount_b);
     Ok(())
 }
@@ -111,6 +144,7 @@ pub struct RemoveLiquidity<'info> {
         bump = position.bump,
         constraint = position.owner == agent.key(),
         constraint = position.pool == pool.key(),
+        constraint = !position.is_locked() @ A2AError::PositionLocked,
     )]
     pub position: Account<'info, Position>,
 
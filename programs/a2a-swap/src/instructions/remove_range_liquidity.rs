@@ -0,0 +1,135 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Token, TokenAccount, Transfer};
+use crate::{constants::*, error::A2AError, state::{RangePool, RangePosition}};
+use super::range_math::{amounts_for_liquidity, sqrt_price_at_tick, update_position};
+
+/// Withdraw `liquidity` from a range position, paying out its share of the
+/// range's tokens plus every fee the position has earned.
+/// Pass `liquidity = 0` to collect fees only.
+pub fn handler(
+    ctx: Context<RemoveRangeLiquidity>,
+    liquidity: u128,
+    min_a: u64,
+    min_b: u64,
+) -> Result<()> {
+    let pool = &mut ctx.accounts.pool;
+    let pos = &mut ctx.accounts.position;
+    require!(liquidity <= pos.liquidity, A2AError::InsufficientLiquidity);
+
+    let sqrt_lo = sqrt_price_at_tick(pos.tick_lower)?;
+    let sqrt_hi = sqrt_price_at_tick(pos.tick_upper)?;
+    let (principal_a, principal_b) =
+        amounts_for_liquidity(pool.sqrt_price_x64, sqrt_lo, sqrt_hi, liquidity, false)?;
+    require!(
+        principal_a >= min_a && principal_b >= min_b,
+        A2AError::SlippageExceeded
+    );
+
+    let delta = i128::try_from(liquidity).map_err(|_| A2AError::MathOverflow)?;
+    update_position(pool, pos, -delta)?;
+
+    let amount_a = principal_a
+        .checked_add(pos.fees_owed_a)
+        .ok_or(A2AError::MathOverflow)?;
+    let amount_b = principal_b
+        .checked_add(pos.fees_owed_b)
+        .ok_or(A2AError::MathOverflow)?;
+    require!(amount_a > 0 || amount_b > 0, A2AError::ZeroAmount);
+    pos.fees_owed_a = 0;
+    pos.fees_owed_b = 0;
+
+    // ── PDA signer seeds for vault → agent transfers ─────────────────────────
+    let pool_key = pool.key();
+    let authority_bump = pool.authority_bump;
+    let seeds: &[&[u8]] = &[POOL_AUTHORITY_SEED, pool_key.as_ref(), &[authority_bump]];
+    let signer = &[seeds];
+
+    for (amount, from, to) in [
+        (amount_a, &ctx.accounts.token_a_vault, &ctx.accounts.agent_token_a),
+        (amount_b, &ctx.accounts.token_b_vault, &ctx.accounts.agent_token_b),
+    ] {
+        if amount > 0 {
+            token::transfer(
+                CpiContext::new_with_signer(
+                    ctx.accounts.token_program.to_account_info(),
+                    Transfer {
+                        from: from.to_account_info(),
+                        to: to.to_account_info(),
+                        authority: ctx.accounts.pool_authority.to_account_info(),
+                    },
+                    signer,
+                ),
+                amount,
+            )?;
+        }
+    }
+
+    msg!(
+        "Range liquidity removed: liquidity={} a={} b={} fees_a={} fees_b={}",
+        liquidity,
+        principal_a,
+        principal_b,
+        amount_a - principal_a,
+        amount_b - principal_b
+    );
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct RemoveRangeLiquidity<'info> {
+    #[account(mut)]
+    pub agent: Signer<'info>,
+
+    #[account(mut)]
+    pub pool: Box<Account<'info, RangePool>>,
+
+    /// CHECK: PDA vault authority
+    #[account(
+        seeds = [POOL_AUTHORITY_SEED, pool.key().as_ref()],
+        bump = pool.authority_bump,
+    )]
+    pub pool_authority: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [
+            RANGE_POSITION_SEED,
+            pool.key().as_ref(),
+            agent.key().as_ref(),
+            &position.tick_lower.to_le_bytes(),
+            &position.tick_upper.to_le_bytes(),
+        ],
+        bump = position.bump,
+        constraint = position.owner == agent.key(),
+        constraint = position.pool == pool.key(),
+    )]
+    pub position: Box<Account<'info, RangePosition>>,
+
+    #[account(
+        mut,
+        constraint = token_a_vault.key() == pool.token_a_vault @ A2AError::MintMismatch,
+    )]
+    pub token_a_vault: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        constraint = token_b_vault.key() == pool.token_b_vault @ A2AError::MintMismatch,
+    )]
+    pub token_b_vault: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        constraint = agent_token_a.mint == pool.token_a_mint @ A2AError::MintMismatch,
+        constraint = agent_token_a.owner == agent.key(),
+    )]
+    pub agent_token_a: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        constraint = agent_token_b.mint == pool.token_b_mint @ A2AError::MintMismatch,
+        constraint = agent_token_b.owner == agent.key(),
+    )]
+    pub agent_token_b: Box<Account<'info, TokenAccount>>,
+
+    pub token_program: Program<'info, Token>,
+}
@@ -0,0 +1,219 @@
+use anchor_lang::prelude::*;
+use anchor_spl::associated_token::get_associated_token_address;
+use anchor_spl::token::{Token, TokenAccount};
+use anchor_spl::token_interface;
+use crate::{constants::*, error::A2AError, state::{AgentVolume, Pool, SwapCommitment, TraderPass}};
+use super::commit_swap::commitment_hash;
+use super::allowlist::require_allowed_trader;
+use super::fee_math::{compute_swap, effective_fee_bps, require_not_paused, tiered_protocol_fee_bps};
+use super::swap::{settle, SettleAccounts};
+
+/// Second half of a commit-reveal swap: open the agent's commitment and
+/// execute it. Same pricing, fees and accounts as `swap` (the optional
+/// `agent_volume` included, the referrer not), plus the commitment, which is
+/// closed back to the agent.
+///
+/// The parameters and `salt` must hash to the stored commitment
+/// (`commitment_hash`), and the reveal must land after the commit slot and
+/// no more than `MAX_REVEAL_DELAY_SLOTS` after it. The commitment keeps the
+/// parameters private until the reveal, but the reveal transaction is as
+/// visible as any swap once sent: `min_amount_out` and
+/// `max_price_impact_bps` still bound what a sandwich around it can take.
+pub fn handler(
+    ctx: Context<RevealSwap>,
+    amount_in: u64,
+    min_amount_out: u64,
+    a_to_b: bool,
+    max_price_impact_bps: u16,
+    salt: [u8; 32],
+) -> Result<()> {
+    require!(amount_in > 0, A2AError::ZeroAmount);
+    require_not_paused(&ctx.accounts.treasury, PAUSE_SWAPS)?;
+    require_allowed_trader(&ctx.accounts.pool, ctx.accounts.trader_pass.as_deref().map(|p| &**p))?;
+    let clock = Clock::get()?;
+    let commit_slot = ctx.accounts.commitment.commit_slot;
+    require!(clock.slot > commit_slot, A2AError::CommitmentNotReady);
+    require!(
+        clock.slot <= commit_slot.saturating_add(MAX_REVEAL_DELAY_SLOTS),
+        A2AError::CommitmentExpired
+    );
+    require!(
+        commitment_hash(amount_in, min_amount_out, a_to_b, max_price_impact_bps, &salt)
+            == ctx.accounts.commitment.hash,
+        A2AError::CommitmentMismatch
+    );
+
+    let (reserve_a, reserve_b) = ctx
+        .accounts
+        .pool
+        .reserves(ctx.accounts.token_a_vault.amount, ctx.accounts.token_b_vault.amount);
+    let (reserve_a, reserve_b) = (reserve_a as u128, reserve_b as u128);
+    require!(reserve_a > 0 && reserve_b > 0, A2AError::InsufficientLiquidity);
+
+    let (reserve_in, reserve_out) = if a_to_b {
+        (reserve_a, reserve_b)
+    } else {
+        (reserve_b, reserve_a)
+    };
+
+    let now = clock.unix_timestamp;
+    let fee_rate_bps = effective_fee_bps(
+        ctx.accounts.pool.fee_rate_bps,
+        &ctx.accounts.pool.dynamic_fee,
+        now,
+    );
+    let agent_volume = ctx.accounts.agent_volume.as_deref().map(|v| &**v);
+    let sa = compute_swap(
+        amount_in,
+        tiered_protocol_fee_bps(&ctx.accounts.treasury, agent_volume)?,
+        fee_rate_bps,
+        ctx.accounts.pool.curve,
+        reserve_in,
+        reserve_out,
+        ctx.accounts.pool.lp_supply,
+        min_amount_out,
+    )?;
+    settle(
+        SettleAccounts {
+            pool: &mut ctx.accounts.pool,
+            pool_authority: ctx.accounts.pool_authority.to_account_info(),
+            token_a_vault: ctx.accounts.token_a_vault.to_account_info(),
+            token_b_vault: ctx.accounts.token_b_vault.to_account_info(),
+            token_in: ctx.accounts.agent_token_in.to_account_info(),
+            token_out: ctx.accounts.agent_token_out.to_account_info(),
+            in_authority: ctx.accounts.agent.to_account_info(),
+            in_signer_seeds: None,
+            treasury: ctx.accounts.treasury.to_account_info(),
+            treasury_token_in: ctx.accounts.treasury_token_in.to_account_info(),
+            token_program: ctx.accounts.token_program.to_account_info(),
+            referrer_token: None,
+            agent_volume: ctx.accounts.agent_volume.as_deref_mut().map(|v| &mut **v),
+        },
+        a_to_b,
+        amount_in,
+        reserve_in,
+        reserve_out,
+        now,
+        &sa,
+        max_price_impact_bps,
+    )?;
+
+    msg!(
+        "Revealed swap: agent={} committed_slot={} in={} protocol_fee={} lp_fee={} out={} a_to_b={}",
+        ctx.accounts.agent.key(),
+        commit_slot,
+        amount_in,
+        sa.protocol_fee,
+        sa.lp_fee,
+        sa.amount_out,
+        a_to_b
+    );
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct RevealSwap<'info> {
+    #[account(mut)]
+    pub agent: Signer<'info>,
+
+    #[account(
+        mut,
+        constraint = !pool.is_gated() || gate_token.is_some() @ A2AError::GateTokenRequired,
+    )]
+    pub pool: Account<'info, Pool>,
+
+    /// The agent's open commitment on this pool; closed back to the agent
+    #[account(
+        mut,
+        close = agent,
+        seeds = [SWAP_COMMITMENT_SEED, pool.key().as_ref(), agent.key().as_ref()],
+        bump = commitment.bump,
+    )]
+    pub commitment: Account<'info, SwapCommitment>,
+
+    /// CHECK: PDA vault authority
+    #[account(
+        seeds = [POOL_AUTHORITY_SEED, pool.key().as_ref()],
+        bump = pool.authority_bump,
+    )]
+    pub pool_authority: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        constraint = token_a_vault.key() == pool.token_a_vault @ A2AError::MintMismatch,
+        constraint = token_a_vault.mint == pool.token_a_mint @ A2AError::MintMismatch,
+        constraint = token_a_vault.owner == pool_authority.key() @ A2AError::MintMismatch,
+    )]
+    pub token_a_vault: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        constraint = token_b_vault.key() == pool.token_b_vault @ A2AError::MintMismatch,
+        constraint = token_b_vault.mint == pool.token_b_mint @ A2AError::MintMismatch,
+        constraint = token_b_vault.owner == pool_authority.key() @ A2AError::MintMismatch,
+    )]
+    pub token_b_vault: Box<Account<'info, TokenAccount>>,
+
+    /// Token account the agent is selling from — must hold one of the pool's tokens
+    #[account(
+        mut,
+        constraint = agent_token_in.owner == agent.key(),
+        constraint = (agent_token_in.mint == pool.token_a_mint
+            || agent_token_in.mint == pool.token_b_mint) @ A2AError::MintMismatch,
+    )]
+    pub agent_token_in: Box<Account<'info, TokenAccount>>,
+
+    /// Token account the agent is receiving into — must be the other pool token
+    #[account(
+        mut,
+        constraint = agent_token_out.owner == agent.key(),
+        constraint = (agent_token_out.mint == pool.token_a_mint
+            || agent_token_out.mint == pool.token_b_mint) @ A2AError::MintMismatch,
+        constraint = agent_token_out.mint != agent_token_in.mint @ A2AError::MintMismatch,
+    )]
+    pub agent_token_out: Box<Account<'info, TokenAccount>>,
+
+    /// CHECK: Global treasury PDA — owns treasury token accounts; holds the
+    /// ProtocolConfig once initialized, parsed in the handler
+    #[account(seeds = [TREASURY_SEED], bump)]
+    pub treasury: UncheckedAccount<'info>,
+
+    /// Treasury's associated token account for the input token
+    #[account(
+        mut,
+        constraint = treasury_token_in.owner == treasury.key() @ A2AError::MintMismatch,
+        constraint = treasury_token_in.mint == agent_token_in.mint @ A2AError::MintMismatch,
+        constraint = treasury_token_in.key()
+            == get_associated_token_address(&treasury.key(), &agent_token_in.mint)
+            @ A2AError::InvalidTreasuryAccount,
+    )]
+    pub treasury_token_in: Box<Account<'info, TokenAccount>>,
+
+    pub token_program: Program<'info, Token>,
+
+    /// Optional: the agent's AgentVolume, for fee tier discounts. May be
+    /// omitted entirely.
+    #[account(
+        mut,
+        seeds = [AGENT_VOLUME_SEED, agent.key().as_ref()],
+        bump = agent_volume.bump,
+    )]
+    pub agent_volume: Option<Box<Account<'info, AgentVolume>>>,
+
+    /// Optional: the agent's TraderPass, required on permissioned pools.
+    /// May be omitted entirely on open pools.
+    #[account(
+        seeds = [TRADER_PASS_SEED, pool.key().as_ref(), agent.key().as_ref()],
+        bump = trader_pass.bump,
+    )]
+    pub trader_pass: Option<Box<Account<'info, TraderPass>>>,
+
+    /// Optional: the agent's token account of the pool's gate mint (SPL
+    /// Token or Token-2022), required on token-gated pools.
+    #[account(
+        constraint = gate_token.mint == pool.gate_mint @ A2AError::GateTokenRequired,
+        constraint = gate_token.owner == agent.key() @ A2AError::GateTokenRequired,
+        constraint = gate_token.amount > 0 @ A2AError::GateTokenRequired,
+    )]
+    pub gate_token: Option<Box<InterfaceAccount<'info, token_interface::TokenAccount>>>,
+}
@@ -0,0 +1,46 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Revoke, Token, TokenAccount};
+use crate::{constants::*, state::Delegate};
+
+/// End a session: revoke the SPL delegation on the owner's token account and
+/// close the Delegate account, returning its rent to the owner.
+pub fn handler(ctx: Context<RevokeDelegate>) -> Result<()> {
+    // Only clear the SPL delegate if it is still ours; the owner may have
+    // approved someone else on this account since.
+    if ctx.accounts.owner_token.delegate == Some(ctx.accounts.delegate_account.key()).into() {
+        token::revoke(CpiContext::new(
+            ctx.accounts.token_program.to_account_info(),
+            Revoke {
+                source: ctx.accounts.owner_token.to_account_info(),
+                authority: ctx.accounts.owner.to_account_info(),
+            },
+        ))?;
+    }
+
+    msg!(
+        "Delegate revoked: owner={} delegate={} spent={}",
+        ctx.accounts.owner.key(),
+        ctx.accounts.delegate_account.delegate,
+        ctx.accounts.delegate_account.spent
+    );
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct RevokeDelegate<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(
+        mut,
+        close = owner,
+        seeds = [DELEGATE_SEED, owner.key().as_ref(), owner_token.key().as_ref()],
+        bump = delegate_account.bump,
+    )]
+    pub delegate_account: Account<'info, Delegate>,
+
+    #[account(mut, constraint = owner_token.key() == delegate_account.token_account)]
+    pub owner_token: Account<'info, TokenAccount>,
+
+    pub token_program: Program<'info, Token>,
+}
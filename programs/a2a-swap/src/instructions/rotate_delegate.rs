@@ -0,0 +1,62 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Approve, Token, TokenAccount};
+use crate::{constants::*, error::A2AError, state::Delegate};
+
+/// Hand an existing session to a new key (or the same key with new limits).
+/// The old key stops working in the same transaction; `spent` starts over
+/// and the SPL delegation is reset to `max_amount_in`.
+pub fn handler(
+    ctx: Context<RotateDelegate>,
+    delegate: Pubkey,
+    max_amount_in: u64,
+    expires_at: i64,
+) -> Result<()> {
+    require!(max_amount_in > 0, A2AError::ZeroAmount);
+    require!(expires_at > Clock::get()?.unix_timestamp, A2AError::DelegateExpired);
+
+    let d = &mut ctx.accounts.delegate_account;
+    let previous = d.delegate;
+    d.delegate = delegate;
+    d.max_amount_in = max_amount_in;
+    d.spent = 0;
+    d.expires_at = expires_at;
+
+    token::approve(
+        CpiContext::new(
+            ctx.accounts.token_program.to_account_info(),
+            Approve {
+                to: ctx.accounts.owner_token.to_account_info(),
+                delegate: ctx.accounts.delegate_account.to_account_info(),
+                authority: ctx.accounts.owner.to_account_info(),
+            },
+        ),
+        max_amount_in,
+    )?;
+
+    msg!(
+        "Delegate rotated: owner={} {} -> {} cap={} expires_at={}",
+        ctx.accounts.owner.key(),
+        previous,
+        delegate,
+        max_amount_in,
+        expires_at
+    );
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct RotateDelegate<'info> {
+    pub owner: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [DELEGATE_SEED, owner.key().as_ref(), owner_token.key().as_ref()],
+        bump = delegate_account.bump,
+    )]
+    pub delegate_account: Account<'info, Delegate>,
+
+    #[account(mut, constraint = owner_token.key() == delegate_account.token_account)]
+    pub owner_token: Account<'info, TokenAccount>,
+
+    pub token_program: Program<'info, Token>,
+}
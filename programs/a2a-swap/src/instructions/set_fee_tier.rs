@@ -0,0 +1,52 @@
+use anchor_lang::prelude::*;
+use crate::{constants::*, error::A2AError, state::{FeeTier, ProtocolConfig}};
+
+/// Replace one step of the protocol fee discount schedule. Admin-only; once
+/// governance is the admin this goes through a `SetFeeTier` proposal instead.
+///
+/// An agent swapping with its `AgentVolume` account gets the largest
+/// `discount_bps` among the tiers whose `min_volume` it has reached, so the
+/// tiers need not be sorted. `discount_bps = 0` clears the tier.
+///
+/// The config must already be at `ProtocolConfig::LEN`
+/// (`migrate_protocol_config`).
+pub fn handler(
+    ctx: Context<SetFeeTier>,
+    index: u8,
+    min_volume: u64,
+    discount_bps: u16,
+) -> Result<()> {
+    validate_fee_tier(index, discount_bps)?;
+
+    ctx.accounts.protocol_config.fee_tiers[index as usize] = FeeTier { min_volume, discount_bps };
+
+    msg!(
+        "Fee tier {} set: min_volume={} discount={}bps",
+        index,
+        min_volume,
+        discount_bps
+    );
+    Ok(())
+}
+
+/// Bounds shared with the `SetFeeTier` governance action.
+pub fn validate_fee_tier(index: u8, discount_bps: u16) -> Result<()> {
+    require!(
+        (index as usize) < FEE_TIER_COUNT && discount_bps as u128 <= BPS_DENOMINATOR,
+        A2AError::InvalidFeeTier
+    );
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct SetFeeTier<'info> {
+    pub admin: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [TREASURY_SEED],
+        bump = protocol_config.bump,
+        has_one = admin @ A2AError::Unauthorized,
+    )]
+    pub protocol_config: Account<'info, ProtocolConfig>,
+}
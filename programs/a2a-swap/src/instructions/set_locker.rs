@@ -0,0 +1,39 @@
+use anchor_lang::prelude::*;
+use crate::{constants::*, error::A2AError, state::ProtocolConfig};
+
+/// Approve `program` to lock positions as collateral in slot `index`, or
+/// clear the slot with the default pubkey. Admin-only; once governance is
+/// the admin this goes through a `SetLocker` proposal instead.
+///
+/// Removing a program stops it locking new positions; positions it already
+/// locked stay locked until it unlocks them.
+///
+/// The config must already be at `ProtocolConfig::LEN`
+/// (`migrate_protocol_config`).
+pub fn handler(ctx: Context<SetLocker>, index: u8, program: Pubkey) -> Result<()> {
+    validate_locker(index)?;
+
+    ctx.accounts.protocol_config.lockers[index as usize] = program;
+
+    msg!("Locker {} set: {}", index, program);
+    Ok(())
+}
+
+/// Bounds shared with the `SetLocker` governance action.
+pub fn validate_locker(index: u8) -> Result<()> {
+    require!((index as usize) < LOCKER_COUNT, A2AError::InvalidGovernanceParams);
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct SetLocker<'info> {
+    pub admin: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [TREASURY_SEED],
+        bump = protocol_config.bump,
+        has_one = admin @ A2AError::Unauthorized,
+    )]
+    pub protocol_config: Account<'info, ProtocolConfig>,
+}
@@ -0,0 +1,33 @@
+use anchor_lang::prelude::*;
+use crate::{error::A2AError, state::Pool};
+
+/// Restrict swaps on a pool to the traders under `merkle_root` (see
+/// `allowlist`), or open it to everyone again with all zeros. Creator-only;
+/// pools from before the creator was recorded can't be permissioned.
+///
+/// Changing the root invalidates every TraderPass: traders still on the new
+/// list re-register with a proof against it. Liquidity provision is not
+/// restricted. Range pools can't be permissioned: their accounts fail the
+/// `Pool` check below, and `swap_range` takes no trader pass.
+pub fn handler(ctx: Context<SetPoolAllowlist>, merkle_root: [u8; 32]) -> Result<()> {
+    let pool = &mut ctx.accounts.pool;
+    pool.allowlist_root = merkle_root;
+
+    if pool.is_permissioned() {
+        msg!("Pool allowlist set: {}", pool.key());
+    } else {
+        msg!("Pool allowlist cleared: {}", pool.key());
+    }
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct SetPoolAllowlist<'info> {
+    pub creator: Signer<'info>,
+
+    #[account(
+        mut,
+        constraint = pool.creator == creator.key() @ A2AError::Unauthorized,
+    )]
+    pub pool: Account<'info, Pool>,
+}
@@ -0,0 +1,35 @@
+use anchor_lang::prelude::*;
+use crate::{error::A2AError, state::Pool};
+
+/// Require traders to hold `gate_mint` — typically a non-transferable
+/// attestation or compliance credential — to swap on a pool, or lift the
+/// requirement with the default pubkey. Creator-only, like the allowlist.
+///
+/// Swaps then pass a token account of the mint, owned by the trader (the
+/// owner on delegate swaps) and holding a balance, as their optional
+/// `gate_token` account. SPL Token and Token-2022 mints both work.
+/// Liquidity provision is not gated. Range pools can't be gated: their
+/// accounts fail the `Pool` check below, and `swap_range` takes no
+/// `gate_token`.
+pub fn handler(ctx: Context<SetPoolGate>, gate_mint: Pubkey) -> Result<()> {
+    let pool = &mut ctx.accounts.pool;
+    pool.gate_mint = gate_mint;
+
+    if pool.is_gated() {
+        msg!("Pool gate set: {} mint={}", pool.key(), gate_mint);
+    } else {
+        msg!("Pool gate cleared: {}", pool.key());
+    }
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct SetPoolGate<'info> {
+    pub creator: Signer<'info>,
+
+    #[account(
+        mut,
+        constraint = pool.creator == creator.key() @ A2AError::Unauthorized,
+    )]
+    pub pool: Account<'info, Pool>,
+}
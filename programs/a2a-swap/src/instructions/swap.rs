@@ -1,29 +1,79 @@
 use anchor_lang::prelude::*;
+use anchor_spl::associated_token::get_associated_token_address;
 use anchor_spl::token::{self, Token, TokenAccount, Transfer};
-use crate::{constants::*, error::A2AError, state::Pool};
-use super::fee_math::compute_swap;
+use anchor_spl::token_interface;
+use crate::{constants::*, error::A2AError, state::{AgentVolume, Pool, TraderPass}};
+use super::allowlist::require_allowed_trader;
+use super::fee_math::{
+    compute_swap, effective_fee_bps, record_swap_reserves, record_swap_stats, record_price_move,
+    record_volatility, require_not_paused, referral_fee, referral_share_bps,
+    tiered_protocol_fee_bps, SwapAmounts,
+};
 
-/// Core constant-product swap: x * y = k.
+/// Core swap on the pool's curve: x * y = k or StableSwap.
 ///
 /// Fee split on every swap (both taken from amount_in):
-///   - Protocol fee (0.020%): sent to the treasury PDA's token account.
-///   - LP fee (pool.fee_rate_bps, default 0.30%): stays in the vault,
+///   - Protocol fee (ProtocolConfig, default 0.020%): sent to the treasury
+///     PDA's token account.
+///   - LP fee (pool.fee_rate_bps, default 0.30%, or the volatility-driven
+///     rate when `dynamic_fee` is configured): stays in the vault,
 ///     increasing k and credited to all LPs via fee_growth_global.
 ///
+/// amount_in and the LP fee are added to the pool's lifetime `volume_*` /
+/// `fees_collected_*` counters on the input token's side.
+///
+/// Prices against the pool's tracked reserves, not the vault balances, so
+/// tokens transferred straight into a vault can't move the price (see `sync`).
+///
 /// Effective flow:
-///   1. agent → treasury_token_in  : protocol_fee tokens
+///   1. agent → treasury_token_in  : protocol_fee − referral_fee tokens
+///      agent → referrer_token     : referral_fee tokens (if a referrer is passed)
 ///   2. agent → vault_in           : amount_in − protocol_fee tokens
 ///   3. vault_out → agent_token_out : amount_out tokens (PDA-signed)
+///
+/// `referrer_token` is an optional trailing account for integrators: it
+/// receives the config's `referral_share_bps` of the protocol fee. Swaps
+/// without it pay the whole protocol fee to the treasury, as before.
+///
+/// `agent_volume` is an optional trailing account (after `referrer_token`;
+/// pass the program ID there to skip the referrer): the agent's
+/// `AgentVolume`. With it the protocol fee is discounted by the agent's tier
+/// in `ProtocolConfig::fee_tiers`, and amount_in is added to its volume.
+///
+/// `trader_pass` is an optional trailing account (after `agent_volume`):
+/// the agent's TraderPass, which permissioned pools require (see
+/// `allowlist`). After it, `gate_token` is the agent's token account of the
+/// pool's gate mint, which token-gated pools require (see `set_pool_gate`).
+///
+/// `max_price_impact_bps` caps the pure curve impact of this trade
+/// (0 = no cap). Unlike `min_amount_out` it does not depend on a
+/// pre-flight estimate, so it also catches fat-fingered amounts in thin pools.
+///
+/// `deadline_unix` is the last unix second the swap may execute in (0 = no
+/// deadline), so a transaction left in a retry queue cannot fill later at a
+/// stale price.
+///
+/// Pools with a `circuit_breaker` also reject any swap that takes the net
+/// price impact of the current slot past the admin's limit.
 pub fn handler(
     ctx: Context<Swap>,
     amount_in: u64,
     min_amount_out: u64,
     a_to_b: bool,
+    max_price_impact_bps: u16,
+    deadline_unix: i64,
 ) -> Result<()> {
     require!(amount_in > 0, A2AError::ZeroAmount);
+    let now = Clock::get()?.unix_timestamp;
+    require!(deadline_unix == 0 || now <= deadline_unix, A2AError::DeadlineExceeded);
+    require_not_paused(&ctx.accounts.treasury, PAUSE_SWAPS)?;
+    require_allowed_trader(&ctx.accounts.pool, ctx.accounts.trader_pass.as_deref().map(|p| &**p))?;
 
-    let reserve_a = ctx.accounts.token_a_vault.amount as u128;
-    let reserve_b = ctx.accounts.token_b_vault.amount as u128;
+    let (reserve_a, reserve_b) = ctx
+        .accounts
+        .pool
+        .reserves(ctx.accounts.token_a_vault.amount, ctx.accounts.token_b_vault.amount);
+    let (reserve_a, reserve_b) = (reserve_a as u128, reserve_b as u128);
     require!(reserve_a > 0 && reserve_b > 0, A2AError::InsufficientLiquidity);
 
     let (reserve_in, reserve_out) = if a_to_b {
@@ -32,18 +82,128 @@ pub fn handler(
         (reserve_b, reserve_a)
     };
 
+    let fee_rate_bps = effective_fee_bps(
+        ctx.accounts.pool.fee_rate_bps,
+        &ctx.accounts.pool.dynamic_fee,
+        now,
+    );
+    let agent_volume = ctx.accounts.agent_volume.as_deref().map(|v| &**v);
     let sa = compute_swap(
         amount_in,
-        ctx.accounts.pool.fee_rate_bps,
+        tiered_protocol_fee_bps(&ctx.accounts.treasury, agent_volume)?,
+        fee_rate_bps,
+        ctx.accounts.pool.curve,
         reserve_in,
         reserve_out,
         ctx.accounts.pool.lp_supply,
         min_amount_out,
     )?;
+    let referral = settle(
+        SettleAccounts {
+            pool: &mut ctx.accounts.pool,
+            pool_authority: ctx.accounts.pool_authority.to_account_info(),
+            token_a_vault: ctx.accounts.token_a_vault.to_account_info(),
+            token_b_vault: ctx.accounts.token_b_vault.to_account_info(),
+            token_in: ctx.accounts.agent_token_in.to_account_info(),
+            token_out: ctx.accounts.agent_token_out.to_account_info(),
+            in_authority: ctx.accounts.agent.to_account_info(),
+            in_signer_seeds: None,
+            treasury: ctx.accounts.treasury.to_account_info(),
+            treasury_token_in: ctx.accounts.treasury_token_in.to_account_info(),
+            token_program: ctx.accounts.token_program.to_account_info(),
+            referrer_token: ctx.accounts.referrer_token.as_ref().map(|t| t.to_account_info()),
+            agent_volume: ctx.accounts.agent_volume.as_deref_mut().map(|v| &mut **v),
+        },
+        a_to_b,
+        amount_in,
+        reserve_in,
+        reserve_out,
+        now,
+        &sa,
+        max_price_impact_bps,
+    )?;
+
+    msg!(
+        "Swap: in={} protocol_fee={} referral_fee={} lp_fee={} out={} impact_bps={} a_to_b={}",
+        amount_in, sa.protocol_fee, referral, sa.lp_fee, sa.amount_out, sa.price_impact_bps, a_to_b
+    );
+    Ok(())
+}
+
+/// The accounts [`settle`] reads and moves tokens between, taken from
+/// whichever swap instruction is settling.
+pub(crate) struct SettleAccounts<'a, 'info> {
+    pub pool: &'a mut Account<'info, Pool>,
+    pub pool_authority: AccountInfo<'info>,
+    pub token_a_vault: AccountInfo<'info>,
+    pub token_b_vault: AccountInfo<'info>,
+    /// Pays the input and the protocol fee
+    pub token_in: AccountInfo<'info>,
+    /// Receives the output
+    pub token_out: AccountInfo<'info>,
+    /// Authority over `token_in`: the transaction's signer, or a PDA that
+    /// signs with `in_signer_seeds` (a Delegate, an IntentNonce)
+    pub in_authority: AccountInfo<'info>,
+    pub in_signer_seeds: Option<&'a [&'a [u8]]>,
+    pub treasury: AccountInfo<'info>,
+    pub treasury_token_in: AccountInfo<'info>,
+    pub token_program: AccountInfo<'info>,
+    /// Receives the referral share of the protocol fee when present
+    pub referrer_token: Option<AccountInfo<'info>>,
+    /// Credited with amount_in when present
+    pub agent_volume: Option<&'a mut AgentVolume>,
+}
+
+impl<'info> SettleAccounts<'_, 'info> {
+    /// `amount` from `token_in` to `to`, signed by `in_authority`.
+    fn transfer_in(&self, to: &AccountInfo<'info>, amount: u64) -> Result<()> {
+        let cpi = CpiContext::new(
+            self.token_program.clone(),
+            Transfer {
+                from: self.token_in.clone(),
+                to: to.clone(),
+                authority: self.in_authority.clone(),
+            },
+        );
+        match self.in_signer_seeds {
+            Some(seeds) => token::transfer(cpi.with_signer(&[seeds]), amount),
+            None => token::transfer(cpi, amount),
+        }
+    }
+}
+
+/// Everything after pricing, shared by every swap instruction: the impact
+/// cap, the circuit breaker, pool stats and reserves, the agent's volume,
+/// fee growth, and the token transfers. Returns the referral fee paid, for
+/// the caller's log line.
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn settle(
+    mut accounts: SettleAccounts,
+    a_to_b: bool,
+    amount_in: u64,
+    reserve_in: u128,
+    reserve_out: u128,
+    now: i64,
+    sa: &SwapAmounts,
+    max_price_impact_bps: u16,
+) -> Result<u64> {
+    require!(
+        max_price_impact_bps == 0 || sa.price_impact_bps <= max_price_impact_bps as u64,
+        A2AError::PriceImpactExceeded
+    );
+
+    let SettleAccounts { pool, agent_volume, .. } = &mut accounts;
+    record_volatility(&mut pool.dynamic_fee, sa.price_impact_bps, now);
+    record_price_move(&mut pool.circuit_breaker, a_to_b, sa.price_impact_bps, Clock::get()?.slot)?;
+    record_swap_stats(pool, a_to_b, amount_in, sa.lp_fee);
+    record_swap_reserves(pool, a_to_b, reserve_in, reserve_out, sa)?;
+    if let Some(v) = agent_volume.as_deref_mut() {
+        v.volume = v.volume.saturating_add(amount_in);
+        v.swaps = v.swaps.saturating_add(1);
+    }
 
     // ── Update fee_growth_global (Q64.64 per LP share) ──────────────────────
     if sa.fee_growth_delta > 0 {
-        let pool = &mut ctx.accounts.pool;
         if a_to_b {
             pool.fee_growth_global_a = pool.fee_growth_global_a.saturating_add(sa.fee_growth_delta);
         } else {
@@ -52,98 +212,43 @@ pub fn handler(
     }
 
     // ── PDA signer seeds for vault → agent transfer ──────────────────────────
-    let pool_key = ctx.accounts.pool.key();
-    let authority_bump = ctx.accounts.pool.authority_bump;
+    let pool_key = pool.key();
+    let authority_bump = pool.authority_bump;
     let seeds: &[&[u8]] = &[POOL_AUTHORITY_SEED, pool_key.as_ref(), &[authority_bump]];
-    let signer = &[seeds];
-
-    if a_to_b {
-        // 1. Protocol fee: agent_token_in → treasury_token_in
-        if sa.protocol_fee > 0 {
-            token::transfer(
-                CpiContext::new(
-                    ctx.accounts.token_program.to_account_info(),
-                    Transfer {
-                        from: ctx.accounts.agent_token_in.to_account_info(),
-                        to: ctx.accounts.treasury_token_in.to_account_info(),
-                        authority: ctx.accounts.agent.to_account_info(),
-                    },
-                ),
-                sa.protocol_fee,
-            )?;
-        }
-        // 2. Net swap input: agent_token_in → vault_a
-        token::transfer(
-            CpiContext::new(
-                ctx.accounts.token_program.to_account_info(),
-                Transfer {
-                    from: ctx.accounts.agent_token_in.to_account_info(),
-                    to: ctx.accounts.token_a_vault.to_account_info(),
-                    authority: ctx.accounts.agent.to_account_info(),
-                },
-            ),
-            sa.net_pool_input,
-        )?;
-        // 3. Output: vault_b → agent_token_out
-        token::transfer(
-            CpiContext::new_with_signer(
-                ctx.accounts.token_program.to_account_info(),
-                Transfer {
-                    from: ctx.accounts.token_b_vault.to_account_info(),
-                    to: ctx.accounts.agent_token_out.to_account_info(),
-                    authority: ctx.accounts.pool_authority.to_account_info(),
-                },
-                signer,
-            ),
-            sa.amount_out,
-        )?;
-    } else {
-        // 1. Protocol fee: agent_token_in → treasury_token_in
-        if sa.protocol_fee > 0 {
-            token::transfer(
-                CpiContext::new(
-                    ctx.accounts.token_program.to_account_info(),
-                    Transfer {
-                        from: ctx.accounts.agent_token_in.to_account_info(),
-                        to: ctx.accounts.treasury_token_in.to_account_info(),
-                        authority: ctx.accounts.agent.to_account_info(),
-                    },
-                ),
-                sa.protocol_fee,
-            )?;
+
+    // 1. Protocol fee: token_in → referrer_token / treasury_token_in
+    let mut referral = 0;
+    if let Some(referrer_token) = &accounts.referrer_token {
+        referral = referral_fee(sa.protocol_fee, referral_share_bps(&accounts.treasury)?);
+        if referral > 0 {
+            accounts.transfer_in(referrer_token, referral)?;
         }
-        // 2. Net swap input: agent_token_in → vault_b
-        token::transfer(
-            CpiContext::new(
-                ctx.accounts.token_program.to_account_info(),
-                Transfer {
-                    from: ctx.accounts.agent_token_in.to_account_info(),
-                    to: ctx.accounts.token_b_vault.to_account_info(),
-                    authority: ctx.accounts.agent.to_account_info(),
-                },
-            ),
-            sa.net_pool_input,
-        )?;
-        // 3. Output: vault_a → agent_token_out
-        token::transfer(
-            CpiContext::new_with_signer(
-                ctx.accounts.token_program.to_account_info(),
-                Transfer {
-                    from: ctx.accounts.token_a_vault.to_account_info(),
-                    to: ctx.accounts.agent_token_out.to_account_info(),
-                    authority: ctx.accounts.pool_authority.to_account_info(),
-                },
-                signer,
-            ),
-            sa.amount_out,
-        )?;
+    }
+    if sa.protocol_fee > referral {
+        accounts.transfer_in(&accounts.treasury_token_in, sa.protocol_fee - referral)?;
     }
 
-    msg!(
-        "Swap: in={} protocol_fee={} lp_fee={} out={} a_to_b={}",
-        amount_in, sa.protocol_fee, sa.lp_fee, sa.amount_out, a_to_b
-    );
-    Ok(())
+    let (vault_in, vault_out) = if a_to_b {
+        (&accounts.token_a_vault, &accounts.token_b_vault)
+    } else {
+        (&accounts.token_b_vault, &accounts.token_a_vault)
+    };
+    // 2. Net swap input: token_in → vault_in
+    accounts.transfer_in(vault_in, sa.net_pool_input)?;
+    // 3. Output: vault_out → token_out (PDA-signed)
+    token::transfer(
+        CpiContext::new_with_signer(
+            accounts.token_program.clone(),
+            Transfer {
+                from: vault_out.clone(),
+                to: accounts.token_out.clone(),
+                authority: accounts.pool_authority.clone(),
+            },
+            &[seeds],
+        ),
+        sa.amount_out,
+    )?;
+    Ok(referral)
 }
 
 #[derive(Accounts)]
@@ -151,7 +256,10 @@ pub struct Swap<'info> {
     #[account(mut)]
     pub agent: Signer<'info>,
 
-    #[account(mut)]
+    #[account(
+        mut,
+        constraint = !pool.is_gated() || gate_token.is_some() @ A2AError::GateTokenRequired,
+    )]
     pub pool: Account<'info, Pool>,
 
     /// CHECK: PDA vault authority
@@ -164,12 +272,16 @@ pub struct Swap<'info> {
     #[account(
         mut,
         constraint = token_a_vault.key() == pool.token_a_vault @ A2AError::MintMismatch,
+        constraint = token_a_vault.mint == pool.token_a_mint @ A2AError::MintMismatch,
+        constraint = token_a_vault.owner == pool_authority.key() @ A2AError::MintMismatch,
     )]
     pub token_a_vault: Box<Account<'info, TokenAccount>>,
 
     #[account(
         mut,
         constraint = token_b_vault.key() == pool.token_b_vault @ A2AError::MintMismatch,
+        constraint = token_b_vault.mint == pool.token_b_mint @ A2AError::MintMismatch,
+        constraint = token_b_vault.owner == pool_authority.key() @ A2AError::MintMismatch,
     )]
     pub token_b_vault: Box<Account<'info, TokenAccount>>,
 
@@ -192,17 +304,55 @@ pub struct Swap<'info> {
     )]
     pub agent_token_out: Box<Account<'info, TokenAccount>>,
 
-    /// CHECK: Global treasury PDA — holds no data, owns treasury token accounts
+    /// CHECK: Global treasury PDA — owns treasury token accounts; holds the
+    /// ProtocolConfig once initialized, parsed in the handler
     #[account(seeds = [TREASURY_SEED], bump)]
     pub treasury: UncheckedAccount<'info>,
 
-    /// Treasury's token account for the input token (same mint as agent_token_in)
+    /// Treasury's associated token account for the input token
     #[account(
         mut,
         constraint = treasury_token_in.owner == treasury.key() @ A2AError::MintMismatch,
         constraint = treasury_token_in.mint == agent_token_in.mint @ A2AError::MintMismatch,
+        constraint = treasury_token_in.key()
+            == get_associated_token_address(&treasury.key(), &agent_token_in.mint)
+            @ A2AError::InvalidTreasuryAccount,
     )]
     pub treasury_token_in: Box<Account<'info, TokenAccount>>,
 
     pub token_program: Program<'info, Token>,
+
+    /// Optional integrator token account for the input token; receives the
+    /// referral share of the protocol fee. May be omitted entirely.
+    #[account(
+        mut,
+        constraint = referrer_token.mint == agent_token_in.mint @ A2AError::MintMismatch,
+    )]
+    pub referrer_token: Option<Box<Account<'info, TokenAccount>>>,
+
+    /// Optional: the agent's AgentVolume, for fee tier discounts. May be
+    /// omitted entirely.
+    #[account(
+        mut,
+        seeds = [AGENT_VOLUME_SEED, agent.key().as_ref()],
+        bump = agent_volume.bump,
+    )]
+    pub agent_volume: Option<Box<Account<'info, AgentVolume>>>,
+
+    /// Optional: the agent's TraderPass, required on permissioned pools.
+    /// May be omitted entirely on open pools.
+    #[account(
+        seeds = [TRADER_PASS_SEED, pool.key().as_ref(), agent.key().as_ref()],
+        bump = trader_pass.bump,
+    )]
+    pub trader_pass: Option<Box<Account<'info, TraderPass>>>,
+
+    /// Optional: the agent's token account of the pool's gate mint (SPL
+    /// Token or Token-2022), required on token-gated pools.
+    #[account(
+        constraint = gate_token.mint == pool.gate_mint @ A2AError::GateTokenRequired,
+        constraint = gate_token.owner == agent.key() @ A2AError::GateTokenRequired,
+        constraint = gate_token.amount > 0 @ A2AError::GateTokenRequired,
+    )]
+    pub gate_token: Option<Box<InterfaceAccount<'info, token_interface::TokenAccount>>>,
 }
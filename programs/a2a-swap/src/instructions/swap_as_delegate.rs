@@ -0,0 +1,222 @@
+use anchor_lang::prelude::*;
+use anchor_spl::associated_token::get_associated_token_address;
+use anchor_spl::token::{Token, TokenAccount};
+use anchor_spl::token_interface;
+use crate::{constants::*, error::A2AError, state::{AgentVolume, Delegate, Pool, TraderPass}};
+use super::allowlist::require_allowed_trader;
+use super::fee_math::{compute_swap, effective_fee_bps, require_not_paused, tiered_protocol_fee_bps};
+use super::swap::{settle, SettleAccounts};
+
+/// `swap` signed by a session key instead of the wallet owner.
+///
+/// Input comes from the Delegate's `token_account` (the PDA signs as its SPL
+/// delegate) and output goes to a token account the owner holds, so the hot
+/// key can trade the owner's funds but never withdraw them. `amount_in` is
+/// counted against `max_amount_in`; past `expires_at` every swap fails.
+/// Fees are the same as `swap`, without the referral share; the owner's
+/// optional `agent_volume` gets its tier discount and counts the swap.
+pub fn handler(
+    ctx: Context<SwapAsDelegate>,
+    amount_in: u64,
+    min_amount_out: u64,
+    a_to_b: bool,
+    max_price_impact_bps: u16,
+) -> Result<()> {
+    require!(amount_in > 0, A2AError::ZeroAmount);
+    require_not_paused(&ctx.accounts.treasury, PAUSE_SWAPS)?;
+    require_allowed_trader(&ctx.accounts.pool, ctx.accounts.trader_pass.as_deref().map(|p| &**p))?;
+
+    let now = Clock::get()?.unix_timestamp;
+    let d = &mut ctx.accounts.delegate_account;
+    require!(now <= d.expires_at, A2AError::DelegateExpired);
+    let spent = d.spent.checked_add(amount_in).ok_or(A2AError::MathOverflow)?;
+    require!(spent <= d.max_amount_in, A2AError::DelegateCapExceeded);
+    d.spent = spent;
+
+    let (reserve_a, reserve_b) = ctx
+        .accounts
+        .pool
+        .reserves(ctx.accounts.token_a_vault.amount, ctx.accounts.token_b_vault.amount);
+    let (reserve_a, reserve_b) = (reserve_a as u128, reserve_b as u128);
+    require!(reserve_a > 0 && reserve_b > 0, A2AError::InsufficientLiquidity);
+
+    let (reserve_in, reserve_out) = if a_to_b {
+        (reserve_a, reserve_b)
+    } else {
+        (reserve_b, reserve_a)
+    };
+
+    let fee_rate_bps = effective_fee_bps(
+        ctx.accounts.pool.fee_rate_bps,
+        &ctx.accounts.pool.dynamic_fee,
+        now,
+    );
+    let agent_volume = ctx.accounts.agent_volume.as_deref().map(|v| &**v);
+    let sa = compute_swap(
+        amount_in,
+        tiered_protocol_fee_bps(&ctx.accounts.treasury, agent_volume)?,
+        fee_rate_bps,
+        ctx.accounts.pool.curve,
+        reserve_in,
+        reserve_out,
+        ctx.accounts.pool.lp_supply,
+        min_amount_out,
+    )?;
+
+    // The Delegate PDA spends the owner's tokens as their SPL delegate
+    let owner_key = ctx.accounts.owner.key();
+    let token_key = ctx.accounts.owner_token_in.key();
+    let delegate_bump = ctx.accounts.delegate_account.bump;
+    let delegate_seeds: &[&[u8]] =
+        &[DELEGATE_SEED, owner_key.as_ref(), token_key.as_ref(), &[delegate_bump]];
+
+    settle(
+        SettleAccounts {
+            pool: &mut ctx.accounts.pool,
+            pool_authority: ctx.accounts.pool_authority.to_account_info(),
+            token_a_vault: ctx.accounts.token_a_vault.to_account_info(),
+            token_b_vault: ctx.accounts.token_b_vault.to_account_info(),
+            token_in: ctx.accounts.owner_token_in.to_account_info(),
+            token_out: ctx.accounts.owner_token_out.to_account_info(),
+            in_authority: ctx.accounts.delegate_account.to_account_info(),
+            in_signer_seeds: Some(delegate_seeds),
+            treasury: ctx.accounts.treasury.to_account_info(),
+            treasury_token_in: ctx.accounts.treasury_token_in.to_account_info(),
+            token_program: ctx.accounts.token_program.to_account_info(),
+            referrer_token: None,
+            agent_volume: ctx.accounts.agent_volume.as_deref_mut().map(|v| &mut **v),
+        },
+        a_to_b,
+        amount_in,
+        reserve_in,
+        reserve_out,
+        now,
+        &sa,
+        max_price_impact_bps,
+    )?;
+
+    msg!(
+        "Delegated swap: owner={} delegate={} in={} protocol_fee={} lp_fee={} out={} spent={}/{} a_to_b={}",
+        owner_key,
+        ctx.accounts.delegate.key(),
+        amount_in,
+        sa.protocol_fee,
+        sa.lp_fee,
+        sa.amount_out,
+        spent,
+        ctx.accounts.delegate_account.max_amount_in,
+        a_to_b
+    );
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct SwapAsDelegate<'info> {
+    /// The session key — signs and pays for the transaction
+    #[account(mut)]
+    pub delegate: Signer<'info>,
+
+    /// CHECK: Wallet owner; only used to derive the Delegate PDA and to check
+    /// the output account. Does not sign.
+    pub owner: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [DELEGATE_SEED, owner.key().as_ref(), owner_token_in.key().as_ref()],
+        bump = delegate_account.bump,
+        has_one = delegate @ A2AError::DelegateMismatch,
+    )]
+    pub delegate_account: Box<Account<'info, Delegate>>,
+
+    #[account(
+        mut,
+        constraint = !pool.is_gated() || gate_token.is_some() @ A2AError::GateTokenRequired,
+    )]
+    pub pool: Account<'info, Pool>,
+
+    /// CHECK: PDA vault authority
+    #[account(
+        seeds = [POOL_AUTHORITY_SEED, pool.key().as_ref()],
+        bump = pool.authority_bump,
+    )]
+    pub pool_authority: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        constraint = token_a_vault.key() == pool.token_a_vault @ A2AError::MintMismatch,
+        constraint = token_a_vault.mint == pool.token_a_mint @ A2AError::MintMismatch,
+        constraint = token_a_vault.owner == pool_authority.key() @ A2AError::MintMismatch,
+    )]
+    pub token_a_vault: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        constraint = token_b_vault.key() == pool.token_b_vault @ A2AError::MintMismatch,
+        constraint = token_b_vault.mint == pool.token_b_mint @ A2AError::MintMismatch,
+        constraint = token_b_vault.owner == pool_authority.key() @ A2AError::MintMismatch,
+    )]
+    pub token_b_vault: Box<Account<'info, TokenAccount>>,
+
+    /// The Delegate's `token_account` — the owner's account being sold from
+    #[account(
+        mut,
+        constraint = owner_token_in.owner == owner.key() @ A2AError::TokenOwnerMismatch,
+        constraint = (owner_token_in.mint == pool.token_a_mint
+            || owner_token_in.mint == pool.token_b_mint) @ A2AError::MintMismatch,
+    )]
+    pub owner_token_in: Box<Account<'info, TokenAccount>>,
+
+    /// Owner's account for the other pool token; receives the output
+    #[account(
+        mut,
+        constraint = owner_token_out.owner == owner.key() @ A2AError::TokenOwnerMismatch,
+        constraint = (owner_token_out.mint == pool.token_a_mint
+            || owner_token_out.mint == pool.token_b_mint) @ A2AError::MintMismatch,
+        constraint = owner_token_out.mint != owner_token_in.mint @ A2AError::MintMismatch,
+    )]
+    pub owner_token_out: Box<Account<'info, TokenAccount>>,
+
+    /// CHECK: Global treasury PDA — owns treasury token accounts; holds the
+    /// ProtocolConfig once initialized, parsed in the handler
+    #[account(seeds = [TREASURY_SEED], bump)]
+    pub treasury: UncheckedAccount<'info>,
+
+    /// Treasury's associated token account for the input token
+    #[account(
+        mut,
+        constraint = treasury_token_in.owner == treasury.key() @ A2AError::MintMismatch,
+        constraint = treasury_token_in.mint == owner_token_in.mint @ A2AError::MintMismatch,
+        constraint = treasury_token_in.key()
+            == get_associated_token_address(&treasury.key(), &owner_token_in.mint)
+            @ A2AError::InvalidTreasuryAccount,
+    )]
+    pub treasury_token_in: Box<Account<'info, TokenAccount>>,
+
+    pub token_program: Program<'info, Token>,
+
+    /// Optional: the owner's AgentVolume, for fee tier discounts. May be
+    /// omitted entirely.
+    #[account(
+        mut,
+        seeds = [AGENT_VOLUME_SEED, owner.key().as_ref()],
+        bump = agent_volume.bump,
+    )]
+    pub agent_volume: Option<Box<Account<'info, AgentVolume>>>,
+
+    /// Optional: the owner's TraderPass, required on permissioned pools.
+    /// May be omitted entirely on open pools.
+    #[account(
+        seeds = [TRADER_PASS_SEED, pool.key().as_ref(), owner.key().as_ref()],
+        bump = trader_pass.bump,
+    )]
+    pub trader_pass: Option<Box<Account<'info, TraderPass>>>,
+
+    /// Optional: the owner's token account of the pool's gate mint (SPL
+    /// Token or Token-2022), required on token-gated pools.
+    #[account(
+        constraint = gate_token.mint == pool.gate_mint @ A2AError::GateTokenRequired,
+        constraint = gate_token.owner == owner.key() @ A2AError::GateTokenRequired,
+        constraint = gate_token.amount > 0 @ A2AError::GateTokenRequired,
+    )]
+    pub gate_token: Option<Box<InterfaceAccount<'info, token_interface::TokenAccount>>>,
+}
@@ -0,0 +1,101 @@
+use anchor_lang::prelude::*;
+use crate::{constants::PAUSE_SWAPS, error::A2AError};
+use super::fee_math::{
+    amount_in_for_exact_out, compute_swap, effective_fee_bps, require_not_paused,
+    tiered_protocol_fee_bps,
+};
+use super::allowlist::require_allowed_trader;
+use super::swap::{settle, SettleAccounts, Swap};
+
+/// Swap for an exact output: the program computes the input `amount_out`
+/// needs on the pool's curve (protocol and LP fees included) and refuses
+/// only if it exceeds `max_amount_in`. For agents settling exact invoices.
+///
+/// Same accounts, fee split, tier discount and transfers as `swap`. The input is rounded
+/// up so the forward quote always covers `amount_out`; the agent receives
+/// exactly `amount_out` and any rounding surplus stays in the pool.
+pub fn handler(
+    ctx: Context<Swap>,
+    amount_out: u64,
+    max_amount_in: u64,
+    a_to_b: bool,
+    max_price_impact_bps: u16,
+) -> Result<()> {
+    require!(amount_out > 0, A2AError::ZeroAmount);
+    require_not_paused(&ctx.accounts.treasury, PAUSE_SWAPS)?;
+    require_allowed_trader(&ctx.accounts.pool, ctx.accounts.trader_pass.as_deref().map(|p| &**p))?;
+
+    let (reserve_a, reserve_b) = ctx
+        .accounts
+        .pool
+        .reserves(ctx.accounts.token_a_vault.amount, ctx.accounts.token_b_vault.amount);
+    let (reserve_a, reserve_b) = (reserve_a as u128, reserve_b as u128);
+    require!(reserve_a > 0 && reserve_b > 0, A2AError::InsufficientLiquidity);
+
+    let (reserve_in, reserve_out) = if a_to_b {
+        (reserve_a, reserve_b)
+    } else {
+        (reserve_b, reserve_a)
+    };
+
+    let now = Clock::get()?.unix_timestamp;
+    let fee_rate_bps = effective_fee_bps(
+        ctx.accounts.pool.fee_rate_bps,
+        &ctx.accounts.pool.dynamic_fee,
+        now,
+    );
+    let agent_volume = ctx.accounts.agent_volume.as_deref().map(|v| &**v);
+    let protocol_fee_bps = tiered_protocol_fee_bps(&ctx.accounts.treasury, agent_volume)?;
+    let amount_in = amount_in_for_exact_out(
+        ctx.accounts.pool.curve,
+        reserve_in,
+        reserve_out,
+        fee_rate_bps,
+        protocol_fee_bps,
+        amount_out,
+    )?;
+    require!(amount_in <= max_amount_in, A2AError::MaxInputExceeded);
+
+    let mut sa = compute_swap(
+        amount_in,
+        protocol_fee_bps,
+        fee_rate_bps,
+        ctx.accounts.pool.curve,
+        reserve_in,
+        reserve_out,
+        ctx.accounts.pool.lp_supply,
+        amount_out,
+    )?;
+    sa.amount_out = amount_out;
+
+    let referral = settle(
+        SettleAccounts {
+            pool: &mut ctx.accounts.pool,
+            pool_authority: ctx.accounts.pool_authority.to_account_info(),
+            token_a_vault: ctx.accounts.token_a_vault.to_account_info(),
+            token_b_vault: ctx.accounts.token_b_vault.to_account_info(),
+            token_in: ctx.accounts.agent_token_in.to_account_info(),
+            token_out: ctx.accounts.agent_token_out.to_account_info(),
+            in_authority: ctx.accounts.agent.to_account_info(),
+            in_signer_seeds: None,
+            treasury: ctx.accounts.treasury.to_account_info(),
+            treasury_token_in: ctx.accounts.treasury_token_in.to_account_info(),
+            token_program: ctx.accounts.token_program.to_account_info(),
+            referrer_token: ctx.accounts.referrer_token.as_ref().map(|t| t.to_account_info()),
+            agent_volume: ctx.accounts.agent_volume.as_deref_mut().map(|v| &mut **v),
+        },
+        a_to_b,
+        amount_in,
+        reserve_in,
+        reserve_out,
+        now,
+        &sa,
+        max_price_impact_bps,
+    )?;
+
+    msg!(
+        "Swap: in={} protocol_fee={} referral_fee={} lp_fee={} out={} impact_bps={} a_to_b={}",
+        amount_in, sa.protocol_fee, referral, sa.lp_fee, sa.amount_out, sa.price_impact_bps, a_to_b
+    );
+    Ok(())
+}
@@ -0,0 +1,173 @@
+use anchor_lang::prelude::*;
+use anchor_spl::associated_token::get_associated_token_address;
+use anchor_spl::token::{self, Token, TokenAccount, Transfer};
+use crate::{constants::*, error::A2AError, state::RangePool};
+use super::{
+    fee_math::{protocol_fee_bps, require_not_paused},
+    range_math::compute_range_swap,
+};
+
+/// Swap against a range pool, crossing ranges as the price moves.
+///
+/// Fees are split exactly as in `swap` — protocol fee to the treasury, LP
+/// fee kept in the vault — but the LP fee only accrues to liquidity whose
+/// range the trade passed through. `max_price_impact_bps` caps the shortfall
+/// against the pre-trade price (0 = no cap). Range pools have no allowlist
+/// or gate (`set_pool_allowlist` / `set_pool_gate` only take a `Pool`), so
+/// there is no trader pass or `gate_token`.
+pub fn handler(
+    ctx: Context<SwapRange>,
+    amount_in: u64,
+    min_amount_out: u64,
+    a_to_b: bool,
+    max_price_impact_bps: u16,
+) -> Result<()> {
+    require!(amount_in > 0, A2AError::ZeroAmount);
+    require_not_paused(&ctx.accounts.treasury, PAUSE_SWAPS)?;
+
+    let protocol_fee_bps = protocol_fee_bps(&ctx.accounts.treasury)?;
+    let sa = compute_range_swap(
+        &mut ctx.accounts.pool,
+        amount_in,
+        protocol_fee_bps,
+        a_to_b,
+        min_amount_out,
+    )?;
+    require!(
+        max_price_impact_bps == 0 || sa.price_impact_bps <= max_price_impact_bps as u64,
+        A2AError::PriceImpactExceeded
+    );
+
+    // ── PDA signer seeds for vault → agent transfer ──────────────────────────
+    let pool_key = ctx.accounts.pool.key();
+    let authority_bump = ctx.accounts.pool.authority_bump;
+    let seeds: &[&[u8]] = &[POOL_AUTHORITY_SEED, pool_key.as_ref(), &[authority_bump]];
+    let signer = &[seeds];
+
+    let (vault_in, vault_out) = if a_to_b {
+        (&ctx.accounts.token_a_vault, &ctx.accounts.token_b_vault)
+    } else {
+        (&ctx.accounts.token_b_vault, &ctx.accounts.token_a_vault)
+    };
+
+    // 1. Protocol fee: agent_token_in → treasury_token_in
+    if sa.protocol_fee > 0 {
+        token::transfer(
+            CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                Transfer {
+                    from: ctx.accounts.agent_token_in.to_account_info(),
+                    to: ctx.accounts.treasury_token_in.to_account_info(),
+                    authority: ctx.accounts.agent.to_account_info(),
+                },
+            ),
+            sa.protocol_fee,
+        )?;
+    }
+    // 2. Net swap input: agent_token_in → vault_in
+    token::transfer(
+        CpiContext::new(
+            ctx.accounts.token_program.to_account_info(),
+            Transfer {
+                from: ctx.accounts.agent_token_in.to_account_info(),
+                to: vault_in.to_account_info(),
+                authority: ctx.accounts.agent.to_account_info(),
+            },
+        ),
+        sa.net_pool_input,
+    )?;
+    // 3. Output: vault_out → agent_token_out
+    token::transfer(
+        CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            Transfer {
+                from: vault_out.to_account_info(),
+                to: ctx.accounts.agent_token_out.to_account_info(),
+                authority: ctx.accounts.pool_authority.to_account_info(),
+            },
+            signer,
+        ),
+        sa.amount_out,
+    )?;
+
+    msg!(
+        "Range swap: in={} protocol_fee={} lp_fee={} out={} impact_bps={} a_to_b={} tick={}",
+        amount_in,
+        sa.protocol_fee,
+        sa.lp_fee,
+        sa.amount_out,
+        sa.price_impact_bps,
+        a_to_b,
+        ctx.accounts.pool.tick_current
+    );
+    Ok(())
+}
+
+#[derive(Accounts)]
+#[instruction(amount_in: u64, min_amount_out: u64, a_to_b: bool)]
+pub struct SwapRange<'info> {
+    #[account(mut)]
+    pub agent: Signer<'info>,
+
+    #[account(mut)]
+    pub pool: Box<Account<'info, RangePool>>,
+
+    /// CHECK: PDA vault authority
+    #[account(
+        seeds = [POOL_AUTHORITY_SEED, pool.key().as_ref()],
+        bump = pool.authority_bump,
+    )]
+    pub pool_authority: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        constraint = token_a_vault.key() == pool.token_a_vault @ A2AError::MintMismatch,
+        constraint = token_a_vault.mint == pool.token_a_mint @ A2AError::MintMismatch,
+        constraint = token_a_vault.owner == pool_authority.key() @ A2AError::MintMismatch,
+    )]
+    pub token_a_vault: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        constraint = token_b_vault.key() == pool.token_b_vault @ A2AError::MintMismatch,
+        constraint = token_b_vault.mint == pool.token_b_mint @ A2AError::MintMismatch,
+        constraint = token_b_vault.owner == pool_authority.key() @ A2AError::MintMismatch,
+    )]
+    pub token_b_vault: Box<Account<'info, TokenAccount>>,
+
+    /// Token account the agent is selling from — token A when a_to_b, else token B
+    #[account(
+        mut,
+        constraint = agent_token_in.owner == agent.key(),
+        constraint = agent_token_in.mint
+            == if a_to_b { pool.token_a_mint } else { pool.token_b_mint } @ A2AError::MintMismatch,
+    )]
+    pub agent_token_in: Box<Account<'info, TokenAccount>>,
+
+    /// Token account the agent is receiving into — the other pool token
+    #[account(
+        mut,
+        constraint = agent_token_out.owner == agent.key(),
+        constraint = agent_token_out.mint
+            == if a_to_b { pool.token_b_mint } else { pool.token_a_mint } @ A2AError::MintMismatch,
+    )]
+    pub agent_token_out: Box<Account<'info, TokenAccount>>,
+
+    /// CHECK: Global treasury PDA — owns treasury token accounts; holds the
+    /// ProtocolConfig once initialized, parsed in the handler
+    #[account(seeds = [TREASURY_SEED], bump)]
+    pub treasury: UncheckedAccount<'info>,
+
+    /// Treasury's associated token account for the input token
+    #[account(
+        mut,
+        constraint = treasury_token_in.owner == treasury.key() @ A2AError::MintMismatch,
+        constraint = treasury_token_in.mint == agent_token_in.mint @ A2AError::MintMismatch,
+        constraint = treasury_token_in.key()
+            == get_associated_token_address(&treasury.key(), &agent_token_in.mint)
+            @ A2AError::InvalidTreasuryAccount,
+    )]
+    pub treasury_token_in: Box<Account<'info, TokenAccount>>,
+
+    pub token_program: Program<'info, Token>,
+}
@@ -0,0 +1,308 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::instruction::Instruction;
+use solana_instructions_sysvar::{load_current_index_checked, load_instruction_at_checked};
+use solana_sdk_ids::{ed25519_program, sysvar::instructions as ix_sysvar};
+use anchor_spl::associated_token::get_associated_token_address;
+use anchor_spl::token::{Token, TokenAccount};
+use anchor_spl::token_interface;
+use crate::{constants::*, error::A2AError, state::{AgentVolume, IntentNonce, Pool, TraderPass}};
+use super::allowlist::require_allowed_trader;
+use super::fee_math::{compute_swap, effective_fee_bps, require_not_paused, tiered_protocol_fee_bps};
+use super::swap::{settle, SettleAccounts};
+
+/// `swap` from a signed intent, submitted and paid for by a relayer.
+///
+/// The agent signs `intent_message` off-chain; the instruction right before
+/// this one must be an ed25519 program instruction verifying that signature.
+/// The intent is good once: its `nonce` must equal the agent's
+/// `IntentNonce::nonce`, which is then bumped, and it fails after
+/// `expires_at`. Input comes from the agent's token account, which must have
+/// approved the IntentNonce PDA as its SPL delegate for at least `amount_in`;
+/// output goes to a token account the agent holds. The relayer pays the
+/// transaction fee and, on the agent's first intent, the IntentNonce rent.
+/// Fees are the same as `swap`, without the referral share; the agent's
+/// optional `agent_volume` gets its tier discount and counts the swap.
+#[allow(clippy::too_many_arguments)]
+pub fn handler(
+    ctx: Context<SwapWithIntent>,
+    amount_in: u64,
+    min_amount_out: u64,
+    a_to_b: bool,
+    max_price_impact_bps: u16,
+    nonce: u64,
+    expires_at: i64,
+) -> Result<()> {
+    require!(amount_in > 0, A2AError::ZeroAmount);
+    require_not_paused(&ctx.accounts.treasury, PAUSE_SWAPS)?;
+    require_allowed_trader(&ctx.accounts.pool, ctx.accounts.trader_pass.as_deref().map(|p| &**p))?;
+
+    let agent_key = ctx.accounts.agent.key();
+    let message = intent_message(
+        ctx.program_id,
+        &agent_key,
+        &ctx.accounts.pool.key(),
+        amount_in,
+        min_amount_out,
+        a_to_b,
+        max_price_impact_bps,
+        nonce,
+        expires_at,
+    );
+    let sysvar = ctx.accounts.instructions.to_account_info();
+    let current = load_current_index_checked(&sysvar)? as usize;
+    require!(current > 0, A2AError::InvalidIntentSignature);
+    verify_ed25519_ix(&load_instruction_at_checked(current - 1, &sysvar)?, &agent_key, &message)?;
+
+    let now = Clock::get()?.unix_timestamp;
+    require!(now <= expires_at, A2AError::IntentExpired);
+    let n = &mut ctx.accounts.intent_nonce;
+    require!(nonce == n.nonce, A2AError::InvalidIntentNonce);
+    n.agent = agent_key;
+    n.nonce = nonce.checked_add(1).ok_or(A2AError::MathOverflow)?;
+    n.bump = ctx.bumps.intent_nonce;
+
+    let (reserve_a, reserve_b) = ctx
+        .accounts
+        .pool
+        .reserves(ctx.accounts.token_a_vault.amount, ctx.accounts.token_b_vault.amount);
+    let (reserve_a, reserve_b) = (reserve_a as u128, reserve_b as u128);
+    require!(reserve_a > 0 && reserve_b > 0, A2AError::InsufficientLiquidity);
+
+    let (reserve_in, reserve_out) = if a_to_b {
+        (reserve_a, reserve_b)
+    } else {
+        (reserve_b, reserve_a)
+    };
+
+    let fee_rate_bps = effective_fee_bps(
+        ctx.accounts.pool.fee_rate_bps,
+        &ctx.accounts.pool.dynamic_fee,
+        now,
+    );
+    let agent_volume = ctx.accounts.agent_volume.as_deref().map(|v| &**v);
+    let sa = compute_swap(
+        amount_in,
+        tiered_protocol_fee_bps(&ctx.accounts.treasury, agent_volume)?,
+        fee_rate_bps,
+        ctx.accounts.pool.curve,
+        reserve_in,
+        reserve_out,
+        ctx.accounts.pool.lp_supply,
+        min_amount_out,
+    )?;
+
+    // The IntentNonce PDA spends the agent's tokens as their SPL delegate
+    let nonce_bump = ctx.accounts.intent_nonce.bump;
+    let nonce_seeds: &[&[u8]] = &[INTENT_NONCE_SEED, agent_key.as_ref(), &[nonce_bump]];
+
+    settle(
+        SettleAccounts {
+            pool: &mut ctx.accounts.pool,
+            pool_authority: ctx.accounts.pool_authority.to_account_info(),
+            token_a_vault: ctx.accounts.token_a_vault.to_account_info(),
+            token_b_vault: ctx.accounts.token_b_vault.to_account_info(),
+            token_in: ctx.accounts.agent_token_in.to_account_info(),
+            token_out: ctx.accounts.agent_token_out.to_account_info(),
+            in_authority: ctx.accounts.intent_nonce.to_account_info(),
+            in_signer_seeds: Some(nonce_seeds),
+            treasury: ctx.accounts.treasury.to_account_info(),
+            treasury_token_in: ctx.accounts.treasury_token_in.to_account_info(),
+            token_program: ctx.accounts.token_program.to_account_info(),
+            referrer_token: None,
+            agent_volume: ctx.accounts.agent_volume.as_deref_mut().map(|v| &mut **v),
+        },
+        a_to_b,
+        amount_in,
+        reserve_in,
+        reserve_out,
+        now,
+        &sa,
+        max_price_impact_bps,
+    )?;
+
+    msg!(
+        "Intent swap: agent={} relayer={} nonce={} in={} protocol_fee={} lp_fee={} out={} a_to_b={}",
+        agent_key,
+        ctx.accounts.relayer.key(),
+        nonce,
+        amount_in,
+        sa.protocol_fee,
+        sa.lp_fee,
+        sa.amount_out,
+        a_to_b
+    );
+    Ok(())
+}
+
+/// The message an agent signs for `swap_with_intent`: INTENT_DOMAIN, then
+/// the program, agent and pool keys, then `amount_in`, `min_amount_out`,
+/// `a_to_b` as one byte, `max_price_impact_bps`, `nonce` and `expires_at`
+/// (integers little-endian).
+#[allow(clippy::too_many_arguments)]
+pub fn intent_message(
+    program_id: &Pubkey,
+    agent: &Pubkey,
+    pool: &Pubkey,
+    amount_in: u64,
+    min_amount_out: u64,
+    a_to_b: bool,
+    max_price_impact_bps: u16,
+    nonce: u64,
+    expires_at: i64,
+) -> Vec<u8> {
+    let mut message = Vec::with_capacity(INTENT_DOMAIN.len() + 96 + 35);
+    message.extend_from_slice(INTENT_DOMAIN);
+    message.extend_from_slice(program_id.as_ref());
+    message.extend_from_slice(agent.as_ref());
+    message.extend_from_slice(pool.as_ref());
+    message.extend_from_slice(&amount_in.to_le_bytes());
+    message.extend_from_slice(&min_amount_out.to_le_bytes());
+    message.push(a_to_b as u8);
+    message.extend_from_slice(&max_price_impact_bps.to_le_bytes());
+    message.extend_from_slice(&nonce.to_le_bytes());
+    message.extend_from_slice(&expires_at.to_le_bytes());
+    message
+}
+
+/// Fail with `InvalidIntentSignature` unless `ix` is an ed25519 program
+/// instruction checking exactly one signature, by `signer` over `message`,
+/// with the key, signature and message all stored in `ix` itself. The
+/// ed25519 program has already failed the transaction if the signature
+/// doesn't verify.
+pub fn verify_ed25519_ix(ix: &Instruction, signer: &Pubkey, message: &[u8]) -> Result<()> {
+    require_keys_eq!(ix.program_id, ed25519_program::ID, A2AError::InvalidIntentSignature);
+    let data = &ix.data;
+    // [count: u8, padding: u8, then per signature seven u16 offsets]
+    require!(data.len() >= 16 && data[0] == 1, A2AError::InvalidIntentSignature);
+    let at = |i: usize| u16::from_le_bytes([data[i], data[i + 1]]);
+    let (signature_ix, key_offset, key_ix) = (at(4), at(6) as usize, at(8));
+    let (message_offset, message_len, message_ix) = (at(10) as usize, at(12) as usize, at(14));
+    require!(
+        signature_ix == u16::MAX && key_ix == u16::MAX && message_ix == u16::MAX,
+        A2AError::InvalidIntentSignature
+    );
+    let key = data.get(key_offset..key_offset + 32);
+    let signed = data.get(message_offset..message_offset + message_len);
+    require!(
+        key == Some(signer.as_ref()) && signed == Some(message),
+        A2AError::InvalidIntentSignature
+    );
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct SwapWithIntent<'info> {
+    /// Submits the intent; pays the transaction fee and any IntentNonce rent
+    #[account(mut)]
+    pub relayer: Signer<'info>,
+
+    /// CHECK: The agent whose ed25519 signature the handler verifies through
+    /// the instructions sysvar. Does not sign the transaction.
+    pub agent: UncheckedAccount<'info>,
+
+    #[account(
+        init_if_needed,
+        payer = relayer,
+        space = IntentNonce::LEN,
+        seeds = [INTENT_NONCE_SEED, agent.key().as_ref()],
+        bump,
+    )]
+    pub intent_nonce: Box<Account<'info, IntentNonce>>,
+
+    #[account(
+        mut,
+        constraint = !pool.is_gated() || gate_token.is_some() @ A2AError::GateTokenRequired,
+    )]
+    pub pool: Account<'info, Pool>,
+
+    /// CHECK: PDA vault authority
+    #[account(
+        seeds = [POOL_AUTHORITY_SEED, pool.key().as_ref()],
+        bump = pool.authority_bump,
+    )]
+    pub pool_authority: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        constraint = token_a_vault.key() == pool.token_a_vault @ A2AError::MintMismatch,
+        constraint = token_a_vault.mint == pool.token_a_mint @ A2AError::MintMismatch,
+        constraint = token_a_vault.owner == pool_authority.key() @ A2AError::MintMismatch,
+    )]
+    pub token_a_vault: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        constraint = token_b_vault.key() == pool.token_b_vault @ A2AError::MintMismatch,
+        constraint = token_b_vault.mint == pool.token_b_mint @ A2AError::MintMismatch,
+        constraint = token_b_vault.owner == pool_authority.key() @ A2AError::MintMismatch,
+    )]
+    pub token_b_vault: Box<Account<'info, TokenAccount>>,
+
+    /// Agent's account being sold from; IntentNonce is its SPL delegate
+    #[account(
+        mut,
+        constraint = agent_token_in.owner == agent.key(),
+        constraint = (agent_token_in.mint == pool.token_a_mint
+            || agent_token_in.mint == pool.token_b_mint) @ A2AError::MintMismatch,
+    )]
+    pub agent_token_in: Box<Account<'info, TokenAccount>>,
+
+    /// Agent's account for the other pool token; receives the output
+    #[account(
+        mut,
+        constraint = agent_token_out.owner == agent.key(),
+        constraint = (agent_token_out.mint == pool.token_a_mint
+            || agent_token_out.mint == pool.token_b_mint) @ A2AError::MintMismatch,
+        constraint = agent_token_out.mint != agent_token_in.mint @ A2AError::MintMismatch,
+    )]
+    pub agent_token_out: Box<Account<'info, TokenAccount>>,
+
+    /// CHECK: Global treasury PDA — owns treasury token accounts; holds the
+    /// ProtocolConfig once initialized, parsed in the handler
+    #[account(seeds = [TREASURY_SEED], bump)]
+    pub treasury: UncheckedAccount<'info>,
+
+    /// Treasury's associated token account for the input token
+    #[account(
+        mut,
+        constraint = treasury_token_in.owner == treasury.key() @ A2AError::MintMismatch,
+        constraint = treasury_token_in.mint == agent_token_in.mint @ A2AError::MintMismatch,
+        constraint = treasury_token_in.key()
+            == get_associated_token_address(&treasury.key(), &agent_token_in.mint)
+            @ A2AError::InvalidTreasuryAccount,
+    )]
+    pub treasury_token_in: Box<Account<'info, TokenAccount>>,
+
+    /// CHECK: Instructions sysvar, read for the ed25519 verification
+    #[account(address = ix_sysvar::ID)]
+    pub instructions: UncheckedAccount<'info>,
+
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+
+    /// Optional: the agent's AgentVolume, for fee tier discounts. May be
+    /// omitted entirely.
+    #[account(
+        mut,
+        seeds = [AGENT_VOLUME_SEED, agent.key().as_ref()],
+        bump = agent_volume.bump,
+    )]
+    pub agent_volume: Option<Box<Account<'info, AgentVolume>>>,
+
+    /// Optional: the agent's TraderPass, required on permissioned pools.
+    /// May be omitted entirely on open pools.
+    #[account(
+        seeds = [TRADER_PASS_SEED, pool.key().as_ref(), agent.key().as_ref()],
+        bump = trader_pass.bump,
+    )]
+    pub trader_pass: Option<Box<Account<'info, TraderPass>>>,
+
+    /// Optional: the agent's token account of the pool's gate mint (SPL
+    /// Token or Token-2022), required on token-gated pools.
+    #[account(
+        constraint = gate_token.mint == pool.gate_mint @ A2AError::GateTokenRequired,
+        constraint = gate_token.owner == agent.key() @ A2AError::GateTokenRequired,
+        constraint = gate_token.amount > 0 @ A2AError::GateTokenRequired,
+    )]
+    pub gate_token: Option<Box<InterfaceAccount<'info, token_interface::TokenAccount>>>,
+}
@@ -0,0 +1,35 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::TokenAccount;
+use crate::{error::A2AError, state::Pool};
+
+/// Reconcile the pool's tracked reserves with its vault balances. Tokens
+/// transferred straight into a vault are ignored by swaps and deposits until
+/// a `sync` folds them into the reserves, where they accrue to the LPs.
+/// Permissionless, like Uniswap's `sync`: a donor only ever gives tokens away.
+pub fn handler(ctx: Context<SyncPool>) -> Result<()> {
+    let pool = &mut ctx.accounts.pool;
+    let (from_a, from_b) = (pool.reserve_a, pool.reserve_b);
+    pool.set_reserves(ctx.accounts.token_a_vault.amount, ctx.accounts.token_b_vault.amount);
+
+    msg!(
+        "Pool synced: {} reserve_a {} -> {} reserve_b {} -> {}",
+        pool.key(), from_a, pool.reserve_a, from_b, pool.reserve_b
+    );
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct SyncPool<'info> {
+    #[account(mut)]
+    pub pool: Account<'info, Pool>,
+
+    #[account(
+        constraint = token_a_vault.key() == pool.token_a_vault @ A2AError::MintMismatch,
+    )]
+    pub token_a_vault: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        constraint = token_b_vault.key() == pool.token_b_vault @ A2AError::MintMismatch,
+    )]
+    pub token_b_vault: Box<Account<'info, TokenAccount>>,
+}
@@ -0,0 +1,33 @@
+use anchor_lang::prelude::*;
+use crate::{constants::*, error::A2AError, state::Position};
+
+/// Release a position locked by `lock_position`. Only the program that
+/// locked it can, by signing with its `[LOCK_AUTHORITY_SEED]` PDA — even
+/// after governance has removed it from the approved lockers, so a delisted
+/// lender can still return its collateral. The owner need not sign.
+pub fn handler(ctx: Context<UnlockPosition>) -> Result<()> {
+    let position = &mut ctx.accounts.position;
+    position.locked_by = Pubkey::default();
+
+    msg!("Position unlocked: {} by={}", position.key(), ctx.accounts.locker_program.key());
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct UnlockPosition<'info> {
+    #[account(
+        mut,
+        seeds = [POSITION_SEED, position.pool.as_ref(), position.owner.as_ref()],
+        bump = position.bump,
+        constraint = position.locked_by == locker_program.key() @ A2AError::LockerNotApproved,
+    )]
+    pub position: Account<'info, Position>,
+
+    /// The locker program's signing PDA
+    #[account(seeds = [LOCK_AUTHORITY_SEED], bump, seeds::program = locker_program.key())]
+    pub locker_authority: Signer<'info>,
+
+    /// CHECK: only its key is used — must be the program that locked the
+    /// position, and the program `locker_authority` is derived under
+    pub locker_program: UncheckedAccount<'info>,
+}
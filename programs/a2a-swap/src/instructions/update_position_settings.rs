@@ -0,0 +1,33 @@
+use anchor_lang::prelude::*;
+use crate::{constants::*, error::A2AError, state::Position};
+
+/// Change a position's auto-compound flag and threshold without depositing.
+/// Fees already owed are untouched; the next claim_fees uses the new settings.
+pub fn handler(
+    ctx: Context<UpdatePositionSettings>,
+    auto_compound: bool,
+    compound_threshold: u64,
+) -> Result<()> {
+    let pos = &mut ctx.accounts.position;
+    pos.auto_compound = auto_compound;
+    pos.compound_threshold = compound_threshold;
+
+    msg!(
+        "Position settings updated: {} auto_compound={} threshold={}",
+        pos.key(), auto_compound, compound_threshold
+    );
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct UpdatePositionSettings<'info> {
+    pub agent: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [POSITION_SEED, position.pool.as_ref(), agent.key().as_ref()],
+        bump = position.bump,
+        constraint = position.owner == agent.key() @ A2AError::Unauthorized,
+    )]
+    pub position: Account<'info, Position>,
+}
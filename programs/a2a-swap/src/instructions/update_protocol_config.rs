@@ -0,0 +1,44 @@
+use anchor_lang::prelude::*;
+use crate::{constants::*, error::A2AError, state::ProtocolConfig};
+
+/// Replace every `ProtocolConfig` field. Admin-only; passing a new `admin`
+/// hands the role over. The new fee applies from the next swap.
+pub fn handler(
+    ctx: Context<UpdateProtocolConfig>,
+    admin: Pubkey,
+    treasury_authority: Pubkey,
+    protocol_fee_bps: u16,
+    referral_share_bps: u16,
+) -> Result<()> {
+    require!(protocol_fee_bps <= MAX_PROTOCOL_FEE_BPS, A2AError::InvalidProtocolFee);
+    require!(referral_share_bps as u128 <= BPS_DENOMINATOR, A2AError::InvalidReferralShare);
+
+    let config = &mut ctx.accounts.protocol_config;
+    config.admin = admin;
+    config.treasury_authority = treasury_authority;
+    config.protocol_fee_bps = protocol_fee_bps;
+    config.referral_share_bps = referral_share_bps;
+
+    msg!(
+        "Protocol config updated: admin={} treasury_authority={} fee={}/{} referral_share={}bps",
+        admin,
+        treasury_authority,
+        protocol_fee_bps,
+        PROTOCOL_FEE_DENOMINATOR,
+        referral_share_bps
+    );
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct UpdateProtocolConfig<'info> {
+    pub admin: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [TREASURY_SEED],
+        bump = protocol_config.bump,
+        has_one = admin @ A2AError::Unauthorized,
+    )]
+    pub protocol_config: Account<'info, ProtocolConfig>,
+}
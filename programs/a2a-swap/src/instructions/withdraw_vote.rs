@@ -0,0 +1,71 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Token, TokenAccount, Transfer};
+use crate::{
+    constants::*,
+    error::A2AError,
+    state::{Governance, Proposal, VoteRecord},
+};
+
+/// Return a ballot's escrowed vote tokens once voting on its proposal has
+/// closed, whether or not it passed or has been executed, and close the
+/// vote record; its rent goes back to the voter.
+pub fn handler(ctx: Context<WithdrawVote>) -> Result<()> {
+    require!(
+        Clock::get()?.unix_timestamp >= ctx.accounts.proposal.voting_ends_at,
+        A2AError::VotingNotEnded
+    );
+
+    let amount = ctx.accounts.vote_record.amount;
+    let seeds: &[&[u8]] = &[GOVERNANCE_SEED, &[ctx.accounts.governance.bump]];
+    token::transfer(
+        CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            Transfer {
+                from: ctx.accounts.vote_vault.to_account_info(),
+                to: ctx.accounts.voter_token.to_account_info(),
+                authority: ctx.accounts.governance.to_account_info(),
+            },
+            &[seeds],
+        ),
+        amount,
+    )?;
+
+    msg!(
+        "Vote withdrawn: proposal {} voter={} amount={}",
+        ctx.accounts.proposal.id,
+        ctx.accounts.voter.key(),
+        amount
+    );
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct WithdrawVote<'info> {
+    #[account(mut)]
+    pub voter: Signer<'info>,
+
+    #[account(seeds = [GOVERNANCE_SEED], bump = governance.bump)]
+    pub governance: Account<'info, Governance>,
+
+    pub proposal: Account<'info, Proposal>,
+
+    #[account(
+        mut,
+        close = voter,
+        seeds = [VOTE_SEED, proposal.key().as_ref(), voter.key().as_ref()],
+        bump = vote_record.bump,
+    )]
+    pub vote_record: Account<'info, VoteRecord>,
+
+    #[account(
+        mut,
+        constraint = voter_token.owner == voter.key(),
+        constraint = voter_token.mint == governance.vote_mint @ A2AError::MintMismatch,
+    )]
+    pub voter_token: Account<'info, TokenAccount>,
+
+    #[account(mut, seeds = [VOTE_VAULT_SEED], bump)]
+    pub vote_vault: Account<'info, TokenAccount>,
+
+    pub token_program: Program<'info, Token>,
+}
@@ -1,12 +1,54 @@
-/// A2A-Swap — lightweight constant-product AMM for autonomous AI agents.
-///
-/// 6 instructions:
-///   initialize_pool     — create a bot-controlled pool with PDA authority
-///   provide_liquidity   — add liquidity; supports auto-compound flag
-///   remove_liquidity    — withdraw proportional reserves
-///   claim_fees          — claim (or auto-compound) accrued trading fees
-///   swap                — direct atomic swap; zero-human by default
-///   approve_and_execute — swap requiring agent + human/co-agent signatures
+//! A2A-Swap — lightweight constant-product / StableSwap AMM for autonomous AI agents.
+//!
+//! Instructions:
+//!   initialize_pool     — create a bot-controlled pool with PDA authority
+//!   migrate_pool        — grow an older pool account to the current layout
+//!   migrate_position    — grow an older position account to the current layout
+//!   sync                — reconcile tracked pool reserves with vault balances
+//!   provide_liquidity   — add liquidity; supports auto-compound flag
+//!   provide_liquidity_proportional — add liquidity; the program sizes the other side
+//!   remove_liquidity    — withdraw proportional reserves
+//!   claim_fees          — claim (or auto-compound) accrued trading fees
+//!   update_position_settings — change auto-compound without depositing
+//!   close_position      — close an empty position and reclaim its rent
+//!   lock_position       — approved program (via CPI): hold a position as collateral
+//!   unlock_position     — the locking program releases a position
+//!   swap                — direct atomic swap; zero-human by default
+//!   swap_exact_out      — swap for an exact output, capped by max_amount_in
+//!   open_agent_volume   — opt in to volume tracking for protocol fee tiers
+//!   register_trader     — prove membership of a permissioned pool's allowlist
+//!   swap_with_intent    — relayer executes an agent's signed swap intent
+//!   approve_and_execute — swap requiring agent + human/co-agent signatures
+//!   commit_swap         — commit to a swap's hashed parameters
+//!   reveal_swap         — execute a committed swap in a later slot
+//!   cancel_swap_commitment — close an unrevealed commitment
+//!
+//!   Concentrated-liquidity (range) pools:
+//!   initialize_range_pool   — create a pool whose LPs choose price ranges
+//!   provide_range_liquidity — add liquidity to a [tick_lower, tick_upper) range
+//!   remove_range_liquidity  — withdraw range liquidity plus earned fees
+//!   swap_range              — swap across ranges, crossing ticks as needed
+//!
+//!   Protocol admin:
+//!   initialize_protocol_config — create the global ProtocolConfig (upgrade authority)
+//!   update_protocol_config     — change the protocol fee, referral share, admin or treasury authority
+//!   set_fee_tier               — set one protocol fee discount tier for high-volume agents
+//!   set_locker                 — approve or remove a program that may lock positions
+//!   collect_protocol_fees      — treasury authority withdraws accumulated protocol fees
+//!   configure_dynamic_fee      — bound a pool's volatility-driven LP fee
+//!   configure_circuit_breaker  — cap how far swaps may move a pool's price per slot
+//!   close_pool                 — creator or admin: close an empty pool and its vaults
+//!   set_pool_allowlist         — creator: restrict a pool's swaps to a merkle allowlist of traders
+//!   set_pool_gate              — creator: require traders to hold a credential token to swap
+//!   migrate_protocol_config    — grow an older ProtocolConfig to the current layout
+//!
+//!   Governance (replaces the admin key once initialized):
+//!   initialize_governance — admin: hand the config to vote-token holders
+//!   create_proposal       — propose a protocol fee, pool fee bounds, pause, fee tier, locker,
+//!                           pool dynamic fee or circuit breaker change
+//!   cast_vote             — escrow vote tokens for or against a proposal
+//!   execute_proposal      — apply a passed proposal once voting closes
+//!   withdraw_vote         — reclaim escrowed vote tokens after voting closes
 
 // ─── Security contact ─────────────────────────────────────────────────────────
 
@@ -27,7 +69,9 @@ security_txt! {
 //
 // Machine-readable protocol description following the A2A Agent Card spec.
 // Agents can discover swap capabilities by reading this constant or fetching
-// the program's Anchor IDL.
+// the program's Anchor IDL. This is the static description; the SDK's
+// `capability_card()` and the API's GET /capability-card build the live one
+// from the deployed IDL and ProtocolConfig (fees, pause switches).
 //
 // Usage (off-chain):
 //   let card: serde_json::Value = serde_json::from_str(a2a_swap::A2A_CAPABILITY_CARD).unwrap();
@@ -45,7 +89,7 @@ pub const A2A_CAPABILITY_CARD: &str = r#"{
   },
   "capabilities": {
     "streaming": false,
-    "pushNotifications": false,
+    "pushNotifications": true,
     "autonomousExecution": true,
     "approvalMode": true,
     "autoCompound": true,
@@ -54,22 +98,29 @@ pub const A2A_CAPABILITY_CARD: &str = r#"{
   "feeModel": {
     "protocolFeeBps": 20,
     "protocolFeeDenominator": 100000,
+    "protocolFeeSource": "ProtocolConfig account at the treasury PDA [\"treasury\"]; protocolFeeBps is the default until it is initialized",
     "lpFeeRangeBps": "1-100",
     "defaultLpFeeBps": 30,
+    "dynamicFees": "optional per pool: fee_rate_bps plus a volatility premium, clamped to admin-set bounds",
+    "referralShareBps": 2000,
+    "referralShareSource": "share of the protocol fee (out of 10000) paid to the optional swap referrer; ProtocolConfig overrides the default",
     "note": "protocol_fee = amount_in * 20 / 100000; lp_fee = net * fee_rate_bps / 10000"
   },
   "skills": [
     {
       "id": "swap",
       "name": "Swap Tokens",
-      "description": "Atomic x*y=k swap. No human gate by default. Includes protocol fee (0.020%) and LP fee (pool-specific).",
+      "description": "Atomic swap on the pool's curve (x*y=k or StableSwap). No human gate by default. Includes protocol fee (0.020%) and LP fee (pool-specific).",
       "tags": ["defi", "swap", "amm", "autonomous"],
       "inputSchema": {
         "mintIn": "PublicKey",
         "mintOut": "PublicKey",
         "amountIn": "u64",
         "minAmountOut": "u64",
-        "aToB": "bool"
+        "aToB": "bool",
+        "maxPriceImpactBps": "u16",
+        "deadlineUnix": "i64",
+        "referrer": "Option<PublicKey>"
       }
     },
     {
@@ -93,6 +144,22 @@ pub const A2A_CAPABILITY_CARD: &str = r#"{
         "amountB": "u64",
         "minLp": "u64",
         "autoCompound": "bool",
+        "compoundThreshold": "u64",
+        "amountAMax": "u64",
+        "amountBMax": "u64"
+      }
+    },
+    {
+      "id": "provide_liquidity_proportional",
+      "name": "Provide Liquidity (Proportional)",
+      "description": "Deposit one token's amount; the program computes the other side from live reserves at execution, rounded up, and rejects it above maxOther. Existing pools only.",
+      "tags": ["defi", "liquidity", "lp", "auto-compound"],
+      "inputSchema": {
+        "amount": "u64",
+        "fixedA": "bool",
+        "maxOther": "u64",
+        "minLp": "u64",
+        "autoCompound": "bool",
         "compoundThreshold": "u64"
       }
     },
@@ -113,6 +180,19 @@ pub const A2A_CAPABILITY_CARD: &str = r#"{
       "description": "Claim accrued trading fees to wallet, or auto-compound them into LP shares if the position flag is set and threshold is met.",
       "tags": ["defi", "fees", "auto-compound"]
     },
+    {
+      "id": "provide_range_liquidity",
+      "name": "Provide Range Liquidity",
+      "description": "Deposit into a price range of a concentrated-liquidity pool. Liquidity only trades, and only earns fees, while the price is inside the range.",
+      "tags": ["defi", "liquidity", "lp", "concentrated-liquidity"],
+      "inputSchema": {
+        "tickLower": "i32",
+        "tickUpper": "i32",
+        "amountAMax": "u64",
+        "amountBMax": "u64",
+        "minLiquidity": "u128"
+      }
+    },
     {
       "id": "approve_and_execute",
       "name": "Approve and Execute",
@@ -121,7 +201,36 @@ pub const A2A_CAPABILITY_CARD: &str = r#"{
       "inputSchema": {
         "amountIn": "u64",
         "minAmountOut": "u64",
-        "aToB": "bool"
+        "aToB": "bool",
+        "expiresAtSlot": "u64",
+        "deadlineUnix": "i64",
+        "maxPriceImpactBps": "u16"
+      }
+    },
+    {
+      "id": "commit_reveal_swap",
+      "name": "Commit-Reveal Swap",
+      "description": "Sandwich-resistant swap in two transactions: commit_swap stores sha256 of the swap parameters and a secret salt; reveal_swap, in a later slot and within 150 slots, checks the hash and executes the swap. The parameters stay hidden until the reveal; min_amount_out and max_price_impact_bps still bound the reveal itself.",
+      "tags": ["defi", "swap", "mev-protection", "commit-reveal"],
+      "inputSchema": {
+        "amountIn": "u64",
+        "minAmountOut": "u64",
+        "aToB": "bool",
+        "maxPriceImpactBps": "u16",
+        "salt": "[u8; 32]"
+      }
+    },
+    {
+      "id": "swap_as_delegate",
+      "name": "Swap as Delegate",
+      "description": "Swap signed by a session key the wallet owner granted with create_delegate. Spends from the owner's token account up to the delegate's cap and before its expiry; output always goes to the owner. Lets operators keep the funded key cold.",
+      "tags": ["defi", "swap", "session-key", "delegation"],
+      "inputSchema": {
+        "owner": "PublicKey",
+        "amountIn": "u64",
+        "minAmountOut": "u64",
+        "aToB": "bool",
+        "maxPriceImpactBps": "u16"
       }
     }
   ]
@@ -143,14 +252,37 @@ declare_id!("8XJfG4mHqRZjByAd7HxHdEALfB8jVtJVQsdhGEmysTFq");
 pub mod a2a_swap {
     use super::*;
 
-    /// Create a constant-product pool. PDA controls vaults — no human key.
-    pub fn initialize_pool(ctx: Context<InitializePool>, fee_rate_bps: u16) -> Result<()> {
-        initialize_pool::handler(ctx, fee_rate_bps)
+    /// Create a constant-product or StableSwap pool. PDA controls vaults — no human key.
+    pub fn initialize_pool(
+        ctx: Context<InitializePool>,
+        fee_rate_bps: u16,
+        curve: CurveKind,
+    ) -> Result<()> {
+        initialize_pool::handler(ctx, fee_rate_bps, curve)
+    }
+
+    /// Grow an older pool account to the current `Pool` layout.
+    pub fn migrate_pool(ctx: Context<MigratePool>) -> Result<()> {
+        migrate_pool::handler(ctx)
+    }
+
+    /// Grow an older position account to the current `Position` layout.
+    pub fn migrate_position(ctx: Context<MigratePosition>) -> Result<()> {
+        migrate_position::handler(ctx)
     }
 
+    /// Set a pool's tracked reserves to its vault balances, absorbing
+    /// tokens sent directly to the vaults. Permissionless.
+    pub fn sync(ctx: Context<SyncPool>) -> Result<()> {
+        sync::handler(ctx)
+    }
+
+
     /// Add liquidity and receive LP shares. Set auto_compound to reinvest fees.
-    pub fn provide_liquidity(
-        ctx: Context<ProvideLiquidity>,
+    /// Pass `[receipt, mpl_core_program]` as remaining accounts to mint an
+    /// LP receipt NFT on the first deposit.
+    pub fn provide_liquidity<'info>(
+        ctx: Context<'_, '_, 'info, 'info, ProvideLiquidity<'info>>,
         amount_a: u64,
         amount_b: u64,
         min_lp: u64,
@@ -160,9 +292,27 @@ pub mod a2a_swap {
         provide_liquidity::handler(ctx, amount_a, amount_b, min_lp, auto_compound, compound_threshold)
     }
 
+    /// Add liquidity naming only one side (`fixed_a`: token A, else token
+    /// B); the program computes the other from live reserves and rejects it
+    /// above `max_other` (0 = no cap). Same accounts as `provide_liquidity`.
+    pub fn provide_liquidity_proportional<'info>(
+        ctx: Context<'_, '_, 'info, 'info, ProvideLiquidity<'info>>,
+        amount: u64,
+        fixed_a: bool,
+        max_other: u64,
+        min_lp: u64,
+        auto_compound: bool,
+        compound_threshold: u64,
+    ) -> Result<()> {
+        provide_liquidity_proportional::handler(
+            ctx, amount, fixed_a, max_other, min_lp, auto_compound, compound_threshold,
+        )
+    }
+
     /// Burn LP shares and withdraw proportional tokens.
-    pub fn remove_liquidity(
-        ctx: Context<RemoveLiquidity>,
+    /// Burns the LP receipt NFT, if one is passed, once the position is empty.
+    pub fn remove_liquidity<'info>(
+        ctx: Context<'_, '_, 'info, 'info, RemoveLiquidity<'info>>,
         lp_shares: u64,
         min_a: u64,
         min_b: u64,
@@ -175,24 +325,342 @@ pub mod a2a_swap {
         claim_fees::handler(ctx)
     }
 
+    /// Set a position's auto-compound flag and threshold without depositing.
+    pub fn update_position_settings(
+        ctx: Context<UpdatePositionSettings>,
+        auto_compound: bool,
+        compound_threshold: u64,
+    ) -> Result<()> {
+        update_position_settings::handler(ctx, auto_compound, compound_threshold)
+    }
+
+    /// Close a position with no LP shares and no owed fees; rent goes to the owner.
+    pub fn close_position(ctx: Context<ClosePosition>) -> Result<()> {
+        close_position::handler(ctx)
+    }
+
+    /// Owner + approved locker program: lock a position as collateral; it
+    /// can't be withdrawn from or closed until the same program unlocks it.
+    pub fn lock_position(ctx: Context<LockPosition>) -> Result<()> {
+        lock_position::handler(ctx)
+    }
+
+    /// The program that locked a position releases it.
+    pub fn unlock_position(ctx: Context<UnlockPosition>) -> Result<()> {
+        unlock_position::handler(ctx)
+    }
+
     /// Direct atomic swap — fully autonomous, no human approval.
+    /// `max_price_impact_bps` rejects trades that move the curve too far (0 = no cap).
+    /// `deadline_unix` rejects it after that unix time (0 = no deadline).
+    /// An optional trailing `referrer_token` receives a share of the protocol fee.
     pub fn swap(
         ctx: Context<Swap>,
         amount_in: u64,
         min_amount_out: u64,
         a_to_b: bool,
+        max_price_impact_bps: u16,
+        deadline_unix: i64,
     ) -> Result<()> {
-        swap::handler(ctx, amount_in, min_amount_out, a_to_b)
+        swap::handler(ctx, amount_in, min_amount_out, a_to_b, max_price_impact_bps, deadline_unix)
+    }
+
+    /// Swap for exactly `amount_out`; the program computes the input and
+    /// rejects it above `max_amount_in`. Same accounts as `swap`.
+    pub fn swap_exact_out(
+        ctx: Context<Swap>,
+        amount_out: u64,
+        max_amount_in: u64,
+        a_to_b: bool,
+        max_price_impact_bps: u16,
+    ) -> Result<()> {
+        swap_exact_out::handler(ctx, amount_out, max_amount_in, a_to_b, max_price_impact_bps)
+    }
+
+    /// Create the agent's AgentVolume; pass it to `swap` for fee tier discounts.
+    pub fn open_agent_volume(ctx: Context<OpenAgentVolume>) -> Result<()> {
+        open_agent_volume::handler(ctx)
+    }
+
+    /// Prove the signer is on a permissioned pool's allowlist; creates or
+    /// refreshes its TraderPass, which swaps on the pool then require.
+    pub fn register_trader(ctx: Context<RegisterTrader>, proof: Vec<[u8; 32]>) -> Result<()> {
+        register_trader::handler(ctx, proof)
     }
 
     /// Swap requiring both agent + designated approver to sign.
-    /// Use when --approval-mode webhook or slack is set.
+    /// Use when --approval-mode webhook or telegram is set.
+    /// `expires_at_slot`: last slot the approval is valid in (0 = no expiry).
+    /// `deadline_unix`: last unix time the swap may execute (0 = no deadline).
+    /// `max_price_impact_bps`: as in `swap` (0 = no cap).
     pub fn approve_and_execute(
         ctx: Context<ApproveAndExecute>,
         amount_in: u64,
         min_amount_out: u64,
         a_to_b: bool,
+        expires_at_slot: u64,
+        deadline_unix: i64,
+        max_price_impact_bps: u16,
+    ) -> Result<()> {
+        approve_and_execute::handler(
+            ctx,
+            amount_in,
+            min_amount_out,
+            a_to_b,
+            expires_at_slot,
+            deadline_unix,
+            max_price_impact_bps,
+        )
+    }
+
+    // ── Commit-reveal swaps ──────────────────────────────────────────────────
+
+    /// Commit to a swap by its `commitment_hash`; reveal it in a later slot.
+    pub fn commit_swap(ctx: Context<CommitSwap>, hash: [u8; 32]) -> Result<()> {
+        commit_swap::handler(ctx, hash)
+    }
+
+    /// Execute a committed swap. The arguments and `salt` must match the
+    /// commitment, and the reveal must land within MAX_REVEAL_DELAY_SLOTS.
+    pub fn reveal_swap(
+        ctx: Context<RevealSwap>,
+        amount_in: u64,
+        min_amount_out: u64,
+        a_to_b: bool,
+        max_price_impact_bps: u16,
+        salt: [u8; 32],
+    ) -> Result<()> {
+        reveal_swap::handler(ctx, amount_in, min_amount_out, a_to_b, max_price_impact_bps, salt)
+    }
+
+    /// Close an unrevealed swap commitment and refund its rent.
+    pub fn cancel_swap_commitment(ctx: Context<CancelSwapCommitment>) -> Result<()> {
+        cancel_swap_commitment::handler(ctx)
+    }
+
+    // ── Session keys ─────────────────────────────────────────────────────────
+
+    /// Owner: let `delegate` swap out of `owner_token`, up to `max_amount_in`
+    /// in total until `expires_at` (unix seconds).
+    pub fn create_delegate(
+        ctx: Context<CreateDelegate>,
+        delegate: Pubkey,
+        max_amount_in: u64,
+        expires_at: i64,
+    ) -> Result<()> {
+        create_delegate::handler(ctx, delegate, max_amount_in, expires_at)
+    }
+
+    /// Owner: move a session to a new key and/or limits; resets `spent`.
+    pub fn rotate_delegate(
+        ctx: Context<RotateDelegate>,
+        delegate: Pubkey,
+        max_amount_in: u64,
+        expires_at: i64,
+    ) -> Result<()> {
+        rotate_delegate::handler(ctx, delegate, max_amount_in, expires_at)
+    }
+
+    /// Owner: end a session and close its Delegate account.
+    pub fn revoke_delegate(ctx: Context<RevokeDelegate>) -> Result<()> {
+        revoke_delegate::handler(ctx)
+    }
+
+    /// Swap the owner's tokens, signed by the session key. Output goes to the owner.
+    /// `max_price_impact_bps` rejects trades that move the curve too far (0 = no cap).
+    pub fn swap_as_delegate(
+        ctx: Context<SwapAsDelegate>,
+        amount_in: u64,
+        min_amount_out: u64,
+        a_to_b: bool,
+        max_price_impact_bps: u16,
+    ) -> Result<()> {
+        swap_as_delegate::handler(ctx, amount_in, min_amount_out, a_to_b, max_price_impact_bps)
+    }
+
+    /// Swap from the agent's signed intent, submitted and paid for by a relayer.
+    /// The preceding instruction must verify the agent's ed25519 signature.
+    #[allow(clippy::too_many_arguments)]
+    pub fn swap_with_intent(
+        ctx: Context<SwapWithIntent>,
+        amount_in: u64,
+        min_amount_out: u64,
+        a_to_b: bool,
+        max_price_impact_bps: u16,
+        nonce: u64,
+        expires_at: i64,
+    ) -> Result<()> {
+        swap_with_intent::handler(
+            ctx,
+            amount_in,
+            min_amount_out,
+            a_to_b,
+            max_price_impact_bps,
+            nonce,
+            expires_at,
+        )
+    }
+
+    /// Create a concentrated-liquidity pool at price 1.0001^initial_tick.
+    pub fn initialize_range_pool(
+        ctx: Context<InitializeRangePool>,
+        fee_rate_bps: u16,
+        tick_spacing: u16,
+        initial_tick: i32,
+    ) -> Result<()> {
+        initialize_range_pool::handler(ctx, fee_rate_bps, tick_spacing, initial_tick)
+    }
+
+    /// Add liquidity to one price range of a range pool.
+    pub fn provide_range_liquidity(
+        ctx: Context<ProvideRangeLiquidity>,
+        tick_lower: i32,
+        tick_upper: i32,
+        amount_a_max: u64,
+        amount_b_max: u64,
+        min_liquidity: u128,
+    ) -> Result<()> {
+        provide_range_liquidity::handler(
+            ctx, tick_lower, tick_upper, amount_a_max, amount_b_max, min_liquidity,
+        )
+    }
+
+    /// Withdraw range liquidity and collect fees (liquidity = 0 collects only).
+    pub fn remove_range_liquidity(
+        ctx: Context<RemoveRangeLiquidity>,
+        liquidity: u128,
+        min_a: u64,
+        min_b: u64,
+    ) -> Result<()> {
+        remove_range_liquidity::handler(ctx, liquidity, min_a, min_b)
+    }
+
+    /// Swap against a range pool.
+    /// `max_price_impact_bps` rejects trades that move the price too far (0 = no cap).
+    pub fn swap_range(
+        ctx: Context<SwapRange>,
+        amount_in: u64,
+        min_amount_out: u64,
+        a_to_b: bool,
+        max_price_impact_bps: u16,
+    ) -> Result<()> {
+        swap_range::handler(ctx, amount_in, min_amount_out, a_to_b, max_price_impact_bps)
+    }
+
+    // ── Protocol admin ───────────────────────────────────────────────────────
+
+    /// Create the global ProtocolConfig at the treasury PDA (upgrade authority only).
+    pub fn initialize_protocol_config(
+        ctx: Context<InitializeProtocolConfig>,
+        protocol_fee_bps: u16,
+        referral_share_bps: u16,
+        treasury_authority: Pubkey,
     ) -> Result<()> {
-        approve_and_execute::handler(ctx, amount_in, min_amount_out, a_to_b)
+        initialize_protocol_config::handler(ctx, protocol_fee_bps, referral_share_bps, treasury_authority)
+    }
+
+    /// Admin: set the protocol fee, referral share, admin and treasury authority.
+    pub fn update_protocol_config(
+        ctx: Context<UpdateProtocolConfig>,
+        admin: Pubkey,
+        treasury_authority: Pubkey,
+        protocol_fee_bps: u16,
+        referral_share_bps: u16,
+    ) -> Result<()> {
+        update_protocol_config::handler(
+            ctx,
+            admin,
+            treasury_authority,
+            protocol_fee_bps,
+            referral_share_bps,
+        )
+    }
+
+    /// Admin: set protocol fee discount tier `index` (discount_bps = 0 clears it).
+    pub fn set_fee_tier(
+        ctx: Context<SetFeeTier>,
+        index: u8,
+        min_volume: u64,
+        discount_bps: u16,
+    ) -> Result<()> {
+        set_fee_tier::handler(ctx, index, min_volume, discount_bps)
+    }
+
+    /// Admin: approve `program` to lock positions in slot `index` (the default pubkey clears it).
+    pub fn set_locker(ctx: Context<SetLocker>, index: u8, program: Pubkey) -> Result<()> {
+        set_locker::handler(ctx, index, program)
+    }
+
+    /// Treasury authority: withdraw protocol fees (amount = 0 sweeps all).
+    pub fn collect_protocol_fees(ctx: Context<CollectProtocolFees>, amount: u64) -> Result<()> {
+        collect_protocol_fees::handler(ctx, amount)
+    }
+
+    /// Admin: bound a pool's volatility-driven LP fee (max_fee_bps = 0 disables).
+    pub fn configure_dynamic_fee(
+        ctx: Context<ConfigureDynamicFee>,
+        min_fee_bps: u16,
+        max_fee_bps: u16,
+    ) -> Result<()> {
+        configure_dynamic_fee::handler(ctx, min_fee_bps, max_fee_bps)
+    }
+
+    /// Admin: cap the net price move of a pool's swaps per slot (max_move_bps = 0 disables).
+    pub fn configure_circuit_breaker(
+        ctx: Context<ConfigureCircuitBreaker>,
+        max_move_bps: u16,
+    ) -> Result<()> {
+        configure_circuit_breaker::handler(ctx, max_move_bps)
+    }
+
+    /// Creator or admin: close an empty pool and its vaults, reclaiming their rent.
+    pub fn close_pool(ctx: Context<ClosePool>) -> Result<()> {
+        close_pool::handler(ctx)
+    }
+
+    /// Creator: only traders under `merkle_root` may swap (all zeros reopens the pool).
+    pub fn set_pool_allowlist(ctx: Context<SetPoolAllowlist>, merkle_root: [u8; 32]) -> Result<()> {
+        set_pool_allowlist::handler(ctx, merkle_root)
+    }
+
+    /// Creator: only holders of `gate_mint` may swap (default pubkey lifts the gate).
+    pub fn set_pool_gate(ctx: Context<SetPoolGate>, gate_mint: Pubkey) -> Result<()> {
+        set_pool_gate::handler(ctx, gate_mint)
+    }
+
+    /// Grow an older ProtocolConfig to the current layout. Permissionless.
+    pub fn migrate_protocol_config(ctx: Context<MigrateProtocolConfig>) -> Result<()> {
+        migrate_protocol_config::handler(ctx)
+    }
+
+    // ── Governance ───────────────────────────────────────────────────────────
+
+    /// Admin: make the Governance PDA the config admin, voted by `vote_mint` holders.
+    pub fn initialize_governance(
+        ctx: Context<InitializeGovernance>,
+        quorum: u64,
+        voting_period_secs: i64,
+    ) -> Result<()> {
+        initialize_governance::handler(ctx, quorum, voting_period_secs)
+    }
+
+    /// Propose a change to the protocol config or a pool; voting opens immediately.
+    pub fn create_proposal(ctx: Context<CreateProposal>, action: GovernanceAction) -> Result<()> {
+        create_proposal::handler(ctx, action)
+    }
+
+    /// Escrow `amount` vote tokens for (`support`) or against a proposal.
+    pub fn cast_vote(ctx: Context<CastVote>, support: bool, amount: u64) -> Result<()> {
+        cast_vote::handler(ctx, support, amount)
+    }
+
+    /// Apply a passed proposal after voting closes. Permissionless.
+    pub fn execute_proposal(ctx: Context<ExecuteProposal>) -> Result<()> {
+        execute_proposal::handler(ctx)
+    }
+
+    /// Return a ballot's vote tokens after voting closes.
+    pub fn withdraw_vote(ctx: Context<WithdrawVote>) -> Result<()> {
+        withdraw_vote::handler(ctx)
     }
 }
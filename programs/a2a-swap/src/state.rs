@@ -1,7 +1,19 @@
 use anchor_lang::prelude::*;
+use crate::constants::{FEE_TIER_COUNT, LOCKER_COUNT, MAX_POOL_FEE_BPS, MAX_RANGE_TICKS, MIN_POOL_FEE_BPS};
+
+// ─── Curve ─────────────────────────────────────────────────────────────────
+/// Swap invariant, fixed when the pool is created.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CurveKind {
+    /// x * y = k
+    ConstantProduct,
+    /// Curve-style StableSwap for correlated pairs (e.g. USDC/USDT).
+    /// `amp` is the amplification coefficient A (MIN_AMP..=MAX_AMP).
+    StableSwap { amp: u64 },
+}
 
 // ─── Pool ──────────────────────────────────────────────────────────────────
-// Constant-product pool (x * y = k).
+// Constant-product or StableSwap pool, see `curve`.
 // Authority is a PDA that owns both token vaults — no human key required.
 #[account]
 pub struct Pool {
@@ -20,11 +32,367 @@ pub struct Pool {
     pub fee_growth_global_a: u128,  // 16
     pub fee_growth_global_b: u128,  // 16
     pub bump: u8,                   // 1
+    /// Swap invariant; pools created before curves existed read as ConstantProduct
+    /// once `migrate_pool` has grown them to LEN
+    pub curve: CurveKind,           // 1 + 8
+    /// Volatility-driven fee bounds; zeroed (disabled) until configured.
+    /// Borsh packs it right after `curve`, so it starts at byte 213 for
+    /// ConstantProduct pools and 221 for StableSwap pools
+    pub dynamic_fee: DynamicFee,    // 16
+    /// Lifetime swap input in token A / token B (gross, before fees)
+    pub volume_a: u64,              // 8
+    pub volume_b: u64,              // 8
+    /// Lifetime LP fees retained in the token A / token B vault
+    pub fees_collected_a: u64,      // 8
+    pub fees_collected_b: u64,      // 8
+    /// Layout version (POOL_VERSION); new fields are only ever appended
+    pub version: u8,                // 1
+    /// Reserves the curve prices against, moved only by the program's own
+    /// transfers, so tokens sent straight to a vault don't shift the price
+    /// until `sync`. Only used once `reserves_tracked`, see `Pool::reserves`
+    pub reserve_a: u64,             // 8
+    pub reserve_b: u64,             // 8
+    /// Per-slot price move limit; zeroed (disabled) until configured
+    pub circuit_breaker: CircuitBreaker, // 14
+    /// Wallet that created the pool and may close it once empty; default
+    /// (unknown) for pools created before it was recorded
+    pub creator: Pubkey,            // 32
+    /// Merkle root of the traders allowed to swap, set by the creator;
+    /// all zeros (the default) leaves the pool open to everyone
+    pub allowlist_root: [u8; 32],   // 32
+    /// Mint of the credential (e.g. a soulbound compliance token) a trader
+    /// must hold to swap, set by the creator; default leaves swaps ungated
+    pub gate_mint: Pubkey,          // 32
+    /// Positions opened in the pool and not yet closed, which may still be
+    /// owed fees; `UNCOUNTED_POSITIONS` for pools migrated from before it
+    /// was counted
+    pub open_positions: u64,        // 8
+    /// Whether `reserve_a` / `reserve_b` hold the pool's reserves. Set at
+    /// creation and by the first instruction that records them; false only
+    /// for pools migrated from before the reserves were tracked
+    pub reserves_tracked: bool,     // 1
 }
 
 impl Pool {
-    // 8 discriminator + 32+1+32+32+32+32+8+2+16+16+1 = 212
-    pub const LEN: usize = 212;
+    // 8 discriminator + 32+1+32+32+32+32+8+2+16+16+1 + 9 + 16 + 8+8+8+8 + 1 + 8+8 + 14 + 32 + 32 + 32 + 8 + 1 = 405
+    pub const LEN: usize = 405;
+    /// Size of pools created before `curve` was added
+    pub const LEGACY_LEN: usize = 212;
+    /// Size of pools created before `dynamic_fee` was added
+    pub const CURVE_LEN: usize = 221;
+    /// Size of pools created before the volume and fee counters were added
+    pub const DYNAMIC_FEE_LEN: usize = 237;
+    /// Size of pools created before `version` was added
+    pub const STATS_LEN: usize = 269;
+    /// Size of pools created before the tracked reserves were added
+    pub const VERSION_LEN: usize = 270;
+    /// Size of pools created before `circuit_breaker` was added
+    pub const RESERVES_LEN: usize = 286;
+    /// Size of pools created before `creator` was added
+    pub const CIRCUIT_BREAKER_LEN: usize = 300;
+    /// Size of pools created before `allowlist_root` was added
+    pub const CREATOR_LEN: usize = 332;
+    /// Size of pools created before `gate_mint` was added
+    pub const ALLOWLIST_LEN: usize = 364;
+    /// Size of pools created before `open_positions` was added
+    pub const GATE_LEN: usize = 396;
+    /// Size of pools created before `reserves_tracked` was added
+    pub const OPEN_POSITIONS_LEN: usize = 404;
+    /// Every earlier size `migrate_pool` accepts
+    pub const PREVIOUS_LENS: [usize; 11] = [
+        Self::LEGACY_LEN,
+        Self::CURVE_LEN,
+        Self::DYNAMIC_FEE_LEN,
+        Self::STATS_LEN,
+        Self::VERSION_LEN,
+        Self::RESERVES_LEN,
+        Self::CIRCUIT_BREAKER_LEN,
+        Self::CREATOR_LEN,
+        Self::ALLOWLIST_LEN,
+        Self::GATE_LEN,
+        Self::OPEN_POSITIONS_LEN,
+    ];
+    /// `open_positions` of a pool whose positions predate the count
+    pub const UNCOUNTED_POSITIONS: u64 = u64::MAX;
+
+    /// Whether only allowlisted traders may swap.
+    pub fn is_permissioned(&self) -> bool {
+        self.allowlist_root != [0; 32]
+    }
+
+    /// Whether traders must hold `gate_mint` to swap.
+    pub fn is_gated(&self) -> bool {
+        self.gate_mint != Pubkey::default()
+    }
+
+    /// Whether `close_pool` may close the pool: no LP shares, no tracked
+    /// reserves, and no open position that could still be owed fees.
+    pub fn is_closable(&self) -> bool {
+        self.lp_supply == 0 && self.reserve_a == 0 && self.reserve_b == 0 && self.open_positions == 0
+    }
+
+    /// Count a newly opened position.
+    pub fn position_opened(&mut self) {
+        // Saturates at UNCOUNTED_POSITIONS, so an uncounted pool stays so
+        self.open_positions = self.open_positions.saturating_add(1);
+    }
+
+    /// Count a closed position.
+    pub fn position_closed(&mut self) {
+        if self.open_positions != Self::UNCOUNTED_POSITIONS {
+            self.open_positions = self.open_positions.saturating_sub(1);
+        }
+    }
+
+    /// Tracked `(reserve_a, reserve_b)`. A pool migrated from before the
+    /// reserves were tracked, and not touched since, adopts its vault
+    /// balances `vault_a` / `vault_b`; a tracked pool drained to zero stays
+    /// at zero, whatever was sent to its vaults.
+    pub fn reserves(&self, vault_a: u64, vault_b: u64) -> (u64, u64) {
+        if self.reserves_tracked {
+            (self.reserve_a, self.reserve_b)
+        } else {
+            (vault_a, vault_b)
+        }
+    }
+
+    /// Record the reserves, which from now on are tracked.
+    pub fn set_reserves(&mut self, reserve_a: u64, reserve_b: u64) {
+        self.reserve_a = reserve_a;
+        self.reserve_b = reserve_b;
+        self.reserves_tracked = true;
+    }
+}
+
+/// Admin-set bounds for a volatility-driven LP fee, plus the accumulator
+/// that drives it. The effective fee is
+/// `clamp(fee_rate_bps + volatility / VOLATILITY_FEE_DIVISOR, min, max)`,
+/// where `volatility` is the summed price impact of recent swaps, halved
+/// every VOLATILITY_HALF_LIFE_SECS. `max_fee_bps == 0` means disabled.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct DynamicFee {
+    pub min_fee_bps: u16,           // 2
+    pub max_fee_bps: u16,           // 2
+    /// Accumulated price impact in bps, as of `last_update_ts`
+    pub volatility_bps: u32,        // 4
+    pub last_update_ts: i64,        // 8
+}
+
+/// Admin-set cap on how far swaps may move a pool's price within one slot,
+/// guarding thin pools against manipulation. Each swap adds its price impact
+/// to `moved_bps`, negative for A→B (B per A falls) and positive for B→A,
+/// and fails once the net move since the slot began exceeds `max_move_bps`.
+/// `max_move_bps == 0` means disabled.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct CircuitBreaker {
+    pub max_move_bps: u16,          // 2
+    /// Net signed price impact in bps of the swaps in `slot`
+    pub moved_bps: i32,             // 4
+    pub slot: u64,                  // 8
+}
+
+// ─── ProtocolConfig ────────────────────────────────────────────────────────
+// Global protocol settings. Lives at the treasury PDA ([TREASURY_SEED]), so
+// swaps read it through the `treasury` account they already pass, and the
+// treasury keeps signing for its token accounts as before.
+#[account]
+pub struct ProtocolConfig {
+    /// May update this config and configure pool dynamic fees; the
+    /// Governance PDA once initialize_governance has run
+    pub admin: Pubkey,                // 32
+    /// May withdraw accumulated protocol fees from the treasury
+    pub treasury_authority: Pubkey,   // 32
+    /// Protocol fee, out of PROTOCOL_FEE_DENOMINATOR (20 = 0.020%)
+    pub protocol_fee_bps: u16,        // 2
+    /// Share of the protocol fee paid to a swap's referrer, out of BPS_DENOMINATOR
+    pub referral_share_bps: u16,      // 2
+    pub bump: u8,                     // 1
+    /// PAUSE_* bits of the operations governance has switched off
+    pub paused: u8,                   // 1
+    /// Bounds on a new pool's fee_rate_bps; both 0 = MIN_POOL_FEE_BPS..=MAX_POOL_FEE_BPS
+    pub min_pool_fee_bps: u16,        // 2
+    pub max_pool_fee_bps: u16,        // 2
+    /// Protocol fee discounts for agents with an AgentVolume account
+    pub fee_tiers: [FeeTier; FEE_TIER_COUNT], // 4 * 10
+    /// Programs approved to lock positions as collateral; default = empty slot
+    pub lockers: [Pubkey; LOCKER_COUNT], // 4 * 32
+}
+
+impl ProtocolConfig {
+    // 8 + 32+32+2+2+1 + 1+2+2 + 4*10 + 4*32 = 250
+    pub const LEN: usize = 250;
+    /// Size before `paused` and the pool fee bounds were appended
+    pub const LEGACY_LEN: usize = 77;
+
+    /// Discount (out of BPS_DENOMINATOR) off the protocol fee for an agent
+    /// with `volume` traded: the largest among the tiers it has reached.
+    /// Unset tiers have a zero discount, so 0 until governance sets one.
+    pub fn fee_discount_bps(&self, volume: u64) -> u16 {
+        self.fee_tiers
+            .iter()
+            .filter(|t| volume >= t.min_volume)
+            .map(|t| t.discount_bps)
+            .max()
+            .unwrap_or(0)
+    }
+
+    /// Range a new pool's `fee_rate_bps` must fall in.
+    pub fn pool_fee_bounds(&self) -> (u16, u16) {
+        if self.max_pool_fee_bps == 0 {
+            (MIN_POOL_FEE_BPS, MAX_POOL_FEE_BPS)
+        } else {
+            (self.min_pool_fee_bps, self.max_pool_fee_bps)
+        }
+    }
+
+    /// Whether governance has switched off `op` (a PAUSE_* bit).
+    pub fn is_paused(&self, op: u8) -> bool {
+        self.paused & op != 0
+    }
+
+    /// Whether `program` may lock positions.
+    pub fn is_locker(&self, program: &Pubkey) -> bool {
+        *program != Pubkey::default() && self.lockers.contains(program)
+    }
+}
+
+/// One step of the protocol fee discount schedule.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct FeeTier {
+    /// Lifetime `AgentVolume::volume` an agent needs to reach this tier
+    pub min_volume: u64,             // 8
+    /// Share of the protocol fee waived, out of BPS_DENOMINATOR; 0 = unset
+    pub discount_bps: u16,           // 2
+}
+
+impl FeeTier {
+    pub const LEN: usize = 10;
+}
+
+// ─── AgentVolume ───────────────────────────────────────────────────────────
+// Opt-in lifetime swap volume of one agent, at [AGENT_VOLUME_SEED, agent].
+// `swap` and `swap_exact_out` add to it when it is passed, and price the
+// protocol fee at the agent's tier in ProtocolConfig::fee_tiers.
+#[account]
+pub struct AgentVolume {
+    pub agent: Pubkey,                   // 32
+    /// Sum of amount_in over the agent's tracked swaps, in atomic units of
+    /// each swap's input token
+    pub volume: u64,                     // 8
+    pub swaps: u64,                      // 8
+    pub bump: u8,                        // 1
+}
+
+impl AgentVolume {
+    // 8 + 32+8+8+1 = 57
+    pub const LEN: usize = 57;
+}
+
+// ─── TraderPass ────────────────────────────────────────────────────────────
+// A trader's proven membership of a permissioned pool's allowlist, at
+// [TRADER_PASS_SEED, pool, trader]. register_trader checks a merkle proof
+// once and records the root it proved against; swaps on the pool accept the
+// pass while that is still the pool's root.
+#[account]
+pub struct TraderPass {
+    pub pool: Pubkey,                    // 32
+    pub trader: Pubkey,                  // 32
+    /// Pool::allowlist_root the trader's proof was checked against
+    pub root: [u8; 32],                  // 32
+    pub bump: u8,                        // 1
+}
+
+impl TraderPass {
+    // 8 + 32+32+32+1 = 105
+    pub const LEN: usize = 105;
+}
+
+// ─── Governance ────────────────────────────────────────────────────────────
+// Token-weighted control of the protocol config. initialize_governance makes
+// this PDA the config's admin, so from then on the fee, pool fee bounds and
+// pause switches only change through a passed Proposal.
+#[account]
+pub struct Governance {
+    /// Token voters escrow in the vote vault; one atomic unit = one vote
+    pub vote_mint: Pubkey,            // 32
+    /// Least total votes (for + against) a proposal needs to pass
+    pub quorum: u64,                  // 8
+    /// How long each proposal is open for votes (seconds)
+    pub voting_period_secs: i64,      // 8
+    /// Proposals created so far; the id of the next one
+    pub proposal_count: u64,          // 8
+    pub bump: u8,                     // 1
+}
+
+impl Governance {
+    // 8 + 32+8+8+8+1 = 65
+    pub const LEN: usize = 65;
+}
+
+/// What a proposal does to the `ProtocolConfig`, or to one pool, once it
+/// passes.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum GovernanceAction {
+    /// Same bounds as `update_protocol_config`
+    SetProtocolFee { protocol_fee_bps: u16, referral_share_bps: u16 },
+    /// Range new pools' `fee_rate_bps` must fall in
+    SetPoolFeeBounds { min_fee_bps: u16, max_fee_bps: u16 },
+    /// Replace the PAUSE_* bits
+    SetPaused { paused: u8 },
+    /// Replace `fee_tiers[index]`
+    SetFeeTier { index: u8, min_volume: u64, discount_bps: u16 },
+    /// Replace `lockers[index]`; the default pubkey clears it
+    SetLocker { index: u8, program: Pubkey },
+    /// Same as `configure_dynamic_fee` on `pool`
+    ConfigureDynamicFee { pool: Pubkey, min_fee_bps: u16, max_fee_bps: u16 },
+    /// Same as `configure_circuit_breaker` on `pool`
+    ConfigureCircuitBreaker { pool: Pubkey, max_move_bps: u16 },
+}
+
+// ─── Proposal ──────────────────────────────────────────────────────────────
+// One governance vote, at [PROPOSAL_SEED, id]. Open until `voting_ends_at`;
+// after that anyone may execute it if it passed.
+#[account]
+pub struct Proposal {
+    pub id: u64,                         // 8
+    pub proposer: Pubkey,                // 32
+    pub action: GovernanceAction,        // 1 + 36
+    pub votes_for: u64,                  // 8
+    pub votes_against: u64,              // 8
+    /// Unix time voting closes
+    pub voting_ends_at: i64,             // 8
+    pub executed: bool,                  // 1
+    pub bump: u8,                        // 1
+}
+
+impl Proposal {
+    // 8 + 8+32+37+8+8+8+1+1 = 111
+    pub const LEN: usize = 111;
+
+    /// More votes for than against, with at least `quorum` votes cast.
+    pub fn passed(&self, quorum: u64) -> bool {
+        self.votes_for > self.votes_against
+            && self.votes_for.saturating_add(self.votes_against) >= quorum
+    }
+}
+
+// ─── VoteRecord ────────────────────────────────────────────────────────────
+// A voter's ballot on one proposal, at [VOTE_SEED, proposal, voter]. The
+// tokens behind it stay in the vote vault until withdraw_vote after voting
+// closes, so the same tokens cannot be counted twice on a proposal.
+#[account]
+pub struct VoteRecord {
+    pub proposal: Pubkey,                // 32
+    pub voter: Pubkey,                   // 32
+    pub support: bool,                   // 1
+    /// Vote tokens escrowed, and the ballot's weight
+    pub amount: u64,                     // 8
+    pub bump: u8,                        // 1
+}
+
+impl VoteRecord {
+    // 8 + 32+32+1+8+1 = 82
+    pub const LEN: usize = 82;
 }
 
 // ─── Position ──────────────────────────────────────────────────────────────
@@ -46,9 +414,163 @@ pub struct Position {
     /// Minimum total fee (token_a + token_b in atomic units) to trigger compound
     pub compound_threshold: u64,         // 8
     pub bump: u8,                        // 1
+    /// Layout version (POSITION_VERSION); new fields are only ever appended
+    pub version: u8,                     // 1
+    /// Approved locker program holding the position as collateral; default
+    /// when unlocked. A locked position can't be withdrawn from or closed
+    pub locked_by: Pubkey,               // 32
 }
 
 impl Position {
-    // 8 + 32+32+8+16+16+8+8+1+8+1 = 138
-    pub const LEN: usize = 138;
+    // 8 + 32+32+8+16+16+8+8+1+8+1+1+32 = 171
+    pub const LEN: usize = 171;
+    /// Size of positions created before `version` was added
+    pub const LEGACY_LEN: usize = 138;
+    /// Size of version-1 positions, before `locked_by` was appended
+    pub const V1_LEN: usize = 139;
+
+    pub fn is_locked(&self) -> bool {
+        self.locked_by != Pubkey::default()
+    }
+}
+
+// ─── RangePool ─────────────────────────────────────────────────────────────
+// Concentrated-liquidity pool: LPs choose a [tick_lower, tick_upper) price
+// range and only earn fees while the price is inside it. Price is token B
+// per token A in atomic units; tick t is price 1.0001^t. Initialized ticks
+// live in the pool account itself (sorted, at most MAX_RANGE_TICKS), so a
+// swap needs no extra accounts however many ranges it crosses.
+#[account]
+pub struct RangePool {
+    /// PDA that owns token_a_vault and token_b_vault
+    pub authority: Pubkey,          // 32
+    pub authority_bump: u8,         // 1
+    pub token_a_mint: Pubkey,       // 32
+    pub token_b_mint: Pubkey,       // 32
+    pub token_a_vault: Pubkey,      // 32
+    pub token_b_vault: Pubkey,      // 32
+    /// Trading fee rate in basis points (e.g. 30 = 0.30 %)
+    pub fee_rate_bps: u16,          // 2
+    /// Range bounds must be multiples of this
+    pub tick_spacing: u16,          // 2
+    /// sqrt(price), Q64.64
+    pub sqrt_price_x64: u128,       // 16
+    /// Greatest tick whose sqrt price is <= sqrt_price_x64
+    pub tick_current: i32,          // 4
+    /// Liquidity of the ranges containing the current price
+    pub liquidity: u128,            // 16
+    /// Cumulative fee earned per unit of liquidity, Q64.64 (wrapping)
+    pub fee_growth_global_a: u128,  // 16
+    pub fee_growth_global_b: u128,  // 16
+    pub bump: u8,                   // 1
+    /// Initialized ticks, sorted by index
+    pub ticks: Vec<Tick>,           // 4 + MAX_RANGE_TICKS * Tick::LEN
+}
+
+impl RangePool {
+    // 8 + 32+1+32+32+32+32+2+2+16+4+16+16+16+1 + 4 + 64 * 68 = 4598
+    pub const LEN: usize = 246 + MAX_RANGE_TICKS * Tick::LEN;
+}
+
+/// A range boundary referenced by at least one position.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Tick {
+    pub index: i32,                  // 4
+    /// Liquidity added when the price crosses this tick upwards
+    pub liquidity_net: i128,         // 16
+    /// Total liquidity referencing this tick; the tick is freed at zero
+    pub liquidity_gross: u128,       // 16
+    /// Fee growth on the other side of this tick from the current price
+    pub fee_growth_outside_a: u128,  // 16
+    pub fee_growth_outside_b: u128,  // 16
+}
+
+impl Tick {
+    pub const LEN: usize = 68;
+}
+
+// ─── RangePosition ─────────────────────────────────────────────────────────
+// One agent's liquidity in one range of a RangePool.
+#[account]
+pub struct RangePosition {
+    pub owner: Pubkey,                   // 32
+    pub pool: Pubkey,                    // 32
+    pub tick_lower: i32,                 // 4
+    pub tick_upper: i32,                 // 4
+    pub liquidity: u128,                 // 16
+    /// Fee growth inside the range at last sync, Q64.64
+    pub fee_growth_inside_last_a: u128,  // 16
+    pub fee_growth_inside_last_b: u128,  // 16
+    /// Accrued but unclaimed fee tokens
+    pub fees_owed_a: u64,                // 8
+    pub fees_owed_b: u64,                // 8
+    pub bump: u8,                        // 1
+}
+
+impl RangePosition {
+    // 8 + 32+32+4+4+16+16+16+8+8+1 = 145
+    pub const LEN: usize = 145;
+}
+
+// ─── Delegate ──────────────────────────────────────────────────────────────
+// Session key: lets a hot `delegate` key swap out of one of the owner's token
+// accounts, up to `max_amount_in` in total and until `expires_at`. The PDA is
+// the SPL delegate of `token_account` for the cap, so the owner's key never
+// has to sign a swap. One per owner token account.
+#[account]
+pub struct Delegate {
+    pub owner: Pubkey,                   // 32
+    /// Key allowed to call swap_as_delegate
+    pub delegate: Pubkey,                // 32
+    /// Owner token account swaps are paid from
+    pub token_account: Pubkey,           // 32
+    /// Total amount_in the delegate may spend
+    pub max_amount_in: u64,              // 8
+    /// amount_in spent so far; reset on rotation
+    pub spent: u64,                      // 8
+    /// Unix timestamp after which swaps are refused
+    pub expires_at: i64,                 // 8
+    pub bump: u8,                        // 1
+}
+
+impl Delegate {
+    // 8 + 32+32+32+8+8+8+1 = 129
+    pub const LEN: usize = 129;
+}
+
+// ─── IntentNonce ───────────────────────────────────────────────────────────
+// Replay guard for an agent's signed swap intents, at [INTENT_NONCE_SEED,
+// agent]. Each `swap_with_intent` must carry the current `nonce` and bumps
+// it. The PDA is also the SPL delegate the agent approves on its input token
+// accounts, so a relayer can execute the intent without the agent's key.
+#[account]
+pub struct IntentNonce {
+    pub agent: Pubkey,                   // 32
+    /// Nonce the agent's next intent must carry
+    pub nonce: u64,                      // 8
+    pub bump: u8,                        // 1
+}
+
+impl IntentNonce {
+    // 8 + 32+8+1 = 49
+    pub const LEN: usize = 49;
+}
+
+// ─── SwapCommitment ────────────────────────────────────────────────────────
+// First half of a commit-reveal swap: the hash of the swap's parameters,
+// published in `commit_slot` and opened by `reveal_swap` in a later slot.
+// One open commitment per agent per pool.
+#[account]
+pub struct SwapCommitment {
+    pub agent: Pubkey,                   // 32
+    pub pool: Pubkey,                    // 32
+    /// sha256 of the swap parameters and a secret salt (`commitment_hash`)
+    pub hash: [u8; 32],                  // 32
+    pub commit_slot: u64,                // 8
+    pub bump: u8,                        // 1
+}
+
+impl SwapCommitment {
+    // 8 + 32+32+32+8+1 = 113
+    pub const LEN: usize = 113;
 }
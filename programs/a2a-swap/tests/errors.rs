@@ -0,0 +1,67 @@
+//! The SDK's `A2AErrorCode` must mirror `A2AError` code-for-code.
+
+use a2a_swap::error::A2AError;
+use a2a_swap_sdk::A2AErrorCode;
+
+#[test]
+fn sdk_error_table_matches_program() {
+    let program = [
+        A2AError::InsufficientLiquidity,
+        A2AError::SlippageExceeded,
+        A2AError::ZeroAmount,
+        A2AError::MathOverflow,
+        A2AError::InvalidFeeRate,
+        A2AError::MintMismatch,
+        A2AError::InvalidMoltAsset,
+        A2AError::MoltAgentMismatch,
+        A2AError::PriceImpactExceeded,
+        A2AError::InvalidReceipt,
+        A2AError::InvalidAmplification,
+        A2AError::CurveNotConverged,
+        A2AError::InvalidTickRange,
+        A2AError::TickCapacityExceeded,
+        A2AError::Unauthorized,
+        A2AError::InvalidProtocolFee,
+        A2AError::InvalidReferralShare,
+        A2AError::ApprovalExpired,
+        A2AError::DelegateExpired,
+        A2AError::DelegateCapExceeded,
+        A2AError::DelegateMismatch,
+        A2AError::InvalidTreasuryAccount,
+        A2AError::MaxInputExceeded,
+        A2AError::PriceMoveExceeded,
+        A2AError::InvalidPriceMoveLimit,
+        A2AError::CommitmentMismatch,
+        A2AError::CommitmentNotReady,
+        A2AError::CommitmentExpired,
+        A2AError::PositionNotEmpty,
+        A2AError::PoolNotEmpty,
+        A2AError::Paused,
+        A2AError::InvalidGovernanceParams,
+        A2AError::VotingClosed,
+        A2AError::VotingNotEnded,
+        A2AError::ProposalNotPassed,
+        A2AError::ProposalAlreadyExecuted,
+        A2AError::InvalidFeeTier,
+        A2AError::IntentExpired,
+        A2AError::InvalidIntentNonce,
+        A2AError::InvalidIntentSignature,
+        A2AError::PositionLocked,
+        A2AError::LockerNotApproved,
+        A2AError::TraderNotAllowed,
+        A2AError::InvalidAllowlistProof,
+        A2AError::GateTokenRequired,
+        A2AError::DeadlineExceeded,
+        A2AError::DepositExceedsMax,
+        A2AError::TokenOwnerMismatch,
+    ];
+    assert_eq!(program.len(), A2AErrorCode::ALL.len());
+
+    for (err, sdk) in program.into_iter().zip(A2AErrorCode::ALL) {
+        let msg = err.to_string();
+        let name = format!("{err:?}");
+        assert_eq!(u32::from(err), sdk.code(), "{name}");
+        assert_eq!(name, sdk.name());
+        assert_eq!(msg, sdk.message(), "{name}");
+    }
+}
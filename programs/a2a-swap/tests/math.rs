@@ -0,0 +1,1841 @@
+//! Property tests for the on-chain fee / curve arithmetic.
+//!
+//! Pure functions only — no bank, no validator. The SDK's `simulate_detailed`
+//! is the off-chain mirror of `compute_swap`; the two must agree to the unit.
+
+use a2a_swap::{
+    instructions::{
+        fee_math::{
+            amount_in_for_exact_out, compute_swap, effective_fee_bps, record_price_move,
+            discounted_fee_bps, record_swap_reserves, record_swap_stats, record_volatility, referral_fee,
+            stable_invariant, stable_swap_out, tiered_protocol_fee_bps,
+        },
+        provide_liquidity::{accrue_fees, isqrt, lp_shares_for_deposit, proportional_amount, within_max},
+        remove_liquidity::amounts_for_lp_shares,
+        range_math::{
+            amounts_for_liquidity, compute_range_swap, mul_div, mul_div_ceil, sqrt_price_at_tick,
+            tick_at_sqrt_price, update_position,
+        },
+    },
+    allowlist::{allowlist_leaf, require_allowed_trader, verify_allowlist_proof},
+    commit_swap::commitment_hash,
+    create_proposal::validate_action,
+    swap_with_intent::{intent_message, verify_ed25519_ix},
+    state::{
+        AgentVolume, CircuitBreaker, CurveKind, DynamicFee, FeeTier, Governance, GovernanceAction, IntentNonce, Pool,
+        Position, Proposal, ProtocolConfig, RangePool, RangePosition, SwapCommitment, TraderPass, VoteRecord,
+    },
+    FEE_TIER_COUNT, LOCKER_COUNT, MAX_POOL_FEE_BPS, MAX_PROTOCOL_FEE_BPS, MAX_TICK, MIN_POOL_FEE_BPS, MIN_TICK, PAUSE_ALL, PAUSE_POOL_CREATION,
+    PAUSE_SWAPS, POOL_FEE_CAP_BPS, POOL_VERSION, POSITION_VERSION, PROTOCOL_FEE_BPS,
+    VOLATILITY_HALF_LIFE_SECS,
+};
+use a2a_swap_sdk::{
+    allowlist::{self as sdk_allowlist, AllowlistTree},
+    math::{
+        curve_amount_in_for_exact_out as sdk_amount_in_for_exact_out,
+        effective_fee_bps as sdk_effective_fee_bps, lp_shares_for_deposit as sdk_lp_shares_for_deposit,
+        lp_underlying as sdk_lp_underlying, proportional_amount as sdk_proportional_amount,
+        referral_fee as sdk_referral_fee, simulate_detailed,
+        stable_invariant as sdk_stable_invariant, stable_swap_out as sdk_stable_swap_out,
+    },
+    instructions as sdk_ix,
+    range_math as sdk_range,
+    state::{
+        parse_agent_volume, parse_governance, parse_pool, parse_position, parse_proposal, parse_protocol_config,
+        parse_vote_record, CircuitBreaker as SdkCircuitBreaker, CurveKind as SdkCurveKind, DynamicFee as SdkDynamicFee,
+        FeeTier as SdkFeeTier, GovernanceAction as SdkGovernanceAction, PoolState, RangePoolState, RangeTick,
+        AGENT_VOLUME_LEN, POOL_LEN, POSITION_LEN, PROTOCOL_CONFIG_LEGACY_LEN, PROTOCOL_CONFIG_LEN,
+    },
+};
+use a2a_swap_core::{ix, Account as _, Instruction as _};
+use anchor_lang::{
+    prelude::Pubkey, solana_program::instruction::Instruction, AccountDeserialize, AccountSerialize,
+    Discriminator, InstructionData,
+};
+use proptest::prelude::*;
+
+fn position(lp_shares: u64) -> Position {
+    Position {
+        owner:                   Pubkey::default(),
+        pool:                    Pubkey::default(),
+        lp_shares,
+        fee_growth_checkpoint_a: 0,
+        fee_growth_checkpoint_b: 0,
+        fees_owed_a:             0,
+        fees_owed_b:             0,
+        auto_compound:           false,
+        compound_threshold:      0,
+        bump:                    0,
+        version:                 POSITION_VERSION,
+        locked_by:               Pubkey::default(),
+    }
+}
+
+fn sdk_pool(fee_rate_bps: u16, lp_supply: u64) -> PoolState {
+    sdk_pool_with(fee_rate_bps, lp_supply, SdkCurveKind::ConstantProduct)
+}
+
+fn sdk_pool_with(fee_rate_bps: u16, lp_supply: u64, curve: SdkCurveKind) -> PoolState {
+    PoolState {
+        token_a_mint:        Pubkey::default(),
+        token_b_mint:        Pubkey::default(),
+        token_a_vault:       Pubkey::default(),
+        token_b_vault:       Pubkey::default(),
+        lp_supply,
+        fee_rate_bps,
+        fee_growth_global_a: 0,
+        fee_growth_global_b: 0,
+        curve,
+        dynamic_fee:         SdkDynamicFee::default(),
+        volume_a:            0,
+        volume_b:            0,
+        fees_collected_a:    0,
+        fees_collected_b:    0,
+        version:             POOL_VERSION,
+        reserve_a:           0,
+        reserve_b:           0,
+        circuit_breaker:     SdkCircuitBreaker::default(),
+        creator:             Pubkey::default(),
+        allowlist_root:      [0; 32],
+        gate_mint:           Pubkey::default(),
+        open_positions:      0,
+        reserves_tracked:    true,
+    }
+}
+
+/// LP tokens paid to a position holding `shares` for a growth delta.
+fn payout(shares: u64, delta: u128) -> u128 {
+    let mut pos = position(shares);
+    accrue_fees(&mut pos, delta, 0).unwrap();
+    pos.fees_owed_a as u128
+}
+
+// ─── isqrt ────────────────────────────────────────────────────────────────────
+
+fn assert_isqrt(n: u128) -> Result<(), TestCaseError> {
+    let r = isqrt(n);
+    prop_assert!(r * r <= n, "isqrt({n}) = {r} is too large");
+    // (r + 1)² overflowing u128 means it is certainly > n.
+    if let Some(next) = (r + 1).checked_mul(r + 1) {
+        prop_assert!(next > n, "isqrt({n}) = {r} is too small");
+    }
+    Ok(())
+}
+
+proptest! {
+    #[test]
+    fn isqrt_is_floor_sqrt(n in any::<u128>()) {
+        assert_isqrt(n)?;
+    }
+
+    /// Perfect squares and their neighbours — where off-by-one errors live.
+    #[test]
+    fn isqrt_exact_at_square_boundaries(r in 0u128..=u64::MAX as u128) {
+        let sq = r * r;
+        prop_assert_eq!(isqrt(sq), r);
+        if sq > 0 {
+            prop_assert_eq!(isqrt(sq - 1), r - 1);
+        }
+        if let Some(above) = sq.checked_add(1) {
+            assert_isqrt(above)?;
+        }
+    }
+}
+
+#[test]
+fn isqrt_edges() {
+    assert_eq!(isqrt(0), 0);
+    assert_eq!(isqrt(1), 1);
+    assert_eq!(isqrt(3), 1);
+    assert_eq!(isqrt(4), 2);
+    assert_eq!(isqrt(u128::MAX), u64::MAX as u128);
+}
+
+#[test]
+fn deposit_caps_of_zero_are_off() {
+    assert!(within_max(u64::MAX, 0));
+    assert!(within_max(100, 100));
+    assert!(!within_max(101, 100));
+}
+
+#[test]
+fn proportional_amount_rounds_up() {
+    assert_eq!(proportional_amount(10, 3, 1).unwrap(), 4);
+    assert_eq!(proportional_amount(9, 3, 1).unwrap(), 3);
+    assert_eq!(proportional_amount(u64::MAX, 1, 1).unwrap(), u64::MAX);
+    assert!(proportional_amount(1, 0, 1).is_err());
+    assert!(proportional_amount(u64::MAX, 1, 2).is_err());
+}
+
+proptest! {
+    /// The SDK's deposit / withdrawal previews use the program's LP math.
+    #[test]
+    fn lp_math_matches_sdk(
+        amount_a  in 0u64..=u64::MAX,
+        amount_b  in 0u64..=u64::MAX,
+        lp_supply in 0u64..=u64::MAX,
+        reserve_a in 0u64..=u64::MAX,
+        reserve_b in 0u64..=u64::MAX,
+    ) {
+        prop_assert_eq!(
+            lp_shares_for_deposit(amount_a, amount_b, lp_supply, reserve_a, reserve_b).ok(),
+            sdk_lp_shares_for_deposit(amount_a, amount_b, lp_supply, reserve_a, reserve_b).ok(),
+        );
+        prop_assert_eq!(
+            proportional_amount(amount_a, reserve_a, reserve_b).ok(),
+            sdk_proportional_amount(amount_a, reserve_a, reserve_b).ok(),
+        );
+        if lp_supply > 0 {
+            let shares = amount_a.min(lp_supply);
+            prop_assert_eq!(
+                amounts_for_lp_shares(shares, lp_supply, reserve_a, reserve_b).unwrap(),
+                sdk_lp_underlying(shares, lp_supply, reserve_a, reserve_b),
+            );
+        }
+    }
+}
+
+// ─── compute_swap ─────────────────────────────────────────────────────────────
+
+proptest! {
+    /// Reserves after a swap never lower k: the pool keeps `net_pool_input`
+    /// (LP fee included) and pays out at most the curve amount.
+    #[test]
+    fn swap_never_decreases_k(
+        reserve_in   in 1u64..=u64::MAX >> 1,
+        reserve_out  in 1u64..=u64::MAX >> 1,
+        amount_in    in 1u64..=u64::MAX >> 1,
+        fee_rate_bps in 1u16..=100,
+    ) {
+        let Ok(sa) = compute_swap(
+            amount_in, PROTOCOL_FEE_BPS, fee_rate_bps, CurveKind::ConstantProduct, reserve_in as u128, reserve_out as u128, 1, 0,
+        ) else {
+            return Ok(()); // ZeroAmount: nothing leaves the pool
+        };
+        prop_assert!(sa.amount_out < reserve_out);
+        let k_before = reserve_in as u128 * reserve_out as u128;
+        let k_after = (reserve_in as u128 + sa.net_pool_input as u128)
+            * (reserve_out - sa.amount_out) as u128;
+        prop_assert!(k_after >= k_before);
+    }
+
+    /// Every input token is accounted for, and the SDK mirror agrees exactly.
+    #[test]
+    fn swap_fees_match_sdk_exactly(
+        reserve_in   in 1u64..=u64::MAX,
+        reserve_out  in 1u64..=u64::MAX,
+        amount_in    in 1u64..=u64::MAX,
+        fee_rate_bps in 1u16..=100,
+        lp_supply    in 1u64..=u64::MAX,
+        protocol_fee in 0u64..=MAX_PROTOCOL_FEE_BPS as u64,
+    ) {
+        let onchain = compute_swap(
+            amount_in, protocol_fee, fee_rate_bps, CurveKind::ConstantProduct, reserve_in as u128, reserve_out as u128, lp_supply, 0,
+        );
+        let sdk = simulate_detailed(
+            Pubkey::default(), &sdk_pool(fee_rate_bps, lp_supply),
+            reserve_in, reserve_out, amount_in, protocol_fee, true,
+        );
+        match (onchain, sdk) {
+            (Ok(sa), Ok(sim)) => {
+                prop_assert_eq!(sa.protocol_fee, sim.protocol_fee);
+                prop_assert_eq!(sa.net_pool_input, sim.net_pool_input);
+                prop_assert_eq!(sa.lp_fee, sim.lp_fee as u128);
+                prop_assert_eq!(sa.amount_out, sim.estimated_out);
+                prop_assert_eq!(
+                    sa.protocol_fee as u128 + sa.lp_fee + sim.after_fees as u128,
+                    amount_in as u128
+                );
+            }
+            // The program rejects zero-output swaps; the SDK just quotes 0.
+            (Err(_), Ok(sim)) => prop_assert_eq!(sim.estimated_out, 0),
+            (Ok(_), Err(e)) => prop_assert!(false, "SDK failed where program succeeded: {e}"),
+            (Err(_), Err(_)) => {}
+        }
+    }
+
+    /// Q64.64 growth never over-distributes, and under-distributes by at most
+    /// one token when a single position holds the whole supply.
+    #[test]
+    fn fee_growth_rounding_is_bounded(
+        amount_in    in 1u64..=u64::MAX,
+        fee_rate_bps in 1u16..=100,
+        lp_supply    in 1u64..=u64::MAX,
+        split        in 0u64..=u64::MAX,
+    ) {
+        let Ok(sa) = compute_swap(amount_in, PROTOCOL_FEE_BPS, fee_rate_bps, CurveKind::ConstantProduct, u64::MAX as u128, u64::MAX as u128, lp_supply, 0)
+        else {
+            return Ok(());
+        };
+        let whole = payout(lp_supply, sa.fee_growth_delta);
+        prop_assert!(whole <= sa.lp_fee);
+        prop_assert!(sa.lp_fee - whole <= 1);
+
+        // Any split of the supply pays out no more than the single holder.
+        let a = (split as u128 % (lp_supply as u128 + 1)) as u64;
+        let parts = payout(a, sa.fee_growth_delta)
+            + payout(lp_supply - a, sa.fee_growth_delta);
+        prop_assert!(parts <= whole);
+    }
+}
+
+// ─── StableSwap ───────────────────────────────────────────────────────────────
+
+proptest! {
+    /// The StableSwap invariant never decreases across a swap, so rounding in
+    /// the Newton solution always favours the pool.
+    #[test]
+    fn stable_swap_never_decreases_invariant(
+        reserve_in   in 1_000u64..=1u64 << 50,
+        reserve_out  in 1_000u64..=1u64 << 50,
+        amount_in    in 1u64..=1u64 << 48,
+        fee_rate_bps in 1u16..=100,
+        amp          in 1u64..=10_000,
+    ) {
+        let curve = CurveKind::StableSwap { amp };
+        let Ok(sa) = compute_swap(
+            amount_in, PROTOCOL_FEE_BPS, fee_rate_bps, curve, reserve_in as u128, reserve_out as u128, 1, 0,
+        ) else {
+            return Ok(());
+        };
+        prop_assert!(sa.amount_out < reserve_out);
+        let d_before = stable_invariant(reserve_in as u128, reserve_out as u128, amp).unwrap();
+        let d_after = stable_invariant(
+            reserve_in as u128 + sa.net_pool_input as u128,
+            (reserve_out - sa.amount_out) as u128,
+            amp,
+        )
+        .unwrap();
+        prop_assert!(d_after >= d_before);
+    }
+
+    /// The SDK's StableSwap quote matches the program to the unit.
+    #[test]
+    fn stable_swap_matches_sdk_exactly(
+        reserve_in   in 1u64..=1u64 << 50,
+        reserve_out  in 1u64..=1u64 << 50,
+        amount_in    in 1u64..=1u64 << 50,
+        fee_rate_bps in 1u16..=100,
+        lp_supply    in 1u64..=u64::MAX,
+        amp          in 1u64..=10_000,
+        protocol_fee in 0u64..=MAX_PROTOCOL_FEE_BPS as u64,
+    ) {
+        let onchain = compute_swap(
+            amount_in, protocol_fee, fee_rate_bps, CurveKind::StableSwap { amp },
+            reserve_in as u128, reserve_out as u128, lp_supply, 0,
+        );
+        let sdk = simulate_detailed(
+            Pubkey::default(),
+            &sdk_pool_with(fee_rate_bps, lp_supply, SdkCurveKind::StableSwap { amp }),
+            reserve_in, reserve_out, amount_in, protocol_fee, true,
+        );
+        match (onchain, sdk) {
+            (Ok(sa), Ok(sim)) => {
+                prop_assert_eq!(sa.protocol_fee, sim.protocol_fee);
+                prop_assert_eq!(sa.net_pool_input, sim.net_pool_input);
+                prop_assert_eq!(sa.lp_fee, sim.lp_fee as u128);
+                prop_assert_eq!(sa.amount_out, sim.estimated_out);
+                let (_, impact_bps) = sdk_stable_swap_out(
+                    sim.after_fees as u128, reserve_in as u128, reserve_out as u128, amp,
+                ).unwrap();
+                let (_, onchain_bps) = stable_swap_out(
+                    sim.after_fees as u128, reserve_in as u128, reserve_out as u128, amp,
+                ).unwrap();
+                prop_assert_eq!(impact_bps, onchain_bps);
+            }
+            (Err(_), Ok(sim)) => prop_assert_eq!(sim.estimated_out, 0),
+            (Ok(_), Err(e)) => prop_assert!(false, "SDK failed where program succeeded: {e}"),
+            (Err(_), Err(_)) => {}
+        }
+    }
+
+    /// The invariant itself is computed identically on both sides.
+    #[test]
+    fn stable_invariant_matches_sdk(
+        x   in 0u64..=1u64 << 60,
+        y   in 0u64..=1u64 << 60,
+        amp in 1u64..=10_000,
+    ) {
+        let onchain = stable_invariant(x as u128, y as u128, amp).ok();
+        let sdk = sdk_stable_invariant(x as u128, y as u128, amp).ok();
+        prop_assert_eq!(onchain, sdk);
+    }
+}
+
+// ─── Exact-output swaps ───────────────────────────────────────────────────────
+
+fn curve_strategy() -> impl Strategy<Value = CurveKind> {
+    prop_oneof![Just(CurveKind::ConstantProduct), (1u64..=10_000).prop_map(|amp| CurveKind::StableSwap { amp })]
+}
+
+fn sdk_curve(curve: CurveKind) -> SdkCurveKind {
+    match curve {
+        CurveKind::ConstantProduct     => SdkCurveKind::ConstantProduct,
+        CurveKind::StableSwap { amp }  => SdkCurveKind::StableSwap { amp },
+    }
+}
+
+proptest! {
+    /// `swap_exact_out` charges what the SDK quotes, and `compute_swap` on
+    /// that input always covers the requested output.
+    #[test]
+    fn exact_out_input_matches_sdk_and_covers_output(
+        reserve_in   in 1_000u64..=1u64 << 50,
+        reserve_out  in 1_000u64..=1u64 << 50,
+        out_frac     in 1u64..=9_000,
+        fee_rate_bps in 1u16..=100,
+        protocol_fee in 0u64..=MAX_PROTOCOL_FEE_BPS as u64,
+        curve        in curve_strategy(),
+    ) {
+        let amount_out = (reserve_out as u128 * out_frac as u128 / 10_000).max(1) as u64;
+        let onchain = amount_in_for_exact_out(
+            curve, reserve_in as u128, reserve_out as u128, fee_rate_bps, protocol_fee, amount_out,
+        );
+        let sdk = sdk_amount_in_for_exact_out(
+            sdk_curve(curve), reserve_in, reserve_out, fee_rate_bps, protocol_fee, amount_out,
+        );
+        match (onchain, sdk) {
+            (Ok(amount_in), Ok(sdk_in)) => {
+                prop_assert_eq!(amount_in, sdk_in);
+                if let Ok(sa) = compute_swap(
+                    amount_in, protocol_fee, fee_rate_bps, curve,
+                    reserve_in as u128, reserve_out as u128, 1, 0,
+                ) {
+                    prop_assert!(sa.amount_out >= amount_out);
+                }
+            }
+            (Ok(_), Err(e)) => prop_assert!(false, "SDK failed where program succeeded: {e}"),
+            (Err(_), Ok(_)) => prop_assert!(false, "program failed where SDK succeeded"),
+            (Err(_), Err(_)) => {}
+        }
+    }
+}
+
+#[test]
+fn exact_out_is_minimal_on_constant_product() {
+    let curve = CurveKind::ConstantProduct;
+    let amount_in = amount_in_for_exact_out(curve, 1_000_000, 2_000_000, 30, PROTOCOL_FEE_BPS, 10_000).unwrap();
+    let out = |a| compute_swap(a, PROTOCOL_FEE_BPS, 30, curve, 1_000_000, 2_000_000, 1, 0).unwrap().amount_out;
+    assert!(out(amount_in) >= 10_000);
+    assert!(out(amount_in - 1) < 10_000);
+    assert!(amount_in_for_exact_out(curve, 1_000, 1_000, 30, PROTOCOL_FEE_BPS, 1_000).is_err());
+}
+
+// ─── Dynamic fees ─────────────────────────────────────────────────────────────
+
+fn dynamic_fee() -> impl Strategy<Value = DynamicFee> {
+    (1u16..=100, 0u16..=100, any::<u32>(), 0i64..1_000_000).prop_map(|(min, spread, vol, ts)| {
+        DynamicFee {
+            min_fee_bps:    min,
+            max_fee_bps:    (min + spread).min(100),
+            volatility_bps: vol,
+            last_update_ts: ts,
+        }
+    })
+}
+
+fn sdk_dynamic_fee(df: DynamicFee) -> SdkDynamicFee {
+    SdkDynamicFee {
+        min_fee_bps:    df.min_fee_bps,
+        max_fee_bps:    df.max_fee_bps,
+        volatility_bps: df.volatility_bps,
+        last_update_ts: df.last_update_ts,
+    }
+}
+
+proptest! {
+    /// The SDK quotes the same LP fee the program charges, always within bounds.
+    #[test]
+    fn effective_fee_matches_sdk_and_stays_in_bounds(
+        base    in 1u16..=100,
+        df      in dynamic_fee(),
+        elapsed in 0i64..100_000,
+    ) {
+        let now = df.last_update_ts + elapsed;
+        let fee = effective_fee_bps(base, &df, now);
+        prop_assert!((df.min_fee_bps..=df.max_fee_bps).contains(&fee));
+
+        let mut pool = sdk_pool_with(base, 0, SdkCurveKind::ConstantProduct);
+        pool.dynamic_fee = sdk_dynamic_fee(df);
+        prop_assert_eq!(sdk_effective_fee_bps(&pool, now), fee);
+    }
+
+    /// More recent price impact never lowers the fee, and it decays back
+    /// to the (clamped) base fee once the accumulator has halved away.
+    #[test]
+    fn effective_fee_rises_with_impact_and_decays(
+        base   in 1u16..=100,
+        df     in dynamic_fee(),
+        impact in 0u64..100_000,
+    ) {
+        let now = df.last_update_ts;
+        let mut after = df;
+        record_volatility(&mut after, impact, now);
+        prop_assert!(effective_fee_bps(base, &after, now) >= effective_fee_bps(base, &df, now));
+
+        let later = now + 33 * VOLATILITY_HALF_LIFE_SECS;
+        let floor = base.clamp(df.min_fee_bps, df.max_fee_bps);
+        prop_assert_eq!(effective_fee_bps(base, &after, later), floor);
+    }
+}
+
+#[test]
+fn disabled_dynamic_fee_is_inert() {
+    let mut df = DynamicFee::default();
+    record_volatility(&mut df, 5_000, 1_000);
+    assert_eq!(df, DynamicFee::default());
+    assert_eq!(effective_fee_bps(30, &df, 1_000), 30);
+}
+
+#[test]
+fn sdk_parses_dynamic_fee_and_counters_after_either_curve() {
+    let df = DynamicFee {
+        min_fee_bps:    5,
+        max_fee_bps:    80,
+        volatility_bps: 1_234,
+        last_update_ts: 1_700_000_000,
+    };
+    for (curve, sdk_curve) in [
+        (CurveKind::ConstantProduct, SdkCurveKind::ConstantProduct),
+        (CurveKind::StableSwap { amp: 200 }, SdkCurveKind::StableSwap { amp: 200 }),
+    ] {
+        let mut pool = Pool {
+            authority:           Pubkey::new_unique(),
+            authority_bump:      254,
+            token_a_mint:        Pubkey::new_unique(),
+            token_b_mint:        Pubkey::new_unique(),
+            token_a_vault:       Pubkey::new_unique(),
+            token_b_vault:       Pubkey::new_unique(),
+            lp_supply:           42,
+            fee_rate_bps:        30,
+            fee_growth_global_a: 7,
+            fee_growth_global_b: 9,
+            bump:                253,
+            curve,
+            dynamic_fee:         df,
+            volume_a:            0,
+            volume_b:            0,
+            fees_collected_a:    0,
+            fees_collected_b:    0,
+            version:             POOL_VERSION,
+            reserve_a:           0,
+            reserve_b:           0,
+            circuit_breaker:     CircuitBreaker::default(),
+            creator:             Pubkey::new_unique(),
+            allowlist_root:      [0; 32],
+            gate_mint:           Pubkey::default(),
+            open_positions:      0,
+            reserves_tracked:    true,
+        };
+        record_swap_stats(&mut pool, true, 1_000, 3);
+        record_swap_stats(&mut pool, true, 500, 1);
+        record_swap_stats(&mut pool, false, 2_000, u128::MAX);
+        let mut data = Vec::new();
+        pool.try_serialize(&mut data).unwrap();
+        assert!(data.len() <= Pool::LEN);
+        data.resize(POOL_LEN, 0);
+
+        let parsed = parse_pool(&data).unwrap();
+        assert_eq!(parsed.curve, sdk_curve);
+        assert_eq!(parsed.dynamic_fee, sdk_dynamic_fee(df));
+        assert_eq!(parsed.lp_supply, 42);
+        assert_eq!((parsed.volume_a, parsed.volume_b), (1_500, 2_000));
+        assert_eq!((parsed.fees_collected_a, parsed.fees_collected_b), (4, u64::MAX));
+        assert_eq!(parsed.version, POOL_VERSION);
+
+        // Fields appended by a later layout are ignored.
+        let mut newer = data.clone();
+        newer.extend_from_slice(&[0xff; 32]);
+        let parsed = parse_pool(&newer).unwrap();
+        assert_eq!((parsed.volume_b, parsed.version), (2_000, POOL_VERSION));
+
+        // Before `migrate_pool` adds the counters they read as zero. An
+        // account from that layout is zero past its last field, which for
+        // constant-product pools ends before DYNAMIC_FEE_LEN.
+        data.truncate(Pool::DYNAMIC_FEE_LEN);
+        data[Pool::LEGACY_LEN + sdk_curve.to_bytes().len() + 16..].fill(0);
+        let parsed = parse_pool(&data).unwrap();
+        assert_eq!(parsed.dynamic_fee, sdk_dynamic_fee(df));
+        assert_eq!((parsed.volume_a, parsed.fees_collected_b), (0, 0));
+        assert_eq!(parsed.version, 0);
+    }
+}
+
+/// Swaps move the tracked reserves by exactly what enters and leaves the
+/// vaults; tokens sent straight to a vault don't count, and the SDK reads
+/// the same reserves back.
+#[test]
+fn tracked_reserves_ignore_vault_donations() {
+    let mut pool = Pool {
+        authority:           Pubkey::new_unique(),
+        authority_bump:      254,
+        token_a_mint:        Pubkey::new_unique(),
+        token_b_mint:        Pubkey::new_unique(),
+        token_a_vault:       Pubkey::new_unique(),
+        token_b_vault:       Pubkey::new_unique(),
+        lp_supply:           1_000_000,
+        fee_rate_bps:        30,
+        fee_growth_global_a: 0,
+        fee_growth_global_b: 0,
+        bump:                253,
+        curve:               CurveKind::StableSwap { amp: 100 },
+        dynamic_fee:         DynamicFee::default(),
+        volume_a:            0,
+        volume_b:            0,
+        fees_collected_a:    0,
+        fees_collected_b:    0,
+        version:             POOL_VERSION,
+        reserve_a:           0,
+        reserve_b:           0,
+        circuit_breaker:     CircuitBreaker::default(),
+        creator:             Pubkey::new_unique(),
+        allowlist_root:      [0; 32],
+        gate_mint:           Pubkey::default(),
+        open_positions:      0,
+        reserves_tracked:    false,
+    };
+    // Untracked (migrated) pools price against the vaults.
+    let (vault_a, vault_b) = (5_000_000u64, 8_000_000u64);
+    assert_eq!(pool.reserves(vault_a, vault_b), (vault_a, vault_b));
+
+    let (ra, rb) = pool.reserves(vault_a, vault_b);
+    let sa = compute_swap(
+        100_000, PROTOCOL_FEE_BPS, 30, pool.curve, ra as u128, rb as u128, pool.lp_supply, 0,
+    )
+    .unwrap();
+    record_swap_reserves(&mut pool, true, ra as u128, rb as u128, &sa).unwrap();
+    let (vault_a, vault_b) = (vault_a + sa.net_pool_input, vault_b - sa.amount_out);
+    assert_eq!((pool.reserve_a, pool.reserve_b), (vault_a, vault_b));
+    assert!(pool.reserves_tracked);
+
+    // A donation to either vault leaves the price untouched.
+    assert_eq!(pool.reserves(vault_a + 1_000_000, vault_b + 7), (vault_a, vault_b));
+
+    let mut data = Vec::new();
+    pool.try_serialize(&mut data).unwrap();
+    assert_eq!(data.len(), Pool::LEN);
+    let parsed = parse_pool(&data).unwrap();
+    assert_eq!((parsed.reserve_a, parsed.reserve_b), (vault_a, vault_b));
+    assert_eq!(parsed.reserves(vault_a + 1_000_000, vault_b), (vault_a, vault_b));
+
+    // Before `migrate_pool` appends them, reserves read as untracked (a
+    // StableSwap pool fills the layout, so the old size ends right before them).
+    data.truncate(Pool::VERSION_LEN);
+    assert_eq!(parse_pool(&data).unwrap().reserves(1, 2), (1, 2));
+}
+
+/// A tracked pool drained back to zero reserves stays at zero: tokens sent
+/// to its vaults afterwards are not picked up as its price.
+#[test]
+fn drained_pool_ignores_vault_donations() {
+    let mut pool = Pool {
+        authority:           Pubkey::new_unique(),
+        authority_bump:      254,
+        token_a_mint:        Pubkey::new_unique(),
+        token_b_mint:        Pubkey::new_unique(),
+        token_a_vault:       Pubkey::new_unique(),
+        token_b_vault:       Pubkey::new_unique(),
+        lp_supply:           1_000,
+        fee_rate_bps:        30,
+        fee_growth_global_a: 0,
+        fee_growth_global_b: 0,
+        bump:                253,
+        curve:               CurveKind::StableSwap { amp: 100 },
+        dynamic_fee:         DynamicFee::default(),
+        volume_a:            0,
+        volume_b:            0,
+        fees_collected_a:    0,
+        fees_collected_b:    0,
+        version:             POOL_VERSION,
+        reserve_a:           0,
+        reserve_b:           0,
+        circuit_breaker:     CircuitBreaker::default(),
+        creator:             Pubkey::new_unique(),
+        allowlist_root:      [0; 32],
+        gate_mint:           Pubkey::default(),
+        open_positions:      0,
+        reserves_tracked:    false,
+    };
+    pool.set_reserves(0, 0);
+    assert!(pool.reserves_tracked);
+    assert_eq!(pool.reserves(1_000_000, 3), (0, 0));
+
+    let mut data = Vec::new();
+    pool.try_serialize(&mut data).unwrap();
+    let parsed = parse_pool(&data).unwrap();
+    assert!(parsed.reserves_tracked);
+    assert_eq!(parsed.reserves(1_000_000, 3), (0, 0));
+
+    // Version 7 accounts derive the flag the way `migrate_pool` does: zero
+    // reserves with LP shares outstanding are untracked, with none tracked.
+    let legacy = parse_pool(&data[..Pool::OPEN_POSITIONS_LEN]).unwrap();
+    assert!(!legacy.reserves_tracked);
+    assert_eq!(legacy.reserves(1_000_000, 3), (1_000_000, 3));
+
+    data.clear();
+    Pool { lp_supply: 0, ..pool }.try_serialize(&mut data).unwrap();
+    let legacy = parse_pool(&data[..Pool::OPEN_POSITIONS_LEN]).unwrap();
+    assert!(legacy.reserves_tracked);
+    assert_eq!(legacy.reserves(1_000_000, 3), (0, 0));
+}
+
+/// The breaker caps the net move of one slot: a reversing trade frees
+/// room, a new slot starts from zero, and the SDK's headroom is the impact
+/// the next swap may still have.
+#[test]
+fn circuit_breaker_caps_net_move_per_slot() {
+    let mut cb = CircuitBreaker { max_move_bps: 300, ..CircuitBreaker::default() };
+    record_price_move(&mut cb, true, 200, 10).unwrap();
+    assert_eq!(cb.moved_bps, -200);
+    let sdk = |cb: CircuitBreaker| SdkCircuitBreaker {
+        max_move_bps: cb.max_move_bps,
+        moved_bps:    cb.moved_bps,
+        slot:         cb.slot,
+    };
+    assert_eq!(sdk(cb).headroom_bps(true, 10), Some(100));
+    assert_eq!(sdk(cb).headroom_bps(false, 10), Some(500));
+    assert_eq!(sdk(cb).headroom_bps(true, 11), Some(300));
+
+    let before = cb;
+    assert!(record_price_move(&mut cb, true, 101, 10).is_err());
+    cb = before;
+    record_price_move(&mut cb, false, 450, 10).unwrap();
+    assert_eq!(cb.moved_bps, 250);
+    record_price_move(&mut cb, false, 300, 11).unwrap();
+    assert_eq!((cb.moved_bps, cb.slot), (300, 11));
+    assert!(record_price_move(&mut cb, false, 1, 11).is_err());
+    assert!(record_price_move(&mut CircuitBreaker { max_move_bps: 300, ..cb }, true, u64::MAX, 12).is_err());
+
+    let mut off = CircuitBreaker::default();
+    record_price_move(&mut off, true, 10_000, 10).unwrap();
+    assert_eq!(off, CircuitBreaker::default());
+    assert_eq!(sdk(off).headroom_bps(true, 10), None);
+}
+
+#[test]
+fn sdk_parses_circuit_breaker() {
+    let pool = Pool {
+        authority:           Pubkey::new_unique(),
+        authority_bump:      254,
+        token_a_mint:        Pubkey::new_unique(),
+        token_b_mint:        Pubkey::new_unique(),
+        token_a_vault:       Pubkey::new_unique(),
+        token_b_vault:       Pubkey::new_unique(),
+        lp_supply:           1,
+        fee_rate_bps:        30,
+        fee_growth_global_a: 0,
+        fee_growth_global_b: 0,
+        bump:                253,
+        curve:               CurveKind::StableSwap { amp: 10 },
+        dynamic_fee:         DynamicFee::default(),
+        volume_a:            0,
+        volume_b:            0,
+        fees_collected_a:    0,
+        fees_collected_b:    0,
+        version:             POOL_VERSION,
+        reserve_a:           9,
+        reserve_b:           8,
+        circuit_breaker:     CircuitBreaker { max_move_bps: 500, moved_bps: -42, slot: 77 },
+        creator:             Pubkey::new_unique(),
+        allowlist_root:      [0; 32],
+        gate_mint:           Pubkey::default(),
+        open_positions:      0,
+        reserves_tracked:    true,
+    };
+    let mut data = Vec::new();
+    pool.try_serialize(&mut data).unwrap();
+    assert_eq!(data.len(), Pool::LEN);
+    let parsed = parse_pool(&data).unwrap();
+    assert_eq!(
+        parsed.circuit_breaker,
+        SdkCircuitBreaker { max_move_bps: 500, moved_bps: -42, slot: 77 }
+    );
+    assert_eq!(parsed.creator, pool.creator);
+
+    // Pools from before `creator` was recorded can only be closed by the admin.
+    data.truncate(Pool::CIRCUIT_BREAKER_LEN);
+    let parsed = parse_pool(&data).unwrap();
+    assert_eq!(parsed.creator, Pubkey::default());
+    assert!(parsed.circuit_breaker.is_enabled());
+
+    // Pools from before `migrate_pool` appends it have no breaker.
+    data.truncate(Pool::RESERVES_LEN);
+    let parsed = parse_pool(&data).unwrap();
+    assert!(!parsed.circuit_breaker.is_enabled());
+    assert_eq!((parsed.reserve_a, parsed.reserve_b), (9, 8));
+}
+
+/// A pool whose last LP withdrew but hasn't claimed its fees can't be
+/// closed until that position is, and neither can a migrated pool whose
+/// positions were never counted.
+#[test]
+fn close_pool_waits_for_positions_owed_fees() {
+    let mut pool = Pool {
+        authority:           Pubkey::new_unique(),
+        authority_bump:      254,
+        token_a_mint:        Pubkey::new_unique(),
+        token_b_mint:        Pubkey::new_unique(),
+        token_a_vault:       Pubkey::new_unique(),
+        token_b_vault:       Pubkey::new_unique(),
+        lp_supply:           0,
+        fee_rate_bps:        30,
+        fee_growth_global_a: 0,
+        fee_growth_global_b: 0,
+        bump:                253,
+        curve:               CurveKind::ConstantProduct,
+        dynamic_fee:         DynamicFee::default(),
+        volume_a:            0,
+        volume_b:            0,
+        fees_collected_a:    0,
+        fees_collected_b:    0,
+        version:             POOL_VERSION,
+        reserve_a:           0,
+        reserve_b:           0,
+        circuit_breaker:     CircuitBreaker::default(),
+        creator:             Pubkey::new_unique(),
+        allowlist_root:      [0; 32],
+        gate_mint:           Pubkey::default(),
+        open_positions:      0,
+        reserves_tracked:    true,
+    };
+    assert!(pool.is_closable());
+    pool.position_opened();
+    let mut owed = position(0);
+    owed.fees_owed_a = 5;
+    assert!(!pool.is_closable());
+
+    let mut data = Vec::new();
+    pool.try_serialize(&mut data).unwrap();
+    let parsed = parse_pool(&data).unwrap();
+    assert_eq!(parsed.open_positions, 1);
+    assert!(!parsed.is_closable());
+
+    // Claiming and closing the position frees the pool.
+    owed.fees_owed_a = 0;
+    pool.position_closed();
+    assert!(pool.is_closable());
+
+    pool.open_positions = Pool::UNCOUNTED_POSITIONS;
+    pool.position_opened();
+    pool.position_closed();
+    assert_eq!(pool.open_positions, Pool::UNCOUNTED_POSITIONS);
+    assert!(!pool.is_closable());
+
+    // The SDK refuses a pool still on a layout from before the count.
+    let mut data = Vec::new();
+    Pool { open_positions: 0, version: 6, ..pool }.try_serialize(&mut data).unwrap();
+    let legacy = parse_pool(&data[..Pool::GATE_LEN]).unwrap();
+    assert_eq!(legacy.open_positions, 0);
+    assert!(!legacy.is_closable());
+}
+
+#[test]
+fn sdk_parses_position_of_every_version() {
+    let mut pos = position(77);
+    pos.fees_owed_b = 5;
+    pos.compound_threshold = 1_000;
+    pos.locked_by = Pubkey::new_unique();
+    assert!(pos.is_locked() && !position(77).is_locked());
+    let mut data = Vec::new();
+    pos.try_serialize(&mut data).unwrap();
+    assert_eq!(data.len(), Position::LEN);
+    assert_eq!(POSITION_LEN, Position::LEN);
+
+    let parsed = parse_position(&data).unwrap();
+    assert_eq!((parsed.lp_shares, parsed.fees_owed_b), (77, 5));
+    assert_eq!(parsed.version, POSITION_VERSION);
+    assert_eq!(parsed.locked_by, Some(pos.locked_by));
+
+    let mut newer = data.clone();
+    newer.extend_from_slice(&[0xff; 16]);
+    assert_eq!(parse_position(&newer).unwrap().compound_threshold, 1_000);
+
+    data.truncate(Position::V1_LEN);
+    let parsed = parse_position(&data).unwrap();
+    assert_eq!((parsed.compound_threshold, parsed.locked_by), (1_000, None));
+
+    data.truncate(Position::LEGACY_LEN);
+    let parsed = parse_position(&data).unwrap();
+    assert_eq!((parsed.lp_shares, parsed.version), (77, 0));
+}
+
+#[test]
+fn sdk_parses_protocol_config() {
+    let config = ProtocolConfig {
+        admin:              Pubkey::new_unique(),
+        treasury_authority: Pubkey::new_unique(),
+        protocol_fee_bps:   35,
+        referral_share_bps: 2_500,
+        bump:               251,
+        paused:             PAUSE_SWAPS,
+        min_pool_fee_bps:   5,
+        max_pool_fee_bps:   300,
+        fee_tiers:          [
+            FeeTier { min_volume: 1_000, discount_bps: 1_000 },
+            FeeTier { min_volume: 50_000, discount_bps: 5_000 },
+            FeeTier { min_volume: 10_000, discount_bps: 2_500 },
+            FeeTier::default(),
+        ],
+        lockers:            [Pubkey::default(), Pubkey::new_unique(), Pubkey::default(), Pubkey::default()],
+    };
+    assert!(config.is_locker(&config.lockers[1]));
+    assert!(!config.is_locker(&Pubkey::default()) && !config.is_locker(&config.admin));
+    let mut data = Vec::new();
+    config.try_serialize(&mut data).unwrap();
+    assert_eq!(data.len(), ProtocolConfig::LEN);
+    assert_eq!(PROTOCOL_CONFIG_LEN, ProtocolConfig::LEN);
+    assert_eq!(PROTOCOL_CONFIG_LEGACY_LEN, ProtocolConfig::LEGACY_LEN);
+
+    let parsed = parse_protocol_config(&data).unwrap();
+    assert_eq!(parsed.admin, config.admin);
+    assert_eq!(parsed.treasury_authority, config.treasury_authority);
+    assert_eq!(parsed.protocol_fee_bps, 35);
+    assert_eq!(parsed.referral_share_bps, 2_500);
+    assert!(parsed.is_paused(PAUSE_SWAPS) && !parsed.is_paused(PAUSE_POOL_CREATION));
+    assert_eq!(parsed.pool_fee_bounds(), config.pool_fee_bounds());
+    assert_eq!(parsed.pool_fee_bounds(), (5, 300));
+    assert_eq!(parsed.fee_tiers[1], SdkFeeTier { min_volume: 50_000, discount_bps: 5_000 });
+    for volume in [0, 999, 1_000, 9_999, 10_000, 50_000, u64::MAX] {
+        let fee = discounted_fee_bps(35, config.fee_discount_bps(volume)) as u16;
+        assert_eq!(parsed.protocol_fee_bps_for(volume), fee, "{volume}");
+    }
+    assert_eq!(parsed.tier_for(999), None);
+    assert_eq!(parsed.tier_for(20_000), Some(2));
+    assert_eq!(parsed.tier_for(u64::MAX), Some(1));
+    assert_eq!(parsed.lockers, config.lockers);
+    assert!(parsed.is_locker(&config.lockers[1]) && !parsed.is_locker(&Pubkey::default()));
+
+    let mut fee_tiers_only = data.clone();
+    fee_tiers_only.truncate(122);
+    assert!(!parse_protocol_config(&fee_tiers_only).unwrap().is_locker(&config.lockers[1]));
+
+    data.truncate(ProtocolConfig::LEGACY_LEN);
+    let parsed = parse_protocol_config(&data).unwrap();
+    assert_eq!(parsed.protocol_fee_bps, 35);
+    assert!(!parsed.is_paused(PAUSE_ALL));
+    assert_eq!(parsed.pool_fee_bounds(), (MIN_POOL_FEE_BPS, MAX_POOL_FEE_BPS));
+    assert_eq!(parsed.tier_for(u64::MAX), None);
+    assert_eq!(parsed.protocol_fee_bps_for(u64::MAX), 35);
+}
+
+#[test]
+fn fee_discounts_round_in_the_agents_favour() {
+    assert_eq!(discounted_fee_bps(20, 0), 20);
+    assert_eq!(discounted_fee_bps(20, 2_500), 15);
+    assert_eq!(discounted_fee_bps(35, 1_000), 31);
+    assert_eq!(discounted_fee_bps(20, 10_000), 0);
+    assert_eq!(discounted_fee_bps(u64::MAX, u16::MAX), 0);
+}
+
+/// Delegate swaps price with the owner's fee tier, and intent, approval and
+/// reveal swaps with the agent's: each takes the optional `agent_volume`
+/// where `swap` does, and the SDK puts it there.
+#[test]
+fn every_swap_path_takes_the_agent_volume() {
+    use anchor_lang::{prelude::AccountInfo, ToAccountMetas};
+
+    let config = ProtocolConfig {
+        admin:              Pubkey::new_unique(),
+        treasury_authority: Pubkey::new_unique(),
+        protocol_fee_bps:   20,
+        referral_share_bps: 0,
+        bump:               251,
+        paused:             0,
+        min_pool_fee_bps:   0,
+        max_pool_fee_bps:   0,
+        fee_tiers:          [FeeTier { min_volume: 1_000, discount_bps: 2_500 }, FeeTier::default(), FeeTier::default(), FeeTier::default()],
+        lockers:            [Pubkey::default(); LOCKER_COUNT],
+    };
+    let mut data = Vec::new();
+    config.try_serialize(&mut data).unwrap();
+    let (treasury_key, mut lamports) = (Pubkey::new_unique(), 0);
+    let treasury = AccountInfo::new(&treasury_key, false, false, &mut lamports, &mut data, &a2a_swap::ID, false, 0);
+    let (program, owner) = (a2a_swap::ID, Pubkey::new_unique());
+    let volume = AgentVolume { agent: owner, volume: 5_000, swaps: 3, bump: 255 };
+    assert_eq!(tiered_protocol_fee_bps(&treasury, None).unwrap(), 20);
+    assert_eq!(tiered_protocol_fee_bps(&treasury, Some(&volume)).unwrap(), 15);
+
+    // The SDK's delegate swap with the owner's volume and pass carries the
+    // accounts the program's `SwapAsDelegate` expects, in either order.
+    let k = Pubkey::new_unique;
+    let pool = k();
+    let swap = sdk_ix::swap_as_delegate_ix(&program, &k(), &owner, &pool, &k(), &k(), &k(), &k(), &k(), &k(), &k(), 9, 8, true, 7);
+    let tracked = sdk_ix::with_trader_pass(sdk_ix::with_agent_volume(swap.clone(), &owner), &pool, &owner);
+    assert_eq!(tracked, sdk_ix::with_agent_volume(sdk_ix::with_trader_pass(swap, &pool, &owner), &owner));
+    let key = |i: usize| tracked.accounts[i].pubkey;
+    let expected = a2a_swap::accounts::SwapAsDelegate {
+        delegate: key(0), owner: key(1), delegate_account: key(2), pool: key(3), pool_authority: key(4),
+        token_a_vault: key(5), token_b_vault: key(6), owner_token_in: key(7), owner_token_out: key(8),
+        treasury: key(9), treasury_token_in: key(10), token_program: key(11),
+        agent_volume: Some(sdk_ix::derive_agent_volume(&owner, &program).0),
+        trader_pass: Some(sdk_ix::derive_trader_pass(&pool, &owner, &program).0),
+        gate_token: None,
+    };
+    let mut expected = expected.to_account_metas(None);
+    expected.pop();  // no gate token
+    assert_eq!(tracked.accounts, expected);
+
+    let agent = k();
+    for swap in [
+        sdk_ix::swap_with_intent_ix(&program, &k(), &agent, &pool, &k(), &k(), &k(), &k(), &k(), &k(), &k(), 9, 8, true, 7, 6, 5),
+        sdk_ix::approve_and_execute_ix(&program, &agent, &k(), &pool, &k(), &k(), &k(), &k(), &k(), &k(), &k(), 9, 8, true, 0, 7, 6),
+        sdk_ix::reveal_swap_ix(&program, &agent, &pool, &k(), &k(), &k(), &k(), &k(), &k(), &k(), 9, 8, true, 7, [1; 32]),
+    ] {
+        let tracked = sdk_ix::with_agent_volume(swap, &agent);
+        let last = tracked.accounts.last().unwrap();
+        assert_eq!((last.pubkey, last.is_writable), (sdk_ix::derive_agent_volume(&agent, &program).0, true));
+    }
+}
+
+#[test]
+fn sdk_parses_agent_volume() {
+    let volume = AgentVolume { agent: Pubkey::new_unique(), volume: 123_456, swaps: 7, bump: 250 };
+    let mut data = Vec::new();
+    volume.try_serialize(&mut data).unwrap();
+    assert_eq!(data.len(), AgentVolume::LEN);
+    assert_eq!(AGENT_VOLUME_LEN, AgentVolume::LEN);
+    let parsed = parse_agent_volume(&data).unwrap();
+    assert_eq!((parsed.agent, parsed.volume, parsed.swaps), (volume.agent, 123_456, 7));
+    assert_eq!(
+        sdk_ix::derive_agent_volume(&volume.agent, &a2a_swap::ID),
+        Pubkey::find_program_address(&[a2a_swap::AGENT_VOLUME_SEED, volume.agent.as_ref()], &a2a_swap::ID),
+    );
+}
+
+#[test]
+fn sdk_parses_governance_accounts() {
+    let governance = Governance {
+        vote_mint:          Pubkey::new_unique(),
+        quorum:             1_000,
+        voting_period_secs: 86_400,
+        proposal_count:     3,
+        bump:               254,
+    };
+    let mut data = Vec::new();
+    governance.try_serialize(&mut data).unwrap();
+    assert_eq!(data.len(), Governance::LEN);
+    let parsed = parse_governance(&data).unwrap();
+    assert_eq!((parsed.vote_mint, parsed.quorum), (governance.vote_mint, 1_000));
+    assert_eq!((parsed.voting_period_secs, parsed.proposal_count), (86_400, 3));
+
+    let proposal = Proposal {
+        id:             2,
+        proposer:       Pubkey::new_unique(),
+        action:         GovernanceAction::SetFeeTier { index: 1, min_volume: 5_000_000, discount_bps: 300 },
+        votes_for:      700,
+        votes_against:  300,
+        voting_ends_at: 1_700_000_000,
+        executed:       false,
+        bump:           253,
+    };
+    let mut data = Vec::new();
+    proposal.try_serialize(&mut data).unwrap();
+    // ConfigureDynamicFee is the largest action; the account pads smaller ones.
+    assert!(data.len() < Proposal::LEN);
+    data.resize(Proposal::LEN, 0);
+    let parsed = parse_proposal(&data).unwrap();
+    assert_eq!(parsed.action, SdkGovernanceAction::SetFeeTier { index: 1, min_volume: 5_000_000, discount_bps: 300 });
+    assert_eq!((parsed.id, parsed.votes_for, parsed.votes_against), (2, 700, 300));
+    assert_eq!(parsed.voting_ends_at, 1_700_000_000);
+    for quorum in [999, 1_000, 1_001] {
+        assert_eq!(parsed.passed(quorum), proposal.passed(quorum));
+    }
+
+    let program = Pubkey::new_unique();
+    let proposal = Proposal { action: GovernanceAction::SetLocker { index: 3, program }, ..proposal };
+    let mut data = Vec::new();
+    proposal.try_serialize(&mut data).unwrap();
+    assert!(data.len() < Proposal::LEN);
+    assert_eq!(parse_proposal(&data).unwrap().action, SdkGovernanceAction::SetLocker { index: 3, program });
+
+    let pool = Pubkey::new_unique();
+    let action = GovernanceAction::ConfigureDynamicFee { pool, min_fee_bps: 5, max_fee_bps: 50 };
+    let proposal = Proposal { action, ..proposal };
+    let mut data = Vec::new();
+    proposal.try_serialize(&mut data).unwrap();
+    assert_eq!(data.len(), Proposal::LEN);
+    let parsed = parse_proposal(&data).unwrap();
+    assert_eq!(parsed.action, SdkGovernanceAction::ConfigureDynamicFee { pool, min_fee_bps: 5, max_fee_bps: 50 });
+    assert_eq!(parsed.action.pool(), Some(pool));
+
+    let record = VoteRecord {
+        proposal: Pubkey::new_unique(),
+        voter:    Pubkey::new_unique(),
+        support:  true,
+        amount:   700,
+        bump:     252,
+    };
+    let mut data = Vec::new();
+    record.try_serialize(&mut data).unwrap();
+    assert_eq!(data.len(), VoteRecord::LEN);
+    let parsed = parse_vote_record(&data).unwrap();
+    assert_eq!((parsed.voter, parsed.support, parsed.amount), (record.voter, true, 700));
+}
+
+#[test]
+fn proposals_pass_on_majority_with_quorum() {
+    let mut proposal = Proposal {
+        id:             0,
+        proposer:       Pubkey::default(),
+        action:         GovernanceAction::SetPaused { paused: PAUSE_ALL },
+        votes_for:      600,
+        votes_against:  400,
+        voting_ends_at: 0,
+        executed:       false,
+        bump:           255,
+    };
+    assert!(proposal.passed(1_000));
+    assert!(!proposal.passed(1_001));
+    proposal.votes_against = 600;
+    assert!(!proposal.passed(0));
+    proposal.votes_for = u64::MAX;
+    assert!(proposal.passed(u64::MAX));
+}
+
+#[test]
+fn governance_actions_are_validated() {
+    let valid = [
+        GovernanceAction::SetProtocolFee { protocol_fee_bps: MAX_PROTOCOL_FEE_BPS, referral_share_bps: 10_000 },
+        GovernanceAction::SetPoolFeeBounds { min_fee_bps: 1, max_fee_bps: POOL_FEE_CAP_BPS },
+        GovernanceAction::SetPoolFeeBounds { min_fee_bps: 30, max_fee_bps: 30 },
+        GovernanceAction::SetPaused { paused: PAUSE_ALL },
+        GovernanceAction::SetPaused { paused: 0 },
+        GovernanceAction::SetFeeTier { index: 0, min_volume: 1_000_000, discount_bps: 10_000 },
+        GovernanceAction::SetFeeTier { index: FEE_TIER_COUNT as u8 - 1, min_volume: 0, discount_bps: 0 },
+        GovernanceAction::SetLocker { index: LOCKER_COUNT as u8 - 1, program: Pubkey::new_unique() },
+        GovernanceAction::SetLocker { index: 0, program: Pubkey::default() },
+        GovernanceAction::ConfigureDynamicFee { pool: Pubkey::new_unique(), min_fee_bps: 1, max_fee_bps: 100 },
+        GovernanceAction::ConfigureDynamicFee { pool: Pubkey::new_unique(), min_fee_bps: 0, max_fee_bps: 0 },
+        GovernanceAction::ConfigureCircuitBreaker { pool: Pubkey::new_unique(), max_move_bps: 10_000 },
+        GovernanceAction::ConfigureCircuitBreaker { pool: Pubkey::new_unique(), max_move_bps: 0 },
+    ];
+    for action in valid {
+        assert!(validate_action(&action).is_ok(), "{action:?}");
+    }
+    let invalid = [
+        GovernanceAction::SetProtocolFee { protocol_fee_bps: MAX_PROTOCOL_FEE_BPS + 1, referral_share_bps: 0 },
+        GovernanceAction::SetProtocolFee { protocol_fee_bps: 0, referral_share_bps: 10_001 },
+        GovernanceAction::SetPoolFeeBounds { min_fee_bps: 0, max_fee_bps: 30 },
+        GovernanceAction::SetPoolFeeBounds { min_fee_bps: 31, max_fee_bps: 30 },
+        GovernanceAction::SetPoolFeeBounds { min_fee_bps: 1, max_fee_bps: POOL_FEE_CAP_BPS + 1 },
+        GovernanceAction::SetPaused { paused: PAUSE_ALL + 1 },
+        GovernanceAction::SetFeeTier { index: FEE_TIER_COUNT as u8, min_volume: 0, discount_bps: 100 },
+        GovernanceAction::SetFeeTier { index: 0, min_volume: 0, discount_bps: 10_001 },
+        GovernanceAction::SetLocker { index: LOCKER_COUNT as u8, program: Pubkey::new_unique() },
+        GovernanceAction::ConfigureDynamicFee { pool: Pubkey::new_unique(), min_fee_bps: 0, max_fee_bps: 50 },
+        GovernanceAction::ConfigureDynamicFee { pool: Pubkey::new_unique(), min_fee_bps: 51, max_fee_bps: 50 },
+        GovernanceAction::ConfigureDynamicFee { pool: Pubkey::new_unique(), min_fee_bps: 1, max_fee_bps: 101 },
+        GovernanceAction::ConfigureCircuitBreaker { pool: Pubkey::new_unique(), max_move_bps: 10_001 },
+    ];
+    for action in invalid {
+        assert!(validate_action(&action).is_err(), "{action:?}");
+    }
+}
+
+/// The checked-in IDL, and the layouts generated from it, still describe
+/// the program: account discriminators, sizes and encodings, and
+/// instruction data, byte for byte.
+#[test]
+fn core_layouts_match_the_program() {
+    assert_eq!(a2a_swap_core::PROGRAM_ID, a2a_swap::ID.to_string());
+    assert_eq!(&a2a_swap_core::Pool::DISCRIMINATOR[..], Pool::DISCRIMINATOR);
+    assert_eq!(&a2a_swap_core::Position::DISCRIMINATOR[..], Position::DISCRIMINATOR);
+    assert_eq!(&a2a_swap_core::ProtocolConfig::DISCRIMINATOR[..], ProtocolConfig::DISCRIMINATOR);
+    assert_eq!(&a2a_swap_core::RangePool::DISCRIMINATOR[..], RangePool::DISCRIMINATOR);
+    assert_eq!(&a2a_swap_core::RangePosition::DISCRIMINATOR[..], RangePosition::DISCRIMINATOR);
+    assert_eq!(a2a_swap_core::Pool::LEN, Pool::LEN);
+    assert_eq!(a2a_swap_core::Position::LEN, Position::LEN);
+    assert_eq!(a2a_swap_core::ProtocolConfig::LEN, ProtocolConfig::LEN);
+    assert_eq!(a2a_swap_core::RangePosition::LEN, RangePosition::LEN);
+    assert_eq!(&a2a_swap_core::SwapCommitment::DISCRIMINATOR[..], SwapCommitment::DISCRIMINATOR);
+    assert_eq!(a2a_swap_core::SwapCommitment::LEN, SwapCommitment::LEN);
+    assert_eq!(&a2a_swap_core::Governance::DISCRIMINATOR[..], Governance::DISCRIMINATOR);
+    assert_eq!(&a2a_swap_core::Proposal::DISCRIMINATOR[..], Proposal::DISCRIMINATOR);
+    assert_eq!(&a2a_swap_core::VoteRecord::DISCRIMINATOR[..], VoteRecord::DISCRIMINATOR);
+    assert_eq!(a2a_swap_core::Governance::LEN, Governance::LEN);
+    assert_eq!(a2a_swap_core::Proposal::LEN, Proposal::LEN);
+    assert_eq!(a2a_swap_core::VoteRecord::LEN, VoteRecord::LEN);
+    assert_eq!(&a2a_swap_core::AgentVolume::DISCRIMINATOR[..], AgentVolume::DISCRIMINATOR);
+    assert_eq!(a2a_swap_core::AgentVolume::LEN, AgentVolume::LEN);
+    assert_eq!(&a2a_swap_core::IntentNonce::DISCRIMINATOR[..], IntentNonce::DISCRIMINATOR);
+    assert_eq!(a2a_swap_core::IntentNonce::LEN, IntentNonce::LEN);
+    assert_eq!(&a2a_swap_core::TraderPass::DISCRIMINATOR[..], TraderPass::DISCRIMINATOR);
+    assert_eq!(a2a_swap_core::TraderPass::LEN, TraderPass::LEN);
+
+    let (range, positions) = range_pool(0, &[(-60, 60, 1_000_000)]);
+    let mut accounts = vec![Vec::new(); 3];
+    range.try_serialize(&mut accounts[0]).unwrap();
+    positions[0].try_serialize(&mut accounts[1]).unwrap();
+    let mut pos = position(77);
+    pos.auto_compound = true;
+    pos.try_serialize(&mut accounts[2]).unwrap();
+    let decoded = a2a_swap_core::RangePool::from_account_data(&accounts[0]).unwrap();
+    assert_eq!(decoded.ticks.len(), range.ticks.len());
+    assert_eq!(decoded.to_account_data(), accounts[0]);
+    let decoded = a2a_swap_core::RangePosition::from_account_data(&accounts[1]).unwrap();
+    assert_eq!(decoded.to_account_data(), accounts[1]);
+    let decoded = a2a_swap_core::Position::from_account_data(&accounts[2]).unwrap();
+    assert!(decoded.auto_compound);
+    assert_eq!(decoded.to_account_data(), accounts[2]);
+    assert!(a2a_swap_core::Pool::from_account_data(&accounts[2]).is_err());
+
+    let curve = CurveKind::StableSwap { amp: 100 };
+    assert_eq!(
+        ix::InitializePool { fee_rate_bps: 4, curve: a2a_swap_core::CurveKind::StableSwap { amp: 100 } }
+            .data(),
+        a2a_swap::instruction::InitializePool { fee_rate_bps: 4, curve }.data(),
+    );
+    assert_eq!(
+        ix::Swap { amount_in: 5, min_amount_out: 4, a_to_b: true, max_price_impact_bps: 300, deadline_unix: 9 }.data(),
+        a2a_swap::instruction::Swap {
+            amount_in: 5, min_amount_out: 4, a_to_b: true, max_price_impact_bps: 300, deadline_unix: 9,
+        }
+        .data(),
+    );
+    assert_eq!(
+        ix::ProvideRangeLiquidity {
+            tick_lower: -60, tick_upper: 60, amount_a_max: 1, amount_b_max: 2, min_liquidity: 3,
+        }
+        .data(),
+        a2a_swap::instruction::ProvideRangeLiquidity {
+            tick_lower: -60, tick_upper: 60, amount_a_max: 1, amount_b_max: 2, min_liquidity: 3,
+        }
+        .data(),
+    );
+    let admin = Pubkey::new_unique();
+    assert_eq!(
+        ix::UpdateProtocolConfig {
+            admin: admin.to_bytes(),
+            treasury_authority: [7; 32],
+            protocol_fee_bps: 20,
+            referral_share_bps: 1_000,
+        }
+        .data(),
+        a2a_swap::instruction::UpdateProtocolConfig {
+            admin,
+            treasury_authority: Pubkey::new_from_array([7; 32]),
+            protocol_fee_bps: 20,
+            referral_share_bps: 1_000,
+        }
+        .data(),
+    );
+    assert_eq!(
+        ix::RevealSwap { amount_in: 5, min_amount_out: 4, a_to_b: false, max_price_impact_bps: 0, salt: [9; 32] }
+            .data(),
+        a2a_swap::instruction::RevealSwap {
+            amount_in: 5, min_amount_out: 4, a_to_b: false, max_price_impact_bps: 0, salt: [9; 32],
+        }
+        .data(),
+    );
+    assert_eq!(ix::ClaimFees.data(), a2a_swap::instruction::ClaimFees {}.data());
+    assert_eq!(ix::MigratePosition.data(), a2a_swap::instruction::MigratePosition {}.data());
+    assert_eq!(ix::LockPosition.data(), a2a_swap::instruction::LockPosition {}.data());
+    let program = Pubkey::new_unique();
+    assert_eq!(
+        ix::SetLocker { index: 2, program: program.to_bytes() }.data(),
+        a2a_swap::instruction::SetLocker { index: 2, program }.data(),
+    );
+}
+
+/// A commitment the SDK computes opens on-chain, and changing any revealed
+/// parameter or the salt breaks it.
+#[test]
+fn sdk_commitment_hash_matches_the_program() {
+    let salt = [42; 32];
+    let hash = commitment_hash(1_000_000, 990_000, true, 150, &salt);
+    assert_eq!(sdk_ix::swap_commitment_hash(1_000_000, 990_000, true, 150, &salt), hash);
+    for other in [
+        commitment_hash(1_000_001, 990_000, true, 150, &salt),
+        commitment_hash(1_000_000, 989_999, true, 150, &salt),
+        commitment_hash(1_000_000, 990_000, false, 150, &salt),
+        commitment_hash(1_000_000, 990_000, true, 0, &salt),
+        commitment_hash(1_000_000, 990_000, true, 150, &[43; 32]),
+    ] {
+        assert_ne!(other, hash);
+    }
+
+    let (pool, agent) = (Pubkey::new_unique(), Pubkey::new_unique());
+    assert_eq!(
+        sdk_ix::derive_swap_commitment(&pool, &agent, &a2a_swap::ID),
+        Pubkey::find_program_address(
+            &[a2a_swap::SWAP_COMMITMENT_SEED, pool.as_ref(), agent.as_ref()],
+            &a2a_swap::ID,
+        ),
+    );
+}
+
+/// An intent the SDK signs is the message the program rebuilds, its ed25519
+/// instruction passes the program's check, and the check fails for any other
+/// signer or message.
+#[test]
+fn sdk_swap_intents_match_the_program() {
+    let (agent, pool) = (Pubkey::new_unique(), Pubkey::new_unique());
+    let message = intent_message(&a2a_swap::ID, &agent, &pool, 1_000, 990, true, 150, 7, 1_700_000_000);
+    assert_eq!(
+        sdk_ix::swap_intent_message(&a2a_swap::ID, &agent, &pool, 1_000, 990, true, 150, 7, 1_700_000_000),
+        message,
+    );
+    for other in [
+        intent_message(&Pubkey::new_unique(), &agent, &pool, 1_000, 990, true, 150, 7, 1_700_000_000),
+        intent_message(&a2a_swap::ID, &agent, &pool, 1_000, 990, true, 150, 8, 1_700_000_000),
+        intent_message(&a2a_swap::ID, &agent, &pool, 1_000, 990, true, 150, 7, 1_700_000_001),
+    ] {
+        assert_ne!(other, message);
+    }
+
+    // The signature itself is the ed25519 program's to check.
+    let verify = sdk_ix::ed25519_verify_ix(&agent, &Default::default(), &message);
+    assert!(verify_ed25519_ix(&verify, &agent, &message).is_ok());
+    assert!(verify_ed25519_ix(&verify, &Pubkey::new_unique(), &message).is_err());
+    assert!(verify_ed25519_ix(&verify, &agent, &message[1..]).is_err());
+    let mut elsewhere = verify.clone();
+    elsewhere.data[14] = 0;  // message read from instruction 0
+    assert!(verify_ed25519_ix(&elsewhere, &agent, &message).is_err());
+    let mut not_ed25519 = verify;
+    not_ed25519.program_id = a2a_swap::ID;
+    assert!(verify_ed25519_ix(&not_ed25519, &agent, &message).is_err());
+
+    assert_eq!(
+        sdk_ix::derive_intent_nonce(&agent, &a2a_swap::ID),
+        Pubkey::find_program_address(&[a2a_swap::INTENT_NONCE_SEED, agent.as_ref()], &a2a_swap::ID),
+    );
+}
+
+/// Proofs from the SDK's allowlist trees pass the program's check for the
+/// trader they were built for and no other, and a swap needs a pass for the
+/// pool's current root.
+#[test]
+fn sdk_allowlists_match_the_program() {
+    for n in [1, 2, 3, 7, 8, 33] {
+        let traders: Vec<Pubkey> = (0..n).map(|_| Pubkey::new_unique()).collect();
+        let tree = AllowlistTree::new(&traders).unwrap();
+        let root = tree.root();
+        assert_eq!(sdk_allowlist::leaf(&traders[0]), allowlist_leaf(&traders[0]));
+        for trader in &traders {
+            let proof = tree.proof(trader).unwrap();
+            assert!(verify_allowlist_proof(&root, trader, &proof), "{n} traders");
+            assert!(!verify_allowlist_proof(&root, &Pubkey::new_unique(), &proof));
+            if let Some((_, rest)) = proof.split_first() {
+                assert!(!verify_allowlist_proof(&root, trader, rest));
+            }
+        }
+        assert!(tree.proof(&Pubkey::new_unique()).is_none());
+        let mut shuffled = traders.clone();
+        shuffled.reverse();
+        shuffled.push(traders[0]);
+        assert_eq!(AllowlistTree::new(&shuffled).unwrap().root(), root);
+    }
+    assert!(AllowlistTree::new(&[]).is_err());
+
+    let trader = Pubkey::new_unique();
+    let mut pool = Pool {
+        authority:           Pubkey::new_unique(),
+        authority_bump:      254,
+        token_a_mint:        Pubkey::new_unique(),
+        token_b_mint:        Pubkey::new_unique(),
+        token_a_vault:       Pubkey::new_unique(),
+        token_b_vault:       Pubkey::new_unique(),
+        lp_supply:           0,
+        fee_rate_bps:        30,
+        fee_growth_global_a: 0,
+        fee_growth_global_b: 0,
+        bump:                253,
+        curve:               CurveKind::ConstantProduct,
+        dynamic_fee:         DynamicFee::default(),
+        volume_a:            0,
+        volume_b:            0,
+        fees_collected_a:    0,
+        fees_collected_b:    0,
+        version:             POOL_VERSION,
+        reserve_a:           0,
+        reserve_b:           0,
+        circuit_breaker:     CircuitBreaker::default(),
+        creator:             Pubkey::new_unique(),
+        allowlist_root:      [0; 32],
+        gate_mint:           Pubkey::default(),
+        open_positions:      0,
+        reserves_tracked:    true,
+    };
+    assert!(require_allowed_trader(&pool, None).is_ok());
+    pool.allowlist_root = AllowlistTree::new(&[trader]).unwrap().root();
+    let pass = TraderPass { pool: Pubkey::new_unique(), trader, root: pool.allowlist_root, bump: 255 };
+    assert!(require_allowed_trader(&pool, None).is_err());
+    assert!(require_allowed_trader(&pool, Some(&pass)).is_ok());
+    pool.allowlist_root = [9; 32];
+    assert!(require_allowed_trader(&pool, Some(&pass)).is_err());
+}
+
+/// Range pools can't be permissioned or gated: `set_pool_allowlist` and
+/// `set_pool_gate` take a `Pool` and a range pool's account fails that
+/// check, so `swap_range` has no allowlist or gate to enforce.
+#[test]
+fn range_pools_reject_allowlists_and_gates() {
+    let (pool, _) = range_pool(0, &[]);
+    let mut data = Vec::new();
+    pool.try_serialize(&mut data).unwrap();
+    assert_eq!(
+        Pool::try_deserialize(&mut &data[..]).err(),
+        Some(anchor_lang::error::ErrorCode::AccountDiscriminatorMismatch.into()),
+    );
+}
+
+/// The SDK's builders encode through the IDL and pass accounts in the order,
+/// and with the flags, the IDL lists.
+#[test]
+fn sdk_instructions_match_the_idl() {
+    fn check<I: a2a_swap_core::Instruction + PartialEq + std::fmt::Debug>(ix: &Instruction, args: I) {
+        assert_eq!(I::from_data(&ix.data), Ok(args), "{}", I::NAME);
+        let required = I::ACCOUNTS.iter().filter(|a| !a.optional).count();
+        assert!((required..=I::ACCOUNTS.len()).contains(&ix.accounts.len()), "{}", I::NAME);
+        for (meta, spec) in ix.accounts.iter().zip(I::ACCOUNTS) {
+            if spec.optional && meta.pubkey == ix.program_id {
+                continue;  // omitted optional account
+            }
+            assert_eq!((meta.is_writable, meta.is_signer), (spec.writable, spec.signer), "{}.{}", I::NAME, spec.name);
+        }
+    }
+
+    let k = || Pubkey::new_unique();
+    let program = a2a_swap::ID;
+    check(
+        &sdk_ix::initialize_pool_ix(&program, &k(), &k(), &k(), &k(), &k(), 30, SdkCurveKind::StableSwap { amp: 50 }),
+        ix::InitializePool { fee_rate_bps: 30, curve: a2a_swap_core::CurveKind::StableSwap { amp: 50 } },
+    );
+    check(
+        &sdk_ix::provide_liquidity_ix(&program, &k(), &k(), &k(), &k(), &k(), &k(), &k(), &k(), 1, 2, 3, true, 4),
+        ix::ProvideLiquidity { amount_a: 1, amount_b: 2, min_lp: 3, auto_compound: true, compound_threshold: 4 },
+    );
+    check(
+        &sdk_ix::provide_liquidity_proportional_ix(&program, &k(), &k(), &k(), &k(), &k(), &k(), &k(), &k(), 1, false, 2, 3, true, 4),
+        ix::ProvideLiquidityProportional {
+            amount: 1, fixed_a: false, max_other: 2, min_lp: 3, auto_compound: true, compound_threshold: 4,
+        },
+    );
+    for referrer in [None, Some(&k())] {
+        check(
+            &sdk_ix::swap_ix(&program, &k(), &k(), &k(), &k(), &k(), &k(), &k(), &k(), &k(), referrer, 9, 8, false, 7, 6),
+            ix::Swap { amount_in: 9, min_amount_out: 8, a_to_b: false, max_price_impact_bps: 7, deadline_unix: 6 },
+        );
+    }
+    check(
+        &sdk_ix::swap_exact_out_ix(&program, &k(), &k(), &k(), &k(), &k(), &k(), &k(), &k(), &k(), None, 9, 8, true, 7),
+        ix::SwapExactOut { amount_out: 9, max_amount_in: 8, a_to_b: true, max_price_impact_bps: 7 },
+    );
+    check(
+        &sdk_ix::approve_and_execute_ix(&program, &k(), &k(), &k(), &k(), &k(), &k(), &k(), &k(), &k(), &k(), 9, 8, true, 7, 6, 5),
+        ix::ApproveAndExecute {
+            amount_in: 9, min_amount_out: 8, a_to_b: true, expires_at_slot: 7, deadline_unix: 6, max_price_impact_bps: 5,
+        },
+    );
+    check(&sdk_ix::commit_swap_ix(&program, &k(), &k(), [4; 32]), ix::CommitSwap { hash: [4; 32] });
+    check(
+        &sdk_ix::reveal_swap_ix(&program, &k(), &k(), &k(), &k(), &k(), &k(), &k(), &k(), &k(), 9, 8, true, 7, [5; 32]),
+        ix::RevealSwap { amount_in: 9, min_amount_out: 8, a_to_b: true, max_price_impact_bps: 7, salt: [5; 32] },
+    );
+    check(&sdk_ix::cancel_swap_commitment_ix(&program, &k(), &k()), ix::CancelSwapCommitment);
+    check(&sdk_ix::migrate_pool_ix(&program, &k(), &k()), ix::MigratePool);
+    check(&sdk_ix::migrate_position_ix(&program, &k(), &k()), ix::MigratePosition);
+    check(&sdk_ix::sync_ix(&program, &k(), &k(), &k()), ix::Sync);
+    check(
+        &sdk_ix::initialize_protocol_config_ix(&program, &k(), 20, 1_000, &Pubkey::new_from_array([3; 32])),
+        ix::InitializeProtocolConfig { protocol_fee_bps: 20, referral_share_bps: 1_000, treasury_authority: [3; 32] },
+    );
+    check(
+        &sdk_ix::update_protocol_config_ix(&program, &k(), &Pubkey::new_from_array([1; 32]), &Pubkey::new_from_array([2; 32]), 20, 0),
+        ix::UpdateProtocolConfig { admin: [1; 32], treasury_authority: [2; 32], protocol_fee_bps: 20, referral_share_bps: 0 },
+    );
+    check(&sdk_ix::collect_protocol_fees_ix(&program, &k(), &k(), &k(), 0), ix::CollectProtocolFees { amount: 0 });
+    check(&sdk_ix::configure_dynamic_fee_ix(&program, &k(), &k(), 5, 80), ix::ConfigureDynamicFee { min_fee_bps: 5, max_fee_bps: 80 });
+    check(&sdk_ix::configure_circuit_breaker_ix(&program, &k(), &k(), 250), ix::ConfigureCircuitBreaker { max_move_bps: 250 });
+    check(
+        &sdk_ix::initialize_range_pool_ix(&program, &k(), &k(), &k(), &k(), &k(), 30, 10, -20),
+        ix::InitializeRangePool { fee_rate_bps: 30, tick_spacing: 10, initial_tick: -20 },
+    );
+    check(
+        &sdk_ix::provide_range_liquidity_ix(&program, &k(), &k(), &k(), &k(), &k(), &k(), -10, 10, 1, 2, 3),
+        ix::ProvideRangeLiquidity { tick_lower: -10, tick_upper: 10, amount_a_max: 1, amount_b_max: 2, min_liquidity: 3 },
+    );
+    check(
+        &sdk_ix::remove_range_liquidity_ix(&program, &k(), &k(), &k(), &k(), &k(), &k(), &k(), 6, 5, 4),
+        ix::RemoveRangeLiquidity { liquidity: 6, min_a: 5, min_b: 4 },
+    );
+    check(
+        &sdk_ix::swap_range_ix(&program, &k(), &k(), &k(), &k(), &k(), &k(), &k(), 9, 8, true, 0),
+        ix::SwapRange { amount_in: 9, min_amount_out: 8, a_to_b: true, max_price_impact_bps: 0 },
+    );
+    check(&sdk_ix::migrate_protocol_config_ix(&program, &k()), ix::MigrateProtocolConfig);
+    check(
+        &sdk_ix::initialize_governance_ix(&program, &k(), &k(), 1_000, 86_400),
+        ix::InitializeGovernance { quorum: 1_000, voting_period_secs: 86_400 },
+    );
+    check(
+        &sdk_ix::create_proposal_ix(&program, &k(), 4, SdkGovernanceAction::SetPaused { paused: PAUSE_SWAPS }),
+        ix::CreateProposal { action: a2a_swap_core::GovernanceAction::SetPaused { paused: PAUSE_SWAPS } },
+    );
+    check(&sdk_ix::cast_vote_ix(&program, &k(), &k(), 4, false, 9), ix::CastVote { support: false, amount: 9 });
+    check(&sdk_ix::execute_proposal_ix(&program, 4, None), ix::ExecuteProposal);
+    check(&sdk_ix::withdraw_vote_ix(&program, &k(), &k(), 4), ix::WithdrawVote);
+    check(
+        &sdk_ix::set_fee_tier_ix(&program, &k(), 2, 1_000_000, 2_500),
+        ix::SetFeeTier { index: 2, min_volume: 1_000_000, discount_bps: 2_500 },
+    );
+    check(&sdk_ix::open_agent_volume_ix(&program, &k()), ix::OpenAgentVolume);
+    check(
+        &sdk_ix::swap_with_intent_ix(&program, &k(), &k(), &k(), &k(), &k(), &k(), &k(), &k(), &k(), &k(), 9, 8, true, 7, 6, 5),
+        ix::SwapWithIntent { amount_in: 9, min_amount_out: 8, a_to_b: true, max_price_impact_bps: 7, nonce: 6, expires_at: 5 },
+    );
+    for referrer in [None, Some(&k())] {
+        let agent = k();
+        let swap = sdk_ix::swap_ix(&program, &agent, &k(), &k(), &k(), &k(), &k(), &k(), &k(), &k(), referrer, 9, 8, true, 7, 6);
+        let tracked = sdk_ix::with_agent_volume(swap, &agent);
+        // Every optional account but the trader pass and gate token.
+        assert_eq!(tracked.accounts.len(), ix::Swap::ACCOUNTS.len() - 2);
+        assert_eq!(tracked.accounts.last().unwrap().pubkey, sdk_ix::derive_agent_volume(&agent, &program).0);
+        check(&tracked, ix::Swap { amount_in: 9, min_amount_out: 8, a_to_b: true, max_price_impact_bps: 7, deadline_unix: 6 });
+
+        let pool = k();
+        let passed = sdk_ix::with_trader_pass(tracked, &pool, &agent);
+        assert_eq!(passed.accounts.len(), ix::Swap::ACCOUNTS.len() - 1);
+        assert_eq!(passed.accounts.last().unwrap().pubkey, sdk_ix::derive_trader_pass(&pool, &agent, &program).0);
+        check(&passed, ix::Swap { amount_in: 9, min_amount_out: 8, a_to_b: true, max_price_impact_bps: 7, deadline_unix: 6 });
+
+        let gate_token = k();
+        let gated = sdk_ix::with_gate_token(passed, &gate_token);
+        assert_eq!(gated.accounts.len(), ix::Swap::ACCOUNTS.len());
+        assert_eq!(gated.accounts.last().unwrap().pubkey, gate_token);
+        check(&gated, ix::Swap { amount_in: 9, min_amount_out: 8, a_to_b: true, max_price_impact_bps: 7, deadline_unix: 6 });
+    }
+    let (agent, pool) = (k(), k());
+    let swap = sdk_ix::swap_exact_out_ix(&program, &agent, &pool, &k(), &k(), &k(), &k(), &k(), &k(), &k(), None, 9, 8, true, 7);
+    let passed = sdk_ix::with_trader_pass(swap, &pool, &agent);
+    assert_eq!(passed.accounts.len(), ix::SwapExactOut::ACCOUNTS.len() - 1);
+    check(&passed, ix::SwapExactOut { amount_out: 9, max_amount_in: 8, a_to_b: true, max_price_impact_bps: 7 });
+    let swap = sdk_ix::swap_with_intent_ix(&program, &k(), &agent, &pool, &k(), &k(), &k(), &k(), &k(), &k(), &k(), 9, 8, true, 7, 6, 5);
+    let passed = sdk_ix::with_trader_pass(swap, &pool, &agent);
+    assert_eq!(passed.accounts.len(), ix::SwapWithIntent::ACCOUNTS.len() - 1);
+    check(&passed, ix::SwapWithIntent { amount_in: 9, min_amount_out: 8, a_to_b: true, max_price_impact_bps: 7, nonce: 6, expires_at: 5 });
+    // Without a pass, the gate token still lands in its own slot.
+    let swap = sdk_ix::swap_as_delegate_ix(&program, &k(), &agent, &pool, &k(), &k(), &k(), &k(), &k(), &k(), &k(), 9, 8, true, 7);
+    let gated = sdk_ix::with_gate_token(swap, &k());
+    assert_eq!(gated.accounts.len(), ix::SwapAsDelegate::ACCOUNTS.len());
+    assert_eq!(gated.accounts[gated.accounts.len() - 2].pubkey, program);
+    check(&gated, ix::SwapAsDelegate { amount_in: 9, min_amount_out: 8, a_to_b: true, max_price_impact_bps: 7 });
+    let gate_mint = k();
+    check(&sdk_ix::set_pool_gate_ix(&program, &k(), &k(), &gate_mint), ix::SetPoolGate { gate_mint: gate_mint.to_bytes() });
+    check(&sdk_ix::set_pool_allowlist_ix(&program, &k(), &k(), [6; 32]), ix::SetPoolAllowlist { merkle_root: [6; 32] });
+    check(
+        &sdk_ix::register_trader_ix(&program, &k(), &k(), vec![[1; 32], [2; 32]]),
+        ix::RegisterTrader { proof: vec![[1; 32], [2; 32]] },
+    );
+}
+
+/// The CPI crate's account structs produce the metas the program's own
+/// Anchor client structs do, optional accounts included.
+#[test]
+fn cpi_accounts_match_the_program() {
+    use a2a_swap_cpi::accounts as cpi;
+    use anchor_lang::{prelude::AccountInfo, ToAccountMetas};
+
+    assert_eq!(a2a_swap_cpi::ID, a2a_swap::ID);
+    let keys: Vec<Pubkey> = (0..14).map(|_| Pubkey::new_unique()).collect();
+    let mut lamports = [0u64; 14];
+    let mut data = vec![Vec::<u8>::new(); 14];
+    let infos: Vec<AccountInfo> = keys
+        .iter()
+        .zip(lamports.iter_mut())
+        .zip(data.iter_mut())
+        .map(|((key, lamports), data)| AccountInfo::new(key, false, false, lamports, data, &a2a_swap::ID, false, 0))
+        .collect();
+    let info = |i: usize| infos[i].clone();
+
+    for flags in 0..16u8 {
+        let (referrer, volume, pass, gate) = (flags & 1 != 0, flags & 2 != 0, flags & 4 != 0, flags & 8 != 0);
+        let swap = cpi::Swap {
+            agent: info(0), pool: info(1), pool_authority: info(2), token_a_vault: info(3), token_b_vault: info(4),
+            agent_token_in: info(5), agent_token_out: info(6), treasury: info(7), treasury_token_in: info(8),
+            token_program: info(9),
+            referrer_token: referrer.then(|| info(10)),
+            agent_volume: volume.then(|| info(11)),
+            trader_pass: pass.then(|| info(12)),
+            gate_token: gate.then(|| info(13)),
+        };
+        let program = a2a_swap::accounts::Swap {
+            agent: keys[0], pool: keys[1], pool_authority: keys[2], token_a_vault: keys[3], token_b_vault: keys[4],
+            agent_token_in: keys[5], agent_token_out: keys[6], treasury: keys[7], treasury_token_in: keys[8],
+            token_program: keys[9],
+            referrer_token: referrer.then_some(keys[10]),
+            agent_volume: volume.then_some(keys[11]),
+            trader_pass: pass.then_some(keys[12]),
+            gate_token: gate.then_some(keys[13]),
+        };
+        let mut expected = program.to_account_metas(None);
+        // Anchor's client passes every missing optional; the CPI crate drops trailing ones.
+        while expected.last().is_some_and(|m| m.pubkey == a2a_swap::ID) {
+            expected.pop();
+        }
+        assert_eq!(swap.to_account_metas(None), expected, "referrer {referrer}, volume {volume}, pass {pass}, gate {gate}");
+    }
+
+    let provide = cpi::ProvideLiquidity {
+        agent: info(0), pool: info(1), pool_authority: info(2), position: info(3), token_a_vault: info(4),
+        token_b_vault: info(5), agent_token_a: info(6), agent_token_b: info(7), token_program: info(8),
+        system_program: info(9), rent: info(10),
+    };
+    let program = a2a_swap::accounts::ProvideLiquidity {
+        agent: keys[0], pool: keys[1], pool_authority: keys[2], position: keys[3], token_a_vault: keys[4],
+        token_b_vault: keys[5], agent_token_a: keys[6], agent_token_b: keys[7], token_program: keys[8],
+        system_program: keys[9], rent: keys[10],
+    };
+    assert_eq!(provide.to_account_metas(None), program.to_account_metas(None));
+
+    let remove = cpi::RemoveLiquidity {
+        agent: info(0), pool: info(1), pool_authority: info(2), position: info(3), token_a_vault: info(4),
+        token_b_vault: info(5), agent_token_a: info(6), agent_token_b: info(7), token_program: info(8),
+    };
+    let program = a2a_swap::accounts::RemoveLiquidity {
+        agent: keys[0], pool: keys[1], pool_authority: keys[2], position: keys[3], token_a_vault: keys[4],
+        token_b_vault: keys[5], agent_token_a: keys[6], agent_token_b: keys[7], token_program: keys[8],
+    };
+    assert_eq!(remove.to_account_metas(None), program.to_account_metas(None));
+
+    let (pool, agent) = (keys[0], keys[1]);
+    assert_eq!(a2a_swap_cpi::pda::position(&pool, &agent), sdk_ix::derive_position(&pool, &agent, &a2a_swap::ID));
+    assert_eq!(a2a_swap_cpi::pda::agent_volume(&agent), sdk_ix::derive_agent_volume(&agent, &a2a_swap::ID));
+    assert_eq!(
+        a2a_swap_cpi::pda::trader_pass(&pool, &agent),
+        sdk_ix::derive_trader_pass(&pool, &agent, &a2a_swap::ID),
+    );
+}
+
+proptest! {
+    /// The referrer's cut never exceeds the protocol fee, grows with the
+    /// share, and the SDK computes the same split.
+    #[test]
+    fn referral_fee_splits_protocol_fee(
+        protocol_fee in any::<u64>(),
+        share_bps    in 0u64..=20_000,
+    ) {
+        let cut = referral_fee(protocol_fee, share_bps);
+        prop_assert!(cut <= protocol_fee);
+        prop_assert_eq!(cut, sdk_referral_fee(protocol_fee, share_bps));
+        if share_bps >= 10_000 {
+            prop_assert_eq!(cut, protocol_fee);
+        } else {
+            prop_assert!(cut <= referral_fee(protocol_fee, share_bps + 1));
+        }
+    }
+}
+
+// ─── Range pools ──────────────────────────────────────────────────────────────
+
+/// Pool at `tick` with one position per `(lower, upper, liquidity)`.
+fn range_pool(tick: i32, ranges: &[(i32, i32, u128)]) -> (RangePool, Vec<RangePosition>) {
+    let mut pool = RangePool {
+        authority:           Pubkey::default(),
+        authority_bump:      0,
+        token_a_mint:        Pubkey::default(),
+        token_b_mint:        Pubkey::default(),
+        token_a_vault:       Pubkey::default(),
+        token_b_vault:       Pubkey::default(),
+        fee_rate_bps:        30,
+        tick_spacing:        1,
+        sqrt_price_x64:      sqrt_price_at_tick(tick).unwrap(),
+        tick_current:        tick,
+        liquidity:           0,
+        fee_growth_global_a: 0,
+        fee_growth_global_b: 0,
+        bump:                0,
+        ticks:               Vec::new(),
+    };
+    let positions = ranges
+        .iter()
+        .map(|&(lower, upper, liquidity)| {
+            let mut pos = range_position(lower, upper);
+            update_position(&mut pool, &mut pos, liquidity as i128).unwrap();
+            pos
+        })
+        .collect();
+    (pool, positions)
+}
+
+fn range_position(tick_lower: i32, tick_upper: i32) -> RangePosition {
+    RangePosition {
+        owner:                    Pubkey::default(),
+        pool:                     Pubkey::default(),
+        tick_lower,
+        tick_upper,
+        liquidity:                0,
+        fee_growth_inside_last_a: 0,
+        fee_growth_inside_last_b: 0,
+        fees_owed_a:              0,
+        fees_owed_b:              0,
+        bump:                     0,
+    }
+}
+
+fn sdk_range_pool(pool: &RangePool) -> RangePoolState {
+    RangePoolState {
+        token_a_mint:        pool.token_a_mint,
+        token_b_mint:        pool.token_b_mint,
+        token_a_vault:       pool.token_a_vault,
+        token_b_vault:       pool.token_b_vault,
+        fee_rate_bps:        pool.fee_rate_bps,
+        tick_spacing:        pool.tick_spacing,
+        sqrt_price_x64:      pool.sqrt_price_x64,
+        tick_current:        pool.tick_current,
+        liquidity:           pool.liquidity,
+        fee_growth_global_a: pool.fee_growth_global_a,
+        fee_growth_global_b: pool.fee_growth_global_b,
+        ticks: pool
+            .ticks
+            .iter()
+            .map(|t| RangeTick {
+                index:                t.index,
+                liquidity_net:        t.liquidity_net,
+                liquidity_gross:      t.liquidity_gross,
+                fee_growth_outside_a: t.fee_growth_outside_a,
+                fee_growth_outside_b: t.fee_growth_outside_b,
+            })
+            .collect(),
+    }
+}
+
+/// Up to three ranges around tick 0, each at least one tick wide.
+fn ranges() -> impl Strategy<Value = Vec<(i32, i32, u128)>> {
+    prop::collection::vec(
+        (-5_000i32..5_000, 1i32..5_000, 1_000_000u128..=1u128 << 64)
+            .prop_map(|(lower, width, liquidity)| (lower, lower + width, liquidity)),
+        1..=3,
+    )
+}
+
+proptest! {
+    /// Every tick maps to a strictly increasing sqrt price that maps back to it.
+    #[test]
+    fn tick_math_round_trips(tick in MIN_TICK..MAX_TICK) {
+        let p = sqrt_price_at_tick(tick).unwrap();
+        let next = sqrt_price_at_tick(tick + 1).unwrap();
+        prop_assert!(next > p);
+        prop_assert_eq!(tick_at_sqrt_price(p).unwrap(), tick);
+        prop_assert_eq!(tick_at_sqrt_price(next - 1).unwrap(), tick);
+        prop_assert_eq!(sdk_range::sqrt_price_at_tick(tick).unwrap(), p);
+    }
+
+    /// The 256-bit mul_div agrees with plain u128 arithmetic where that fits.
+    #[test]
+    fn mul_div_matches_u128(a in any::<u64>(), b in any::<u64>(), d in 1u128..) {
+        let exact = a as u128 * b as u128;
+        prop_assert_eq!(mul_div(a as u128, b as u128, d).unwrap(), exact / d);
+        prop_assert_eq!(
+            mul_div_ceil(a as u128, b as u128, d).unwrap(),
+            exact / d + (exact % d != 0) as u128
+        );
+    }
+
+    /// The SDK's range simulation reproduces the program's swap exactly,
+    /// including the pool state it leaves behind.
+    #[test]
+    fn range_swap_matches_sdk_exactly(
+        ranges    in ranges(),
+        amount_in in 1u64..=1u64 << 40,
+        a_to_b    in any::<bool>(),
+    ) {
+        let (mut pool, _) = range_pool(0, &ranges);
+        let mut sdk = sdk_range_pool(&pool);
+        let onchain = compute_range_swap(&mut pool, amount_in, PROTOCOL_FEE_BPS, a_to_b, 0);
+        let offchain = sdk_range::apply_range_swap(&mut sdk, amount_in, PROTOCOL_FEE_BPS, a_to_b);
+        match (onchain, offchain) {
+            (Ok(sa), Ok((out, impact_bps))) => {
+                prop_assert_eq!(sa.amount_out, out);
+                prop_assert_eq!(sa.price_impact_bps, impact_bps);
+                let after = sdk_range_pool(&pool);
+                prop_assert_eq!(after.sqrt_price_x64, sdk.sqrt_price_x64);
+                prop_assert_eq!(after.tick_current, sdk.tick_current);
+                prop_assert_eq!(after.liquidity, sdk.liquidity);
+                prop_assert_eq!(after.fee_growth_global_a, sdk.fee_growth_global_a);
+                prop_assert_eq!(after.fee_growth_global_b, sdk.fee_growth_global_b);
+                prop_assert_eq!(after.ticks, sdk.ticks);
+            }
+            // The program rejects zero-output swaps; the SDK returns 0.
+            (Err(_), Ok((out, _))) => prop_assert_eq!(out, 0),
+            (Ok(_), Err(e)) => prop_assert!(false, "SDK failed where program succeeded: {e}"),
+            (Err(_), Err(_)) => {}
+        }
+    }
+
+    /// Deposits, a swap each way, then a full withdrawal of every position
+    /// (principal plus fees) never pays out more than the vaults hold.
+    #[test]
+    fn range_pool_stays_solvent(
+        ranges in ranges(),
+        swaps  in prop::collection::vec((1u64..=1u64 << 36, any::<bool>()), 1..=4),
+    ) {
+        let (mut pool, mut positions) = range_pool(0, &[]);
+        let (mut vault_a, mut vault_b) = (0u128, 0u128);
+        for &(lower, upper, liquidity) in &ranges {
+            let (a, b) = amounts_for_liquidity(
+                pool.sqrt_price_x64,
+                sqrt_price_at_tick(lower).unwrap(),
+                sqrt_price_at_tick(upper).unwrap(),
+                liquidity,
+                true,
+            ).unwrap();
+            let mut pos = range_position(lower, upper);
+            update_position(&mut pool, &mut pos, liquidity as i128).unwrap();
+            vault_a += a as u128;
+            vault_b += b as u128;
+            positions.push(pos);
+        }
+        for (amount_in, a_to_b) in swaps {
+            // A failed swap aborts its instruction, so the pool is untouched.
+            let before = pool.clone();
+            let Ok(sa) = compute_range_swap(&mut pool, amount_in, PROTOCOL_FEE_BPS, a_to_b, 0) else {
+                pool = before;
+                continue;
+            };
+            if a_to_b {
+                vault_a += sa.net_pool_input as u128;
+                vault_b -= sa.amount_out as u128;
+            } else {
+                vault_b += sa.net_pool_input as u128;
+                vault_a -= sa.amount_out as u128;
+            }
+        }
+        for pos in &mut positions {
+            let liquidity = pos.liquidity;
+            let (a, b) = amounts_for_liquidity(
+                pool.sqrt_price_x64,
+                sqrt_price_at_tick(pos.tick_lower).unwrap(),
+                sqrt_price_at_tick(pos.tick_upper).unwrap(),
+                liquidity,
+                false,
+            ).unwrap();
+            update_position(&mut pool, pos, -(liquidity as i128)).unwrap();
+            let (out_a, out_b) = (a as u128 + pos.fees_owed_a as u128, b as u128 + pos.fees_owed_b as u128);
+            prop_assert!(out_a <= vault_a && out_b <= vault_b);
+            vault_a -= out_a;
+            vault_b -= out_b;
+        }
+        prop_assert!(pool.ticks.is_empty());
+        prop_assert_eq!(pool.liquidity, 0);
+    }
+}
+
+#[test]
+fn range_swap_pays_fees_only_to_ranges_it_crosses() {
+    // One range around the price, one far above it.
+    let (mut pool, mut positions) = range_pool(0, &[(-100, 100, 1 << 40), (1_000, 2_000, 1 << 40)]);
+    compute_range_swap(&mut pool, 1_000_000, PROTOCOL_FEE_BPS, true, 0).unwrap();
+    for pos in &mut positions {
+        update_position(&mut pool, pos, 0).unwrap();
+    }
+    assert!(positions[0].fees_owed_a > 0);
+    assert_eq!(positions[1].fees_owed_a, 0);
+    assert_eq!(positions[1].fees_owed_b, 0);
+}
+
+#[test]
+fn range_swap_fails_past_the_last_range() {
+    let (mut pool, _) = range_pool(0, &[(-10, 10, 1_000_000)]);
+    assert!(compute_range_swap(&mut pool, u64::MAX >> 8, PROTOCOL_FEE_BPS, true, 0).is_err());
+}
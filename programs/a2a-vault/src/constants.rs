@@ -0,0 +1,18 @@
+/// PDA seeds
+pub const VAULT_SEED: &[u8] = b"vault";
+pub const VAULT_AUTHORITY_SEED: &[u8] = b"vault_authority";
+pub const SHARE_MINT_SEED: &[u8] = b"share_mint";
+
+/// Pools one vault may hold positions in
+pub const MAX_POOLS: usize = 4;
+
+/// Virtual shares and base tokens added to both sides of the share price,
+/// so a first depositor cannot inflate it by donating to the vault
+pub const VIRTUAL_SHARES: u128 = 1;
+pub const VIRTUAL_ASSETS: u128 = 1;
+
+/// Largest net price move, in bps, a vault pool may have made within the
+/// current slot for deposits and withdrawals to price against it. Measured
+/// by the pool's circuit breaker from the slot's opening price, so a swap
+/// and a deposit in one transaction cannot skew the NAV by more than this.
+pub const MAX_NAV_PRICE_MOVE_BPS: u32 = 50;
@@ -0,0 +1,40 @@
+use anchor_lang::prelude::*;
+
+#[error_code]
+pub enum VaultError {
+    #[msg("Amount must be greater than zero")]
+    ZeroAmount,
+    #[msg("Math overflow")]
+    MathOverflow,
+    #[msg("Output below minimum — slippage exceeded")]
+    SlippageExceeded,
+    /// `initialize_vault` takes 1–MAX_POOLS pools
+    #[msg("A vault holds between one and four pools")]
+    InvalidPoolCount,
+    /// Not an A2A-Swap `Pool`, listed twice, or without the vault's base mint
+    #[msg("Pool cannot be added to this vault")]
+    InvalidPool,
+    #[msg("Pool is not one of the vault's pools")]
+    PoolNotInVault,
+    /// Deposits and withdrawals pass `[pool, position, other_token]` for
+    /// every vault pool, in the vault's order
+    #[msg("Pool accounts do not match the vault's pools")]
+    InvalidPoolAccounts,
+    /// The pool's reserves were never tracked; migrate and `sync` it first
+    #[msg("Pool reserves are not tracked")]
+    UntrackedReserves,
+    /// Withdrawals are paid from idle base tokens; the manager has to
+    /// remove liquidity first
+    #[msg("Vault has too few idle base tokens for this withdrawal")]
+    InsufficientIdle,
+    #[msg("Deposit is too small to mint any shares")]
+    ZeroShares,
+    /// NAV needs each pool's per-slot price tally, which only an enabled
+    /// circuit breaker keeps
+    #[msg("Pool has no circuit breaker")]
+    PoolUnguarded,
+    /// A pool moved more than MAX_NAV_PRICE_MOVE_BPS this slot; retry in a
+    /// later slot
+    #[msg("Pool price moved too far this slot")]
+    PoolPriceMoved,
+}
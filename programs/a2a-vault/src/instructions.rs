@@ -0,0 +1,18 @@
+#![allow(ambiguous_glob_reexports)]
+
+pub mod nav;
+pub mod initialize_vault;
+pub mod deposit;
+pub mod withdraw;
+pub mod provide_liquidity;
+pub mod remove_liquidity;
+pub mod swap;
+pub mod compound;
+
+pub use initialize_vault::*;
+pub use deposit::*;
+pub use withdraw::*;
+pub use provide_liquidity::*;
+pub use remove_liquidity::*;
+pub use swap::*;
+pub use compound::*;
@@ -0,0 +1,89 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Mint, MintTo, Token, TokenAccount, Transfer};
+use crate::{constants::*, error::VaultError, state::Vault};
+use super::nav::{net_asset_value, shares_for_deposit};
+
+/// Deposit `amount` base tokens for at least `min_shares` vault shares,
+/// priced at the vault's net asset value before the deposit.
+/// Remaining accounts: `[pool, position, other_token]` per vault pool, see
+/// `nav::net_asset_value`.
+pub fn handler<'info>(
+    ctx: Context<'_, '_, 'info, 'info, Deposit<'info>>,
+    amount: u64,
+    min_shares: u64,
+) -> Result<()> {
+    require!(amount > 0, VaultError::ZeroAmount);
+
+    let vault = &ctx.accounts.vault;
+    let nav = net_asset_value(vault, ctx.accounts.base_token.amount, ctx.remaining_accounts)?;
+    let supply = ctx.accounts.share_mint.supply;
+    let shares = shares_for_deposit(amount, supply, nav)?;
+    require!(shares > 0, VaultError::ZeroShares);
+    require!(shares >= min_shares, VaultError::SlippageExceeded);
+
+    token::transfer(
+        CpiContext::new(
+            ctx.accounts.token_program.to_account_info(),
+            Transfer {
+                from: ctx.accounts.depositor_base.to_account_info(),
+                to: ctx.accounts.base_token.to_account_info(),
+                authority: ctx.accounts.depositor.to_account_info(),
+            },
+        ),
+        amount,
+    )?;
+
+    let vault_key = vault.key();
+    let seeds = vault.authority_seeds(&vault_key);
+    token::mint_to(
+        CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            MintTo {
+                mint: ctx.accounts.share_mint.to_account_info(),
+                to: ctx.accounts.depositor_shares.to_account_info(),
+                authority: ctx.accounts.authority.to_account_info(),
+            },
+            &[&seeds],
+        ),
+        shares,
+    )?;
+
+    msg!("Vault deposit: amount={} shares={} nav={} supply={}", amount, shares, nav, supply);
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct Deposit<'info> {
+    pub depositor: Signer<'info>,
+
+    pub vault: Box<Account<'info, Vault>>,
+
+    /// CHECK: PDA share mint authority
+    #[account(seeds = [VAULT_AUTHORITY_SEED, vault.key().as_ref()], bump = vault.authority_bump)]
+    pub authority: UncheckedAccount<'info>,
+
+    #[account(mut, address = vault.share_mint)]
+    pub share_mint: Box<Account<'info, Mint>>,
+
+    #[account(
+        mut,
+        associated_token::mint = vault.base_mint,
+        associated_token::authority = authority,
+    )]
+    pub base_token: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        token::mint = vault.base_mint,
+        token::authority = depositor,
+    )]
+    pub depositor_base: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        token::mint = share_mint,
+    )]
+    pub depositor_shares: Box<Account<'info, TokenAccount>>,
+
+    pub token_program: Program<'info, Token>,
+}
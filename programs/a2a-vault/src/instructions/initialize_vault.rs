@@ -0,0 +1,87 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token::{Mint, Token, TokenAccount},
+};
+use a2a_swap_core::{Account as _, Pool};
+use crate::{constants::*, error::VaultError, state::Vault};
+
+/// Create a vault over the pools passed as remaining accounts (1–MAX_POOLS
+/// A2A-Swap pools, each containing `base_mint` and with its circuit breaker
+/// on). The pool list is fixed for the vault's lifetime, since depositors
+/// price their shares against it.
+pub fn handler(ctx: Context<InitializeVault>) -> Result<()> {
+    let pools = ctx.remaining_accounts;
+    require!(!pools.is_empty() && pools.len() <= MAX_POOLS, VaultError::InvalidPoolCount);
+
+    let base_mint = ctx.accounts.base_mint.key();
+    let vault = &mut ctx.accounts.vault;
+    for (i, info) in pools.iter().enumerate() {
+        require_keys_eq!(*info.owner, a2a_swap_cpi::ID, VaultError::InvalidPool);
+        let pool = Pool::from_account_data(&info.try_borrow_data()?)
+            .map_err(|_| VaultError::InvalidPool)?;
+        require!(
+            pool.token_a_mint == base_mint.to_bytes() || pool.token_b_mint == base_mint.to_bytes(),
+            VaultError::InvalidPool
+        );
+        require!(pool.circuit_breaker.max_move_bps != 0, VaultError::PoolUnguarded);
+        require!(!vault.pools[..i].contains(info.key), VaultError::InvalidPool);
+        vault.pools[i] = info.key();
+    }
+
+    vault.manager = ctx.accounts.manager.key();
+    vault.base_mint = base_mint;
+    vault.share_mint = ctx.accounts.share_mint.key();
+    vault.authority = ctx.accounts.authority.key();
+    vault.pool_count = pools.len() as u8;
+    vault.bump = ctx.bumps.vault;
+    vault.authority_bump = ctx.bumps.authority;
+    vault.share_mint_bump = ctx.bumps.share_mint;
+
+    msg!("Vault initialized: base_mint={} pools={}", base_mint, pools.len());
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct InitializeVault<'info> {
+    #[account(mut)]
+    pub manager: Signer<'info>,
+
+    pub base_mint: Box<Account<'info, Mint>>,
+
+    #[account(
+        init,
+        payer = manager,
+        space = Vault::LEN,
+        seeds = [VAULT_SEED, manager.key().as_ref(), base_mint.key().as_ref()],
+        bump,
+    )]
+    pub vault: Box<Account<'info, Vault>>,
+
+    /// CHECK: system-owned PDA; owns the vault's tokens and positions
+    #[account(seeds = [VAULT_AUTHORITY_SEED, vault.key().as_ref()], bump)]
+    pub authority: UncheckedAccount<'info>,
+
+    #[account(
+        init,
+        payer = manager,
+        seeds = [SHARE_MINT_SEED, vault.key().as_ref()],
+        bump,
+        mint::decimals = base_mint.decimals,
+        mint::authority = authority,
+    )]
+    pub share_mint: Box<Account<'info, Mint>>,
+
+    /// The vault's idle base tokens
+    #[account(
+        init_if_needed,
+        payer = manager,
+        associated_token::mint = base_mint,
+        associated_token::authority = authority,
+    )]
+    pub base_token: Box<Account<'info, TokenAccount>>,
+
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
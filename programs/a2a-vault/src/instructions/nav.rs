@@ -0,0 +1,147 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{associated_token::get_associated_token_address, token::{self, TokenAccount}};
+use a2a_swap_core::{Account as _, Pool, Position};
+use crate::{constants::*, error::VaultError, state::Vault};
+
+// ─── Share math ──────────────────────────────────────────────────────────────
+// Shares are priced at (nav + VIRTUAL_ASSETS) / (supply + VIRTUAL_SHARES),
+// rounded in the vault's favour both ways.
+
+/// Shares minted for depositing `amount` base tokens into a vault worth `nav`
+/// with `share_supply` shares outstanding.
+pub fn shares_for_deposit(amount: u64, share_supply: u64, nav: u64) -> Result<u64> {
+    let shares = (amount as u128)
+        .checked_mul(share_supply as u128 + VIRTUAL_SHARES)
+        .ok_or(VaultError::MathOverflow)?
+        / (nav as u128 + VIRTUAL_ASSETS);
+    u64::try_from(shares).map_err(|_| VaultError::MathOverflow.into())
+}
+
+/// Base tokens paid for burning `shares` of `share_supply` in a vault worth `nav`.
+pub fn amount_for_shares(shares: u64, share_supply: u64, nav: u64) -> Result<u64> {
+    let amount = (shares as u128)
+        .checked_mul(nav as u128 + VIRTUAL_ASSETS)
+        .ok_or(VaultError::MathOverflow)?
+        / (share_supply as u128 + VIRTUAL_SHARES);
+    u64::try_from(amount).map_err(|_| VaultError::MathOverflow.into())
+}
+
+/// `(token_a, token_b)` a position would hold if it withdrew everything and
+/// claimed its fees, including fees accrued since its last sync.
+pub fn position_amounts(pool: &Pool, position: &Position) -> (u128, u128) {
+    let shares = position.lp_shares as u128;
+    let (lp_a, lp_b) = if pool.lp_supply == 0 {
+        (0, 0)
+    } else {
+        (
+            shares * pool.reserve_a as u128 / pool.lp_supply as u128,
+            shares * pool.reserve_b as u128 / pool.lp_supply as u128,
+        )
+    };
+    let pending = |global: u128, checkpoint: u128| {
+        shares.saturating_mul(global.saturating_sub(checkpoint)) >> 64
+    };
+    (
+        lp_a + position.fees_owed_a as u128 + pending(pool.fee_growth_global_a, position.fee_growth_checkpoint_a),
+        lp_b + position.fees_owed_b as u128 + pending(pool.fee_growth_global_b, position.fee_growth_checkpoint_b),
+    )
+}
+
+/// Base-token value of what the vault holds in one pool: its position plus
+/// `idle_other` of the pool's other token, priced at the pool's reserves.
+pub fn pool_holding_value(
+    pool: &Pool,
+    position: &Position,
+    idle_other: u64,
+    base_mint: &Pubkey,
+) -> Result<u128> {
+    require!(pool.reserves_tracked, VaultError::UntrackedReserves);
+    let base_is_a = pool.token_a_mint == base_mint.to_bytes();
+    let (a, b) = position_amounts(pool, position);
+    let (base, other, reserve_base, reserve_other) = if base_is_a {
+        (a, b, pool.reserve_a, pool.reserve_b)
+    } else {
+        (b, a, pool.reserve_b, pool.reserve_a)
+    };
+    let other = other + idle_other as u128;
+    let other_in_base = if reserve_other == 0 {
+        0
+    } else {
+        other
+            .checked_mul(reserve_base as u128)
+            .ok_or(VaultError::MathOverflow)?
+            / reserve_other as u128
+    };
+    base.checked_add(other_in_base).ok_or(VaultError::MathOverflow.into())
+}
+
+/// Fail unless `pool`'s price is close to where it opened `slot`. The pool's
+/// circuit breaker tallies the net price impact of the slot's swaps, so a
+/// pool pushed around earlier in the same transaction or slot is refused
+/// instead of pricing vault shares. Pools without a breaker keep no tally.
+pub fn check_pool_price(pool: &Pool, slot: u64) -> Result<()> {
+    let cb = &pool.circuit_breaker;
+    require!(cb.max_move_bps != 0, VaultError::PoolUnguarded);
+    require!(
+        cb.slot != slot || cb.moved_bps.unsigned_abs() <= MAX_NAV_PRICE_MOVE_BPS,
+        VaultError::PoolPriceMoved
+    );
+    Ok(())
+}
+
+// ─── Net asset value ─────────────────────────────────────────────────────────
+
+/// The vault's value in base tokens: `idle_base` plus every pool holding.
+///
+/// `accounts` is `[pool, position, other_token]` per vault pool, in the
+/// vault's order: the A2A-Swap pool, the authority's position in it and the
+/// authority's associated token account for the pool's non-base token. The
+/// position and token account may not exist yet; they then count as empty.
+///
+/// Prices are the pools' reserves, accepted only while each pool is within
+/// MAX_NAV_PRICE_MOVE_BPS of its price at the start of the slot (see
+/// `check_pool_price`), so a deposit or withdrawal can't be sandwiched.
+pub fn net_asset_value(vault: &Vault, idle_base: u64, accounts: &[AccountInfo]) -> Result<u64> {
+    require!(accounts.len() == vault.pools().len() * 3, VaultError::InvalidPoolAccounts);
+
+    let slot = Clock::get()?.slot;
+    let mut nav = idle_base as u128;
+    for (expected, chunk) in vault.pools().iter().zip(accounts.chunks_exact(3)) {
+        let [pool_info, position_info, other_info] = chunk else { unreachable!() };
+
+        require_keys_eq!(pool_info.key(), *expected, VaultError::InvalidPoolAccounts);
+        require_keys_eq!(*pool_info.owner, a2a_swap_cpi::ID, VaultError::InvalidPoolAccounts);
+        let pool = Pool::from_account_data(&pool_info.try_borrow_data()?)
+            .map_err(|_| VaultError::InvalidPoolAccounts)?;
+        check_pool_price(&pool, slot)?;
+
+        let (position_key, _) = a2a_swap_cpi::pda::position(expected, &vault.authority);
+        require_keys_eq!(position_info.key(), position_key, VaultError::InvalidPoolAccounts);
+        let position = if position_info.data_is_empty() {
+            Position::default()
+        } else {
+            require_keys_eq!(*position_info.owner, a2a_swap_cpi::ID, VaultError::InvalidPoolAccounts);
+            Position::from_account_data(&position_info.try_borrow_data()?)
+                .map_err(|_| VaultError::InvalidPoolAccounts)?
+        };
+
+        let other_mint = if pool.token_a_mint == vault.base_mint.to_bytes() {
+            pool.token_b_mint
+        } else {
+            pool.token_a_mint
+        };
+        let other_ata = get_associated_token_address(&vault.authority, &Pubkey::new_from_array(other_mint));
+        require_keys_eq!(other_info.key(), other_ata, VaultError::InvalidPoolAccounts);
+        let idle_other = if other_info.data_is_empty() {
+            0
+        } else {
+            require_keys_eq!(*other_info.owner, token::ID, VaultError::InvalidPoolAccounts);
+            TokenAccount::try_deserialize(&mut &other_info.try_borrow_data()?[..])?.amount
+        };
+
+        nav = nav
+            .checked_add(pool_holding_value(&pool, &position, idle_other, &vault.base_mint)?)
+            .ok_or(VaultError::MathOverflow)?;
+    }
+    u64::try_from(nav).map_err(|_| VaultError::MathOverflow.into())
+}
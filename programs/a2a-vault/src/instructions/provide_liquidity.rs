@@ -0,0 +1,87 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::Token;
+use a2a_swap_cpi::{accounts, cpi, A2ASwap};
+use crate::{constants::*, state::Vault};
+
+/// Manager: move idle tokens into one of the vault's pools. The authority
+/// PDA is the position's agent and pays its rent on the first deposit.
+pub fn handler(
+    ctx: Context<ProvideLiquidity>,
+    amount_a: u64,
+    amount_b: u64,
+    min_lp: u64,
+    auto_compound: bool,
+    compound_threshold: u64,
+) -> Result<()> {
+    let a = &ctx.accounts;
+    a.vault.require_pool(a.pool.key)?;
+
+    let vault_key = a.vault.key();
+    let seeds = a.vault.authority_seeds(&vault_key);
+    cpi::provide_liquidity(
+        CpiContext::new_with_signer(
+            a.a2a_swap_program.to_account_info(),
+            accounts::ProvideLiquidity {
+                agent:          a.authority.to_account_info(),
+                pool:           a.pool.to_account_info(),
+                pool_authority: a.pool_authority.to_account_info(),
+                position:       a.position.to_account_info(),
+                token_a_vault:  a.token_a_vault.to_account_info(),
+                token_b_vault:  a.token_b_vault.to_account_info(),
+                agent_token_a:  a.authority_token_a.to_account_info(),
+                agent_token_b:  a.authority_token_b.to_account_info(),
+                token_program:  a.token_program.to_account_info(),
+                system_program: a.system_program.to_account_info(),
+                rent:           a.rent.to_account_info(),
+            },
+            &[&seeds],
+        ),
+        amount_a,
+        amount_b,
+        min_lp,
+        auto_compound,
+        compound_threshold,
+    )
+}
+
+/// A2A-Swap accounts are checked by A2A-Swap itself.
+#[derive(Accounts)]
+pub struct ProvideLiquidity<'info> {
+    pub manager: Signer<'info>,
+
+    #[account(has_one = manager)]
+    pub vault: Box<Account<'info, Vault>>,
+
+    #[account(
+        mut,
+        seeds = [VAULT_AUTHORITY_SEED, vault.key().as_ref()],
+        bump = vault.authority_bump,
+    )]
+    pub authority: SystemAccount<'info>,
+
+    /// CHECK: one of the vault's pools
+    #[account(mut)]
+    pub pool: UncheckedAccount<'info>,
+    /// CHECK: checked by A2A-Swap
+    pub pool_authority: UncheckedAccount<'info>,
+    /// CHECK: the authority's position, created by A2A-Swap on first deposit
+    #[account(mut)]
+    pub position: UncheckedAccount<'info>,
+    /// CHECK: checked by A2A-Swap
+    #[account(mut)]
+    pub token_a_vault: UncheckedAccount<'info>,
+    /// CHECK: checked by A2A-Swap
+    #[account(mut)]
+    pub token_b_vault: UncheckedAccount<'info>,
+    /// CHECK: A2A-Swap requires the authority to own it
+    #[account(mut)]
+    pub authority_token_a: UncheckedAccount<'info>,
+    /// CHECK: A2A-Swap requires the authority to own it
+    #[account(mut)]
+    pub authority_token_b: UncheckedAccount<'info>,
+
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+    pub a2a_swap_program: Program<'info, A2ASwap>,
+}
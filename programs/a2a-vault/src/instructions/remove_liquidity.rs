@@ -0,0 +1,74 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::Token;
+use a2a_swap_cpi::{accounts, cpi, A2ASwap};
+use crate::{constants::*, state::Vault};
+
+/// Manager: burn `lp_shares` of the vault's position back into idle tokens,
+/// for rebalancing or to fund withdrawals.
+pub fn handler(ctx: Context<RemoveLiquidity>, lp_shares: u64, min_a: u64, min_b: u64) -> Result<()> {
+    let a = &ctx.accounts;
+    a.vault.require_pool(a.pool.key)?;
+
+    let vault_key = a.vault.key();
+    let seeds = a.vault.authority_seeds(&vault_key);
+    cpi::remove_liquidity(
+        CpiContext::new_with_signer(
+            a.a2a_swap_program.to_account_info(),
+            accounts::RemoveLiquidity {
+                agent:          a.authority.to_account_info(),
+                pool:           a.pool.to_account_info(),
+                pool_authority: a.pool_authority.to_account_info(),
+                position:       a.position.to_account_info(),
+                token_a_vault:  a.token_a_vault.to_account_info(),
+                token_b_vault:  a.token_b_vault.to_account_info(),
+                agent_token_a:  a.authority_token_a.to_account_info(),
+                agent_token_b:  a.authority_token_b.to_account_info(),
+                token_program:  a.token_program.to_account_info(),
+            },
+            &[&seeds],
+        ),
+        lp_shares,
+        min_a,
+        min_b,
+    )
+}
+
+/// A2A-Swap accounts are checked by A2A-Swap itself.
+#[derive(Accounts)]
+pub struct RemoveLiquidity<'info> {
+    pub manager: Signer<'info>,
+
+    #[account(has_one = manager)]
+    pub vault: Box<Account<'info, Vault>>,
+
+    #[account(
+        mut,
+        seeds = [VAULT_AUTHORITY_SEED, vault.key().as_ref()],
+        bump = vault.authority_bump,
+    )]
+    pub authority: SystemAccount<'info>,
+
+    /// CHECK: one of the vault's pools
+    #[account(mut)]
+    pub pool: UncheckedAccount<'info>,
+    /// CHECK: checked by A2A-Swap
+    pub pool_authority: UncheckedAccount<'info>,
+    /// CHECK: the authority's position
+    #[account(mut)]
+    pub position: UncheckedAccount<'info>,
+    /// CHECK: checked by A2A-Swap
+    #[account(mut)]
+    pub token_a_vault: UncheckedAccount<'info>,
+    /// CHECK: checked by A2A-Swap
+    #[account(mut)]
+    pub token_b_vault: UncheckedAccount<'info>,
+    /// CHECK: A2A-Swap requires the authority to own it
+    #[account(mut)]
+    pub authority_token_a: UncheckedAccount<'info>,
+    /// CHECK: A2A-Swap requires the authority to own it
+    #[account(mut)]
+    pub authority_token_b: UncheckedAccount<'info>,
+
+    pub token_program: Program<'info, Token>,
+    pub a2a_swap_program: Program<'info, A2ASwap>,
+}
@@ -0,0 +1,90 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::Token;
+use a2a_swap_cpi::{accounts, cpi, A2ASwap};
+use crate::{constants::*, state::Vault};
+
+/// Manager: swap idle tokens in one of the vault's pools, e.g. to balance a
+/// deposit or to bring a pool's other token back to base for withdrawals.
+/// Output goes to the authority's own token account; A2A-Swap enforces that.
+pub fn handler(
+    ctx: Context<Swap>,
+    amount_in: u64,
+    min_amount_out: u64,
+    a_to_b: bool,
+    max_price_impact_bps: u16,
+) -> Result<()> {
+    let a = &ctx.accounts;
+    a.vault.require_pool(a.pool.key)?;
+
+    let vault_key = a.vault.key();
+    let seeds = a.vault.authority_seeds(&vault_key);
+    cpi::swap(
+        CpiContext::new_with_signer(
+            a.a2a_swap_program.to_account_info(),
+            accounts::Swap {
+                agent:             a.authority.to_account_info(),
+                pool:              a.pool.to_account_info(),
+                pool_authority:    a.pool_authority.to_account_info(),
+                token_a_vault:     a.token_a_vault.to_account_info(),
+                token_b_vault:     a.token_b_vault.to_account_info(),
+                agent_token_in:    a.authority_token_in.to_account_info(),
+                agent_token_out:   a.authority_token_out.to_account_info(),
+                treasury:          a.treasury.to_account_info(),
+                treasury_token_in: a.treasury_token_in.to_account_info(),
+                token_program:     a.token_program.to_account_info(),
+                referrer_token:    None,
+                agent_volume:      None,
+                trader_pass:       None,
+                gate_token:        None,
+            },
+            &[&seeds],
+        ),
+        amount_in,
+        min_amount_out,
+        a_to_b,
+        max_price_impact_bps,
+        0,  // no deadline
+    )
+}
+
+/// A2A-Swap accounts are checked by A2A-Swap itself.
+#[derive(Accounts)]
+pub struct Swap<'info> {
+    pub manager: Signer<'info>,
+
+    #[account(has_one = manager)]
+    pub vault: Box<Account<'info, Vault>>,
+
+    #[account(
+        mut,
+        seeds = [VAULT_AUTHORITY_SEED, vault.key().as_ref()],
+        bump = vault.authority_bump,
+    )]
+    pub authority: SystemAccount<'info>,
+
+    /// CHECK: one of the vault's pools
+    #[account(mut)]
+    pub pool: UncheckedAccount<'info>,
+    /// CHECK: checked by A2A-Swap
+    pub pool_authority: UncheckedAccount<'info>,
+    /// CHECK: checked by A2A-Swap
+    #[account(mut)]
+    pub token_a_vault: UncheckedAccount<'info>,
+    /// CHECK: checked by A2A-Swap
+    #[account(mut)]
+    pub token_b_vault: UncheckedAccount<'info>,
+    /// CHECK: A2A-Swap requires the authority to own it
+    #[account(mut)]
+    pub authority_token_in: UncheckedAccount<'info>,
+    /// CHECK: A2A-Swap requires the authority to own it
+    #[account(mut)]
+    pub authority_token_out: UncheckedAccount<'info>,
+    /// CHECK: checked by A2A-Swap
+    pub treasury: UncheckedAccount<'info>,
+    /// CHECK: checked by A2A-Swap
+    #[account(mut)]
+    pub treasury_token_in: UncheckedAccount<'info>,
+
+    pub token_program: Program<'info, Token>,
+    pub a2a_swap_program: Program<'info, A2ASwap>,
+}
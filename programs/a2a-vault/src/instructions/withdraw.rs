@@ -0,0 +1,93 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Burn, Mint, Token, TokenAccount, Transfer};
+use crate::{constants::*, error::VaultError, state::Vault};
+use super::nav::{amount_for_shares, net_asset_value};
+
+/// Burn `shares` for at least `min_amount` base tokens, priced at the
+/// vault's net asset value. Paid from idle base tokens only: when the vault
+/// has too few, the manager has to unwind liquidity first.
+/// Remaining accounts: `[pool, position, other_token]` per vault pool, see
+/// `nav::net_asset_value`.
+pub fn handler<'info>(
+    ctx: Context<'_, '_, 'info, 'info, Withdraw<'info>>,
+    shares: u64,
+    min_amount: u64,
+) -> Result<()> {
+    require!(shares > 0, VaultError::ZeroAmount);
+
+    let vault = &ctx.accounts.vault;
+    let idle = ctx.accounts.base_token.amount;
+    let nav = net_asset_value(vault, idle, ctx.remaining_accounts)?;
+    let supply = ctx.accounts.share_mint.supply;
+    let amount = amount_for_shares(shares, supply, nav)?;
+    require!(amount >= min_amount, VaultError::SlippageExceeded);
+    require!(amount <= idle, VaultError::InsufficientIdle);
+
+    token::burn(
+        CpiContext::new(
+            ctx.accounts.token_program.to_account_info(),
+            Burn {
+                mint: ctx.accounts.share_mint.to_account_info(),
+                from: ctx.accounts.owner_shares.to_account_info(),
+                authority: ctx.accounts.owner.to_account_info(),
+            },
+        ),
+        shares,
+    )?;
+
+    let vault_key = vault.key();
+    let seeds = vault.authority_seeds(&vault_key);
+    if amount > 0 {
+        token::transfer(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                Transfer {
+                    from: ctx.accounts.base_token.to_account_info(),
+                    to: ctx.accounts.owner_base.to_account_info(),
+                    authority: ctx.accounts.authority.to_account_info(),
+                },
+                &[&seeds],
+            ),
+            amount,
+        )?;
+    }
+
+    msg!("Vault withdrawal: shares={} amount={} nav={} supply={}", shares, amount, nav, supply);
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct Withdraw<'info> {
+    pub owner: Signer<'info>,
+
+    pub vault: Box<Account<'info, Vault>>,
+
+    /// CHECK: PDA that owns the vault's tokens
+    #[account(seeds = [VAULT_AUTHORITY_SEED, vault.key().as_ref()], bump = vault.authority_bump)]
+    pub authority: UncheckedAccount<'info>,
+
+    #[account(mut, address = vault.share_mint)]
+    pub share_mint: Box<Account<'info, Mint>>,
+
+    #[account(
+        mut,
+        associated_token::mint = vault.base_mint,
+        associated_token::authority = authority,
+    )]
+    pub base_token: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        token::mint = share_mint,
+        token::authority = owner,
+    )]
+    pub owner_shares: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        token::mint = vault.base_mint,
+    )]
+    pub owner_base: Box<Account<'info, TokenAccount>>,
+
+    pub token_program: Program<'info, Token>,
+}
@@ -0,0 +1,117 @@
+//! A2A-Vault — pooled LP vault over A2A-Swap, run by an agent strategy.
+//!
+//! Depositors put in one base token and hold vault shares (an SPL mint); a
+//! manager — usually an agent running an SDK strategy — moves the vault's
+//! assets between idle tokens and A2A-Swap LP positions in up to four pools
+//! that contain the base token. Shares are priced at the vault's net asset
+//! value: idle base, plus each position's tokens and fees and any idle
+//! other token, valued at the pool's reserves.
+//!
+//! 7 instructions:
+//!   initialize_vault  — manager: create a vault over 1–4 pools
+//!   deposit           — deposit base tokens for shares at NAV
+//!   withdraw          — burn shares for base tokens at NAV, from idle base
+//!
+//!   Strategy (manager, through A2A-Swap CPIs signed by the vault authority):
+//!   provide_liquidity — move idle tokens into a pool position
+//!   remove_liquidity  — move a position's liquidity back to idle tokens
+//!   swap              — swap idle tokens in one of the vault's pools
+//!
+//!   compound          — permissionless: claim or compound a position's fees
+
+// ─── Security contact ─────────────────────────────────────────────────────────
+
+use solana_security_txt::security_txt;
+
+#[cfg(not(feature = "no-entrypoint"))]
+security_txt! {
+    name:             "A2A-Vault",
+    project_url:      "https://github.com/liqdlad-rgb/a2a-swap",
+    contacts:         "email:liqdlad@gmail.com",
+    policy:           "Please report security vulnerabilities by emailing liqdlad@gmail.com. \
+                       We aim to respond within 48 hours.",
+    source_code:      "https://github.com/liqdlad-rgb/a2a-swap",
+    preferred_languages: "en"
+}
+
+pub mod constants;
+pub mod error;
+pub mod instructions;
+pub mod state;
+
+use anchor_lang::prelude::*;
+pub use constants::*;
+pub use instructions::*;
+pub use state::*;
+
+declare_id!("47rcd1WG3Ep2tfvuQqvxeXkftPBov9jvjYE6xvYU4XaE");
+
+#[program]
+pub mod a2a_vault {
+    use super::*;
+
+    /// Create a vault for `base_mint` over the A2A-Swap pools passed as
+    /// remaining accounts. The signer becomes its manager.
+    pub fn initialize_vault(ctx: Context<InitializeVault>) -> Result<()> {
+        initialize_vault::handler(ctx)
+    }
+
+    /// Deposit base tokens for vault shares at NAV. Remaining accounts:
+    /// `[pool, position, other_token]` for every vault pool.
+    pub fn deposit<'info>(
+        ctx: Context<'_, '_, 'info, 'info, Deposit<'info>>,
+        amount: u64,
+        min_shares: u64,
+    ) -> Result<()> {
+        deposit::handler(ctx, amount, min_shares)
+    }
+
+    /// Burn vault shares for base tokens at NAV, paid from idle base.
+    /// Remaining accounts as for `deposit`.
+    pub fn withdraw<'info>(
+        ctx: Context<'_, '_, 'info, 'info, Withdraw<'info>>,
+        shares: u64,
+        min_amount: u64,
+    ) -> Result<()> {
+        withdraw::handler(ctx, shares, min_amount)
+    }
+
+    /// Manager: add the vault's idle tokens to its position in a pool.
+    pub fn provide_liquidity(
+        ctx: Context<ProvideLiquidity>,
+        amount_a: u64,
+        amount_b: u64,
+        min_lp: u64,
+        auto_compound: bool,
+        compound_threshold: u64,
+    ) -> Result<()> {
+        provide_liquidity::handler(ctx, amount_a, amount_b, min_lp, auto_compound, compound_threshold)
+    }
+
+    /// Manager: withdraw LP shares from the vault's position in a pool.
+    pub fn remove_liquidity(
+        ctx: Context<RemoveLiquidity>,
+        lp_shares: u64,
+        min_a: u64,
+        min_b: u64,
+    ) -> Result<()> {
+        remove_liquidity::handler(ctx, lp_shares, min_a, min_b)
+    }
+
+    /// Manager: swap the vault's idle tokens in one of its pools.
+    pub fn swap(
+        ctx: Context<Swap>,
+        amount_in: u64,
+        min_amount_out: u64,
+        a_to_b: bool,
+        max_price_impact_bps: u16,
+    ) -> Result<()> {
+        swap::handler(ctx, amount_in, min_amount_out, a_to_b, max_price_impact_bps)
+    }
+
+    /// Claim the vault position's fees in a pool, compounding them if the
+    /// position auto-compounds. Permissionless.
+    pub fn compound(ctx: Context<Compound>) -> Result<()> {
+        compound::handler(ctx)
+    }
+}
@@ -0,0 +1,46 @@
+use anchor_lang::prelude::*;
+use crate::{constants::{MAX_POOLS, VAULT_AUTHORITY_SEED}, error::VaultError};
+
+// ─── Vault ─────────────────────────────────────────────────────────────────
+// Depositors hold `share_mint` tokens; the vault's assets are held by its
+// authority PDA, which is the A2A-Swap agent for every position.
+#[account]
+pub struct Vault {
+    /// Runs the strategy: moves assets between idle tokens and LP positions
+    pub manager: Pubkey,            // 32
+    /// Token deposits and withdrawals are made in, and NAV is priced in
+    pub base_mint: Pubkey,          // 32
+    /// Vault shares, minted by the authority PDA
+    pub share_mint: Pubkey,         // 32
+    /// System-owned PDA that owns the vault's token accounts and positions
+    /// and pays their rent; the manager tops up its lamports
+    pub authority: Pubkey,          // 32
+    /// A2A-Swap pools the vault may hold; each contains `base_mint`.
+    /// Only the first `pool_count` are set
+    pub pools: [Pubkey; MAX_POOLS], // 128
+    pub pool_count: u8,             // 1
+    pub bump: u8,                   // 1
+    pub authority_bump: u8,         // 1
+    pub share_mint_bump: u8,        // 1
+}
+
+impl Vault {
+    /// 8 discriminator + 260 fields
+    pub const LEN: usize = 8 + 32 * 4 + 32 * MAX_POOLS + 4;
+
+    /// The vault's pools, in the order deposits and withdrawals pass them.
+    pub fn pools(&self) -> &[Pubkey] {
+        &self.pools[..self.pool_count as usize]
+    }
+
+    /// Fails unless `pool` is one of the vault's pools.
+    pub fn require_pool(&self, pool: &Pubkey) -> Result<()> {
+        require!(self.pools().contains(pool), VaultError::PoolNotInVault);
+        Ok(())
+    }
+
+    /// Signer seeds of the authority PDA; `vault` is this account's address.
+    pub fn authority_seeds<'a>(&'a self, vault: &'a Pubkey) -> [&'a [u8]; 3] {
+        [VAULT_AUTHORITY_SEED, vault.as_ref(), std::slice::from_ref(&self.authority_bump)]
+    }
+}
@@ -0,0 +1,408 @@
+//! The SDK's vault module against the program: share math, NAV valuation,
+//! the `Vault` layout and every instruction's data and accounts.
+//!
+//! Pure functions only — no bank, no validator.
+
+use a2a_swap_core::{
+    math::{price_impact_bps, quote_swap},
+    CircuitBreaker, Pool, Position,
+};
+use a2a_swap_sdk::{
+    instructions::{derive_ata, derive_pool_authority, derive_position, derive_treasury},
+    state::{PoolState, PositionState},
+    vault::{self as sdk, VaultPool, VaultState},
+};
+use a2a_vault::{
+    error::VaultError,
+    instructions::nav::{amount_for_shares, check_pool_price, pool_holding_value, shares_for_deposit},
+    state::Vault,
+    MAX_NAV_PRICE_MOVE_BPS, MAX_POOLS, SHARE_MINT_SEED, VAULT_AUTHORITY_SEED, VAULT_SEED,
+};
+use anchor_lang::{
+    prelude::Pubkey, solana_program::{instruction::Instruction, sysvar}, AccountSerialize, InstructionData,
+    ToAccountMetas,
+};
+
+fn program_id() -> Pubkey {
+    a2a_swap_cpi::ID
+}
+
+/// A vault over two pools, as the SDK and the program see it.
+fn vault() -> (Pubkey, VaultState, Vec<VaultPool>) {
+    let manager = Pubkey::new_unique();
+    let base_mint = Pubkey::new_unique();
+    let (key, bump) = sdk::derive_vault(&manager, &base_mint, &a2a_vault::ID);
+    let (authority, authority_bump) = sdk::derive_vault_authority(&key, &a2a_vault::ID);
+    let (share_mint, share_mint_bump) = sdk::derive_share_mint(&key, &a2a_vault::ID);
+    let pools = vec![
+        VaultPool { pool: Pubkey::new_unique(), other_mint: Pubkey::new_unique() },
+        VaultPool { pool: Pubkey::new_unique(), other_mint: Pubkey::new_unique() },
+    ];
+    let state = VaultState {
+        manager,
+        base_mint,
+        share_mint,
+        authority,
+        pools: pools.iter().map(|p| p.pool).collect(),
+        bump,
+        authority_bump,
+        share_mint_bump,
+    };
+    (key, state, pools)
+}
+
+fn pool_state(pool: &Pool) -> PoolState {
+    a2a_swap_sdk::state::parse_pool(&a2a_swap_core::Account::to_account_data(pool)).unwrap()
+}
+
+fn position_state(position: &Position) -> PositionState {
+    a2a_swap_sdk::state::parse_position(&a2a_swap_core::Account::to_account_data(position)).unwrap()
+}
+
+#[test]
+fn seeds_and_program_id_match_the_sdk() {
+    assert_eq!(sdk::vault_program_id(), a2a_vault::ID);
+    assert_eq!(sdk::VAULT_SEED, VAULT_SEED);
+    assert_eq!(sdk::VAULT_AUTHORITY_SEED, VAULT_AUTHORITY_SEED);
+    assert_eq!(sdk::SHARE_MINT_SEED, SHARE_MINT_SEED);
+    assert_eq!(sdk::MAX_POOLS, MAX_POOLS);
+    assert_eq!(sdk::VAULT_LEN, Vault::LEN);
+}
+
+#[test]
+fn share_math_matches_the_sdk() {
+    let values = [0u64, 1, 2, 999, 1_000_000, 123_456_789_012, u64::MAX / 3, u64::MAX];
+    for &a in &values {
+        for &supply in &values {
+            for &nav in &values {
+                assert_eq!(shares_for_deposit(a, supply, nav).ok(), sdk::shares_for_deposit(a, supply, nav));
+                assert_eq!(amount_for_shares(a, supply, nav).ok(), sdk::amount_for_shares(a, supply, nav));
+            }
+        }
+    }
+}
+
+#[test]
+fn round_trip_never_pays_out_more_than_deposited() {
+    for (supply, nav) in [(0u64, 0u64), (1_000, 1_000), (1_000, 1_500), (7, 1_000_003), (1_000_000, 999)] {
+        for amount in [1u64, 10, 333, 1_000_000] {
+            let shares = shares_for_deposit(amount, supply, nav).unwrap();
+            let back = amount_for_shares(shares, supply + shares, nav + amount).unwrap();
+            assert!(back <= amount, "supply {supply} nav {nav} amount {amount}: {back}");
+        }
+    }
+}
+
+#[test]
+fn donation_cannot_steal_the_next_deposit() {
+    // Attacker deposits 1 and donates a large amount straight to the vault.
+    let shares = shares_for_deposit(1, 0, 0).unwrap();
+    assert_eq!(shares, 1);
+    let nav = 1 + 1_000_000_000;
+    // The victim's deposit mints zero shares, which `deposit` rejects
+    // (ZeroShares) instead of handing the deposit to the attacker.
+    assert_eq!(shares_for_deposit(1_000_000, shares, nav).unwrap(), 0);
+    // Larger deposits lose at most the value of one share to rounding.
+    let amount = 10_000_000_000;
+    let victim = shares_for_deposit(amount, shares, nav).unwrap();
+    let paid = amount_for_shares(victim, shares + victim, nav + amount).unwrap();
+    let one_share = (nav + amount) / (shares + victim);
+    assert!(paid + one_share >= amount, "victim paid back {paid} of {amount}");
+}
+
+#[test]
+fn pool_holding_value_matches_the_sdk() {
+    let base_mint = Pubkey::new_unique();
+    let other_mint = Pubkey::new_unique();
+    for base_is_a in [true, false] {
+        let (mint_a, mint_b) = if base_is_a { (base_mint, other_mint) } else { (other_mint, base_mint) };
+        let pool = Pool {
+            token_a_mint:        mint_a.to_bytes(),
+            token_b_mint:        mint_b.to_bytes(),
+            lp_supply:           1_000_000,
+            reserve_a:           5_000_000,
+            reserve_b:           20_000_000,
+            fee_growth_global_a: 3u128 << 64,
+            fee_growth_global_b: 1u128 << 63,
+            reserves_tracked:    true,
+            ..Default::default()
+        };
+        let position = Position {
+            lp_shares:               250_000,
+            fee_growth_checkpoint_a: 1u128 << 64,
+            fees_owed_a:             17,
+            fees_owed_b:             4,
+            ..Default::default()
+        };
+        let value = pool_holding_value(&pool, &position, 1_000, &base_mint).unwrap();
+        assert_eq!(
+            value,
+            sdk::pool_holding_value(&pool_state(&pool), Some(&position_state(&position)), 1_000, &base_mint),
+        );
+
+        // A quarter of the reserves plus owed and pending fees, other side at the pool price.
+        let a = 1_250_000u128 + 17 + 250_000 * 2;
+        let b = 5_000_000u128 + 4 + 125_000;
+        let expected = if base_is_a { a + (b + 1_000) * 5 / 20 } else { b + (a + 1_000) * 20 / 5 };
+        assert_eq!(value, expected);
+    }
+}
+
+#[test]
+fn untracked_reserves_are_rejected() {
+    let base_mint = Pubkey::new_unique();
+    let pool = Pool { token_a_mint: base_mint.to_bytes(), lp_supply: 1_000, ..Default::default() };
+    assert!(pool_holding_value(&pool, &Position::default(), 0, &base_mint).is_err());
+    // A tracked pool drained to zero is worth zero, not its vault balances.
+    let drained = Pool { reserves_tracked: true, ..pool };
+    assert_eq!(pool_holding_value(&drained, &Position::default(), 0, &base_mint).unwrap(), 0);
+}
+
+/// An attacker sells the other token into a vault pool right before a
+/// deposit, so the NAV drops and the deposit mints extra shares. The pool's
+/// breaker tally shows the move, and the vault refuses to price against it.
+#[test]
+fn sandwiched_deposit_is_refused() {
+    let base_mint = Pubkey::new_unique();
+    let slot = 1_000;
+    let mut pool = Pool {
+        token_a_mint:     base_mint.to_bytes(),
+        token_b_mint:     Pubkey::new_unique().to_bytes(),
+        lp_supply:        10_000_000,
+        reserve_a:        10_000_000,
+        reserve_b:        10_000_000,
+        fee_rate_bps:     30,
+        reserves_tracked: true,
+        circuit_breaker:  CircuitBreaker { max_move_bps: 5_000, moved_bps: 0, slot: slot - 1 },
+        ..Default::default()
+    };
+    let position = Position { lp_shares: 5_000_000, ..Default::default() };
+    check_pool_price(&pool, slot).unwrap();
+    let fair = pool_holding_value(&pool, &position, 0, &base_mint).unwrap() as u64;
+
+    // Front-run: B→A in the same slot, as `swap` records it.
+    let q = quote_swap(pool.curve, pool.fee_rate_bps, pool.reserve_b, pool.reserve_a, 5_000_000, 0).unwrap();
+    pool.circuit_breaker.slot = slot;
+    pool.circuit_breaker.moved_bps = price_impact_bps(q.after_fees, pool.reserve_b) as i32;
+    pool.reserve_b += q.net_pool_input;
+    pool.reserve_a -= q.amount_out;
+
+    let skewed = pool_holding_value(&pool, &position, 0, &base_mint).unwrap() as u64;
+    let (supply, deposit) = (1_000_000, 1_000_000);
+    assert!(
+        shares_for_deposit(deposit, supply, skewed).unwrap() > shares_for_deposit(deposit, supply, fair).unwrap(),
+        "the front-run makes the deposit cheaper",
+    );
+    assert_eq!(check_pool_price(&pool, slot).unwrap_err(), VaultError::PoolPriceMoved.into());
+
+    // The tally is per slot; small moves within the bound are fine.
+    check_pool_price(&pool, slot + 1).unwrap();
+    pool.circuit_breaker.moved_bps = -(MAX_NAV_PRICE_MOVE_BPS as i32);
+    check_pool_price(&pool, slot).unwrap();
+
+    // Without a breaker there is no tally to check against.
+    pool.circuit_breaker = CircuitBreaker::default();
+    assert_eq!(check_pool_price(&pool, slot + 1).unwrap_err(), VaultError::PoolUnguarded.into());
+}
+
+#[test]
+fn vault_layout_matches_the_sdk() {
+    let (_, state, _) = vault();
+    let mut pools = [Pubkey::default(); MAX_POOLS];
+    pools[..state.pools.len()].copy_from_slice(&state.pools);
+    let account = Vault {
+        manager:         state.manager,
+        base_mint:       state.base_mint,
+        share_mint:      state.share_mint,
+        authority:       state.authority,
+        pools,
+        pool_count:      state.pools.len() as u8,
+        bump:            state.bump,
+        authority_bump:  state.authority_bump,
+        share_mint_bump: state.share_mint_bump,
+    };
+    let mut data = Vec::new();
+    account.try_serialize(&mut data).unwrap();
+    assert_eq!(data.len(), Vault::LEN);
+    assert_eq!(sdk::parse_vault(&data).unwrap(), state);
+    assert!(sdk::parse_vault(&data[..Vault::LEN - 1]).is_err());
+}
+
+fn assert_ix(sdk_ix: &Instruction, data: Vec<u8>, metas: Vec<anchor_lang::prelude::AccountMeta>) {
+    assert_eq!(sdk_ix.program_id, a2a_vault::ID);
+    assert_eq!(sdk_ix.data, data);
+    assert_eq!(sdk_ix.accounts[..metas.len()], metas[..]);
+}
+
+#[test]
+fn depositor_instructions_match_the_program() {
+    let (key, state, pools) = vault();
+    let user = Pubkey::new_unique();
+    let nav_accounts = sdk::nav_accounts(&state, &pools, &program_id());
+    assert_eq!(nav_accounts.len(), 3 * pools.len());
+    for (chunk, p) in nav_accounts.chunks(3).zip(&pools) {
+        assert_eq!(chunk[0].pubkey, p.pool);
+        assert_eq!(chunk[1].pubkey, a2a_swap_cpi::pda::position(&p.pool, &state.authority).0);
+        assert_eq!(chunk[2].pubkey, anchor_spl::associated_token::get_associated_token_address(&state.authority, &p.other_mint));
+        assert!(chunk.iter().all(|m| !m.is_writable && !m.is_signer));
+    }
+
+    let init = sdk::initialize_vault_ix(&a2a_vault::ID, &state.manager, &state.base_mint, &state.pools);
+    assert_ix(
+        &init,
+        a2a_vault::instruction::InitializeVault {}.data(),
+        a2a_vault::accounts::InitializeVault {
+            manager:                  state.manager,
+            base_mint:                state.base_mint,
+            vault:                    key,
+            authority:                state.authority,
+            share_mint:               state.share_mint,
+            base_token:               derive_ata(&state.authority, &state.base_mint),
+            token_program:            anchor_spl::token::ID,
+            associated_token_program: anchor_spl::associated_token::ID,
+            system_program:           anchor_lang::system_program::ID,
+        }
+        .to_account_metas(None),
+    );
+    assert_eq!(init.accounts[9..].iter().map(|m| m.pubkey).collect::<Vec<_>>(), state.pools);
+
+    let deposit = sdk::deposit_ix(&a2a_vault::ID, &program_id(), &user, &key, &state, &pools, 1_000, 990);
+    assert_ix(
+        &deposit,
+        a2a_vault::instruction::Deposit { amount: 1_000, min_shares: 990 }.data(),
+        a2a_vault::accounts::Deposit {
+            depositor:        user,
+            vault:            key,
+            authority:        state.authority,
+            share_mint:       state.share_mint,
+            base_token:       derive_ata(&state.authority, &state.base_mint),
+            depositor_base:   derive_ata(&user, &state.base_mint),
+            depositor_shares: derive_ata(&user, &state.share_mint),
+            token_program:    anchor_spl::token::ID,
+        }
+        .to_account_metas(None),
+    );
+    assert_eq!(deposit.accounts[8..], nav_accounts[..]);
+
+    let withdraw = sdk::withdraw_ix(&a2a_vault::ID, &program_id(), &user, &key, &state, &pools, 500, 480);
+    assert_ix(
+        &withdraw,
+        a2a_vault::instruction::Withdraw { shares: 500, min_amount: 480 }.data(),
+        a2a_vault::accounts::Withdraw {
+            owner:         user,
+            vault:         key,
+            authority:     state.authority,
+            share_mint:    state.share_mint,
+            base_token:    derive_ata(&state.authority, &state.base_mint),
+            owner_shares:  derive_ata(&user, &state.share_mint),
+            owner_base:    derive_ata(&user, &state.base_mint),
+            token_program: anchor_spl::token::ID,
+        }
+        .to_account_metas(None),
+    );
+    assert_eq!(withdraw.accounts[8..], nav_accounts[..]);
+}
+
+#[test]
+fn strategy_instructions_match_the_program() {
+    let (key, state, pools) = vault();
+    let pool_key = pools[0].pool;
+    let pool = PoolState {
+        token_a_mint:  state.base_mint,
+        token_b_mint:  pools[0].other_mint,
+        token_a_vault: Pubkey::new_unique(),
+        token_b_vault: Pubkey::new_unique(),
+        ..pool_state(&Pool::default())
+    };
+    let pool_authority = derive_pool_authority(&pool_key, &program_id()).0;
+    let position = derive_position(&pool_key, &state.authority, &program_id()).0;
+    let token_a = derive_ata(&state.authority, &pool.token_a_mint);
+    let token_b = derive_ata(&state.authority, &pool.token_b_mint);
+
+    assert_ix(
+        &sdk::provide_liquidity_ix(&a2a_vault::ID, &program_id(), &key, &state, &pool_key, &pool, 10, 20, 5, true, 100),
+        a2a_vault::instruction::ProvideLiquidity {
+            amount_a: 10, amount_b: 20, min_lp: 5, auto_compound: true, compound_threshold: 100,
+        }
+        .data(),
+        a2a_vault::accounts::ProvideLiquidity {
+            manager:           state.manager,
+            vault:             key,
+            authority:         state.authority,
+            pool:              pool_key,
+            pool_authority,
+            position,
+            token_a_vault:     pool.token_a_vault,
+            token_b_vault:     pool.token_b_vault,
+            authority_token_a: token_a,
+            authority_token_b: token_b,
+            token_program:     anchor_spl::token::ID,
+            system_program:    anchor_lang::system_program::ID,
+            rent:              sysvar::rent::ID,
+            a2a_swap_program:  program_id(),
+        }
+        .to_account_metas(None),
+    );
+
+    assert_ix(
+        &sdk::remove_liquidity_ix(&a2a_vault::ID, &program_id(), &key, &state, &pool_key, &pool, 7, 1, 2),
+        a2a_vault::instruction::RemoveLiquidity { lp_shares: 7, min_a: 1, min_b: 2 }.data(),
+        a2a_vault::accounts::RemoveLiquidity {
+            manager:           state.manager,
+            vault:             key,
+            authority:         state.authority,
+            pool:              pool_key,
+            pool_authority,
+            position,
+            token_a_vault:     pool.token_a_vault,
+            token_b_vault:     pool.token_b_vault,
+            authority_token_a: token_a,
+            authority_token_b: token_b,
+            token_program:     anchor_spl::token::ID,
+            a2a_swap_program:  program_id(),
+        }
+        .to_account_metas(None),
+    );
+
+    let treasury = derive_treasury(&program_id()).0;
+    assert_ix(
+        &sdk::swap_ix(&a2a_vault::ID, &program_id(), &key, &state, &pool_key, &pool, 1_000, 900, false, 300),
+        a2a_vault::instruction::Swap { amount_in: 1_000, min_amount_out: 900, a_to_b: false, max_price_impact_bps: 300 }
+            .data(),
+        a2a_vault::accounts::Swap {
+            manager:             state.manager,
+            vault:               key,
+            authority:           state.authority,
+            pool:                pool_key,
+            pool_authority,
+            token_a_vault:       pool.token_a_vault,
+            token_b_vault:       pool.token_b_vault,
+            authority_token_in:  token_b,
+            authority_token_out: token_a,
+            treasury,
+            treasury_token_in:   derive_ata(&treasury, &pool.token_b_mint),
+            token_program:       anchor_spl::token::ID,
+            a2a_swap_program:    program_id(),
+        }
+        .to_account_metas(None),
+    );
+
+    assert_ix(
+        &sdk::compound_ix(&a2a_vault::ID, &program_id(), &key, &state, &pool_key, &pool),
+        a2a_vault::instruction::Compound {}.data(),
+        a2a_vault::accounts::Compound {
+            vault:             key,
+            authority:         state.authority,
+            pool:              pool_key,
+            pool_authority,
+            position,
+            token_a_vault:     pool.token_a_vault,
+            token_b_vault:     pool.token_b_vault,
+            authority_token_a: token_a,
+            authority_token_b: token_b,
+            token_program:     anchor_spl::token::ID,
+            a2a_swap_program:  program_id(),
+        }
+        .to_account_metas(None),
+    );
+}
@@ -0,0 +1,76 @@
+#!/usr/bin/env bash
+# build-idl.sh — Regenerate the program's Anchor IDL.
+#
+# Writes packages/core/idl/a2a_swap.json, the artifact `a2a-swap-core`
+# generates its account and instruction layouts from. Run it after changing
+# any `#[account]`, `#[derive(Accounts)]`, instruction argument or error in
+# programs/a2a-swap, and commit the result alongside the change.
+#
+# Uses the same `idl-build` test hook `anchor idl build` drives, so no Anchor
+# CLI or validator is needed — only cargo and python3.
+#
+# Usage:
+#   scripts/build-idl.sh           # rewrite the checked-in IDL
+#   scripts/build-idl.sh --check   # fail if the checked-in IDL is stale (CI)
+
+set -euo pipefail
+
+REPO_ROOT="$(cd "$(dirname "${BASH_SOURCE[0]}")/.." && pwd)"
+IDL_PATH="$REPO_ROOT/packages/core/idl/a2a_swap.json"
+
+cd "$REPO_ROOT"
+
+RAW="$(mktemp)"
+OUT="$(mktemp)"
+trap 'rm -f "$RAW" "$OUT"' EXIT
+
+cargo test -p a2a-swap --features idl-build --lib __anchor_private_print_idl \
+  -- --show-output --quiet --test-threads=1 > "$RAW"
+
+python3 - "$RAW" "$OUT" <<'PY'
+import json, re, sys
+
+raw = open(sys.argv[1]).read()
+
+def section(name):
+    m = re.search(rf"--- IDL begin {name} ---\n(.*?)\n--- IDL end {name} ---", raw, re.S)
+    if not m:
+        sys.exit(f"build-idl: no '{name}' section in the idl-build output")
+    return json.loads(m.group(1))
+
+idl = section("program")
+# The address is printed on its own, JSON-encoded twice.
+idl["address"] = json.loads(section("address"))
+idl["errors"] = section("errors")
+
+# The idl-build hook names types by full path (`a2a_swap::state::Pool`);
+# the Anchor CLI shortens them, and so do we.
+def shorten(v):
+    if isinstance(v, dict):
+        return {k: (x.rsplit("::", 1)[-1] if k == "name" and isinstance(x, str) else shorten(x))
+                for k, x in v.items()}
+    if isinstance(v, list):
+        return [shorten(x) for x in v]
+    return v
+
+idl = shorten(idl)
+names = [t["name"] for t in idl["types"]]
+if len(names) != len(set(names)):
+    sys.exit("build-idl: type names collide once shortened")
+
+with open(sys.argv[2], "w") as f:
+    json.dump(idl, f, indent=2, ensure_ascii=False)
+    f.write("\n")
+PY
+
+if [[ "${1:-}" == "--check" ]]; then
+  if ! diff -u "$IDL_PATH" "$OUT"; then
+    echo "build-idl: $IDL_PATH is stale — run scripts/build-idl.sh" >&2
+    exit 1
+  fi
+  echo "build-idl: IDL is up to date"
+else
+  mkdir -p "$(dirname "$IDL_PATH")"
+  cp "$OUT" "$IDL_PATH"
+  echo "build-idl: wrote $IDL_PATH"
+fi
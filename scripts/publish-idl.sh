@@ -0,0 +1,38 @@
+#!/usr/bin/env bash
+# publish-idl.sh — Publish the program's Anchor IDL on-chain.
+#
+# Writes packages/core/idl/a2a_swap.json (see build-idl.sh) into the
+# program's IDL account, so `A2ASwapClient::fetch_idl`, `a2a-swap idl` and
+# any Anchor client can read it. The first run creates the account with
+# `anchor idl init`; later runs replace it with `anchor idl upgrade`. The
+# wallet must be the program's upgrade authority (init) or the IDL
+# authority (upgrade).
+#
+# Usage:
+#   scripts/publish-idl.sh                      # mainnet, ~/.config/solana/id.json
+#   scripts/publish-idl.sh devnet
+#   scripts/publish-idl.sh mainnet path/to/authority.json
+
+set -euo pipefail
+
+REPO_ROOT="$(cd "$(dirname "${BASH_SOURCE[0]}")/.." && pwd)"
+IDL_PATH="$REPO_ROOT/packages/core/idl/a2a_swap.json"
+PROGRAM_ID="8XJfG4mHqRZjByAd7HxHdEALfB8jVtJVQsdhGEmysTFq"
+
+CLUSTER="${1:-mainnet}"
+WALLET="${2:-$HOME/.config/solana/id.json}"
+
+cd "$REPO_ROOT"
+
+# Publish what the program was built from, never a stale copy.
+scripts/build-idl.sh --check
+
+ARGS=(--filepath "$IDL_PATH" --provider.cluster "$CLUSTER" --provider.wallet "$WALLET")
+
+if anchor idl fetch --provider.cluster "$CLUSTER" "$PROGRAM_ID" > /dev/null 2>&1; then
+  echo "publish-idl: upgrading the IDL of $PROGRAM_ID on $CLUSTER"
+  anchor idl upgrade "${ARGS[@]}" "$PROGRAM_ID"
+else
+  echo "publish-idl: creating the IDL account of $PROGRAM_ID on $CLUSTER"
+  anchor idl init "${ARGS[@]}" "$PROGRAM_ID"
+fi
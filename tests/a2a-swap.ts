@@ -4,6 +4,7 @@ import { A2aSwap } from "../target/types/a2a_swap";
 import {
   createMint,
   createAccount,
+  createAssociatedTokenAccount,
   mintTo,
   getAccount,
   TOKEN_PROGRAM_ID,
@@ -67,9 +68,6 @@ describe("a2a-swap", () => {
   const approver      = Keypair.generate();
   const vaultAKp      = Keypair.generate();
   const vaultBKp      = Keypair.generate();
-  // Explicit keypairs for treasury token accounts (PDA owner is off-curve)
-  const treasuryATAKp = Keypair.generate();
-  const treasuryBTAKp = Keypair.generate();
 
   // ── Addresses set in before() ─────────────────────────────────────────────
   let mintA: PublicKey;
@@ -79,8 +77,8 @@ describe("a2a-swap", () => {
   let poolPda: PublicKey;
   let poolAuthPda: PublicKey;
   let treasuryPda: PublicKey;
-  let treasuryATA: PublicKey; // treasury's token-A account
-  let treasuryBTA: PublicKey; // treasury's token-B account
+  let treasuryATA: PublicKey; // treasury's token-A ATA
+  let treasuryBTA: PublicKey; // treasury's token-B ATA
   let positionPda: PublicKey;
 
   const INIT_A = 10_000_000n; // initial liquidity seed
@@ -121,7 +119,7 @@ describe("a2a-swap", () => {
 
     // Initialize pool — vaults are created here via `init`
     await program.methods
-      .initializePool(30)
+      .initializePool(30, { constantProduct: {} })
       .accounts({
         creator:       agent.publicKey,
         tokenAMint:    mintA,
@@ -137,10 +135,12 @@ describe("a2a-swap", () => {
       .signers([agent, vaultAKp, vaultBKp])
       .rpc();
 
-    // Treasury token accounts — owned by the treasury PDA.
-    // Must use explicit keypairs because treasuryPda is off-curve (a PDA).
-    treasuryATA = await createAccount(conn, agent, mintA, treasuryPda, treasuryATAKp);
-    treasuryBTA = await createAccount(conn, agent, mintB, treasuryPda, treasuryBTAKp);
+    // Treasury ATAs — swaps reject any other treasury token account.
+    // allowOwnerOffCurve = true because treasuryPda is a PDA.
+    treasuryATA = await createAssociatedTokenAccount(
+      conn, agent, mintA, treasuryPda, undefined, undefined, undefined, true);
+    treasuryBTA = await createAssociatedTokenAccount(
+      conn, agent, mintB, treasuryPda, undefined, undefined, undefined, true);
   });
 
   // ─── 1. Pool state ─────────────────────────────────────────────────────────
@@ -210,7 +210,7 @@ describe("a2a-swap", () => {
     const vaultBBefore    = await bal(conn, vaultBKp.publicKey);
 
     await program.methods
-      .swap(new BN(amtIn.toString()), new BN(0), true)
+      .swap(new BN(amtIn.toString()), new BN(0), true, 0, new BN(0))
       .accounts({
         agent:           agent.publicKey,
         pool:            poolPda,
@@ -329,6 +329,74 @@ describe("a2a-swap", () => {
     expect(pos.autoCompound).to.equal(true);
   });
 
+  it("provide_liquidity_proportional: reverts with DepositExceedsMax above max_other", async () => {
+    const vaultA = await bal(conn, vaultAKp.publicKey);
+    const vaultB = await bal(conn, vaultBKp.publicKey);
+    const amtA = 1_000_000n;
+    const needB = (amtA * vaultB + vaultA - 1n) / vaultA; // rounded up
+    let err = "";
+    try {
+      await program.methods
+        .provideLiquidityProportional(
+          new BN(amtA.toString()),
+          true,                             // fixed_a
+          new BN((needB - 1n).toString()),  // max_other just below what it takes
+          new BN(0), true, new BN(1),
+        )
+        .accounts({
+          agent:         agent.publicKey,
+          pool:          poolPda,
+          poolAuthority: poolAuthPda,
+          position:      positionPda,
+          tokenAVault:   vaultAKp.publicKey,
+          tokenBVault:   vaultBKp.publicKey,
+          agentTokenA:   agentATA,
+          agentTokenB:   agentBTA,
+          tokenProgram:  TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+          rent:          SYSVAR_RENT_PUBKEY,
+        })
+        .signers([agent])
+        .rpc();
+    } catch (e) {
+      err = String(e);
+    }
+    expect(err).to.include("DepositExceedsMax");
+  });
+
+  it("provide_liquidity_proportional: program sizes token B from live reserves", async () => {
+    const vaultA = await bal(conn, vaultAKp.publicKey);
+    const vaultB = await bal(conn, vaultBKp.publicKey);
+    const amtA = 1_000_000n;
+    const expectedB = (amtA * vaultB + vaultA - 1n) / vaultA; // rounded up
+
+    await program.methods
+      .provideLiquidityProportional(
+        new BN(amtA.toString()),
+        true,                             // fixed_a
+        new BN(expectedB.toString()),     // max_other: exactly the proportional amount
+        new BN(0), true, new BN(1),
+      )
+      .accounts({
+        agent:         agent.publicKey,
+        pool:          poolPda,
+        poolAuthority: poolAuthPda,
+        position:      positionPda,
+        tokenAVault:   vaultAKp.publicKey,
+        tokenBVault:   vaultBKp.publicKey,
+        agentTokenA:   agentATA,
+        agentTokenB:   agentBTA,
+        tokenProgram:  TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+        rent:          SYSVAR_RENT_PUBKEY,
+      })
+      .signers([agent])
+      .rpc();
+
+    expect((await bal(conn, vaultAKp.publicKey) - vaultA).toString()).to.equal(amtA.toString());
+    expect((await bal(conn, vaultBKp.publicKey) - vaultB).toString()).to.equal(expectedB.toString());
+  });
+
   // ─── 6a. Swap a→b (small) — generate fee_growth_a ─────────────────────────
   it("swap a→b (small): sets fee_growth_a ahead of auto_compound test", async () => {
     const vA = await bal(conn, vaultAKp.publicKey);
@@ -337,7 +405,7 @@ describe("a2a-swap", () => {
     const tBefore = await bal(conn, treasuryATA);
 
     await program.methods
-      .swap(new BN(300_000), new BN(0), true)
+      .swap(new BN(300_000), new BN(0), true, 0, new BN(0))
       .accounts({
         agent:           agent.publicKey,
         pool:            poolPda,
@@ -358,6 +426,110 @@ describe("a2a-swap", () => {
       "treasury still receives 0.02% on small a→b swap");
   });
 
+  it("swap: reverts with InvalidTreasuryAccount for a non-ATA treasury account", async () => {
+    const stray = await createAccount(conn, agent, mintA, treasuryPda, Keypair.generate());
+    let err = "";
+    try {
+      await program.methods
+        .swap(new BN(1_000), new BN(0), true, 0, new BN(0))
+        .accounts({
+          agent:           agent.publicKey,
+          pool:            poolPda,
+          poolAuthority:   poolAuthPda,
+          tokenAVault:     vaultAKp.publicKey,
+          tokenBVault:     vaultBKp.publicKey,
+          agentTokenIn:    agentATA,
+          agentTokenOut:   agentBTA,
+          treasury:        treasuryPda,
+          treasuryTokenIn: stray,
+          tokenProgram:    TOKEN_PROGRAM_ID,
+        })
+        .signers([agent])
+        .rpc();
+    } catch (e) {
+      err = String(e);
+    }
+    expect(err).to.include("InvalidTreasuryAccount");
+  });
+
+  it("swap: reverts with DeadlineExceeded once deadline_unix has passed", async () => {
+    const deadlineUnix = Math.floor(Date.now() / 1000) - 60;
+    let err = "";
+    try {
+      await program.methods
+        .swap(new BN(1_000), new BN(0), true, 0, new BN(deadlineUnix))
+        .accounts({
+          agent:           agent.publicKey,
+          pool:            poolPda,
+          poolAuthority:   poolAuthPda,
+          tokenAVault:     vaultAKp.publicKey,
+          tokenBVault:     vaultBKp.publicKey,
+          agentTokenIn:    agentATA,
+          agentTokenOut:   agentBTA,
+          treasury:        treasuryPda,
+          treasuryTokenIn: treasuryATA,
+          tokenProgram:    TOKEN_PROGRAM_ID,
+        })
+        .signers([agent])
+        .rpc();
+    } catch (e) {
+      err = String(e);
+    }
+    expect(err).to.include("DeadlineExceeded");
+  });
+
+  it("swap_exact_out: agent receives exactly amount_out, pays at most max_amount_in", async () => {
+    const amountOut = 250_000n;
+    const agentABefore = await bal(conn, agentATA);
+    const agentBBefore = await bal(conn, agentBTA);
+
+    await program.methods
+      .swapExactOut(new BN(amountOut.toString()), new BN(1_000_000), true, 0)
+      .accounts({
+        agent:           agent.publicKey,
+        pool:            poolPda,
+        poolAuthority:   poolAuthPda,
+        tokenAVault:     vaultAKp.publicKey,
+        tokenBVault:     vaultBKp.publicKey,
+        agentTokenIn:    agentATA,
+        agentTokenOut:   agentBTA,
+        treasury:        treasuryPda,
+        treasuryTokenIn: treasuryATA,
+        tokenProgram:    TOKEN_PROGRAM_ID,
+      })
+      .signers([agent])
+      .rpc();
+
+    const spent = agentABefore - (await bal(conn, agentATA));
+    expect(((await bal(conn, agentBTA)) - agentBBefore).toString()).to.equal(amountOut.toString());
+    expect(spent > 0n && spent <= 1_000_000n).to.be.true;
+  });
+
+  it("swap_exact_out: reverts with MaxInputExceeded when the input cap is too low", async () => {
+    let err = "";
+    try {
+      await program.methods
+        .swapExactOut(new BN(250_000), new BN(1), true, 0)
+        .accounts({
+          agent:           agent.publicKey,
+          pool:            poolPda,
+          poolAuthority:   poolAuthPda,
+          tokenAVault:     vaultAKp.publicKey,
+          tokenBVault:     vaultBKp.publicKey,
+          agentTokenIn:    agentATA,
+          agentTokenOut:   agentBTA,
+          treasury:        treasuryPda,
+          treasuryTokenIn: treasuryATA,
+          tokenProgram:    TOKEN_PROGRAM_ID,
+        })
+        .signers([agent])
+        .rpc();
+    } catch (e) {
+      err = String(e);
+    }
+    expect(err).to.include("MaxInputExceeded");
+  });
+
   // ─── 6b. Swap b→a — generate fee_growth_b ─────────────────────────────────
   it("swap b→a: protocol fee to treasury in token B", async () => {
     const vA = await bal(conn, vaultAKp.publicKey);
@@ -371,7 +543,7 @@ describe("a2a-swap", () => {
     const agentBBefore = await bal(conn, agentBTA);
 
     await program.methods
-      .swap(new BN(amtIn.toString()), new BN(0), false) // a_to_b = false
+      .swap(new BN(amtIn.toString()), new BN(0), false, 0, new BN(0)) // a_to_b = false
       .accounts({
         agent:           agent.publicKey,
         pool:            poolPda,
@@ -458,7 +630,7 @@ describe("a2a-swap", () => {
     const treasuryBefore = await bal(conn, treasuryATA);
 
     await program.methods
-      .approveAndExecute(new BN(amtIn.toString()), new BN(0), true)
+      .approveAndExecute(new BN(amtIn.toString()), new BN(0), true, new BN(0), new BN(0), 0)
       .accounts({
         agent:           agent.publicKey,
         approver:        approver.publicKey,
@@ -491,7 +663,7 @@ describe("a2a-swap", () => {
     let threw = false;
     try {
       await program.methods
-        .approveAndExecute(new BN(50_000), new BN(0), true)
+        .approveAndExecute(new BN(50_000), new BN(0), true, new BN(0), new BN(0), 0)
         .accounts({
           agent:           agent.publicKey,
           approver:        approver.publicKey, // key present, but no sig
@@ -513,6 +685,138 @@ describe("a2a-swap", () => {
     expect(threw).to.be.true;
   });
 
+  it("approve_and_execute: reverts with ApprovalExpired past expires_at_slot", async () => {
+    const expiresAtSlot = (await conn.getSlot()) - 1;
+    let err = "";
+    try {
+      await program.methods
+        .approveAndExecute(new BN(50_000), new BN(0), true, new BN(expiresAtSlot), new BN(0), 0)
+        .accounts({
+          agent:           agent.publicKey,
+          approver:        approver.publicKey,
+          pool:            poolPda,
+          poolAuthority:   poolAuthPda,
+          tokenAVault:     vaultAKp.publicKey,
+          tokenBVault:     vaultBKp.publicKey,
+          agentTokenIn:    agentATA,
+          agentTokenOut:   agentBTA,
+          treasury:        treasuryPda,
+          treasuryTokenIn: treasuryATA,
+          tokenProgram:    TOKEN_PROGRAM_ID,
+        })
+        .signers([agent, approver])
+        .rpc();
+    } catch (e) {
+      err = String(e);
+    }
+    expect(err).to.include("ApprovalExpired");
+  });
+
+  // ─── 8b. Session keys ──────────────────────────────────────────────────────
+  // The agent is the (cold) owner; `approver` doubles as the hot session key.
+  const delegateAccounts = () => ({
+    owner:           agent.publicKey,
+    delegateAccount: delegatePda,
+    pool:            poolPda,
+    poolAuthority:   poolAuthPda,
+    tokenAVault:     vaultAKp.publicKey,
+    tokenBVault:     vaultBKp.publicKey,
+    ownerTokenIn:    agentATA,
+    ownerTokenOut:   agentBTA,
+    treasury:        treasuryPda,
+    treasuryTokenIn: treasuryATA,
+    tokenProgram:    TOKEN_PROGRAM_ID,
+  });
+  let delegatePda: PublicKey;
+
+  it("swap_as_delegate: session key spends the owner's tokens into the owner's account", async () => {
+    [delegatePda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("delegate"), agent.publicKey.toBuffer(), agentATA.toBuffer()],
+      program.programId);
+    const expiresAt = Math.floor(Date.now() / 1000) + 3600;
+    await program.methods
+      .createDelegate(approver.publicKey, new BN(300_000), new BN(expiresAt))
+      .accounts({
+        owner:           agent.publicKey,
+        delegateAccount: delegatePda,
+        ownerToken:      agentATA,
+        tokenProgram:    TOKEN_PROGRAM_ID,
+        systemProgram:   SystemProgram.programId,
+      })
+      .signers([agent])
+      .rpc();
+
+    const vA = await bal(conn, vaultAKp.publicKey);
+    const vB = await bal(conn, vaultBKp.publicKey);
+    const amtIn = 200_000n;
+    const { out } = swapCalc(vA, vB, amtIn);
+    const agentABefore = await bal(conn, agentATA);
+    const agentBBefore = await bal(conn, agentBTA);
+
+    await program.methods
+      .swapAsDelegate(new BN(amtIn.toString()), new BN(0), true, 0)
+      .accounts({ delegate: approver.publicKey, ...delegateAccounts() })
+      .signers([approver])
+      .rpc();
+
+    expect((agentABefore - (await bal(conn, agentATA))).toString()).to.equal(amtIn.toString());
+    expect(((await bal(conn, agentBTA)) - agentBBefore).toString()).to.equal(out.toString());
+    const d = await program.account.delegate.fetch(delegatePda);
+    expect(d.spent.toString()).to.equal(amtIn.toString());
+  });
+
+  it("swap_as_delegate: reverts with DelegateCapExceeded past max_amount_in", async () => {
+    let err = "";
+    try {
+      await program.methods
+        .swapAsDelegate(new BN(100_001), new BN(0), true, 0)
+        .accounts({ delegate: approver.publicKey, ...delegateAccounts() })
+        .signers([approver])
+        .rpc();
+    } catch (e) {
+      err = String(e);
+    }
+    expect(err).to.include("DelegateCapExceeded");
+  });
+
+  it("rotate_delegate / revoke_delegate: old key is refused, account closes", async () => {
+    const expiresAt = Math.floor(Date.now() / 1000) + 3600;
+    await program.methods
+      .rotateDelegate(Keypair.generate().publicKey, new BN(300_000), new BN(expiresAt))
+      .accounts({
+        owner:           agent.publicKey,
+        delegateAccount: delegatePda,
+        ownerToken:      agentATA,
+        tokenProgram:    TOKEN_PROGRAM_ID,
+      })
+      .signers([agent])
+      .rpc();
+
+    let err = "";
+    try {
+      await program.methods
+        .swapAsDelegate(new BN(1_000), new BN(0), true, 0)
+        .accounts({ delegate: approver.publicKey, ...delegateAccounts() })
+        .signers([approver])
+        .rpc();
+    } catch (e) {
+      err = String(e);
+    }
+    expect(err).to.include("DelegateMismatch");
+
+    await program.methods
+      .revokeDelegate()
+      .accounts({
+        owner:           agent.publicKey,
+        delegateAccount: delegatePda,
+        ownerToken:      agentATA,
+        tokenProgram:    TOKEN_PROGRAM_ID,
+      })
+      .signers([agent])
+      .rpc();
+    expect(await conn.getAccountInfo(delegatePda)).to.be.null;
+  });
+
   // ─── 9. Remove liquidity ───────────────────────────────────────────────────
   it("remove_liquidity: burns all LP shares, returns proportional reserves", async () => {
     const pos   = await program.account.position.fetch(positionPda);
@@ -467,16 +467,21 @@ describe("a2a-swap SDK integration", () => {
     const treasuryTokenIn = deriveAta(treasury, mintC);
 
     // Build approve_and_execute instruction manually
-    // Layout: 8-byte disc | amountIn(u64) | minAmountOut(u64) | aToB(u8) = 25 bytes
+    // Layout: 8-byte disc | amountIn(u64) | minAmountOut(u64) | aToB(u8) |
+    //         expiresAtSlot(u64) | deadlineUnix(i64) | maxPriceImpactBps(u16)
+    //         = 43 bytes
     // Accounts: [agent(signer,w), approver(signer), pool(w), poolAuthority,
     //            vaultA(w), vaultB(w), agentTokenIn(w), agentTokenOut(w),
     //            treasury, treasuryTokenIn(w), tokenProgram]
     const disc = instructionDisc("approve_and_execute");
-    const data = Buffer.alloc(25);
+    const data = Buffer.alloc(43);
     disc.copy(data, 0);
     data.writeBigUInt64LE(amtIn, 8);
     data.writeBigUInt64LE(0n,   16);  // min_amount_out = 0 (no slippage guard)
     data.writeUInt8(1,          24);  // a_to_b = true
+    data.writeBigUInt64LE(0n,   25);  // expires_at_slot = 0 (no expiry)
+    data.writeBigInt64LE(0n,    33);  // deadline_unix = 0 (no deadline)
+    data.writeUInt16LE(0,       41);  // max_price_impact_bps = 0 (no cap)
 
     const ix = new TransactionInstruction({
       programId: PROGRAM_ID,
@@ -516,11 +521,15 @@ describe("a2a-swap SDK integration", () => {
     const treasuryTokenIn = deriveAta(treasury, mintC);
 
     const disc = instructionDisc("approve_and_execute");
-    const data = Buffer.alloc(25);
+    // Same 43-byte layout as above, so the only thing wrong is the missing
+    // approver signature; the deadline is well in the future.
+    const data = Buffer.alloc(43);
     disc.copy(data, 0);
     data.writeBigUInt64LE(50_000n, 8);
     data.writeBigUInt64LE(0n,     16);
     data.writeUInt8(1,            24);
+    data.writeBigUInt64LE(0n,     25);
+    data.writeBigInt64LE(BigInt(Math.floor(Date.now() / 1000) + 3600), 33);
 
     const ix = new TransactionInstruction({
       programId: PROGRAM_ID,